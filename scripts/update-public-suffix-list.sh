@@ -0,0 +1,32 @@
+#!/bin/bash
+
+# 更新 sentinel-core 内置的 Public Suffix List
+# 用法: ./scripts/update-public-suffix-list.sh
+
+set -euo pipefail
+
+ROOT_DIR="$(cd "$(dirname "$0")/.." && pwd)"
+TARGET="$ROOT_DIR/src-tauri/sentinel-core/data/public_suffix_list.dat"
+SOURCE_URL="https://publicsuffix.org/list/public_suffix_list.dat"
+
+TMP_FILE="$(mktemp)"
+trap 'rm -f "$TMP_FILE"' EXIT
+
+echo "下载 $SOURCE_URL ..."
+curl -fsSL "$SOURCE_URL" -o "$TMP_FILE"
+
+# 只使用 ICANN 部分，缺少该段说明下载内容不对
+if ! grep -q "===BEGIN ICANN DOMAINS===" "$TMP_FILE"; then
+    echo "❌ 下载内容不是有效的 Public Suffix List"
+    exit 1
+fi
+
+{
+    echo "// Snapshot: $(date -u +%Y-%m-%d) from $SOURCE_URL"
+    echo "// Update with scripts/update-public-suffix-list.sh"
+    echo
+    cat "$TMP_FILE"
+} > "$TARGET"
+
+echo "✅ 已更新 $TARGET ($(wc -l < "$TARGET") 行)"
+echo "   运行 cargo test -p sentinel-core domain 确认列表可以解析"
@@ -0,0 +1,406 @@
+//! 认证会话（共享 Cookie Jar）
+//!
+//! 保存一次登录得到的 Cookie，内置 HTTP 工具与插件发出的请求按主机范围自动携带，
+//! 用于测试需要登录的攻击面。会话可由手动填写的 Cookie 字符串设置，
+//! 也可从抓到的登录响应 `Set-Cookie` 导入。
+//!
+//! 作用范围：
+//! - 每个 Cookie 绑定域名，`example.com` 匹配自身及所有子域名
+//! - `path` 为路径前缀，`secure` 的 Cookie 只随 HTTPS 请求发送
+//! - 范围内的响应下发的 `Set-Cookie`（如会话轮换）会回写到会话中
+//!
+//! 会话只保存在内存中，不落库。
+
+use once_cell::sync::Lazy;
+use reqwest::cookie::CookieStore;
+use reqwest::header::HeaderValue;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+
+/// 会话中的单个 Cookie
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionCookie {
+    pub name: String,
+    pub value: String,
+    /// 作用域名（匹配自身及子域名）
+    pub domain: String,
+    /// 路径前缀
+    #[serde(default = "default_path")]
+    pub path: String,
+    /// 仅随 HTTPS 请求发送
+    #[serde(default)]
+    pub secure: bool,
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+/// 认证会话
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthSession {
+    /// 会话名称（如登录账号），仅用于展示
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub cookies: Vec<SessionCookie>,
+}
+
+impl AuthSession {
+    /// 添加 `Cookie` 请求头格式的 Cookie（`a=1; b=2`），作用于 `domain`
+    pub fn add_cookie_header(&mut self, domain: &str, header: &str) {
+        let domain = normalize_domain(domain);
+        for pair in header.split(';') {
+            let Some((name, value)) = pair.split_once('=') else {
+                continue;
+            };
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            self.upsert(SessionCookie {
+                name: name.to_string(),
+                value: value.trim().to_string(),
+                domain: domain.clone(),
+                path: default_path(),
+                secure: false,
+            });
+        }
+    }
+
+    /// 应用 `url` 的响应中的一条 `Set-Cookie`；`Max-Age<=0` 视为删除
+    pub fn add_set_cookie(&mut self, url: &Url, set_cookie: &str) {
+        let Some(host) = url.host_str() else {
+            return;
+        };
+        let mut parts = set_cookie.split(';');
+        let Some((name, value)) = parts.next().and_then(|p| p.split_once('=')) else {
+            return;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+
+        let mut cookie = SessionCookie {
+            name: name.to_string(),
+            value: value.trim().trim_matches('"').to_string(),
+            domain: normalize_domain(host),
+            path: default_path(),
+            secure: false,
+        };
+        let mut expired = false;
+        for attr in parts {
+            let (key, val) = attr.split_once('=').unwrap_or((attr, ""));
+            let val = val.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" => {
+                    // 不接受为无关域名设置的 Cookie
+                    let domain = normalize_domain(val);
+                    if !domain_matches(host, &domain) {
+                        return;
+                    }
+                    cookie.domain = domain;
+                }
+                "path" if val.starts_with('/') => cookie.path = val.to_string(),
+                "secure" => cookie.secure = true,
+                "max-age" => expired = val.parse::<i64>().map(|v| v <= 0).unwrap_or(false),
+                _ => {}
+            }
+        }
+
+        if expired {
+            self.cookies.retain(|c| {
+                !(c.name == cookie.name && c.domain == cookie.domain && c.path == cookie.path)
+            });
+        } else {
+            self.upsert(cookie);
+        }
+    }
+
+    /// 新增或替换同名、同域、同路径的 Cookie
+    pub fn upsert(&mut self, cookie: SessionCookie) {
+        match self
+            .cookies
+            .iter_mut()
+            .find(|c| c.name == cookie.name && c.domain == cookie.domain && c.path == cookie.path)
+        {
+            Some(existing) => *existing = cookie,
+            None => self.cookies.push(cookie),
+        }
+    }
+
+    /// `url` 是否在会话作用范围内
+    pub fn in_scope(&self, url: &Url) -> bool {
+        url.host_str()
+            .map(|host| self.cookies.iter().any(|c| domain_matches(host, &c.domain)))
+            .unwrap_or(false)
+    }
+
+    /// 请求 `url` 应携带的 `Cookie` 头，路径更具体的 Cookie 在前
+    pub fn cookie_header(&self, url: &Url) -> Option<String> {
+        let host = url.host_str()?;
+        let https = url.scheme() == "https";
+        let mut matched: Vec<&SessionCookie> = self
+            .cookies
+            .iter()
+            .filter(|c| domain_matches(host, &c.domain))
+            .filter(|c| path_matches(url.path(), &c.path))
+            .filter(|c| https || !c.secure)
+            .collect();
+        if matched.is_empty() {
+            return None;
+        }
+        matched.sort_by_key(|c| std::cmp::Reverse(c.path.len()));
+        Some(
+            matched
+                .iter()
+                .map(|c| format!("{}={}", c.name, c.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+}
+
+fn normalize_domain(domain: &str) -> String {
+    domain.trim().trim_start_matches('.').to_ascii_lowercase()
+}
+
+fn domain_matches(host: &str, domain: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    !domain.is_empty()
+        && (host == domain
+            || host
+                .strip_suffix(domain)
+                .map(|prefix| prefix.ends_with('.'))
+                .unwrap_or(false))
+}
+
+fn path_matches(path: &str, prefix: &str) -> bool {
+    prefix == "/"
+        || path == prefix
+        || path
+            .strip_prefix(prefix)
+            .map(|rest| prefix.ends_with('/') || rest.starts_with('/'))
+            .unwrap_or(false)
+}
+
+/// 会话 Cookie Jar，作为 reqwest 的 cookie provider 挂到客户端上
+#[derive(Debug, Default)]
+pub struct SessionJar {
+    session: RwLock<Option<AuthSession>>,
+}
+
+impl SessionJar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, session: AuthSession) {
+        *self.session.write().unwrap_or_else(|e| e.into_inner()) = Some(session);
+    }
+
+    pub fn clear(&self) {
+        *self.session.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    pub fn get(&self) -> Option<AuthSession> {
+        self.session
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl CookieStore for SessionJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let mut guard = self.session.write().unwrap_or_else(|e| e.into_inner());
+        // 只跟踪会话范围内主机下发的 Cookie
+        let Some(session) = guard.as_mut().filter(|s| s.in_scope(url)) else {
+            return;
+        };
+        for header in cookie_headers {
+            if let Ok(value) = header.to_str() {
+                session.add_set_cookie(url, value);
+            }
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let guard = self.session.read().unwrap_or_else(|e| e.into_inner());
+        let header = guard.as_ref()?.cookie_header(url)?;
+        HeaderValue::from_str(&header).ok()
+    }
+}
+
+/// 全局认证会话
+static GLOBAL_SESSION: Lazy<Arc<SessionJar>> = Lazy::new(|| Arc::new(SessionJar::new()));
+
+/// 设置全局认证会话
+pub fn set_auth_session(session: AuthSession) {
+    tracing::info!(
+        "Auth session set: {} cookies{}",
+        session.cookies.len(),
+        session
+            .label
+            .as_deref()
+            .map(|l| format!(" ({})", l))
+            .unwrap_or_default()
+    );
+    GLOBAL_SESSION.set(session);
+}
+
+/// 清除全局认证会话
+pub fn clear_auth_session() {
+    tracing::info!("Auth session cleared");
+    GLOBAL_SESSION.clear();
+}
+
+/// 获取全局认证会话
+pub fn get_auth_session() -> Option<AuthSession> {
+    GLOBAL_SESSION.get()
+}
+
+/// 为 reqwest ClientBuilder 挂上全局认证会话
+///
+/// 会话在请求时读取，之后再设置或清除的会话对已创建的客户端同样生效。
+/// 请求显式设置了 `Cookie` 头时不会被覆盖。
+pub fn apply_session_to_client(builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+    builder.cookie_provider(GLOBAL_SESSION.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// 返回请求中 Cookie 头的模拟服务器，`/login` 额外下发轮换后的会话
+    async fn spawn_echo_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 2048];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                    let cookie = request
+                        .lines()
+                        .find(|l| l.to_ascii_lowercase().starts_with("cookie:"))
+                        .map(|l| l["cookie:".len()..].trim().to_string())
+                        .unwrap_or_default();
+                    let extra = if request.starts_with("GET /login ") {
+                        "Set-Cookie: sid=rotated; Path=/; HttpOnly\r\n"
+                    } else {
+                        ""
+                    };
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        extra,
+                        cookie.len(),
+                        cookie
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        format!("http://127.0.0.1:{}", addr.port())
+    }
+
+    fn login_session() -> AuthSession {
+        let mut session = AuthSession::default();
+        session.add_cookie_header("127.0.0.1", "sid=abc123; role=admin");
+        session
+    }
+
+    #[tokio::test]
+    async fn test_requests_carry_session_cookies() {
+        let base = spawn_echo_server().await;
+        let jar = Arc::new(SessionJar::new());
+        jar.set(login_session());
+
+        let with_session = reqwest::Client::builder()
+            .cookie_provider(jar.clone())
+            .build()
+            .unwrap();
+        let body = with_session
+            .get(format!("{}/profile", base))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(body, "sid=abc123; role=admin");
+
+        let without_session = reqwest::Client::new();
+        let body = without_session
+            .get(format!("{}/profile", base))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(body, "");
+
+        // 会话轮换回写到 jar，清除后不再携带
+        with_session
+            .get(format!("{}/login", base))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(
+            jar.get()
+                .unwrap()
+                .cookie_header(&Url::parse(&base).unwrap()),
+            Some("sid=rotated; role=admin".to_string())
+        );
+        jar.clear();
+        let body = with_session
+            .get(format!("{}/profile", base))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(body, "");
+    }
+
+    #[test]
+    fn test_cookie_scope() {
+        let mut session = AuthSession::default();
+        let login = Url::parse("https://app.example.com/login").unwrap();
+        session.add_set_cookie(&login, "sid=1; Domain=.example.com; Path=/; Secure");
+        session.add_set_cookie(&login, "admin=1; Path=/admin");
+        session.add_set_cookie(&login, "evil=1; Domain=attacker.com");
+
+        let header =
+            |session: &AuthSession, url: &str| session.cookie_header(&Url::parse(url).unwrap());
+        assert_eq!(
+            header(&session, "https://api.example.com/v1"),
+            Some("sid=1".to_string())
+        );
+        assert_eq!(
+            header(&session, "https://app.example.com/admin/users"),
+            Some("admin=1; sid=1".to_string())
+        );
+        assert_eq!(
+            header(&session, "https://app.example.com/administrator"),
+            Some("sid=1".to_string())
+        );
+        assert_eq!(header(&session, "http://app.example.com/"), None);
+        assert_eq!(header(&session, "https://example.org/"), None);
+        assert_eq!(header(&session, "https://notexample.com/"), None);
+        assert!(session.cookies.iter().all(|c| c.name != "evil"));
+
+        session.add_set_cookie(&login, "admin=; Path=/admin; Max-Age=0");
+        assert_eq!(
+            header(&session, "https://app.example.com/admin/users"),
+            Some("sid=1".to_string())
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! 域名工具
+//!
+//! 基于内置的 Public Suffix List（仅 ICANN 部分）计算可注册域名，
+//! 例如 `a.b.example.co.uk` 的可注册域名为 `example.co.uk`。
+//! 列表文件位于 `data/public_suffix_list.dat`，首行注释记录快照日期；
+//! 用 `scripts/update-public-suffix-list.sh` 从
+//! <https://publicsuffix.org/list/public_suffix_list.dat> 更新。
+
+use once_cell::sync::Lazy;
+use publicsuffix::{IcannList, Psl};
+
+static PUBLIC_SUFFIXES: Lazy<IcannList> = Lazy::new(|| {
+    include_str!("../data/public_suffix_list.dat")
+        .parse()
+        .expect("bundled public suffix list is valid")
+});
+
+/// 返回主机名的可注册域名（小写，去掉末尾的 `.`）
+///
+/// IP 地址、公共后缀本身（如 `co.uk`）以及无法解析的名称返回 `None`。
+pub fn registrable_domain(host: &str) -> Option<String> {
+    let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+    if host.is_empty() || host.parse::<std::net::IpAddr>().is_ok() {
+        return None;
+    }
+    let domain = PUBLIC_SUFFIXES.domain(host.as_bytes())?;
+    std::str::from_utf8(domain.as_bytes())
+        .ok()
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registrable_domain() {
+        assert_eq!(
+            registrable_domain("api.example.com").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            registrable_domain("a.b.Example.CO.UK.").as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(
+            registrable_domain("shop.example.com.cn").as_deref(),
+            Some("example.com.cn")
+        );
+        assert_eq!(
+            registrable_domain("example.com").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(registrable_domain("co.uk"), None);
+        assert_eq!(registrable_domain("10.0.0.1"), None);
+        assert_eq!(registrable_domain(""), None);
+    }
+}
@@ -7,13 +7,29 @@
 //! - https: HTTPS代理
 //! - socks5: SOCKS5代理（本地DNS解析）
 //! - socks5h: SOCKS5代理（远程DNS解析，更安全）
+//!
+//! 主代理之后可配置按优先级排列的备用代理。开启健康检查后定期通过每个代理请求探测 URL，
+//! 标记其可用状态，新建的客户端总是走第一个可用的代理。
+//!
+//! `no_proxy` 中的地址直连，不经过代理，支持：
+//! - 域名后缀：`example.com` / `.example.com` 匹配其自身及所有子域名
+//! - 通配符：`*.corp.local`、`10.*`，`*` 单独出现时所有地址直连
+//! - IP 与 CIDR：`192.168.1.10`、`10.0.0.0/8`、`fd00::/8`
+//!
+//! `localhost` 与回环地址总是直连。
 
+use base64::Engine;
 use once_cell::sync::Lazy;
 use reqwest::Proxy;
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
 use tokio::sync::RwLock;
-use tracing::{debug, warn};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
 
 /// 全局代理配置
 ///
@@ -34,9 +50,140 @@ pub struct GlobalProxyConfig {
     pub password: Option<String>,
     /// 不使用代理的地址列表（逗号分隔）
     pub no_proxy: Option<String>,
+    /// 备用代理（按优先级排列，主代理不可用时依次切换）
+    #[serde(default)]
+    pub fallbacks: Vec<ProxyEndpoint>,
+    /// 健康检查配置
+    #[serde(default)]
+    pub health_check: ProxyHealthCheckConfig,
+}
+
+/// 单个上游代理
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ProxyEndpoint {
+    /// 代理协议 (http/https/socks5/socks5h)
+    pub scheme: Option<String>,
+    /// 代理主机地址
+    pub host: String,
+    /// 代理端口
+    pub port: u16,
+    /// 用户名（可选）
+    pub username: Option<String>,
+    /// 密码（可选）
+    pub password: Option<String>,
+}
+
+impl ProxyEndpoint {
+    /// 构建代理 URL
+    pub fn proxy_url(&self) -> String {
+        let scheme = self.scheme.as_deref().unwrap_or("http");
+        if let (Some(username), Some(password)) = (&self.username, &self.password) {
+            format!(
+                "{}://{}:{}@{}:{}",
+                scheme, username, password, self.host, self.port
+            )
+        } else {
+            format!("{}://{}:{}", scheme, self.host, self.port)
+        }
+    }
+}
+
+/// 代理健康检查配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProxyHealthCheckConfig {
+    /// 是否启用定期健康检查（默认关闭：探测会定期向外部 URL 发请求）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 探测 URL
+    #[serde(default = "default_probe_url")]
+    pub probe_url: String,
+    /// 检查间隔（秒）
+    #[serde(default = "default_health_check_interval_secs")]
+    pub interval_secs: u64,
+    /// 单次探测超时（秒）
+    #[serde(default = "default_health_check_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_probe_url() -> String {
+    "http://www.gstatic.com/generate_204".to_string()
+}
+
+fn default_health_check_interval_secs() -> u64 {
+    60
+}
+
+fn default_health_check_timeout_secs() -> u64 {
+    10
+}
+
+impl Default for ProxyHealthCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            probe_url: default_probe_url(),
+            interval_secs: default_health_check_interval_secs(),
+            timeout_secs: default_health_check_timeout_secs(),
+        }
+    }
+}
+
+/// 单个代理的健康状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProxyHealthStatus {
+    pub scheme: String,
+    pub host: String,
+    pub port: u16,
+    /// 是否可用（尚未检查的代理视为可用）
+    pub healthy: bool,
+    /// 新建客户端当前是否走该代理
+    pub active: bool,
+    /// 最近一次检查时间（毫秒时间戳）
+    pub last_checked_at: Option<i64>,
+    pub latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+impl ProxyHealthStatus {
+    fn unchecked(endpoint: &ProxyEndpoint) -> Self {
+        Self {
+            scheme: endpoint
+                .scheme
+                .clone()
+                .unwrap_or_else(|| "http".to_string()),
+            host: endpoint.host.clone(),
+            port: endpoint.port,
+            healthy: true,
+            active: false,
+            last_checked_at: None,
+            latency_ms: None,
+            last_error: None,
+        }
+    }
 }
 
 impl GlobalProxyConfig {
+    /// 按优先级排列的代理列表（主代理在前，单代理配置即为单元素列表）
+    pub fn endpoints(&self) -> Vec<ProxyEndpoint> {
+        let mut endpoints = Vec::new();
+        if let (Some(host), Some(port)) = (&self.host, self.port) {
+            endpoints.push(ProxyEndpoint {
+                scheme: self.scheme.clone(),
+                host: host.clone(),
+                port,
+                username: self.username.clone(),
+                password: self.password.clone(),
+            });
+        }
+        endpoints.extend(
+            self.fallbacks
+                .iter()
+                .filter(|e| !e.host.trim().is_empty() && e.port != 0)
+                .cloned(),
+        );
+        endpoints
+    }
+
     /// 构建代理 URL
     pub fn build_proxy_url(&self) -> Option<String> {
         if !self.enabled {
@@ -62,6 +209,21 @@ impl GlobalProxyConfig {
 static GLOBAL_PROXY: Lazy<Arc<RwLock<GlobalProxyConfig>>> =
     Lazy::new(|| Arc::new(RwLock::new(GlobalProxyConfig::default())));
 
+/// 代理列表及其健康状态
+#[derive(Default)]
+struct ProxyHealthState {
+    endpoints: Vec<ProxyEndpoint>,
+    status: Vec<ProxyHealthStatus>,
+    active: Option<usize>,
+}
+
+static PROXY_HEALTH: Lazy<RwLock<ProxyHealthState>> =
+    Lazy::new(|| RwLock::new(ProxyHealthState::default()));
+
+/// 健康检查后台任务
+static HEALTH_MONITOR: Lazy<std::sync::Mutex<Option<JoinHandle<()>>>> =
+    Lazy::new(|| std::sync::Mutex::new(None));
+
 const PROXY_ENV_KEYS: [&str; 6] = [
     "HTTP_PROXY",
     "HTTPS_PROXY",
@@ -78,7 +240,9 @@ const LOCAL_NO_PROXY_DEFAULTS: [&str; 3] = ["localhost", "127.0.0.1", "::1"];
 pub async fn set_global_proxy(config: GlobalProxyConfig) {
     let mut proxy = GLOBAL_PROXY.write().await;
     *proxy = config;
-    apply_proxy_env_vars(&proxy);
+    let active = reset_proxy_health(&proxy).await;
+    apply_proxy_env_vars(&proxy, active.as_ref());
+    restart_health_monitor(&proxy);
 }
 
 /// 获取全局代理配置
@@ -90,9 +254,298 @@ pub async fn get_global_proxy() -> GlobalProxyConfig {
 pub async fn clear_global_proxy() {
     let mut proxy = GLOBAL_PROXY.write().await;
     *proxy = GlobalProxyConfig::default();
+    reset_proxy_health(&proxy).await;
+    stop_health_monitor();
     clear_proxy_env_vars();
 }
 
+/// 获取各代理的健康状态
+pub async fn get_proxy_health() -> Vec<ProxyHealthStatus> {
+    PROXY_HEALTH.read().await.status.clone()
+}
+
+/// 当前用于新建客户端的代理
+pub async fn active_proxy_endpoint() -> Option<ProxyEndpoint> {
+    let state = PROXY_HEALTH.read().await;
+    state.active.and_then(|i| state.endpoints.get(i).cloned())
+}
+
+/// 标记代理可用状态，必要时切换当前代理
+pub async fn mark_proxy_health(index: usize, healthy: bool, error: Option<String>) {
+    let switched = {
+        let mut state = PROXY_HEALTH.write().await;
+        let Some(status) = state.status.get_mut(index) else {
+            return;
+        };
+        status.healthy = healthy;
+        status.last_error = if healthy { None } else { error };
+        status.last_checked_at = Some(chrono::Utc::now().timestamp_millis());
+        update_active(&mut state)
+    };
+
+    if let Some(endpoint) = switched {
+        let config = GLOBAL_PROXY.read().await;
+        info!(
+            "Global proxy switched to {}:{}",
+            endpoint.host, endpoint.port
+        );
+        apply_proxy_env_vars(&config, Some(&endpoint));
+    }
+}
+
+/// 立即探测所有代理并更新健康状态
+pub async fn check_proxy_health() -> Vec<ProxyHealthStatus> {
+    let (endpoints, health_check) = {
+        let config = GLOBAL_PROXY.read().await;
+        if !config.enabled {
+            return get_proxy_health().await;
+        }
+        (config.endpoints(), config.health_check.clone())
+    };
+
+    for (index, endpoint) in endpoints.iter().enumerate() {
+        let started = Instant::now();
+        match probe_proxy(endpoint, &health_check).await {
+            Ok(()) => {
+                let latency_ms = started.elapsed().as_millis() as u64;
+                mark_proxy_health(index, true, None).await;
+                if let Some(status) = PROXY_HEALTH.write().await.status.get_mut(index) {
+                    status.latency_ms = Some(latency_ms);
+                }
+            }
+            Err(e) => {
+                debug!(
+                    "Proxy {}:{} failed health check: {}",
+                    endpoint.host, endpoint.port, e
+                );
+                mark_proxy_health(index, false, Some(e)).await;
+            }
+        }
+    }
+
+    get_proxy_health().await
+}
+
+/// 通过指定代理请求探测 URL；收到任何 HTTP 响应即视为可用
+async fn probe_proxy(
+    endpoint: &ProxyEndpoint,
+    health_check: &ProxyHealthCheckConfig,
+) -> Result<(), String> {
+    let proxy = Proxy::all(endpoint.proxy_url()).map_err(|e| e.to_string())?;
+    let client = reqwest::Client::builder()
+        .default_headers(sentinel_headers())
+        .proxy(proxy)
+        .timeout(Duration::from_secs(health_check.timeout_secs.max(1)))
+        .build()
+        .map_err(|e| e.to_string())?;
+    client
+        .get(&health_check.probe_url)
+        .send()
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// 按新配置重建代理列表，返回当前代理
+async fn reset_proxy_health(config: &GlobalProxyConfig) -> Option<ProxyEndpoint> {
+    let mut state = PROXY_HEALTH.write().await;
+    let endpoints = if config.enabled {
+        config.endpoints()
+    } else {
+        Vec::new()
+    };
+    state.status = endpoints.iter().map(ProxyHealthStatus::unchecked).collect();
+    state.endpoints = endpoints;
+    state.active = None;
+    update_active(&mut state);
+    state.active.and_then(|i| state.endpoints.get(i).cloned())
+}
+
+/// 重新选择当前代理，切换时返回新代理
+fn update_active(state: &mut ProxyHealthState) -> Option<ProxyEndpoint> {
+    let selected = select_active(&state.status);
+    for (i, status) in state.status.iter_mut().enumerate() {
+        status.active = Some(i) == selected;
+    }
+    if selected == state.active {
+        return None;
+    }
+    state.active = selected;
+    selected.and_then(|i| state.endpoints.get(i).cloned())
+}
+
+/// 第一个可用的代理；全部不可用时保留主代理，避免静默直连
+fn select_active(status: &[ProxyHealthStatus]) -> Option<usize> {
+    if status.is_empty() {
+        return None;
+    }
+    Some(status.iter().position(|s| s.healthy).unwrap_or(0))
+}
+
+fn restart_health_monitor(config: &GlobalProxyConfig) {
+    stop_health_monitor();
+    if !config.enabled || !config.health_check.enabled || config.endpoints().is_empty() {
+        return;
+    }
+
+    let interval = Duration::from_secs(config.health_check.interval_secs.max(1));
+    let handle = tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            check_proxy_health().await;
+        }
+    });
+    if let Ok(mut monitor) = HEALTH_MONITOR.lock() {
+        *monitor = Some(handle);
+    }
+}
+
+fn stop_health_monitor() {
+    if let Ok(mut monitor) = HEALTH_MONITOR.lock() {
+        if let Some(handle) = monitor.take() {
+            handle.abort();
+        }
+    }
+}
+
+/// 单条 no_proxy 规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NoProxyRule {
+    /// `*`：所有地址直连
+    All,
+    /// 精确 IP
+    Ip(IpAddr),
+    /// CIDR 网段
+    Cidr(IpAddr, u8),
+    /// 域名及其子域名
+    Domain(String),
+    /// 含 `*` 的通配模式
+    Wildcard(String),
+}
+
+impl NoProxyRule {
+    fn parse(entry: &str) -> Option<Self> {
+        let entry = entry.trim().to_ascii_lowercase();
+        if entry.is_empty() {
+            return None;
+        }
+        if entry == "*" {
+            return Some(Self::All);
+        }
+        if let Some((addr, prefix)) = entry.split_once('/') {
+            let addr: IpAddr = addr.trim_matches(|c| c == '[' || c == ']').parse().ok()?;
+            let prefix: u8 = prefix.parse().ok()?;
+            let max = if addr.is_ipv4() { 32 } else { 128 };
+            return (prefix <= max).then_some(Self::Cidr(addr, prefix));
+        }
+        if let Some(ip) = parse_host_ip(&entry) {
+            return Some(Self::Ip(ip));
+        }
+        if entry.contains('*') {
+            return Some(Self::Wildcard(entry));
+        }
+        // 去掉端口与前导点
+        let domain = entry.split(':').next().unwrap_or_default();
+        let domain = domain.trim_start_matches('.').trim_end_matches('.');
+        (!domain.is_empty()).then(|| Self::Domain(domain.to_string()))
+    }
+
+    fn matches(&self, host: &str, ip: Option<IpAddr>) -> bool {
+        match self {
+            Self::All => true,
+            Self::Ip(rule) => ip == Some(*rule),
+            Self::Cidr(network, prefix) => {
+                ip.is_some_and(|ip| cidr_contains(*network, *prefix, ip))
+            }
+            Self::Domain(domain) => {
+                host == domain
+                    || host
+                        .strip_suffix(domain.as_str())
+                        .is_some_and(|rest| rest.ends_with('.'))
+            }
+            Self::Wildcard(pattern) => wildcard_match(pattern, host),
+        }
+    }
+}
+
+fn parse_host_ip(host: &str) -> Option<IpAddr> {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+fn cidr_contains(network: IpAddr, prefix: u8, ip: IpAddr) -> bool {
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+/// `*` 匹配任意长度字符
+fn wildcard_match(pattern: &str, host: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !host.starts_with(first) || host.len() < first.len() + last.len() {
+        return false;
+    }
+    let mut rest = &host[first.len()..];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// 解析后的 no_proxy 规则集
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NoProxyRules {
+    rules: Vec<NoProxyRule>,
+}
+
+impl NoProxyRules {
+    /// 解析逗号分隔的 no_proxy 列表，无法识别的条目被忽略
+    pub fn parse(list: &str) -> Self {
+        Self {
+            rules: list.split(',').filter_map(NoProxyRule::parse).collect(),
+        }
+    }
+
+    /// 目标主机是否应直连；`localhost` 与回环地址总是直连
+    pub fn matches(&self, host: &str) -> bool {
+        let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+        let ip = parse_host_ip(&host);
+        if host == "localhost"
+            || host.ends_with(".localhost")
+            || ip.is_some_and(|ip| ip.is_loopback())
+        {
+            return true;
+        }
+        self.rules.iter().any(|rule| rule.matches(&host, ip))
+    }
+}
+
+/// 按当前全局代理配置判断目标主机是否绕过代理
+///
+/// 未启用代理时所有主机都直连，返回 true。
+pub async fn should_bypass_proxy(host: &str) -> bool {
+    let config = get_global_proxy().await;
+    if !config.enabled {
+        return true;
+    }
+    NoProxyRules::parse(config.no_proxy.as_deref().unwrap_or_default()).matches(host)
+}
+
 /// 创建一个应用了全局代理的 HTTP 客户端
 pub async fn create_client_with_proxy() -> Result<reqwest::Client, reqwest::Error> {
     let builder = reqwest::Client::builder();
@@ -100,6 +553,16 @@ pub async fn create_client_with_proxy() -> Result<reqwest::Client, reqwest::Erro
     builder.build()
 }
 
+/// 标识本应用流量的默认请求头（X-Sentinel-Internal），绕过全局代理构建客户端时也需带上
+pub fn sentinel_headers() -> reqwest::header::HeaderMap {
+    let mut default_headers = reqwest::header::HeaderMap::new();
+    default_headers.insert(
+        reqwest::header::HeaderName::from_static("x-sentinel-internal"),
+        reqwest::header::HeaderValue::from_static("true"),
+    );
+    default_headers
+}
+
 /// 为 reqwest ClientBuilder 应用全局代理配置
 ///
 /// 支持的代理协议：
@@ -108,36 +571,33 @@ pub async fn create_client_with_proxy() -> Result<reqwest::Client, reqwest::Erro
 /// - socks5: SOCKS5代理（本地DNS解析）
 /// - socks5h: SOCKS5代理（远程DNS解析，更安全）
 ///
-/// 自动为所有请求添加 X-Sentinel-Internal header，用于在代理中识别本应用流量
+/// 自动为所有请求添加 X-Sentinel-Internal header，用于在代理中识别本应用流量。
+/// 命中 `no_proxy` 的目标主机直连。
 pub async fn apply_proxy_to_client(builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
     let config = get_global_proxy().await;
 
     // 添加标识 Header，用于在代理中识别本应用的流量
-    let mut default_headers = reqwest::header::HeaderMap::new();
-    default_headers.insert(
-        reqwest::header::HeaderName::from_static("x-sentinel-internal"),
-        reqwest::header::HeaderValue::from_static("true"),
-    );
-
-    let builder = builder.default_headers(default_headers);
+    let builder = builder.default_headers(sentinel_headers());
 
     if !config.enabled {
         debug!("Global proxy not enabled, returning client builder with sentinel headers");
         return builder;
     }
 
-    if let Some(proxy_url) = config.build_proxy_url() {
-        let scheme = config.scheme.as_deref().unwrap_or("http");
+    if let Some(endpoint) = active_proxy_endpoint().await {
+        let scheme = endpoint.scheme.as_deref().unwrap_or("http");
 
-        match Proxy::all(&proxy_url) {
-            Ok(proxy) => {
+        match reqwest::Url::parse(&endpoint.proxy_url()) {
+            Ok(proxy_url) => {
                 debug!(
                     "Applying {} proxy to reqwest client: {}:{}",
-                    scheme,
-                    config.host.as_deref().unwrap_or("unknown"),
-                    config.port.unwrap_or(0)
+                    scheme, endpoint.host, endpoint.port
                 );
-                builder.proxy(proxy)
+                let bypass = NoProxyRules::parse(config.no_proxy.as_deref().unwrap_or_default());
+                builder.proxy(Proxy::custom(move |target| {
+                    let host = target.host_str()?;
+                    (!bypass.matches(host)).then(|| proxy_url.clone())
+                }))
             }
             Err(e) => {
                 warn!(
@@ -153,13 +613,222 @@ pub async fn apply_proxy_to_client(builder: reqwest::ClientBuilder) -> reqwest::
     }
 }
 
-fn apply_proxy_env_vars(config: &GlobalProxyConfig) {
+/// 建立到目标主机的原始 TCP 连接，按全局代理配置经代理拨号
+///
+/// 供 WHOIS 等非 HTTP 协议使用：socks5/socks5h 代理走 SOCKS5 CONNECT，
+/// http 代理走 HTTP CONNECT 隧道；未启用代理或命中 `no_proxy` 时直连。
+pub async fn connect_tcp(host: &str, port: u16) -> std::io::Result<TcpStream> {
+    let config = get_global_proxy().await;
+    let bypass = !config.enabled
+        || NoProxyRules::parse(config.no_proxy.as_deref().unwrap_or_default()).matches(host);
+    match active_proxy_endpoint().await {
+        Some(endpoint) if !bypass => connect_via_endpoint(&endpoint, host, port).await,
+        _ => TcpStream::connect((host, port)).await,
+    }
+}
+
+/// 经指定代理建立到 `host:port` 的 TCP 隧道
+pub async fn connect_via_endpoint(
+    endpoint: &ProxyEndpoint,
+    host: &str,
+    port: u16,
+) -> std::io::Result<TcpStream> {
+    let scheme = endpoint
+        .scheme
+        .as_deref()
+        .unwrap_or("http")
+        .to_ascii_lowercase();
+    let mut stream = TcpStream::connect((endpoint.host.as_str(), endpoint.port)).await?;
+    match scheme.as_str() {
+        "socks5" | "socks5h" => {
+            socks5_handshake(&mut stream, endpoint, host, port, scheme == "socks5h").await?
+        }
+        "http" => http_connect_handshake(&mut stream, endpoint, host, port).await?,
+        other => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("{} proxy cannot tunnel raw TCP", other),
+            ))
+        }
+    }
+    Ok(stream)
+}
+
+fn proxy_error(message: impl Into<String>) -> std::io::Error {
+    std::io::Error::other(message.into())
+}
+
+/// SOCKS5 CONNECT（RFC 1928，用户名密码认证见 RFC 1929）
+///
+/// socks5 在本地解析目标地址，socks5h 把域名交给代理解析。
+/// 认证失败返回 `PermissionDenied`，代理连不上目标返回 `HostUnreachable`。
+pub async fn socks5_handshake(
+    stream: &mut TcpStream,
+    endpoint: &ProxyEndpoint,
+    host: &str,
+    port: u16,
+    remote_dns: bool,
+) -> std::io::Result<()> {
+    let credentials = endpoint
+        .username
+        .as_deref()
+        .zip(endpoint.password.as_deref());
+    let method = if credentials.is_some() { 0x02 } else { 0x00 };
+    stream.write_all(&[0x05, 0x01, method]).await?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply[0] != 0x05 || reply[1] != method {
+        return Err(proxy_error(
+            "SOCKS5 proxy rejected the authentication method",
+        ));
+    }
+
+    if let Some((username, password)) = credentials {
+        if username.len() > 255 || password.len() > 255 {
+            return Err(proxy_error("SOCKS5 credentials are too long"));
+        }
+        let mut auth = vec![0x01, username.len() as u8];
+        auth.extend_from_slice(username.as_bytes());
+        auth.push(password.len() as u8);
+        auth.extend_from_slice(password.as_bytes());
+        stream.write_all(&auth).await?;
+        stream.read_exact(&mut reply).await?;
+        if reply[1] != 0x00 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "SOCKS5 authentication failed",
+            ));
+        }
+    }
+
+    let target_ip = match parse_host_ip(host) {
+        Some(ip) => Some(ip),
+        None if !remote_dns => Some(
+            tokio::net::lookup_host((host, port))
+                .await?
+                .next()
+                .ok_or_else(|| proxy_error(format!("Failed to resolve {}", host)))?
+                .ip(),
+        ),
+        None => None,
+    };
+    let mut request = vec![0x05, 0x01, 0x00];
+    match target_ip {
+        Some(IpAddr::V4(ip)) => {
+            request.push(0x01);
+            request.extend_from_slice(&ip.octets());
+        }
+        Some(IpAddr::V6(ip)) => {
+            request.push(0x04);
+            request.extend_from_slice(&ip.octets());
+        }
+        None => {
+            if host.len() > 255 {
+                return Err(proxy_error("Host name is too long for SOCKS5"));
+            }
+            request.push(0x03);
+            request.push(host.len() as u8);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut head = [0u8; 4];
+    stream.read_exact(&mut head).await?;
+    if head[1] != 0x00 {
+        let message = format!(
+            "SOCKS5 connect to {}:{} failed (reply {})",
+            host, port, head[1]
+        );
+        // 网络不可达 / 主机不可达 / 连接被拒绝
+        return Err(match head[1] {
+            0x03..=0x05 => std::io::Error::new(std::io::ErrorKind::HostUnreachable, message),
+            _ => proxy_error(message),
+        });
+    }
+    // 跳过代理返回的绑定地址
+    let address_len = match head[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).await?;
+            len[0] as usize
+        }
+        other => {
+            return Err(proxy_error(format!(
+                "Invalid SOCKS5 address type {}",
+                other
+            )))
+        }
+    };
+    let mut bound = vec![0u8; address_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
+
+/// HTTP CONNECT 隧道
+///
+/// 407 返回 `PermissionDenied`，502/503/504（代理连不上目标）返回 `HostUnreachable`。
+pub async fn http_connect_handshake(
+    stream: &mut TcpStream,
+    endpoint: &ProxyEndpoint,
+    host: &str,
+    port: u16,
+) -> std::io::Result<()> {
+    let authority = match parse_host_ip(host) {
+        Some(IpAddr::V6(ip)) => format!("[{}]:{}", ip, port),
+        _ => format!("{}:{}", host, port),
+    };
+    let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", authority);
+    if let (Some(username), Some(password)) = (&endpoint.username, &endpoint.password) {
+        let token =
+            base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // 逐字节读取响应头，避免吞掉隧道中的后续数据
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() > 8192 {
+            return Err(proxy_error("Proxy CONNECT response is too large"));
+        }
+        stream.read_exact(&mut byte).await?;
+        head.push(byte[0]);
+    }
+    let response = String::from_utf8_lossy(&head);
+    let status_line = response.lines().next().unwrap_or_default();
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(0);
+    let message = format!("Proxy CONNECT to {} failed: {}", authority, status_line);
+    match status {
+        200..=299 => Ok(()),
+        407 => Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            message,
+        )),
+        502..=504 => Err(std::io::Error::new(
+            std::io::ErrorKind::HostUnreachable,
+            message,
+        )),
+        _ => Err(proxy_error(message)),
+    }
+}
+
+fn apply_proxy_env_vars(config: &GlobalProxyConfig, active: Option<&ProxyEndpoint>) {
     if !config.enabled {
         clear_proxy_env_vars();
         return;
     }
 
-    let Some(proxy_url) = config.build_proxy_url() else {
+    let Some(proxy_url) = active.map(ProxyEndpoint::proxy_url) else {
         clear_proxy_env_vars();
         return;
     };
@@ -195,7 +864,11 @@ fn merged_no_proxy(custom: Option<&str>) -> String {
         .split(',')
         .map(str::trim)
         .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
+        // 环境变量不支持任意通配，`*.example.com` 写为等价的 `.example.com`
+        .map(|s| match s.strip_prefix("*.") {
+            Some(domain) if !domain.contains('*') => format!(".{}", domain),
+            _ => s.to_string(),
+        })
         .collect();
 
     for local in LOCAL_NO_PROXY_DEFAULTS {
@@ -206,3 +879,214 @@ fn merged_no_proxy(custom: Option<&str>) -> String {
 
     entries.join(",")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(port: u16) -> ProxyEndpoint {
+        ProxyEndpoint {
+            scheme: Some("http".to_string()),
+            host: "127.0.0.1".to_string(),
+            port,
+            username: None,
+            password: None,
+        }
+    }
+
+    fn config_with_fallbacks() -> GlobalProxyConfig {
+        GlobalProxyConfig {
+            enabled: true,
+            scheme: Some("http".to_string()),
+            host: Some("127.0.0.1".to_string()),
+            port: Some(18080),
+            fallbacks: vec![endpoint(18081), endpoint(18082)],
+            health_check: ProxyHealthCheckConfig {
+                enabled: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_single_proxy_is_one_element_list() {
+        let config = GlobalProxyConfig {
+            enabled: true,
+            host: Some("proxy.local".to_string()),
+            port: Some(3128),
+            ..Default::default()
+        };
+        let endpoints = config.endpoints();
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints[0].proxy_url(), "http://proxy.local:3128");
+        assert_eq!(Some(endpoints[0].proxy_url()), config.build_proxy_url());
+
+        // 旧版配置 JSON 没有 fallbacks / health_check 字段
+        let legacy: GlobalProxyConfig =
+            serde_json::from_str(r#"{"enabled":true,"host":"proxy.local","port":3128}"#).unwrap();
+        assert!(legacy.fallbacks.is_empty());
+        assert!(!legacy.health_check.enabled);
+        assert_eq!(legacy.endpoints(), endpoints);
+    }
+
+    #[test]
+    fn test_no_proxy_suffix_and_wildcard() {
+        let rules =
+            NoProxyRules::parse("corp.example.com, .internal, *.svc.cluster.local, build-*.ci");
+        assert!(rules.matches("corp.example.com"));
+        assert!(rules.matches("git.corp.example.com"));
+        assert!(rules.matches("Wiki.Corp.Example.com."));
+        assert!(!rules.matches("notcorp.example.com"));
+        assert!(!rules.matches("example.com"));
+        assert!(rules.matches("db.internal"));
+        assert!(rules.matches("api.default.svc.cluster.local"));
+        assert!(rules.matches("build-42.ci"));
+        assert!(!rules.matches("build.ci"));
+        assert!(!rules.matches("api.openai.com"));
+        assert!(NoProxyRules::parse("*").matches("api.openai.com"));
+    }
+
+    #[test]
+    fn test_no_proxy_cidr_and_ip() {
+        let rules = NoProxyRules::parse("10.0.0.0/8,192.168.1.0/24,172.16.0.5,fd00::/8,bogus/99");
+        assert!(rules.matches("10.1.2.3"));
+        assert!(rules.matches("192.168.1.200"));
+        assert!(!rules.matches("192.168.2.1"));
+        assert!(rules.matches("172.16.0.5"));
+        assert!(!rules.matches("172.16.0.6"));
+        assert!(rules.matches("[fd12::1]"));
+        assert!(!rules.matches("2001:db8::1"));
+        assert!(!rules.matches("8.8.8.8"));
+    }
+
+    #[test]
+    fn test_localhost_always_bypasses() {
+        let rules = NoProxyRules::default();
+        assert!(rules.matches("localhost"));
+        assert!(rules.matches("app.localhost"));
+        assert!(rules.matches("127.0.0.1"));
+        assert!(rules.matches("127.10.0.1"));
+        assert!(rules.matches("[::1]"));
+        assert!(!rules.matches("example.com"));
+
+        let merged = merged_no_proxy(Some("*.corp.local, 10.0.0.0/8"));
+        assert_eq!(merged, ".corp.local,10.0.0.0/8,localhost,127.0.0.1,::1");
+    }
+
+    #[tokio::test]
+    async fn test_failover_when_primary_marked_down() {
+        set_global_proxy(config_with_fallbacks()).await;
+        assert_eq!(active_proxy_endpoint().await.unwrap().port, 18080);
+
+        mark_proxy_health(0, false, Some("connection refused".to_string())).await;
+        assert_eq!(active_proxy_endpoint().await.unwrap().port, 18081);
+        assert_eq!(
+            std::env::var("HTTP_PROXY").unwrap(),
+            "http://127.0.0.1:18081"
+        );
+
+        let health = get_proxy_health().await;
+        assert!(!health[0].healthy && !health[0].active);
+        assert_eq!(health[0].last_error.as_deref(), Some("connection refused"));
+        assert!(health[1].healthy && health[1].active);
+
+        // 所有代理不可用时保留主代理
+        mark_proxy_health(1, false, None).await;
+        mark_proxy_health(2, false, None).await;
+        assert_eq!(active_proxy_endpoint().await.unwrap().port, 18080);
+
+        // 主代理恢复后切回
+        mark_proxy_health(0, true, None).await;
+        mark_proxy_health(1, true, None).await;
+        assert_eq!(active_proxy_endpoint().await.unwrap().port, 18080);
+
+        clear_global_proxy().await;
+        assert!(active_proxy_endpoint().await.is_none());
+        assert!(get_proxy_health().await.is_empty());
+    }
+
+    /// 单连接 SOCKS5 代理：要求用户名密码认证，记录 CONNECT 目标后回显数据
+    async fn spawn_socks5_proxy() -> (u16, tokio::task::JoinHandle<(String, u16)>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let task = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 3];
+            socket.read_exact(&mut greeting).await.unwrap();
+            assert_eq!(greeting, [0x05, 0x01, 0x02]);
+            socket.write_all(&[0x05, 0x02]).await.unwrap();
+
+            let mut head = [0u8; 2];
+            socket.read_exact(&mut head).await.unwrap();
+            let mut user = vec![0u8; head[1] as usize + 1];
+            socket.read_exact(&mut user).await.unwrap();
+            let mut pass = vec![0u8; user[user.len() - 1] as usize];
+            socket.read_exact(&mut pass).await.unwrap();
+            assert_eq!(&user[..user.len() - 1], b"alice");
+            assert_eq!(pass, b"secret");
+            socket.write_all(&[0x01, 0x00]).await.unwrap();
+
+            let mut request = [0u8; 5];
+            socket.read_exact(&mut request).await.unwrap();
+            assert_eq!(request[3], 0x03, "socks5h sends the domain name");
+            let mut name = vec![0u8; request[4] as usize + 2];
+            socket.read_exact(&mut name).await.unwrap();
+            let target_port = u16::from_be_bytes([name[name.len() - 2], name[name.len() - 1]]);
+            let target = String::from_utf8(name[..name.len() - 2].to_vec()).unwrap();
+            socket
+                .write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+                .await
+                .unwrap();
+
+            let mut line = [0u8; 5];
+            socket.read_exact(&mut line).await.unwrap();
+            socket.write_all(&line).await.unwrap();
+            (target, target_port)
+        });
+        (port, task)
+    }
+
+    #[tokio::test]
+    async fn test_connect_via_socks5h_proxy() {
+        let (port, proxy) = spawn_socks5_proxy().await;
+        let endpoint = ProxyEndpoint {
+            scheme: Some("socks5h".to_string()),
+            host: "127.0.0.1".to_string(),
+            port,
+            username: Some("alice".to_string()),
+            password: Some("secret".to_string()),
+        };
+
+        let mut stream = connect_via_endpoint(&endpoint, "whois.example.org", 43)
+            .await
+            .unwrap();
+        stream.write_all(b"ping\n").await.unwrap();
+        let mut echoed = [0u8; 5];
+        stream.read_exact(&mut echoed).await.unwrap();
+
+        assert_eq!(&echoed, b"ping\n");
+        assert_eq!(proxy.await.unwrap(), ("whois.example.org".to_string(), 43));
+    }
+
+    #[tokio::test]
+    async fn test_connect_via_http_proxy_rejected() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 512];
+            let n = socket.read(&mut buf).await.unwrap();
+            assert!(buf[..n].starts_with(b"CONNECT whois.example.org:43 HTTP/1.1\r\n"));
+            socket
+                .write_all(b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let err = connect_via_endpoint(&endpoint(port), "whois.example.org", 43)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("403"));
+    }
+}
@@ -2,5 +2,8 @@ pub mod prelude {
     pub use anyhow::{anyhow, Result};
 }
 
+pub mod auth_session;
+pub mod domain;
 pub mod global_proxy;
 pub mod models;
+pub mod shutdown;
@@ -151,6 +151,27 @@ impl FromStr for RiskLevel {
     }
 }
 
+/// 规范化单个标签（去除首尾空白并转为小写）
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// 规范化标签列表：去空、去重，保留首次出现的顺序
+pub fn normalize_tags<I, S>(tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = normalize_tag(tag.as_ref());
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
 /// 资产实体
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Asset {
@@ -211,7 +232,7 @@ impl Asset {
     }
 
     pub fn with_tags(mut self, tags: Vec<String>) -> Self {
-        self.tags = tags;
+        self.tags = normalize_tags(tags);
         self
     }
 
@@ -226,13 +247,15 @@ impl Asset {
     }
 
     pub fn add_tag(&mut self, tag: String) {
-        if !self.tags.contains(&tag) {
+        let tag = normalize_tag(&tag);
+        if !tag.is_empty() && !self.tags.contains(&tag) {
             self.tags.push(tag);
         }
     }
 
     pub fn remove_tag(&mut self, tag: &str) {
-        self.tags.retain(|t| t != tag);
+        let tag = normalize_tag(tag);
+        self.tags.retain(|t| *t != tag);
     }
 
     pub fn update_last_seen(&mut self) {
@@ -312,6 +335,82 @@ impl AssetRelationship {
     }
 }
 
+/// 资产关系图导出格式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetGraphFormat {
+    /// Graphviz DOT
+    Dot,
+    /// 节点/边 JSON（供前端图组件使用）
+    #[default]
+    Json,
+}
+
+/// 资产关系图节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetGraphNode {
+    pub id: String,
+    pub name: String,
+    pub value: String,
+    pub asset_type: AssetType,
+    pub risk_level: RiskLevel,
+    /// 距根节点的跳数
+    pub depth: u32,
+}
+
+/// 资产关系图的边（关系类型作为标签）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetGraphEdge {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    pub label: String,
+}
+
+/// 以某个资产为根的关系图
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetGraph {
+    pub root_id: String,
+    pub nodes: Vec<AssetGraphNode>,
+    pub edges: Vec<AssetGraphEdge>,
+    /// 是否因节点数上限而截断
+    pub truncated: bool,
+}
+
+impl AssetGraph {
+    /// 输出 Graphviz DOT
+    pub fn to_dot(&self) -> String {
+        fn escape(s: &str) -> String {
+            s.replace('\\', "\\\\").replace('"', "\\\"")
+        }
+
+        let mut dot = String::from("digraph assets {\n    rankdir=LR;\n");
+        for node in &self.nodes {
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\\n({})\"{}];\n",
+                escape(&node.id),
+                escape(&node.value),
+                node.asset_type.as_str(),
+                if node.id == self.root_id {
+                    ", shape=doublecircle"
+                } else {
+                    ""
+                }
+            ));
+        }
+        for edge in &self.edges {
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                escape(&edge.source),
+                escape(&edge.target),
+                escape(&edge.label)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
 /// 资产历史记录
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetHistory {
@@ -368,12 +467,13 @@ pub struct UpdateAssetRequest {
 }
 
 /// 资产查询过滤器
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AssetFilter {
     pub asset_types: Option<Vec<AssetType>>,
     pub statuses: Option<Vec<AssetStatus>>,
     pub risk_levels: Option<Vec<RiskLevel>>,
     pub sources: Option<Vec<String>>,
+    /// 匹配带有其中任一标签的资产
     pub tags: Option<Vec<String>>,
     pub search: Option<String>,
     pub created_after: Option<DateTime<Utc>>,
@@ -403,6 +503,26 @@ pub struct AssetDetail {
     pub history: Vec<AssetHistory>,
 }
 
+/// 批量打标签请求：按资产 ID 列表或过滤条件选中资产
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkTagAssetsRequest {
+    pub asset_ids: Option<Vec<String>>,
+    pub filter: Option<AssetFilter>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+    #[serde(default)]
+    pub remove_tags: Vec<String>,
+}
+
+/// 批量打标签结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkTagAssetsResult {
+    /// 选中的资产数
+    pub matched: usize,
+    /// 标签实际发生变化的资产数
+    pub updated: usize,
+}
+
 /// 资产导入请求
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImportAssetsRequest {
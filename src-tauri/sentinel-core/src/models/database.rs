@@ -493,6 +493,14 @@ pub struct NotificationRule {
     pub config: Option<String>,
     pub is_encrypted: bool,
     pub enabled: bool,
+    /// 触发条件（JSON：严重级别阈值、主机/范围、插件ID、漏洞状态），为空时总是触发
+    #[serde(default)]
+    #[sqlx(default)]
+    pub conditions: Option<String>,
+    /// 去重与摘要设置（JSON：去重窗口、摘要间隔），为空时逐条立即发送
+    #[serde(default)]
+    #[sqlx(default)]
+    pub throttle: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -508,6 +516,8 @@ impl NotificationRule {
             config: config.map(|v| serde_json::to_string(&v).unwrap_or_default()),
             is_encrypted: false,
             enabled: true,
+            conditions: None,
+            throttle: None,
             created_at: now,
             updated_at: now,
         }
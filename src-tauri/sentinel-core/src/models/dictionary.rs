@@ -227,6 +227,10 @@ pub struct DictionaryWord {
     pub category: Option<String>,
     pub metadata: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// 导入顺序（在文件中的位置），早期数据为空
+    #[serde(default)]
+    #[sqlx(default)]
+    pub position: Option<i64>,
 }
 
 impl DictionaryWord {
@@ -242,9 +246,15 @@ impl DictionaryWord {
             category: None,
             metadata: None,
             created_at: now,
+            position: None,
         }
     }
 
+    pub fn with_position(mut self, position: i64) -> Self {
+        self.position = Some(position);
+        self
+    }
+
     pub fn with_weight(mut self, weight: f64) -> Self {
         self.weight = weight;
         self
@@ -330,6 +340,29 @@ impl DictionarySetRelation {
     }
 }
 
+/// 词条排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordOrder {
+    /// 按权重降序（最可能命中的优先）
+    #[default]
+    Weight,
+    /// 按导入顺序（文件顺序）；没有位置的早期数据排在前面并按创建时间排序
+    Insertion,
+}
+
+impl WordOrder {
+    /// SQL ORDER BY 子句内容
+    pub fn order_by(&self) -> &'static str {
+        match self {
+            WordOrder::Weight => "COALESCE(weight, 0) DESC, word ASC",
+            WordOrder::Insertion => {
+                "CASE WHEN position IS NULL THEN 0 ELSE 1 END, position ASC, created_at ASC, word ASC"
+            }
+        }
+    }
+}
+
 /// 字典查询过滤器
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DictionaryFilter {
@@ -395,3 +428,57 @@ pub enum MergeMode {
     /// 创建新字典
     CreateNew,
 }
+
+/// 子域名变异规则（类似 altdns）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PermutationRules {
+    /// 根域名；设置后从输入中剥离，并追加到每个候选
+    pub root_domain: Option<String>,
+    /// 插入用的变异词
+    pub words: Vec<String>,
+    /// 前缀插入（dev-api、dev.api）
+    pub prefix: bool,
+    /// 后缀插入（api-dev、api.dev）
+    pub suffix: bool,
+    /// 数字变异（api1 → api2，api → api1）
+    pub number_mutation: bool,
+    /// 数字变异范围
+    pub number_range: u32,
+    /// 连字符与点互换（dev-api ↔ dev.api）
+    pub dash_dot_swap: bool,
+    /// 候选数量上限，防止组合爆炸
+    pub max_results: usize,
+}
+
+impl Default for PermutationRules {
+    fn default() -> Self {
+        Self {
+            root_domain: None,
+            words: [
+                "dev", "test", "staging", "stage", "prod", "qa", "uat", "beta", "internal", "old",
+                "new", "admin", "api",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            prefix: true,
+            suffix: true,
+            number_mutation: true,
+            number_range: 3,
+            dash_dot_swap: true,
+            max_results: 10_000,
+        }
+    }
+}
+
+/// 子域名变异结果
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PermutationResult {
+    pub candidates: Vec<String>,
+    pub count: usize,
+    /// 是否因达到上限而截断
+    pub truncated: bool,
+    /// 合并到目标字典的新词数量
+    pub merged_count: Option<usize>,
+}
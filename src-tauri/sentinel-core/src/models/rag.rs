@@ -36,6 +36,9 @@ pub struct DocumentSource {
     pub file_type: String,
     pub file_size: u64,
     pub file_hash: String,
+    /// 源文件修改时间（Unix 秒），用于增量摄取
+    #[serde(default)]
+    pub file_mtime: Option<i64>,
     pub chunk_count: usize,
     pub ingestion_status: IngestionStatusEnum,
     pub created_at: DateTime<Utc>,
@@ -119,6 +122,7 @@ impl DocumentSource {
             file_type,
             file_size,
             file_hash,
+            file_mtime: None,
             chunk_count: 0,
             ingestion_status: IngestionStatusEnum::Pending,
             created_at: Utc::now(),
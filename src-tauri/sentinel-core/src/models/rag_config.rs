@@ -35,6 +35,33 @@ pub struct RagConfig {
     pub chunk_expansion_before: usize,
     #[serde(default = "default_chunk_expansion_after")]
     pub chunk_expansion_after: usize,
+    #[serde(default)]
+    pub retrieval_mode: RetrievalMode,
+    #[serde(default = "default_hybrid_alpha")]
+    pub hybrid_alpha: f64,
+    #[serde(default)]
+    pub embedding_fallbacks: Vec<EmbeddingFallbackConfig>,
+    #[serde(default = "default_embedding_timeout_secs")]
+    pub embedding_timeout_secs: u64,
+    #[serde(default = "default_embedding_failure_threshold")]
+    pub embedding_failure_threshold: u32,
+    #[serde(default = "default_embedding_circuit_cooldown_secs")]
+    pub embedding_circuit_cooldown_secs: u64,
+}
+
+/// Secondary embedding provider used when the primary one fails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingFallbackConfig {
+    pub provider: String,
+    pub model: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub dimensions: Option<usize>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -44,6 +71,19 @@ pub enum ChunkingStrategy {
     RecursiveCharacter,
     Semantic,
     StructureAware,
+    /// 按句子聚合到 chunk_size 以内，不在句中切断
+    SentenceBoundary,
+    /// 按 Markdown 标题切分章节，代码块保持完整，并在每块前附加标题路径
+    Markdown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RetrievalMode {
+    #[default]
+    Vector,
+    Keyword,
+    Hybrid,
 }
 
 fn default_context_window() -> usize {
@@ -64,6 +104,18 @@ fn default_chunk_expansion_before() -> usize {
 fn default_chunk_expansion_after() -> usize {
     1
 }
+fn default_hybrid_alpha() -> f64 {
+    0.5
+}
+fn default_embedding_timeout_secs() -> u64 {
+    120
+}
+fn default_embedding_failure_threshold() -> u32 {
+    3
+}
+fn default_embedding_circuit_cooldown_secs() -> u64 {
+    60
+}
 
 impl Default for RagConfig {
     fn default() -> Self {
@@ -92,6 +144,12 @@ impl Default for RagConfig {
             chunk_expansion_enabled: true,
             chunk_expansion_before: 1,
             chunk_expansion_after: 1,
+            retrieval_mode: RetrievalMode::Vector,
+            hybrid_alpha: 0.5,
+            embedding_fallbacks: Vec::new(),
+            embedding_timeout_secs: 120,
+            embedding_failure_threshold: 3,
+            embedding_circuit_cooldown_secs: 60,
         }
     }
 }
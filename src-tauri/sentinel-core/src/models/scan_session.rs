@@ -221,3 +221,524 @@ impl ScanStage {
         }
     }
 }
+
+/// 扫描结果中的一项发现或资产，按指纹跨会话匹配
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanSessionItem {
+    /// 跨会话稳定的指纹（不依赖记录 ID）
+    pub fingerprint: String,
+    pub title: String,
+    pub severity: Option<String>,
+    /// 来源阶段
+    pub stage_name: Option<String>,
+    pub data: serde_json::Value,
+}
+
+/// 一个会话的发现与资产
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanSessionItems {
+    pub findings: Vec<ScanSessionItem>,
+    pub assets: Vec<ScanSessionItem>,
+}
+
+/// 两次扫描间的差异分组
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanDiffBuckets {
+    /// 仅出现在新会话中
+    pub new: Vec<ScanSessionItem>,
+    /// 仅出现在基线会话中
+    pub resolved: Vec<ScanSessionItem>,
+    /// 两次都出现（取新会话中的版本）
+    pub persisting: Vec<ScanSessionItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanSessionDiff {
+    pub base_session_id: Uuid,
+    pub compare_session_id: Uuid,
+    pub findings: ScanDiffBuckets,
+    pub assets: ScanDiffBuckets,
+}
+
+const FINDING_KEYS: &[&str] = &["findings", "vulnerabilities"];
+const ASSET_KEYS: &[&str] = &["assets"];
+
+impl ScanSessionItems {
+    /// 从各阶段结果与会话汇总中收集发现和资产
+    ///
+    /// 结果 JSON 中 `findings` / `vulnerabilities` 数组视为发现，`assets` 数组视为资产，
+    /// 同一会话内指纹相同的项只保留一次
+    pub fn collect(session: &ScanSession, stages: &[ScanStage]) -> Self {
+        let mut items = Self::default();
+        let sources = stages
+            .iter()
+            .filter_map(|s| s.results.as_ref().map(|r| (Some(s.stage_name.as_str()), r)))
+            .chain(session.results_summary.as_ref().map(|r| (None, r)));
+        for (stage_name, results) in sources {
+            for value in array_items(results, FINDING_KEYS) {
+                items.findings.push(finding_item(value, stage_name));
+            }
+            for value in array_items(results, ASSET_KEYS) {
+                items.assets.push(asset_item(value, stage_name));
+            }
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        items
+            .findings
+            .retain(|i| seen.insert(("finding", i.fingerprint.clone())));
+        items
+            .assets
+            .retain(|i| seen.insert(("asset", i.fingerprint.clone())));
+        items
+    }
+}
+
+impl ScanSessionDiff {
+    /// 对比基线会话 `base` 与新会话 `compare`
+    pub fn compute(
+        base_session_id: Uuid,
+        base: &ScanSessionItems,
+        compare_session_id: Uuid,
+        compare: &ScanSessionItems,
+    ) -> Self {
+        Self {
+            base_session_id,
+            compare_session_id,
+            findings: diff_items(&base.findings, &compare.findings),
+            assets: diff_items(&base.assets, &compare.assets),
+        }
+    }
+}
+
+fn diff_items(base: &[ScanSessionItem], compare: &[ScanSessionItem]) -> ScanDiffBuckets {
+    let base_prints: std::collections::HashSet<&str> =
+        base.iter().map(|i| i.fingerprint.as_str()).collect();
+    let compare_prints: std::collections::HashSet<&str> =
+        compare.iter().map(|i| i.fingerprint.as_str()).collect();
+
+    let (persisting, new): (Vec<_>, Vec<_>) = compare
+        .iter()
+        .cloned()
+        .partition(|i| base_prints.contains(i.fingerprint.as_str()));
+    let resolved = base
+        .iter()
+        .filter(|i| !compare_prints.contains(i.fingerprint.as_str()))
+        .cloned()
+        .collect();
+    ScanDiffBuckets {
+        new,
+        resolved,
+        persisting,
+    }
+}
+
+fn array_items<'a>(
+    results: &'a serde_json::Value,
+    keys: &'a [&str],
+) -> impl Iterator<Item = &'a serde_json::Value> {
+    keys.iter()
+        .filter_map(move |k| results.get(k).and_then(|v| v.as_array()))
+        .flatten()
+}
+
+fn str_field<'a>(value: &'a serde_json::Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|k| value.get(k).and_then(|v| v.as_str()))
+        .filter(|s| !s.is_empty())
+}
+
+/// 发现指纹：优先使用结果自带的 fingerprint / signature，
+/// 否则按插件、漏洞类型、URL 与位置计算（与流量漏洞签名的字段一致）
+fn finding_item(value: &serde_json::Value, stage_name: Option<&str>) -> ScanSessionItem {
+    let fingerprint = str_field(value, &["fingerprint", "signature"])
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            let parts: Vec<&str> = [
+                &["plugin_id", "tool"][..],
+                &["vuln_type", "type"],
+                &["url", "target", "host"],
+                &["location", "parameter", "param"],
+            ]
+            .iter()
+            .map(|keys| str_field(value, keys).unwrap_or(""))
+            .collect();
+            format!("{:x}", md5::compute(parts.join("\n").to_lowercase()))
+        });
+    ScanSessionItem {
+        fingerprint,
+        title: str_field(value, &["title", "name", "vuln_type", "type"])
+            .unwrap_or("untitled")
+            .to_string(),
+        severity: str_field(value, &["severity"]).map(str::to_string),
+        stage_name: stage_name.map(str::to_string),
+        data: value.clone(),
+    }
+}
+
+/// 资产指纹：资产类型 + 规范化后的值，字符串项直接视为资产值
+fn asset_item(value: &serde_json::Value, stage_name: Option<&str>) -> ScanSessionItem {
+    let asset_type = str_field(value, &["asset_type", "type"]).unwrap_or("");
+    let asset_value = value
+        .as_str()
+        .or_else(|| str_field(value, &["value", "host", "domain", "ip", "url", "name"]))
+        .unwrap_or("")
+        .trim()
+        .trim_end_matches('/')
+        .to_lowercase();
+    let fingerprint = str_field(value, &["fingerprint"])
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{}:{}", asset_type.to_lowercase(), asset_value));
+    ScanSessionItem {
+        fingerprint,
+        title: asset_value,
+        severity: None,
+        stage_name: stage_name.map(str::to_string),
+        data: value.clone(),
+    }
+}
+
+/// 进度吞吐量的采样窗口（最近 N 次上报）
+const PROGRESS_SAMPLE_WINDOW: usize = 10;
+
+/// 扫描进度事件名，payload 为 [`ScanProgressEvent`]
+pub const SCAN_PROGRESS_EVENT: &str = "scan:progress";
+
+/// 阶段级进度事件，扫描过程中通过 [`SCAN_PROGRESS_EVENT`] 推送给前端
+///
+/// `stage_name` 与会话当前的 `current_stage` 一致
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanProgressEvent {
+    pub session_id: Uuid,
+    pub stage_name: String,
+    pub done: u64,
+    pub total: u64,
+    /// 每秒完成的条目数（按最近的采样窗口计算）
+    pub throughput: f64,
+    /// 预计剩余秒数，吞吐量未知时为空
+    pub eta_seconds: Option<i64>,
+    pub estimated_completion: Option<chrono::DateTime<chrono::Utc>>,
+    pub overall_progress: f64,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// 跟踪一个阶段的完成数量，按最近吞吐量估算剩余时间
+#[derive(Debug, Clone)]
+pub struct ScanProgressTracker {
+    session_id: Uuid,
+    stage_name: String,
+    total: u64,
+    done: u64,
+    samples: std::collections::VecDeque<(chrono::DateTime<chrono::Utc>, u64)>,
+}
+
+impl ScanProgressTracker {
+    pub fn new(session_id: Uuid, stage_name: impl Into<String>, total: u64) -> Self {
+        Self::start_at(session_id, stage_name, total, chrono::Utc::now())
+    }
+
+    pub fn start_at(
+        session_id: Uuid,
+        stage_name: impl Into<String>,
+        total: u64,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        Self {
+            session_id,
+            stage_name: stage_name.into(),
+            total,
+            done: 0,
+            samples: std::collections::VecDeque::from([(now, 0)]),
+        }
+    }
+
+    /// 切换到新的阶段名，已完成数量与吞吐量采样保留
+    pub fn set_stage(&mut self, stage_name: impl Into<String>) {
+        self.stage_name = stage_name.into();
+    }
+
+    /// 上报当前完成数量并生成进度事件
+    pub fn record(&mut self, done: u64) -> ScanProgressEvent {
+        self.record_at(done, chrono::Utc::now())
+    }
+
+    pub fn record_at(
+        &mut self,
+        done: u64,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> ScanProgressEvent {
+        // 完成数只增不减，且不超过总数
+        self.done = done.max(self.done).min(self.total);
+        self.samples.push_back((now, self.done));
+        while self.samples.len() > PROGRESS_SAMPLE_WINDOW {
+            self.samples.pop_front();
+        }
+
+        let throughput = match (self.samples.front(), self.samples.back()) {
+            (Some((first_at, first_done)), Some((last_at, last_done))) => {
+                let secs =
+                    last_at.signed_duration_since(*first_at).num_milliseconds() as f64 / 1000.0;
+                if secs > 0.0 {
+                    (last_done - first_done) as f64 / secs
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        };
+        let remaining = self.total - self.done;
+        let eta_seconds = if remaining == 0 {
+            Some(0)
+        } else if throughput > 0.0 {
+            Some((remaining as f64 / throughput).ceil() as i64)
+        } else {
+            None
+        };
+
+        ScanProgressEvent {
+            session_id: self.session_id,
+            stage_name: self.stage_name.clone(),
+            done: self.done,
+            total: self.total,
+            throughput,
+            eta_seconds,
+            estimated_completion: eta_seconds.map(|secs| now + chrono::Duration::seconds(secs)),
+            overall_progress: if self.total == 0 {
+                100.0
+            } else {
+                self.done as f64 / self.total as f64 * 100.0
+            },
+            timestamp: now,
+        }
+    }
+}
+
+/// 带耗时估算的扫描阶段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanStageWithEstimate {
+    #[serde(flatten)]
+    pub stage: ScanStage,
+    /// 已耗时（毫秒），未开始为空
+    pub elapsed_ms: Option<i64>,
+    /// 预计剩余耗时（毫秒），无已完成阶段可参考时为空
+    pub estimated_remaining_ms: Option<i64>,
+    pub estimated_completion: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ScanStageWithEstimate {
+    /// 以已完成阶段的平均耗时估算运行中和待执行阶段（阶段按执行顺序传入）
+    pub fn estimate(stages: Vec<ScanStage>, now: chrono::DateTime<chrono::Utc>) -> Vec<Self> {
+        let durations: Vec<i64> = stages
+            .iter()
+            .filter(|s| matches!(s.status, ScanStageStatus::Completed))
+            .filter_map(|s| s.duration_ms)
+            .collect();
+        let average_ms =
+            (!durations.is_empty()).then(|| durations.iter().sum::<i64>() / durations.len() as i64);
+
+        let mut cursor = Some(now);
+        stages
+            .into_iter()
+            .map(|stage| {
+                let (elapsed_ms, remaining_ms, completion) = match stage.status {
+                    ScanStageStatus::Completed
+                    | ScanStageStatus::Failed
+                    | ScanStageStatus::Skipped => (
+                        stage.duration_ms.or_else(|| {
+                            Some(
+                                stage
+                                    .completed_at?
+                                    .signed_duration_since(stage.started_at?)
+                                    .num_milliseconds(),
+                            )
+                        }),
+                        Some(0),
+                        stage.completed_at,
+                    ),
+                    ScanStageStatus::Running => {
+                        let elapsed = stage
+                            .started_at
+                            .map(|started| now.signed_duration_since(started).num_milliseconds());
+                        let remaining = average_ms.map(|avg| (avg - elapsed.unwrap_or(0)).max(0));
+                        (elapsed, remaining, None)
+                    }
+                    ScanStageStatus::Pending => (None, average_ms, None),
+                };
+                let completion = completion.or_else(|| {
+                    if remaining_ms == Some(0) {
+                        return None;
+                    }
+                    let next = cursor? + chrono::Duration::milliseconds(remaining_ms?);
+                    cursor = Some(next);
+                    Some(next)
+                });
+                if remaining_ms.is_none() {
+                    cursor = None;
+                }
+                Self {
+                    stage,
+                    elapsed_ms,
+                    estimated_remaining_ms: remaining_ms,
+                    estimated_completion: completion,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn session_with(stage_results: serde_json::Value) -> (ScanSession, Vec<ScanStage>) {
+        let session = ScanSession::new(
+            "nightly".to_string(),
+            "https://example.com".to_string(),
+            "web".to_string(),
+            json!({}),
+            None,
+        );
+        let mut stage = ScanStage::new(
+            session.id,
+            "vuln_scan".to_string(),
+            1,
+            "scanner".to_string(),
+            json!({}),
+        );
+        stage.complete(Some(stage_results));
+        (session, vec![stage])
+    }
+
+    #[test]
+    fn test_diff_buckets_by_fingerprint() {
+        let (base_session, base_stages) = session_with(json!({
+            "findings": [
+                {"id": "1", "plugin_id": "xss", "vuln_type": "xss", "url": "https://example.com/search", "location": "q", "title": "Reflected XSS"},
+                {"id": "2", "plugin_id": "sqli", "vuln_type": "sqli", "url": "https://example.com/item", "location": "id", "title": "SQL injection"},
+                {"id": "3", "signature": "cors-1", "title": "CORS"}
+            ],
+            "assets": ["api.example.com", {"type": "domain", "value": "old.example.com"}]
+        }));
+        // 同一问题在新会话中 ID 不同，仍应按指纹匹配
+        let (compare_session, compare_stages) = session_with(json!({
+            "findings": [
+                {"id": "9", "plugin_id": "xss", "vuln_type": "xss", "url": "https://example.com/search", "location": "q", "title": "Reflected XSS"},
+                {"id": "10", "signature": "cors-1", "title": "CORS"},
+                {"id": "11", "plugin_id": "lfi", "vuln_type": "lfi", "url": "https://example.com/file", "location": "path", "title": "LFI"},
+                {"id": "12", "signature": "cors-1", "title": "CORS (duplicate)"}
+            ],
+            "assets": ["API.example.com/", "new.example.com"]
+        }));
+
+        let base = ScanSessionItems::collect(&base_session, &base_stages);
+        let compare = ScanSessionItems::collect(&compare_session, &compare_stages);
+        let diff = ScanSessionDiff::compute(base_session.id, &base, compare_session.id, &compare);
+
+        let titles = |items: &[ScanSessionItem]| {
+            let mut titles: Vec<String> = items.iter().map(|i| i.title.clone()).collect();
+            titles.sort();
+            titles
+        };
+        assert_eq!(titles(&diff.findings.new), vec!["LFI"]);
+        assert_eq!(titles(&diff.findings.resolved), vec!["SQL injection"]);
+        assert_eq!(
+            titles(&diff.findings.persisting),
+            vec!["CORS", "Reflected XSS"]
+        );
+        assert_eq!(
+            diff.findings
+                .persisting
+                .iter()
+                .find(|i| i.title == "CORS")
+                .unwrap()
+                .data["id"],
+            "10"
+        );
+
+        assert_eq!(titles(&diff.assets.new), vec!["new.example.com"]);
+        assert_eq!(titles(&diff.assets.resolved), vec!["old.example.com"]);
+        assert_eq!(titles(&diff.assets.persisting), vec!["api.example.com"]);
+    }
+
+    #[test]
+    fn test_progress_events_monotonic_with_eta() {
+        let start = chrono::Utc::now();
+        let mut tracker = ScanProgressTracker::start_at(Uuid::new_v4(), "batch", 10, start);
+
+        // 每 2 秒完成一项，中间有一次回退的上报
+        let reports = [1, 2, 3, 2, 5, 6];
+        let events: Vec<ScanProgressEvent> = reports
+            .iter()
+            .enumerate()
+            .map(|(i, done)| {
+                tracker.record_at(*done, start + chrono::Duration::seconds(2 * (i as i64 + 1)))
+            })
+            .collect();
+
+        assert!(events.windows(2).all(|w| w[0].done <= w[1].done));
+        assert_eq!(events[3].done, 3);
+
+        let last = events.last().unwrap();
+        assert_eq!(last.done, 6);
+        assert_eq!(last.overall_progress, 60.0);
+        assert!((last.throughput - 0.5).abs() < 1e-9);
+        // 剩余 4 项，每秒 0.5 项
+        assert_eq!(last.eta_seconds, Some(8));
+        assert_eq!(
+            last.estimated_completion,
+            Some(last.timestamp + chrono::Duration::seconds(8))
+        );
+
+        assert_eq!(last.stage_name, "batch");
+
+        tracker.set_stage("batch_execution_completed");
+        let done = tracker.record_at(12, start + chrono::Duration::seconds(20));
+        assert_eq!(done.stage_name, "batch_execution_completed");
+        assert_eq!(done.done, 10);
+        assert_eq!(done.eta_seconds, Some(0));
+    }
+
+    #[test]
+    fn test_stage_estimates_from_completed_durations() {
+        let now = chrono::Utc::now();
+        let session_id = Uuid::new_v4();
+        let stage = |order: i32, status: ScanStageStatus, duration_ms: Option<i64>| {
+            let mut stage = ScanStage::new(
+                session_id,
+                format!("stage-{}", order),
+                order,
+                "tool".to_string(),
+                json!({}),
+            );
+            stage.status = status;
+            stage.duration_ms = duration_ms;
+            stage
+        };
+        let mut running = stage(3, ScanStageStatus::Running, None);
+        running.started_at = Some(now - chrono::Duration::seconds(10));
+
+        let estimates = ScanStageWithEstimate::estimate(
+            vec![
+                stage(1, ScanStageStatus::Completed, Some(20_000)),
+                stage(2, ScanStageStatus::Completed, Some(40_000)),
+                running,
+                stage(4, ScanStageStatus::Pending, None),
+            ],
+            now,
+        );
+
+        assert_eq!(estimates[0].estimated_remaining_ms, Some(0));
+        assert_eq!(estimates[2].elapsed_ms, Some(10_000));
+        assert_eq!(estimates[2].estimated_remaining_ms, Some(20_000));
+        assert_eq!(
+            estimates[2].estimated_completion,
+            Some(now + chrono::Duration::seconds(20))
+        );
+        assert_eq!(estimates[3].estimated_remaining_ms, Some(30_000));
+        assert_eq!(
+            estimates[3].estimated_completion,
+            Some(now + chrono::Duration::seconds(50))
+        );
+    }
+}
@@ -0,0 +1,162 @@
+//! 优雅退出协调器
+//!
+//! 退出时先通过取消令牌通知各子系统停止（扫描、工作流、Agent 流），再在有限时间内
+//! 等待进行中的操作（数据库写入、通知发送等）结束，最后才关闭数据库并退出进程。
+//! 需要在退出前完成的操作用 [`begin_operation`] 登记，守卫 drop 时视为完成。
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// 默认等待进行中操作结束的最长时间
+pub const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+static COORDINATOR: LazyLock<Arc<ShutdownCoordinator>> =
+    LazyLock::new(|| Arc::new(ShutdownCoordinator::new()));
+
+/// 排空结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrainOutcome {
+    /// 所有操作均已在超时前结束
+    pub drained: bool,
+    /// 超时时仍未结束的操作数
+    pub remaining: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct ShutdownCoordinator {
+    token: CancellationToken,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 退出开始时被取消的令牌，长时间运行的任务可据此提前结束
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// 当前进行中的操作数
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// 登记一个需在退出前完成的操作；已开始退出时返回 None
+    pub fn begin_operation(self: &Arc<Self>, name: &'static str) -> Option<OperationGuard> {
+        if self.is_shutting_down() {
+            tracing::debug!("Rejecting {} during shutdown", name);
+            return None;
+        }
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        Some(OperationGuard {
+            coordinator: self.clone(),
+        })
+    }
+
+    fn finish_operation(&self) {
+        if self.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+
+    /// 发出退出信号并等待进行中的操作结束，最多等待 `timeout`
+    pub async fn drain(&self, timeout: Duration) -> DrainOutcome {
+        self.token.cancel();
+        let wait_idle = async {
+            loop {
+                // 先创建 Notified 再检查计数，避免错过通知
+                let idle = self.idle.notified();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        };
+        let drained = tokio::time::timeout(timeout, wait_idle).await.is_ok();
+        DrainOutcome {
+            drained,
+            remaining: self.in_flight(),
+        }
+    }
+}
+
+/// 进行中操作的守卫，drop 时操作结束
+#[derive(Debug)]
+pub struct OperationGuard {
+    coordinator: Arc<ShutdownCoordinator>,
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        self.coordinator.finish_operation();
+    }
+}
+
+/// 全局协调器
+pub fn coordinator() -> Arc<ShutdownCoordinator> {
+    COORDINATOR.clone()
+}
+
+/// 在全局协调器上登记操作
+pub fn begin_operation(name: &'static str) -> Option<OperationGuard> {
+    COORDINATOR.begin_operation(name)
+}
+
+/// 全局退出信号
+pub fn shutdown_token() -> CancellationToken {
+    COORDINATOR.token()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+
+    #[tokio::test]
+    async fn test_pending_write_completes_before_drain_returns() {
+        let coordinator = Arc::new(ShutdownCoordinator::new());
+        let written = Arc::new(AtomicBool::new(false));
+
+        let guard = coordinator.begin_operation("db write").unwrap();
+        let written_clone = written.clone();
+        tokio::spawn(async move {
+            let _guard = guard;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            written_clone.store(true, Ordering::SeqCst);
+        });
+
+        let outcome = coordinator.drain(Duration::from_secs(5)).await;
+        assert!(written.load(Ordering::SeqCst));
+        assert_eq!(
+            outcome,
+            DrainOutcome {
+                drained: true,
+                remaining: 0
+            }
+        );
+        // 退出开始后不再接受新操作
+        assert!(coordinator.begin_operation("late write").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_drain_is_bounded_by_timeout() {
+        let coordinator = Arc::new(ShutdownCoordinator::new());
+        let _stuck = coordinator.begin_operation("stuck").unwrap();
+        let token = coordinator.token();
+
+        let outcome = coordinator.drain(Duration::from_millis(50)).await;
+        assert!(token.is_cancelled());
+        assert!(!outcome.drained);
+        assert_eq!(outcome.remaining, 1);
+    }
+}
@@ -0,0 +1,147 @@
+//! Agent replan history
+//!
+//! One row per replan of an agent execution. The event itself is stored as
+//! JSON so the history outlives the todo list, which is deleted when the
+//! execution finishes.
+
+use crate::database_service::connection_manager::DatabasePool;
+use crate::database_service::service::DatabaseService;
+use anyhow::Result;
+use chrono::Utc;
+
+pub(super) const CREATE_REPLAN_EVENTS_TABLE: &str = r#"CREATE TABLE IF NOT EXISTS agent_replan_events (
+    execution_id VARCHAR(128) NOT NULL,
+    event_index BIGINT NOT NULL,
+    event_json TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    PRIMARY KEY (execution_id, event_index)
+)"#;
+
+impl DatabaseService {
+    /// Append a replan event (JSON) to the history of an execution
+    pub async fn append_agent_replan_event(
+        &self,
+        execution_id: &str,
+        event_json: &str,
+    ) -> Result<()> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let now = Utc::now().timestamp_millis();
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query(
+                    "INSERT INTO agent_replan_events (execution_id, event_index, event_json, created_at)
+                     SELECT $1, COUNT(*), $2, $3 FROM agent_replan_events WHERE execution_id = $1",
+                )
+                .bind(execution_id)
+                .bind(event_json)
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query(
+                    "INSERT INTO agent_replan_events (execution_id, event_index, event_json, created_at)
+                     SELECT ?, COUNT(*), ?, ? FROM agent_replan_events WHERE execution_id = ?",
+                )
+                .bind(execution_id)
+                .bind(event_json)
+                .bind(now)
+                .bind(execution_id)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                // MySQL cannot select from the table being inserted into
+                let (count,): (i64,) = sqlx::query_as(
+                    "SELECT COUNT(*) FROM agent_replan_events WHERE execution_id = ?",
+                )
+                .bind(execution_id)
+                .fetch_one(pool)
+                .await?;
+                sqlx::query(
+                    "INSERT INTO agent_replan_events (execution_id, event_index, event_json, created_at)
+                     VALUES (?, ?, ?, ?)",
+                )
+                .bind(execution_id)
+                .bind(count)
+                .bind(event_json)
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Replan events (JSON) of an execution, oldest first
+    pub async fn list_agent_replan_events(&self, execution_id: &str) -> Result<Vec<String>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let rows: Vec<(String,)> = match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query_as(
+                    "SELECT event_json FROM agent_replan_events WHERE execution_id = $1 ORDER BY event_index",
+                )
+                .bind(execution_id)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query_as(
+                    "SELECT event_json FROM agent_replan_events WHERE execution_id = ? ORDER BY event_index",
+                )
+                .bind(execution_id)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query_as(
+                    "SELECT event_json FROM agent_replan_events WHERE execution_id = ? ORDER BY event_index",
+                )
+                .bind(execution_id)
+                .fetch_all(pool)
+                .await?
+            }
+        };
+        Ok(rows.into_iter().map(|(json,)| json).collect())
+    }
+}
+
+#[cfg(all(test, feature = "db-sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database_service::test_service;
+
+    #[tokio::test]
+    async fn test_replan_events_append_in_order() {
+        let service = test_service(&[]).await;
+        service
+            .append_agent_replan_event("exec-1", r#"{"n":1}"#)
+            .await
+            .unwrap();
+        service
+            .append_agent_replan_event("exec-1", r#"{"n":2}"#)
+            .await
+            .unwrap();
+        service
+            .append_agent_replan_event("exec-2", r#"{"n":3}"#)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            service.list_agent_replan_events("exec-1").await.unwrap(),
+            vec![r#"{"n":1}"#, r#"{"n":2}"#]
+        );
+        assert_eq!(
+            service.list_agent_replan_events("exec-2").await.unwrap(),
+            vec![r#"{"n":3}"#]
+        );
+    }
+}
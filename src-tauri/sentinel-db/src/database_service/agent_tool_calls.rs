@@ -0,0 +1,261 @@
+//! Agent tool-call records
+//!
+//! One row per tool call of an agent execution (arguments with secrets
+//! redacted, a result summary, timing and outcome), so a finished run can be
+//! inspected and individual calls replayed.
+
+use crate::database_service::connection_manager::DatabasePool;
+use crate::database_service::service::DatabaseService;
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+pub(super) const CREATE_TOOL_CALLS_TABLE: &str = r#"CREATE TABLE IF NOT EXISTS agent_tool_calls (
+    execution_id VARCHAR(128) NOT NULL,
+    tool_call_id VARCHAR(128) NOT NULL,
+    tool_name VARCHAR(255) NOT NULL,
+    arguments TEXT NOT NULL,
+    result_summary TEXT,
+    success BOOLEAN NOT NULL,
+    call_index BIGINT NOT NULL,
+    started_at_ms BIGINT NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    created_at BIGINT NOT NULL,
+    PRIMARY KEY (execution_id, tool_call_id)
+)"#;
+
+const SELECT_COLUMNS: &str = "execution_id, tool_call_id, tool_name, arguments, result_summary, success, call_index, started_at_ms, duration_ms";
+
+/// A persisted tool call of an agent execution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AgentToolCallRecord {
+    pub execution_id: String,
+    pub tool_call_id: String,
+    pub tool_name: String,
+    /// Tool arguments as JSON, secrets replaced by `[REDACTED]`
+    pub arguments: String,
+    /// Truncated tool result
+    pub result_summary: Option<String>,
+    pub success: bool,
+    /// Order of the call within the execution (0-based)
+    #[sqlx(rename = "call_index")]
+    pub sequence: i64,
+    pub started_at_ms: i64,
+    pub duration_ms: i64,
+}
+
+impl DatabaseService {
+    /// Insert or replace a tool call record
+    pub async fn save_agent_tool_call(&self, record: &AgentToolCallRecord) -> Result<()> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let now = Utc::now().timestamp_millis();
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query(
+                    "INSERT INTO agent_tool_calls (execution_id, tool_call_id, tool_name, arguments, result_summary, success, call_index, started_at_ms, duration_ms, created_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                     ON CONFLICT(execution_id, tool_call_id) DO UPDATE SET tool_name = excluded.tool_name, arguments = excluded.arguments, result_summary = excluded.result_summary, success = excluded.success, call_index = excluded.call_index, started_at_ms = excluded.started_at_ms, duration_ms = excluded.duration_ms",
+                )
+                .bind(&record.execution_id)
+                .bind(&record.tool_call_id)
+                .bind(&record.tool_name)
+                .bind(&record.arguments)
+                .bind(&record.result_summary)
+                .bind(record.success)
+                .bind(record.sequence)
+                .bind(record.started_at_ms)
+                .bind(record.duration_ms)
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query(
+                    "INSERT INTO agent_tool_calls (execution_id, tool_call_id, tool_name, arguments, result_summary, success, call_index, started_at_ms, duration_ms, created_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                     ON CONFLICT(execution_id, tool_call_id) DO UPDATE SET tool_name = excluded.tool_name, arguments = excluded.arguments, result_summary = excluded.result_summary, success = excluded.success, call_index = excluded.call_index, started_at_ms = excluded.started_at_ms, duration_ms = excluded.duration_ms",
+                )
+                .bind(&record.execution_id)
+                .bind(&record.tool_call_id)
+                .bind(&record.tool_name)
+                .bind(&record.arguments)
+                .bind(&record.result_summary)
+                .bind(record.success)
+                .bind(record.sequence)
+                .bind(record.started_at_ms)
+                .bind(record.duration_ms)
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query(
+                    "INSERT INTO agent_tool_calls (execution_id, tool_call_id, tool_name, arguments, result_summary, success, call_index, started_at_ms, duration_ms, created_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                     ON DUPLICATE KEY UPDATE tool_name = VALUES(tool_name), arguments = VALUES(arguments), result_summary = VALUES(result_summary), success = VALUES(success), call_index = VALUES(call_index), started_at_ms = VALUES(started_at_ms), duration_ms = VALUES(duration_ms)",
+                )
+                .bind(&record.execution_id)
+                .bind(&record.tool_call_id)
+                .bind(&record.tool_name)
+                .bind(&record.arguments)
+                .bind(&record.result_summary)
+                .bind(record.success)
+                .bind(record.sequence)
+                .bind(record.started_at_ms)
+                .bind(record.duration_ms)
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Tool calls of an execution in call order
+    pub async fn list_agent_tool_calls(
+        &self,
+        execution_id: &str,
+    ) -> Result<Vec<AgentToolCallRecord>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let records = match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM agent_tool_calls WHERE execution_id = $1 ORDER BY call_index, started_at_ms",
+                    SELECT_COLUMNS
+                ))
+                .bind(execution_id)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM agent_tool_calls WHERE execution_id = ? ORDER BY call_index, started_at_ms",
+                    SELECT_COLUMNS
+                ))
+                .bind(execution_id)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM agent_tool_calls WHERE execution_id = ? ORDER BY call_index, started_at_ms",
+                    SELECT_COLUMNS
+                ))
+                .bind(execution_id)
+                .fetch_all(pool)
+                .await?
+            }
+        };
+        Ok(records)
+    }
+
+    pub async fn get_agent_tool_call(
+        &self,
+        execution_id: &str,
+        tool_call_id: &str,
+    ) -> Result<Option<AgentToolCallRecord>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let record = match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM agent_tool_calls WHERE execution_id = $1 AND tool_call_id = $2",
+                    SELECT_COLUMNS
+                ))
+                .bind(execution_id)
+                .bind(tool_call_id)
+                .fetch_optional(pool)
+                .await?
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM agent_tool_calls WHERE execution_id = ? AND tool_call_id = ?",
+                    SELECT_COLUMNS
+                ))
+                .bind(execution_id)
+                .bind(tool_call_id)
+                .fetch_optional(pool)
+                .await?
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM agent_tool_calls WHERE execution_id = ? AND tool_call_id = ?",
+                    SELECT_COLUMNS
+                ))
+                .bind(execution_id)
+                .bind(tool_call_id)
+                .fetch_optional(pool)
+                .await?
+            }
+        };
+        Ok(record)
+    }
+}
+
+#[cfg(all(test, feature = "db-sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database_service::test_service;
+
+    fn record(execution_id: &str, id: &str, sequence: i64) -> AgentToolCallRecord {
+        AgentToolCallRecord {
+            execution_id: execution_id.to_string(),
+            tool_call_id: id.to_string(),
+            tool_name: "local_time".to_string(),
+            arguments: r#"{"timezone":"utc"}"#.to_string(),
+            result_summary: Some("2026-01-01 00:00:00".to_string()),
+            success: true,
+            sequence,
+            started_at_ms: 1_000 + sequence,
+            duration_ms: 5,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_round_trip_by_execution() {
+        let service = test_service(&[]).await;
+        service
+            .save_agent_tool_call(&record("exec-1", "call-b", 1))
+            .await
+            .unwrap();
+        service
+            .save_agent_tool_call(&record("exec-1", "call-a", 0))
+            .await
+            .unwrap();
+        service
+            .save_agent_tool_call(&record("exec-2", "call-c", 0))
+            .await
+            .unwrap();
+
+        let calls = service.list_agent_tool_calls("exec-1").await.unwrap();
+        let ids: Vec<&str> = calls.iter().map(|c| c.tool_call_id.as_str()).collect();
+        assert_eq!(ids, vec!["call-a", "call-b"]);
+
+        // Saving the same call again replaces it
+        let mut failed = record("exec-1", "call-b", 1);
+        failed.success = false;
+        service.save_agent_tool_call(&failed).await.unwrap();
+        let stored = service
+            .get_agent_tool_call("exec-1", "call-b")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored, failed);
+        assert!(service
+            .get_agent_tool_call("exec-1", "missing")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
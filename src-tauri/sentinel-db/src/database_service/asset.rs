@@ -4,7 +4,7 @@ use crate::database_service::service::DatabaseService;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use sqlx::FromRow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::str::FromStr;
 
 #[derive(Debug, Clone, FromRow)]
@@ -35,7 +35,7 @@ struct AssetRelationshipDbRow {
     source_asset_id: String,
     target_asset_id: String,
     relationship_type: String,
-    description: String,
+    description: Option<String>,
     confidence: f64,
     metadata: String,
     created_at: DateTime<Utc>,
@@ -98,7 +98,7 @@ fn relationship_from_db_row(row: AssetRelationshipDbRow) -> AssetRelationship {
         source_asset_id: row.source_asset_id,
         target_asset_id: row.target_asset_id,
         relationship_type,
-        description: Some(row.description),
+        description: row.description,
         confidence: row.confidence,
         metadata,
         created_at: row.created_at,
@@ -122,6 +122,12 @@ fn asset_matches_filter(asset: &Asset, filter: &AssetFilter) -> bool {
             return false;
         }
     }
+    if let Some(tags) = &filter.tags {
+        let tags = normalize_tags(tags);
+        if !tags.is_empty() && !tags.iter().any(|t| asset.tags.contains(t)) {
+            return false;
+        }
+    }
     if let Some(search) = &filter.search {
         let q = search.to_lowercase();
         let name_ok = asset.name.to_lowercase().contains(&q);
@@ -418,7 +424,7 @@ impl DatabaseService {
         let tags_json = request
             .tags
             .as_ref()
-            .map(|t| serde_json::to_string(t).unwrap_or_default());
+            .map(|t| serde_json::to_string(&normalize_tags(t)).unwrap_or_default());
         let risk_level = request.risk_level.as_ref().map(|r| r.as_str().to_string());
 
         let has_updates = project_id.is_some()
@@ -623,6 +629,23 @@ impl DatabaseService {
                             query_builder.push(")");
                         }
                     }
+                    if let Some(tags) = filter.tags {
+                        let tags = normalize_tags(tags);
+                        if !tags.is_empty() {
+                            if !has_conditions {
+                                query_builder.push(" WHERE ");
+                                has_conditions = true;
+                            } else {
+                                query_builder.push(" AND ");
+                            }
+                            query_builder.push("COALESCE(tags, '[]')::jsonb ?| ARRAY[");
+                            let mut separated = query_builder.separated(", ");
+                            for tag in tags {
+                                separated.push_bind(tag);
+                            }
+                            query_builder.push("]::text[]");
+                        }
+                    }
                     if let Some(search) = filter.search {
                         let search_pattern = format!("%{}%", search);
                         if !has_conditions {
@@ -747,6 +770,181 @@ impl DatabaseService {
         }
     }
 
+    /// 为资产添加标签，返回标签实际发生变化的资产数
+    pub async fn add_asset_tags_internal(
+        &self,
+        asset_ids: &[String],
+        tags: &[String],
+    ) -> Result<usize> {
+        let tags = normalize_tags(tags);
+        self.retag_assets(asset_ids, |asset| {
+            for tag in &tags {
+                asset.add_tag(tag.clone());
+            }
+        })
+        .await
+        .map(|result| result.updated)
+    }
+
+    /// 移除资产标签，返回标签实际发生变化的资产数
+    pub async fn remove_asset_tags_internal(
+        &self,
+        asset_ids: &[String],
+        tags: &[String],
+    ) -> Result<usize> {
+        let tags = normalize_tags(tags);
+        self.retag_assets(asset_ids, |asset| {
+            for tag in &tags {
+                asset.remove_tag(tag);
+            }
+        })
+        .await
+        .map(|result| result.updated)
+    }
+
+    /// 批量打标签：选中 ID 列表或过滤条件命中的资产（两者都提供时取并集）
+    pub async fn bulk_tag_assets_internal(
+        &self,
+        request: BulkTagAssetsRequest,
+    ) -> Result<BulkTagAssetsResult> {
+        if request.asset_ids.is_none() && request.filter.is_none() {
+            return Err(anyhow::anyhow!("必须提供资产 ID 列表或过滤条件"));
+        }
+
+        let mut asset_ids = request.asset_ids.unwrap_or_default();
+        if let Some(filter) = request.filter {
+            for asset in self.list_assets_internal(Some(filter), None, None).await? {
+                if !asset_ids.contains(&asset.id) {
+                    asset_ids.push(asset.id);
+                }
+            }
+        }
+
+        let add_tags = normalize_tags(&request.add_tags);
+        let remove_tags = normalize_tags(&request.remove_tags);
+        self.retag_assets(&asset_ids, |asset| {
+            for tag in &remove_tags {
+                asset.remove_tag(tag);
+            }
+            for tag in &add_tags {
+                asset.add_tag(tag.clone());
+            }
+        })
+        .await
+    }
+
+    async fn retag_assets<F>(
+        &self,
+        asset_ids: &[String],
+        mut apply: F,
+    ) -> Result<BulkTagAssetsResult>
+    where
+        F: FnMut(&mut Asset),
+    {
+        let mut result = BulkTagAssetsResult {
+            matched: 0,
+            updated: 0,
+        };
+        for id in asset_ids {
+            let Some(mut asset) = self.get_asset_by_id_internal(id).await? else {
+                continue;
+            };
+            result.matched += 1;
+            let before = asset.tags.clone();
+            apply(&mut asset);
+            if asset.tags == before {
+                continue;
+            }
+            let request = UpdateAssetRequest {
+                project_id: None,
+                name: None,
+                value: None,
+                description: None,
+                confidence: None,
+                status: None,
+                metadata: None,
+                tags: Some(asset.tags),
+                risk_level: None,
+            };
+            if self.update_asset_internal(id, request).await? {
+                result.updated += 1;
+            }
+        }
+        Ok(result)
+    }
+
+    /// 从根资产出发按关系做有界 BFS，构建关系图
+    ///
+    /// 关系按无向边遍历（入边和出边都会展开），已访问节点不会重复展开，
+    /// 因此环不会导致死循环；节点数达到 `max_nodes` 后停止加入新节点。
+    pub async fn build_asset_graph_internal(
+        &self,
+        root_id: &str,
+        depth: u32,
+        max_nodes: usize,
+    ) -> Result<AssetGraph> {
+        let root = self
+            .get_asset_by_id_internal(root_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("资产不存在: {}", root_id))?;
+
+        let graph_node = |asset: Asset, depth: u32| AssetGraphNode {
+            id: asset.id,
+            name: asset.name,
+            value: asset.value,
+            asset_type: asset.asset_type,
+            risk_level: asset.risk_level,
+            depth,
+        };
+
+        let mut visited: HashSet<String> = HashSet::from([root.id.clone()]);
+        let mut edge_ids: HashSet<String> = HashSet::new();
+        let mut queue = VecDeque::from([(root.id.clone(), 0u32)]);
+        let mut graph = AssetGraph {
+            root_id: root.id.clone(),
+            nodes: vec![graph_node(root, 0)],
+            edges: Vec::new(),
+            truncated: false,
+        };
+
+        while let Some((id, node_depth)) = queue.pop_front() {
+            if node_depth >= depth {
+                continue;
+            }
+            let (incoming, outgoing) = self.get_asset_relationships_internal(&id).await?;
+            for rel in incoming.into_iter().chain(outgoing) {
+                let neighbor = if rel.source_asset_id == id {
+                    &rel.target_asset_id
+                } else {
+                    &rel.source_asset_id
+                };
+                if !visited.contains(neighbor) {
+                    if graph.nodes.len() >= max_nodes {
+                        graph.truncated = true;
+                        continue;
+                    }
+                    // 跳过指向已删除资产的关系
+                    let Some(asset) = self.get_asset_by_id_internal(neighbor).await? else {
+                        continue;
+                    };
+                    visited.insert(neighbor.clone());
+                    queue.push_back((neighbor.clone(), node_depth + 1));
+                    graph.nodes.push(graph_node(asset, node_depth + 1));
+                }
+                if edge_ids.insert(rel.id.clone()) {
+                    graph.edges.push(AssetGraphEdge {
+                        label: rel.relationship_type.as_str().to_string(),
+                        id: rel.id,
+                        source: rel.source_asset_id,
+                        target: rel.target_asset_id,
+                    });
+                }
+            }
+        }
+
+        Ok(graph)
+    }
+
     /// 获取资产统计信息
     pub async fn get_asset_stats_internal(&self) -> Result<AssetStats> {
         let runtime = self
@@ -1198,3 +1396,252 @@ enum ImportAction {
     Updated,
     Skipped,
 }
+
+#[cfg(all(test, feature = "db-sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database_service::test_service;
+
+    async fn service() -> DatabaseService {
+        test_service(&[
+            r#"CREATE TABLE assets (
+                id TEXT PRIMARY KEY,
+                project_id TEXT,
+                asset_type TEXT NOT NULL,
+                name TEXT NOT NULL,
+                value TEXT NOT NULL,
+                description TEXT,
+                confidence DOUBLE DEFAULT 1.0,
+                status TEXT NOT NULL,
+                source TEXT,
+                source_scan_id TEXT,
+                metadata TEXT,
+                tags TEXT,
+                risk_level TEXT,
+                last_seen DATETIME NOT NULL,
+                first_seen DATETIME NOT NULL,
+                created_at DATETIME NOT NULL,
+                updated_at DATETIME NOT NULL,
+                created_by TEXT NOT NULL
+            )"#,
+            r#"CREATE TABLE asset_relationships (
+                id TEXT PRIMARY KEY,
+                source_asset_id TEXT NOT NULL,
+                target_asset_id TEXT NOT NULL,
+                relationship_type TEXT NOT NULL,
+                description TEXT,
+                confidence DOUBLE DEFAULT 1.0,
+                metadata TEXT,
+                created_at DATETIME NOT NULL,
+                created_by TEXT NOT NULL
+            )"#,
+        ])
+        .await
+    }
+
+    async fn create(service: &DatabaseService, asset_type: AssetType, value: &str) -> Asset {
+        service
+            .create_asset_internal(
+                CreateAssetRequest {
+                    project_id: None,
+                    asset_type,
+                    name: value.to_string(),
+                    value: value.to_string(),
+                    description: None,
+                    confidence: None,
+                    source: None,
+                    source_scan_id: None,
+                    metadata: None,
+                    tags: Some(vec![" Discovered ".to_string(), "discovered".to_string()]),
+                    risk_level: None,
+                },
+                "tester".to_string(),
+            )
+            .await
+            .unwrap()
+    }
+
+    fn tag_filter(tag: &str) -> Option<AssetFilter> {
+        Some(AssetFilter {
+            tags: Some(vec![tag.to_string()]),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_tags_are_normalized_and_removable() {
+        let service = service().await;
+        let asset = create(&service, AssetType::Domain, "example.com").await;
+        assert_eq!(asset.tags, vec!["discovered"]);
+
+        let ids = vec![asset.id.clone()];
+        let tags = vec![
+            "PROD".to_string(),
+            " pci-scope ".to_string(),
+            "prod".to_string(),
+        ];
+        assert_eq!(
+            service.add_asset_tags_internal(&ids, &tags).await.unwrap(),
+            1
+        );
+        // Adding the same tags again changes nothing
+        assert_eq!(
+            service.add_asset_tags_internal(&ids, &tags).await.unwrap(),
+            0
+        );
+        let stored = service
+            .get_asset_by_id_internal(&asset.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.tags, vec!["discovered", "prod", "pci-scope"]);
+
+        let removed = service
+            .remove_asset_tags_internal(&ids, &["Discovered".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        let stored = service
+            .get_asset_by_id_internal(&asset.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.tags, vec!["prod", "pci-scope"]);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_tag_filtered_set_and_filter_by_tag() {
+        let service = service().await;
+        let domain = create(&service, AssetType::Domain, "example.com").await;
+        let ip_a = create(&service, AssetType::Ip, "10.0.0.1").await;
+        let ip_b = create(&service, AssetType::Ip, "10.0.0.2").await;
+
+        let result = service
+            .bulk_tag_assets_internal(BulkTagAssetsRequest {
+                filter: Some(AssetFilter {
+                    asset_types: Some(vec![AssetType::Ip]),
+                    ..Default::default()
+                }),
+                add_tags: vec!["Internal".to_string()],
+                remove_tags: vec!["discovered".to_string()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.matched, 2);
+        assert_eq!(result.updated, 2);
+
+        let internal = service
+            .list_assets_internal(tag_filter(" INTERNAL"), None, None)
+            .await
+            .unwrap();
+        let mut ids: Vec<&str> = internal.iter().map(|a| a.id.as_str()).collect();
+        ids.sort();
+        let mut expected = vec![ip_a.id.as_str(), ip_b.id.as_str()];
+        expected.sort();
+        assert_eq!(ids, expected);
+        assert!(internal.iter().all(|a| a.tags == vec!["internal"]));
+
+        let discovered = service
+            .list_assets_internal(tag_filter("discovered"), None, None)
+            .await
+            .unwrap();
+        assert_eq!(discovered.len(), 1);
+        assert_eq!(discovered[0].id, domain.id);
+
+        // An id list is combined with the filter; unknown ids are skipped
+        let result = service
+            .bulk_tag_assets_internal(BulkTagAssetsRequest {
+                asset_ids: Some(vec![domain.id.clone(), "missing".to_string()]),
+                add_tags: vec!["external".to_string()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!((result.matched, result.updated), (1, 1));
+        assert!(service
+            .bulk_tag_assets_internal(BulkTagAssetsRequest::default())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_graph_export_handles_cycles() {
+        let service = service().await;
+        let domain = create(&service, AssetType::Domain, "example.com").await;
+        let ip = create(&service, AssetType::Ip, "10.0.0.1").await;
+        let port = create(&service, AssetType::Port, "10.0.0.1:443").await;
+        let website = create(&service, AssetType::Website, "https://example.com").await;
+        create(&service, AssetType::Domain, "unrelated.com").await;
+
+        // domain -> ip -> port -> domain forms a cycle; port -> website hangs off it
+        for (source, target, kind) in [
+            (&domain, &ip, RelationshipType::ResolvesTo),
+            (&ip, &port, RelationshipType::Exposes),
+            (&port, &domain, RelationshipType::BelongsTo),
+            (&port, &website, RelationshipType::Hosts),
+        ] {
+            service
+                .create_relationship_internal(
+                    source.id.clone(),
+                    target.id.clone(),
+                    kind,
+                    "tester".to_string(),
+                )
+                .await
+                .unwrap();
+        }
+
+        let graph = service
+            .build_asset_graph_internal(&domain.id, 5, 100)
+            .await
+            .unwrap();
+        assert!(!graph.truncated);
+        let mut values: Vec<&str> = graph.nodes.iter().map(|n| n.value.as_str()).collect();
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                "10.0.0.1",
+                "10.0.0.1:443",
+                "example.com",
+                "https://example.com"
+            ]
+        );
+        assert_eq!(graph.edges.len(), 4);
+        let website_node = graph.nodes.iter().find(|n| n.id == website.id).unwrap();
+        assert_eq!(website_node.depth, 2);
+        let labels: HashSet<&str> = graph.edges.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            HashSet::from(["resolves_to", "exposes", "belongs_to", "hosts"])
+        );
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph assets {"));
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\" [label=\"hosts\"]",
+            port.id, website.id
+        )));
+
+        // Depth 1 only reaches direct neighbours, in either direction
+        let graph = service
+            .build_asset_graph_internal(&domain.id, 1, 100)
+            .await
+            .unwrap();
+        assert_eq!(graph.nodes.len(), 3);
+        assert_eq!(graph.edges.len(), 2);
+
+        let graph = service
+            .build_asset_graph_internal(&domain.id, 5, 2)
+            .await
+            .unwrap();
+        assert_eq!(graph.nodes.len(), 2);
+        assert!(graph.truncated);
+        assert!(graph
+            .edges
+            .iter()
+            .all(|e| graph.nodes.iter().any(|n| n.id == e.source)
+                && graph.nodes.iter().any(|n| n.id == e.target)));
+    }
+}
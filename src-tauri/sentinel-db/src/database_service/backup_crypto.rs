@@ -0,0 +1,201 @@
+//! Encrypted database backups
+//!
+//! An encrypted backup is a single file: a fixed header (magic, format
+//! version, Argon2id salt, AES-GCM nonce) followed by the AES-256-GCM
+//! ciphertext of the plain backup (SQLite file or SQL dump). Files without
+//! the header are treated as plain backups.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::Result;
+use argon2::Argon2;
+use rand::RngCore;
+
+const MAGIC: &[u8; 7] = b"SNTLBAK";
+const FORMAT_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + SALT_LEN + NONCE_LEN;
+
+/// File extension appended to encrypted backups
+pub const ENCRYPTED_BACKUP_EXTENSION: &str = "enc";
+
+/// Whether the data starts with the encrypted backup header
+pub fn is_encrypted_backup(data: &[u8]) -> bool {
+    data.len() >= MAGIC.len() && &data[..MAGIC.len()] == MAGIC
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow::anyhow!("密钥派生失败: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt a plain backup with a passphrase
+pub fn encrypt_backup(plain: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    if passphrase.is_empty() {
+        return Err(anyhow::anyhow!("备份密码不能为空"));
+    }
+
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    let mut rng = rand::thread_rng();
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut nonce);
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new(&key.into());
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plain)
+        .map_err(|_| anyhow::anyhow!("备份加密失败"))?;
+
+    let mut out = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.push(FORMAT_VERSION);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Decrypt an encrypted backup; fails on a wrong passphrase or tampered data
+pub fn decrypt_backup(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    if !is_encrypted_backup(data) || data.len() < HEADER_LEN {
+        return Err(anyhow::anyhow!("不是有效的加密备份文件"));
+    }
+    let version = data[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(anyhow::anyhow!("不支持的加密备份版本: {}", version));
+    }
+
+    let salt_start = MAGIC.len() + 1;
+    let nonce_start = salt_start + SALT_LEN;
+    let salt = &data[salt_start..nonce_start];
+    let nonce = &data[nonce_start..HEADER_LEN];
+
+    let key = derive_key(passphrase, salt)?;
+    let cipher = Aes256Gcm::new(&key.into());
+    cipher
+        .decrypt(Nonce::from_slice(nonce), &data[HEADER_LEN..])
+        .map_err(|_| anyhow::anyhow!("备份解密失败：密码错误或文件已损坏"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_round_trip() {
+        let plain = b"SQLite format 3\0 some pages".to_vec();
+        let encrypted = encrypt_backup(&plain, "correct horse").unwrap();
+
+        assert!(is_encrypted_backup(&encrypted));
+        assert!(!is_encrypted_backup(&plain));
+        assert_eq!(decrypt_backup(&encrypted, "correct horse").unwrap(), plain);
+        assert!(decrypt_backup(&encrypted, "wrong horse").is_err());
+
+        // Same input encrypts differently each time (random salt and nonce)
+        assert_ne!(encrypt_backup(&plain, "correct horse").unwrap(), encrypted);
+
+        let mut tampered = encrypted.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt_backup(&tampered, "correct horse").is_err());
+        assert!(encrypt_backup(&plain, "").is_err());
+    }
+
+    #[cfg(feature = "db-sqlite")]
+    async fn open(path: &std::path::Path) -> sqlx::SqlitePool {
+        sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect(&format!("sqlite://{}?mode=rwc", path.display()))
+            .await
+            .unwrap()
+    }
+
+    #[cfg(feature = "db-sqlite")]
+    async fn count_notes(path: &std::path::Path) -> i64 {
+        let pool = open(path).await;
+        let count = sqlx::query_scalar("SELECT COUNT(*) FROM notes")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+        count
+    }
+
+    #[cfg(feature = "db-sqlite")]
+    #[tokio::test]
+    async fn test_encrypted_backup_restore_round_trip() {
+        use crate::database_service::connection_manager::DatabasePool;
+        use crate::database_service::db_config::DatabaseConfig;
+        use crate::database_service::service::DatabaseService;
+
+        let dir = std::env::temp_dir().join(format!("sentinel-backup-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db_path = dir.join("database.db");
+
+        let pool = open(&db_path).await;
+        sqlx::query("CREATE TABLE notes (body TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO notes (body) VALUES ('backed-up-note')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        let mut service = DatabaseService::new();
+        service.config = Some(DatabaseConfig {
+            path: Some(db_path.to_string_lossy().to_string()),
+            ..Default::default()
+        });
+        // SQLite backup/restore work on the database file; keep no connection open
+        let runtime = open(&db_path).await;
+        runtime.close().await;
+        service.runtime_pool = Some(DatabasePool::SQLite(runtime));
+
+        let backup_path = service
+            .backup(Some(dir.join("backup.db.enc")), Some("s3cret"))
+            .await
+            .unwrap();
+        let archive = std::fs::read(&backup_path).unwrap();
+        assert!(is_encrypted_backup(&archive));
+        assert!(!archive.windows(14).any(|w| w == b"backed-up-note"));
+        assert!(!dir.join("backup.db.enc.partial").exists());
+
+        let pool = open(&db_path).await;
+        sqlx::query("INSERT INTO notes (body) VALUES ('later-note')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        // Wrong or missing passphrase leaves the current database untouched
+        assert!(service
+            .restore(backup_path.clone(), Some("wrong"))
+            .await
+            .is_err());
+        assert!(service.restore(backup_path.clone(), None).await.is_err());
+        assert_eq!(count_notes(&db_path).await, 2);
+
+        service
+            .restore(backup_path.clone(), Some("s3cret"))
+            .await
+            .unwrap();
+        assert_eq!(count_notes(&db_path).await, 1);
+
+        // Unencrypted backups keep working
+        let plain_path = service
+            .backup(Some(dir.join("plain.db")), None)
+            .await
+            .unwrap();
+        assert!(!is_encrypted_backup(&std::fs::read(&plain_path).unwrap()));
+        service.restore(plain_path, None).await.unwrap();
+        assert_eq!(count_notes(&db_path).await, 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -459,6 +459,31 @@ pub struct ProgramScopeRow {
     pub updated_at: String,
 }
 
+impl ProgramScopeRow {
+    /// Check if a target matches this scope pattern
+    pub fn matches(&self, target: &str) -> bool {
+        match self.target_type.as_str() {
+            "domain" => target == self.target || target.ends_with(&format!(".{}", self.target)),
+            "wildcard_domain" => {
+                let base = self.target.trim_start_matches("*.");
+                target == base || target.ends_with(&format!(".{}", base))
+            }
+            "url" => target.starts_with(&self.target),
+            _ => target == self.target,
+        }
+    }
+}
+
+/// Whether a target is in scope: out-of-scope rules win over in-scope rules
+pub fn is_target_in_scope(scopes: &[ProgramScopeRow], target: &str) -> bool {
+    let matching = |scope_type: &str| {
+        scopes
+            .iter()
+            .any(|s| s.scope_type == scope_type && s.matches(target))
+    };
+    !matching("out_of_scope") && matching("in_scope")
+}
+
 /// Bounty Finding database model
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct BountyFindingRow {
@@ -1357,6 +1382,12 @@ impl DatabaseService {
 
         Ok(rows.into_iter().map(row_to_program_scope).collect())
     }
+
+    /// Whether a host or URL is in scope of any bounty program
+    pub async fn is_target_in_any_scope(&self, target: &str) -> Result<bool> {
+        let scopes = self.list_program_scopes(None, None).await?;
+        Ok(is_target_in_scope(&scopes, target))
+    }
 }
 
 /// Program statistics
@@ -5869,3 +5900,51 @@ impl DatabaseService {
         Ok(rows.into_iter().map(row_to_bounty_asset).collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope(scope_type: &str, target_type: &str, target: &str) -> ProgramScopeRow {
+        ProgramScopeRow {
+            id: target.to_string(),
+            program_id: "p1".to_string(),
+            scope_type: scope_type.to_string(),
+            target_type: target_type.to_string(),
+            target: target.to_string(),
+            description: None,
+            allowed_tests_json: None,
+            instructions_json: None,
+            requires_auth: false,
+            test_accounts_json: None,
+            asset_count: 0,
+            finding_count: 0,
+            priority: 0.0,
+            metadata_json: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_out_of_scope_rules_win() {
+        let scopes = vec![
+            scope("in_scope", "wildcard_domain", "*.example.com"),
+            scope("out_of_scope", "domain", "legacy.example.com"),
+        ];
+        assert!(is_target_in_scope(&scopes, "api.example.com"));
+        assert!(!is_target_in_scope(&scopes, "legacy.example.com"));
+        assert!(!is_target_in_scope(&scopes, "old.legacy.example.com"));
+        assert!(!is_target_in_scope(&scopes, "example.org"));
+        assert!(!is_target_in_scope(&[], "api.example.com"));
+    }
+
+    #[test]
+    fn test_wildcard_domain_needs_label_boundary() {
+        let wildcard = scope("in_scope", "wildcard_domain", "*.example.com");
+        assert!(wildcard.matches("example.com"));
+        assert!(wildcard.matches("a.b.example.com"));
+        assert!(!wildcard.matches("notexample.com"));
+        assert!(!wildcard.matches("evil-example.com"));
+    }
+}
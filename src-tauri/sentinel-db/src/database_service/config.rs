@@ -162,8 +162,8 @@ impl DatabaseService {
         match runtime {
             DatabasePool::PostgreSQL(pool) => {
                 sqlx::query(
-                    r#"INSERT INTO notification_rules (id, name, description, channel, config, is_encrypted, enabled, created_at, updated_at)
-                       VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#,
+                    r#"INSERT INTO notification_rules (id, name, description, channel, config, is_encrypted, enabled, conditions, throttle, created_at, updated_at)
+                       VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
                 )
                 .bind(&rule.id)
                 .bind(&rule.name)
@@ -172,6 +172,8 @@ impl DatabaseService {
                 .bind(&rule.config)
                 .bind(rule.is_encrypted)
                 .bind(rule.enabled)
+                .bind(&rule.conditions)
+                .bind(&rule.throttle)
                 .bind(rule.created_at)
                 .bind(rule.updated_at)
                 .execute(pool)
@@ -179,8 +181,8 @@ impl DatabaseService {
             }
             DatabasePool::SQLite(pool) => {
                 sqlx::query(
-                    r#"INSERT INTO notification_rules (id, name, description, channel, config, is_encrypted, enabled, created_at, updated_at)
-                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                    r#"INSERT INTO notification_rules (id, name, description, channel, config, is_encrypted, enabled, conditions, throttle, created_at, updated_at)
+                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
                 )
                 .bind(&rule.id)
                 .bind(&rule.name)
@@ -189,6 +191,8 @@ impl DatabaseService {
                 .bind(&rule.config)
                 .bind(rule.is_encrypted)
                 .bind(rule.enabled)
+                .bind(&rule.conditions)
+                .bind(&rule.throttle)
                 .bind(rule.created_at)
                 .bind(rule.updated_at)
                 .execute(pool)
@@ -196,8 +200,8 @@ impl DatabaseService {
             }
             DatabasePool::MySQL(pool) => {
                 sqlx::query(
-                    r#"INSERT INTO notification_rules (id, name, description, channel, config, is_encrypted, enabled, created_at, updated_at)
-                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+                    r#"INSERT INTO notification_rules (id, name, description, channel, config, is_encrypted, enabled, conditions, throttle, created_at, updated_at)
+                       VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
                 )
                 .bind(&rule.id)
                 .bind(&rule.name)
@@ -206,6 +210,8 @@ impl DatabaseService {
                 .bind(&rule.config)
                 .bind(rule.is_encrypted)
                 .bind(rule.enabled)
+                .bind(&rule.conditions)
+                .bind(&rule.throttle)
                 .bind(rule.created_at)
                 .bind(rule.updated_at)
                 .execute(pool)
@@ -292,8 +298,8 @@ impl DatabaseService {
             DatabasePool::PostgreSQL(pool) => {
                 sqlx::query(
                     r#"UPDATE notification_rules
-                       SET name = $1, description = $2, channel = $3, config = $4, is_encrypted = $5, enabled = $6, updated_at = $7
-                       WHERE id = $8"#,
+                       SET name = $1, description = $2, channel = $3, config = $4, is_encrypted = $5, enabled = $6, conditions = $7, throttle = $8, updated_at = $9
+                       WHERE id = $10"#,
                 )
                 .bind(&rule.name)
                 .bind(&rule.description)
@@ -301,6 +307,8 @@ impl DatabaseService {
                 .bind(&rule.config)
                 .bind(rule.is_encrypted)
                 .bind(rule.enabled)
+                .bind(&rule.conditions)
+                .bind(&rule.throttle)
                 .bind(chrono::Utc::now())
                 .bind(&rule.id)
                 .execute(pool)
@@ -309,7 +317,7 @@ impl DatabaseService {
             DatabasePool::SQLite(pool) => {
                 sqlx::query(
                     r#"UPDATE notification_rules
-                       SET name = ?, description = ?, channel = ?, config = ?, is_encrypted = ?, enabled = ?, updated_at = ?
+                       SET name = ?, description = ?, channel = ?, config = ?, is_encrypted = ?, enabled = ?, conditions = ?, throttle = ?, updated_at = ?
                        WHERE id = ?"#,
                 )
                 .bind(&rule.name)
@@ -318,6 +326,8 @@ impl DatabaseService {
                 .bind(&rule.config)
                 .bind(rule.is_encrypted)
                 .bind(rule.enabled)
+                .bind(&rule.conditions)
+                .bind(&rule.throttle)
                 .bind(chrono::Utc::now())
                 .bind(&rule.id)
                 .execute(pool)
@@ -326,7 +336,7 @@ impl DatabaseService {
             DatabasePool::MySQL(pool) => {
                 sqlx::query(
                     r#"UPDATE notification_rules
-                       SET name = ?, description = ?, channel = ?, config = ?, is_encrypted = ?, enabled = ?, updated_at = ?
+                       SET name = ?, description = ?, channel = ?, config = ?, is_encrypted = ?, enabled = ?, conditions = ?, throttle = ?, updated_at = ?
                        WHERE id = ?"#,
                 )
                 .bind(&rule.name)
@@ -335,6 +345,8 @@ impl DatabaseService {
                 .bind(&rule.config)
                 .bind(rule.is_encrypted)
                 .bind(rule.enabled)
+                .bind(&rule.conditions)
+                .bind(&rule.throttle)
                 .bind(chrono::Utc::now())
                 .bind(&rule.id)
                 .execute(pool)
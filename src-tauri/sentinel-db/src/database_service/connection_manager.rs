@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::info;
 
 use super::db_config::{DatabaseConfig, DatabaseType};
+use super::pool_health::{recycle_hook, PoolHealth};
 #[cfg(feature = "db-mysql")]
 use super::sqlx_compat::MySqlPoolOptions;
 #[cfg(feature = "db-postgres")]
@@ -19,6 +21,14 @@ pub enum DatabasePool {
 
 impl DatabasePool {
     pub async fn connect(config: &DatabaseConfig) -> Result<Self> {
+        Self::connect_with_health(config, Arc::new(PoolHealth::new())).await
+    }
+
+    /// 建立连接池，并挂载由 `health` 驱动的旧连接回收钩子
+    pub async fn connect_with_health(
+        config: &DatabaseConfig,
+        health: Arc<PoolHealth>,
+    ) -> Result<Self> {
         info!("Connecting to database: {:?}", config.db_type);
 
         let connection_string = config.build_connection_string();
@@ -30,6 +40,7 @@ impl DatabasePool {
                     let pool = PgPoolOptions::new()
                         .max_connections(config.max_connections)
                         .acquire_timeout(Duration::from_secs(config.query_timeout))
+                        .before_acquire(recycle_hook::<sqlx::Postgres>(health))
                         .connect(&connection_string)
                         .await
                         .context("Failed to connect to PostgreSQL database")?;
@@ -61,6 +72,7 @@ impl DatabasePool {
                 let pool = sqlx::sqlite::SqlitePoolOptions::new()
                     .max_connections(config.max_connections)
                     .acquire_timeout(Duration::from_secs(config.query_timeout))
+                    .before_acquire(recycle_hook::<sqlx::Sqlite>(health))
                     .after_connect(move |conn, _meta| {
                         Box::pin(async move {
                             sqlx::query("PRAGMA foreign_keys = ON")
@@ -93,6 +105,7 @@ impl DatabasePool {
                     let pool = MySqlPoolOptions::new()
                         .max_connections(config.max_connections)
                         .acquire_timeout(Duration::from_secs(config.query_timeout))
+                        .before_acquire(recycle_hook::<sqlx::MySql>(health))
                         .connect(&connection_string)
                         .await
                         .context("Failed to connect to MySQL database")?;
@@ -124,6 +137,42 @@ impl DatabasePool {
         Ok(())
     }
 
+    /// 是否为内存 SQLite 数据库（数据只存在于连接中，丢弃连接即丢失数据）
+    pub fn is_in_memory(&self) -> bool {
+        match self {
+            DatabasePool::SQLite(pool) => {
+                let options = pool.connect_options();
+                let filename = options.get_filename().to_string_lossy();
+                filename.is_empty()
+                    || filename == ":memory:"
+                    || filename.starts_with("file:sqlx-in-memory-")
+            }
+            _ => false,
+        }
+    }
+
+    /// 连接池是否已满：连接数达到上限且没有空闲连接
+    pub fn is_saturated(&self) -> bool {
+        let (size, idle, max) = match self {
+            DatabasePool::PostgreSQL(pool) => (
+                pool.size(),
+                pool.num_idle(),
+                pool.options().get_max_connections(),
+            ),
+            DatabasePool::SQLite(pool) => (
+                pool.size(),
+                pool.num_idle(),
+                pool.options().get_max_connections(),
+            ),
+            DatabasePool::MySQL(pool) => (
+                pool.size(),
+                pool.num_idle(),
+                pool.options().get_max_connections(),
+            ),
+        };
+        size >= max && idle == 0
+    }
+
     pub fn db_type(&self) -> DatabaseType {
         match self {
             DatabasePool::PostgreSQL(_) => DatabaseType::PostgreSQL,
@@ -1,3 +1,4 @@
+use crate::database_service::connection_manager::DatabasePool;
 use crate::database_service::migrations::{
     AgentTeamMigration, AgentTodosMigration, AsmEnhancementMigration, FloatTypeMigration,
     IntegerTypeMigration, SubagentMessagesMigration, SubagentRunsMigration,
@@ -5,10 +6,39 @@ use crate::database_service::migrations::{
 };
 use crate::database_service::service::DatabaseService;
 use crate::database_service::sqlx_compat::PgPool;
+use crate::database_service::traffic::ProxyRequestFilters;
+use crate::database_service::traffic_search::insert_proxy_request_fts;
+use crate::database_service::{
+    agent_replan_events, agent_tool_calls, notification_deliveries, plugin_metrics,
+    subdomain_brute, traffic_status_log, traffic_suppression, workflow_artifact,
+};
 use anyhow::Result;
 use chrono::Utc;
 use tracing::info;
 
+/// SQLite FTS5 tables and the triggers that drop deleted rows from them
+const TRAFFIC_SEARCH_DDL: &[&str] = &[
+    "CREATE VIRTUAL TABLE IF NOT EXISTS proxy_requests_fts USING fts5(url, request_headers, request_body, response_headers, response_body)",
+    "CREATE TRIGGER IF NOT EXISTS proxy_requests_fts_delete AFTER DELETE ON proxy_requests BEGIN DELETE FROM proxy_requests_fts WHERE rowid = old.id; END",
+    "CREATE VIRTUAL TABLE IF NOT EXISTS traffic_vulnerabilities_fts USING fts5(vuln_id UNINDEXED, title, description, url, evidence)",
+    "CREATE TRIGGER IF NOT EXISTS traffic_vulnerabilities_fts_delete AFTER DELETE ON traffic_vulnerabilities BEGIN DELETE FROM traffic_vulnerabilities_fts WHERE vuln_id = old.id; END",
+];
+
+/// Page size used when backfilling proxy history into a new search index
+const TRAFFIC_SEARCH_BACKFILL_PAGE_SIZE: i64 = 500;
+
+/// 各功能模块自带的表，DDL 在所有数据库类型上通用
+const FEATURE_TABLES_DDL: &[&str] = &[
+    traffic_status_log::CREATE_STATUS_LOG_TABLE,
+    traffic_suppression::CREATE_SUPPRESSION_TABLE,
+    workflow_artifact::CREATE_ARTIFACT_TABLE,
+    subdomain_brute::CREATE_CHECKPOINT_TABLE,
+    plugin_metrics::CREATE_PLUGIN_METRICS_TABLE,
+    agent_tool_calls::CREATE_TOOL_CALLS_TABLE,
+    agent_replan_events::CREATE_REPLAN_EVENTS_TABLE,
+    notification_deliveries::CREATE_DELIVERIES_TABLE,
+];
+
 impl DatabaseService {
     pub async fn create_database_schema(&self, pool: &PgPool) -> Result<()> {
         info!("Creating database schema...");
@@ -442,6 +472,8 @@ impl DatabaseService {
                 config TEXT,
                 is_encrypted BOOLEAN DEFAULT FALSE,
                 enabled BOOLEAN DEFAULT TRUE,
+                conditions TEXT,
+                throttle TEXT,
                 created_at TIMESTAMP WITH TIME ZONE NOT NULL,
                 updated_at TIMESTAMP WITH TIME ZONE NOT NULL
             )"#,
@@ -719,12 +751,18 @@ impl DatabaseService {
                 category TEXT,
                 metadata TEXT,
                 created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                position BIGINT,
                 FOREIGN KEY(dictionary_id) REFERENCES dictionaries(id) ON DELETE CASCADE
             )"#,
         )
         .execute(pool)
         .await?;
 
+        // 添加导入顺序列（如果表已存在）
+        let _ = sqlx::query("ALTER TABLE dictionary_words ADD COLUMN position BIGINT")
+            .execute(pool)
+            .await;
+
         sqlx::query(
             r#"CREATE INDEX IF NOT EXISTS idx_dictionary_words_dict_id 
                ON dictionary_words(dictionary_id)"#,
@@ -823,6 +861,7 @@ impl DatabaseService {
                 file_type TEXT,
                 file_size BIGINT,
                 file_hash TEXT,
+                file_mtime BIGINT,
                 content_hash TEXT,
                 status TEXT DEFAULT 'Pending',
                 chunk_count BIGINT DEFAULT 0,
@@ -1550,4 +1589,94 @@ impl DatabaseService {
     pub async fn apply_agent_team_migration(&self, pool: &PgPool) -> Result<()> {
         AgentTeamMigration::apply(pool).await
     }
+
+    /// Create the SQLite FTS5 search index over proxy history and findings
+    ///
+    /// Rows that already exist are backfilled once, when the index is first
+    /// created; afterwards inserts index themselves. No-op outside SQLite
+    /// and when the schema has no proxy history table.
+    pub async fn apply_traffic_search_migration(&self) -> Result<()> {
+        let Some(DatabasePool::SQLite(pool)) = self.runtime_pool.as_ref() else {
+            return Ok(());
+        };
+        let source_tables: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN ('proxy_requests', 'traffic_vulnerabilities')",
+        )
+        .fetch_one(pool)
+        .await?;
+        if source_tables < 2 {
+            return Ok(());
+        }
+        let exists: Option<String> = sqlx::query_scalar(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'proxy_requests_fts'",
+        )
+        .fetch_optional(pool)
+        .await?;
+        if exists.is_some() {
+            return Ok(());
+        }
+
+        for ddl in TRAFFIC_SEARCH_DDL {
+            sqlx::query(ddl).execute(pool).await?;
+        }
+
+        let mut offset = 0;
+        let mut indexed = 0;
+        loop {
+            let page = self
+                .list_proxy_requests(ProxyRequestFilters {
+                    limit: Some(TRAFFIC_SEARCH_BACKFILL_PAGE_SIZE),
+                    offset: Some(offset),
+                    ..Default::default()
+                })
+                .await?;
+            for record in &page {
+                if let Some(id) = record.id {
+                    insert_proxy_request_fts(pool, id, record).await?;
+                    indexed += 1;
+                }
+            }
+            if (page.len() as i64) < TRAFFIC_SEARCH_BACKFILL_PAGE_SIZE {
+                break;
+            }
+            offset += TRAFFIC_SEARCH_BACKFILL_PAGE_SIZE;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO traffic_vulnerabilities_fts (vuln_id, title, description, url, evidence)
+            SELECT v.id, v.title, v.description,
+                   COALESCE((SELECT e.url FROM traffic_evidence e WHERE e.vuln_id = v.id LIMIT 1), ''),
+                   COALESCE((SELECT e.evidence_snippet FROM traffic_evidence e WHERE e.vuln_id = v.id LIMIT 1), '')
+            FROM traffic_vulnerabilities v
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        info!("Built traffic search index ({} proxy requests)", indexed);
+        Ok(())
+    }
+
+    /// 创建各功能模块的表（初始化时执行一次）
+    pub async fn ensure_feature_tables(&self) -> Result<()> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+        for ddl in FEATURE_TABLES_DDL {
+            match runtime {
+                DatabasePool::PostgreSQL(pool) => {
+                    sqlx::query(ddl).execute(pool).await?;
+                }
+                DatabasePool::SQLite(pool) => {
+                    sqlx::query(ddl).execute(pool).await?;
+                }
+                DatabasePool::MySQL(pool) => {
+                    sqlx::query(ddl).execute(pool).await?;
+                }
+            }
+        }
+        Ok(())
+    }
 }
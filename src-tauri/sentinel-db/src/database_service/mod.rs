@@ -1,6 +1,9 @@
 pub mod agent;
+pub mod agent_replan_events;
+pub mod agent_tool_calls;
 pub mod ai;
 pub mod asset;
+pub mod backup_crypto;
 pub mod bounty;
 pub mod cache;
 pub mod config;
@@ -10,30 +13,46 @@ pub mod init;
 pub mod memory;
 pub mod migration;
 pub mod migrations;
+pub mod notification_deliveries;
 pub mod plugin;
+pub mod plugin_metrics;
+pub mod pool_health;
+pub mod prompt;
 pub mod proxifier;
 pub mod rag;
 pub mod repeater;
 pub mod scan;
+pub mod scan_bundle;
 pub mod scan_session;
 pub mod service;
 pub mod skills;
 pub mod sliding_window;
 pub mod sqlx_compat;
+pub mod subdomain_brute;
 pub mod task_tool;
 pub mod todos;
 pub mod traffic;
+pub mod traffic_search;
+pub mod traffic_status_log;
+pub mod traffic_suppression;
 pub mod traits;
 pub mod traits_impl;
 pub mod workflow;
+pub mod workflow_artifact;
 
 #[allow(unused_imports)]
 pub use agent::*;
 #[allow(unused_imports)]
+pub use agent_replan_events::*;
+#[allow(unused_imports)]
+pub use agent_tool_calls::*;
+#[allow(unused_imports)]
 pub use ai::*;
 #[allow(unused_imports)]
 pub use asset::*;
 #[allow(unused_imports)]
+pub use backup_crypto::*;
+#[allow(unused_imports)]
 pub use bounty::*;
 #[allow(unused_imports)]
 pub use cache::*;
@@ -50,14 +69,24 @@ pub use memory::*;
 #[allow(unused_imports)]
 pub use migration::*;
 #[allow(unused_imports)]
+pub use notification_deliveries::*;
+#[allow(unused_imports)]
 pub use plugin::*;
 #[allow(unused_imports)]
+pub use plugin_metrics::*;
+#[allow(unused_imports)]
+pub use pool_health::*;
+#[allow(unused_imports)]
+pub use prompt::*;
+#[allow(unused_imports)]
 pub use proxifier::*;
 #[allow(unused_imports)]
 pub use rag::*;
 #[allow(unused_imports)]
 pub use scan::*;
 #[allow(unused_imports)]
+pub use scan_bundle::*;
+#[allow(unused_imports)]
 pub use scan_session::*;
 #[allow(unused_imports)]
 #[allow(unused_imports)]
@@ -69,12 +98,39 @@ pub use sliding_window::*;
 #[allow(unused_imports)]
 pub use sqlx_compat::*;
 #[allow(unused_imports)]
+pub use subdomain_brute::*;
+#[allow(unused_imports)]
 pub use todos::*;
 #[allow(unused_imports)]
 pub use traffic::*;
 #[allow(unused_imports)]
+pub use traffic_search::*;
+#[allow(unused_imports)]
+pub use traffic_status_log::*;
+#[allow(unused_imports)]
+pub use traffic_suppression::*;
+#[allow(unused_imports)]
 pub use traits::*;
 #[allow(unused_imports)]
 pub use traits_impl::*;
 #[allow(unused_imports)]
 pub use workflow::*;
+#[allow(unused_imports)]
+pub use workflow_artifact::*;
+
+/// 测试用的内存 SQLite 服务：先执行 `ddl` 建表，再创建功能表
+#[cfg(test)]
+pub(crate) async fn test_service(ddl: &[&str]) -> DatabaseService {
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    for statement in ddl {
+        sqlx::query(statement).execute(&pool).await.unwrap();
+    }
+    let mut service = DatabaseService::new();
+    service.runtime_pool = Some(DatabasePool::SQLite(pool));
+    service.ensure_feature_tables().await.unwrap();
+    service
+}
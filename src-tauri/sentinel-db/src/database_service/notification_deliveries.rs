@@ -0,0 +1,212 @@
+//! Notification delivery log
+//!
+//! One row per notification delivery (rule, channel, status, attempts and the
+//! last error), updated in place as retries happen, so failed alerts are
+//! visible instead of silently lost.
+
+use crate::database_service::connection_manager::DatabasePool;
+use crate::database_service::service::DatabaseService;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+pub(super) const CREATE_DELIVERIES_TABLE: &str = r#"CREATE TABLE IF NOT EXISTS notification_deliveries (
+    id VARCHAR(64) PRIMARY KEY,
+    rule_id VARCHAR(128),
+    channel VARCHAR(64) NOT NULL,
+    title TEXT NOT NULL,
+    status VARCHAR(32) NOT NULL,
+    attempts BIGINT NOT NULL,
+    last_error TEXT,
+    created_at_ms BIGINT NOT NULL,
+    updated_at_ms BIGINT NOT NULL
+)"#;
+
+const SELECT_COLUMNS: &str =
+    "id, rule_id, channel, title, status, attempts, last_error, created_at_ms, updated_at_ms";
+
+/// A persisted notification delivery
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct NotificationDeliveryRecord {
+    pub id: String,
+    /// `None` for ad-hoc sends such as connection tests without a saved rule
+    pub rule_id: Option<String>,
+    pub channel: String,
+    pub title: String,
+    /// `sent`, `retrying` or `failed`
+    pub status: String,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+}
+
+impl DatabaseService {
+    /// Insert a delivery or update it after another attempt
+    pub async fn save_notification_delivery(
+        &self,
+        record: &NotificationDeliveryRecord,
+    ) -> Result<()> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query(
+                    "INSERT INTO notification_deliveries (id, rule_id, channel, title, status, attempts, last_error, created_at_ms, updated_at_ms)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                     ON CONFLICT(id) DO UPDATE SET status = excluded.status, attempts = excluded.attempts, last_error = excluded.last_error, updated_at_ms = excluded.updated_at_ms",
+                )
+                .bind(&record.id)
+                .bind(&record.rule_id)
+                .bind(&record.channel)
+                .bind(&record.title)
+                .bind(&record.status)
+                .bind(record.attempts)
+                .bind(&record.last_error)
+                .bind(record.created_at_ms)
+                .bind(record.updated_at_ms)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query(
+                    "INSERT INTO notification_deliveries (id, rule_id, channel, title, status, attempts, last_error, created_at_ms, updated_at_ms)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                     ON CONFLICT(id) DO UPDATE SET status = excluded.status, attempts = excluded.attempts, last_error = excluded.last_error, updated_at_ms = excluded.updated_at_ms",
+                )
+                .bind(&record.id)
+                .bind(&record.rule_id)
+                .bind(&record.channel)
+                .bind(&record.title)
+                .bind(&record.status)
+                .bind(record.attempts)
+                .bind(&record.last_error)
+                .bind(record.created_at_ms)
+                .bind(record.updated_at_ms)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query(
+                    "INSERT INTO notification_deliveries (id, rule_id, channel, title, status, attempts, last_error, created_at_ms, updated_at_ms)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                     ON DUPLICATE KEY UPDATE status = VALUES(status), attempts = VALUES(attempts), last_error = VALUES(last_error), updated_at_ms = VALUES(updated_at_ms)",
+                )
+                .bind(&record.id)
+                .bind(&record.rule_id)
+                .bind(&record.channel)
+                .bind(&record.title)
+                .bind(&record.status)
+                .bind(record.attempts)
+                .bind(&record.last_error)
+                .bind(record.created_at_ms)
+                .bind(record.updated_at_ms)
+                .execute(pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Most recently updated deliveries, optionally for one rule
+    pub async fn list_notification_deliveries(
+        &self,
+        rule_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<NotificationDeliveryRecord>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let records = match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM notification_deliveries WHERE ($1::TEXT IS NULL OR rule_id = $1) ORDER BY updated_at_ms DESC LIMIT $2",
+                    SELECT_COLUMNS
+                ))
+                .bind(rule_id)
+                .bind(limit)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM notification_deliveries WHERE (? IS NULL OR rule_id = ?) ORDER BY updated_at_ms DESC LIMIT ?",
+                    SELECT_COLUMNS
+                ))
+                .bind(rule_id)
+                .bind(rule_id)
+                .bind(limit)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM notification_deliveries WHERE (? IS NULL OR rule_id = ?) ORDER BY updated_at_ms DESC LIMIT ?",
+                    SELECT_COLUMNS
+                ))
+                .bind(rule_id)
+                .bind(rule_id)
+                .bind(limit)
+                .fetch_all(pool)
+                .await?
+            }
+        };
+        Ok(records)
+    }
+}
+
+#[cfg(all(test, feature = "db-sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database_service::test_service;
+
+    fn record(id: &str, rule_id: Option<&str>, updated_at_ms: i64) -> NotificationDeliveryRecord {
+        NotificationDeliveryRecord {
+            id: id.to_string(),
+            rule_id: rule_id.map(str::to_string),
+            channel: "webhook".to_string(),
+            title: "Critical finding".to_string(),
+            status: "retrying".to_string(),
+            attempts: 1,
+            last_error: Some("connection refused".to_string()),
+            created_at_ms: 1_000,
+            updated_at_ms,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delivery_is_updated_in_place() {
+        let service = test_service(&[]).await;
+        service
+            .save_notification_delivery(&record("d-1", Some("rule-1"), 1_000))
+            .await
+            .unwrap();
+        service
+            .save_notification_delivery(&record("d-2", None, 2_000))
+            .await
+            .unwrap();
+
+        let mut sent = record("d-1", Some("rule-1"), 3_000);
+        sent.status = "sent".to_string();
+        sent.attempts = 2;
+        sent.last_error = None;
+        service.save_notification_delivery(&sent).await.unwrap();
+
+        let all = service
+            .list_notification_deliveries(None, 10)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0], sent);
+
+        let for_rule = service
+            .list_notification_deliveries(Some("rule-1"), 10)
+            .await
+            .unwrap();
+        assert_eq!(for_rule, vec![sent]);
+    }
+}
@@ -0,0 +1,178 @@
+//! Plugin execution metrics
+//!
+//! One row per plugin with cumulative counters and the most recent duration
+//! samples (JSON array), so percentiles survive a restart.
+
+use crate::database_service::connection_manager::DatabasePool;
+use crate::database_service::service::DatabaseService;
+use anyhow::Result;
+use chrono::Utc;
+use sentinel_plugins::PluginMetricsRecord;
+
+pub(super) const CREATE_PLUGIN_METRICS_TABLE: &str = r#"CREATE TABLE IF NOT EXISTS plugin_execution_metrics (
+    plugin_id VARCHAR(128) PRIMARY KEY,
+    invocation_count BIGINT NOT NULL,
+    error_count BIGINT NOT NULL,
+    findings_count BIGINT NOT NULL,
+    total_duration_ms BIGINT NOT NULL,
+    recent_durations TEXT NOT NULL,
+    last_error TEXT,
+    last_invoked_at BIGINT,
+    updated_at BIGINT NOT NULL
+)"#;
+
+const SELECT_COLUMNS: &str = "plugin_id, invocation_count, error_count, findings_count, total_duration_ms, recent_durations, last_error, last_invoked_at";
+
+#[derive(sqlx::FromRow)]
+struct PluginMetricsRow {
+    plugin_id: String,
+    invocation_count: i64,
+    error_count: i64,
+    findings_count: i64,
+    total_duration_ms: i64,
+    recent_durations: String,
+    last_error: Option<String>,
+    last_invoked_at: Option<i64>,
+}
+
+impl From<PluginMetricsRow> for PluginMetricsRecord {
+    fn from(row: PluginMetricsRow) -> Self {
+        Self {
+            plugin_id: row.plugin_id,
+            invocation_count: row.invocation_count.max(0) as u64,
+            error_count: row.error_count.max(0) as u64,
+            findings_count: row.findings_count.max(0) as u64,
+            total_duration_ms: row.total_duration_ms.max(0) as u64,
+            recent_durations: serde_json::from_str(&row.recent_durations).unwrap_or_default(),
+            last_error: row.last_error,
+            last_invoked_at: row.last_invoked_at,
+        }
+    }
+}
+
+impl DatabaseService {
+    pub async fn list_plugin_metrics(&self) -> Result<Vec<PluginMetricsRecord>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let sql = format!(
+            "SELECT {} FROM plugin_execution_metrics ORDER BY plugin_id",
+            SELECT_COLUMNS
+        );
+        let rows: Vec<PluginMetricsRow> = match runtime {
+            DatabasePool::PostgreSQL(pool) => sqlx::query_as(&sql).fetch_all(pool).await?,
+            DatabasePool::SQLite(pool) => sqlx::query_as(&sql).fetch_all(pool).await?,
+            DatabasePool::MySQL(pool) => sqlx::query_as(&sql).fetch_all(pool).await?,
+        };
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Upsert metrics; each record replaces the stored row for its plugin
+    pub async fn save_plugin_metrics(&self, records: &[PluginMetricsRecord]) -> Result<()> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let now = Utc::now().timestamp_millis();
+        for record in records {
+            let durations = serde_json::to_string(&record.recent_durations)?;
+            match runtime {
+                DatabasePool::PostgreSQL(pool) => {
+                    sqlx::query(
+                        "INSERT INTO plugin_execution_metrics (plugin_id, invocation_count, error_count, findings_count, total_duration_ms, recent_durations, last_error, last_invoked_at, updated_at)
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                         ON CONFLICT(plugin_id) DO UPDATE SET invocation_count = excluded.invocation_count, error_count = excluded.error_count, findings_count = excluded.findings_count, total_duration_ms = excluded.total_duration_ms, recent_durations = excluded.recent_durations, last_error = excluded.last_error, last_invoked_at = excluded.last_invoked_at, updated_at = excluded.updated_at",
+                    )
+                    .bind(&record.plugin_id)
+                    .bind(record.invocation_count as i64)
+                    .bind(record.error_count as i64)
+                    .bind(record.findings_count as i64)
+                    .bind(record.total_duration_ms as i64)
+                    .bind(&durations)
+                    .bind(&record.last_error)
+                    .bind(record.last_invoked_at)
+                    .bind(now)
+                    .execute(pool)
+                    .await?;
+                }
+                DatabasePool::SQLite(pool) => {
+                    sqlx::query(
+                        "INSERT INTO plugin_execution_metrics (plugin_id, invocation_count, error_count, findings_count, total_duration_ms, recent_durations, last_error, last_invoked_at, updated_at)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                         ON CONFLICT(plugin_id) DO UPDATE SET invocation_count = excluded.invocation_count, error_count = excluded.error_count, findings_count = excluded.findings_count, total_duration_ms = excluded.total_duration_ms, recent_durations = excluded.recent_durations, last_error = excluded.last_error, last_invoked_at = excluded.last_invoked_at, updated_at = excluded.updated_at",
+                    )
+                    .bind(&record.plugin_id)
+                    .bind(record.invocation_count as i64)
+                    .bind(record.error_count as i64)
+                    .bind(record.findings_count as i64)
+                    .bind(record.total_duration_ms as i64)
+                    .bind(&durations)
+                    .bind(&record.last_error)
+                    .bind(record.last_invoked_at)
+                    .bind(now)
+                    .execute(pool)
+                    .await?;
+                }
+                DatabasePool::MySQL(pool) => {
+                    sqlx::query(
+                        "INSERT INTO plugin_execution_metrics (plugin_id, invocation_count, error_count, findings_count, total_duration_ms, recent_durations, last_error, last_invoked_at, updated_at)
+                         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                         ON DUPLICATE KEY UPDATE invocation_count = VALUES(invocation_count), error_count = VALUES(error_count), findings_count = VALUES(findings_count), total_duration_ms = VALUES(total_duration_ms), recent_durations = VALUES(recent_durations), last_error = VALUES(last_error), last_invoked_at = VALUES(last_invoked_at), updated_at = VALUES(updated_at)",
+                    )
+                    .bind(&record.plugin_id)
+                    .bind(record.invocation_count as i64)
+                    .bind(record.error_count as i64)
+                    .bind(record.findings_count as i64)
+                    .bind(record.total_duration_ms as i64)
+                    .bind(&durations)
+                    .bind(&record.last_error)
+                    .bind(record.last_invoked_at)
+                    .bind(now)
+                    .execute(pool)
+                    .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "db-sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database_service::test_service;
+
+    #[tokio::test]
+    async fn test_plugin_metrics_round_trip() {
+        let service = test_service(&[]).await;
+        let metrics = sentinel_plugins::PluginMetrics::new();
+        metrics.record("xss", 12, 1, None);
+        metrics.record("xss", 40, 0, Some("script error"));
+        service
+            .save_plugin_metrics(&metrics.take_dirty())
+            .await
+            .unwrap();
+
+        metrics.record("xss", 8, 2, None);
+        service
+            .save_plugin_metrics(&metrics.take_dirty())
+            .await
+            .unwrap();
+
+        let records = service.list_plugin_metrics().await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].invocation_count, 3);
+        assert_eq!(records[0].findings_count, 3);
+        assert_eq!(records[0].recent_durations, vec![12, 40, 8]);
+
+        let restarted = sentinel_plugins::PluginMetrics::new();
+        restarted.restore(records);
+        let xss = restarted.get("xss").unwrap();
+        assert_eq!(xss.error_count, 1);
+        assert_eq!(xss.max_ms, 40);
+        assert_eq!(xss.last_error.as_deref(), Some("script error"));
+    }
+}
@@ -0,0 +1,359 @@
+//! 连接池健康监控与自动恢复
+//!
+//! 记录每次探测获取连接的等待时间与失败情况；连续获取失败达到阈值时判定连接池异常，
+//! 并在冷却时间与次数上限内尝试恢复：把恢复前创建的连接全部标记为过期，
+//! 由 `before_acquire` 钩子在下次取出时丢弃并重新建立连接。连接池本身不会被替换，
+//! 持有连接池克隆的模块无需感知恢复。
+//!
+//! 只有真实的连接错误计入连续失败；连接池已满导致的等待超时属于正常饱和，
+//! 不会触发恢复。内存数据库的数据只存在于连接中，从不回收。
+
+use crate::database_service::connection_manager::DatabasePool;
+use crate::database_service::service::DatabaseService;
+use serde::{Deserialize, Serialize};
+use sqlx::pool::PoolConnectionMetadata;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 连续获取失败多少次后判定连接池异常
+const BROKEN_THRESHOLD: u32 = 3;
+/// 两次恢复尝试之间的最短间隔
+const RECOVERY_COOLDOWN: Duration = Duration::from_secs(60);
+/// 连接池恢复正常前最多尝试恢复的次数
+const MAX_RECOVERY_ATTEMPTS: u32 = 5;
+
+/// 连接池健康状态（在 `DatabaseService` 的所有克隆之间共享）
+#[derive(Debug, Default)]
+pub struct PoolHealth {
+    acquire_count: AtomicU64,
+    acquire_failures: AtomicU64,
+    consecutive_failures: AtomicU32,
+    total_wait_ms: AtomicU64,
+    max_wait_ms: AtomicU64,
+    last_wait_ms: AtomicU64,
+    recovery_attempts: AtomicU32,
+    /// 本轮异常以来的恢复次数，连接池恢复正常后清零
+    pending_recoveries: AtomicU32,
+    last_recovery: Mutex<Option<Instant>>,
+    last_recovery_at_ms: AtomicU64,
+    /// 早于该时间点创建的连接在取出时会被丢弃
+    recycle_before: Mutex<Option<Instant>>,
+    last_error: Mutex<Option<String>>,
+}
+
+/// 连接池指标
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PoolMetrics {
+    pub size: u32,
+    pub idle: u32,
+    pub active: u32,
+    pub max_connections: u32,
+    pub acquire_count: u64,
+    pub acquire_failures: u64,
+    pub consecutive_failures: u32,
+    pub avg_acquire_wait_ms: u64,
+    pub max_acquire_wait_ms: u64,
+    pub last_acquire_wait_ms: u64,
+    pub broken: bool,
+    pub recovery_attempts: u32,
+    pub last_recovery_at: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+impl PoolHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次成功获取连接
+    pub fn record_acquire_success(&self, wait: Duration) {
+        let wait_ms = wait.as_millis() as u64;
+        self.acquire_count.fetch_add(1, Ordering::Relaxed);
+        self.total_wait_ms.fetch_add(wait_ms, Ordering::Relaxed);
+        self.max_wait_ms.fetch_max(wait_ms, Ordering::Relaxed);
+        self.last_wait_ms.store(wait_ms, Ordering::Relaxed);
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        self.pending_recoveries.store(0, Ordering::Relaxed);
+    }
+
+    /// 记录一次因连接池已满导致的获取超时（不计入连续失败）
+    pub fn record_acquire_saturated(&self, wait: Duration) {
+        let wait_ms = wait.as_millis() as u64;
+        self.acquire_count.fetch_add(1, Ordering::Relaxed);
+        self.acquire_failures.fetch_add(1, Ordering::Relaxed);
+        self.total_wait_ms.fetch_add(wait_ms, Ordering::Relaxed);
+        self.max_wait_ms.fetch_max(wait_ms, Ordering::Relaxed);
+        self.last_wait_ms.store(wait_ms, Ordering::Relaxed);
+    }
+
+    /// 记录一次获取连接失败
+    pub fn record_acquire_failure(&self, wait: Duration, error: &str) {
+        let wait_ms = wait.as_millis() as u64;
+        self.acquire_count.fetch_add(1, Ordering::Relaxed);
+        self.acquire_failures.fetch_add(1, Ordering::Relaxed);
+        self.total_wait_ms.fetch_add(wait_ms, Ordering::Relaxed);
+        self.max_wait_ms.fetch_max(wait_ms, Ordering::Relaxed);
+        self.last_wait_ms.store(wait_ms, Ordering::Relaxed);
+        self.consecutive_failures.fetch_add(1, Ordering::Relaxed);
+        *self.last_error.lock().unwrap() = Some(error.to_string());
+    }
+
+    /// 连接池是否处于异常状态
+    pub fn is_broken(&self) -> bool {
+        self.consecutive_failures.load(Ordering::Relaxed) >= BROKEN_THRESHOLD
+    }
+
+    /// 连接是否早于最近一次恢复创建（需要丢弃）
+    pub fn should_discard(&self, connection_age: Duration) -> bool {
+        match *self.recycle_before.lock().unwrap() {
+            Some(recycle_before) => connection_age > recycle_before.elapsed(),
+            None => false,
+        }
+    }
+
+    /// 申请一次恢复尝试，受冷却时间和次数上限约束
+    fn begin_recovery(&self) -> bool {
+        if self.pending_recoveries.load(Ordering::Relaxed) >= MAX_RECOVERY_ATTEMPTS {
+            return false;
+        }
+        let mut last_recovery = self.last_recovery.lock().unwrap();
+        if let Some(last) = *last_recovery {
+            if last.elapsed() < RECOVERY_COOLDOWN {
+                return false;
+            }
+        }
+        let now = Instant::now();
+        *last_recovery = Some(now);
+        *self.recycle_before.lock().unwrap() = Some(now);
+        self.recovery_attempts.fetch_add(1, Ordering::Relaxed);
+        self.pending_recoveries.fetch_add(1, Ordering::Relaxed);
+        self.last_recovery_at_ms.store(
+            chrono::Utc::now().timestamp_millis() as u64,
+            Ordering::Relaxed,
+        );
+        true
+    }
+
+    fn snapshot(&self) -> PoolMetrics {
+        let acquire_count = self.acquire_count.load(Ordering::Relaxed);
+        let last_recovery_at_ms = self.last_recovery_at_ms.load(Ordering::Relaxed);
+        PoolMetrics {
+            acquire_count,
+            acquire_failures: self.acquire_failures.load(Ordering::Relaxed),
+            consecutive_failures: self.consecutive_failures.load(Ordering::Relaxed),
+            avg_acquire_wait_ms: self
+                .total_wait_ms
+                .load(Ordering::Relaxed)
+                .checked_div(acquire_count)
+                .unwrap_or(0),
+            max_acquire_wait_ms: self.max_wait_ms.load(Ordering::Relaxed),
+            last_acquire_wait_ms: self.last_wait_ms.load(Ordering::Relaxed),
+            broken: self.is_broken(),
+            recovery_attempts: self.recovery_attempts.load(Ordering::Relaxed),
+            last_recovery_at: (last_recovery_at_ms > 0).then_some(last_recovery_at_ms as i64),
+            last_error: self.last_error.lock().unwrap().clone(),
+            ..Default::default()
+        }
+    }
+}
+
+type HookFuture<'c> = Pin<Box<dyn Future<Output = Result<bool, sqlx::Error>> + Send + 'c>>;
+
+/// 连接池 `before_acquire` 钩子：丢弃最近一次恢复之前创建的连接
+pub(crate) fn recycle_hook<DB: sqlx::Database>(
+    health: Arc<PoolHealth>,
+) -> impl for<'c> Fn(&'c mut DB::Connection, PoolConnectionMetadata) -> HookFuture<'c>
+       + Send
+       + Sync
+       + 'static {
+    move |_conn, meta| {
+        let keep = !health.should_discard(meta.age);
+        Box::pin(async move { Ok(keep) })
+    }
+}
+
+impl DatabaseService {
+    /// 当前连接池指标
+    pub fn pool_metrics(&self) -> PoolMetrics {
+        let mut metrics = self.pool_health.snapshot();
+        if let Ok(runtime) = self.get_runtime_pool() {
+            let (size, idle, max) = match &runtime {
+                DatabasePool::PostgreSQL(pool) => (
+                    pool.size(),
+                    pool.num_idle() as u32,
+                    pool.options().get_max_connections(),
+                ),
+                DatabasePool::SQLite(pool) => (
+                    pool.size(),
+                    pool.num_idle() as u32,
+                    pool.options().get_max_connections(),
+                ),
+                DatabasePool::MySQL(pool) => (
+                    pool.size(),
+                    pool.num_idle() as u32,
+                    pool.options().get_max_connections(),
+                ),
+            };
+            metrics.size = size;
+            metrics.idle = idle;
+            metrics.active = size.saturating_sub(idle);
+            metrics.max_connections = max;
+        }
+        metrics
+    }
+
+    /// 探测连接池：计时获取一个连接，连接池异常时尝试有限次数的恢复
+    pub async fn check_pool_health(&self) -> PoolMetrics {
+        let runtime = match self.get_runtime_pool() {
+            Ok(runtime) => runtime,
+            Err(_) => return self.pool_metrics(),
+        };
+
+        let started = Instant::now();
+        match Self::acquire_probe(&runtime).await {
+            Ok(()) => self.pool_health.record_acquire_success(started.elapsed()),
+            Err(sqlx::Error::PoolTimedOut) if runtime.is_saturated() => {
+                tracing::debug!("Database pool saturated, acquire timed out");
+                self.pool_health.record_acquire_saturated(started.elapsed());
+            }
+            Err(e) => {
+                tracing::warn!("Database pool acquire failed: {}", e);
+                self.pool_health
+                    .record_acquire_failure(started.elapsed(), &e.to_string());
+            }
+        }
+
+        if self.pool_health.is_broken() && !runtime.is_in_memory() {
+            self.recover_pool(&runtime).await;
+        }
+        self.pool_metrics()
+    }
+
+    async fn acquire_probe(runtime: &DatabasePool) -> Result<(), sqlx::Error> {
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => pool.acquire().await.map(|_| ()),
+            DatabasePool::SQLite(pool) => pool.acquire().await.map(|_| ()),
+            DatabasePool::MySQL(pool) => pool.acquire().await.map(|_| ()),
+        }
+    }
+
+    /// 回收旧连接并验证连接池是否恢复
+    async fn recover_pool(&self, runtime: &DatabasePool) {
+        if !self.pool_health.begin_recovery() {
+            return;
+        }
+        tracing::warn!(
+            "Database pool appears broken ({} consecutive acquire failures), recycling connections (attempt {})",
+            self.pool_health.consecutive_failures.load(Ordering::Relaxed),
+            self.pool_health.recovery_attempts.load(Ordering::Relaxed)
+        );
+
+        let started = Instant::now();
+        match runtime.test_connection().await {
+            Ok(()) => {
+                self.pool_health.record_acquire_success(started.elapsed());
+                tracing::info!("Database pool recovered");
+            }
+            Err(e) => {
+                self.pool_health
+                    .record_acquire_failure(started.elapsed(), &e.to_string());
+                tracing::error!("Database pool recovery attempt failed: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "db-sqlite"))]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    #[tokio::test]
+    async fn test_saturated_pool_is_not_recovered() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(Duration::from_millis(50))
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let mut service = DatabaseService::new();
+        service.runtime_pool = Some(DatabasePool::SQLite(pool.clone()));
+
+        let metrics = service.check_pool_health().await;
+        assert_eq!(metrics.consecutive_failures, 0);
+        assert_eq!(metrics.max_connections, 1);
+
+        // 占住唯一的连接，后续获取全部因连接池已满而超时
+        let held = pool.acquire().await.unwrap();
+        for _ in 0..=BROKEN_THRESHOLD {
+            service.check_pool_health().await;
+        }
+        let metrics = service.pool_metrics();
+        assert!(!metrics.broken);
+        assert_eq!(metrics.consecutive_failures, 0);
+        assert_eq!(metrics.recovery_attempts, 0);
+        assert!(metrics.acquire_failures >= BROKEN_THRESHOLD as u64);
+        assert!(!service
+            .pool_health
+            .should_discard(Duration::from_secs(3600)));
+        drop(held);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_pool_is_never_recycled() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let mut service = DatabaseService::new();
+        service.runtime_pool = Some(DatabasePool::SQLite(pool.clone()));
+        assert!(service.get_runtime_pool().unwrap().is_in_memory());
+
+        for _ in 0..BROKEN_THRESHOLD {
+            service
+                .pool_health
+                .record_acquire_failure(Duration::ZERO, "connection reset");
+        }
+        let metrics = service.check_pool_health().await;
+        assert_eq!(metrics.recovery_attempts, 0);
+        assert!(!service
+            .pool_health
+            .should_discard(Duration::from_secs(3600)));
+    }
+
+    #[tokio::test]
+    async fn test_connection_errors_trigger_recovery() {
+        let missing = std::env::temp_dir()
+            .join(format!("sentinel-pool-health-{}", uuid::Uuid::new_v4()))
+            .join("database.db");
+        let options = SqliteConnectOptions::new()
+            .filename(&missing)
+            .create_if_missing(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(Duration::from_millis(200))
+            .connect_lazy_with(options);
+        let mut service = DatabaseService::new();
+        service.runtime_pool = Some(DatabasePool::SQLite(pool));
+        assert!(!service.get_runtime_pool().unwrap().is_in_memory());
+
+        for _ in 0..BROKEN_THRESHOLD {
+            service.check_pool_health().await;
+        }
+        let metrics = service.pool_metrics();
+        assert!(metrics.broken);
+        assert_eq!(metrics.recovery_attempts, 1);
+        assert!(metrics.last_error.is_some());
+        assert!(service
+            .pool_health
+            .should_discard(Duration::from_secs(3600)));
+
+        // 冷却期内不会重复尝试恢复
+        service.check_pool_health().await;
+        assert_eq!(service.pool_metrics().recovery_attempts, 1);
+    }
+}
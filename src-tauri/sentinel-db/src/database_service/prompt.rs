@@ -0,0 +1,90 @@
+use crate::database_service::connection_manager::DatabasePool;
+use crate::database_service::service::DatabaseService;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+/// Stored prompt template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplateRecord {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub content: String,
+    pub category: Option<String>,
+    /// JSON array of variable names, as stored
+    pub variables: Option<String>,
+}
+
+impl PromptTemplateRecord {
+    /// Parse the stored variable list; malformed values yield an empty list
+    pub fn variable_names(&self) -> Vec<String> {
+        self.variables
+            .as_deref()
+            .and_then(|v| serde_json::from_str(v).ok())
+            .unwrap_or_default()
+    }
+}
+
+const LIST_ACTIVE_PROMPT_TEMPLATES_SQL: &str =
+    "SELECT id, name, description, content, category, variables \
+     FROM prompt_templates WHERE is_active = TRUE ORDER BY priority DESC, name";
+
+impl DatabaseService {
+    /// List active prompt templates
+    pub async fn list_active_prompt_templates(&self) -> Result<Vec<PromptTemplateRecord>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+        let templates = match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                let rows = sqlx::query(LIST_ACTIVE_PROMPT_TEMPLATES_SQL)
+                    .fetch_all(pool)
+                    .await?;
+                rows.into_iter()
+                    .map(|row| PromptTemplateRecord {
+                        id: row.get::<i32, _>("id") as i64,
+                        name: row.get("name"),
+                        description: row.get("description"),
+                        content: row.get("content"),
+                        category: row.get("category"),
+                        variables: row.get("variables"),
+                    })
+                    .collect()
+            }
+            DatabasePool::SQLite(pool) => {
+                let rows = sqlx::query(LIST_ACTIVE_PROMPT_TEMPLATES_SQL)
+                    .fetch_all(pool)
+                    .await?;
+                rows.into_iter()
+                    .map(|row| PromptTemplateRecord {
+                        id: row.get("id"),
+                        name: row.get("name"),
+                        description: row.get("description"),
+                        content: row.get("content"),
+                        category: row.get("category"),
+                        variables: row.get("variables"),
+                    })
+                    .collect()
+            }
+            DatabasePool::MySQL(pool) => {
+                let rows = sqlx::query(LIST_ACTIVE_PROMPT_TEMPLATES_SQL)
+                    .fetch_all(pool)
+                    .await?;
+                rows.into_iter()
+                    .map(|row| PromptTemplateRecord {
+                        id: row.get("id"),
+                        name: row.get("name"),
+                        description: row.get("description"),
+                        content: row.get("content"),
+                        category: row.get("category"),
+                        variables: row.get("variables"),
+                    })
+                    .collect()
+            }
+        };
+
+        Ok(templates)
+    }
+}
@@ -52,6 +52,7 @@ fn rag_doc_source_to_model(row: RagDocumentSourceRow) -> sentinel_rag::models::D
         file_type: row.file_type,
         file_size: row.file_size as u64,
         file_hash: row.file_hash,
+        file_mtime: row.file_mtime,
         chunk_count: row.chunk_count as usize,
         ingestion_status: parse_ingestion_status(&row.status),
         created_at: chrono::DateTime::parse_from_rfc3339(&row.created_at)
@@ -85,6 +86,7 @@ pub struct RagDocumentSourceRow {
     pub file_type: String,
     pub file_size: i64,
     pub file_hash: String,
+    pub file_mtime: Option<i64>,
     pub content_hash: String,
     pub status: String,
     pub metadata: String,
@@ -765,6 +767,58 @@ impl DatabaseService {
         Ok(())
     }
 
+    /// 记录文档源文件的哈希与修改时间，用于增量摄取比对
+    pub async fn update_rag_document_fingerprint_internal(
+        &self,
+        document_id: &str,
+        file_hash: &str,
+        file_mtime: Option<i64>,
+    ) -> Result<()> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+        let now = chrono::Utc::now();
+
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query(
+                    "UPDATE rag_document_sources SET file_hash = $1, file_mtime = $2, updated_at = $3 WHERE id = $4",
+                )
+                .bind(file_hash)
+                .bind(file_mtime)
+                .bind(now)
+                .bind(document_id)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query(
+                    "UPDATE rag_document_sources SET file_hash = ?, file_mtime = ?, updated_at = ? WHERE id = ?",
+                )
+                .bind(file_hash)
+                .bind(file_mtime)
+                .bind(now)
+                .bind(document_id)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query(
+                    "UPDATE rag_document_sources SET file_hash = ?, file_mtime = ?, updated_at = ? WHERE id = ?",
+                )
+                .bind(file_hash)
+                .bind(file_mtime)
+                .bind(now)
+                .bind(document_id)
+                .execute(pool)
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn delete_document_cascade_internal(&self, document_id: &str) -> Result<()> {
         let runtime = self
             .runtime_pool
@@ -992,6 +1046,7 @@ impl DatabaseService {
             file_type: row.get("file_type"),
             file_size: row.get("file_size"),
             file_hash: row.get("file_hash"),
+            file_mtime: row.try_get::<Option<i64>, _>("file_mtime").ok().flatten(),
             content_hash: row.get("content_hash"),
             status: row.get("status"),
             metadata: row.get("metadata"),
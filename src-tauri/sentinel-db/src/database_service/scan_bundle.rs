@@ -0,0 +1,1103 @@
+//! Portable scan session bundles
+//!
+//! A bundle is a zip archive holding everything needed to archive a scan
+//! session or move it to another install:
+//!
+//! - `manifest.json`: format version, source session and item counts
+//! - `session.json`: the session record and its stages (stage results carry
+//!   the session's findings and assets)
+//! - `findings.json` / `assets.json`: findings and assets collected from the
+//!   stage results, keyed by fingerprint
+//! - `evidence.json`: passive-scan vulnerabilities with their evidence for the
+//!   session's target host
+//! - `requests.har`: proxy history for the target host as HAR 1.2
+//!
+//! On import, records whose id already exists locally get a fresh id, and
+//! vulnerabilities whose signature is already known are merged into the
+//! existing record instead of being duplicated. Evidence and proxy requests
+//! that are already present are skipped, and the whole import runs in one
+//! transaction.
+
+use crate::core::models::scan_session::{ScanSession, ScanSessionItems, ScanStage};
+use crate::database_service::connection_manager::DatabasePool;
+use crate::database_service::service::DatabaseService;
+use crate::database_service::traffic::{
+    smart_compress, ProxyRequestFilters, ProxyRequestRecord, TrafficEvidenceRecord, TrafficFinding,
+    TrafficVulnerabilityFilters, TrafficVulnerabilityRecord, TrafficVulnerabilityWithEvidence,
+    INSERT_DEDUPE_INDEX, INSERT_EVIDENCE,
+};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tracing::info;
+use uuid::Uuid;
+
+/// Format identifier written to the manifest
+pub const SCAN_BUNDLE_FORMAT: &str = "sentinel-scan-bundle";
+/// Current bundle format version
+pub const SCAN_BUNDLE_VERSION: u32 = 1;
+/// Upper bound for proxy history entries exported per bundle
+const MAX_BUNDLE_REQUESTS: i64 = 5000;
+/// Upper bound for a single decompressed bundle entry
+const MAX_BUNDLE_ENTRY_SIZE: u64 = 512 * 1024 * 1024;
+
+// Import statements, written with `?` placeholders (see `numbered` for PostgreSQL)
+const SELECT_SESSION_ID: &str = "SELECT id FROM scan_sessions WHERE id = ?";
+const INSERT_SESSION: &str = r#"
+    INSERT INTO scan_sessions (
+        id, name, description, target, scan_type, status, config,
+        progress, current_stage, total_stages, completed_stages,
+        results_summary, error_message, created_at, created_by
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+const INSERT_STAGE: &str = r#"
+    INSERT INTO scan_stages (
+        id, session_id, stage_name, stage_order, status, tool_name, config,
+        results, error_message, started_at, completed_at, duration_ms
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+const SELECT_VULN_BY_SIGNATURE: &str =
+    "SELECT vuln_id FROM traffic_dedupe_index WHERE signature = ?";
+const SELECT_VULN_ID: &str = "SELECT id FROM traffic_vulnerabilities WHERE id = ?";
+const INSERT_VULNERABILITY: &str = r#"
+    INSERT INTO traffic_vulnerabilities (
+        id, plugin_id, vuln_type, severity, confidence, title, description,
+        cwe, owasp, remediation, status, signature, first_seen_at, last_seen_at, hit_count
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+const SELECT_DUPLICATE_EVIDENCE: &str =
+    "SELECT id FROM traffic_evidence WHERE vuln_id = ? AND url = ? AND timestamp = ?";
+const SELECT_EVIDENCE_ID: &str = "SELECT id FROM traffic_evidence WHERE id = ?";
+const SELECT_REQUEST_ID: &str =
+    "SELECT id FROM proxy_requests WHERE url = ? AND method = ? AND timestamp = ?";
+const INSERT_REQUEST: &str = r#"
+    INSERT INTO proxy_requests (
+        url, host, protocol, method, status_code,
+        request_headers, request_body, response_headers, response_body,
+        response_size, response_time, timestamp,
+        request_body_compressed, response_body_compressed
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const SESSION_FILE: &str = "session.json";
+const FINDINGS_FILE: &str = "findings.json";
+const ASSETS_FILE: &str = "assets.json";
+const EVIDENCE_FILE: &str = "evidence.json";
+const HAR_FILE: &str = "requests.har";
+
+/// Bundle manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanBundleManifest {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub session_id: Uuid,
+    pub session_name: String,
+    pub target: String,
+    pub stages: usize,
+    pub findings: usize,
+    pub assets: usize,
+    /// Passive-scan vulnerabilities included in `evidence.json`
+    pub vulnerabilities: usize,
+    /// Evidence records across those vulnerabilities
+    pub evidence: usize,
+    pub requests: usize,
+}
+
+/// Session record plus its stages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BundledSession {
+    session: ScanSession,
+    stages: Vec<ScanStage>,
+}
+
+/// Outcome of importing a bundle
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanBundleImport {
+    /// Id of the imported session on this install
+    pub session_id: Uuid,
+    pub original_session_id: Uuid,
+    /// The original session id was taken and a new one was assigned
+    pub session_remapped: bool,
+    pub stages: usize,
+    pub findings: usize,
+    pub vulnerabilities: usize,
+    /// Vulnerabilities whose id was taken and got a new one
+    pub remapped_vulnerabilities: usize,
+    /// Vulnerabilities already known locally (same signature); evidence was added to them
+    pub merged_vulnerabilities: usize,
+    pub evidence: usize,
+    /// Evidence records whose id was taken by another record and got a new one
+    pub remapped_evidence: usize,
+    pub requests: usize,
+}
+
+impl DatabaseService {
+    /// Export a scan session to a zip bundle at `path`
+    pub async fn export_scan_session_bundle(
+        &self,
+        session_id: Uuid,
+        path: &Path,
+    ) -> Result<ScanBundleManifest> {
+        let session = self
+            .get_scan_session_internal(session_id)
+            .await?
+            .ok_or_else(|| anyhow!("扫描会话不存在: {}", session_id))?;
+        let stages = self.get_scan_session_stages_internal(session_id).await?;
+        let items = ScanSessionItems::collect(&session, &stages);
+
+        let (vulnerabilities, requests) = match target_host(&session.target) {
+            Some(host) => {
+                // The host filters are pattern matches; keep only exact host hits
+                let is_target = |url: &str| target_host(url).as_deref() == Some(host.as_str());
+                let vulnerabilities = self
+                    .list_traffic_vulnerabilities_with_evidence(TrafficVulnerabilityFilters {
+                        host: Some(host.clone()),
+                        ..Default::default()
+                    })
+                    .await?
+                    .into_iter()
+                    .filter_map(|mut v| {
+                        v.evidence.retain(|e| is_target(&e.url));
+                        let first = v.evidence.first()?;
+                        v.url = Some(first.url.clone());
+                        v.method = Some(first.method.clone());
+                        Some(v)
+                    })
+                    .collect();
+                let requests = self
+                    .list_proxy_requests(ProxyRequestFilters {
+                        host: Some(host.clone()),
+                        limit: Some(MAX_BUNDLE_REQUESTS),
+                        ..Default::default()
+                    })
+                    .await?
+                    .into_iter()
+                    .filter(|r| is_target(&r.url))
+                    .collect();
+                (vulnerabilities, requests)
+            }
+            None => (Vec::new(), Vec::new()),
+        };
+
+        let manifest = ScanBundleManifest {
+            format: SCAN_BUNDLE_FORMAT.to_string(),
+            version: SCAN_BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            session_id,
+            session_name: session.name.clone(),
+            target: session.target.clone(),
+            stages: stages.len(),
+            findings: items.findings.len(),
+            assets: items.assets.len(),
+            vulnerabilities: vulnerabilities.len(),
+            evidence: vulnerabilities.iter().map(|v| v.evidence.len()).sum(),
+            requests: requests.len(),
+        };
+
+        let entries = vec![
+            (MANIFEST_FILE, serde_json::to_vec_pretty(&manifest)?),
+            (
+                SESSION_FILE,
+                serde_json::to_vec_pretty(&BundledSession { session, stages })?,
+            ),
+            (FINDINGS_FILE, serde_json::to_vec_pretty(&items.findings)?),
+            (ASSETS_FILE, serde_json::to_vec_pretty(&items.assets)?),
+            (EVIDENCE_FILE, serde_json::to_vec_pretty(&vulnerabilities)?),
+            (HAR_FILE, serde_json::to_vec_pretty(&to_har(&requests))?),
+        ];
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || write_zip(&path, &entries)).await??;
+
+        info!(
+            "Exported scan session {} ({} findings, {} evidence, {} requests)",
+            session_id, manifest.findings, manifest.evidence, manifest.requests
+        );
+        Ok(manifest)
+    }
+
+    /// Import a bundle written by [`Self::export_scan_session_bundle`]
+    pub async fn import_scan_session_bundle(&self, path: &Path) -> Result<ScanBundleImport> {
+        let path: PathBuf = path.to_path_buf();
+        let mut files = tokio::task::spawn_blocking(move || read_zip(&path)).await??;
+        let mut take = |name: &str| {
+            files
+                .remove(name)
+                .ok_or_else(|| anyhow!("Bundle is missing {}", name))
+        };
+
+        let manifest: ScanBundleManifest = serde_json::from_slice(&take(MANIFEST_FILE)?)?;
+        if manifest.format != SCAN_BUNDLE_FORMAT {
+            return Err(anyhow!("Not a scan session bundle: {}", manifest.format));
+        }
+        if manifest.version > SCAN_BUNDLE_VERSION {
+            return Err(anyhow!(
+                "Unsupported bundle version {} (max {})",
+                manifest.version,
+                SCAN_BUNDLE_VERSION
+            ));
+        }
+        let BundledSession {
+            mut session,
+            mut stages,
+        } = serde_json::from_slice(&take(SESSION_FILE)?)?;
+        let vulnerabilities: Vec<TrafficVulnerabilityWithEvidence> =
+            serde_json::from_slice(&take(EVIDENCE_FILE)?)?;
+        let har: Value = serde_json::from_slice(&take(HAR_FILE)?)?;
+
+        let requests = from_har(&har);
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow!("数据库未初始化"))?;
+
+        let mut result = ScanBundleImport {
+            original_session_id: session.id,
+            ..Default::default()
+        };
+        // Written rows that belong in the search index once the transaction has committed
+        let mut indexed_findings: Vec<TrafficFinding> = Vec::new();
+        let mut indexed_requests: Vec<(i64, ProxyRequestRecord)> = Vec::new();
+
+        macro_rules! import_in_tx {
+            ($pool:expr, $sql:expr, $returns_id:expr) => {{
+                let sql: fn(&str) -> String = $sql;
+                let mut tx = $pool.begin().await?;
+
+                // Session and stages: keep ids unless the session already exists here
+                let taken: Option<String> = sqlx::query_scalar(&sql(SELECT_SESSION_ID))
+                    .bind(session.id.to_string())
+                    .fetch_optional(&mut *tx)
+                    .await?;
+                if taken.is_some() {
+                    session.id = Uuid::new_v4();
+                    for stage in &mut stages {
+                        stage.id = Uuid::new_v4();
+                    }
+                    result.session_remapped = true;
+                }
+                sqlx::query(&sql(INSERT_SESSION))
+                    .bind(session.id.to_string())
+                    .bind(&session.name)
+                    .bind(&session.description)
+                    .bind(&session.target)
+                    .bind(&session.scan_type)
+                    .bind(serde_json::to_string(&session.status)?)
+                    .bind(serde_json::to_string(&session.config)?)
+                    .bind(session.progress)
+                    .bind(&session.current_stage)
+                    .bind(session.total_stages)
+                    .bind(session.completed_stages)
+                    .bind(
+                        session
+                            .results_summary
+                            .as_ref()
+                            .map(serde_json::to_string)
+                            .transpose()?,
+                    )
+                    .bind(&session.error_message)
+                    .bind(session.created_at)
+                    .bind(&session.created_by)
+                    .execute(&mut *tx)
+                    .await?;
+                for stage in &mut stages {
+                    stage.session_id = session.id;
+                    sqlx::query(&sql(INSERT_STAGE))
+                        .bind(stage.id.to_string())
+                        .bind(stage.session_id.to_string())
+                        .bind(&stage.stage_name)
+                        .bind(stage.stage_order)
+                        .bind(serde_json::to_string(&stage.status)?)
+                        .bind(&stage.tool_name)
+                        .bind(serde_json::to_string(&stage.config)?)
+                        .bind(
+                            stage
+                                .results
+                                .as_ref()
+                                .map(serde_json::to_string)
+                                .transpose()?,
+                        )
+                        .bind(&stage.error_message)
+                        .bind(stage.started_at)
+                        .bind(stage.completed_at)
+                        .bind(stage.duration_ms)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+
+                for bundled in &vulnerabilities {
+                    let vuln = &bundled.vulnerability;
+                    let mut evidence = bundled.evidence.clone();
+                    evidence.sort_by_key(|e| e.timestamp);
+
+                    let known: Option<String> = sqlx::query_scalar(&sql(SELECT_VULN_BY_SIGNATURE))
+                        .bind(&vuln.signature)
+                        .fetch_optional(&mut *tx)
+                        .await?;
+                    let vuln_id = match known {
+                        Some(existing) => {
+                            result.merged_vulnerabilities += 1;
+                            existing
+                        }
+                        None => {
+                            let taken: Option<String> = sqlx::query_scalar(&sql(SELECT_VULN_ID))
+                                .bind(&vuln.id)
+                                .fetch_optional(&mut *tx)
+                                .await?;
+                            let vuln_id = if taken.is_some() {
+                                result.remapped_vulnerabilities += 1;
+                                Uuid::new_v4().to_string()
+                            } else {
+                                vuln.id.clone()
+                            };
+                            // Stored columns are copied as-is (confidence keeps its stored form)
+                            sqlx::query(&sql(INSERT_VULNERABILITY))
+                                .bind(&vuln_id)
+                                .bind(&vuln.plugin_id)
+                                .bind(&vuln.vuln_type)
+                                .bind(&vuln.severity)
+                                .bind(&vuln.confidence)
+                                .bind(&vuln.title)
+                                .bind(&vuln.description)
+                                .bind(&vuln.cwe)
+                                .bind(&vuln.owasp)
+                                .bind(&vuln.remediation)
+                                .bind(&vuln.status)
+                                .bind(&vuln.signature)
+                                .bind(vuln.first_seen_at)
+                                .bind(vuln.last_seen_at)
+                                .bind(vuln.hit_count)
+                                .execute(&mut *tx)
+                                .await?;
+                            sqlx::query(&sql(INSERT_DEDUPE_INDEX))
+                                .bind(&vuln.signature)
+                                .bind(&vuln_id)
+                                .execute(&mut *tx)
+                                .await?;
+                            result.vulnerabilities += 1;
+                            indexed_findings.push(search_entry(&vuln_id, vuln, evidence.first()));
+                            vuln_id
+                        }
+                    };
+
+                    for mut record in evidence {
+                        let present: Option<String> =
+                            sqlx::query_scalar(&sql(SELECT_DUPLICATE_EVIDENCE))
+                                .bind(&vuln_id)
+                                .bind(&record.url)
+                                .bind(record.timestamp)
+                                .fetch_optional(&mut *tx)
+                                .await?;
+                        if present.is_some() {
+                            continue;
+                        }
+                        let taken: Option<String> = sqlx::query_scalar(&sql(SELECT_EVIDENCE_ID))
+                            .bind(&record.id)
+                            .fetch_optional(&mut *tx)
+                            .await?;
+                        if taken.is_some() {
+                            record.id = Uuid::new_v4().to_string();
+                            result.remapped_evidence += 1;
+                        }
+                        sqlx::query(&sql(INSERT_EVIDENCE))
+                            .bind(&record.id)
+                            .bind(&vuln_id)
+                            .bind(&record.url)
+                            .bind(&record.method)
+                            .bind(&record.location)
+                            .bind(&record.evidence_snippet)
+                            .bind(&record.request_headers)
+                            .bind(&record.request_body)
+                            .bind(record.response_status)
+                            .bind(&record.response_headers)
+                            .bind(&record.response_body)
+                            .bind(record.timestamp)
+                            .execute(&mut *tx)
+                            .await?;
+                        result.evidence += 1;
+                    }
+                }
+
+                for record in requests {
+                    let present: Option<i64> = sqlx::query_scalar(&sql(SELECT_REQUEST_ID))
+                        .bind(&record.url)
+                        .bind(&record.method)
+                        .bind(record.timestamp)
+                        .fetch_optional(&mut *tx)
+                        .await?;
+                    if present.is_some() {
+                        continue;
+                    }
+                    let (request_body, request_compressed) =
+                        smart_compress(record.request_body.as_ref())?;
+                    let (response_body, response_compressed) =
+                        smart_compress(record.response_body.as_ref())?;
+                    let insert = if $returns_id {
+                        format!("{} RETURNING id", sql(INSERT_REQUEST).trim_end())
+                    } else {
+                        sql(INSERT_REQUEST)
+                    };
+                    let query = sqlx::query_scalar::<_, i64>(&insert)
+                        .bind(&record.url)
+                        .bind(&record.host)
+                        .bind(&record.protocol)
+                        .bind(&record.method)
+                        .bind(record.status_code)
+                        .bind(&record.request_headers)
+                        .bind(&request_body)
+                        .bind(&record.response_headers)
+                        .bind(&response_body)
+                        .bind(record.response_size)
+                        .bind(record.response_time)
+                        .bind(record.timestamp)
+                        .bind(request_compressed)
+                        .bind(response_compressed);
+                    if $returns_id {
+                        let id = query.fetch_one(&mut *tx).await?;
+                        indexed_requests.push((id, record));
+                    } else {
+                        query.fetch_optional(&mut *tx).await?;
+                    }
+                    result.requests += 1;
+                }
+
+                tx.commit().await?;
+            }};
+        }
+
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => import_in_tx!(pool, numbered, true),
+            DatabasePool::SQLite(pool) => import_in_tx!(pool, str::to_string, true),
+            DatabasePool::MySQL(pool) => import_in_tx!(pool, str::to_string, false),
+        }
+
+        result.session_id = session.id;
+        result.stages = stages.len();
+        result.findings = ScanSessionItems::collect(&session, &stages).findings.len();
+
+        self.index_traffic_findings(&indexed_findings).await;
+        for (id, record) in &indexed_requests {
+            self.index_proxy_request(*id, record).await;
+        }
+
+        info!(
+            "Imported scan session {} as {} ({} vulnerabilities, {} evidence ({} remapped), {} requests)",
+            result.original_session_id,
+            result.session_id,
+            result.vulnerabilities,
+            result.evidence,
+            result.remapped_evidence,
+            result.requests
+        );
+        Ok(result)
+    }
+}
+
+/// Rewrite `?` placeholders as `$1, $2, ...` for PostgreSQL
+fn numbered(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len() + 16);
+    let mut n = 0;
+    for c in sql.chars() {
+        if c == '?' {
+            n += 1;
+            out.push_str(&format!("${}", n));
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Search index entry for an imported vulnerability
+fn search_entry(
+    vuln_id: &str,
+    vuln: &TrafficVulnerabilityRecord,
+    first: Option<&TrafficEvidenceRecord>,
+) -> TrafficFinding {
+    TrafficFinding {
+        id: vuln_id.to_string(),
+        plugin_id: vuln.plugin_id.clone(),
+        vuln_type: vuln.vuln_type.clone(),
+        severity: vuln.severity.clone(),
+        confidence: vuln.confidence.clone(),
+        title: vuln.title.clone(),
+        description: vuln.description.clone(),
+        cwe: vuln.cwe.clone(),
+        owasp: vuln.owasp.clone(),
+        remediation: vuln.remediation.clone(),
+        url: first.map(|e| e.url.clone()).unwrap_or_default(),
+        method: first.map(|e| e.method.clone()).unwrap_or_default(),
+        location: first.map(|e| e.location.clone()).unwrap_or_default(),
+        evidence: first
+            .map(|e| e.evidence_snippet.clone())
+            .unwrap_or_default(),
+        request_headers: None,
+        request_body: None,
+        response_status: first.and_then(|e| e.response_status),
+        response_headers: None,
+        response_body: None,
+        created_at: vuln.first_seen_at,
+        signature: Some(vuln.signature.clone()),
+    }
+}
+
+/// Host part of a session target (`https://app.example.com/x`, `app.example.com:8443`, ...)
+fn target_host(target: &str) -> Option<String> {
+    let rest = target
+        .trim()
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(target.trim());
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let authority = authority.rsplit('@').next()?;
+    let host = if authority.starts_with('[') {
+        authority.split(']').next()?.trim_start_matches('[')
+    } else {
+        authority.split(':').next()?
+    };
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+fn write_zip(path: &Path, entries: &[(&str, Vec<u8>)]) -> Result<()> {
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create bundle {}", path.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (name, data) in entries {
+        zip.start_file(*name, options)?;
+        zip.write_all(data)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+fn read_zip(path: &Path) -> Result<HashMap<String, Vec<u8>>> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open bundle {}", path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut files = HashMap::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)?;
+        let name = entry.name().to_string();
+        if entry.size() > MAX_BUNDLE_ENTRY_SIZE {
+            return Err(anyhow!(
+                "Bundle entry {} is too large ({} bytes)",
+                name,
+                entry.size()
+            ));
+        }
+        // The declared size can lie; never read past the limit
+        let mut data = Vec::new();
+        entry
+            .take(MAX_BUNDLE_ENTRY_SIZE + 1)
+            .read_to_end(&mut data)?;
+        if data.len() as u64 > MAX_BUNDLE_ENTRY_SIZE {
+            return Err(anyhow!("Bundle entry {} is too large", name));
+        }
+        files.insert(name, data);
+    }
+    Ok(files)
+}
+
+/// Stored header JSON (`{"name": "value"}`) as HAR name/value pairs; merged Set-Cookie lines are split
+fn har_headers(headers: Option<&String>) -> Vec<Value> {
+    let map: HashMap<String, String> = headers
+        .and_then(|h| serde_json::from_str(h).ok())
+        .unwrap_or_default();
+    let mut pairs: Vec<(String, String)> = map
+        .into_iter()
+        .flat_map(|(name, value)| {
+            if name.eq_ignore_ascii_case("set-cookie") {
+                value
+                    .lines()
+                    .map(|line| (name.clone(), line.to_string()))
+                    .collect::<Vec<_>>()
+            } else {
+                vec![(name, value)]
+            }
+        })
+        .collect();
+    // Stable sort by name only: Set-Cookie lines keep their original order
+    pairs.sort_by(|a, b| a.0.cmp(&b.0));
+    pairs
+        .into_iter()
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect()
+}
+
+/// HAR name/value pairs back to stored header JSON
+fn stored_headers(headers: Option<&Value>) -> Option<String> {
+    let mut map: HashMap<String, String> = HashMap::new();
+    for header in headers.and_then(|h| h.as_array())? {
+        let (Some(name), Some(value)) = (
+            header.get("name").and_then(|v| v.as_str()),
+            header.get("value").and_then(|v| v.as_str()),
+        ) else {
+            continue;
+        };
+        match map.get_mut(name) {
+            Some(existing) => {
+                existing.push_str(if name.eq_ignore_ascii_case("set-cookie") {
+                    "\n"
+                } else {
+                    ", "
+                });
+                existing.push_str(value);
+            }
+            None => {
+                map.insert(name.to_string(), value.to_string());
+            }
+        }
+    }
+    serde_json::to_string(&map).ok()
+}
+
+fn header_value<'a>(headers: &'a [Value], name: &str) -> Option<&'a str> {
+    headers.iter().find_map(|h| {
+        h.get("name")
+            .and_then(|n| n.as_str())
+            .filter(|n| n.eq_ignore_ascii_case(name))
+            .and_then(|_| h.get("value").and_then(|v| v.as_str()))
+    })
+}
+
+fn to_har(records: &[ProxyRequestRecord]) -> Value {
+    let entries: Vec<Value> = records
+        .iter()
+        .map(|r| {
+            let request_headers = har_headers(r.request_headers.as_ref());
+            let response_headers = har_headers(r.response_headers.as_ref());
+            let mut request = json!({
+                "method": r.method,
+                "url": r.url,
+                "httpVersion": r.protocol,
+                "headers": request_headers,
+                "queryString": [],
+                "cookies": [],
+                "headersSize": -1,
+                "bodySize": r.request_body.as_ref().map_or(0, |b| b.len()),
+            });
+            if let Some(body) = &r.request_body {
+                request["postData"] = json!({
+                    "mimeType": header_value(&request_headers, "content-type").unwrap_or(""),
+                    "text": body,
+                });
+            }
+            json!({
+                "startedDateTime": r.timestamp.to_rfc3339(),
+                "time": r.response_time,
+                "request": request,
+                "response": {
+                    "status": r.status_code,
+                    "statusText": "",
+                    "httpVersion": r.protocol,
+                    "headers": response_headers,
+                    "cookies": [],
+                    "content": {
+                        "size": r.response_size,
+                        "mimeType": header_value(&response_headers, "content-type").unwrap_or(""),
+                        "text": r.response_body.clone().unwrap_or_default(),
+                    },
+                    "redirectURL": header_value(&response_headers, "location").unwrap_or(""),
+                    "headersSize": -1,
+                    "bodySize": r.response_size,
+                },
+                "cache": {},
+                "timings": { "send": 0, "wait": r.response_time, "receive": 0 },
+            })
+        })
+        .collect();
+
+    json!({
+        "log": {
+            "version": "1.2",
+            "creator": { "name": "sentinel-ai", "version": env!("CARGO_PKG_VERSION") },
+            "entries": entries,
+        }
+    })
+}
+
+fn from_har(har: &Value) -> Vec<ProxyRequestRecord> {
+    let Some(entries) = har.pointer("/log/entries").and_then(|e| e.as_array()) else {
+        return Vec::new();
+    };
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let request = entry.get("request")?;
+            let response = entry.get("response");
+            let url = request.get("url")?.as_str()?.to_string();
+            let str_at = |v: Option<&Value>, pointer: &str| {
+                v.and_then(|v| v.pointer(pointer))
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            };
+            let response_body = str_at(response, "/content/text").filter(|b| !b.is_empty());
+            Some(ProxyRequestRecord {
+                id: None,
+                host: target_host(&url).unwrap_or_default(),
+                protocol: str_at(Some(request), "/httpVersion")
+                    .unwrap_or_else(|| "HTTP/1.1".into()),
+                method: str_at(Some(request), "/method").unwrap_or_else(|| "GET".into()),
+                status_code: response
+                    .and_then(|r| r.get("status"))
+                    .and_then(|s| s.as_i64())
+                    .unwrap_or(0) as i32,
+                request_headers: stored_headers(request.get("headers")),
+                request_body: str_at(Some(request), "/postData/text"),
+                response_headers: stored_headers(response.and_then(|r| r.get("headers"))),
+                response_size: response
+                    .and_then(|r| r.pointer("/content/size"))
+                    .and_then(|s| s.as_i64())
+                    .unwrap_or_else(|| response_body.as_ref().map_or(0, |b| b.len() as i64)),
+                response_body,
+                response_time: entry.get("time").and_then(|t| t.as_f64()).unwrap_or(0.0) as i64,
+                timestamp: entry
+                    .get("startedDateTime")
+                    .and_then(|t| t.as_str())
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .map(|t| t.with_timezone(&Utc))
+                    .unwrap_or_else(Utc::now),
+                url,
+                request_body_compressed: false,
+                response_body_compressed: false,
+            })
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "db-sqlite"))]
+mod tests {
+    use super::*;
+    use crate::core::models::scan_session::CreateScanSessionRequest;
+    use crate::database_service::test_service;
+
+    async fn service() -> DatabaseService {
+        test_service(&[
+            "CREATE TABLE scan_sessions (id TEXT PRIMARY KEY, name TEXT NOT NULL, description TEXT, target TEXT NOT NULL, scan_type TEXT NOT NULL, status TEXT NOT NULL, config TEXT NOT NULL, progress DOUBLE PRECISION DEFAULT 0.0, current_stage TEXT NOT NULL, total_stages INTEGER DEFAULT 0, completed_stages INTEGER DEFAULT 0, results_summary TEXT, error_message TEXT, created_at TIMESTAMP NOT NULL, started_at TIMESTAMP, completed_at TIMESTAMP, created_by TEXT)",
+            "CREATE TABLE scan_stages (id TEXT PRIMARY KEY, session_id TEXT NOT NULL, stage_name TEXT NOT NULL, stage_order INTEGER NOT NULL, status TEXT NOT NULL, tool_name TEXT NOT NULL, config TEXT NOT NULL, results TEXT, error_message TEXT, started_at TIMESTAMP, completed_at TIMESTAMP, duration_ms INTEGER)",
+            "CREATE TABLE traffic_vulnerabilities (id TEXT PRIMARY KEY, plugin_id TEXT, vuln_type TEXT, severity TEXT, confidence TEXT, title TEXT, description TEXT, cwe TEXT, owasp TEXT, remediation TEXT, status TEXT NOT NULL DEFAULT 'open', signature TEXT, first_seen_at TIMESTAMP, last_seen_at TIMESTAMP, hit_count INTEGER NOT NULL DEFAULT 1, session_id TEXT, created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+            "CREATE TABLE traffic_dedupe_index (signature TEXT PRIMARY KEY, vuln_id TEXT)",
+            "CREATE TABLE traffic_evidence (id TEXT PRIMARY KEY, vuln_id TEXT, url TEXT, method TEXT, location TEXT, evidence_snippet TEXT, request_headers TEXT, request_body TEXT, response_status INTEGER, response_headers TEXT, response_body TEXT, timestamp TIMESTAMP)",
+            "CREATE TABLE proxy_requests (id INTEGER PRIMARY KEY AUTOINCREMENT, url TEXT NOT NULL, host TEXT NOT NULL, protocol TEXT NOT NULL, method TEXT NOT NULL, status_code INTEGER NOT NULL, request_headers TEXT, request_body TEXT, response_headers TEXT, response_body TEXT, response_size INTEGER NOT NULL DEFAULT 0, response_time INTEGER NOT NULL DEFAULT 0, timestamp TIMESTAMP NOT NULL, request_body_compressed BOOLEAN NOT NULL DEFAULT FALSE, response_body_compressed BOOLEAN NOT NULL DEFAULT FALSE)",
+        ])
+        .await
+    }
+
+    fn finding(id: &str, url: &str, location: &str) -> TrafficFinding {
+        TrafficFinding {
+            id: id.to_string(),
+            plugin_id: "xss".to_string(),
+            vuln_type: "xss".to_string(),
+            severity: "high".to_string(),
+            confidence: "Firm".to_string(),
+            title: "Reflected XSS".to_string(),
+            description: "payload reflected".to_string(),
+            cwe: None,
+            owasp: None,
+            remediation: None,
+            url: url.to_string(),
+            method: "GET".to_string(),
+            location: location.to_string(),
+            evidence: format!("<script>{}</script>", location),
+            request_headers: None,
+            request_body: None,
+            response_status: Some(200),
+            response_headers: None,
+            response_body: None,
+            created_at: Utc::now(),
+            signature: None,
+        }
+    }
+
+    async fn seed(service: &DatabaseService) -> Uuid {
+        let session = service
+            .create_scan_session_internal(CreateScanSessionRequest {
+                name: "nightly".to_string(),
+                description: None,
+                target: "https://app.test/".to_string(),
+                scan_type: "web".to_string(),
+                config: json!({}),
+                created_by: None,
+            })
+            .await
+            .unwrap();
+        let mut stage = ScanStage::new(
+            session.id,
+            "vuln_scan".to_string(),
+            1,
+            "scanner".to_string(),
+            json!({}),
+        );
+        service
+            .create_scan_stage_internal(stage.clone())
+            .await
+            .unwrap();
+        stage.complete(Some(json!({
+            "findings": [
+                {"plugin_id": "xss", "vuln_type": "xss", "url": "https://app.test/search", "location": "q", "title": "Reflected XSS"},
+                {"plugin_id": "sqli", "vuln_type": "sqli", "url": "https://app.test/item", "location": "id", "title": "SQL injection"}
+            ],
+            "assets": ["app.test"]
+        })));
+        service.update_scan_stage_internal(&stage).await.unwrap();
+
+        service
+            .insert_findings_batch(&[
+                finding("v1", "https://app.test/search", "q"),
+                finding("v2", "https://app.test/profile", "name"),
+                finding("v3", "https://other.test/", "q"),
+            ])
+            .await
+            .unwrap();
+        service
+            .insert_traffic_evidence(&TrafficEvidenceRecord {
+                id: "v1-extra".to_string(),
+                vuln_id: "v1".to_string(),
+                url: "https://app.test/search?q=2".to_string(),
+                method: "GET".to_string(),
+                location: "q".to_string(),
+                evidence_snippet: "<script>2</script>".to_string(),
+                request_headers: None,
+                request_body: None,
+                response_status: Some(200),
+                response_headers: None,
+                response_body: None,
+                timestamp: Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        for url in ["https://app.test/login", "https://other.test/"] {
+            service
+                .insert_proxy_request(&ProxyRequestRecord {
+                    id: None,
+                    url: url.to_string(),
+                    host: target_host(url).unwrap(),
+                    protocol: "HTTP/1.1".to_string(),
+                    method: "POST".to_string(),
+                    status_code: 302,
+                    request_headers: Some(
+                        r#"{"content-type":"application/x-www-form-urlencoded"}"#.to_string(),
+                    ),
+                    request_body: Some("user=admin".to_string()),
+                    response_headers: Some(r#"{"set-cookie":"sid=1\nremember=1"}"#.to_string()),
+                    response_body: None,
+                    response_size: 0,
+                    response_time: 12,
+                    timestamp: Utc::now(),
+                    request_body_compressed: false,
+                    response_body_compressed: false,
+                })
+                .await
+                .unwrap();
+        }
+        session.id
+    }
+
+    async fn evidence_snippets(service: &DatabaseService) -> Vec<String> {
+        let mut snippets: Vec<String> = service
+            .list_traffic_vulnerabilities_with_evidence(TrafficVulnerabilityFilters::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .flat_map(|v| v.evidence.into_iter().map(|e| e.evidence_snippet))
+            .collect();
+        snippets.sort();
+        snippets
+    }
+
+    #[tokio::test]
+    async fn test_bundle_round_trip() {
+        let source = service().await;
+        let session_id = seed(&source).await;
+        let path = std::env::temp_dir().join(format!("scan-bundle-{}.zip", Uuid::new_v4()));
+
+        let manifest = source
+            .export_scan_session_bundle(session_id, &path)
+            .await
+            .unwrap();
+        assert_eq!(manifest.findings, 2);
+        assert_eq!(manifest.assets, 1);
+        assert_eq!(manifest.vulnerabilities, 2);
+        assert_eq!(manifest.evidence, 3);
+        assert_eq!(manifest.requests, 1);
+
+        let target = service().await;
+        let imported = target.import_scan_session_bundle(&path).await.unwrap();
+        assert_eq!(imported.session_id, session_id);
+        assert!(!imported.session_remapped);
+        assert_eq!(imported.findings, 2);
+        assert_eq!(imported.vulnerabilities, 2);
+        assert_eq!(imported.evidence, 3);
+        assert_eq!(imported.requests, 1);
+
+        let session = target
+            .get_scan_session_internal(session_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let stages = target
+            .get_scan_session_stages_internal(session_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            ScanSessionItems::collect(&session, &stages).findings.len(),
+            2
+        );
+        assert_eq!(
+            evidence_snippets(&target).await,
+            vec![
+                "<script>2</script>",
+                "<script>name</script>",
+                "<script>q</script>"
+            ]
+        );
+        let requests = target
+            .list_proxy_requests(ProxyRequestFilters::default())
+            .await
+            .unwrap();
+        assert_eq!(requests[0].request_body.as_deref(), Some("user=admin"));
+        let headers: HashMap<String, String> =
+            serde_json::from_str(requests[0].response_headers.as_deref().unwrap()).unwrap();
+        assert_eq!(headers["set-cookie"], "sid=1\nremember=1");
+
+        // Importing into the source install collides with the original ids
+        let again = source.import_scan_session_bundle(&path).await.unwrap();
+        assert!(again.session_remapped);
+        assert_ne!(again.session_id, session_id);
+        assert_eq!(again.merged_vulnerabilities, 2);
+        assert_eq!(again.vulnerabilities, 0);
+        // Evidence and requests already present are not duplicated
+        assert_eq!(again.evidence, 0);
+        assert_eq!(again.requests, 0);
+        assert_eq!(evidence_snippets(&source).await.len(), 4);
+        assert_eq!(
+            source
+                .list_proxy_requests(ProxyRequestFilters::default())
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_colliding_evidence_id_is_remapped() {
+        let source = service().await;
+        let session_id = seed(&source).await;
+        let path = std::env::temp_dir().join(format!("scan-bundle-{}.zip", Uuid::new_v4()));
+        source
+            .export_scan_session_bundle(session_id, &path)
+            .await
+            .unwrap();
+
+        // An unrelated local record already uses the bundled evidence id
+        let target = service().await;
+        target
+            .insert_traffic_evidence(&TrafficEvidenceRecord {
+                id: "v1-extra".to_string(),
+                vuln_id: "local".to_string(),
+                url: "https://local.test/".to_string(),
+                method: "GET".to_string(),
+                location: "q".to_string(),
+                evidence_snippet: "<script>local</script>".to_string(),
+                request_headers: None,
+                request_body: None,
+                response_status: Some(200),
+                response_headers: None,
+                response_body: None,
+                timestamp: Utc::now(),
+            })
+            .await
+            .unwrap();
+
+        let imported = target.import_scan_session_bundle(&path).await.unwrap();
+        assert_eq!(imported.evidence, 3);
+        assert_eq!(imported.remapped_evidence, 1);
+        assert_eq!(
+            evidence_snippets(&target).await,
+            vec![
+                "<script>2</script>",
+                "<script>name</script>",
+                "<script>q</script>"
+            ]
+        );
+        let local = target
+            .get_traffic_evidence_by_vuln_id("local")
+            .await
+            .unwrap();
+        assert_eq!(local.len(), 1);
+        assert_eq!(local[0].id, "v1-extra");
+        assert_eq!(local[0].evidence_snippet, "<script>local</script>");
+
+        // Re-importing recognises the remapped evidence and adds nothing
+        let again = target.import_scan_session_bundle(&path).await.unwrap();
+        assert_eq!(again.evidence, 0);
+        assert_eq!(again.remapped_evidence, 0);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_import_keeps_vulnerabilities_without_evidence() {
+        let source = service().await;
+        let session_id = seed(&source).await;
+        let path = std::env::temp_dir().join(format!("scan-bundle-{}.zip", Uuid::new_v4()));
+        source
+            .export_scan_session_bundle(session_id, &path)
+            .await
+            .unwrap();
+
+        // Strip the evidence from the bundle
+        let mut files = read_zip(&path).unwrap();
+        let mut vulnerabilities: Vec<TrafficVulnerabilityWithEvidence> =
+            serde_json::from_slice(&files[EVIDENCE_FILE]).unwrap();
+        for v in &mut vulnerabilities {
+            v.evidence.clear();
+        }
+        files.insert(
+            EVIDENCE_FILE.to_string(),
+            serde_json::to_vec(&vulnerabilities).unwrap(),
+        );
+        let entries: Vec<(&str, Vec<u8>)> = files
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.clone()))
+            .collect();
+        write_zip(&path, &entries).unwrap();
+
+        let target = service().await;
+        let imported = target.import_scan_session_bundle(&path).await.unwrap();
+        assert_eq!(imported.vulnerabilities, 2);
+        assert_eq!(imported.evidence, 0);
+        assert_eq!(
+            target
+                .list_traffic_vulnerabilities_with_evidence(TrafficVulnerabilityFilters::default())
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_target_host() {
+        assert_eq!(
+            target_host("https://App.test:8443/x?y").as_deref(),
+            Some("app.test")
+        );
+        assert_eq!(target_host("app.test").as_deref(), Some("app.test"));
+        assert_eq!(target_host("http://user@[::1]:80/").as_deref(), Some("::1"));
+        assert_eq!(target_host(""), None);
+    }
+}
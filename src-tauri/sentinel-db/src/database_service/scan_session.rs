@@ -1,6 +1,7 @@
 use crate::core::models::scan_session::{
-    CreateScanSessionRequest, ScanProgress, ScanSession, ScanSessionStatus, ScanStage,
-    ScanStageProgress, ScanStageStatus, UpdateScanSessionRequest,
+    CreateScanSessionRequest, ScanProgress, ScanSession, ScanSessionDiff, ScanSessionItems,
+    ScanSessionStatus, ScanStage, ScanStageProgress, ScanStageStatus, ScanStageWithEstimate,
+    UpdateScanSessionRequest,
 };
 use crate::database_service::connection_manager::DatabasePool;
 use crate::database_service::service::DatabaseService;
@@ -14,10 +15,6 @@ impl DatabaseService {
         &self,
         request: CreateScanSessionRequest,
     ) -> Result<ScanSession> {
-        let runtime = self
-            .runtime_pool
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
         let session = ScanSession::new(
             request.name,
             request.target,
@@ -25,6 +22,16 @@ impl DatabaseService {
             request.config,
             request.created_by,
         );
+        self.insert_scan_session_internal(&session).await?;
+        Ok(session)
+    }
+
+    /// 按会话自身的 ID 写入记录（导入时保留原 ID）
+    pub async fn insert_scan_session_internal(&self, session: &ScanSession) -> Result<()> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
 
         let query = r#"
             INSERT INTO scan_sessions (
@@ -105,7 +112,7 @@ impl DatabaseService {
             }
         }
 
-        Ok(session)
+        Ok(())
     }
 
     pub async fn get_scan_session_internal(&self, session_id: Uuid) -> Result<Option<ScanSession>> {
@@ -534,22 +541,45 @@ impl DatabaseService {
         let session = self.get_scan_session_internal(session_id).await?;
         if let Some(session) = session {
             let stages = self.get_scan_session_stages_internal(session_id).await?;
+            let now = Utc::now();
+            let estimates = ScanStageWithEstimate::estimate(stages, now);
 
-            let stage_progress: Vec<ScanStageProgress> = stages
+            let stage_progress: Vec<ScanStageProgress> = estimates
                 .iter()
-                .map(|stage| ScanStageProgress {
-                    stage_name: stage.stage_name.clone(),
-                    status: stage.status.clone(),
-                    progress: match stage.status {
+                .map(|estimate| ScanStageProgress {
+                    stage_name: estimate.stage.stage_name.clone(),
+                    status: estimate.stage.status.clone(),
+                    progress: match estimate.stage.status {
                         ScanStageStatus::Completed => 100.0,
-                        ScanStageStatus::Running => 50.0, // 估算值
+                        ScanStageStatus::Running => {
+                            match (estimate.elapsed_ms, estimate.estimated_remaining_ms) {
+                                (Some(elapsed), Some(remaining)) if elapsed + remaining > 0 => {
+                                    (elapsed as f64 / (elapsed + remaining) as f64 * 100.0)
+                                        .min(99.0)
+                                }
+                                _ => 50.0, // 无参考耗时时的估算值
+                            }
+                        }
                         _ => 0.0,
                     },
-                    started_at: stage.started_at,
-                    estimated_completion: None, // TODO: 实现时间估算
+                    started_at: estimate.stage.started_at,
+                    estimated_completion: estimate.estimated_completion,
                 })
                 .collect();
 
+            // 优先按阶段平均耗时估算，否则按会话整体进度线性外推
+            let remaining_ms: Option<i64> =
+                estimates.iter().map(|e| e.estimated_remaining_ms).sum();
+            let estimated_time_remaining = remaining_ms
+                .filter(|_| !estimates.is_empty())
+                .map(|ms| ms / 1000)
+                .or_else(|| {
+                    let elapsed = now.signed_duration_since(session.started_at?).num_seconds();
+                    (session.progress > 0.0 && session.progress < 100.0).then(|| {
+                        (elapsed as f64 * (100.0 - session.progress) / session.progress) as i64
+                    })
+                });
+
             let progress = ScanProgress {
                 session_id,
                 overall_progress: session.progress,
@@ -557,7 +587,7 @@ impl DatabaseService {
                 completed_stages: session.completed_stages,
                 total_stages: session.total_stages,
                 stages: stage_progress,
-                estimated_time_remaining: None, // TODO: 实现时间估算
+                estimated_time_remaining,
             };
 
             Ok(Some(progress))
@@ -565,6 +595,31 @@ impl DatabaseService {
             Ok(None)
         }
     }
+
+    /// 对比两次扫描会话的发现与资产（按指纹匹配）
+    pub async fn diff_scan_sessions_internal(
+        &self,
+        base_session_id: Uuid,
+        compare_session_id: Uuid,
+    ) -> Result<ScanSessionDiff> {
+        let base = self.load_scan_session_items(base_session_id).await?;
+        let compare = self.load_scan_session_items(compare_session_id).await?;
+        Ok(ScanSessionDiff::compute(
+            base_session_id,
+            &base,
+            compare_session_id,
+            &compare,
+        ))
+    }
+
+    async fn load_scan_session_items(&self, session_id: Uuid) -> Result<ScanSessionItems> {
+        let session = self
+            .get_scan_session_internal(session_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("扫描会话不存在: {}", session_id))?;
+        let stages = self.get_scan_session_stages_internal(session_id).await?;
+        Ok(ScanSessionItems::collect(&session, &stages))
+    }
 }
 
 fn parse_scan_session_row(row: &Value) -> Result<ScanSession> {
@@ -1,11 +1,15 @@
 use crate::core::models::database::DatabaseStats;
+use crate::database_service::backup_crypto::{
+    decrypt_backup, encrypt_backup, is_encrypted_backup, ENCRYPTED_BACKUP_EXTENSION,
+};
 use crate::database_service::connection_manager::DatabasePool;
 use crate::database_service::db_config::{
     db_config_toml_path, load_db_config_from_disk, DatabaseConfig, DatabaseType,
 };
 use crate::database_service::migration::DatabaseMigration;
 use crate::database_service::migrations::AgentTeamMigration;
-use crate::database_service::sqlx_compat::{MySqlRow, PgPool, PgPoolOptions, PgRow};
+use crate::database_service::pool_health::{recycle_hook, PoolHealth};
+use crate::database_service::sqlx_compat::{MySqlRow, PgPool, PgPoolOptions, PgRow, Postgres};
 use anyhow::Result;
 use serde_json::Value;
 use sqlx::{Column, Row, TypeInfo};
@@ -21,6 +25,7 @@ pub struct DatabaseService {
     pub(crate) runtime_pool: Option<DatabasePool>,
     pub(crate) config: Option<DatabaseConfig>,
     pub(crate) write_semaphore: Arc<Semaphore>,
+    pub(crate) pool_health: Arc<PoolHealth>,
 }
 
 impl DatabaseService {
@@ -36,6 +41,7 @@ impl DatabaseService {
             runtime_pool: None,
             config: None,
             write_semaphore: Arc::new(Semaphore::new(10)), // Higher limit for PG
+            pool_health: Arc::new(PoolHealth::new()),
         }
     }
 
@@ -66,6 +72,44 @@ impl DatabaseService {
         Err(anyhow::anyhow!("数据库未初始化"))
     }
 
+    /// 对连接池执行一次 `SELECT 1`，确认数据库实际可用
+    pub async fn ping(&self) -> Result<()> {
+        match self.get_runtime_pool()? {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query("SELECT 1").execute(&pool).await?;
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query("SELECT 1").execute(&pool).await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query("SELECT 1").execute(&pool).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 关闭连接池：等待已借出的连接归还（进行中的写入完成）后关闭；SQLite 先执行 WAL checkpoint
+    pub async fn close(&self) {
+        if let Some(runtime) = self.runtime_pool.as_ref() {
+            match runtime {
+                DatabasePool::PostgreSQL(pool) => pool.close().await,
+                DatabasePool::SQLite(pool) => {
+                    if let Err(e) = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+                        .execute(pool)
+                        .await
+                    {
+                        tracing::warn!("SQLite WAL checkpoint failed: {}", e);
+                    }
+                    pool.close().await;
+                }
+                DatabasePool::MySQL(pool) => pool.close().await,
+            }
+        }
+        if let Some(pool) = self.pool.as_ref() {
+            pool.close().await;
+        }
+    }
+
     /// Get database pool (public method for external use)
     pub fn pool(&self) -> &PgPool {
         self.get_pool().expect("Database not initialized")
@@ -120,7 +164,8 @@ impl DatabaseService {
             .join("skills")
     }
 
-    pub async fn backup(&self, path: Option<PathBuf>) -> Result<PathBuf> {
+    /// 创建备份；提供密码时输出加密备份（`.enc`）
+    pub async fn backup(&self, path: Option<PathBuf>, passphrase: Option<&str>) -> Result<PathBuf> {
         let runtime = self
             .runtime_pool
             .as_ref()
@@ -130,7 +175,10 @@ impl DatabaseService {
             DatabasePool::SQLite(_) => "db",
             DatabasePool::PostgreSQL(_) | DatabasePool::MySQL(_) => "sql",
         };
-        let filename = format!("backup_{}.{}", chrono::Utc::now().timestamp(), ext);
+        let mut filename = format!("backup_{}.{}", chrono::Utc::now().timestamp(), ext);
+        if passphrase.is_some() {
+            filename = format!("{}.{}", filename, ENCRYPTED_BACKUP_EXTENSION);
+        }
         let default_dir = dirs::data_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("sentinel-ai");
@@ -143,29 +191,66 @@ impl DatabaseService {
             std::fs::create_dir_all(parent)?;
         }
 
-        match runtime {
-            DatabasePool::SQLite(_) => {
-                let source = self.get_db_path();
-                if source == backup_path {
-                    return Err(anyhow::anyhow!("备份路径不能与当前数据库文件相同"));
+        if let DatabasePool::SQLite(_) = runtime {
+            if self.get_db_path() == backup_path {
+                return Err(anyhow::anyhow!("备份路径不能与当前数据库文件相同"));
+            }
+        }
+
+        let Some(passphrase) = passphrase else {
+            match runtime {
+                DatabasePool::SQLite(_) => {
+                    tokio::fs::copy(self.get_db_path(), &backup_path).await?;
+                }
+                DatabasePool::PostgreSQL(_) | DatabasePool::MySQL(_) => {
+                    let migration = DatabaseMigration::new(runtime.clone());
+                    migration.export_to_sql(&backup_path).await?;
                 }
-                tokio::fs::copy(&source, &backup_path).await?;
             }
+            return Ok(backup_path);
+        };
+
+        // 加密备份只向目标路径写入密文：SQLite 直接在内存中读取数据库文件，
+        // SQL 导出暂存在应用数据目录下，读取后立即删除
+        let plain = match runtime {
+            DatabasePool::SQLite(_) => tokio::fs::read(self.get_db_path()).await?,
             DatabasePool::PostgreSQL(_) | DatabasePool::MySQL(_) => {
+                let staging_dir = default_dir.join("tmp");
+                std::fs::create_dir_all(&staging_dir)?;
+                let staging_path =
+                    staging_dir.join(format!("backup-{}.sql.partial", uuid::Uuid::new_v4()));
                 let migration = DatabaseMigration::new(runtime.clone());
-                migration.export_to_sql(&backup_path).await?;
+                let plain = async {
+                    migration.export_to_sql(&staging_path).await?;
+                    Ok::<_, anyhow::Error>(tokio::fs::read(&staging_path).await?)
+                }
+                .await;
+                let _ = tokio::fs::remove_file(&staging_path).await;
+                plain?
             }
-        }
+        };
+        let encrypted = encrypt_backup(&plain, passphrase)?;
+        tokio::fs::write(&backup_path, encrypted).await?;
 
         Ok(backup_path)
     }
 
-    pub async fn restore(&self, path: PathBuf) -> Result<()> {
+    /// 恢复备份；加密备份需要提供密码，解密失败时不会改动当前数据库
+    pub async fn restore(&self, path: PathBuf, passphrase: Option<&str>) -> Result<()> {
         let runtime = self
             .runtime_pool
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
 
+        let data = tokio::fs::read(&path).await?;
+        let data = if is_encrypted_backup(&data) {
+            let passphrase =
+                passphrase.ok_or_else(|| anyhow::anyhow!("备份文件已加密，请提供密码"))?;
+            decrypt_backup(&data, passphrase)?
+        } else {
+            data
+        };
+
         match runtime {
             DatabasePool::SQLite(_) => {
                 let target = self.get_db_path();
@@ -175,11 +260,12 @@ impl DatabaseService {
                 if let Some(parent) = target.parent() {
                     std::fs::create_dir_all(parent)?;
                 }
-                tokio::fs::copy(&path, &target).await?;
+                tokio::fs::write(&target, data).await?;
                 Ok(())
             }
             DatabasePool::PostgreSQL(_) | DatabasePool::MySQL(_) => {
-                let sql = tokio::fs::read_to_string(&path).await?;
+                let sql = String::from_utf8(data)
+                    .map_err(|_| anyhow::anyhow!("备份文件不是有效的 SQL 文本"))?;
                 self.execute_sql_script(runtime, &sql).await
             }
         }
@@ -200,7 +286,11 @@ impl DatabaseService {
                 DatabaseConfig::default()
             }
         };
+        self.initialize_with_config(config).await
+    }
 
+    /// 使用给定配置初始化（不读取磁盘上的配置文件）
+    pub async fn initialize_with_config(&mut self, config: DatabaseConfig) -> Result<()> {
         #[cfg(not(feature = "db-postgres"))]
         if matches!(config.db_type, DatabaseType::PostgreSQL) {
             return Err(anyhow::anyhow!(
@@ -219,10 +309,13 @@ impl DatabaseService {
 
         // Non-PostgreSQL databases use runtime pool for generic commands and migrations.
         if !matches!(config.db_type, DatabaseType::PostgreSQL) {
-            let runtime = DatabasePool::connect(&config).await?;
+            let runtime =
+                DatabasePool::connect_with_health(&config, self.pool_health.clone()).await?;
             self.ensure_compat_schema(&runtime).await?;
             self.runtime_pool = Some(runtime);
             self.pool = None;
+            self.ensure_feature_tables().await?;
+            self.apply_traffic_search_migration().await?;
             self.ensure_runtime_default_data().await?;
             tracing::warn!(
                 "Database initialized in {:?} compatibility mode; PostgreSQL-specific features may be unavailable",
@@ -247,6 +340,7 @@ impl DatabaseService {
         let pool = match PgPoolOptions::new()
             .max_connections(config.max_connections)
             .acquire_timeout(Duration::from_secs(config.query_timeout as u64))
+            .before_acquire(recycle_hook::<Postgres>(self.pool_health.clone()))
             .connect(&conn_str)
             .await
         {
@@ -274,6 +368,7 @@ impl DatabaseService {
                     PgPoolOptions::new()
                         .max_connections(config.max_connections)
                         .acquire_timeout(Duration::from_secs(config.query_timeout as u64))
+                        .before_acquire(recycle_hook::<Postgres>(self.pool_health.clone()))
                         .connect(&conn_str)
                         .await?
                 } else {
@@ -288,6 +383,7 @@ impl DatabaseService {
 
         self.runtime_pool = Some(DatabasePool::PostgreSQL(pool.clone()));
         self.pool = Some(pool);
+        self.ensure_feature_tables().await?;
         self.ensure_runtime_default_data().await?;
         Ok(())
     }
@@ -340,6 +436,42 @@ impl DatabaseService {
                 .await?;
         }
 
+        // 确保 rag_document_sources 表有 file_mtime 字段（增量摄取）
+        let has_file_mtime: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM information_schema.columns WHERE table_name = 'rag_document_sources' AND column_name = 'file_mtime')"
+        ).fetch_one(pool).await?;
+
+        if !has_file_mtime {
+            info!("Adding file_mtime column to rag_document_sources table");
+            sqlx::query("ALTER TABLE rag_document_sources ADD COLUMN file_mtime BIGINT")
+                .execute(pool)
+                .await?;
+        }
+
+        // 确保 notification_rules 表有 conditions 字段（通知触发条件）
+        let has_notification_conditions: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM information_schema.columns WHERE table_name = 'notification_rules' AND column_name = 'conditions')"
+        ).fetch_one(pool).await?;
+
+        if !has_notification_conditions {
+            info!("Adding conditions column to notification_rules table");
+            sqlx::query("ALTER TABLE notification_rules ADD COLUMN conditions TEXT")
+                .execute(pool)
+                .await?;
+        }
+
+        // 确保 notification_rules 表有 throttle 字段（去重与摘要）
+        let has_notification_throttle: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM information_schema.columns WHERE table_name = 'notification_rules' AND column_name = 'throttle')"
+        ).fetch_one(pool).await?;
+
+        if !has_notification_throttle {
+            info!("Adding throttle column to notification_rules table");
+            sqlx::query("ALTER TABLE notification_rules ADD COLUMN throttle TEXT")
+                .execute(pool)
+                .await?;
+        }
+
         // Ensure memory_executions table exists
         let memory_table_exists: bool = sqlx::query_scalar(
             "SELECT EXISTS (SELECT 1 FROM information_schema.tables WHERE table_name = 'memory_executions')"
@@ -428,6 +560,7 @@ impl DatabaseService {
                     category TEXT,
                     metadata TEXT,
                     created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+                    position BIGINT,
                     FOREIGN KEY(dictionary_id) REFERENCES dictionaries(id) ON DELETE CASCADE
                 )"#,
             )
@@ -481,6 +614,18 @@ impl DatabaseService {
             info!("Dictionaries tables created successfully");
         }
 
+        // 确保 dictionary_words 表有 position 字段（导入顺序）
+        let has_word_position: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM information_schema.columns WHERE table_name = 'dictionary_words' AND column_name = 'position')"
+        ).fetch_one(pool).await?;
+
+        if !has_word_position {
+            info!("Adding position column to dictionary_words table");
+            sqlx::query("ALTER TABLE dictionary_words ADD COLUMN position BIGINT")
+                .execute(pool)
+                .await?;
+        }
+
         // Ensure skills table exists and includes required columns
         let skills_table_exists: bool = sqlx::query_scalar(
             "SELECT EXISTS (SELECT 1 FROM information_schema.tables WHERE table_name = 'skills')",
@@ -749,6 +894,17 @@ impl DatabaseService {
                 updated_at DATETIME NOT NULL,
                 created_by TEXT NOT NULL
             )"#,
+            r#"CREATE TABLE IF NOT EXISTS asset_relationships (
+                id TEXT PRIMARY KEY,
+                source_asset_id TEXT NOT NULL,
+                target_asset_id TEXT NOT NULL,
+                relationship_type TEXT NOT NULL,
+                description TEXT,
+                confidence DOUBLE DEFAULT 1.0,
+                metadata TEXT,
+                created_at DATETIME NOT NULL,
+                created_by TEXT NOT NULL
+            )"#,
             r#"CREATE TABLE IF NOT EXISTS workflow_definitions (
                 id TEXT PRIMARY KEY,
                 name TEXT NOT NULL,
@@ -861,6 +1017,8 @@ impl DatabaseService {
                 config TEXT,
                 is_encrypted BOOLEAN DEFAULT FALSE,
                 enabled BOOLEAN DEFAULT TRUE,
+                conditions TEXT,
+                throttle TEXT,
                 created_at DATETIME NOT NULL,
                 updated_at DATETIME NOT NULL
             )"#,
@@ -931,7 +1089,8 @@ impl DatabaseService {
                 weight DOUBLE DEFAULT 1.0,
                 category TEXT,
                 metadata TEXT,
-                created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                position BIGINT
             )"#,
             r#"CREATE TABLE IF NOT EXISTS rag_collections (
                 id TEXT PRIMARY KEY,
@@ -951,6 +1110,7 @@ impl DatabaseService {
                 file_type TEXT,
                 file_size BIGINT,
                 file_hash TEXT,
+                file_mtime BIGINT,
                 content_hash TEXT,
                 status TEXT DEFAULT 'Pending',
                 chunk_count BIGINT DEFAULT 0,
@@ -998,6 +1158,30 @@ impl DatabaseService {
         )
         .await
         .ok();
+        self.execute_runtime_ddl(
+            runtime,
+            "ALTER TABLE rag_document_sources ADD COLUMN file_mtime BIGINT",
+        )
+        .await
+        .ok();
+        self.execute_runtime_ddl(
+            runtime,
+            "ALTER TABLE dictionary_words ADD COLUMN position BIGINT",
+        )
+        .await
+        .ok();
+        self.execute_runtime_ddl(
+            runtime,
+            "ALTER TABLE notification_rules ADD COLUMN conditions TEXT",
+        )
+        .await
+        .ok();
+        self.execute_runtime_ddl(
+            runtime,
+            "ALTER TABLE notification_rules ADD COLUMN throttle TEXT",
+        )
+        .await
+        .ok();
         self.execute_runtime_ddl(
             runtime,
             "CREATE INDEX IF NOT EXISTS idx_traffic_evidence_vuln_id ON traffic_evidence(vuln_id)",
@@ -0,0 +1,189 @@
+//! Subdomain brute-force checkpoints
+//!
+//! One row per (domain, dictionary) pair records how far into the word list a
+//! run got and which subdomains it already reported, so an interrupted run can
+//! resume instead of starting over.
+
+use crate::database_service::connection_manager::DatabasePool;
+use crate::database_service::service::DatabaseService;
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+pub(super) const CREATE_CHECKPOINT_TABLE: &str = r#"CREATE TABLE IF NOT EXISTS subdomain_brute_checkpoints (
+    domain VARCHAR(255) NOT NULL,
+    dictionary_key VARCHAR(128) NOT NULL,
+    next_offset BIGINT NOT NULL,
+    total_words BIGINT NOT NULL,
+    found_json TEXT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (domain, dictionary_key)
+)"#;
+
+/// Progress of a subdomain brute-force run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubdomainBruteCheckpoint {
+    pub domain: String,
+    /// Stable identifier of the word list
+    pub dictionary_key: String,
+    /// Index of the first word not yet tried
+    pub next_offset: i64,
+    pub total_words: i64,
+    /// Subdomains already reported (JSON-encoded results)
+    pub found_json: String,
+}
+
+impl DatabaseService {
+    pub async fn get_subdomain_brute_checkpoint(
+        &self,
+        domain: &str,
+        dictionary_key: &str,
+    ) -> Result<Option<SubdomainBruteCheckpoint>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let row: Option<(i64, i64, String)> = match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query_as(
+                    "SELECT next_offset, total_words, found_json FROM subdomain_brute_checkpoints WHERE domain = $1 AND dictionary_key = $2",
+                )
+                .bind(domain)
+                .bind(dictionary_key)
+                .fetch_optional(pool)
+                .await?
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query_as(
+                    "SELECT next_offset, total_words, found_json FROM subdomain_brute_checkpoints WHERE domain = ? AND dictionary_key = ?",
+                )
+                .bind(domain)
+                .bind(dictionary_key)
+                .fetch_optional(pool)
+                .await?
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query_as(
+                    "SELECT next_offset, total_words, found_json FROM subdomain_brute_checkpoints WHERE domain = ? AND dictionary_key = ?",
+                )
+                .bind(domain)
+                .bind(dictionary_key)
+                .fetch_optional(pool)
+                .await?
+            }
+        };
+
+        Ok(row.map(
+            |(next_offset, total_words, found_json)| SubdomainBruteCheckpoint {
+                domain: domain.to_string(),
+                dictionary_key: dictionary_key.to_string(),
+                next_offset,
+                total_words,
+                found_json,
+            },
+        ))
+    }
+
+    pub async fn save_subdomain_brute_checkpoint(
+        &self,
+        checkpoint: &SubdomainBruteCheckpoint,
+    ) -> Result<()> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let now = Utc::now().timestamp_millis();
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query(
+                    "INSERT INTO subdomain_brute_checkpoints (domain, dictionary_key, next_offset, total_words, found_json, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6)
+                     ON CONFLICT(domain, dictionary_key) DO UPDATE SET next_offset = excluded.next_offset, total_words = excluded.total_words, found_json = excluded.found_json, updated_at = excluded.updated_at",
+                )
+                .bind(&checkpoint.domain)
+                .bind(&checkpoint.dictionary_key)
+                .bind(checkpoint.next_offset)
+                .bind(checkpoint.total_words)
+                .bind(&checkpoint.found_json)
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query(
+                    "INSERT INTO subdomain_brute_checkpoints (domain, dictionary_key, next_offset, total_words, found_json, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?)
+                     ON CONFLICT(domain, dictionary_key) DO UPDATE SET next_offset = excluded.next_offset, total_words = excluded.total_words, found_json = excluded.found_json, updated_at = excluded.updated_at",
+                )
+                .bind(&checkpoint.domain)
+                .bind(&checkpoint.dictionary_key)
+                .bind(checkpoint.next_offset)
+                .bind(checkpoint.total_words)
+                .bind(&checkpoint.found_json)
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query(
+                    "INSERT INTO subdomain_brute_checkpoints (domain, dictionary_key, next_offset, total_words, found_json, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?)
+                     ON DUPLICATE KEY UPDATE next_offset = VALUES(next_offset), total_words = VALUES(total_words), found_json = VALUES(found_json), updated_at = VALUES(updated_at)",
+                )
+                .bind(&checkpoint.domain)
+                .bind(&checkpoint.dictionary_key)
+                .bind(checkpoint.next_offset)
+                .bind(checkpoint.total_words)
+                .bind(&checkpoint.found_json)
+                .bind(now)
+                .execute(pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn delete_subdomain_brute_checkpoint(
+        &self,
+        domain: &str,
+        dictionary_key: &str,
+    ) -> Result<()> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query(
+                    "DELETE FROM subdomain_brute_checkpoints WHERE domain = $1 AND dictionary_key = $2",
+                )
+                .bind(domain)
+                .bind(dictionary_key)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query(
+                    "DELETE FROM subdomain_brute_checkpoints WHERE domain = ? AND dictionary_key = ?",
+                )
+                .bind(domain)
+                .bind(dictionary_key)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query(
+                    "DELETE FROM subdomain_brute_checkpoints WHERE domain = ? AND dictionary_key = ?",
+                )
+                .bind(domain)
+                .bind(dictionary_key)
+                .execute(pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -24,6 +24,23 @@ use crate::database_service::sqlx_compat::{MySql, Postgres};
 /// 压缩阈值（字节）- 超过此大小才压缩
 const COMPRESSION_THRESHOLD: usize = 1024; // 1KB
 
+/// 批量写入使用的语句（SQLite / MySQL 占位符）
+const INSERT_VULNERABILITY: &str = r#"
+    INSERT INTO traffic_vulnerabilities (
+        id, plugin_id, vuln_type, severity, confidence, title, description,
+        cwe, owasp, remediation, signature, first_seen_at, last_seen_at, session_id
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NULL)
+"#;
+pub(super) const INSERT_DEDUPE_INDEX: &str =
+    "INSERT INTO traffic_dedupe_index (signature, vuln_id) VALUES (?, ?)";
+pub(super) const INSERT_EVIDENCE: &str = r#"
+    INSERT INTO traffic_evidence (
+        id, vuln_id, url, method, location, evidence_snippet,
+        request_headers, request_body, response_status, response_headers,
+        response_body, timestamp
+    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+
 /// 压缩数据
 fn compress_data(data: &str) -> Result<Vec<u8>> {
     let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
@@ -40,7 +57,7 @@ fn decompress_data(data: &[u8]) -> Result<String> {
 }
 
 /// 智能压缩：只压缩超过阈值的数据
-fn smart_compress(data: Option<&String>) -> Result<(Option<String>, bool)> {
+pub(super) fn smart_compress(data: Option<&String>) -> Result<(Option<String>, bool)> {
     match data {
         Some(s) if s.len() > COMPRESSION_THRESHOLD => {
             let compressed = compress_data(s)?;
@@ -54,7 +71,10 @@ fn smart_compress(data: Option<&String>) -> Result<(Option<String>, bool)> {
 }
 
 /// 智能解压：根据标记决定是否解压
-fn smart_decompress(data: Option<String>, is_compressed: bool) -> Result<Option<String>> {
+pub(super) fn smart_decompress(
+    data: Option<String>,
+    is_compressed: bool,
+) -> Result<Option<String>> {
     match (data, is_compressed) {
         (Some(s), true) => {
             let decoded = general_purpose::STANDARD.decode(&s)?;
@@ -66,6 +86,121 @@ fn smart_decompress(data: Option<String>, is_compressed: bool) -> Result<Option<
     }
 }
 
+/// 漏洞列表查询的公共 SELECT 部分
+const VULNERABILITY_SELECT: &str = r#"
+    SELECT id, plugin_id, vuln_type, severity, confidence, title, description,
+           cwe, owasp, remediation, status, signature, first_seen_at, last_seen_at,
+           hit_count, session_id, created_at, updated_at
+    FROM traffic_vulnerabilities
+    WHERE 1=1
+"#;
+
+/// 分页查询默认每页条数
+const DEFAULT_VULNERABILITY_PAGE_SIZE: i64 = 50;
+/// 分页查询每页最大条数
+const MAX_VULNERABILITY_PAGE_SIZE: i64 = 500;
+
+/// 转义 LIKE 通配符，配合 `ESCAPE '\'` 使用户输入按字面匹配
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// 向漏洞查询追加过滤条件（三种数据库共用）
+///
+/// `$created_at` / `$time_open` / `$time_close` 用于包装时间比较：SQLite 中时间以文本存储，
+/// 格式不一，需要统一经 `datetime()` 归一化后再比较。
+/// `$like_escape` 是 LIKE 的 ESCAPE 子句：MySQL 字符串字面量中反斜杠本身需要转义。
+macro_rules! push_vulnerability_filters {
+    ($query_builder:expr, $filters:expr, $created_at:literal, $time_open:literal, $time_close:literal, $like_escape:literal) => {{
+        let filters = &$filters;
+        if let Some(ref vuln_type) = filters.vuln_type {
+            $query_builder
+                .push(" AND vuln_type = ")
+                .push_bind(vuln_type.clone());
+        }
+        if let Some(ref severity) = filters.severity {
+            $query_builder
+                .push(" AND severity = ")
+                .push_bind(severity.clone());
+        }
+        if let Some(ref status) = filters.status {
+            $query_builder
+                .push(" AND status = ")
+                .push_bind(status.clone());
+        }
+        if let Some(ref plugin_id) = filters.plugin_id {
+            $query_builder
+                .push(" AND plugin_id = ")
+                .push_bind(plugin_id.clone());
+        }
+        if let Some(ref exclude_plugin_id) = filters.exclude_plugin_id {
+            $query_builder
+                .push(" AND plugin_id != ")
+                .push_bind(exclude_plugin_id.clone());
+        }
+        if let Some(created_after) = filters.created_after {
+            $query_builder
+                .push(concat!(" AND ", $created_at, " >= ", $time_open))
+                .push_bind(created_after)
+                .push($time_close);
+        }
+        if let Some(created_before) = filters.created_before {
+            $query_builder
+                .push(concat!(" AND ", $created_at, " < ", $time_open))
+                .push_bind(created_before)
+                .push($time_close);
+        }
+        if let Some(search) = filters
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let pattern = format!("%{}%", escape_like(&search.to_lowercase()));
+            $query_builder
+                .push(" AND (LOWER(title) LIKE ")
+                .push_bind(pattern.clone())
+                .push($like_escape)
+                .push(" OR LOWER(description) LIKE ")
+                .push_bind(pattern.clone())
+                .push($like_escape)
+                .push(" OR LOWER(vuln_type) LIKE ")
+                .push_bind(pattern)
+                .push($like_escape)
+                .push(")");
+        }
+        if let Some(host) = filters
+            .host
+            .as_deref()
+            .map(str::trim)
+            .filter(|h| !h.is_empty())
+        {
+            // 按证据 URL 的主机部分匹配，避免 example.com 命中 example.com.evil.test
+            let host = escape_like(&host.to_lowercase());
+            $query_builder
+                .push(
+                    " AND EXISTS (SELECT 1 FROM traffic_evidence ev \
+                     WHERE ev.vuln_id = traffic_vulnerabilities.id AND (LOWER(ev.url) LIKE ",
+                )
+                .push_bind(format!("%://{}/%", host))
+                .push($like_escape)
+                .push(" OR LOWER(ev.url) LIKE ")
+                .push_bind(format!("%://{}:%", host))
+                .push($like_escape)
+                .push(" OR LOWER(ev.url) LIKE ")
+                .push_bind(format!("%://{}?%", host))
+                .push($like_escape)
+                .push(" OR LOWER(ev.url) LIKE ")
+                .push_bind(format!("%://{}", host))
+                .push($like_escape)
+                .push("))");
+        }
+    }};
+}
+
 impl DatabaseService {
     /// Migrate old table names
 
@@ -79,7 +214,7 @@ impl DatabaseService {
             .runtime_pool
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
-        let signature = finding.calculate_signature();
+        let signature = finding.stored_signature();
 
         debug!(
             "Inserting vulnerability: title='{}', description='{}'",
@@ -207,6 +342,8 @@ impl DatabaseService {
         };
 
         self.insert_traffic_evidence(&evidence).await?;
+        self.index_traffic_findings(std::slice::from_ref(finding))
+            .await;
 
         debug!(
             "Vulnerability inserted with evidence: {} - {}",
@@ -215,6 +352,103 @@ impl DatabaseService {
         Ok(())
     }
 
+    /// Insert several findings with their evidence in one transaction
+    ///
+    /// Any failing row rolls back the whole batch. Returns the number of findings inserted.
+    pub async fn insert_findings_batch(&self, findings: &[TrafficFinding]) -> Result<usize> {
+        if findings.is_empty() {
+            return Ok(0);
+        }
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+        let evidence_ts = chrono::Utc::now().timestamp_millis();
+
+        macro_rules! run_batch {
+            ($pool:expr, $insert_vuln:expr, $insert_dedupe:expr, $insert_evidence:expr) => {{
+                let mut tx = $pool.begin().await?;
+                for finding in findings {
+                    let signature = finding.stored_signature();
+                    sqlx::query($insert_vuln)
+                        .bind(&finding.id)
+                        .bind(&finding.plugin_id)
+                        .bind(&finding.vuln_type)
+                        .bind(finding.severity.to_string())
+                        .bind(format!("{:?}", finding.confidence))
+                        .bind(&finding.title)
+                        .bind(&finding.description)
+                        .bind(&finding.cwe)
+                        .bind(&finding.owasp)
+                        .bind(&finding.remediation)
+                        .bind(&signature)
+                        .bind(finding.created_at)
+                        .bind(finding.created_at)
+                        .execute(&mut *tx)
+                        .await?;
+                    sqlx::query($insert_dedupe)
+                        .bind(&signature)
+                        .bind(&finding.id)
+                        .execute(&mut *tx)
+                        .await?;
+                    sqlx::query($insert_evidence)
+                        .bind(format!("{}-evidence-{}", finding.id, evidence_ts))
+                        .bind(&finding.id)
+                        .bind(&finding.url)
+                        .bind(&finding.method)
+                        .bind(&finding.location)
+                        .bind(&finding.evidence)
+                        .bind(&finding.request_headers)
+                        .bind(&finding.request_body)
+                        .bind(finding.response_status)
+                        .bind(&finding.response_headers)
+                        .bind(&finding.response_body)
+                        .bind(finding.created_at)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                tx.commit().await?;
+            }};
+        }
+
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => run_batch!(
+                pool,
+                r#"
+                INSERT INTO traffic_vulnerabilities (
+                    id, plugin_id, vuln_type, severity, confidence, title, description,
+                    cwe, owasp, remediation, signature, first_seen_at, last_seen_at, session_id
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, NULL)
+                "#,
+                "INSERT INTO traffic_dedupe_index (signature, vuln_id) VALUES ($1, $2)",
+                r#"
+                INSERT INTO traffic_evidence (
+                    id, vuln_id, url, method, location, evidence_snippet,
+                    request_headers, request_body, response_status, response_headers,
+                    response_body, timestamp
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                "#
+            ),
+            DatabasePool::SQLite(pool) => run_batch!(
+                pool,
+                INSERT_VULNERABILITY,
+                INSERT_DEDUPE_INDEX,
+                INSERT_EVIDENCE
+            ),
+            DatabasePool::MySQL(pool) => run_batch!(
+                pool,
+                INSERT_VULNERABILITY,
+                INSERT_DEDUPE_INDEX,
+                INSERT_EVIDENCE
+            ),
+        }
+
+        self.index_traffic_findings(findings).await;
+
+        debug!("Inserted batch of {} vulnerabilities", findings.len());
+        Ok(findings.len())
+    }
+
     /// Update vulnerability hit count
     pub async fn update_traffic_vulnerability_hit(&self, signature: &str) -> Result<()> {
         let runtime = self
@@ -312,6 +546,8 @@ impl DatabaseService {
     }
 
     /// List vulnerabilities with pagination and filters
+    ///
+    /// Ordered by `created_at DESC, id DESC` so pages stay stable when rows share a timestamp.
     pub async fn list_traffic_vulnerabilities(
         &self,
         filters: TrafficVulnerabilityFilters,
@@ -321,35 +557,18 @@ impl DatabaseService {
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
 
-        match runtime {
-            DatabasePool::PostgreSQL(pool) => {
-                let mut query_builder = sqlx::QueryBuilder::<Postgres>::new(
-                    r#"
-                    SELECT id, plugin_id, vuln_type, severity, confidence, title, description,
-                           cwe, owasp, remediation, status, signature, first_seen_at, last_seen_at,
-                           hit_count, session_id, created_at, updated_at
-                    FROM traffic_vulnerabilities
-                    WHERE 1=1
-                    "#,
+        macro_rules! run_list {
+            ($db:ty, $pool:expr, $created_at:literal, $time_open:literal, $time_close:literal, $like_escape:literal) => {{
+                let mut query_builder = sqlx::QueryBuilder::<$db>::new(VULNERABILITY_SELECT);
+                push_vulnerability_filters!(
+                    query_builder,
+                    filters,
+                    $created_at,
+                    $time_open,
+                    $time_close,
+                    $like_escape
                 );
-                if let Some(ref vuln_type) = filters.vuln_type {
-                    query_builder.push(" AND vuln_type = ").push_bind(vuln_type);
-                }
-                if let Some(ref severity) = filters.severity {
-                    query_builder.push(" AND severity = ").push_bind(severity);
-                }
-                if let Some(ref status) = filters.status {
-                    query_builder.push(" AND status = ").push_bind(status);
-                }
-                if let Some(ref plugin_id) = filters.plugin_id {
-                    query_builder.push(" AND plugin_id = ").push_bind(plugin_id);
-                }
-                if let Some(ref exclude_plugin_id) = filters.exclude_plugin_id {
-                    query_builder
-                        .push(" AND plugin_id != ")
-                        .push_bind(exclude_plugin_id);
-                }
-                query_builder.push(" ORDER BY created_at DESC");
+                query_builder.push(" ORDER BY created_at DESC, id DESC");
                 if let Some(limit) = filters.limit {
                     query_builder.push(" LIMIT ").push_bind(limit);
                 }
@@ -358,89 +577,28 @@ impl DatabaseService {
                 }
                 let records = query_builder
                     .build_query_as::<TrafficVulnerabilityRecord>()
-                    .fetch_all(pool)
+                    .fetch_all($pool)
                     .await?;
                 Ok(records)
+            }};
+        }
+
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                run_list!(Postgres, pool, "created_at", "", "", " ESCAPE '\\'")
             }
             DatabasePool::SQLite(pool) => {
-                let mut query_builder = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
-                    r#"
-                    SELECT id, plugin_id, vuln_type, severity, confidence, title, description,
-                           cwe, owasp, remediation, status, signature, first_seen_at, last_seen_at,
-                           hit_count, session_id, created_at, updated_at
-                    FROM traffic_vulnerabilities
-                    WHERE 1=1
-                    "#,
-                );
-                if let Some(ref vuln_type) = filters.vuln_type {
-                    query_builder.push(" AND vuln_type = ").push_bind(vuln_type);
-                }
-                if let Some(ref severity) = filters.severity {
-                    query_builder.push(" AND severity = ").push_bind(severity);
-                }
-                if let Some(ref status) = filters.status {
-                    query_builder.push(" AND status = ").push_bind(status);
-                }
-                if let Some(ref plugin_id) = filters.plugin_id {
-                    query_builder.push(" AND plugin_id = ").push_bind(plugin_id);
-                }
-                if let Some(ref exclude_plugin_id) = filters.exclude_plugin_id {
-                    query_builder
-                        .push(" AND plugin_id != ")
-                        .push_bind(exclude_plugin_id);
-                }
-                query_builder.push(" ORDER BY created_at DESC");
-                if let Some(limit) = filters.limit {
-                    query_builder.push(" LIMIT ").push_bind(limit);
-                }
-                if let Some(offset) = filters.offset {
-                    query_builder.push(" OFFSET ").push_bind(offset);
-                }
-                let records = query_builder
-                    .build_query_as::<TrafficVulnerabilityRecord>()
-                    .fetch_all(pool)
-                    .await?;
-                Ok(records)
+                run_list!(
+                    sqlx::Sqlite,
+                    pool,
+                    "datetime(created_at)",
+                    "datetime(",
+                    ")",
+                    " ESCAPE '\\'"
+                )
             }
             DatabasePool::MySQL(pool) => {
-                let mut query_builder = sqlx::QueryBuilder::<MySql>::new(
-                    r#"
-                    SELECT id, plugin_id, vuln_type, severity, confidence, title, description,
-                           cwe, owasp, remediation, status, signature, first_seen_at, last_seen_at,
-                           hit_count, session_id, created_at, updated_at
-                    FROM traffic_vulnerabilities
-                    WHERE 1=1
-                    "#,
-                );
-                if let Some(ref vuln_type) = filters.vuln_type {
-                    query_builder.push(" AND vuln_type = ").push_bind(vuln_type);
-                }
-                if let Some(ref severity) = filters.severity {
-                    query_builder.push(" AND severity = ").push_bind(severity);
-                }
-                if let Some(ref status) = filters.status {
-                    query_builder.push(" AND status = ").push_bind(status);
-                }
-                if let Some(ref plugin_id) = filters.plugin_id {
-                    query_builder.push(" AND plugin_id = ").push_bind(plugin_id);
-                }
-                if let Some(ref exclude_plugin_id) = filters.exclude_plugin_id {
-                    query_builder
-                        .push(" AND plugin_id != ")
-                        .push_bind(exclude_plugin_id);
-                }
-                query_builder.push(" ORDER BY created_at DESC");
-                if let Some(limit) = filters.limit {
-                    query_builder.push(" LIMIT ").push_bind(limit);
-                }
-                if let Some(offset) = filters.offset {
-                    query_builder.push(" OFFSET ").push_bind(offset);
-                }
-                let records = query_builder
-                    .build_query_as::<TrafficVulnerabilityRecord>()
-                    .fetch_all(pool)
-                    .await?;
-                Ok(records)
+                run_list!(MySql, pool, "created_at", "", "", " ESCAPE '\\\\'")
             }
         }
     }
@@ -472,6 +630,35 @@ impl DatabaseService {
         Ok(results)
     }
 
+    /// One page of vulnerabilities (with evidence) plus the total matching the filters
+    pub async fn list_traffic_vulnerabilities_page(
+        &self,
+        filters: TrafficVulnerabilityFilters,
+    ) -> Result<TrafficVulnerabilityPage> {
+        let limit = filters
+            .limit
+            .unwrap_or(DEFAULT_VULNERABILITY_PAGE_SIZE)
+            .clamp(1, MAX_VULNERABILITY_PAGE_SIZE);
+        let offset = filters.offset.unwrap_or(0).max(0);
+
+        let total = self.count_traffic_vulnerabilities(filters.clone()).await?;
+        let items = self
+            .list_traffic_vulnerabilities_with_evidence(TrafficVulnerabilityFilters {
+                limit: Some(limit),
+                offset: Some(offset),
+                ..filters
+            })
+            .await?;
+
+        Ok(TrafficVulnerabilityPage {
+            has_more: offset + (items.len() as i64) < total,
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
     /// Count vulnerabilities
     pub async fn count_traffic_vulnerabilities(
         &self,
@@ -482,90 +669,40 @@ impl DatabaseService {
             .as_ref()
             .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
 
-        match runtime {
-            DatabasePool::PostgreSQL(pool) => {
-                let mut query_builder = sqlx::QueryBuilder::<Postgres>::new(
-                    r#"
-                    SELECT COUNT(*)
-                    FROM traffic_vulnerabilities
-                    WHERE 1=1
-                    "#,
+        macro_rules! run_count {
+            ($db:ty, $pool:expr, $created_at:literal, $time_open:literal, $time_close:literal, $like_escape:literal) => {{
+                let mut query_builder = sqlx::QueryBuilder::<$db>::new(
+                    "SELECT COUNT(*) FROM traffic_vulnerabilities WHERE 1=1",
                 );
-                if let Some(ref vuln_type) = filters.vuln_type {
-                    query_builder.push(" AND vuln_type = ").push_bind(vuln_type);
-                }
-                if let Some(ref severity) = filters.severity {
-                    query_builder.push(" AND severity = ").push_bind(severity);
-                }
-                if let Some(ref status) = filters.status {
-                    query_builder.push(" AND status = ").push_bind(status);
-                }
-                if let Some(ref plugin_id) = filters.plugin_id {
-                    query_builder.push(" AND plugin_id = ").push_bind(plugin_id);
-                }
-                if let Some(ref exclude_plugin_id) = filters.exclude_plugin_id {
-                    query_builder
-                        .push(" AND plugin_id != ")
-                        .push_bind(exclude_plugin_id);
-                }
-                let row: (i64,) = query_builder.build_query_as().fetch_one(pool).await?;
+                push_vulnerability_filters!(
+                    query_builder,
+                    filters,
+                    $created_at,
+                    $time_open,
+                    $time_close,
+                    $like_escape
+                );
+                let row: (i64,) = query_builder.build_query_as().fetch_one($pool).await?;
                 Ok(row.0)
+            }};
+        }
+
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                run_count!(Postgres, pool, "created_at", "", "", " ESCAPE '\\'")
             }
             DatabasePool::SQLite(pool) => {
-                let mut query_builder = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
-                    r#"
-                    SELECT COUNT(*)
-                    FROM traffic_vulnerabilities
-                    WHERE 1=1
-                    "#,
-                );
-                if let Some(ref vuln_type) = filters.vuln_type {
-                    query_builder.push(" AND vuln_type = ").push_bind(vuln_type);
-                }
-                if let Some(ref severity) = filters.severity {
-                    query_builder.push(" AND severity = ").push_bind(severity);
-                }
-                if let Some(ref status) = filters.status {
-                    query_builder.push(" AND status = ").push_bind(status);
-                }
-                if let Some(ref plugin_id) = filters.plugin_id {
-                    query_builder.push(" AND plugin_id = ").push_bind(plugin_id);
-                }
-                if let Some(ref exclude_plugin_id) = filters.exclude_plugin_id {
-                    query_builder
-                        .push(" AND plugin_id != ")
-                        .push_bind(exclude_plugin_id);
-                }
-                let row: (i64,) = query_builder.build_query_as().fetch_one(pool).await?;
-                Ok(row.0)
+                run_count!(
+                    sqlx::Sqlite,
+                    pool,
+                    "datetime(created_at)",
+                    "datetime(",
+                    ")",
+                    " ESCAPE '\\'"
+                )
             }
             DatabasePool::MySQL(pool) => {
-                let mut query_builder = sqlx::QueryBuilder::<MySql>::new(
-                    r#"
-                    SELECT COUNT(*)
-                    FROM traffic_vulnerabilities
-                    WHERE 1=1
-                    "#,
-                );
-                if let Some(ref vuln_type) = filters.vuln_type {
-                    query_builder.push(" AND vuln_type = ").push_bind(vuln_type);
-                }
-                if let Some(ref severity) = filters.severity {
-                    query_builder.push(" AND severity = ").push_bind(severity);
-                }
-                if let Some(ref status) = filters.status {
-                    query_builder.push(" AND status = ").push_bind(status);
-                }
-                if let Some(ref plugin_id) = filters.plugin_id {
-                    query_builder.push(" AND plugin_id = ").push_bind(plugin_id);
-                }
-                if let Some(ref exclude_plugin_id) = filters.exclude_plugin_id {
-                    query_builder
-                        .push(" AND plugin_id != ")
-                        .push_bind(exclude_plugin_id);
-                }
-                let row: (i64,) = query_builder.build_query_as().fetch_one(pool).await?;
-                Ok(row.0)
+                run_count!(MySql, pool, "created_at", "", "", " ESCAPE '\\\\'")
             }
         }
     }
@@ -1614,6 +1751,7 @@ impl DatabaseService {
                 .bind(response_compressed)
                 .fetch_one(pool)
                 .await?;
+                self.index_proxy_request(row.0, request).await;
                 Ok(row.0)
             }
             DatabasePool::MySQL(pool) => {
@@ -2195,10 +2333,32 @@ pub struct TrafficVulnerabilityFilters {
     pub status: Option<String>,
     pub plugin_id: Option<String>,
     pub exclude_plugin_id: Option<String>,
+    /// Host of any evidence URL (exact host match)
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Case-insensitive text match on title, description and vulnerability type
+    #[serde(default)]
+    pub search: Option<String>,
+    /// Only findings created at or after this time
+    #[serde(default)]
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only findings created before this time
+    #[serde(default)]
+    pub created_before: Option<DateTime<Utc>>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
 
+/// A page of vulnerabilities with the total matching the filters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrafficVulnerabilityPage {
+    pub items: Vec<TrafficVulnerabilityWithEvidence>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+}
+
 /// Vulnerability record
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct TrafficVulnerabilityRecord {
@@ -2309,9 +2469,19 @@ pub struct TrafficFinding {
     pub response_headers: Option<String>,
     pub response_body: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Dedupe key computed by the caller (e.g. the scanner's dedup policy);
+    /// stored as the vulnerability signature and `traffic_dedupe_index` key
+    pub signature: Option<String>,
 }
 
 impl TrafficFinding {
+    /// Signature to persist: the caller's dedupe key, else [`Self::calculate_signature`]
+    pub fn stored_signature(&self) -> String {
+        self.signature
+            .clone()
+            .unwrap_or_else(|| self.calculate_signature())
+    }
+
     pub fn calculate_signature(&self) -> String {
         use sha2::{Digest, Sha256};
         let mut hasher = Sha256::new();
@@ -2336,3 +2506,259 @@ pub struct TrafficPluginMetadata {
     pub default_severity: String,
     pub tags: Vec<String>,
 }
+
+#[cfg(all(test, feature = "db-sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database_service::test_service;
+
+    async fn service() -> (DatabaseService, sqlx::SqlitePool) {
+        let service = test_service(&[
+            "CREATE TABLE traffic_vulnerabilities (id TEXT PRIMARY KEY, plugin_id TEXT, vuln_type TEXT, severity TEXT, confidence TEXT, title TEXT, description TEXT, cwe TEXT, owasp TEXT, remediation TEXT, status TEXT NOT NULL DEFAULT 'open', signature TEXT, first_seen_at TIMESTAMP, last_seen_at TIMESTAMP, hit_count INTEGER NOT NULL DEFAULT 1, session_id TEXT, created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+            "CREATE TABLE traffic_dedupe_index (signature TEXT PRIMARY KEY, vuln_id TEXT)",
+            "CREATE TABLE traffic_evidence (id TEXT PRIMARY KEY, vuln_id TEXT, url TEXT, method TEXT, location TEXT, evidence_snippet TEXT, request_headers TEXT, request_body TEXT, response_status INTEGER, response_headers TEXT, response_body TEXT, timestamp TIMESTAMP)",
+        ])
+        .await;
+        let Some(DatabasePool::SQLite(pool)) = service.runtime_pool.clone() else {
+            unreachable!()
+        };
+        (service, pool)
+    }
+
+    fn finding(id: &str, url: &str) -> TrafficFinding {
+        TrafficFinding {
+            id: id.to_string(),
+            plugin_id: "xss".to_string(),
+            vuln_type: "xss".to_string(),
+            severity: "high".to_string(),
+            confidence: "Firm".to_string(),
+            title: "Reflected XSS".to_string(),
+            description: "payload reflected".to_string(),
+            cwe: None,
+            owasp: None,
+            remediation: None,
+            url: url.to_string(),
+            method: "GET".to_string(),
+            location: "param:q".to_string(),
+            evidence: "<script>".to_string(),
+            request_headers: None,
+            request_body: None,
+            response_status: Some(200),
+            response_headers: None,
+            response_body: None,
+            created_at: Utc::now(),
+            signature: None,
+        }
+    }
+
+    async fn count(pool: &sqlx::SqlitePool, table: &str) -> i64 {
+        sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_batch_inserts_findings_and_evidence() {
+        let (service, pool) = service().await;
+        let batch = [
+            finding("v1", "https://a.test/1"),
+            finding("v2", "https://a.test/2"),
+        ];
+        assert_eq!(service.insert_findings_batch(&batch).await.unwrap(), 2);
+        assert_eq!(count(&pool, "traffic_vulnerabilities").await, 2);
+        assert_eq!(count(&pool, "traffic_dedupe_index").await, 2);
+        assert_eq!(count(&pool, "traffic_evidence").await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_mid_batch_error_rolls_back_whole_batch() {
+        let (service, pool) = service().await;
+        // 第三条与第一条签名相同，写入去重索引时违反主键约束
+        let batch = [
+            finding("v1", "https://a.test/1"),
+            finding("v2", "https://a.test/2"),
+            finding("v3", "https://a.test/1"),
+        ];
+        assert!(service.insert_findings_batch(&batch).await.is_err());
+        assert_eq!(count(&pool, "traffic_vulnerabilities").await, 0);
+        assert_eq!(count(&pool, "traffic_dedupe_index").await, 0);
+        assert_eq!(count(&pool, "traffic_evidence").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_caller_signature_is_the_dedupe_key() {
+        let (service, pool) = service().await;
+        // 同一默认签名、不同去重键的两条 Finding 都能写入
+        let mut a = finding("v1", "https://a.test/1");
+        a.signature = Some("policy-key-a".to_string());
+        let mut b = finding("v2", "https://a.test/1");
+        b.signature = Some("policy-key-b".to_string());
+        assert_eq!(service.insert_findings_batch(&[a, b]).await.unwrap(), 2);
+        assert_eq!(count(&pool, "traffic_dedupe_index").await, 2);
+
+        assert!(service
+            .check_traffic_signature_exists("policy-key-a")
+            .await
+            .unwrap());
+        service
+            .update_traffic_vulnerability_hit("policy-key-a")
+            .await
+            .unwrap();
+        let hits: i64 =
+            sqlx::query_scalar("SELECT hit_count FROM traffic_vulnerabilities WHERE id = 'v1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(hits, 2);
+    }
+
+    async fn set_created_at(pool: &sqlx::SqlitePool, id: &str, created_at: DateTime<Utc>) {
+        sqlx::query(
+            "UPDATE traffic_vulnerabilities SET created_at = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(created_at)
+        .bind(created_at)
+        .bind(id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_combined_filters() {
+        let (service, pool) = service().await;
+        let mut sqli = finding("v2", "https://shop.test/login?id=1");
+        sqli.plugin_id = "sqli".to_string();
+        sqli.vuln_type = "sqli".to_string();
+        sqli.title = "SQL Injection".to_string();
+        let mut low = finding("v4", "https://shop.test/about");
+        low.severity = "low".to_string();
+        let batch = [
+            finding("v1", "https://shop.test/search?q=1"),
+            sqli,
+            finding("v3", "https://shop.test.evil.test/search"),
+            low,
+            finding("v5", "http://shop.test:8080/old"),
+        ];
+        service.insert_findings_batch(&batch).await.unwrap();
+
+        let now = Utc::now();
+        for (id, days_ago) in [("v1", 1), ("v2", 1), ("v3", 1), ("v4", 1), ("v5", 30)] {
+            set_created_at(&pool, id, now - chrono::Duration::days(days_ago)).await;
+        }
+
+        let filters = TrafficVulnerabilityFilters {
+            severity: Some("high".to_string()),
+            host: Some("SHOP.test".to_string()),
+            search: Some("xss".to_string()),
+            ..Default::default()
+        };
+        let page = service
+            .list_traffic_vulnerabilities_page(filters.clone())
+            .await
+            .unwrap();
+        let ids: Vec<_> = page
+            .items
+            .iter()
+            .map(|v| v.vulnerability.id.as_str())
+            .collect();
+        assert_eq!(ids, ["v1", "v5"]);
+        assert_eq!(page.total, 2);
+        assert_eq!(
+            page.items[0].url.as_deref(),
+            Some("https://shop.test/search?q=1")
+        );
+
+        let recent = TrafficVulnerabilityFilters {
+            created_after: Some(now - chrono::Duration::days(7)),
+            ..filters
+        };
+        let page = service
+            .list_traffic_vulnerabilities_page(recent)
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].vulnerability.id, "v1");
+        assert!(!page.has_more);
+
+        let older = TrafficVulnerabilityFilters {
+            created_before: Some(now - chrono::Duration::days(7)),
+            ..Default::default()
+        };
+        assert_eq!(
+            service.count_traffic_vulnerabilities(older).await.unwrap(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_and_host_wildcards_match_literally() {
+        let (service, _pool) = service().await;
+        let mut banner = finding("v1", "https://shop.test/promo");
+        banner.title = "Reflected XSS in 100%_off banner".to_string();
+        let batch = [banner, finding("v2", "https://shopxtest/promo")];
+        service.insert_findings_batch(&batch).await.unwrap();
+
+        let count = |search: Option<&str>, host: Option<&str>| {
+            service.count_traffic_vulnerabilities(TrafficVulnerabilityFilters {
+                search: search.map(str::to_string),
+                host: host.map(str::to_string),
+                ..Default::default()
+            })
+        };
+        assert_eq!(count(Some("%"), None).await.unwrap(), 1);
+        assert_eq!(count(Some("100%_off"), None).await.unwrap(), 1);
+        assert_eq!(count(Some("_"), None).await.unwrap(), 1);
+        assert_eq!(count(None, Some("shop_test")).await.unwrap(), 0);
+        assert_eq!(count(None, Some("shop.test")).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_page_boundaries_are_stable() {
+        let (service, pool) = service().await;
+        let batch: Vec<_> = (0..7)
+            .map(|i| finding(&format!("v{}", i), &format!("https://a.test/{}", i)))
+            .collect();
+        service.insert_findings_batch(&batch).await.unwrap();
+        // 同一时间戳的记录按 id 排序，翻页不会重复或遗漏
+        let created_at = Utc::now();
+        for f in &batch {
+            set_created_at(&pool, &f.id, created_at).await;
+        }
+
+        let mut seen = Vec::new();
+        let mut offset = 0;
+        loop {
+            let page = service
+                .list_traffic_vulnerabilities_page(TrafficVulnerabilityFilters {
+                    limit: Some(3),
+                    offset: Some(offset),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            assert_eq!(page.total, 7);
+            assert_eq!(page.limit, 3);
+            seen.extend(page.items.iter().map(|v| v.vulnerability.id.clone()));
+            offset += page.items.len() as i64;
+            if !page.has_more {
+                assert_eq!(page.items.len(), 1);
+                break;
+            }
+            assert_eq!(page.items.len(), 3);
+        }
+        assert_eq!(seen, ["v6", "v5", "v4", "v3", "v2", "v1", "v0"]);
+
+        let past_end = service
+            .list_traffic_vulnerabilities_page(TrafficVulnerabilityFilters {
+                offset: Some(10),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(past_end.items.is_empty());
+        assert!(!past_end.has_more);
+        assert_eq!(past_end.total, 7);
+    }
+}
@@ -0,0 +1,411 @@
+//! Full-text search over proxy history and traffic findings
+//!
+//! SQLite only: FTS5 tables hold the decompressed text of each proxy request
+//! (rowid = `proxy_requests.id`) and of each finding. Rows are indexed from Rust
+//! on insert, because bodies are stored compressed; delete triggers keep the
+//! index in step with every delete path. The tables are created, and existing
+//! rows backfilled, by the init migration (`apply_traffic_search_migration`).
+
+use anyhow::Result;
+use tracing::warn;
+
+use super::service::DatabaseService;
+use super::traffic::{
+    smart_decompress, ProxyRequestRecord, TrafficFinding, TrafficVulnerabilityRecord,
+};
+use crate::database_service::connection_manager::DatabasePool;
+
+/// Translate a user query into an FTS5 MATCH expression
+///
+/// Supports bare terms (all must match), `"quoted phrases"`, `-excluded` terms
+/// and `prefix*`. Every token is quoted so FTS5 operators in user input are
+/// treated literally. At least one non-excluded term is required.
+pub fn build_fts_query(query: &str) -> Result<String> {
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let negated = c == '-';
+        if negated {
+            chars.next();
+        }
+
+        let (text, prefix) = if chars.peek() == Some(&'"') {
+            chars.next();
+            let phrase: String = chars.by_ref().take_while(|c| *c != '"').collect();
+            (phrase, false)
+        } else {
+            let word: String =
+                std::iter::from_fn(|| chars.next_if(|c| !c.is_whitespace())).collect();
+            match word.strip_suffix('*') {
+                Some(stem) => (stem.to_string(), true),
+                None => (word, false),
+            }
+        };
+
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let mut token = format!("\"{}\"", text.replace('"', "\"\""));
+        if prefix {
+            token.push('*');
+        }
+        if negated {
+            exclude.push(token);
+        } else {
+            include.push(token);
+        }
+    }
+
+    if include.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Search query needs at least one term to match"
+        ));
+    }
+    let mut expression = include.join(" AND ");
+    for token in exclude {
+        expression.push_str(" NOT ");
+        expression.push_str(&token);
+    }
+    Ok(expression)
+}
+
+impl DatabaseService {
+    /// Add a proxy request to the search index (no-op outside SQLite)
+    pub(crate) async fn index_proxy_request(&self, id: i64, record: &ProxyRequestRecord) {
+        let Some(DatabasePool::SQLite(pool)) = self.runtime_pool.as_ref() else {
+            return;
+        };
+        if let Err(e) = insert_proxy_request_fts(pool, id, record).await {
+            warn!("Failed to index proxy request {}: {}", id, e);
+        }
+    }
+
+    /// Add findings to the search index (no-op outside SQLite)
+    pub(crate) async fn index_traffic_findings(&self, findings: &[TrafficFinding]) {
+        let Some(DatabasePool::SQLite(pool)) = self.runtime_pool.as_ref() else {
+            return;
+        };
+        let result = async {
+            for finding in findings {
+                sqlx::query("DELETE FROM traffic_vulnerabilities_fts WHERE vuln_id = ?")
+                    .bind(&finding.id)
+                    .execute(pool)
+                    .await?;
+                sqlx::query(
+                    "INSERT INTO traffic_vulnerabilities_fts (vuln_id, title, description, url, evidence) VALUES (?, ?, ?, ?, ?)",
+                )
+                .bind(&finding.id)
+                .bind(&finding.title)
+                .bind(&finding.description)
+                .bind(&finding.url)
+                .bind(&finding.evidence)
+                .execute(pool)
+                .await?;
+            }
+            anyhow::Ok(())
+        }
+        .await;
+        if let Err(e) = result {
+            warn!("Failed to index {} findings: {}", findings.len(), e);
+        }
+    }
+
+    fn search_pool(&self) -> Result<&sqlx::SqlitePool> {
+        match self.runtime_pool.as_ref() {
+            Some(DatabasePool::SQLite(pool)) => Ok(pool),
+            Some(_) => Err(anyhow::anyhow!(
+                "Full-text search is only available with the SQLite database"
+            )),
+            None => Err(anyhow::anyhow!("数据库未初始化")),
+        }
+    }
+
+    /// Search proxy history by URL, headers and bodies, best matches first
+    pub async fn search_proxy_requests(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ProxyRequestRecord>> {
+        let pool = self.search_pool()?;
+        let expression = build_fts_query(query)?;
+
+        let mut records: Vec<ProxyRequestRecord> = sqlx::query_as(
+            r#"
+            SELECT p.id, p.url, p.host, p.protocol, p.method, p.status_code,
+                   p.request_headers, p.request_body, p.response_headers, p.response_body,
+                   p.response_size, p.response_time, p.timestamp,
+                   p.request_body_compressed, p.response_body_compressed
+            FROM proxy_requests_fts
+            JOIN proxy_requests p ON p.id = proxy_requests_fts.rowid
+            WHERE proxy_requests_fts MATCH ?
+            ORDER BY bm25(proxy_requests_fts)
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(&expression)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        for record in &mut records {
+            record.request_body =
+                smart_decompress(record.request_body.take(), record.request_body_compressed)?;
+            record.response_body =
+                smart_decompress(record.response_body.take(), record.response_body_compressed)?;
+        }
+        Ok(records)
+    }
+
+    /// Search findings by title, description, URL and evidence, best matches first
+    pub async fn search_traffic_vulnerabilities(
+        &self,
+        query: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<TrafficVulnerabilityRecord>> {
+        let pool = self.search_pool()?;
+        let expression = build_fts_query(query)?;
+
+        let records = sqlx::query_as(
+            r#"
+            SELECT v.id, v.plugin_id, v.vuln_type, v.severity, v.confidence, v.title, v.description,
+                   v.cwe, v.owasp, v.remediation, v.status, v.signature, v.first_seen_at, v.last_seen_at,
+                   v.hit_count, v.session_id, v.created_at, v.updated_at
+            FROM traffic_vulnerabilities_fts
+            JOIN traffic_vulnerabilities v ON v.id = traffic_vulnerabilities_fts.vuln_id
+            WHERE traffic_vulnerabilities_fts MATCH ?
+            ORDER BY bm25(traffic_vulnerabilities_fts)
+            LIMIT ? OFFSET ?
+            "#,
+        )
+        .bind(&expression)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+        Ok(records)
+    }
+}
+
+pub(crate) async fn insert_proxy_request_fts(
+    pool: &sqlx::SqlitePool,
+    id: i64,
+    record: &ProxyRequestRecord,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT OR REPLACE INTO proxy_requests_fts (rowid, url, request_headers, request_body, response_headers, response_body) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(id)
+    .bind(&record.url)
+    .bind(&record.request_headers)
+    .bind(&record.request_body)
+    .bind(&record.response_headers)
+    .bind(&record.response_body)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+#[cfg(all(test, feature = "db-sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database_service::test_service;
+    use chrono::Utc;
+
+    /// Tables without the search index, as before the migration ran
+    async fn unmigrated_service() -> DatabaseService {
+        test_service(&[
+            "CREATE TABLE proxy_requests (id INTEGER PRIMARY KEY AUTOINCREMENT, url TEXT NOT NULL, host TEXT NOT NULL, protocol TEXT NOT NULL, method TEXT NOT NULL, status_code INTEGER NOT NULL, request_headers TEXT, request_body TEXT, response_headers TEXT, response_body TEXT, response_size INTEGER NOT NULL DEFAULT 0, response_time INTEGER NOT NULL DEFAULT 0, timestamp TIMESTAMP NOT NULL, request_body_compressed BOOLEAN NOT NULL DEFAULT 0, response_body_compressed BOOLEAN NOT NULL DEFAULT 0)",
+            "CREATE TABLE traffic_vulnerabilities (id TEXT PRIMARY KEY, plugin_id TEXT NOT NULL, vuln_type TEXT NOT NULL, severity TEXT NOT NULL, confidence TEXT NOT NULL, title TEXT NOT NULL, description TEXT NOT NULL, cwe TEXT, owasp TEXT, remediation TEXT, status TEXT NOT NULL DEFAULT 'open', signature TEXT NOT NULL, first_seen_at TIMESTAMP NOT NULL, last_seen_at TIMESTAMP NOT NULL, hit_count INTEGER NOT NULL DEFAULT 1, session_id TEXT, created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP, updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+            "CREATE TABLE traffic_dedupe_index (signature TEXT PRIMARY KEY, vuln_id TEXT)",
+            "CREATE TABLE traffic_evidence (id TEXT PRIMARY KEY, vuln_id TEXT, url TEXT, method TEXT, location TEXT, evidence_snippet TEXT, request_headers TEXT, request_body TEXT, response_status INTEGER, response_headers TEXT, response_body TEXT, timestamp TIMESTAMP)",
+        ])
+        .await
+    }
+
+    async fn service() -> DatabaseService {
+        let service = unmigrated_service().await;
+        service.apply_traffic_search_migration().await.unwrap();
+        service
+    }
+
+    fn request(url: &str, response_body: &str) -> ProxyRequestRecord {
+        ProxyRequestRecord {
+            id: None,
+            url: url.to_string(),
+            host: "shop.test".to_string(),
+            protocol: "https".to_string(),
+            method: "GET".to_string(),
+            status_code: 200,
+            request_headers: Some(r#"{"Accept":"text/html"}"#.to_string()),
+            request_body: None,
+            response_headers: Some(r#"{"Content-Type":"text/html"}"#.to_string()),
+            response_body: Some(response_body.to_string()),
+            response_size: response_body.len() as i64,
+            response_time: 12,
+            timestamp: Utc::now(),
+            request_body_compressed: false,
+            response_body_compressed: false,
+        }
+    }
+
+    #[test]
+    fn test_query_operators() {
+        assert_eq!(
+            build_fts_query("admin token").unwrap(),
+            r#""admin" AND "token""#
+        );
+        assert_eq!(
+            build_fts_query(r#""set-cookie: sid" -logout"#).unwrap(),
+            r#""set-cookie: sid" NOT "logout""#
+        );
+        assert_eq!(build_fts_query("pass*").unwrap(), r#""pass"*"#);
+        assert_eq!(build_fts_query(r#"a"b"#).unwrap(), r#""a""b""#);
+        assert!(build_fts_query("-only -excluded").is_err());
+        assert!(build_fts_query("   ").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_keyword_in_response_body_is_searchable() {
+        let service = service().await;
+        // 超过压缩阈值的正文以压缩形式存储，索引中保存的是原文
+        let large = format!(
+            "{} internal-debug-token=7f3a {}",
+            "x ".repeat(800),
+            "y ".repeat(800)
+        );
+        service
+            .insert_proxy_request(&request("https://shop.test/account", &large))
+            .await
+            .unwrap();
+        service
+            .insert_proxy_request(&request(
+                "https://shop.test/logout",
+                "bye, debug session closed",
+            ))
+            .await
+            .unwrap();
+
+        let hits = service.search_proxy_requests("7f3a", 10, 0).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].url, "https://shop.test/account");
+        assert_eq!(hits[0].response_body.as_deref(), Some(large.as_str()));
+
+        let hits = service.search_proxy_requests("debug", 10, 0).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        let hits = service
+            .search_proxy_requests("debug -logout", 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        let hits = service
+            .search_proxy_requests(r#""session closed""#, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(hits[0].url, "https://shop.test/logout");
+    }
+
+    #[tokio::test]
+    async fn test_migration_backfills_existing_rows_once() {
+        let service = unmigrated_service().await;
+        service
+            .insert_proxy_request(&request("https://shop.test/old", "legacy-marker"))
+            .await
+            .unwrap();
+
+        service.apply_traffic_search_migration().await.unwrap();
+        // A second run finds the index and does not index the rows again
+        service.apply_traffic_search_migration().await.unwrap();
+
+        let hits = service
+            .search_proxy_requests("legacy-marker", 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].url, "https://shop.test/old");
+    }
+
+    #[tokio::test]
+    async fn test_migration_skips_schema_without_proxy_history() {
+        let service =
+            test_service(&["CREATE TABLE traffic_vulnerabilities (id TEXT PRIMARY KEY)"]).await;
+        service.apply_traffic_search_migration().await.unwrap();
+
+        let fts: Option<String> =
+            sqlx::query_scalar("SELECT name FROM sqlite_master WHERE name = 'proxy_requests_fts'")
+                .fetch_optional(service.search_pool().unwrap())
+                .await
+                .unwrap();
+        assert!(fts.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deleted_records_drop_out_of_index() {
+        let service = service().await;
+        service
+            .insert_proxy_request(&request("https://shop.test/a", "needle in body"))
+            .await
+            .unwrap();
+        assert_eq!(
+            service
+                .search_proxy_requests("needle", 10, 0)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        service.clear_proxy_requests().await.unwrap();
+        assert!(service
+            .search_proxy_requests("needle", 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let finding = TrafficFinding {
+            id: "v1".to_string(),
+            plugin_id: "sqli".to_string(),
+            vuln_type: "sqli".to_string(),
+            severity: "high".to_string(),
+            confidence: "Firm".to_string(),
+            title: "SQL injection".to_string(),
+            description: "Boolean-based blind injection in the sort parameter".to_string(),
+            cwe: None,
+            owasp: None,
+            remediation: None,
+            url: "https://shop.test/products?sort=1".to_string(),
+            method: "GET".to_string(),
+            location: "param:sort".to_string(),
+            evidence: "sort=1 AND 1=1".to_string(),
+            request_headers: None,
+            request_body: None,
+            response_status: Some(200),
+            response_headers: None,
+            response_body: None,
+            created_at: Utc::now(),
+            signature: None,
+        };
+        service.insert_findings_batch(&[finding]).await.unwrap();
+        let hits = service
+            .search_traffic_vulnerabilities("blind sort", 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, "v1");
+
+        service.delete_traffic_vulnerability("v1").await.unwrap();
+        assert!(service
+            .search_traffic_vulnerabilities("blind", 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,296 @@
+//! Traffic finding status audit trail
+//!
+//! Every status transition is appended to `traffic_vulnerability_status_log`
+//! (who / when / from / to / note). Batch transitions run in one transaction:
+//! either every finding changes and is logged, or none does.
+
+use crate::database_service::connection_manager::DatabasePool;
+use crate::database_service::service::DatabaseService;
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+pub(super) const CREATE_STATUS_LOG_TABLE: &str = r#"CREATE TABLE IF NOT EXISTS traffic_vulnerability_status_log (
+    id VARCHAR(64) PRIMARY KEY,
+    vuln_id VARCHAR(64) NOT NULL,
+    seq BIGINT NOT NULL,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    note TEXT,
+    changed_by TEXT NOT NULL,
+    changed_at BIGINT NOT NULL
+)"#;
+
+const SELECT_CURRENT_STATUS_PG: &str = "SELECT status FROM traffic_vulnerabilities WHERE id = $1";
+const SELECT_CURRENT_STATUS: &str = "SELECT status FROM traffic_vulnerabilities WHERE id = ?";
+const NEXT_SEQ_PG: &str =
+    "SELECT COALESCE(MAX(seq), 0) + 1 FROM traffic_vulnerability_status_log WHERE vuln_id = $1";
+const NEXT_SEQ: &str =
+    "SELECT COALESCE(MAX(seq), 0) + 1 FROM traffic_vulnerability_status_log WHERE vuln_id = ?";
+const UPDATE_STATUS_PG: &str =
+    "UPDATE traffic_vulnerabilities SET status = $1, updated_at = $2 WHERE id = $3";
+const UPDATE_STATUS: &str =
+    "UPDATE traffic_vulnerabilities SET status = ?, updated_at = ? WHERE id = ?";
+const INSERT_LOG_PG: &str = "INSERT INTO traffic_vulnerability_status_log (id, vuln_id, seq, from_status, to_status, note, changed_by, changed_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)";
+const INSERT_LOG: &str = "INSERT INTO traffic_vulnerability_status_log (id, vuln_id, seq, from_status, to_status, note, changed_by, changed_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)";
+
+/// One status transition of a finding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TrafficStatusChange {
+    pub id: String,
+    pub vuln_id: String,
+    /// Position in the finding's history, starting at 1
+    pub seq: i64,
+    pub from_status: String,
+    pub to_status: String,
+    pub note: Option<String>,
+    pub changed_by: String,
+    /// Milliseconds since the Unix epoch
+    pub changed_at: i64,
+}
+
+impl DatabaseService {
+    /// Change the status of several findings at once and log each transition
+    ///
+    /// Runs in a single transaction; an unknown id rolls back the whole batch.
+    pub async fn update_traffic_vulnerabilities_status_batch(
+        &self,
+        vuln_ids: &[String],
+        status: &str,
+        note: Option<&str>,
+        changed_by: &str,
+    ) -> Result<Vec<TrafficStatusChange>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let changed_at = Utc::now().timestamp_millis();
+        let mut changes = Vec::with_capacity(vuln_ids.len());
+
+        macro_rules! run_batch {
+            ($pool:expr, $select:expr, $next_seq:expr, $update:expr, $insert:expr) => {{
+                let mut tx = $pool.begin().await?;
+                for vuln_id in vuln_ids {
+                    let from_status: Option<String> = sqlx::query_scalar($select)
+                        .bind(vuln_id)
+                        .fetch_optional(&mut *tx)
+                        .await?;
+                    let Some(from_status) = from_status else {
+                        return Err(anyhow::anyhow!("Vulnerability not found: {}", vuln_id));
+                    };
+                    let seq: i64 = sqlx::query_scalar($next_seq)
+                        .bind(vuln_id)
+                        .fetch_one(&mut *tx)
+                        .await?;
+
+                    sqlx::query($update)
+                        .bind(status)
+                        .bind(Utc::now())
+                        .bind(vuln_id)
+                        .execute(&mut *tx)
+                        .await?;
+
+                    let change = TrafficStatusChange {
+                        id: uuid::Uuid::new_v4().to_string(),
+                        vuln_id: vuln_id.clone(),
+                        seq,
+                        from_status,
+                        to_status: status.to_string(),
+                        note: note.map(str::to_string),
+                        changed_by: changed_by.to_string(),
+                        changed_at,
+                    };
+                    sqlx::query($insert)
+                        .bind(&change.id)
+                        .bind(&change.vuln_id)
+                        .bind(change.seq)
+                        .bind(&change.from_status)
+                        .bind(&change.to_status)
+                        .bind(&change.note)
+                        .bind(&change.changed_by)
+                        .bind(change.changed_at)
+                        .execute(&mut *tx)
+                        .await?;
+                    changes.push(change);
+                }
+                tx.commit().await?;
+            }};
+        }
+
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => run_batch!(
+                pool,
+                SELECT_CURRENT_STATUS_PG,
+                NEXT_SEQ_PG,
+                UPDATE_STATUS_PG,
+                INSERT_LOG_PG
+            ),
+            DatabasePool::SQLite(pool) => run_batch!(
+                pool,
+                SELECT_CURRENT_STATUS,
+                NEXT_SEQ,
+                UPDATE_STATUS,
+                INSERT_LOG
+            ),
+            DatabasePool::MySQL(pool) => run_batch!(
+                pool,
+                SELECT_CURRENT_STATUS,
+                NEXT_SEQ,
+                UPDATE_STATUS,
+                INSERT_LOG
+            ),
+        }
+
+        tracing::info!(
+            "Updated status of {} vulnerabilities to {} by {}",
+            changes.len(),
+            status,
+            changed_by
+        );
+        Ok(changes)
+    }
+
+    /// Status history of a finding, oldest first
+    pub async fn get_traffic_vulnerability_status_history(
+        &self,
+        vuln_id: &str,
+    ) -> Result<Vec<TrafficStatusChange>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let history = match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query_as(
+                    "SELECT id, vuln_id, seq, from_status, to_status, note, changed_by, changed_at FROM traffic_vulnerability_status_log WHERE vuln_id = $1 ORDER BY seq",
+                )
+                .bind(vuln_id)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query_as(
+                    "SELECT id, vuln_id, seq, from_status, to_status, note, changed_by, changed_at FROM traffic_vulnerability_status_log WHERE vuln_id = ? ORDER BY seq",
+                )
+                .bind(vuln_id)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query_as(
+                    "SELECT id, vuln_id, seq, from_status, to_status, note, changed_by, changed_at FROM traffic_vulnerability_status_log WHERE vuln_id = ? ORDER BY seq",
+                )
+                .bind(vuln_id)
+                .fetch_all(pool)
+                .await?
+            }
+        };
+        Ok(history)
+    }
+}
+
+#[cfg(all(test, feature = "db-sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database_service::test_service;
+
+    async fn service_with_findings(ids: &[&str]) -> DatabaseService {
+        let service = test_service(&[
+            "CREATE TABLE traffic_vulnerabilities (id TEXT PRIMARY KEY, status TEXT NOT NULL DEFAULT 'open', updated_at TIMESTAMP)",
+        ])
+        .await;
+        let Some(DatabasePool::SQLite(pool)) = &service.runtime_pool else {
+            unreachable!()
+        };
+        for id in ids {
+            sqlx::query("INSERT INTO traffic_vulnerabilities (id) VALUES (?)")
+                .bind(id)
+                .execute(pool)
+                .await
+                .unwrap();
+        }
+        service
+    }
+
+    async fn status_of(service: &DatabaseService, id: &str) -> String {
+        let Some(DatabasePool::SQLite(pool)) = &service.runtime_pool else {
+            unreachable!()
+        };
+        sqlx::query_scalar("SELECT status FROM traffic_vulnerabilities WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_batch_transition_records_history_in_order() {
+        let service = service_with_findings(&["v1", "v2", "v3"]).await;
+        let ids: Vec<String> = ["v1", "v2", "v3"].iter().map(|s| s.to_string()).collect();
+
+        let changes = service
+            .update_traffic_vulnerabilities_status_batch(&ids, "reviewed", Some("triaged"), "alice")
+            .await
+            .unwrap();
+        assert_eq!(changes.len(), 3);
+        service
+            .update_traffic_vulnerabilities_status_batch(
+                &ids[..1],
+                "not_applicable",
+                Some("internal host"),
+                "bob",
+            )
+            .await
+            .unwrap();
+
+        for id in &ids {
+            assert_ne!(status_of(&service, id).await, "open");
+        }
+        assert_eq!(status_of(&service, "v1").await, "not_applicable");
+
+        let history = service
+            .get_traffic_vulnerability_status_history("v1")
+            .await
+            .unwrap();
+        let transitions: Vec<(i64, &str, &str, &str)> = history
+            .iter()
+            .map(|c| {
+                (
+                    c.seq,
+                    c.from_status.as_str(),
+                    c.to_status.as_str(),
+                    c.changed_by.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            transitions,
+            vec![
+                (1, "open", "reviewed", "alice"),
+                (2, "reviewed", "not_applicable", "bob"),
+            ]
+        );
+        assert_eq!(history[1].note.as_deref(), Some("internal host"));
+    }
+
+    #[tokio::test]
+    async fn test_batch_with_unknown_id_rolls_back() {
+        let service = service_with_findings(&["v1"]).await;
+        let ids = vec!["v1".to_string(), "missing".to_string()];
+
+        let err = service
+            .update_traffic_vulnerabilities_status_batch(&ids, "fixed", None, "alice")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("missing"));
+
+        assert_eq!(status_of(&service, "v1").await, "open");
+        assert!(service
+            .get_traffic_vulnerability_status_history("v1")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,228 @@
+//! Traffic finding suppression rules
+//!
+//! Rules mark known false positives: a new finding matching an enabled rule is
+//! stored with status `suppressed` instead of being surfaced.
+
+use crate::database_service::connection_manager::DatabasePool;
+use crate::database_service::service::DatabaseService;
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+pub(super) const CREATE_SUPPRESSION_TABLE: &str = r#"CREATE TABLE IF NOT EXISTS traffic_suppression_rules (
+    id VARCHAR(64) PRIMARY KEY,
+    name TEXT NOT NULL,
+    plugin_id TEXT,
+    host_pattern TEXT,
+    path_pattern TEXT,
+    evidence_pattern TEXT,
+    enabled BOOLEAN NOT NULL,
+    hit_count BIGINT NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL
+)"#;
+
+const SELECT_COLUMNS: &str = "id, name, plugin_id, host_pattern, path_pattern, evidence_pattern, enabled, hit_count, created_at, updated_at";
+
+/// Suppression rule; empty matchers match anything
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TrafficSuppressionRule {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Exact plugin id
+    #[serde(default)]
+    pub plugin_id: Option<String>,
+    /// Host glob (`*` / `?`), e.g. `*.example.com`
+    #[serde(default)]
+    pub host_pattern: Option<String>,
+    /// Path glob, e.g. `/static/*`
+    #[serde(default)]
+    pub path_pattern: Option<String>,
+    /// Glob over the finding evidence, or an exact finding signature
+    #[serde(default)]
+    pub evidence_pattern: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub hit_count: i64,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl DatabaseService {
+    pub async fn list_traffic_suppression_rules(&self) -> Result<Vec<TrafficSuppressionRule>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let sql = format!(
+            "SELECT {} FROM traffic_suppression_rules ORDER BY created_at",
+            SELECT_COLUMNS
+        );
+        let rules = match runtime {
+            DatabasePool::PostgreSQL(pool) => sqlx::query_as(&sql).fetch_all(pool).await?,
+            DatabasePool::SQLite(pool) => sqlx::query_as(&sql).fetch_all(pool).await?,
+            DatabasePool::MySQL(pool) => sqlx::query_as(&sql).fetch_all(pool).await?,
+        };
+        Ok(rules)
+    }
+
+    /// Insert or update a rule; a new id is assigned when `rule.id` is empty
+    pub async fn save_traffic_suppression_rule(
+        &self,
+        rule: &TrafficSuppressionRule,
+    ) -> Result<TrafficSuppressionRule> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let now = Utc::now().timestamp_millis();
+        let mut rule = rule.clone();
+        if rule.id.is_empty() {
+            rule.id = uuid::Uuid::new_v4().to_string();
+            rule.created_at = now;
+        }
+        if rule.created_at == 0 {
+            rule.created_at = now;
+        }
+        rule.updated_at = now;
+
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query(
+                    "INSERT INTO traffic_suppression_rules (id, name, plugin_id, host_pattern, path_pattern, evidence_pattern, enabled, hit_count, created_at, updated_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                     ON CONFLICT(id) DO UPDATE SET name = excluded.name, plugin_id = excluded.plugin_id, host_pattern = excluded.host_pattern, path_pattern = excluded.path_pattern, evidence_pattern = excluded.evidence_pattern, enabled = excluded.enabled, updated_at = excluded.updated_at",
+                )
+                .bind(&rule.id)
+                .bind(&rule.name)
+                .bind(&rule.plugin_id)
+                .bind(&rule.host_pattern)
+                .bind(&rule.path_pattern)
+                .bind(&rule.evidence_pattern)
+                .bind(rule.enabled)
+                .bind(rule.hit_count)
+                .bind(rule.created_at)
+                .bind(rule.updated_at)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query(
+                    "INSERT INTO traffic_suppression_rules (id, name, plugin_id, host_pattern, path_pattern, evidence_pattern, enabled, hit_count, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                     ON CONFLICT(id) DO UPDATE SET name = excluded.name, plugin_id = excluded.plugin_id, host_pattern = excluded.host_pattern, path_pattern = excluded.path_pattern, evidence_pattern = excluded.evidence_pattern, enabled = excluded.enabled, updated_at = excluded.updated_at",
+                )
+                .bind(&rule.id)
+                .bind(&rule.name)
+                .bind(&rule.plugin_id)
+                .bind(&rule.host_pattern)
+                .bind(&rule.path_pattern)
+                .bind(&rule.evidence_pattern)
+                .bind(rule.enabled)
+                .bind(rule.hit_count)
+                .bind(rule.created_at)
+                .bind(rule.updated_at)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query(
+                    "INSERT INTO traffic_suppression_rules (id, name, plugin_id, host_pattern, path_pattern, evidence_pattern, enabled, hit_count, created_at, updated_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                     ON DUPLICATE KEY UPDATE name = VALUES(name), plugin_id = VALUES(plugin_id), host_pattern = VALUES(host_pattern), path_pattern = VALUES(path_pattern), evidence_pattern = VALUES(evidence_pattern), enabled = VALUES(enabled), updated_at = VALUES(updated_at)",
+                )
+                .bind(&rule.id)
+                .bind(&rule.name)
+                .bind(&rule.plugin_id)
+                .bind(&rule.host_pattern)
+                .bind(&rule.path_pattern)
+                .bind(&rule.evidence_pattern)
+                .bind(rule.enabled)
+                .bind(rule.hit_count)
+                .bind(rule.created_at)
+                .bind(rule.updated_at)
+                .execute(pool)
+                .await?;
+            }
+        }
+        Ok(rule)
+    }
+
+    pub async fn delete_traffic_suppression_rule(&self, id: &str) -> Result<bool> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let rows = match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query("DELETE FROM traffic_suppression_rules WHERE id = $1")
+                    .bind(id)
+                    .execute(pool)
+                    .await?
+                    .rows_affected()
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query("DELETE FROM traffic_suppression_rules WHERE id = ?")
+                    .bind(id)
+                    .execute(pool)
+                    .await?
+                    .rows_affected()
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query("DELETE FROM traffic_suppression_rules WHERE id = ?")
+                    .bind(id)
+                    .execute(pool)
+                    .await?
+                    .rows_affected()
+            }
+        };
+        Ok(rows > 0)
+    }
+
+    /// Count one more finding suppressed by a rule
+    pub async fn record_traffic_suppression_hit(&self, id: &str) -> Result<()> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query(
+                    "UPDATE traffic_suppression_rules SET hit_count = hit_count + 1 WHERE id = $1",
+                )
+                .bind(id)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query(
+                    "UPDATE traffic_suppression_rules SET hit_count = hit_count + 1 WHERE id = ?",
+                )
+                .bind(id)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query(
+                    "UPDATE traffic_suppression_rules SET hit_count = hit_count + 1 WHERE id = ?",
+                )
+                .bind(id)
+                .execute(pool)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
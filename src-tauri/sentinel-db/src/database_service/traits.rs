@@ -10,8 +10,8 @@ use crate::core::models::database::{
 };
 use crate::core::models::rag_config::RagConfig;
 use crate::core::models::scan_session::{
-    CreateScanSessionRequest, ScanProgress, ScanSession, ScanSessionStatus, ScanStage,
-    UpdateScanSessionRequest,
+    CreateScanSessionRequest, ScanProgress, ScanSession, ScanSessionDiff, ScanSessionStatus,
+    ScanStage, UpdateScanSessionRequest,
 };
 use crate::core::models::workflow::WorkflowStepDetail;
 use crate::database_service::proxifier::{ProxifierProxyRecord, ProxifierRuleRecord};
@@ -90,6 +90,11 @@ pub trait Database: Send + Sync + std::fmt::Debug {
     async fn update_scan_stage(&self, stage: &ScanStage) -> Result<()>;
     async fn get_scan_session_stages(&self, session_id: uuid::Uuid) -> Result<Vec<ScanStage>>;
     async fn get_scan_progress(&self, session_id: uuid::Uuid) -> Result<Option<ScanProgress>>;
+    async fn diff_scan_sessions(
+        &self,
+        base_session_id: uuid::Uuid,
+        compare_session_id: uuid::Uuid,
+    ) -> Result<ScanSessionDiff>;
 
     // 扫描任务相关方法
     async fn create_scan_task(&self, task: &ScanTask) -> Result<()>;
@@ -451,6 +456,9 @@ pub trait Database: Send + Sync + std::fmt::Debug {
         offset: Option<u32>,
     ) -> Result<Vec<Asset>>;
     async fn get_asset_stats(&self) -> Result<AssetStats>;
+    async fn add_asset_tags(&self, asset_ids: &[String], tags: &[String]) -> Result<usize>;
+    async fn remove_asset_tags(&self, asset_ids: &[String], tags: &[String]) -> Result<usize>;
+    async fn bulk_tag_assets(&self, request: BulkTagAssetsRequest) -> Result<BulkTagAssetsResult>;
     async fn create_relationship(
         &self,
         source_asset_id: String,
@@ -462,6 +470,12 @@ pub trait Database: Send + Sync + std::fmt::Debug {
         &self,
         asset_id: &str,
     ) -> Result<(Vec<AssetRelationship>, Vec<AssetRelationship>)>;
+    async fn build_asset_graph(
+        &self,
+        root_id: &str,
+        depth: u32,
+        max_nodes: usize,
+    ) -> Result<AssetGraph>;
     async fn import_assets(
         &self,
         request: ImportAssetsRequest,
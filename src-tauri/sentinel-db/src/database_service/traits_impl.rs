@@ -10,8 +10,8 @@ use crate::core::models::database::{
 };
 use crate::core::models::rag_config::RagConfig;
 use crate::core::models::scan_session::{
-    CreateScanSessionRequest, ScanProgress, ScanSession, ScanSessionStatus, ScanStage,
-    UpdateScanSessionRequest,
+    CreateScanSessionRequest, ScanProgress, ScanSession, ScanSessionDiff, ScanSessionStatus,
+    ScanStage, UpdateScanSessionRequest,
 };
 use crate::core::models::workflow::WorkflowStepDetail;
 use crate::database_service::proxifier::{ProxifierProxyRecord, ProxifierRuleRecord};
@@ -178,6 +178,13 @@ impl Database for DatabaseService {
     async fn get_scan_progress(&self, session_id: uuid::Uuid) -> Result<Option<ScanProgress>> {
         Self::get_scan_progress_internal(self, session_id).await
     }
+    async fn diff_scan_sessions(
+        &self,
+        base_session_id: uuid::Uuid,
+        compare_session_id: uuid::Uuid,
+    ) -> Result<ScanSessionDiff> {
+        Self::diff_scan_sessions_internal(self, base_session_id, compare_session_id).await
+    }
 
     // Scan
     async fn create_scan_task(&self, task: &ScanTask) -> Result<()> {
@@ -898,6 +905,15 @@ impl Database for DatabaseService {
     async fn get_asset_stats(&self) -> Result<AssetStats> {
         Self::get_asset_stats_internal(self).await
     }
+    async fn add_asset_tags(&self, asset_ids: &[String], tags: &[String]) -> Result<usize> {
+        Self::add_asset_tags_internal(self, asset_ids, tags).await
+    }
+    async fn remove_asset_tags(&self, asset_ids: &[String], tags: &[String]) -> Result<usize> {
+        Self::remove_asset_tags_internal(self, asset_ids, tags).await
+    }
+    async fn bulk_tag_assets(&self, request: BulkTagAssetsRequest) -> Result<BulkTagAssetsResult> {
+        Self::bulk_tag_assets_internal(self, request).await
+    }
     async fn create_relationship(
         &self,
         source_asset_id: String,
@@ -920,6 +936,14 @@ impl Database for DatabaseService {
     ) -> Result<(Vec<AssetRelationship>, Vec<AssetRelationship>)> {
         Self::get_asset_relationships_internal(self, asset_id).await
     }
+    async fn build_asset_graph(
+        &self,
+        root_id: &str,
+        depth: u32,
+        max_nodes: usize,
+    ) -> Result<AssetGraph> {
+        Self::build_asset_graph_internal(self, root_id, depth, max_nodes).await
+    }
     async fn import_assets(
         &self,
         request: ImportAssetsRequest,
@@ -1297,6 +1321,15 @@ impl RagDatabase for DatabaseService {
     async fn delete_rag_document(&self, document_id: &str) -> Result<()> {
         Self::delete_rag_document_internal(self, document_id).await
     }
+    async fn update_rag_document_fingerprint(
+        &self,
+        document_id: &str,
+        file_hash: &str,
+        file_mtime: Option<i64>,
+    ) -> Result<()> {
+        Self::update_rag_document_fingerprint_internal(self, document_id, file_hash, file_mtime)
+            .await
+    }
     async fn save_rag_query(
         &self,
         collection_id: Option<&str>,
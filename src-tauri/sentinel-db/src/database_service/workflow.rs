@@ -376,6 +376,7 @@ impl DatabaseService {
                 run_id.replace('\'', "''")
             ))
             .await?;
+        let artifacts = self.list_workflow_run_artifacts(run_id).await?;
 
         Ok(Some(serde_json::json!({
             "execution_id": row.get("id").cloned().unwrap_or(serde_json::Value::Null),
@@ -390,7 +391,8 @@ impl DatabaseService {
             "completed_steps": row.get("completed_steps").cloned().unwrap_or(serde_json::Value::Null),
             "total_steps": row.get("total_steps").cloned().unwrap_or(serde_json::Value::Null),
             "error_message": row.get("error_message").cloned().unwrap_or(serde_json::Value::Null),
-            "steps": steps
+            "steps": steps,
+            "artifacts": artifacts
         })))
     }
 
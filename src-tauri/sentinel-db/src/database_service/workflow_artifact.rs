@@ -0,0 +1,440 @@
+//! Workflow run artifacts
+//!
+//! Each node's output is kept per run. Small outputs are stored inline in
+//! `workflow_run_artifacts`; outputs above the inline limit are written to a
+//! file under the artifacts directory and only their path is stored.
+
+use crate::database_service::connection_manager::DatabasePool;
+use crate::database_service::service::DatabaseService;
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Outputs larger than this many bytes are stored as files
+pub const WORKFLOW_ARTIFACT_INLINE_LIMIT: usize = 64 * 1024;
+
+pub const ARTIFACT_STORAGE_INLINE: &str = "inline";
+pub const ARTIFACT_STORAGE_FILE: &str = "file";
+
+pub(super) const CREATE_ARTIFACT_TABLE: &str = r#"CREATE TABLE IF NOT EXISTS workflow_run_artifacts (
+    run_id VARCHAR(64) NOT NULL,
+    node_id VARCHAR(128) NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    storage TEXT NOT NULL,
+    inline_content TEXT,
+    file_path TEXT,
+    created_at BIGINT NOT NULL,
+    PRIMARY KEY (run_id, node_id)
+)"#;
+
+const SELECT_META_COLUMNS: &str =
+    "run_id, node_id, content_type, size_bytes, storage, file_path, created_at";
+
+/// Artifact metadata (without content)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WorkflowRunArtifact {
+    pub run_id: String,
+    pub node_id: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// `inline` or `file`
+    pub storage: String,
+    pub file_path: Option<String>,
+    /// Milliseconds since the Unix epoch
+    pub created_at: i64,
+}
+
+/// Artifact metadata plus its content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRunArtifactContent {
+    #[serde(flatten)]
+    pub artifact: WorkflowRunArtifact,
+    pub content: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct ArtifactRow {
+    run_id: String,
+    node_id: String,
+    content_type: String,
+    size_bytes: i64,
+    storage: String,
+    inline_content: Option<String>,
+    file_path: Option<String>,
+    created_at: i64,
+}
+
+impl DatabaseService {
+    pub fn get_workflow_artifacts_dir(&self) -> PathBuf {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("sentinel-ai")
+            .join("workflow_artifacts")
+    }
+
+    /// Store a node output using the default directory and inline limit
+    pub async fn save_workflow_run_artifact(
+        &self,
+        run_id: &str,
+        node_id: &str,
+        content_type: &str,
+        content: &str,
+    ) -> Result<WorkflowRunArtifact> {
+        let dir = self.get_workflow_artifacts_dir();
+        self.save_workflow_run_artifact_with(
+            &dir,
+            WORKFLOW_ARTIFACT_INLINE_LIMIT,
+            run_id,
+            node_id,
+            content_type,
+            content,
+        )
+        .await
+    }
+
+    /// Store a node output, writing it to `dir` when larger than `inline_limit`
+    pub async fn save_workflow_run_artifact_with(
+        &self,
+        dir: &Path,
+        inline_limit: usize,
+        run_id: &str,
+        node_id: &str,
+        content_type: &str,
+        content: &str,
+    ) -> Result<WorkflowRunArtifact> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let (storage, inline_content, file_path) = if content.len() > inline_limit {
+            let run_dir = dir.join(sanitize_path_component(run_id));
+            tokio::fs::create_dir_all(&run_dir).await?;
+            let path = run_dir.join(sanitize_path_component(node_id));
+            tokio::fs::write(&path, content).await?;
+            (
+                ARTIFACT_STORAGE_FILE,
+                None,
+                Some(path.to_string_lossy().to_string()),
+            )
+        } else {
+            (ARTIFACT_STORAGE_INLINE, Some(content), None)
+        };
+
+        let artifact = WorkflowRunArtifact {
+            run_id: run_id.to_string(),
+            node_id: node_id.to_string(),
+            content_type: content_type.to_string(),
+            size_bytes: content.len() as i64,
+            storage: storage.to_string(),
+            file_path,
+            created_at: Utc::now().timestamp_millis(),
+        };
+
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query(
+                    "INSERT INTO workflow_run_artifacts (run_id, node_id, content_type, size_bytes, storage, inline_content, file_path, created_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                     ON CONFLICT(run_id, node_id) DO UPDATE SET content_type = excluded.content_type, size_bytes = excluded.size_bytes, storage = excluded.storage, inline_content = excluded.inline_content, file_path = excluded.file_path, created_at = excluded.created_at",
+                )
+                .bind(&artifact.run_id)
+                .bind(&artifact.node_id)
+                .bind(&artifact.content_type)
+                .bind(artifact.size_bytes)
+                .bind(&artifact.storage)
+                .bind(inline_content)
+                .bind(&artifact.file_path)
+                .bind(artifact.created_at)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query(
+                    "INSERT INTO workflow_run_artifacts (run_id, node_id, content_type, size_bytes, storage, inline_content, file_path, created_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                     ON CONFLICT(run_id, node_id) DO UPDATE SET content_type = excluded.content_type, size_bytes = excluded.size_bytes, storage = excluded.storage, inline_content = excluded.inline_content, file_path = excluded.file_path, created_at = excluded.created_at",
+                )
+                .bind(&artifact.run_id)
+                .bind(&artifact.node_id)
+                .bind(&artifact.content_type)
+                .bind(artifact.size_bytes)
+                .bind(&artifact.storage)
+                .bind(inline_content)
+                .bind(&artifact.file_path)
+                .bind(artifact.created_at)
+                .execute(pool)
+                .await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query(
+                    "INSERT INTO workflow_run_artifacts (run_id, node_id, content_type, size_bytes, storage, inline_content, file_path, created_at)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                     ON DUPLICATE KEY UPDATE content_type = VALUES(content_type), size_bytes = VALUES(size_bytes), storage = VALUES(storage), inline_content = VALUES(inline_content), file_path = VALUES(file_path), created_at = VALUES(created_at)",
+                )
+                .bind(&artifact.run_id)
+                .bind(&artifact.node_id)
+                .bind(&artifact.content_type)
+                .bind(artifact.size_bytes)
+                .bind(&artifact.storage)
+                .bind(inline_content)
+                .bind(&artifact.file_path)
+                .bind(artifact.created_at)
+                .execute(pool)
+                .await?;
+            }
+        }
+        Ok(artifact)
+    }
+
+    /// Artifacts of a run, without content
+    pub async fn list_workflow_run_artifacts(
+        &self,
+        run_id: &str,
+    ) -> Result<Vec<WorkflowRunArtifact>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let artifacts = match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM workflow_run_artifacts WHERE run_id = $1 ORDER BY created_at",
+                    SELECT_META_COLUMNS
+                ))
+                .bind(run_id)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM workflow_run_artifacts WHERE run_id = ? ORDER BY created_at",
+                    SELECT_META_COLUMNS
+                ))
+                .bind(run_id)
+                .fetch_all(pool)
+                .await?
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query_as(&format!(
+                    "SELECT {} FROM workflow_run_artifacts WHERE run_id = ? ORDER BY created_at",
+                    SELECT_META_COLUMNS
+                ))
+                .bind(run_id)
+                .fetch_all(pool)
+                .await?
+            }
+        };
+        Ok(artifacts)
+    }
+
+    /// Load one artifact with its content, reading the backing file if needed
+    pub async fn get_workflow_run_artifact(
+        &self,
+        run_id: &str,
+        node_id: &str,
+    ) -> Result<Option<WorkflowRunArtifactContent>> {
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+
+        let row: Option<ArtifactRow> = match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query_as(
+                    "SELECT run_id, node_id, content_type, size_bytes, storage, inline_content, file_path, created_at FROM workflow_run_artifacts WHERE run_id = $1 AND node_id = $2",
+                )
+                .bind(run_id)
+                .bind(node_id)
+                .fetch_optional(pool)
+                .await?
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query_as(
+                    "SELECT run_id, node_id, content_type, size_bytes, storage, inline_content, file_path, created_at FROM workflow_run_artifacts WHERE run_id = ? AND node_id = ?",
+                )
+                .bind(run_id)
+                .bind(node_id)
+                .fetch_optional(pool)
+                .await?
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query_as(
+                    "SELECT run_id, node_id, content_type, size_bytes, storage, inline_content, file_path, created_at FROM workflow_run_artifacts WHERE run_id = ? AND node_id = ?",
+                )
+                .bind(run_id)
+                .bind(node_id)
+                .fetch_optional(pool)
+                .await?
+            }
+        };
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let content = match (&row.inline_content, &row.file_path) {
+            (Some(content), _) => content.clone(),
+            (None, Some(path)) => tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to read artifact file {}: {}", path, e))?,
+            (None, None) => String::new(),
+        };
+
+        Ok(Some(WorkflowRunArtifactContent {
+            artifact: WorkflowRunArtifact {
+                run_id: row.run_id,
+                node_id: row.node_id,
+                content_type: row.content_type,
+                size_bytes: row.size_bytes,
+                storage: row.storage,
+                file_path: row.file_path,
+                created_at: row.created_at,
+            },
+            content,
+        }))
+    }
+
+    /// Remove all artifacts of a run, including backing files
+    pub async fn delete_workflow_run_artifacts(&self, run_id: &str) -> Result<()> {
+        let artifacts = self.list_workflow_run_artifacts(run_id).await?;
+        for path in artifacts.iter().filter_map(|a| a.file_path.as_ref()) {
+            if let Err(e) = tokio::fs::remove_file(path).await {
+                tracing::warn!("Failed to remove artifact file {}: {}", path, e);
+            }
+        }
+
+        let runtime = self
+            .runtime_pool
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("数据库未初始化"))?;
+        match runtime {
+            DatabasePool::PostgreSQL(pool) => {
+                sqlx::query("DELETE FROM workflow_run_artifacts WHERE run_id = $1")
+                    .bind(run_id)
+                    .execute(pool)
+                    .await?;
+            }
+            DatabasePool::SQLite(pool) => {
+                sqlx::query("DELETE FROM workflow_run_artifacts WHERE run_id = ?")
+                    .bind(run_id)
+                    .execute(pool)
+                    .await?;
+            }
+            DatabasePool::MySQL(pool) => {
+                sqlx::query("DELETE FROM workflow_run_artifacts WHERE run_id = ?")
+                    .bind(run_id)
+                    .execute(pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keep ids usable as file names
+fn sanitize_path_component(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "db-sqlite"))]
+mod tests {
+    use super::*;
+    use crate::database_service::test_service;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}-{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_small_output_is_stored_inline() {
+        let service = test_service(&[]).await;
+        let dir = temp_dir("artifacts-inline");
+        let content = r#"{"endpoints":["/login","/api"]}"#;
+
+        let artifact = service
+            .save_workflow_run_artifact_with(
+                &dir,
+                1024,
+                "run-1",
+                "node-a",
+                "application/json",
+                content,
+            )
+            .await
+            .unwrap();
+        assert_eq!(artifact.storage, ARTIFACT_STORAGE_INLINE);
+        assert_eq!(artifact.size_bytes, content.len() as i64);
+        assert!(artifact.file_path.is_none());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        let loaded = service
+            .get_workflow_run_artifact("run-1", "node-a")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.content, content);
+        assert_eq!(loaded.artifact.content_type, "application/json");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_large_output_is_stored_in_file() {
+        let service = test_service(&[]).await;
+        let dir = temp_dir("artifacts-file");
+        let content = "PORT   STATE SERVICE\n".repeat(200);
+
+        let artifact = service
+            .save_workflow_run_artifact_with(
+                &dir,
+                1024,
+                "run-1",
+                "nmap/scan",
+                "text/plain",
+                &content,
+            )
+            .await
+            .unwrap();
+        assert_eq!(artifact.storage, ARTIFACT_STORAGE_FILE);
+        let path = PathBuf::from(artifact.file_path.clone().unwrap());
+        assert!(path.starts_with(&dir));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+
+        let loaded = service
+            .get_workflow_run_artifact("run-1", "nmap/scan")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.content, content);
+        assert_eq!(loaded.artifact.size_bytes, content.len() as i64);
+
+        let listed = service.list_workflow_run_artifacts("run-1").await.unwrap();
+        assert_eq!(listed, vec![artifact]);
+
+        service
+            .delete_workflow_run_artifacts("run-1")
+            .await
+            .unwrap();
+        assert!(!path.exists());
+        assert!(service
+            .get_workflow_run_artifact("run-1", "nmap/scan")
+            .await
+            .unwrap()
+            .is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -26,6 +26,8 @@ struct LicenseKey {
     machine_id: String,
     signature: String,
     metadata: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    components: Option<String>,
 }
 
 fn main() {
@@ -47,6 +49,15 @@ fn main() {
             let metadata = args.get(3).map(|s| s.as_str());
             sign_license(&args[2], metadata);
         }
+        "respond" => {
+            if args.len() < 3 {
+                eprintln!("Error: Activation request file required");
+                eprintln!("Usage: license_generator respond <request_file> [metadata]");
+                return;
+            }
+            let metadata = args.get(3).map(|s| s.as_str());
+            respond_to_request(&args[2], metadata);
+        }
         "verify" => {
             if args.len() < 3 {
                 eprintln!("Error: License key required");
@@ -73,6 +84,9 @@ Sentinel AI License Generator
 Commands:
   generate-keys, gen     Generate a new Ed25519 key pair
   sign <machine_id>      Sign a license for the given machine ID
+                         or component fingerprint (cpu=..;disk=..;os=..;mac=..)
+                         Optional: add metadata as third argument
+  respond <request_file> Answer an offline activation request file
                          Optional: add metadata as third argument
   verify <license_key>   Verify a license key
   show-public-key, pubkey Show the public key to embed in application
@@ -82,6 +96,8 @@ Examples:
   license_generator gen
   license_generator sign ABCD-1234-EFGH-5678
   license_generator sign ABCD-1234-EFGH-5678 "Customer: John Doe"
+  license_generator sign "cpu=...;disk=...;os=...;mac=..."
+  license_generator respond activation_request.json
   license_generator verify <base64_license_key>
   license_generator pubkey
 
@@ -89,6 +105,8 @@ Notes:
   - Keep the private key secure! Never share it.
   - The public key should be embedded in the application (crypto.rs).
   - Machine IDs are in format: XXXX-XXXX-XXXX-XXXX
+  - Licenses signed for a fingerprint keep working when a minority of
+    hardware components (e.g. a network adapter) change.
 "#
     );
 }
@@ -157,9 +175,21 @@ fn sign_license(machine_id: &str, metadata: Option<&str>) {
 
     let signing_key = SigningKey::from_bytes(&key_array);
 
-    // Parse machine ID - support both display format (XXXX-XXXX-XXXX-XXXX) and full hash
+    // Parse machine ID - support component fingerprint, display format (XXXX-XXXX-XXXX-XXXX) and full hash
     let machine_id_clean = machine_id.replace("-", "").to_lowercase();
-    let machine_id_hash: Vec<u8> = if machine_id_clean.len() == 64 {
+    let mut components = None;
+    let machine_id_hash: Vec<u8> = if machine_id.contains('=') {
+        match sentinel_license::MachineId::from_fingerprint(machine_id) {
+            Some(id) => {
+                components = Some(id.to_fingerprint());
+                id.to_hash().to_vec()
+            }
+            None => {
+                eprintln!("Error: Invalid component fingerprint");
+                return;
+            }
+        }
+    } else if machine_id_clean.len() == 64 {
         // Full 64-char hex hash (32 bytes)
         match hex::decode(&machine_id_clean) {
             Ok(h) => h,
@@ -200,6 +230,10 @@ fn sign_license(machine_id: &str, metadata: Option<&str>) {
     if let Some(meta) = metadata {
         hasher.update(meta.as_bytes());
     }
+    if let Some(components) = &components {
+        hasher.update(b"|components=");
+        hasher.update(components.as_bytes());
+    }
     let message: [u8; 32] = hasher.finalize().into();
 
     // Sign
@@ -210,6 +244,7 @@ fn sign_license(machine_id: &str, metadata: Option<&str>) {
         machine_id: hex::encode(&machine_id_hash),
         signature: BASE64.encode(signature.to_bytes()),
         metadata: metadata.map(|s| s.to_string()),
+        components,
     };
 
     // Encode to final format
@@ -222,6 +257,72 @@ fn sign_license(machine_id: &str, metadata: Option<&str>) {
     if let Some(meta) = &license.metadata {
         println!("Metadata: {}", meta);
     }
+    if let Some(components) = &license.components {
+        println!("Components: {}", components);
+    }
+}
+
+fn respond_to_request(request_file: &str, metadata: Option<&str>) {
+    use sentinel_license::{
+        sign_machine_license, ActivationRequest, ActivationResponse, MachineId,
+    };
+
+    let content = match fs::read_to_string(request_file) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Error reading activation request: {}", e);
+            return;
+        }
+    };
+    let request = match ActivationRequest::parse(&content) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return;
+        }
+    };
+    let machine_id = match MachineId::from_fingerprint(&request.components) {
+        Some(id) if id.to_full_hex() == request.machine_id => id,
+        _ => {
+            eprintln!("Error: Component fingerprint does not match the machine ID");
+            return;
+        }
+    };
+
+    let keys = match load_keys() {
+        Some(k) => k,
+        None => {
+            eprintln!("Error: No keys found. Run 'license_generator generate-keys' first.");
+            return;
+        }
+    };
+    let key_array: [u8; 32] = match BASE64
+        .decode(&keys.private_key)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+    {
+        Some(a) => a,
+        None => {
+            eprintln!("Error: Invalid private key");
+            return;
+        }
+    };
+    let signing_key = SigningKey::from_bytes(&key_array);
+
+    let license = sign_machine_license(&machine_id, &signing_key, metadata).to_string();
+    let response = ActivationResponse::sign(&request, &license, &signing_key);
+    let response_file = format!("{}.response.json", request_file.trim_end_matches(".json"));
+    if let Err(e) = fs::write(&response_file, response.to_file_content()) {
+        eprintln!("Error writing activation response: {}", e);
+        return;
+    }
+
+    println!("=== ACTIVATION RESPONSE ===");
+    println!("Saved to: {}\n", response_file);
+    println!("Machine ID (full): {}", request.machine_id);
+    if let Some(meta) = metadata {
+        println!("Metadata: {}", meta);
+    }
 }
 
 fn verify_license(license_key: &str) {
@@ -311,6 +412,10 @@ fn verify_license(license_key: &str) {
     if let Some(ref meta) = license.metadata {
         hasher.update(meta.as_bytes());
     }
+    if let Some(ref components) = license.components {
+        hasher.update(b"|components=");
+        hasher.update(components.as_bytes());
+    }
     let message: [u8; 32] = hasher.finalize().into();
 
     // Verify
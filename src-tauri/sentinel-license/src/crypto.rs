@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use thiserror::Error;
 
+use crate::machine_id::MachineId;
+
 /// Embedded public key for license verification
 const EMBEDDED_PUBLIC_KEY: &str = "yzCNnuh1Mj0rXdWqvjvWRS6bxXp3Kw9GPu5gDDxrSsk=";
 
@@ -37,6 +39,9 @@ pub struct LicenseKey {
     /// Optional metadata
     #[serde(default)]
     pub metadata: Option<String>,
+    /// Component fingerprint the license is bound to (enables drift tolerance)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<String>,
 }
 
 impl std::str::FromStr for LicenseKey {
@@ -78,6 +83,27 @@ pub fn generate_keypair() -> KeyPair {
     }
 }
 
+/// Message signed for a license
+///
+/// Licenses without a component fingerprint keep the original message, so
+/// existing licenses still verify.
+fn license_message(
+    machine_id_hash: &[u8],
+    metadata: Option<&str>,
+    components: Option<&str>,
+) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(machine_id_hash);
+    if let Some(meta) = metadata {
+        hasher.update(meta.as_bytes());
+    }
+    if let Some(components) = components {
+        hasher.update(b"|components=");
+        hasher.update(components.as_bytes());
+    }
+    hasher.finalize().into()
+}
+
 /// Sign a license for a machine ID
 pub fn sign_license(
     machine_id_hash: &[u8; 32],
@@ -85,12 +111,7 @@ pub fn sign_license(
     metadata: Option<&str>,
 ) -> LicenseKey {
     // Create message to sign: hash of machine_id + optional metadata
-    let mut hasher = Sha256::new();
-    hasher.update(machine_id_hash);
-    if let Some(meta) = metadata {
-        hasher.update(meta.as_bytes());
-    }
-    let message: [u8; 32] = hasher.finalize().into();
+    let message = license_message(machine_id_hash, metadata, None);
 
     // Sign
     let signature = signing_key.sign(&message);
@@ -99,6 +120,27 @@ pub fn sign_license(
         machine_id: hex::encode(machine_id_hash),
         signature: BASE64.encode(signature.to_bytes()),
         metadata: metadata.map(|s| s.to_string()),
+        components: None,
+    }
+}
+
+/// Sign a license bound to a machine's component fingerprint
+///
+/// Such a license keeps validating when a minority of components drift.
+pub fn sign_machine_license(
+    machine_id: &MachineId,
+    signing_key: &SigningKey,
+    metadata: Option<&str>,
+) -> LicenseKey {
+    let components = machine_id.to_fingerprint();
+    let message = license_message(&machine_id.to_hash(), metadata, Some(&components));
+    let signature = signing_key.sign(&message);
+
+    LicenseKey {
+        machine_id: machine_id.to_full_hex(),
+        signature: BASE64.encode(signature.to_bytes()),
+        metadata: metadata.map(|s| s.to_string()),
+        components: Some(components),
     }
 }
 
@@ -117,12 +159,11 @@ pub fn verify_license(
     let public_key = get_embedded_public_key()?;
 
     // Recreate message
-    let mut hasher = Sha256::new();
-    hasher.update(machine_id_hash);
-    if let Some(ref meta) = license.metadata {
-        hasher.update(meta.as_bytes());
-    }
-    let message: [u8; 32] = hasher.finalize().into();
+    let message = license_message(
+        machine_id_hash,
+        license.metadata.as_deref(),
+        license.components.as_deref(),
+    );
 
     // Get signature
     let sig_bytes = license.signature_bytes()?;
@@ -136,7 +177,7 @@ pub fn verify_license(
 }
 
 /// Get the embedded public key
-fn get_embedded_public_key() -> Result<VerifyingKey, CryptoError> {
+pub(crate) fn get_embedded_public_key() -> Result<VerifyingKey, CryptoError> {
     // In production, this would decode the actual embedded key
     if EMBEDDED_PUBLIC_KEY == "REPLACE_WITH_YOUR_PUBLIC_KEY_BASE64" {
         // For development/testing, return error
@@ -208,6 +249,7 @@ mod tests {
             machine_id: "abc123".to_string(),
             signature: "sig123".to_string(),
             metadata: Some("test".to_string()),
+            components: None,
         };
 
         let encoded = license.to_string();
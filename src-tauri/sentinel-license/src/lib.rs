@@ -3,6 +3,7 @@
 //! Features:
 //! - Ed25519 asymmetric signature verification
 //! - Multi-point distributed validation
+//! - Offline challenge/response activation
 //! - Anti-debugging detection
 //! - String encryption
 //! - Validation logic obfuscation
@@ -12,17 +13,24 @@ mod crypto;
 mod integrity;
 mod machine_id;
 mod obfuscate;
+mod offline;
 mod storage;
 mod validator;
 
 pub use anti_debug::is_debugger_present;
-pub use crypto::{generate_keypair, sign_license, KeyPair, LicenseKey};
+pub use crypto::{generate_keypair, sign_license, sign_machine_license, KeyPair, LicenseKey};
 pub use integrity::{
     function_checksum, is_integrity_ok, verify_function_checksum, verify_integrity,
 };
-pub use machine_id::MachineId;
+pub use machine_id::{ComponentKind, ComponentMatch, MachineId};
+pub use offline::{
+    activate_from_response_file, generate_activation_request, ActivationRequest,
+    ActivationResponse, OfflineActivationError,
+};
 pub use storage::LicenseStorage;
-pub use validator::{LicenseStatus, LicenseValidator, ValidationResult};
+pub use validator::{
+    LicenseStatus, LicenseValidator, MachineMatch, ValidationResult, DEFAULT_MATCH_THRESHOLD,
+};
 
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
@@ -161,6 +169,11 @@ pub fn get_machine_id_full() -> String {
     MachineId::generate().to_full_hex()
 }
 
+/// Get the component fingerprint (send with the machine ID for drift-tolerant licenses)
+pub fn get_machine_fingerprint() -> String {
+    MachineId::generate().to_fingerprint()
+}
+
 /// Check if application needs activation
 pub fn needs_activation() -> bool {
     if !is_enforcement_enabled() {
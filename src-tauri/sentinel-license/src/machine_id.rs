@@ -1,14 +1,92 @@
 //! Machine ID generation - Cross-platform unique identifier
 
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use sysinfo::System;
 
+/// Hex chars kept per component digest in the fingerprint
+const COMPONENT_DIGEST_LEN: usize = 16;
+
+/// Hardware/OS component contributing to the machine ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentKind {
+    Cpu,
+    Disk,
+    /// machine-id (Linux), MachineGuid (Windows), platform serial (macOS)
+    OsInstall,
+    Mac,
+}
+
+impl ComponentKind {
+    pub const ALL: [ComponentKind; 4] = [
+        ComponentKind::Cpu,
+        ComponentKind::Disk,
+        ComponentKind::OsInstall,
+        ComponentKind::Mac,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComponentKind::Cpu => "cpu",
+            ComponentKind::Disk => "disk",
+            ComponentKind::OsInstall => "os",
+            ComponentKind::Mac => "mac",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == s)
+    }
+
+    /// Weight in the tolerant match; NICs change most often and weigh least
+    pub fn weight(&self) -> u32 {
+        match self {
+            ComponentKind::OsInstall | ComponentKind::Disk => 3,
+            ComponentKind::Cpu => 2,
+            ComponentKind::Mac => 1,
+        }
+    }
+
+    /// Whether the component identifies this particular machine
+    ///
+    /// CPU model and NICs are shared by many machines, so a tolerant match must
+    /// keep at least one of these.
+    pub fn is_anchor(&self) -> bool {
+        matches!(self, ComponentKind::OsInstall | ComponentKind::Disk)
+    }
+}
+
+/// Result of comparing the current machine against the components a license is bound to
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ComponentMatch {
+    pub matched: Vec<ComponentKind>,
+    /// Bound components that changed or are no longer readable
+    pub drifted: Vec<ComponentKind>,
+    pub matched_weight: u32,
+    pub total_weight: u32,
+}
+
+impl ComponentMatch {
+    /// Whether the matched share of the bound weight reaches `threshold` (0.0..=1.0)
+    /// and at least one anchor component (disk or OS install) still matches
+    pub fn meets(&self, threshold: f64) -> bool {
+        self.total_weight > 0
+            && self.matched.iter().any(ComponentKind::is_anchor)
+            && f64::from(self.matched_weight) / f64::from(self.total_weight) >= threshold
+    }
+}
+
 /// Machine identifier based on hardware characteristics
 #[derive(Debug, Clone)]
 pub struct MachineId {
-    #[allow(dead_code)]
-    components: Vec<String>,
+    /// Truncated SHA-256 digest of each component value
+    components: BTreeMap<ComponentKind, String>,
     hash: [u8; 32],
+    /// Hash from the pre-fingerprint derivation, sha256(mac|cpu|os|hostname)
+    ///
+    /// Licenses issued before component fingerprints are bound to this hash.
+    legacy_hash: Option<[u8; 32]>,
 }
 
 impl MachineId {
@@ -16,44 +94,121 @@ impl MachineId {
     pub fn generate() -> Self {
         let mut components = Vec::new();
 
-        // Get MAC address
-        if let Some(mac) = get_mac_address() {
-            components.push(mac);
+        // Get CPU info
+        let cpu = get_cpu_info();
+        if let Some(cpu) = &cpu {
+            components.push((ComponentKind::Cpu, cpu.clone()));
         }
 
-        // Get CPU info
-        if let Some(cpu) = get_cpu_info() {
-            components.push(cpu);
+        if let Some(serial) = get_disk_serial() {
+            components.push((ComponentKind::Disk, serial));
         }
 
         // Get platform-specific identifiers
         #[cfg(target_os = "macos")]
-        if let Some(serial) = get_macos_serial() {
-            components.push(serial);
-        }
+        let os_id = get_macos_serial();
 
         #[cfg(target_os = "windows")]
-        if let Some(id) = get_windows_machine_guid() {
-            components.push(id);
-        }
+        let os_id = get_windows_machine_guid();
 
         #[cfg(target_os = "linux")]
-        if let Some(id) = get_linux_machine_id() {
-            components.push(id);
+        let os_id = get_linux_machine_id();
+
+        #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+        let os_id: Option<String> = None;
+
+        if let Some(id) = &os_id {
+            components.push((ComponentKind::OsInstall, id.clone()));
+        }
+
+        // Get MAC address
+        let mac = get_mac_address();
+        if let Some(mac) = &mac {
+            components.push((ComponentKind::Mac, mac.clone()));
         }
 
-        // Fallback: hostname
-        if let Some(hostname) = get_hostname() {
-            components.push(hostname);
+        // Legacy derivation order: MAC, CPU, platform id, hostname
+        let legacy = [mac, cpu, os_id, get_hostname()];
+        Self::from_raw_components(components).with_legacy_components(legacy.into_iter().flatten())
+    }
+
+    /// Build from raw component values (hashed before they are stored)
+    pub fn from_raw_components(raw: impl IntoIterator<Item = (ComponentKind, String)>) -> Self {
+        let components = raw
+            .into_iter()
+            .map(|(kind, value)| {
+                let digest = hex::encode(Sha256::digest(value.trim().as_bytes()));
+                (kind, digest[..COMPONENT_DIGEST_LEN].to_string())
+            })
+            .collect();
+        Self::from_digests(components)
+    }
+
+    /// Parse a fingerprint produced by [`MachineId::to_fingerprint`]
+    pub fn from_fingerprint(fingerprint: &str) -> Option<Self> {
+        let mut components = BTreeMap::new();
+        for part in fingerprint.trim().split(';').filter(|p| !p.is_empty()) {
+            let (kind, digest) = part.split_once('=')?;
+            let digest = digest.trim().to_lowercase();
+            if digest.len() != COMPONENT_DIGEST_LEN || hex::decode(&digest).is_err() {
+                return None;
+            }
+            components.insert(ComponentKind::parse(kind.trim())?, digest);
+        }
+        if components.is_empty() {
+            return None;
         }
+        Some(Self::from_digests(components))
+    }
 
-        // Compute hash
-        let combined = components.join("|");
-        let mut hasher = Sha256::new();
-        hasher.update(combined.as_bytes());
-        let hash: [u8; 32] = hasher.finalize().into();
+    fn from_digests(components: BTreeMap<ComponentKind, String>) -> Self {
+        let mut id = Self {
+            components,
+            hash: [0; 32],
+            legacy_hash: None,
+        };
+        // The machine hash is derived from the fingerprint, so the vendor can
+        // recompute it from the fingerprint alone
+        id.hash = Sha256::digest(id.to_fingerprint().as_bytes()).into();
+        id
+    }
+
+    /// Attach the legacy hash, computed from raw component values in legacy order
+    pub fn with_legacy_components(mut self, raw: impl IntoIterator<Item = String>) -> Self {
+        let combined = raw.into_iter().collect::<Vec<_>>().join("|");
+        self.legacy_hash = Some(Sha256::digest(combined.as_bytes()).into());
+        self
+    }
 
-        Self { components, hash }
+    /// Component digests as `cpu=<hex>;disk=<hex>;os=<hex>;mac=<hex>`
+    ///
+    /// Components that could not be read are omitted.
+    pub fn to_fingerprint(&self) -> String {
+        self.components
+            .iter()
+            .map(|(kind, digest)| format!("{}={}", kind.as_str(), digest))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    /// Compare against the components of another (bound) machine ID
+    pub fn compare(&self, bound: &MachineId) -> ComponentMatch {
+        let mut result = ComponentMatch {
+            matched: Vec::new(),
+            drifted: Vec::new(),
+            matched_weight: 0,
+            total_weight: 0,
+        };
+        for (kind, digest) in &bound.components {
+            result.total_weight += kind.weight();
+            if self.components.get(kind) == Some(digest) {
+                result.matched.push(*kind);
+                result.matched_weight += kind.weight();
+            } else {
+                result.drifted.push(*kind);
+            }
+        }
+        result
     }
 
     /// Get raw hash bytes
@@ -78,6 +233,11 @@ impl MachineId {
     pub fn to_full_hex(&self) -> String {
         hex::encode(self.hash)
     }
+
+    /// Legacy hash as hex string, for licenses issued before component fingerprints
+    pub fn to_legacy_hex(&self) -> Option<String> {
+        self.legacy_hash.map(hex::encode)
+    }
 }
 
 fn get_mac_address() -> Option<String> {
@@ -103,6 +263,72 @@ fn get_hostname() -> Option<String> {
     System::host_name()
 }
 
+fn get_disk_serial() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        // Serial of the first physical disk, in a stable order
+        let mut disks: Vec<_> = std::fs::read_dir("/sys/block")
+            .ok()?
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|name| !name.starts_with("loop") && !name.starts_with("ram"))
+            .collect();
+        disks.sort();
+        disks.into_iter().find_map(|name| {
+            ["device/serial", "serial", "device/wwid"]
+                .iter()
+                .find_map(|file| {
+                    std::fs::read_to_string(format!("/sys/block/{}/{}", name, file))
+                        .ok()
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                })
+        })
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::process::Command;
+
+        // wmic is deprecated and missing on recent Windows builds
+        let output = Command::new("powershell")
+            .args([
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "Get-CimInstance Win32_DiskDrive | Sort-Object Index | ForEach-Object { $_.SerialNumber }",
+            ])
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::process::Command;
+
+        let output = Command::new("system_profiler")
+            .args(["SPNVMeDataType", "SPSerialATADataType"])
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        stdout
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("Serial Number:"))
+            .map(str::trim)
+            .find(|serial| !serial.is_empty())
+            .map(str::to_string)
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+    None
+}
+
 #[cfg(target_os = "macos")]
 fn get_macos_serial() -> Option<String> {
     use std::process::Command;
@@ -197,4 +423,19 @@ mod tests {
             assert_eq!(part.len(), 4);
         }
     }
+
+    #[test]
+    fn test_fingerprint_roundtrip() {
+        let id = MachineId::from_raw_components([
+            (ComponentKind::Mac, "aa:bb:cc:dd:ee:ff".to_string()),
+            (ComponentKind::Cpu, "Intel Core i7:8".to_string()),
+        ]);
+        let fingerprint = id.to_fingerprint();
+        assert!(fingerprint.starts_with("cpu="));
+
+        let parsed = MachineId::from_fingerprint(&fingerprint).unwrap();
+        assert_eq!(parsed.to_hash(), id.to_hash());
+        assert!(MachineId::from_fingerprint("cpu=zz").is_none());
+        assert!(MachineId::from_fingerprint("").is_none());
+    }
 }
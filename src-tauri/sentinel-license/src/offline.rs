@@ -0,0 +1,319 @@
+//! Offline (air-gapped) activation via challenge/response files
+//!
+//! 1. [`generate_activation_request`] creates a challenge for this machine
+//!    (machine id, component fingerprint, random nonce) and remembers it.
+//! 2. The customer takes the challenge file to the activation portal, which
+//!    returns an [`ActivationResponse`] signed with the vendor key.
+//! 3. [`activate_from_response_file`] checks the response signature against the
+//!    embedded public key, that it answers the pending challenge of this
+//!    machine, and then activates the contained license as usual.
+//!
+//! The nonce is consumed on success, so a response cannot be replayed or used
+//! on another machine.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+use crate::crypto::get_embedded_public_key;
+use crate::machine_id::MachineId;
+use crate::storage::LicenseStorage;
+use crate::validator::ValidationResult;
+
+const REQUEST_VERSION: u32 = 1;
+const REQUEST_DOMAIN: &str = "sentinel-activation-request-v1";
+const RESPONSE_DOMAIN: &str = "sentinel-offline-activation-v1";
+
+#[derive(Error, Debug, PartialEq)]
+pub enum OfflineActivationError {
+    #[error("Invalid activation file: {0}")]
+    InvalidFormat(String),
+    #[error("Activation request checksum mismatch")]
+    RequestTampered,
+    #[error("Activation response signature is invalid")]
+    InvalidSignature,
+    #[error("Activation response is for a different machine")]
+    MachineMismatch,
+    #[error("Activation response does not answer the pending request")]
+    ChallengeMismatch,
+    #[error("No pending activation request")]
+    NoPendingRequest,
+}
+
+/// Challenge file taken to the activation portal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivationRequest {
+    pub version: u32,
+    /// Full machine id hash (hex)
+    pub machine_id: String,
+    /// Component fingerprint, lets the portal issue a drift-tolerant license
+    pub components: String,
+    /// Random challenge (hex), echoed by the response
+    pub nonce: String,
+    pub created_at: u64,
+    /// SHA-256 over the fields above, detects edits in transit
+    pub checksum: String,
+}
+
+/// Signed answer from the activation portal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivationResponse {
+    pub machine_id: String,
+    pub nonce: String,
+    /// License key (same format as online activation)
+    pub license: String,
+    /// Ed25519 signature (base64) over machine id, nonce and license
+    pub signature: String,
+}
+
+fn request_checksum(machine_id: &str, components: &str, nonce: &str, created_at: u64) -> String {
+    let canonical = format!(
+        "{}\n{}\n{}\n{}\n{}",
+        REQUEST_DOMAIN, machine_id, components, nonce, created_at
+    );
+    hex::encode(Sha256::digest(canonical.as_bytes()))
+}
+
+fn response_message(machine_id: &str, nonce: &str, license: &str) -> [u8; 32] {
+    let canonical = format!(
+        "{}\n{}\n{}\n{}",
+        RESPONSE_DOMAIN, machine_id, nonce, license
+    );
+    Sha256::digest(canonical.as_bytes()).into()
+}
+
+impl ActivationRequest {
+    /// Create a challenge for a machine
+    pub fn new(machine_id: &MachineId) -> Self {
+        let mut nonce = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let machine_id_hex = machine_id.to_full_hex();
+        let components = machine_id.to_fingerprint();
+        let nonce = hex::encode(nonce);
+        let created_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let checksum = request_checksum(&machine_id_hex, &components, &nonce, created_at);
+
+        Self {
+            version: REQUEST_VERSION,
+            machine_id: machine_id_hex,
+            components,
+            nonce,
+            created_at,
+            checksum,
+        }
+    }
+
+    /// Parse a challenge file and check its checksum (portal side)
+    pub fn parse(content: &str) -> Result<Self, OfflineActivationError> {
+        let request: Self = serde_json::from_str(content.trim())
+            .map_err(|e| OfflineActivationError::InvalidFormat(e.to_string()))?;
+        let expected = request_checksum(
+            &request.machine_id,
+            &request.components,
+            &request.nonce,
+            request.created_at,
+        );
+        if request.checksum != expected {
+            return Err(OfflineActivationError::RequestTampered);
+        }
+        Ok(request)
+    }
+
+    pub fn to_file_content(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+impl ActivationResponse {
+    /// Sign a response for a challenge (portal / license generator side)
+    pub fn sign(request: &ActivationRequest, license: &str, signing_key: &SigningKey) -> Self {
+        let message = response_message(&request.machine_id, &request.nonce, license);
+        Self {
+            machine_id: request.machine_id.clone(),
+            nonce: request.nonce.clone(),
+            license: license.to_string(),
+            signature: BASE64.encode(signing_key.sign(&message).to_bytes()),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, OfflineActivationError> {
+        serde_json::from_str(content.trim())
+            .map_err(|e| OfflineActivationError::InvalidFormat(e.to_string()))
+    }
+
+    pub fn to_file_content(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Check the signature and that this response answers `pending` for `machine_id`
+    ///
+    /// Returns the license key to activate.
+    pub fn verify(
+        &self,
+        pending: &ActivationRequest,
+        machine_id: &MachineId,
+        public_key: &VerifyingKey,
+    ) -> Result<&str, OfflineActivationError> {
+        let sig_bytes: [u8; 64] = BASE64
+            .decode(&self.signature)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(OfflineActivationError::InvalidSignature)?;
+        let message = response_message(&self.machine_id, &self.nonce, &self.license);
+        public_key
+            .verify(&message, &Signature::from_bytes(&sig_bytes))
+            .map_err(|_| OfflineActivationError::InvalidSignature)?;
+
+        if self.machine_id != machine_id.to_full_hex() || pending.machine_id != self.machine_id {
+            return Err(OfflineActivationError::MachineMismatch);
+        }
+        if self.nonce != pending.nonce {
+            return Err(OfflineActivationError::ChallengeMismatch);
+        }
+        Ok(&self.license)
+    }
+}
+
+/// Create an activation challenge for this machine and remember it
+///
+/// Returns the content of the challenge file to hand to the activation portal.
+/// A new request replaces any earlier pending one.
+pub fn generate_activation_request() -> Result<String, String> {
+    let request = ActivationRequest::new(&MachineId::generate());
+    let content = request.to_file_content();
+    LicenseStorage::save_challenge(&content)?;
+    Ok(content)
+}
+
+/// Activate from a response file returned by the activation portal
+pub fn activate_from_response_file(path: impl AsRef<Path>) -> ValidationResult {
+    let result = std::fs::read_to_string(path.as_ref())
+        .map_err(|e| format!("Failed to read activation response: {}", e))
+        .and_then(|content| verify_pending_response(&content).map_err(|e| e.to_string()));
+
+    let license = match result {
+        Ok(license) => license,
+        Err(e) => {
+            tracing::warn!("Offline activation rejected: {}", e);
+            return ValidationResult::Invalid(e);
+        }
+    };
+
+    let result = crate::activate(&license);
+    if matches!(result, ValidationResult::Valid) {
+        // Consume the challenge so the response cannot be replayed
+        if let Err(e) = LicenseStorage::remove_challenge() {
+            tracing::warn!("Failed to remove activation challenge: {}", e);
+        }
+    }
+    result
+}
+
+fn verify_pending_response(content: &str) -> Result<String, OfflineActivationError> {
+    let pending = LicenseStorage::load_challenge()
+        .ok_or(OfflineActivationError::NoPendingRequest)
+        .and_then(|c| ActivationRequest::parse(&c))?;
+    let response = ActivationResponse::parse(content)?;
+    let public_key =
+        get_embedded_public_key().map_err(|_| OfflineActivationError::InvalidSignature)?;
+
+    response
+        .verify(&pending, &MachineId::generate(), &public_key)
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{generate_keypair, sign_machine_license};
+    use crate::machine_id::ComponentKind;
+
+    fn machine(os: &str) -> MachineId {
+        MachineId::from_raw_components([
+            (ComponentKind::Cpu, "Intel i7:8".to_string()),
+            (ComponentKind::OsInstall, os.to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_offline_activation_round_trip() {
+        let keypair = generate_keypair();
+        let machine = machine("machine-guid");
+
+        // Customer side: challenge file
+        let request = ActivationRequest::new(&machine);
+        let challenge = request.to_file_content();
+
+        // Portal side: verify challenge, issue license and signed response
+        let received = ActivationRequest::parse(&challenge).unwrap();
+        let bound = MachineId::from_fingerprint(&received.components).unwrap();
+        assert_eq!(bound.to_full_hex(), received.machine_id);
+        let license = sign_machine_license(&bound, &keypair.signing_key, None).to_string();
+        let response =
+            ActivationResponse::sign(&received, &license, &keypair.signing_key).to_file_content();
+
+        // Customer side: verify response against the pending challenge
+        let response = ActivationResponse::parse(&response).unwrap();
+        assert_eq!(
+            response.verify(&request, &machine, &keypair.verifying_key),
+            Ok(license.as_str())
+        );
+    }
+
+    #[test]
+    fn test_response_is_bound_to_machine_and_challenge() {
+        let keypair = generate_keypair();
+        let machine_a = machine("machine-a");
+        let request = ActivationRequest::new(&machine_a);
+        let response = ActivationResponse::sign(&request, "license", &keypair.signing_key);
+
+        // Another machine, or a later challenge on the same machine
+        let machine_b = machine("machine-b");
+        assert_eq!(
+            response.verify(
+                &ActivationRequest::new(&machine_b),
+                &machine_b,
+                &keypair.verifying_key
+            ),
+            Err(OfflineActivationError::MachineMismatch)
+        );
+        assert_eq!(
+            response.verify(
+                &ActivationRequest::new(&machine_a),
+                &machine_a,
+                &keypair.verifying_key
+            ),
+            Err(OfflineActivationError::ChallengeMismatch)
+        );
+
+        // Tampered license or a foreign signing key
+        let tampered = ActivationResponse {
+            license: "other-license".to_string(),
+            ..response.clone()
+        };
+        assert_eq!(
+            tampered.verify(&request, &machine_a, &keypair.verifying_key),
+            Err(OfflineActivationError::InvalidSignature)
+        );
+        assert_eq!(
+            response.verify(&request, &machine_a, &generate_keypair().verifying_key),
+            Err(OfflineActivationError::InvalidSignature)
+        );
+
+        // Edited challenge
+        let mut edited = request.clone();
+        edited.machine_id = machine_b.to_full_hex();
+        assert_eq!(
+            ActivationRequest::parse(&edited.to_file_content()),
+            Err(OfflineActivationError::RequestTampered)
+        );
+    }
+}
@@ -8,12 +8,20 @@ use std::str::FromStr;
 /// License file name (obfuscated)
 const LICENSE_FILE: &str = ".sentinel_auth";
 
+/// Pending offline activation challenge
+const CHALLENGE_FILE: &str = ".sentinel_challenge";
+
 /// License storage manager
 pub struct LicenseStorage;
 
 impl LicenseStorage {
     /// Get license file path
     fn get_license_path() -> Option<PathBuf> {
+        Self::get_storage_path(LICENSE_FILE)
+    }
+
+    /// Get path of a file in the app data directory
+    fn get_storage_path(file_name: &str) -> Option<PathBuf> {
         // Store in app data directory
         let data_dir = dirs::data_local_dir()?;
         let app_dir = data_dir.join("sentinel-ai");
@@ -23,7 +31,7 @@ impl LicenseStorage {
             fs::create_dir_all(&app_dir).ok()?;
         }
 
-        Some(app_dir.join(LICENSE_FILE))
+        Some(app_dir.join(file_name))
     }
 
     /// Load license from disk
@@ -72,6 +80,35 @@ impl LicenseStorage {
         Ok(())
     }
 
+    /// Save the pending offline activation challenge
+    pub fn save_challenge(challenge: &str) -> Result<(), String> {
+        let path = Self::get_storage_path(CHALLENGE_FILE)
+            .ok_or_else(|| "Cannot determine license storage path".to_string())?;
+
+        fs::write(&path, Self::encrypt_storage(challenge))
+            .map_err(|e| format!("Failed to write activation challenge: {}", e))
+    }
+
+    /// Load the pending offline activation challenge
+    pub fn load_challenge() -> Option<String> {
+        let path = Self::get_storage_path(CHALLENGE_FILE)?;
+        let content = fs::read_to_string(path).ok()?;
+        Self::decrypt_storage(&content)
+    }
+
+    /// Remove the pending challenge once it has been used
+    pub fn remove_challenge() -> Result<(), String> {
+        let path = Self::get_storage_path(CHALLENGE_FILE)
+            .ok_or_else(|| "Cannot determine license storage path".to_string())?;
+
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove activation challenge: {}", e))?;
+        }
+
+        Ok(())
+    }
+
     /// Check if license file exists
     pub fn exists() -> bool {
         Self::get_license_path()
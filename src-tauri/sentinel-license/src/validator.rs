@@ -1,9 +1,10 @@
 //! License validation with multiple checks
 
 use crate::crypto::{verify_license, LicenseKey};
-use crate::machine_id::MachineId;
+use crate::machine_id::{ComponentKind, ComponentMatch, MachineId};
 use crate::obfuscate;
 use std::str::FromStr;
+use std::sync::Mutex;
 
 #[cfg(not(debug_assertions))]
 use crate::anti_debug;
@@ -27,9 +28,26 @@ pub enum LicenseStatus {
     Error(String),
 }
 
+/// Default share of the bound component weight that must still match
+pub const DEFAULT_MATCH_THRESHOLD: f64 = 0.6;
+
+/// How the current machine relates to the machine a license is bound to
+#[derive(Debug, Clone, PartialEq)]
+pub enum MachineMatch {
+    /// Machine ID is identical
+    Exact,
+    /// Some components drifted, but enough weight still matches
+    Tolerated(ComponentMatch),
+    /// Different machine (component comparison, if the license carries one)
+    Mismatch(Option<ComponentMatch>),
+}
+
 /// License validator with multiple verification points
 pub struct LicenseValidator {
     machine_id: MachineId,
+    match_threshold: f64,
+    /// Components that drifted in the last tolerated validation
+    last_drift: Mutex<Vec<ComponentKind>>,
     #[allow(dead_code)]
     check_count: u32,
 }
@@ -37,12 +55,62 @@ pub struct LicenseValidator {
 impl LicenseValidator {
     /// Create new validator
     pub fn new() -> Self {
+        Self::with_machine_id(MachineId::generate())
+    }
+
+    /// Create a validator for a given machine ID
+    pub fn with_machine_id(machine_id: MachineId) -> Self {
         Self {
-            machine_id: MachineId::generate(),
+            machine_id,
+            match_threshold: DEFAULT_MATCH_THRESHOLD,
+            last_drift: Mutex::new(Vec::new()),
             check_count: 0,
         }
     }
 
+    /// Set the share of bound component weight (0.0..=1.0) that must match
+    pub fn with_match_threshold(mut self, threshold: f64) -> Self {
+        self.match_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Components that drifted when the license was last accepted with tolerance
+    pub fn drifted_components(&self) -> Vec<ComponentKind> {
+        self.last_drift
+            .lock()
+            .map(|d| d.clone())
+            .unwrap_or_default()
+    }
+
+    /// Compare this machine against the one the license is bound to
+    pub fn match_machine(&self, license: &LicenseKey) -> MachineMatch {
+        let expected_id = self.machine_id.to_full_hex();
+        // Licenses issued before component fingerprints carry the legacy hash
+        let legacy_id = self.machine_id.to_legacy_hex();
+        if hash_matches(&license.machine_id, &expected_id)
+            || legacy_id.is_some_and(|legacy| hash_matches(&license.machine_id, &legacy))
+        {
+            return MachineMatch::Exact;
+        }
+
+        // Component fingerprint must belong to the bound machine ID
+        let Some(bound) = license
+            .components
+            .as_deref()
+            .and_then(MachineId::from_fingerprint)
+            .filter(|bound| bound.to_full_hex() == license.machine_id)
+        else {
+            return MachineMatch::Mismatch(None);
+        };
+
+        let comparison = self.machine_id.compare(&bound);
+        if comparison.meets(self.match_threshold) {
+            MachineMatch::Tolerated(comparison)
+        } else {
+            MachineMatch::Mismatch(Some(comparison))
+        }
+    }
+
     /// Validate license from string
     pub fn validate_str(&self, license_str: &str) -> ValidationResult {
         // Parse license
@@ -64,23 +132,24 @@ impl LicenseValidator {
             return ValidationResult::Invalid(obfuscate::decrypt_str("debug_detected"));
         }
 
-        // Check 2: Machine ID match
-        let machine_hash = self.machine_id.to_hash();
-        let expected_id = hex::encode(machine_hash);
-
-        // Support both full hash and partial hash (display format + zeros)
-        let id_match = if license.machine_id == expected_id {
-            true
-        } else if license.machine_id.len() == 64 {
-            // Check if first 16 chars match (display format: 8 bytes = 16 hex chars)
-            license.machine_id[..16] == expected_id[..16]
-                && license.machine_id[16..].chars().all(|c| c == '0')
-        } else {
-            false
-        };
-
-        if !id_match {
-            return ValidationResult::Invalid(obfuscate::decrypt_str("machine_mismatch"));
+        // Check 2: Machine ID match (tolerating minority component drift)
+        match self.match_machine(license) {
+            MachineMatch::Exact => {}
+            MachineMatch::Tolerated(comparison) => {
+                tracing::warn!(
+                    "License accepted with drifted machine components: {:?}",
+                    comparison.drifted
+                );
+                if let Ok(mut drift) = self.last_drift.lock() {
+                    *drift = comparison.drifted;
+                }
+            }
+            MachineMatch::Mismatch(comparison) => {
+                if let Some(comparison) = comparison {
+                    tracing::debug!("Machine components drifted: {:?}", comparison.drifted);
+                }
+                return ValidationResult::Invalid(obfuscate::decrypt_str("machine_mismatch"));
+            }
         }
 
         // Get the machine_id bytes from license for signature verification
@@ -124,14 +193,20 @@ impl LicenseValidator {
     /// Perform quick validation (for multi-point checks)
     #[inline]
     pub fn quick_check(&self, license: &LicenseKey) -> bool {
-        // Simple machine ID check without full signature verification
-        let machine_hash = self.machine_id.to_hash();
-        let expected_id = hex::encode(machine_hash);
-
-        license.machine_id == expected_id
+        // Machine ID check without full signature verification
+        !matches!(self.match_machine(license), MachineMatch::Mismatch(_))
     }
 }
 
+/// Support both full hash and partial hash (display format + zeros)
+fn hash_matches(license_id: &str, expected: &str) -> bool {
+    license_id == expected
+        || (license_id.len() == 64
+            && expected.len() == 64
+            && license_id[..16] == expected[..16]
+            && license_id[16..].chars().all(|c| c == '0'))
+}
+
 impl Default for LicenseValidator {
     fn default() -> Self {
         Self::new()
@@ -163,6 +238,7 @@ pub fn verify_feature_key(key: u64, feature_id: u32) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sha2::{Digest, Sha256};
 
     #[test]
     fn test_validator_creation() {
@@ -180,4 +256,163 @@ mod tests {
         let result = validator.validate_str("invalid_license");
         assert!(matches!(result, ValidationResult::Invalid(_)));
     }
+
+    fn machine(cpu: &str, disk: &str, os: &str, mac: &str) -> MachineId {
+        MachineId::from_raw_components([
+            (ComponentKind::Cpu, cpu.to_string()),
+            (ComponentKind::Disk, disk.to_string()),
+            (ComponentKind::OsInstall, os.to_string()),
+            (ComponentKind::Mac, mac.to_string()),
+        ])
+    }
+
+    fn bound_license(bound: &MachineId) -> LicenseKey {
+        LicenseKey {
+            machine_id: bound.to_full_hex(),
+            signature: String::new(),
+            metadata: None,
+            components: Some(bound.to_fingerprint()),
+        }
+    }
+
+    #[test]
+    fn test_one_component_drift_is_accepted() {
+        let bound = machine("Intel i7:8", "S3R1AL", "machine-guid", "aa:bb:cc:dd:ee:ff");
+        let license = bound_license(&bound);
+
+        // Swapped NIC
+        let validator = LicenseValidator::with_machine_id(machine(
+            "Intel i7:8",
+            "S3R1AL",
+            "machine-guid",
+            "11:22:33:44:55:66",
+        ));
+        match validator.match_machine(&license) {
+            MachineMatch::Tolerated(comparison) => {
+                assert_eq!(comparison.drifted, vec![ComponentKind::Mac]);
+                assert_eq!((comparison.matched_weight, comparison.total_weight), (8, 9));
+            }
+            other => panic!("expected tolerated match, got {:?}", other),
+        }
+        assert!(validator.quick_check(&license));
+
+        // Same machine is an exact match
+        let validator = LicenseValidator::with_machine_id(bound.clone());
+        assert_eq!(validator.match_machine(&license), MachineMatch::Exact);
+    }
+
+    #[test]
+    fn test_majority_drift_is_rejected() {
+        let bound = machine("Intel i7:8", "S3R1AL", "machine-guid", "aa:bb:cc:dd:ee:ff");
+        let license = bound_license(&bound);
+
+        // New disk and OS install: only CPU and MAC still match
+        let validator = LicenseValidator::with_machine_id(machine(
+            "Intel i7:8",
+            "OTHER",
+            "reinstalled-guid",
+            "aa:bb:cc:dd:ee:ff",
+        ));
+        match validator.match_machine(&license) {
+            MachineMatch::Mismatch(Some(comparison)) => {
+                assert_eq!(
+                    comparison.drifted,
+                    vec![ComponentKind::Disk, ComponentKind::OsInstall]
+                );
+            }
+            other => panic!("expected mismatch, got {:?}", other),
+        }
+        assert!(!validator.quick_check(&license));
+        assert!(matches!(
+            validator.validate(&license),
+            ValidationResult::Invalid(_)
+        ));
+
+        // A stricter threshold also rejects the single NIC swap
+        let validator = LicenseValidator::with_machine_id(machine(
+            "Intel i7:8",
+            "S3R1AL",
+            "machine-guid",
+            "11:22:33:44:55:66",
+        ))
+        .with_match_threshold(1.0);
+        assert!(matches!(
+            validator.match_machine(&license),
+            MachineMatch::Mismatch(Some(_))
+        ));
+
+        // Licenses without a fingerprint still require an exact match
+        let unbound = LicenseKey {
+            components: None,
+            ..license
+        };
+        assert_eq!(
+            validator.match_machine(&unbound),
+            MachineMatch::Mismatch(None)
+        );
+    }
+
+    #[test]
+    fn test_cpu_only_match_is_rejected() {
+        // Bound while disk and OS install were unreadable
+        let bound = MachineId::from_raw_components([
+            (ComponentKind::Cpu, "Intel i7:8".to_string()),
+            (ComponentKind::Mac, "aa:bb:cc:dd:ee:ff".to_string()),
+        ]);
+        let license = bound_license(&bound);
+
+        // Another machine with the same CPU model scores 2/3 but matches no anchor
+        let validator = LicenseValidator::with_machine_id(machine(
+            "Intel i7:8",
+            "OTHER",
+            "other-guid",
+            "11:22:33:44:55:66",
+        ));
+        match validator.match_machine(&license) {
+            MachineMatch::Mismatch(Some(comparison)) => {
+                assert_eq!(comparison.matched, vec![ComponentKind::Cpu]);
+                assert_eq!((comparison.matched_weight, comparison.total_weight), (2, 3));
+            }
+            other => panic!("expected mismatch, got {:?}", other),
+        }
+        assert!(!validator.quick_check(&license));
+    }
+
+    #[test]
+    fn test_legacy_derivation_license_still_matches() {
+        let legacy_components = [
+            "aa:bb:cc:dd:ee:ff",
+            "Intel i7:8",
+            "machine-guid",
+            "build-host",
+        ]
+        .map(String::from);
+        let current = machine("Intel i7:8", "S3R1AL", "machine-guid", "aa:bb:cc:dd:ee:ff")
+            .with_legacy_components(legacy_components.clone());
+
+        // Issued before component fingerprints: sha256(mac|cpu|os|hostname), no components
+        let legacy_hash: [u8; 32] = Sha256::digest(legacy_components.join("|").as_bytes()).into();
+        let keypair = crate::crypto::generate_keypair();
+        let license = crate::crypto::sign_license(&legacy_hash, &keypair.signing_key, None);
+        assert!(license.components.is_none());
+        assert_ne!(license.machine_id, current.to_full_hex());
+
+        let validator = LicenseValidator::with_machine_id(current);
+        assert_eq!(validator.match_machine(&license), MachineMatch::Exact);
+        assert!(validator.quick_check(&license));
+
+        // Display-format activation (first 16 hex chars + zeros) of the legacy hash
+        let partial = LicenseKey {
+            machine_id: format!("{}{}", &license.machine_id[..16], "0".repeat(48)),
+            ..license.clone()
+        };
+        assert_eq!(validator.match_machine(&partial), MachineMatch::Exact);
+
+        // A different machine's legacy license still mismatches
+        let other = crate::crypto::sign_license(&[0x42; 32], &keypair.signing_key, None);
+        assert_eq!(
+            validator.match_machine(&other),
+            MachineMatch::Mismatch(None)
+        );
+    }
 }
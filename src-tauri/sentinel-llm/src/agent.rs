@@ -30,6 +30,8 @@ pub fn validate_config(config: &LlmConfig) -> Result<()> {
         ));
     }
 
+    config.header_map()?;
+
     Ok(())
 }
 
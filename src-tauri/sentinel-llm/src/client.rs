@@ -3,7 +3,7 @@
 use anyhow::{anyhow, Result};
 use futures::StreamExt;
 use rig::agent::MultiTurnStreamItem;
-use rig::client::{CompletionClient, ProviderClient};
+use rig::client::CompletionClient;
 use rig::completion::Message;
 use rig::providers::gemini::completion::gemini_api_types::{
     AdditionalParameters, GenerationConfig,
@@ -285,8 +285,9 @@ impl LlmClient {
 
         let api_key = self.config.api_key.clone().unwrap_or_default();
 
-        let mut builder =
-            deepseek::Client::<rig::http_client::ReqwestClient>::builder().api_key(api_key);
+        let mut builder = deepseek::Client::<rig::http_client::ReqwestClient>::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?);
 
         if let Some(base_url) = &self.config.base_url {
             builder = builder.base_url(base_url);
@@ -324,6 +325,7 @@ impl LlmClient {
             );
             let client: openai::CompletionsClient = openai::Client::builder()
                 .api_key(api_key)
+                .http_headers(self.config.header_map()?)
                 .base_url(base_url)
                 .build()
                 .map_err(|e| anyhow::anyhow!("Failed to build OpenAI client: {:?}", e))?
@@ -337,6 +339,7 @@ impl LlmClient {
             info!("Using Responses API for official OpenAI");
             let client: openai::Client = openai::Client::builder()
                 .api_key(api_key)
+                .http_headers(self.config.header_map()?)
                 .build()
                 .map_err(|e| anyhow::anyhow!("Failed to build OpenAI client: {:?}", e))?;
 
@@ -364,8 +367,9 @@ impl LlmClient {
             .or_else(|| std::env::var("MOONSHOT_API_KEY").ok())
             .ok_or_else(|| anyhow::anyhow!("MOONSHOT_API_KEY not set"))?;
 
-        let mut builder =
-            moonshot::Client::<rig::http_client::ReqwestClient>::builder().api_key(api_key);
+        let mut builder = moonshot::Client::<rig::http_client::ReqwestClient>::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?);
 
         if let Some(base_url) = &self.config.base_url {
             builder = builder.base_url(base_url);
@@ -397,8 +401,9 @@ impl LlmClient {
         let api_key = std::env::var("ANTHROPIC_API_KEY")
             .map_err(|_| anyhow::anyhow!("ANTHROPIC_API_KEY not set"))?;
 
-        let mut builder =
-            anthropic::Client::<rig::http_client::ReqwestClient>::builder().api_key(api_key);
+        let mut builder = anthropic::Client::<rig::http_client::ReqwestClient>::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?);
 
         // 检查是否设置了自定义 base_url
         if let Ok(base_url) = std::env::var("ANTHROPIC_API_BASE") {
@@ -430,7 +435,13 @@ impl LlmClient {
         timeout: std::time::Duration,
     ) -> Result<String> {
         use rig::providers::gemini;
-        let client = gemini::Client::from_env();
+        let api_key = std::env::var("GEMINI_API_KEY")
+            .map_err(|_| anyhow::anyhow!("GEMINI_API_KEY not set"))?;
+        let client = gemini::Client::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build Gemini client: {:?}", e))?;
         let gen_cfg = GenerationConfig::default();
         let cfg = AdditionalParameters::default().with_config(gen_cfg);
         let builder = client
@@ -451,7 +462,15 @@ impl LlmClient {
         timeout: std::time::Duration,
     ) -> Result<String> {
         use rig::providers::ollama;
-        let client = ollama::Client::from_env();
+        let mut builder = ollama::Client::builder()
+            .api_key(rig::client::Nothing)
+            .http_headers(self.config.header_map()?);
+        if let Ok(base_url) = std::env::var("OLLAMA_API_BASE_URL") {
+            builder = builder.base_url(&base_url);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build Ollama client: {:?}", e))?;
         let builder = client.agent(model).preamble(preamble);
         let agent = self.apply_generation_settings(builder).build();
         self.execute_chat(agent, user_message, chat_history, timeout)
@@ -472,8 +491,9 @@ impl LlmClient {
             .or_else(|_| std::env::var("OPENAI_API_KEY"))
             .map_err(|_| anyhow::anyhow!("DEEPSEEK_API_KEY not set"))?;
 
-        let mut builder =
-            deepseek::Client::<rig::http_client::ReqwestClient>::builder().api_key(api_key);
+        let mut builder = deepseek::Client::<rig::http_client::ReqwestClient>::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?);
 
         // Use custom base_url if configured
         if let Some(base_url) = &self.config.base_url {
@@ -500,7 +520,13 @@ impl LlmClient {
         timeout: std::time::Duration,
     ) -> Result<String> {
         use rig::providers::openrouter;
-        let client = openrouter::Client::from_env();
+        let api_key = std::env::var("OPENROUTER_API_KEY")
+            .map_err(|_| anyhow::anyhow!("OPENROUTER_API_KEY not set"))?;
+        let client = openrouter::Client::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build OpenRouter client: {:?}", e))?;
         let builder = client.agent(model).preamble(preamble);
         let agent = self.apply_generation_settings(builder).build();
         self.execute_chat(agent, user_message, chat_history, timeout)
@@ -516,7 +542,13 @@ impl LlmClient {
         timeout: std::time::Duration,
     ) -> Result<String> {
         use rig::providers::xai;
-        let client = xai::Client::from_env();
+        let api_key =
+            std::env::var("XAI_API_KEY").map_err(|_| anyhow::anyhow!("XAI_API_KEY not set"))?;
+        let client = xai::Client::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build xAI client: {:?}", e))?;
         let builder = client.agent(model).preamble(preamble);
         let agent = self.apply_generation_settings(builder).build();
         self.execute_chat(agent, user_message, chat_history, timeout)
@@ -532,7 +564,13 @@ impl LlmClient {
         timeout: std::time::Duration,
     ) -> Result<String> {
         use rig::providers::groq;
-        let client = groq::Client::from_env();
+        let api_key =
+            std::env::var("GROQ_API_KEY").map_err(|_| anyhow::anyhow!("GROQ_API_KEY not set"))?;
+        let client = groq::Client::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build Groq client: {:?}", e))?;
         let builder = client.agent(model).preamble(preamble);
         let agent = self.apply_generation_settings(builder).build();
         self.execute_chat(agent, user_message, chat_history, timeout)
@@ -606,3 +644,101 @@ impl LlmClient {
         Ok(content)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::StreamingLlmClient;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// 接收一个请求并返回其请求头（名称转为小写），响应固定为 500
+    async fn capture_request_headers(listener: &TcpListener) -> Vec<(String, String)> {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+            let n = socket.read(&mut chunk).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        let _ = socket
+            .write_all(
+                b"HTTP/1.1 500 Internal Server Error\r\ncontent-type: application/json\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}",
+            )
+            .await;
+
+        let head = String::from_utf8_lossy(&buf).to_string();
+        head.split("\r\n\r\n")
+            .next()
+            .unwrap_or_default()
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_once(':'))
+            .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+            .collect()
+    }
+
+    fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[tokio::test]
+    async fn test_custom_headers_reach_request() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = LlmConfig::new("custom-gateway", "test-model")
+            .with_api_key("sk-test")
+            .with_base_url(format!("http://{}", listener.local_addr().unwrap()))
+            .with_header("X-Tenant-Id", "tenant-42")
+            .with_headers([("X-Trace-Id", "trace-1")])
+            .with_timeout(5);
+
+        // 非流式调用
+        let client = LlmClient::new(config.clone());
+        let (headers, _) = tokio::join!(
+            capture_request_headers(&listener),
+            client.completion(None, "ping")
+        );
+        assert_eq!(header(&headers, "x-tenant-id"), Some("tenant-42"));
+        assert_eq!(header(&headers, "x-trace-id"), Some("trace-1"));
+        // 提供商自身的请求头仍然保留
+        assert_eq!(header(&headers, "authorization"), Some("Bearer sk-test"));
+
+        // 流式调用
+        let client = StreamingLlmClient::new(config);
+        let (headers, _) = tokio::join!(
+            capture_request_headers(&listener),
+            client.stream_chat(None, "ping", &[], None, |_| true)
+        );
+        assert_eq!(header(&headers, "x-tenant-id"), Some("tenant-42"));
+        assert_eq!(header(&headers, "x-trace-id"), Some("trace-1"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_header_name_is_rejected() {
+        let config = LlmConfig::new("custom-gateway", "test-model")
+            .with_api_key("sk-test")
+            .with_base_url("http://127.0.0.1:9")
+            .with_header("Bad Header", "value");
+        assert!(config.header_map().is_err());
+        assert!(crate::agent::validate_config(&config).is_err());
+
+        let err = LlmClient::new(config)
+            .completion(None, "ping")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Bad Header"));
+
+        let config = LlmConfig::default().with_header("X-Ok", "line\nbreak");
+        assert!(config.header_map().is_err());
+        assert!(LlmConfig::default()
+            .with_header("X-Ok", "fine")
+            .header_map()
+            .is_ok());
+    }
+}
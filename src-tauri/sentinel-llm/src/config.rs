@@ -1,6 +1,9 @@
 //! LLM 配置模块
 
+use anyhow::{anyhow, Result};
+use http::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// LLM 配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +28,9 @@ pub struct LlmConfig {
     pub max_tokens: Option<u32>,
     /// 最大对话轮数（工具调用循环次数）
     pub max_turns: Option<usize>,
+    /// 自定义请求头（附加到所有提供商的请求中，与提供商自身要求的请求头合并）
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
 }
 
 impl Default for LlmConfig {
@@ -40,6 +46,7 @@ impl Default for LlmConfig {
             temperature: Some(0.7),
             max_tokens: Some(4096),
             max_turns: Some(100),
+            headers: BTreeMap::new(),
         }
     }
 }
@@ -123,6 +130,36 @@ impl LlmConfig {
         self.max_turns.unwrap_or(100)
     }
 
+    /// 添加自定义请求头（同名请求头后设置的覆盖先设置的）
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// 批量添加自定义请求头
+    pub fn with_headers<K, V>(mut self, headers: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.headers
+            .extend(headers.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+
+    /// 校验并转换自定义请求头；请求头名称或值不合法时返回错误
+    pub fn header_map(&self) -> Result<HeaderMap> {
+        let mut map = HeaderMap::with_capacity(self.headers.len());
+        for (name, value) in &self.headers {
+            let header_name = HeaderName::from_bytes(name.trim().as_bytes())
+                .map_err(|_| anyhow!("Invalid custom header name: '{}'", name))?;
+            let header_value = HeaderValue::from_str(value)
+                .map_err(|_| anyhow!("Invalid value for custom header '{}'", name))?;
+            map.insert(header_name, header_value);
+        }
+        Ok(map)
+    }
+
     /// 获取实际使用的 rig provider（优先使用 rig_provider，否则使用 provider）
     pub fn get_effective_rig_provider(&self) -> String {
         self.rig_provider
@@ -36,6 +36,7 @@ mod client;
 mod config;
 pub mod log;
 mod message;
+pub mod normalize;
 pub mod service;
 mod streaming;
 pub mod types;
@@ -47,13 +48,14 @@ pub use config::LlmConfig;
 pub use log::{log_request, log_request_with_image, log_response, write_llm_log};
 pub use message::ImageAttachment;
 pub use message::{build_user_message, convert_chat_history, parse_image_from_json, ChatMessage};
+pub use normalize::{ProviderStreamEvent, StreamNormalizer};
 pub use service::{AiService, StreamChunk};
 pub use streaming::{StreamContent, StreamingLlmClient};
 pub use types::{
     AiConfig, AiToolCall, SchedulerConfig, SchedulerStage, StreamError, StreamMessage,
     TaskProgressMessage, TaskStreamMessage, ToolCallResultMessage,
 };
-pub use usage::{calculate_cost, TokenUsage};
+pub use usage::{calculate_cost, count_tokens, estimate_usage, TokenEncoding, TokenUsage};
 
 // Re-export rig types for convenience
 pub use rig::completion::Message;
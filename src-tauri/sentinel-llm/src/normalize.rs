@@ -0,0 +1,202 @@
+//! 流式事件归一化
+//!
+//! 不同提供商经 rig-core 解码后的流事件形态并不一致：
+//! - OpenAI 兼容接口（OpenAI / DeepSeek / Moonshot / OpenRouter / ModelScope）先发送工具名增量，再分片发送参数增量，最后给出完整调用；
+//! - Gemini 只发送完整的 functionCall，没有任何增量；
+//! - 部分提供商按轮次上报用量，部分只在最终响应里给出汇总用量，且可能不发送最终响应就结束流。
+//!
+//! `StreamNormalizer` 把这些差异抹平，保证调用方对所有提供商看到相同的 `StreamContent` 序列：
+//! 每个工具调用严格按 `ToolCallStart` → `ToolCallDelta`* → `ToolCallComplete` 发出，
+//! 用量只上报一次（不重复计数），`Done` 在流结束时恰好发出一次。
+
+use std::collections::HashMap;
+
+use crate::streaming::StreamContent;
+
+/// 提供商原始流事件（与 rig-core 的 `StreamedAssistantContent` 等结构一一对应）
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProviderStreamEvent {
+    /// 文本增量
+    Text(String),
+    /// 推理增量
+    Reasoning(String),
+    /// 工具名增量（OpenAI 兼容接口的首个 tool_calls 分片）
+    ToolCallName { id: String, name: String },
+    /// 工具参数增量
+    ToolCallArguments { id: String, delta: String },
+    /// 完整的工具调用
+    ToolCall {
+        id: String,
+        name: String,
+        arguments: String,
+    },
+    /// 工具执行结果
+    ToolResult { id: String, result: String },
+    /// 单轮用量
+    TurnUsage {
+        input_tokens: u64,
+        output_tokens: u64,
+    },
+    /// 最终响应（累计用量）
+    Final {
+        input_tokens: u64,
+        output_tokens: u64,
+    },
+}
+
+#[derive(Debug, Default)]
+struct PendingToolCall {
+    name: Option<String>,
+    started: bool,
+    /// 工具名到达前收到的参数分片
+    buffered_arguments: String,
+    streamed_arguments: bool,
+}
+
+/// 流式事件归一化器
+#[derive(Debug, Default)]
+pub struct StreamNormalizer {
+    tool_calls: HashMap<String, PendingToolCall>,
+    reported_input_tokens: u64,
+    reported_output_tokens: u64,
+    done: bool,
+}
+
+impl StreamNormalizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 是否已发出 `Done`
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// 处理一条原始事件，返回需要向调用方发出的归一化事件
+    pub fn push(&mut self, event: ProviderStreamEvent) -> Vec<StreamContent> {
+        if self.done {
+            return Vec::new();
+        }
+
+        let mut out = Vec::new();
+        match event {
+            ProviderStreamEvent::Text(text) => {
+                if !text.is_empty() {
+                    out.push(StreamContent::Text(text));
+                }
+            }
+            ProviderStreamEvent::Reasoning(text) => {
+                if !text.is_empty() {
+                    out.push(StreamContent::Reasoning(text));
+                }
+            }
+            ProviderStreamEvent::ToolCallName { id, name } => {
+                let pending = self.tool_calls.entry(id.clone()).or_default();
+                if name.is_empty() || pending.started {
+                    return out;
+                }
+                pending.name = Some(name.clone());
+                pending.started = true;
+                out.push(StreamContent::ToolCallStart {
+                    id: id.clone(),
+                    name,
+                });
+                if !pending.buffered_arguments.is_empty() {
+                    pending.streamed_arguments = true;
+                    out.push(StreamContent::ToolCallDelta {
+                        id,
+                        delta: std::mem::take(&mut pending.buffered_arguments),
+                    });
+                }
+            }
+            ProviderStreamEvent::ToolCallArguments { id, delta } => {
+                if delta.is_empty() {
+                    return out;
+                }
+                let pending = self.tool_calls.entry(id.clone()).or_default();
+                if pending.started {
+                    pending.streamed_arguments = true;
+                    out.push(StreamContent::ToolCallDelta { id, delta });
+                } else {
+                    pending.buffered_arguments.push_str(&delta);
+                }
+            }
+            ProviderStreamEvent::ToolCall {
+                id,
+                name,
+                arguments,
+            } => {
+                let pending = self.tool_calls.remove(&id).unwrap_or_default();
+                let name = if name.is_empty() {
+                    pending.name.clone().unwrap_or_default()
+                } else {
+                    name
+                };
+                if !pending.started {
+                    out.push(StreamContent::ToolCallStart {
+                        id: id.clone(),
+                        name: name.clone(),
+                    });
+                }
+                // 未流式发送过参数的提供商（如 Gemini）补发一次完整参数增量
+                if !pending.streamed_arguments && !arguments.is_empty() {
+                    out.push(StreamContent::ToolCallDelta {
+                        id: id.clone(),
+                        delta: arguments.clone(),
+                    });
+                }
+                out.push(StreamContent::ToolCallComplete {
+                    id,
+                    name,
+                    arguments,
+                });
+            }
+            ProviderStreamEvent::ToolResult { id, result } => {
+                out.push(StreamContent::ToolResult { id, result });
+            }
+            ProviderStreamEvent::TurnUsage {
+                input_tokens,
+                output_tokens,
+            } => {
+                if input_tokens > 0 || output_tokens > 0 {
+                    self.reported_input_tokens += input_tokens;
+                    self.reported_output_tokens += output_tokens;
+                    out.push(StreamContent::Usage {
+                        input_tokens: input_tokens as u32,
+                        output_tokens: output_tokens as u32,
+                        estimated: false,
+                    });
+                }
+            }
+            ProviderStreamEvent::Final {
+                input_tokens,
+                output_tokens,
+            } => {
+                // 最终汇总只补报差额，避免与按轮次上报的用量重复
+                let remaining_input = input_tokens.saturating_sub(self.reported_input_tokens);
+                let remaining_output = output_tokens.saturating_sub(self.reported_output_tokens);
+                if remaining_input > 0 || remaining_output > 0 {
+                    self.reported_input_tokens += remaining_input;
+                    self.reported_output_tokens += remaining_output;
+                    out.push(StreamContent::Usage {
+                        input_tokens: remaining_input as u32,
+                        output_tokens: remaining_output as u32,
+                        estimated: false,
+                    });
+                }
+                self.done = true;
+                out.push(StreamContent::Done);
+            }
+        }
+        out
+    }
+
+    /// 流正常结束（提供商关闭连接）时调用，补发未发出的 `Done`
+    pub fn finish(&mut self) -> Vec<StreamContent> {
+        if self.done {
+            return Vec::new();
+        }
+        self.done = true;
+        vec![StreamContent::Done]
+    }
+}
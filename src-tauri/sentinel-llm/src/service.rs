@@ -21,7 +21,7 @@ use crate::config::LlmConfig;
 use crate::log::{build_log_session_id, log_error_response, log_request, log_response};
 use crate::message::{ChatMessage, ImageAttachment};
 use crate::types::AiConfig;
-use crate::usage::TokenUsage;
+use crate::usage::{prompt_text, TokenUsage};
 
 /// AI 服务 - 无应用依赖版本
 #[derive(Clone)]
@@ -103,6 +103,12 @@ impl AiService {
             )
             .await?;
 
+        if usage.total_tokens == 0 {
+            let prompt = prompt_text(system_prompt, history, user_prompt);
+            usage = usage.or_estimate(&self.config.model, &prompt, &content);
+            usage.estimate_cost(&self.config.provider, &self.config.model);
+        }
+
         Ok(CompletionResponse { content, usage })
     }
 
@@ -2,7 +2,7 @@
 use anyhow::{anyhow, Result};
 use futures::StreamExt;
 use rig::agent::MultiTurnStreamItem;
-use rig::client::{CompletionClient, ProviderClient};
+use rig::client::CompletionClient;
 use rig::completion::Message;
 use rig::providers::gemini::completion::gemini_api_types::{
     AdditionalParameters, GenerationConfig,
@@ -19,10 +19,12 @@ use crate::log::{
     log_turn_summary,
 };
 use crate::message::{build_user_message, convert_chat_history, ChatMessage, ImageAttachment};
+use crate::normalize::{ProviderStreamEvent, StreamNormalizer};
+use crate::usage::{estimate_usage, prompt_text, ToolTurnUsage};
 use sentinel_tools::DynamicTool;
 
 /// 流式内容类型
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StreamContent {
     /// 文本内容
     Text(String),
@@ -40,10 +42,11 @@ pub enum StreamContent {
     },
     /// 工具执行结果（tool_call_id, result）
     ToolResult { id: String, result: String },
-    /// 用量统计
+    /// 用量统计（estimated 为 true 表示提供商未返回用量，由分词器估算）
     Usage {
         input_tokens: u32,
         output_tokens: u32,
+        estimated: bool,
     },
     /// 流完成
     Done,
@@ -364,132 +367,179 @@ impl StreamingLlmClient {
         let mut stream_completed = false;
         let mut tool_calls_by_id: HashMap<String, serde_json::Map<String, serde_json::Value>> =
             HashMap::new();
+        let prompt_for_estimate = prompt_text(Some(preamble.as_str()), history, user_prompt);
+        let mut tool_turn_usage = ToolTurnUsage::new(model, prompt_for_estimate.clone());
+        let mut provider_usage_seen = false;
 
         let mut emit_content = |chunk: StreamContent| {
-            let (event_type, payload) = match &chunk {
-                StreamContent::Text(text) => (
-                    "assistant_text_delta",
-                    json!({
-                        "content": text,
-                        "content_length": text.len(),
-                    }),
-                ),
-                StreamContent::Reasoning(text) => (
-                    "reasoning_delta",
-                    json!({
-                        "content": text,
-                        "content_length": text.len(),
-                    }),
-                ),
-                StreamContent::ToolCallStart { id, name } => (
-                    "tool_call_start",
-                    json!({
-                        "tool_call_id": id,
-                        "tool_name": name,
-                    }),
-                ),
-                StreamContent::ToolCallDelta { id, delta } => (
-                    "tool_call_delta",
-                    json!({
-                        "tool_call_id": id,
-                        "delta": delta,
-                        "delta_length": delta.len(),
-                    }),
-                ),
-                StreamContent::ToolCallComplete {
-                    id,
-                    name,
-                    arguments,
-                } => (
-                    "tool_call_complete",
-                    json!({
-                        "tool_call_id": id,
-                        "tool_name": name,
-                        "arguments": arguments,
-                    }),
-                ),
-                StreamContent::ToolResult { id, result } => (
-                    "tool_result",
-                    json!({
-                        "tool_call_id": id,
-                        "result": result,
-                    }),
-                ),
-                StreamContent::Usage {
-                    input_tokens,
-                    output_tokens,
-                } => (
-                    "usage",
-                    json!({
-                        "input_tokens": input_tokens,
-                        "output_tokens": output_tokens,
-                    }),
-                ),
-                StreamContent::Done => ("done", json!({})),
-            };
-            match &chunk {
-                StreamContent::Text(text) => assistant_text_full.push_str(text),
-                StreamContent::Reasoning(text) => reasoning_text_full.push_str(text),
-                StreamContent::ToolCallStart { id, name } => {
-                    let entry = tool_calls_by_id.entry(id.clone()).or_default();
-                    entry.insert("tool_call_id".to_string(), json!(id));
-                    entry.insert("tool_name".to_string(), json!(name));
-                    entry.insert("status".to_string(), json!("started"));
-                }
-                StreamContent::ToolCallDelta { id, delta } => {
-                    let entry = tool_calls_by_id.entry(id.clone()).or_default();
-                    entry.insert("tool_call_id".to_string(), json!(id));
-                    let current = entry
-                        .get("arguments_delta")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or_default();
-                    entry.insert(
-                        "arguments_delta".to_string(),
-                        json!(format!("{}{}", current, delta)),
+            // 日志记录提供商上报的原始用量；转发给调用方的用量扣除纯工具调用轮次已补发的估算
+            if let StreamContent::Usage {
+                input_tokens,
+                output_tokens,
+                estimated: false,
+            } = &chunk
+            {
+                last_usage = Some((*input_tokens, *output_tokens));
+                provider_usage_seen = true;
+            }
+            for chunk in tool_turn_usage.process(chunk) {
+                // 提供商未返回用量（本地/部分 OpenAI 兼容接口）：在 Done 之前补发分词器估算的用量
+                if matches!(chunk, StreamContent::Done) && !provider_usage_seen {
+                    let mut completion = format!("{}{}", reasoning_text_full, assistant_text_full);
+                    for call in tool_calls_by_id.values() {
+                        if let Some(args) = call.get("arguments_raw").and_then(|v| v.as_str()) {
+                            completion.push_str(args);
+                        }
+                    }
+                    let usage =
+                        estimate_usage(&model_for_stream, &prompt_for_estimate, &completion);
+                    last_usage = Some((usage.input_tokens, usage.output_tokens));
+                    // 纯工具调用轮次已补发过估算，这里只报剩余部分
+                    let (input_tokens, output_tokens) =
+                        tool_turn_usage.deduct(usage.input_tokens, usage.output_tokens);
+                    log_stream_event(
+                        &session_id_for_stream,
+                        conversation_id_for_stream.as_deref(),
+                        &provider_for_stream,
+                        &model_for_stream,
+                        "usage",
+                        &json!({
+                            "input_tokens": input_tokens,
+                            "output_tokens": output_tokens,
+                            "estimated": true,
+                        }),
                     );
-                    entry.insert("status".to_string(), json!("streaming"));
-                }
-                StreamContent::ToolCallComplete {
-                    id,
-                    name,
-                    arguments,
-                } => {
-                    let entry = tool_calls_by_id.entry(id.clone()).or_default();
-                    entry.insert("tool_call_id".to_string(), json!(id));
-                    entry.insert("tool_name".to_string(), json!(name));
-                    entry.insert("arguments".to_string(), normalize_jsonish_string(arguments));
-                    entry.insert("arguments_raw".to_string(), json!(arguments));
-                    entry.insert("status".to_string(), json!("called"));
-                }
-                StreamContent::ToolResult { id, result } => {
-                    let entry = tool_calls_by_id.entry(id.clone()).or_default();
-                    entry.insert("tool_call_id".to_string(), json!(id));
-                    let normalized_result = normalize_jsonish_string(result);
-                    let success = infer_tool_result_success_for_turn(&normalized_result);
-                    entry.insert("result".to_string(), normalized_result);
-                    entry.insert("result_raw".to_string(), json!(result));
-                    entry.insert("success".to_string(), json!(success));
-                    entry.insert("status".to_string(), json!("completed"));
+                    let _ = on_content(StreamContent::Usage {
+                        input_tokens,
+                        output_tokens,
+                        estimated: true,
+                    });
                 }
-                StreamContent::Usage {
-                    input_tokens,
-                    output_tokens,
-                } => {
-                    last_usage = Some((*input_tokens, *output_tokens));
+                let (event_type, payload) = match &chunk {
+                    StreamContent::Text(text) => (
+                        "assistant_text_delta",
+                        json!({
+                            "content": text,
+                            "content_length": text.len(),
+                        }),
+                    ),
+                    StreamContent::Reasoning(text) => (
+                        "reasoning_delta",
+                        json!({
+                            "content": text,
+                            "content_length": text.len(),
+                        }),
+                    ),
+                    StreamContent::ToolCallStart { id, name } => (
+                        "tool_call_start",
+                        json!({
+                            "tool_call_id": id,
+                            "tool_name": name,
+                        }),
+                    ),
+                    StreamContent::ToolCallDelta { id, delta } => (
+                        "tool_call_delta",
+                        json!({
+                            "tool_call_id": id,
+                            "delta": delta,
+                            "delta_length": delta.len(),
+                        }),
+                    ),
+                    StreamContent::ToolCallComplete {
+                        id,
+                        name,
+                        arguments,
+                    } => (
+                        "tool_call_complete",
+                        json!({
+                            "tool_call_id": id,
+                            "tool_name": name,
+                            "arguments": arguments,
+                        }),
+                    ),
+                    StreamContent::ToolResult { id, result } => (
+                        "tool_result",
+                        json!({
+                            "tool_call_id": id,
+                            "result": result,
+                        }),
+                    ),
+                    StreamContent::Usage {
+                        input_tokens,
+                        output_tokens,
+                        estimated,
+                    } => (
+                        "usage",
+                        json!({
+                            "input_tokens": input_tokens,
+                            "output_tokens": output_tokens,
+                            "estimated": estimated,
+                        }),
+                    ),
+                    StreamContent::Done => ("done", json!({})),
+                };
+                match &chunk {
+                    StreamContent::Text(text) => assistant_text_full.push_str(text),
+                    StreamContent::Reasoning(text) => reasoning_text_full.push_str(text),
+                    StreamContent::ToolCallStart { id, name } => {
+                        let entry = tool_calls_by_id.entry(id.clone()).or_default();
+                        entry.insert("tool_call_id".to_string(), json!(id));
+                        entry.insert("tool_name".to_string(), json!(name));
+                        entry.insert("status".to_string(), json!("started"));
+                    }
+                    StreamContent::ToolCallDelta { id, delta } => {
+                        let entry = tool_calls_by_id.entry(id.clone()).or_default();
+                        entry.insert("tool_call_id".to_string(), json!(id));
+                        let current = entry
+                            .get("arguments_delta")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default();
+                        entry.insert(
+                            "arguments_delta".to_string(),
+                            json!(format!("{}{}", current, delta)),
+                        );
+                        entry.insert("status".to_string(), json!("streaming"));
+                    }
+                    StreamContent::ToolCallComplete {
+                        id,
+                        name,
+                        arguments,
+                    } => {
+                        let entry = tool_calls_by_id.entry(id.clone()).or_default();
+                        entry.insert("tool_call_id".to_string(), json!(id));
+                        entry.insert("tool_name".to_string(), json!(name));
+                        entry.insert("arguments".to_string(), normalize_jsonish_string(arguments));
+                        entry.insert("arguments_raw".to_string(), json!(arguments));
+                        entry.insert("status".to_string(), json!("called"));
+                    }
+                    StreamContent::ToolResult { id, result } => {
+                        let entry = tool_calls_by_id.entry(id.clone()).or_default();
+                        entry.insert("tool_call_id".to_string(), json!(id));
+                        let normalized_result = normalize_jsonish_string(result);
+                        let success = infer_tool_result_success_for_turn(&normalized_result);
+                        entry.insert("result".to_string(), normalized_result);
+                        entry.insert("result_raw".to_string(), json!(result));
+                        entry.insert("success".to_string(), json!(success));
+                        entry.insert("status".to_string(), json!("completed"));
+                    }
+                    StreamContent::Usage { .. } => {}
+                    StreamContent::Done => {
+                        stream_completed = true;
+                    }
                 }
-                StreamContent::Done => {
-                    stream_completed = true;
+                log_stream_event(
+                    &session_id_for_stream,
+                    conversation_id_for_stream.as_deref(),
+                    &provider_for_stream,
+                    &model_for_stream,
+                    event_type,
+                    &payload,
+                );
+                if !on_content(chunk) {
+                    return false;
                 }
             }
-            log_stream_event(
-                &session_id_for_stream,
-                conversation_id_for_stream.as_deref(),
-                &provider_for_stream,
-                &model_for_stream,
-                event_type,
-                &payload,
-            );
-            on_content(chunk)
+            true
         };
 
         // 根据 provider 创建带动态工具的 agent
@@ -790,20 +840,24 @@ impl StreamingLlmClient {
     where
         F: FnMut(StreamContent) -> bool,
     {
-        use rig::providers::deepseek;
+        use rig::providers::openai;
 
         let api_key = self.config.api_key.clone().unwrap_or_default();
 
-        let mut builder =
-            deepseek::Client::<rig::http_client::ReqwestClient>::builder().api_key(api_key);
+        // 使用 Chat Completions 解析器：DeepSeek 客户端要求 DeepSeek 专有的 usage 字段，
+        // 标准 OpenAI usage 分片会整片解析失败，连同其中的文本与工具调用一起丢失
+        let mut builder = openai::Client::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?);
 
         if let Some(base_url) = &self.config.base_url {
             builder = builder.base_url(base_url);
         }
 
-        let client = builder
+        let client: openai::CompletionsClient = builder
             .build()
-            .map_err(|e| anyhow::anyhow!("Failed to build generic client: {}", e))?;
+            .map_err(|e| anyhow::anyhow!("Failed to build generic client: {}", e))?
+            .completions_api();
 
         let tool_server_handle = Self::build_tool_server(dynamic_tools);
         let builder = client.agent(model).preamble(preamble);
@@ -845,6 +899,7 @@ impl StreamingLlmClient {
             );
             let client: openai::CompletionsClient = openai::Client::builder()
                 .api_key(api_key)
+                .http_headers(self.config.header_map()?)
                 .base_url(base_url)
                 .build()
                 .map_err(|e| anyhow::anyhow!("Failed to build OpenAI client: {:?}", e))?
@@ -861,6 +916,7 @@ impl StreamingLlmClient {
             info!("Using Responses API for official OpenAI");
             let client: openai::Client = openai::Client::builder()
                 .api_key(api_key)
+                .http_headers(self.config.header_map()?)
                 .build()
                 .map_err(|e| anyhow::anyhow!("Failed to build OpenAI client: {:?}", e))?;
 
@@ -898,6 +954,7 @@ impl StreamingLlmClient {
 
         let client: openai::CompletionsClient = openai::Client::builder()
             .api_key(api_key)
+            .http_headers(self.config.header_map()?)
             .base_url(base_url)
             .build()
             .map_err(|e| anyhow::anyhow!("Failed to build OpenAI client: {:?}", e))?
@@ -931,8 +988,9 @@ impl StreamingLlmClient {
             .or_else(|| std::env::var("MOONSHOT_API_KEY").ok())
             .ok_or_else(|| anyhow::anyhow!("MOONSHOT_API_KEY not set"))?;
 
-        let mut builder =
-            moonshot::Client::<rig::http_client::ReqwestClient>::builder().api_key(api_key);
+        let mut builder = moonshot::Client::<rig::http_client::ReqwestClient>::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?);
 
         if let Some(base_url) = &self.config.base_url {
             builder = builder.base_url(base_url);
@@ -974,8 +1032,9 @@ impl StreamingLlmClient {
         let api_key = std::env::var("ANTHROPIC_API_KEY")
             .map_err(|_| anyhow::anyhow!("ANTHROPIC_API_KEY not set"))?;
 
-        let mut builder =
-            anthropic::Client::<rig::http_client::ReqwestClient>::builder().api_key(api_key);
+        let mut builder = anthropic::Client::<rig::http_client::ReqwestClient>::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?);
 
         if let Ok(base_url) = std::env::var("ANTHROPIC_API_BASE") {
             if !base_url.is_empty() {
@@ -1015,7 +1074,19 @@ impl StreamingLlmClient {
         F: FnMut(StreamContent) -> bool,
     {
         use rig::providers::gemini;
-        let client = gemini::Client::from_env();
+        let api_key = std::env::var("GEMINI_API_KEY")
+            .map_err(|_| anyhow::anyhow!("GEMINI_API_KEY not set"))?;
+        let mut builder = gemini::Client::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?);
+
+        if let Some(base_url) = &self.config.base_url {
+            builder = builder.base_url(base_url);
+        }
+
+        let client = builder
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build Gemini client: {:?}", e))?;
         let gen_cfg = GenerationConfig::default();
         let cfg = AdditionalParameters::default().with_config(gen_cfg);
 
@@ -1052,8 +1123,9 @@ impl StreamingLlmClient {
             .or_else(|_| std::env::var("OPENAI_API_KEY"))
             .map_err(|_| anyhow::anyhow!("DEEPSEEK_API_KEY not set"))?;
 
-        let mut builder =
-            deepseek::Client::<rig::http_client::ReqwestClient>::builder().api_key(api_key);
+        let mut builder = deepseek::Client::<rig::http_client::ReqwestClient>::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?);
 
         if let Some(base_url) = &self.config.base_url {
             builder = builder.base_url(base_url);
@@ -1088,7 +1160,15 @@ impl StreamingLlmClient {
         F: FnMut(StreamContent) -> bool,
     {
         use rig::providers::ollama;
-        let client = ollama::Client::from_env();
+        let mut builder = ollama::Client::builder()
+            .api_key(rig::client::Nothing)
+            .http_headers(self.config.header_map()?);
+        if let Ok(base_url) = std::env::var("OLLAMA_API_BASE_URL") {
+            builder = builder.base_url(&base_url);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build Ollama client: {:?}", e))?;
 
         let tool_server_handle = Self::build_tool_server(dynamic_tools);
         let builder = client.agent(model).preamble(preamble);
@@ -1115,7 +1195,19 @@ impl StreamingLlmClient {
         F: FnMut(StreamContent) -> bool,
     {
         use rig::providers::openrouter;
-        let client = openrouter::Client::from_env();
+        let api_key = std::env::var("OPENROUTER_API_KEY")
+            .map_err(|_| anyhow::anyhow!("OPENROUTER_API_KEY not set"))?;
+        let mut builder = openrouter::Client::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?);
+
+        if let Some(base_url) = &self.config.base_url {
+            builder = builder.base_url(base_url);
+        }
+
+        let client = builder
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build OpenRouter client: {:?}", e))?;
 
         let tool_server_handle = Self::build_tool_server(dynamic_tools);
         let builder = client.agent(model).preamble(preamble);
@@ -1142,7 +1234,13 @@ impl StreamingLlmClient {
         F: FnMut(StreamContent) -> bool,
     {
         use rig::providers::xai;
-        let client = xai::Client::from_env();
+        let api_key =
+            std::env::var("XAI_API_KEY").map_err(|_| anyhow::anyhow!("XAI_API_KEY not set"))?;
+        let client = xai::Client::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build xAI client: {:?}", e))?;
 
         let tool_server_handle = Self::build_tool_server(dynamic_tools);
         let builder = client.agent(model).preamble(preamble);
@@ -1169,7 +1267,13 @@ impl StreamingLlmClient {
         F: FnMut(StreamContent) -> bool,
     {
         use rig::providers::groq;
-        let client = groq::Client::from_env();
+        let api_key =
+            std::env::var("GROQ_API_KEY").map_err(|_| anyhow::anyhow!("GROQ_API_KEY not set"))?;
+        let client = groq::Client::builder()
+            .api_key(api_key)
+            .http_headers(self.config.header_map()?)
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to build Groq client: {:?}", e))?;
 
         let tool_server_handle = Self::build_tool_server(dynamic_tools);
         let builder = client.agent(model).preamble(preamble);
@@ -1206,8 +1310,6 @@ impl StreamingLlmClient {
         M::StreamingResponse: Clone + Unpin + rig::completion::GetTokenUsage,
         F: FnMut(StreamContent) -> bool,
     {
-        let mut tool_call_args: HashMap<String, String> = HashMap::new();
-        let mut tool_call_names: HashMap<String, String> = HashMap::new();
         info!("Starting stream iteration...");
 
         self.validate_moonshot_temperature()?;
@@ -1255,6 +1357,8 @@ impl StreamingLlmClient {
 
         let mut content = String::new();
         let mut chunk_count = 0;
+        let mut normalizer = StreamNormalizer::new();
+        let mut cancelled = false;
 
         loop {
             let item = match stream_iter.next().await {
@@ -1263,85 +1367,62 @@ impl StreamingLlmClient {
             };
 
             chunk_count += 1;
-            match item {
+            let event = match item {
                 // 文本内容
                 Ok(MultiTurnStreamItem::StreamAssistantItem(StreamedAssistantContent::Text(t))) => {
-                    let piece = t.text;
-                    if !piece.is_empty() {
-                        content.push_str(&piece);
-                        if !on_content(StreamContent::Text(piece)) {
-                            info!("Stream cancelled by callback");
-                            break;
-                        }
-                    }
+                    content.push_str(&t.text);
+                    ProviderStreamEvent::Text(t.text)
                 }
                 // 推理内容
                 Ok(MultiTurnStreamItem::StreamAssistantItem(
                     StreamedAssistantContent::Reasoning(r),
-                )) => {
-                    let piece = r.display_text();
-                    if !piece.is_empty() && !on_content(StreamContent::Reasoning(piece)) {
-                        info!("Stream cancelled by callback");
-                        break;
-                    }
-                }
+                )) => ProviderStreamEvent::Reasoning(r.display_text()),
+                // 推理增量（DeepSeek reasoning_content、OpenRouter reasoning、Gemini thought 等）
+                Ok(MultiTurnStreamItem::StreamAssistantItem(
+                    StreamedAssistantContent::ReasoningDelta { reasoning, .. },
+                )) => ProviderStreamEvent::Reasoning(reasoning),
                 // 完整的工具调用
                 Ok(MultiTurnStreamItem::StreamAssistantItem(
                     StreamedAssistantContent::ToolCall { tool_call, .. },
-                )) => {
-                    // info!(
-                    //     "Tool call received: id={}, name={}, args={}",
-                    //     tool_call.id, tool_call.function.name, tool_call.function.arguments
-                    // );
-                    if !on_content(StreamContent::ToolCallComplete {
-                        id: tool_call.id.clone(),
-                        name: tool_call.function.name.clone(),
-                        arguments: tool_call.function.arguments.to_string(),
-                    }) {
-                        info!("Stream cancelled by callback");
-                        break;
-                    }
-                    tool_call_names.insert(tool_call.id.clone(), tool_call.function.name.clone());
-                }
-                // 工具调用增量
+                )) => ProviderStreamEvent::ToolCall {
+                    id: tool_call.id,
+                    name: tool_call.function.name,
+                    arguments: tool_call.function.arguments.to_string(),
+                },
+                // 工具调用增量：工具名与参数分片分别归一化
                 Ok(MultiTurnStreamItem::StreamAssistantItem(
                     StreamedAssistantContent::ToolCallDelta { id, content, .. },
                 )) => {
                     use rig::streaming::ToolCallDeltaContent;
-                    let delta_str = match &content {
-                        ToolCallDeltaContent::Name(n) => n.clone(),
-                        ToolCallDeltaContent::Delta(d) => d.clone(),
-                    };
-                    tool_call_args
-                        .entry(id.clone())
-                        .or_default()
-                        .push_str(&delta_str);
-                    if !on_content(StreamContent::ToolCallDelta {
-                        id,
-                        delta: delta_str,
-                    }) {
-                        info!("Stream cancelled by callback");
-                        break;
+                    match content {
+                        ToolCallDeltaContent::Name(name) => {
+                            ProviderStreamEvent::ToolCallName { id, name }
+                        }
+                        ToolCallDeltaContent::Delta(delta) => {
+                            ProviderStreamEvent::ToolCallArguments { id, delta }
+                        }
                     }
                 }
                 // 工具执行结果
                 Ok(MultiTurnStreamItem::StreamUserItem(user_content)) => {
                     let rig::streaming::StreamedUserContent::ToolResult { tool_result, .. } =
                         user_content;
-                    let result_str =
-                        serde_json::to_string(&tool_result.content).unwrap_or_default();
-                    // info!(
-                    //     "Tool result received: id={}, result_len={}, content_preview={}",
-                    //     tool_result.id,
-                    //     result_str.len(),
-                    //     &result_str.chars().take(300).collect::<String>()
-                    // );
-                    if !on_content(StreamContent::ToolResult {
+                    ProviderStreamEvent::ToolResult {
                         id: tool_result.id,
-                        result: result_str,
-                    }) {
-                        info!("Stream cancelled by callback");
-                        break;
+                        result: serde_json::to_string(&tool_result.content).unwrap_or_default(),
+                    }
+                }
+                // 单轮结束：按轮次上报用量，便于调用方实时控制成本
+                Ok(MultiTurnStreamItem::StreamAssistantItem(StreamedAssistantContent::Final(
+                    turn_resp,
+                ))) => {
+                    use rig::completion::GetTokenUsage;
+                    match turn_resp.token_usage() {
+                        Some(usage) => ProviderStreamEvent::TurnUsage {
+                            input_tokens: usage.input_tokens,
+                            output_tokens: usage.output_tokens,
+                        },
+                        None => continue,
                     }
                 }
                 // 最终响应
@@ -1355,21 +1436,35 @@ impl StreamingLlmClient {
                     if !final_text.is_empty() && !content.ends_with(final_text) {
                         content.push_str(final_text);
                     }
-
                     let usage = final_resp.usage();
-                    let _ = on_content(StreamContent::Usage {
-                        input_tokens: usage.input_tokens as u32,
-                        output_tokens: usage.output_tokens as u32,
-                    });
-
-                    let _ = on_content(StreamContent::Done);
-                    break;
+                    ProviderStreamEvent::Final {
+                        input_tokens: usage.input_tokens,
+                        output_tokens: usage.output_tokens,
+                    }
                 }
-                Ok(_) => {}
+                Ok(_) => continue,
                 Err(e) => {
                     error!("LLM stream error: {}", e);
                     return Err(anyhow!("LLM stream error: {}", e));
                 }
+            };
+
+            for chunk in normalizer.push(event) {
+                // 最终响应之后的用量与 Done 必须送达，忽略回调的取消信号
+                if !on_content(chunk) && !normalizer.is_done() {
+                    info!("Stream cancelled by callback");
+                    cancelled = true;
+                    break;
+                }
+            }
+            if cancelled || normalizer.is_done() {
+                break;
+            }
+        }
+        // 提供商未发送最终响应就关闭了流：仍然补发 Done，保证各提供商事件序列一致
+        if !cancelled {
+            for chunk in normalizer.finish() {
+                let _ = on_content(chunk);
             }
         }
         info!(
@@ -1,8 +1,16 @@
 //! Token usage tracking and statistics
 //!
-//! 提供 token 使用统计和成本估算功能
+//! 提供 token 使用统计和成本估算功能。
+//! 对于不返回 usage 的提供商（本地模型、部分 OpenAI 兼容接口），
+//! 使用 tiktoken 兼容的分词器估算 token 数量，并将结果标记为估算值。
+
+use std::sync::OnceLock;
 
 use serde::{Deserialize, Serialize};
+use tiktoken_rs::CoreBPE;
+
+use crate::message::ChatMessage;
+use crate::streaming::StreamContent;
 
 /// Token 使用统计
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -15,6 +23,9 @@ pub struct TokenUsage {
     pub total_tokens: u32,
     /// 估算成本（美元）
     pub estimated_cost: f64,
+    /// token 数量是否由本地分词器估算（提供商未返回 usage）
+    #[serde(default)]
+    pub estimated: bool,
 }
 
 impl TokenUsage {
@@ -25,9 +36,26 @@ impl TokenUsage {
             output_tokens,
             total_tokens: input_tokens + output_tokens,
             estimated_cost: 0.0,
+            estimated: false,
+        }
+    }
+
+    /// 创建由分词器估算的 token 使用统计
+    pub fn estimated(input_tokens: u32, output_tokens: u32) -> Self {
+        Self {
+            estimated: true,
+            ..Self::new(input_tokens, output_tokens)
         }
     }
 
+    /// 提供商未返回用量时，用分词器估算的用量替代
+    pub fn or_estimate(self, model: &str, prompt: &str, completion: &str) -> Self {
+        if self.total_tokens > 0 {
+            return self;
+        }
+        estimate_usage(model, prompt, completion)
+    }
+
     /// 设置成本
     pub fn with_cost(mut self, cost: f64) -> Self {
         self.estimated_cost = cost;
@@ -46,12 +74,223 @@ impl TokenUsage {
         self.output_tokens += other.output_tokens;
         self.total_tokens += other.total_tokens;
         self.estimated_cost += other.estimated_cost;
+        self.estimated |= other.estimated;
+    }
+}
+
+/// tiktoken 兼容的分词编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenEncoding {
+    /// GPT-4o / GPT-4.1 / GPT-5 / o 系列
+    O200kBase,
+    /// GPT-4 / GPT-3.5 / text-embedding-3，也作为非 OpenAI 模型的近似编码
+    Cl100kBase,
+    /// text-davinci / code-davinci 等旧模型
+    P50kBase,
+}
+
+impl TokenEncoding {
+    /// 按模型族选择编码
+    pub fn for_model(model: &str) -> Self {
+        let model = model.to_lowercase();
+        // OpenRouter 等聚合商的模型名带有厂商前缀，如 openai/gpt-4o
+        let model = model.rsplit('/').next().unwrap_or(&model);
+
+        if model.starts_with("gpt-4o")
+            || model.starts_with("chatgpt-4o")
+            || model.starts_with("gpt-4.1")
+            || model.starts_with("gpt-4.5")
+            || model.starts_with("gpt-5")
+            || model.starts_with("gpt-oss")
+            || model.starts_with("o1")
+            || model.starts_with("o3")
+            || model.starts_with("o4")
+        {
+            Self::O200kBase
+        } else if model.starts_with("text-davinci") || model.starts_with("code-") {
+            Self::P50kBase
+        } else {
+            Self::Cl100kBase
+        }
+    }
+
+    fn bpe(self) -> Option<&'static CoreBPE> {
+        static O200K: OnceLock<Option<CoreBPE>> = OnceLock::new();
+        static CL100K: OnceLock<Option<CoreBPE>> = OnceLock::new();
+        static P50K: OnceLock<Option<CoreBPE>> = OnceLock::new();
+
+        match self {
+            Self::O200kBase => O200K.get_or_init(|| tiktoken_rs::o200k_base().ok()),
+            Self::Cl100kBase => CL100K.get_or_init(|| tiktoken_rs::cl100k_base().ok()),
+            Self::P50kBase => P50K.get_or_init(|| tiktoken_rs::p50k_base().ok()),
+        }
+        .as_ref()
+    }
+}
+
+/// 计算文本的 token 数量
+///
+/// 分词器不可用时退化为按字符数估算（约 4 字符 / token）
+pub fn count_tokens(model: &str, text: &str) -> u32 {
+    if text.is_empty() {
+        return 0;
+    }
+    match TokenEncoding::for_model(model).bpe() {
+        Some(bpe) => bpe.encode_ordinary(text).len() as u32,
+        None => text.chars().count().div_ceil(4) as u32,
+    }
+}
+
+/// 估算一次调用的 token 使用统计（结果标记为估算值）
+pub fn estimate_usage(model: &str, prompt: &str, completion: &str) -> TokenUsage {
+    TokenUsage::estimated(count_tokens(model, prompt), count_tokens(model, completion))
+}
+
+/// 拼接一次请求发送给模型的全部提示文本，用于估算输入 token
+pub(crate) fn prompt_text(
+    system_prompt: Option<&str>,
+    history: &[ChatMessage],
+    user_prompt: &str,
+) -> String {
+    let mut text = system_prompt.unwrap_or_default().to_string();
+    for msg in history {
+        text.push('\n');
+        text.push_str(&msg.content);
+        for extra in [&msg.tool_calls, &msg.reasoning_content]
+            .into_iter()
+            .flatten()
+        {
+            text.push('\n');
+            text.push_str(extra);
+        }
+    }
+    text.push('\n');
+    text.push_str(user_prompt);
+    text
+}
+
+/// 纯工具调用轮次的用量估算
+///
+/// rig 只在含文本的轮次结束时上报单轮用量；只有工具调用的轮次，用量要到整个对话结束才随
+/// 最终汇总给出，调用方因此无法在工具循环中途按成本终止。这类轮次在首个工具结果之后补发
+/// 一次估算用量，之后提供商上报的真实用量扣除已估算的部分，保证总量不变。
+#[derive(Debug)]
+pub(crate) struct ToolTurnUsage {
+    model: String,
+    /// 下一次请求的提示文本（随工具轮次增长）
+    prompt: String,
+    /// 当前轮次的模型输出
+    completion: String,
+    has_text: bool,
+    in_tool_results: bool,
+    /// 已估算、尚未被真实用量抵扣的 token
+    pending_input: u32,
+    pending_output: u32,
+}
+
+impl ToolTurnUsage {
+    pub(crate) fn new(model: &str, prompt: String) -> Self {
+        Self {
+            model: model.to_string(),
+            prompt,
+            completion: String::new(),
+            has_text: false,
+            in_tool_results: false,
+            pending_input: 0,
+            pending_output: 0,
+        }
+    }
+
+    /// 处理一个流事件，返回应依次转发给调用方的事件
+    pub(crate) fn process(&mut self, chunk: StreamContent) -> Vec<StreamContent> {
+        match chunk {
+            StreamContent::Text(ref text) => {
+                self.start_turn();
+                self.has_text = true;
+                self.completion.push_str(text);
+            }
+            StreamContent::Reasoning(ref text) => {
+                self.start_turn();
+                self.completion.push_str(text);
+            }
+            StreamContent::ToolCallStart { .. } | StreamContent::ToolCallDelta { .. } => {
+                self.start_turn();
+            }
+            StreamContent::ToolCallComplete {
+                ref name,
+                ref arguments,
+                ..
+            } => {
+                self.start_turn();
+                self.completion.push_str(name);
+                self.completion.push_str(arguments);
+            }
+            StreamContent::ToolResult { ref result, .. } => {
+                let mut estimate = None;
+                if !self.in_tool_results {
+                    self.in_tool_results = true;
+                    if !self.has_text {
+                        let usage = estimate_usage(&self.model, &self.prompt, &self.completion);
+                        self.pending_input += usage.input_tokens;
+                        self.pending_output += usage.output_tokens;
+                        estimate = Some(StreamContent::Usage {
+                            input_tokens: usage.input_tokens,
+                            output_tokens: usage.output_tokens,
+                            estimated: true,
+                        });
+                    }
+                    self.prompt.push('\n');
+                    self.prompt.push_str(&self.completion);
+                }
+                self.prompt.push('\n');
+                self.prompt.push_str(result);
+                return std::iter::once(chunk).chain(estimate).collect();
+            }
+            StreamContent::Usage {
+                input_tokens,
+                output_tokens,
+                estimated: false,
+            } => {
+                let (input_tokens, output_tokens) = self.deduct(input_tokens, output_tokens);
+                if input_tokens == 0 && output_tokens == 0 {
+                    return Vec::new();
+                }
+                return vec![StreamContent::Usage {
+                    input_tokens,
+                    output_tokens,
+                    estimated: false,
+                }];
+            }
+            StreamContent::Usage { .. } | StreamContent::Done => {}
+        }
+        vec![chunk]
+    }
+
+    /// 从用量中扣除尚未抵扣的估算部分
+    pub(crate) fn deduct(&mut self, input_tokens: u32, output_tokens: u32) -> (u32, u32) {
+        let input_deducted = input_tokens.min(self.pending_input);
+        let output_deducted = output_tokens.min(self.pending_output);
+        self.pending_input -= input_deducted;
+        self.pending_output -= output_deducted;
+        (
+            input_tokens - input_deducted,
+            output_tokens - output_deducted,
+        )
+    }
+
+    /// 工具结果之后的首个模型输出开启新一轮
+    fn start_turn(&mut self) {
+        if self.in_tool_results {
+            self.in_tool_results = false;
+            self.has_text = false;
+            self.completion.clear();
+        }
     }
 }
 
 /// 计算成本（美元）
 ///
-/// 基于各提供商的公开定价
+/// 基于各提供商的公开定价；token 数量可以来自提供商上报，也可以来自 `estimate_usage`
 pub fn calculate_cost(provider: &str, model: &str, input_tokens: u32, output_tokens: u32) -> f64 {
     let provider_lower = provider.to_lowercase();
     let model_lower = model.to_lowercase();
@@ -180,4 +419,133 @@ mod tests {
         assert_eq!(usage1.total_tokens, 450);
         assert!((usage1.estimated_cost - 0.015).abs() < 0.0001);
     }
+
+    #[test]
+    fn test_encoding_for_model() {
+        assert_eq!(
+            TokenEncoding::for_model("gpt-4o-mini"),
+            TokenEncoding::O200kBase
+        );
+        assert_eq!(
+            TokenEncoding::for_model("openai/o3-mini"),
+            TokenEncoding::O200kBase
+        );
+        assert_eq!(
+            TokenEncoding::for_model("gpt-4-turbo"),
+            TokenEncoding::Cl100kBase
+        );
+        assert_eq!(
+            TokenEncoding::for_model("text-davinci-003"),
+            TokenEncoding::P50kBase
+        );
+        assert_eq!(
+            TokenEncoding::for_model("qwen2.5:7b"),
+            TokenEncoding::Cl100kBase
+        );
+    }
+
+    #[test]
+    fn test_estimated_matches_reported_usage() {
+        // cl100k_base 下 "Hello, world!" 为 4 个 token，"Hi there" 为 2 个 token
+        let reported = TokenUsage::new(4, 2);
+        let estimated = TokenUsage::default().or_estimate("gpt-4", "Hello, world!", "Hi there");
+
+        assert!(estimated.estimated);
+        assert!(!reported.estimated);
+        assert_eq!(estimated.input_tokens, reported.input_tokens);
+        assert_eq!(estimated.output_tokens, reported.output_tokens);
+
+        // 已有上报用量时不做估算
+        let kept = reported
+            .clone()
+            .or_estimate("gpt-4", "Hello, world!", "Hi there");
+        assert!(!kept.estimated);
+    }
+
+    fn tool_turn(id: &str) -> Vec<StreamContent> {
+        vec![
+            StreamContent::ToolCallStart {
+                id: id.to_string(),
+                name: "http_request".to_string(),
+            },
+            StreamContent::ToolCallComplete {
+                id: id.to_string(),
+                name: "http_request".to_string(),
+                arguments: r#"{"url":"https://example.com"}"#.to_string(),
+            },
+            StreamContent::ToolResult {
+                id: id.to_string(),
+                result: r#"{"status":200}"#.to_string(),
+            },
+        ]
+    }
+
+    fn usage_events(events: &[StreamContent]) -> Vec<(u32, u32, bool)> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                StreamContent::Usage {
+                    input_tokens,
+                    output_tokens,
+                    estimated,
+                } => Some((*input_tokens, *output_tokens, *estimated)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tool_only_turns_report_estimated_usage() {
+        let mut tracker = ToolTurnUsage::new("gpt-4o", "Fetch https://example.com".to_string());
+        let mut events = Vec::new();
+        for chunk in tool_turn("c1").into_iter().chain(tool_turn("c2")) {
+            events.extend(tracker.process(chunk));
+        }
+
+        // 每个纯工具轮次在工具结果之后各补发一次估算，第二轮的输入包含第一轮的输出与结果
+        let estimates = usage_events(&events);
+        assert_eq!(estimates.len(), 2);
+        assert!(estimates
+            .iter()
+            .all(|(_, output, estimated)| *output > 0 && *estimated));
+        assert!(estimates[1].0 > estimates[0].0);
+        assert!(matches!(events[3], StreamContent::Usage { .. }));
+
+        // 最终汇总扣除已估算部分，总量与提供商上报一致
+        let (input, output) = estimates
+            .iter()
+            .fold((0, 0), |acc, (i, o, _)| (acc.0 + i, acc.1 + o));
+        let final_usage = tracker.process(StreamContent::Usage {
+            input_tokens: 1_000,
+            output_tokens: 100,
+            estimated: false,
+        });
+        assert_eq!(
+            usage_events(&final_usage),
+            vec![(1_000 - input, 100 - output, false)]
+        );
+    }
+
+    #[test]
+    fn test_turns_with_text_keep_provider_usage() {
+        let mut tracker = ToolTurnUsage::new("gpt-4o", "Fetch https://example.com".to_string());
+        let mut events = tracker.process(StreamContent::Text("I'll fetch it.".to_string()));
+        for chunk in tool_turn("c1") {
+            events.extend(tracker.process(chunk));
+        }
+        events.extend(tracker.process(StreamContent::Usage {
+            input_tokens: 42,
+            output_tokens: 17,
+            estimated: false,
+        }));
+        assert_eq!(usage_events(&events), vec![(42, 17, false)]);
+    }
+
+    #[test]
+    fn test_cost_from_estimated_usage() {
+        let mut usage = estimate_usage("gpt-4o-mini", &"word ".repeat(1000), "");
+        usage.estimate_cost("openai", "gpt-4o-mini");
+        assert!(usage.input_tokens > 0);
+        assert!(usage.estimated_cost > 0.0);
+    }
 }
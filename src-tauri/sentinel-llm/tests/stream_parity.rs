@@ -0,0 +1,543 @@
+//! 各提供商流式事件一致性测试
+//!
+//! 在本地启动一个按顺序回放固定 SSE 响应体的 HTTP 服务，把每个提供商的真实客户端
+//! （`StreamingLlmClient` → rig 提供商解析 → `StreamNormalizer`）指向它，完整跑一轮
+//! “工具调用 → 工具执行 → 最终回答”，断言所有提供商得到相同的 `StreamContent` 序列。
+//! 分片粒度与工具调用 id 因提供商而异，比较前会合并相邻增量并按出现顺序重编号 id。
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use sentinel_llm::{
+    LlmConfig, ProviderStreamEvent, StreamContent, StreamNormalizer, StreamingLlmClient,
+};
+use sentinel_tools::{DynamicTool, DynamicToolBuilder};
+use serde_json::Value;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// 按请求顺序回放 SSE 响应体，返回服务地址与收到的请求体
+async fn serve_sse(bodies: Vec<&'static str>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+    let mut queue: VecDeque<&'static str> = bodies.into();
+
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let request = read_request(&mut socket).await;
+            recorded.lock().unwrap().push(request);
+            // SSE 事件以空行结尾，固定响应体末尾只有一个换行
+            let body = format!("{}\n", queue.pop_front().unwrap_or("data: [DONE]\n"));
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        }
+    });
+
+    (format!("http://{}", addr), requests)
+}
+
+async fn read_request(socket: &mut tokio::net::TcpStream) -> String {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = socket.read(&mut chunk).await.unwrap_or(0);
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        let text = String::from_utf8_lossy(&buf);
+        if let Some(header_end) = text.find("\r\n\r\n") {
+            let content_length = text[..header_end]
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())
+                        .flatten()
+                })
+                .unwrap_or(0);
+            if buf.len() >= header_end + 4 + content_length {
+                return String::from_utf8_lossy(&buf[header_end + 4..]).into_owned();
+            }
+        }
+    }
+    String::from_utf8_lossy(&buf).into_owned()
+}
+
+fn http_request_tool() -> DynamicTool {
+    let def = DynamicToolBuilder::new("http_request")
+        .description("Fetch a URL")
+        .input_schema(serde_json::json!({
+            "type": "object",
+            "properties": { "url": { "type": "string" } },
+            "required": ["url"]
+        }))
+        .executor(|_args| async move { Ok(serde_json::json!({ "status": 200 })) })
+        .build()
+        .unwrap();
+    DynamicTool::new(def)
+}
+
+/// 用真实客户端跑完整的一轮工具调用对话，收集回调收到的事件
+async fn run_provider(
+    provider: &str,
+    model: &str,
+    bodies: Vec<&'static str>,
+) -> (Vec<StreamContent>, Vec<String>) {
+    let (base_url, requests) = serve_sse(bodies).await;
+    let config = LlmConfig::new(provider, model)
+        .with_api_key("test-key")
+        .with_base_url(base_url)
+        .with_timeout(30);
+    let client = StreamingLlmClient::new(config);
+
+    let mut events = Vec::new();
+    client
+        .stream_chat_with_dynamic_tools(
+            Some("You are a security assistant."),
+            "Fetch https://example.com",
+            &[],
+            None,
+            vec![http_request_tool()],
+            |chunk| {
+                events.push(chunk);
+                true
+            },
+        )
+        .await
+        .unwrap_or_else(|e| panic!("provider {} failed: {}", provider, e));
+
+    let requests = requests.lock().unwrap().clone();
+    (events, requests)
+}
+
+fn normalize(events: Vec<ProviderStreamEvent>) -> Vec<StreamContent> {
+    let mut normalizer = StreamNormalizer::new();
+    let mut out: Vec<StreamContent> = events
+        .into_iter()
+        .flat_map(|event| normalizer.push(event))
+        .collect();
+    out.extend(normalizer.finish());
+    out
+}
+
+/// 合并相邻的同类增量，把工具调用 id 按出现顺序重编号，并把参数统一为紧凑 JSON
+///
+/// 估算用量依赖分词器，比较时只保留“已估算”这一形态。
+fn canonicalize(events: Vec<StreamContent>) -> Vec<StreamContent> {
+    let mut ids: HashMap<String, String> = HashMap::new();
+    let mut rename = |id: String| {
+        let next = format!("call_{}", ids.len());
+        ids.entry(id).or_insert(next).clone()
+    };
+
+    let mut out: Vec<StreamContent> = Vec::new();
+    for event in events {
+        let event = match event {
+            StreamContent::ToolCallStart { id, name } => StreamContent::ToolCallStart {
+                id: rename(id),
+                name,
+            },
+            StreamContent::ToolCallDelta { id, delta } => StreamContent::ToolCallDelta {
+                id: rename(id),
+                delta,
+            },
+            StreamContent::ToolCallComplete {
+                id,
+                name,
+                arguments,
+            } => StreamContent::ToolCallComplete {
+                id: rename(id),
+                name,
+                arguments,
+            },
+            StreamContent::ToolResult { id, result } => StreamContent::ToolResult {
+                id: rename(id),
+                result,
+            },
+            StreamContent::Usage {
+                estimated: true, ..
+            } => StreamContent::Usage {
+                input_tokens: 0,
+                output_tokens: 0,
+                estimated: true,
+            },
+            other => other,
+        };
+        let merged = match (out.last_mut(), &event) {
+            (Some(StreamContent::Text(prev)), StreamContent::Text(next))
+            | (Some(StreamContent::Reasoning(prev)), StreamContent::Reasoning(next)) => {
+                prev.push_str(next);
+                true
+            }
+            (
+                Some(StreamContent::ToolCallDelta { id: prev_id, delta }),
+                StreamContent::ToolCallDelta { id, delta: next },
+            ) if prev_id == id => {
+                delta.push_str(next);
+                true
+            }
+            _ => false,
+        };
+        if !merged {
+            out.push(event);
+        }
+    }
+
+    // 参数增量的空白差异（如 ModelScope 的 `{"url": ...}`）不影响事件形态
+    for event in &mut out {
+        if let StreamContent::ToolCallDelta { delta, .. } = event {
+            if let Ok(value) = serde_json::from_str::<Value>(delta) {
+                *delta = value.to_string();
+            }
+        }
+    }
+    out
+}
+
+const OPENAI_BODY: &str = r#"
+data: {"choices":[{"index":0,"delta":{"role":"assistant","content":"I'll fetch "},"finish_reason":null}]}
+
+data: {"choices":[{"index":0,"delta":{"content":"the page."},"finish_reason":null}]}
+
+data: {"choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"id":"call_abc","type":"function","function":{"name":"http_request","arguments":""}}]},"finish_reason":null}]}
+
+data: {"choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"url\":"}}]},"finish_reason":null}]}
+
+data: {"choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"https://example.com\"}"}}]},"finish_reason":null}]}
+
+data: {"choices":[{"index":0,"delta":{},"finish_reason":"tool_calls"}]}
+
+data: {"choices":[],"usage":{"prompt_tokens":42,"completion_tokens":17,"total_tokens":59}}
+
+data: [DONE]
+"#;
+
+const DEEPSEEK_BODY: &str = r#"
+data: {"id":"1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"role":"assistant","content":null,"reasoning_content":"Let me check "},"finish_reason":null}]}
+
+data: {"id":"1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"content":null,"reasoning_content":"the target."},"finish_reason":null}]}
+
+data: {"id":"1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"content":"I'll fetch the page."},"finish_reason":null}]}
+
+data: {"id":"1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"id":"call_0_deepseek","type":"function","function":{"name":"http_request","arguments":""}}]},"finish_reason":null}]}
+
+data: {"id":"1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"url\":\"https://example.com\"}"}}]},"finish_reason":null}]}
+
+data: {"id":"1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"content":""},"finish_reason":"tool_calls"}],"usage":{"prompt_tokens":42,"completion_tokens":17,"total_tokens":59,"prompt_cache_hit_tokens":0,"prompt_cache_miss_tokens":42}}
+
+data: [DONE]
+"#;
+
+const MOONSHOT_BODY: &str = r#"
+data: {"id":"cmpl-1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"role":"assistant","reasoning_content":"Let me check the target."},"finish_reason":null}]}
+
+data: {"id":"cmpl-1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"content":"I'll"},"finish_reason":null}]}
+
+data: {"id":"cmpl-1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"content":" fetch the page."},"finish_reason":null}]}
+
+data: {"id":"cmpl-1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"id":"http_request:0","type":"function","function":{"name":"http_request","arguments":"{\"url\":\"https"}}]},"finish_reason":null}]}
+
+data: {"id":"cmpl-1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"://example.com\"}"}}]},"finish_reason":null}]}
+
+data: {"id":"cmpl-1","object":"chat.completion.chunk","choices":[{"index":0,"delta":{},"finish_reason":"tool_calls","usage":null}],"usage":{"prompt_tokens":42,"completion_tokens":17,"total_tokens":59}}
+
+data: [DONE]
+"#;
+
+const OPENROUTER_BODY: &str = r#"
+: OPENROUTER PROCESSING
+
+data: {"id":"gen-1","provider":"OpenAI","model":"openai/gpt-4o","choices":[{"index":0,"delta":{"role":"assistant","content":"","reasoning":"Let me check the target."},"finish_reason":null}]}
+
+data: {"id":"gen-1","provider":"OpenAI","model":"openai/gpt-4o","choices":[{"index":0,"delta":{"role":"assistant","content":"I'll fetch the page."},"finish_reason":null}]}
+
+data: {"id":"gen-1","provider":"OpenAI","model":"openai/gpt-4o","choices":[{"index":0,"delta":{"role":"assistant","tool_calls":[{"index":0,"id":"call_or","type":"function","function":{"name":"http_request","arguments":""}}]},"finish_reason":null}]}
+
+data: {"id":"gen-1","provider":"OpenAI","model":"openai/gpt-4o","choices":[{"index":0,"delta":{"role":"assistant","tool_calls":[{"index":0,"function":{"arguments":"{\"url\":\"https://example.com\"}"}}]},"finish_reason":null}]}
+
+data: {"id":"gen-1","provider":"OpenAI","model":"openai/gpt-4o","choices":[{"index":0,"delta":{"role":"assistant","content":""},"finish_reason":"tool_calls"}]}
+
+data: {"id":"gen-1","provider":"OpenAI","model":"openai/gpt-4o","choices":[],"usage":{"prompt_tokens":42,"completion_tokens":17,"total_tokens":59}}
+
+data: [DONE]
+"#;
+
+const MODELSCOPE_BODY: &str = r#"
+data: {"id":"chatcmpl-ms","object":"chat.completion.chunk","model":"Qwen/Qwen3-32B","choices":[{"index":0,"delta":{"role":"assistant","content":"","reasoning_content":"Let me check the target."},"finish_reason":null}],"usage":null}
+
+data: {"id":"chatcmpl-ms","object":"chat.completion.chunk","model":"Qwen/Qwen3-32B","choices":[{"index":0,"delta":{"content":"I'll fetch the page.","reasoning_content":null},"finish_reason":null}],"usage":null}
+
+data: {"id":"chatcmpl-ms","object":"chat.completion.chunk","model":"Qwen/Qwen3-32B","choices":[{"index":0,"delta":{"content":null,"tool_calls":[{"index":0,"id":"call_ms","type":"function","function":{"name":"http_request","arguments":"{\"url\": "}}]},"finish_reason":null}],"usage":null}
+
+data: {"id":"chatcmpl-ms","object":"chat.completion.chunk","model":"Qwen/Qwen3-32B","choices":[{"index":0,"delta":{"content":null,"tool_calls":[{"index":0,"function":{"arguments":"\"https://example.com\"}"}}]},"finish_reason":null}],"usage":null}
+
+data: {"id":"chatcmpl-ms","object":"chat.completion.chunk","model":"Qwen/Qwen3-32B","choices":[{"index":0,"delta":{"content":""},"finish_reason":"tool_calls"}],"usage":{"prompt_tokens":42,"completion_tokens":17,"total_tokens":59}}
+
+data: [DONE]
+"#;
+
+const GEMINI_BODY: &str = r#"
+data: {"candidates":[{"content":{"parts":[{"text":"Let me check the target.","thought":true}],"role":"model"},"index":0}],"modelVersion":"gemini-2.5-flash"}
+
+data: {"candidates":[{"content":{"parts":[{"text":"I'll fetch the page."}],"role":"model"},"index":0}],"modelVersion":"gemini-2.5-flash"}
+
+data: {"candidates":[{"content":{"parts":[{"functionCall":{"name":"http_request","args":{"url":"https://example.com"}}}],"role":"model"},"finishReason":"STOP","index":0}],"usageMetadata":{"promptTokenCount":42,"candidatesTokenCount":17,"totalTokenCount":59},"modelVersion":"gemini-2.5-flash"}
+"#;
+
+fn usage(input_tokens: u32, output_tokens: u32) -> StreamContent {
+    StreamContent::Usage {
+        input_tokens,
+        output_tokens,
+        estimated: false,
+    }
+}
+
+/// 一轮工具调用加一轮最终回答的规范事件序列
+///
+/// `usage_in_stream` 为 false 时提供商的用量分片无法被解析，客户端在 Done 之前补发一次估算用量。
+fn expected_conversation(with_reasoning: bool, usage_in_stream: bool) -> Vec<StreamContent> {
+    let mut expected = Vec::new();
+    if with_reasoning {
+        expected.push(StreamContent::Reasoning(
+            "Let me check the target.".to_string(),
+        ));
+    }
+    expected.extend([
+        StreamContent::Text("I'll fetch the page.".to_string()),
+        StreamContent::ToolCallStart {
+            id: "call_0".to_string(),
+            name: "http_request".to_string(),
+        },
+        StreamContent::ToolCallDelta {
+            id: "call_0".to_string(),
+            delta: r#"{"url":"https://example.com"}"#.to_string(),
+        },
+        StreamContent::ToolCallComplete {
+            id: "call_0".to_string(),
+            name: "http_request".to_string(),
+            arguments: r#"{"url":"https://example.com"}"#.to_string(),
+        },
+        StreamContent::ToolResult {
+            id: "call_0".to_string(),
+            result: r#"[{"type":"text","text":"{\"status\":200}"}]"#.to_string(),
+        },
+    ]);
+    if usage_in_stream {
+        expected.push(usage(42, 17));
+    }
+    expected.push(StreamContent::Text("The page returned 200.".to_string()));
+    if usage_in_stream {
+        expected.push(usage(60, 5));
+    } else {
+        expected.push(StreamContent::Usage {
+            input_tokens: 0,
+            output_tokens: 0,
+            estimated: true,
+        });
+    }
+    expected.push(StreamContent::Done);
+    expected
+}
+
+const OPENAI_COMPATIBLE_ANSWER: &str = r#"
+data: {"id":"2","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"role":"assistant","content":"The page "},"finish_reason":null}]}
+
+data: {"id":"2","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"content":"returned 200."},"finish_reason":"stop"}],"usage":{"prompt_tokens":60,"completion_tokens":5,"total_tokens":65}}
+
+data: [DONE]
+"#;
+
+const DEEPSEEK_ANSWER: &str = r#"
+data: {"id":"2","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"role":"assistant","content":"The page "},"finish_reason":null}]}
+
+data: {"id":"2","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"content":"returned 200."},"finish_reason":"stop"}],"usage":{"prompt_tokens":60,"completion_tokens":5,"total_tokens":65,"prompt_cache_hit_tokens":42,"prompt_cache_miss_tokens":18}}
+
+data: [DONE]
+"#;
+
+const OPENROUTER_ANSWER: &str = r#"
+: OPENROUTER PROCESSING
+
+data: {"id":"gen-2","provider":"OpenAI","model":"openai/gpt-4o","choices":[{"index":0,"delta":{"role":"assistant","content":"The page returned 200."},"finish_reason":"stop"}]}
+
+data: {"id":"gen-2","provider":"OpenAI","model":"openai/gpt-4o","choices":[],"usage":{"prompt_tokens":60,"completion_tokens":5,"total_tokens":65}}
+
+data: [DONE]
+"#;
+
+const GEMINI_ANSWER: &str = r#"
+data: {"candidates":[{"content":{"parts":[{"text":"The page returned 200."}],"role":"model"},"finishReason":"STOP","index":0}],"usageMetadata":{"promptTokenCount":60,"candidatesTokenCount":5,"totalTokenCount":65},"modelVersion":"gemini-2.5-flash"}
+"#;
+
+#[tokio::test]
+async fn test_all_providers_emit_identical_events() {
+    // 客户端把请求日志写入当前目录下的 logs/，避免污染源码目录
+    let work_dir = std::env::temp_dir().join("sentinel-llm-stream-parity");
+    std::fs::create_dir_all(&work_dir).unwrap();
+    std::env::set_current_dir(&work_dir).unwrap();
+
+    // (provider, model, 工具调用轮, 回答轮, 是否含推理, 用量是否可从流中解析)
+    // OpenRouter 把用量放在 `choices` 为空的末尾分片里，rig 的解析器会跳过该分片
+    let cases = [
+        (
+            "openai",
+            "gpt-4o",
+            OPENAI_BODY,
+            OPENAI_COMPATIBLE_ANSWER,
+            false,
+            true,
+        ),
+        (
+            "deepseek",
+            "deepseek-reasoner",
+            DEEPSEEK_BODY,
+            DEEPSEEK_ANSWER,
+            true,
+            true,
+        ),
+        (
+            "moonshot",
+            "kimi-k2",
+            MOONSHOT_BODY,
+            OPENAI_COMPATIBLE_ANSWER,
+            true,
+            true,
+        ),
+        (
+            "openrouter",
+            "openai/gpt-4o",
+            OPENROUTER_BODY,
+            OPENROUTER_ANSWER,
+            true,
+            false,
+        ),
+        (
+            "modelscope",
+            "Qwen/Qwen3-32B",
+            MODELSCOPE_BODY,
+            OPENAI_COMPATIBLE_ANSWER,
+            true,
+            true,
+        ),
+        (
+            "gemini",
+            "gemini-2.5-flash",
+            GEMINI_BODY,
+            GEMINI_ANSWER,
+            true,
+            true,
+        ),
+    ];
+
+    for (provider, model, tool_turn, answer, with_reasoning, usage_in_stream) in cases {
+        let (events, requests) = run_provider(provider, model, vec![tool_turn, answer]).await;
+        assert_eq!(
+            canonicalize(events),
+            expected_conversation(with_reasoning, usage_in_stream),
+            "provider {} diverged",
+            provider
+        );
+        // 第二次请求必须带上工具执行结果
+        assert_eq!(requests.len(), 2, "provider {}", provider);
+        assert!(
+            requests[1].contains("status"),
+            "provider {} did not send the tool result back",
+            provider
+        );
+    }
+}
+
+#[test]
+fn test_tool_call_start_precedes_deltas() {
+    // 参数分片先于工具名到达时，应缓冲到 ToolCallStart 之后再发出
+    let events = normalize(vec![
+        ProviderStreamEvent::ToolCallArguments {
+            id: "c1".to_string(),
+            delta: "{}".to_string(),
+        },
+        ProviderStreamEvent::ToolCallName {
+            id: "c1".to_string(),
+            name: "noop".to_string(),
+        },
+        ProviderStreamEvent::ToolCall {
+            id: "c1".to_string(),
+            name: "noop".to_string(),
+            arguments: "{}".to_string(),
+        },
+    ]);
+
+    assert_eq!(
+        events,
+        vec![
+            StreamContent::ToolCallStart {
+                id: "c1".to_string(),
+                name: "noop".to_string(),
+            },
+            StreamContent::ToolCallDelta {
+                id: "c1".to_string(),
+                delta: "{}".to_string(),
+            },
+            StreamContent::ToolCallComplete {
+                id: "c1".to_string(),
+                name: "noop".to_string(),
+                arguments: "{}".to_string(),
+            },
+            StreamContent::Done,
+        ]
+    );
+}
+
+#[test]
+fn test_usage_is_not_double_counted() {
+    let events = normalize(vec![
+        ProviderStreamEvent::TurnUsage {
+            input_tokens: 30,
+            output_tokens: 10,
+        },
+        ProviderStreamEvent::TurnUsage {
+            input_tokens: 12,
+            output_tokens: 7,
+        },
+        ProviderStreamEvent::Final {
+            input_tokens: 42,
+            output_tokens: 17,
+        },
+    ]);
+
+    let (input, output) = events.iter().fold((0, 0), |(i, o), event| match event {
+        StreamContent::Usage {
+            input_tokens,
+            output_tokens,
+            ..
+        } => (i + input_tokens, o + output_tokens),
+        _ => (i, o),
+    });
+    assert_eq!((input, output), (42, 17));
+    assert_eq!(
+        events
+            .iter()
+            .filter(|e| matches!(e, StreamContent::Done))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_done_emitted_when_stream_closes_without_final() {
+    let events = normalize(vec![ProviderStreamEvent::Text("partial".to_string())]);
+    assert_eq!(
+        events,
+        vec![
+            StreamContent::Text("partial".to_string()),
+            StreamContent::Done
+        ]
+    );
+}
@@ -42,6 +42,13 @@ pub struct MemoryMatch {
     pub reasons: Vec<String>,
 }
 
+/// Prior executions rendered for a planning prompt, with the records that were used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanningMemoryContext {
+    pub text: String,
+    pub record_ids: Vec<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct MemoryConfig {
     pub max_records: usize,
@@ -151,6 +158,53 @@ impl MemoryManager {
         Ok(Some(lines.join("\n")))
     }
 
+    /// Successful prior executions similar to the task, rendered as reusable tool sequences.
+    /// Entries that would push the text past `max_chars` are left out.
+    pub async fn build_planning_context(
+        &self,
+        request: MemoryContextRequest,
+        max_chars: usize,
+    ) -> Result<Option<PlanningMemoryContext>> {
+        self.sync_from_db(500).await?;
+        let matches = self.query(request).await?;
+
+        let mut text = "[Memory Context: successful prior executions]\nReuse a tool sequence below when the current task is similar; adapt it if the target or scope differs.".to_string();
+        let mut record_ids = Vec::new();
+        for entry in matches
+            .iter()
+            .filter(|entry| entry.record.success && !entry.record.tool_calls.is_empty())
+        {
+            let record = &entry.record;
+            let sequence = record
+                .tool_calls
+                .iter()
+                .filter(|t| t.success)
+                .map(|t| t.name.as_str())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            if sequence.is_empty() {
+                continue;
+            }
+            let block = format!(
+                "\n{}. task='{}' score={:.2}\n   tool sequence: {}",
+                record_ids.len() + 1,
+                truncate(&record.task, 160),
+                entry.score,
+                sequence
+            );
+            if text.len() + block.len() > max_chars {
+                break;
+            }
+            text.push_str(&block);
+            record_ids.push(record.id.clone());
+        }
+
+        if record_ids.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(PlanningMemoryContext { text, record_ids }))
+    }
+
     async fn query(&self, request: MemoryContextRequest) -> Result<Vec<MemoryMatch>> {
         let store = self.store.read().await;
         let mut results = Vec::new();
@@ -376,4 +430,87 @@ mod tests {
 
         assert!(context.is_some());
     }
+
+    fn record(id: &str, task: &str, tools: &[(&str, bool)], success: bool) -> ExecutionRecord {
+        ExecutionRecord {
+            id: id.to_string(),
+            task: task.to_string(),
+            environment: None,
+            tool_calls: tools
+                .iter()
+                .map(|(name, success)| ToolCallSummary {
+                    name: name.to_string(),
+                    success: *success,
+                    duration_ms: None,
+                })
+                .collect(),
+            success,
+            error: None,
+            response_excerpt: None,
+            created_at: Utc::now().timestamp(),
+        }
+    }
+
+    #[tokio::test]
+    async fn planning_context_reuses_successful_tool_sequence() {
+        let memory = MemoryManager::new(MemoryConfig::default());
+        memory
+            .record_execution(record(
+                "recon-ok",
+                "full recon of example.com subdomains",
+                &[
+                    ("subdomain_enum", true),
+                    ("port_scan", true),
+                    ("http_probe", false),
+                    ("http_request", true),
+                ],
+                true,
+            ))
+            .await
+            .unwrap();
+        memory
+            .record_execution(record(
+                "recon-failed",
+                "full recon of example.com subdomains",
+                &[("port_scan", false)],
+                false,
+            ))
+            .await
+            .unwrap();
+
+        let request = MemoryContextRequest {
+            task: "full recon of example.org subdomains".to_string(),
+            environment: None,
+            tool_names: Vec::new(),
+            max_results: 3,
+        };
+        let context = memory
+            .build_planning_context(request.clone(), 2000)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(context.record_ids, ["recon-ok"]);
+        assert!(context
+            .text
+            .contains("tool sequence: subdomain_enum -> port_scan -> http_request"));
+
+        // A cap too small for any entry injects nothing
+        assert!(memory
+            .build_planning_context(request, 120)
+            .await
+            .unwrap()
+            .is_none());
+
+        let unrelated = MemoryContextRequest {
+            task: "write a phishing awareness report".to_string(),
+            environment: None,
+            tool_names: Vec::new(),
+            max_results: 3,
+        };
+        assert!(memory
+            .build_planning_context(unrelated, 2000)
+            .await
+            .unwrap()
+            .is_none());
+    }
 }
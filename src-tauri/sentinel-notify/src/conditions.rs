@@ -0,0 +1,255 @@
+//! Notification rule conditions
+//!
+//! A rule may carry conditions that are evaluated against the finding that
+//! triggered it. Every condition that is set must match; an empty condition set
+//! matches every event, so rules without conditions keep firing as before.
+
+use anyhow::{anyhow, Result};
+use sentinel_core::models::scan::Severity;
+use serde::{Deserialize, Serialize};
+
+/// Conditions attached to a notification rule (stored as JSON)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationConditions {
+    /// Lowest severity that fires the rule (`info`..`critical`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_severity: Option<String>,
+    /// Only fire for findings on hosts inside the engagement scope
+    #[serde(default)]
+    pub in_scope_only: bool,
+    /// Host patterns: `example.com` (and its subdomains), `*.example.com` or an exact IP
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<String>,
+    /// Plugin ids whose findings fire the rule
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugin_ids: Vec<String>,
+    /// Finding statuses that fire the rule (e.g. `open`, `confirmed`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub statuses: Vec<String>,
+}
+
+/// The finding a notification is about
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FindingEvent {
+    #[serde(default)]
+    pub severity: Option<String>,
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Whether the producer resolved the host as in scope
+    #[serde(default)]
+    pub in_scope: Option<bool>,
+    #[serde(default)]
+    pub plugin_id: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    match Severity::from_string(severity.trim()) {
+        Severity::Info => 0,
+        Severity::Low => 1,
+        Severity::Medium => 2,
+        Severity::High => 3,
+        Severity::Critical => 4,
+    }
+}
+
+fn is_known_severity(severity: &str) -> bool {
+    matches!(
+        severity.trim().to_lowercase().as_str(),
+        "info" | "low" | "medium" | "high" | "critical"
+    )
+}
+
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    let host = host.trim().trim_end_matches('.').to_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(base) => host.ends_with(&format!(".{}", base)),
+        None => host == pattern || host.ends_with(&format!(".{}", pattern)),
+    }
+}
+
+fn contains_ignore_case(values: &[String], value: Option<&str>) -> bool {
+    value.is_some_and(|value| {
+        values
+            .iter()
+            .any(|v| v.trim().eq_ignore_ascii_case(value.trim()))
+    })
+}
+
+impl NotificationConditions {
+    /// Parse the JSON stored on a rule; missing or blank means no conditions
+    pub fn parse(raw: Option<&str>) -> Result<Self> {
+        let conditions = match raw.map(str::trim).filter(|s| !s.is_empty()) {
+            Some(raw) => serde_json::from_str::<Self>(raw)
+                .map_err(|e| anyhow!("Invalid notification conditions: {}", e))?,
+            None => Self::default(),
+        };
+        conditions.validate()?;
+        Ok(conditions)
+    }
+
+    /// Parse the conditions of a rule about to be dispatched
+    ///
+    /// Invalid conditions skip the rule (`None`) instead of firing it for every
+    /// event.
+    pub fn for_rule(rule_id: &str, raw: Option<&str>) -> Option<Self> {
+        match Self::parse(raw) {
+            Ok(conditions) => Some(conditions),
+            Err(e) => {
+                tracing::warn!("Skipping notification rule {}: {}", rule_id, e);
+                None
+            }
+        }
+    }
+
+    /// Host whose scope the caller must resolve before matching, if any
+    ///
+    /// Only `in_scope_only` rules need it, and only when the producer did not
+    /// already say whether the host is in scope.
+    pub fn scope_to_resolve<'a>(&self, event: &'a FindingEvent) -> Option<&'a str> {
+        if self.in_scope_only && event.in_scope.is_none() {
+            event.host.as_deref()
+        } else {
+            None
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(min) = &self.min_severity {
+            if !is_known_severity(min) {
+                return Err(anyhow!("Unknown severity threshold: {}", min));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether `event` satisfies every condition that is set
+    ///
+    /// A condition on a field the event does not carry fails, so an unknown
+    /// severity never passes a severity threshold.
+    pub fn matches(&self, event: &FindingEvent) -> bool {
+        if let Some(min) = &self.min_severity {
+            match &event.severity {
+                Some(severity) if is_known_severity(severity) => {
+                    if severity_rank(severity) < severity_rank(min) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+        if self.in_scope_only && event.in_scope != Some(true) {
+            return false;
+        }
+        if !self.hosts.is_empty() {
+            let Some(host) = &event.host else {
+                return false;
+            };
+            if !self.hosts.iter().any(|pattern| host_matches(pattern, host)) {
+                return false;
+            }
+        }
+        if !self.plugin_ids.is_empty()
+            && !contains_ignore_case(&self.plugin_ids, event.plugin_id.as_deref())
+        {
+            return false;
+        }
+        if !self.statuses.is_empty()
+            && !contains_ignore_case(&self.statuses, event.status.as_deref())
+        {
+            return false;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(severity: &str, host: &str, in_scope: bool) -> FindingEvent {
+        FindingEvent {
+            severity: Some(severity.to_string()),
+            host: Some(host.to_string()),
+            in_scope: Some(in_scope),
+            plugin_id: Some("sqli-detector".to_string()),
+            status: Some("open".to_string()),
+        }
+    }
+
+    fn high_in_scope_rule() -> NotificationConditions {
+        NotificationConditions::parse(Some(
+            r#"{"min_severity": "high", "in_scope_only": true, "hosts": ["*.example.com"]}"#,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_low_severity_finding_is_filtered_out() {
+        let rule = high_in_scope_rule();
+        assert!(!rule.matches(&finding("low", "api.example.com", true)));
+        assert!(!rule.matches(&finding("medium", "api.example.com", true)));
+        // Out of scope or off the host list
+        assert!(!rule.matches(&finding("critical", "api.example.com", false)));
+        assert!(!rule.matches(&finding("critical", "example.org", true)));
+    }
+
+    #[test]
+    fn test_critical_in_scope_finding_triggers() {
+        let rule = high_in_scope_rule();
+        assert!(rule.matches(&finding("Critical", "api.example.com", true)));
+        assert!(rule.matches(&finding("high", "API.Example.com.", true)));
+
+        let rule = NotificationConditions {
+            plugin_ids: vec!["sqli-detector".to_string()],
+            statuses: vec!["open".to_string(), "confirmed".to_string()],
+            ..high_in_scope_rule()
+        };
+        assert!(rule.matches(&finding("critical", "api.example.com", true)));
+        let mut fixed = finding("critical", "api.example.com", true);
+        fixed.status = Some("fixed".to_string());
+        assert!(!rule.matches(&fixed));
+    }
+
+    #[test]
+    fn test_empty_and_invalid_conditions() {
+        let empty = NotificationConditions::parse(None).unwrap();
+        assert!(empty.is_empty());
+        assert!(empty.matches(&FindingEvent::default()));
+        assert!(NotificationConditions::parse(Some("  "))
+            .unwrap()
+            .is_empty());
+
+        assert!(NotificationConditions::parse(Some(r#"{"min_severity": "urgent"}"#)).is_err());
+        assert!(NotificationConditions::parse(Some("not json")).is_err());
+
+        // Unknown severity never passes a threshold
+        let rule = NotificationConditions {
+            min_severity: Some("info".to_string()),
+            ..Default::default()
+        };
+        assert!(!rule.matches(&FindingEvent::default()));
+    }
+
+    #[test]
+    fn test_scope_resolution_and_invalid_rules() {
+        let rule = high_in_scope_rule();
+        let mut event = finding("critical", "api.example.com", true);
+        assert_eq!(rule.scope_to_resolve(&event), None);
+        event.in_scope = None;
+        assert_eq!(rule.scope_to_resolve(&event), Some("api.example.com"));
+        assert_eq!(
+            NotificationConditions::default().scope_to_resolve(&event),
+            None
+        );
+
+        assert!(NotificationConditions::for_rule("r1", Some("not json")).is_none());
+        assert!(NotificationConditions::for_rule("r1", None).is_some());
+    }
+}
@@ -0,0 +1,385 @@
+//! Delivery tracking and retry queue
+//!
+//! Every tracked send produces a [`DeliveryRecord`] that is handed to the
+//! registered delivery listener (the app persists it). Failed sends are queued
+//! and re-attempted by a background worker with exponential backoff until
+//! [`RetryPolicy::max_attempts`] is reached.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::NotificationMessage;
+
+/// How often the background worker checks for due retries
+const RETRY_TICK: Duration = Duration::from_secs(5);
+
+/// Delivery state of a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Sent,
+    /// Failed and queued for another attempt
+    Retrying,
+    /// Failed and out of attempts (or not retried)
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Sent => "sent",
+            DeliveryStatus::Retrying => "retrying",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+/// Outcome of a notification delivery, updated on every attempt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeliveryRecord {
+    pub id: String,
+    pub rule_id: Option<String>,
+    pub channel: String,
+    pub title: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+}
+
+/// Backoff settings for failed sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first send
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(30),
+            max_delay: Duration::from_secs(30 * 60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before the next attempt after `attempts` failed attempts
+    pub fn delay_after(&self, attempts: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// A notification addressed to one channel
+#[derive(Debug, Clone)]
+pub struct OutgoingNotification {
+    pub rule_id: Option<String>,
+    pub channel: String,
+    pub config: Value,
+    pub message: NotificationMessage,
+}
+
+struct PendingDelivery {
+    record: DeliveryRecord,
+    outgoing: OutgoingNotification,
+    due_at_ms: i64,
+}
+
+/// Failed deliveries waiting for their next attempt
+#[derive(Default)]
+pub struct RetryQueue {
+    policy: RetryPolicy,
+    pending: Vec<PendingDelivery>,
+}
+
+impl RetryQueue {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self {
+            policy,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Apply the result of an attempt to `record`, queueing it again on failure
+    fn settle(
+        &mut self,
+        mut record: DeliveryRecord,
+        outgoing: OutgoingNotification,
+        result: Result<()>,
+        retry: bool,
+        now_ms: i64,
+    ) -> DeliveryRecord {
+        record.attempts += 1;
+        record.updated_at_ms = now_ms;
+        match result {
+            Ok(()) => {
+                record.status = DeliveryStatus::Sent;
+                record.last_error = None;
+            }
+            Err(e) => {
+                record.last_error = Some(e.to_string());
+                if retry && record.attempts < self.policy.max_attempts {
+                    record.status = DeliveryStatus::Retrying;
+                    let delay = self.policy.delay_after(record.attempts);
+                    self.pending.push(PendingDelivery {
+                        record: record.clone(),
+                        outgoing,
+                        due_at_ms: now_ms + delay.as_millis() as i64,
+                    });
+                } else {
+                    record.status = DeliveryStatus::Failed;
+                }
+            }
+        }
+        record
+    }
+
+    fn take_due(&mut self, now_ms: i64) -> Vec<PendingDelivery> {
+        let (due, waiting) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|p| p.due_at_ms <= now_ms);
+        self.pending = waiting;
+        due
+    }
+}
+
+/// Send one notification and record the outcome; failures are queued when `retry` is set
+pub async fn deliver<S, Fut>(
+    queue: &Mutex<RetryQueue>,
+    sender: &S,
+    outgoing: OutgoingNotification,
+    retry: bool,
+    now_ms: i64,
+) -> DeliveryRecord
+where
+    S: Fn(OutgoingNotification) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let record = DeliveryRecord {
+        id: uuid::Uuid::new_v4().to_string(),
+        rule_id: outgoing.rule_id.clone(),
+        channel: outgoing.channel.clone(),
+        title: outgoing.message.title.clone(),
+        status: DeliveryStatus::Failed,
+        attempts: 0,
+        last_error: None,
+        created_at_ms: now_ms,
+        updated_at_ms: now_ms,
+    };
+    let result = sender(outgoing.clone()).await;
+    lock(queue).settle(record, outgoing, result, retry, now_ms)
+}
+
+/// Re-attempt every queued delivery that is due
+pub async fn retry_due<S, Fut>(
+    queue: &Mutex<RetryQueue>,
+    sender: &S,
+    now_ms: i64,
+) -> Vec<DeliveryRecord>
+where
+    S: Fn(OutgoingNotification) -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let due = lock(queue).take_due(now_ms);
+    let mut records = Vec::with_capacity(due.len());
+    for pending in due {
+        let result = sender(pending.outgoing.clone()).await;
+        records.push(lock(queue).settle(pending.record, pending.outgoing, result, true, now_ms));
+    }
+    records
+}
+
+/// Receives every delivery record (initial attempt and retries)
+pub type DeliveryListener = Arc<dyn Fn(DeliveryRecord) + Send + Sync>;
+
+static RETRY_QUEUE: LazyLock<Mutex<RetryQueue>> =
+    LazyLock::new(|| Mutex::new(RetryQueue::new(RetryPolicy::default())));
+static DELIVERY_LISTENER: RwLock<Option<DeliveryListener>> = RwLock::new(None);
+static RETRY_WORKER_STARTED: AtomicBool = AtomicBool::new(false);
+
+fn lock(queue: &Mutex<RetryQueue>) -> std::sync::MutexGuard<'_, RetryQueue> {
+    queue.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+/// Register the listener that persists delivery records
+pub fn set_delivery_listener(listener: DeliveryListener) {
+    *DELIVERY_LISTENER.write().unwrap_or_else(|e| e.into_inner()) = Some(listener);
+}
+
+fn publish(record: &DeliveryRecord) {
+    let listener = DELIVERY_LISTENER
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    if let Some(listener) = listener {
+        listener(record.clone());
+    }
+}
+
+async fn channel_sender(outgoing: OutgoingNotification) -> Result<()> {
+    crate::send(&outgoing.channel, outgoing.config, outgoing.message).await
+}
+
+fn ensure_retry_worker() {
+    if RETRY_WORKER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tokio::spawn(async {
+        let mut tick = tokio::time::interval(RETRY_TICK);
+        loop {
+            tick.tick().await;
+            for record in retry_due(&RETRY_QUEUE, &channel_sender, now_ms()).await {
+                if record.status != DeliveryStatus::Sent {
+                    tracing::warn!(
+                        "Notification retry {} for rule {:?} on {} failed (attempt {}): {}",
+                        record.id,
+                        record.rule_id,
+                        record.channel,
+                        record.attempts,
+                        record.last_error.as_deref().unwrap_or_default()
+                    );
+                }
+                publish(&record);
+            }
+        }
+    });
+}
+
+/// Send through a channel, record the delivery and queue a retry on failure
+pub async fn send_tracked(outgoing: OutgoingNotification, retry: bool) -> DeliveryRecord {
+    let record = deliver(&RETRY_QUEUE, &channel_sender, outgoing, retry, now_ms()).await;
+    if record.status == DeliveryStatus::Retrying {
+        ensure_retry_worker();
+    }
+    publish(&record);
+    record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    fn outgoing(rule_id: Option<&str>, channel: &str) -> OutgoingNotification {
+        OutgoingNotification {
+            rule_id: rule_id.map(str::to_string),
+            channel: channel.to_string(),
+            config: serde_json::json!({}),
+            message: NotificationMessage {
+                title: "Critical finding".to_string(),
+                content: "SQL injection on api.example.com".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failing_channel_enqueues_then_succeeds_on_retry() {
+        let queue = Mutex::new(RetryQueue::new(RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(60),
+        }));
+        // Channel is down for the first two attempts
+        let calls = AtomicU32::new(0);
+        let sender = |_outgoing: OutgoingNotification| {
+            let attempt = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            async move {
+                if attempt <= 2 {
+                    anyhow::bail!("connection refused")
+                }
+                Ok(())
+            }
+        };
+
+        let first = deliver(
+            &queue,
+            &sender,
+            outgoing(Some("rule-1"), "webhook"),
+            true,
+            0,
+        )
+        .await;
+        assert_eq!(first.status, DeliveryStatus::Retrying);
+        assert_eq!(first.attempts, 1);
+        assert_eq!(first.last_error.as_deref(), Some("connection refused"));
+        assert_eq!(lock(&queue).len(), 1);
+
+        // Not due before the backoff elapses
+        assert!(retry_due(&queue, &sender, 9_999).await.is_empty());
+
+        let second = retry_due(&queue, &sender, 10_000).await;
+        assert_eq!(second[0].status, DeliveryStatus::Retrying);
+        assert_eq!(second[0].attempts, 2);
+        assert_eq!(second[0].id, first.id);
+        // Backoff doubles: 20s after the second failure
+        assert!(retry_due(&queue, &sender, 29_999).await.is_empty());
+
+        let third = retry_due(&queue, &sender, 30_000).await;
+        assert_eq!(third[0].status, DeliveryStatus::Sent);
+        assert_eq!(third[0].attempts, 3);
+        assert_eq!(third[0].last_error, None);
+        assert!(lock(&queue).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_attempt_limit_and_untracked_retry() {
+        let queue = Mutex::new(RetryQueue::new(RetryPolicy {
+            max_attempts: 2,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(1),
+        }));
+        let sender =
+            |_outgoing: OutgoingNotification| async { Err::<(), _>(anyhow::anyhow!("HTTP 500")) };
+
+        // Connection tests are recorded but not retried
+        let test = deliver(&queue, &sender, outgoing(None, "dingtalk"), false, 0).await;
+        assert_eq!(test.status, DeliveryStatus::Failed);
+        assert!(lock(&queue).is_empty());
+
+        deliver(
+            &queue,
+            &sender,
+            outgoing(Some("rule-1"), "dingtalk"),
+            true,
+            0,
+        )
+        .await;
+        let last = retry_due(&queue, &sender, 1_000).await;
+        assert_eq!(last[0].status, DeliveryStatus::Failed);
+        assert_eq!(last[0].attempts, 2);
+        assert!(lock(&queue).is_empty());
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay_after(1), Duration::from_secs(30));
+        assert_eq!(policy.delay_after(2), Duration::from_secs(60));
+        assert_eq!(policy.delay_after(20), Duration::from_secs(30 * 60));
+    }
+}
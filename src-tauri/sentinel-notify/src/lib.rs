@@ -3,6 +3,17 @@ use base64::Engine as _;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+pub mod conditions;
+pub mod delivery;
+pub mod signing;
+pub mod throttle;
+
+pub use conditions::{FindingEvent, NotificationConditions};
+pub use delivery::{
+    send_tracked, set_delivery_listener, DeliveryRecord, DeliveryStatus, OutgoingNotification,
+};
+pub use throttle::{send_throttled, ThrottleConfig, ThrottleDecision};
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct NotificationMessage {
     pub title: String,
@@ -32,17 +43,55 @@ pub async fn send(channel: &str, config: Value, message: NotificationMessage) ->
     }
 }
 
+/// Send only when the finding satisfies the rule's conditions
+///
+/// Returns `Ok(false)` when the rule was skipped because its conditions did not match.
+pub async fn send_if_matches(
+    channel: &str,
+    config: Value,
+    conditions: &NotificationConditions,
+    event: &FindingEvent,
+    message: NotificationMessage,
+) -> Result<bool> {
+    if !conditions.matches(event) {
+        return Ok(false);
+    }
+    send(channel, config, message).await?;
+    Ok(true)
+}
+
 async fn send_webhook(config: Value, message: &NotificationMessage) -> Result<()> {
-    let url = read_str(&config, "webhook_url")
-        .or_else(|| read_str(&config, "url"))
-        .ok_or_else(|| anyhow!("Missing webhook url"))?;
-    let method = read_str(&config, "method").unwrap_or_else(|| "POST".to_string());
     // Apply global proxy configuration
     let builder = reqwest::Client::builder();
     let builder = sentinel_core::global_proxy::apply_proxy_to_client(builder).await;
     let client = builder.build().unwrap_or_else(|_| reqwest::Client::new());
 
-    let body_template = read_str(&config, "body_template");
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let res = webhook_request(&client, &config, message, timestamp)?
+        .send()
+        .await?;
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(anyhow!("Webhook status: {}", res.status()))
+    }
+}
+
+/// Build the webhook request, signing the body when `signing_secret` is set
+fn webhook_request(
+    client: &reqwest::Client,
+    config: &Value,
+    message: &NotificationMessage,
+    timestamp: u64,
+) -> Result<reqwest::RequestBuilder> {
+    let url = read_str(config, "webhook_url")
+        .or_else(|| read_str(config, "url"))
+        .ok_or_else(|| anyhow!("Missing webhook url"))?;
+    let method = read_str(config, "method").unwrap_or_else(|| "POST".to_string());
+
+    let body_template = read_str(config, "body_template");
     let payload = if let Some(tpl) = body_template {
         match serde_json::from_str::<Value>(&tpl) {
             Ok(mut v) => {
@@ -61,7 +110,7 @@ async fn send_webhook(config: Value, message: &NotificationMessage) -> Result<()
         serde_json::json!({"title": message.title, "content": message.content})
     };
 
-    let headers_json = read_str(&config, "headers_json");
+    let headers_json = read_str(config, "headers_json");
     let mut req = match method.as_str() {
         "GET" => client.get(&url),
         "POST" => client.post(&url),
@@ -78,12 +127,23 @@ async fn send_webhook(config: Value, message: &NotificationMessage) -> Result<()
             }
         }
     }
-    let res = req.json(&payload).send().await?;
-    if res.status().is_success() {
-        Ok(())
-    } else {
-        Err(anyhow!("Webhook status: {}", res.status()))
+
+    // Serialize once so the signature covers the exact bytes that are sent
+    let body = serde_json::to_vec(&payload)?;
+    if let Some(secret) = read_str(config, "signing_secret").filter(|s| !s.is_empty()) {
+        let header = read_str(config, "signature_header")
+            .filter(|h| !h.trim().is_empty())
+            .unwrap_or_else(|| signing::DEFAULT_SIGNATURE_HEADER.to_string());
+        req = req
+            .header(signing::TIMESTAMP_HEADER, timestamp.to_string())
+            .header(
+                header.trim(),
+                signing::sign_payload(&secret, timestamp, &body),
+            );
     }
+    Ok(req
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body))
 }
 
 async fn send_dingtalk(config: Value, message: &NotificationMessage) -> Result<()> {
@@ -359,3 +419,70 @@ async fn send_email(config: Value, message: &NotificationMessage) -> Result<()>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    fn message() -> NotificationMessage {
+        NotificationMessage {
+            title: "Critical finding".to_string(),
+            content: "SQL injection on api.example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_webhook_signature_matches_independent_hmac() {
+        let config = serde_json::json!({
+            "webhook_url": "https://hooks.example.com/sentinel",
+            "signing_secret": "s3cret",
+            "signature_header": "X-Hub-Signature",
+        });
+        let request = webhook_request(&reqwest::Client::new(), &config, &message(), 1_700_000_000)
+            .unwrap()
+            .build()
+            .unwrap();
+        let body = request.body().and_then(|b| b.as_bytes()).unwrap();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"s3cret").unwrap();
+        mac.update(format!("1700000000.{}", String::from_utf8_lossy(body)).as_bytes());
+        let expected: String = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+
+        let headers = request.headers();
+        assert_eq!(headers["X-Sentinel-Timestamp"], "1700000000");
+        assert_eq!(
+            headers["X-Hub-Signature"],
+            format!("sha256={}", expected).as_str()
+        );
+        assert!(signing::verify_signature(
+            "s3cret",
+            1_700_000_000,
+            body,
+            headers["X-Hub-Signature"].to_str().unwrap(),
+            1_700_000_030,
+            signing::DEFAULT_TOLERANCE_SECS,
+        ));
+    }
+
+    #[test]
+    fn test_webhook_without_secret_is_unsigned() {
+        let config = serde_json::json!({ "webhook_url": "https://hooks.example.com/sentinel" });
+        let request = webhook_request(&reqwest::Client::new(), &config, &message(), 1_700_000_000)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(request
+            .headers()
+            .get(signing::DEFAULT_SIGNATURE_HEADER)
+            .is_none());
+        assert!(request.headers().get(signing::TIMESTAMP_HEADER).is_none());
+        assert_eq!(request.headers()["content-type"], "application/json");
+    }
+}
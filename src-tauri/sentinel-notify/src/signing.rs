@@ -0,0 +1,117 @@
+//! Webhook request signing
+//!
+//! When a webhook rule has a `signing_secret`, every request carries two
+//! headers so receivers can check that it came from us and is fresh:
+//!
+//! - `X-Sentinel-Timestamp`: Unix time in seconds when the request was signed
+//! - `X-Sentinel-Signature` (name configurable via `signature_header`):
+//!   `sha256=<hex>` where `<hex>` is the lowercase hex HMAC-SHA256, keyed with
+//!   the secret, of the string `"{timestamp}.{body}"`
+//!
+//! `body` is the exact raw request body as sent (UTF-8 JSON bytes, not
+//! re-serialized). Receivers should recompute the HMAC over the raw body,
+//! compare in constant time and reject timestamps outside a small window
+//! (see [`verify_signature`]) to prevent replay.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt::Write as _;
+
+pub const DEFAULT_SIGNATURE_HEADER: &str = "X-Sentinel-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Sentinel-Timestamp";
+/// Default replay window for [`verify_signature`]
+pub const DEFAULT_TOLERANCE_SECS: u64 = 300;
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+fn hmac_hex(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    // HMAC accepts keys of any length, so this cannot fail
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC key");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{:02x}", byte);
+            hex
+        })
+}
+
+/// Signature header value (`sha256=<hex>`) for a request body
+pub fn sign_payload(secret: &str, timestamp: u64, body: &[u8]) -> String {
+    format!("{}{}", SIGNATURE_PREFIX, hmac_hex(secret, timestamp, body))
+}
+
+/// Check a received signature; intended for receivers and tests
+///
+/// Fails when the timestamp is more than `tolerance_secs` away from `now_secs`.
+pub fn verify_signature(
+    secret: &str,
+    timestamp: u64,
+    body: &[u8],
+    signature: &str,
+    now_secs: u64,
+    tolerance_secs: u64,
+) -> bool {
+    if now_secs.abs_diff(timestamp) > tolerance_secs {
+        return false;
+    }
+    let Some(hex) = signature.trim().strip_prefix(SIGNATURE_PREFIX) else {
+        return false;
+    };
+    let expected = hmac_hex(secret, timestamp, body);
+    // Constant-time comparison
+    expected.len() == hex.len()
+        && expected
+            .bytes()
+            .zip(hex.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_signature_rejects_tampering_and_replay() {
+        let body = br#"{"title":"t","content":"c"}"#;
+        let signature = sign_payload("secret", 1_700_000_000, body);
+        assert!(signature.starts_with("sha256="));
+        assert!(verify_signature(
+            "secret",
+            1_700_000_000,
+            body,
+            &signature,
+            1_700_000_100,
+            DEFAULT_TOLERANCE_SECS
+        ));
+        // Wrong secret, modified body, stale timestamp
+        assert!(!verify_signature(
+            "other",
+            1_700_000_000,
+            body,
+            &signature,
+            1_700_000_000,
+            DEFAULT_TOLERANCE_SECS
+        ));
+        assert!(!verify_signature(
+            "secret",
+            1_700_000_000,
+            b"{}",
+            &signature,
+            1_700_000_000,
+            DEFAULT_TOLERANCE_SECS
+        ));
+        assert!(!verify_signature(
+            "secret",
+            1_700_000_000,
+            body,
+            &signature,
+            1_700_001_000,
+            DEFAULT_TOLERANCE_SECS
+        ));
+    }
+}
@@ -0,0 +1,497 @@
+//! Notification deduplication and digest batching
+//!
+//! Sits in front of [`crate::delivery`]. Identical notifications for the same rule
+//! are suppressed inside a dedup window. When a digest interval is set, events
+//! are buffered per rule and flushed as a single digest message once the
+//! interval has elapsed since the first buffered event.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::delivery::{send_tracked, DeliveryStatus, OutgoingNotification};
+use crate::{read_bool, read_str, NotificationMessage};
+
+/// How often the background flusher checks for due digests
+const DIGEST_FLUSH_TICK: Duration = Duration::from_secs(5);
+/// Events listed in a digest body; the rest are summarised as a count
+const MAX_DIGEST_ITEMS: usize = 50;
+
+/// Throttle settings stored on a notification rule (JSON)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThrottleConfig {
+    /// Suppress identical notifications within this window (0 or unset disables)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedup_window_secs: Option<u64>,
+    /// Batch events into one digest sent on this interval (0 or unset sends immediately)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest_interval_secs: Option<u64>,
+}
+
+impl ThrottleConfig {
+    /// Parse the JSON stored on a rule; missing or blank means no throttling
+    pub fn parse(raw: Option<&str>) -> Result<Self> {
+        match raw.map(str::trim).filter(|s| !s.is_empty()) {
+            Some(raw) => serde_json::from_str(raw)
+                .map_err(|e| anyhow!("Invalid notification throttle config: {}", e)),
+            None => Ok(Self::default()),
+        }
+    }
+
+    fn dedup_window_ms(&self) -> Option<i64> {
+        self.dedup_window_secs
+            .filter(|secs| *secs > 0)
+            .map(|secs| secs as i64 * 1000)
+    }
+
+    fn digest_interval_ms(&self) -> Option<i64> {
+        self.digest_interval_secs
+            .filter(|secs| *secs > 0)
+            .map(|secs| secs as i64 * 1000)
+    }
+}
+
+/// What the throttle did with a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThrottleDecision {
+    /// Send right away
+    Send,
+    /// Identical notification already sent inside the dedup window
+    Suppressed,
+    /// Buffered for the next digest
+    Queued,
+}
+
+impl ThrottleDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThrottleDecision::Send => "sent",
+            ThrottleDecision::Suppressed => "suppressed",
+            ThrottleDecision::Queued => "queued",
+        }
+    }
+}
+
+/// Buffered events of one rule, flushed as a single message
+#[derive(Debug, Clone)]
+pub struct Digest {
+    pub rule_id: String,
+    pub channel: String,
+    pub config: Value,
+    pub interval_secs: u64,
+    pub first_at_ms: i64,
+    pub messages: Vec<NotificationMessage>,
+}
+
+enum DigestFormat {
+    Text,
+    Markdown,
+    Html,
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn plural(count: u64, unit: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, unit)
+    } else {
+        format!("{} {}s", count, unit)
+    }
+}
+
+fn interval_label(secs: u64) -> String {
+    match secs {
+        s if s % 3600 == 0 => plural(s / 3600, "hour"),
+        s if s % 60 == 0 => plural(s / 60, "minute"),
+        s => plural(s, "second"),
+    }
+}
+
+impl Digest {
+    fn format(&self) -> DigestFormat {
+        match self.channel.as_str() {
+            "email" if read_bool(&self.config, "email_is_html").unwrap_or(false) => {
+                DigestFormat::Html
+            }
+            "dingtalk" | "feishu" | "wecom"
+                if read_str(&self.config, "message_type").as_deref() == Some("markdown") =>
+            {
+                DigestFormat::Markdown
+            }
+            _ => DigestFormat::Text,
+        }
+    }
+
+    /// Render the digest in the format the rule's channel displays
+    pub fn render(&self) -> NotificationMessage {
+        let title = format!(
+            "{} in the last {}",
+            plural(self.messages.len() as u64, "new notification"),
+            interval_label(self.interval_secs)
+        );
+        let format = self.format();
+        let mut lines: Vec<String> = self
+            .messages
+            .iter()
+            .take(MAX_DIGEST_ITEMS)
+            .enumerate()
+            .map(|(i, m)| {
+                let content = m.content.trim();
+                match format {
+                    DigestFormat::Markdown if content.is_empty() => format!("- **{}**", m.title),
+                    DigestFormat::Markdown => format!("- **{}**: {}", m.title, content),
+                    DigestFormat::Html => format!(
+                        "<li><b>{}</b> {}</li>",
+                        escape_html(&m.title),
+                        escape_html(content)
+                    ),
+                    DigestFormat::Text if content.is_empty() => format!("{}. {}", i + 1, m.title),
+                    DigestFormat::Text => format!("{}. {}: {}", i + 1, m.title, content),
+                }
+            })
+            .collect();
+        let hidden = self.messages.len().saturating_sub(MAX_DIGEST_ITEMS);
+        if hidden > 0 {
+            lines.push(match format {
+                DigestFormat::Html => format!("<li>... and {} more</li>", hidden),
+                _ => format!("... and {} more", hidden),
+            });
+        }
+        let content = match format {
+            DigestFormat::Html => format!("<ul>{}</ul>", lines.concat()),
+            DigestFormat::Markdown | DigestFormat::Text => lines.join("\n"),
+        };
+        NotificationMessage { title, content }
+    }
+
+    /// Channel config for sending the digest (a fixed markdown template would hide it)
+    pub fn send_config(&self) -> Value {
+        let mut config = self.config.clone();
+        if let Some(obj) = config.as_object_mut() {
+            obj.remove("markdown_text");
+        }
+        config
+    }
+}
+
+fn fingerprint(message: &NotificationMessage) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    message.title.trim().hash(&mut hasher);
+    message.content.trim().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Dedup and digest state, keyed by rule id
+#[derive(Default)]
+pub struct NotificationThrottle {
+    last_sent: HashMap<(String, u64), i64>,
+    pending: HashMap<String, Digest>,
+}
+
+impl NotificationThrottle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decide what to do with `message`; queued messages are kept until
+    /// [`take_due_digests`](Self::take_due_digests) returns them
+    pub fn admit(
+        &mut self,
+        rule_id: &str,
+        channel: &str,
+        config: &Value,
+        throttle: &ThrottleConfig,
+        message: &NotificationMessage,
+        now_ms: i64,
+    ) -> ThrottleDecision {
+        if let Some(window) = throttle.dedup_window_ms() {
+            self.last_sent
+                .retain(|(rule, _), sent_at| rule != rule_id || now_ms - *sent_at < window);
+            let key = (rule_id.to_string(), fingerprint(message));
+            if self.last_sent.contains_key(&key) {
+                return ThrottleDecision::Suppressed;
+            }
+            self.last_sent.insert(key, now_ms);
+        }
+
+        let Some(interval) = throttle.digest_interval_ms() else {
+            return ThrottleDecision::Send;
+        };
+        self.pending
+            .entry(rule_id.to_string())
+            .or_insert_with(|| Digest {
+                rule_id: rule_id.to_string(),
+                channel: channel.to_string(),
+                config: config.clone(),
+                interval_secs: (interval / 1000) as u64,
+                first_at_ms: now_ms,
+                messages: Vec::new(),
+            })
+            .messages
+            .push(message.clone());
+        ThrottleDecision::Queued
+    }
+
+    /// Remove and return digests whose interval has elapsed
+    pub fn take_due_digests(&mut self, now_ms: i64) -> Vec<Digest> {
+        let due: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, d)| now_ms - d.first_at_ms >= d.interval_secs as i64 * 1000)
+            .map(|(rule_id, _)| rule_id.clone())
+            .collect();
+        due.into_iter()
+            .filter_map(|rule_id| self.pending.remove(&rule_id))
+            .collect()
+    }
+
+    pub fn pending_count(&self, rule_id: &str) -> usize {
+        self.pending
+            .get(rule_id)
+            .map(|d| d.messages.len())
+            .unwrap_or(0)
+    }
+}
+
+static THROTTLE: LazyLock<Mutex<NotificationThrottle>> =
+    LazyLock::new(|| Mutex::new(NotificationThrottle::new()));
+static FLUSHER_STARTED: AtomicBool = AtomicBool::new(false);
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+async fn flush_due_digests() {
+    let due = THROTTLE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take_due_digests(now_ms());
+    for digest in due {
+        let record = send_tracked(
+            OutgoingNotification {
+                rule_id: Some(digest.rule_id.clone()),
+                channel: digest.channel.clone(),
+                config: digest.send_config(),
+                message: digest.render(),
+            },
+            true,
+        )
+        .await;
+        if record.status != DeliveryStatus::Sent {
+            tracing::warn!(
+                "Failed to send notification digest for rule {}: {}",
+                digest.rule_id,
+                record.last_error.as_deref().unwrap_or_default()
+            );
+        }
+    }
+}
+
+fn ensure_flusher() {
+    if FLUSHER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tokio::spawn(async {
+        let mut tick = tokio::time::interval(DIGEST_FLUSH_TICK);
+        loop {
+            tick.tick().await;
+            flush_due_digests().await;
+        }
+    });
+}
+
+/// Send through the rule's dedup/digest settings
+pub async fn send_throttled(
+    rule_id: &str,
+    channel: &str,
+    config: Value,
+    throttle: &ThrottleConfig,
+    message: NotificationMessage,
+) -> Result<ThrottleDecision> {
+    let decision = THROTTLE.lock().unwrap_or_else(|e| e.into_inner()).admit(
+        rule_id,
+        channel,
+        &config,
+        throttle,
+        &message,
+        now_ms(),
+    );
+    match decision {
+        ThrottleDecision::Send => {
+            let record = send_tracked(
+                OutgoingNotification {
+                    rule_id: Some(rule_id.to_string()),
+                    channel: channel.to_string(),
+                    config,
+                    message,
+                },
+                true,
+            )
+            .await;
+            if record.status != DeliveryStatus::Sent {
+                return Err(anyhow!(
+                    "{} (delivery {}: {})",
+                    record.last_error.unwrap_or_default(),
+                    record.id,
+                    record.status.as_str()
+                ));
+            }
+        }
+        ThrottleDecision::Queued => ensure_flusher(),
+        ThrottleDecision::Suppressed => {}
+    }
+    Ok(decision)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(title: &str) -> NotificationMessage {
+        NotificationMessage {
+            title: title.to_string(),
+            content: "host: api.example.com".to_string(),
+        }
+    }
+
+    fn admit(
+        throttle: &mut NotificationThrottle,
+        config: &ThrottleConfig,
+        title: &str,
+        now_ms: i64,
+    ) -> ThrottleDecision {
+        throttle.admit(
+            "rule-1",
+            "webhook",
+            &json!({}),
+            config,
+            &message(title),
+            now_ms,
+        )
+    }
+
+    #[test]
+    fn test_identical_notifications_suppressed_within_window() {
+        let config = ThrottleConfig::parse(Some(r#"{"dedup_window_secs": 60}"#)).unwrap();
+        let mut throttle = NotificationThrottle::new();
+
+        assert_eq!(
+            admit(&mut throttle, &config, "SQLi", 0),
+            ThrottleDecision::Send
+        );
+        assert_eq!(
+            admit(&mut throttle, &config, "SQLi", 30_000),
+            ThrottleDecision::Suppressed
+        );
+        assert_eq!(
+            admit(&mut throttle, &config, "XSS", 30_000),
+            ThrottleDecision::Send
+        );
+        // Other rules keep their own history
+        assert_eq!(
+            throttle.admit(
+                "rule-2",
+                "webhook",
+                &json!({}),
+                &config,
+                &message("SQLi"),
+                30_000
+            ),
+            ThrottleDecision::Send
+        );
+        // Window expired
+        assert_eq!(
+            admit(&mut throttle, &config, "SQLi", 60_000),
+            ThrottleDecision::Send
+        );
+
+        let off = ThrottleConfig::default();
+        assert_eq!(
+            admit(&mut throttle, &off, "SQLi", 60_001),
+            ThrottleDecision::Send
+        );
+    }
+
+    #[test]
+    fn test_digest_aggregates_until_interval_elapses() {
+        let config = ThrottleConfig {
+            dedup_window_secs: Some(600),
+            digest_interval_secs: Some(300),
+        };
+        let mut throttle = NotificationThrottle::new();
+        for (i, title) in ["SQLi", "XSS", "SQLi", "SSRF"].iter().enumerate() {
+            let decision = admit(&mut throttle, &config, title, i as i64 * 1000);
+            let expected = if i == 2 {
+                ThrottleDecision::Suppressed
+            } else {
+                ThrottleDecision::Queued
+            };
+            assert_eq!(decision, expected);
+        }
+        assert_eq!(throttle.pending_count("rule-1"), 3);
+
+        assert!(throttle.take_due_digests(299_999).is_empty());
+        let due = throttle.take_due_digests(300_000);
+        assert_eq!(due.len(), 1);
+        assert_eq!(throttle.pending_count("rule-1"), 0);
+
+        let rendered = due[0].render();
+        assert_eq!(rendered.title, "3 new notifications in the last 5 minutes");
+        assert!(rendered
+            .content
+            .starts_with("1. SQLi: host: api.example.com"));
+        assert!(rendered.content.contains("3. SSRF"));
+
+        // A new event after the flush opens a fresh digest window
+        assert_eq!(
+            admit(&mut throttle, &config, "RCE", 301_000),
+            ThrottleDecision::Queued
+        );
+        assert!(throttle.take_due_digests(400_000).is_empty());
+        assert_eq!(throttle.take_due_digests(601_000).len(), 1);
+    }
+
+    #[test]
+    fn test_digest_renders_per_channel() {
+        let digest = |channel: &str, config: Value| Digest {
+            rule_id: "rule-1".to_string(),
+            channel: channel.to_string(),
+            config,
+            interval_secs: 60,
+            first_at_ms: 0,
+            messages: vec![message("<SQLi>")],
+        };
+
+        let markdown = digest(
+            "dingtalk",
+            json!({"message_type": "markdown", "markdown_text": "fixed"}),
+        );
+        assert!(markdown
+            .render()
+            .content
+            .contains("- **<SQLi>**: host: api.example.com"));
+        assert!(markdown.send_config().get("markdown_text").is_none());
+
+        let html = digest("email", json!({"email_is_html": true})).render();
+        assert!(html.content.starts_with("<ul><li><b>&lt;SQLi&gt;</b>"));
+
+        let text = digest("dingtalk", json!({})).render();
+        assert_eq!(text.content, "1. <SQLi>: host: api.example.com");
+        assert_eq!(text.title, "1 new notification in the last 1 minute");
+    }
+}
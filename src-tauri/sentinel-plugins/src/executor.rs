@@ -527,6 +527,8 @@ export function scan_transaction(transaction) {
                 edited_url: None,
                 edited_headers: None,
                 edited_body: None,
+                body_truncated: false,
+                body_size: None,
             },
             response: None,
         }
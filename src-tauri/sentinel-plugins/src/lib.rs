@@ -10,6 +10,7 @@
 //!
 //! - `plugin_engine`: Deno Core 插件引擎
 //! - `plugin`: 插件管理器（PluginManager）
+//! - `plugin_metrics`: 插件执行指标（调用次数、耗时分位数、错误数）
 //! - `types`: 核心类型（Finding, RequestContext, ResponseContext 等）
 //! - `error`: 错误类型
 //!
@@ -25,6 +26,7 @@ pub mod error;
 pub mod executor;
 pub mod plugin;
 pub mod plugin_engine;
+pub mod plugin_metrics;
 pub mod plugin_ops;
 pub mod types;
 
@@ -35,6 +37,7 @@ pub use plugin::{
     PluginStatus,
 };
 pub use plugin_engine::PluginEngine;
+pub use plugin_metrics::{PluginExecutionMetrics, PluginMetrics, PluginMetricsRecord};
 pub use plugin_ops::{init_dictionary_pool, sentinel_plugin_ext, PluginContext};
 pub use types::*;
 
@@ -4,6 +4,7 @@
 
 use crate::error::{PluginError, Result};
 use crate::plugin_engine::PluginEngine;
+use crate::plugin_metrics::{PluginExecutionMetrics, PluginMetrics};
 use crate::types::{Finding, HttpTransaction, PluginMetadata};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -97,6 +98,8 @@ pub struct PluginManager {
     /// 插件代码缓存（plugin_id -> code）
     /// 用于执行时快速访问插件代码
     code_cache: Arc<RwLock<HashMap<String, String>>>,
+    /// 插件执行指标（与 ScanPipeline 共享）
+    metrics: PluginMetrics,
 }
 
 impl Default for PluginManager {
@@ -111,9 +114,20 @@ impl PluginManager {
         Self {
             registry: Arc::new(RwLock::new(HashMap::new())),
             code_cache: Arc::new(RwLock::new(HashMap::new())),
+            metrics: PluginMetrics::new(),
         }
     }
 
+    /// 获取插件执行指标存储
+    pub fn metrics(&self) -> PluginMetrics {
+        self.metrics.clone()
+    }
+
+    /// 获取所有插件的执行指标
+    pub fn get_plugin_metrics(&self) -> Vec<PluginExecutionMetrics> {
+        self.metrics.snapshot()
+    }
+
     /// 启用插件
     pub async fn enable_plugin(&self, plugin_id: &str) -> Result<()> {
         let mut registry = self.registry.write().await;
@@ -359,7 +373,7 @@ impl PluginManager {
         let tx_clone = transaction.clone();
 
         // 使用 PluginEngine 执行
-        let findings = tokio::task::spawn_blocking(move || {
+        let scan = tokio::task::spawn_blocking(move || {
             let rt = tokio::runtime::Builder::new_current_thread()
                 .enable_all()
                 .build()
@@ -370,9 +384,14 @@ impl PluginManager {
                 engine.load_plugin_with_metadata(&code, metadata).await?;
                 engine.scan_transaction(&tx_clone).await
             })
-        })
-        .await
-        .map_err(|e| PluginError::Execution(format!("Task join error: {}", e)))??;
+        });
+        let findings = self
+            .metrics
+            .track(plugin_id, async move {
+                scan.await
+                    .map_err(|e| PluginError::Execution(format!("Task join error: {}", e)))?
+            })
+            .await?;
 
         debug!(
             "Plugin {} found {} issues in transaction {}",
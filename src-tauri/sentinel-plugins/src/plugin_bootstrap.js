@@ -158,6 +158,16 @@ globalThis.Sentinel = {
       return await Deno.core.ops.op_get_dictionary_words(idOrName, limit || null)
     },
     
+    /**
+     * Record words that produced hits; their weight is bumped so they are served first next time
+     * @param {string} idOrName - Dictionary ID or name
+     * @param {string[]} words - Words that hit
+     * @returns {Promise<number>} Number of words updated
+     */
+    recordHits: async (idOrName, words) => {
+      return await Deno.core.ops.op_record_dictionary_hits(idOrName, words)
+    },
+    
     /**
      * List all dictionaries with optional filter
      * @param {object} [filter] - Filter options
@@ -0,0 +1,317 @@
+//! 插件执行指标模块
+//!
+//! 记录每个插件的调用次数、耗时分布、产出的 Finding 数和错误数。
+//! 计数为累计值；耗时样本只保留最近 `MAX_DURATION_SAMPLES` 次，分位数按需计算。
+//! 指标可导出为 `PluginMetricsRecord` 持久化，重启后再恢复。
+
+use crate::error::Result;
+use crate::types::Finding;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// 每个插件保留的耗时样本数
+pub const MAX_DURATION_SAMPLES: usize = 512;
+
+/// 插件执行指标（供前端展示）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginExecutionMetrics {
+    pub plugin_id: String,
+    pub invocation_count: u64,
+    pub error_count: u64,
+    pub findings_count: u64,
+    pub error_rate: f64,
+    pub avg_ms: f64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+    pub last_error: Option<String>,
+    /// 最近一次调用时间（毫秒时间戳）
+    pub last_invoked_at: Option<i64>,
+}
+
+/// 插件指标持久化记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginMetricsRecord {
+    pub plugin_id: String,
+    pub invocation_count: u64,
+    pub error_count: u64,
+    pub findings_count: u64,
+    pub total_duration_ms: u64,
+    /// 最近的耗时样本（毫秒）
+    pub recent_durations: Vec<u64>,
+    pub last_error: Option<String>,
+    pub last_invoked_at: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+struct PluginMetricEntry {
+    invocation_count: u64,
+    error_count: u64,
+    findings_count: u64,
+    total_duration_ms: u64,
+    samples: VecDeque<u64>,
+    last_error: Option<String>,
+    last_invoked_at: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+struct PluginMetricsInner {
+    entries: HashMap<String, PluginMetricEntry>,
+    /// 上次持久化后有变化的插件
+    dirty: HashSet<String>,
+    restored: bool,
+}
+
+/// 插件指标存储（可跨线程共享，克隆后指向同一份数据）
+#[derive(Debug, Clone, Default)]
+pub struct PluginMetrics {
+    inner: Arc<Mutex<PluginMetricsInner>>,
+}
+
+impl PluginMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PluginMetricsInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 记录一次插件调用；`error` 为 `Some` 表示调用失败
+    pub fn record(&self, plugin_id: &str, duration_ms: u64, findings: usize, error: Option<&str>) {
+        let mut inner = self.lock();
+        let entry = inner.entries.entry(plugin_id.to_string()).or_default();
+        entry.invocation_count += 1;
+        entry.findings_count += findings as u64;
+        entry.total_duration_ms += duration_ms;
+        if entry.samples.len() == MAX_DURATION_SAMPLES {
+            entry.samples.pop_front();
+        }
+        entry.samples.push_back(duration_ms);
+        entry.last_invoked_at = Some(chrono::Utc::now().timestamp_millis());
+        if let Some(error) = error {
+            entry.error_count += 1;
+            entry.last_error = Some(error.to_string());
+        }
+        inner.dirty.insert(plugin_id.to_string());
+    }
+
+    /// 执行一次插件扫描并记录耗时、Finding 数和错误
+    pub async fn track<F>(&self, plugin_id: &str, scan: F) -> Result<Vec<Finding>>
+    where
+        F: Future<Output = Result<Vec<Finding>>>,
+    {
+        let start = Instant::now();
+        let result = scan.await;
+        let duration_ms = start.elapsed().as_millis() as u64;
+        match &result {
+            Ok(findings) => self.record(plugin_id, duration_ms, findings.len(), None),
+            Err(e) => self.record(plugin_id, duration_ms, 0, Some(&e.to_string())),
+        }
+        result
+    }
+
+    /// 所有插件的指标，按 p95 耗时从高到低排序
+    pub fn snapshot(&self) -> Vec<PluginExecutionMetrics> {
+        let inner = self.lock();
+        let mut metrics: Vec<PluginExecutionMetrics> = inner
+            .entries
+            .iter()
+            .map(|(plugin_id, entry)| {
+                let mut sorted: Vec<u64> = entry.samples.iter().copied().collect();
+                sorted.sort_unstable();
+                let calls = entry.invocation_count.max(1) as f64;
+                PluginExecutionMetrics {
+                    plugin_id: plugin_id.clone(),
+                    invocation_count: entry.invocation_count,
+                    error_count: entry.error_count,
+                    findings_count: entry.findings_count,
+                    error_rate: entry.error_count as f64 / calls,
+                    avg_ms: entry.total_duration_ms as f64 / calls,
+                    p50_ms: percentile(&sorted, 50.0),
+                    p95_ms: percentile(&sorted, 95.0),
+                    p99_ms: percentile(&sorted, 99.0),
+                    max_ms: sorted.last().copied().unwrap_or(0),
+                    last_error: entry.last_error.clone(),
+                    last_invoked_at: entry.last_invoked_at,
+                }
+            })
+            .collect();
+        metrics.sort_by(|a, b| {
+            b.p95_ms
+                .cmp(&a.p95_ms)
+                .then_with(|| a.plugin_id.cmp(&b.plugin_id))
+        });
+        metrics
+    }
+
+    /// 单个插件的指标
+    pub fn get(&self, plugin_id: &str) -> Option<PluginExecutionMetrics> {
+        self.snapshot()
+            .into_iter()
+            .find(|m| m.plugin_id == plugin_id)
+    }
+
+    /// 取出上次持久化后有变化的记录
+    pub fn take_dirty(&self) -> Vec<PluginMetricsRecord> {
+        let mut inner = self.lock();
+        let dirty: Vec<String> = inner.dirty.drain().collect();
+        dirty
+            .into_iter()
+            .filter_map(|plugin_id| {
+                inner
+                    .entries
+                    .get(&plugin_id)
+                    .map(|entry| PluginMetricsRecord {
+                        invocation_count: entry.invocation_count,
+                        error_count: entry.error_count,
+                        findings_count: entry.findings_count,
+                        total_duration_ms: entry.total_duration_ms,
+                        recent_durations: entry.samples.iter().copied().collect(),
+                        last_error: entry.last_error.clone(),
+                        last_invoked_at: entry.last_invoked_at,
+                        plugin_id,
+                    })
+            })
+            .collect()
+    }
+
+    /// 是否已从持久化记录恢复过
+    pub fn is_restored(&self) -> bool {
+        self.lock().restored
+    }
+
+    /// 从持久化记录恢复；与本次启动后产生的计数累加，只生效一次
+    pub fn restore(&self, records: Vec<PluginMetricsRecord>) {
+        let mut inner = self.lock();
+        if inner.restored {
+            return;
+        }
+        inner.restored = true;
+        for record in records {
+            let entry = inner.entries.entry(record.plugin_id).or_default();
+            entry.invocation_count += record.invocation_count;
+            entry.error_count += record.error_count;
+            entry.findings_count += record.findings_count;
+            entry.total_duration_ms += record.total_duration_ms;
+            let mut samples: VecDeque<u64> = record.recent_durations.into_iter().collect();
+            samples.extend(entry.samples.drain(..));
+            while samples.len() > MAX_DURATION_SAMPLES {
+                samples.pop_front();
+            }
+            entry.samples = samples;
+            if entry.last_invoked_at.is_none() {
+                entry.last_error = record.last_error;
+                entry.last_invoked_at = record.last_invoked_at;
+            }
+        }
+    }
+}
+
+/// 最近秩分位数；无样本时为 0
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::PluginError;
+    use crate::types::{Confidence, Severity};
+    use std::time::Duration;
+
+    fn finding(plugin_id: &str) -> Finding {
+        Finding {
+            id: uuid::Uuid::new_v4().to_string(),
+            plugin_id: plugin_id.to_string(),
+            vuln_type: "xss".to_string(),
+            severity: Severity::Medium,
+            title: "Reflected XSS".to_string(),
+            description: String::new(),
+            evidence: String::new(),
+            location: "query".to_string(),
+            confidence: Confidence::High,
+            cwe: None,
+            owasp: None,
+            remediation: None,
+            url: "http://example.com/?q=1".to_string(),
+            method: "GET".to_string(),
+            created_at: chrono::Utc::now(),
+            request_headers: None,
+            request_body: None,
+            response_status: None,
+            response_headers: None,
+            response_body: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_track_reflects_dispatches() {
+        let metrics = PluginMetrics::new();
+        let dispatches = 6;
+        for i in 0..dispatches {
+            let result = metrics
+                .track("xss", async move {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    if i == 0 {
+                        Err(PluginError::Execution("timeout".to_string()))
+                    } else {
+                        Ok(vec![finding("xss"), finding("xss")])
+                    }
+                })
+                .await;
+            assert_eq!(result.is_err(), i == 0);
+        }
+        metrics
+            .track("fast", async { Ok(Vec::new()) })
+            .await
+            .unwrap();
+
+        let xss = metrics.get("xss").unwrap();
+        assert_eq!(xss.invocation_count, dispatches);
+        assert_eq!(xss.error_count, 1);
+        assert_eq!(xss.findings_count, 2 * (dispatches - 1));
+        assert!(xss.p50_ms >= 20, "p50 was {}", xss.p50_ms);
+        assert!(xss.avg_ms >= 20.0);
+        assert!(xss.max_ms >= xss.p95_ms && xss.p95_ms >= xss.p50_ms);
+        assert!(xss.last_error.unwrap().contains("timeout"));
+
+        let all = metrics.snapshot();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].plugin_id, "xss");
+        assert_eq!(all[1].invocation_count, 1);
+    }
+
+    #[test]
+    fn test_persist_and_restore() {
+        let metrics = PluginMetrics::new();
+        metrics.record("sqli", 10, 1, None);
+        metrics.record("sqli", 30, 0, Some("boom"));
+
+        let records = metrics.take_dirty();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].recent_durations, vec![10, 30]);
+        assert!(metrics.take_dirty().is_empty());
+
+        let restarted = PluginMetrics::new();
+        restarted.record("sqli", 20, 2, None);
+        restarted.restore(records.clone());
+        restarted.restore(records);
+
+        let sqli = restarted.get("sqli").unwrap();
+        assert_eq!(sqli.invocation_count, 3);
+        assert_eq!(sqli.error_count, 1);
+        assert_eq!(sqli.findings_count, 3);
+        assert_eq!(sqli.avg_ms, 20.0);
+        assert_eq!(sqli.p50_ms, 20);
+        assert_eq!(sqli.max_ms, 30);
+    }
+}
@@ -234,6 +234,7 @@ extension!(
         // Dictionary operations
         op_get_dictionary,
         op_get_dictionary_words,
+        op_record_dictionary_hits,
         op_list_dictionaries,
         // JavaScript AST parsing
         op_parse_js,
@@ -313,6 +314,7 @@ async fn op_fetch(#[string] url: String, #[serde] options: Option<FetchOptions>)
     let builder = reqwest::Client::builder().timeout(std::time::Duration::from_millis(timeout_ms));
     let builder: reqwest::ClientBuilder =
         sentinel_core::global_proxy::apply_proxy_to_client(builder).await;
+    let builder = sentinel_core::auth_session::apply_session_to_client(builder);
     let client = match builder.build() {
         Ok(c) => c,
         Err(e) => {
@@ -1179,6 +1181,57 @@ async fn op_get_dictionary_words(
     }
 }
 
+/// Bump the weight of words that produced hits so they are tried first next time
+#[op2(async)]
+#[number]
+async fn op_record_dictionary_hits(
+    #[string] id_or_name: String,
+    #[serde] words: Vec<String>,
+) -> Result<u64, deno_error::JsErrorBox> {
+    #[cfg(feature = "db-postgres")]
+    {
+        let pool = get_dictionary_pool().ok_or_else(|| {
+            deno_error::JsErrorBox::generic("Dictionary database not initialized")
+        })?;
+
+        let dict_id: Option<String> =
+            sqlx::query_scalar("SELECT id FROM dictionaries WHERE id = $1 OR name = $2")
+                .bind(&id_or_name)
+                .bind(&id_or_name)
+                .fetch_optional(pool)
+                .await
+                .map_err(|e| deno_error::JsErrorBox::generic(format!("Query error: {}", e)))?;
+
+        let dict_id = match dict_id {
+            Some(id) => id,
+            None => return Ok(0),
+        };
+
+        let mut updated = 0;
+        for word in &words {
+            updated += sqlx::query(
+                "UPDATE dictionary_words SET weight = COALESCE(weight, 0) + 1 WHERE dictionary_id = $1 AND word = $2",
+            )
+            .bind(&dict_id)
+            .bind(word)
+            .execute(pool)
+            .await
+            .map_err(|e| deno_error::JsErrorBox::generic(format!("Query error: {}", e)))?
+            .rows_affected();
+        }
+
+        return Ok(updated);
+    }
+
+    #[cfg(not(feature = "db-postgres"))]
+    {
+        let _ = (id_or_name, words);
+        Err(deno_error::JsErrorBox::generic(
+            "Dictionary operations require `db-postgres` feature",
+        ))
+    }
+}
+
 /// List dictionaries with optional filter
 #[op2(async)]
 #[serde]
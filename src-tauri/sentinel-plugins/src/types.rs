@@ -102,6 +102,12 @@ pub struct RequestContext {
     /// 修改后的请求体（如果经过拦截修改）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub edited_body: Option<Vec<u8>>,
+    /// 请求体超过代理大小上限，`body` 只是前缀
+    #[serde(default)]
+    pub body_truncated: bool,
+    /// 被截断时的原始请求体大小（未知时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_size: Option<u64>,
 }
 
 /// 响应上下文
@@ -125,6 +131,15 @@ pub struct ResponseContext {
     /// 修改后的响应体（如果经过拦截修改）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub edited_body: Option<Vec<u8>>,
+    /// 响应体超过代理大小上限，`body` 只是前缀（压缩响应为空）
+    #[serde(default)]
+    pub body_truncated: bool,
+    /// 被截断时的原始响应体大小（未知时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_size: Option<u64>,
+    /// 解压前的原始响应体（仍按 Content-Encoding 编码）；仅在 `body` 已解压时保存
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_body: Option<Vec<u8>>,
 }
 
 /// HTTP 事务（包含请求和响应）
@@ -93,6 +93,8 @@ fn create_test_transaction(body_size_kb: usize) -> HttpTransaction {
             edited_url: None,
             edited_headers: None,
             edited_body: None,
+            body_truncated: false,
+            body_size: None,
         },
         response: None,
     }
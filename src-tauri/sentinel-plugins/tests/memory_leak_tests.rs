@@ -120,6 +120,8 @@ fn create_test_transaction() -> HttpTransaction {
             edited_url: None,
             edited_headers: None,
             edited_body: None,
+            body_truncated: false,
+            body_size: None,
         },
         response: None,
     }
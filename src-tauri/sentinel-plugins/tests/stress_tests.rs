@@ -126,6 +126,8 @@ fn create_test_transaction(size_kb: usize) -> HttpTransaction {
             edited_url: None,
             edited_headers: None,
             edited_body: None,
+            body_truncated: false,
+            body_size: None,
         },
         response: Some(ResponseContext {
             request_id: uuid::Uuid::new_v4().to_string(),
@@ -138,6 +140,9 @@ fn create_test_transaction(size_kb: usize) -> HttpTransaction {
             edited_status: None,
             edited_headers: None,
             edited_body: None,
+            body_truncated: false,
+            body_size: None,
+            raw_body: None,
         }),
     }
 }
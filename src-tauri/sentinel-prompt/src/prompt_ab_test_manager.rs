@@ -342,6 +342,59 @@ pub struct StatisticalSignificance {
     pub test_method: String,
 }
 
+/// 显著性检验方法
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum SignificanceTest {
+    /// 双比例 z 检验（成功率、转化率）
+    TwoProportionZ,
+    /// Welch t 检验（延迟等连续指标）
+    WelchT,
+}
+
+impl SignificanceTest {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SignificanceTest::TwoProportionZ => "two_proportion_z_test",
+            SignificanceTest::WelchT => "welch_t_test",
+        }
+    }
+}
+
+/// 变体与对照组的显著性对比
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantComparison {
+    /// 指标名称
+    pub metric_name: String,
+    /// 对照组变体ID
+    pub control_variant: String,
+    /// 实验组变体ID
+    pub treatment_variant: String,
+    /// 对照组样本数
+    pub control_sample_size: usize,
+    /// 实验组样本数
+    pub treatment_sample_size: usize,
+    /// 对照组指标值（比例或均值）
+    pub control_value: f64,
+    /// 实验组指标值（比例或均值）
+    pub treatment_value: f64,
+    /// 检验方法
+    pub test_method: SignificanceTest,
+    /// 检验统计量（z 或 t）
+    pub statistic: f64,
+    /// 双侧 p 值
+    pub p_value: f64,
+    /// 置信水平
+    pub confidence_level: f32,
+    /// 效应大小（比例为 Cohen's h，连续指标为 Cohen's d）
+    pub effect_size: f64,
+    /// 两组是否都达到最小样本量
+    pub sufficient_sample: bool,
+    /// 是否显著（样本不足时恒为 false）
+    pub is_significant: bool,
+    /// 显著时的获胜变体
+    pub winner: Option<String>,
+}
+
 /// 推荐动作
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Recommendation {
@@ -618,6 +671,13 @@ impl PromptABTestManager {
         }
     }
 
+    /// 对比各实验组与对照组在主要指标上的差异是否显著
+    pub async fn analyze_ab_test(&self, test_id: &str) -> Result<Vec<VariantComparison>> {
+        let test = self.get_test(test_id).await?;
+        let executions = self.results_storage.get_executions(test_id).await?;
+        self.analyzer.compare_variants(&test, &executions)
+    }
+
     /// 获取测试列表
     pub async fn list_tests(&self) -> Result<Vec<ABTest>> {
         let active_tests = self.active_tests.read().await;
@@ -650,11 +710,8 @@ impl PromptABTestManager {
         let analysis = self.analyze_test(test_id).await?;
 
         // 找到获胜变体
-        let winning_variant = analysis
-            .variant_results
-            .iter()
-            .max_by(|a, b| a.1.overall_score.partial_cmp(&b.1.overall_score).unwrap())
-            .map(|(variant_id, _)| variant_id.clone());
+        let winning_variant =
+            best_variant(&analysis.variant_results).map(|(variant_id, _)| variant_id.clone());
 
         // 计算总样本数
         let total_samples = analysis
@@ -691,10 +748,7 @@ impl PromptABTestManager {
             .map(|r| r.sample_size)
             .sum();
 
-        let best_variant = analysis
-            .variant_results
-            .iter()
-            .max_by(|a, b| a.1.overall_score.partial_cmp(&b.1.overall_score).unwrap())
+        let best_variant = best_variant(&analysis.variant_results)
             .map(|(variant_id, result)| (variant_id, result.overall_score));
 
         match best_variant {
@@ -930,7 +984,12 @@ impl StatisticalAnalyzer {
         }
 
         // 计算统计显著性
-        let statistical_significance = self.calculate_statistical_significance(&variant_results)?;
+        let comparisons = if test.variants.len() >= 2 && !test.metrics.is_empty() {
+            self.compare_variants(test, executions)?
+        } else {
+            Vec::new()
+        };
+        let statistical_significance = self.summarize_significance(test, &comparisons);
 
         // 生成推荐
         let recommendations =
@@ -940,7 +999,10 @@ impl StatisticalAnalyzer {
         let confidence_intervals = self.calculate_confidence_intervals(&variant_results)?;
 
         // 计算效应大小
-        let effect_sizes = self.calculate_effect_sizes(&variant_results)?;
+        let effect_sizes = comparisons
+            .iter()
+            .map(|c| (c.treatment_variant.clone(), c.effect_size))
+            .collect();
 
         Ok(TestAnalysis {
             test_id: test.test_id.clone(),
@@ -967,6 +1029,7 @@ impl StatisticalAnalyzer {
                 .iter()
                 .filter_map(|e| e.metric_values.get(&metric.name))
                 .copied()
+                .filter(|v| v.is_finite())
                 .collect();
 
             if !values.is_empty() {
@@ -991,7 +1054,7 @@ impl StatisticalAnalyzer {
     /// 计算指标统计
     fn calculate_metric_statistics(&self, values: &[f64]) -> MetricResult {
         let mut sorted_values = values.to_vec();
-        sorted_values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        sorted_values.sort_by(|a, b| a.total_cmp(b));
 
         let mean = values.iter().sum::<f64>() / values.len() as f64;
         let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / values.len() as f64;
@@ -1030,18 +1093,130 @@ impl StatisticalAnalyzer {
         sorted_values[index]
     }
 
-    /// 计算统计显著性（简化实现）
-    fn calculate_statistical_significance(
+    /// 对比各实验组与对照组
+    ///
+    /// 使用主要指标（未标记时取第一个指标）：成功率/转化率做双比例 z 检验，
+    /// 其余指标做 Welch t 检验。任一组样本数低于最小样本量时不判定显著。
+    pub fn compare_variants(
         &self,
-        _variant_results: &HashMap<String, VariantResult>,
-    ) -> Result<StatisticalSignificance> {
-        // 简化实现，实际应该进行适当的统计检验
-        Ok(StatisticalSignificance {
-            is_significant: false,
-            p_value: 0.05,
-            confidence_level: self.config.default_confidence_level,
-            test_method: "t_test".to_string(),
-        })
+        test: &ABTest,
+        executions: &[TestExecution],
+    ) -> Result<Vec<VariantComparison>> {
+        let metric = test
+            .metrics
+            .iter()
+            .find(|m| m.is_primary)
+            .or_else(|| test.metrics.first())
+            .ok_or_else(|| anyhow!("Test has no metrics: {}", test.test_id))?;
+        let control = test
+            .variants
+            .iter()
+            .find(|v| v.is_control)
+            .or_else(|| test.variants.first())
+            .ok_or_else(|| anyhow!("Test has no variants: {}", test.test_id))?;
+
+        let confidence_level = if test.conditions.confidence_level > 0.0 {
+            test.conditions.confidence_level
+        } else {
+            self.config.default_confidence_level
+        };
+        let alpha = 1.0 - confidence_level as f64;
+        let min_sample_size = if test.conditions.min_sample_size > 0 {
+            test.conditions.min_sample_size
+        } else {
+            self.config.min_sample_size
+        }
+        .max(2);
+
+        let method = match metric.metric_type {
+            MetricType::SuccessRate | MetricType::ConversionRate => {
+                SignificanceTest::TwoProportionZ
+            }
+            _ => SignificanceTest::WelchT,
+        };
+        let lower_is_better = matches!(metric.metric_type, MetricType::ResponseTime);
+
+        let outcomes = |variant_id: &str| -> Vec<f64> {
+            executions
+                .iter()
+                .filter(|e| e.variant_id == variant_id)
+                .map(|e| outcome_value(e, metric))
+                .filter(|v| v.is_finite())
+                .collect()
+        };
+        let control_values = outcomes(&control.variant_id);
+
+        let mut comparisons = Vec::new();
+        for variant in test
+            .variants
+            .iter()
+            .filter(|v| v.variant_id != control.variant_id)
+        {
+            let treatment_values = outcomes(&variant.variant_id);
+            let result = match method {
+                SignificanceTest::TwoProportionZ => {
+                    two_proportion_z_test(&control_values, &treatment_values)
+                }
+                SignificanceTest::WelchT => welch_t_test(&control_values, &treatment_values),
+            };
+
+            let sufficient_sample = control_values.len() >= min_sample_size
+                && treatment_values.len() >= min_sample_size;
+            let is_significant = sufficient_sample && result.p_value < alpha;
+            let winner = if !is_significant {
+                None
+            } else if (result.treatment_value < result.control_value) == lower_is_better {
+                Some(variant.variant_id.clone())
+            } else {
+                Some(control.variant_id.clone())
+            };
+
+            comparisons.push(VariantComparison {
+                metric_name: metric.name.clone(),
+                control_variant: control.variant_id.clone(),
+                treatment_variant: variant.variant_id.clone(),
+                control_sample_size: control_values.len(),
+                treatment_sample_size: treatment_values.len(),
+                control_value: result.control_value,
+                treatment_value: result.treatment_value,
+                test_method: method,
+                statistic: result.statistic,
+                p_value: result.p_value,
+                confidence_level,
+                effect_size: result.effect_size,
+                sufficient_sample,
+                is_significant,
+                winner,
+            });
+        }
+
+        Ok(comparisons)
+    }
+
+    /// 汇总显著性：取 p 值最小的对比
+    fn summarize_significance(
+        &self,
+        test: &ABTest,
+        comparisons: &[VariantComparison],
+    ) -> StatisticalSignificance {
+        match comparisons
+            .iter()
+            .filter(|c| c.p_value.is_finite())
+            .min_by(|a, b| a.p_value.total_cmp(&b.p_value))
+        {
+            Some(best) => StatisticalSignificance {
+                is_significant: best.is_significant,
+                p_value: best.p_value,
+                confidence_level: best.confidence_level,
+                test_method: best.test_method.as_str().to_string(),
+            },
+            None => StatisticalSignificance {
+                is_significant: false,
+                p_value: 1.0,
+                confidence_level: test.conditions.confidence_level,
+                test_method: "none".to_string(),
+            },
+        }
     }
 
     /// 生成推荐
@@ -1049,15 +1224,27 @@ impl StatisticalAnalyzer {
         &self,
         _test: &ABTest,
         variant_results: &HashMap<String, VariantResult>,
-        _statistical_significance: &StatisticalSignificance,
+        statistical_significance: &StatisticalSignificance,
     ) -> Result<Vec<Recommendation>> {
         let mut recommendations = Vec::new();
 
+        // 未达到显著性时继续收集数据
+        if !statistical_significance.is_significant {
+            recommendations.push(Recommendation {
+                recommendation_type: RecommendationType::ContinueTesting,
+                description: format!(
+                    "差异尚未达到统计显著性（p = {:.4}），建议继续收集样本",
+                    statistical_significance.p_value
+                ),
+                confidence: statistical_significance.confidence_level,
+                expected_impact: 0.0,
+                priority: Priority::Medium,
+            });
+            return Ok(recommendations);
+        }
+
         // 找到最佳变体
-        if let Some((best_variant, _)) = variant_results
-            .iter()
-            .max_by(|a, b| a.1.overall_score.partial_cmp(&b.1.overall_score).unwrap())
-        {
+        if let Some((best_variant, _)) = best_variant(variant_results) {
             recommendations.push(Recommendation {
                 recommendation_type: RecommendationType::SelectWinner,
                 description: format!("建议选择变体 {} 作为获胜者", best_variant),
@@ -1077,16 +1264,229 @@ impl StatisticalAnalyzer {
     ) -> Result<HashMap<String, ConfidenceInterval>> {
         Ok(HashMap::new())
     }
+}
 
-    /// 计算效应大小（简化实现）
-    fn calculate_effect_sizes(
-        &self,
-        _variant_results: &HashMap<String, VariantResult>,
-    ) -> Result<HashMap<String, f64>> {
-        Ok(HashMap::new())
+/// 总体评分最高的变体
+///
+/// 非有限评分（NaN 样本或无有效指标时的 0/0）不参与排名
+fn best_variant(
+    variant_results: &HashMap<String, VariantResult>,
+) -> Option<(&String, &VariantResult)> {
+    variant_results
+        .iter()
+        .filter(|(_, result)| result.overall_score.is_finite())
+        .max_by(|a, b| a.1.overall_score.total_cmp(&b.1.overall_score))
+}
+
+/// 单次执行在指定指标上的取值
+///
+/// 比例类指标优先取记录值（按 0/1 处理），否则以执行状态判定成功；
+/// 响应时间缺省取执行时长。
+fn outcome_value(execution: &TestExecution, metric: &EvaluationMetric) -> f64 {
+    let recorded = execution.metric_values.get(&metric.name).copied();
+    match metric.metric_type {
+        MetricType::SuccessRate | MetricType::ConversionRate => match recorded {
+            Some(value) => (value >= 0.5) as u8 as f64,
+            None => matches!(execution.status, ExecutionStatus::Success) as u8 as f64,
+        },
+        MetricType::ResponseTime => recorded.unwrap_or(execution.duration_ms),
+        _ => recorded.unwrap_or(0.0),
     }
 }
 
+/// 检验结果
+struct TestOutcome {
+    control_value: f64,
+    treatment_value: f64,
+    statistic: f64,
+    p_value: f64,
+    effect_size: f64,
+}
+
+/// 双比例 z 检验（合并方差），效应大小为 Cohen's h
+fn two_proportion_z_test(control: &[f64], treatment: &[f64]) -> TestOutcome {
+    let (n1, n2) = (control.len() as f64, treatment.len() as f64);
+    let p1 = if n1 > 0.0 {
+        control.iter().sum::<f64>() / n1
+    } else {
+        0.0
+    };
+    let p2 = if n2 > 0.0 {
+        treatment.iter().sum::<f64>() / n2
+    } else {
+        0.0
+    };
+
+    let (statistic, p_value) = if n1 == 0.0 || n2 == 0.0 {
+        (0.0, 1.0)
+    } else {
+        let pooled = (p1 * n1 + p2 * n2) / (n1 + n2);
+        let se = (pooled * (1.0 - pooled) * (1.0 / n1 + 1.0 / n2)).sqrt();
+        if se == 0.0 {
+            (0.0, 1.0)
+        } else {
+            let z = (p2 - p1) / se;
+            (z, erfc(z.abs() / std::f64::consts::SQRT_2))
+        }
+    };
+
+    TestOutcome {
+        control_value: p1,
+        treatment_value: p2,
+        statistic,
+        p_value,
+        effect_size: 2.0 * p2.sqrt().asin() - 2.0 * p1.sqrt().asin(),
+    }
+}
+
+/// Welch t 检验（不假设方差相等），效应大小为 Cohen's d
+fn welch_t_test(control: &[f64], treatment: &[f64]) -> TestOutcome {
+    let mean = |v: &[f64]| {
+        if v.is_empty() {
+            0.0
+        } else {
+            v.iter().sum::<f64>() / v.len() as f64
+        }
+    };
+    let sample_variance = |v: &[f64], m: f64| {
+        if v.len() < 2 {
+            0.0
+        } else {
+            v.iter().map(|x| (x - m).powi(2)).sum::<f64>() / (v.len() - 1) as f64
+        }
+    };
+
+    let (m1, m2) = (mean(control), mean(treatment));
+    let mut outcome = TestOutcome {
+        control_value: m1,
+        treatment_value: m2,
+        statistic: 0.0,
+        p_value: 1.0,
+        effect_size: 0.0,
+    };
+    if control.len() < 2 || treatment.len() < 2 {
+        return outcome;
+    }
+
+    let (n1, n2) = (control.len() as f64, treatment.len() as f64);
+    let (v1, v2) = (sample_variance(control, m1), sample_variance(treatment, m2));
+
+    let pooled_sd = (((n1 - 1.0) * v1 + (n2 - 1.0) * v2) / (n1 + n2 - 2.0)).sqrt();
+    if pooled_sd > 0.0 {
+        outcome.effect_size = (m2 - m1) / pooled_sd;
+    }
+
+    let se = (v1 / n1 + v2 / n2).sqrt();
+    if se == 0.0 {
+        // 两组均无波动：均值不同即视为确定性差异
+        if m1 != m2 {
+            outcome.statistic = f64::MAX.copysign(m2 - m1);
+            outcome.p_value = 0.0;
+        }
+        return outcome;
+    }
+
+    let t = (m2 - m1) / se;
+    let df = (v1 / n1 + v2 / n2).powi(2)
+        / ((v1 / n1).powi(2) / (n1 - 1.0) + (v2 / n2).powi(2) / (n2 - 1.0));
+    outcome.statistic = t;
+    outcome.p_value = regularized_incomplete_beta(df / 2.0, 0.5, df / (df + t * t));
+    outcome
+}
+
+/// 互补误差函数（Chebyshev 近似，相对误差 < 1.2e-7）
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let r = t
+        * (-z * z - 1.265_512_23
+            + t * (1.000_023_68
+                + t * (0.374_091_96
+                    + t * (0.096_784_18
+                        + t * (-0.186_288_06
+                            + t * (0.278_868_07
+                                + t * (-1.135_203_98
+                                    + t * (1.488_515_87
+                                        + t * (-0.822_152_23 + t * 0.170_872_77)))))))))
+            .exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+/// ln Γ(x)（Lanczos 近似）
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.120_865_097_386_617_9e-2,
+        -0.539_523_938_495_3e-5,
+    ];
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut series = 1.000_000_000_190_015;
+    for (i, c) in COEFFICIENTS.iter().enumerate() {
+        series += c / (x + 1.0 + i as f64);
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// 正则化不完全 Beta 函数 I_x(a, b)
+fn regularized_incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// 不完全 Beta 函数的连分式展开（修正 Lentz 法）
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 200;
+    const EPSILON: f64 = 3e-14;
+    const TINY: f64 = 1e-300;
+    let clamp = |v: f64| if v.abs() < TINY { TINY } else { v };
+
+    let (qab, qap, qam) = (a + b, a + 1.0, a - 1.0);
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - qab * x / qap);
+    let mut h = d;
+
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        let aa = m * (b - m) * x / ((qam + m2) * (a + m2));
+        d = 1.0 / clamp(1.0 + aa * d);
+        c = clamp(1.0 + aa / c);
+        h *= d * c;
+
+        let aa = -(a + m) * (qab + m) * x / ((a + m2) * (qap + m2));
+        d = 1.0 / clamp(1.0 + aa * d);
+        c = clamp(1.0 + aa / c);
+        let delta = d * c;
+        h *= delta;
+
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+
+    h
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1153,6 +1553,234 @@ mod tests {
         assert_eq!(test.status, TestStatus::Draft);
     }
 
+    fn significance_test(metric_type: MetricType, min_sample_size: usize) -> ABTest {
+        let variant = |id: &str, is_control: bool| TestVariant {
+            variant_id: id.to_string(),
+            name: id.to_string(),
+            description: String::new(),
+            is_control,
+            prompt_config: PromptConfig::default(),
+            traffic_weight: 0.5,
+            variant_config: HashMap::new(),
+        };
+        ABTest {
+            test_id: "sig".to_string(),
+            name: "Significance".to_string(),
+            description: String::new(),
+            status: TestStatus::Running,
+            created_at: Utc::now(),
+            started_at: Some(Utc::now()),
+            ended_at: None,
+            variants: vec![variant("control", true), variant("treatment", false)],
+            traffic_allocation: TrafficAllocation {
+                strategy: AllocationStrategy::Random,
+                total_traffic_percent: 100.0,
+                variant_weights: HashMap::new(),
+                user_segmentation: None,
+            },
+            metrics: vec![EvaluationMetric {
+                name: "metric".to_string(),
+                metric_type,
+                description: String::new(),
+                target_value: None,
+                weight: 1.0,
+                is_primary: true,
+                calculation_method: CalculationMethod::Average,
+            }],
+            conditions: TestConditions {
+                min_sample_size,
+                max_duration_hours: None,
+                confidence_level: 0.95,
+                minimum_detectable_effect: 0.05,
+                early_stopping_rules: vec![],
+            },
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn executions(variant_id: &str, values: &[f64]) -> Vec<TestExecution> {
+        values
+            .iter()
+            .map(|&value| TestExecution {
+                execution_id: Uuid::new_v4().to_string(),
+                test_id: "sig".to_string(),
+                variant_id: variant_id.to_string(),
+                user_id: None,
+                session_id: "session".to_string(),
+                executed_at: Utc::now(),
+                input_data: serde_json::Value::Null,
+                output_result: serde_json::Value::Null,
+                metric_values: [("metric".to_string(), value)].into_iter().collect(),
+                status: ExecutionStatus::Success,
+                error_message: None,
+                duration_ms: 0.0,
+            })
+            .collect()
+    }
+
+    /// 生成 n 个 0/1 结果，其中前 successes 个为成功
+    fn binary(n: usize, successes: usize) -> Vec<f64> {
+        (0..n)
+            .map(|i| if i < successes { 1.0 } else { 0.0 })
+            .collect()
+    }
+
+    #[test]
+    fn test_success_rate_clearly_significant() {
+        let analyzer = StatisticalAnalyzer::new(AnalysisConfig::default());
+        let test = significance_test(MetricType::SuccessRate, 100);
+        let mut data = executions("control", &binary(200, 60));
+        data.extend(executions("treatment", &binary(200, 140)));
+
+        let comparisons = analyzer.compare_variants(&test, &data).unwrap();
+        assert_eq!(comparisons.len(), 1);
+        let c = &comparisons[0];
+        assert_eq!(c.test_method, SignificanceTest::TwoProportionZ);
+        assert!((c.control_value - 0.3).abs() < 1e-9);
+        assert!((c.treatment_value - 0.7).abs() < 1e-9);
+        assert!(c.statistic > 7.0);
+        assert!(c.p_value < 1e-6);
+        assert!(c.effect_size > 0.7);
+        assert!(c.sufficient_sample && c.is_significant);
+        assert_eq!(c.winner.as_deref(), Some("treatment"));
+    }
+
+    #[test]
+    fn test_success_rate_inconclusive() {
+        let analyzer = StatisticalAnalyzer::new(AnalysisConfig::default());
+        let test = significance_test(MetricType::SuccessRate, 100);
+        let mut data = executions("control", &binary(200, 100));
+        data.extend(executions("treatment", &binary(200, 104)));
+
+        let c = &analyzer.compare_variants(&test, &data).unwrap()[0];
+        assert!(c.p_value > 0.5);
+        assert!(!c.is_significant);
+        assert!(c.winner.is_none());
+    }
+
+    #[test]
+    fn test_small_sample_never_significant() {
+        let analyzer = StatisticalAnalyzer::new(AnalysisConfig::default());
+        let test = significance_test(MetricType::SuccessRate, 100);
+        let mut data = executions("control", &binary(20, 0));
+        data.extend(executions("treatment", &binary(20, 20)));
+
+        let c = &analyzer.compare_variants(&test, &data).unwrap()[0];
+        assert!(c.p_value < 0.001);
+        assert!(!c.sufficient_sample);
+        assert!(!c.is_significant);
+    }
+
+    #[test]
+    fn test_latency_welch_t_test() {
+        let analyzer = StatisticalAnalyzer::new(AnalysisConfig::default());
+        let test = significance_test(MetricType::ResponseTime, 30);
+        let control: Vec<f64> = (0..50).map(|i| 500.0 + (i % 10) as f64 * 5.0).collect();
+        let faster: Vec<f64> = (0..50).map(|i| 400.0 + (i % 10) as f64 * 5.0).collect();
+        let noisy: Vec<f64> = (0..50)
+            .map(|i| 520.0 + if i % 2 == 0 { 100.0 } else { -100.0 } + (i % 3) as f64)
+            .collect();
+
+        let mut data = executions("control", &control);
+        data.extend(executions("treatment", &faster));
+        let c = &analyzer.compare_variants(&test, &data).unwrap()[0];
+        assert_eq!(c.test_method, SignificanceTest::WelchT);
+        assert!(c.statistic < 0.0);
+        assert!(c.p_value < 1e-6);
+        assert!(c.effect_size < -1.0);
+        assert_eq!(c.winner.as_deref(), Some("treatment"));
+
+        let mut data = executions("control", &control);
+        data.extend(executions("treatment", &noisy));
+        let c = &analyzer.compare_variants(&test, &data).unwrap()[0];
+        assert!(c.p_value > 0.2);
+        assert!(!c.is_significant);
+    }
+
+    #[test]
+    fn test_distribution_helpers() {
+        // 标准正态双侧 p 值：|z| = 1.96 时约为 0.05
+        assert!((erfc(1.96 / std::f64::consts::SQRT_2) - 0.05).abs() < 1e-3);
+        // t 分布：df = 10, t = 2.228 时双侧 p 约为 0.05
+        let (df, t) = (10.0_f64, 2.228_f64);
+        let p = regularized_incomplete_beta(df / 2.0, 0.5, df / (df + t * t));
+        assert!((p - 0.05).abs() < 1e-3);
+    }
+
+    #[tokio::test]
+    async fn test_analyze_ab_test_uses_recorded_executions() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager =
+            PromptABTestManager::new(temp_dir.path().to_path_buf(), ABTestConfig::default());
+        let test = significance_test(MetricType::SuccessRate, 100);
+        manager
+            .active_tests
+            .write()
+            .await
+            .insert(test.test_id.clone(), test);
+
+        let mut data = executions("control", &binary(150, 45));
+        data.extend(executions("treatment", &binary(150, 105)));
+        for execution in data {
+            manager.record_execution(execution).await.unwrap();
+        }
+
+        let comparisons = manager.analyze_ab_test("sig").await.unwrap();
+        assert!(comparisons[0].is_significant);
+
+        let results = manager.get_test_results("sig").await.unwrap();
+        let significance = results.statistical_significance.unwrap();
+        assert!(significance.is_significant);
+        assert_eq!(significance.test_method, "two_proportion_z_test");
+        assert!(results.summary.contains("结果具有统计显著性"));
+    }
+
+    #[tokio::test]
+    async fn test_nan_metric_values_do_not_panic_ranking() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager =
+            PromptABTestManager::new(temp_dir.path().to_path_buf(), ABTestConfig::default());
+        let test = significance_test(MetricType::ResponseTime, 2);
+        manager
+            .active_tests
+            .write()
+            .await
+            .insert(test.test_id.clone(), test);
+
+        let mut data = executions("control", &[f64::NAN, 120.0, 130.0]);
+        data.extend(executions("treatment", &[100.0, f64::NAN, 90.0]));
+        for execution in data {
+            manager.record_execution(execution).await.unwrap();
+        }
+
+        let results = manager.get_test_results("sig").await.unwrap();
+        assert!(results.winning_variant.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_nan_overall_score_never_wins() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager =
+            PromptABTestManager::new(temp_dir.path().to_path_buf(), ABTestConfig::default());
+        let test = significance_test(MetricType::ResponseTime, 2);
+        manager
+            .active_tests
+            .write()
+            .await
+            .insert(test.test_id.clone(), test);
+
+        // control 只有 NaN 样本，总体评分无法计算
+        let mut data = executions("control", &[f64::NAN, f64::NAN]);
+        data.extend(executions("treatment", &[100.0, 90.0]));
+        for execution in data {
+            manager.record_execution(execution).await.unwrap();
+        }
+
+        let results = manager.get_test_results("sig").await.unwrap();
+        assert_eq!(results.winning_variant.as_deref(), Some("treatment"));
+        assert!(results.summary.contains("'treatment'"));
+    }
+
     #[test]
     fn test_statistical_analyzer() {
         let analyzer = StatisticalAnalyzer::new(AnalysisConfig::default());
@@ -6,7 +6,7 @@
 //! - 动态工具信息生成
 //! - Prompt优化和验证
 use super::*;
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -43,6 +43,52 @@ pub struct VariableResolver {
     variable_pattern: Regex,
     /// 内置变量
     builtin_variables: HashMap<String, String>,
+    /// 严格模式：存在未提供的变量时报错，否则替换为空并记录警告
+    strict: bool,
+}
+
+/// Prompt构建错误
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum PromptBuildError {
+    /// 模板引用了上下文中不存在的变量
+    #[error("未解析的变量: {}{}", .missing.join(", "), format_suggestions(.suggestions))]
+    MissingVariables {
+        /// 未解析的变量名
+        missing: Vec<String>,
+        /// 疑似拼写错误：(模板中的变量名, 上下文中相近的变量名)
+        suggestions: Vec<(String, String)>,
+    },
+}
+
+fn format_suggestions(suggestions: &[(String, String)]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
+    }
+    let hints: Vec<String> = suggestions
+        .iter()
+        .map(|(name, candidate)| format!("{} -> {}", name, candidate))
+        .collect();
+    format!("（是否拼写错误: {}）", hints.join(", "))
+}
+
+/// 变量校验结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VariableValidation {
+    /// 模板引用的变量（去重，按出现顺序）
+    pub referenced: Vec<String>,
+    /// 模板引用但未提供的变量
+    pub missing: Vec<String>,
+    /// 提供了但模板未引用的变量
+    pub unused: Vec<String>,
+    /// 疑似拼写错误：(模板中的变量名, 上下文中相近的变量名)
+    pub suggestions: Vec<(String, String)>,
+}
+
+impl VariableValidation {
+    /// 所有引用的变量是否都已提供
+    pub fn is_satisfied(&self) -> bool {
+        self.missing.is_empty()
+    }
 }
 
 /// Prompt构建上下文
@@ -280,6 +326,35 @@ impl PromptBuilder {
         }
     }
 
+    /// 设置变量校验模式（默认严格）
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.variable_resolver = self.variable_resolver.with_strict(strict);
+        self
+    }
+
+    /// 校验并渲染模板
+    ///
+    /// 上下文中的自定义变量会补充到 `variables`（不覆盖已有值），随后检查模板
+    /// 引用的变量是否都已提供。严格模式下缺失变量返回
+    /// [`PromptBuildError::MissingVariables`]。
+    pub fn build_prompt(
+        &self,
+        template: &str,
+        variables: &mut HashMap<String, String>,
+        context: &PromptBuildContext,
+    ) -> Result<String> {
+        for (key, value) in &context.custom_variables {
+            let value = match value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            variables.entry(key.clone()).or_insert(value);
+        }
+
+        self.variable_resolver
+            .resolve_variables(template, variables)
+    }
+
     /// 构建规划器prompt
     pub async fn build_planner_prompt(
         &self,
@@ -347,9 +422,11 @@ impl PromptBuilder {
         }
 
         // 构建prompt
-        let prompt = self
-            .variable_resolver
-            .resolve_variables(&optimal_config.core_templates.planner_core, &variables)?;
+        let prompt = self.build_prompt(
+            &optimal_config.core_templates.planner_core,
+            &mut variables,
+            context,
+        )?;
 
         let build_time = start_time.elapsed().as_millis() as u64;
 
@@ -422,9 +499,11 @@ impl PromptBuilder {
         }
 
         // 构建prompt
-        let prompt = self
-            .variable_resolver
-            .resolve_variables(&optimal_config.core_templates.executor_core, &variables)?;
+        let prompt = self.build_prompt(
+            &optimal_config.core_templates.executor_core,
+            &mut variables,
+            context,
+        )?;
 
         let build_time = start_time.elapsed().as_millis() as u64;
 
@@ -501,9 +580,11 @@ impl PromptBuilder {
         }
 
         // 构建prompt
-        let prompt = self
-            .variable_resolver
-            .resolve_variables(&optimal_config.core_templates.replanner_core, &variables)?;
+        let prompt = self.build_prompt(
+            &optimal_config.core_templates.replanner_core,
+            &mut variables,
+            context,
+        )?;
 
         let build_time = start_time.elapsed().as_millis() as u64;
 
@@ -570,9 +651,10 @@ impl PromptBuilder {
         }
 
         // 构建prompt
-        let prompt = self.variable_resolver.resolve_variables(
+        let prompt = self.build_prompt(
             &optimal_config.core_templates.report_generator_core,
-            &variables,
+            &mut variables,
+            context,
         )?;
 
         let build_time = start_time.elapsed().as_millis() as u64;
@@ -856,7 +938,9 @@ pub struct ValidationResult {
 impl VariableResolver {
     /// 创建新的变量解析器
     pub fn new() -> Self {
-        let variable_pattern = Regex::new(r"\{([^}]+)\}").unwrap();
+        // 支持 {name} 与 {{name}}，只匹配标识符，避免把 JSON 示例误认为变量；
+        // 花括号数量在 placeholder_name 中校验
+        let variable_pattern = Regex::new(r"(\{+)\s*([A-Za-z_][A-Za-z0-9_.]*)\s*(\}+)").unwrap();
         let mut builtin_variables = HashMap::new();
 
         // 添加内置变量
@@ -878,50 +962,142 @@ impl VariableResolver {
         Self {
             variable_pattern,
             builtin_variables,
+            strict: true,
         }
     }
 
-    /// 解析变量
-    pub fn resolve_variables(
+    /// 设置严格模式
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// 校验模板引用的变量与提供的变量
+    pub fn validate_variables(
         &self,
         template: &str,
         variables: &HashMap<String, String>,
-    ) -> Result<String> {
-        let mut result = template.to_string();
+    ) -> VariableValidation {
+        let mut referenced: Vec<String> = Vec::new();
+        for name in self.extract_variables(template) {
+            if !referenced.contains(&name) {
+                referenced.push(name);
+            }
+        }
+
+        let missing: Vec<String> = referenced
+            .iter()
+            .filter(|name| {
+                !variables.contains_key(name.as_str())
+                    && !self.builtin_variables.contains_key(name.as_str())
+            })
+            .cloned()
+            .collect();
 
-        // 合并变量（自定义变量优先）
-        let mut all_variables = self.builtin_variables.clone();
-        all_variables.extend(variables.clone());
+        let mut unused: Vec<String> = variables
+            .keys()
+            .filter(|key| !referenced.contains(key))
+            .cloned()
+            .collect();
+        unused.sort();
 
-        // 替换变量
-        for (key, value) in &all_variables {
-            let pattern = format!("{{{}}}", key);
-            result = result.replace(&pattern, value);
+        let suggestions = missing
+            .iter()
+            .filter_map(|name| {
+                unused
+                    .iter()
+                    .map(|candidate| (edit_distance(name, candidate), candidate))
+                    .filter(|(distance, _)| *distance <= 2)
+                    .min()
+                    .map(|(_, candidate)| (name.clone(), candidate.clone()))
+            })
+            .collect();
+
+        VariableValidation {
+            referenced,
+            missing,
+            unused,
+            suggestions,
         }
+    }
 
-        // 检查是否还有未解析的变量
-        if self.variable_pattern.is_match(&result) {
-            let unresolved: Vec<&str> = self
-                .variable_pattern
-                .captures_iter(&result)
-                .map(|cap| cap.get(1).unwrap().as_str())
-                .collect();
+    /// 解析变量
+    pub fn resolve_variables(
+        &self,
+        template: &str,
+        variables: &HashMap<String, String>,
+    ) -> Result<String> {
+        let validation = self.validate_variables(template, variables);
 
-            return Err(anyhow!("未解析的变量: {:?}", unresolved));
+        if !validation.is_satisfied() {
+            if self.strict {
+                return Err(PromptBuildError::MissingVariables {
+                    missing: validation.missing,
+                    suggestions: validation.suggestions,
+                }
+                .into());
+            }
+            tracing::warn!(
+                "Prompt template has unresolved variables, replacing with empty: {}",
+                PromptBuildError::MissingVariables {
+                    missing: validation.missing.clone(),
+                    suggestions: validation.suggestions.clone(),
+                }
+            );
         }
 
-        Ok(result)
+        // 单次替换，变量值中出现的花括号不会被再次解析（自定义变量优先于内置变量）
+        let result = self
+            .variable_pattern
+            .replace_all(template, |caps: &regex::Captures| {
+                let Some(name) = placeholder_name(caps) else {
+                    return caps[0].to_string();
+                };
+                variables
+                    .get(name)
+                    .or_else(|| self.builtin_variables.get(name))
+                    .cloned()
+                    .unwrap_or_default()
+            });
+
+        Ok(result.into_owned())
     }
 
     /// 提取模板中的变量
     pub fn extract_variables(&self, template: &str) -> Vec<String> {
         self.variable_pattern
             .captures_iter(template)
-            .map(|cap| cap.get(1).unwrap().as_str().to_string())
+            .filter_map(|cap| placeholder_name(&cap).map(str::to_string))
             .collect()
     }
 }
 
+/// 只有花括号成对的 `{name}` 或 `{{name}}` 是变量，`{x}}`、`{{x}` 等按原文保留
+fn placeholder_name<'t>(caps: &regex::Captures<'t>) -> Option<&'t str> {
+    let (open, close) = (caps[1].len(), caps[3].len());
+    (open == close && open <= 2).then(|| caps.get(2).unwrap().as_str())
+}
+
+/// 计算两个变量名的编辑距离，用于提示拼写错误
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            current[j] = (prev[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(prev[j - 1] + cost);
+        }
+        prev = current;
+    }
+
+    prev[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -939,6 +1115,78 @@ mod tests {
         assert!(result.contains("today is"));
     }
 
+    #[test]
+    fn test_missing_variables_rejected_in_strict_mode() {
+        let resolver = VariableResolver::new();
+        let template = "Scan {{target_url}} with {scan_profile}";
+
+        let err = resolver
+            .resolve_variables(template, &HashMap::new())
+            .unwrap_err();
+        match err.downcast_ref::<PromptBuildError>() {
+            Some(PromptBuildError::MissingVariables { missing, .. }) => {
+                assert_eq!(
+                    missing,
+                    &vec!["target_url".to_string(), "scan_profile".to_string()]
+                );
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_missing_variables_blank_in_lenient_mode() {
+        let resolver = VariableResolver::new().with_strict(false);
+        let result = resolver
+            .resolve_variables("Scan [{{target_url}}]", &HashMap::new())
+            .unwrap();
+        assert_eq!(result, "Scan []");
+    }
+
+    #[test]
+    fn test_extra_and_typo_variables_detected() {
+        let resolver = VariableResolver::new();
+        let mut variables = HashMap::new();
+        variables.insert("target_ur".to_string(), "https://example.com".to_string());
+        variables.insert("notes".to_string(), "n/a".to_string());
+
+        let validation = resolver.validate_variables("Scan {target_url}", &variables);
+        assert_eq!(validation.missing, vec!["target_url".to_string()]);
+        assert_eq!(
+            validation.unused,
+            vec!["notes".to_string(), "target_ur".to_string()]
+        );
+        assert_eq!(
+            validation.suggestions,
+            vec![("target_url".to_string(), "target_ur".to_string())]
+        );
+
+        let err = resolver
+            .resolve_variables("Scan {target_url}", &variables)
+            .unwrap_err();
+        assert!(err.to_string().contains("target_url -> target_ur"));
+    }
+
+    #[test]
+    fn test_satisfied_variables_resolved_once() {
+        let resolver = VariableResolver::new();
+        let mut variables = HashMap::new();
+        variables.insert("target_url".to_string(), "https://example.com".to_string());
+        // 变量值中的占位符不应再被解析
+        variables.insert("payload".to_string(), "{\"q\": \"{user}\"}".to_string());
+
+        let template = "Target {{target_url}}, body {payload}, example {\"k\": 1}";
+        let validation = resolver.validate_variables(template, &variables);
+        assert!(validation.is_satisfied());
+        assert!(validation.unused.is_empty());
+
+        let result = resolver.resolve_variables(template, &variables).unwrap();
+        assert_eq!(
+            result,
+            "Target https://example.com, body {\"q\": \"{user}\"}, example {\"k\": 1}"
+        );
+    }
+
     #[test]
     fn test_extract_variables() {
         let resolver = VariableResolver::new();
@@ -950,6 +1198,22 @@ mod tests {
         assert!(variables.contains(&"var2".to_string()));
     }
 
+    #[test]
+    fn test_unbalanced_braces_are_not_variables() {
+        let resolver = VariableResolver::new();
+        let template = "{a} {{b}} {c}} {{d} {{{e}}}";
+        assert_eq!(resolver.extract_variables(template), vec!["a", "b"]);
+
+        let variables = HashMap::from([
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+        ]);
+        assert_eq!(
+            resolver.resolve_variables(template, &variables).unwrap(),
+            "1 2 {c}} {{d} {{{e}}}"
+        );
+    }
+
     #[tokio::test]
     async fn test_prompt_builder() {
         let config_manager = PromptConfigManager::new();
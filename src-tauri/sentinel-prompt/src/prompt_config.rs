@@ -39,10 +39,93 @@ pub struct PromptConfig {
     pub core_templates: CoreTemplates,
     /// 自定义约束
     pub custom_constraints: HashMap<String, Vec<String>>,
+    /// 复杂度强制规则（按顺序匹配，先于启发式评估）
+    #[serde(default)]
+    pub complexity_overrides: Vec<ComplexityOverrideRule>,
     /// 配置版本
     pub version: String,
 }
 
+/// 复杂度等级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplexityTier {
+    Low,
+    Medium,
+    High,
+}
+
+impl ComplexityTier {
+    /// 等级对应的复杂度评分
+    pub fn score(self) -> f32 {
+        match self {
+            ComplexityTier::Low => 0.2,
+            ComplexityTier::Medium => 0.5,
+            ComplexityTier::High => 0.9,
+        }
+    }
+}
+
+/// 复杂度强制规则：命中的任务直接使用指定等级，不再进行启发式评估
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplexityOverrideRule {
+    /// 关键词（不区分大小写的子串匹配）或正则表达式
+    pub pattern: String,
+    /// `pattern` 是否为正则表达式
+    #[serde(default)]
+    pub is_regex: bool,
+    /// 强制使用的复杂度等级
+    pub tier: ComplexityTier,
+    /// 编译后的正则（加载配置时生成，不参与序列化）
+    #[serde(skip)]
+    compiled: Option<regex::Regex>,
+}
+
+impl ComplexityOverrideRule {
+    pub fn keyword(pattern: &str, tier: ComplexityTier) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            is_regex: false,
+            tier,
+            compiled: None,
+        }
+    }
+
+    /// 正则规则，创建时即编译，无效的正则返回错误
+    pub fn regex(pattern: &str, tier: ComplexityTier) -> Result<Self> {
+        let mut rule = Self {
+            pattern: pattern.to_string(),
+            is_regex: true,
+            tier,
+            compiled: None,
+        };
+        rule.compile()?;
+        Ok(rule)
+    }
+
+    /// 编译并缓存正则；关键词规则无需编译
+    pub fn compile(&mut self) -> Result<()> {
+        if self.is_regex {
+            let re = regex::Regex::new(&self.pattern)
+                .map_err(|e| anyhow!("复杂度强制规则 {} 不是有效的正则: {}", self.pattern, e))?;
+            self.compiled = Some(re);
+        }
+        Ok(())
+    }
+
+    /// 查询是否命中该规则；未编译的正则规则视为不匹配
+    pub fn matches(&self, query: &str) -> bool {
+        if self.is_regex {
+            self.compiled.as_ref().is_some_and(|re| re.is_match(query))
+        } else {
+            !self.pattern.trim().is_empty()
+                && query
+                    .to_lowercase()
+                    .contains(&self.pattern.trim().to_lowercase())
+        }
+    }
+}
+
 /// 代理配置文件
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AgentProfile {
@@ -328,7 +411,10 @@ impl PromptConfigManager {
     /// 从文件加载配置
     pub async fn load_from_file<P: AsRef<Path>>(config_path: P) -> Result<Self> {
         let content = fs::read_to_string(config_path).await?;
-        let config: PromptConfig = serde_yaml::from_str(&content)?;
+        let mut config: PromptConfig = serde_yaml::from_str(&content)?;
+        for rule in &mut config.complexity_overrides {
+            rule.compile()?;
+        }
 
         Ok(Self {
             config,
@@ -364,6 +450,32 @@ impl PromptConfigManager {
             .await
     }
 
+    /// 设置复杂度强制规则；任一正则无效时保持原规则
+    pub fn set_complexity_overrides(
+        &mut self,
+        mut rules: Vec<ComplexityOverrideRule>,
+    ) -> Result<()> {
+        for rule in &mut rules {
+            rule.compile()?;
+        }
+        self.config.complexity_overrides = rules;
+        Ok(())
+    }
+
+    /// 获取复杂度强制规则
+    pub fn complexity_overrides(&self) -> &[ComplexityOverrideRule] {
+        &self.config.complexity_overrides
+    }
+
+    /// 查找第一条命中的复杂度强制规则
+    pub fn match_complexity_override(&self, query: &str) -> Option<ComplexityTier> {
+        self.config
+            .complexity_overrides
+            .iter()
+            .find(|rule| rule.matches(query))
+            .map(|rule| rule.tier)
+    }
+
     /// 检测领域
     async fn detect_domain(&self, query: &str) -> Result<Option<String>> {
         // 多策略领域检测
@@ -465,6 +577,11 @@ impl PromptConfigManager {
 
     /// 评估复杂度
     async fn assess_complexity(&self, query: &str) -> Result<f32> {
+        // 命中强制规则时跳过启发式评估
+        if let Some(tier) = self.match_complexity_override(query) {
+            return Ok(tier.score());
+        }
+
         let mut complexity = 0.0;
 
         // 基于查询长度
@@ -684,6 +801,15 @@ impl PromptConfigManager {
             }
         }
 
+        for rule in &config.complexity_overrides {
+            if rule.pattern.trim().is_empty() {
+                return Err(anyhow!("复杂度强制规则的匹配模式不能为空"));
+            }
+            if rule.compiled.is_none() {
+                rule.clone().compile()?;
+            }
+        }
+
         Ok(())
     }
 
@@ -791,6 +917,10 @@ impl PromptConfigManager {
             domain_template: DomainTemplate::default(),
             core_templates: Self::create_default_core_templates(),
             custom_constraints,
+            complexity_overrides: vec![
+                ComplexityOverrideRule::keyword("full recon", ComplexityTier::High),
+                ComplexityOverrideRule::keyword("全面侦察", ComplexityTier::High),
+            ],
             version: "1.0.0".to_string(),
         }
     }
@@ -974,6 +1104,86 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_complexity_override_forces_tier() {
+        let mut manager = PromptConfigManager::new();
+        // 默认规则：full recon 一律按高复杂度处理
+        assert_eq!(
+            manager
+                .assess_complexity("Run a full recon on example.com")
+                .await
+                .unwrap(),
+            ComplexityTier::High.score()
+        );
+
+        manager
+            .set_complexity_overrides(vec![
+                ComplexityOverrideRule::regex(r"(?i)^ping\s+\S+$", ComplexityTier::Low).unwrap(),
+                ComplexityOverrideRule::keyword("comprehensive", ComplexityTier::Medium),
+            ])
+            .unwrap();
+        assert_eq!(
+            manager.match_complexity_override("PING 10.0.0.1"),
+            Some(ComplexityTier::Low)
+        );
+        // 规则优先于关键词启发式（comprehensive 原本会提高评分）
+        assert_eq!(
+            manager
+                .assess_complexity("comprehensive deep multi-step audit")
+                .await
+                .unwrap(),
+            ComplexityTier::Medium.score()
+        );
+        assert!(manager.validate_config(&manager.config).is_ok());
+
+        assert!(ComplexityOverrideRule::regex("(", ComplexityTier::High).is_err());
+        let mut invalid = ComplexityOverrideRule::keyword("(", ComplexityTier::High);
+        invalid.is_regex = true;
+        assert!(manager.set_complexity_overrides(vec![invalid]).is_err());
+        // 无效规则不会替换已有规则
+        assert_eq!(manager.complexity_overrides().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_override_regex_is_compiled_on_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prompt_config.yaml");
+        let mut manager = PromptConfigManager::new();
+        manager
+            .set_complexity_overrides(vec![ComplexityOverrideRule::regex(
+                r"(?i)^ping\s+\S+$",
+                ComplexityTier::Low,
+            )
+            .unwrap()])
+            .unwrap();
+        manager.save_to_file(&path).await.unwrap();
+
+        let loaded = PromptConfigManager::load_from_file(&path).await.unwrap();
+        assert_eq!(
+            loaded.match_complexity_override("ping 10.0.0.1"),
+            Some(ComplexityTier::Low)
+        );
+
+        manager.config.complexity_overrides[0].pattern = "(".to_string();
+        manager.save_to_file(&path).await.unwrap();
+        let err = PromptConfigManager::load_from_file(&path)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("不是有效的正则"));
+    }
+
+    #[tokio::test]
+    async fn test_unmatched_query_falls_back_to_heuristic() {
+        let mut manager = PromptConfigManager::new();
+        let query = "complex detailed scan";
+        assert_eq!(manager.match_complexity_override(query), None);
+        let heuristic = manager.assess_complexity(query).await.unwrap();
+
+        manager.set_complexity_overrides(Vec::new()).unwrap();
+        assert_eq!(manager.assess_complexity(query).await.unwrap(), heuristic);
+        assert!(heuristic > 0.0 && heuristic < ComplexityTier::High.score());
+    }
+
     #[test]
     fn test_prompt_building() {
         // let manager = PromptConfigManager::new();
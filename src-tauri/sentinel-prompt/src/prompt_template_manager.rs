@@ -273,32 +273,38 @@ impl PromptTemplateManager {
     }
 
     /// 保存模板
+    ///
+    /// 启用版本控制时每次保存都会生成一个新的不可变版本，模板文件本身
+    /// 始终指向最新版本，返回该版本号。
     pub async fn save_template(
         &mut self,
         template_id: &str,
         template: &CustomTemplate,
-    ) -> Result<()> {
+    ) -> Result<String> {
         // 验证模板
         self.validate_template(template)?;
 
+        let mut template = template.clone();
+
         // 创建版本（如果启用版本控制）
         if self.config.enable_versioning {
-            self.version_manager
-                .create_version(template_id, template)
+            template.version = self
+                .version_manager
+                .create_version(template_id, &template)
                 .await?;
         }
 
         // 保存到文件
         let file_path = self.get_template_file_path(template_id);
-        let content = serde_yaml::to_string(template)?;
+        let content = serde_yaml::to_string(&template)?;
         fs::write(&file_path, content).await?;
 
         // 更新缓存
         if self.config.enable_cache {
-            self.update_cache(template_id, template).await?;
+            self.update_cache(template_id, &template).await?;
         }
 
-        Ok(())
+        Ok(template.version)
     }
 
     /// 删除模板
@@ -389,17 +395,69 @@ impl PromptTemplateManager {
         self.version_manager.get_versions(template_id).await
     }
 
+    /// 列出模板版本历史（按版本号升序）
+    pub async fn list_template_versions(&self, template_id: &str) -> Result<Vec<TemplateVersion>> {
+        self.get_template_versions(template_id).await
+    }
+
+    /// 对比两个版本的模板内容，返回按行的文本差异
+    pub async fn diff_template_versions(
+        &self,
+        template_id: &str,
+        version_a: &str,
+        version_b: &str,
+    ) -> Result<String> {
+        let a = self
+            .version_manager
+            .get_version(template_id, version_a)
+            .await?;
+        let b = self
+            .version_manager
+            .get_version(template_id, version_b)
+            .await?;
+
+        Ok(format!(
+            "--- {}@{}\n+++ {}@{}\n{}",
+            template_id,
+            a.version,
+            template_id,
+            b.version,
+            diff_lines(&a.content, &b.content)
+        ))
+    }
+
+    /// 回滚到指定版本
+    ///
+    /// 旧版本内容会作为一个新版本保存，历史记录不会被改写，返回新版本号。
+    pub async fn rollback_template(&mut self, template_id: &str, version: &str) -> Result<String> {
+        let target = self
+            .version_manager
+            .get_version(template_id, version)
+            .await?;
+
+        // 尽量保留当前模板的名称、描述等元信息，只替换内容
+        let mut template = match self.load_template_from_file(template_id).await {
+            Ok(current) => current,
+            Err(_) => {
+                self.version_manager
+                    .get_version_content(template_id, version)
+                    .await?
+            }
+        };
+        template.content = target.content;
+        template.tags = target.tags;
+
+        self.save_template(template_id, &template).await
+    }
+
     /// 恢复模板版本
     pub async fn restore_template_version(
         &mut self,
         template_id: &str,
         version: &str,
     ) -> Result<()> {
-        let template = self
-            .version_manager
-            .get_version_content(template_id, version)
-            .await?;
-        self.save_template(template_id, &template).await
+        self.rollback_template(template_id, version).await?;
+        Ok(())
     }
 
     /// 获取统计信息
@@ -654,12 +712,19 @@ impl TemplateVersionManager {
         })
     }
 
-    /// 创建新版本
+    /// 创建新版本，返回版本号
     pub async fn create_version(
         &mut self,
         template_id: &str,
         template: &CustomTemplate,
-    ) -> Result<()> {
+    ) -> Result<String> {
+        // 首次写入前先同步磁盘上的历史，避免重启后版本号重复
+        if !self.version_history.contains_key(template_id) {
+            let existing = self.load_versions_from_disk(template_id).await?;
+            self.version_history
+                .insert(template_id.to_string(), existing);
+        }
+
         let version = self.generate_version_number(template_id).await?;
         let content_hash = self.calculate_content_hash(&template.content);
 
@@ -689,7 +754,7 @@ impl TemplateVersionManager {
         // 清理旧版本
         self.cleanup_old_versions(template_id).await?;
 
-        Ok(())
+        Ok(version)
     }
 
     /// 获取版本列表
@@ -702,6 +767,20 @@ impl TemplateVersionManager {
         }
     }
 
+    /// 获取特定版本记录
+    pub async fn get_version(&self, template_id: &str, version: &str) -> Result<TemplateVersion> {
+        let version_file = self
+            .version_dir
+            .join(format!("{}_{}.yaml", template_id, version));
+
+        if !version_file.exists() {
+            return Err(anyhow!("Version not found: {} {}", template_id, version));
+        }
+
+        let content = fs::read_to_string(version_file).await?;
+        Ok(serde_yaml::from_str(&content)?)
+    }
+
     /// 获取特定版本内容
     pub async fn get_version_content(
         &self,
@@ -770,9 +849,16 @@ impl TemplateVersionManager {
     }
 
     /// 生成版本号
+    ///
+    /// 取已有最大版本号加一，旧版本被清理后也不会复用版本号。
     async fn generate_version_number(&self, template_id: &str) -> Result<String> {
         let versions = self.get_versions(template_id).await.unwrap_or_default();
-        let next_version = versions.len() + 1;
+        let next_version = versions
+            .iter()
+            .filter_map(|v| parse_version_number(&v.version))
+            .max()
+            .unwrap_or(0)
+            + 1;
         Ok(format!("v{}", next_version))
     }
 
@@ -793,19 +879,25 @@ impl TemplateVersionManager {
 
         while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
-            if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                if filename.starts_with(&format!("{}_", template_id)) && filename.ends_with(".yaml")
-                {
-                    let content = fs::read_to_string(&path).await?;
-                    if let Ok(version) = serde_yaml::from_str::<TemplateVersion>(&content) {
-                        versions.push(version);
-                    }
+            if let Some(version_part) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix(&format!("{}_", template_id)))
+                .and_then(|n| n.strip_suffix(".yaml"))
+            {
+                // 排除 ID 互为前缀的其他模板（如 foo 与 foo_bar）
+                if parse_version_number(version_part).is_none() {
+                    continue;
+                }
+                let content = fs::read_to_string(&path).await?;
+                if let Ok(version) = serde_yaml::from_str::<TemplateVersion>(&content) {
+                    versions.push(version);
                 }
             }
         }
 
         // 按版本号排序
-        versions.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        versions.sort_by_key(|v| parse_version_number(&v.version));
 
         Ok(versions)
     }
@@ -817,8 +909,9 @@ impl TemplateVersionManager {
         if let Some(versions) = self.version_history.get_mut(template_id) {
             if versions.len() > MAX_VERSIONS {
                 // 保留最新的版本
-                versions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-                let to_remove = versions.split_off(MAX_VERSIONS);
+                versions.sort_by_key(|v| parse_version_number(&v.version));
+                let excess = versions.len() - MAX_VERSIONS;
+                let to_remove: Vec<TemplateVersion> = versions.drain(..excess).collect();
 
                 // 删除文件
                 for version in to_remove {
@@ -836,6 +929,46 @@ impl TemplateVersionManager {
     }
 }
 
+/// 解析 `v{n}` 形式的版本号
+fn parse_version_number(version: &str) -> Option<u64> {
+    version.strip_prefix('v')?.parse().ok()
+}
+
+/// 基于最长公共子序列的按行差异，输出 ` `/`-`/`+` 前缀的行
+fn diff_lines(old: &str, new: &str) -> String {
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] 为 a[i..] 与 b[j..] 的最长公共子序列长度
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            out.push_str(&format!(" {}\n", a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push_str(&format!("-{}\n", a[i]));
+            i += 1;
+        } else {
+            out.push_str(&format!("+{}\n", b[j]));
+            j += 1;
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -874,6 +1007,90 @@ mod tests {
         assert_eq!(loaded.name, template.name);
     }
 
+    #[tokio::test]
+    async fn test_version_history_diff_and_rollback() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = TemplateManagerConfig {
+            enable_hot_reload: false,
+            ..TemplateManagerConfig::default()
+        };
+        let mut manager = PromptTemplateManager::new(temp_dir.path(), config)
+            .await
+            .unwrap();
+
+        let original =
+            "**Role**: security analyst\n**Task**: review {target}\n**Output**: findings list";
+        let mut template = CustomTemplate {
+            id: "recon".to_string(),
+            name: "Recon".to_string(),
+            description: "Recon prompt".to_string(),
+            content: original.to_string(),
+            template_type: TemplateType::Custom,
+            creator: "test_user".to_string(),
+            created_at: chrono::Utc::now(),
+            version: String::new(),
+            tags: vec![],
+            usage_stats: UsageStats::default(),
+            variables: vec!["target".to_string()],
+            metadata: HashMap::new(),
+            category: None,
+            is_system: false,
+            priority: 0,
+        };
+
+        // 创建
+        assert_eq!(
+            manager.save_template("recon", &template).await.unwrap(),
+            "v1"
+        );
+
+        // 编辑
+        template.content = original.replace("findings list", "JSON report");
+        assert_eq!(
+            manager.save_template("recon", &template).await.unwrap(),
+            "v2"
+        );
+        assert!(manager
+            .load_template("recon")
+            .await
+            .unwrap()
+            .content
+            .contains("JSON report"));
+
+        // 对比
+        let diff = manager
+            .diff_template_versions("recon", "v1", "v2")
+            .await
+            .unwrap();
+        assert!(diff.starts_with("--- recon@v1\n+++ recon@v2\n"));
+        assert!(diff.contains(" **Role**: security analyst\n"));
+        assert!(diff.contains("-**Output**: findings list\n"));
+        assert!(diff.contains("+**Output**: JSON report\n"));
+
+        // 回滚：旧内容作为新版本保存，历史保持不变
+        assert_eq!(
+            manager.rollback_template("recon", "v1").await.unwrap(),
+            "v3"
+        );
+        let latest = manager.load_template("recon").await.unwrap();
+        assert_eq!(latest.content, original);
+        assert_eq!(latest.version, "v3");
+        assert_eq!(latest.name, "Recon");
+
+        let versions = manager.list_template_versions("recon").await.unwrap();
+        let numbers: Vec<_> = versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(numbers, vec!["v1", "v2", "v3"]);
+        assert_eq!(versions[1].content, template.content);
+
+        assert!(manager.rollback_template("recon", "v9").await.is_err());
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        assert_eq!(diff_lines("a\nb\nc", "a\nc\nd"), " a\n-b\n c\n+d\n");
+        assert_eq!(diff_lines("same", "same"), " same\n");
+    }
+
     #[test]
     fn test_version_manager() {
         let temp_dir = TempDir::new().unwrap();
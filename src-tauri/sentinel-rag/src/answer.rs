@@ -0,0 +1,346 @@
+//! 基于检索结果的流式回答
+//!
+//! 将检索到的 chunk 组装成带编号的证据块，交给 LLM 流式生成回答；
+//! 生成过程中识别回答里的 `[SOURCE n]` 标记，记录实际被引用的 chunk，
+//! 结束时给出引用列表（chunk id / 文档 id），便于前端跳转到来源。
+
+use anyhow::Result;
+use async_trait::async_trait;
+use regex::Regex;
+use std::collections::HashSet;
+
+use crate::models::{AssistantRagResponse, Citation, QueryResult};
+
+/// 回答规则：只依据证据作答，并用 `[SOURCE n]` 标注来源
+pub const ANSWER_POLICY: &str = "you must strictly answer the question based on the evidence. When citing evidence in your response, use the [SOURCE n] format. If the evidence is insufficient, please answer directly and avoid fabricating. ";
+
+/// 流式回答事件
+#[derive(Debug, Clone)]
+pub enum RagAnswerEvent {
+    /// 文本增量
+    Delta(String),
+    /// 回答中首次引用某个 chunk
+    Cited(Citation),
+    /// 回答结束，附带按首次引用顺序排列的引用列表
+    Completed(Vec<Citation>),
+}
+
+/// 流式生成文本的 LLM 抽象
+///
+/// `on_text` 返回 `false` 表示调用方要求停止生成。
+#[async_trait]
+pub trait AnswerStreamer: Send + Sync {
+    async fn stream(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        on_text: &mut (dyn for<'a> FnMut(&'a str) -> bool + Send),
+    ) -> Result<String>;
+}
+
+/// 将检索结果组装为证据块上下文和对应的引用（第 n 个证据块对应 `[SOURCE n]`）
+pub fn build_evidence_context(results: &[QueryResult]) -> (String, Vec<Citation>) {
+    let mut evidence_blocks = Vec::with_capacity(results.len());
+    let mut citations = Vec::with_capacity(results.len());
+
+    for (idx, item) in results.iter().enumerate() {
+        let chunk = &item.chunk;
+        evidence_blocks.push(format!(
+            "=== SOURCE {} | {} | page: {} | score: {:.2} ===\n{}",
+            idx + 1,
+            chunk.metadata.file_name,
+            chunk.metadata.page_number.unwrap_or(1),
+            item.score,
+            chunk.content
+        ));
+        citations.push(Citation {
+            id: chunk.id.clone(),
+            source_id: chunk.source_id.clone(),
+            file_name: chunk.metadata.file_name.clone(),
+            file_path: Some(chunk.metadata.file_path.clone()),
+            page_number: chunk.metadata.page_number.map(|p| p as i32),
+            section_title: chunk.metadata.section_title.clone(),
+            start_char: chunk.metadata.chunk_start_char,
+            end_char: chunk.metadata.chunk_end_char,
+            score: item.score,
+            content_preview: chunk.content.clone(),
+        });
+    }
+
+    (evidence_blocks.join("\n\n"), citations)
+}
+
+/// 在基础系统提示词后追加回答规则和证据块
+pub fn build_answer_system_prompt(base: Option<&str>, context: &str) -> String {
+    match base.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(base) => format!(
+            "{}\n\n[rule of knowledge]\n{}\n\n[Source Evidence Block]\n{}",
+            base, ANSWER_POLICY, context
+        ),
+        None => format!(
+            "[rule of knowledge]\n{}\n\n[Source Evidence Block]\n{}",
+            ANSWER_POLICY, context
+        ),
+    }
+}
+
+/// 从流式文本中识别 `[SOURCE n]` 引用
+///
+/// 标记可能被拆在两个增量里，未闭合的 `[` 之后的内容留到下次再扫描。
+pub struct CitationTracker {
+    citations: Vec<Citation>,
+    cited: Vec<usize>,
+    seen: HashSet<usize>,
+    answer: String,
+    scanned: usize,
+    marker_regex: Regex,
+}
+
+impl CitationTracker {
+    pub fn new(citations: Vec<Citation>) -> Self {
+        Self {
+            citations,
+            cited: Vec::new(),
+            seen: HashSet::new(),
+            answer: String::new(),
+            scanned: 0,
+            // 支持 [SOURCE 1]、[SOURCE 1, 3]、[SOURCE 1, SOURCE 2]
+            marker_regex: Regex::new(r"(?i)\[\s*SOURCE\s+(\d+(?:\s*,\s*(?:SOURCE\s+)?\d+)*)\s*\]")
+                .unwrap(),
+        }
+    }
+
+    /// 追加一段增量文本，返回其中首次被引用的 chunk
+    pub fn push(&mut self, delta: &str) -> Vec<Citation> {
+        self.answer.push_str(delta);
+        let region = &self.answer[self.scanned..];
+
+        let mut newly_cited = Vec::new();
+        let mut consumed = 0;
+        for caps in self.marker_regex.captures_iter(region) {
+            for number in caps[1].split(',').filter_map(|n| {
+                n.trim()
+                    .trim_start_matches(|c: char| !c.is_ascii_digit())
+                    .parse::<usize>()
+                    .ok()
+            }) {
+                let Some(index) = number.checked_sub(1) else {
+                    continue;
+                };
+                if index < self.citations.len() && self.seen.insert(index) {
+                    self.cited.push(index);
+                    newly_cited.push(self.citations[index].clone());
+                }
+            }
+            consumed = caps.get(0).map(|m| m.end()).unwrap_or(consumed);
+        }
+
+        let rest = &region[consumed..];
+        let keep = match rest.rfind('[') {
+            Some(pos) if !rest[pos..].contains(']') => pos,
+            _ => rest.len(),
+        };
+        self.scanned += consumed + keep;
+        newly_cited
+    }
+
+    pub fn answer(&self) -> &str {
+        &self.answer
+    }
+
+    /// 按首次引用顺序返回被引用的 chunk
+    pub fn cited(&self) -> Vec<Citation> {
+        self.cited
+            .iter()
+            .map(|&index| self.citations[index].clone())
+            .collect()
+    }
+}
+
+/// 基于证据流式生成回答，并通过 `on_event` 推送增量、引用和结束事件
+pub async fn stream_answer<S, F>(
+    streamer: &S,
+    query: &str,
+    context: &str,
+    citations: Vec<Citation>,
+    base_system_prompt: Option<&str>,
+    mut on_event: F,
+) -> Result<AssistantRagResponse>
+where
+    S: AnswerStreamer + ?Sized,
+    F: FnMut(RagAnswerEvent) -> bool + Send,
+{
+    let start_time = std::time::Instant::now();
+    let fallback_reason = if context.trim().is_empty() {
+        Some("no relevant documents found".to_string())
+    } else {
+        None
+    };
+    let system_prompt = if fallback_reason.is_some() {
+        base_system_prompt.unwrap_or_default().to_string()
+    } else {
+        build_answer_system_prompt(base_system_prompt, context)
+    };
+
+    let mut tracker = CitationTracker::new(citations);
+    let mut on_text = |text: &str| {
+        let newly_cited = tracker.push(text);
+        let mut keep_going = on_event(RagAnswerEvent::Delta(text.to_string()));
+        for citation in newly_cited {
+            keep_going &= on_event(RagAnswerEvent::Cited(citation));
+        }
+        keep_going
+    };
+    let content = streamer.stream(&system_prompt, query, &mut on_text).await?;
+
+    // 部分提供商只在结束时返回完整文本
+    let answer = if tracker.answer().trim().is_empty() && !content.is_empty() {
+        tracker.push(&content);
+        content
+    } else {
+        tracker.answer().to_string()
+    };
+    let cited = tracker.cited();
+    on_event(RagAnswerEvent::Completed(cited.clone()));
+
+    let rag_tokens = context.chars().count() / 4;
+    let llm_tokens = answer.chars().count() / 4;
+    Ok(AssistantRagResponse {
+        answer,
+        citations: cited,
+        context_used: context.to_string(),
+        total_tokens_used: rag_tokens + llm_tokens,
+        rag_tokens,
+        llm_tokens,
+        processing_time_ms: start_time.elapsed().as_millis() as u64,
+        fallback_reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ChunkMetadata, DocumentChunk};
+    use std::collections::HashMap;
+
+    fn result(id: &str, content: &str) -> QueryResult {
+        QueryResult {
+            chunk: DocumentChunk {
+                id: id.to_string(),
+                source_id: format!("doc-{}", id),
+                content: content.to_string(),
+                content_hash: format!("{:x}", md5::compute(content.as_bytes())),
+                chunk_index: 0,
+                metadata: ChunkMetadata {
+                    file_path: format!("/kb/{}.md", id),
+                    file_name: format!("{}.md", id),
+                    file_type: "md".to_string(),
+                    file_size: content.len() as u64,
+                    chunk_start_char: 0,
+                    chunk_end_char: content.len(),
+                    page_number: None,
+                    section_title: None,
+                    custom_fields: HashMap::new(),
+                },
+                embedding: None,
+                created_at: chrono::Utc::now(),
+            },
+            score: 0.0,
+            rank: 0,
+        }
+    }
+
+    /// 按固定切片输出回答，模拟 LLM 流式返回
+    struct ScriptedStreamer(Vec<&'static str>);
+
+    #[async_trait]
+    impl AnswerStreamer for ScriptedStreamer {
+        async fn stream(
+            &self,
+            system_prompt: &str,
+            _user_prompt: &str,
+            on_text: &mut (dyn for<'a> FnMut(&'a str) -> bool + Send),
+        ) -> Result<String> {
+            assert!(system_prompt.contains("=== SOURCE 1 |"));
+            let mut content = String::new();
+            for piece in &self.0 {
+                content.push_str(piece);
+                if !on_text(piece) {
+                    break;
+                }
+            }
+            Ok(content)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_completes_with_answer_and_citation() {
+        let corpus = vec![
+            result(
+                "log4j",
+                "Log4j CVE-2021-44228 is exploited through JNDI lookups; upgrade to 2.17.1",
+            ),
+            result(
+                "xss",
+                "Reflected XSS is mitigated with contextual output encoding",
+            ),
+            result(
+                "ssrf",
+                "SSRF protections block requests to internal metadata endpoints",
+            ),
+        ];
+        let query = "how to fix CVE-2021-44228";
+        let results = crate::retrieval::bm25_rank(query, corpus, 2);
+        assert_eq!(results[0].chunk.id, "log4j");
+        let (context, citations) = build_evidence_context(&results);
+
+        let streamer = ScriptedStreamer(vec![
+            "Upgrade Log4j to 2.17.1 and disable JNDI lookups ",
+            "[SOU",
+            "RCE 1].",
+        ]);
+        let mut deltas = String::new();
+        let mut cited_events = Vec::new();
+        let mut completed = None;
+        let response = stream_answer(&streamer, query, &context, citations, None, |event| {
+            match event {
+                RagAnswerEvent::Delta(text) => deltas.push_str(&text),
+                RagAnswerEvent::Cited(citation) => cited_events.push(citation.id),
+                RagAnswerEvent::Completed(citations) => completed = Some(citations),
+            }
+            true
+        })
+        .await
+        .unwrap();
+
+        assert!(!response.answer.is_empty());
+        assert_eq!(response.answer, deltas);
+        assert_eq!(cited_events, vec!["log4j".to_string()]);
+        let completed = completed.expect("stream should emit a completion event");
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].id, "log4j");
+        assert_eq!(completed[0].source_id, "doc-log4j");
+        assert_eq!(response.citations.len(), 1);
+        assert!(response.fallback_reason.is_none());
+    }
+
+    #[test]
+    fn test_tracker_handles_lists_and_out_of_range() {
+        let (_, citations) = build_evidence_context(&[
+            result("a", "alpha"),
+            result("b", "beta"),
+            result("c", "gamma"),
+        ]);
+        let mut tracker = CitationTracker::new(citations);
+
+        assert!(tracker.push("see [SOURCE 9] and [").is_empty());
+        let cited: Vec<String> = tracker
+            .push("source 3, SOURCE 1] then [SOURCE 3]")
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        assert_eq!(cited, vec!["c".to_string(), "a".to_string()]);
+        let ids: Vec<String> = tracker.cited().into_iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec!["c".to_string(), "a".to_string()]);
+    }
+}
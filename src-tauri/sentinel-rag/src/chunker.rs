@@ -1,11 +1,13 @@
 use anyhow::{anyhow, Result};
 use log::{debug, info, warn};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use uuid::Uuid;
 
 use crate::config::{ChunkingStrategy, RagConfig, SupportedFileType};
+use crate::incremental::{file_mtime, hash_file};
 use crate::models::{ChunkMetadata, DocumentChunk, DocumentSource, IngestionStatusEnum};
 
 pub struct DocumentChunker {
@@ -45,19 +47,34 @@ impl DocumentChunker {
             file_type: file_type.clone(),
             file_size,
             file_hash: String::new(),
+            file_mtime: None,
             chunk_count: 0,
             ingestion_status: IngestionStatusEnum::Processing,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             metadata: HashMap::new(),
         };
+        // 与增量摄取比对时使用同一哈希（原始文件字节），首次增量运行才能识别未变化的文件
+        source.file_hash = hash_file(path)?;
+        source.file_mtime = file_mtime(path);
         let supported_file_type = self.detect_file_type(file_path)?;
-        let content = self
-            .extract_text_content(file_path, &supported_file_type)
-            .await?;
-        let cleaned_content = self.text_cleaner.clean_text(&content);
-        source.file_hash = format!("{:x}", md5::compute(content.as_bytes()));
-        let chunks = self.chunk_text(&cleaned_content, file_path, &source)?;
+        let chunks = if matches!(
+            self.config.chunking_strategy,
+            ChunkingStrategy::StructureAware | ChunkingStrategy::Markdown
+        ) && matches!(supported_file_type, SupportedFileType::Md)
+        {
+            // 标题与代码围栏在提取/清洗后会丢失，结构化分块直接使用原始 Markdown
+            let raw = tokio::fs::read_to_string(file_path)
+                .await
+                .map_err(|e| anyhow!("读取MD文件失败: {}", e))?;
+            self.chunk_markdown_structure(&raw, file_path, &source)?
+        } else {
+            let content = self
+                .extract_text_content(file_path, &supported_file_type)
+                .await?;
+            let cleaned_content = self.text_cleaner.clean_text(&content);
+            self.chunk_text(&cleaned_content, file_path, &source)?
+        };
         source.chunk_count = chunks.len();
         source.ingestion_status = IngestionStatusEnum::Completed;
         source.updated_at = chrono::Utc::now();
@@ -146,6 +163,10 @@ impl DocumentChunker {
             ChunkingStrategy::StructureAware => {
                 self.chunk_text_structure_aware(content, file_path, source)
             }
+            ChunkingStrategy::SentenceBoundary => {
+                self.chunk_text_sentence_boundary(content, file_path, source)
+            }
+            ChunkingStrategy::Markdown => self.chunk_text_markdown(content, file_path, source),
         }
     }
 
@@ -443,77 +464,87 @@ impl DocumentChunker {
         }
     }
 
-    fn chunk_markdown_structure(
+    fn chunk_text_sentence_boundary(
         &self,
         content: &str,
         file_path: &str,
         source: &DocumentSource,
     ) -> Result<Vec<DocumentChunk>> {
-        let mut chunks = Vec::new();
-        let lines: Vec<&str> = content.lines().collect();
-        let mut current_section = String::new();
-        let mut chunk_index = 0;
-        let mut current_header = String::new();
-        for line in lines {
-            if line.starts_with('#') {
-                if !current_section.trim().is_empty() {
-                    let chunk_content = if !current_header.is_empty() {
-                        format!("{}\n\n{}", current_header, current_section)
-                    } else {
-                        current_section.clone()
-                    };
-                    if chunk_content.chars().count() >= self.config.min_chunk_size_chars {
-                        let chunk = self.create_chunk(
-                            &chunk_content,
-                            chunk_index,
-                            0,
-                            chunk_content.chars().count(),
-                            file_path,
-                            source,
-                        )?;
-                        chunks.push(chunk);
-                        chunk_index += 1;
-                    }
-                }
-                current_header = line.to_string();
-                current_section.clear();
-            } else {
-                current_section.push_str(line);
-                current_section.push('\n');
-                if current_section.chars().count() > self.config.max_chunk_size_chars {
-                    let chunk_content = if !current_header.is_empty() {
-                        format!("{}\n\n{}", current_header, current_section)
-                    } else {
-                        current_section.clone()
-                    };
-                    let mut sub_chunks =
-                        self.chunk_text_recursive(&chunk_content, file_path, source)?;
-                    for chunk in &mut sub_chunks {
-                        chunk.chunk_index = chunk_index;
-                        chunk_index += 1;
-                    }
-                    chunks.append(&mut sub_chunks);
-                    current_section.clear();
-                }
+        let chars: Vec<char> = content.chars().collect();
+        if chars.is_empty() || self.config.chunk_size_chars == 0 {
+            return Ok(Vec::new());
+        }
+        let pieces = chunk_sentences(
+            &chars,
+            0,
+            chars.len(),
+            self.config.chunk_size_chars,
+            self.config.chunk_overlap_chars,
+            &[],
+        );
+        self.bounded_chunks(&chars, pieces, &[], file_path, source)
+    }
+
+    fn chunk_text_markdown(
+        &self,
+        content: &str,
+        file_path: &str,
+        source: &DocumentSource,
+    ) -> Result<Vec<DocumentChunk>> {
+        match self.detect_file_type(file_path)? {
+            SupportedFileType::Md => self.chunk_markdown_structure(content, file_path, source),
+            _ => {
+                debug!("Markdown chunking on non-markdown file, fallback to sentence boundary");
+                self.chunk_text_sentence_boundary(content, file_path, source)
             }
         }
-        if !current_section.trim().is_empty() {
-            let chunk_content = if !current_header.is_empty() {
-                format!("{}\n\n{}", current_header, current_section)
-            } else {
-                current_section
-            };
-            if chunk_content.chars().count() >= self.config.min_chunk_size_chars {
-                let chunk = self.create_chunk(
-                    &chunk_content,
-                    chunk_index,
-                    0,
-                    chunk_content.chars().count(),
-                    file_path,
-                    source,
-                )?;
-                chunks.push(chunk);
+    }
+
+    fn chunk_markdown_structure(
+        &self,
+        content: &str,
+        file_path: &str,
+        source: &DocumentSource,
+    ) -> Result<Vec<DocumentChunk>> {
+        let chars: Vec<char> = content.chars().collect();
+        if chars.is_empty() || self.config.chunk_size_chars == 0 {
+            return Ok(Vec::new());
+        }
+        let pieces = chunk_markdown(content, &chars, self.config.chunk_size_chars);
+        let fences = code_fence_spans(content);
+        self.bounded_chunks(&chars, pieces, &fences, file_path, source)
+    }
+
+    /// 按 min/max 约束整理分块结果并生成 DocumentChunk，`fences` 内的代码块不会被切开
+    fn bounded_chunks(
+        &self,
+        chars: &[char],
+        pieces: Vec<TextChunk>,
+        fences: &[(usize, usize)],
+        file_path: &str,
+        source: &DocumentSource,
+    ) -> Result<Vec<DocumentChunk>> {
+        let pieces = enforce_size_bounds(
+            chars,
+            pieces,
+            fences,
+            self.config.min_chunk_size_chars,
+            self.config.max_chunk_size_chars,
+        );
+        let mut chunks = Vec::with_capacity(pieces.len());
+        for (index, piece) in pieces.into_iter().enumerate() {
+            let mut chunk = self.create_chunk(
+                &piece.content,
+                index,
+                piece.start_char,
+                piece.end_char,
+                file_path,
+                source,
+            )?;
+            if !piece.headings.is_empty() {
+                chunk.metadata.section_title = Some(piece.headings.join(" > "));
             }
+            chunks.push(chunk);
         }
         Ok(chunks)
     }
@@ -599,6 +630,364 @@ impl DocumentChunker {
     }
 }
 
+/// 分块策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkStrategy {
+    /// 固定长度切分，相邻块重叠 `overlap` 个字符
+    #[default]
+    FixedSize,
+    /// 按句子聚合到 `size` 以内，重叠部分取上一块末尾的整句
+    SentenceBoundary,
+    /// 按 Markdown 标题切分章节，代码块保持完整，并在每块前附加标题路径
+    Markdown,
+}
+
+/// 分块配置，`size` 与 `overlap` 以字符计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkConfig {
+    pub strategy: ChunkStrategy,
+    pub size: usize,
+    pub overlap: usize,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        Self {
+            strategy: ChunkStrategy::FixedSize,
+            size: 1500,
+            overlap: 150,
+        }
+    }
+}
+
+/// 分块结果，`start_char`/`end_char` 为在原文中的字符区间
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextChunk {
+    pub content: String,
+    pub start_char: usize,
+    pub end_char: usize,
+    /// 所在章节的标题路径（仅 Markdown 策略）
+    pub headings: Vec<String>,
+}
+
+/// 按配置的策略对文本分块
+pub fn chunk_document(content: &str, config: &ChunkConfig) -> Vec<TextChunk> {
+    if config.size == 0 {
+        return Vec::new();
+    }
+    let chars: Vec<char> = content.chars().collect();
+    match config.strategy {
+        ChunkStrategy::FixedSize => chunk_fixed_size(&chars, config.size, config.overlap),
+        ChunkStrategy::SentenceBoundary => {
+            chunk_sentences(&chars, 0, chars.len(), config.size, config.overlap, &[])
+        }
+        ChunkStrategy::Markdown => chunk_markdown(content, &chars, config.size),
+    }
+}
+
+fn text_chunk(chars: &[char], start: usize, end: usize, headings: &[String]) -> Option<TextChunk> {
+    let body: String = chars[start..end].iter().collect();
+    if body.trim().is_empty() {
+        return None;
+    }
+    let content = if headings.is_empty() {
+        body
+    } else {
+        format!("{}\n\n{}", headings.join(" > "), body.trim_matches('\n'))
+    };
+    Some(TextChunk {
+        content,
+        start_char: start,
+        end_char: end,
+        headings: headings.to_vec(),
+    })
+}
+
+fn chunk_fixed_size(chars: &[char], size: usize, overlap: usize) -> Vec<TextChunk> {
+    let step = size - overlap.min(size - 1);
+    let mut out = Vec::new();
+    let mut start = 0usize;
+    while start < chars.len() {
+        let end = (start + size).min(chars.len());
+        out.extend(text_chunk(chars, start, end, &[]));
+        if end >= chars.len() {
+            break;
+        }
+        start += step;
+    }
+    out
+}
+
+/// 在 `chars[from..to]` 内按句子聚合分块
+fn chunk_sentences(
+    chars: &[char],
+    from: usize,
+    to: usize,
+    size: usize,
+    overlap: usize,
+    headings: &[String],
+) -> Vec<TextChunk> {
+    // 超过 size 的单句按固定长度拆开
+    let mut spans = Vec::new();
+    for (s, e) in DocumentChunker::split_into_sentences(&chars[from..to]) {
+        let (s, e) = (s + from, e + from);
+        let mut x = s;
+        while x < e {
+            let y = (x + size).min(e);
+            spans.push((x, y));
+            x = y;
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut i = 0usize;
+    while i < spans.len() {
+        let start = spans[i].0;
+        let mut j = i;
+        while j + 1 < spans.len() && spans[j + 1].1 - start <= size {
+            j += 1;
+        }
+        let end = spans[j].1;
+        out.extend(text_chunk(chars, start, end, headings));
+        if j + 1 >= spans.len() {
+            break;
+        }
+        // 回退若干整句作为重叠，且保证至少前进一句
+        let mut k = j + 1;
+        while k > i + 1 && end - spans[k - 1].0 <= overlap {
+            k -= 1;
+        }
+        i = k;
+    }
+    out
+}
+
+enum MarkdownBlock {
+    Heading {
+        level: usize,
+        text: String,
+    },
+    Body {
+        start: usize,
+        end: usize,
+        is_code: bool,
+    },
+}
+
+fn parse_markdown_blocks(content: &str) -> Vec<MarkdownBlock> {
+    let mut blocks = Vec::new();
+    let mut offset = 0usize;
+    let mut para: Option<(usize, usize)> = None;
+    // (围栏字符, 长度, 代码块起始位置)
+    let mut fence: Option<(char, usize, usize)> = None;
+
+    for line in content.split('\n') {
+        let line_start = offset;
+        let line_end = offset + line.chars().count();
+        offset = line_end + 1;
+        let trimmed = line.trim_start();
+        let marker = trimmed
+            .chars()
+            .next()
+            .filter(|c| *c == '`' || *c == '~')
+            .map(|c| (c, trimmed.chars().take_while(|x| *x == c).count()))
+            .filter(|(_, n)| *n >= 3);
+
+        if let Some((fence_char, fence_len, code_start)) = fence {
+            if matches!(marker, Some((c, n)) if c == fence_char && n >= fence_len) {
+                blocks.push(MarkdownBlock::Body {
+                    start: code_start,
+                    end: line_end,
+                    is_code: true,
+                });
+                fence = None;
+            }
+            continue;
+        }
+
+        if let Some((c, n)) = marker {
+            if let Some((s, e)) = para.take() {
+                blocks.push(MarkdownBlock::Body {
+                    start: s,
+                    end: e,
+                    is_code: false,
+                });
+            }
+            fence = Some((c, n, line_start));
+            continue;
+        }
+
+        let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+        let is_heading = (1..=6).contains(&hashes) && trimmed[hashes..].starts_with([' ', '\t']);
+        if is_heading || trimmed.is_empty() {
+            if let Some((s, e)) = para.take() {
+                blocks.push(MarkdownBlock::Body {
+                    start: s,
+                    end: e,
+                    is_code: false,
+                });
+            }
+            if is_heading {
+                blocks.push(MarkdownBlock::Heading {
+                    level: hashes,
+                    text: trimmed[hashes..]
+                        .trim()
+                        .trim_end_matches('#')
+                        .trim()
+                        .to_string(),
+                });
+            }
+            continue;
+        }
+
+        para = Some(match para {
+            Some((s, _)) => (s, line_end),
+            None => (line_start, line_end),
+        });
+    }
+
+    if let Some((_, _, code_start)) = fence {
+        // 未闭合的代码块延伸到文末
+        blocks.push(MarkdownBlock::Body {
+            start: code_start,
+            end: offset.saturating_sub(1),
+            is_code: true,
+        });
+    } else if let Some((s, e)) = para {
+        blocks.push(MarkdownBlock::Body {
+            start: s,
+            end: e,
+            is_code: false,
+        });
+    }
+    blocks
+}
+
+/// Markdown 中代码围栏块的字符区间（含开闭围栏行）
+fn code_fence_spans(content: &str) -> Vec<(usize, usize)> {
+    parse_markdown_blocks(content)
+        .into_iter()
+        .filter_map(|block| match block {
+            MarkdownBlock::Body {
+                start,
+                end,
+                is_code: true,
+            } => Some((start, end)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn chunk_markdown(content: &str, chars: &[char], size: usize) -> Vec<TextChunk> {
+    let mut out = Vec::new();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    let mut headings: Vec<String> = Vec::new();
+    let mut current: Option<(usize, usize)> = None;
+
+    for block in parse_markdown_blocks(content) {
+        match block {
+            MarkdownBlock::Heading { level, text } => {
+                if let Some((s, e)) = current.take() {
+                    out.extend(text_chunk(chars, s, e, &headings));
+                }
+                while stack.last().is_some_and(|(l, _)| *l >= level) {
+                    stack.pop();
+                }
+                stack.push((level, text));
+                headings = stack.iter().map(|(_, t)| t.clone()).collect();
+            }
+            MarkdownBlock::Body {
+                start,
+                end,
+                is_code,
+            } => {
+                if let Some((s, e)) = current {
+                    if end - s <= size {
+                        current = Some((s, end));
+                        continue;
+                    }
+                    out.extend(text_chunk(chars, s, e, &headings));
+                    current = None;
+                }
+                if end - start <= size || is_code {
+                    // 代码块超过 size 时仍整体保留，仅受 max_chunk_size_chars 约束
+                    current = Some((start, end));
+                } else {
+                    out.extend(chunk_sentences(chars, start, end, size, 0, &headings));
+                }
+            }
+        }
+    }
+    if let Some((s, e)) = current {
+        out.extend(text_chunk(chars, s, e, &headings));
+    }
+    out
+}
+
+/// 将分块约束在 `[min, max]` 字符区间内：超过 max 的块按 max 切开，
+/// 过短的块并入同一章节的前一块，仍不足 min 的块丢弃（整篇都过短时保留原样）
+///
+/// 切点不会落在 `fences` 中的代码块内部：切点移到代码块之前，
+/// 代码块本身超过 max 时整体保留为一块。
+fn enforce_size_bounds(
+    chars: &[char],
+    pieces: Vec<TextChunk>,
+    fences: &[(usize, usize)],
+    min: usize,
+    max: usize,
+) -> Vec<TextChunk> {
+    let max = if max == 0 { usize::MAX } else { max };
+    let mut split = Vec::with_capacity(pieces.len());
+    for piece in pieces {
+        if piece.end_char - piece.start_char <= max {
+            split.push(piece);
+            continue;
+        }
+        let mut start = piece.start_char;
+        while start < piece.end_char {
+            let mut end = start.saturating_add(max).min(piece.end_char);
+            if let Some(&(fence_start, fence_end)) =
+                fences.iter().find(|(fs, fe)| *fs < end && end < *fe)
+            {
+                end = if fence_start > start {
+                    fence_start
+                } else {
+                    fence_end.min(piece.end_char)
+                };
+            }
+            split.extend(text_chunk(chars, start, end, &piece.headings));
+            start = end;
+        }
+    }
+
+    let mut merged: Vec<TextChunk> = Vec::with_capacity(split.len());
+    for piece in split {
+        if let Some(last) = merged.last_mut() {
+            let too_short =
+                last.content.chars().count() < min || piece.content.chars().count() < min;
+            if too_short
+                && last.headings == piece.headings
+                && piece.end_char - last.start_char <= max
+            {
+                if let Some(joined) =
+                    text_chunk(chars, last.start_char, piece.end_char, &piece.headings)
+                {
+                    *last = joined;
+                    continue;
+                }
+            }
+        }
+        merged.push(piece);
+    }
+
+    if merged.iter().all(|c| c.content.chars().count() < min) {
+        return merged;
+    }
+    merged.retain(|c| c.content.chars().count() >= min);
+    merged
+}
+
 pub struct TextCleaner {
     whitespace_regex: Regex,
     special_chars_regex: Regex,
@@ -629,3 +1018,229 @@ impl TextCleaner {
         cleaned
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunker(strategy: ChunkingStrategy, size: usize, min: usize, max: usize) -> DocumentChunker {
+        DocumentChunker::new(RagConfig {
+            chunking_strategy: strategy,
+            chunk_size_chars: size,
+            chunk_overlap_chars: 0,
+            min_chunk_size_chars: min,
+            max_chunk_size_chars: max,
+            ..RagConfig::default()
+        })
+    }
+
+    fn source() -> DocumentSource {
+        DocumentSource {
+            id: "src".to_string(),
+            file_path: "doc.md".to_string(),
+            file_name: "doc.md".to_string(),
+            file_type: "md".to_string(),
+            file_size: 0,
+            file_hash: String::new(),
+            file_mtime: None,
+            chunk_count: 0,
+            ingestion_status: IngestionStatusEnum::Processing,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn contents(chunks: &[DocumentChunk]) -> Vec<&str> {
+        chunks.iter().map(|c| c.content.as_str()).collect()
+    }
+
+    fn config(strategy: ChunkStrategy, size: usize, overlap: usize) -> ChunkConfig {
+        ChunkConfig {
+            strategy,
+            size,
+            overlap,
+        }
+    }
+
+    #[test]
+    fn fixed_size_applies_overlap() {
+        let chunks = chunk_document("abcdefghij", &config(ChunkStrategy::FixedSize, 4, 1));
+        let texts: Vec<_> = chunks.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(texts, vec!["abcd", "defg", "ghij"]);
+        assert_eq!((chunks[1].start_char, chunks[1].end_char), (3, 7));
+    }
+
+    #[test]
+    fn chunk_document_keeps_code_blocks_whole() {
+        let code = "```python\n# not a heading\nprint('x' * 80)\n```";
+        let doc = format!("# Exploit\n\nSee below.\n\n{}\n\nDone.\n", code);
+
+        let chunks = chunk_document(&doc, &config(ChunkStrategy::Markdown, 20, 0));
+        assert_eq!(
+            chunks.iter().filter(|c| c.content.contains("```")).count(),
+            1
+        );
+        assert!(chunks.iter().any(|c| c.content.contains(code)));
+        assert!(chunks
+            .iter()
+            .all(|c| c.headings == vec!["Exploit".to_string()]));
+    }
+
+    #[test]
+    fn sentence_boundary_never_cuts_sentences() {
+        let chunker = chunker(ChunkingStrategy::SentenceBoundary, 10, 0, 3000);
+        let chunks = chunker
+            .chunk_text("One. Two. Three. Four.", "doc.txt", &source())
+            .unwrap();
+        assert_eq!(chunks.len(), 3);
+        for chunk in &chunks {
+            assert!(
+                chunk.content.trim_end().ends_with('.'),
+                "{:?}",
+                chunk.content
+            );
+        }
+        assert_eq!(chunks[0].content, "One. Two.");
+    }
+
+    #[test]
+    fn markdown_keeps_fenced_code_block_intact() {
+        let code = "```python\ndef handler(event):\n\n    # not a heading\n    payload = event['body']\n    return payload\n```";
+        let doc = format!(
+            "# Exploit\n\nThe handler below echoes the request body back to the caller.\n\n{}\n\nAfter the code block there is more prose.\n",
+            code
+        );
+
+        let chunks = chunker(ChunkingStrategy::Markdown, 40, 0, 3000)
+            .chunk_text(&doc, "doc.md", &source())
+            .unwrap();
+        assert!(chunks.len() > 1);
+
+        let with_code: Vec<_> = chunks
+            .iter()
+            .filter(|c| c.content.contains("```"))
+            .collect();
+        assert_eq!(with_code.len(), 1);
+        assert!(with_code[0].content.contains(code));
+        assert!(chunks
+            .iter()
+            .all(|c| c.content.matches("```").count() % 2 == 0));
+        // 代码块中的 # 注释不能被当作标题
+        assert!(chunks
+            .iter()
+            .all(|c| c.metadata.section_title.as_deref() == Some("Exploit")));
+    }
+
+    #[test]
+    fn markdown_prepends_heading_breadcrumbs() {
+        let doc = "# Guide\n\nIntro.\n\n## Install\n\nRun it.\n\n### Linux\n\nUse apt.\n\n## Usage\n\nCall it.\n";
+        let chunks = chunker(ChunkingStrategy::Markdown, 500, 0, 3000)
+            .chunk_text(doc, "doc.md", &source())
+            .unwrap();
+
+        assert_eq!(
+            contents(&chunks),
+            vec![
+                "Guide\n\nIntro.",
+                "Guide > Install\n\nRun it.",
+                "Guide > Install > Linux\n\nUse apt.",
+                "Guide > Usage\n\nCall it.",
+            ]
+        );
+        assert_eq!(
+            chunks[3].metadata.section_title.as_deref(),
+            Some("Guide > Usage")
+        );
+    }
+
+    #[test]
+    fn new_strategies_honor_max_chunk_size() {
+        let prose = "Some words here. ".repeat(6);
+        let doc = format!("# Big\n\n{}\n", prose.trim_end());
+        let chunks = chunker(ChunkingStrategy::Markdown, 200, 0, 50)
+            .chunk_text(&doc, "doc.md", &source())
+            .unwrap();
+        assert!(chunks.len() > 1);
+        assert!(chunks
+            .iter()
+            .all(|c| c.metadata.chunk_end_char - c.metadata.chunk_start_char <= 50));
+
+        let long_sentence = format!("{}.", "y".repeat(120));
+        let chunks = chunker(ChunkingStrategy::SentenceBoundary, 200, 0, 50)
+            .chunk_text(&long_sentence, "doc.txt", &source())
+            .unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.content.chars().count() <= 50));
+    }
+
+    #[test]
+    fn max_chunk_size_never_cuts_code_fences() {
+        let code = format!("```\n{}\n```", "x".repeat(100));
+        let doc = format!(
+            "# Big\n\n{}\n\n{}\n",
+            "Intro text. ".repeat(3).trim_end(),
+            code
+        );
+        let chunks = chunker(ChunkingStrategy::Markdown, 200, 0, 50)
+            .chunk_text(&doc, "doc.md", &source())
+            .unwrap();
+
+        // 超过 max 的代码块整体保留在一块中，切点落在代码块之前
+        let with_fence: Vec<_> = chunks
+            .iter()
+            .filter(|c| c.content.contains("```"))
+            .collect();
+        assert_eq!(with_fence.len(), 1);
+        assert!(with_fence[0].content.contains(&code));
+        assert!(chunks
+            .iter()
+            .filter(|c| !c.content.contains("```"))
+            .all(|c| c.metadata.chunk_end_char - c.metadata.chunk_start_char <= 50));
+    }
+
+    #[test]
+    fn new_strategies_merge_or_drop_chunks_below_min() {
+        let text = "Hello there. Hi. Bye.";
+        let unbounded = chunker(ChunkingStrategy::SentenceBoundary, 13, 0, 3000)
+            .chunk_text(text, "doc.txt", &source())
+            .unwrap();
+        assert_eq!(unbounded.len(), 2);
+        // "Hi. Bye." 不足 min，并入前一块
+        let bounded = chunker(ChunkingStrategy::SentenceBoundary, 13, 10, 3000)
+            .chunk_text(text, "doc.txt", &source())
+            .unwrap();
+        assert_eq!(contents(&bounded), vec![text]);
+
+        // 不同章节不能合并，过短的章节被丢弃
+        let doc = "# A\n\nThis section is long enough to keep.\n\n# B\n\nTiny.\n";
+        let chunks = DocumentChunker::new(RagConfig {
+            chunking_strategy: ChunkingStrategy::Markdown,
+            min_chunk_size_chars: 20,
+            ..RagConfig::default()
+        })
+        .chunk_text(doc, "doc.md", &source())
+        .unwrap();
+        assert_eq!(
+            contents(&chunks),
+            vec!["A\n\nThis section is long enough to keep."]
+        );
+    }
+
+    #[tokio::test]
+    async fn process_document_uses_configured_markdown_strategy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("guide.md");
+        std::fs::write(&path, "# Guide\n\nIntro text.\n\n## Install\n\nRun it.\n").unwrap();
+
+        let (source, chunks) = chunker(ChunkingStrategy::Markdown, 500, 0, 3000)
+            .process_document(path.to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(source.chunk_count, 2);
+        assert_eq!(
+            contents(&chunks),
+            vec!["Guide\n\nIntro text.", "Guide > Install\n\nRun it."]
+        );
+    }
+}
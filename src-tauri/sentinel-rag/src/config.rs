@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+pub use sentinel_core::models::rag_config::RetrievalMode;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RagConfig {
     pub database_path: Option<PathBuf>,
@@ -38,6 +40,24 @@ pub struct RagConfig {
     /// Number of adjacent chunks to include after the matched chunk
     #[serde(default = "default_chunk_expansion_after")]
     pub chunk_expansion_after: usize,
+    /// Retrieval mode used by queries: vector, keyword or hybrid
+    #[serde(default)]
+    pub retrieval_mode: RetrievalMode,
+    /// Weight of the vector ranking in hybrid mode (keyword weight is `1 - alpha`)
+    #[serde(default = "default_hybrid_alpha")]
+    pub hybrid_alpha: f32,
+    /// Fallback embedding providers, tried in order when the primary fails or times out
+    #[serde(default)]
+    pub embedding_fallbacks: Vec<EmbeddingConfig>,
+    /// Default per-provider embedding timeout in seconds
+    #[serde(default = "default_embedding_timeout_secs")]
+    pub embedding_timeout_secs: u64,
+    /// Consecutive failures before a provider's circuit opens
+    #[serde(default = "default_embedding_failure_threshold")]
+    pub embedding_failure_threshold: u32,
+    /// How long an open circuit skips its provider, in seconds
+    #[serde(default = "default_embedding_circuit_cooldown_secs")]
+    pub embedding_circuit_cooldown_secs: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -47,6 +67,10 @@ pub enum ChunkingStrategy {
     RecursiveCharacter,
     Semantic,
     StructureAware,
+    /// 按句子聚合到 chunk_size 以内，不在句中切断
+    SentenceBoundary,
+    /// 按 Markdown 标题切分章节，代码块保持完整，并在每块前附加标题路径
+    Markdown,
 }
 
 impl Default for RagConfig {
@@ -76,6 +100,12 @@ impl Default for RagConfig {
             chunk_expansion_enabled: true,
             chunk_expansion_before: 1,
             chunk_expansion_after: 1,
+            retrieval_mode: RetrievalMode::Vector,
+            hybrid_alpha: 0.5,
+            embedding_fallbacks: Vec::new(),
+            embedding_timeout_secs: 120,
+            embedding_failure_threshold: 3,
+            embedding_circuit_cooldown_secs: 60,
         }
     }
 }
@@ -87,6 +117,9 @@ pub struct EmbeddingConfig {
     pub api_key: Option<String>,
     pub base_url: Option<String>,
     pub dimensions: Option<usize>,
+    /// Per-provider timeout in seconds; falls back to `RagConfig::embedding_timeout_secs`
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
 }
 
 impl Default for EmbeddingConfig {
@@ -97,6 +130,7 @@ impl Default for EmbeddingConfig {
             api_key: None,
             base_url: Some("http://localhost:11434".to_string()),
             dimensions: None,
+            timeout_secs: None,
         }
     }
 }
@@ -160,3 +194,15 @@ fn default_chunk_expansion_before() -> usize {
 fn default_chunk_expansion_after() -> usize {
     1
 }
+fn default_hybrid_alpha() -> f32 {
+    0.5
+}
+fn default_embedding_timeout_secs() -> u64 {
+    120
+}
+fn default_embedding_failure_threshold() -> u32 {
+    3
+}
+fn default_embedding_circuit_cooldown_secs() -> u64 {
+    60
+}
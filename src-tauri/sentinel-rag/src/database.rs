@@ -10,18 +10,26 @@ use rig_sqlite::{
 use rusqlite::ffi::{sqlite3, sqlite3_api_routines, sqlite3_auto_extension};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
 use std::path::Path;
-use std::sync::Once;
+use std::pin::Pin;
+use std::sync::{Arc, Once};
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio_rusqlite::Connection;
 use tracing::{error, info, warn};
 
 use crate::config::EmbeddingConfig;
+use crate::embeddings::{
+    provider_label, CircuitBreakerPolicy, EmbeddingDimensionGuard, EmbeddingFallbackChain,
+    EmbeddingProviderStatus,
+};
 use crate::models::{DocumentChunk, QueryResult};
 
 type SqliteExtensionFn =
     unsafe extern "C" fn(*mut sqlite3, *mut *mut i8, *const sqlite3_api_routines) -> i32;
 static SQLITE_VEC_REGISTER: Once = Once::new();
+const DEFAULT_EMBEDDING_TIMEOUT: Duration = Duration::from_secs(120);
 
 type HttpClient = rig::http_client::ReqwestClient;
 type OpenAiEmbedding = rig::providers::openai::EmbeddingModel<HttpClient>;
@@ -30,6 +38,33 @@ type OllamaEmbedding = rig::providers::ollama::EmbeddingModel<HttpClient>;
 type CohereEmbedding = rig::providers::cohere::EmbeddingModel<HttpClient>;
 type GeminiEmbedding = rig::providers::gemini::EmbeddingModel<HttpClient>;
 
+/// Vector write prepared by a lane after its embedding call succeeded
+type PendingInsert<'a> = Pin<Box<dyn Future<Output = Result<usize>> + Send + 'a>>;
+
+async fn write_chunk_rows<M>(
+    collection_name: &str,
+    chunks: Vec<DocumentChunk>,
+    embeddings: Vec<Embedding>,
+    store: SqliteVectorStore<M, RagVectorRow>,
+) -> Result<usize>
+where
+    M: EmbeddingModel + Sync + Send + Clone + 'static,
+{
+    let docs = chunks_to_rows(collection_name, chunks)
+        .into_iter()
+        .zip(embeddings)
+        .map(|(row, embedding)| (row, OneOrMany::one(embedding)))
+        .collect::<Vec<(RagVectorRow, OneOrMany<Embedding>)>>();
+    let count = docs.len();
+
+    store
+        .add_rows(docs)
+        .await
+        .map_err(|e| anyhow!("Failed to insert into sqlite vector store: {}", e))?;
+
+    Ok(count)
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct RagVectorRow {
     id: String,
@@ -80,15 +115,62 @@ pub struct SqliteVectorManager {
     embedding_config: EmbeddingConfig,
     conn: RwLock<Option<Connection>>,
     store: RwLock<Option<ProviderStore>>,
+    /// Fallback providers, each with its own typed store over the same connection
+    fallbacks: Vec<SqliteVectorManager>,
+    chain: EmbeddingFallbackChain,
+    /// Shared with the fallbacks so every provider writes vectors of one dimension
+    dimensions: Arc<EmbeddingDimensionGuard>,
 }
 
 impl SqliteVectorManager {
     pub fn new(database_path: String, embedding_config: EmbeddingConfig) -> Self {
+        let chain = EmbeddingFallbackChain::from_configs(
+            std::slice::from_ref(&embedding_config),
+            DEFAULT_EMBEDDING_TIMEOUT,
+            CircuitBreakerPolicy::default(),
+        );
         Self {
             database_path,
             embedding_config,
             conn: RwLock::new(None),
             store: RwLock::new(None),
+            fallbacks: Vec::new(),
+            chain,
+            dimensions: Arc::new(EmbeddingDimensionGuard::new()),
+        }
+    }
+    /// Try `fallbacks` in order when the primary embedding provider fails or times out
+    pub fn with_fallbacks(
+        mut self,
+        fallbacks: Vec<EmbeddingConfig>,
+        default_timeout: Duration,
+        policy: CircuitBreakerPolicy,
+    ) -> Self {
+        let mut configs = vec![self.embedding_config.clone()];
+        configs.extend(fallbacks.iter().cloned());
+        self.chain = EmbeddingFallbackChain::from_configs(&configs, default_timeout, policy);
+        self.fallbacks = fallbacks
+            .into_iter()
+            .map(|config| {
+                let mut lane = SqliteVectorManager::new(self.database_path.clone(), config);
+                lane.dimensions = self.dimensions.clone();
+                lane
+            })
+            .collect();
+        self
+    }
+    /// Provider (`provider/model`) that served the last successful embedding call
+    pub fn active_embedding_provider(&self) -> Option<String> {
+        self.chain.active_provider()
+    }
+    pub fn embedding_provider_status(&self) -> Vec<EmbeddingProviderStatus> {
+        self.chain.status()
+    }
+    fn lane(&self, index: usize) -> &SqliteVectorManager {
+        if index == 0 {
+            self
+        } else {
+            &self.fallbacks[index - 1]
         }
     }
     pub async fn initialize(&self) -> Result<()> {
@@ -103,6 +185,9 @@ impl SqliteVectorManager {
             .await
             .map_err(|e| anyhow!("Failed to open sqlite vector DB: {}", e))?;
 
+        for fallback in &self.fallbacks {
+            *fallback.conn.write().await = Some(conn.clone());
+        }
         let mut guard = self.conn.write().await;
         *guard = Some(conn);
 
@@ -126,6 +211,25 @@ impl SqliteVectorManager {
             return Ok(0);
         }
 
+        // 降级链（及其超时）只覆盖嵌入；写库在选定提供商之后进行，慢写入不会被误判为超时而重复嵌入
+        let (_, write) = self
+            .chain
+            .execute(|index| {
+                let chunks = chunks.clone();
+                async move {
+                    self.lane(index)
+                        .insert_chunks_direct(collection_name, chunks)
+                        .await
+                }
+            })
+            .await?;
+        write.await
+    }
+    async fn insert_chunks_direct<'a>(
+        &'a self,
+        collection_name: &'a str,
+        chunks: Vec<DocumentChunk>,
+    ) -> Result<PendingInsert<'a>> {
         let provider = self.embedding_config.provider.to_lowercase();
         info!(
             "Using embedding provider: {}, model: {}",
@@ -151,11 +255,11 @@ impl SqliteVectorManager {
             )),
         }
     }
-    async fn insert_chunks_ollama(
-        &self,
-        collection_name: &str,
+    async fn insert_chunks_ollama<'a>(
+        &'a self,
+        collection_name: &'a str,
         chunks: Vec<DocumentChunk>,
-    ) -> Result<usize> {
+    ) -> Result<PendingInsert<'a>> {
         let base_url = self
             .embedding_config
             .base_url
@@ -175,27 +279,31 @@ impl SqliteVectorManager {
             dimensions,
         );
 
-        let store = self.ensure_ollama_store(&embedding_model).await?;
-        self.insert_chunks_impl(collection_name, chunks, embedding_model, store)
-            .await
+        let embeddings = self.embed_chunks(&embedding_model, &chunks).await?;
+        Ok(Box::pin(async move {
+            let store = self.ensure_ollama_store(&embedding_model).await?;
+            write_chunk_rows(collection_name, chunks, embeddings, store).await
+        }))
     }
-    async fn insert_chunks_openai(
-        &self,
-        collection_name: &str,
+    async fn insert_chunks_openai<'a>(
+        &'a self,
+        collection_name: &'a str,
         chunks: Vec<DocumentChunk>,
-    ) -> Result<usize> {
+    ) -> Result<PendingInsert<'a>> {
         let client =
             self.openai_compatible_client("OPENAI_API_KEY", "https://api.openai.com/v1")?;
         let embedding_model = self.openai_embedding_model(&client)?;
-        let store = self.ensure_openai_store(&embedding_model).await?;
-        self.insert_chunks_impl(collection_name, chunks, embedding_model, store)
-            .await
+        let embeddings = self.embed_chunks(&embedding_model, &chunks).await?;
+        Ok(Box::pin(async move {
+            let store = self.ensure_openai_store(&embedding_model).await?;
+            write_chunk_rows(collection_name, chunks, embeddings, store).await
+        }))
     }
-    async fn insert_chunks_cohere(
-        &self,
-        collection_name: &str,
+    async fn insert_chunks_cohere<'a>(
+        &'a self,
+        collection_name: &'a str,
         chunks: Vec<DocumentChunk>,
-    ) -> Result<usize> {
+    ) -> Result<PendingInsert<'a>> {
         let api_key_str = self
             .embedding_config
             .api_key
@@ -214,23 +322,25 @@ impl SqliteVectorManager {
             _ => client.embedding_model(&self.embedding_config.model, "search_document"),
         };
 
-        let store = self.ensure_cohere_store(&embedding_model).await?;
-        self.insert_chunks_impl(collection_name, chunks, embedding_model, store)
-            .await
+        let embeddings = self.embed_chunks(&embedding_model, &chunks).await?;
+        Ok(Box::pin(async move {
+            let store = self.ensure_cohere_store(&embedding_model).await?;
+            write_chunk_rows(collection_name, chunks, embeddings, store).await
+        }))
     }
-    async fn insert_chunks_anthropic(
-        &self,
-        _collection_name: &str,
+    async fn insert_chunks_anthropic<'a>(
+        &'a self,
+        _collection_name: &'a str,
         _chunks: Vec<DocumentChunk>,
-    ) -> Result<usize> {
+    ) -> Result<PendingInsert<'a>> {
         warn!("Anthropic doesn't provide embedding models.");
         Err(anyhow!("Anthropic doesn't support embedding models. Please use OpenAI, Cohere, or other embedding providers."))
     }
-    async fn insert_chunks_gemini(
-        &self,
-        collection_name: &str,
+    async fn insert_chunks_gemini<'a>(
+        &'a self,
+        collection_name: &'a str,
         chunks: Vec<DocumentChunk>,
-    ) -> Result<usize> {
+    ) -> Result<PendingInsert<'a>> {
         let api_key_str = self
             .embedding_config
             .api_key
@@ -243,80 +353,97 @@ impl SqliteVectorManager {
         let client = rig::providers::gemini::Client::from_env();
         let embedding_model: GeminiEmbedding = client.embedding_model(&self.embedding_config.model);
 
-        let store = self.ensure_gemini_store(&embedding_model).await?;
-        self.insert_chunks_impl(collection_name, chunks, embedding_model, store)
-            .await
+        let embeddings = self.embed_chunks(&embedding_model, &chunks).await?;
+        Ok(Box::pin(async move {
+            let store = self.ensure_gemini_store(&embedding_model).await?;
+            write_chunk_rows(collection_name, chunks, embeddings, store).await
+        }))
     }
-    async fn insert_chunks_deepseek(
-        &self,
-        collection_name: &str,
+    async fn insert_chunks_deepseek<'a>(
+        &'a self,
+        collection_name: &'a str,
         chunks: Vec<DocumentChunk>,
-    ) -> Result<usize> {
+    ) -> Result<PendingInsert<'a>> {
         let client =
             self.openai_compatible_client("DEEPSEEK_API_KEY", "https://api.deepseek.com/v1")?;
         let embedding_model = self.openai_embedding_model(&client)?;
-        let store = self.ensure_openai_store(&embedding_model).await?;
-        self.insert_chunks_impl(collection_name, chunks, embedding_model, store)
-            .await
+        let embeddings = self.embed_chunks(&embedding_model, &chunks).await?;
+        Ok(Box::pin(async move {
+            let store = self.ensure_openai_store(&embedding_model).await?;
+            write_chunk_rows(collection_name, chunks, embeddings, store).await
+        }))
     }
-    async fn insert_chunks_moonshot(
-        &self,
-        collection_name: &str,
+    async fn insert_chunks_moonshot<'a>(
+        &'a self,
+        collection_name: &'a str,
         chunks: Vec<DocumentChunk>,
-    ) -> Result<usize> {
+    ) -> Result<PendingInsert<'a>> {
         let client =
             self.openai_compatible_client("MOONSHOT_API_KEY", "https://api.moonshot.cn/v1")?;
         let embedding_model = self.openai_embedding_model(&client)?;
-        let store = self.ensure_openai_store(&embedding_model).await?;
-        self.insert_chunks_impl(collection_name, chunks, embedding_model, store)
-            .await
+        let embeddings = self.embed_chunks(&embedding_model, &chunks).await?;
+        Ok(Box::pin(async move {
+            let store = self.ensure_openai_store(&embedding_model).await?;
+            write_chunk_rows(collection_name, chunks, embeddings, store).await
+        }))
     }
-    async fn insert_chunks_openrouter(
-        &self,
-        collection_name: &str,
+    async fn insert_chunks_openrouter<'a>(
+        &'a self,
+        collection_name: &'a str,
         chunks: Vec<DocumentChunk>,
-    ) -> Result<usize> {
+    ) -> Result<PendingInsert<'a>> {
         let client = self.openrouter_client()?;
         let embedding_model = self.openrouter_embedding_model(&client)?;
-        let store = self.ensure_openrouter_store(&embedding_model).await?;
-        self.insert_chunks_impl(collection_name, chunks, embedding_model, store)
-            .await
+        let embeddings = self.embed_chunks(&embedding_model, &chunks).await?;
+        Ok(Box::pin(async move {
+            let store = self.ensure_openrouter_store(&embedding_model).await?;
+            write_chunk_rows(collection_name, chunks, embeddings, store).await
+        }))
     }
-    async fn insert_chunks_modelscope(
-        &self,
-        collection_name: &str,
+    async fn insert_chunks_modelscope<'a>(
+        &'a self,
+        collection_name: &'a str,
         chunks: Vec<DocumentChunk>,
-    ) -> Result<usize> {
+    ) -> Result<PendingInsert<'a>> {
         let client = self.openai_compatible_client(
             "MODELSCOPE_API_KEY",
             "https://api-inference.modelscope.cn/v1",
         )?;
         let embedding_model = self.openai_embedding_model(&client)?;
-        let store = self.ensure_openai_store(&embedding_model).await?;
-        self.insert_chunks_impl(collection_name, chunks, embedding_model, store)
-            .await
+        let embeddings = self.embed_chunks(&embedding_model, &chunks).await?;
+        Ok(Box::pin(async move {
+            let store = self.ensure_openai_store(&embedding_model).await?;
+            write_chunk_rows(collection_name, chunks, embeddings, store).await
+        }))
     }
-    async fn insert_chunks_lmstudio(
-        &self,
-        collection_name: &str,
+    async fn insert_chunks_lmstudio<'a>(
+        &'a self,
+        collection_name: &'a str,
         chunks: Vec<DocumentChunk>,
-    ) -> Result<usize> {
+    ) -> Result<PendingInsert<'a>> {
         let client = self.openai_compatible_client("OPENAI_API_KEY", "http://localhost:1234/v1")?;
         let embedding_model = self.openai_embedding_model(&client)?;
-        let store = self.ensure_openai_store(&embedding_model).await?;
-        self.insert_chunks_impl(collection_name, chunks, embedding_model, store)
-            .await
+        let embeddings = self.embed_chunks(&embedding_model, &chunks).await?;
+        Ok(Box::pin(async move {
+            let store = self.ensure_openai_store(&embedding_model).await?;
+            write_chunk_rows(collection_name, chunks, embeddings, store).await
+        }))
     }
-    async fn insert_chunks_impl<M>(
+    /// Embed chunk contents with retries; the vectors are written later by `write_chunk_rows`
+    async fn embed_chunks<M>(
         &self,
-        collection_name: &str,
-        chunks: Vec<DocumentChunk>,
-        embedding_model: M,
-        store: SqliteVectorStore<M, RagVectorRow>,
-    ) -> Result<usize>
+        embedding_model: &M,
+        chunks: &[DocumentChunk],
+    ) -> Result<Vec<Embedding>>
     where
         M: EmbeddingModel + Sync + Send + Clone + 'static,
     {
+        // Fail over to the next provider on a dimension mismatch before spending a request
+        self.validate_nonzero_dimensions(embedding_model)?;
+        self.dimensions.check(
+            &provider_label(&self.embedding_config),
+            embedding_model.ndims(),
+        )?;
         let definitions: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
 
         let mut retry_count = 0;
@@ -354,19 +481,7 @@ impl SqliteVectorManager {
                 embeddings.len()
             ));
         }
-
-        let docs = chunks_to_rows(collection_name, chunks)
-            .into_iter()
-            .zip(embeddings.into_iter())
-            .map(|(row, embedding)| (row, OneOrMany::one(embedding)))
-            .collect::<Vec<(RagVectorRow, OneOrMany<Embedding>)>>();
-
-        store
-            .add_rows(docs.clone())
-            .await
-            .map_err(|e| anyhow!("Failed to insert into sqlite vector store: {}", e))?;
-
-        Ok(docs.len())
+        Ok(embeddings)
     }
     pub async fn search_similar(
         &self,
@@ -378,6 +493,22 @@ impl SqliteVectorManager {
             return Ok(Vec::new());
         }
 
+        let (_, results) = self
+            .chain
+            .execute(|index| async move {
+                self.lane(index)
+                    .search_similar_direct(collection_name, query, top_k)
+                    .await
+            })
+            .await?;
+        Ok(results)
+    }
+    async fn search_similar_direct(
+        &self,
+        collection_name: &str,
+        query: &str,
+        top_k: usize,
+    ) -> Result<Vec<QueryResult>> {
         let provider = self.embedding_config.provider.to_lowercase();
 
         match provider.as_str() {
@@ -630,6 +761,59 @@ impl SqliteVectorManager {
         }
         Ok(results)
     }
+    /// 基于 BM25 的关键词检索，对集合内全部 chunk 文本打分
+    pub async fn search_keyword(
+        &self,
+        collection_name: &str,
+        query: &str,
+        top_k: usize,
+    ) -> Result<Vec<QueryResult>> {
+        if top_k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.connection().await?;
+        let collection = collection_name.to_string();
+
+        let rows = conn
+            .call(move |conn| {
+                let table_exists: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='rag_vectors'",
+                    [],
+                    |row| row.get(0),
+                )?;
+
+                if table_exists == 0 {
+                    return Ok(Vec::new());
+                }
+
+                let mut stmt = conn.prepare(
+                    "SELECT id, source_id, chunk_index, definition FROM rag_vectors WHERE collection_name = ?1",
+                )?;
+                let rows = stmt
+                    .query_map(rusqlite::params![collection], |row| {
+                        Ok(RagVectorRow {
+                            id: row.get(0)?,
+                            collection_name: String::new(),
+                            source_id: row.get(1)?,
+                            chunk_index: row.get(2)?,
+                            definition: row.get(3)?,
+                        })
+                    })?
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(rows)
+            })
+            .await
+            .map_err(|e| anyhow!("Keyword search failed: {}", e))?;
+
+        let candidates = rows
+            .into_iter()
+            .enumerate()
+            .map(|(rank, row)| map_row_to_query_result(row, 0.0, rank))
+            .collect();
+
+        Ok(crate::retrieval::bm25_rank(query, candidates, top_k))
+    }
     pub async fn delete_collection(&self, collection_name: &str) -> Result<()> {
         let conn = self.connection().await?;
         let collection_name = collection_name.to_string();
@@ -675,6 +859,51 @@ impl SqliteVectorManager {
         info!("Deleted collection: {}", collection_name_for_log);
         Ok(())
     }
+    /// 删除某个文档源在向量库中的全部 chunk
+    pub async fn delete_source(&self, source_id: &str) -> Result<usize> {
+        let conn = self.connection().await?;
+        let source_id = source_id.to_string();
+
+        let deleted = conn
+            .call(move |conn| {
+                let table_exists: i64 = conn.query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='rag_vectors'",
+                    [],
+                    |row| row.get(0),
+                )?;
+
+                if table_exists == 0 {
+                    return Ok(0);
+                }
+
+                let tx = conn.transaction()?;
+
+                let embeddings_exists: i64 = tx.query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='rag_vectors_embeddings'",
+                    [],
+                    |row| row.get(0),
+                )?;
+
+                if embeddings_exists > 0 {
+                    tx.execute(
+                        "DELETE FROM rag_vectors_embeddings WHERE rowid IN (SELECT rowid FROM rag_vectors WHERE source_id = ?1)",
+                        rusqlite::params![source_id],
+                    )?;
+                }
+
+                let deleted = tx.execute(
+                    "DELETE FROM rag_vectors WHERE source_id = ?1",
+                    rusqlite::params![source_id],
+                )?;
+
+                tx.commit()?;
+                Ok(deleted)
+            })
+            .await
+            .map_err(|e| anyhow!("Failed to delete source vectors: {}", e))?;
+
+        Ok(deleted)
+    }
     pub async fn list_collections(&self) -> Result<Vec<String>> {
         let conn = self.connection().await?;
 
@@ -794,6 +1023,10 @@ impl SqliteVectorManager {
         M: EmbeddingModel + Sync + Send + Clone + 'static,
     {
         self.validate_nonzero_dimensions(embedding_model)?;
+        self.dimensions.check(
+            &provider_label(&self.embedding_config),
+            embedding_model.ndims(),
+        )?;
         self.rollback_stale_transaction(conn).await?;
 
         match SqliteVectorStore::new(conn.clone(), embedding_model).await {
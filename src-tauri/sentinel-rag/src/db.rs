@@ -57,6 +57,12 @@ pub trait RagDatabase: Send + Sync {
     ) -> Result<(Vec<DocumentSource>, i64)>;
     async fn get_rag_chunks(&self, document_id: &str) -> Result<Vec<DocumentChunk>>;
     async fn delete_rag_document(&self, document_id: &str) -> Result<()>;
+    async fn update_rag_document_fingerprint(
+        &self,
+        document_id: &str,
+        file_hash: &str,
+        file_mtime: Option<i64>,
+    ) -> Result<()>;
 
     async fn save_rag_query(
         &self,
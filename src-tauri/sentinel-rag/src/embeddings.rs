@@ -1,8 +1,12 @@
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use log::{debug, error, info, warn};
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use crate::config::EmbeddingConfig;
 
@@ -489,3 +493,580 @@ pub fn create_embedding_provider(config: &EmbeddingConfig) -> Result<Box<dyn Emb
         }
     }
 }
+
+/// 熔断策略：连续失败达到阈值后，在冷却期内跳过该提供商
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerPolicy {
+    pub failure_threshold: u32,
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            cooldown: Duration::from_secs(60),
+        }
+    }
+}
+
+/// 降级链中单个提供商的状态
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingProviderStatus {
+    pub provider: String,
+    pub timeout_secs: u64,
+    pub consecutive_failures: u32,
+    pub circuit_open: bool,
+    pub last_error: Option<String>,
+}
+
+/// 不同提供商产出的向量维度不一致
+#[derive(Debug, Clone)]
+pub struct EmbeddingDimensionMismatch {
+    pub provider: String,
+    pub dimension: usize,
+    pub expected_provider: String,
+    pub expected_dimension: usize,
+}
+
+impl std::fmt::Display for EmbeddingDimensionMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Embedding dimension mismatch: {} produces {}-dim vectors but {} produced {}-dim vectors; \
+             configure fallback providers with the same dimension or re-index the collection",
+            self.provider, self.dimension, self.expected_provider, self.expected_dimension
+        )
+    }
+}
+
+impl std::error::Error for EmbeddingDimensionMismatch {}
+
+/// 记录首个提供商的向量维度，后续提供商必须与之一致
+#[derive(Debug, Default)]
+pub struct EmbeddingDimensionGuard {
+    expected: Mutex<Option<(String, usize)>>,
+}
+
+impl EmbeddingDimensionGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn check(&self, provider: &str, dimension: usize) -> Result<()> {
+        let mut expected = self.expected.lock().unwrap_or_else(|e| e.into_inner());
+        match expected.as_ref() {
+            Some((expected_provider, expected_dimension)) if *expected_dimension != dimension => {
+                Err(EmbeddingDimensionMismatch {
+                    provider: provider.to_string(),
+                    dimension,
+                    expected_provider: expected_provider.clone(),
+                    expected_dimension: *expected_dimension,
+                }
+                .into())
+            }
+            Some(_) => Ok(()),
+            None => {
+                *expected = Some((provider.to_string(), dimension));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn expected(&self) -> Option<usize> {
+        self.expected
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .map(|(_, dimension)| *dimension)
+    }
+}
+
+#[derive(Debug)]
+struct ChainEntry {
+    label: String,
+    timeout: Duration,
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    last_error: Option<String>,
+}
+
+/// 嵌入提供商降级链
+///
+/// 按顺序尝试各提供商，每个提供商有独立超时；连续失败的提供商会被熔断一段时间。
+/// 所有提供商都处于熔断状态时仍会全部尝试一遍，避免单提供商配置被完全阻断。
+#[derive(Debug)]
+pub struct EmbeddingFallbackChain {
+    entries: Mutex<Vec<ChainEntry>>,
+    policy: CircuitBreakerPolicy,
+    active: Mutex<Option<usize>>,
+}
+
+impl EmbeddingFallbackChain {
+    /// `providers` 为 (名称, 超时) 列表，第一个为主提供商
+    pub fn new(providers: Vec<(String, Duration)>, policy: CircuitBreakerPolicy) -> Self {
+        let entries = providers
+            .into_iter()
+            .map(|(label, timeout)| ChainEntry {
+                label,
+                timeout,
+                consecutive_failures: 0,
+                open_until: None,
+                last_error: None,
+            })
+            .collect();
+        Self {
+            entries: Mutex::new(entries),
+            policy,
+            active: Mutex::new(None),
+        }
+    }
+
+    /// 根据嵌入配置构建降级链，未单独配置超时的提供商使用 `default_timeout`
+    pub fn from_configs(
+        configs: &[EmbeddingConfig],
+        default_timeout: Duration,
+        policy: CircuitBreakerPolicy,
+    ) -> Self {
+        Self::new(
+            configs
+                .iter()
+                .map(|config| {
+                    let timeout = config
+                        .timeout_secs
+                        .map(Duration::from_secs)
+                        .unwrap_or(default_timeout);
+                    (provider_label(config), timeout)
+                })
+                .collect(),
+            policy,
+        )
+    }
+
+    fn lock_entries(&self) -> std::sync::MutexGuard<'_, Vec<ChainEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock_entries().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 最近一次成功的提供商下标
+    pub fn active_index(&self) -> Option<usize> {
+        *self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 最近一次成功的提供商名称
+    pub fn active_provider(&self) -> Option<String> {
+        let index = self.active_index()?;
+        self.lock_entries().get(index).map(|e| e.label.clone())
+    }
+
+    pub fn status(&self) -> Vec<EmbeddingProviderStatus> {
+        let now = Instant::now();
+        self.lock_entries()
+            .iter()
+            .map(|entry| EmbeddingProviderStatus {
+                provider: entry.label.clone(),
+                timeout_secs: entry.timeout.as_secs(),
+                consecutive_failures: entry.consecutive_failures,
+                circuit_open: entry.open_until.is_some_and(|until| until > now),
+                last_error: entry.last_error.clone(),
+            })
+            .collect()
+    }
+
+    /// 依次在各提供商上执行 `op(下标)`，返回首个成功的提供商下标和结果
+    pub async fn execute<T, F, Fut>(&self, op: F) -> Result<(usize, T)>
+    where
+        F: Fn(usize) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let candidates: Vec<(usize, String, Duration)> = {
+            let entries = self.lock_entries();
+            let now = Instant::now();
+            let closed: Vec<(usize, String, Duration)> = entries
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| e.open_until.is_none_or(|until| until <= now))
+                .map(|(i, e)| (i, e.label.clone(), e.timeout))
+                .collect();
+            if closed.is_empty() {
+                entries
+                    .iter()
+                    .enumerate()
+                    .map(|(i, e)| (i, e.label.clone(), e.timeout))
+                    .collect()
+            } else {
+                closed
+            }
+        };
+        if candidates.is_empty() {
+            return Err(anyhow!("未配置嵌入提供商"));
+        }
+
+        let mut errors = Vec::new();
+        for (index, label, timeout) in candidates {
+            let error = match tokio::time::timeout(timeout, op(index)).await {
+                Ok(Ok(value)) => {
+                    self.record_success(index);
+                    if index > 0 {
+                        warn!("主嵌入提供商不可用，已降级到 {}", label);
+                    }
+                    return Ok((index, value));
+                }
+                Ok(Err(e)) => {
+                    if e.downcast_ref::<EmbeddingDimensionMismatch>().is_some() {
+                        error!("嵌入提供商 {} 维度不匹配: {}", label, e);
+                    } else {
+                        warn!("嵌入提供商 {} 调用失败: {}", label, e);
+                    }
+                    e.to_string()
+                }
+                Err(_) => {
+                    warn!("嵌入提供商 {} 超时 ({}s)", label, timeout.as_secs());
+                    format!("timed out after {}s", timeout.as_secs())
+                }
+            };
+            self.record_failure(index, &error);
+            errors.push(format!("{}: {}", label, error));
+        }
+        Err(anyhow!("所有嵌入提供商均失败: {}", errors.join("; ")))
+    }
+
+    fn record_success(&self, index: usize) {
+        if let Some(entry) = self.lock_entries().get_mut(index) {
+            entry.consecutive_failures = 0;
+            entry.open_until = None;
+        }
+        *self.active.lock().unwrap_or_else(|e| e.into_inner()) = Some(index);
+    }
+
+    fn record_failure(&self, index: usize, error: &str) {
+        if let Some(entry) = self.lock_entries().get_mut(index) {
+            entry.consecutive_failures += 1;
+            entry.last_error = Some(error.to_string());
+            if entry.consecutive_failures >= self.policy.failure_threshold.max(1) {
+                info!(
+                    "嵌入提供商 {} 连续失败 {} 次，熔断 {}s",
+                    entry.label,
+                    entry.consecutive_failures,
+                    self.policy.cooldown.as_secs()
+                );
+                entry.open_until = Some(Instant::now() + self.policy.cooldown);
+            }
+        }
+    }
+}
+
+/// 带降级链的嵌入提供商，对外表现为单个提供商
+pub struct FallbackEmbeddingProvider {
+    providers: Vec<Box<dyn EmbeddingProvider>>,
+    chain: EmbeddingFallbackChain,
+    dimensions: EmbeddingDimensionGuard,
+}
+
+impl FallbackEmbeddingProvider {
+    /// `providers` 为 (提供商, 超时) 列表，第一个为主提供商
+    pub fn new(
+        providers: Vec<(Box<dyn EmbeddingProvider>, Duration)>,
+        policy: CircuitBreakerPolicy,
+    ) -> Self {
+        let chain = EmbeddingFallbackChain::new(
+            providers
+                .iter()
+                .map(|(p, timeout)| {
+                    (
+                        format!("{}/{}", p.provider_name(), p.model_name()),
+                        *timeout,
+                    )
+                })
+                .collect(),
+            policy,
+        );
+        Self {
+            providers: providers.into_iter().map(|(p, _)| p).collect(),
+            chain,
+            dimensions: EmbeddingDimensionGuard::new(),
+        }
+    }
+
+    pub fn chain(&self) -> &EmbeddingFallbackChain {
+        &self.chain
+    }
+
+    /// 当前使用的提供商；尚未成功调用时为主提供商
+    fn current(&self) -> &dyn EmbeddingProvider {
+        let index = self.chain.active_index().unwrap_or(0);
+        self.providers[index].as_ref()
+    }
+
+    /// 最近一次成功的提供商（provider/model）
+    pub fn active_provider(&self) -> Option<String> {
+        self.chain.active_provider()
+    }
+
+    /// 比较各提供商声明的向量维度，不一致时返回错误
+    pub async fn check_dimensions(&self) -> Result<usize> {
+        let guard = EmbeddingDimensionGuard::new();
+        let mut dimension = 0;
+        for provider in &self.providers {
+            dimension = provider.get_embedding_dimension().await?;
+            guard.check(
+                &format!("{}/{}", provider.provider_name(), provider.model_name()),
+                dimension,
+            )?;
+        }
+        Ok(dimension)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for FallbackEmbeddingProvider {
+    async fn embed_texts(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let (_, embeddings) = self
+            .chain
+            .execute(|index| async move {
+                let provider = &self.providers[index];
+                let embeddings = provider.embed_texts(texts).await?;
+                let label = format!("{}/{}", provider.provider_name(), provider.model_name());
+                for embedding in &embeddings {
+                    self.dimensions.check(&label, embedding.len())?;
+                }
+                Ok(embeddings)
+            })
+            .await?;
+        Ok(embeddings)
+    }
+    async fn get_embedding_dimension(&self) -> Result<usize> {
+        match self.dimensions.expected() {
+            Some(dimension) => Ok(dimension),
+            None => self.current().get_embedding_dimension().await,
+        }
+    }
+    fn provider_name(&self) -> &str {
+        self.current().provider_name()
+    }
+    fn model_name(&self) -> &str {
+        self.current().model_name()
+    }
+}
+
+/// 根据主提供商和降级提供商配置创建降级链
+pub fn create_fallback_embedding_provider(
+    configs: &[EmbeddingConfig],
+    default_timeout: Duration,
+    policy: CircuitBreakerPolicy,
+) -> Result<FallbackEmbeddingProvider> {
+    let providers = configs
+        .iter()
+        .map(|config| {
+            let timeout = config
+                .timeout_secs
+                .map(Duration::from_secs)
+                .unwrap_or(default_timeout);
+            Ok((create_embedding_provider(config)?, timeout))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if providers.is_empty() {
+        return Err(anyhow!("未配置嵌入提供商"));
+    }
+    Ok(FallbackEmbeddingProvider::new(providers, policy))
+}
+
+pub fn provider_label(config: &EmbeddingConfig) -> String {
+    format!("{}/{}", config.provider, config.model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    enum Behavior {
+        Ok(usize),
+        Fail,
+        Hang,
+    }
+
+    struct MockProvider {
+        name: &'static str,
+        behavior: Behavior,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl EmbeddingProvider for MockProvider {
+        async fn embed_texts(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.behavior {
+                Behavior::Ok(dim) => Ok(texts.iter().map(|_| vec![0.1; dim]).collect()),
+                Behavior::Fail => Err(anyhow!("connection refused")),
+                Behavior::Hang => {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    Ok(Vec::new())
+                }
+            }
+        }
+        async fn get_embedding_dimension(&self) -> Result<usize> {
+            match self.behavior {
+                Behavior::Ok(dim) => Ok(dim),
+                _ => Ok(768),
+            }
+        }
+        fn provider_name(&self) -> &str {
+            self.name
+        }
+        fn model_name(&self) -> &str {
+            "mock"
+        }
+    }
+
+    fn mock(
+        name: &'static str,
+        behavior: Behavior,
+    ) -> (Box<dyn EmbeddingProvider>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = MockProvider {
+            name,
+            behavior,
+            calls: calls.clone(),
+        };
+        (Box::new(provider), calls)
+    }
+
+    fn texts() -> Vec<String> {
+        vec!["hello".to_string(), "world".to_string()]
+    }
+
+    #[tokio::test]
+    async fn test_primary_fails_fallback_succeeds() {
+        let (primary, _) = mock("remote", Behavior::Fail);
+        let (secondary, _) = mock("local", Behavior::Ok(4));
+        let provider = FallbackEmbeddingProvider::new(
+            vec![
+                (primary, Duration::from_secs(5)),
+                (secondary, Duration::from_secs(5)),
+            ],
+            CircuitBreakerPolicy::default(),
+        );
+
+        let embeddings = provider.embed_texts(&texts()).await.unwrap();
+        assert_eq!(embeddings.len(), 2);
+        assert_eq!(provider.active_provider().as_deref(), Some("local/mock"));
+        assert_eq!(provider.provider_name(), "local");
+        assert_eq!(provider.get_embedding_dimension().await.unwrap(), 4);
+        assert_eq!(provider.chain().status()[0].consecutive_failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_primary_timeout_falls_back() {
+        let (primary, _) = mock("remote", Behavior::Hang);
+        let (secondary, _) = mock("local", Behavior::Ok(4));
+        let provider = FallbackEmbeddingProvider::new(
+            vec![
+                (primary, Duration::from_millis(50)),
+                (secondary, Duration::from_secs(5)),
+            ],
+            CircuitBreakerPolicy::default(),
+        );
+
+        provider.embed_texts(&texts()).await.unwrap();
+        assert_eq!(provider.active_provider().as_deref(), Some("local/mock"));
+        let status = provider.chain().status();
+        assert!(status[0]
+            .last_error
+            .as_deref()
+            .unwrap()
+            .contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout_does_not_cover_deferred_work() {
+        // insert_chunks 依赖此约定：op 只在链内完成嵌入，返回的写库 future 在链外执行
+        let chain = EmbeddingFallbackChain::new(
+            vec![("remote/mock".to_string(), Duration::from_millis(50))],
+            CircuitBreakerPolicy::default(),
+        );
+        let (index, write) = chain
+            .execute(|_| async {
+                Ok(Box::pin(async {
+                    tokio::time::sleep(Duration::from_millis(150)).await;
+                    7usize
+                }))
+            })
+            .await
+            .unwrap();
+        assert_eq!(index, 0);
+        assert_eq!(write.await, 7);
+        assert!(chain.status()[0].last_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_skips_failing_provider() {
+        let (primary, primary_calls) = mock("remote", Behavior::Fail);
+        let (secondary, secondary_calls) = mock("local", Behavior::Ok(4));
+        let provider = FallbackEmbeddingProvider::new(
+            vec![
+                (primary, Duration::from_secs(5)),
+                (secondary, Duration::from_secs(5)),
+            ],
+            CircuitBreakerPolicy {
+                failure_threshold: 2,
+                cooldown: Duration::from_secs(60),
+            },
+        );
+
+        for _ in 0..5 {
+            provider.embed_texts(&texts()).await.unwrap();
+        }
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(secondary_calls.load(Ordering::SeqCst), 5);
+        assert!(provider.chain().status()[0].circuit_open);
+    }
+
+    #[tokio::test]
+    async fn test_all_open_still_tries_providers() {
+        let (primary, primary_calls) = mock("remote", Behavior::Fail);
+        let provider = FallbackEmbeddingProvider::new(
+            vec![(primary, Duration::from_secs(5))],
+            CircuitBreakerPolicy {
+                failure_threshold: 1,
+                cooldown: Duration::from_secs(60),
+            },
+        );
+
+        assert!(provider.embed_texts(&texts()).await.is_err());
+        let err = provider.embed_texts(&texts()).await.unwrap_err();
+        assert!(err.to_string().contains("connection refused"));
+        assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dimension_mismatch_is_reported() {
+        let (primary, _) = mock("remote", Behavior::Ok(8));
+        let (secondary, _) = mock("local", Behavior::Ok(4));
+        let provider = FallbackEmbeddingProvider::new(
+            vec![
+                (primary, Duration::from_secs(5)),
+                (secondary, Duration::from_secs(5)),
+            ],
+            CircuitBreakerPolicy::default(),
+        );
+
+        let err = provider.check_dimensions().await.unwrap_err();
+        assert!(err.downcast_ref::<EmbeddingDimensionMismatch>().is_some());
+        assert!(err.to_string().contains("local/mock produces 4-dim"));
+
+        // 主提供商成功后记录维度，降级到不同维度的提供商时报错而非混用向量
+        provider.embed_texts(&texts()).await.unwrap();
+        let guard = &provider.dimensions;
+        assert!(guard.check("local/mock", 4).is_err());
+        assert!(guard.check("remote/mock", 8).is_ok());
+    }
+}
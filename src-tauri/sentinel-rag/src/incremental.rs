@@ -0,0 +1,261 @@
+//! 增量摄取
+//!
+//! 对比目录中文件的内容哈希与已入库文档记录，只重新处理新增或变化的文件，
+//! 并清理已从磁盘消失的文件对应的文档。
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::config::SupportedFileType;
+use crate::models::DocumentSource;
+
+/// 增量摄取结果统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IncrementalIngestReport {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub errors: Vec<String>,
+}
+
+/// 扫描到的磁盘文件
+#[derive(Debug, Clone)]
+pub struct ScannedFile {
+    pub path: String,
+    pub hash: String,
+    pub mtime: Option<i64>,
+}
+
+/// 增量摄取计划
+#[derive(Debug, Default)]
+pub struct IncrementalPlan {
+    /// 新文件
+    pub to_add: Vec<ScannedFile>,
+    /// 内容已变化的文件及其旧文档 ID
+    pub to_update: Vec<(String, ScannedFile)>,
+    /// 磁盘上已不存在的文档 ID
+    pub to_remove: Vec<String>,
+    /// 哈希一致、无需处理的文件数
+    pub skipped: usize,
+}
+
+/// 计算文件内容哈希
+pub fn hash_file(path: &Path) -> Result<String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+    Ok(format!("{:x}", md5::compute(&bytes)))
+}
+
+/// 获取文件修改时间（Unix 秒）
+pub fn file_mtime(path: &Path) -> Option<i64> {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+}
+
+/// 递归扫描路径下所有支持的文件；`root` 为单个文件时只返回该文件
+pub fn scan_supported_files(root: &Path) -> Result<Vec<ScannedFile>> {
+    let mut paths = Vec::new();
+    if root.is_file() {
+        paths.push(root.to_path_buf());
+    } else if root.is_dir() {
+        collect_files(root, &mut paths)?;
+    } else {
+        return Err(anyhow!("Path not found: {}", root.display()));
+    }
+
+    let mut files = Vec::new();
+    for path in paths {
+        let supported = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(SupportedFileType::from_extension)
+            .is_some();
+        if !supported {
+            continue;
+        }
+        files.push(ScannedFile {
+            hash: hash_file(&path)?,
+            mtime: file_mtime(&path),
+            path: path.to_string_lossy().to_string(),
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, out)?;
+        } else {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 根据已入库文档与扫描结果生成增量计划
+///
+/// 只有 `file_path` 位于 `root` 下的已有文档参与删除判断，
+/// 避免同一集合中其他来源的文档被误删。
+pub fn plan_incremental_ingest(
+    root: &Path,
+    existing: &[DocumentSource],
+    scanned: Vec<ScannedFile>,
+) -> IncrementalPlan {
+    let in_scope: Vec<&DocumentSource> = existing
+        .iter()
+        .filter(|d| Path::new(&d.file_path).starts_with(root))
+        .collect();
+    let by_path: HashMap<&str, &DocumentSource> = in_scope
+        .iter()
+        .map(|d| (d.file_path.as_str(), *d))
+        .collect();
+
+    let mut plan = IncrementalPlan::default();
+    let mut seen: HashSet<String> = HashSet::new();
+
+    for file in scanned {
+        seen.insert(file.path.clone());
+        match by_path.get(file.path.as_str()) {
+            None => plan.to_add.push(file),
+            Some(doc) if doc.file_hash == file.hash => plan.skipped += 1,
+            Some(doc) => plan.to_update.push((doc.id.clone(), file)),
+        }
+    }
+
+    // 同一路径若有多条旧记录，除了参与比对的那条外也视为过期
+    let matched_ids: HashSet<&str> = by_path.values().map(|d| d.id.as_str()).collect();
+    for doc in in_scope {
+        if !seen.contains(&doc.file_path) || !matched_ids.contains(doc.id.as_str()) {
+            plan.to_remove.push(doc.id.clone());
+        }
+    }
+
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::IngestionStatusEnum;
+
+    fn doc_for(file: &ScannedFile, id: &str) -> DocumentSource {
+        let mut doc = DocumentSource::new(
+            file.path.clone(),
+            Path::new(&file.path)
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+            "md".to_string(),
+            0,
+            file.hash.clone(),
+        );
+        doc.id = id.to_string();
+        doc.file_mtime = file.mtime;
+        doc.ingestion_status = IngestionStatusEnum::Completed;
+        doc
+    }
+
+    #[tokio::test]
+    async fn freshly_ingested_files_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("guide.md");
+        std::fs::write(&path, "# Guide\n\nSome **bold** text.\n").unwrap();
+
+        let chunker = crate::chunker::DocumentChunker::new(crate::config::RagConfig::default());
+        let (mut source, _) = chunker
+            .process_document(path.to_str().unwrap())
+            .await
+            .unwrap();
+        source.ingestion_status = IngestionStatusEnum::Completed;
+
+        let scanned = scan_supported_files(dir.path()).unwrap();
+        let plan = plan_incremental_ingest(dir.path(), &[source], scanned);
+        assert_eq!(plan.skipped, 1);
+        assert!(plan.to_update.is_empty());
+        assert!(plan.to_add.is_empty());
+    }
+
+    #[test]
+    fn new_files_are_added() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "# A").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        std::fs::write(dir.path().join("ignored.bin"), [0u8, 1, 2]).unwrap();
+
+        let scanned = scan_supported_files(dir.path()).unwrap();
+        assert_eq!(scanned.len(), 2);
+
+        let plan = plan_incremental_ingest(dir.path(), &[], scanned);
+        assert_eq!(plan.to_add.len(), 2);
+        assert!(plan.to_update.is_empty());
+        assert!(plan.to_remove.is_empty());
+        assert_eq!(plan.skipped, 0);
+    }
+
+    #[test]
+    fn unchanged_files_are_skipped_and_modified_updated() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "# A").unwrap();
+        std::fs::write(dir.path().join("b.md"), "# B").unwrap();
+
+        let first = scan_supported_files(dir.path()).unwrap();
+        let existing: Vec<DocumentSource> = first
+            .iter()
+            .enumerate()
+            .map(|(i, f)| doc_for(f, &format!("doc-{}", i)))
+            .collect();
+
+        std::fs::write(dir.path().join("b.md"), "# B, revised").unwrap();
+        let second = scan_supported_files(dir.path()).unwrap();
+
+        let plan = plan_incremental_ingest(dir.path(), &existing, second);
+        assert!(plan.to_add.is_empty());
+        assert_eq!(plan.skipped, 1);
+        assert_eq!(plan.to_update.len(), 1);
+        assert_eq!(plan.to_update[0].0, "doc-1");
+        assert!(plan.to_update[0].1.path.ends_with("b.md"));
+        assert!(plan.to_remove.is_empty());
+    }
+
+    #[test]
+    fn deleted_files_are_removed_but_out_of_scope_docs_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.md"), "# A").unwrap();
+        std::fs::write(dir.path().join("b.md"), "# B").unwrap();
+
+        let first = scan_supported_files(dir.path()).unwrap();
+        let mut existing: Vec<DocumentSource> = first
+            .iter()
+            .enumerate()
+            .map(|(i, f)| doc_for(f, &format!("doc-{}", i)))
+            .collect();
+        existing.push(doc_for(
+            &ScannedFile {
+                path: "/elsewhere/notes.md".to_string(),
+                hash: "x".to_string(),
+                mtime: None,
+            },
+            "doc-other",
+        ));
+
+        std::fs::remove_file(dir.path().join("a.md")).unwrap();
+        let second = scan_supported_files(dir.path()).unwrap();
+
+        let plan = plan_incremental_ingest(dir.path(), &existing, second);
+        assert_eq!(plan.to_remove, vec!["doc-0".to_string()]);
+        assert_eq!(plan.skipped, 1);
+        assert!(plan.to_add.is_empty());
+        assert!(plan.to_update.is_empty());
+    }
+}
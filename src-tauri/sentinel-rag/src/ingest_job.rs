@@ -0,0 +1,173 @@
+//! 可取消的导入任务
+//!
+//! 导入按批写入：每批 chunk 先写向量库再写 SQL 记录，取消只在两批之间生效，
+//! 因此已写入的 chunk 保持完整可检索，不会出现只写了一半的 chunk。
+
+use anyhow::Result;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::models::DocumentChunk;
+
+/// 导入任务取消令牌（克隆后共享同一状态）
+#[derive(Debug, Clone, Default)]
+pub struct IngestCancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl IngestCancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// 分批写入的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchedIngestOutcome {
+    /// 已完整写入的 chunk 数
+    pub written: usize,
+    pub cancelled: bool,
+}
+
+/// 按 `batch_size` 分批调用 `write_batch`，每批前检查取消令牌
+///
+/// `on_batch` 在每批写入后收到累计写入数。某批写入失败时直接返回错误，
+/// 之前的批次保持已写入状态。
+pub async fn write_chunks_in_batches<W, Fut, P>(
+    chunks: &[DocumentChunk],
+    batch_size: usize,
+    cancel: &IngestCancelToken,
+    mut write_batch: W,
+    mut on_batch: P,
+) -> Result<BatchedIngestOutcome>
+where
+    W: FnMut(usize, Vec<DocumentChunk>) -> Fut,
+    Fut: Future<Output = Result<()>>,
+    P: FnMut(usize),
+{
+    let mut written = 0;
+    for batch in chunks.chunks(batch_size.max(1)) {
+        if cancel.is_cancelled() {
+            return Ok(BatchedIngestOutcome {
+                written,
+                cancelled: true,
+            });
+        }
+        write_batch(written, batch.to_vec()).await?;
+        written += batch.len();
+        on_batch(written);
+    }
+    Ok(BatchedIngestOutcome {
+        written,
+        cancelled: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ChunkMetadata;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    fn chunk(index: usize) -> DocumentChunk {
+        let content = format!("chunk {}", index);
+        DocumentChunk {
+            id: format!("c{}", index),
+            source_id: "doc".to_string(),
+            content_hash: format!("{:x}", md5::compute(content.as_bytes())),
+            content,
+            chunk_index: index,
+            metadata: ChunkMetadata {
+                file_path: "/kb/doc.md".to_string(),
+                file_name: "doc.md".to_string(),
+                file_type: "md".to_string(),
+                file_size: 0,
+                chunk_start_char: 0,
+                chunk_end_char: 0,
+                page_number: None,
+                section_title: None,
+                custom_fields: HashMap::new(),
+            },
+            embedding: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_partway_leaves_consistent_partial_collection() {
+        let chunks: Vec<DocumentChunk> = (0..10).map(chunk).collect();
+        let cancel = IngestCancelToken::new();
+        // 模拟向量库和 SQL 两处存储
+        let vectors = Mutex::new(Vec::new());
+        let rows = Mutex::new(Vec::new());
+
+        let outcome = write_chunks_in_batches(
+            &chunks,
+            3,
+            &cancel,
+            |offset, batch| {
+                let (vectors, rows, cancel) = (&vectors, &rows, &cancel);
+                async move {
+                    vectors
+                        .lock()
+                        .unwrap()
+                        .extend(batch.iter().map(|c| c.id.clone()));
+                    // 写入过程中收到取消请求，本批仍应完整写完
+                    if offset == 3 {
+                        cancel.cancel();
+                    }
+                    tokio::task::yield_now().await;
+                    rows.lock()
+                        .unwrap()
+                        .extend(batch.iter().map(|c| c.id.clone()));
+                    Ok(())
+                }
+            },
+            |_| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            outcome,
+            BatchedIngestOutcome {
+                written: 6,
+                cancelled: true
+            }
+        );
+        let vectors = vectors.into_inner().unwrap();
+        let rows = rows.into_inner().unwrap();
+        assert_eq!(vectors, rows);
+        let expected: Vec<String> = (0..6).map(|i| format!("c{}", i)).collect();
+        assert_eq!(vectors, expected);
+    }
+
+    #[tokio::test]
+    async fn test_uncancelled_writes_everything() {
+        let chunks: Vec<DocumentChunk> = (0..5).map(chunk).collect();
+        let mut progress = Vec::new();
+        let outcome = write_chunks_in_batches(
+            &chunks,
+            2,
+            &IngestCancelToken::new(),
+            |_, _| async { Ok(()) },
+            |written| progress.push(written),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(outcome.written, 5);
+        assert!(!outcome.cancelled);
+        assert_eq!(progress, vec![2, 4, 5]);
+    }
+}
@@ -1,17 +1,25 @@
+pub mod answer;
 pub mod chunker;
 pub mod config;
 pub mod database;
 pub mod db;
 pub mod embeddings;
+pub mod incremental;
+pub mod ingest_job;
 pub mod models;
 pub mod query_utils;
+pub mod retrieval;
 pub mod service;
 
+pub use answer::*;
 pub use chunker::*;
 pub use config::*;
 pub use database::*;
 pub use db::*;
 pub use embeddings::*;
+pub use incremental::*;
+pub use ingest_job::*;
 pub use models::*;
 pub use query_utils::*;
+pub use retrieval::*;
 pub use service::*;
@@ -0,0 +1,298 @@
+//! 关键词检索与混合检索
+//!
+//! 纯向量检索容易漏掉错误码、CVE 编号这类需要精确匹配的词，
+//! 这里提供基于 BM25 的关键词打分，以及与向量结果的加权 RRF 融合。
+
+use std::collections::{HashMap, HashSet};
+
+use crate::models::QueryResult;
+
+/// BM25 词频饱和参数
+const BM25_K1: f64 = 1.2;
+/// BM25 文档长度归一化参数
+const BM25_B: f64 = 0.75;
+/// RRF 平滑常数
+pub const RRF_K: f64 = 60.0;
+
+/// 将文本切分为检索词
+///
+/// ASCII 词保留 `-`、`_`、`.` 以便 `CVE-2021-44228`、`ERR_SSL` 等整体匹配；
+/// CJK 字符按单字切分。
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+
+    let flush = |current: &mut String, tokens: &mut Vec<String>| {
+        let word = current.trim_matches(|c| c == '-' || c == '_' || c == '.');
+        if !word.is_empty() {
+            tokens.push(word.to_lowercase());
+        }
+        current.clear();
+    };
+
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+            current.push(c);
+        } else {
+            flush(&mut current, &mut tokens);
+            if is_cjk(c) {
+                tokens.push(c.to_string());
+            }
+        }
+    }
+    flush(&mut current, &mut tokens);
+
+    tokens
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x4E00..=0x9FFF | 0x3400..=0x4DBF | 0x3040..=0x30FF | 0xAC00..=0xD7AF)
+}
+
+/// 对候选结果按 BM25 打分，返回得分大于 0 的结果（按得分降序，分数归一化到 0~1）
+pub fn bm25_rank(query: &str, candidates: Vec<QueryResult>, top_k: usize) -> Vec<QueryResult> {
+    let query_terms: HashSet<String> = tokenize(query).into_iter().collect();
+    if query_terms.is_empty() || candidates.is_empty() || top_k == 0 {
+        return Vec::new();
+    }
+
+    let docs: Vec<Vec<String>> = candidates
+        .iter()
+        .map(|c| tokenize(&c.chunk.content))
+        .collect();
+    let doc_count = docs.len() as f64;
+    let avg_len = docs.iter().map(|d| d.len()).sum::<usize>() as f64 / doc_count;
+
+    let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+    for doc in &docs {
+        let unique: HashSet<&str> = doc.iter().map(|t| t.as_str()).collect();
+        for term in unique {
+            if query_terms.contains(term) {
+                *doc_freq.entry(term).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut scored: Vec<(f64, QueryResult)> = candidates
+        .into_iter()
+        .zip(docs.iter())
+        .filter_map(|(candidate, doc)| {
+            let mut tf: HashMap<&str, usize> = HashMap::new();
+            for t in doc {
+                if query_terms.contains(t) {
+                    *tf.entry(t.as_str()).or_insert(0) += 1;
+                }
+            }
+            if tf.is_empty() {
+                return None;
+            }
+
+            let len_norm = 1.0 - BM25_B + BM25_B * doc.len() as f64 / avg_len.max(1.0);
+            let score: f64 = tf
+                .iter()
+                .map(|(term, &freq)| {
+                    let df = *doc_freq.get(term).unwrap_or(&0) as f64;
+                    let idf = ((doc_count - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    let freq = freq as f64;
+                    idf * freq * (BM25_K1 + 1.0) / (freq + BM25_K1 * len_norm)
+                })
+                .sum();
+            Some((score, candidate))
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(top_k);
+
+    let max_score = scored
+        .first()
+        .map(|(s, _)| *s)
+        .unwrap_or(1.0)
+        .max(f64::EPSILON);
+    scored
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (score, mut result))| {
+            result.score = score / max_score;
+            result.rank = rank;
+            result
+        })
+        .collect()
+}
+
+/// 加权倒数排名融合（RRF）
+///
+/// `alpha` 为向量结果权重，`1 - alpha` 为关键词结果权重。融合得分按
+/// `1 / (RRF_K + 1)` 归一化，两路都排第一时为 1.0；它只反映排名，
+/// 与余弦相似度不在同一尺度，不能再用相似度阈值过滤。
+pub fn fuse_hybrid(
+    vector_results: Vec<QueryResult>,
+    keyword_results: Vec<QueryResult>,
+    alpha: f64,
+    top_k: usize,
+) -> Vec<QueryResult> {
+    let alpha = alpha.clamp(0.0, 1.0);
+    let norm = RRF_K + 1.0;
+
+    let mut fused: HashMap<String, (f64, QueryResult)> = HashMap::new();
+    for (weight, results) in [(alpha, vector_results), (1.0 - alpha, keyword_results)] {
+        for (rank, result) in results.into_iter().enumerate() {
+            let contribution = weight * norm / (RRF_K + rank as f64 + 1.0);
+            fused
+                .entry(result.chunk.id.clone())
+                .and_modify(|(score, _)| *score += contribution)
+                .or_insert((contribution, result));
+        }
+    }
+
+    let mut merged: Vec<(f64, QueryResult)> = fused.into_values().collect();
+    merged.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    merged.truncate(top_k);
+
+    merged
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (score, mut result))| {
+            result.score = score;
+            result.rank = rank;
+            result
+        })
+        .collect()
+}
+
+/// 混合检索：相似度阈值（余弦下限）只过滤向量结果，再与关键词结果融合
+pub fn hybrid_rank(
+    vector_results: Vec<QueryResult>,
+    keyword_results: Vec<QueryResult>,
+    similarity_threshold: f64,
+    alpha: f64,
+    top_k: usize,
+) -> Vec<QueryResult> {
+    let vector_results = vector_results
+        .into_iter()
+        .filter(|r| r.score >= similarity_threshold)
+        .collect();
+    fuse_hybrid(vector_results, keyword_results, alpha, top_k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ChunkMetadata, DocumentChunk};
+
+    fn result(id: &str, content: &str, score: f64, rank: usize) -> QueryResult {
+        QueryResult {
+            chunk: DocumentChunk {
+                id: id.to_string(),
+                source_id: "src".to_string(),
+                content: content.to_string(),
+                content_hash: format!("{:x}", md5::compute(content.as_bytes())),
+                chunk_index: rank,
+                metadata: ChunkMetadata {
+                    file_path: String::new(),
+                    file_name: String::new(),
+                    file_type: "txt".to_string(),
+                    file_size: 0,
+                    chunk_start_char: 0,
+                    chunk_end_char: 0,
+                    page_number: None,
+                    section_title: None,
+                    custom_fields: HashMap::new(),
+                },
+                embedding: None,
+                created_at: chrono::Utc::now(),
+            },
+            score,
+            rank,
+        }
+    }
+
+    fn corpus() -> Vec<QueryResult> {
+        vec![
+            result(
+                "a",
+                "Log4j remote code execution overview and JNDI lookup mitigation",
+                0.0,
+                0,
+            ),
+            result(
+                "b",
+                "Apache vulnerability advisories for recent remote code execution bugs",
+                0.0,
+                1,
+            ),
+            result(
+                "c",
+                "Patch notes: CVE-2021-44228 fixed in 2.17.1, upgrade required",
+                0.0,
+                2,
+            ),
+        ]
+    }
+
+    #[test]
+    fn tokenize_keeps_identifiers_whole() {
+        let tokens = tokenize("Fix for CVE-2021-44228 (ERR_SSL_PROTOCOL).");
+        assert!(tokens.contains(&"cve-2021-44228".to_string()));
+        assert!(tokens.contains(&"err_ssl_protocol".to_string()));
+        assert_eq!(tokenize("命令注入"), vec!["命", "令", "注", "入"]);
+    }
+
+    #[test]
+    fn keyword_rank_only_returns_matches() {
+        let ranked = bm25_rank("CVE-2021-44228", corpus(), 5);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].chunk.id, "c");
+        assert!((ranked[0].score - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hybrid_ranks_exact_cve_match_first() {
+        // 向量检索认为 c 只是中等相关
+        let vector = vec![
+            result("a", &corpus()[0].chunk.content, 0.82, 0),
+            result("b", &corpus()[1].chunk.content, 0.78, 1),
+            result("c", &corpus()[2].chunk.content, 0.41, 2),
+        ];
+        let keyword = bm25_rank("CVE-2021-44228", corpus(), 5);
+
+        let fused = fuse_hybrid(vector, keyword, 0.5, 3);
+        assert_eq!(fused[0].chunk.id, "c");
+        assert_eq!(fused[0].rank, 0);
+        assert!(fused[0].score > fused[1].score);
+    }
+
+    #[test]
+    fn alpha_one_preserves_vector_order() {
+        let vector = vec![
+            result("a", &corpus()[0].chunk.content, 0.82, 0),
+            result("c", &corpus()[2].chunk.content, 0.41, 1),
+        ];
+        let keyword = bm25_rank("CVE-2021-44228", corpus(), 5);
+
+        let fused = fuse_hybrid(vector, keyword, 1.0, 2);
+        let ids: Vec<_> = fused.iter().map(|r| r.chunk.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c"]);
+    }
+
+    #[test]
+    fn keyword_only_hit_survives_default_threshold() {
+        let config = sentinel_core::models::rag_config::RagConfig::default();
+
+        // 向量检索只召回了低于阈值的 a，c 只被关键词检索命中
+        let vector = vec![result("a", &corpus()[0].chunk.content, 0.41, 0)];
+        let keyword = bm25_rank("CVE-2021-44228", corpus(), 5);
+
+        let fused = hybrid_rank(
+            vector,
+            keyword,
+            config.similarity_threshold,
+            config.hybrid_alpha,
+            config.top_k,
+        );
+        let ids: Vec<_> = fused.iter().map(|r| r.chunk.id.as_str()).collect();
+        assert_eq!(ids, vec!["c"]);
+        assert!(fused[0].score < config.similarity_threshold);
+    }
+}
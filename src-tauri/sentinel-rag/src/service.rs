@@ -8,10 +8,14 @@ use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::chunker::DocumentChunker;
-use crate::config::{EmbeddingConfig, RagConfig};
+use crate::config::{EmbeddingConfig, RagConfig, RetrievalMode};
 use crate::database::SqliteVectorManager;
 use crate::db::RagDatabase;
-use crate::embeddings::{create_reranking_provider, RerankingManager};
+use crate::embeddings::{
+    create_reranking_provider, CircuitBreakerPolicy, EmbeddingProviderStatus, RerankingManager,
+};
+use crate::incremental::{plan_incremental_ingest, scan_supported_files, IncrementalIngestReport};
+use crate::ingest_job::{write_chunks_in_batches, IngestCancelToken};
 use crate::models::{
     CollectionInfo, DocumentChunk, DocumentSource, IngestRequest, IngestResponse, IngestionStatus,
     QueryResult, RagQueryRequest, RagQueryResponse, RagStatus,
@@ -98,14 +102,38 @@ impl<D: RagDatabase> RagService<D> {
             api_key: config.embedding_api_key.clone(),
             base_url: config.embedding_base_url.clone(),
             dimensions: config.embedding_dimensions,
+            timeout_secs: None,
         };
 
         info!("RAG服务使用SQLite路径: {}", normalized_db_path.display());
 
-        let vector_store = Arc::new(SqliteVectorManager::new(
-            normalized_db_path.to_string_lossy().to_string(),
-            embedding_config,
-        ));
+        if !config.embedding_fallbacks.is_empty() {
+            info!(
+                "嵌入降级链: {}",
+                config
+                    .embedding_fallbacks
+                    .iter()
+                    .map(|c| format!("{}/{}", c.provider, c.model))
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            );
+        }
+        let vector_store = Arc::new(
+            SqliteVectorManager::new(
+                normalized_db_path.to_string_lossy().to_string(),
+                embedding_config,
+            )
+            .with_fallbacks(
+                config.embedding_fallbacks.clone(),
+                std::time::Duration::from_secs(config.embedding_timeout_secs),
+                CircuitBreakerPolicy {
+                    failure_threshold: config.embedding_failure_threshold,
+                    cooldown: std::time::Duration::from_secs(
+                        config.embedding_circuit_cooldown_secs,
+                    ),
+                },
+            ),
+        );
         vector_store.initialize().await?;
 
         let mut reranker: Option<RerankingManager> = None;
@@ -273,6 +301,24 @@ impl<D: RagDatabase> RagService<D> {
 
     /// 摄取文档源
     pub async fn ingest_source(&self, request: IngestRequest) -> Result<IngestResponse> {
+        self.ingest_source_with_cancel(request, &IngestCancelToken::new(), |_| {})
+            .await
+    }
+
+    /// 摄取文档源，可通过 `cancel` 中途停止
+    ///
+    /// chunk 分批写入，取消在两批之间生效：已写入的 chunk 保留且可检索，
+    /// 状态标记为 `cancelled`；尚未写入任何 chunk 时删除文档记录。
+    /// 每写完一批通过 `on_progress` 回调当前状态。
+    pub async fn ingest_source_with_cancel<P>(
+        &self,
+        request: IngestRequest,
+        cancel: &IngestCancelToken,
+        mut on_progress: P,
+    ) -> Result<IngestResponse>
+    where
+        P: FnMut(&IngestionStatus),
+    {
         let task_id = Uuid::new_v4().to_string();
         info!("开始摄取任务: {} - {}", task_id, request.file_path);
 
@@ -401,6 +447,19 @@ impl<D: RagDatabase> RagService<D> {
             }
         };
 
+        // 记录文件指纹，后续增量摄取据此跳过未变化的文件
+        if let Err(e) = self
+            .database
+            .update_rag_document_fingerprint(
+                &document_id,
+                &document_source.file_hash,
+                document_source.file_mtime,
+            )
+            .await
+        {
+            warn!("记录文档指纹失败 {}: {}", document_source.file_path, e);
+        }
+
         // 关键修复：确保所有chunks使用正确的document_id (source_id)
         // 这样在检索时才能正确关联到SQL数据库中的记录
         for chunk in &mut chunks {
@@ -418,19 +477,64 @@ impl<D: RagDatabase> RagService<D> {
             "default".to_string()
         };
 
-        // Insert into vector store using Rig + SQLite
-        let chunks_created = match self
-            .vector_store
-            .insert_chunks(&collection_name, chunks.clone())
-            .await
-        {
-            Ok(count) => count,
+        // 分批写入向量库和 SQL，每批完整写完后才检查取消
+        on_progress(&ingestion_status);
+        let total_chunks = chunks.len();
+        let outcome = write_chunks_in_batches(
+            &chunks,
+            self._config.batch_size,
+            cancel,
+            |offset, batch| {
+                let (collection_name, collection_id, document_id) =
+                    (&collection_name, &collection_id, &document_id);
+                async move {
+                    // Insert into vector store using Rig + SQLite
+                    self.vector_store
+                        .insert_chunks(collection_name, batch.clone())
+                        .await
+                        .map_err(|e| anyhow!("向量存储插入失败: {}", e))?;
+
+                    // Also save chunks to SQL database for metadata
+                    for (i, chunk) in batch.iter().enumerate() {
+                        let metadata_json =
+                            serde_json::to_string(&chunk.metadata).unwrap_or("{}".to_string());
+                        if let Err(e) = self
+                            .database
+                            .create_rag_chunk(
+                                document_id,
+                                collection_id,
+                                &chunk.content,
+                                (offset + i) as i32,
+                                None, // No embedding in SQL - stored in SQLite vector table
+                                &metadata_json,
+                            )
+                            .await
+                        {
+                            warn!("创建chunk记录失败: {}", e);
+                        }
+                    }
+                    Ok(())
+                }
+            },
+            |written| {
+                ingestion_status.processed_chunks = written;
+                ingestion_status.progress = written as f64 / total_chunks.max(1) as f64 * 100.0;
+                on_progress(&ingestion_status);
+            },
+        )
+        .await;
+
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
             Err(e) => {
-                error!("向量存储插入失败: {}", e);
+                error!("{}", e);
 
-                // Rollback: delete the document from SQL database
-                if let Err(del_err) = self.database.delete_rag_document(&document_id).await {
-                    error!("Failed to rollback (delete) document {} after vector insertion failure: {}", document_id, del_err);
+                // Rollback: delete the document and any vectors already written
+                if let Err(del_err) = self.delete_document(&document_id).await {
+                    error!(
+                        "Failed to rollback (delete) document {} after vector insertion failure: {}",
+                        document_id, del_err
+                    );
                 } else {
                     info!(
                         "Rolled back document {} due to vector insertion failure",
@@ -439,34 +543,50 @@ impl<D: RagDatabase> RagService<D> {
                 }
 
                 ingestion_status.status = "failed".to_string();
-                ingestion_status.error_message = Some(format!("向量存储插入失败: {}", e));
+                ingestion_status.error_message = Some(e.to_string());
                 ingestion_status.completed_at = Some(chrono::Utc::now());
                 self.ingestion_status
                     .write()
                     .await
-                    .insert(task_id.clone(), ingestion_status);
-                return Err(anyhow!("向量存储插入失败: {}", e));
+                    .insert(task_id.clone(), ingestion_status.clone());
+                on_progress(&ingestion_status);
+                return Err(e);
             }
         };
+        let chunks_created = outcome.written;
 
-        // Also save chunks to SQL database for metadata
-        for (index, chunk) in chunks.iter().enumerate() {
-            let metadata_json = serde_json::to_string(&chunk.metadata).unwrap_or("{}".to_string());
+        if outcome.cancelled {
+            info!(
+                "摄取任务 {} 已取消，保留已写入的 {}/{} 个chunk",
+                task_id, chunks_created, total_chunks
+            );
+            if chunks_created == 0 {
+                if let Err(e) = self.delete_document(&document_id).await {
+                    warn!("删除已取消的空文档失败: {}", e);
+                }
+            } else if let Err(e) = self.database.update_collection_stats(&collection_id).await {
+                warn!("更新集合统计失败: {}", e);
+            }
 
-            if let Err(e) = self
-                .database
-                .create_rag_chunk(
-                    &document_id,
-                    &collection_id,
-                    &chunk.content,
-                    index as i32,
-                    None, // No embedding in SQL - stored in SQLite vector table
-                    &metadata_json,
-                )
+            ingestion_status.status = "cancelled".to_string();
+            ingestion_status.completed_at = Some(chrono::Utc::now());
+            self.ingestion_status
+                .write()
                 .await
-            {
-                warn!("创建chunk记录失败: {}", e);
-            }
+                .insert(task_id.clone(), ingestion_status.clone());
+            on_progress(&ingestion_status);
+
+            let processing_time = ingestion_status
+                .completed_at
+                .unwrap()
+                .signed_duration_since(ingestion_status.started_at)
+                .num_milliseconds() as u64;
+            return Ok(IngestResponse {
+                source_id: document_id,
+                chunks_created,
+                processing_time_ms: processing_time,
+                status: ingestion_status,
+            });
         }
 
         // 完成摄取，更新集合统计
@@ -478,6 +598,7 @@ impl<D: RagDatabase> RagService<D> {
         ingestion_status.completed_at = Some(chrono::Utc::now());
         ingestion_status.processed_chunks = chunks.len();
         ingestion_status.progress = 100.0;
+        on_progress(&ingestion_status);
         self.ingestion_status
             .write()
             .await
@@ -607,6 +728,16 @@ impl<D: RagDatabase> RagService<D> {
             }
         };
 
+        // 手动文本没有对应文件，以内容哈希作为指纹
+        let content_hash = format!("{:x}", md5::compute(content.as_bytes()));
+        if let Err(e) = self
+            .database
+            .update_rag_document_fingerprint(&document_id, &content_hash, None)
+            .await
+        {
+            warn!("记录文档指纹失败 {}: {}", title, e);
+        }
+
         // 关键修复：确保所有chunks使用正确的document_id (source_id)
         // 这样在检索时才能正确关联到SQL数据库中的记录
         for chunk in &mut chunks {
@@ -928,6 +1059,63 @@ impl<D: RagDatabase> RagService<D> {
         Ok(expanded_results)
     }
 
+    /// 检索得分是否为余弦相似度，只有此时结果才按相似度阈值过滤
+    ///
+    /// 关键词检索得分为归一化 BM25，混合检索得分为 RRF 融合分，都与阈值不在同一尺度。
+    fn filters_by_similarity(&self) -> bool {
+        matches!(self._config.retrieval_mode, RetrievalMode::Vector)
+    }
+
+    /// 按配置的检索模式（向量 / 关键词 / 混合）检索集合
+    ///
+    /// `similarity_threshold` 是余弦相似度下限：混合检索在融合前用它过滤向量结果，
+    /// 纯向量检索由调用方在上下文扩展后过滤，关键词检索不使用。
+    async fn retrieve(
+        &self,
+        collection_name: &str,
+        query: &str,
+        top_k: usize,
+        similarity_threshold: f64,
+    ) -> Result<Vec<QueryResult>> {
+        match self._config.retrieval_mode {
+            RetrievalMode::Vector => {
+                self.vector_store
+                    .search_similar(collection_name, query, top_k)
+                    .await
+            }
+            RetrievalMode::Keyword => {
+                self.vector_store
+                    .search_keyword(collection_name, query, top_k)
+                    .await
+            }
+            RetrievalMode::Hybrid => {
+                // 两路各多取一些候选，融合后再截断
+                let candidates = top_k.saturating_mul(2).max(top_k);
+                let vector_results = self
+                    .vector_store
+                    .search_similar(collection_name, query, candidates)
+                    .await?;
+                let keyword_results = self
+                    .vector_store
+                    .search_keyword(collection_name, query, candidates)
+                    .await?;
+                info!(
+                    "Hybrid retrieval: {} vector + {} keyword candidates (alpha={:.2})",
+                    vector_results.len(),
+                    keyword_results.len(),
+                    self._config.hybrid_alpha
+                );
+                Ok(crate::retrieval::hybrid_rank(
+                    vector_results,
+                    keyword_results,
+                    similarity_threshold,
+                    self._config.hybrid_alpha as f64,
+                    top_k,
+                ))
+            }
+        }
+    }
+
     /// 查询相似文档 - 使用 Rig + SQLite
     pub async fn query(&self, request: RagQueryRequest) -> Result<RagQueryResponse> {
         info!("执行RAG查询 (使用 Rig + SQLite): {}", request.query);
@@ -951,14 +1139,25 @@ impl<D: RagDatabase> RagService<D> {
         };
 
         let top_k = request.top_k.unwrap_or(5);
+        let similarity_threshold = request
+            .similarity_threshold
+            .unwrap_or(self._config.similarity_threshold as f64);
 
-        // 使用 Rig + SQLite 进行语义搜索
+        // 使用 Rig + SQLite 按检索模式搜索
         let mut query_results = self
-            .vector_store
-            .search_similar(&collection_name, &request.query, top_k)
+            .retrieve(
+                &collection_name,
+                &request.query,
+                top_k,
+                similarity_threshold,
+            )
             .await?;
 
-        info!("Vector search returned {} results", query_results.len());
+        info!(
+            "{:?} retrieval returned {} results",
+            self._config.retrieval_mode,
+            query_results.len()
+        );
         for (i, r) in query_results.iter().enumerate() {
             info!(
                 "  Result {}: score={:.4}, content_preview={}",
@@ -1023,12 +1222,15 @@ impl<D: RagDatabase> RagService<D> {
         query_results = unique_results;
 
         // Apply similarity threshold filter (but skip expanded chunks)
-        let similarity_threshold = request
-            .similarity_threshold
-            .unwrap_or(self._config.similarity_threshold as f64);
-        info!("Applying similarity threshold: {:.2}", similarity_threshold);
+        let applies_threshold = self.filters_by_similarity();
+        if applies_threshold {
+            info!("Applying similarity threshold: {:.2}", similarity_threshold);
+        }
         let before_filter = query_results.len();
         query_results.retain(|r| {
+            if !applies_threshold {
+                return true;
+            }
             // 扩展后的chunk跳过相似度过滤
             let skip_filter = r
                 .chunk
@@ -1113,8 +1315,6 @@ impl<D: RagDatabase> RagService<D> {
         &self,
         request: &crate::models::AssistantRagRequest,
     ) -> Result<(String, Vec<crate::models::Citation>)> {
-        use crate::models::Citation;
-
         let start_time = std::time::Instant::now();
 
         // 获取所有要查询的集合名称
@@ -1139,13 +1339,20 @@ impl<D: RagDatabase> RagService<D> {
         }
 
         let top_k = request.top_k.unwrap_or(5);
+        let similarity_threshold = request
+            .similarity_threshold
+            .unwrap_or(self._config.similarity_threshold as f64);
         let mut all_results: Vec<crate::models::QueryResult> = Vec::new();
 
         // 从各个集合中搜索并汇总
         for collection_name in collection_names {
             match self
-                .vector_store
-                .search_similar(&collection_name, &request.query, top_k)
+                .retrieve(
+                    &collection_name,
+                    &request.query,
+                    top_k,
+                    similarity_threshold,
+                )
                 .await
             {
                 Ok(results) => {
@@ -1191,11 +1398,12 @@ impl<D: RagDatabase> RagService<D> {
         info!("After chunk expansion: {} results", results.len());
 
         // Apply similarity threshold filter (but skip expanded chunks)
-        let similarity_threshold = request
-            .similarity_threshold
-            .unwrap_or(self._config.similarity_threshold as f64);
+        let applies_threshold = self.filters_by_similarity();
         let before_filter = results.len();
         results.retain(|r| {
+            if !applies_threshold {
+                return true;
+            }
             // 扩展后的chunk跳过相似度过滤
             let skip_filter = r
                 .chunk
@@ -1303,43 +1511,7 @@ impl<D: RagDatabase> RagService<D> {
         }
 
         // 构建Evidence Blocks格式的上下文
-        let mut evidence_blocks = Vec::new();
-        let mut citations = Vec::new();
-
-        for (idx, item) in results.iter().enumerate() {
-            let chunk = &item.chunk;
-            let source_num = idx + 1;
-            let file_name = chunk.metadata.file_name.clone();
-            let content_preview = chunk.content.clone();
-
-            // 格式化Evidence Block
-            let evidence_block = format!(
-                "=== SOURCE {} | {} | page: {} | score: {:.2} ===\n{}",
-                source_num,
-                file_name,
-                chunk.metadata.page_number.unwrap_or(1),
-                item.score,
-                chunk.content
-            );
-            evidence_blocks.push(evidence_block);
-
-            // 构建Citation
-            let citation = Citation {
-                id: chunk.id.clone(),
-                source_id: chunk.source_id.clone(),
-                file_name: file_name.clone(),
-                file_path: Some(chunk.metadata.file_path.clone()),
-                page_number: chunk.metadata.page_number.map(|p| p as i32),
-                section_title: chunk.metadata.section_title.clone(),
-                start_char: chunk.metadata.chunk_start_char,
-                end_char: chunk.metadata.chunk_end_char,
-                score: item.score,
-                content_preview,
-            };
-            citations.push(citation);
-        }
-
-        let context = evidence_blocks.join("\n\n");
+        let (context, citations) = crate::answer::build_evidence_context(&results);
         let processing_time = start_time.elapsed().as_millis() as u64;
 
         // 保存查询历史
@@ -1366,18 +1538,129 @@ impl<D: RagDatabase> RagService<D> {
         Ok((context, citations))
     }
 
+    /// 检索后基于证据流式生成回答，回答中引用的 chunk 通过 `on_event` 推送
+    pub async fn stream_assistant_answer<S, F>(
+        &self,
+        request: &crate::models::AssistantRagRequest,
+        streamer: &S,
+        on_event: F,
+    ) -> Result<crate::models::AssistantRagResponse>
+    where
+        S: crate::answer::AnswerStreamer + ?Sized,
+        F: FnMut(crate::answer::RagAnswerEvent) -> bool + Send,
+    {
+        let (context, citations) = self.query_for_assistant(request).await?;
+        crate::answer::stream_answer(
+            streamer,
+            &request.query,
+            &context,
+            citations,
+            request.system_prompt.as_deref(),
+            on_event,
+        )
+        .await
+    }
+
     /// 获取文档列表
     pub async fn get_documents(&self, collection_id: &str) -> Result<Vec<DocumentSource>> {
         info!("获取文档列表: {}", collection_id);
         self.database.get_rag_documents(collection_id).await
     }
 
-    /// 删除文档
+    /// 删除文档（同时清理向量库中的 chunk）
     pub async fn delete_document(&self, document_id: &str) -> Result<()> {
         info!("删除文档: {}", document_id);
+        if let Err(e) = self.vector_store.delete_source(document_id).await {
+            warn!("删除文档向量失败: {}", e);
+        }
         self.database.delete_rag_document(document_id).await
     }
 
+    /// 增量重新摄取目录：跳过哈希未变化的文件，重新处理变化的文件，删除已消失文件的文档
+    pub async fn reingest_incremental(
+        &self,
+        collection_id: &str,
+        path: &str,
+    ) -> Result<IncrementalIngestReport> {
+        info!("增量摄取: collection={}, path={}", collection_id, path);
+
+        if self
+            .database
+            .get_rag_collection_by_id(collection_id)
+            .await?
+            .is_none()
+        {
+            return Err(anyhow!("Collection with id {} not found", collection_id));
+        }
+
+        let root = PathBuf::from(path);
+        let scan_root = root.clone();
+        let scanned =
+            tokio::task::spawn_blocking(move || scan_supported_files(&scan_root)).await??;
+        let existing = self.database.get_rag_documents(collection_id).await?;
+        let plan = plan_incremental_ingest(&root, &existing, scanned);
+
+        let mut report = IncrementalIngestReport {
+            skipped: plan.skipped,
+            ..Default::default()
+        };
+
+        for document_id in &plan.to_remove {
+            match self.delete_document(document_id).await {
+                Ok(()) => report.removed += 1,
+                Err(e) => {
+                    report.failed += 1;
+                    report.errors.push(format!("remove {}: {}", document_id, e));
+                }
+            }
+        }
+
+        let updates = plan
+            .to_update
+            .into_iter()
+            .map(|(old_id, file)| (Some(old_id), file));
+        let additions = plan.to_add.into_iter().map(|file| (None, file));
+
+        // 先摄取新版本，成功后再删除旧文档；摄取失败时旧文档保持可检索
+        for (old_id, file) in updates.chain(additions) {
+            let request = IngestRequest {
+                file_path: file.path.clone(),
+                collection_id: Some(collection_id.to_string()),
+                metadata: None,
+            };
+            // 指纹由 ingest_source 在创建文档记录时写入
+            match self.ingest_source(request).await {
+                Ok(_) => match &old_id {
+                    Some(old_id) => match self.delete_document(old_id).await {
+                        Ok(()) => report.updated += 1,
+                        Err(e) => {
+                            report.failed += 1;
+                            report.errors.push(format!(
+                                "{}: remove previous version {}: {}",
+                                file.path, old_id, e
+                            ));
+                        }
+                    },
+                    None => report.added += 1,
+                },
+                Err(e) => {
+                    report.failed += 1;
+                    report.errors.push(format!("{}: {}", file.path, e));
+                }
+            }
+        }
+
+        if let Err(e) = self.database.update_collection_stats(collection_id).await {
+            warn!("更新集合统计失败: {}", e);
+        }
+
+        info!(
+            "增量摄取完成: added={}, updated={}, removed={}, skipped={}, failed={}",
+            report.added, report.updated, report.removed, report.skipped, report.failed
+        );
+        Ok(report)
+    }
+
     /// 获取文档chunks
     pub async fn get_document_chunks(&self, document_id: &str) -> Result<Vec<DocumentChunk>> {
         info!("获取文档chunks: {}", document_id);
@@ -1431,6 +1714,16 @@ impl<D: RagDatabase> RagService<D> {
         &self._config
     }
 
+    /// 最近一次成功生成嵌入的提供商（provider/model）
+    pub fn active_embedding_provider(&self) -> Option<String> {
+        self.vector_store.active_embedding_provider()
+    }
+
+    /// 嵌入降级链中各提供商的状态
+    pub fn embedding_provider_status(&self) -> Vec<EmbeddingProviderStatus> {
+        self.vector_store.embedding_provider_status()
+    }
+
     /// 确保默认集合存在（公开方法供命令调用）
     pub async fn ensure_default_collection_public(&self) -> Result<String> {
         const DEFAULT_COLLECTION_NAME: &str = "default";
@@ -1485,4 +1778,344 @@ impl<D: RagDatabase> RagService<D> {
     }
 }
 
-// Tests omitted in crate to avoid cross-crate dependencies
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// 内存中的 RAG 元数据库，只实现摄取与增量摄取用到的部分
+    #[derive(Default)]
+    struct MemoryRagDatabase {
+        collections: Mutex<Vec<CollectionInfo>>,
+        documents: Mutex<Vec<(String, DocumentSource)>>,
+    }
+
+    impl MemoryRagDatabase {
+        fn add_document(&self, collection_id: &str, document: DocumentSource) {
+            self.documents
+                .lock()
+                .unwrap()
+                .push((collection_id.to_string(), document));
+        }
+
+        fn document_ids(&self) -> Vec<String> {
+            self.documents
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(_, d)| d.id.clone())
+                .collect()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl RagDatabase for MemoryRagDatabase {
+        async fn create_rag_collection(
+            &self,
+            name: &str,
+            description: Option<&str>,
+        ) -> Result<String> {
+            let id = Uuid::new_v4().to_string();
+            self.collections.lock().unwrap().push(CollectionInfo {
+                id: id.clone(),
+                name: name.to_string(),
+                description: description.map(str::to_string),
+                is_active: true,
+                document_count: 0,
+                chunk_count: 0,
+                created_at: chrono::Utc::now(),
+                updated_at: chrono::Utc::now(),
+            });
+            Ok(id)
+        }
+        async fn get_rag_collections(&self) -> Result<Vec<CollectionInfo>> {
+            Ok(self.collections.lock().unwrap().clone())
+        }
+        async fn get_rag_collection_by_id(&self, id: &str) -> Result<Option<CollectionInfo>> {
+            Ok(self
+                .collections
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|c| c.id == id)
+                .cloned())
+        }
+        async fn get_rag_collection_by_name(&self, name: &str) -> Result<Option<CollectionInfo>> {
+            Ok(self
+                .collections
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|c| c.name == name)
+                .cloned())
+        }
+        async fn delete_rag_collection(&self, id: &str) -> Result<()> {
+            self.collections.lock().unwrap().retain(|c| c.id != id);
+            Ok(())
+        }
+        async fn create_rag_document(
+            &self,
+            collection_id: &str,
+            file_path: &str,
+            file_name: &str,
+            _content: &str,
+            _metadata: &str,
+        ) -> Result<String> {
+            let document = DocumentSource::new(
+                file_path.to_string(),
+                file_name.to_string(),
+                String::new(),
+                0,
+                String::new(),
+            );
+            let id = document.id.clone();
+            self.add_document(collection_id, document);
+            Ok(id)
+        }
+        async fn insert_document_source(
+            &self,
+            _id: &str,
+            _collection_id: &str,
+            _file_path: &str,
+            _file_name: &str,
+            _file_type: &str,
+            _file_size: i64,
+            _file_hash: &str,
+            _content_hash: &str,
+            _status: &str,
+            _metadata: &str,
+            _created_at: &str,
+            _updated_at: &str,
+        ) -> Result<()> {
+            Ok(())
+        }
+        async fn create_rag_chunk(
+            &self,
+            _document_id: &str,
+            _collection_id: &str,
+            _content: &str,
+            _chunk_index: i32,
+            _embedding: Option<&[f32]>,
+            _metadata_json: &str,
+        ) -> Result<String> {
+            Ok(Uuid::new_v4().to_string())
+        }
+        async fn update_collection_stats(&self, _collection_id: &str) -> Result<()> {
+            Ok(())
+        }
+        async fn get_rag_documents(&self, collection_id: &str) -> Result<Vec<DocumentSource>> {
+            Ok(self
+                .documents
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(cid, _)| cid == collection_id)
+                .map(|(_, d)| d.clone())
+                .collect())
+        }
+        async fn get_rag_documents_paginated(
+            &self,
+            collection_id: &str,
+            _limit: i64,
+            _offset: i64,
+            _search_query: Option<&str>,
+        ) -> Result<(Vec<DocumentSource>, i64)> {
+            let documents = self.get_rag_documents(collection_id).await?;
+            let total = documents.len() as i64;
+            Ok((documents, total))
+        }
+        async fn get_rag_chunks(&self, _document_id: &str) -> Result<Vec<DocumentChunk>> {
+            Ok(Vec::new())
+        }
+        async fn delete_rag_document(&self, document_id: &str) -> Result<()> {
+            self.documents
+                .lock()
+                .unwrap()
+                .retain(|(_, d)| d.id != document_id);
+            Ok(())
+        }
+        async fn update_rag_document_fingerprint(
+            &self,
+            document_id: &str,
+            file_hash: &str,
+            file_mtime: Option<i64>,
+        ) -> Result<()> {
+            for (_, document) in self.documents.lock().unwrap().iter_mut() {
+                if document.id == document_id {
+                    document.file_hash = file_hash.to_string();
+                    document.file_mtime = file_mtime;
+                }
+            }
+            Ok(())
+        }
+        async fn save_rag_query(
+            &self,
+            _collection_id: Option<&str>,
+            _conversation_id: Option<&str>,
+            _query: &str,
+            _response: &str,
+            _processing_time_ms: u64,
+        ) -> Result<()> {
+            Ok(())
+        }
+        async fn get_rag_query_history(
+            &self,
+            _collection_id: Option<&str>,
+            _limit: Option<i32>,
+        ) -> Result<Vec<QueryResult>> {
+            Ok(Vec::new())
+        }
+    }
+
+    async fn service_in(
+        dir: &Path,
+        config: RagConfig,
+    ) -> (RagService<MemoryRagDatabase>, Arc<MemoryRagDatabase>) {
+        let database = Arc::new(MemoryRagDatabase::default());
+        let config = RagConfig {
+            database_path: Some(dir.join("rag_vectors.db")),
+            ..config
+        };
+        let service = RagService::new(config, database.clone()).await.unwrap();
+        (service, database)
+    }
+
+    /// 最小的 Ollama `/api/embed` 模拟服务，为每条输入返回固定的 4 维向量
+    async fn mock_ollama() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    let body = loop {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                        let text = String::from_utf8_lossy(&request);
+                        let Some(header_end) = text.find("\r\n\r\n") else {
+                            continue;
+                        };
+                        let length = text[..header_end]
+                            .lines()
+                            .find_map(|line| {
+                                let (name, value) = line.split_once(':')?;
+                                name.eq_ignore_ascii_case("content-length")
+                                    .then(|| value.trim().parse::<usize>().ok())?
+                            })
+                            .unwrap_or(0);
+                        if request.len() >= header_end + 4 + length {
+                            break request[header_end + 4..header_end + 4 + length].to_vec();
+                        }
+                    };
+                    let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                    let inputs = request["input"].as_array().map_or(1, Vec::len);
+                    let response = serde_json::json!({
+                        "model": "mock",
+                        "embeddings": vec![vec![0.1, 0.2, 0.3, 0.4]; inputs],
+                    })
+                    .to_string();
+                    let _ = socket
+                        .write_all(
+                            format!(
+                                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                                response.len(),
+                                response
+                            )
+                            .as_bytes(),
+                        )
+                        .await;
+                });
+            }
+        });
+        format!("http://{}", address)
+    }
+
+    #[tokio::test]
+    async fn ingested_files_are_skipped_by_incremental_reingest() {
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        let file = docs.join("notes.txt");
+        std::fs::write(&file, "port 8443 exposes the admin console").unwrap();
+
+        let (service, database) = service_in(
+            dir.path(),
+            RagConfig {
+                embedding_provider: "ollama".to_string(),
+                embedding_model: "mock".to_string(),
+                embedding_dimensions: Some(4),
+                embedding_base_url: Some(mock_ollama().await),
+                ..RagConfig::default()
+            },
+        )
+        .await;
+        let collection_id = service.create_collection("kb", None).await.unwrap();
+
+        service
+            .ingest_source(IngestRequest {
+                file_path: file.to_string_lossy().to_string(),
+                collection_id: Some(collection_id.clone()),
+                metadata: None,
+            })
+            .await
+            .unwrap();
+        let ingested = database.document_ids();
+
+        let report = service
+            .reingest_incremental(&collection_id, docs.to_str().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(report.skipped, 1, "{:?}", report);
+        assert_eq!(report.added + report.updated + report.failed, 0);
+        assert_eq!(database.document_ids(), ingested);
+    }
+
+    #[tokio::test]
+    async fn failed_reingest_keeps_previous_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        let file = docs.join("notes.txt");
+        std::fs::write(&file, "updated notes").unwrap();
+
+        // 该提供商不支持嵌入，新版本的摄取必然失败
+        let (service, database) = service_in(
+            dir.path(),
+            RagConfig {
+                embedding_provider: "anthropic".to_string(),
+                ..RagConfig::default()
+            },
+        )
+        .await;
+        let collection_id = service.create_collection("kb", None).await.unwrap();
+
+        let mut previous = DocumentSource::new(
+            file.to_string_lossy().to_string(),
+            "notes.txt".to_string(),
+            "txt".to_string(),
+            0,
+            "stale-hash".to_string(),
+        );
+        previous.id = "previous-version".to_string();
+        database.add_document(&collection_id, previous);
+
+        let report = service
+            .reingest_incremental(&collection_id, docs.to_str().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.updated, 0);
+        assert_eq!(
+            database.document_ids(),
+            vec!["previous-version".to_string()]
+        );
+    }
+}
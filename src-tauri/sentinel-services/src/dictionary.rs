@@ -1,10 +1,11 @@
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 use sentinel_core::models::dictionary::{
     Dictionary, DictionaryExport, DictionaryFilter, DictionaryImportOptions, DictionarySet,
-    DictionarySetRelation, DictionaryStats, DictionaryType, DictionaryWord, MergeMode, ServiceType,
+    DictionarySetRelation, DictionaryStats, DictionaryType, DictionaryWord, MergeMode,
+    PermutationResult, PermutationRules, ServiceType, WordOrder,
 };
 use sentinel_db::DatabasePool;
 
@@ -328,15 +329,23 @@ impl DictionaryService {
         words: Vec<String>,
     ) -> Result<Vec<DictionaryWord>> {
         let mut added_words = Vec::new();
+        // 新词条接在已有词条之后，保持文件中的顺序
+        let next_position: i64 = db_fetch_scalar!(
+            self,
+            i64,
+            "SELECT COALESCE(MAX(position) + 1, 0) FROM dictionary_words WHERE dictionary_id = $1",
+            |q| q.bind(dictionary_id)
+        );
 
-        for word in words {
-            let dict_word = DictionaryWord::new(dictionary_id.to_string(), word);
+        for (offset, word) in words.into_iter().enumerate() {
+            let dict_word = DictionaryWord::new(dictionary_id.to_string(), word)
+                .with_position(next_position + offset as i64);
 
             db_execute!(
                 self,
                 r#"
-                INSERT INTO dictionary_words (id, dictionary_id, word, weight, category, metadata, created_at)
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                INSERT INTO dictionary_words (id, dictionary_id, word, weight, category, metadata, created_at, position)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             "#,
                 |q| {
                     q.bind(&dict_word.id)
@@ -346,6 +355,7 @@ impl DictionaryService {
                         .bind(&dict_word.category)
                         .bind(&dict_word.metadata)
                         .bind(dict_word.created_at)
+                        .bind(dict_word.position)
                 }
             );
 
@@ -357,6 +367,29 @@ impl DictionaryService {
         Ok(added_words)
     }
 
+    /// 合并词条到字典，跳过已存在的词，返回新增数量
+    pub async fn merge_words(&self, dictionary_id: &str, words: Vec<String>) -> Result<usize> {
+        self.get_dictionary(dictionary_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Dictionary not found"))?;
+
+        let existing: HashSet<String> = self
+            .get_dictionary_words(dictionary_id)
+            .await?
+            .into_iter()
+            .map(|w| w.word)
+            .collect();
+        let new_words: Vec<String> = words
+            .into_iter()
+            .filter(|w| !existing.contains(w))
+            .collect();
+        if new_words.is_empty() {
+            return Ok(0);
+        }
+
+        Ok(self.add_words(dictionary_id, new_words).await?.len())
+    }
+
     pub async fn remove_words(&self, dictionary_id: &str, words: Vec<String>) -> Result<u64> {
         let mut removed_count = 0;
 
@@ -391,17 +424,55 @@ impl DictionaryService {
         dictionary_id: &str,
         offset: u32,
         limit: u32,
+        order: WordOrder,
     ) -> Result<Vec<DictionaryWord>> {
-        let words = db_fetch_all_as!(
-            self,
-            DictionaryWord,
-            "SELECT * FROM dictionary_words WHERE dictionary_id = $1 ORDER BY weight DESC, word ASC LIMIT $2 OFFSET $3",
-            |q| q.bind(dictionary_id).bind(limit as i64).bind(offset as i64)
+        let query = format!(
+            "SELECT * FROM dictionary_words WHERE dictionary_id = $1 ORDER BY {} LIMIT $2 OFFSET $3",
+            order.order_by()
         );
+        let words = db_fetch_all_as!(self, DictionaryWord, &query, |q| q
+            .bind(dictionary_id)
+            .bind(limit as i64)
+            .bind(offset as i64));
 
         Ok(words)
     }
 
+    /// 记录词条命中，提升其权重（学习排序），返回更新的词条数
+    pub async fn record_word_hits(
+        &self,
+        dictionary_id: &str,
+        words: &[String],
+        increment: f64,
+    ) -> Result<u64> {
+        let mut updated = 0;
+        for word in words {
+            updated += db_execute!(
+                self,
+                "UPDATE dictionary_words SET weight = COALESCE(weight, 0) + $1 WHERE dictionary_id = $2 AND word = $3",
+                |q| q.bind(increment).bind(dictionary_id).bind(word)
+            );
+        }
+
+        Ok(updated)
+    }
+
+    /// 设置词条权重
+    pub async fn set_word_weight(
+        &self,
+        dictionary_id: &str,
+        word: &str,
+        weight: f64,
+    ) -> Result<u64> {
+        let updated = db_execute!(
+            self,
+            "UPDATE dictionary_words SET weight = $1 WHERE dictionary_id = $2 AND word = $3",
+            |q| q.bind(weight).bind(dictionary_id).bind(word)
+        );
+
+        Ok(updated)
+    }
+
     pub async fn search_words(
         &self,
         dictionary_id: &str,
@@ -855,4 +926,388 @@ impl DictionaryService {
         tracing::info!("Builtin dictionaries initialized");
         Ok(())
     }
+
+    /// 根据已知子域名生成变异候选（前后缀插入、数字变异、连字符/点互换）
+    ///
+    /// 结果去重且不含输入本身，数量不超过 `rules.max_results`。
+    pub fn generate_permutations(
+        base_domains: &[String],
+        rules: &PermutationRules,
+    ) -> PermutationResult {
+        let root = rules
+            .root_domain
+            .as_deref()
+            .map(|r| r.trim().trim_matches('.').to_lowercase())
+            .filter(|r| !r.is_empty());
+        let words: Vec<String> = rules
+            .words
+            .iter()
+            .map(|w| w.trim().to_lowercase())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        // 提取子域名部分（剥离根域名）
+        let mut known = HashSet::new();
+        let mut subs = Vec::new();
+        for domain in base_domains {
+            let domain = domain.trim().trim_matches('.').to_lowercase();
+            let sub = match &root {
+                Some(root) => match domain.strip_suffix(&format!(".{}", root)) {
+                    Some(sub) => sub.to_string(),
+                    None => continue,
+                },
+                None => domain,
+            };
+            if !sub.is_empty() && known.insert(sub.clone()) {
+                subs.push(sub);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let mut candidates = Vec::new();
+        let mut truncated = false;
+        'outer: for sub in &subs {
+            for candidate in permute_subdomain(sub, &words, rules) {
+                if known.contains(&candidate)
+                    || !is_valid_subdomain(&candidate)
+                    || !seen.insert(candidate.clone())
+                {
+                    continue;
+                }
+                if candidates.len() >= rules.max_results {
+                    truncated = true;
+                    break 'outer;
+                }
+                candidates.push(match &root {
+                    Some(root) => format!("{}.{}", candidate, root),
+                    None => candidate,
+                });
+            }
+        }
+
+        PermutationResult {
+            count: candidates.len(),
+            candidates,
+            truncated,
+            merged_count: None,
+        }
+    }
+}
+
+/// 单个子域名的全部变异（未去重）
+fn permute_subdomain(sub: &str, words: &[String], rules: &PermutationRules) -> Vec<String> {
+    let labels: Vec<&str> = sub.split('.').collect();
+    let mut out = Vec::new();
+
+    if rules.dash_dot_swap {
+        if sub.contains('-') {
+            out.push(sub.replace('-', "."));
+        }
+        if sub.contains('.') {
+            out.push(sub.replace('.', "-"));
+        }
+    }
+
+    for (i, label) in labels.iter().enumerate() {
+        for word in words {
+            if rules.prefix {
+                out.push(replace_label(&labels, i, &format!("{}-{}", word, label)));
+                out.push(insert_label(&labels, i, word));
+            }
+            if rules.suffix {
+                out.push(replace_label(&labels, i, &format!("{}-{}", label, word)));
+                out.push(insert_label(&labels, i + 1, word));
+            }
+        }
+        if rules.number_mutation {
+            for mutated in mutate_numbers(label, rules.number_range) {
+                out.push(replace_label(&labels, i, &mutated));
+            }
+        }
+    }
+
+    out
+}
+
+fn replace_label(labels: &[&str], index: usize, label: &str) -> String {
+    let mut parts = labels.to_vec();
+    parts[index] = label;
+    parts.join(".")
+}
+
+fn insert_label(labels: &[&str], index: usize, label: &str) -> String {
+    let mut parts = labels.to_vec();
+    parts.insert(index, label);
+    parts.join(".")
+}
+
+/// 数字变异：对最后一段数字加减（保留前导零），无数字时追加数字后缀
+fn mutate_numbers(label: &str, range: u32) -> Vec<String> {
+    let Some(end) = label.rfind(|c: char| c.is_ascii_digit()).map(|i| i + 1) else {
+        return (1..=range).map(|n| format!("{}{}", label, n)).collect();
+    };
+    let start = label[..end]
+        .rfind(|c: char| !c.is_ascii_digit())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let Ok(value) = label[start..end].parse::<u64>() else {
+        return Vec::new();
+    };
+    let width = end - start;
+
+    let mut out = Vec::new();
+    for delta in 1..=range as u64 {
+        let neighbours = [value.checked_add(delta), value.checked_sub(delta)];
+        for n in neighbours.into_iter().flatten() {
+            out.push(format!(
+                "{}{:0width$}{}",
+                &label[..start],
+                n,
+                &label[end..],
+                width = width
+            ));
+        }
+    }
+    out
+}
+
+fn is_valid_subdomain(sub: &str) -> bool {
+    sub.split('.').all(|label| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn domains(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    async fn memory_service() -> DictionaryService {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        for ddl in [
+            r#"CREATE TABLE dictionaries (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                description TEXT,
+                dict_type TEXT NOT NULL,
+                service_type TEXT,
+                category TEXT,
+                is_builtin BOOLEAN DEFAULT FALSE,
+                is_active BOOLEAN DEFAULT TRUE,
+                word_count BIGINT DEFAULT 0,
+                file_size BIGINT DEFAULT 0,
+                checksum TEXT,
+                version TEXT DEFAULT '1.0.0',
+                author TEXT,
+                source_url TEXT,
+                tags TEXT,
+                metadata TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )"#,
+            r#"CREATE TABLE dictionary_words (
+                id TEXT PRIMARY KEY,
+                dictionary_id TEXT NOT NULL,
+                word TEXT NOT NULL,
+                weight DOUBLE DEFAULT 1.0,
+                category TEXT,
+                metadata TEXT,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                position BIGINT
+            )"#,
+        ] {
+            sqlx::query(ddl).execute(&pool).await.unwrap();
+        }
+        DictionaryService::new(DatabasePool::SQLite(pool))
+    }
+
+    fn page_words(words: Vec<DictionaryWord>) -> Vec<String> {
+        words.into_iter().map(|w| w.word).collect()
+    }
+
+    #[tokio::test]
+    async fn test_higher_weight_words_served_first() {
+        let service = memory_service().await;
+        let dict = service
+            .create_dictionary(Dictionary::new(
+                "paths".to_string(),
+                DictionaryType::Subdomain,
+                None,
+                None,
+            ))
+            .await
+            .unwrap();
+        service
+            .add_words(&dict.id, domains(&["www", "admin", "mail"]))
+            .await
+            .unwrap();
+        service
+            .set_word_weight(&dict.id, "mail", 5.0)
+            .await
+            .unwrap();
+
+        let by_weight = service
+            .get_dictionary_words_paged(&dict.id, 0, 10, WordOrder::Weight)
+            .await
+            .unwrap();
+        assert_eq!(page_words(by_weight), domains(&["mail", "admin", "www"]));
+
+        let by_insertion = service
+            .get_dictionary_words_paged(&dict.id, 0, 10, WordOrder::Insertion)
+            .await
+            .unwrap();
+        assert_eq!(page_words(by_insertion), domains(&["www", "admin", "mail"]));
+    }
+
+    #[tokio::test]
+    async fn test_insertion_order_follows_file_order_across_imports() {
+        let service = memory_service().await;
+        let dict = service
+            .create_dictionary(Dictionary::new(
+                "subs".to_string(),
+                DictionaryType::Subdomain,
+                None,
+                None,
+            ))
+            .await
+            .unwrap();
+        service
+            .add_words(&dict.id, domains(&["zeta", "beta", "omega"]))
+            .await
+            .unwrap();
+        service
+            .add_words(&dict.id, domains(&["delta", "alpha"]))
+            .await
+            .unwrap();
+
+        let all = service
+            .get_dictionary_words_paged(&dict.id, 0, 10, WordOrder::Insertion)
+            .await
+            .unwrap();
+        assert_eq!(
+            page_words(all),
+            domains(&["zeta", "beta", "omega", "delta", "alpha"])
+        );
+
+        let page = service
+            .get_dictionary_words_paged(&dict.id, 2, 2, WordOrder::Insertion)
+            .await
+            .unwrap();
+        assert_eq!(page_words(page), domains(&["omega", "delta"]));
+    }
+
+    #[tokio::test]
+    async fn test_recorded_hits_persist_and_reorder() {
+        let service = memory_service().await;
+        let dict = service
+            .create_dictionary(Dictionary::new(
+                "subs".to_string(),
+                DictionaryType::Subdomain,
+                None,
+                None,
+            ))
+            .await
+            .unwrap();
+        service
+            .add_words(&dict.id, domains(&["admin", "www"]))
+            .await
+            .unwrap();
+
+        let updated = service
+            .record_word_hits(&dict.id, &domains(&["www", "missing"]), 2.5)
+            .await
+            .unwrap();
+        assert_eq!(updated, 1);
+
+        let first = service
+            .get_dictionary_words_paged(&dict.id, 0, 1, WordOrder::Weight)
+            .await
+            .unwrap();
+        assert_eq!(first[0].word, "www");
+        assert_eq!(first[0].weight, 3.5);
+
+        let reloaded = service.get_dictionary_words(&dict.id).await.unwrap();
+        assert_eq!(reloaded[0].word, "www");
+        assert_eq!(reloaded[1].weight, 1.0);
+    }
+
+    #[test]
+    fn test_generate_permutations_for_sample_input() {
+        let rules = PermutationRules {
+            root_domain: Some("example.com".to_string()),
+            words: domains(&["dev"]),
+            ..Default::default()
+        };
+        let result = DictionaryService::generate_permutations(
+            &domains(&[
+                "api.example.com",
+                "web01.example.com",
+                "dev-portal.example.com",
+                "unrelated.org",
+            ]),
+            &rules,
+        );
+
+        for expected in [
+            "dev-api.example.com",
+            "api-dev.example.com",
+            "dev.api.example.com",
+            "api.dev.example.com",
+            "api1.example.com",
+            "api3.example.com",
+            "web02.example.com",
+            "web00.example.com",
+            "web04.example.com",
+            "dev.portal.example.com",
+            "dev-portal-dev.example.com",
+        ] {
+            assert!(
+                result.candidates.contains(&expected.to_string()),
+                "missing {}",
+                expected
+            );
+        }
+        assert!(!result.candidates.contains(&"api.example.com".to_string()));
+        assert!(!result.candidates.iter().any(|c| c.contains("unrelated")));
+
+        let unique: HashSet<_> = result.candidates.iter().collect();
+        assert_eq!(unique.len(), result.candidates.len());
+        assert_eq!(result.count, result.candidates.len());
+        assert!(!result.truncated);
+    }
+
+    #[test]
+    fn test_generate_permutations_respects_rules_and_cap() {
+        let rules = PermutationRules {
+            words: vec![],
+            dash_dot_swap: false,
+            number_range: 1,
+            ..Default::default()
+        };
+        let result = DictionaryService::generate_permutations(&domains(&["node9"]), &rules);
+        assert_eq!(result.candidates, domains(&["node10", "node8"]));
+
+        let capped = PermutationRules {
+            max_results: 5,
+            ..Default::default()
+        };
+        let result = DictionaryService::generate_permutations(&domains(&["api", "mail"]), &capped);
+        assert_eq!(result.count, 5);
+        assert!(result.truncated);
+    }
 }
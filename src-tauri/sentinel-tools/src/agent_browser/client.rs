@@ -5,6 +5,7 @@ use super::types::{BrowserCommand, BrowserResponse};
 use anyhow::{Context, Result};
 use serde_json::Value;
 use std::io::{BufRead, BufReader, Write};
+use std::time::Duration;
 use tracing::debug;
 use uuid::Uuid;
 
@@ -16,15 +17,26 @@ use std::os::unix::net::UnixStream;
 /// Client for communicating with agent-browser daemon
 pub struct BrowserClient {
     session: String,
+    read_timeout: Duration,
 }
 
+/// Default time to wait for a daemon response
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl BrowserClient {
     pub fn new(session: &str) -> Self {
         Self {
             session: session.to_string(),
+            read_timeout: DEFAULT_READ_TIMEOUT,
         }
     }
 
+    /// Wait longer for responses, e.g. for navigations with a long timeout
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
     /// Send a command to the daemon and get response
     pub fn send_command(&self, action: &str, params: Value) -> Result<BrowserResponse> {
         let command = BrowserCommand {
@@ -65,7 +77,7 @@ impl BrowserClient {
 
         // Use short timeouts to avoid blocking the UI
         // If daemon is unresponsive, fail fast rather than hang
-        stream.set_read_timeout(Some(std::time::Duration::from_secs(10)))?;
+        stream.set_read_timeout(Some(self.read_timeout))?;
         stream.set_write_timeout(Some(std::time::Duration::from_secs(3)))?;
 
         // Send command with newline
@@ -92,7 +104,7 @@ impl BrowserClient {
         .context("Failed to connect to daemon")?;
 
         // Use short timeouts to avoid blocking the UI
-        stream.set_read_timeout(Some(std::time::Duration::from_secs(10)))?;
+        stream.set_read_timeout(Some(self.read_timeout))?;
         stream.set_write_timeout(Some(std::time::Duration::from_secs(3)))?;
 
         // Send command with newline
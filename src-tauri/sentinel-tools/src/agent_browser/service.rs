@@ -23,10 +23,53 @@ pub async fn get_browser_service() -> Arc<RwLock<AgentBrowserService>> {
     BROWSER_SERVICE.clone()
 }
 
+/// Stable ids for tabs, kept in tab order.
+///
+/// The daemon only addresses tabs by index, and indices shift when earlier tabs
+/// close; owned tabs keep their id wherever they move. Tabs not opened through
+/// [`AgentBrowserService::new_owned_tab`] have no id.
+#[derive(Debug, Default)]
+struct TabIds {
+    slots: Vec<Option<u64>>,
+    next_id: u64,
+}
+
+impl TabIds {
+    /// Align with the daemon's tab count (tabs opened or closed elsewhere)
+    fn sync(&mut self, count: usize) {
+        self.slots.resize(count, None);
+    }
+
+    fn opened(&mut self, index: usize, owned: bool) -> Option<u64> {
+        let id = owned.then(|| {
+            self.next_id += 1;
+            self.next_id
+        });
+        let index = index.min(self.slots.len());
+        self.slots.insert(index, id);
+        id
+    }
+
+    fn closed(&mut self, index: usize) {
+        if index < self.slots.len() {
+            self.slots.remove(index);
+        }
+    }
+
+    fn position(&self, id: u64) -> Option<usize> {
+        self.slots.iter().position(|slot| *slot == Some(id))
+    }
+
+    fn clear(&mut self) {
+        self.slots.clear();
+    }
+}
+
 /// Browser automation service
 pub struct AgentBrowserService {
     config: BrowserConfig,
     initialized: bool,
+    tab_ids: TabIds,
 }
 
 impl AgentBrowserService {
@@ -34,6 +77,7 @@ impl AgentBrowserService {
         Self {
             config: BrowserConfig::default(),
             initialized: false,
+            tab_ids: TabIds::default(),
         }
     }
 
@@ -52,6 +96,7 @@ impl AgentBrowserService {
         client.launch(self.config.headless, viewport)?;
 
         self.initialized = true;
+        self.tab_ids.clear();
         info!(
             "Browser service initialized with session: {}",
             self.config.session
@@ -70,6 +115,7 @@ impl AgentBrowserService {
             let client = self.client();
             let viewport = Some((self.config.viewport_width, self.config.viewport_height));
             client.launch(self.config.headless, viewport)?;
+            self.tab_ids.clear();
         }
         Ok(())
     }
@@ -391,10 +437,23 @@ impl AgentBrowserService {
 
     /// Create new tab
     pub async fn new_tab(&mut self) -> Result<u32> {
-        self.ensure_init().await?;
+        self.open_tab(false).await.map(|(index, _)| index)
+    }
+
+    /// Create a new tab with a stable id that survives other tabs closing
+    pub async fn new_owned_tab(&mut self) -> Result<u64> {
+        let (_, id) = self.open_tab(true).await?;
+        id.ok_or_else(|| anyhow::anyhow!("tab id not assigned"))
+    }
+
+    async fn open_tab(&mut self, owned: bool) -> Result<(u32, Option<u64>)> {
+        let count = self.list_tabs().await?.len();
+        self.tab_ids.sync(count);
         let client = self.client();
         let result = client.new_tab()?;
-        Ok(result["index"].as_u64().unwrap_or(0) as u32)
+        let index = result["index"].as_u64().unwrap_or(0) as u32;
+        let id = self.tab_ids.opened(index as usize, owned);
+        Ok((index, id))
     }
 
     /// Switch to tab
@@ -405,14 +464,54 @@ impl AgentBrowserService {
         Ok(())
     }
 
+    /// Make a tab opened with [`new_owned_tab`](Self::new_owned_tab) the active one.
+    ///
+    /// Page actions apply to the active tab, which other runs may have changed.
+    /// Returns false when the tab is already gone.
+    pub async fn activate_owned_tab(&mut self, id: u64) -> Result<bool> {
+        let count = self.list_tabs().await?.len();
+        self.tab_ids.sync(count);
+        let Some(index) = self.tab_ids.position(id) else {
+            return Ok(false);
+        };
+        self.client().switch_tab(index as u32)?;
+        Ok(true)
+    }
+
     /// Close tab
     pub async fn close_tab(&mut self, index: Option<u32>) -> Result<()> {
-        self.ensure_init().await?;
+        let tabs = self.list_tabs().await?;
+        self.tab_ids.sync(tabs.len());
+        let closed = index.or_else(|| tabs.iter().find(|t| t.active).map(|t| t.index));
         let client = self.client();
         client.close_tab(index)?;
+        if let Some(closed) = closed {
+            self.tab_ids.closed(closed as usize);
+        }
         Ok(())
     }
 
+    /// Close a tab opened with [`new_owned_tab`](Self::new_owned_tab).
+    ///
+    /// The last remaining tab is blanked instead of closed. Returns false when the
+    /// tab is already gone.
+    pub async fn close_owned_tab(&mut self, id: u64) -> Result<bool> {
+        let count = self.list_tabs().await?.len();
+        self.tab_ids.sync(count);
+        let Some(index) = self.tab_ids.position(id) else {
+            return Ok(false);
+        };
+        if count > 1 {
+            self.close_tab(Some(index as u32)).await?;
+        } else {
+            let client = self.client();
+            client.switch_tab(index as u32)?;
+            client.navigate("about:blank", None)?;
+            self.tab_ids.slots[index] = None;
+        }
+        Ok(true)
+    }
+
     // ==================== Viewport ====================
 
     /// Set viewport size
@@ -433,6 +532,7 @@ impl AgentBrowserService {
             let client = self.client();
             let _ = client.close();
             self.initialized = false;
+            self.tab_ids.clear();
         }
         Ok(())
     }
@@ -485,6 +585,17 @@ impl AgentBrowserService {
         Ok(())
     }
 
+    /// Use this headless mode for the next launch without restarting a running
+    /// browser, which other users may share. Returns whether the running browser
+    /// already matches.
+    pub fn prefer_headless(&mut self, headless: bool) -> bool {
+        if self.is_ready() {
+            return self.config.headless == headless;
+        }
+        self.config.headless = headless;
+        true
+    }
+
     // ==================== Network Interception ====================
 
     /// Start tracking network requests
@@ -636,3 +747,28 @@ impl Default for AgentBrowserService {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owned_tab_ids_survive_other_tabs_closing() {
+        let mut tabs = TabIds::default();
+        tabs.sync(1);
+        let first = tabs.opened(1, true).unwrap();
+        let second = tabs.opened(2, true).unwrap();
+        assert_eq!(tabs.position(second), Some(2));
+
+        // Another run closes its tab in front of ours
+        tabs.closed(tabs.position(first).unwrap());
+        assert_eq!(tabs.position(first), None);
+        assert_eq!(tabs.position(second), Some(1));
+
+        // A tab opened elsewhere is appended without an id
+        tabs.sync(3);
+        assert_eq!(tabs.position(second), Some(1));
+        tabs.closed(0);
+        assert_eq!(tabs.position(second), Some(0));
+    }
+}
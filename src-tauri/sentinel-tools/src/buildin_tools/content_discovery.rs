@@ -0,0 +1,1010 @@
+//! Wordlist-driven content discovery (directory busting) tool using rig-core Tool trait
+//!
+//! Requests `<base>/<word>` for every word of a dictionary (stored dictionary
+//! by id or an inline list), keeps responses whose status passes the filters,
+//! and recurses into discovered directories up to a depth limit. Requests go
+//! through the global proxy and are paced by a per-scan rate limit.
+//!
+//! When the target starts answering with 429s, WAF block pages or a run of
+//! identical 403s, the scan pauses with exponential backoff, slows down, and
+//! retries the blocked requests; it stops early if the target keeps blocking.
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use once_cell::sync::Lazy;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+/// Upper bound for concurrent requests
+const MAX_CONCURRENCY: usize = 200;
+
+/// Body markers of WAF / bot-protection block pages (matched lowercase)
+const WAF_SIGNATURES: &[&str] = &[
+    "attention required",
+    "cloudflare ray id",
+    "access denied",
+    "request blocked",
+    "request rejected",
+    "captcha",
+    "mod_security",
+    "incapsula",
+    "akamai reference",
+    "too many requests",
+    "rate limit",
+];
+
+/// Only the start of a block page is inspected for signatures
+const SIGNATURE_SCAN_BYTES: usize = 4096;
+
+/// Slowest pacing applied after repeated backoffs
+const MAX_THROTTLE: Duration = Duration::from_secs(2);
+
+/// Content discovery arguments
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ContentDiscoveryArgs {
+    /// Base URL to brute-force below (e.g. "https://example.com/app/")
+    pub base_url: String,
+    /// ID of a stored dictionary to use as word list
+    #[serde(default)]
+    pub dictionary_id: Option<String>,
+    /// Inline word list (used when no dictionary_id is given)
+    #[serde(default)]
+    pub words: Option<Vec<String>>,
+    /// Extensions appended to each word without one (e.g. ["php", "bak"])
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// Number of concurrent requests
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// Maximum requests per second (0 = unlimited)
+    #[serde(default = "default_rate_limit")]
+    pub rate_limit: u32,
+    /// Status codes reported as found
+    #[serde(default = "default_match_status")]
+    pub match_status: Vec<u16>,
+    /// Status codes never reported, even if matched
+    #[serde(default)]
+    pub filter_status: Vec<u16>,
+    /// Per-request timeout in seconds
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+    /// How many directory levels to recurse into (0 = no recursion)
+    #[serde(default)]
+    pub max_depth: usize,
+    /// Stop after this many discovered paths
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+    /// Thresholds for backing off when the target rate-limits or blocks the scan
+    #[serde(default)]
+    pub backoff: BackoffConfig,
+}
+
+/// Rate-limit / soft-block detection thresholds
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct BackoffConfig {
+    /// Consecutive blocked responses (429, WAF page, Retry-After) before pausing
+    #[serde(default = "default_block_threshold")]
+    pub block_threshold: usize,
+    /// Consecutive 403 responses of identical size treated as a soft block
+    #[serde(default = "default_soft_block_threshold")]
+    pub soft_block_threshold: usize,
+    /// First pause in milliseconds, doubled on every further backoff
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    /// Longest pause in milliseconds
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// Stop the scan after this many backoffs without the target recovering
+    #[serde(default = "default_max_backoffs")]
+    pub max_backoffs: usize,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self {
+            block_threshold: default_block_threshold(),
+            soft_block_threshold: default_soft_block_threshold(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            max_backoffs: default_max_backoffs(),
+        }
+    }
+}
+
+fn default_concurrency() -> usize {
+    20
+}
+fn default_rate_limit() -> u32 {
+    50
+}
+fn default_match_status() -> Vec<u16> {
+    vec![200, 204, 301, 302, 307, 308, 401, 403, 405]
+}
+fn default_timeout() -> u64 {
+    10
+}
+fn default_max_results() -> usize {
+    500
+}
+fn default_block_threshold() -> usize {
+    5
+}
+fn default_soft_block_threshold() -> usize {
+    20
+}
+fn default_initial_backoff_ms() -> u64 {
+    2_000
+}
+fn default_max_backoff_ms() -> u64 {
+    60_000
+}
+fn default_max_backoffs() -> usize {
+    6
+}
+
+/// A discovered path
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveredPath {
+    pub url: String,
+    /// Path relative to the base URL
+    pub path: String,
+    pub status: u16,
+    pub content_length: usize,
+    pub redirect_location: Option<String>,
+    /// Recursion level the path was found at
+    pub depth: usize,
+    pub is_directory: bool,
+}
+
+/// Content discovery result
+#[derive(Debug, Clone, Serialize)]
+pub struct ContentDiscoveryOutput {
+    pub base_url: String,
+    pub discovered: Vec<DiscoveredPath>,
+    pub total_found: usize,
+    pub requests_sent: usize,
+    /// Requests that failed (timeouts, connection errors)
+    pub errors: usize,
+    /// Stopped early because `max_results` was reached
+    pub truncated: bool,
+    /// The target rate-limited or blocked the scan at some point
+    pub rate_limited: bool,
+    /// Number of backoff pauses taken
+    pub backoffs: usize,
+    /// Stopped early because the target kept blocking after `max_backoffs` pauses
+    pub stopped_by_rate_limit: bool,
+    pub scan_duration_ms: u64,
+}
+
+/// Content discovery errors
+#[derive(Debug, thiserror::Error)]
+pub enum ContentDiscoveryError {
+    #[error("Invalid base URL: {0}")]
+    InvalidUrl(String),
+    #[error("Dictionary error: {0}")]
+    Dictionary(String),
+    #[error("Scan failed: {0}")]
+    ScanFailed(String),
+}
+
+/// Source of stored dictionaries
+#[async_trait]
+pub trait ContentDictionarySource: Send + Sync {
+    async fn words(&self, dictionary_id: &str) -> anyhow::Result<Vec<String>>;
+}
+
+static DICTIONARY_SOURCE: Lazy<RwLock<Option<Arc<dyn ContentDictionarySource>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+/// Set the source used to resolve `dictionary_id`
+pub async fn set_content_dictionary_source(source: Arc<dyn ContentDictionarySource>) {
+    *DICTIONARY_SOURCE.write().await = Some(source);
+}
+
+/// Spaces requests evenly to stay under a requests-per-second budget
+struct RateLimiter {
+    interval: Option<Duration>,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(per_second: u32) -> Self {
+        Self {
+            interval: (per_second > 0).then(|| Duration::from_secs(1) / per_second),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn acquire(&self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+        let wait_until = {
+            let mut next = self.next.lock().await;
+            let slot = (*next).max(Instant::now());
+            *next = slot + interval;
+            slot
+        };
+        tokio::time::sleep_until(wait_until.into()).await;
+    }
+}
+
+/// What a response says about the target's rate limiting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    /// Normal response
+    Pass,
+    /// Blocked; retry after sleeping for the given delay
+    Retry(Duration),
+    /// The target keeps blocking; stop the scan
+    Abort,
+}
+
+#[derive(Debug, Default)]
+struct BackoffState {
+    /// Bumped on every backoff; responses to requests sent earlier are not counted again
+    epoch: u64,
+    consecutive_blocked: usize,
+    /// Size and count of the current run of 403 responses
+    forbidden_run: Option<(usize, usize)>,
+    /// Normal responses since the last backoff
+    recovered: usize,
+    paused_until: Option<Instant>,
+    next_backoff: Duration,
+    /// Extra spacing between requests after backoffs
+    throttle: Option<Duration>,
+    next_slot: Option<Instant>,
+    backoffs: usize,
+    aborted: bool,
+}
+
+/// Detects sustained blocking and pauses/slows all probes of a scan
+struct AdaptiveBackoff {
+    config: BackoffConfig,
+    state: Mutex<BackoffState>,
+}
+
+impl AdaptiveBackoff {
+    fn new(config: BackoffConfig) -> Self {
+        let state = BackoffState {
+            next_backoff: Duration::from_millis(config.initial_backoff_ms),
+            ..Default::default()
+        };
+        Self {
+            config,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Wait out an active pause and the throttle; returns the epoch to report with, or None once aborted
+    async fn wait(&self) -> Option<u64> {
+        loop {
+            let wait_until = {
+                let mut state = self.state.lock().await;
+                if state.aborted {
+                    return None;
+                }
+                let now = Instant::now();
+                match state.paused_until.filter(|until| *until > now) {
+                    Some(until) => until,
+                    None => {
+                        let Some(throttle) = state.throttle else {
+                            return Some(state.epoch);
+                        };
+                        let slot = state.next_slot.unwrap_or(now).max(now);
+                        state.next_slot = Some(slot + throttle);
+                        if slot <= now {
+                            return Some(state.epoch);
+                        }
+                        let epoch = state.epoch;
+                        drop(state);
+                        tokio::time::sleep_until(slot.into()).await;
+                        return Some(epoch);
+                    }
+                }
+            };
+            tokio::time::sleep_until(wait_until.into()).await;
+        }
+    }
+
+    fn is_blocked(&self, state: &mut BackoffState, probe: &Probe) -> bool {
+        if probe.status == 429 || probe.block_signature {
+            state.forbidden_run = None;
+            return true;
+        }
+        if probe.status != 403 {
+            state.forbidden_run = None;
+            return false;
+        }
+        // A 403 for one path is normal; the same 403 page for every path is a block
+        let run = match state.forbidden_run {
+            Some((len, count)) if len == probe.content_length => count + 1,
+            _ => 1,
+        };
+        state.forbidden_run = Some((probe.content_length, run));
+        run >= self.config.soft_block_threshold.max(1)
+    }
+
+    async fn observe(&self, probe: &Probe, epoch: u64) -> Verdict {
+        let mut state = self.state.lock().await;
+        if state.aborted {
+            return Verdict::Abort;
+        }
+        if !self.is_blocked(&mut state, probe) {
+            state.consecutive_blocked = 0;
+            if state.backoffs > 0 {
+                state.recovered += 1;
+                // Back to normal: relax the throttle step by step
+                if state.recovered >= self.config.block_threshold.max(1) {
+                    state.recovered = 0;
+                    state.next_backoff = Duration::from_millis(self.config.initial_backoff_ms);
+                    state.throttle = state
+                        .throttle
+                        .map(|t| t / 2)
+                        .filter(|t| *t >= Duration::from_millis(10));
+                }
+            }
+            return Verdict::Pass;
+        }
+        let max_backoff = Duration::from_millis(self.config.max_backoff_ms);
+        let retry_after = probe.retry_after.unwrap_or_default().min(max_backoff);
+        if epoch != state.epoch {
+            // Sent before the last pause; wait() holds it back until the pause ends
+            return Verdict::Retry(retry_after);
+        }
+        state.recovered = 0;
+        state.consecutive_blocked += 1;
+        if state.consecutive_blocked < self.config.block_threshold.max(1) {
+            // Not a sustained block yet: only this request waits, doubling per blocked response
+            let exponent = (state.consecutive_blocked - 1).min(16) as u32;
+            let delay = Duration::from_millis(self.config.initial_backoff_ms)
+                .saturating_mul(1 << exponent)
+                .min(max_backoff);
+            return Verdict::Retry(delay.max(retry_after));
+        }
+
+        if state.backoffs >= self.config.max_backoffs {
+            tracing::warn!(
+                "Target keeps blocking after {} backoffs, stopping content discovery",
+                state.backoffs
+            );
+            state.aborted = true;
+            return Verdict::Abort;
+        }
+        let pause = state.next_backoff.max(retry_after);
+        tracing::info!(
+            "Rate-limited by target (status {}), pausing for {:?}",
+            probe.status,
+            pause
+        );
+        state.epoch += 1;
+        state.backoffs += 1;
+        state.consecutive_blocked = 0;
+        state.forbidden_run = None;
+        state.paused_until = Some(Instant::now() + pause);
+        state.next_backoff = (pause * 2).min(max_backoff);
+        state.throttle = Some(
+            state
+                .throttle
+                .map_or(Duration::from_millis(100), |t| t * 2)
+                .min(MAX_THROTTLE),
+        );
+        // The pause applies to every probe through wait()
+        Verdict::Retry(Duration::ZERO)
+    }
+
+    async fn summary(&self) -> (usize, bool) {
+        let state = self.state.lock().await;
+        (state.backoffs, state.aborted)
+    }
+}
+
+/// Response of a single probe
+struct Probe {
+    url: String,
+    status: u16,
+    content_length: usize,
+    location: Option<String>,
+    /// Retry-After header or a WAF block page
+    block_signature: bool,
+    /// Delay requested by the Retry-After header
+    retry_after: Option<Duration>,
+}
+
+/// Parse a Retry-After value: delay in seconds or an HTTP date
+fn parse_retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&chrono::Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Why a probe produced no response
+enum ProbeError {
+    Http(reqwest::Error),
+    /// The scan stopped because the target keeps blocking
+    Aborted,
+    /// The enclosing execution was cancelled
+    Cancelled,
+}
+
+/// Content discovery tool
+#[derive(Debug, Clone, Default)]
+pub struct ContentDiscoveryTool;
+
+impl ContentDiscoveryTool {
+    pub const NAME: &'static str = "content_discovery";
+    pub const DESCRIPTION: &'static str = "Brute-force directories and files below a base URL with a word list (stored dictionary by id or inline words), like ffuf/gobuster. Supports extensions, status filters, concurrency, rate limiting and recursion into discovered directories. Backs off automatically when the target rate-limits (429/WAF). Returns found paths with status and length and whether the scan was rate-limited.";
+
+    /// Normalize words: trim, drop comments and leading slashes, expand extensions, dedupe
+    fn candidates(words: &[String], extensions: &[String]) -> Vec<String> {
+        let extensions: Vec<&str> = extensions
+            .iter()
+            .map(|e| e.trim().trim_start_matches('.'))
+            .filter(|e| !e.is_empty())
+            .collect();
+        let mut seen = HashSet::new();
+        let mut out = Vec::new();
+        for word in words {
+            let word = word.trim().trim_start_matches('/');
+            if word.is_empty() || word.starts_with('#') {
+                continue;
+            }
+            let mut push = |candidate: String| {
+                if seen.insert(candidate.clone()) {
+                    out.push(candidate);
+                }
+            };
+            push(word.to_string());
+            if !word.contains('.') && !word.ends_with('/') {
+                for ext in &extensions {
+                    push(format!("{}.{}", word, ext));
+                }
+            }
+        }
+        out
+    }
+
+    async fn resolve_words(
+        args: &ContentDiscoveryArgs,
+    ) -> Result<Vec<String>, ContentDiscoveryError> {
+        if let Some(id) = args.dictionary_id.as_deref().filter(|id| !id.is_empty()) {
+            let source = DICTIONARY_SOURCE.read().await.clone().ok_or_else(|| {
+                ContentDiscoveryError::Dictionary("Dictionary storage is not available".to_string())
+            })?;
+            return source
+                .words(id)
+                .await
+                .map_err(|e| ContentDiscoveryError::Dictionary(format!("{}: {}", id, e)));
+        }
+        match &args.words {
+            Some(words) if !words.is_empty() => Ok(words.clone()),
+            _ => Err(ContentDiscoveryError::Dictionary(
+                "Provide either dictionary_id or words".to_string(),
+            )),
+        }
+    }
+
+    async fn send(client: &reqwest::Client, url: &str) -> Result<Probe, reqwest::Error> {
+        let response = client.get(url).send().await?;
+        let status = response.status().as_u16();
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let retry_after_header = response.headers().get(reqwest::header::RETRY_AFTER);
+        let has_retry_after = retry_after_header.is_some();
+        let retry_after = retry_after_header
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| parse_retry_after(v, chrono::Utc::now()));
+        let body = response.bytes().await.unwrap_or_default();
+        let block_signature = matches!(status, 403 | 406 | 429 | 503)
+            && (has_retry_after || {
+                let head = String::from_utf8_lossy(&body[..body.len().min(SIGNATURE_SCAN_BYTES)])
+                    .to_lowercase();
+                WAF_SIGNATURES.iter().any(|s| head.contains(s))
+            });
+        Ok(Probe {
+            url: url.to_string(),
+            status,
+            content_length: body.len(),
+            location,
+            block_signature,
+            retry_after: retry_after.filter(|_| matches!(status, 429 | 503)),
+        })
+    }
+
+    /// Send one request, retrying it while the target is rate limiting
+    async fn probe(
+        client: &reqwest::Client,
+        limiter: &RateLimiter,
+        backoff: &AdaptiveBackoff,
+        url: String,
+    ) -> Result<Probe, ProbeError> {
+        loop {
+            if crate::cancellation::is_cancelled() {
+                return Err(ProbeError::Cancelled);
+            }
+            let epoch = backoff.wait().await.ok_or(ProbeError::Aborted)?;
+            limiter.acquire().await;
+            let probe = Self::send(client, &url).await.map_err(ProbeError::Http)?;
+            match backoff.observe(&probe, epoch).await {
+                Verdict::Pass => return Ok(probe),
+                Verdict::Retry(delay) => {
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+                Verdict::Abort => return Err(ProbeError::Aborted),
+            }
+        }
+    }
+
+    /// Whether a probe points at a directory worth recursing into
+    fn is_directory(probe: &Probe) -> bool {
+        if probe.url.ends_with('/') {
+            return true;
+        }
+        if !(300..400).contains(&probe.status) {
+            return false;
+        }
+        let Some(location) = &probe.location else {
+            return false;
+        };
+        let target = reqwest::Url::parse(&probe.url)
+            .and_then(|base| base.join(location))
+            .map(|u| u.to_string())
+            .unwrap_or_else(|_| location.clone());
+        target.split(['?', '#']).next() == Some(format!("{}/", probe.url).as_str())
+    }
+
+    async fn run(
+        args: ContentDiscoveryArgs,
+        words: Vec<String>,
+    ) -> Result<ContentDiscoveryOutput, ContentDiscoveryError> {
+        let start = Instant::now();
+        let mut base = reqwest::Url::parse(args.base_url.trim())
+            .map_err(|e| ContentDiscoveryError::InvalidUrl(format!("{}: {}", args.base_url, e)))?;
+        if !matches!(base.scheme(), "http" | "https") {
+            return Err(ContentDiscoveryError::InvalidUrl(format!(
+                "unsupported scheme: {}",
+                base.scheme()
+            )));
+        }
+        base.set_query(None);
+        base.set_fragment(None);
+        let mut base_url = base.to_string();
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+
+        let candidates = Self::candidates(&words, &args.extensions);
+        if candidates.is_empty() {
+            return Err(ContentDiscoveryError::Dictionary(
+                "Word list is empty".to_string(),
+            ));
+        }
+
+        let builder = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .redirect(reqwest::redirect::Policy::none())
+            .timeout(Duration::from_secs(args.timeout_secs.max(1)));
+        let builder = sentinel_core::global_proxy::apply_proxy_to_client(builder).await;
+        let client = sentinel_core::auth_session::apply_session_to_client(builder)
+            .build()
+            .map_err(|e| ContentDiscoveryError::ScanFailed(e.to_string()))?;
+        let limiter = RateLimiter::new(args.rate_limit);
+        let backoff = AdaptiveBackoff::new(args.backoff.clone());
+        let concurrency = args.concurrency.clamp(1, MAX_CONCURRENCY);
+        let matched = |status: u16| {
+            args.match_status.contains(&status) && !args.filter_status.contains(&status)
+        };
+
+        let mut discovered = Vec::new();
+        let mut requests_sent = 0;
+        let mut errors = 0;
+        let mut truncated = false;
+        let mut visited_dirs = HashSet::from([base_url.clone()]);
+        let mut queue = vec![(base_url.clone(), 0usize)];
+
+        while let Some((prefix, depth)) = queue.pop() {
+            // A random path answering with a matched status means catch-all routing;
+            // responses identical to it are noise
+            let wildcard = Self::probe(
+                &client,
+                &limiter,
+                &backoff,
+                format!("{}{}", prefix, uuid::Uuid::new_v4().simple()),
+            )
+            .await
+            .ok()
+            .filter(|p| matched(p.status))
+            .map(|p| (p.status, p.content_length));
+            requests_sent += 1;
+
+            let urls: Vec<String> = candidates
+                .iter()
+                .map(|word| format!("{}{}", prefix, word))
+                .collect();
+            let mut results = stream::iter(urls)
+                .map(|url| Self::probe(&client, &limiter, &backoff, url))
+                .buffer_unordered(concurrency);
+
+            while let Some(result) = results.next().await {
+                requests_sent += 1;
+                let probe = match result {
+                    Ok(probe) => probe,
+                    Err(ProbeError::Http(e)) => {
+                        tracing::debug!("Content discovery request failed: {}", e);
+                        errors += 1;
+                        continue;
+                    }
+                    Err(ProbeError::Aborted | ProbeError::Cancelled) => break,
+                };
+                if !matched(probe.status) || wildcard == Some((probe.status, probe.content_length))
+                {
+                    continue;
+                }
+
+                let is_directory = Self::is_directory(&probe);
+                if is_directory && depth < args.max_depth {
+                    let dir = if probe.url.ends_with('/') {
+                        probe.url.clone()
+                    } else {
+                        format!("{}/", probe.url)
+                    };
+                    if visited_dirs.insert(dir.clone()) {
+                        queue.push((dir, depth + 1));
+                    }
+                }
+                discovered.push(DiscoveredPath {
+                    path: probe.url[base_url.len()..].to_string(),
+                    url: probe.url,
+                    status: probe.status,
+                    content_length: probe.content_length,
+                    redirect_location: probe.location,
+                    depth,
+                    is_directory,
+                });
+                if discovered.len() >= args.max_results {
+                    truncated = true;
+                    break;
+                }
+            }
+            if crate::cancellation::is_cancelled() {
+                return Err(ContentDiscoveryError::ScanFailed(
+                    "scan cancelled".to_string(),
+                ));
+            }
+            if truncated || backoff.summary().await.1 {
+                break;
+            }
+        }
+
+        let (backoffs, stopped_by_rate_limit) = backoff.summary().await;
+        discovered.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(ContentDiscoveryOutput {
+            base_url,
+            total_found: discovered.len(),
+            discovered,
+            requests_sent,
+            errors,
+            truncated,
+            rate_limited: backoffs > 0,
+            backoffs,
+            stopped_by_rate_limit,
+            scan_duration_ms: start.elapsed().as_millis() as u64,
+        })
+    }
+}
+
+impl Tool for ContentDiscoveryTool {
+    const NAME: &'static str = Self::NAME;
+    type Args = ContentDiscoveryArgs;
+    type Output = ContentDiscoveryOutput;
+    type Error = ContentDiscoveryError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: Self::DESCRIPTION.to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(ContentDiscoveryArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let words = Self::resolve_words(&args).await?;
+        Self::run(args, words).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Mock server exposing /admin (redirects to /admin/), /admin/config and /robots.txt
+    async fn spawn_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 2048];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                    let (status, extra, body) = match path.as_str() {
+                        "/admin" => ("301 Moved Permanently", "Location: /admin/\r\n", ""),
+                        "/admin/config" => ("200 OK", "", "debug=true"),
+                        "/robots.txt" => ("200 OK", "", "User-agent: *"),
+                        "/private" => ("403 Forbidden", "", "denied"),
+                        _ => ("404 Not Found", "", "not found"),
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        extra,
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        format!("http://{}/", addr)
+    }
+
+    fn args(base_url: &str, max_depth: usize) -> ContentDiscoveryArgs {
+        ContentDiscoveryArgs {
+            base_url: base_url.to_string(),
+            dictionary_id: None,
+            words: Some(
+                [
+                    "admin", "config", "robots", "private", "missing", "#comment",
+                ]
+                .iter()
+                .map(|w| w.to_string())
+                .collect(),
+            ),
+            extensions: vec!["txt".to_string()],
+            concurrency: 4,
+            rate_limit: 0,
+            match_status: default_match_status(),
+            filter_status: vec![403],
+            timeout_secs: 5,
+            max_depth,
+            max_results: default_max_results(),
+            backoff: BackoffConfig::default(),
+        }
+    }
+
+    /// Answers requests in `blocked` (by arrival order) with 429, then like `spawn_server`
+    async fn spawn_rate_limited_server(blocked: std::ops::Range<usize>) -> String {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let counter = Arc::new(AtomicUsize::new(0));
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let counter = counter.clone();
+                let blocked = blocked.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 2048];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                    let (status, extra, body) =
+                        if blocked.contains(&counter.fetch_add(1, Ordering::SeqCst)) {
+                            ("429 Too Many Requests", "Retry-After: 1\r\n", "slow down")
+                        } else {
+                            match path.as_str() {
+                                "/admin" => ("301 Moved Permanently", "Location: /admin/\r\n", ""),
+                                "/robots.txt" => ("200 OK", "", "User-agent: *"),
+                                _ => ("404 Not Found", "", "not found"),
+                            }
+                        };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        extra,
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        format!("http://{}/", addr)
+    }
+
+    fn backoff_args(base_url: &str, max_backoffs: usize) -> ContentDiscoveryArgs {
+        let mut args = args(base_url, 0);
+        args.concurrency = 1;
+        args.backoff = BackoffConfig {
+            block_threshold: 2,
+            soft_block_threshold: 20,
+            initial_backoff_ms: 20,
+            max_backoff_ms: 100,
+            max_backoffs,
+        };
+        args
+    }
+
+    fn paths(output: &ContentDiscoveryOutput) -> Vec<&str> {
+        output.discovered.iter().map(|d| d.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_candidates_expand_extensions() {
+        let words = vec![
+            "/admin".to_string(),
+            "index.php".to_string(),
+            "admin".to_string(),
+        ];
+        assert_eq!(
+            ContentDiscoveryTool::candidates(&words, &[".bak".to_string()]),
+            vec!["admin", "admin.bak", "index.php"]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_discovers_paths_on_mock_server() {
+        let base = spawn_server().await;
+
+        let output = ContentDiscoveryTool.call(args(&base, 0)).await.unwrap();
+        assert_eq!(paths(&output), vec!["admin", "robots.txt"]);
+        let admin = &output.discovered[0];
+        assert_eq!(admin.status, 301);
+        assert!(admin.is_directory);
+        assert_eq!(output.discovered[1].content_length, "User-agent: *".len());
+        assert!(!output.truncated);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_recursion_depth_limit() {
+        let base = spawn_server().await;
+
+        let output = ContentDiscoveryTool.call(args(&base, 1)).await.unwrap();
+        assert_eq!(paths(&output), vec!["admin", "admin/config", "robots.txt"]);
+        assert_eq!(output.discovered[1].depth, 1);
+
+        let mut limited = args(&base, 1);
+        limited.max_results = 1;
+        let output = ContentDiscoveryTool.call(limited).await.unwrap();
+        assert_eq!(output.total_found, 1);
+        assert!(output.truncated);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_backs_off_when_rate_limited() {
+        // Wildcard probe and two words pass, then four requests get 429
+        let base = spawn_rate_limited_server(3..7).await;
+
+        let output = ContentDiscoveryTool
+            .call(backoff_args(&base, 6))
+            .await
+            .unwrap();
+        assert!(output.rate_limited);
+        assert_eq!(output.backoffs, 2);
+        assert!(!output.stopped_by_rate_limit);
+        // Blocked requests were retried, so nothing is missed
+        assert_eq!(paths(&output), vec!["admin", "robots.txt"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stops_when_target_keeps_blocking() {
+        let base = spawn_rate_limited_server(0..usize::MAX).await;
+
+        let output = ContentDiscoveryTool
+            .call(backoff_args(&base, 2))
+            .await
+            .unwrap();
+        assert!(output.rate_limited);
+        assert_eq!(output.backoffs, 2);
+        assert!(output.stopped_by_rate_limit);
+        assert!(output.discovered.is_empty());
+    }
+
+    #[test]
+    fn test_soft_block_needs_identical_forbidden_run() {
+        let backoff = AdaptiveBackoff::new(BackoffConfig {
+            soft_block_threshold: 3,
+            ..Default::default()
+        });
+        let forbidden = |len| Probe {
+            url: String::new(),
+            status: 403,
+            content_length: len,
+            location: None,
+            block_signature: false,
+            retry_after: None,
+        };
+        let mut state = BackoffState::default();
+        assert!(!backoff.is_blocked(&mut state, &forbidden(10)));
+        assert!(!backoff.is_blocked(&mut state, &forbidden(12)));
+        assert!(!backoff.is_blocked(&mut state, &forbidden(12)));
+        assert!(backoff.is_blocked(&mut state, &forbidden(12)));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = chrono::DateTime::parse_from_rfc2822("Wed, 21 Oct 2026 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            parse_retry_after(" 120 ", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2026 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2026 07:27:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[tokio::test]
+    async fn test_blocked_probe_waits_before_retry() {
+        let backoff = AdaptiveBackoff::new(BackoffConfig {
+            block_threshold: 3,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1_000,
+            ..Default::default()
+        });
+        let limited = |retry_after: Option<u64>| Probe {
+            url: String::new(),
+            status: 429,
+            content_length: 0,
+            location: None,
+            block_signature: retry_after.is_some(),
+            retry_after: retry_after.map(Duration::from_secs),
+        };
+
+        // Below the threshold the delay doubles and Retry-After wins when longer (capped)
+        assert_eq!(
+            backoff.observe(&limited(None), 0).await,
+            Verdict::Retry(Duration::from_millis(100))
+        );
+        assert_eq!(
+            backoff.observe(&limited(Some(30)), 0).await,
+            Verdict::Retry(Duration::from_millis(1_000))
+        );
+        // At the threshold the whole scan pauses for at least Retry-After
+        let before = Instant::now();
+        assert_eq!(
+            backoff.observe(&limited(Some(1)), 0).await,
+            Verdict::Retry(Duration::ZERO)
+        );
+        let paused_until = backoff.state.lock().await.paused_until.unwrap();
+        assert!(paused_until >= before + Duration::from_millis(1_000));
+    }
+
+    #[tokio::test]
+    async fn test_requires_word_list() {
+        let mut no_words = args("http://127.0.0.1:1/", 0);
+        no_words.words = None;
+        assert!(matches!(
+            ContentDiscoveryTool.call(no_words).await,
+            Err(ContentDiscoveryError::Dictionary(_))
+        ));
+    }
+}
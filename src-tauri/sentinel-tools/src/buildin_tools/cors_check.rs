@@ -0,0 +1,478 @@
+//! CORS misconfiguration check using rig-core Tool trait
+//!
+//! Sends the same request with a series of crafted `Origin` headers (arbitrary
+//! site, `null`, trusted subdomain, trusted-domain prefix/suffix, plain http)
+//! and inspects `Access-Control-Allow-Origin` / `-Credentials`. Origins the
+//! server should not trust but reflects are reported as plugin `Finding`s,
+//! with reflected-origin-plus-credentials rated highest.
+
+use chrono::Utc;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use sentinel_core::domain::registrable_domain;
+use sentinel_plugins::{Confidence, Finding, Severity};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Attacker-controlled registrable domain used in crafted origins
+const ATTACKER_DOMAIN: &str = "cors-attacker.com";
+
+/// CORS check arguments
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct CorsCheckArgs {
+    /// Target URL (typically an authenticated API endpoint)
+    pub url: String,
+    /// HTTP method (default: GET)
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Per-request timeout in seconds (default: 10)
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout() -> u64 {
+    10
+}
+
+/// Kind of crafted origin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OriginProbe {
+    /// Unrelated site
+    Arbitrary,
+    /// `null` origin (sandboxed iframes, file://)
+    Null,
+    /// Subdomain of the target's domain
+    Subdomain,
+    /// Target domain followed by an attacker suffix (unanchored regex end)
+    TrustedPrefix,
+    /// Attacker name ending in the target domain (unanchored regex start)
+    TrustedSuffix,
+    /// Target origin over plain http
+    InsecureScheme,
+}
+
+impl OriginProbe {
+    const ALL: [OriginProbe; 6] = [
+        OriginProbe::Arbitrary,
+        OriginProbe::Null,
+        OriginProbe::Subdomain,
+        OriginProbe::TrustedPrefix,
+        OriginProbe::TrustedSuffix,
+        OriginProbe::InsecureScheme,
+    ];
+
+    /// Origin header value for a target host and its registrable domain
+    fn origin(&self, host: &str, domain: &str) -> String {
+        match self {
+            OriginProbe::Arbitrary => format!("https://{}", ATTACKER_DOMAIN),
+            OriginProbe::Null => "null".to_string(),
+            OriginProbe::Subdomain => format!("https://cors-probe.{}", domain),
+            OriginProbe::TrustedPrefix => format!("https://{}.{}", domain, ATTACKER_DOMAIN),
+            OriginProbe::TrustedSuffix => format!("https://cors-attacker{}", domain),
+            OriginProbe::InsecureScheme => format!("http://{}", host),
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            OriginProbe::Arbitrary => "CORS reflects arbitrary origin",
+            OriginProbe::Null => "CORS trusts null origin",
+            OriginProbe::Subdomain => "CORS trusts any subdomain",
+            OriginProbe::TrustedPrefix => "CORS origin check allows trusted-domain prefix",
+            OriginProbe::TrustedSuffix => "CORS origin check allows trusted-domain suffix",
+            OriginProbe::InsecureScheme => "CORS trusts insecure http origin",
+        }
+    }
+
+    /// Severity when the origin is allowed; None when it is not worth reporting
+    fn severity(&self, credentials: bool) -> Option<Severity> {
+        match (self, credentials) {
+            (
+                OriginProbe::Arbitrary
+                | OriginProbe::Null
+                | OriginProbe::TrustedPrefix
+                | OriginProbe::TrustedSuffix,
+                true,
+            ) => Some(Severity::High),
+            (
+                OriginProbe::Arbitrary
+                | OriginProbe::Null
+                | OriginProbe::TrustedPrefix
+                | OriginProbe::TrustedSuffix,
+                false,
+            ) => Some(Severity::Low),
+            (OriginProbe::InsecureScheme, true) => Some(Severity::Medium),
+            (OriginProbe::Subdomain, true) => Some(Severity::Low),
+            (OriginProbe::InsecureScheme | OriginProbe::Subdomain, false) => None,
+        }
+    }
+}
+
+/// Response of one probe
+#[derive(Debug, Clone, Serialize)]
+pub struct CorsProbeResult {
+    pub probe: OriginProbe,
+    pub origin: String,
+    pub status_code: u16,
+    pub allow_origin: Option<String>,
+    pub allow_credentials: bool,
+    /// The crafted origin was allowed
+    pub allowed: bool,
+}
+
+/// CORS check result
+#[derive(Debug, Clone, Serialize)]
+pub struct CorsCheckOutput {
+    pub url: String,
+    pub vulnerable: bool,
+    pub probes: Vec<CorsProbeResult>,
+    /// Findings for misconfigurations, compatible with `FindingDeduplicator`
+    pub findings: Vec<Finding>,
+}
+
+/// CORS check errors
+#[derive(Debug, thiserror::Error)]
+pub enum CorsCheckError {
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+    #[error("Request failed: {0}")]
+    RequestFailed(String),
+}
+
+/// CORS misconfiguration check tool
+#[derive(Debug, Clone, Default)]
+pub struct CorsCheckTool {
+    /// Client to use instead of one built from the global proxy settings
+    client: Option<reqwest::Client>,
+}
+
+impl CorsCheckTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_client(client: reqwest::Client) -> Self {
+        Self {
+            client: Some(client),
+        }
+    }
+
+    pub const NAME: &'static str = "cors_check";
+    pub const DESCRIPTION: &'static str = "Check a URL for CORS misconfigurations. Sends requests with crafted Origin headers (arbitrary site, null, subdomain, trusted-domain prefix/suffix, http scheme) and analyzes Access-Control-Allow-Origin/-Credentials. Reports findings such as reflected origin with credentials.";
+
+    async fn client(&self) -> Result<reqwest::Client, CorsCheckError> {
+        if let Some(client) = &self.client {
+            return Ok(client.clone());
+        }
+        let builder = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .redirect(reqwest::redirect::Policy::none());
+        let builder = sentinel_core::global_proxy::apply_proxy_to_client(builder).await;
+        let builder = sentinel_core::auth_session::apply_session_to_client(builder);
+        builder
+            .build()
+            .map_err(|e| CorsCheckError::RequestFailed(e.to_string()))
+    }
+
+    async fn probe(
+        client: &reqwest::Client,
+        method: &reqwest::Method,
+        url: &str,
+        probe: OriginProbe,
+        origin: String,
+        timeout: Duration,
+    ) -> Result<CorsProbeResult, CorsCheckError> {
+        let response = client
+            .request(method.clone(), url)
+            .header("Origin", &origin)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| CorsCheckError::RequestFailed(e.to_string()))?;
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .map(|v| String::from_utf8_lossy(v.as_bytes()).trim().to_string())
+        };
+        let allow_origin = header("access-control-allow-origin");
+        let allow_credentials = header("access-control-allow-credentials")
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+        let allowed = allow_origin.as_deref() == Some(origin.as_str());
+
+        Ok(CorsProbeResult {
+            probe,
+            origin,
+            status_code: response.status().as_u16(),
+            allow_origin,
+            allow_credentials,
+            allowed,
+        })
+    }
+
+    fn to_finding(
+        url: &str,
+        method: &str,
+        result: &CorsProbeResult,
+        severity: Severity,
+    ) -> Finding {
+        let credentials = if result.allow_credentials {
+            "\r\nAccess-Control-Allow-Credentials: true"
+        } else {
+            ""
+        };
+        let response_headers = format!(
+            "Access-Control-Allow-Origin: {}{}",
+            result.allow_origin.as_deref().unwrap_or_default(),
+            credentials
+        );
+        let impact = if result.allow_credentials {
+            "Pages on that origin can read authenticated responses with the victim's cookies."
+        } else {
+            "Pages on that origin can read unauthenticated responses from this endpoint."
+        };
+
+        Finding {
+            id: uuid::Uuid::new_v4().to_string(),
+            plugin_id: format!("builtin:{}", Self::NAME),
+            vuln_type: "cors_misconfiguration".to_string(),
+            severity,
+            title: result.probe.title().to_string(),
+            description: format!(
+                "The server allowed the crafted origin '{}'. {}",
+                result.origin, impact
+            ),
+            evidence: format!("Origin: {}\n{}", result.origin, response_headers),
+            location: "header:Access-Control-Allow-Origin".to_string(),
+            confidence: Confidence::High,
+            cwe: Some("CWE-942".to_string()),
+            owasp: Some("A05:2021".to_string()),
+            remediation: Some(
+                "Validate Origin against an exact allow-list of trusted origins; never reflect arbitrary or null origins, and only send Access-Control-Allow-Credentials for trusted https origins.".to_string(),
+            ),
+            url: url.to_string(),
+            method: method.to_string(),
+            created_at: Utc::now(),
+            request_headers: Some(format!("Origin: {}", result.origin)),
+            request_body: None,
+            response_status: Some(result.status_code as i32),
+            response_headers: Some(response_headers),
+            response_body: None,
+        }
+    }
+}
+
+impl Tool for CorsCheckTool {
+    const NAME: &'static str = Self::NAME;
+    type Args = CorsCheckArgs;
+    type Output = CorsCheckOutput;
+    type Error = CorsCheckError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: Self::DESCRIPTION.to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(CorsCheckArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let url = reqwest::Url::parse(args.url.trim())
+            .map_err(|e| CorsCheckError::InvalidUrl(format!("{}: {}", args.url, e)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(CorsCheckError::InvalidUrl(format!(
+                "unsupported scheme '{}'",
+                url.scheme()
+            )));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| CorsCheckError::InvalidUrl(args.url.clone()))?
+            .to_ascii_lowercase();
+        let host_with_port = match url.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.clone(),
+        };
+        // IPs and bare public suffixes fall back to the host itself
+        let domain = registrable_domain(&host).unwrap_or_else(|| host.clone());
+        let method_name = args
+            .method
+            .as_deref()
+            .unwrap_or("GET")
+            .trim()
+            .to_uppercase();
+        let method = reqwest::Method::from_bytes(method_name.as_bytes())
+            .map_err(|_| CorsCheckError::InvalidUrl(format!("method '{}'", method_name)))?;
+        let timeout = Duration::from_secs(args.timeout_secs.max(1));
+        let client = self.client().await?;
+
+        let mut probes = Vec::new();
+        let mut findings = Vec::new();
+        let mut last_error = None;
+        for probe in OriginProbe::ALL {
+            // Plain http is the target's own origin when it is not https
+            if probe == OriginProbe::InsecureScheme && url.scheme() != "https" {
+                continue;
+            }
+            let origin = probe.origin(&host_with_port, &domain);
+            let result =
+                match Self::probe(&client, &method, url.as_str(), probe, origin, timeout).await {
+                    Ok(result) => result,
+                    Err(e) => {
+                        tracing::debug!("CORS probe {:?} against {} failed: {}", probe, url, e);
+                        last_error = Some(e);
+                        continue;
+                    }
+                };
+            if result.allowed {
+                if let Some(severity) = probe.severity(result.allow_credentials) {
+                    findings.push(Self::to_finding(
+                        url.as_str(),
+                        &method_name,
+                        &result,
+                        severity,
+                    ));
+                }
+            }
+            probes.push(result);
+        }
+
+        if probes.is_empty() {
+            return Err(last_error
+                .unwrap_or_else(|| CorsCheckError::RequestFailed("no probe was sent".into())));
+        }
+
+        Ok(CorsCheckOutput {
+            url: url.to_string(),
+            vulnerable: !findings.is_empty(),
+            probes,
+            findings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Mock server
+    /// - `/vulnerable` reflects any Origin with credentials
+    /// - `/wildcard` answers `*` without credentials
+    /// - `/safe` only allows https://app.example.com
+    async fn spawn_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 2048];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let origin = request
+                    .lines()
+                    .find_map(|line| {
+                        let (key, value) = line.split_once(':')?;
+                        key.eq_ignore_ascii_case("origin")
+                            .then(|| value.trim().to_string())
+                    })
+                    .unwrap_or_default();
+                let cors = if request.starts_with("GET /vulnerable ") {
+                    format!(
+                        "Access-Control-Allow-Origin: {}\r\nAccess-Control-Allow-Credentials: true\r\n",
+                        origin
+                    )
+                } else if request.starts_with("GET /wildcard ") {
+                    "Access-Control-Allow-Origin: *\r\n".to_string()
+                } else if origin == "https://app.example.com" {
+                    "Access-Control-Allow-Origin: https://app.example.com\r\nAccess-Control-Allow-Credentials: true\r\n".to_string()
+                } else {
+                    String::new()
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\n{}Content-Length: 2\r\nConnection: close\r\n\r\n{{}}",
+                    cors
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    async fn check(url: String) -> CorsCheckOutput {
+        CorsCheckTool::with_client(reqwest::Client::new())
+            .call(CorsCheckArgs {
+                url,
+                method: None,
+                timeout_secs: 5,
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reflected_origin_with_credentials_is_flagged() {
+        let base = spawn_server().await;
+        let output = check(format!("{}/vulnerable", base)).await;
+
+        assert!(output.vulnerable);
+        let arbitrary = output
+            .findings
+            .iter()
+            .find(|f| f.title == OriginProbe::Arbitrary.title())
+            .expect("arbitrary origin finding");
+        assert_eq!(arbitrary.severity, Severity::High);
+        assert_eq!(arbitrary.cwe.as_deref(), Some("CWE-942"));
+        assert!(arbitrary
+            .evidence
+            .contains("Origin: https://cors-attacker.com"));
+        assert!(arbitrary
+            .evidence
+            .contains("Access-Control-Allow-Credentials: true"));
+        assert!(output
+            .findings
+            .iter()
+            .any(|f| f.title == OriginProbe::Null.title() && f.severity == Severity::High));
+        // InsecureScheme is skipped for an http target
+        assert_eq!(output.probes.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_safe_and_wildcard_configs_are_not_high() {
+        let base = spawn_server().await;
+
+        let safe = check(format!("{}/safe", base)).await;
+        assert!(!safe.vulnerable);
+        assert!(safe.probes.iter().all(|p| !p.allowed));
+
+        // `*` never matches a crafted origin and cannot carry credentials
+        let wildcard = check(format!("{}/wildcard", base)).await;
+        assert!(!wildcard.vulnerable);
+        assert_eq!(wildcard.probes[0].allow_origin.as_deref(), Some("*"));
+    }
+
+    #[test]
+    fn test_crafted_origins() {
+        let domain = registrable_domain("api.shop.example.com").unwrap();
+        assert_eq!(domain, "example.com");
+        assert_eq!(
+            registrable_domain("api.shop.example.co.uk").as_deref(),
+            Some("example.co.uk")
+        );
+        assert_eq!(
+            OriginProbe::TrustedPrefix.origin("api.shop.example.com", &domain),
+            "https://example.com.cors-attacker.com"
+        );
+        assert_eq!(
+            OriginProbe::TrustedSuffix.origin("api.shop.example.com", &domain),
+            "https://cors-attackerexample.com"
+        );
+        assert_eq!(
+            OriginProbe::InsecureScheme.origin("api.shop.example.com", &domain),
+            "http://api.shop.example.com"
+        );
+        assert_eq!(OriginProbe::Subdomain.severity(false), None);
+    }
+}
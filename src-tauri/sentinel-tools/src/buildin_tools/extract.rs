@@ -0,0 +1,459 @@
+//! Structured data extraction tool using rig-core Tool trait
+//!
+//! Pulls fields out of a large text blob (usually a previous tool's output)
+//! with a regex, a JSONPath expression or a CSS selector, so the agent only
+//! has to reason over the extracted matches.
+
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Extraction mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExtractMode {
+    /// Regular expression; each match reports its numbered and named capture groups
+    Regex,
+    /// JSONPath over JSON input (`$`, `.key`, `['key']`, `[n]`, `[*]`, `..key`)
+    Jsonpath,
+    /// CSS selector over HTML input
+    Css,
+}
+
+/// Extract arguments
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ExtractArgs {
+    /// Text to extract from
+    pub input: String,
+    /// Extraction mode: "regex", "jsonpath" or "css"
+    pub mode: ExtractMode,
+    /// Regex, JSONPath expression or CSS selector
+    pub pattern: String,
+    /// CSS mode only: return this attribute instead of the element text
+    #[serde(default)]
+    pub attribute: Option<String>,
+    /// Maximum number of matches to return
+    #[serde(default = "default_max_matches")]
+    pub max_matches: usize,
+}
+
+fn default_max_matches() -> usize {
+    100
+}
+
+/// Extract result
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtractOutput {
+    pub mode: ExtractMode,
+    pub count: usize,
+    pub matches: Vec<Value>,
+    /// More matches were found than `max_matches`
+    pub truncated: bool,
+}
+
+/// Extract errors
+#[derive(Debug, thiserror::Error)]
+pub enum ExtractError {
+    #[error("Invalid pattern: {0}")]
+    InvalidPattern(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+}
+
+/// Extract tool
+#[derive(Debug, Clone, Default)]
+pub struct ExtractTool;
+
+impl ExtractTool {
+    pub const NAME: &'static str = "extract";
+    pub const DESCRIPTION: &'static str = "Extract structured matches from text (e.g. a previous tool's output) using a regex with capture groups, a JSONPath expression over JSON, or a CSS selector over HTML. Returns only the matches as JSON.";
+}
+
+impl Tool for ExtractTool {
+    const NAME: &'static str = Self::NAME;
+    type Args = ExtractArgs;
+    type Output = ExtractOutput;
+    type Error = ExtractError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: Self::DESCRIPTION.to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(ExtractArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let mut matches = match args.mode {
+            ExtractMode::Regex => extract_regex(&args.input, &args.pattern)?,
+            ExtractMode::Jsonpath => extract_jsonpath(&args.input, &args.pattern)?,
+            ExtractMode::Css => extract_css(&args.input, &args.pattern, args.attribute.as_deref())?,
+        };
+
+        let truncated = matches.len() > args.max_matches;
+        matches.truncate(args.max_matches);
+        Ok(ExtractOutput {
+            mode: args.mode,
+            count: matches.len(),
+            matches,
+            truncated,
+        })
+    }
+}
+
+fn extract_regex(input: &str, pattern: &str) -> Result<Vec<Value>, ExtractError> {
+    let re = regex::Regex::new(pattern).map_err(|e| ExtractError::InvalidPattern(e.to_string()))?;
+    let names: Vec<Option<&str>> = re.capture_names().collect();
+
+    Ok(re
+        .captures_iter(input)
+        .map(|caps| {
+            let groups: Vec<Value> = caps
+                .iter()
+                .skip(1)
+                .map(|g| g.map_or(Value::Null, |m| Value::String(m.as_str().to_string())))
+                .collect();
+            let named: serde_json::Map<String, Value> = names
+                .iter()
+                .flatten()
+                .map(|name| {
+                    let value = caps
+                        .name(name)
+                        .map_or(Value::Null, |m| Value::String(m.as_str().to_string()));
+                    (name.to_string(), value)
+                })
+                .collect();
+            serde_json::json!({
+                "match": caps.get(0).map(|m| m.as_str()).unwrap_or_default(),
+                "groups": groups,
+                "named": named,
+            })
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(i64),
+    Wildcard,
+    Descendant(String),
+    DescendantWildcard,
+}
+
+fn parse_jsonpath(path: &str) -> Result<Vec<PathSegment>, ExtractError> {
+    let invalid = |msg: &str| ExtractError::InvalidPattern(format!("{} in '{}'", msg, path));
+    let chars: Vec<char> = path.trim().chars().collect();
+    let mut i = 0;
+    if chars.first() == Some(&'$') {
+        i = 1;
+    }
+
+    let read_name = |i: &mut usize| -> String {
+        let start = *i;
+        while *i < chars.len() && chars[*i] != '.' && chars[*i] != '[' {
+            *i += 1;
+        }
+        chars[start..*i].iter().collect()
+    };
+
+    let mut segments = Vec::new();
+    while i < chars.len() {
+        match chars[i] {
+            '.' if chars.get(i + 1) == Some(&'.') => {
+                i += 2;
+                if chars.get(i) == Some(&'*') {
+                    i += 1;
+                    segments.push(PathSegment::DescendantWildcard);
+                } else {
+                    let name = read_name(&mut i);
+                    if name.is_empty() {
+                        return Err(invalid("missing name after '..'"));
+                    }
+                    segments.push(PathSegment::Descendant(name));
+                }
+            }
+            '.' => {
+                i += 1;
+                if chars.get(i) == Some(&'*') {
+                    i += 1;
+                    segments.push(PathSegment::Wildcard);
+                } else {
+                    let name = read_name(&mut i);
+                    if name.is_empty() {
+                        return Err(invalid("missing name after '.'"));
+                    }
+                    segments.push(PathSegment::Key(name));
+                }
+            }
+            '[' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|c| *c == ']')
+                    .map(|p| i + p)
+                    .ok_or_else(|| invalid("unclosed '['"))?;
+                let inner: String = chars[i + 1..end].iter().collect();
+                let inner = inner.trim();
+                let segment = if inner == "*" {
+                    PathSegment::Wildcard
+                } else if let Some(key) = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+                {
+                    PathSegment::Key(key.to_string())
+                } else {
+                    PathSegment::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| invalid("unsupported bracket expression"))?,
+                    )
+                };
+                segments.push(segment);
+                i = end + 1;
+            }
+            _ if segments.is_empty() && i == 0 => {
+                // Bare leading key, e.g. `data.items`
+                let name = read_name(&mut i);
+                segments.push(PathSegment::Key(name));
+            }
+            c => return Err(invalid(&format!("unexpected '{}'", c))),
+        }
+    }
+    Ok(segments)
+}
+
+fn collect_descendants<'a>(value: &'a Value, out: &mut Vec<&'a Value>) {
+    match value {
+        Value::Object(map) => {
+            for child in map.values() {
+                out.push(child);
+                collect_descendants(child, out);
+            }
+        }
+        Value::Array(items) => {
+            for child in items {
+                out.push(child);
+                collect_descendants(child, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn children(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Object(map) => map.values().collect(),
+        Value::Array(items) => items.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn extract_jsonpath(input: &str, path: &str) -> Result<Vec<Value>, ExtractError> {
+    let segments = parse_jsonpath(path)?;
+    let root: Value =
+        serde_json::from_str(input).map_err(|e| ExtractError::InvalidInput(e.to_string()))?;
+
+    let mut current: Vec<&Value> = vec![&root];
+    for segment in &segments {
+        current = current
+            .into_iter()
+            .flat_map(|value| -> Vec<&Value> {
+                match segment {
+                    PathSegment::Key(key) => value.get(key.as_str()).into_iter().collect(),
+                    PathSegment::Index(index) => {
+                        let Some(items) = value.as_array() else {
+                            return Vec::new();
+                        };
+                        let resolved = if *index < 0 {
+                            items.len() as i64 + index
+                        } else {
+                            *index
+                        };
+                        usize::try_from(resolved)
+                            .ok()
+                            .and_then(|i| items.get(i))
+                            .into_iter()
+                            .collect()
+                    }
+                    PathSegment::Wildcard => children(value),
+                    PathSegment::Descendant(key) => {
+                        let mut all = vec![value];
+                        collect_descendants(value, &mut all);
+                        all.into_iter()
+                            .filter_map(|v| v.as_object().and_then(|m| m.get(key.as_str())))
+                            .collect()
+                    }
+                    PathSegment::DescendantWildcard => {
+                        let mut all = Vec::new();
+                        collect_descendants(value, &mut all);
+                        all
+                    }
+                }
+            })
+            .collect();
+    }
+    Ok(current.into_iter().cloned().collect())
+}
+
+fn extract_css(
+    input: &str,
+    selector: &str,
+    attribute: Option<&str>,
+) -> Result<Vec<Value>, ExtractError> {
+    use kuchikiki::traits::TendrilSink;
+
+    let document = kuchikiki::parse_html().one(input).document_node;
+    let selected = document
+        .select(selector)
+        .map_err(|_| ExtractError::InvalidPattern(format!("Invalid CSS selector: {}", selector)))?;
+
+    Ok(selected
+        .filter_map(|element| match attribute {
+            Some(name) => element
+                .attributes
+                .borrow()
+                .get(name)
+                .map(|v| Value::String(v.to_string())),
+            None => Some(serde_json::json!({
+                "tag": element.name.local.to_string(),
+                "text": element.text_contents().trim(),
+                "html": element.as_node().to_string(),
+            })),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn extract(
+        mode: ExtractMode,
+        input: &str,
+        pattern: &str,
+        attribute: Option<&str>,
+    ) -> ExtractOutput {
+        ExtractTool
+            .call(ExtractArgs {
+                input: input.to_string(),
+                mode,
+                pattern: pattern.to_string(),
+                attribute: attribute.map(str::to_string),
+                max_matches: default_max_matches(),
+            })
+            .await
+            .unwrap()
+    }
+
+    const NMAP: &str = "22/tcp   open  ssh     OpenSSH 8.9\n80/tcp   open  http    nginx 1.25\n443/tcp  closed https\n";
+
+    #[tokio::test]
+    async fn test_regex_capture_groups() {
+        let out = extract(
+            ExtractMode::Regex,
+            NMAP,
+            r"(?m)^(?P<port>\d+)/tcp\s+open\s+(\S+)",
+            None,
+        )
+        .await;
+        assert_eq!(out.count, 2);
+        assert_eq!(out.matches[0]["named"]["port"], "22");
+        assert_eq!(out.matches[1]["groups"], serde_json::json!(["80", "http"]));
+        assert!(!out.truncated);
+
+        let none = extract(ExtractMode::Regex, NMAP, r"\d+/udp", None).await;
+        assert_eq!(none.count, 0);
+        assert!(none.matches.is_empty());
+
+        let err = ExtractTool
+            .call(ExtractArgs {
+                input: NMAP.to_string(),
+                mode: ExtractMode::Regex,
+                pattern: "(".to_string(),
+                attribute: None,
+                max_matches: 10,
+            })
+            .await;
+        assert!(matches!(err, Err(ExtractError::InvalidPattern(_))));
+    }
+
+    #[tokio::test]
+    async fn test_jsonpath() {
+        let input = r#"{"data":{"endpoints":[{"path":"/login","method":"POST"},{"path":"/api/users","method":"GET"}]},"meta":{"path":"/"}}"#;
+
+        let paths = extract(
+            ExtractMode::Jsonpath,
+            input,
+            "$.data.endpoints[*].path",
+            None,
+        )
+        .await;
+        assert_eq!(paths.matches, vec!["/login", "/api/users"]);
+
+        let last = extract(
+            ExtractMode::Jsonpath,
+            input,
+            "$.data.endpoints[-1]['method']",
+            None,
+        )
+        .await;
+        assert_eq!(last.matches, vec!["GET"]);
+
+        let all = extract(ExtractMode::Jsonpath, input, "$..path", None).await;
+        assert_eq!(all.count, 3);
+
+        let none = extract(ExtractMode::Jsonpath, input, "$.data.missing[0]", None).await;
+        assert_eq!(none.count, 0);
+
+        let not_json = ExtractTool
+            .call(ExtractArgs {
+                input: "not json".to_string(),
+                mode: ExtractMode::Jsonpath,
+                pattern: "$.a".to_string(),
+                attribute: None,
+                max_matches: 10,
+            })
+            .await;
+        assert!(matches!(not_json, Err(ExtractError::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_css_selector() {
+        let html = r#"<html><body>
+            <a href="/admin" class="nav">Admin</a>
+            <a href="/logout" class="nav">Log out</a>
+            <form action="/login"><input name="user"></form>
+        </body></html>"#;
+
+        let links = extract(ExtractMode::Css, html, "a.nav", None).await;
+        assert_eq!(links.count, 2);
+        assert_eq!(links.matches[0]["tag"], "a");
+        assert_eq!(links.matches[1]["text"], "Log out");
+
+        let hrefs = extract(ExtractMode::Css, html, "a.nav", Some("href")).await;
+        assert_eq!(hrefs.matches, vec!["/admin", "/logout"]);
+
+        let none = extract(ExtractMode::Css, html, "table td", None).await;
+        assert_eq!(none.count, 0);
+        assert!(!none.truncated);
+    }
+
+    #[tokio::test]
+    async fn test_max_matches_truncates() {
+        let out = ExtractTool
+            .call(ExtractArgs {
+                input: NMAP.to_string(),
+                mode: ExtractMode::Regex,
+                pattern: r"\d+/tcp".to_string(),
+                attribute: None,
+                max_matches: 1,
+            })
+            .await
+            .unwrap();
+        assert_eq!(out.count, 1);
+        assert!(out.truncated);
+    }
+}
@@ -26,6 +26,10 @@ pub struct HttpRequestArgs {
     /// Follow redirects
     #[serde(default = "default_follow_redirects")]
     pub follow_redirects: bool,
+    /// Proxy for this request only: a proxy URL (http/https/socks5/socks5h),
+    /// or "direct" / "none" to bypass the global proxy. Defaults to the global proxy.
+    #[serde(default)]
+    pub proxy: Option<String>,
 }
 
 fn default_method() -> String {
@@ -61,6 +65,62 @@ pub enum HttpRequestError {
     RequestFailed(String),
     #[error("Timeout: {0}")]
     Timeout(String),
+    #[error("Invalid proxy: {0}")]
+    InvalidProxy(String),
+}
+
+const PROXY_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+/// Per-request proxy override
+#[derive(Debug, Clone, PartialEq)]
+enum ProxyOverride {
+    /// Connect without any proxy
+    Direct,
+    /// Route through this proxy URL
+    Proxy(String),
+}
+
+impl ProxyOverride {
+    fn parse(value: &str) -> Result<Self, HttpRequestError> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("direct") || value.eq_ignore_ascii_case("none") {
+            return Ok(Self::Direct);
+        }
+        let url = reqwest::Url::parse(value)
+            .map_err(|e| HttpRequestError::InvalidProxy(format!("{}: {}", value, e)))?;
+        if !PROXY_SCHEMES.contains(&url.scheme()) {
+            return Err(HttpRequestError::InvalidProxy(format!(
+                "unsupported scheme '{}', expected one of {}",
+                url.scheme(),
+                PROXY_SCHEMES.join(", ")
+            )));
+        }
+        if url.host_str().is_none() {
+            return Err(HttpRequestError::InvalidProxy(format!(
+                "{}: missing host",
+                value
+            )));
+        }
+        Ok(Self::Proxy(value.to_string()))
+    }
+
+    /// Client that ignores the global proxy (and proxy env vars)
+    fn build_client(&self) -> Result<reqwest::Client, HttpRequestError> {
+        let builder = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .default_headers(sentinel_core::global_proxy::sentinel_headers());
+        let builder = match self {
+            Self::Direct => builder.no_proxy(),
+            Self::Proxy(url) => builder.proxy(
+                reqwest::Proxy::all(url)
+                    .map_err(|e| HttpRequestError::InvalidProxy(e.to_string()))?,
+            ),
+        };
+        let builder = sentinel_core::auth_session::apply_session_to_client(builder);
+        builder
+            .build()
+            .map_err(|e| HttpRequestError::RequestFailed(e.to_string()))
+    }
 }
 
 /// HTTP request tool
@@ -76,6 +136,7 @@ impl Default for HttpRequestTool {
             tokio::runtime::Handle::current().block_on(async {
                 let builder = reqwest::Client::builder().danger_accept_invalid_certs(true);
                 let builder = sentinel_core::global_proxy::apply_proxy_to_client(builder).await;
+                let builder = sentinel_core::auth_session::apply_session_to_client(builder);
                 builder.build().unwrap_or_default()
             })
         });
@@ -119,15 +180,22 @@ impl Tool for HttpRequestTool {
         let url = reqwest::Url::parse(&args.url)
             .map_err(|e| HttpRequestError::InvalidUrl(e.to_string()))?;
 
+        // Per-request proxy override, otherwise the shared client with the global proxy
+        let override_client = match args.proxy.as_deref().map(str::trim) {
+            Some(proxy) if !proxy.is_empty() => Some(ProxyOverride::parse(proxy)?.build_client()?),
+            _ => None,
+        };
+        let client = override_client.as_ref().unwrap_or(&self.client);
+
         // Build request
         let method = args.method.to_uppercase();
         let mut request = match method.as_str() {
-            "GET" => self.client.get(url.clone()),
-            "POST" => self.client.post(url.clone()),
-            "PUT" => self.client.put(url.clone()),
-            "DELETE" => self.client.delete(url.clone()),
-            "HEAD" => self.client.head(url.clone()),
-            "PATCH" => self.client.patch(url.clone()),
+            "GET" => client.get(url.clone()),
+            "POST" => client.post(url.clone()),
+            "PUT" => client.put(url.clone()),
+            "DELETE" => client.delete(url.clone()),
+            "HEAD" => client.head(url.clone()),
+            "PATCH" => client.patch(url.clone()),
             _ => {
                 return Err(HttpRequestError::RequestFailed(format!(
                     "Unsupported method: {}",
@@ -211,3 +279,112 @@ impl Tool for HttpRequestTool {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sentinel_core::global_proxy::{clear_global_proxy, set_global_proxy, GlobalProxyConfig};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Minimal HTTP server answering every request with "ok"
+    async fn spawn_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                    )
+                    .await;
+            }
+        });
+        format!("http://{}/", addr)
+    }
+
+    fn args(url: &str, proxy: Option<&str>) -> HttpRequestArgs {
+        HttpRequestArgs {
+            url: url.to_string(),
+            method: default_method(),
+            headers: HashMap::new(),
+            body: None,
+            timeout_secs: 5,
+            follow_redirects: true,
+            proxy: proxy.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_parse_proxy_override() {
+        assert_eq!(
+            ProxyOverride::parse("direct").unwrap(),
+            ProxyOverride::Direct
+        );
+        assert_eq!(ProxyOverride::parse("NONE").unwrap(), ProxyOverride::Direct);
+        assert!(matches!(
+            ProxyOverride::parse("socks5h://127.0.0.1:1080").unwrap(),
+            ProxyOverride::Proxy(_)
+        ));
+        assert!(matches!(
+            ProxyOverride::parse("ftp://127.0.0.1:21"),
+            Err(HttpRequestError::InvalidProxy(_))
+        ));
+        assert!(matches!(
+            ProxyOverride::parse("not a url"),
+            Err(HttpRequestError::InvalidProxy(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_direct_override_bypasses_global_proxy() {
+        let url = spawn_server().await;
+
+        // Global proxy points at a closed port, so proxied requests fail
+        let dead = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let dead_port = dead.local_addr().unwrap().port();
+        drop(dead);
+        set_global_proxy(GlobalProxyConfig {
+            enabled: true,
+            host: Some("127.0.0.1".to_string()),
+            port: Some(dead_port),
+            ..Default::default()
+        })
+        .await;
+
+        let tool = HttpRequestTool::new();
+        let via_global = tool.call(args(&url, None)).await;
+        let direct = tool.call(args(&url, Some("direct"))).await;
+        clear_global_proxy().await;
+
+        assert!(via_global.is_err(), "global proxy is down");
+        let direct = direct.unwrap();
+        assert_eq!(direct.status_code, 200);
+        assert_eq!(direct.body, "ok");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_override_client_sends_sentinel_header() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 2048];
+            let n = socket.read(&mut buf).await.unwrap();
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .await;
+            String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase()
+        });
+
+        let response = HttpRequestTool::new()
+            .call(args(&url, Some("direct")))
+            .await
+            .unwrap();
+        assert_eq!(response.status_code, 200);
+        let request = server.await.unwrap();
+        assert!(request.contains("x-sentinel-internal: true"), "{}", request);
+    }
+}
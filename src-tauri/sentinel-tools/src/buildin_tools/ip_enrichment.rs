@@ -0,0 +1,670 @@
+//! IP enrichment tool using rig-core Tool trait
+//!
+//! Resolves a host to its IP addresses and looks up ASN, organization and
+//! rough geolocation for each. The data source is configurable: an online
+//! ipinfo-compatible API (through the global proxy) or local MaxMind
+//! GeoLite2/GeoIP2 databases. Lookups are cached per IP for a configurable TTL
+//! in a bounded cache.
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+const DEFAULT_API_URL: &str = "https://ipinfo.io";
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+/// Maximum number of cached IPs
+const CACHE_CAPACITY: u64 = 10_000;
+/// Placeholder returned to the frontend instead of the API token
+pub const MASKED_TOKEN: &str = "********";
+
+/// Where lookups come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpDataProvider {
+    /// ipinfo-compatible HTTP API
+    #[default]
+    Online,
+    /// Local MaxMind databases
+    Maxmind,
+}
+
+/// IP enrichment settings (stored as `tools/ip_enrichment` config)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IpEnrichmentConfig {
+    pub provider: IpDataProvider,
+    /// Base URL of the online API (default: https://ipinfo.io)
+    pub api_url: Option<String>,
+    pub api_token: Option<String>,
+    /// GeoLite2-City / GeoIP2-City database
+    pub city_db_path: Option<String>,
+    /// GeoLite2-ASN database
+    pub asn_db_path: Option<String>,
+    pub timeout_secs: u64,
+    pub cache_ttl_secs: u64,
+}
+
+impl IpEnrichmentConfig {
+    /// Replace the API token with a placeholder for display
+    pub fn masked(mut self) -> Self {
+        if self.api_token.as_deref().is_some_and(|t| !t.is_empty()) {
+            self.api_token = Some(MASKED_TOKEN.to_string());
+        }
+        self
+    }
+
+    /// Keep the stored token when the frontend sends the placeholder back
+    pub fn with_stored_token(mut self, stored: &IpEnrichmentConfig) -> Self {
+        if self.api_token.as_deref() == Some(MASKED_TOKEN) {
+            self.api_token = stored.api_token.clone();
+        }
+        self
+    }
+}
+
+impl Default for IpEnrichmentConfig {
+    fn default() -> Self {
+        Self {
+            provider: IpDataProvider::Online,
+            api_url: None,
+            api_token: None,
+            city_db_path: None,
+            asn_db_path: None,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            cache_ttl_secs: DEFAULT_CACHE_TTL_SECS,
+        }
+    }
+}
+
+/// Enrichment data for one IP
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct IpInfo {
+    pub ip: String,
+    pub asn: Option<u32>,
+    pub as_org: Option<String>,
+    /// ISO 3166-1 alpha-2 country code
+    pub country: Option<String>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// Served from the lookup cache
+    pub cached: bool,
+    /// Why this IP could not be enriched
+    pub error: Option<String>,
+}
+
+/// IP enrichment arguments
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct IpEnrichmentArgs {
+    /// IP address, hostname or URL
+    pub target: String,
+}
+
+/// IP enrichment output
+#[derive(Debug, Clone, Serialize)]
+pub struct IpEnrichmentOutput {
+    pub target: String,
+    /// Data source name ("ipinfo", "maxmind", ...)
+    pub source: String,
+    pub results: Vec<IpInfo>,
+}
+
+/// IP enrichment errors
+#[derive(Debug, thiserror::Error)]
+pub enum IpEnrichmentError {
+    #[error("Invalid target: {0}")]
+    InvalidTarget(String),
+    #[error("Resolution failed: {0}")]
+    Resolution(String),
+    #[error("Configuration error: {0}")]
+    Config(String),
+}
+
+/// Backend answering ASN/geolocation queries
+#[async_trait]
+pub trait IpInfoSource: Send + Sync {
+    fn name(&self) -> &str;
+    async fn lookup(&self, ip: IpAddr) -> anyhow::Result<IpInfo>;
+}
+
+/// ipinfo-compatible HTTP API (`GET {base}/{ip}/json`)
+pub struct OnlineIpSource {
+    base_url: String,
+    token: Option<String>,
+    timeout: Duration,
+}
+
+impl OnlineIpSource {
+    pub fn new(base_url: impl Into<String>, token: Option<String>, timeout: Duration) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token,
+            timeout,
+        }
+    }
+}
+
+/// Split ipinfo's `org` field ("AS15169 Google LLC") into number and name
+fn parse_as_org(org: &str) -> (Option<u32>, Option<String>) {
+    let org = org.trim();
+    match org.split_once(' ') {
+        Some((asn, name)) if asn.starts_with("AS") => (
+            asn[2..].parse().ok(),
+            Some(name.trim().to_string()).filter(|n| !n.is_empty()),
+        ),
+        _ => match org.strip_prefix("AS").and_then(|n| n.parse().ok()) {
+            Some(asn) => (Some(asn), None),
+            None => (None, Some(org.to_string()).filter(|o| !o.is_empty())),
+        },
+    }
+}
+
+#[async_trait]
+impl IpInfoSource for OnlineIpSource {
+    fn name(&self) -> &str {
+        "ipinfo"
+    }
+
+    async fn lookup(&self, ip: IpAddr) -> anyhow::Result<IpInfo> {
+        let builder = reqwest::Client::builder().timeout(self.timeout);
+        let client = sentinel_core::global_proxy::apply_proxy_to_client(builder)
+            .await
+            .build()?;
+        let mut request = client.get(format!("{}/{}/json", self.base_url, ip));
+        if let Some(token) = self.token.as_deref().filter(|t| !t.is_empty()) {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            anyhow::bail!("{} returned {}", self.base_url, response.status());
+        }
+        let body: Value = response.json().await?;
+        if let Some(error) = body.get("error") {
+            anyhow::bail!("{}", error);
+        }
+
+        let text = |key: &str| {
+            body[key]
+                .as_str()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let (asn, as_org) = text("org")
+            .map(|org| parse_as_org(&org))
+            .unwrap_or_default();
+        let (latitude, longitude) = text("loc")
+            .and_then(|loc| {
+                let (lat, lon) = loc.split_once(',')?;
+                Some((lat.trim().parse().ok()?, lon.trim().parse().ok()?))
+            })
+            .map_or((None, None), |(lat, lon)| (Some(lat), Some(lon)));
+
+        Ok(IpInfo {
+            ip: ip.to_string(),
+            asn,
+            as_org,
+            country: text("country"),
+            region: text("region"),
+            city: text("city"),
+            latitude,
+            longitude,
+            ..Default::default()
+        })
+    }
+}
+
+/// Local MaxMind databases, loaded once into memory
+pub struct MaxmindIpSource {
+    city: Option<maxminddb::Reader<Vec<u8>>>,
+    asn: Option<maxminddb::Reader<Vec<u8>>>,
+}
+
+impl MaxmindIpSource {
+    pub fn open(
+        city_db_path: Option<&str>,
+        asn_db_path: Option<&str>,
+    ) -> Result<Self, IpEnrichmentError> {
+        let open = |path: Option<&str>| {
+            path.filter(|p| !p.trim().is_empty())
+                .map(|p| {
+                    maxminddb::Reader::open_readfile(PathBuf::from(p))
+                        .map_err(|e| IpEnrichmentError::Config(format!("{}: {}", p, e)))
+                })
+                .transpose()
+        };
+        let source = Self {
+            city: open(city_db_path)?,
+            asn: open(asn_db_path)?,
+        };
+        if source.city.is_none() && source.asn.is_none() {
+            return Err(IpEnrichmentError::Config(
+                "no MaxMind database path configured".to_string(),
+            ));
+        }
+        Ok(source)
+    }
+}
+
+#[async_trait]
+impl IpInfoSource for MaxmindIpSource {
+    fn name(&self) -> &str {
+        "maxmind"
+    }
+
+    async fn lookup(&self, ip: IpAddr) -> anyhow::Result<IpInfo> {
+        let mut info = IpInfo {
+            ip: ip.to_string(),
+            ..Default::default()
+        };
+        if let Some(reader) = &self.asn {
+            if let Ok(asn) = reader.lookup::<maxminddb::geoip2::Asn>(ip) {
+                info.asn = asn.autonomous_system_number;
+                info.as_org = asn.autonomous_system_organization.map(str::to_string);
+            }
+        }
+        if let Some(reader) = &self.city {
+            if let Ok(city) = reader.lookup::<maxminddb::geoip2::City>(ip) {
+                let english = |names: Option<std::collections::BTreeMap<&str, &str>>| {
+                    names.and_then(|n| n.get("en").map(|s| s.to_string()))
+                };
+                info.country = city.country.and_then(|c| c.iso_code).map(str::to_string);
+                info.region = city
+                    .subdivisions
+                    .and_then(|s| s.into_iter().next())
+                    .and_then(|s| english(s.names));
+                info.city = city.city.and_then(|c| english(c.names));
+                if let Some(location) = city.location {
+                    info.latitude = location.latitude;
+                    info.longitude = location.longitude;
+                }
+            }
+        }
+        Ok(info)
+    }
+}
+
+/// Per-IP lookup cache with a fixed TTL and bounded size
+pub struct LookupCache {
+    entries: moka::sync::Cache<IpAddr, IpInfo>,
+}
+
+impl LookupCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self::with_capacity(ttl, CACHE_CAPACITY)
+    }
+
+    fn with_capacity(ttl: Duration, capacity: u64) -> Self {
+        Self {
+            entries: moka::sync::Cache::builder()
+                .max_capacity(capacity)
+                .time_to_live(ttl)
+                .build(),
+        }
+    }
+
+    fn get(&self, ip: &IpAddr) -> Option<IpInfo> {
+        self.entries.get(ip)
+    }
+
+    fn insert(&self, ip: IpAddr, info: IpInfo) {
+        self.entries.insert(ip, info);
+    }
+}
+
+struct ActiveSource {
+    source: Arc<dyn IpInfoSource>,
+    cache: Arc<LookupCache>,
+}
+
+impl ActiveSource {
+    fn build(config: &IpEnrichmentConfig) -> Result<Self, IpEnrichmentError> {
+        let source: Arc<dyn IpInfoSource> = match config.provider {
+            IpDataProvider::Online => Arc::new(OnlineIpSource::new(
+                config.api_url.as_deref().unwrap_or(DEFAULT_API_URL),
+                config.api_token.clone(),
+                Duration::from_secs(config.timeout_secs.max(1)),
+            )),
+            IpDataProvider::Maxmind => Arc::new(MaxmindIpSource::open(
+                config.city_db_path.as_deref(),
+                config.asn_db_path.as_deref(),
+            )?),
+        };
+        Ok(Self {
+            source,
+            cache: Arc::new(LookupCache::new(Duration::from_secs(config.cache_ttl_secs))),
+        })
+    }
+}
+
+static ACTIVE_SOURCE: Lazy<RwLock<Option<ActiveSource>>> = Lazy::new(|| RwLock::new(None));
+
+/// Apply a new configuration; the cache is reset since results may differ
+pub async fn set_ip_enrichment_config(
+    config: &IpEnrichmentConfig,
+) -> Result<(), IpEnrichmentError> {
+    let active = ActiveSource::build(config)?;
+    *ACTIVE_SOURCE.write().await = Some(active);
+    Ok(())
+}
+
+/// Extract an IP or hostname from an IP, host[:port] or URL
+fn parse_target(target: &str) -> Result<Result<IpAddr, String>, IpEnrichmentError> {
+    let target = target.trim();
+    let host = match reqwest::Url::parse(target) {
+        Ok(url) if url.has_host() => url.host_str().unwrap_or_default().to_string(),
+        _ => target.to_string(),
+    };
+    let host = host.trim_matches(['[', ']']);
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(Ok(ip));
+    }
+    let host = host
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('.');
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(Ok(ip));
+    }
+    if host.is_empty()
+        || !host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+    {
+        return Err(IpEnrichmentError::InvalidTarget(target.to_string()));
+    }
+    Ok(Err(host.to_ascii_lowercase()))
+}
+
+/// Addresses that no public data source knows about
+fn is_non_public(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            v4.is_private()
+                || v4.is_loopback()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                // 100.64.0.0/10 carrier-grade NAT
+                || (v4.octets()[0] == 100 && (v4.octets()[1] & 0xc0) == 64)
+        }
+        IpAddr::V6(v6) => {
+            v6.is_loopback()
+                || v6.is_unspecified()
+                // fc00::/7 unique local, fe80::/10 link local
+                || (v6.segments()[0] & 0xfe00) == 0xfc00
+                || (v6.segments()[0] & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// IP ASN/geolocation enrichment tool
+#[derive(Clone, Default)]
+pub struct IpEnrichmentTool {
+    /// Fixed source and cache; the configured global source when None
+    source: Option<(Arc<dyn IpInfoSource>, Arc<LookupCache>)>,
+}
+
+impl IpEnrichmentTool {
+    pub const NAME: &'static str = "ip_enrichment";
+    pub const DESCRIPTION: &'static str = "Resolve a host (or take an IP/URL) and look up the ASN, AS organization and rough geolocation (country, region, city, coordinates) of each IP. Useful for triage: identifying hosting/CDN providers and where assets live.";
+
+    /// Use a specific source with its own cache
+    pub fn with_source(source: Arc<dyn IpInfoSource>, cache_ttl: Duration) -> Self {
+        Self {
+            source: Some((source, Arc::new(LookupCache::new(cache_ttl)))),
+        }
+    }
+
+    async fn active_source(
+        &self,
+    ) -> Result<(Arc<dyn IpInfoSource>, Arc<LookupCache>), IpEnrichmentError> {
+        if let Some((source, cache)) = &self.source {
+            return Ok((source.clone(), cache.clone()));
+        }
+        if let Some(active) = ACTIVE_SOURCE.read().await.as_ref() {
+            return Ok((active.source.clone(), active.cache.clone()));
+        }
+        // Not configured yet: fall back to the default online API
+        let mut guard = ACTIVE_SOURCE.write().await;
+        let active = match guard.take() {
+            Some(active) => active,
+            None => ActiveSource::build(&IpEnrichmentConfig::default())?,
+        };
+        let pair = (active.source.clone(), active.cache.clone());
+        *guard = Some(active);
+        Ok(pair)
+    }
+
+    async fn resolve(target: &str) -> Result<Vec<IpAddr>, IpEnrichmentError> {
+        match parse_target(target)? {
+            Ok(ip) => Ok(vec![ip]),
+            Err(host) => {
+                let addrs = tokio::net::lookup_host((host.as_str(), 0))
+                    .await
+                    .map_err(|e| IpEnrichmentError::Resolution(format!("{}: {}", host, e)))?;
+                let mut ips: Vec<IpAddr> = Vec::new();
+                for addr in addrs {
+                    if !ips.contains(&addr.ip()) {
+                        ips.push(addr.ip());
+                    }
+                }
+                if ips.is_empty() {
+                    return Err(IpEnrichmentError::Resolution(format!(
+                        "{}: no addresses",
+                        host
+                    )));
+                }
+                Ok(ips)
+            }
+        }
+    }
+}
+
+impl Tool for IpEnrichmentTool {
+    const NAME: &'static str = Self::NAME;
+    type Args = IpEnrichmentArgs;
+    type Output = IpEnrichmentOutput;
+    type Error = IpEnrichmentError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: Self::DESCRIPTION.to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(IpEnrichmentArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (source, cache) = self.active_source().await?;
+        let ips = Self::resolve(&args.target).await?;
+
+        let mut results = Vec::with_capacity(ips.len());
+        for ip in ips {
+            if is_non_public(&ip) {
+                results.push(IpInfo {
+                    ip: ip.to_string(),
+                    error: Some("private or reserved address".to_string()),
+                    ..Default::default()
+                });
+                continue;
+            }
+            if let Some(mut info) = cache.get(&ip) {
+                info.cached = true;
+                results.push(info);
+                continue;
+            }
+            match source.lookup(ip).await {
+                Ok(info) => {
+                    cache.insert(ip, info.clone());
+                    results.push(info);
+                }
+                // Failures are not cached so a transient error can be retried
+                Err(e) => results.push(IpInfo {
+                    ip: ip.to_string(),
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                }),
+            }
+        }
+
+        Ok(IpEnrichmentOutput {
+            target: args.target,
+            source: source.name().to_string(),
+            results,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Mock source counting how often it is queried
+    #[derive(Default)]
+    struct CountingSource {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl IpInfoSource for CountingSource {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        async fn lookup(&self, ip: IpAddr) -> anyhow::Result<IpInfo> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(IpInfo {
+                ip: ip.to_string(),
+                asn: Some(15169),
+                as_org: Some("Google LLC".to_string()),
+                country: Some("US".to_string()),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_prevents_duplicate_lookups() {
+        let source = Arc::new(CountingSource::default());
+        let tool = IpEnrichmentTool::with_source(source.clone(), Duration::from_secs(60));
+
+        let first = tool
+            .call(IpEnrichmentArgs {
+                target: "8.8.8.8".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(first.source, "mock");
+        assert_eq!(first.results.len(), 1);
+        assert_eq!(first.results[0].asn, Some(15169));
+        assert_eq!(first.results[0].as_org.as_deref(), Some("Google LLC"));
+        assert!(!first.results[0].cached);
+
+        let second = tool
+            .call(IpEnrichmentArgs {
+                target: "https://8.8.8.8:443/dns-query".to_string(),
+            })
+            .await
+            .unwrap();
+        assert!(second.results[0].cached);
+        assert_eq!(second.results[0].country.as_deref(), Some("US"));
+        assert_eq!(source.calls.load(Ordering::SeqCst), 1);
+
+        // Expired entries are looked up again
+        let tool = IpEnrichmentTool::with_source(source.clone(), Duration::ZERO);
+        for _ in 0..2 {
+            tool.call(IpEnrichmentArgs {
+                target: "1.1.1.1".to_string(),
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(source.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_private_addresses_are_not_looked_up() {
+        let source = Arc::new(CountingSource::default());
+        let tool = IpEnrichmentTool::with_source(source.clone(), Duration::from_secs(60));
+        let output = tool
+            .call(IpEnrichmentArgs {
+                target: "192.168.1.10".to_string(),
+            })
+            .await
+            .unwrap();
+        assert!(output.results[0].error.is_some());
+        assert_eq!(source.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_cache_is_bounded() {
+        let cache = LookupCache::with_capacity(Duration::from_secs(60), 2);
+        for i in 1..=10u8 {
+            let ip = IpAddr::from([8, 8, 8, i]);
+            cache.insert(
+                ip,
+                IpInfo {
+                    ip: ip.to_string(),
+                    ..Default::default()
+                },
+            );
+        }
+        cache.entries.run_pending_tasks();
+        assert!(cache.entries.entry_count() <= 2);
+    }
+
+    #[test]
+    fn test_api_token_is_masked() {
+        let stored = IpEnrichmentConfig {
+            api_token: Some("secret-token".to_string()),
+            ..Default::default()
+        };
+        let masked = stored.clone().masked();
+        assert_eq!(masked.api_token.as_deref(), Some(MASKED_TOKEN));
+        assert_eq!(masked.clone().with_stored_token(&stored), stored);
+
+        let replaced = IpEnrichmentConfig {
+            api_token: Some("new-token".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            replaced.with_stored_token(&stored).api_token.as_deref(),
+            Some("new-token")
+        );
+        assert_eq!(IpEnrichmentConfig::default().masked().api_token, None);
+    }
+
+    #[test]
+    fn test_parse_target_and_as_org() {
+        assert_eq!(
+            parse_target("[2001:4860::8888]").unwrap(),
+            Ok("2001:4860::8888".parse().unwrap())
+        );
+        assert_eq!(
+            parse_target("Example.com:8443").unwrap(),
+            Err("example.com".to_string())
+        );
+        assert!(parse_target("bad host!").is_err());
+
+        assert_eq!(
+            parse_as_org("AS13335 Cloudflare, Inc."),
+            (Some(13335), Some("Cloudflare, Inc.".to_string()))
+        );
+        assert_eq!(parse_as_org("AS64512"), (Some(64512), None));
+    }
+}
@@ -0,0 +1,742 @@
+//! JWT decode/analyze tool using rig-core Tool trait
+//!
+//! Decodes the header and claims of a JSON Web Token without trusting it,
+//! reports common weaknesses (`alg: none`, symmetric or unknown algorithms,
+//! missing/expired `exp`, key-injection headers) and optionally verifies the
+//! signature against a shared secret or a JWKS.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Utc};
+use rig::tool::Tool;
+use ring::signature;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Lifetimes longer than this are reported as excessive
+const MAX_REASONABLE_LIFETIME_SECS: i64 = 30 * 24 * 3600;
+
+/// Claim names that should never be carried in a readable token
+const SENSITIVE_CLAIMS: &[&str] = &[
+    "password",
+    "passwd",
+    "pwd",
+    "secret",
+    "client_secret",
+    "api_key",
+    "apikey",
+    "private_key",
+    "credit_card",
+    "ssn",
+];
+
+/// JWT arguments
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct JwtArgs {
+    /// The JWT to analyze (a leading "Bearer " is ignored)
+    pub token: String,
+    /// Shared secret for HS256/HS384/HS512 signature verification
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// JWKS (or a single JWK) as JSON for signature verification
+    #[serde(default)]
+    pub jwks: Option<String>,
+}
+
+/// Observation severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ObservationSeverity {
+    Info,
+    Low,
+    Medium,
+    High,
+}
+
+/// A single security observation about the token
+#[derive(Debug, Clone, Serialize)]
+pub struct JwtObservation {
+    pub severity: ObservationSeverity,
+    pub code: String,
+    pub message: String,
+}
+
+/// Signature verification result
+#[derive(Debug, Clone, Serialize)]
+pub struct JwtSignatureCheck {
+    pub verified: bool,
+    /// "secret" or "jwks"
+    pub method: String,
+    /// `kid` of the JWK that verified the signature
+    pub key_id: Option<String>,
+    pub error: Option<String>,
+}
+
+/// JWT analysis result
+#[derive(Debug, Clone, Serialize)]
+pub struct JwtOutput {
+    pub header: Value,
+    pub claims: Value,
+    /// Signature segment as it appears in the token (base64url)
+    pub signature: String,
+    pub algorithm: Option<String>,
+    pub expires_at: Option<String>,
+    pub issued_at: Option<String>,
+    pub not_before: Option<String>,
+    pub expired: Option<bool>,
+    pub signature_check: Option<JwtSignatureCheck>,
+    pub observations: Vec<JwtObservation>,
+}
+
+/// JWT errors
+#[derive(Debug, thiserror::Error)]
+pub enum JwtError {
+    #[error("Invalid token: {0}")]
+    InvalidToken(String),
+    #[error("Invalid JWKS: {0}")]
+    InvalidJwks(String),
+}
+
+/// JWT tool
+#[derive(Debug, Clone, Default)]
+pub struct JwtTool;
+
+impl JwtTool {
+    pub const NAME: &'static str = "jwt_analyze";
+    pub const DESCRIPTION: &'static str = "Decode a JWT (header, claims, signature) without verifying it and report security observations: alg none, weak or unknown algorithms, missing/expired exp, key-injection headers, sensitive claims. Optionally verify the signature with a shared secret (HS*) or a JWKS (RS*, PS*, ES256/ES384, EdDSA).";
+
+    /// Analyze a token relative to the given unix timestamp
+    pub fn analyze(args: &JwtArgs, now: i64) -> Result<JwtOutput, JwtError> {
+        let token = args.token.trim();
+        let token = token
+            .strip_prefix("Bearer ")
+            .or_else(|| token.strip_prefix("bearer "))
+            .unwrap_or(token)
+            .trim();
+
+        let parts: Vec<&str> = token.split('.').collect();
+        if parts.len() == 5 {
+            return Err(JwtError::InvalidToken(
+                "JWE (encrypted) tokens cannot be decoded without the key".to_string(),
+            ));
+        }
+        if parts.len() != 3 {
+            return Err(JwtError::InvalidToken(format!(
+                "expected 3 dot-separated segments, found {}",
+                parts.len()
+            )));
+        }
+
+        let header = decode_segment(parts[0], "header")?;
+        let claims = decode_segment(parts[1], "claims")?;
+        let signature_bytes = base64url_decode(parts[2])
+            .map_err(|e| JwtError::InvalidToken(format!("signature is not base64url: {}", e)))?;
+        let algorithm = header
+            .get("alg")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+
+        let mut observations = Vec::new();
+        check_header(
+            &header,
+            algorithm.as_deref(),
+            &signature_bytes,
+            &mut observations,
+        );
+        let expired = check_claims(&claims, now, &mut observations);
+
+        let signing_input = format!("{}.{}", parts[0], parts[1]);
+        let signature_check = if let Some(secret) = &args.secret {
+            let check = verify_with_secret(
+                algorithm.as_deref(),
+                secret.as_bytes(),
+                signing_input.as_bytes(),
+                &signature_bytes,
+            );
+            if check.verified {
+                check_secret_strength(algorithm.as_deref(), secret, &mut observations);
+            }
+            Some(check)
+        } else if let Some(jwks) = &args.jwks {
+            Some(verify_with_jwks(
+                algorithm.as_deref(),
+                header.get("kid").and_then(Value::as_str),
+                jwks,
+                signing_input.as_bytes(),
+                &signature_bytes,
+            )?)
+        } else {
+            None
+        };
+
+        Ok(JwtOutput {
+            expires_at: timestamp_claim(&claims, "exp").and_then(format_timestamp),
+            issued_at: timestamp_claim(&claims, "iat").and_then(format_timestamp),
+            not_before: timestamp_claim(&claims, "nbf").and_then(format_timestamp),
+            header,
+            claims,
+            signature: parts[2].to_string(),
+            algorithm,
+            expired,
+            signature_check,
+            observations,
+        })
+    }
+}
+
+impl Tool for JwtTool {
+    const NAME: &'static str = Self::NAME;
+    type Args = JwtArgs;
+    type Output = JwtOutput;
+    type Error = JwtError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: Self::DESCRIPTION.to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(JwtArgs)).unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        Self::analyze(&args, Utc::now().timestamp())
+    }
+}
+
+fn base64url_decode(segment: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    URL_SAFE_NO_PAD.decode(segment.trim_end_matches('='))
+}
+
+fn decode_segment(segment: &str, name: &str) -> Result<Value, JwtError> {
+    let bytes = base64url_decode(segment)
+        .map_err(|e| JwtError::InvalidToken(format!("{} is not base64url: {}", name, e)))?;
+    serde_json::from_slice(&bytes)
+        .map_err(|e| JwtError::InvalidToken(format!("{} is not JSON: {}", name, e)))
+}
+
+fn observe(
+    observations: &mut Vec<JwtObservation>,
+    severity: ObservationSeverity,
+    code: &str,
+    message: impl Into<String>,
+) {
+    observations.push(JwtObservation {
+        severity,
+        code: code.to_string(),
+        message: message.into(),
+    });
+}
+
+fn check_header(
+    header: &Value,
+    algorithm: Option<&str>,
+    signature: &[u8],
+    observations: &mut Vec<JwtObservation>,
+) {
+    match algorithm {
+        None => observe(
+            observations,
+            ObservationSeverity::High,
+            "missing_alg",
+            "Header has no `alg`; the verifier decides how (or whether) to check the signature",
+        ),
+        Some(alg) if alg.eq_ignore_ascii_case("none") => observe(
+            observations,
+            ObservationSeverity::High,
+            "alg_none",
+            "Token uses `alg: none`; a server that accepts it accepts forged tokens",
+        ),
+        Some(alg) if alg.starts_with("HS") => observe(
+            observations,
+            ObservationSeverity::Low,
+            "symmetric_algorithm",
+            format!(
+                "{} is HMAC-based: a weak secret can be brute-forced offline, and servers holding an RSA key may be vulnerable to key confusion",
+                alg
+            ),
+        ),
+        Some(alg) if !is_known_algorithm(alg) => observe(
+            observations,
+            ObservationSeverity::Medium,
+            "unknown_algorithm",
+            format!("Unrecognized algorithm `{}`", alg),
+        ),
+        Some(_) => {}
+    }
+
+    if signature.is_empty() && !algorithm.is_some_and(|a| a.eq_ignore_ascii_case("none")) {
+        observe(
+            observations,
+            ObservationSeverity::High,
+            "missing_signature",
+            "Signature segment is empty although an algorithm is declared",
+        );
+    }
+
+    for name in ["jku", "x5u"] {
+        if let Some(url) = header.get(name).and_then(Value::as_str) {
+            observe(
+                observations,
+                ObservationSeverity::Medium,
+                "remote_key_header",
+                format!(
+                    "Header `{}` points to {}; check whether the server fetches keys from attacker-controlled URLs",
+                    name, url
+                ),
+            );
+        }
+    }
+    if header.get("jwk").is_some() {
+        observe(
+            observations,
+            ObservationSeverity::Medium,
+            "embedded_jwk",
+            "Header embeds a `jwk`; servers trusting it accept self-signed tokens",
+        );
+    }
+    if let Some(kid) = header.get("kid").and_then(Value::as_str) {
+        if kid.contains("..") || kid.contains('/') || kid.contains('\'') || kid.contains(';') {
+            observe(
+                observations,
+                ObservationSeverity::Low,
+                "suspicious_kid",
+                format!(
+                    "`kid` value {:?} contains path or injection characters; test kid-based file/SQL lookups",
+                    kid
+                ),
+            );
+        }
+    }
+}
+
+/// Checks time-based and sensitive claims, returns whether the token is expired
+fn check_claims(claims: &Value, now: i64, observations: &mut Vec<JwtObservation>) -> Option<bool> {
+    let exp = timestamp_claim(claims, "exp");
+    let iat = timestamp_claim(claims, "iat");
+
+    let expired = match (claims.get("exp"), exp) {
+        (None, _) => {
+            observe(
+                observations,
+                ObservationSeverity::Medium,
+                "missing_exp",
+                "Token has no `exp` claim and never expires",
+            );
+            None
+        }
+        (Some(_), None) => {
+            observe(
+                observations,
+                ObservationSeverity::Medium,
+                "invalid_exp",
+                "`exp` claim is not a numeric timestamp",
+            );
+            None
+        }
+        (Some(_), Some(exp)) => {
+            if exp <= now {
+                observe(
+                    observations,
+                    ObservationSeverity::Medium,
+                    "expired",
+                    format!(
+                        "Token expired at {}; check whether the server still accepts it",
+                        format_timestamp(exp).unwrap_or_else(|| exp.to_string())
+                    ),
+                );
+            }
+            let lifetime = exp.saturating_sub(iat.unwrap_or(now));
+            if lifetime > MAX_REASONABLE_LIFETIME_SECS {
+                observe(
+                    observations,
+                    ObservationSeverity::Low,
+                    "long_lifetime",
+                    format!("Token lifetime is {} days", lifetime / 86_400),
+                );
+            }
+            Some(exp <= now)
+        }
+    };
+
+    if let Some(nbf) = timestamp_claim(claims, "nbf") {
+        if nbf > now {
+            observe(
+                observations,
+                ObservationSeverity::Info,
+                "not_yet_valid",
+                "`nbf` is in the future; the token is not valid yet",
+            );
+        }
+    }
+    if iat.is_some_and(|iat| iat > now + 60) {
+        observe(
+            observations,
+            ObservationSeverity::Low,
+            "issued_in_future",
+            "`iat` is in the future",
+        );
+    }
+
+    if let Some(object) = claims.as_object() {
+        for name in object.keys() {
+            if SENSITIVE_CLAIMS.contains(&name.to_ascii_lowercase().as_str()) {
+                observe(
+                    observations,
+                    ObservationSeverity::Medium,
+                    "sensitive_claim",
+                    format!("Claim `{}` is readable by anyone holding the token", name),
+                );
+            }
+        }
+    }
+
+    expired
+}
+
+fn check_secret_strength(
+    algorithm: Option<&str>,
+    secret: &str,
+    observations: &mut Vec<JwtObservation>,
+) {
+    // RFC 7518 §3.2: the key must be at least as long as the hash output
+    let min_len = match algorithm {
+        Some("HS384") => 48,
+        Some("HS512") => 64,
+        _ => 32,
+    };
+    if secret.len() < min_len {
+        observe(
+            observations,
+            ObservationSeverity::High,
+            "weak_secret",
+            format!(
+                "Signature verified with a {}-byte secret; {} requires at least {} bytes",
+                secret.len(),
+                algorithm.unwrap_or("HMAC"),
+                min_len
+            ),
+        );
+    }
+}
+
+fn timestamp_claim(claims: &Value, name: &str) -> Option<i64> {
+    let value = claims.get(name)?;
+    value.as_i64().or_else(|| value.as_f64().map(|f| f as i64))
+}
+
+fn format_timestamp(secs: i64) -> Option<String> {
+    DateTime::<Utc>::from_timestamp(secs, 0).map(|t| t.to_rfc3339())
+}
+
+fn is_known_algorithm(alg: &str) -> bool {
+    matches!(
+        alg,
+        "HS256"
+            | "HS384"
+            | "HS512"
+            | "RS256"
+            | "RS384"
+            | "RS512"
+            | "PS256"
+            | "PS384"
+            | "PS512"
+            | "ES256"
+            | "ES384"
+            | "ES512"
+            | "EdDSA"
+    )
+}
+
+fn hmac_algorithm(alg: &str) -> Option<ring::hmac::Algorithm> {
+    match alg {
+        "HS256" => Some(ring::hmac::HMAC_SHA256),
+        "HS384" => Some(ring::hmac::HMAC_SHA384),
+        "HS512" => Some(ring::hmac::HMAC_SHA512),
+        _ => None,
+    }
+}
+
+fn verify_hmac(algorithm: ring::hmac::Algorithm, key: &[u8], message: &[u8], sig: &[u8]) -> bool {
+    let key = ring::hmac::Key::new(algorithm, key);
+    ring::hmac::verify(&key, message, sig).is_ok()
+}
+
+fn verify_with_secret(
+    algorithm: Option<&str>,
+    secret: &[u8],
+    message: &[u8],
+    signature: &[u8],
+) -> JwtSignatureCheck {
+    let mut check = JwtSignatureCheck {
+        verified: false,
+        method: "secret".to_string(),
+        key_id: None,
+        error: None,
+    };
+    match algorithm.and_then(hmac_algorithm) {
+        Some(hmac) => check.verified = verify_hmac(hmac, secret, message, signature),
+        None => {
+            check.error = Some(format!(
+                "Secret verification requires an HS256/HS384/HS512 token, got {}",
+                algorithm.unwrap_or("no algorithm")
+            ))
+        }
+    }
+    check
+}
+
+fn verify_with_jwks(
+    algorithm: Option<&str>,
+    kid: Option<&str>,
+    jwks: &str,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<JwtSignatureCheck, JwtError> {
+    let parsed: Value =
+        serde_json::from_str(jwks).map_err(|e| JwtError::InvalidJwks(e.to_string()))?;
+    let keys = match parsed.get("keys") {
+        Some(Value::Array(keys)) => keys.clone(),
+        Some(_) => return Err(JwtError::InvalidJwks("`keys` must be an array".to_string())),
+        None => vec![parsed],
+    };
+
+    let mut check = JwtSignatureCheck {
+        verified: false,
+        method: "jwks".to_string(),
+        key_id: None,
+        error: None,
+    };
+    let Some(alg) = algorithm.filter(|a| !a.eq_ignore_ascii_case("none")) else {
+        check.error = Some("Token declares no signing algorithm to verify".to_string());
+        return Ok(check);
+    };
+
+    let candidates: Vec<&Value> = keys
+        .iter()
+        .filter(|jwk| match (kid, jwk.get("kid").and_then(Value::as_str)) {
+            (Some(kid), Some(key_kid)) => kid == key_kid,
+            _ => true,
+        })
+        .collect();
+    if candidates.is_empty() {
+        check.error = Some(format!("No JWK matches kid {:?}", kid.unwrap_or_default()));
+        return Ok(check);
+    }
+
+    let mut last_error = None;
+    for jwk in candidates {
+        match verify_with_jwk(alg, jwk, message, signature) {
+            Ok(true) => {
+                check.verified = true;
+                check.key_id = jwk.get("kid").and_then(Value::as_str).map(str::to_string);
+                return Ok(check);
+            }
+            Ok(false) => {}
+            Err(e) => last_error = Some(e),
+        }
+    }
+    check.error =
+        Some(last_error.unwrap_or_else(|| "No key in the JWKS verified the signature".to_string()));
+    Ok(check)
+}
+
+fn jwk_bytes(jwk: &Value, field: &str) -> Result<Vec<u8>, String> {
+    let value = jwk
+        .get(field)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("JWK is missing `{}`", field))?;
+    base64url_decode(value).map_err(|e| format!("JWK `{}` is not base64url: {}", field, e))
+}
+
+/// Verifies with one JWK; keys whose type does not fit the algorithm never verify
+fn verify_with_jwk(alg: &str, jwk: &Value, message: &[u8], sig: &[u8]) -> Result<bool, String> {
+    let kty = jwk.get("kty").and_then(Value::as_str).unwrap_or("");
+    let crv = jwk.get("crv").and_then(Value::as_str).unwrap_or("");
+
+    if let Some(hmac) = hmac_algorithm(alg) {
+        return Ok(kty == "oct" && verify_hmac(hmac, &jwk_bytes(jwk, "k")?, message, sig));
+    }
+
+    let rsa_params: Option<&'static signature::RsaParameters> = match alg {
+        "RS256" => Some(&signature::RSA_PKCS1_2048_8192_SHA256),
+        "RS384" => Some(&signature::RSA_PKCS1_2048_8192_SHA384),
+        "RS512" => Some(&signature::RSA_PKCS1_2048_8192_SHA512),
+        "PS256" => Some(&signature::RSA_PSS_2048_8192_SHA256),
+        "PS384" => Some(&signature::RSA_PSS_2048_8192_SHA384),
+        "PS512" => Some(&signature::RSA_PSS_2048_8192_SHA512),
+        _ => None,
+    };
+    if let Some(params) = rsa_params {
+        if kty != "RSA" {
+            return Ok(false);
+        }
+        let key = signature::RsaPublicKeyComponents {
+            n: jwk_bytes(jwk, "n")?,
+            e: jwk_bytes(jwk, "e")?,
+        };
+        return Ok(key.verify(params, message, sig).is_ok());
+    }
+
+    let (expected_kty, expected_crv, verification): (
+        &str,
+        &str,
+        &'static dyn signature::VerificationAlgorithm,
+    ) = match alg {
+        "ES256" => ("EC", "P-256", &signature::ECDSA_P256_SHA256_FIXED),
+        "ES384" => ("EC", "P-384", &signature::ECDSA_P384_SHA384_FIXED),
+        "EdDSA" => ("OKP", "Ed25519", &signature::ED25519),
+        other => return Err(format!("Verification of {} is not supported", other)),
+    };
+    if kty != expected_kty || crv != expected_crv {
+        return Ok(false);
+    }
+    let public_key = if kty == "EC" {
+        // Uncompressed SEC1 point: 0x04 || x || y
+        let mut point = vec![0x04];
+        point.extend(jwk_bytes(jwk, "x")?);
+        point.extend(jwk_bytes(jwk, "y")?);
+        point
+    } else {
+        jwk_bytes(jwk, "x")?
+    };
+    Ok(signature::UnparsedPublicKey::new(verification, public_key)
+        .verify(message, sig)
+        .is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+    const SECRET: &str = "sentinel-test-secret-that-is-long-enough";
+
+    fn encode(value: &Value) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(value).unwrap())
+    }
+
+    fn hs256_token(claims: Value, secret: &str) -> String {
+        let signing_input = format!(
+            "{}.{}",
+            encode(&serde_json::json!({ "alg": "HS256", "typ": "JWT" })),
+            encode(&claims)
+        );
+        let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, secret.as_bytes());
+        let tag = ring::hmac::sign(&key, signing_input.as_bytes());
+        format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(tag.as_ref()))
+    }
+
+    fn args(token: String, secret: Option<&str>) -> JwtArgs {
+        JwtArgs {
+            token,
+            secret: secret.map(str::to_string),
+            jwks: None,
+        }
+    }
+
+    fn codes(output: &JwtOutput) -> Vec<&str> {
+        output
+            .observations
+            .iter()
+            .map(|o| o.code.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_hs256_token() {
+        let token = hs256_token(
+            serde_json::json!({ "sub": "alice", "iat": NOW - 60, "exp": NOW + 3600 }),
+            SECRET,
+        );
+
+        let output =
+            JwtTool::analyze(&args(format!("Bearer {}", token), Some(SECRET)), NOW).unwrap();
+        assert_eq!(output.algorithm.as_deref(), Some("HS256"));
+        assert_eq!(output.claims["sub"], "alice");
+        assert_eq!(output.expired, Some(false));
+        assert!(output.signature_check.as_ref().unwrap().verified);
+        assert_eq!(codes(&output), vec!["symmetric_algorithm"]);
+
+        let wrong = JwtTool::analyze(&args(token, Some("wrong-secret")), NOW).unwrap();
+        assert!(!wrong.signature_check.unwrap().verified);
+
+        // JWKS with an `oct` key verifies the same token
+        let jwks = serde_json::json!({
+            "keys": [{ "kty": "oct", "kid": "k1", "k": URL_SAFE_NO_PAD.encode(SECRET) }]
+        })
+        .to_string();
+        let token = hs256_token(serde_json::json!({ "exp": NOW + 60 }), SECRET);
+        let output = JwtTool::analyze(
+            &JwtArgs {
+                token,
+                secret: None,
+                jwks: Some(jwks),
+            },
+            NOW,
+        )
+        .unwrap();
+        let check = output.signature_check.unwrap();
+        assert!(check.verified);
+        assert_eq!(check.key_id.as_deref(), Some("k1"));
+    }
+
+    #[test]
+    fn test_alg_none_token() {
+        let token = format!(
+            "{}.{}.",
+            encode(&serde_json::json!({ "alg": "none", "typ": "JWT" })),
+            encode(&serde_json::json!({ "sub": "admin", "exp": NOW + 3600 }))
+        );
+
+        let output = JwtTool::analyze(&args(token, Some(SECRET)), NOW).unwrap();
+        let alg_none = output
+            .observations
+            .iter()
+            .find(|o| o.code == "alg_none")
+            .expect("alg none must be flagged");
+        assert_eq!(alg_none.severity, ObservationSeverity::High);
+        assert!(!codes(&output).contains(&"missing_signature"));
+
+        let check = output.signature_check.unwrap();
+        assert!(!check.verified);
+        assert!(check.error.is_some());
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let token = hs256_token(
+            serde_json::json!({ "sub": "bob", "iat": NOW - 7200, "exp": NOW - 3600 }),
+            "short",
+        );
+
+        let output = JwtTool::analyze(&args(token, Some("short")), NOW).unwrap();
+        assert_eq!(output.expired, Some(true));
+        assert!(output.expires_at.is_some());
+        let codes = codes(&output);
+        assert!(codes.contains(&"expired"));
+        assert!(codes.contains(&"weak_secret"));
+    }
+
+    #[test]
+    fn test_extreme_timestamps_do_not_overflow() {
+        let token = hs256_token(
+            serde_json::json!({ "iat": i64::MIN, "exp": i64::MAX }),
+            SECRET,
+        );
+        let output = JwtTool::analyze(&args(token, None), NOW).unwrap();
+        assert_eq!(output.expired, Some(false));
+        assert!(codes(&output).contains(&"long_lifetime"));
+    }
+
+    #[test]
+    fn test_missing_exp_and_malformed_tokens() {
+        let token = hs256_token(serde_json::json!({ "password": "hunter2" }), SECRET);
+        let output = JwtTool::analyze(&args(token, None), NOW).unwrap();
+        assert_eq!(output.expired, None);
+        assert!(codes(&output).contains(&"missing_exp"));
+        assert!(codes(&output).contains(&"sensitive_claim"));
+
+        assert!(JwtTool::analyze(&args("not-a-jwt".to_string(), None), NOW).is_err());
+        assert!(JwtTool::analyze(&args("a.b.c.d.e".to_string(), None), NOW).is_err());
+    }
+}
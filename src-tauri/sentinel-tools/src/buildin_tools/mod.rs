@@ -1,9 +1,16 @@
 pub mod browser;
+pub mod content_discovery;
+pub mod cors_check;
+pub mod extract;
 pub mod http_request;
+pub mod ip_enrichment;
+pub mod jwt;
 pub mod local_time;
 pub mod memory;
+pub mod nuclei_template;
 pub mod ocr;
 pub mod port_scan;
+pub mod screenshot;
 pub mod search_exploit;
 pub mod shell;
 pub mod skills;
@@ -12,13 +19,21 @@ pub mod subdomain_brute;
 pub mod tenth_man_tool;
 pub mod todos;
 pub mod web_search;
+pub mod whois;
 
 pub use browser::*;
+pub use content_discovery::ContentDiscoveryTool;
+pub use cors_check::CorsCheckTool;
+pub use extract::ExtractTool;
 pub use http_request::HttpRequestTool;
+pub use ip_enrichment::IpEnrichmentTool;
+pub use jwt::JwtTool;
 pub use local_time::LocalTimeTool;
 pub use memory::MemoryManagerTool;
+pub use nuclei_template::NucleiTemplateTool;
 pub use ocr::OcrTool;
 pub use port_scan::PortScanTool;
+pub use screenshot::ScreenshotTool;
 pub use search_exploit::SearchExploitTool;
 pub use shell::ShellTool;
 pub use skills::SkillsTool;
@@ -27,6 +42,7 @@ pub use subdomain_brute::SubdomainBruteTool;
 pub use tenth_man_tool::TenthManTool;
 pub use todos::TodosTool;
 pub use web_search::WebSearchTool;
+pub use whois::WhoisTool;
 
 use rig::tool::ToolSet;
 
@@ -38,11 +54,19 @@ pub fn create_buildin_toolset() -> ToolSet {
     toolset.add_tool(LocalTimeTool);
     toolset.add_tool(ShellTool::new());
     toolset.add_tool(SubdomainBruteTool);
+    toolset.add_tool(ContentDiscoveryTool);
     toolset.add_tool(TodosTool);
     toolset.add_tool(WebSearchTool::default());
     toolset.add_tool(SearchExploitTool);
     toolset.add_tool(MemoryManagerTool);
     toolset.add_tool(OcrTool);
+    toolset.add_tool(ExtractTool);
+    toolset.add_tool(JwtTool);
+    toolset.add_tool(NucleiTemplateTool::new());
+    toolset.add_tool(ScreenshotTool);
+    toolset.add_tool(WhoisTool::default());
+    toolset.add_tool(IpEnrichmentTool::default());
+    toolset.add_tool(CorsCheckTool::new());
     toolset.add_tool(SkillsTool);
     // Condensed subagent tools
     toolset.add_tool(SubagentExecuteTool::new());
@@ -59,11 +83,19 @@ pub async fn get_tool_definitions() -> Vec<rig::completion::ToolDefinition> {
         Box::new(LocalTimeTool),
         Box::new(ShellTool::new()),
         Box::new(SubdomainBruteTool),
+        Box::new(ContentDiscoveryTool),
         Box::new(TodosTool),
         Box::new(WebSearchTool::default()),
         Box::new(SearchExploitTool),
         Box::new(MemoryManagerTool),
         Box::new(OcrTool),
+        Box::new(ExtractTool),
+        Box::new(JwtTool),
+        Box::new(NucleiTemplateTool::new()),
+        Box::new(ScreenshotTool),
+        Box::new(WhoisTool::default()),
+        Box::new(IpEnrichmentTool::default()),
+        Box::new(CorsCheckTool::new()),
         Box::new(SkillsTool),
         // Condensed subagent tools
         Box::new(SubagentExecuteTool::new()),
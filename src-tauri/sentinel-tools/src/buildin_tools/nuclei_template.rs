@@ -0,0 +1,905 @@
+//! Nuclei-style YAML template runner using rig-core Tool trait
+//!
+//! Runs a subset of the nuclei template DSL: a single HTTP request block
+//! (one or more paths) with status/word/regex/binary matchers,
+//! `matchers-condition`, and regex/kval extractors. Matches are reported as
+//! plugin `Finding`s so they can go through `FindingDeduplicator` like
+//! passive scan results.
+
+use chrono::Utc;
+use regex::Regex;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use sentinel_plugins::{Confidence, Finding, Severity};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Response bodies larger than this are truncated before matching
+const MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+
+/// Evidence stored on findings is capped to keep them readable
+const MAX_EVIDENCE_CHARS: usize = 2000;
+
+/// Nuclei template arguments
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct NucleiTemplateArgs {
+    /// Template YAML content (nuclei format, single HTTP request)
+    pub template: String,
+    /// Target base URL, substituted for {{BaseURL}}
+    pub target: String,
+    /// Per-request timeout in seconds
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_timeout() -> u64 {
+    10
+}
+
+/// Parsed template (only the supported subset of fields)
+#[derive(Debug, Clone, Deserialize)]
+pub struct NucleiTemplate {
+    pub id: String,
+    #[serde(default)]
+    pub info: TemplateInfo,
+    #[serde(default)]
+    pub http: Vec<HttpRequestBlock>,
+    /// Legacy name of the `http` section
+    #[serde(default)]
+    pub requests: Vec<HttpRequestBlock>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TemplateInfo {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub severity: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub remediation: Option<String>,
+    #[serde(default)]
+    pub classification: Option<TemplateClassification>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TemplateClassification {
+    #[serde(default, rename = "cwe-id")]
+    pub cwe_id: Option<StringOrList>,
+}
+
+/// A YAML scalar or a list of scalars
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum StringOrList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl StringOrList {
+    fn first(&self) -> Option<&str> {
+        match self {
+            Self::One(value) => Some(value),
+            Self::Many(values) => values.first().map(String::as_str),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct HttpRequestBlock {
+    #[serde(default = "default_method")]
+    pub method: String,
+    #[serde(default)]
+    pub path: Vec<String>,
+    #[serde(default)]
+    pub raw: Vec<String>,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub redirects: bool,
+    #[serde(default)]
+    pub matchers_condition: Option<String>,
+    #[serde(default)]
+    pub matchers: Vec<Matcher>,
+    #[serde(default)]
+    pub extractors: Vec<Extractor>,
+    #[serde(default)]
+    pub stop_at_first_match: bool,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Matcher {
+    #[serde(rename = "type")]
+    pub matcher_type: String,
+    #[serde(default)]
+    pub part: Option<String>,
+    #[serde(default)]
+    pub condition: Option<String>,
+    #[serde(default)]
+    pub negative: bool,
+    #[serde(default)]
+    pub case_insensitive: bool,
+    #[serde(default)]
+    pub status: Vec<u16>,
+    #[serde(default)]
+    pub words: Vec<String>,
+    #[serde(default)]
+    pub regex: Vec<String>,
+    #[serde(default)]
+    pub binary: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Extractor {
+    #[serde(rename = "type")]
+    pub extractor_type: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub part: Option<String>,
+    #[serde(default)]
+    pub regex: Vec<String>,
+    #[serde(default)]
+    pub group: usize,
+    #[serde(default)]
+    pub kval: Vec<String>,
+}
+
+/// Result of one request sent by the template
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateRequestResult {
+    pub url: String,
+    pub status_code: u16,
+    pub matched: bool,
+    /// Matcher evidence (matched words, regex matches, status codes)
+    pub matched_values: Vec<String>,
+    /// Extracted values keyed by extractor name
+    pub extracted: HashMap<String, Vec<String>>,
+}
+
+/// Template execution result
+#[derive(Debug, Clone, Serialize)]
+pub struct NucleiTemplateOutput {
+    pub template_id: String,
+    pub name: String,
+    pub severity: Severity,
+    pub matched: bool,
+    pub results: Vec<TemplateRequestResult>,
+    /// Findings for matched requests, compatible with `FindingDeduplicator`
+    pub findings: Vec<Finding>,
+}
+
+/// Nuclei template errors
+#[derive(Debug, thiserror::Error)]
+pub enum NucleiTemplateError {
+    #[error("Invalid template: {0}")]
+    InvalidTemplate(String),
+    #[error("Unsupported template feature: {0}")]
+    Unsupported(String),
+    #[error("Invalid target: {0}")]
+    InvalidTarget(String),
+    #[error("Request failed: {0}")]
+    RequestFailed(String),
+}
+
+/// Response fields the matchers and extractors run against
+struct ResponseParts {
+    status: u16,
+    headers: String,
+    header_map: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+impl ResponseParts {
+    fn part(&self, part: Option<&str>) -> Vec<u8> {
+        match part.unwrap_or("body") {
+            "header" | "all_headers" => self.headers.as_bytes().to_vec(),
+            "all" | "response" | "raw" => {
+                let mut all = self.headers.as_bytes().to_vec();
+                all.extend_from_slice(b"\r\n");
+                all.extend_from_slice(&self.body);
+                all
+            }
+            _ => self.body.clone(),
+        }
+    }
+}
+
+impl NucleiTemplate {
+    /// Parse and validate a template
+    pub fn parse(yaml: &str) -> Result<Self, NucleiTemplateError> {
+        let template: Self = serde_yaml::from_str(yaml)
+            .map_err(|e| NucleiTemplateError::InvalidTemplate(e.to_string()))?;
+        if template.id.trim().is_empty() {
+            return Err(NucleiTemplateError::InvalidTemplate(
+                "missing template id".to_string(),
+            ));
+        }
+
+        let blocks = template.request_blocks();
+        if blocks.len() != 1 {
+            return Err(NucleiTemplateError::Unsupported(format!(
+                "expected exactly one http request block, found {}",
+                blocks.len()
+            )));
+        }
+        let block = &blocks[0];
+        if !block.raw.is_empty() {
+            return Err(NucleiTemplateError::Unsupported("raw requests".to_string()));
+        }
+        if block.path.is_empty() {
+            return Err(NucleiTemplateError::InvalidTemplate(
+                "request has no path".to_string(),
+            ));
+        }
+        if block.matchers.is_empty() {
+            return Err(NucleiTemplateError::InvalidTemplate(
+                "request has no matchers".to_string(),
+            ));
+        }
+        for matcher in &block.matchers {
+            if !matches!(
+                matcher.matcher_type.as_str(),
+                "status" | "word" | "regex" | "binary"
+            ) {
+                return Err(NucleiTemplateError::Unsupported(format!(
+                    "matcher type '{}'",
+                    matcher.matcher_type
+                )));
+            }
+            for pattern in &matcher.regex {
+                Regex::new(pattern).map_err(|e| {
+                    NucleiTemplateError::InvalidTemplate(format!("regex '{}': {}", pattern, e))
+                })?;
+            }
+            for hex in &matcher.binary {
+                decode_hex(hex).ok_or_else(|| {
+                    NucleiTemplateError::InvalidTemplate(format!("binary '{}' is not hex", hex))
+                })?;
+            }
+        }
+        for extractor in &block.extractors {
+            if !matches!(extractor.extractor_type.as_str(), "regex" | "kval") {
+                return Err(NucleiTemplateError::Unsupported(format!(
+                    "extractor type '{}'",
+                    extractor.extractor_type
+                )));
+            }
+            for pattern in &extractor.regex {
+                Regex::new(pattern).map_err(|e| {
+                    NucleiTemplateError::InvalidTemplate(format!("regex '{}': {}", pattern, e))
+                })?;
+            }
+        }
+        Ok(template)
+    }
+
+    fn request_blocks(&self) -> &[HttpRequestBlock] {
+        if self.http.is_empty() {
+            &self.requests
+        } else {
+            &self.http
+        }
+    }
+
+    fn severity(&self) -> Severity {
+        match self
+            .info
+            .severity
+            .as_deref()
+            .unwrap_or("info")
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "critical" => Severity::Critical,
+            "high" => Severity::High,
+            "medium" => Severity::Medium,
+            "low" => Severity::Low,
+            _ => Severity::Info,
+        }
+    }
+
+    fn name(&self) -> String {
+        self.info.name.clone().unwrap_or_else(|| self.id.clone())
+    }
+}
+
+/// Substitute nuclei URL variables for the target
+fn substitute(value: &str, target: &reqwest::Url) -> String {
+    let base_url = target.as_str().trim_end_matches('/');
+    let host = target.host_str().unwrap_or_default();
+    let port = target
+        .port_or_known_default()
+        .map(|p| p.to_string())
+        .unwrap_or_default();
+    let hostname = match target.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    };
+    let root_url = format!("{}://{}", target.scheme(), hostname);
+
+    value
+        .replace("{{BaseURL}}", base_url)
+        .replace("{{RootURL}}", &root_url)
+        .replace("{{Hostname}}", &hostname)
+        .replace("{{Host}}", host)
+        .replace("{{Port}}", &port)
+        .replace("{{Scheme}}", target.scheme())
+        .replace("{{Path}}", target.path().trim_end_matches('/'))
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.trim();
+    if hex.is_empty() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Combine per-item results with an and/or condition
+fn combine(results: &[bool], condition: Option<&str>) -> bool {
+    if condition.is_some_and(|c| c.eq_ignore_ascii_case("and")) {
+        !results.is_empty() && results.iter().all(|r| *r)
+    } else {
+        results.iter().any(|r| *r)
+    }
+}
+
+/// Evaluate one matcher, returning whether it matched and the matched values
+fn evaluate_matcher(matcher: &Matcher, response: &ResponseParts) -> (bool, Vec<String>) {
+    let condition = matcher.condition.as_deref();
+    let mut evidence = Vec::new();
+    let results: Vec<bool> = match matcher.matcher_type.as_str() {
+        "status" => matcher
+            .status
+            .iter()
+            .map(|status| *status == response.status)
+            .collect(),
+        "word" => {
+            let data =
+                String::from_utf8_lossy(&response.part(matcher.part.as_deref())).into_owned();
+            let data = if matcher.case_insensitive {
+                data.to_lowercase()
+            } else {
+                data
+            };
+            matcher
+                .words
+                .iter()
+                .map(|word| {
+                    let word = if matcher.case_insensitive {
+                        word.to_lowercase()
+                    } else {
+                        word.clone()
+                    };
+                    let found = data.contains(&word);
+                    if found {
+                        evidence.push(word);
+                    }
+                    found
+                })
+                .collect()
+        }
+        "regex" => {
+            let data =
+                String::from_utf8_lossy(&response.part(matcher.part.as_deref())).into_owned();
+            matcher
+                .regex
+                .iter()
+                .map(|pattern| match Regex::new(pattern) {
+                    Ok(re) => match re.find(&data) {
+                        Some(m) => {
+                            evidence.push(m.as_str().to_string());
+                            true
+                        }
+                        None => false,
+                    },
+                    Err(_) => false,
+                })
+                .collect()
+        }
+        "binary" => {
+            let data = response.part(matcher.part.as_deref());
+            matcher
+                .binary
+                .iter()
+                .map(|hex| {
+                    let found = decode_hex(hex).is_some_and(|needle| {
+                        data.windows(needle.len()).any(|window| window == needle)
+                    });
+                    if found {
+                        evidence.push(hex.clone());
+                    }
+                    found
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    let mut matched = combine(&results, condition);
+    if matched && matcher.matcher_type == "status" {
+        evidence.push(response.status.to_string());
+    }
+    if matcher.negative {
+        matched = !matched;
+        evidence.clear();
+    }
+    (matched, evidence)
+}
+
+/// Run the extractors against a response
+fn run_extractors(
+    extractors: &[Extractor],
+    response: &ResponseParts,
+) -> HashMap<String, Vec<String>> {
+    let mut extracted: HashMap<String, Vec<String>> = HashMap::new();
+    for (index, extractor) in extractors.iter().enumerate() {
+        let name = extractor
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("extractor_{}", index));
+        let mut values = Vec::new();
+        match extractor.extractor_type.as_str() {
+            "regex" => {
+                let data =
+                    String::from_utf8_lossy(&response.part(extractor.part.as_deref())).into_owned();
+                for pattern in &extractor.regex {
+                    let Ok(re) = Regex::new(pattern) else {
+                        continue;
+                    };
+                    for caps in re.captures_iter(&data) {
+                        if let Some(m) = caps.get(extractor.group) {
+                            values.push(m.as_str().to_string());
+                        }
+                    }
+                }
+            }
+            "kval" => {
+                for key in &extractor.kval {
+                    // nuclei normalizes header names to lowercase with underscores
+                    let key = key.to_ascii_lowercase().replace('-', "_");
+                    if let Some(value) = response.header_map.get(&key) {
+                        values.push(value.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+        values.dedup();
+        if !values.is_empty() {
+            extracted.entry(name).or_default().extend(values);
+        }
+    }
+    extracted
+}
+
+fn truncate(value: &str, max_chars: usize) -> String {
+    if value.chars().count() <= max_chars {
+        value.to_string()
+    } else {
+        let mut truncated: String = value.chars().take(max_chars).collect();
+        truncated.push_str("...");
+        truncated
+    }
+}
+
+/// Nuclei template tool
+#[derive(Debug, Clone, Default)]
+pub struct NucleiTemplateTool {
+    /// Client to use instead of one built from the global proxy settings
+    client: Option<reqwest::Client>,
+}
+
+impl NucleiTemplateTool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_client(client: reqwest::Client) -> Self {
+        Self {
+            client: Some(client),
+        }
+    }
+
+    pub const NAME: &'static str = "nuclei_template";
+    pub const DESCRIPTION: &'static str = "Run a nuclei-style YAML template against a target URL. Supports a single HTTP request block (method, one or more paths with {{BaseURL}}/{{RootURL}}/{{Hostname}} variables, headers, body, redirects), status/word/regex/binary matchers with matchers-condition, negative and case-insensitive matchers, and regex/kval extractors. Returns per-request results and findings for matches.";
+
+    async fn client(&self, follow_redirects: bool) -> Result<reqwest::Client, NucleiTemplateError> {
+        if let Some(client) = &self.client {
+            return Ok(client.clone());
+        }
+        let policy = if follow_redirects {
+            reqwest::redirect::Policy::limited(10)
+        } else {
+            reqwest::redirect::Policy::none()
+        };
+        let builder = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .redirect(policy);
+        let builder = sentinel_core::global_proxy::apply_proxy_to_client(builder).await;
+        let builder = sentinel_core::auth_session::apply_session_to_client(builder);
+        builder
+            .build()
+            .map_err(|e| NucleiTemplateError::RequestFailed(e.to_string()))
+    }
+
+    async fn send(
+        client: &reqwest::Client,
+        block: &HttpRequestBlock,
+        url: &str,
+        target: &reqwest::Url,
+        timeout_secs: u64,
+    ) -> Result<ResponseParts, NucleiTemplateError> {
+        let method =
+            reqwest::Method::from_bytes(block.method.to_uppercase().as_bytes()).map_err(|_| {
+                NucleiTemplateError::InvalidTemplate(format!("method '{}'", block.method))
+            })?;
+        let mut request = client
+            .request(method, url)
+            .timeout(std::time::Duration::from_secs(timeout_secs));
+        for (key, value) in &block.headers {
+            request = request.header(key.as_str(), substitute(value, target));
+        }
+        if let Some(body) = &block.body {
+            request = request.body(substitute(body, target));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| NucleiTemplateError::RequestFailed(e.to_string()))?;
+        let status = response.status().as_u16();
+
+        let mut headers = format!("{:?} {}\r\n", response.version(), response.status());
+        let mut header_map = HashMap::new();
+        for (key, value) in response.headers() {
+            let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+            headers.push_str(&format!("{}: {}\r\n", key, value));
+            header_map.insert(key.as_str().to_ascii_lowercase().replace('-', "_"), value);
+        }
+
+        let mut body = response
+            .bytes()
+            .await
+            .map_err(|e| NucleiTemplateError::RequestFailed(e.to_string()))?
+            .to_vec();
+        body.truncate(MAX_BODY_BYTES);
+
+        Ok(ResponseParts {
+            status,
+            headers,
+            header_map,
+            body,
+        })
+    }
+
+    fn to_finding(
+        template: &NucleiTemplate,
+        block: &HttpRequestBlock,
+        result: &TemplateRequestResult,
+        response: &ResponseParts,
+    ) -> Finding {
+        let mut evidence = result.matched_values.join(", ");
+        for (name, values) in &result.extracted {
+            evidence.push_str(&format!("\n{}: {}", name, values.join(", ")));
+        }
+
+        Finding {
+            id: uuid::Uuid::new_v4().to_string(),
+            plugin_id: format!("nuclei:{}", template.id),
+            vuln_type: template.id.clone(),
+            severity: template.severity(),
+            title: template.name(),
+            description: template
+                .info
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("Matched nuclei template {}", template.id)),
+            evidence: truncate(evidence.trim(), MAX_EVIDENCE_CHARS),
+            location: "response".to_string(),
+            confidence: Confidence::Medium,
+            cwe: template
+                .info
+                .classification
+                .as_ref()
+                .and_then(|c| c.cwe_id.as_ref())
+                .and_then(|cwe| cwe.first())
+                .map(|cwe| cwe.to_uppercase()),
+            owasp: None,
+            remediation: template.info.remediation.clone(),
+            url: result.url.clone(),
+            method: block.method.to_uppercase(),
+            created_at: Utc::now(),
+            request_headers: None,
+            request_body: None,
+            response_status: Some(response.status as i32),
+            response_headers: Some(response.headers.clone()),
+            response_body: Some(truncate(
+                &String::from_utf8_lossy(&response.body),
+                MAX_EVIDENCE_CHARS,
+            )),
+        }
+    }
+}
+
+impl Tool for NucleiTemplateTool {
+    const NAME: &'static str = Self::NAME;
+    type Args = NucleiTemplateArgs;
+    type Output = NucleiTemplateOutput;
+    type Error = NucleiTemplateError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: Self::DESCRIPTION.to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(NucleiTemplateArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let template = NucleiTemplate::parse(&args.template)?;
+        let target = reqwest::Url::parse(args.target.trim())
+            .map_err(|e| NucleiTemplateError::InvalidTarget(format!("{}: {}", args.target, e)))?;
+        if !matches!(target.scheme(), "http" | "https") {
+            return Err(NucleiTemplateError::InvalidTarget(format!(
+                "unsupported scheme '{}'",
+                target.scheme()
+            )));
+        }
+
+        let block = &template.request_blocks()[0];
+        let client = self.client(block.redirects).await?;
+        let condition = block.matchers_condition.as_deref();
+
+        let mut results = Vec::new();
+        let mut findings = Vec::new();
+        for path in &block.path {
+            if crate::cancellation::is_cancelled() {
+                return Err(NucleiTemplateError::RequestFailed(
+                    "scan cancelled".to_string(),
+                ));
+            }
+            let url = substitute(path, &target);
+            let response = match Self::send(&client, block, &url, &target, args.timeout_secs).await
+            {
+                Ok(response) => response,
+                Err(e) => {
+                    tracing::debug!("Template {} request to {} failed: {}", template.id, url, e);
+                    continue;
+                }
+            };
+
+            let evaluated: Vec<(bool, Vec<String>)> = block
+                .matchers
+                .iter()
+                .map(|matcher| evaluate_matcher(matcher, &response))
+                .collect();
+            let outcomes: Vec<bool> = evaluated.iter().map(|(matched, _)| *matched).collect();
+            let matched = combine(&outcomes, condition);
+
+            let result = TemplateRequestResult {
+                url,
+                status_code: response.status,
+                matched,
+                matched_values: if matched {
+                    evaluated
+                        .into_iter()
+                        .filter(|(matched, _)| *matched)
+                        .flat_map(|(_, values)| values)
+                        .collect()
+                } else {
+                    Vec::new()
+                },
+                extracted: run_extractors(&block.extractors, &response),
+            };
+            if matched {
+                findings.push(Self::to_finding(&template, block, &result, &response));
+            }
+            results.push(result);
+
+            if matched && block.stop_at_first_match {
+                break;
+            }
+        }
+
+        if results.is_empty() {
+            return Err(NucleiTemplateError::RequestFailed(format!(
+                "no request for template {} reached {}",
+                template.id, target
+            )));
+        }
+
+        Ok(NucleiTemplateOutput {
+            template_id: template.id.clone(),
+            name: template.name(),
+            severity: template.severity(),
+            matched: !findings.is_empty(),
+            results,
+            findings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Mock server: `/admin` returns a versioned admin page, everything else 404
+    async fn spawn_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 2048];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_string();
+                let response = if request.starts_with("GET /admin ") {
+                    let body = "<title>Admin Panel</title> version=1.2.3\x00\x01";
+                    format!(
+                        "HTTP/1.1 200 OK\r\nServer: mock-httpd\r\nX-App-Version: 1.2.3\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                } else {
+                    "HTTP/1.1 404 Not Found\r\nContent-Length: 9\r\nConnection: close\r\n\r\nnot found"
+                        .to_string()
+                };
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    const TEMPLATE: &str = r#"
+id: exposed-admin-panel
+info:
+  name: Exposed Admin Panel
+  severity: medium
+  description: Admin panel is reachable without authentication
+  classification:
+    cwe-id: cwe-200
+http:
+  - method: GET
+    path:
+      - "{{BaseURL}}/admin"
+      - "{{BaseURL}}/missing"
+    matchers-condition: and
+    matchers:
+      - type: status
+        status:
+          - 200
+      - type: word
+        part: body
+        case-insensitive: true
+        words:
+          - "admin panel"
+      - type: regex
+        regex:
+          - "version=[0-9.]+"
+      - type: binary
+        binary:
+          - "0001"
+      - type: word
+        part: header
+        negative: true
+        words:
+          - "cloudflare"
+    extractors:
+      - type: regex
+        name: version
+        group: 1
+        regex:
+          - "version=([0-9.]+)"
+      - type: kval
+        name: server
+        kval:
+          - Server
+"#;
+
+    fn args(template: &str, target: &str) -> NucleiTemplateArgs {
+        NucleiTemplateArgs {
+            template: template.to_string(),
+            target: target.to_string(),
+            timeout_secs: 5,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_template_matches_and_does_not_match() {
+        let target = spawn_server().await;
+        let tool = NucleiTemplateTool::with_client(reqwest::Client::new());
+
+        let output = tool.call(args(TEMPLATE, &target)).await.unwrap();
+        assert!(output.matched);
+        assert_eq!(output.severity, Severity::Medium);
+        assert_eq!(output.results.len(), 2);
+
+        let admin = &output.results[0];
+        assert!(admin.matched);
+        assert_eq!(admin.url, format!("{}/admin", target));
+        assert_eq!(admin.extracted["version"], vec!["1.2.3"]);
+        assert_eq!(admin.extracted["server"], vec!["mock-httpd"]);
+
+        let missing = &output.results[1];
+        assert!(!missing.matched);
+        assert_eq!(missing.status_code, 404);
+
+        assert_eq!(output.findings.len(), 1);
+        let finding = &output.findings[0];
+        assert_eq!(finding.plugin_id, "nuclei:exposed-admin-panel");
+        assert_eq!(finding.cwe.as_deref(), Some("CWE-200"));
+        assert_eq!(finding.url, format!("{}/admin", target));
+        assert!(finding.evidence.contains("version=1.2.3"));
+
+        // Same match twice yields the same dedup signature
+        let again = tool.call(args(TEMPLATE, &target)).await.unwrap();
+        assert_eq!(
+            again.findings[0].calculate_signature(),
+            finding.calculate_signature()
+        );
+
+        // With "or" the 404 page still matches none of the matchers
+        let or_template = TEMPLATE
+            .replace("matchers-condition: and", "matchers-condition: or")
+            .replace("negative: true", "negative: false")
+            .replace("\"{{BaseURL}}/admin\"\n      - ", "");
+        let output = tool.call(args(&or_template, &target)).await.unwrap();
+        assert_eq!(output.results.len(), 1);
+        assert!(!output.matched, "404 page matches none of the matchers");
+
+        let negative = TEMPLATE.replace("\"cloudflare\"", "\"mock-httpd\"");
+        let output = tool.call(args(&negative, &target)).await.unwrap();
+        assert!(!output.matched);
+        assert!(output.findings.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_unsupported_templates() {
+        assert!(matches!(
+            NucleiTemplate::parse("id: t\nhttp:\n  - raw:\n      - GET / HTTP/1.1\n    matchers:\n      - type: status\n        status: [200]\n"),
+            Err(NucleiTemplateError::Unsupported(_))
+        ));
+        assert!(matches!(
+            NucleiTemplate::parse("id: t\nhttp:\n  - path: ['{{BaseURL}}']\n    matchers:\n      - type: dsl\n        dsl: ['true']\n"),
+            Err(NucleiTemplateError::Unsupported(_))
+        ));
+        assert!(matches!(
+            NucleiTemplate::parse("id: t\nhttp:\n  - path: ['{{BaseURL}}']\n    matchers:\n      - type: binary\n        binary: ['zz']\n"),
+            Err(NucleiTemplateError::InvalidTemplate(_))
+        ));
+        assert!(NucleiTemplate::parse(
+            "id: t\nrequests:\n  - path: ['{{BaseURL}}']\n    matchers:\n      - type: status\n        status: [200]\n"
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let target = reqwest::Url::parse("https://example.com:8443/app/").unwrap();
+        assert_eq!(
+            substitute("{{BaseURL}}/login", &target),
+            "https://example.com:8443/app/login"
+        );
+        assert_eq!(
+            substitute("{{RootURL}}/x {{Hostname}} {{Host}} {{Port}}", &target),
+            "https://example.com:8443/x example.com:8443 example.com 8443"
+        );
+    }
+}
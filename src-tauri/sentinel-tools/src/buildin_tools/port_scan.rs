@@ -206,14 +206,20 @@ impl Tool for PortScanTool {
         // Scan ports concurrently
         let semaphore = Arc::new(Semaphore::new(threads));
         let mut tasks = Vec::new();
+        // Spawned probes outlive a dropped call, so they check the scope themselves
+        let cancel = crate::cancellation::current_token().unwrap_or_default();
 
         for port in &ports {
             let sem = semaphore.clone();
             let port = *port;
+            let cancel = cancel.clone();
 
             let task = tokio::spawn(async move {
                 let _permit = sem.acquire().await.unwrap();
-                Self::scan_port(target_ip, port, timeout_ms).await
+                if cancel.is_cancelled() {
+                    return None;
+                }
+                Some(Self::scan_port(target_ip, port, timeout_ms).await)
             });
             tasks.push(task);
         }
@@ -221,12 +227,15 @@ impl Tool for PortScanTool {
         // Collect results
         let mut open_ports = Vec::new();
         for task in tasks {
-            if let Ok(result) = task.await {
+            if let Ok(Some(result)) = task.await {
                 if result.status == "open" {
                     open_ports.push(result);
                 }
             }
         }
+        if cancel.is_cancelled() {
+            return Err(PortScanError::ScanFailed("scan cancelled".to_string()));
+        }
 
         // Sort by port number
         open_ports.sort_by_key(|p| p.port);
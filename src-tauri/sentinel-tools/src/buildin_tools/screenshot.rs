@@ -0,0 +1,320 @@
+//! URL screenshot tool using rig-core Tool trait
+//!
+//! Opens a URL in a headless browser (the agent-browser/Playwright daemon used
+//! by the web explorer, in its own session so it does not disturb an ongoing
+//! browsing task) and returns a PNG screenshot of the viewport or the full page.
+//! The browser is launched through the global proxy, honoring `no_proxy`.
+
+use crate::agent_browser::client::BrowserClient;
+use crate::agent_browser::ensure_daemon;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use once_cell::sync::Lazy;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use sentinel_core::global_proxy::{
+    active_proxy_endpoint, get_global_proxy, GlobalProxyConfig, NoProxyRules, ProxyEndpoint,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Browser session dedicated to screenshots
+const SCREENSHOT_SESSION: &str = "screenshot";
+const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+const MAX_TIMEOUT_MS: u64 = 120_000;
+const DEFAULT_VIEWPORT_WIDTH: u32 = 1280;
+const DEFAULT_VIEWPORT_HEIGHT: u32 = 720;
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// The screenshot session drives a single page, so captures run one at a time
+static CAPTURE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Screenshot arguments
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct ScreenshotArgs {
+    /// URL to capture (http or https)
+    pub url: String,
+    /// Capture the full scrollable page instead of the viewport
+    #[serde(default)]
+    pub full_page: bool,
+    /// CSS selector to wait for before capturing
+    #[serde(default)]
+    pub wait_for_selector: Option<String>,
+    /// Navigation timeout in milliseconds (default 30000, max 120000)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Viewport width in pixels (default 1280)
+    #[serde(default)]
+    pub viewport_width: Option<u32>,
+    /// Viewport height in pixels (default 720)
+    #[serde(default)]
+    pub viewport_height: Option<u32>,
+}
+
+/// Screenshot result
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenshotOutput {
+    /// Final URL after redirects
+    pub url: String,
+    pub title: String,
+    /// Base64 encoded PNG
+    pub base64: String,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+    pub full_page: bool,
+}
+
+/// Screenshot errors
+#[derive(Debug, thiserror::Error)]
+pub enum ScreenshotError {
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+    #[error("Browser error: {0}")]
+    Browser(String),
+    #[error("Timeout: {0}")]
+    Timeout(String),
+    #[error("Invalid image: {0}")]
+    InvalidImage(String),
+}
+
+/// Width and height from a PNG IHDR chunk
+fn png_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.len() < 24 || bytes[..8] != PNG_SIGNATURE || &bytes[12..16] != b"IHDR" {
+        return None;
+    }
+    let width = u32::from_be_bytes(bytes[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(bytes[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// Playwright `launch` proxy option for the active global proxy
+///
+/// Chromium does not accept credentials in the proxy URL, so they are passed
+/// separately; `no_proxy` entries become the bypass list.
+fn launch_proxy(config: &GlobalProxyConfig, endpoint: Option<&ProxyEndpoint>) -> Option<Value> {
+    if !config.enabled {
+        return None;
+    }
+    let endpoint = endpoint?;
+    let mut proxy = json!({
+        "server": format!(
+            "{}://{}:{}",
+            endpoint.scheme.as_deref().unwrap_or("http"),
+            endpoint.host,
+            endpoint.port
+        ),
+    });
+    if let (Some(username), Some(password)) = (&endpoint.username, &endpoint.password) {
+        proxy["username"] = json!(username);
+        proxy["password"] = json!(password);
+    }
+    if let Some(no_proxy) = config.no_proxy.as_deref().filter(|s| !s.trim().is_empty()) {
+        proxy["bypass"] = json!(no_proxy);
+    }
+    Some(proxy)
+}
+
+/// URL screenshot tool
+#[derive(Debug, Clone, Default)]
+pub struct ScreenshotTool;
+
+impl ScreenshotTool {
+    pub const NAME: &'static str = "screenshot_url";
+    pub const DESCRIPTION: &'static str = "Capture a PNG screenshot of a web page in a headless browser. Returns base64 image data and its dimensions. Useful as evidence for findings and reports.";
+
+    fn capture(
+        args: &ScreenshotArgs,
+        proxy: Option<Value>,
+        timeout_ms: u64,
+    ) -> Result<(String, String, String), ScreenshotError> {
+        let browser = |e: anyhow::Error| ScreenshotError::Browser(e.to_string());
+
+        ensure_daemon(SCREENSHOT_SESSION).map_err(browser)?;
+        let client = BrowserClient::new(SCREENSHOT_SESSION)
+            .with_read_timeout(Duration::from_millis(timeout_ms + 5_000));
+
+        let mut launch = json!({
+            "headless": true,
+            "viewport": {
+                "width": args.viewport_width.unwrap_or(DEFAULT_VIEWPORT_WIDTH),
+                "height": args.viewport_height.unwrap_or(DEFAULT_VIEWPORT_HEIGHT),
+            },
+        });
+        if let Some(proxy) = proxy {
+            launch["proxy"] = proxy;
+        }
+        client.execute("launch", launch).map_err(browser)?;
+
+        let result = (|| {
+            let page = client
+                .execute(
+                    "navigate",
+                    json!({ "url": args.url, "waitUntil": "load", "timeout": timeout_ms }),
+                )
+                .map_err(browser)?;
+            if let Some(selector) = args.wait_for_selector.as_deref() {
+                client.wait(Some(selector), Some(timeout_ms)).map_err(|e| {
+                    ScreenshotError::Timeout(format!("waiting for '{}': {}", selector, e))
+                })?;
+            }
+            let shot = client.screenshot(args.full_page, None).map_err(browser)?;
+            let base64 = shot["base64"]
+                .as_str()
+                .ok_or_else(|| ScreenshotError::Browser("No screenshot data returned".into()))?
+                .to_string();
+            Ok((
+                page["url"].as_str().unwrap_or(&args.url).to_string(),
+                page["title"].as_str().unwrap_or_default().to_string(),
+                base64,
+            ))
+        })();
+
+        // Close so the next capture relaunches with the current proxy settings
+        let _ = client.close();
+        result
+    }
+}
+
+impl Tool for ScreenshotTool {
+    const NAME: &'static str = Self::NAME;
+    type Args = ScreenshotArgs;
+    type Output = ScreenshotOutput;
+    type Error = ScreenshotError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: Self::DESCRIPTION.to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(ScreenshotArgs))
+                .unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let url = reqwest::Url::parse(&args.url)
+            .map_err(|e| ScreenshotError::InvalidUrl(format!("{}: {}", args.url, e)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(ScreenshotError::InvalidUrl(format!(
+                "unsupported scheme '{}'",
+                url.scheme()
+            )));
+        }
+        let timeout_ms = args
+            .timeout_ms
+            .unwrap_or(DEFAULT_TIMEOUT_MS)
+            .clamp(1_000, MAX_TIMEOUT_MS);
+
+        let config = get_global_proxy().await;
+        let no_proxy = NoProxyRules::parse(config.no_proxy.as_deref().unwrap_or_default());
+        let proxy = if url.host_str().is_some_and(|host| no_proxy.matches(host)) {
+            None
+        } else {
+            launch_proxy(&config, active_proxy_endpoint().await.as_ref())
+        };
+
+        let _guard = CAPTURE_LOCK.lock().await;
+        let full_page = args.full_page;
+        // Allow for browser launch on top of the navigation timeout
+        let deadline = Duration::from_millis(timeout_ms * 2 + 10_000);
+        let (final_url, title, base64) = tokio::time::timeout(
+            deadline,
+            tokio::task::spawn_blocking(move || Self::capture(&args, proxy, timeout_ms)),
+        )
+        .await
+        .map_err(|_| ScreenshotError::Timeout(format!("no screenshot after {:?}", deadline)))?
+        .map_err(|e| ScreenshotError::Browser(format!("Task execution failed: {}", e)))??;
+
+        let bytes = BASE64
+            .decode(base64.as_bytes())
+            .map_err(|e| ScreenshotError::InvalidImage(e.to_string()))?;
+        let (width, height) = png_dimensions(&bytes)
+            .ok_or_else(|| ScreenshotError::InvalidImage("not a PNG image".to_string()))?;
+
+        Ok(ScreenshotOutput {
+            url: final_url,
+            title,
+            base64,
+            mime_type: "image/png".to_string(),
+            width,
+            height,
+            full_page,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_png_dimensions_and_launch_proxy() {
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend_from_slice(&13u32.to_be_bytes());
+        png.extend_from_slice(b"IHDR");
+        png.extend_from_slice(&1280u32.to_be_bytes());
+        png.extend_from_slice(&2400u32.to_be_bytes());
+        assert_eq!(png_dimensions(&png), Some((1280, 2400)));
+        assert_eq!(png_dimensions(b"GIF89a..................."), None);
+
+        let config = GlobalProxyConfig {
+            enabled: true,
+            no_proxy: Some("*.corp.local".to_string()),
+            ..Default::default()
+        };
+        let endpoint = ProxyEndpoint {
+            scheme: Some("socks5".to_string()),
+            host: "10.0.0.2".to_string(),
+            port: 1080,
+            username: Some("user".to_string()),
+            password: Some("pass".to_string()),
+        };
+        let proxy = launch_proxy(&config, Some(&endpoint)).unwrap();
+        assert_eq!(proxy["server"], "socks5://10.0.0.2:1080");
+        assert_eq!(proxy["username"], "user");
+        assert_eq!(proxy["bypass"], "*.corp.local");
+        assert!(launch_proxy(&GlobalProxyConfig::default(), Some(&endpoint)).is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    #[ignore] // Requires the agent-browser daemon and Playwright Chromium
+    async fn test_screenshot_local_static_page() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            let body = "<html><head><title>Static</title></head>\
+                        <body><h1 id=\"ready\">Hello</h1></body></html>";
+            for mut stream in listener.incoming().flatten() {
+                let mut buf = [0u8; 2048];
+                let _ = stream.read(&mut buf);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        });
+
+        let output = ScreenshotTool
+            .call(ScreenshotArgs {
+                url: format!("http://127.0.0.1:{}/", port),
+                full_page: false,
+                wait_for_selector: Some("#ready".to_string()),
+                timeout_ms: Some(15_000),
+                viewport_width: Some(800),
+                viewport_height: Some(600),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(output.title, "Static");
+        assert!(!output.base64.is_empty());
+        let bytes = BASE64.decode(&output.base64).unwrap();
+        assert_eq!(bytes[..8], PNG_SIGNATURE);
+        assert!(output.width > 0 && output.height > 0);
+    }
+}
@@ -1,10 +1,32 @@
 //! Subdomain brute-force tool using rig-core Tool trait
+//!
+//! The word list (inline, file, or rsubdomain's built-in dictionary) is scanned
+//! in chunks and progress is recorded per (domain, dictionary) pair after each
+//! chunk, so an interrupted run can be resumed with `resume: true`. A chunk
+//! that fails after earlier chunks succeeded is treated as resolver rate
+//! limiting and retried after an exponential backoff.
 
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
 use rig::tool::Tool;
-use rsubdomain::{SubdomainBruteConfig, SubdomainBruteEngine};
+use rsubdomain::{
+    DnsResolver, DomainVerifier, SubdomainBruteConfig, SubdomainBruteEngine, SubdomainResult,
+    WildcardDetector,
+};
 use schemars::JsonSchema;
+use sentinel_db::{DatabaseService, SubdomainBruteCheckpoint};
 use serde::{Deserialize, Serialize};
-use std::time::Instant;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use super::content_discovery::BackoffConfig;
+
+/// Words tried per engine run; progress is checkpointed after each chunk
+const CHECKPOINT_CHUNK_SIZE: usize = 500;
 
 /// Subdomain brute-force arguments
 #[derive(Debug, Clone, Deserialize, JsonSchema)]
@@ -32,6 +54,13 @@ pub struct SubdomainBruteArgs {
     /// Enable DNS record resolution
     #[serde(default = "default_resolve_records")]
     pub resolve_records: bool,
+    /// Resume from the last checkpoint of an interrupted run with the same domain and dictionary
+    #[serde(default)]
+    pub resume: bool,
+    /// Pauses before retrying a chunk the resolvers failed; only the backoff
+    /// durations and `max_backoffs` apply to DNS scans
+    #[serde(default)]
+    pub backoff: BackoffConfig,
 }
 
 fn default_resolvers() -> String {
@@ -51,7 +80,7 @@ fn default_resolve_records() -> bool {
 }
 
 /// Single subdomain result
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubdomainInfo {
     pub domain: String,
     pub ip: String,
@@ -70,8 +99,15 @@ pub struct SubdomainInfo {
 #[derive(Debug, Clone, Serialize)]
 pub struct SubdomainBruteOutput {
     pub target_domains: Vec<String>,
+    /// Subdomains found by this run (results reported before a resume are not repeated)
     pub subdomains: Vec<SubdomainInfo>,
     pub total_found: usize,
+    /// Subdomains already reported by the interrupted run this one resumed
+    pub previously_found: usize,
+    /// The resolvers failed a chunk at some point and the scan backed off
+    pub rate_limited: bool,
+    /// Number of backoff pauses taken
+    pub backoffs: usize,
     pub scan_duration_ms: u64,
 }
 
@@ -86,6 +122,307 @@ pub enum SubdomainBruteError {
     ScanFailed(String),
 }
 
+/// Persistence for brute-force progress
+#[async_trait]
+pub trait SubdomainCheckpointStore: Send + Sync {
+    async fn load(
+        &self,
+        domain: &str,
+        dictionary_key: &str,
+    ) -> anyhow::Result<Option<SubdomainBruteCheckpoint>>;
+    async fn save(&self, checkpoint: &SubdomainBruteCheckpoint) -> anyhow::Result<()>;
+    async fn clear(&self, domain: &str, dictionary_key: &str) -> anyhow::Result<()>;
+}
+
+#[async_trait]
+impl SubdomainCheckpointStore for DatabaseService {
+    async fn load(
+        &self,
+        domain: &str,
+        dictionary_key: &str,
+    ) -> anyhow::Result<Option<SubdomainBruteCheckpoint>> {
+        self.get_subdomain_brute_checkpoint(domain, dictionary_key)
+            .await
+    }
+
+    async fn save(&self, checkpoint: &SubdomainBruteCheckpoint) -> anyhow::Result<()> {
+        self.save_subdomain_brute_checkpoint(checkpoint).await
+    }
+
+    async fn clear(&self, domain: &str, dictionary_key: &str) -> anyhow::Result<()> {
+        self.delete_subdomain_brute_checkpoint(domain, dictionary_key)
+            .await
+    }
+}
+
+static CHECKPOINT_STORE: Lazy<RwLock<Option<Arc<dyn SubdomainCheckpointStore>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+/// Set the store used for brute-force checkpoints
+pub async fn set_subdomain_checkpoint_store(store: Arc<dyn SubdomainCheckpointStore>) {
+    *CHECKPOINT_STORE.write().await = Some(store);
+}
+
+/// Outcome of a checkpointed scan of one domain
+struct CheckpointedRun {
+    found: Vec<SubdomainInfo>,
+    previously_found: usize,
+    backoffs: usize,
+}
+
+/// Scan `words` in chunks, saving progress after each chunk
+///
+/// With `resume`, scanning starts at the stored offset and subdomains the
+/// interrupted run already reported are not returned again. The checkpoint is
+/// cleared once the word list is exhausted.
+///
+/// Once a chunk has succeeded, a failing chunk is retried after a doubling
+/// pause (honouring `backoff`); the run fails when the resolvers keep failing
+/// after `max_backoffs` pauses, leaving the checkpoint in place for a resume.
+#[allow(clippy::too_many_arguments)]
+async fn run_checkpointed<F, Fut>(
+    domain: &str,
+    dictionary_key: &str,
+    words: &[String],
+    resume: bool,
+    chunk_size: usize,
+    backoff: &BackoffConfig,
+    store: Option<&dyn SubdomainCheckpointStore>,
+    mut scan: F,
+) -> Result<CheckpointedRun, SubdomainBruteError>
+where
+    F: FnMut(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<Vec<SubdomainInfo>, SubdomainBruteError>>,
+{
+    let total = words.len();
+    let mut offset = 0;
+    let mut found_before: Vec<SubdomainInfo> = Vec::new();
+
+    if let Some(store) = store {
+        if resume {
+            match store.load(domain, dictionary_key).await {
+                Ok(Some(checkpoint)) if checkpoint.total_words == total as i64 => {
+                    offset = (checkpoint.next_offset.max(0) as usize).min(total);
+                    found_before = serde_json::from_str(&checkpoint.found_json).unwrap_or_default();
+                    tracing::info!(
+                        "Resuming subdomain brute for {} at word {}/{}",
+                        domain,
+                        offset,
+                        total
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to load subdomain brute checkpoint: {}", e),
+            }
+        } else if let Err(e) = store.clear(domain, dictionary_key).await {
+            tracing::warn!("Failed to clear subdomain brute checkpoint: {}", e);
+        }
+    }
+
+    let mut seen: HashSet<String> = found_before.iter().map(|f| f.domain.clone()).collect();
+    let mut found = Vec::new();
+    let max_backoff = Duration::from_millis(backoff.max_backoff_ms);
+    let mut next_backoff = Duration::from_millis(backoff.initial_backoff_ms).min(max_backoff);
+    let mut backoffs = 0;
+    let mut scanned_chunk = false;
+
+    while offset < total {
+        // Progress up to `offset` is already checkpointed, so a cancelled run can resume
+        if crate::cancellation::is_cancelled() {
+            return Err(SubdomainBruteError::ScanFailed(
+                "scan cancelled".to_string(),
+            ));
+        }
+        let end = (offset + chunk_size.max(1)).min(total);
+        let results = match scan(words[offset..end].to_vec()).await {
+            Ok(results) => results,
+            // Without a successful chunk the failure is a setup problem, not throttling
+            Err(e) if !scanned_chunk || crate::cancellation::is_cancelled() => return Err(e),
+            Err(e) if backoffs >= backoff.max_backoffs => {
+                tracing::warn!(
+                    "Resolvers keep failing for {} after {} backoffs, stopping",
+                    domain,
+                    backoffs
+                );
+                return Err(e);
+            }
+            Err(e) => {
+                tracing::info!(
+                    "Subdomain brute chunk for {} failed ({}), retrying in {:?}",
+                    domain,
+                    e,
+                    next_backoff
+                );
+                backoffs += 1;
+                tokio::time::sleep(next_backoff).await;
+                next_backoff = (next_backoff * 2).min(max_backoff);
+                continue;
+            }
+        };
+        scanned_chunk = true;
+        next_backoff = Duration::from_millis(backoff.initial_backoff_ms).min(max_backoff);
+        for result in results {
+            if seen.insert(result.domain.clone()) {
+                found.push(result);
+            }
+        }
+        offset = end;
+
+        if let (Some(store), true) = (store, offset < total) {
+            let reported: Vec<&SubdomainInfo> = found_before.iter().chain(found.iter()).collect();
+            let checkpoint = SubdomainBruteCheckpoint {
+                domain: domain.to_string(),
+                dictionary_key: dictionary_key.to_string(),
+                next_offset: offset as i64,
+                total_words: total as i64,
+                found_json: serde_json::to_string(&reported).unwrap_or_else(|_| "[]".to_string()),
+            };
+            if let Err(e) = store.save(&checkpoint).await {
+                tracing::warn!("Failed to save subdomain brute checkpoint: {}", e);
+            }
+        }
+    }
+
+    if let Some(store) = store {
+        if let Err(e) = store.clear(domain, dictionary_key).await {
+            tracing::warn!("Failed to clear subdomain brute checkpoint: {}", e);
+        }
+    }
+
+    Ok(CheckpointedRun {
+        found,
+        previously_found: found_before.len(),
+        backoffs,
+    })
+}
+
+/// rsubdomain engine state shared by every chunk of a run
+///
+/// `SubdomainBruteEngine` fixes its word list at construction, so each chunk
+/// runs a bare DNS engine while the wildcard detector (with the per-domain
+/// wildcard IPs it learned), the HTTP verifier and the record resolver are
+/// created once and reused.
+struct ChunkedBruteEngine {
+    config: SubdomainBruteConfig,
+    wildcard: Option<WildcardDetector>,
+    verifier: Option<DomainVerifier>,
+    resolver: Option<DnsResolver>,
+}
+
+impl ChunkedBruteEngine {
+    async fn new(config: SubdomainBruteConfig) -> Result<Self, SubdomainBruteError> {
+        let setup_error = |e: Box<dyn std::error::Error>| {
+            SubdomainBruteError::ConfigError(format!("Failed to set up engine: {}", e))
+        };
+        let wildcard = if config.skip_wildcard {
+            let detector = WildcardDetector::new().await.map_err(setup_error)?;
+            for domain in &config.domains {
+                if let Ok(true) = detector.detect_wildcard(domain).await {
+                    tracing::info!("Wildcard DNS detected for {}", domain);
+                }
+            }
+            Some(detector)
+        } else {
+            None
+        };
+        let verifier = if config.verify_mode {
+            Some(DomainVerifier::new(10).map_err(setup_error)?)
+        } else {
+            None
+        };
+        let resolver = if config.resolve_records {
+            Some(DnsResolver::new().await.map_err(setup_error)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            config: SubdomainBruteConfig {
+                skip_wildcard: false,
+                verify_mode: false,
+                resolve_records: false,
+                ..config
+            },
+            wildcard,
+            verifier,
+            resolver,
+        })
+    }
+
+    /// Brute-force `words` below `domain`
+    async fn scan(
+        &self,
+        domain: &str,
+        words: Vec<String>,
+    ) -> Result<Vec<SubdomainInfo>, SubdomainBruteError> {
+        let config = SubdomainBruteConfig {
+            domains: vec![domain.to_string()],
+            dictionary: Some(words),
+            ..self.config.clone()
+        };
+        let run = async {
+            let engine = SubdomainBruteEngine::new(config).await?;
+            engine.run_brute_force().await
+        };
+        let results = crate::cancellation::run_cancellable(run)
+            .await
+            .map_err(|_| SubdomainBruteError::ScanFailed("scan cancelled".to_string()))?
+            .map_err(|e| SubdomainBruteError::ScanFailed(e.to_string()))?;
+
+        let results: Vec<SubdomainResult> = results
+            .into_iter()
+            .filter(|r| {
+                let Some(detector) = &self.wildcard else {
+                    return true;
+                };
+                r.ip.parse::<std::net::Ipv4Addr>()
+                    .map_or(true, |ip| !detector.is_wildcard_result(&r.domain, &ip))
+            })
+            .collect();
+        let names: Vec<String> = results.iter().map(|r| r.domain.clone()).collect();
+        let mut verified: HashMap<String, _> = match &self.verifier {
+            Some(verifier) => verifier
+                .verify_domains(names.clone())
+                .await
+                .into_iter()
+                .map(|v| (v.domain.clone(), v))
+                .collect(),
+            None => HashMap::new(),
+        };
+        let mut records: HashMap<String, usize> = match &self.resolver {
+            Some(resolver) => resolver
+                .resolve_domains(names)
+                .await
+                .into_iter()
+                .map(|d| (d.domain.clone(), d.records.len()))
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        Ok(results
+            .into_iter()
+            .map(|r| {
+                let verified = verified.remove(&r.domain);
+                SubdomainInfo {
+                    http_status: verified.as_ref().and_then(|v| v.http_status),
+                    https_status: verified.as_ref().and_then(|v| v.https_status),
+                    title: verified.and_then(|v| v.title),
+                    dns_records_count: records.remove(&r.domain),
+                    domain: r.domain,
+                    ip: r.ip,
+                    record_type: r.record_type,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Stable key for a word list
+fn dictionary_key(source: &str, words: &[String]) -> String {
+    let digest = Sha256::digest(words.join("\n").as_bytes());
+    let hash: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}:{}", source, hash)
+}
+
 /// Subdomain brute-force tool
 #[derive(Debug, Clone, Default)]
 pub struct SubdomainBruteTool;
@@ -100,8 +437,39 @@ impl SubdomainBruteTool {
             .collect()
     }
 
+    /// Resolve the word list and its checkpoint key
+    async fn resolve_words(
+        dictionary: Option<&str>,
+        dictionary_file: Option<&str>,
+    ) -> Result<(String, Vec<String>), SubdomainBruteError> {
+        if let Some(dictionary) = dictionary {
+            let words = Self::parse_list(dictionary);
+            return Ok((dictionary_key("inline", &words), words));
+        }
+        if let Some(path) = dictionary_file {
+            let content = tokio::fs::read_to_string(path).await.map_err(|e| {
+                SubdomainBruteError::ConfigError(format!(
+                    "Failed to read dictionary file {}: {}",
+                    path, e
+                ))
+            })?;
+            let words: Vec<String> = content
+                .lines()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .collect();
+            return Ok((dictionary_key("file", &words), words));
+        }
+        // rsubdomain's own built-in list, as used before checkpointing existed
+        let words: Vec<String> = rsubdomain::subdata::get_default_sub_next_data()
+            .iter()
+            .map(|w| w.to_string())
+            .collect();
+        Ok((dictionary_key("builtin", &words), words))
+    }
+
     pub const NAME: &'static str = "subdomain_brute";
-    pub const DESCRIPTION: &'static str = "High-performance subdomain brute-force scanner. Discovers subdomains using dictionary attack with DNS resolution, HTTP/HTTPS verification, and wildcard detection.";
+    pub const DESCRIPTION: &'static str = "High-performance subdomain brute-force scanner. Discovers subdomains using dictionary attack with DNS resolution, HTTP/HTTPS verification, and wildcard detection. Progress is checkpointed; pass resume=true to continue an interrupted run.";
 }
 
 impl Tool for SubdomainBruteTool {
@@ -135,36 +503,58 @@ impl Tool for SubdomainBruteTool {
         // Parse resolvers
         let resolvers = Self::parse_list(&args.resolvers);
 
-        // Parse dictionary if provided
-        let dictionary = args.dictionary.map(|d| Self::parse_list(&d));
+        let store = CHECKPOINT_STORE.read().await.clone();
+        let (key, words) =
+            Self::resolve_words(args.dictionary.as_deref(), args.dictionary_file.as_deref())
+                .await?;
 
-        // Create config
         let config = SubdomainBruteConfig {
             domains: domains.clone(),
             resolvers,
-            dictionary_file: args.dictionary_file,
-            dictionary,
+            dictionary_file: None,
+            dictionary: None,
             skip_wildcard: args.skip_wildcard,
-            bandwidth_limit: args.bandwidth_limit,
+            bandwidth_limit: args.bandwidth_limit.clone(),
             verify_mode: args.verify_mode,
             resolve_records: args.resolve_records,
             silent: true,
             device: None,
         };
+        let resume = args.resume;
+        let backoff = args.backoff.clone();
+        let target_domains = domains.clone();
+        // The blocking thread does not inherit the task-local scope
+        let cancel = crate::cancellation::current_token().unwrap_or_default();
 
-        // Run in blocking context because rsubdomain is not Send-safe
-        let results = tokio::task::spawn_blocking(move || {
-            tokio::runtime::Handle::current().block_on(async move {
-                let engine = SubdomainBruteEngine::new(config)
-                    .await
-                    .map_err(|e| e.to_string())?;
-                engine
-                    .run_brute_force()
-                    .await
-                    .map_err(|e| e.to_string())
-            })
+        // rsubdomain is not Send-safe, so the whole run stays on one blocking thread
+        let (subdomains, previously_found, backoffs) = tokio::task::spawn_blocking(move || {
+            tokio::runtime::Handle::current().block_on(crate::cancellation::with_token(
+                cancel,
+                async move {
+                    let engine = ChunkedBruteEngine::new(config).await?;
+                    let mut subdomains = Vec::new();
+                    let mut previously_found = 0;
+                    let mut backoffs = 0;
+                    for domain in &target_domains {
+                        let run = run_checkpointed(
+                            domain,
+                            &key,
+                            &words,
+                            resume,
+                            CHECKPOINT_CHUNK_SIZE,
+                            &backoff,
+                            store.as_deref(),
+                            |chunk| engine.scan(domain, chunk),
+                        )
+                        .await?;
+                        subdomains.extend(run.found);
+                        previously_found += run.previously_found;
+                        backoffs += run.backoffs;
+                    }
+                    Ok::<_, SubdomainBruteError>((subdomains, previously_found, backoffs))
+                },
+            ))
         })
-
         .await
         .map_err(|e| {
             if e.is_panic() {
@@ -172,36 +562,7 @@ impl Tool for SubdomainBruteTool {
             } else {
                 SubdomainBruteError::ScanFailed(format!("Task execution failed: {}", e))
             }
-        })?
-        .map_err(SubdomainBruteError::ScanFailed)?;
-
-        // Convert results
-        let subdomains: Vec<SubdomainInfo> = results
-            .iter()
-            .map(|r| {
-                let (http_status, https_status, title) = if let Some(ref verified) = r.verified {
-                    (
-                        verified.http_status,
-                        verified.https_status,
-                        verified.title.clone(),
-                    )
-                } else {
-                    (None, None, None)
-                };
-
-                let dns_records_count = r.dns_records.as_ref().map(|d| d.records.len());
-
-                SubdomainInfo {
-                    domain: r.domain.clone(),
-                    ip: r.ip.clone(),
-                    record_type: r.record_type.clone(),
-                    http_status,
-                    https_status,
-                    title,
-                    dns_records_count,
-                }
-            })
-            .collect();
+        })??;
 
         let total_found = subdomains.len();
         let scan_duration_ms = start_time.elapsed().as_millis() as u64;
@@ -210,6 +571,9 @@ impl Tool for SubdomainBruteTool {
             target_domains: domains,
             subdomains,
             total_found,
+            previously_found,
+            rate_limited: backoffs > 0,
+            backoffs,
             scan_duration_ms,
         })
     }
@@ -228,4 +592,216 @@ mod tests {
         let resolvers = SubdomainBruteTool::parse_list("8.8.8.8, 1.1.1.1");
         assert_eq!(resolvers.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_default_word_list_is_builtin_and_checkpointable() {
+        let (key, words) = SubdomainBruteTool::resolve_words(None, None).await.unwrap();
+        assert!(key.starts_with("builtin:"));
+        assert!(words.iter().any(|w| w == "www"));
+
+        let (again, _) = SubdomainBruteTool::resolve_words(None, None).await.unwrap();
+        assert_eq!(key, again);
+    }
+
+    #[derive(Default)]
+    struct MemoryStore {
+        checkpoints: std::sync::Mutex<std::collections::HashMap<String, SubdomainBruteCheckpoint>>,
+    }
+
+    #[async_trait]
+    impl SubdomainCheckpointStore for MemoryStore {
+        async fn load(
+            &self,
+            domain: &str,
+            dictionary_key: &str,
+        ) -> anyhow::Result<Option<SubdomainBruteCheckpoint>> {
+            let key = format!("{}|{}", domain, dictionary_key);
+            Ok(self.checkpoints.lock().unwrap().get(&key).cloned())
+        }
+
+        async fn save(&self, checkpoint: &SubdomainBruteCheckpoint) -> anyhow::Result<()> {
+            let key = format!("{}|{}", checkpoint.domain, checkpoint.dictionary_key);
+            self.checkpoints
+                .lock()
+                .unwrap()
+                .insert(key, checkpoint.clone());
+            Ok(())
+        }
+
+        async fn clear(&self, domain: &str, dictionary_key: &str) -> anyhow::Result<()> {
+            let key = format!("{}|{}", domain, dictionary_key);
+            self.checkpoints.lock().unwrap().remove(&key);
+            Ok(())
+        }
+    }
+
+    fn found(name: &str) -> SubdomainInfo {
+        SubdomainInfo {
+            domain: format!("{}.example.com", name),
+            ip: "10.0.0.1".to_string(),
+            record_type: "A".to_string(),
+            http_status: None,
+            https_status: None,
+            title: None,
+            dns_records_count: None,
+        }
+    }
+
+    /// Resolves w1 and w7; fails on the chunk starting at `fail_at`
+    fn fake_scan(
+        scanned: &std::sync::Mutex<Vec<String>>,
+        fail_at: Option<&str>,
+        chunk: Vec<String>,
+    ) -> Result<Vec<SubdomainInfo>, SubdomainBruteError> {
+        if fail_at.is_some_and(|w| chunk.first().map(String::as_str) == Some(w)) {
+            return Err(SubdomainBruteError::ScanFailed("interrupted".to_string()));
+        }
+        scanned.lock().unwrap().extend(chunk.iter().cloned());
+        Ok(chunk
+            .iter()
+            .filter(|w| *w == "w1" || *w == "w7")
+            .map(|w| found(w))
+            .collect())
+    }
+
+    fn fast_backoff() -> BackoffConfig {
+        BackoffConfig {
+            initial_backoff_ms: 10,
+            max_backoff_ms: 40,
+            max_backoffs: 2,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failing_chunk_is_retried_after_backoff() {
+        let words: Vec<String> = (0..10).map(|i| format!("w{}", i)).collect();
+        let key = dictionary_key("inline", &words);
+        let scanned = std::sync::Mutex::new(Vec::new());
+        let failures = std::sync::atomic::AtomicUsize::new(0);
+
+        // The chunk starting at w3 fails twice, as when the resolvers throttle the scan
+        let start = Instant::now();
+        let run = run_checkpointed(
+            "example.com",
+            &key,
+            &words,
+            false,
+            3,
+            &fast_backoff(),
+            None,
+            |c| {
+                let fail =
+                    c[0] == "w3" && failures.fetch_add(1, std::sync::atomic::Ordering::SeqCst) < 2;
+                std::future::ready(fake_scan(&scanned, fail.then_some("w3"), c))
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(run.backoffs, 2);
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert_eq!(scanned.lock().unwrap().len(), 10);
+        assert_eq!(run.found.len(), 2);
+
+        // A failing first chunk is a setup error and is not retried
+        let run = run_checkpointed(
+            "example.com",
+            &key,
+            &words,
+            false,
+            3,
+            &fast_backoff(),
+            None,
+            |c| std::future::ready(fake_scan(&scanned, Some("w0"), c)),
+        )
+        .await;
+        assert!(run.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resume_continues_from_checkpoint() {
+        let store = MemoryStore::default();
+        let words: Vec<String> = (0..10).map(|i| format!("w{}", i)).collect();
+        let key = dictionary_key("inline", &words);
+        let scanned = std::sync::Mutex::new(Vec::new());
+
+        let err = run_checkpointed(
+            "example.com",
+            &key,
+            &words,
+            false,
+            3,
+            &fast_backoff(),
+            Some(&store),
+            |c| std::future::ready(fake_scan(&scanned, Some("w6"), c)),
+        )
+        .await;
+        assert!(err.is_err());
+        let checkpoint = store.load("example.com", &key).await.unwrap().unwrap();
+        assert_eq!(checkpoint.next_offset, 6);
+        assert!(checkpoint.found_json.contains("w1.example.com"));
+
+        scanned.lock().unwrap().clear();
+        let run = run_checkpointed(
+            "example.com",
+            &key,
+            &words,
+            true,
+            3,
+            &fast_backoff(),
+            Some(&store),
+            |c| std::future::ready(fake_scan(&scanned, None, c)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            *scanned.lock().unwrap(),
+            vec!["w6", "w7", "w8", "w9"]
+                .into_iter()
+                .map(String::from)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(run.previously_found, 1);
+        assert_eq!(run.found.len(), 1);
+        assert_eq!(run.found[0].domain, "w7.example.com");
+        assert!(store.load("example.com", &key).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_scope_stops_between_chunks() {
+        let store = MemoryStore::default();
+        let words: Vec<String> = (0..10).map(|i| format!("w{}", i)).collect();
+        let key = dictionary_key("inline", &words);
+        let scope = format!("brute-cancel-{}", uuid::Uuid::new_v4());
+        let scanned = std::sync::Mutex::new(Vec::new());
+
+        let result = crate::cancellation::with_cancellation_scope(&scope, async {
+            run_checkpointed(
+                "example.com",
+                &key,
+                &words,
+                false,
+                3,
+                &fast_backoff(),
+                Some(&store),
+                |c| {
+                    let result = fake_scan(&scanned, None, c);
+                    let scope = scope.clone();
+                    async move {
+                        crate::cancellation::cancel_scope(&scope).await;
+                        result
+                    }
+                },
+            )
+            .await
+        })
+        .await;
+        crate::cancellation::clear_cancellation_scope(&scope).await;
+
+        assert!(result.is_err());
+        assert_eq!(scanned.lock().unwrap().len(), 3);
+        let checkpoint = store.load("example.com", &key).await.unwrap().unwrap();
+        assert_eq!(checkpoint.next_offset, 3);
+    }
 }
@@ -89,6 +89,100 @@ impl TodosList {
     }
 }
 
+/// Why the plan was replaced
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplanTrigger {
+    /// A step failed and the remaining plan no longer works
+    StepFailed,
+    /// New information changed the approach
+    NewInformation,
+    /// A step or the run took too long
+    Timeout,
+    /// Any other course correction
+    Manual,
+}
+
+/// Result prefix for steps that failed because they timed out
+pub const TIMEOUT_RESULT_PREFIX: &str = "[timeout]";
+
+/// A recorded replan with the difference between the old and new plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplanEvent {
+    pub execution_id: String,
+    pub trigger: ReplanTrigger,
+    pub reason: Option<String>,
+    /// Steps that had failed when the replan happened
+    pub failed_steps: Vec<String>,
+    /// Steps dropped from the old plan
+    pub removed_steps: Vec<String>,
+    /// Steps new in the replacement plan
+    pub added_steps: Vec<String>,
+    /// Steps present in both plans
+    pub kept_steps: Vec<String>,
+    pub timestamp: i64,
+}
+
+impl ReplanEvent {
+    fn new(
+        execution_id: &str,
+        old_items: &[TodoItem],
+        new_items: &[TodoItem],
+        trigger: Option<ReplanTrigger>,
+        reason: Option<String>,
+    ) -> Self {
+        let failed_items: Vec<&TodoItem> = old_items
+            .iter()
+            .filter(|item| item.status == TodoStatus::Failed)
+            .collect();
+        let timed_out = failed_items.iter().any(|item| {
+            item.result
+                .as_deref()
+                .is_some_and(|r| r.starts_with(TIMEOUT_RESULT_PREFIX))
+        });
+        let failed_steps: Vec<String> = failed_items
+            .iter()
+            .map(|item| item.description.clone())
+            .collect();
+        let in_new = |desc: &str| new_items.iter().any(|item| item.description == desc);
+        let in_old = |desc: &str| old_items.iter().any(|item| item.description == desc);
+
+        Self {
+            execution_id: execution_id.to_string(),
+            // Without an explicit trigger, a failed step is the most likely cause
+            trigger: trigger.unwrap_or(if timed_out {
+                ReplanTrigger::Timeout
+            } else if failed_steps.is_empty() {
+                ReplanTrigger::Manual
+            } else {
+                ReplanTrigger::StepFailed
+            }),
+            reason,
+            failed_steps,
+            removed_steps: old_items
+                .iter()
+                .filter(|item| !in_new(&item.description))
+                .map(|item| item.description.clone())
+                .collect(),
+            added_steps: new_items
+                .iter()
+                .filter(|item| !in_old(&item.description))
+                .map(|item| item.description.clone())
+                .collect(),
+            kept_steps: new_items
+                .iter()
+                .filter(|item| in_old(&item.description))
+                .map(|item| item.description.clone())
+                .collect(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+}
+
+/// Replan history per execution
+static REPLAN_EVENTS: Lazy<RwLock<HashMap<String, Vec<ReplanEvent>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
 /// In-memory cache for todos (synced with database)
 static TODOS_CACHE: Lazy<Arc<RwLock<HashMap<String, TodosList>>>> =
     Lazy::new(|| Arc::new(RwLock::new(HashMap::new())));
@@ -222,6 +316,12 @@ pub struct TodosArgs {
     pub result: Option<String>,
     /// New item description (required for "update_item", "insert_item")
     pub new_description: Option<String>,
+    /// Why the plan is being replaced (for "replan"): "step_failed", "new_information", "timeout" or "manual"
+    #[serde(default)]
+    pub replan_trigger: Option<ReplanTrigger>,
+    /// Short explanation of the replan (for "replan")
+    #[serde(default)]
+    pub replan_reason: Option<String>,
 }
 
 /// Todos tool output
@@ -253,7 +353,7 @@ impl TodosTool {
     }
 
     pub const NAME: &'static str = "todos";
-    pub const DESCRIPTION: &'static str = "Manage and track the agent's execution todos. Actions: add_items (append), update_status (change status), get_list (view existing todos), reset (clear all), replan (replace all items), update_item (modify description), delete_item (remove), insert_item (add at position), cleanup (remove list). Todos persist across sessions - use get_list first to check existing todos before creating new ones.";
+    pub const DESCRIPTION: &'static str = "Manage and track the agent's execution todos. Actions: add_items (append), update_status (change status), get_list (view existing todos), reset (clear all), replan (replace all items; set replan_trigger and replan_reason to explain why), update_item (modify description), delete_item (remove), insert_item (add at position), cleanup (remove list). Todos persist across sessions - use get_list first to check existing todos before creating new ones.";
 }
 
 impl Tool for TodosTool {
@@ -370,7 +470,7 @@ impl Tool for TodosTool {
                     .ok_or_else(|| TodosError::MissingParameters("replan".to_string()))?;
 
                 // Clear existing items and add new ones (deduplicated)
-                list.items.clear();
+                let old_items = std::mem::take(&mut list.items);
                 let mut seen_descriptions = std::collections::HashSet::new();
                 for desc in new_items {
                     if seen_descriptions.insert(desc.clone()) {
@@ -389,6 +489,16 @@ impl Tool for TodosTool {
                 } else {
                     list.current_index = None;
                 }
+
+                let event = ReplanEvent::new(
+                    &execution_id,
+                    &old_items,
+                    &list.items,
+                    args.replan_trigger,
+                    args.replan_reason,
+                );
+                record_replan_event(event).await;
+
                 needs_save = true;
                 Ok(TodosOutput {
                     success: true,
@@ -583,11 +693,89 @@ pub async fn get_execution_todos(execution_id: &str) -> Option<TodosList> {
     }
 }
 
-/// Helper function to cleanup todos list for an execution
+/// Record a replan and notify the UI
+async fn record_replan_event(event: ReplanEvent) {
+    tracing::info!(
+        "Replanned execution {} ({:?}): -{} +{} steps",
+        event.execution_id,
+        event.trigger,
+        event.removed_steps.len(),
+        event.added_steps.len()
+    );
+
+    if let Some(handle) = &*APP_HANDLE.read().await {
+        let _ = handle.emit("agent:replanned", &event);
+    }
+
+    if let Some(db) = get_db_service().await {
+        match serde_json::to_string(&event) {
+            Ok(json) => {
+                if let Err(e) = db
+                    .append_agent_replan_event(&event.execution_id, &json)
+                    .await
+                {
+                    tracing::warn!("Failed to save replan event to database: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to serialize replan event: {}", e),
+        }
+    }
+
+    REPLAN_EVENTS
+        .write()
+        .await
+        .entry(event.execution_id.clone())
+        .or_default()
+        .push(event);
+}
+
+/// Helper function to mark the in-progress todo as failed, so the agent replans around it.
+/// Returns false when no todo is in progress.
+pub async fn mark_current_todo_failed(execution_id: &str, result: &str) -> bool {
+    let mut list = get_or_load_todos(execution_id).await;
+    let Some(idx) = list.current_index.filter(|idx| *idx < list.items.len()) else {
+        return false;
+    };
+    list.items[idx].status = TodoStatus::Failed;
+    list.items[idx].result = Some(result.to_string());
+
+    {
+        let mut cache = TODOS_CACHE.write().await;
+        cache.insert(execution_id.to_string(), list.clone());
+    }
+    save_todos_to_db(execution_id, &list).await;
+    true
+}
+
+/// Helper function to get the replan history for an execution (oldest first).
+/// Falls back to the database once the execution has been cleaned up.
+pub async fn get_replan_events(execution_id: &str) -> Vec<ReplanEvent> {
+    if let Some(events) = REPLAN_EVENTS.read().await.get(execution_id) {
+        return events.clone();
+    }
+
+    let Some(db) = get_db_service().await else {
+        return Vec::new();
+    };
+    match db.list_agent_replan_events(execution_id).await {
+        Ok(rows) => rows
+            .iter()
+            .filter_map(|json| serde_json::from_str(json).ok())
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to load replan events from database: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Helper function to cleanup todos list for an execution.
+/// The replan history stays in the database for later review.
 pub async fn cleanup_execution_todos(execution_id: &str) -> bool {
     let mut cache = TODOS_CACHE.write().await;
     let removed = cache.remove(execution_id).is_some();
     drop(cache);
+    REPLAN_EVENTS.write().await.remove(execution_id);
 
     delete_todos_from_db(execution_id).await;
     removed
@@ -681,3 +869,95 @@ pub async fn cleanup_all_todos() {
     let mut cache = TODOS_CACHE.write().await;
     cache.clear();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(execution_id: &str, action: &str) -> TodosArgs {
+        TodosArgs {
+            execution_id: execution_id.to_string(),
+            action: action.to_string(),
+            items: None,
+            item_index: None,
+            status: None,
+            result: None,
+            new_description: None,
+            replan_trigger: None,
+            replan_reason: None,
+        }
+    }
+
+    fn steps(items: &[&str]) -> Option<Vec<String>> {
+        Some(items.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[tokio::test]
+    async fn test_step_failure_records_replan_event() {
+        let execution_id = "test_replan_step_failed";
+        let tool = TodosTool::new();
+        tool.call(TodosArgs {
+            items: steps(&["scan ports", "probe login", "write report"]),
+            ..args(execution_id, "add_items")
+        })
+        .await
+        .unwrap();
+        tool.call(TodosArgs {
+            item_index: Some(0),
+            status: Some(TodoStatus::Failed),
+            result: Some("nmap timed out".to_string()),
+            ..args(execution_id, "update_status")
+        })
+        .await
+        .unwrap();
+        tool.call(TodosArgs {
+            items: steps(&["scan top 100 ports", "probe login", "write report"]),
+            replan_reason: Some("full port scan keeps failing".to_string()),
+            ..args(execution_id, "replan")
+        })
+        .await
+        .unwrap();
+
+        let events = get_replan_events(execution_id).await;
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.trigger, ReplanTrigger::StepFailed);
+        assert_eq!(
+            event.reason.as_deref(),
+            Some("full port scan keeps failing")
+        );
+        assert_eq!(event.failed_steps, ["scan ports"]);
+        assert_eq!(event.removed_steps, ["scan ports"]);
+        assert_eq!(event.added_steps, ["scan top 100 ports"]);
+        assert_eq!(event.kept_steps, ["probe login", "write report"]);
+
+        cleanup_execution_todos(execution_id).await;
+        assert!(get_execution_todos(execution_id).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_explicit_replan_trigger() {
+        let execution_id = "test_replan_explicit_trigger";
+        let tool = TodosTool::new();
+        tool.call(TodosArgs {
+            items: steps(&["crawl site"]),
+            ..args(execution_id, "add_items")
+        })
+        .await
+        .unwrap();
+        tool.call(TodosArgs {
+            items: steps(&["crawl site", "test GraphQL endpoint"]),
+            replan_trigger: Some(ReplanTrigger::NewInformation),
+            ..args(execution_id, "replan")
+        })
+        .await
+        .unwrap();
+
+        let events = get_replan_events(execution_id).await;
+        assert_eq!(events[0].trigger, ReplanTrigger::NewInformation);
+        assert!(events[0].removed_steps.is_empty());
+        assert_eq!(events[0].added_steps, ["test GraphQL endpoint"]);
+
+        cleanup_execution_todos(execution_id).await;
+    }
+}
@@ -0,0 +1,579 @@
+//! WHOIS / RDAP lookup tool using rig-core Tool trait
+//!
+//! Queries RDAP first (structured JSON) and falls back to classic WHOIS over
+//! TCP port 43 when no RDAP service answers; both go through the global proxy.
+//! Hostnames are reduced to their registrable domain before the lookup.
+//! WHOIS referrals are followed once starting from whois.iana.org. Both paths
+//! are normalized into the same registration summary.
+
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::net::IpAddr;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// RDAP bootstrap redirector (forwards to the authoritative registry)
+const DEFAULT_RDAP_BASE: &str = "https://rdap.org";
+const IANA_WHOIS_SERVER: &str = "whois.iana.org";
+const WHOIS_PORT: u16 = 43;
+const DEFAULT_TIMEOUT_SECS: u64 = 15;
+/// Raw WHOIS text kept in the output
+const MAX_RAW_LEN: usize = 8 * 1024;
+
+/// WHOIS arguments
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct WhoisArgs {
+    /// Domain name or IP address
+    pub query: String,
+    /// Timeout per lookup in seconds (default: 15)
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Normalized registration data
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WhoisOutput {
+    pub query: String,
+    /// "domain" or "ip"
+    pub query_type: String,
+    /// "rdap" or "whois"
+    pub source: String,
+    pub registrar: Option<String>,
+    pub created: Option<String>,
+    pub expires: Option<String>,
+    pub updated: Option<String>,
+    pub nameservers: Vec<String>,
+    pub registrant_org: Option<String>,
+    pub abuse_email: Option<String>,
+    pub status: Vec<String>,
+    /// IP network range (IP lookups only)
+    pub network: Option<String>,
+    /// Raw WHOIS response (WHOIS fallback only, truncated)
+    pub raw: Option<String>,
+}
+
+/// WHOIS errors
+#[derive(Debug, thiserror::Error)]
+pub enum WhoisError {
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+    #[error("Lookup failed: {0}")]
+    LookupFailed(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QueryKind {
+    Domain,
+    Ip,
+}
+
+impl QueryKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            QueryKind::Domain => "domain",
+            QueryKind::Ip => "ip",
+        }
+    }
+}
+
+/// Normalize the query and decide whether it is a domain or an IP
+fn classify(query: &str) -> Result<(String, QueryKind), WhoisError> {
+    let query = query.trim().trim_end_matches('.').to_ascii_lowercase();
+    // Accept URLs and host:port for convenience
+    let host = query
+        .split("://")
+        .last()
+        .unwrap_or_default()
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    if let Ok(ip) = host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        return Ok((ip.to_string(), QueryKind::Ip));
+    }
+    let host = host.split(':').next().unwrap_or_default();
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok((ip.to_string(), QueryKind::Ip));
+    }
+    let valid = host.contains('.')
+        && host.split('.').all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    if !valid {
+        return Err(WhoisError::InvalidQuery(query));
+    }
+    // Registration data lives on the registrable domain, not on subdomains
+    let domain =
+        sentinel_core::domain::registrable_domain(host).unwrap_or_else(|| host.to_string());
+    Ok((domain, QueryKind::Domain))
+}
+
+/// Value of a vCard property (`["fn", {}, "text", "Example Inc."]`)
+fn vcard_value(entity: &Value, property: &str) -> Option<String> {
+    entity["vcardArray"][1]
+        .as_array()?
+        .iter()
+        .find(|entry| entry[0].as_str() == Some(property))
+        .and_then(|entry| match &entry[3] {
+            Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+            Value::Array(parts) => parts
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|s| !s.trim().is_empty())
+                .map(str::to_string),
+            _ => None,
+        })
+}
+
+fn has_role(entity: &Value, role: &str) -> bool {
+    entity["roles"]
+        .as_array()
+        .is_some_and(|roles| roles.iter().any(|r| r.as_str() == Some(role)))
+}
+
+/// All entities, including nested ones (abuse contacts hang off the registrar)
+fn entities(value: &Value) -> Vec<&Value> {
+    let mut found = Vec::new();
+    for entity in value["entities"].as_array().into_iter().flatten() {
+        found.push(entity);
+        found.extend(entities(entity));
+    }
+    found
+}
+
+/// Map an RDAP domain or IP network object to the output
+fn parse_rdap(query: &str, kind: QueryKind, rdap: &Value) -> WhoisOutput {
+    let event = |action: &str| {
+        rdap["events"].as_array().and_then(|events| {
+            events
+                .iter()
+                .find(|e| e["eventAction"].as_str() == Some(action))
+                .and_then(|e| e["eventDate"].as_str())
+                .map(str::to_string)
+        })
+    };
+    let all_entities = entities(rdap);
+    let registrar = all_entities
+        .iter()
+        .find(|e| has_role(e, "registrar"))
+        .and_then(|e| vcard_value(e, "fn"));
+    let registrant_org = all_entities
+        .iter()
+        .find(|e| has_role(e, "registrant"))
+        .and_then(|e| vcard_value(e, "org").or_else(|| vcard_value(e, "fn")))
+        .or_else(|| {
+            rdap["name"]
+                .as_str()
+                .filter(|_| kind == QueryKind::Ip)
+                .map(str::to_string)
+        });
+    let abuse_email = all_entities
+        .iter()
+        .find(|e| has_role(e, "abuse"))
+        .and_then(|e| vcard_value(e, "email"));
+    let nameservers = rdap["nameservers"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|ns| ns["ldhName"].as_str())
+        .map(|ns| ns.trim_end_matches('.').to_ascii_lowercase())
+        .collect();
+    let status = rdap["status"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(str::to_string)
+        .collect();
+    let network = match (rdap["startAddress"].as_str(), rdap["endAddress"].as_str()) {
+        (Some(start), Some(end)) => Some(format!("{} - {}", start, end)),
+        _ => None,
+    };
+
+    WhoisOutput {
+        query: query.to_string(),
+        query_type: kind.as_str().to_string(),
+        source: "rdap".to_string(),
+        registrar,
+        created: event("registration"),
+        expires: event("expiration"),
+        updated: event("last changed"),
+        nameservers,
+        registrant_org,
+        abuse_email,
+        status,
+        network,
+        raw: None,
+    }
+}
+
+/// Extract the common fields from a raw WHOIS response
+///
+/// Covers the ICANN gTLD format plus the RIR (ARIN/RIPE/APNIC) and common
+/// ccTLD key spellings; the first non-empty value of each field wins.
+fn parse_whois(query: &str, kind: QueryKind, raw: &str) -> WhoisOutput {
+    let mut output = WhoisOutput {
+        query: query.to_string(),
+        query_type: kind.as_str().to_string(),
+        source: "whois".to_string(),
+        ..Default::default()
+    };
+    for line in raw.lines() {
+        let line = line.trim();
+        if line.starts_with('%') || line.starts_with('#') || line.starts_with(">>>") {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+        let set = |field: &mut Option<String>| {
+            if field.is_none() {
+                *field = Some(value.to_string());
+            }
+        };
+        match key.trim().to_ascii_lowercase().as_str() {
+            "registrar" | "sponsoring registrar" | "registrar name" => set(&mut output.registrar),
+            "creation date" | "created" | "registered on" | "regdate" | "registration time" => {
+                set(&mut output.created)
+            }
+            "registry expiry date"
+            | "registrar registration expiration date"
+            | "expiration date"
+            | "expiry date"
+            | "paid-till"
+            | "expires on" => set(&mut output.expires),
+            "updated date" | "last-modified" | "last modified" | "changed" | "updated" => {
+                set(&mut output.updated)
+            }
+            "registrant organization"
+            | "registrant"
+            | "org-name"
+            | "orgname"
+            | "org"
+            | "organization" => set(&mut output.registrant_org),
+            "registrar abuse contact email" | "orgabuseemail" | "abuse-mailbox" => {
+                set(&mut output.abuse_email)
+            }
+            "netrange" | "inetnum" | "inet6num" => set(&mut output.network),
+            "name server" | "nserver" | "nameserver" => {
+                let ns = value
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or(value)
+                    .trim_end_matches('.')
+                    .to_ascii_lowercase();
+                if !output.nameservers.contains(&ns) {
+                    output.nameservers.push(ns);
+                }
+            }
+            "domain status" | "status" => {
+                let status = value.split_whitespace().next().unwrap_or(value).to_string();
+                if !output.status.contains(&status) {
+                    output.status.push(status);
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut raw = raw.to_string();
+    if raw.len() > MAX_RAW_LEN {
+        let mut end = MAX_RAW_LEN;
+        while !raw.is_char_boundary(end) {
+            end -= 1;
+        }
+        raw.truncate(end);
+    }
+    output.raw = Some(raw);
+    output
+}
+
+/// `refer:` / `whois:` line of an IANA response
+fn whois_referral(raw: &str) -> Option<String> {
+    raw.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        matches!(key.trim().to_ascii_lowercase().as_str(), "refer" | "whois")
+            .then(|| value.trim().to_string())
+            .filter(|v| !v.is_empty())
+    })
+}
+
+/// WHOIS / RDAP lookup tool
+#[derive(Debug, Clone)]
+pub struct WhoisTool {
+    rdap_base: String,
+}
+
+impl Default for WhoisTool {
+    fn default() -> Self {
+        Self {
+            rdap_base: DEFAULT_RDAP_BASE.to_string(),
+        }
+    }
+}
+
+impl WhoisTool {
+    pub const NAME: &'static str = "whois_lookup";
+    pub const DESCRIPTION: &'static str = "Look up registration data for a domain or IP address via RDAP (falls back to WHOIS). Returns registrar, creation/expiry dates, nameservers, registrant organization and abuse contact.";
+
+    /// Use another RDAP base URL (e.g. a registry's own service)
+    pub fn with_rdap_base(rdap_base: impl Into<String>) -> Self {
+        Self {
+            rdap_base: rdap_base.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn rdap_lookup(
+        &self,
+        query: &str,
+        kind: QueryKind,
+        timeout: Duration,
+    ) -> Result<WhoisOutput, String> {
+        let builder = reqwest::Client::builder().timeout(timeout);
+        let client = sentinel_core::global_proxy::apply_proxy_to_client(builder)
+            .await
+            .build()
+            .map_err(|e| e.to_string())?;
+        let url = format!("{}/{}/{}", self.rdap_base, kind.as_str(), query);
+        let response = client
+            .get(&url)
+            .header("Accept", "application/rdap+json, application/json")
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("RDAP returned {}", response.status()));
+        }
+        let rdap: Value = response.json().await.map_err(|e| e.to_string())?;
+        Ok(parse_rdap(query, kind, &rdap))
+    }
+
+    async fn whois_query(server: &str, query: &str, timeout: Duration) -> Result<String, String> {
+        let exchange = async {
+            let mut stream = sentinel_core::global_proxy::connect_tcp(server, WHOIS_PORT)
+                .await
+                .map_err(|e| format!("{}: {}", server, e))?;
+            stream
+                .write_all(format!("{}\r\n", query).as_bytes())
+                .await
+                .map_err(|e| e.to_string())?;
+            let mut response = Vec::new();
+            stream
+                .read_to_end(&mut response)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(String::from_utf8_lossy(&response).to_string())
+        };
+        tokio::time::timeout(timeout, exchange)
+            .await
+            .map_err(|_| format!("{} timed out", server))?
+    }
+
+    async fn whois_lookup(
+        query: &str,
+        kind: QueryKind,
+        timeout: Duration,
+    ) -> Result<WhoisOutput, String> {
+        let iana = Self::whois_query(IANA_WHOIS_SERVER, query, timeout).await?;
+        let raw = match whois_referral(&iana) {
+            Some(server) => Self::whois_query(&server, query, timeout).await?,
+            None => iana,
+        };
+        Ok(parse_whois(query, kind, &raw))
+    }
+}
+
+impl Tool for WhoisTool {
+    const NAME: &'static str = Self::NAME;
+    type Args = WhoisArgs;
+    type Output = WhoisOutput;
+    type Error = WhoisError;
+
+    async fn definition(&self, _prompt: String) -> rig::completion::ToolDefinition {
+        rig::completion::ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: Self::DESCRIPTION.to_string(),
+            parameters: serde_json::to_value(schemars::schema_for!(WhoisArgs)).unwrap_or_default(),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (query, kind) = classify(&args.query)?;
+        let timeout = Duration::from_secs(args.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS).max(1));
+
+        let rdap_error = match self.rdap_lookup(&query, kind, timeout).await {
+            Ok(output) => return Ok(output),
+            Err(e) => e,
+        };
+        tracing::debug!(
+            "RDAP lookup for {} failed ({}), trying WHOIS",
+            query,
+            rdap_error
+        );
+
+        Self::whois_lookup(&query, kind, timeout)
+            .await
+            .map_err(|e| WhoisError::LookupFailed(format!("RDAP: {}; WHOIS: {}", rdap_error, e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    const EXAMPLE_RDAP: &str = r#"{
+        "objectClassName": "domain",
+        "ldhName": "EXAMPLE.COM",
+        "status": ["client delete prohibited", "client transfer prohibited"],
+        "events": [
+            {"eventAction": "registration", "eventDate": "1995-08-14T04:00:00Z"},
+            {"eventAction": "expiration", "eventDate": "2026-08-13T04:00:00Z"},
+            {"eventAction": "last changed", "eventDate": "2025-08-14T07:01:34Z"}
+        ],
+        "nameservers": [
+            {"objectClassName": "nameserver", "ldhName": "A.IANA-SERVERS.NET"},
+            {"objectClassName": "nameserver", "ldhName": "B.IANA-SERVERS.NET"}
+        ],
+        "entities": [
+            {
+                "roles": ["registrar"],
+                "vcardArray": ["vcard", [["version", {}, "text", "4.0"], ["fn", {}, "text", "RESERVED-Internet Assigned Numbers Authority"]]],
+                "entities": [
+                    {
+                        "roles": ["abuse"],
+                        "vcardArray": ["vcard", [["fn", {}, "text", ""], ["email", {}, "text", "abuse@iana.org"]]]
+                    }
+                ]
+            },
+            {
+                "roles": ["registrant"],
+                "vcardArray": ["vcard", [["fn", {}, "text", ""], ["org", {}, "text", "Internet Assigned Numbers Authority"]]]
+            }
+        ]
+    }"#;
+
+    /// Mock RDAP server answering /domain/example.com and 404 for everything else
+    async fn spawn_rdap_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 2048];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                    let (status, body) = match path.as_str() {
+                        "/domain/example.com" => ("200 OK", EXAMPLE_RDAP),
+                        _ => ("404 Not Found", "{}"),
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Type: application/rdap+json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_rdap_lookup_with_mocked_response() {
+        let tool = WhoisTool::with_rdap_base(spawn_rdap_server().await);
+        let output = tool
+            .call(WhoisArgs {
+                query: "https://www.Example.com/path".to_string(),
+                timeout_secs: Some(5),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(output.query, "example.com");
+        assert_eq!(output.query_type, "domain");
+        assert_eq!(output.source, "rdap");
+        assert_eq!(
+            output.registrar.as_deref(),
+            Some("RESERVED-Internet Assigned Numbers Authority")
+        );
+        assert_eq!(output.created.as_deref(), Some("1995-08-14T04:00:00Z"));
+        assert_eq!(output.expires.as_deref(), Some("2026-08-13T04:00:00Z"));
+        assert_eq!(
+            output.nameservers,
+            vec!["a.iana-servers.net", "b.iana-servers.net"]
+        );
+        assert_eq!(
+            output.registrant_org.as_deref(),
+            Some("Internet Assigned Numbers Authority")
+        );
+        assert_eq!(output.abuse_email.as_deref(), Some("abuse@iana.org"));
+        assert_eq!(output.status.len(), 2);
+    }
+
+    #[test]
+    fn test_whois_fallback_parsing() {
+        let raw = "% IANA WHOIS server\n\
+                   Domain Name: EXAMPLE.ORG\n\
+                   Registrar: Example Registrar, Inc.\n\
+                   Creation Date: 1995-04-30T04:00:00Z\n\
+                   Registry Expiry Date: 2026-08-30T04:00:00Z\n\
+                   Registrant Organization: Example Org\n\
+                   Registrar Abuse Contact Email: abuse@registrar.example\n\
+                   Domain Status: clientTransferProhibited https://icann.org/epp#clientTransferProhibited\n\
+                   Name Server: NS1.EXAMPLE.ORG\n\
+                   Name Server: NS2.EXAMPLE.ORG\n";
+        let output = parse_whois("example.org", QueryKind::Domain, raw);
+        assert_eq!(output.source, "whois");
+        assert_eq!(output.registrar.as_deref(), Some("Example Registrar, Inc."));
+        assert_eq!(output.expires.as_deref(), Some("2026-08-30T04:00:00Z"));
+        assert_eq!(output.registrant_org.as_deref(), Some("Example Org"));
+        assert_eq!(
+            output.abuse_email.as_deref(),
+            Some("abuse@registrar.example")
+        );
+        assert_eq!(
+            output.nameservers,
+            vec!["ns1.example.org", "ns2.example.org"]
+        );
+        assert_eq!(output.status, vec!["clientTransferProhibited"]);
+
+        assert_eq!(
+            whois_referral("domain: ORG\nrefer:        whois.publicinterestregistry.org\n")
+                .as_deref(),
+            Some("whois.publicinterestregistry.org")
+        );
+    }
+
+    #[test]
+    fn test_classify_query() {
+        assert_eq!(
+            classify("8.8.8.8").unwrap(),
+            ("8.8.8.8".to_string(), QueryKind::Ip)
+        );
+        assert_eq!(
+            classify("[2001:db8::1]").unwrap(),
+            ("2001:db8::1".to_string(), QueryKind::Ip)
+        );
+        assert_eq!(
+            classify("Example.com.").unwrap(),
+            ("example.com".to_string(), QueryKind::Domain)
+        );
+        assert_eq!(
+            classify("https://api.eu.example.co.uk:8443/login").unwrap(),
+            ("example.co.uk".to_string(), QueryKind::Domain)
+        );
+        assert_eq!(
+            classify("www.example.com").unwrap(),
+            ("example.com".to_string(), QueryKind::Domain)
+        );
+        assert!(classify("not a domain").is_err());
+        assert!(classify("localhost").is_err());
+    }
+}
@@ -0,0 +1,402 @@
+//! Execution-wide cancellation scopes
+//!
+//! Every agent execution, workflow run and sub-run owns one cancellation token,
+//! keyed by its scope id (the execution id). Work that runs inside
+//! [`with_cancellation_scope`] can read the token through [`current_token`] and
+//! check it at I/O boundaries. A single [`cancel_scope`] stops in-flight tool
+//! calls (built-in, MCP, plugin, workflow) and every child scope linked with
+//! [`link_child_scope`].
+
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+use crate::dynamic_tool::ToolExecutor;
+
+/// Cancellation tokens keyed by scope (execution id)
+static SCOPE_TOKENS: Lazy<RwLock<HashMap<String, CancellationToken>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// In-flight calls of scopes opened by [`with_call_scope`], which drops the
+/// scope when the count reaches zero. Entries are only added or removed under
+/// the `SCOPE_TOKENS` write lock.
+static CALL_SCOPE_REFS: Lazy<std::sync::Mutex<HashMap<String, usize>>> =
+    Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+
+tokio::task_local! {
+    static CANCELLATION_SCOPE: CancellationToken;
+}
+
+/// Returned by [`run_cancellable`] when the enclosing scope is cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Execution was cancelled")]
+pub struct Cancelled;
+
+/// Get (or create) the token of a scope
+pub async fn scope_token(scope_id: &str) -> CancellationToken {
+    SCOPE_TOKENS
+        .write()
+        .await
+        .entry(scope_id.to_string())
+        .or_default()
+        .clone()
+}
+
+/// Run `fut` inside the `scope_id` cancellation scope
+pub async fn with_cancellation_scope<F: Future>(scope_id: &str, fut: F) -> F::Output {
+    let token = scope_token(scope_id).await;
+    with_token(token, fut).await
+}
+
+/// Run a single call in the `scope_id` scope
+///
+/// Unlike [`with_cancellation_scope`], a scope that did not exist before the
+/// call is dropped again once the last such call finishes, so standalone calls
+/// naming an execution do not accumulate scopes. Scopes registered by their
+/// execution are left to that execution's [`clear_cancellation_scope`].
+pub async fn with_call_scope<F: Future>(scope_id: &str, fut: F) -> F::Output {
+    // The guard releases the reference even when the caller drops this future
+    let (token, _call_ref) = {
+        let mut tokens = SCOPE_TOKENS.write().await;
+        let mut refs = CALL_SCOPE_REFS.lock().unwrap_or_else(|e| e.into_inner());
+        let call_ref = || CallScopeRef {
+            scope_id: scope_id.to_string(),
+        };
+        match tokens.get(scope_id) {
+            Some(token) => match refs.get_mut(scope_id) {
+                Some(count) => {
+                    *count += 1;
+                    (token.clone(), Some(call_ref()))
+                }
+                None => (token.clone(), None),
+            },
+            None => {
+                refs.insert(scope_id.to_string(), 1);
+                let token = tokens.entry(scope_id.to_string()).or_default().clone();
+                (token, Some(call_ref()))
+            }
+        }
+    };
+
+    with_token(token, fut).await
+}
+
+/// One in-flight call of a scope opened by [`with_call_scope`]
+struct CallScopeRef {
+    scope_id: String,
+}
+
+impl Drop for CallScopeRef {
+    fn drop(&mut self) {
+        let idle = {
+            let mut refs = CALL_SCOPE_REFS.lock().unwrap_or_else(|e| e.into_inner());
+            match refs.get_mut(&self.scope_id) {
+                Some(count) => {
+                    *count = count.saturating_sub(1);
+                    *count == 0
+                }
+                None => false,
+            }
+        };
+        if !idle {
+            return;
+        }
+
+        // Drop cannot await the lock; when it is busy, finish the cleanup in a task.
+        // The zero count stays registered meanwhile, so a call joining in between
+        // takes the scope over instead of having it removed underneath it.
+        match SCOPE_TOKENS.try_write() {
+            Ok(mut tokens) => remove_idle_call_scope(&mut tokens, &self.scope_id),
+            Err(_) => {
+                if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                    let scope_id = std::mem::take(&mut self.scope_id);
+                    handle.spawn(async move {
+                        let mut tokens = SCOPE_TOKENS.write().await;
+                        remove_idle_call_scope(&mut tokens, &scope_id);
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Drop a call scope whose last call has finished
+fn remove_idle_call_scope(tokens: &mut HashMap<String, CancellationToken>, scope_id: &str) {
+    let mut refs = CALL_SCOPE_REFS.lock().unwrap_or_else(|e| e.into_inner());
+    if refs.get(scope_id) == Some(&0) {
+        refs.remove(scope_id);
+        tokens.remove(scope_id);
+    }
+}
+
+/// Run `fut` under an already resolved token
+///
+/// Task-locals do not cross `spawn_blocking`; capture [`current_token`] before
+/// leaving the task and re-enter the scope with this on the other side.
+pub async fn with_token<F: Future>(token: CancellationToken, fut: F) -> F::Output {
+    CANCELLATION_SCOPE.scope(token, fut).await
+}
+
+/// Make `child_id` a sub-scope of `parent_id`
+///
+/// Cancelling the parent cancels the child; cancelling the child leaves the
+/// parent running.
+pub async fn link_child_scope(parent_id: &str, child_id: &str) -> CancellationToken {
+    let mut tokens = SCOPE_TOKENS.write().await;
+    let child = tokens
+        .entry(parent_id.to_string())
+        .or_default()
+        .child_token();
+    tokens.insert(child_id.to_string(), child.clone());
+    child
+}
+
+/// Cancel a scope and all of its child scopes
+///
+/// The cancelled token stays registered as a tombstone until
+/// [`clear_cancellation_scope`], so work that joins the scope afterwards (a
+/// late tool call, a subagent linking to it) sees it cancelled instead of
+/// getting a fresh token. Returns false for unknown or already cancelled scopes.
+pub async fn cancel_scope(scope_id: &str) -> bool {
+    match SCOPE_TOKENS.read().await.get(scope_id) {
+        Some(token) if !token.is_cancelled() => {
+            token.cancel();
+            tracing::info!("Cancelled execution scope: {}", scope_id);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Cancel every active scope (used on application shutdown)
+///
+/// Returns the number of scopes cancelled.
+pub async fn cancel_all_scopes() -> usize {
+    let tokens = SCOPE_TOKENS.read().await;
+    let mut cancelled = 0;
+    for token in tokens.values().filter(|t| !t.is_cancelled()) {
+        token.cancel();
+        cancelled += 1;
+    }
+    cancelled
+}
+
+/// Whether a scope has been cancelled (unknown scopes are not cancelled)
+pub async fn is_scope_cancelled(scope_id: &str) -> bool {
+    SCOPE_TOKENS
+        .read()
+        .await
+        .get(scope_id)
+        .is_some_and(|token| token.is_cancelled())
+}
+
+/// Drop a scope's token once its execution has finished
+pub async fn clear_cancellation_scope(scope_id: &str) {
+    let mut tokens = SCOPE_TOKENS.write().await;
+    CALL_SCOPE_REFS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(scope_id);
+    tokens.remove(scope_id);
+}
+
+/// Token of the enclosing cancellation scope, if any
+pub fn current_token() -> Option<CancellationToken> {
+    CANCELLATION_SCOPE.try_with(|token| token.clone()).ok()
+}
+
+/// Whether the enclosing cancellation scope has been cancelled
+///
+/// Cheap enough to call between requests, probes or file chunks.
+pub fn is_cancelled() -> bool {
+    CANCELLATION_SCOPE
+        .try_with(|token| token.is_cancelled())
+        .unwrap_or(false)
+}
+
+/// Race `fut` against the enclosing scope; outside a scope it just runs `fut`
+pub async fn run_cancellable<F: Future>(fut: F) -> Result<F::Output, Cancelled> {
+    match current_token() {
+        Some(token) => tokio::select! {
+            biased;
+            _ = token.cancelled() => Err(Cancelled),
+            output = fut => Ok(output),
+        },
+        None => Ok(fut.await),
+    }
+}
+
+/// Wrap an executor so its calls run inside the `scope_id` cancellation scope
+///
+/// The call is dropped as soon as the scope is cancelled, so tools that never
+/// check [`is_cancelled`] still stop at their next await point.
+pub fn scoped_executor(scope_id: String, inner: ToolExecutor) -> ToolExecutor {
+    Arc::new(move |args: Value| {
+        let scope_id = scope_id.clone();
+        let inner = inner.clone();
+        Box::pin(async move {
+            let call = async {
+                run_cancellable(inner(args))
+                    .await
+                    .unwrap_or_else(|_| Err(format!("Tool call cancelled (scope: {})", scope_id)))
+            };
+            with_cancellation_scope(&scope_id, call).await
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::{Duration, Instant};
+
+    fn slow_executor() -> ToolExecutor {
+        Arc::new(|_args: Value| {
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Ok(json!({ "success": true }))
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn test_cancel_scope_stops_in_flight_tool() {
+        let scope = format!("cancel-scope-{}", uuid::Uuid::new_v4());
+        let executor = scoped_executor(scope.clone(), slow_executor());
+        let call = tokio::spawn(async move { executor(json!({})).await });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let started = Instant::now();
+        assert!(cancel_scope(&scope).await);
+
+        let result = tokio::time::timeout(Duration::from_secs(2), call)
+            .await
+            .expect("cancellation should end the call")
+            .unwrap();
+        assert!(result.unwrap_err().contains("cancelled"));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(!cancel_scope(&scope).await);
+
+        // The tombstone keeps later calls in the scope cancelled until cleanup
+        let late = with_cancellation_scope(&scope, async { is_cancelled() }).await;
+        assert!(late);
+        assert!(is_scope_cancelled(&scope).await);
+        clear_cancellation_scope(&scope).await;
+        assert!(!is_scope_cancelled(&scope).await);
+    }
+
+    #[tokio::test]
+    async fn test_parent_cancellation_reaches_child_scope() {
+        let parent = format!("cancel-parent-{}", uuid::Uuid::new_v4());
+        let child = format!("cancel-child-{}", uuid::Uuid::new_v4());
+        let sibling = format!("cancel-sibling-{}", uuid::Uuid::new_v4());
+        link_child_scope(&parent, &child).await;
+        link_child_scope(&parent, &sibling).await;
+
+        // Cancelling a child leaves the parent running
+        assert!(cancel_scope(&sibling).await);
+        assert!(!is_scope_cancelled(&parent).await);
+
+        let observed = with_cancellation_scope(&child, async {
+            assert!(!is_cancelled());
+            cancel_scope(&parent).await;
+            is_cancelled()
+        })
+        .await;
+        assert!(observed);
+        assert!(is_scope_cancelled(&child).await);
+        assert_eq!(run_cancellable(async {}).await, Ok(()));
+        clear_cancellation_scope(&child).await;
+    }
+
+    #[tokio::test]
+    async fn test_call_scope_is_dropped_after_the_call() {
+        let scope = format!("call-scope-{}", uuid::Uuid::new_v4());
+        let failed: Result<(), String> = with_call_scope(&scope, async {
+            assert!(SCOPE_TOKENS.read().await.contains_key(&scope));
+            Err("tool failed".to_string())
+        })
+        .await;
+        assert!(failed.is_err());
+        assert!(!SCOPE_TOKENS.read().await.contains_key(&scope));
+
+        // A call cancelled mid-flight leaves no tombstone behind either
+        let cancelled = with_call_scope(&scope, async {
+            cancel_scope(&scope).await;
+            is_cancelled()
+        })
+        .await;
+        assert!(cancelled);
+        assert!(!SCOPE_TOKENS.read().await.contains_key(&scope));
+    }
+
+    #[tokio::test]
+    async fn test_call_scope_keeps_scopes_it_did_not_open() {
+        let scope = format!("call-owned-{}", uuid::Uuid::new_v4());
+        let owner = scope_token(&scope).await;
+
+        with_call_scope(&scope, async {}).await;
+        assert!(SCOPE_TOKENS.read().await.contains_key(&scope));
+
+        // Cancelling the execution still reaches calls made afterwards
+        assert!(cancel_scope(&scope).await);
+        assert!(owner.is_cancelled());
+        assert!(with_call_scope(&scope, async { is_cancelled() }).await);
+        clear_cancellation_scope(&scope).await;
+
+        // Overlapping calls share the scope until the last one finishes
+        let (first_done, wait_first) = tokio::sync::oneshot::channel::<()>();
+        let first_scope = scope.clone();
+        let first = tokio::spawn(async move {
+            with_call_scope(&first_scope, async {
+                let _ = wait_first.await;
+            })
+            .await
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        with_call_scope(&scope, async {}).await;
+        assert!(SCOPE_TOKENS.read().await.contains_key(&scope));
+        first_done.send(()).unwrap();
+        first.await.unwrap();
+        assert!(!SCOPE_TOKENS.read().await.contains_key(&scope));
+    }
+
+    #[tokio::test]
+    async fn test_call_scope_is_dropped_with_an_abandoned_call() {
+        let scope = format!("call-dropped-{}", uuid::Uuid::new_v4());
+        let timed_out = tokio::time::timeout(
+            Duration::from_millis(20),
+            with_call_scope(&scope, std::future::pending::<()>()),
+        )
+        .await;
+        assert!(timed_out.is_err());
+        assert!(!SCOPE_TOKENS.read().await.contains_key(&scope));
+        assert!(!CALL_SCOPE_REFS.lock().unwrap().contains_key(&scope));
+
+        // Dropped while the scope map is locked: cleanup finishes once it is free
+        let call = tokio::spawn({
+            let scope = scope.clone();
+            async move { with_call_scope(&scope, std::future::pending::<()>()).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let tokens = SCOPE_TOKENS.read().await;
+        call.abort();
+        assert!(call.await.unwrap_err().is_cancelled());
+        assert!(tokens.contains_key(&scope));
+        drop(tokens);
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while SCOPE_TOKENS.read().await.contains_key(&scope) {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("scope should be dropped once the lock is free");
+        assert!(!CALL_SCOPE_REFS.lock().unwrap().contains_key(&scope));
+    }
+}
@@ -3,6 +3,8 @@
 //! Implements dynamic tool registration and Rig Tool trait adaptation.
 //! Supports builtin tools, MCP tools, plugin tools, and workflow tools.
 
+use crate::tool_concurrency::acquire_tool_permit;
+use crate::tool_metrics::record_tool_metric;
 use rig::completion::ToolDefinition;
 use rig::tool::{Tool, ToolSet};
 use serde::{Deserialize, Serialize};
@@ -38,6 +40,47 @@ pub enum ToolSource {
     Workflow { workflow_id: String },
 }
 
+/// How strictly call arguments are checked against `input_schema`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaValidation {
+    /// Reject calls whose arguments violate the schema
+    #[default]
+    Strict,
+    /// Log violations but still invoke the tool
+    Warn,
+    /// Skip argument validation
+    Off,
+}
+
+/// Kind of schema violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaViolationKind {
+    MissingRequired,
+    TypeMismatch,
+    InvalidValue,
+}
+
+/// A single argument that does not match the declared schema
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    pub kind: SchemaViolationKind,
+    /// JSON pointer to the offending field ("" for the root object)
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
 /// Dynamic tool definition
 #[derive(Clone)]
 pub struct DynamicToolDef {
@@ -53,6 +96,8 @@ pub struct DynamicToolDef {
     pub source: ToolSource,
     /// Tool category
     pub category: String,
+    /// Argument validation mode
+    pub schema_validation: SchemaValidation,
     /// Tool executor function
     pub executor: ToolExecutor,
 }
@@ -74,12 +119,35 @@ pub enum DynamicToolError {
     ExecutionFailed(String),
     #[error("Invalid arguments: {0}")]
     InvalidArguments(String),
+    #[error("Invalid arguments for {tool}: {}", format_violations(.violations))]
+    SchemaValidation {
+        tool: String,
+        violations: Vec<SchemaViolation>,
+    },
     #[error("Invalid output: {0}")]
     InvalidOutput(String),
     #[error("Tool not found: {0}")]
     NotFound(String),
 }
 
+impl DynamicToolError {
+    /// Structured schema violations, if the call was rejected by validation
+    pub fn validation_errors(&self) -> Option<&[SchemaViolation]> {
+        match self {
+            Self::SchemaValidation { violations, .. } => Some(violations),
+            _ => None,
+        }
+    }
+}
+
+fn format_violations(violations: &[SchemaViolation]) -> String {
+    violations
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 /// Dynamic tool instance - implements Rig's Tool trait
 #[derive(Clone)]
 pub struct DynamicTool {
@@ -102,6 +170,10 @@ impl DynamicTool {
     pub fn source(&self) -> &ToolSource {
         &self.def.source
     }
+
+    pub fn def(&self) -> &DynamicToolDef {
+        &self.def
+    }
 }
 
 /// Implementation of Rig's Tool trait for DynamicTool
@@ -125,10 +197,41 @@ impl Tool for DynamicTool {
     }
 
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Time spent queued is not part of the tool's latency
+        let _permit = acquire_tool_permit(&self.def.name, &self.def.category)
+            .await
+            .map_err(|e| DynamicToolError::ExecutionFailed(e.to_string()))?;
+        let start = std::time::Instant::now();
+        let result = self.call_inner(args).await;
+        let error = result.as_ref().err().map(|e| e.to_string());
+        record_tool_metric(
+            &self.def.name,
+            start.elapsed().as_millis() as u64,
+            error.as_deref(),
+        );
+        result
+    }
+}
+
+impl DynamicTool {
+    async fn call_inner(&self, args: Value) -> Result<Value, DynamicToolError> {
         let executor = self.def.executor.clone();
-        if should_validate_schema(&self.def.input_schema) {
-            validate_schema(&self.def.input_schema, &args)
-                .map_err(DynamicToolError::InvalidArguments)?;
+        if self.def.schema_validation != SchemaValidation::Off
+            && should_validate_schema(&self.def.input_schema)
+        {
+            if let Err(violations) = validate_arguments(&self.def.input_schema, &args) {
+                if self.def.schema_validation == SchemaValidation::Strict {
+                    return Err(DynamicToolError::SchemaValidation {
+                        tool: self.def.name.clone(),
+                        violations,
+                    });
+                }
+                tracing::warn!(
+                    "Arguments for tool {} do not match its schema: {}",
+                    self.def.name,
+                    format_violations(&violations)
+                );
+            }
         }
 
         let timeout_secs = TOOL_TIMEOUT_FLOOR_SECS;
@@ -206,6 +309,18 @@ impl ToolRegistry {
         tools.retain(|_, t| &t.source != source);
     }
 
+    /// Set the argument validation mode of a registered tool
+    pub async fn set_schema_validation(&self, name: &str, mode: SchemaValidation) -> bool {
+        let mut tools = self.tools.write().await;
+        match tools.get_mut(name) {
+            Some(def) => {
+                def.schema_validation = mode;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Get tool count
     pub async fn count(&self) -> usize {
         let tools = self.tools.read().await;
@@ -301,6 +416,7 @@ pub struct DynamicToolBuilder {
     output_schema: Option<Value>,
     source: ToolSource,
     category: String,
+    schema_validation: SchemaValidation,
     executor: Option<ToolExecutor>,
 }
 
@@ -316,6 +432,7 @@ impl DynamicToolBuilder {
             output_schema: None,
             source: ToolSource::Builtin,
             category: "other".to_string(),
+            schema_validation: SchemaValidation::default(),
             executor: None,
         }
     }
@@ -345,6 +462,11 @@ impl DynamicToolBuilder {
         self
     }
 
+    pub fn schema_validation(mut self, mode: SchemaValidation) -> Self {
+        self.schema_validation = mode;
+        self
+    }
+
     pub fn executor<F, Fut>(mut self, f: F) -> Self
     where
         F: Fn(Value) -> Fut + Send + Sync + 'static,
@@ -369,6 +491,7 @@ impl DynamicToolBuilder {
             output_schema: self.output_schema,
             source: self.source,
             category: self.category,
+            schema_validation: self.schema_validation,
             executor,
         })
     }
@@ -383,6 +506,55 @@ fn should_validate_schema(schema: &Value) -> bool {
     }
 }
 
+/// Validate call arguments, classifying each violation
+fn validate_arguments(schema: &Value, args: &Value) -> Result<(), Vec<SchemaViolation>> {
+    let compiled = match jsonschema::JSONSchema::options()
+        .with_draft(jsonschema::Draft::Draft7)
+        .compile(schema)
+    {
+        Ok(compiled) => compiled,
+        Err(e) => {
+            // A broken declared schema is the tool's problem, not the caller's
+            tracing::warn!("Skipping argument validation, schema compile failed: {}", e);
+            return Ok(());
+        }
+    };
+
+    let result = compiled.validate(args);
+    if let Err(errors) = result {
+        let violations = errors
+            .map(|e| {
+                let kind = match &e.kind {
+                    jsonschema::error::ValidationErrorKind::Required { .. } => {
+                        SchemaViolationKind::MissingRequired
+                    }
+                    jsonschema::error::ValidationErrorKind::Type { .. } => {
+                        SchemaViolationKind::TypeMismatch
+                    }
+                    _ => SchemaViolationKind::InvalidValue,
+                };
+                let message = match &e.kind {
+                    jsonschema::error::ValidationErrorKind::Required { property } => format!(
+                        "missing required field {}",
+                        property
+                            .as_str()
+                            .map(|p| format!("'{}'", p))
+                            .unwrap_or_else(|| property.to_string())
+                    ),
+                    _ => e.to_string(),
+                };
+                SchemaViolation {
+                    kind,
+                    path: e.instance_path.to_string(),
+                    message,
+                }
+            })
+            .collect();
+        return Err(violations);
+    }
+    Ok(())
+}
+
 fn validate_schema(schema: &Value, instance: &Value) -> Result<(), String> {
     let compiled = jsonschema::JSONSchema::options()
         .with_draft(jsonschema::Draft::Draft7)
@@ -437,4 +609,73 @@ mod tests {
 
         assert!(result.get("result").is_some());
     }
+
+    fn scan_tool(mode: SchemaValidation) -> DynamicToolDef {
+        DynamicToolBuilder::new("scan_tool")
+            .input_schema(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "target": {"type": "string"},
+                    "ports": {"type": "integer"}
+                },
+                "required": ["target"]
+            }))
+            .schema_validation(mode)
+            .executor(|args| async move { Ok(args) })
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_missing_required_argument_is_rejected() {
+        let registry = ToolRegistry::new();
+        registry.register(scan_tool(SchemaValidation::Strict)).await;
+
+        let err = registry
+            .execute("scan_tool", serde_json::json!({"ports": 80}))
+            .await
+            .unwrap_err();
+        let violations = err.validation_errors().unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind, SchemaViolationKind::MissingRequired);
+        assert!(err.to_string().contains("missing required field 'target'"));
+    }
+
+    #[tokio::test]
+    async fn test_type_mismatch_is_rejected_unless_validation_is_relaxed() {
+        let registry = ToolRegistry::new();
+        registry.register(scan_tool(SchemaValidation::Strict)).await;
+        let args = serde_json::json!({"target": "example.com", "ports": "80"});
+
+        let err = registry
+            .execute("scan_tool", args.clone())
+            .await
+            .unwrap_err();
+        let violations = err.validation_errors().unwrap();
+        assert_eq!(violations[0].kind, SchemaViolationKind::TypeMismatch);
+        assert_eq!(violations[0].path, "/ports");
+
+        // Relaxing validation lets the tool see (and handle) the raw arguments
+        assert!(
+            registry
+                .set_schema_validation("scan_tool", SchemaValidation::Warn)
+                .await
+        );
+        assert_eq!(
+            registry.execute("scan_tool", args.clone()).await.unwrap(),
+            args
+        );
+        registry
+            .set_schema_validation("scan_tool", SchemaValidation::Off)
+            .await;
+        assert_eq!(
+            registry.execute("scan_tool", args.clone()).await.unwrap(),
+            args
+        );
+        assert!(
+            !registry
+                .set_schema_validation("missing_tool", SchemaValidation::Off)
+                .await
+        );
+    }
 }
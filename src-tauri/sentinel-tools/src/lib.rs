@@ -5,8 +5,11 @@
 //! # Modules
 //! - `agent_browser`: Browser automation using agent-browser daemon
 //! - `buildin_tools`: Built-in tools (port_scan, http_request, local_time, shell, browser)
+//! - `cancellation`: Execution-wide cancellation scopes shared by engines and tools
 //! - `dynamic_tool`: Dynamic tool registration and Rig Tool trait adaptation
 //! - `tool_server`: Tool server for managing all tools
+//! - `tool_metrics`: Per-tool call counts and latency percentiles
+//! - `tool_concurrency`: Global and per-category limits on concurrent tool calls
 //! - `mcp_adapter`: MCP tool adapter
 //! - `plugin_adapter`: Plugin tool adapter
 //! - `workflow_adapter`: Workflow tool adapter
@@ -16,30 +19,38 @@
 pub mod agent_browser;
 pub mod batch_progress_manager;
 pub mod buildin_tools;
+pub mod cancellation;
 pub mod docker_sandbox;
 pub mod dynamic_tool;
 pub mod error_classifier;
 pub mod error_config_loader;
 pub mod exploitdb;
 pub mod mcp_adapter;
+pub mod mcp_client;
 pub mod output_storage;
 pub mod plugin_adapter;
 pub mod terminal;
+pub mod tool_concurrency;
+pub mod tool_metrics;
 pub mod tool_server;
 pub mod workflow_adapter;
 
 pub use agent_browser::*;
 pub use batch_progress_manager::*;
 pub use buildin_tools::*;
+pub use cancellation::*;
 pub use docker_sandbox::*;
 pub use dynamic_tool::*;
 pub use error_classifier::*;
 pub use error_config_loader::*;
 pub use exploitdb::*;
 pub use mcp_adapter::*;
+pub use mcp_client::*;
 pub use output_storage::*;
 pub use plugin_adapter::*;
 pub use terminal::*;
+pub use tool_concurrency::*;
+pub use tool_metrics::*;
 pub use tool_server::*;
 pub use workflow_adapter::*;
 
@@ -8,7 +8,10 @@ use serde_json::Value;
 use std::collections::HashMap;
 use tokio::sync::RwLock;
 
-use crate::dynamic_tool::{create_executor, DynamicToolDef, ToolExecutor, ToolSource};
+use crate::dynamic_tool::{
+    create_executor, DynamicToolDef, SchemaValidation, ToolExecutor, ToolSource,
+};
+use crate::mcp_client::{call_result_to_json, call_tool, get_mcp_client_config};
 use crate::tool_server::ToolServer;
 
 /// MCP tool metadata from server
@@ -120,48 +123,20 @@ async fn execute_mcp_tool_internal(
     };
 
     // Call the tool
-    let result = client
-        .call_tool(rmcp::model::CallToolRequestParam {
+    let config = get_mcp_client_config().await;
+    let result = call_tool(
+        client.peer(),
+        rmcp::model::CallToolRequestParam {
             name: tool_name.to_string().into(),
             arguments: args_map,
-        })
-        .await
-        .map_err(|e| format!("MCP tool call failed: {}", e))?;
-
-    // Convert result to JSON
-    let content_json: Vec<Value> = result
-        .content
-        .iter()
-        .map(|c| match &c.raw {
-            rmcp::model::RawContent::Text(text) => serde_json::json!({
-                "type": "text",
-                "text": text.text
-            }),
-            rmcp::model::RawContent::Image(img) => serde_json::json!({
-                "type": "image",
-                "data": img.data,
-                "mime_type": img.mime_type
-            }),
-            rmcp::model::RawContent::Audio(audio) => serde_json::json!({
-                "type": "audio",
-                "data": audio.data,
-                "mime_type": audio.mime_type
-            }),
-            rmcp::model::RawContent::Resource(res) => serde_json::json!({
-                "type": "resource",
-                "resource": res.resource
-            }),
-            rmcp::model::RawContent::ResourceLink(link) => serde_json::json!({
-                "type": "resource_link",
-                "uri": link.uri
-            }),
-        })
-        .collect();
+        },
+        &config,
+        None,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
 
-    Ok(serde_json::json!({
-        "content": content_json,
-        "is_error": result.is_error.unwrap_or(false)
-    }))
+    Ok(call_result_to_json(&result))
 }
 
 /// Load and register MCP tools from a connected server
@@ -333,6 +308,7 @@ impl McpToolAdapter {
                 server_name: server_name.clone(),
             },
             category: "mcp".to_string(),
+            schema_validation: SchemaValidation::default(),
             executor: create_mcp_tool_executor(server_name, tool_name),
         }
     }
@@ -0,0 +1,280 @@
+//! MCP client call helpers
+//!
+//! Every tool call is bounded by a per-call timeout and can be cancelled through
+//! the enclosing [`cancellation`] scope (an agent or workflow execution). On
+//! timeout or cancellation a `notifications/cancelled` is sent for the in-flight
+//! request, so the server can abandon it and the connection stays usable.
+
+use once_cell::sync::Lazy;
+use rmcp::model::{
+    CallToolRequest, CallToolRequestParam, CallToolResult, CancelledNotificationParam,
+    ClientRequest, RawContent, ServerResult,
+};
+use rmcp::service::{Peer, PeerRequestOptions};
+use rmcp::RoleClient;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+use crate::cancellation;
+
+pub const DEFAULT_MCP_CALL_TIMEOUT_SECS: u64 = 120;
+
+/// MCP client settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpClientConfig {
+    /// Upper bound for a single tool call
+    #[serde(default = "default_call_timeout_secs")]
+    pub call_timeout_secs: u64,
+}
+
+fn default_call_timeout_secs() -> u64 {
+    DEFAULT_MCP_CALL_TIMEOUT_SECS
+}
+
+impl Default for McpClientConfig {
+    fn default() -> Self {
+        Self {
+            call_timeout_secs: DEFAULT_MCP_CALL_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl McpClientConfig {
+    pub fn call_timeout(&self) -> Duration {
+        Duration::from_secs(self.call_timeout_secs.max(1))
+    }
+}
+
+/// MCP tool call errors
+#[derive(Debug, thiserror::Error)]
+pub enum McpCallError {
+    #[error("MCP tool '{tool}' timed out after {timeout_secs}s")]
+    Timeout { tool: String, timeout_secs: u64 },
+    #[error("MCP tool '{tool}' was cancelled")]
+    Cancelled { tool: String },
+    #[error("MCP tool call failed: {0}")]
+    Service(String),
+}
+
+static MCP_CLIENT_CONFIG: Lazy<RwLock<McpClientConfig>> =
+    Lazy::new(|| RwLock::new(McpClientConfig::default()));
+
+/// Get current MCP client config
+pub async fn get_mcp_client_config() -> McpClientConfig {
+    MCP_CLIENT_CONFIG.read().await.clone()
+}
+
+/// Update MCP client config
+pub async fn set_mcp_client_config(config: McpClientConfig) {
+    *MCP_CLIENT_CONFIG.write().await = config;
+}
+
+/// Call a tool with timeout and cancellation
+///
+/// `cancel` defaults to the token of the enclosing cancellation scope, if any.
+pub async fn call_tool(
+    peer: &Peer<RoleClient>,
+    param: CallToolRequestParam,
+    config: &McpClientConfig,
+    cancel: Option<CancellationToken>,
+) -> Result<CallToolResult, McpCallError> {
+    let tool = param.name.to_string();
+    let cancel = cancel
+        .or_else(cancellation::current_token)
+        .unwrap_or_default();
+
+    let handle = peer
+        .send_cancellable_request(
+            ClientRequest::CallToolRequest(CallToolRequest {
+                method: Default::default(),
+                params: param,
+                extensions: Default::default(),
+            }),
+            PeerRequestOptions {
+                timeout: None,
+                meta: None,
+            },
+        )
+        .await
+        .map_err(|e| McpCallError::Service(e.to_string()))?;
+    let request_id = handle.id.clone();
+
+    let error = tokio::select! {
+        response = handle.await_response() => {
+            return match response {
+                Ok(ServerResult::CallToolResult(result)) => Ok(result),
+                Ok(_) => Err(McpCallError::Service("Unexpected response type".to_string())),
+                Err(e) => Err(McpCallError::Service(e.to_string())),
+            };
+        }
+        _ = tokio::time::sleep(config.call_timeout()) => McpCallError::Timeout {
+            tool,
+            timeout_secs: config.call_timeout().as_secs(),
+        },
+        _ = cancel.cancelled() => McpCallError::Cancelled { tool },
+    };
+
+    tracing::warn!("{}, cancelling request {:?}", error, request_id);
+    let _ = peer
+        .notify_cancelled(CancelledNotificationParam {
+            request_id,
+            reason: Some(error.to_string()),
+        })
+        .await;
+    Err(error)
+}
+
+/// Convert a tool result to the JSON shape used by tool executors
+pub fn call_result_to_json(result: &CallToolResult) -> Value {
+    let content: Vec<Value> = result
+        .content
+        .iter()
+        .map(|c| match &c.raw {
+            RawContent::Text(text) => serde_json::json!({
+                "type": "text",
+                "text": text.text
+            }),
+            RawContent::Image(img) => serde_json::json!({
+                "type": "image",
+                "data": img.data,
+                "mime_type": img.mime_type
+            }),
+            RawContent::Audio(audio) => serde_json::json!({
+                "type": "audio",
+                "data": audio.data,
+                "mime_type": audio.mime_type
+            }),
+            RawContent::Resource(res) => serde_json::json!({
+                "type": "resource",
+                "resource": res.resource
+            }),
+            RawContent::ResourceLink(link) => serde_json::json!({
+                "type": "resource_link",
+                "uri": link.uri
+            }),
+        })
+        .collect();
+
+    serde_json::json!({
+        "content": content,
+        "is_error": result.is_error.unwrap_or(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::{Content, ServerCapabilities, ServerInfo};
+    use rmcp::service::{RequestContext, RunningService};
+    use rmcp::{ErrorData as McpError, RoleServer, ServerHandler, ServiceExt};
+
+    /// Mock server: `slow` never answers in time, anything else echoes `pong`
+    struct SlowServer;
+
+    impl ServerHandler for SlowServer {
+        fn get_info(&self) -> ServerInfo {
+            ServerInfo {
+                capabilities: ServerCapabilities::builder().enable_tools().build(),
+                ..Default::default()
+            }
+        }
+
+        async fn call_tool(
+            &self,
+            request: CallToolRequestParam,
+            _context: RequestContext<RoleServer>,
+        ) -> Result<CallToolResult, McpError> {
+            if request.name == "slow" {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            }
+            Ok(CallToolResult::success(vec![Content::text("pong")]))
+        }
+    }
+
+    async fn connect() -> RunningService<RoleClient, ()> {
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            if let Ok(running) = SlowServer.serve(server_io).await {
+                let _ = running.waiting().await;
+            }
+        });
+        ().serve(client_io).await.expect("client handshake")
+    }
+
+    fn request(name: &str) -> CallToolRequestParam {
+        CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout_fires_and_connection_stays_usable() {
+        let client = connect().await;
+        let config = McpClientConfig {
+            call_timeout_secs: 1,
+        };
+
+        let started = std::time::Instant::now();
+        let err = call_tool(client.peer(), request("slow"), &config, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            McpCallError::Timeout {
+                timeout_secs: 1,
+                ..
+            }
+        ));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let result = call_tool(client.peer(), request("ping"), &config, None)
+            .await
+            .unwrap();
+        assert_eq!(call_result_to_json(&result)["content"][0]["text"], "pong");
+
+        let _ = client.cancel().await;
+    }
+
+    #[tokio::test]
+    async fn test_scope_cancellation_propagates_to_call() {
+        let client = connect().await;
+        let peer = client.peer().clone();
+        let scope = format!("mcp-scope-{}", uuid::Uuid::new_v4());
+
+        let scope_for_call = scope.clone();
+        let call = tokio::spawn(async move {
+            cancellation::with_cancellation_scope(
+                &scope_for_call,
+                call_tool(&peer, request("slow"), &McpClientConfig::default(), None),
+            )
+            .await
+        });
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(cancellation::cancel_scope(&scope).await);
+
+        let err = tokio::time::timeout(Duration::from_secs(5), call)
+            .await
+            .expect("cancellation should end the call")
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(err, McpCallError::Cancelled { .. }));
+        cancellation::clear_cancellation_scope(&scope).await;
+
+        let result = call_tool(
+            client.peer(),
+            request("ping"),
+            &McpClientConfig::default(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(!result.is_error.unwrap_or(false));
+
+        let _ = client.cancel().await;
+    }
+}
@@ -0,0 +1,302 @@
+//! Concurrency control for dynamic tool execution
+//!
+//! Every dynamic tool call takes a permit from a global semaphore and, when
+//! its category has a limit, from a per-category semaphore. Calls waiting for
+//! a permit count as queued; once the queue is full new calls are rejected
+//! instead of piling up, which pushes back on the agent or workflow.
+//!
+//! `subagent_*` tools are exempt: they mostly wait on child agents, and those
+//! children need permits for their own tool calls. Holding a slot while
+//! waiting would deadlock once enough subagents run in parallel.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
+
+/// Tools with this prefix never take a permit
+const EXEMPT_TOOL_PREFIX: &str = "subagent_";
+
+static TOOL_LIMITER: Lazy<RwLock<Arc<ToolConcurrencyLimiter>>> = Lazy::new(|| {
+    RwLock::new(Arc::new(ToolConcurrencyLimiter::new(
+        ToolConcurrencyConfig::default(),
+    )))
+});
+
+/// Tool concurrency configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolConcurrencyConfig {
+    /// Maximum tool calls running at once across all tools
+    pub max_concurrent: usize,
+    /// Maximum calls waiting for a permit before new calls are rejected
+    pub max_queue: usize,
+    /// Per-category limits, keyed by tool category (e.g. `shell`)
+    #[serde(default)]
+    pub category_limits: HashMap<String, usize>,
+}
+
+impl Default for ToolConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 16,
+            max_queue: 64,
+            category_limits: HashMap::from([("shell".to_string(), 2)]),
+        }
+    }
+}
+
+/// Current limiter state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolQueueStatus {
+    pub running: usize,
+    pub queued: usize,
+    pub max_concurrent: usize,
+    pub max_queue: usize,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ToolConcurrencyError {
+    #[error("Tool queue is full ({0} calls waiting), try again later")]
+    QueueFull(usize),
+    #[error("Tool concurrency limiter closed")]
+    Closed,
+}
+
+/// Held while a tool runs; releases its slots on drop
+pub struct ToolPermit {
+    _global: OwnedSemaphorePermit,
+    _category: Option<OwnedSemaphorePermit>,
+    running: Arc<AtomicUsize>,
+}
+
+impl Drop for ToolPermit {
+    fn drop(&mut self) {
+        self.running.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub struct ToolConcurrencyLimiter {
+    config: ToolConcurrencyConfig,
+    global: Arc<Semaphore>,
+    categories: HashMap<String, Arc<Semaphore>>,
+    queued: AtomicUsize,
+    running: Arc<AtomicUsize>,
+}
+
+impl ToolConcurrencyLimiter {
+    pub fn new(config: ToolConcurrencyConfig) -> Self {
+        let categories = config
+            .category_limits
+            .iter()
+            .map(|(category, limit)| (category.clone(), Arc::new(Semaphore::new((*limit).max(1)))))
+            .collect();
+        Self {
+            global: Arc::new(Semaphore::new(config.max_concurrent.max(1))),
+            categories,
+            queued: AtomicUsize::new(0),
+            running: Arc::new(AtomicUsize::new(0)),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &ToolConcurrencyConfig {
+        &self.config
+    }
+
+    /// Wait for a slot, or fail right away when the queue is full
+    pub async fn acquire(&self, category: &str) -> Result<ToolPermit, ToolConcurrencyError> {
+        let queued = self.queued.fetch_add(1, Ordering::SeqCst);
+        let _queued = QueuedGuard(&self.queued);
+        if queued >= self.config.max_queue {
+            return Err(ToolConcurrencyError::QueueFull(queued));
+        }
+
+        // Category first so a call blocked on its category doesn't hold a global slot
+        let category_permit = match self.categories.get(category) {
+            Some(semaphore) => Some(
+                semaphore
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .map_err(|_| ToolConcurrencyError::Closed)?,
+            ),
+            None => None,
+        };
+        let global_permit = self
+            .global
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|_| ToolConcurrencyError::Closed)?;
+
+        self.running.fetch_add(1, Ordering::SeqCst);
+        Ok(ToolPermit {
+            _global: global_permit,
+            _category: category_permit,
+            running: self.running.clone(),
+        })
+    }
+
+    pub fn status(&self) -> ToolQueueStatus {
+        ToolQueueStatus {
+            running: self.running.load(Ordering::SeqCst),
+            queued: self.queued.load(Ordering::SeqCst),
+            max_concurrent: self.config.max_concurrent,
+            max_queue: self.config.max_queue,
+        }
+    }
+}
+
+/// Whether calls of this tool bypass the limiter
+pub fn is_exempt_tool(tool_name: &str) -> bool {
+    tool_name.starts_with(EXEMPT_TOOL_PREFIX)
+}
+
+/// Acquire a slot from the global limiter; exempt tools get `None` right away
+pub async fn acquire_tool_permit(
+    tool_name: &str,
+    category: &str,
+) -> Result<Option<ToolPermit>, ToolConcurrencyError> {
+    if is_exempt_tool(tool_name) {
+        return Ok(None);
+    }
+    let limiter = TOOL_LIMITER.read().await.clone();
+    limiter.acquire(category).await.map(Some)
+}
+
+/// Replace the global limiter; calls already running keep their old slots
+pub async fn set_tool_concurrency_config(config: ToolConcurrencyConfig) {
+    *TOOL_LIMITER.write().await = Arc::new(ToolConcurrencyLimiter::new(config));
+}
+
+pub async fn get_tool_concurrency_config() -> ToolConcurrencyConfig {
+    TOOL_LIMITER.read().await.config().clone()
+}
+
+pub async fn get_tool_queue_status() -> ToolQueueStatus {
+    TOOL_LIMITER.read().await.status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limiter(
+        max_concurrent: usize,
+        max_queue: usize,
+        shell: usize,
+    ) -> Arc<ToolConcurrencyLimiter> {
+        Arc::new(ToolConcurrencyLimiter::new(ToolConcurrencyConfig {
+            max_concurrent,
+            max_queue,
+            category_limits: HashMap::from([("shell".to_string(), shell)]),
+        }))
+    }
+
+    /// Runs `count` calls that each hold a slot for a while; returns the peak concurrency
+    async fn run_calls(
+        limiter: Arc<ToolConcurrencyLimiter>,
+        category: &str,
+        count: usize,
+    ) -> usize {
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..count)
+            .map(|_| {
+                let (limiter, active, peak) = (limiter.clone(), active.clone(), peak.clone());
+                let category = category.to_string();
+                tokio::spawn(async move {
+                    let _permit = limiter.acquire(&category).await.unwrap();
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(30)).await;
+                    active.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let status = limiter.status();
+        assert!(
+            status.queued > 0,
+            "extra calls should be queued: {:?}",
+            status
+        );
+
+        for handle in handles {
+            handle.await.unwrap();
+        }
+        peak.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn test_calls_over_limit_are_queued() {
+        let limiter = limiter(3, 32, 2);
+        let peak = run_calls(limiter.clone(), "network", 10).await;
+        assert_eq!(peak, 3);
+        assert_eq!(
+            limiter.status(),
+            ToolQueueStatus {
+                running: 0,
+                queued: 0,
+                max_concurrent: 3,
+                max_queue: 32,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_category_limit_applies() {
+        let limiter = limiter(8, 32, 2);
+        assert_eq!(run_calls(limiter, "shell", 6).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_rejects() {
+        let limiter = limiter(1, 1, 1);
+        let held = limiter.acquire("other").await.unwrap();
+
+        let waiting = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire("other").await.map(|_| ()) })
+        };
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(limiter.status().queued, 1);
+
+        assert!(matches!(
+            limiter.acquire("other").await,
+            Err(ToolConcurrencyError::QueueFull(1))
+        ));
+
+        drop(held);
+        assert!(waiting.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_subagent_tools_bypass_limiter() {
+        assert!(is_exempt_tool("subagent_execute"));
+        assert!(is_exempt_tool("subagent_await"));
+        assert!(!is_exempt_tool("shell"));
+
+        // Returns without touching the global limiter, so it can never wait
+        let permit = tokio::time::timeout(
+            Duration::from_millis(100),
+            acquire_tool_permit("subagent_await", "system"),
+        )
+        .await
+        .expect("exempt tool must not wait for a permit")
+        .unwrap();
+        assert!(permit.is_none());
+    }
+}
@@ -0,0 +1,197 @@
+//! Per-tool execution metrics
+//!
+//! Every dynamic tool call records its duration and outcome. Counters are
+//! cumulative; latency samples keep the most recent `MAX_SAMPLES_PER_TOOL`
+//! calls per tool and percentiles are computed from them on demand.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Latency samples kept per tool
+pub const MAX_SAMPLES_PER_TOOL: usize = 1024;
+
+static TOOL_METRICS: Lazy<ToolMetrics> = Lazy::new(ToolMetrics::default);
+
+/// Performance summary of one tool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolLatencyStats {
+    pub tool_name: String,
+    pub call_count: u64,
+    pub success_count: u64,
+    pub error_count: u64,
+    pub success_rate: f64,
+    pub error_rate: f64,
+    pub avg_ms: f64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+    pub last_error: Option<String>,
+    /// Unix timestamp (seconds) of the last error
+    pub last_error_at: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+struct ToolMetricEntry {
+    call_count: u64,
+    error_count: u64,
+    samples: VecDeque<u64>,
+    last_error: Option<String>,
+    last_error_at: Option<i64>,
+}
+
+/// Metrics store keyed by tool name
+#[derive(Debug, Default)]
+pub struct ToolMetrics {
+    entries: Mutex<HashMap<String, ToolMetricEntry>>,
+}
+
+impl ToolMetrics {
+    /// Record one call; `error` is `Some` for failed calls
+    pub fn record(&self, tool_name: &str, duration_ms: u64, error: Option<&str>) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = entries.entry(tool_name.to_string()).or_default();
+        entry.call_count += 1;
+        if entry.samples.len() == MAX_SAMPLES_PER_TOOL {
+            entry.samples.pop_front();
+        }
+        entry.samples.push_back(duration_ms);
+        if let Some(error) = error {
+            entry.error_count += 1;
+            entry.last_error = Some(error.to_string());
+            entry.last_error_at = Some(chrono::Utc::now().timestamp());
+        }
+    }
+
+    /// Stats for every tool, slowest p95 first
+    pub fn snapshot(&self) -> Vec<ToolLatencyStats> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stats: Vec<ToolLatencyStats> = entries
+            .iter()
+            .map(|(name, entry)| {
+                let mut sorted: Vec<u64> = entry.samples.iter().copied().collect();
+                sorted.sort_unstable();
+                let calls = entry.call_count.max(1) as f64;
+                let success_count = entry.call_count - entry.error_count;
+                ToolLatencyStats {
+                    tool_name: name.clone(),
+                    call_count: entry.call_count,
+                    success_count,
+                    error_count: entry.error_count,
+                    success_rate: success_count as f64 / calls,
+                    error_rate: entry.error_count as f64 / calls,
+                    avg_ms: if sorted.is_empty() {
+                        0.0
+                    } else {
+                        sorted.iter().sum::<u64>() as f64 / sorted.len() as f64
+                    },
+                    p50_ms: percentile(&sorted, 50.0),
+                    p95_ms: percentile(&sorted, 95.0),
+                    p99_ms: percentile(&sorted, 99.0),
+                    max_ms: sorted.last().copied().unwrap_or(0),
+                    last_error: entry.last_error.clone(),
+                    last_error_at: entry.last_error_at,
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| {
+            b.p95_ms
+                .cmp(&a.p95_ms)
+                .then_with(|| a.tool_name.cmp(&b.tool_name))
+        });
+        stats
+    }
+
+    /// Clear counters and histograms
+    pub fn reset(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+/// Nearest-rank percentile over sorted samples; 0 when empty
+pub fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Record a call in the global metrics store
+pub fn record_tool_metric(tool_name: &str, duration_ms: u64, error: Option<&str>) {
+    TOOL_METRICS.record(tool_name, duration_ms, error);
+}
+
+/// Per-tool stats from the global metrics store
+pub fn get_tool_latency_stats() -> Vec<ToolLatencyStats> {
+    TOOL_METRICS.snapshot()
+}
+
+/// Clear the global metrics store
+pub fn reset_tool_metrics() {
+    TOOL_METRICS.reset();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_from_recorded_timings() {
+        let metrics = ToolMetrics::default();
+        for ms in 1..=100 {
+            metrics.record("port_scan", ms, None);
+        }
+        metrics.record("http_request", 40, None);
+        metrics.record("http_request", 10, Some("connection refused"));
+        metrics.record("http_request", 20, None);
+
+        let stats = metrics.snapshot();
+        assert_eq!(stats[0].tool_name, "port_scan");
+        let port_scan = &stats[0];
+        assert_eq!(port_scan.call_count, 100);
+        assert_eq!(port_scan.p50_ms, 50);
+        assert_eq!(port_scan.p95_ms, 95);
+        assert_eq!(port_scan.p99_ms, 99);
+        assert_eq!(port_scan.max_ms, 100);
+        assert_eq!(port_scan.avg_ms, 50.5);
+        assert_eq!(port_scan.error_rate, 0.0);
+
+        let http = &stats[1];
+        assert_eq!(http.call_count, 3);
+        assert_eq!(http.error_count, 1);
+        assert_eq!(http.p50_ms, 20);
+        assert_eq!(http.p99_ms, 40);
+        assert!((http.success_rate - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(http.last_error.as_deref(), Some("connection refused"));
+    }
+
+    #[test]
+    fn test_samples_are_bounded_and_reset_clears() {
+        let metrics = ToolMetrics::default();
+        for _ in 0..MAX_SAMPLES_PER_TOOL {
+            metrics.record("shell", 1000, None);
+        }
+        for _ in 0..MAX_SAMPLES_PER_TOOL {
+            metrics.record("shell", 5, None);
+        }
+        let stats = metrics.snapshot();
+        assert_eq!(stats[0].call_count, 2 * MAX_SAMPLES_PER_TOOL as u64);
+        assert_eq!(stats[0].max_ms, 5);
+
+        metrics.reset();
+        assert!(metrics.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_percentile_edge_cases() {
+        assert_eq!(percentile(&[], 50.0), 0);
+        assert_eq!(percentile(&[7], 99.0), 7);
+        assert_eq!(percentile(&[1, 2], 0.0), 1);
+    }
+}
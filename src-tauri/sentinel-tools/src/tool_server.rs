@@ -11,15 +11,17 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::buildin_tools::{
-    browser::constants as browser_constants, HttpRequestTool, LocalTimeTool, MemoryManagerTool,
-    OcrTool, PortScanTool, SearchExploitTool, ShellTool, SkillsTool, SubdomainBruteTool,
-    TenthManTool, TodosTool, WebSearchTool,
+    browser::constants as browser_constants, ContentDiscoveryTool, CorsCheckTool, ExtractTool,
+    HttpRequestTool, IpEnrichmentTool, JwtTool, LocalTimeTool, MemoryManagerTool,
+    NucleiTemplateTool, OcrTool, PortScanTool, ScreenshotTool, SearchExploitTool, ShellTool,
+    SkillsTool, SubdomainBruteTool, TenthManTool, TodosTool, WebSearchTool, WhoisTool,
 };
 
 use crate::terminal::server::TerminalServer;
 
 use crate::dynamic_tool::{
-    DynamicTool, DynamicToolBuilder, DynamicToolDef, ToolExecutor, ToolRegistry, ToolSource,
+    DynamicTool, DynamicToolBuilder, DynamicToolDef, SchemaValidation, SchemaViolation,
+    ToolExecutor, ToolRegistry, ToolSource,
 };
 
 /// Global tool server instance
@@ -72,6 +74,9 @@ pub struct ToolResult {
     pub tool_name: String,
     pub output: Option<Value>,
     pub error: Option<String>,
+    /// Argument schema violations when the call was rejected before execution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation_errors: Option<Vec<SchemaViolation>>,
     pub execution_time_ms: u64,
 }
 
@@ -282,6 +287,7 @@ impl ToolServer {
                 "required": ["command"]
             }))
             .source(ToolSource::Builtin)
+            .category("shell")
             .executor(|args| async move {
                 use crate::buildin_tools::shell::ShellArgs;
                 use rig::tool::Tool;
@@ -370,6 +376,122 @@ impl ToolServer {
 
         self.registry.register(subdomain_brute_def).await;
 
+        // Register content_discovery tool
+        let content_discovery_def = DynamicToolBuilder::new(ContentDiscoveryTool::NAME.to_string())
+            .description(ContentDiscoveryTool::DESCRIPTION.to_string())
+            .input_schema(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "base_url": {
+                        "type": "string",
+                        "description": "Base URL to brute-force below (e.g. 'https://example.com/app/')"
+                    },
+                    "dictionary_id": {
+                        "type": "string",
+                        "description": "ID of a stored dictionary to use as word list"
+                    },
+                    "words": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Inline word list (used when no dictionary_id is given)"
+                    },
+                    "extensions": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Extensions appended to each word without one (e.g. ['php', 'bak'])"
+                    },
+                    "concurrency": {
+                        "type": "integer",
+                        "description": "Number of concurrent requests",
+                        "default": 20
+                    },
+                    "rate_limit": {
+                        "type": "integer",
+                        "description": "Maximum requests per second (0 = unlimited)",
+                        "default": 50
+                    },
+                    "match_status": {
+                        "type": "array",
+                        "items": { "type": "integer" },
+                        "description": "Status codes reported as found",
+                        "default": [200, 204, 301, 302, 307, 308, 401, 403, 405]
+                    },
+                    "filter_status": {
+                        "type": "array",
+                        "items": { "type": "integer" },
+                        "description": "Status codes never reported, even if matched"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "Per-request timeout in seconds",
+                        "default": 10
+                    },
+                    "max_depth": {
+                        "type": "integer",
+                        "description": "How many directory levels to recurse into (0 = no recursion)",
+                        "default": 0
+                    },
+                    "max_results": {
+                        "type": "integer",
+                        "description": "Stop after this many discovered paths",
+                        "default": 500
+                    },
+                    "backoff": {
+                        "type": "object",
+                        "description": "Backoff when the target rate-limits (429, WAF block pages, identical 403s)",
+                        "properties": {
+                            "block_threshold": {
+                                "type": "integer",
+                                "description": "Consecutive blocked responses before pausing",
+                                "default": 5
+                            },
+                            "soft_block_threshold": {
+                                "type": "integer",
+                                "description": "Consecutive identical 403 responses treated as a block",
+                                "default": 20
+                            },
+                            "initial_backoff_ms": {
+                                "type": "integer",
+                                "description": "First pause in milliseconds, doubled on each further backoff",
+                                "default": 2000
+                            },
+                            "max_backoff_ms": {
+                                "type": "integer",
+                                "description": "Longest pause in milliseconds",
+                                "default": 60000
+                            },
+                            "max_backoffs": {
+                                "type": "integer",
+                                "description": "Stop the scan after this many backoffs",
+                                "default": 6
+                            }
+                        }
+                    }
+                },
+                "required": ["base_url"]
+            }))
+            .source(ToolSource::Builtin)
+            .category("recon")
+            .executor(|args| async move {
+                use crate::buildin_tools::content_discovery::ContentDiscoveryArgs;
+                use rig::tool::Tool;
+
+                let tool_args: ContentDiscoveryArgs = serde_json::from_value(args)
+                    .map_err(|e| format!("Invalid arguments: {}", e))?;
+
+                let result = ContentDiscoveryTool
+                    .call(tool_args)
+                    .await
+                    .map_err(|e| format!("Content discovery failed: {}", e))?;
+
+                serde_json::to_value(result)
+                    .map_err(|e| format!("Failed to serialize result: {}", e))
+            })
+            .build()
+            .expect("Failed to build content_discovery tool");
+
+        self.registry.register(content_discovery_def).await;
+
         // Register todos tool
         let todos_def = DynamicToolBuilder::new(TodosTool::NAME.to_string())
             .description(TodosTool::DESCRIPTION.to_string())
@@ -670,6 +792,318 @@ impl ToolServer {
 
         self.registry.register(ocr_def).await;
 
+        // Register extract tool
+        let extract_def = DynamicToolBuilder::new(ExtractTool::NAME.to_string())
+            .description(ExtractTool::DESCRIPTION.to_string())
+            .input_schema(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "input": {
+                        "type": "string",
+                        "description": "Text to extract from, e.g. a previous tool's output"
+                    },
+                    "mode": {
+                        "type": "string",
+                        "description": "Extraction mode",
+                        "enum": ["regex", "jsonpath", "css"]
+                    },
+                    "pattern": {
+                        "type": "string",
+                        "description": "Regex with capture groups, JSONPath expression or CSS selector"
+                    },
+                    "attribute": {
+                        "type": "string",
+                        "description": "CSS mode only: return this attribute instead of element text"
+                    },
+                    "max_matches": {
+                        "type": "integer",
+                        "description": "Maximum number of matches to return",
+                        "default": 100
+                    }
+                },
+                "required": ["input", "mode", "pattern"]
+            }))
+            .source(ToolSource::Builtin)
+            .executor(|args| async move {
+                use crate::buildin_tools::extract::ExtractArgs;
+                use rig::tool::Tool;
+
+                let tool_args: ExtractArgs = serde_json::from_value(args)
+                    .map_err(|e| format!("Invalid arguments: {}", e))?;
+
+                let result = ExtractTool
+                    .call(tool_args)
+                    .await
+                    .map_err(|e| format!("Extract failed: {}", e))?;
+
+                serde_json::to_value(result)
+                    .map_err(|e| format!("Failed to serialize result: {}", e))
+            })
+            .build()
+            .expect("Failed to build extract tool");
+
+        self.registry.register(extract_def).await;
+
+        // Register jwt_analyze tool
+        let jwt_def = DynamicToolBuilder::new(JwtTool::NAME.to_string())
+            .description(JwtTool::DESCRIPTION.to_string())
+            .input_schema(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "token": {
+                        "type": "string",
+                        "description": "The JWT to analyze (a leading 'Bearer ' is ignored)"
+                    },
+                    "secret": {
+                        "type": "string",
+                        "description": "Shared secret for HS256/HS384/HS512 signature verification"
+                    },
+                    "jwks": {
+                        "type": "string",
+                        "description": "JWKS (or a single JWK) as JSON for signature verification"
+                    }
+                },
+                "required": ["token"]
+            }))
+            .source(ToolSource::Builtin)
+            .executor(|args| async move {
+                use crate::buildin_tools::jwt::JwtArgs;
+                use rig::tool::Tool;
+
+                let tool_args: JwtArgs = serde_json::from_value(args)
+                    .map_err(|e| format!("Invalid arguments: {}", e))?;
+
+                let result = JwtTool
+                    .call(tool_args)
+                    .await
+                    .map_err(|e| format!("JWT analysis failed: {}", e))?;
+
+                serde_json::to_value(result)
+                    .map_err(|e| format!("Failed to serialize result: {}", e))
+            })
+            .build()
+            .expect("Failed to build jwt_analyze tool");
+
+        self.registry.register(jwt_def).await;
+
+        // Register nuclei_template tool
+        let nuclei_def = DynamicToolBuilder::new(NucleiTemplateTool::NAME.to_string())
+            .description(NucleiTemplateTool::DESCRIPTION.to_string())
+            .input_schema(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "template": {
+                        "type": "string",
+                        "description": "Template YAML content (nuclei format, single HTTP request)"
+                    },
+                    "target": {
+                        "type": "string",
+                        "description": "Target base URL, substituted for {{BaseURL}}"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "Per-request timeout in seconds",
+                        "default": 10
+                    }
+                },
+                "required": ["template", "target"]
+            }))
+            .source(ToolSource::Builtin)
+            .executor(|args| async move {
+                use crate::buildin_tools::nuclei_template::NucleiTemplateArgs;
+                use rig::tool::Tool;
+
+                let tool_args: NucleiTemplateArgs = serde_json::from_value(args)
+                    .map_err(|e| format!("Invalid arguments: {}", e))?;
+
+                let result = NucleiTemplateTool::new()
+                    .call(tool_args)
+                    .await
+                    .map_err(|e| format!("Template execution failed: {}", e))?;
+
+                serde_json::to_value(result)
+                    .map_err(|e| format!("Failed to serialize result: {}", e))
+            })
+            .build()
+            .expect("Failed to build nuclei_template tool");
+
+        self.registry.register(nuclei_def).await;
+
+        // Register screenshot_url tool
+        let screenshot_def = DynamicToolBuilder::new(ScreenshotTool::NAME.to_string())
+            .description(ScreenshotTool::DESCRIPTION.to_string())
+            .input_schema(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "URL to capture (http or https)"
+                    },
+                    "full_page": {
+                        "type": "boolean",
+                        "description": "Capture the full scrollable page instead of the viewport",
+                        "default": false
+                    },
+                    "wait_for_selector": {
+                        "type": "string",
+                        "description": "CSS selector to wait for before capturing"
+                    },
+                    "timeout_ms": {
+                        "type": "integer",
+                        "description": "Navigation timeout in milliseconds",
+                        "default": 30000
+                    },
+                    "viewport_width": {
+                        "type": "integer",
+                        "description": "Viewport width in pixels",
+                        "default": 1280
+                    },
+                    "viewport_height": {
+                        "type": "integer",
+                        "description": "Viewport height in pixels",
+                        "default": 720
+                    }
+                },
+                "required": ["url"]
+            }))
+            .source(ToolSource::Builtin)
+            .executor(|args| async move {
+                use crate::buildin_tools::screenshot::ScreenshotArgs;
+                use rig::tool::Tool;
+
+                let tool_args: ScreenshotArgs = serde_json::from_value(args)
+                    .map_err(|e| format!("Invalid arguments: {}", e))?;
+
+                let result = ScreenshotTool
+                    .call(tool_args)
+                    .await
+                    .map_err(|e| format!("Screenshot failed: {}", e))?;
+
+                serde_json::to_value(result)
+                    .map_err(|e| format!("Failed to serialize result: {}", e))
+            })
+            .build()
+            .expect("Failed to build screenshot_url tool");
+
+        self.registry.register(screenshot_def).await;
+
+        // Register whois_lookup tool
+        let whois_def = DynamicToolBuilder::new(WhoisTool::NAME.to_string())
+            .description(WhoisTool::DESCRIPTION.to_string())
+            .input_schema(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Domain name or IP address"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "Timeout per lookup in seconds",
+                        "default": 15
+                    }
+                },
+                "required": ["query"]
+            }))
+            .source(ToolSource::Builtin)
+            .executor(|args| async move {
+                use crate::buildin_tools::whois::WhoisArgs;
+                use rig::tool::Tool;
+
+                let tool_args: WhoisArgs = serde_json::from_value(args)
+                    .map_err(|e| format!("Invalid arguments: {}", e))?;
+
+                let result = WhoisTool::default()
+                    .call(tool_args)
+                    .await
+                    .map_err(|e| format!("WHOIS lookup failed: {}", e))?;
+
+                serde_json::to_value(result)
+                    .map_err(|e| format!("Failed to serialize result: {}", e))
+            })
+            .build()
+            .expect("Failed to build whois_lookup tool");
+
+        self.registry.register(whois_def).await;
+
+        // Register ip_enrichment tool
+        let ip_enrichment_def = DynamicToolBuilder::new(IpEnrichmentTool::NAME.to_string())
+            .description(IpEnrichmentTool::DESCRIPTION.to_string())
+            .input_schema(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "target": {
+                        "type": "string",
+                        "description": "IP address, hostname or URL"
+                    }
+                },
+                "required": ["target"]
+            }))
+            .source(ToolSource::Builtin)
+            .executor(|args| async move {
+                use crate::buildin_tools::ip_enrichment::IpEnrichmentArgs;
+                use rig::tool::Tool;
+
+                let tool_args: IpEnrichmentArgs = serde_json::from_value(args)
+                    .map_err(|e| format!("Invalid arguments: {}", e))?;
+
+                let result = IpEnrichmentTool::default()
+                    .call(tool_args)
+                    .await
+                    .map_err(|e| format!("IP enrichment failed: {}", e))?;
+
+                serde_json::to_value(result)
+                    .map_err(|e| format!("Failed to serialize result: {}", e))
+            })
+            .build()
+            .expect("Failed to build ip_enrichment tool");
+
+        self.registry.register(ip_enrichment_def).await;
+
+        // Register cors_check tool
+        let cors_check_def = DynamicToolBuilder::new(CorsCheckTool::NAME.to_string())
+            .description(CorsCheckTool::DESCRIPTION.to_string())
+            .input_schema(serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "url": {
+                        "type": "string",
+                        "description": "Target URL (typically an authenticated API endpoint)"
+                    },
+                    "method": {
+                        "type": "string",
+                        "description": "HTTP method",
+                        "default": "GET"
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "Per-request timeout in seconds",
+                        "default": 10
+                    }
+                },
+                "required": ["url"]
+            }))
+            .source(ToolSource::Builtin)
+            .executor(|args| async move {
+                use crate::buildin_tools::cors_check::CorsCheckArgs;
+                use rig::tool::Tool;
+
+                let tool_args: CorsCheckArgs = serde_json::from_value(args)
+                    .map_err(|e| format!("Invalid arguments: {}", e))?;
+
+                let result = CorsCheckTool::new()
+                    .call(tool_args)
+                    .await
+                    .map_err(|e| format!("CORS check failed: {}", e))?;
+
+                serde_json::to_value(result)
+                    .map_err(|e| format!("Failed to serialize result: {}", e))
+            })
+            .build()
+            .expect("Failed to build cors_check tool");
+
+        self.registry.register(cors_check_def).await;
+
         // Register subagent tools (spawn, wait, run)
         self.register_subagent_tools().await;
 
@@ -799,6 +1233,7 @@ impl ToolServer {
                 }
             }))
             .source(ToolSource::Builtin)
+            .category("shell")
             .executor(|args| async move {
                 use crate::buildin_tools::shell::check_shell_permission;
                 use crate::terminal::{TERMINAL_MANAGER, TerminalSessionConfig, WaitStrategy, normalize_command, detect_shell_prompt, ExecutionMode};
@@ -1447,6 +1882,7 @@ impl ToolServer {
                 tool_name: name.to_string(),
                 output: Some(output),
                 error: None,
+                validation_errors: None,
                 execution_time_ms: start.elapsed().as_millis() as u64,
             },
             Err(e) => ToolResult {
@@ -1454,11 +1890,17 @@ impl ToolServer {
                 tool_name: name.to_string(),
                 output: None,
                 error: Some(e.to_string()),
+                validation_errors: e.validation_errors().map(<[SchemaViolation]>::to_vec),
                 execution_time_ms: start.elapsed().as_millis() as u64,
             },
         }
     }
 
+    /// Set how strictly a tool's call arguments are validated against its schema
+    pub async fn set_schema_validation(&self, name: &str, mode: SchemaValidation) -> bool {
+        self.registry.set_schema_validation(name, mode).await
+    }
+
     /// List all tools
     pub async fn list_tools(&self) -> Vec<ToolInfo> {
         self.registry
@@ -1538,6 +1980,7 @@ impl ToolServer {
                 server_name: server_name.to_string(),
             },
             category: "mcp".to_string(),
+            schema_validation: SchemaValidation::default(),
             executor,
         };
 
@@ -1567,6 +2010,7 @@ impl ToolServer {
                 plugin_id: plugin_id.to_string(),
             },
             category: category.unwrap_or_else(|| "other".to_string()),
+            schema_validation: SchemaValidation::default(),
             executor,
         };
 
@@ -1594,6 +2038,7 @@ impl ToolServer {
                 workflow_id: workflow_id.to_string(),
             },
             category: "workflow".to_string(),
+            schema_validation: SchemaValidation::default(),
             executor,
         };
 
@@ -1818,6 +2263,8 @@ mod tests {
         assert!(server.get_tool("subdomain_brute").await.is_some());
         assert!(server.get_tool("todos").await.is_some());
         assert!(server.get_tool("web_search").await.is_some());
+        assert!(server.get_tool("jwt_analyze").await.is_some());
+        assert!(server.get_tool("content_discovery").await.is_some());
     }
 
     #[tokio::test]
@@ -0,0 +1,517 @@
+//! 被动资产提取
+//!
+//! 从经过代理的流量中提取主机（域名/IP）、站点、接口端点和技术栈。提取结果先在内存中
+//! 去重缓冲，由后台任务定期批量写入资产库并建立关系，避免每个请求都访问数据库。
+
+use sentinel_core::domain::registrable_domain;
+use sentinel_core::models::asset::{AssetType, CreateAssetRequest, RelationshipType, RiskLevel};
+use sentinel_db::{Database, DatabaseService};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+use url::{Host, Url};
+
+/// 被动提取资产的来源标识
+pub const PASSIVE_ASSET_SOURCE: &str = "proxy_traffic";
+
+const CREATED_BY: &str = "passive_asset_extractor";
+
+/// 已见资产键的上限，超过后清空（只会导致重复查询数据库，不会产生重复资产）
+const MAX_SEEN_KEYS: usize = 50_000;
+
+/// 不作为接口端点记录的静态资源扩展名
+const STATIC_EXTENSIONS: &[&str] = &[
+    "js", "css", "map", "png", "jpg", "jpeg", "gif", "svg", "ico", "webp", "woff", "woff2", "ttf",
+    "eot", "mp4", "mp3",
+];
+
+/// 作为技术栈记录的响应头
+const TECHNOLOGY_HEADERS: &[&str] = &["server", "x-powered-by"];
+
+/// 从流量中发现的资产
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredAsset {
+    pub asset_type: AssetType,
+    pub value: String,
+}
+
+impl DiscoveredAsset {
+    fn new(asset_type: AssetType, value: impl Into<String>) -> Self {
+        Self {
+            asset_type,
+            value: value.into(),
+        }
+    }
+
+    fn key(&self) -> String {
+        format!("{}:{}", self.asset_type.as_str(), self.value)
+    }
+}
+
+/// 资产之间的关系
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredRelation {
+    pub source: DiscoveredAsset,
+    pub target: DiscoveredAsset,
+    pub relationship_type: RelationshipType,
+}
+
+impl DiscoveredRelation {
+    fn key(&self) -> String {
+        format!(
+            "{}>{}>{}",
+            self.source.key(),
+            self.relationship_type.as_str(),
+            self.target.key()
+        )
+    }
+}
+
+/// 待写入的资产与关系
+#[derive(Debug, Default)]
+pub struct PendingAssets {
+    pub assets: Vec<DiscoveredAsset>,
+    pub relations: Vec<DiscoveredRelation>,
+}
+
+impl PendingAssets {
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty() && self.relations.is_empty()
+    }
+}
+
+/// 把路径中的数字、UUID、长十六进制段替换为 `{id}`，避免同一接口产生大量资产
+fn normalize_path(path: &str) -> String {
+    let segments: Vec<&str> = path
+        .split('/')
+        .map(|segment| {
+            let is_numeric = !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit());
+            let is_hex_id =
+                segment.len() >= 16 && segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+            if is_numeric || is_hex_id {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect();
+    segments.join("/")
+}
+
+/// 从一次请求/响应中提取资产和关系
+pub fn extract_assets(
+    url: &str,
+    status: Option<u16>,
+    response_headers: Option<&HashMap<String, String>>,
+) -> PendingAssets {
+    let mut extracted = PendingAssets::default();
+    let Ok(parsed) = Url::parse(url) else {
+        return extracted;
+    };
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return extracted;
+    }
+
+    let host_asset = match parsed.host() {
+        Some(Host::Ipv4(ip)) => DiscoveredAsset::new(AssetType::Ip, ip.to_string()),
+        Some(Host::Ipv6(ip)) => DiscoveredAsset::new(AssetType::Ip, ip.to_string()),
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            // 按公共后缀列表取可注册域名（example.co.uk 而不是 co.uk）
+            let parent = registrable_domain(&domain).filter(|parent| *parent != domain);
+            if let Some(parent) = parent {
+                let subdomain = DiscoveredAsset::new(AssetType::Subdomain, domain.clone());
+                let parent = DiscoveredAsset::new(AssetType::Domain, parent);
+                extracted.relations.push(DiscoveredRelation {
+                    source: subdomain.clone(),
+                    target: parent.clone(),
+                    relationship_type: RelationshipType::BelongsTo,
+                });
+                extracted.assets.push(parent);
+                subdomain
+            } else {
+                DiscoveredAsset::new(AssetType::Domain, domain)
+            }
+        }
+        None => return extracted,
+    };
+
+    let website = DiscoveredAsset::new(AssetType::Website, parsed.origin().ascii_serialization());
+    extracted.relations.push(DiscoveredRelation {
+        source: host_asset.clone(),
+        target: website.clone(),
+        relationship_type: RelationshipType::Hosts,
+    });
+    extracted.assets.push(host_asset);
+
+    let path = parsed.path();
+    let is_static = path
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .is_some_and(|(_, ext)| STATIC_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+    if path != "/" && !is_static && status != Some(404) {
+        let endpoint = DiscoveredAsset::new(
+            AssetType::Api,
+            format!("{}{}", website.value, normalize_path(path)),
+        );
+        extracted.relations.push(DiscoveredRelation {
+            source: website.clone(),
+            target: endpoint.clone(),
+            relationship_type: RelationshipType::Contains,
+        });
+        extracted.assets.push(endpoint);
+    }
+
+    if let Some(headers) = response_headers {
+        for (name, value) in headers {
+            let value = value.trim();
+            if value.is_empty() || !TECHNOLOGY_HEADERS.contains(&name.to_ascii_lowercase().as_str())
+            {
+                continue;
+            }
+            let technology: String = value.chars().take(100).collect();
+            let technology = DiscoveredAsset::new(AssetType::Technology, technology);
+            extracted.relations.push(DiscoveredRelation {
+                source: website.clone(),
+                target: technology.clone(),
+                relationship_type: RelationshipType::Uses,
+            });
+            extracted.assets.push(technology);
+        }
+    }
+
+    extracted.assets.push(website);
+    extracted
+}
+
+#[derive(Default)]
+struct ExtractorState {
+    seen: HashSet<String>,
+    pending: PendingAssets,
+}
+
+/// 被动资产提取器：观察流量、去重并缓冲，按批写入数据库
+#[derive(Default)]
+pub struct PassiveAssetExtractor {
+    state: Mutex<ExtractorState>,
+}
+
+impl PassiveAssetExtractor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 观察一次请求/响应，返回新发现（尚未见过）的资产和关系数量
+    pub fn observe(
+        &self,
+        url: &str,
+        status: Option<u16>,
+        response_headers: Option<&HashMap<String, String>>,
+    ) -> usize {
+        let extracted = extract_assets(url, status, response_headers);
+        let mut state = self.state.lock().unwrap();
+        if state.seen.len() > MAX_SEEN_KEYS {
+            state.seen.clear();
+        }
+
+        let mut added = 0;
+        for asset in extracted.assets {
+            if state.seen.insert(asset.key()) {
+                state.pending.assets.push(asset);
+                added += 1;
+            }
+        }
+        for relation in extracted.relations {
+            if state.seen.insert(relation.key()) {
+                state.pending.relations.push(relation);
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// 取出待写入的资产和关系
+    pub fn take_pending(&self) -> PendingAssets {
+        std::mem::take(&mut self.state.lock().unwrap().pending)
+    }
+
+    /// 把缓冲的资产写入数据库（已存在的资产复用，已存在的关系跳过），返回新建资产数
+    pub async fn flush(&self, db: &DatabaseService) -> usize {
+        let pending = self.take_pending();
+        if pending.is_empty() {
+            return 0;
+        }
+
+        let mut ids: HashMap<String, String> = HashMap::new();
+        let mut created = 0;
+        for asset in &pending.assets {
+            match Self::upsert_asset(db, asset).await {
+                Ok((id, is_new)) => {
+                    if is_new {
+                        created += 1;
+                    }
+                    ids.insert(asset.key(), id);
+                }
+                Err(e) => warn!("Failed to upsert passive asset {}: {}", asset.value, e),
+            }
+        }
+
+        for relation in &pending.relations {
+            // 端点资产可能在之前的批次中已写入，本批没有其 id
+            let (Some(source_id), Some(target_id)) = (
+                Self::resolve_id(db, &mut ids, &relation.source).await,
+                Self::resolve_id(db, &mut ids, &relation.target).await,
+            ) else {
+                warn!(
+                    "Skipping passive asset link {} -> {}: asset not found",
+                    relation.source.value, relation.target.value
+                );
+                continue;
+            };
+            if let Err(e) = Self::ensure_relationship(db, &source_id, &target_id, relation).await {
+                warn!(
+                    "Failed to link passive assets {} -> {}: {}",
+                    relation.source.value, relation.target.value, e
+                );
+            }
+        }
+
+        debug!(
+            "Flushed {} passive assets ({} new) and {} relationships",
+            pending.assets.len(),
+            created,
+            pending.relations.len()
+        );
+        created
+    }
+
+    /// 查找资产 id：先查本批写入的结果，再查数据库
+    async fn resolve_id(
+        db: &DatabaseService,
+        ids: &mut HashMap<String, String>,
+        asset: &DiscoveredAsset,
+    ) -> Option<String> {
+        if let Some(id) = ids.get(&asset.key()) {
+            return Some(id.clone());
+        }
+        match db
+            .find_asset_by_type_and_value(&asset.asset_type, &asset.value)
+            .await
+        {
+            Ok(Some(existing)) => {
+                ids.insert(asset.key(), existing.id.clone());
+                Some(existing.id)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                warn!("Failed to look up passive asset {}: {}", asset.value, e);
+                None
+            }
+        }
+    }
+
+    async fn upsert_asset(
+        db: &DatabaseService,
+        asset: &DiscoveredAsset,
+    ) -> anyhow::Result<(String, bool)> {
+        if let Some(existing) = db
+            .find_asset_by_type_and_value(&asset.asset_type, &asset.value)
+            .await?
+        {
+            return Ok((existing.id, false));
+        }
+        let request = CreateAssetRequest {
+            project_id: None,
+            asset_type: asset.asset_type.clone(),
+            name: asset.value.clone(),
+            value: asset.value.clone(),
+            description: Some("Discovered passively from proxy traffic".to_string()),
+            confidence: Some(1.0),
+            source: Some(PASSIVE_ASSET_SOURCE.to_string()),
+            source_scan_id: None,
+            metadata: None,
+            tags: Some(vec![
+                "discovered".to_string(),
+                PASSIVE_ASSET_SOURCE.to_string(),
+            ]),
+            risk_level: Some(RiskLevel::Unknown),
+        };
+        let created = db.create_asset(request, CREATED_BY.to_string()).await?;
+        Ok((created.id, true))
+    }
+
+    async fn ensure_relationship(
+        db: &DatabaseService,
+        source_id: &str,
+        target_id: &str,
+        relation: &DiscoveredRelation,
+    ) -> anyhow::Result<()> {
+        let (_, outgoing) = db.get_asset_relationships(source_id).await?;
+        let exists = outgoing.iter().any(|rel| {
+            rel.target_asset_id == target_id && rel.relationship_type == relation.relationship_type
+        });
+        if !exists {
+            db.create_relationship(
+                source_id.to_string(),
+                target_id.to_string(),
+                relation.relationship_type.clone(),
+                CREATED_BY.to_string(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pending: &PendingAssets, asset_type: AssetType) -> Vec<&str> {
+        let mut values: Vec<&str> = pending
+            .assets
+            .iter()
+            .filter(|a| a.asset_type == asset_type)
+            .map(|a| a.value.as_str())
+            .collect();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn test_processing_requests_yields_deduped_assets() {
+        let extractor = PassiveAssetExtractor::new();
+        let nginx = HashMap::from([("Server".to_string(), "nginx".to_string())]);
+
+        assert!(
+            extractor.observe(
+                "https://api.example.com/v1/users/123",
+                Some(200),
+                Some(&nginx)
+            ) > 0
+        );
+        extractor.observe(
+            "https://api.example.com/v1/users/456",
+            Some(200),
+            Some(&nginx),
+        );
+        extractor.observe("https://API.example.com/static/app.js", Some(200), None);
+        extractor.observe("https://api.example.com/missing", Some(404), None);
+        extractor.observe("http://10.0.0.5:8080/health", Some(200), None);
+        extractor.observe("https://example.com/", Some(200), Some(&nginx));
+        extractor.observe("not a url", None, None);
+
+        let pending = extractor.take_pending();
+        assert_eq!(
+            values(&pending, AssetType::Subdomain),
+            vec!["api.example.com"]
+        );
+        assert_eq!(values(&pending, AssetType::Domain), vec!["example.com"]);
+        assert_eq!(values(&pending, AssetType::Ip), vec!["10.0.0.5"]);
+        assert_eq!(
+            values(&pending, AssetType::Website),
+            vec![
+                "http://10.0.0.5:8080",
+                "https://api.example.com",
+                "https://example.com"
+            ]
+        );
+        assert_eq!(
+            values(&pending, AssetType::Api),
+            vec![
+                "http://10.0.0.5:8080/health",
+                "https://api.example.com/v1/users/{id}"
+            ]
+        );
+        assert_eq!(values(&pending, AssetType::Technology), vec!["nginx"]);
+
+        assert!(pending
+            .relations
+            .iter()
+            .any(|r| r.source.value == "api.example.com"
+                && r.target.value == "example.com"
+                && r.relationship_type == RelationshipType::BelongsTo));
+        let relation_keys: HashSet<String> = pending.relations.iter().map(|r| r.key()).collect();
+        assert_eq!(relation_keys.len(), pending.relations.len());
+
+        // 已见过的流量不会产生新资产
+        assert_eq!(
+            extractor.observe(
+                "https://api.example.com/v1/users/789",
+                Some(200),
+                Some(&nginx)
+            ),
+            0
+        );
+        assert!(extractor.take_pending().is_empty());
+    }
+
+    #[test]
+    fn test_parent_domain_uses_public_suffix() {
+        let pending = extract_assets("https://shop.example.co.uk/cart", Some(200), None);
+        assert_eq!(
+            values(&pending, AssetType::Subdomain),
+            vec!["shop.example.co.uk"]
+        );
+        assert_eq!(values(&pending, AssetType::Domain), vec!["example.co.uk"]);
+
+        let pending = extract_assets("https://example.com.cn/", Some(200), None);
+        assert_eq!(values(&pending, AssetType::Domain), vec!["example.com.cn"]);
+        assert!(values(&pending, AssetType::Subdomain).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_flush_links_assets_from_earlier_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = DatabaseService::new();
+        db.initialize_with_config(sentinel_db::DatabaseConfig {
+            path: Some(dir.path().join("assets.db").to_string_lossy().to_string()),
+            // 单连接，避免其他连接在迁移后仍使用旧的 schema
+            max_connections: 1,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let extractor = PassiveAssetExtractor::new();
+        extractor.observe("https://api.example.com/v1/users/1", Some(200), None);
+        // subdomain, domain, website, api
+        assert_eq!(extractor.flush(&db).await, 4);
+
+        // 第二批只有新的技术栈资产，网站资产来自上一批
+        let nginx = HashMap::from([("Server".to_string(), "nginx".to_string())]);
+        extractor.observe(
+            "https://api.example.com/v1/users/2",
+            Some(200),
+            Some(&nginx),
+        );
+        assert_eq!(extractor.flush(&db).await, 1);
+
+        let website = db
+            .find_asset_by_type_and_value(&AssetType::Website, "https://api.example.com")
+            .await
+            .unwrap()
+            .unwrap();
+        let technology = db
+            .find_asset_by_type_and_value(&AssetType::Technology, "nginx")
+            .await
+            .unwrap()
+            .unwrap();
+        let (_, outgoing) = db.get_asset_relationships(&website.id).await.unwrap();
+        assert!(outgoing
+            .iter()
+            .any(|rel| rel.target_asset_id == technology.id
+                && rel.relationship_type == RelationshipType::Uses));
+
+        let subdomain = db
+            .find_asset_by_type_and_value(&AssetType::Subdomain, "api.example.com")
+            .await
+            .unwrap()
+            .unwrap();
+        let (_, outgoing) = db.get_asset_relationships(&subdomain.id).await.unwrap();
+        assert!(outgoing
+            .iter()
+            .any(|rel| rel.relationship_type == RelationshipType::BelongsTo));
+    }
+}
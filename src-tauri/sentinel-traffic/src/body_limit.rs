@@ -0,0 +1,221 @@
+//! 代理正文大小限制
+//!
+//! 正文在大小上限以内时照常整体缓冲；超过上限后只保留已读到的前缀供插件分析，
+//! 剩余部分不再缓冲，边读边转发给对端，转发结束（或连接中断）时回报实际转发的字节数。
+
+use http_body::Frame;
+use http_body_util::BodyExt;
+use hudsucker::hyper::body::Bytes;
+use hudsucker::Body;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::oneshot;
+
+/// 按上限读取后的正文
+pub enum CappedBody {
+    /// 正文未超过上限，已完整读取
+    Complete(Bytes),
+    /// 正文超过上限：`prefix` 为已读取的部分（不小于上限），`rest` 为尚未读取的剩余正文
+    Overflow { prefix: Bytes, rest: Body },
+}
+
+impl CappedBody {
+    /// 供分析使用的正文（超过上限时截断到 `max_size`）与是否被截断
+    pub fn captured(&self, max_size: usize) -> (Vec<u8>, bool) {
+        match self {
+            CappedBody::Complete(bytes) => (bytes.to_vec(), false),
+            CappedBody::Overflow { prefix, .. } => {
+                (prefix[..max_size.min(prefix.len())].to_vec(), true)
+            }
+        }
+    }
+
+    /// 转换为转发用的正文；超过上限时 `on_end` 在转发结束后收到实际转发的总字节数
+    pub fn into_forward_body(self, on_end: Option<oneshot::Sender<u64>>) -> Body {
+        match self {
+            CappedBody::Complete(bytes) => {
+                if let Some(tx) = on_end {
+                    let _ = tx.send(bytes.len() as u64);
+                }
+                Body::from(http_body_util::Full::new(bytes))
+            }
+            CappedBody::Overflow { prefix, rest } => Body::from(
+                PrefixedBody {
+                    prefix: Some(prefix),
+                    inner: rest,
+                    forwarded: 0,
+                    on_end,
+                }
+                .boxed(),
+            ),
+        }
+    }
+}
+
+/// 读取正文，累计超过 `max_size` 字节后停止缓冲
+pub async fn read_body_capped(
+    mut body: Body,
+    max_size: usize,
+) -> std::result::Result<CappedBody, hudsucker::Error> {
+    let mut buffer = Vec::new();
+    while let Some(frame) = body.frame().await {
+        // 尾部字段（trailers）在缓冲模式下与 collect() 一样被丢弃
+        if let Ok(data) = frame?.into_data() {
+            buffer.extend_from_slice(&data);
+            if buffer.len() > max_size {
+                return Ok(CappedBody::Overflow {
+                    prefix: Bytes::from(buffer),
+                    rest: body,
+                });
+            }
+        }
+    }
+    Ok(CappedBody::Complete(Bytes::from(buffer)))
+}
+
+/// 先输出已缓冲的前缀，再直接透传剩余正文的 Body
+struct PrefixedBody {
+    prefix: Option<Bytes>,
+    inner: Body,
+    forwarded: u64,
+    on_end: Option<oneshot::Sender<u64>>,
+}
+
+impl PrefixedBody {
+    fn report(&mut self) {
+        if let Some(tx) = self.on_end.take() {
+            let _ = tx.send(self.forwarded);
+        }
+    }
+}
+
+impl http_body::Body for PrefixedBody {
+    type Data = Bytes;
+    type Error = hudsucker::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Frame<Self::Data>, Self::Error>>> {
+        if let Some(prefix) = self.prefix.take() {
+            self.forwarded += prefix.len() as u64;
+            return Poll::Ready(Some(Ok(Frame::data(prefix))));
+        }
+        let poll = Pin::new(&mut self.inner).poll_frame(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let Some(data) = frame.data_ref() {
+                    self.forwarded += data.len() as u64;
+                }
+            }
+            Poll::Ready(None) => self.report(),
+            _ => {}
+        }
+        poll
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.prefix.is_none() && self.inner.is_end_stream()
+    }
+}
+
+impl Drop for PrefixedBody {
+    fn drop(&mut self) {
+        // 对端提前断开时回报已转发的字节数
+        self.report();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    /// 按需生成固定大小分块的正文，记录被读取的分块数
+    struct ChunkedBody {
+        remaining: usize,
+        produced: Arc<AtomicUsize>,
+    }
+
+    impl http_body::Body for ChunkedBody {
+        type Data = Bytes;
+        type Error = hudsucker::Error;
+
+        fn poll_frame(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<std::result::Result<Frame<Self::Data>, Self::Error>>> {
+            if self.remaining == 0 {
+                return Poll::Ready(None);
+            }
+            self.remaining -= 1;
+            let index = self.produced.fetch_add(1, Ordering::SeqCst);
+            Poll::Ready(Some(Ok(Frame::data(Bytes::from(vec![
+                (index % 251) as u8;
+                CHUNK_SIZE
+            ])))))
+        }
+    }
+
+    fn chunked(chunks: usize) -> (Body, Arc<AtomicUsize>) {
+        let produced = Arc::new(AtomicUsize::new(0));
+        let body = ChunkedBody {
+            remaining: chunks,
+            produced: produced.clone(),
+        };
+        (Body::from(body.boxed()), produced)
+    }
+
+    #[tokio::test]
+    async fn test_small_body_is_buffered() {
+        let (body, _) = chunked(2);
+        let capped = read_body_capped(body, 1024 * 1024).await.unwrap();
+        let (captured, truncated) = capped.captured(1024 * 1024);
+        assert!(!truncated);
+        assert_eq!(captured.len(), 2 * CHUNK_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_large_body_streams_without_buffering() {
+        let chunks = 64; // 4MB
+        let max_size = 1024 * 1024;
+        let (body, produced) = chunked(chunks);
+
+        let capped = read_body_capped(body, max_size).await.unwrap();
+        // 只读到刚超过上限的那个分块为止
+        assert_eq!(produced.load(Ordering::SeqCst), max_size / CHUNK_SIZE + 1);
+        let (captured, truncated) = capped.captured(max_size);
+        assert!(truncated);
+        assert_eq!(captured.len(), max_size);
+
+        let (tx, rx) = oneshot::channel();
+        let mut forward = capped.into_forward_body(Some(tx));
+        let mut total = 0;
+        let mut max_frame = 0;
+        while let Some(frame) = forward.frame().await {
+            let data = frame.unwrap().into_data().unwrap();
+            // 前缀之后逐块透传，不再累积
+            assert_eq!(data[0], ((total / CHUNK_SIZE) % 251) as u8);
+            max_frame = max_frame.max(data.len());
+            total += data.len();
+        }
+        assert_eq!(total, chunks * CHUNK_SIZE);
+        assert!(max_frame <= max_size + CHUNK_SIZE);
+        assert_eq!(produced.load(Ordering::SeqCst), chunks);
+        assert_eq!(rx.await.unwrap(), (chunks * CHUNK_SIZE) as u64);
+    }
+
+    #[tokio::test]
+    async fn test_aborted_forward_reports_partial_size() {
+        let (body, _) = chunked(32);
+        let capped = read_body_capped(body, CHUNK_SIZE).await.unwrap();
+        let (tx, rx) = oneshot::channel();
+        let mut forward = capped.into_forward_body(Some(tx));
+        forward.frame().await.unwrap().unwrap();
+        drop(forward);
+        assert_eq!(rx.await.unwrap(), (2 * CHUNK_SIZE) as u64);
+    }
+}
@@ -11,8 +11,50 @@ use hudsucker::certificate_authority::RcgenAuthority;
 use hudsucker::rcgen::{CertificateParams, Issuer, KeyPair, SerialNumber};
 use rand::RngCore;
 use rustls::crypto::ring;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// 按主机指定的额外根 CA（如由企业 CA 签发内网主机的证书）
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct HostCaConfig {
+    /// 显示名称
+    #[serde(default)]
+    pub name: String,
+    /// CA 证书（PEM）
+    pub cert_pem: String,
+    /// CA 私钥（PEM），仅在保存时提交；保存后写入证书目录，不随配置存储或返回
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub key_pem: String,
+    /// 证书目录下的私钥文件
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+    /// 由该 CA 签发的主机模式（`*.corp.example` 或 `corp.example`）
+    #[serde(default)]
+    pub hosts: Vec<String>,
+}
+
+impl std::fmt::Debug for HostCaConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出私钥
+        f.debug_struct("HostCaConfig")
+            .field("name", &self.name)
+            .field("key_path", &self.key_path)
+            .field("hosts", &self.hosts)
+            .finish()
+    }
+}
+
+impl HostCaConfig {
+    /// 去掉私钥内容的副本，用于返回给前端
+    pub fn redacted(&self) -> Self {
+        Self {
+            key_pem: String::new(),
+            ..self.clone()
+        }
+    }
+}
 
 /// 证书管理服务
 pub struct CertificateService {
@@ -250,6 +292,88 @@ impl CertificateService {
         ))
     }
 
+    /// 获取带主机 CA 映射的 CertificateAuthority
+    ///
+    /// 匹配 `host_cas` 中主机模式的主机由对应 CA 签发，其余主机仍使用默认 Root CA。
+    pub fn get_chained_ca_with_host_cas(
+        &self,
+        host_cas: &[HostCaConfig],
+    ) -> Result<ChainedCertificateAuthority> {
+        let mut ca = self.get_chained_ca()?;
+        for host_ca in host_cas.iter().filter(|c| !c.hosts.is_empty()) {
+            let (issuer, ca_cert_der) = self.host_ca_issuer(host_ca)?;
+            tracing::info!("Using CA '{}' for hosts {:?}", host_ca.name, host_ca.hosts);
+            ca = ca.with_host_ca(host_ca.hosts.clone(), issuer, ca_cert_der);
+        }
+        Ok(ca)
+    }
+
+    /// 校验主机 CA，并把提交的私钥移到证书目录
+    ///
+    /// 带 `key_pem` 的条目写入 `host-cas/<证书指纹>.key`，配置中只保留文件路径；
+    /// 其余条目从已有的 `key_path` 读取私钥校验。证书或私钥无法解析时返回错误。
+    pub fn store_host_ca_keys(&self, host_cas: &mut [HostCaConfig]) -> Result<()> {
+        for host_ca in host_cas.iter_mut() {
+            let (_, ca_cert_der) = self.host_ca_issuer(host_ca)?;
+            if host_ca.key_pem.is_empty() {
+                continue;
+            }
+
+            let key_dir = self.ca_dir.join("host-cas");
+            fs::create_dir_all(&key_dir).map_err(|e| {
+                TrafficError::Certificate(format!("Failed to create CA key directory: {}", e))
+            })?;
+            let fingerprint = hex::encode(Sha256::digest(&ca_cert_der));
+            let key_path = key_dir.join(format!("{}.key", &fingerprint[..16]));
+            write_private_key(&key_path, &host_ca.key_pem)?;
+
+            host_ca.key_path = Some(key_path.to_string_lossy().to_string());
+            host_ca.key_pem.clear();
+        }
+        Ok(())
+    }
+
+    fn host_ca_issuer(
+        &self,
+        host_ca: &HostCaConfig,
+    ) -> Result<(Issuer<'static, KeyPair>, Vec<u8>)> {
+        let key_pem = if !host_ca.key_pem.is_empty() {
+            host_ca.key_pem.clone()
+        } else {
+            let key_path = host_ca.key_path.as_deref().ok_or_else(|| {
+                TrafficError::Certificate(format!("CA '{}' has no private key", host_ca.name))
+            })?;
+            fs::read_to_string(key_path).map_err(|e| {
+                TrafficError::Certificate(format!(
+                    "Failed to read private key of CA '{}': {}",
+                    host_ca.name, e
+                ))
+            })?
+        };
+        let key_pair = KeyPair::from_pem(&key_pem).map_err(|e| {
+            TrafficError::Certificate(format!(
+                "Failed to parse private key of CA '{}': {}",
+                host_ca.name, e
+            ))
+        })?;
+        let issuer = Issuer::from_ca_cert_pem(&host_ca.cert_pem, key_pair).map_err(|e| {
+            TrafficError::Certificate(format!(
+                "Failed to create issuer for CA '{}': {}",
+                host_ca.name, e
+            ))
+        })?;
+        let ca_cert_der = pem::parse(&host_ca.cert_pem)
+            .map_err(|e| {
+                TrafficError::Certificate(format!(
+                    "Failed to parse certificate of CA '{}': {}",
+                    host_ca.name, e
+                ))
+            })?
+            .contents()
+            .to_vec();
+        Ok((issuer, ca_cert_der))
+    }
+
     /// 读取 Root CA PEM 内容
     pub fn read_root_ca_pem(&self) -> Result<String> {
         let cert_path = self.ca_dir.join("root-ca.pem");
@@ -482,3 +606,88 @@ impl CertificateService {
         Ok(())
     }
 }
+
+/// 写入私钥文件，Unix 下仅当前用户可读写
+fn write_private_key(path: &Path, key_pem: &str) -> Result<()> {
+    fs::write(path, key_pem)
+        .map_err(|e| TrafficError::Certificate(format!("Failed to write private key: {}", e)))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600)).map_err(|e| {
+            TrafficError::Certificate(format!("Failed to restrict private key file: {}", e))
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hudsucker::rcgen::{BasicConstraints, IsCa};
+
+    fn host_ca(key_pem: String, cert_pem: String) -> HostCaConfig {
+        HostCaConfig {
+            name: "Corp".to_string(),
+            cert_pem,
+            key_pem,
+            key_path: None,
+            hosts: vec!["*.corp.internal".to_string()],
+        }
+    }
+
+    fn generate_ca() -> (String, String) {
+        let key_pair = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::default();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let cert = params.self_signed(&key_pair).unwrap();
+        (key_pair.serialize_pem(), cert.pem())
+    }
+
+    #[test]
+    fn test_host_ca_keys_are_moved_out_of_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = CertificateService::new(dir.path().to_path_buf());
+        let (key_pem, cert_pem) = generate_ca();
+        let mut host_cas = vec![host_ca(key_pem.clone(), cert_pem)];
+
+        service.store_host_ca_keys(&mut host_cas).unwrap();
+
+        let stored = &host_cas[0];
+        assert!(stored.key_pem.is_empty());
+        let key_path = stored.key_path.clone().unwrap();
+        assert!(Path::new(&key_path).starts_with(dir.path()));
+        assert_eq!(fs::read_to_string(&key_path).unwrap(), key_pem);
+        let json = serde_json::to_string(&host_cas).unwrap();
+        assert!(!json.contains("PRIVATE KEY"));
+
+        // 再次保存（前端回传不含私钥的配置）仍可通过文件校验
+        service.store_host_ca_keys(&mut host_cas).unwrap();
+        assert_eq!(host_cas[0].key_path.as_deref(), Some(key_path.as_str()));
+    }
+
+    #[test]
+    fn test_invalid_host_ca_rejected_at_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = CertificateService::new(dir.path().to_path_buf());
+        let (key_pem, cert_pem) = generate_ca();
+
+        let mut bad_key = vec![host_ca("not a key".to_string(), cert_pem.clone())];
+        assert!(service.store_host_ca_keys(&mut bad_key).is_err());
+        let mut bad_cert = vec![host_ca(key_pem, "not a cert".to_string())];
+        assert!(service.store_host_ca_keys(&mut bad_cert).is_err());
+        let mut no_key = vec![host_ca(String::new(), cert_pem)];
+        assert!(service.store_host_ca_keys(&mut no_key).is_err());
+        assert!(!dir.path().join("host-cas").exists());
+    }
+
+    #[test]
+    fn test_redacted_host_ca_omits_key() {
+        let (key_pem, cert_pem) = generate_ca();
+        let redacted = host_ca(key_pem, cert_pem).redacted();
+        assert!(redacted.key_pem.is_empty());
+        assert!(!serde_json::to_string(&redacted)
+            .unwrap()
+            .contains("PRIVATE KEY"));
+    }
+}
@@ -29,9 +29,8 @@ const NOT_BEFORE_OFFSET: i64 = 60;
 /// 解决 Proxifier 虚拟 IP 模式下，CONNECT 请求使用虚拟 IP
 /// 但客户端仍然期望原始域名证书的问题。
 struct SniCertResolver {
-    issuer: Arc<Issuer<'static, KeyPair>>,
-    private_key: PrivateKeyDer<'static>,
-    ca_cert: CertificateDer<'static>,
+    default_ca: Arc<CaSigner>,
+    host_cas: Arc<Vec<HostCa>>,
     cache: SyncCache<String, Arc<CertifiedKey>>,
     provider: Arc<CryptoProvider>,
     /// 来自 CONNECT 请求的 authority（可能是虚拟 IP）
@@ -49,16 +48,14 @@ impl Debug for SniCertResolver {
 
 impl SniCertResolver {
     fn new(
-        issuer: Arc<Issuer<'static, KeyPair>>,
-        private_key: PrivateKeyDer<'static>,
-        ca_cert: CertificateDer<'static>,
+        default_ca: Arc<CaSigner>,
+        host_cas: Arc<Vec<HostCa>>,
         provider: Arc<CryptoProvider>,
         fallback_authority: String,
     ) -> Self {
         Self {
-            issuer,
-            private_key,
-            ca_cert,
+            default_ca,
+            host_cas,
             cache: SyncCache::builder()
                 .max_capacity(1000)
                 .time_to_live(std::time::Duration::from_secs(CACHE_TTL))
@@ -68,6 +65,15 @@ impl SniCertResolver {
         }
     }
 
+    /// 选择签发该主机证书的 CA：按配置顺序取第一个匹配的映射，未匹配时使用默认 CA
+    fn signer_for(&self, host: &str) -> &CaSigner {
+        self.host_cas
+            .iter()
+            .find(|mapping| mapping.patterns.iter().any(|p| host_matches(p, host)))
+            .map(|mapping| mapping.signer.as_ref())
+            .unwrap_or(&self.default_ca)
+    }
+
     /// 为指定主机生成证书
     fn gen_certified_key(&self, host: &str) -> Arc<CertifiedKey> {
         // 检查缓存
@@ -131,19 +137,20 @@ impl SniCertResolver {
             hudsucker::rcgen::ExtendedKeyUsagePurpose::ClientAuth,
         ];
 
+        let signer = self.signer_for(host);
         let leaf_cert: CertificateDer<'static> = params
-            .signed_by(self.issuer.key(), self.issuer.as_ref())
+            .signed_by(signer.issuer.key(), &signer.issuer)
             .expect("Failed to sign certificate")
             .into();
 
         // 构建完整证书链
-        let cert_chain = vec![leaf_cert, self.ca_cert.clone()];
+        let cert_chain = vec![leaf_cert, signer.ca_cert.clone()];
 
         // 创建签名密钥
         let signing_key = self
             .provider
             .key_provider
-            .load_private_key(self.private_key.clone_key())
+            .load_private_key(signer.private_key.clone_key())
             .expect("Failed to load private key");
 
         let certified_key = Arc::new(CertifiedKey::new(cert_chain, signing_key));
@@ -196,15 +203,57 @@ impl ResolvesServerCert for SniCertResolver {
     }
 }
 
+/// 签发叶子证书的 CA（叶子证书复用 CA 的密钥）
+struct CaSigner {
+    issuer: Issuer<'static, KeyPair>,
+    private_key: PrivateKeyDer<'static>,
+    ca_cert: CertificateDer<'static>,
+}
+
+impl CaSigner {
+    fn new(issuer: Issuer<'static, KeyPair>, ca_cert_der: Vec<u8>) -> Self {
+        let private_key =
+            PrivateKeyDer::from(PrivatePkcs8KeyDer::from(issuer.key().serialize_der()));
+        Self {
+            issuer,
+            private_key,
+            ca_cert: CertificateDer::from(ca_cert_der),
+        }
+    }
+}
+
+/// 主机模式到签发 CA 的映射
+#[derive(Clone)]
+struct HostCa {
+    patterns: Vec<String>,
+    signer: Arc<CaSigner>,
+}
+
+/// 主机匹配：`*` 匹配所有，`*.corp.example` 只匹配子域名，其余匹配域名本身及其子域名
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().trim_end_matches('.').to_lowercase();
+    let host = host.trim().trim_end_matches('.').to_lowercase();
+    if pattern.is_empty() {
+        return false;
+    }
+    if pattern == "*" {
+        return true;
+    }
+    match pattern.strip_prefix("*.") {
+        Some(base) => host.ends_with(&format!(".{}", base)),
+        None => host == pattern || host.ends_with(&format!(".{}", pattern)),
+    }
+}
+
 /// 支持完整证书链和 SNI 的证书颁发机构
 ///
 /// 特性：
 /// 1. 发送完整证书链（叶子证书 + CA 证书）
 /// 2. 根据 TLS SNI 动态生成证书（解决 Proxifier 虚拟 IP 问题）
+/// 3. 可按主机模式指定其他根 CA 签发（如内网主机使用企业 CA），未匹配时使用默认 CA
 pub struct ChainedCertificateAuthority {
-    issuer: Arc<Issuer<'static, KeyPair>>,
-    private_key: PrivateKeyDer<'static>,
-    ca_cert: CertificateDer<'static>,
+    default_ca: Arc<CaSigner>,
+    host_cas: Arc<Vec<HostCa>>,
     provider: Arc<CryptoProvider>,
 }
 
@@ -216,28 +265,43 @@ impl ChainedCertificateAuthority {
         _cache_size: u64,
         provider: CryptoProvider,
     ) -> Self {
-        let private_key =
-            PrivateKeyDer::from(PrivatePkcs8KeyDer::from(issuer.key().serialize_der()));
-
         Self {
-            issuer: Arc::new(issuer),
-            private_key,
-            ca_cert: CertificateDer::from(ca_cert_der),
+            default_ca: Arc::new(CaSigner::new(issuer, ca_cert_der)),
+            host_cas: Arc::new(Vec::new()),
             provider: Arc::new(provider),
         }
     }
+
+    /// 由指定 CA 为匹配 `patterns` 的主机签发证书
+    ///
+    /// 按添加顺序匹配，先添加的映射优先。
+    pub fn with_host_ca(
+        mut self,
+        patterns: Vec<String>,
+        issuer: Issuer<'static, KeyPair>,
+        ca_cert_der: Vec<u8>,
+    ) -> Self {
+        Arc::make_mut(&mut self.host_cas).push(HostCa {
+            patterns,
+            signer: Arc::new(CaSigner::new(issuer, ca_cert_der)),
+        });
+        self
+    }
+
+    fn resolver(&self, fallback_authority: String) -> SniCertResolver {
+        SniCertResolver::new(
+            self.default_ca.clone(),
+            self.host_cas.clone(),
+            self.provider.clone(),
+            fallback_authority,
+        )
+    }
 }
 
 impl CertificateAuthority for ChainedCertificateAuthority {
     async fn gen_server_config(&self, authority: &Authority) -> Arc<ServerConfig> {
         // 创建 SNI 证书解析器
-        let cert_resolver = SniCertResolver::new(
-            self.issuer.clone(),
-            self.private_key.clone_key(),
-            self.ca_cert.clone(),
-            self.provider.clone(),
-            authority.to_string(),
-        );
+        let cert_resolver = self.resolver(authority.to_string());
 
         // 创建带有 cert_resolver 的 ServerConfig
         let mut server_cfg = ServerConfig::builder_with_provider(Arc::clone(&self.provider))
@@ -255,3 +319,76 @@ impl CertificateAuthority for ChainedCertificateAuthority {
         Arc::new(server_cfg)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hudsucker::rcgen::{BasicConstraints, IsCa};
+    use rustls::crypto::ring;
+
+    fn root_ca(common_name: &str) -> (Issuer<'static, KeyPair>, Vec<u8>) {
+        let key_pair = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::default();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params
+            .distinguished_name
+            .push(DnType::CommonName, common_name);
+        let cert = params.self_signed(&key_pair).unwrap();
+        let der = cert.der().to_vec();
+        (
+            Issuer::from_ca_cert_pem(&cert.pem(), key_pair).unwrap(),
+            der,
+        )
+    }
+
+    fn issuer_cn(der: &[u8]) -> String {
+        let (_, cert) = x509_parser::parse_x509_certificate(der).unwrap();
+        let cn = cert
+            .issuer()
+            .iter_common_name()
+            .next()
+            .and_then(|cn| cn.as_str().ok())
+            .unwrap()
+            .to_string();
+        cn
+    }
+
+    #[test]
+    fn test_mapped_host_signed_by_mapped_ca() {
+        let (default_issuer, default_der) = root_ca("Sentinel Default CA");
+        let (corp_issuer, corp_der) = root_ca("Corp Internal CA");
+        let ca = ChainedCertificateAuthority::new(
+            default_issuer,
+            default_der.clone(),
+            1000,
+            ring::default_provider(),
+        )
+        .with_host_ca(
+            vec!["*.corp.internal".to_string()],
+            corp_issuer,
+            corp_der.clone(),
+        );
+        let resolver = ca.resolver("10.0.0.1:443".to_string());
+
+        let mapped = resolver.gen_certified_key("App.Corp.Internal");
+        assert_eq!(issuer_cn(&mapped.cert[0]), "Corp Internal CA");
+        assert_eq!(mapped.cert[1].as_ref(), corp_der.as_slice());
+
+        for host in ["corp.internal", "example.com"] {
+            let key = resolver.gen_certified_key(host);
+            assert_eq!(issuer_cn(&key.cert[0]), "Sentinel Default CA");
+            assert_eq!(key.cert[1].as_ref(), default_der.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_host_matches() {
+        assert!(host_matches("*.corp.internal", "a.b.corp.internal"));
+        assert!(!host_matches("*.corp.internal", "corp.internal"));
+        assert!(host_matches("corp.internal", "corp.internal."));
+        assert!(host_matches("corp.internal", "git.corp.internal"));
+        assert!(!host_matches("corp.internal", "evilcorp.internal"));
+        assert!(host_matches("*", "anything"));
+        assert!(!host_matches("", "anything"));
+    }
+}
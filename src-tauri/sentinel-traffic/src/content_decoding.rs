@@ -0,0 +1,200 @@
+//! 历史记录正文解码
+//!
+//! 历史记录中的正文按原始字节保存（二进制内容以 `[BASE64]` 前缀存储），
+//! 展示时按 `Content-Encoding` 透明解压（gzip / deflate / br / zstd），原始数据保留用于重放。
+//! 解压结果超过上限时视为解压炸弹并放弃解码。
+
+use base64::{engine::general_purpose, Engine as _};
+use std::collections::HashMap;
+use std::io::Read;
+
+/// 展示用解压后正文的大小上限（20MB）
+pub const MAX_DECODED_BODY_SIZE: usize = 20 * 1024 * 1024;
+
+/// 二进制正文在历史记录中的前缀
+const BASE64_PREFIX: &str = "[BASE64]";
+
+/// 按 `Content-Encoding` 解压正文，多个编码按逆序依次解开
+pub fn decode_content(
+    body: &[u8],
+    content_encoding: &str,
+    limit: usize,
+) -> Result<Vec<u8>, String> {
+    let mut current = body.to_vec();
+    for encoding in content_encoding.split(',').rev() {
+        let encoding = encoding.trim().to_ascii_lowercase();
+        current = match encoding.as_str() {
+            "" | "identity" => current,
+            "gzip" | "x-gzip" => read_limited(flate2::read::GzDecoder::new(&current[..]), limit)?,
+            "deflate" => {
+                // 规范要求 zlib 包装，但不少服务器直接发送裸 deflate 流
+                read_limited(flate2::read::ZlibDecoder::new(&current[..]), limit).or_else(|_| {
+                    read_limited(flate2::read::DeflateDecoder::new(&current[..]), limit)
+                })?
+            }
+            "br" => read_limited(brotli::Decompressor::new(&current[..], 4096), limit)?,
+            "zstd" => {
+                let decoder = zstd::stream::read::Decoder::new(&current[..])
+                    .map_err(|e| format!("Failed to init zstd decoder: {}", e))?;
+                read_limited(decoder, limit)?
+            }
+            other => return Err(format!("Unsupported content encoding: {}", other)),
+        };
+    }
+    Ok(current)
+}
+
+/// 读取解压流，超过上限即报错
+fn read_limited<R: Read>(reader: R, limit: usize) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    reader
+        .take(limit as u64 + 1)
+        .read_to_end(&mut out)
+        .map_err(|e| format!("Failed to decompress body: {}", e))?;
+    if out.len() > limit {
+        return Err(format!(
+            "Decompressed body exceeds {} bytes, refusing to decode",
+            limit
+        ));
+    }
+    Ok(out)
+}
+
+/// 还原历史记录中保存的正文字节
+pub fn stored_body_bytes(stored: &str) -> Vec<u8> {
+    match stored.strip_prefix(BASE64_PREFIX) {
+        Some(encoded) => general_purpose::STANDARD
+            .decode(encoded)
+            .unwrap_or_else(|_| stored.as_bytes().to_vec()),
+        None => stored.as_bytes().to_vec(),
+    }
+}
+
+/// 把正文字节转换为历史记录格式（非 UTF-8 内容使用 `[BASE64]` 前缀）
+pub fn body_to_stored(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => s.to_string(),
+        Err(_) => format!(
+            "{}{}",
+            BASE64_PREFIX,
+            general_purpose::STANDARD.encode(bytes)
+        ),
+    }
+}
+
+/// 从 JSON 形式的头部中查找 `Content-Encoding`
+pub fn content_encoding_from_headers(headers_json: Option<&str>) -> Option<String> {
+    let headers: HashMap<String, String> = serde_json::from_str(headers_json?).ok()?;
+    headers
+        .into_iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-encoding"))
+        .map(|(_, value)| value)
+}
+
+/// 生成展示用正文：按头部声明的编码解压，无需解压或解压失败时原样返回
+pub fn decode_body_for_display(
+    stored: Option<&str>,
+    headers_json: Option<&str>,
+    limit: usize,
+) -> Option<String> {
+    let stored = stored?;
+    let Some(encoding) = content_encoding_from_headers(headers_json) else {
+        return Some(stored.to_string());
+    };
+
+    match decode_content(&stored_body_bytes(stored), &encoding, limit) {
+        Ok(decoded) => Some(body_to_stored(&decoded)),
+        Err(e) => {
+            tracing::debug!("Keeping encoded body for display: {}", e);
+            Some(stored.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const PLAINTEXT: &str = "<html><body>hello sentinel, hello proxy history</body></html>";
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn raw_deflate(data: &[u8]) -> Vec<u8> {
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn brotli(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        {
+            let mut encoder = brotli::CompressorWriter::new(&mut out, 4096, 5, 22);
+            encoder.write_all(data).unwrap();
+        }
+        out
+    }
+
+    fn zstd(data: &[u8]) -> Vec<u8> {
+        zstd::stream::encode_all(data, 3).unwrap()
+    }
+
+    fn headers(encoding: &str) -> String {
+        serde_json::json!({ "Content-Type": "text/html", "Content-Encoding": encoding }).to_string()
+    }
+
+    #[test]
+    fn test_each_encoding_round_trips() {
+        let cases = [
+            ("gzip", gzip(PLAINTEXT.as_bytes())),
+            ("deflate", zlib(PLAINTEXT.as_bytes())),
+            ("deflate", raw_deflate(PLAINTEXT.as_bytes())),
+            ("br", brotli(PLAINTEXT.as_bytes())),
+            ("zstd", zstd(PLAINTEXT.as_bytes())),
+        ];
+
+        for (encoding, compressed) in cases {
+            let stored = body_to_stored(&compressed);
+            let headers = headers(encoding);
+            let body =
+                decode_body_for_display(Some(&stored), Some(&headers), MAX_DECODED_BODY_SIZE);
+            assert_eq!(body.as_deref(), Some(PLAINTEXT), "encoding {}", encoding);
+        }
+    }
+
+    #[test]
+    fn test_stacked_encodings_and_identity() {
+        let compressed = gzip(&zstd(PLAINTEXT.as_bytes()));
+        let decoded = decode_content(&compressed, "zstd, gzip", MAX_DECODED_BODY_SIZE).unwrap();
+        assert_eq!(decoded, PLAINTEXT.as_bytes());
+
+        let body = decode_body_for_display(Some(PLAINTEXT), Some(&headers("identity")), 1024);
+        assert_eq!(body.as_deref(), Some(PLAINTEXT));
+        let body = decode_body_for_display(Some(PLAINTEXT), None, 1024);
+        assert_eq!(body.as_deref(), Some(PLAINTEXT));
+    }
+
+    #[test]
+    fn test_decompression_bomb_is_rejected() {
+        let bomb = gzip(&vec![b'A'; 1024 * 1024]);
+        let err = decode_content(&bomb, "gzip", 64 * 1024).unwrap_err();
+        assert!(err.contains("exceeds"));
+
+        // 超限时展示原始（编码后的）正文
+        let stored = body_to_stored(&bomb);
+        let body = decode_body_for_display(Some(&stored), Some(&headers("gzip")), 64 * 1024);
+        assert_eq!(body.as_deref(), Some(stored.as_str()));
+    }
+}
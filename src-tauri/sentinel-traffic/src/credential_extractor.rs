@@ -0,0 +1,515 @@
+//! Credential extraction from captured traffic
+//!
+//! Scans the HTTP messages reassembled from TCP streams (see
+//! [`http_exchanges`]) for Basic-Auth, Bearer/JWT tokens and sensitive
+//! cookies, and runs configurable regex patterns over those messages and over
+//! the transport payloads of non-HTTP packets to catch API keys. Findings
+//! carry packet and stream references; secrets are only logged masked and at
+//! debug level.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tracing::{debug, info};
+
+use crate::packet_capture::{CapturedPacket, FileExtractor};
+use crate::protocol_decoder::{http_exchanges, transport_payload, HttpMessage, HttpStartLine};
+
+/// Kind of leaked credential
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialKind {
+    BasicAuth,
+    BearerToken,
+    Jwt,
+    Cookie,
+    ApiKey,
+}
+
+/// Regex pattern applied to packet payloads
+///
+/// If the regex has a capture group, group 1 is taken as the secret.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialPattern {
+    pub name: String,
+    pub kind: CredentialKind,
+    pub regex: String,
+}
+
+impl CredentialPattern {
+    fn new(name: &str, kind: CredentialKind, regex: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            regex: regex.to_string(),
+        }
+    }
+}
+
+/// Detection settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialExtractorConfig {
+    /// Cookie names (case-insensitive substrings) treated as sensitive
+    #[serde(default = "default_sensitive_cookies")]
+    pub sensitive_cookies: Vec<String>,
+    /// Payload patterns for API keys and tokens
+    #[serde(default = "default_patterns")]
+    pub patterns: Vec<CredentialPattern>,
+}
+
+impl Default for CredentialExtractorConfig {
+    fn default() -> Self {
+        Self {
+            sensitive_cookies: default_sensitive_cookies(),
+            patterns: default_patterns(),
+        }
+    }
+}
+
+fn default_sensitive_cookies() -> Vec<String> {
+    [
+        "session", "sessid", "sid", "token", "auth", "jwt", "remember",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+fn default_patterns() -> Vec<CredentialPattern> {
+    vec![
+        CredentialPattern::new(
+            "JSON Web Token",
+            CredentialKind::Jwt,
+            r"eyJ[A-Za-z0-9_-]{5,}\.eyJ[A-Za-z0-9_-]{5,}\.[A-Za-z0-9_-]+",
+        ),
+        CredentialPattern::new(
+            "AWS Access Key",
+            CredentialKind::ApiKey,
+            r"\bAKIA[0-9A-Z]{16}\b",
+        ),
+        CredentialPattern::new(
+            "GitHub Token",
+            CredentialKind::ApiKey,
+            r"\bgh[pousr]_[A-Za-z0-9]{36}\b",
+        ),
+        CredentialPattern::new(
+            "Slack Token",
+            CredentialKind::ApiKey,
+            r"\bxox[abprs]-[A-Za-z0-9-]{10,}",
+        ),
+        CredentialPattern::new(
+            "Google API Key",
+            CredentialKind::ApiKey,
+            r"\bAIza[0-9A-Za-z_-]{35}\b",
+        ),
+        CredentialPattern::new(
+            "Generic API Key",
+            CredentialKind::ApiKey,
+            r#"(?i)\b(?:api[_-]?key|access[_-]?token|secret[_-]?key)["']?\s*[:=]\s*["']?([A-Za-z0-9_\-]{16,})"#,
+        ),
+    ]
+}
+
+/// One detected credential
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialFinding {
+    pub kind: CredentialKind,
+    /// Pattern or header that matched
+    pub source: String,
+    pub secret: String,
+    /// Masked secret, safe for display and logs
+    pub masked: String,
+    /// Decoded username for Basic-Auth
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    pub packet_id: u64,
+    pub src: String,
+    pub dst: String,
+    pub stream_key: String,
+}
+
+struct CompiledPattern {
+    name: String,
+    kind: CredentialKind,
+    regex: Regex,
+}
+
+/// Credential analyzer over captured packets
+pub struct CredentialExtractor {
+    sensitive_cookies: Vec<String>,
+    patterns: Vec<CompiledPattern>,
+}
+
+impl CredentialExtractor {
+    pub fn new(config: &CredentialExtractorConfig) -> Result<Self, String> {
+        let patterns = config
+            .patterns
+            .iter()
+            .map(|p| {
+                Regex::new(&p.regex)
+                    .map(|regex| CompiledPattern {
+                        name: p.name.clone(),
+                        kind: p.kind,
+                        regex,
+                    })
+                    .map_err(|e| format!("Invalid credential pattern '{}': {}", p.name, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            sensitive_cookies: config
+                .sensitive_cookies
+                .iter()
+                .map(|c| c.to_lowercase())
+                .collect(),
+            patterns,
+        })
+    }
+
+    /// Scan packets and return findings in packet order
+    pub fn extract(&self, packets: &[CapturedPacket]) -> Vec<CredentialFinding> {
+        let mut found = Vec::new();
+        let mut http_packets = HashSet::new();
+
+        for exchange in http_exchanges(packets) {
+            let messages = [
+                (&exchange.request, &exchange.client, &exchange.server),
+                (&exchange.response, &exchange.server, &exchange.client),
+            ];
+            for (message, src, dst) in messages {
+                let Some(message) = message else {
+                    continue;
+                };
+                http_packets.extend(message.packet_ids.iter().copied());
+                let origin = Origin {
+                    packet_id: message.packet_ids.first().copied().unwrap_or_default(),
+                    src,
+                    dst,
+                };
+                found.extend(self.scan_http_headers(&origin, message));
+                found.extend(self.scan_text(&origin, &message_text(message)));
+            }
+        }
+
+        // Packets outside any HTTP message: patterns over the raw transport payload
+        for pkt in packets {
+            if http_packets.contains(&pkt.id) {
+                continue;
+            }
+            if let Some(payload) = transport_payload(pkt) {
+                let origin = Origin {
+                    packet_id: pkt.id,
+                    src: &pkt.src,
+                    dst: &pkt.dst,
+                };
+                found.extend(self.scan_text(&origin, &String::from_utf8_lossy(&payload)));
+            }
+        }
+        found.sort_by_key(|f| f.packet_id);
+
+        let mut findings = Vec::new();
+        let mut seen = HashSet::new();
+        for finding in found {
+            if seen.insert((finding.packet_id, finding.secret.clone())) {
+                debug!(
+                    "Credential {:?} ({}) in packet {}: {}",
+                    finding.kind, finding.source, finding.packet_id, finding.masked
+                );
+                findings.push(finding);
+            }
+        }
+
+        info!(
+            "Found {} credentials in {} packets",
+            findings.len(),
+            packets.len()
+        );
+        findings
+    }
+
+    fn scan_http_headers(&self, origin: &Origin, message: &HttpMessage) -> Vec<CredentialFinding> {
+        let mut found = Vec::new();
+        for (name, value) in &message.headers {
+            let value = value.trim();
+            match name.to_ascii_lowercase().as_str() {
+                "authorization" | "proxy-authorization" => {
+                    if let Some(finding) = self.parse_authorization(origin, name, value) {
+                        found.push(finding);
+                    }
+                }
+                "cookie" => {
+                    for pair in value.split(';') {
+                        if let Some(finding) = self.parse_cookie(origin, name, pair) {
+                            found.push(finding);
+                        }
+                    }
+                }
+                "set-cookie" => {
+                    let pair = value.split(';').next().unwrap_or_default();
+                    if let Some(finding) = self.parse_cookie(origin, name, pair) {
+                        found.push(finding);
+                    }
+                }
+                _ => {}
+            }
+        }
+        found
+    }
+
+    fn parse_authorization(
+        &self,
+        origin: &Origin,
+        header: &str,
+        value: &str,
+    ) -> Option<CredentialFinding> {
+        let (scheme, credentials) = value.split_once(' ')?;
+        let credentials = credentials.trim();
+        if credentials.is_empty() {
+            return None;
+        }
+
+        if scheme.eq_ignore_ascii_case("basic") {
+            let username =
+                base64::Engine::decode(&base64::engine::general_purpose::STANDARD, credentials)
+                    .ok()
+                    .and_then(|decoded| String::from_utf8(decoded).ok())
+                    .and_then(|decoded| decoded.split_once(':').map(|(user, _)| user.to_string()));
+            let mut finding = finding(origin, CredentialKind::BasicAuth, header, credentials);
+            finding.username = username;
+            Some(finding)
+        } else if scheme.eq_ignore_ascii_case("bearer") {
+            let kind = if is_jwt(credentials) {
+                CredentialKind::Jwt
+            } else {
+                CredentialKind::BearerToken
+            };
+            Some(finding(origin, kind, header, credentials))
+        } else {
+            None
+        }
+    }
+
+    fn parse_cookie(&self, origin: &Origin, header: &str, pair: &str) -> Option<CredentialFinding> {
+        let (name, value) = pair.trim().split_once('=')?;
+        let lower = name.trim().to_lowercase();
+        if value.is_empty() || !self.sensitive_cookies.iter().any(|c| lower.contains(c)) {
+            return None;
+        }
+        Some(finding(
+            origin,
+            CredentialKind::Cookie,
+            &format!("{} {}", header, name.trim()),
+            value.trim(),
+        ))
+    }
+
+    fn scan_text(&self, origin: &Origin, text: &str) -> Vec<CredentialFinding> {
+        let mut found = Vec::new();
+        for pattern in &self.patterns {
+            for caps in pattern.regex.captures_iter(text) {
+                let Some(secret) = caps.get(1).or_else(|| caps.get(0)) else {
+                    continue;
+                };
+                found.push(finding(
+                    origin,
+                    pattern.kind,
+                    &pattern.name,
+                    secret.as_str(),
+                ));
+            }
+        }
+        found
+    }
+}
+
+/// Where a finding was seen: the packet a message starts in and its direction
+struct Origin<'a> {
+    packet_id: u64,
+    src: &'a str,
+    dst: &'a str,
+}
+
+/// Message rendered back to text (decoded body) for pattern matching
+fn message_text(message: &HttpMessage) -> String {
+    let mut text = match &message.start_line {
+        HttpStartLine::Request {
+            method,
+            target,
+            version,
+        } => format!("{} {} {}\r\n", method, target, version),
+        HttpStartLine::Response {
+            version,
+            status,
+            reason,
+        } => format!("{} {} {}\r\n", version, status, reason),
+    };
+    for (name, value) in &message.headers {
+        text.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    text.push_str("\r\n");
+    text.push_str(&String::from_utf8_lossy(&message.body));
+    text
+}
+
+fn finding(origin: &Origin, kind: CredentialKind, source: &str, secret: &str) -> CredentialFinding {
+    CredentialFinding {
+        kind,
+        source: source.to_string(),
+        secret: secret.to_string(),
+        masked: mask_secret(secret),
+        username: None,
+        packet_id: origin.packet_id,
+        src: origin.src.to_string(),
+        dst: origin.dst.to_string(),
+        stream_key: FileExtractor::stream_key(origin.src, origin.dst),
+    }
+}
+
+fn is_jwt(token: &str) -> bool {
+    let parts: Vec<&str> = token.split('.').collect();
+    parts.len() == 3 && parts[0].starts_with("eyJ") && parts[1].starts_with("eyJ")
+}
+
+/// Keep a short prefix and suffix, hide the rest
+fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 8 {
+        return "****".to_string();
+    }
+    let prefix: String = chars[..4].iter().collect();
+    let suffix: String = chars[chars.len() - 2..].iter().collect();
+    format!("{}****{}", prefix, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet_capture::PcapFileOps;
+    use pcap_file::pcap::{PcapPacket, PcapWriter};
+    use std::time::Duration;
+
+    /// Ethernet + IPv4 + TCP frame carrying `payload` from 10.0.0.1:51000 to 10.0.0.2:80
+    fn tcp_frame(payload: &[u8]) -> Vec<u8> {
+        tcp_segment(1, payload)
+    }
+
+    /// Like [`tcp_frame`], at sequence number `seq`
+    fn tcp_segment(seq: u32, payload: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x02, 0x02, 0, 0, 0, 0, 0x01, 0x08, 0x00]);
+
+        let total_len = (20 + 20 + payload.len()) as u16;
+        frame.extend_from_slice(&[0x45, 0x00]);
+        frame.extend_from_slice(&total_len.to_be_bytes());
+        frame.extend_from_slice(&[0x00, 0x01, 0x40, 0x00, 64, 6, 0x00, 0x00]);
+        frame.extend_from_slice(&[10, 0, 0, 1, 10, 0, 0, 2]);
+
+        frame.extend_from_slice(&51000u16.to_be_bytes());
+        frame.extend_from_slice(&80u16.to_be_bytes());
+        frame.extend_from_slice(&seq.to_be_bytes());
+        frame.extend_from_slice(&1u32.to_be_bytes());
+        frame.extend_from_slice(&[0x50, 0x18, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00]);
+
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    fn write_test_pcap(frames: &[Vec<u8>]) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut writer = PcapWriter::new(file.reopen().unwrap()).unwrap();
+        for (i, frame) in frames.iter().enumerate() {
+            writer
+                .write_packet(&PcapPacket::new(
+                    Duration::from_secs(1_700_000_000 + i as u64),
+                    frame.len() as u32,
+                    frame,
+                ))
+                .unwrap();
+        }
+        drop(writer);
+        file
+    }
+
+    #[test]
+    fn test_detects_basic_auth_in_pcap() {
+        let request = b"GET /admin HTTP/1.1\r\nHost: 10.0.0.2\r\nAuthorization: Basic YWRtaW46czNjcmV0\r\nCookie: theme=dark; PHPSESSID=abc123def456\r\n\r\n";
+        let pcap = write_test_pcap(&[tcp_frame(request)]);
+        let packets = PcapFileOps::read_pcap_file(pcap.path()).unwrap();
+        assert_eq!(packets.len(), 1);
+
+        let extractor = CredentialExtractor::new(&CredentialExtractorConfig::default()).unwrap();
+        let findings = extractor.extract(&packets);
+
+        let basic = findings
+            .iter()
+            .find(|f| f.kind == CredentialKind::BasicAuth)
+            .expect("Authorization header should be detected");
+        assert_eq!(basic.secret, "YWRtaW46czNjcmV0");
+        assert_eq!(basic.username.as_deref(), Some("admin"));
+        assert_eq!(basic.packet_id, packets[0].id);
+        assert_eq!(basic.src, "10.0.0.1:51000");
+        assert!(!basic.masked.contains("czNjcmV0"));
+
+        let cookie = findings
+            .iter()
+            .find(|f| f.kind == CredentialKind::Cookie)
+            .unwrap();
+        assert_eq!(cookie.secret, "abc123def456");
+        assert!(!findings.iter().any(|f| f.secret == "dark"));
+    }
+
+    #[test]
+    fn test_detects_header_split_across_segments() {
+        let request = b"GET /admin HTTP/1.1\r\nHost: 10.0.0.2\r\nAuthorization: Bearer abcdef0123456789\r\n\r\n";
+        let (first, second) = request.split_at(50);
+        // Out of order on the wire; reassembly puts them back together
+        let pcap = write_test_pcap(&[
+            tcp_segment(1 + first.len() as u32, second),
+            tcp_segment(1, first),
+        ]);
+        let packets = PcapFileOps::read_pcap_file(pcap.path()).unwrap();
+
+        let extractor = CredentialExtractor::new(&CredentialExtractorConfig::default()).unwrap();
+        let findings = extractor.extract(&packets);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, CredentialKind::BearerToken);
+        assert_eq!(findings[0].secret, "abcdef0123456789");
+        assert_eq!(findings[0].packet_id, packets[1].id);
+    }
+
+    #[test]
+    fn test_configurable_patterns() {
+        let payload = b"POST /v1 HTTP/1.1\r\nHost: api\r\nContent-Length: 61\r\n\r\n{\"api_key\": \"sk_live_0123456789abcdef\", \"ref\": \"ACME-42-XYZ\"}";
+        let pcap = write_test_pcap(&[tcp_frame(payload)]);
+        let packets = PcapFileOps::read_pcap_file(pcap.path()).unwrap();
+
+        let defaults = CredentialExtractor::new(&CredentialExtractorConfig::default()).unwrap();
+        let findings = defaults.extract(&packets);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, CredentialKind::ApiKey);
+        assert_eq!(findings[0].secret, "sk_live_0123456789abcdef");
+
+        let custom = CredentialExtractor::new(&CredentialExtractorConfig {
+            sensitive_cookies: vec![],
+            patterns: vec![CredentialPattern::new(
+                "ACME ref",
+                CredentialKind::ApiKey,
+                r"ACME-\d+-[A-Z]+",
+            )],
+        })
+        .unwrap();
+        let findings = custom.extract(&packets);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].source, "ACME ref");
+
+        let invalid = CredentialExtractor::new(&CredentialExtractorConfig {
+            sensitive_cookies: vec![],
+            patterns: vec![CredentialPattern::new(
+                "broken",
+                CredentialKind::ApiKey,
+                "(",
+            )],
+        });
+        assert!(invalid.is_err());
+    }
+}
@@ -1,9 +1,11 @@
 //! Finding 去重与持久化模块
 
 use crate::Finding;
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// 计算 Finding 签名（用于去重）
@@ -16,6 +18,172 @@ pub fn compute_finding_signature(finding: &Finding) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// 去重键的组成字段（漏洞类型始终参与）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupKeyFields {
+    /// 主机（含端口）
+    pub host: bool,
+    /// URL 路径
+    pub path: bool,
+    /// 参数/位置（Finding.location）
+    pub param: bool,
+    /// 插件 ID
+    pub plugin: bool,
+    /// 证据内容签名
+    pub evidence: bool,
+}
+
+impl Default for DedupKeyFields {
+    fn default() -> Self {
+        Self {
+            host: true,
+            path: true,
+            param: true,
+            plugin: true,
+            evidence: false,
+        }
+    }
+}
+
+impl DedupKeyFields {
+    /// 按选中的字段计算去重键
+    pub fn key_for(&self, finding: &Finding) -> String {
+        let parsed = url::Url::parse(&finding.url).ok();
+        let mut hasher = Sha256::new();
+        hasher.update(finding.vuln_type.as_bytes());
+        if self.host {
+            let host = parsed
+                .as_ref()
+                .and_then(|u| u.host_str())
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            let port = parsed.as_ref().and_then(|u| u.port_or_known_default());
+            hasher.update(b"\0host:");
+            hasher.update(host.as_bytes());
+            if let Some(port) = port {
+                hasher.update(port.to_string().as_bytes());
+            }
+        }
+        if self.path {
+            hasher.update(b"\0path:");
+            hasher.update(parsed.as_ref().map(|u| u.path()).unwrap_or("/").as_bytes());
+        }
+        if self.param {
+            hasher.update(b"\0param:");
+            hasher.update(finding.location.as_bytes());
+        }
+        if self.plugin {
+            hasher.update(b"\0plugin:");
+            hasher.update(finding.plugin_id.as_bytes());
+        }
+        if self.evidence {
+            hasher.update(b"\0evidence:");
+            hasher.update(Sha256::digest(finding.evidence.as_bytes()));
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// 去重策略
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupPolicy {
+    /// 自定义去重键；为空时使用 Finding 默认签名
+    pub key: Option<DedupKeyFields>,
+    /// 相同 Finding 允许再次上报的间隔（秒）；为空表示只上报一次
+    pub ttl_secs: Option<u64>,
+}
+
+impl DedupPolicy {
+    pub fn key_for(&self, finding: &Finding) -> String {
+        match &self.key {
+            Some(fields) => fields.key_for(finding),
+            None => finding.calculate_signature(),
+        }
+    }
+
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl_secs.map(Duration::from_secs)
+    }
+}
+
+/// 去重配置：全局策略 + 按插件覆盖
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DedupConfig {
+    pub default: DedupPolicy,
+    /// 插件 ID -> 覆盖策略
+    pub plugin_overrides: HashMap<String, DedupPolicy>,
+}
+
+impl DedupConfig {
+    /// 获取插件适用的策略
+    pub fn policy_for(&self, plugin_id: &str) -> &DedupPolicy {
+        self.plugin_overrides
+            .get(plugin_id)
+            .unwrap_or(&self.default)
+    }
+
+    /// 所有策略中最长的 TTL
+    pub fn max_ttl(&self) -> Option<Duration> {
+        std::iter::once(&self.default)
+            .chain(self.plugin_overrides.values())
+            .filter_map(DedupPolicy::ttl)
+            .max()
+    }
+}
+
+/// 共享的去重配置（命令层修改后即时生效）
+pub type SharedDedupConfig = Arc<RwLock<DedupConfig>>;
+
+/// 去重窗口：记录设置了 TTL 的去重键最近一次上报时间
+#[derive(Debug, Default)]
+pub struct DedupWindow {
+    reported_at: HashMap<String, Instant>,
+}
+
+impl DedupWindow {
+    /// 记录一次上报（无 TTL 的键不需要记录）
+    pub fn record(&mut self, key: &str, ttl: Option<Duration>, now: Instant) {
+        if ttl.is_some() {
+            self.reported_at.insert(key.to_string(), now);
+        }
+    }
+
+    /// 已见过的 Finding 是否超出窗口可再次上报；可以时刷新上报时间
+    pub fn should_refire(&mut self, key: &str, ttl: Option<Duration>, now: Instant) -> bool {
+        let Some(ttl) = ttl else {
+            return false;
+        };
+        match self.reported_at.get(key) {
+            Some(last) if now.saturating_duration_since(*last) < ttl => false,
+            _ => {
+                self.reported_at.insert(key.to_string(), now);
+                true
+            }
+        }
+    }
+
+    /// 移除已超出窗口的记录
+    pub fn prune(&mut self, max_ttl: Duration, now: Instant) {
+        self.reported_at
+            .retain(|_, last| now.saturating_duration_since(*last) < max_ttl);
+    }
+
+    pub fn forget(&mut self, key: &str) {
+        self.reported_at.remove(key);
+    }
+
+    pub fn len(&self) -> usize {
+        self.reported_at.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reported_at.is_empty()
+    }
+}
+
 /// Finding 去重服务
 pub struct FindingDeduplicator {
     /// 内存中的签名集合（用于快速去重）
@@ -90,3 +258,107 @@ impl Default for FindingDeduplicator {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Confidence, Severity};
+
+    fn finding(plugin_id: &str, url: &str, location: &str) -> Finding {
+        Finding {
+            id: "f1".to_string(),
+            plugin_id: plugin_id.to_string(),
+            vuln_type: "sqli_time".to_string(),
+            severity: Severity::High,
+            title: "Time-based SQL injection".to_string(),
+            description: String::new(),
+            evidence: "delay 5012ms".to_string(),
+            location: location.to_string(),
+            confidence: Confidence::Medium,
+            cwe: None,
+            owasp: None,
+            remediation: None,
+            url: url.to_string(),
+            method: "GET".to_string(),
+            created_at: chrono::Utc::now(),
+            request_headers: None,
+            request_body: None,
+            response_status: None,
+            response_headers: None,
+            response_body: None,
+        }
+    }
+
+    #[test]
+    fn test_key_composition() {
+        let a = finding("sqli", "https://Shop.test/item?id=1", "param:id");
+        let b = finding("sqli", "https://shop.test/item?id=2", "param:sort");
+        let fields = DedupKeyFields::default();
+        // 查询串不参与，参数不同则不合并
+        assert_ne!(fields.key_for(&a), fields.key_for(&b));
+
+        let without_param = DedupKeyFields {
+            param: false,
+            ..Default::default()
+        };
+        assert_eq!(without_param.key_for(&a), without_param.key_for(&b));
+
+        let mut other_host = a.clone();
+        other_host.url = "https://shop.test:8443/item".to_string();
+        assert_ne!(fields.key_for(&a), fields.key_for(&other_host));
+
+        let mut other_evidence = a.clone();
+        other_evidence.evidence = "delay 5100ms".to_string();
+        assert_eq!(fields.key_for(&a), fields.key_for(&other_evidence));
+        let with_evidence = DedupKeyFields {
+            evidence: true,
+            ..Default::default()
+        };
+        assert_ne!(
+            with_evidence.key_for(&a),
+            with_evidence.key_for(&other_evidence)
+        );
+
+        // 未配置自定义键时保持默认签名
+        assert_eq!(DedupPolicy::default().key_for(&a), a.calculate_signature());
+    }
+
+    #[test]
+    fn test_plugin_override() {
+        let mut config = DedupConfig::default();
+        config.plugin_overrides.insert(
+            "sqli".to_string(),
+            DedupPolicy {
+                key: None,
+                ttl_secs: Some(600),
+            },
+        );
+        assert_eq!(
+            config.policy_for("sqli").ttl(),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(config.policy_for("xss").ttl(), None);
+    }
+
+    #[test]
+    fn test_window_suppresses_then_refires() {
+        let ttl = Some(Duration::from_secs(60));
+        let start = Instant::now();
+        let mut window = DedupWindow::default();
+        window.record("k", ttl, start);
+
+        assert!(!window.should_refire("k", ttl, start + Duration::from_secs(30)));
+        assert!(window.should_refire("k", ttl, start + Duration::from_secs(61)));
+        // 再次上报后重新计时
+        assert!(!window.should_refire("k", ttl, start + Duration::from_secs(90)));
+        assert!(window.should_refire("k", ttl, start + Duration::from_secs(122)));
+
+        // 无 TTL 时永不再次上报
+        window.record("once", None, start);
+        assert!(!window.should_refire("once", None, start + Duration::from_secs(86400)));
+        assert_eq!(window.len(), 1);
+
+        window.prune(Duration::from_secs(60), start + Duration::from_secs(500));
+        assert!(window.is_empty());
+    }
+}
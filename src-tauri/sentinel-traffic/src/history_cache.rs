@@ -30,6 +30,14 @@ pub struct HttpRequestRecord {
     pub request_body: Option<String>,
     pub response_headers: Option<String>,
     pub response_body: Option<String>,
+    /// 解压前的原始响应体（历史记录格式，二进制以 `[BASE64]` 前缀存储）；
+    /// 有值时 `response_body` 为解压后的正文
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_response_body: Option<String>,
+    /// `response_body` 已是解压后的正文且原始数据未保留（如从数据库加载的记录），
+    /// 此时不应再次解压，也不能作为原始数据重放
+    #[serde(default)]
+    pub response_body_decoded: bool,
     pub response_size: i64,
     pub response_time: i64,
     pub timestamp: DateTime<Utc>,
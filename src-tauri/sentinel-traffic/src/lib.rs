@@ -9,32 +9,57 @@
 //! - 插件管理器（加载、启用/禁用、注册表）
 //! - 内置插件（SQL 注入、XSS、敏感信息检测）
 
+pub mod asset_extractor;
+pub mod body_limit;
 pub mod certificate;
 pub mod certificate_authority;
+pub mod content_decoding;
+pub mod credential_extractor;
 pub mod error;
 pub mod finding;
 pub mod history_cache;
+pub mod match_replace;
 pub mod packet_capture;
+pub mod protocol_decoder;
 pub mod proxy;
+pub mod replay_template;
+pub mod report_export;
 pub mod scanner;
+pub mod security_headers;
+pub mod suppression;
 pub mod system_proxy;
 pub mod types;
+pub mod upstream_chain;
 
-pub use certificate::CertificateService;
+pub use asset_extractor::{PassiveAssetExtractor, PASSIVE_ASSET_SOURCE};
+pub use certificate::{CertificateService, HostCaConfig};
 pub use certificate_authority::ChainedCertificateAuthority;
+pub use credential_extractor::{
+    CredentialExtractor, CredentialExtractorConfig, CredentialFinding, CredentialKind,
+    CredentialPattern,
+};
 pub use error::{Result, TrafficError};
 
 // Re-export traffic database types from sentinel-db
+pub use finding::{DedupConfig, DedupKeyFields, DedupPolicy, SharedDedupConfig};
 pub use history_cache::{
     HistoryCacheConfig, HistoryCacheStats, HttpRequestFilters, HttpRequestRecord,
     ProxyHistoryCache, ProxyHistoryFilters, ProxyHistoryItem, WebSocketConnectionRecord,
     WebSocketConnectionStatus, WebSocketDirection, WebSocketFilters, WebSocketMessageRecord,
     WebSocketMessageType,
 };
+pub use match_replace::{
+    compile_rules as compile_match_replace_rules, validate_rule as validate_match_replace_rule,
+    CompiledMatchReplaceRule, MatchReplaceRule, MatchReplaceTarget, SharedMatchReplaceRules,
+};
 pub use packet_capture::{
     CapturedPacket, ExtractedFile, FileExtractor, InterfaceInfo, PacketCaptureService, PcapFileOps,
     ProtocolLayer,
 };
+pub use protocol_decoder::{
+    attach_decoded, decode_dns, decode_http_streams, dns_messages, http_exchanges, DecodedProtocol,
+    DnsMessage, DnsQuestion, DnsRecord, HttpExchange, HttpMessage, HttpStartLine,
+};
 pub use proxy::{
     FailedConnection, InterceptAction, InterceptFilterRule, InterceptState,
     PendingInterceptRequest, PendingInterceptResponse, PendingInterceptWebSocketMessage,
@@ -42,18 +67,33 @@ pub use proxy::{
     WebSocketConnectionContext, WebSocketDirection as ProxyWebSocketDirection,
     WebSocketMessageContext,
 };
+pub use replay_template::{
+    expand_template, ExpandedRequest, RequestTemplate, TemplateVariable, TemplateVariableSource,
+    MAX_TEMPLATE_REQUESTS,
+};
+pub use report_export::{
+    build_json_report, build_sarif_report, severity_to_sarif_level, FindingsJsonReport,
+};
 pub use scanner::{FindingDeduplicator, FindingReceiver, FindingSender, ScanPipeline};
+pub use security_headers::{
+    SecurityHeader, SecurityHeaderConfig, SecurityHeaderRule, SharedSecurityHeaderConfig,
+    SECURITY_HEADERS_PLUGIN_ID,
+};
 pub use sentinel_db::{
     ProxyRequestFilters, ProxyRequestRecord, TrafficEvidenceRecord as EvidenceRecord,
-    TrafficVulnerabilityFilters as VulnerabilityFilters,
+    TrafficSuppressionRule as SuppressionRule, TrafficVulnerabilityFilters as VulnerabilityFilters,
+    TrafficVulnerabilityPage as VulnerabilityPage,
     TrafficVulnerabilityRecord as VulnerabilityRecord,
     TrafficVulnerabilityWithEvidence as VulnerabilityWithEvidence,
 };
+pub use suppression::{SharedSuppressionRules, SUPPRESSED_STATUS};
 pub use types::*;
+pub use upstream_chain::{UpstreamHopError, UpstreamProxyHop, UpstreamProxyKind};
 
 // 重导出插件系统（来自 sentinel-plugins）
 pub use sentinel_plugins::{
-    PluginEngine, PluginError, PluginManager, PluginMetadata, PluginRecord, PluginStatus,
+    PluginEngine, PluginError, PluginExecutionMetrics, PluginManager, PluginMetadata,
+    PluginMetrics, PluginRecord, PluginStatus,
 };
 
 /// 流量分析系统版本
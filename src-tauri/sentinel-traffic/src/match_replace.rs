@@ -0,0 +1,409 @@
+//! 匹配替换规则
+//!
+//! 按顺序对经过代理的请求/响应头和正文做正则替换（类似 Burp 的 Match and Replace）：
+//! - 头部规则按 `Name: value` 整行匹配；`pattern` 为空时把 `replacement` 作为新头部追加，
+//!   替换结果为空时删除该头部；
+//! - 正文规则直接在原始字节上替换；
+//! - `host_pattern` 为 glob（如 `*.example.com`），为空表示作用于所有主机。
+
+use crate::suppression::glob_match;
+use hudsucker::hyper::header::{HeaderMap, HeaderName, HeaderValue};
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// 规则作用位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchReplaceTarget {
+    RequestHeader,
+    RequestBody,
+    ResponseHeader,
+    ResponseBody,
+}
+
+/// 匹配替换规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchReplaceRule {
+    /// 规则 ID
+    #[serde(default)]
+    pub id: String,
+    /// 是否启用
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 作用位置
+    pub target: MatchReplaceTarget,
+    /// 匹配正则（头部规则为空表示追加新头部）
+    #[serde(default)]
+    pub pattern: String,
+    /// 替换内容（支持 `$1` 等捕获组引用）
+    #[serde(default)]
+    pub replacement: String,
+    /// 主机范围（glob），为空表示所有主机
+    #[serde(default)]
+    pub host_pattern: Option<String>,
+    /// 备注
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 预编译的规则（仅包含已启用且正则合法的规则）
+#[derive(Debug, Clone)]
+pub struct CompiledMatchReplaceRule {
+    pub rule: MatchReplaceRule,
+    regex: Option<Regex>,
+}
+
+/// 共享的匹配替换规则（命令层修改后即时生效）
+pub type SharedMatchReplaceRules = Arc<RwLock<Vec<CompiledMatchReplaceRule>>>;
+
+/// 校验规则的正则是否合法
+pub fn validate_rule(rule: &MatchReplaceRule) -> Result<(), String> {
+    if rule.pattern.is_empty() {
+        return match rule.target {
+            MatchReplaceTarget::RequestHeader | MatchReplaceTarget::ResponseHeader => Ok(()),
+            _ => Err("Body rules require a match pattern".to_string()),
+        };
+    }
+    Regex::new(&rule.pattern)
+        .map(|_| ())
+        .map_err(|e| format!("Invalid regex '{}': {}", rule.pattern, e))
+}
+
+/// 按原有顺序编译规则，跳过禁用和非法的规则
+pub fn compile_rules(rules: &[MatchReplaceRule]) -> Vec<CompiledMatchReplaceRule> {
+    rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .filter_map(|rule| {
+            if let Err(e) = validate_rule(rule) {
+                warn!("Skipping match-and-replace rule {}: {}", rule.id, e);
+                return None;
+            }
+            let regex = if rule.pattern.is_empty() {
+                None
+            } else {
+                Regex::new(&rule.pattern).ok()
+            };
+            Some(CompiledMatchReplaceRule {
+                rule: rule.clone(),
+                regex,
+            })
+        })
+        .collect()
+}
+
+impl CompiledMatchReplaceRule {
+    fn applies_to(&self, target: MatchReplaceTarget, host: Option<&str>) -> bool {
+        if self.rule.target != target {
+            return false;
+        }
+        match self
+            .rule
+            .host_pattern
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+        {
+            None => true,
+            Some(pattern) => host.is_some_and(|h| {
+                let host = h.split(':').next().unwrap_or(h).to_ascii_lowercase();
+                glob_match(&pattern.to_ascii_lowercase(), &host)
+            }),
+        }
+    }
+}
+
+/// 是否存在作用于该位置和主机的规则
+pub fn has_rules(
+    rules: &[CompiledMatchReplaceRule],
+    target: MatchReplaceTarget,
+    host: Option<&str>,
+) -> bool {
+    rules.iter().any(|r| r.applies_to(target, host))
+}
+
+/// 解析 `Name: value` 形式的头部行
+fn parse_header_line(line: &str) -> Option<(HeaderName, HeaderValue)> {
+    let Some((name, value)) = line.split_once(':') else {
+        warn!("Ignoring malformed rewritten header line: {}", line);
+        return None;
+    };
+    match (
+        HeaderName::from_bytes(name.trim().as_bytes()),
+        HeaderValue::from_str(value.trim()),
+    ) {
+        (Ok(name), Ok(value)) => Some((name, value)),
+        _ => {
+            warn!("Ignoring invalid rewritten header line: {}", line);
+            None
+        }
+    }
+}
+
+/// 对每个头部值按 `Name: value` 整行应用一条正则规则，仅改动命中的头部
+fn rewrite_header_values(regex: &Regex, replacement: &str, headers: &mut HeaderMap) -> bool {
+    let names: Vec<HeaderName> = headers.keys().cloned().collect();
+    let mut renamed = Vec::new();
+    let mut changed = false;
+    for name in names {
+        let mut kept = Vec::new();
+        let mut touched = false;
+        for value in headers.get_all(&name) {
+            // 非 UTF-8 的值无法按行匹配，原样保留
+            let Ok(text) = value.to_str() else {
+                kept.push(value.clone());
+                continue;
+            };
+            let line = format!("{}: {}", name.as_str(), text);
+            let replaced = regex.replace_all(line.as_bytes(), replacement.as_bytes());
+            if replaced.as_ref() == line.as_bytes() {
+                kept.push(value.clone());
+                continue;
+            }
+            let replaced = String::from_utf8_lossy(&replaced).into_owned();
+            if replaced.trim().is_empty() {
+                // 替换结果为空即删除该值
+                touched = true;
+                continue;
+            }
+            match parse_header_line(&replaced) {
+                Some((new_name, new_value)) if new_name == name => {
+                    touched = true;
+                    kept.push(new_value);
+                }
+                Some(entry) => {
+                    touched = true;
+                    renamed.push(entry);
+                }
+                None => kept.push(value.clone()),
+            }
+        }
+        if touched {
+            changed = true;
+            headers.remove(&name);
+            for value in kept {
+                headers.append(name.clone(), value);
+            }
+        }
+    }
+    // 改名后的头部放到最后追加，避免被同一条规则再次匹配
+    for (name, value) in renamed {
+        headers.append(name, value);
+    }
+    changed
+}
+
+/// 就地改写 HeaderMap，返回是否有规则生效
+///
+/// 只有命中规则的头部会被改动，其余头部（包括非 UTF-8 值）保持原样。
+pub fn rewrite_headers(
+    rules: &[CompiledMatchReplaceRule],
+    target: MatchReplaceTarget,
+    host: Option<&str>,
+    headers: &mut HeaderMap,
+) -> bool {
+    let mut changed = false;
+    for rule in rules.iter().filter(|r| r.applies_to(target, host)) {
+        match &rule.regex {
+            None => {
+                if let Some((name, value)) = parse_header_line(&rule.rule.replacement) {
+                    headers.append(name, value);
+                    changed = true;
+                }
+            }
+            Some(regex) => {
+                changed |= rewrite_header_values(regex, &rule.rule.replacement, headers);
+            }
+        }
+    }
+    changed
+}
+
+/// 改写正文，未发生变化时返回 None
+pub fn rewrite_body(
+    rules: &[CompiledMatchReplaceRule],
+    target: MatchReplaceTarget,
+    host: Option<&str>,
+    body: &[u8],
+) -> Option<Vec<u8>> {
+    let mut current = body.to_vec();
+    let mut changed = false;
+    for rule in rules.iter().filter(|r| r.applies_to(target, host)) {
+        let Some(regex) = &rule.regex else {
+            continue;
+        };
+        let replaced = regex.replace_all(&current, rule.rule.replacement.as_bytes());
+        if replaced.as_ref() != current.as_slice() {
+            current = replaced.into_owned();
+            changed = true;
+        }
+    }
+    changed.then_some(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(target: MatchReplaceTarget, pattern: &str, replacement: &str) -> MatchReplaceRule {
+        MatchReplaceRule {
+            id: "r1".to_string(),
+            enabled: true,
+            target,
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            host_pattern: None,
+            comment: None,
+        }
+    }
+
+    #[test]
+    fn test_header_add_and_strip() {
+        let rules = compile_rules(&[
+            rule(MatchReplaceTarget::RequestHeader, "", "X-Pentest: sentinel"),
+            rule(
+                MatchReplaceTarget::ResponseHeader,
+                "(?i)^content-security-policy:.*$",
+                "",
+            ),
+        ]);
+
+        let mut request = HeaderMap::new();
+        request.insert("host", HeaderValue::from_static("example.com"));
+        assert!(rewrite_headers(
+            &rules,
+            MatchReplaceTarget::RequestHeader,
+            Some("example.com"),
+            &mut request
+        ));
+        assert_eq!(request.get("x-pentest").unwrap(), "sentinel");
+        assert_eq!(request.get("host").unwrap(), "example.com");
+
+        let mut response = HeaderMap::new();
+        response.insert(
+            "content-security-policy",
+            HeaderValue::from_static("default-src 'self'"),
+        );
+        response.insert("server", HeaderValue::from_static("nginx"));
+        assert!(rewrite_headers(
+            &rules,
+            MatchReplaceTarget::ResponseHeader,
+            Some("example.com"),
+            &mut response
+        ));
+        assert!(response.get("content-security-policy").is_none());
+        assert_eq!(response.get("server").unwrap(), "nginx");
+    }
+
+    #[test]
+    fn test_rewrite_keeps_non_utf8_and_untouched_headers() {
+        let rules = compile_rules(&[rule(
+            MatchReplaceTarget::RequestHeader,
+            "(?i)^user-agent: .*$",
+            "User-Agent: sentinel",
+        )]);
+
+        let raw = HeaderValue::from_bytes(b"caf\xe9").unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-legacy", raw.clone());
+        headers.append("cookie", HeaderValue::from_static("a=1"));
+        headers.append("cookie", HeaderValue::from_static("b=2"));
+        headers.insert("user-agent", HeaderValue::from_static("curl/8.0"));
+
+        assert!(rewrite_headers(
+            &rules,
+            MatchReplaceTarget::RequestHeader,
+            None,
+            &mut headers
+        ));
+        assert_eq!(headers.get("user-agent").unwrap(), "sentinel");
+        assert_eq!(headers.get("x-legacy").unwrap(), &raw);
+        let cookies: Vec<_> = headers.get_all("cookie").iter().collect();
+        assert_eq!(cookies, vec!["a=1", "b=2"]);
+
+        // 没有命中时不改动
+        let mut untouched = headers.clone();
+        let rules = compile_rules(&[rule(
+            MatchReplaceTarget::RequestHeader,
+            "^x-missing:.*$",
+            "",
+        )]);
+        assert!(!rewrite_headers(
+            &rules,
+            MatchReplaceTarget::RequestHeader,
+            None,
+            &mut untouched
+        ));
+        assert_eq!(untouched, headers);
+    }
+
+    #[test]
+    fn test_body_substitution_in_order() {
+        let rules = compile_rules(&[
+            rule(
+                MatchReplaceTarget::ResponseBody,
+                r#""admin":\s*false"#,
+                r#""admin": true"#,
+            ),
+            rule(MatchReplaceTarget::ResponseBody, "true", "TRUE"),
+            rule(
+                MatchReplaceTarget::RequestBody,
+                "user=(\\w+)",
+                "user=${1}_x",
+            ),
+        ]);
+
+        let body = br#"{"name":"bob","admin": false}"#;
+        let rewritten = rewrite_body(&rules, MatchReplaceTarget::ResponseBody, None, body).unwrap();
+        assert_eq!(rewritten, br#"{"name":"bob","admin": TRUE}"#.to_vec());
+
+        let rewritten =
+            rewrite_body(&rules, MatchReplaceTarget::RequestBody, None, b"user=bob").unwrap();
+        assert_eq!(rewritten, b"user=bob_x".to_vec());
+
+        assert!(rewrite_body(&rules, MatchReplaceTarget::RequestBody, None, b"other").is_none());
+    }
+
+    #[test]
+    fn test_host_scoped_rule() {
+        let mut scoped = rule(MatchReplaceTarget::RequestHeader, "", "X-Scoped: 1");
+        scoped.host_pattern = Some("*.Example.com".to_string());
+        let mut disabled = rule(MatchReplaceTarget::RequestHeader, "", "X-Disabled: 1");
+        disabled.enabled = false;
+        let rules = compile_rules(&[scoped, disabled]);
+
+        let mut in_scope = HeaderMap::new();
+        assert!(rewrite_headers(
+            &rules,
+            MatchReplaceTarget::RequestHeader,
+            Some("api.example.com:8443"),
+            &mut in_scope
+        ));
+        assert_eq!(in_scope.get("x-scoped").unwrap(), "1");
+        assert!(in_scope.get("x-disabled").is_none());
+
+        let mut out_of_scope = HeaderMap::new();
+        assert!(!rewrite_headers(
+            &rules,
+            MatchReplaceTarget::RequestHeader,
+            Some("other.org"),
+            &mut out_of_scope
+        ));
+        assert!(out_of_scope.is_empty());
+        assert!(!has_rules(&rules, MatchReplaceTarget::RequestHeader, None));
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        assert!(validate_rule(&rule(MatchReplaceTarget::RequestBody, "", "x")).is_err());
+        assert!(validate_rule(&rule(MatchReplaceTarget::RequestBody, "(", "x")).is_err());
+        assert!(compile_rules(&[rule(MatchReplaceTarget::ResponseBody, "(", "x")]).is_empty());
+    }
+}
@@ -33,6 +33,8 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
+use crate::protocol_decoder::DecodedProtocol;
+
 /// Network interface information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InterfaceInfo {
@@ -89,6 +91,9 @@ pub struct CapturedPacket {
     pub info: String,
     pub layers: Vec<ProtocolLayer>,
     pub raw: Vec<u8>,
+    /// Application-layer decode (HTTP message or DNS message starting in this packet)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<DecodedProtocol>,
 }
 
 /// Packet capture service
@@ -309,6 +314,7 @@ impl PacketCaptureService {
             info,
             layers,
             raw: data.to_vec(),
+            decoded: None,
         })
     }
 
@@ -1431,7 +1437,7 @@ impl FileExtractor {
         files
     }
 
-    fn stream_key(src: &str, dst: &str) -> String {
+    pub(crate) fn stream_key(src: &str, dst: &str) -> String {
         let mut parts = [src, dst];
         parts.sort();
         format!("{}-{}", parts[0], parts[1])
@@ -2017,7 +2023,7 @@ impl FileExtractor {
     }
 
     /// Extract payload from raw packet (skip headers)
-    fn extract_payload(raw: &[u8]) -> &[u8] {
+    pub(crate) fn extract_payload(raw: &[u8]) -> &[u8] {
         if raw.len() < 54 {
             return &[];
         }
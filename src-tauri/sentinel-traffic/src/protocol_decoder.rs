@@ -0,0 +1,975 @@
+//! Application-layer decoders for captured packets
+//!
+//! Reassembles TCP streams from captured frames and parses the HTTP/1.x
+//! requests and responses they carry (start line, headers and body, with
+//! chunked transfer-encoding and gzip/deflate content-encoding removed), and
+//! parses DNS messages carried over UDP port 53. Each decoded message is also
+//! attached to the packet it starts in via [`CapturedPacket::decoded`].
+
+use pnet::packet::ethernet::{EtherTypes, EthernetPacket};
+use pnet::packet::ip::{IpNextHeaderProtocol, IpNextHeaderProtocols};
+use pnet::packet::ipv4::Ipv4Packet;
+use pnet::packet::ipv6::Ipv6Packet;
+use pnet::packet::tcp::TcpPacket;
+use pnet::packet::udp::UdpPacket;
+use pnet::packet::Packet;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+use tracing::debug;
+
+use crate::content_decoding::{decode_content, MAX_DECODED_BODY_SIZE};
+use crate::packet_capture::{CapturedPacket, FileExtractor, PcapFileOps};
+
+const DNS_PORT: u16 = 53;
+const DNS_HEADER_LEN: usize = 12;
+/// Upper bound on compression pointer hops while reading a DNS name
+const MAX_DNS_POINTER_HOPS: usize = 64;
+
+/// Application-layer decode attached to a packet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "protocol", rename_all = "snake_case")]
+pub enum DecodedProtocol {
+    Http(HttpMessage),
+    Dns(DnsMessage),
+}
+
+/// First line of an HTTP message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HttpStartLine {
+    Request {
+        method: String,
+        target: String,
+        version: String,
+    },
+    Response {
+        version: String,
+        status: u16,
+        reason: String,
+    },
+}
+
+/// Decoded HTTP request or response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpMessage {
+    pub start_line: HttpStartLine,
+    /// Headers in wire order
+    pub headers: Vec<(String, String)>,
+    /// Body with transfer and content encodings removed
+    pub body: Vec<u8>,
+    /// The stream ended before the body did
+    pub body_truncated: bool,
+    /// Content-Encoding that could not be removed; `body` is left encoded
+    pub decode_error: Option<String>,
+    /// Packets carrying this message, in stream order
+    pub packet_ids: Vec<u64>,
+}
+
+impl HttpMessage {
+    /// First header with the given name (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Request/response pair from one TCP connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpExchange {
+    pub stream_key: String,
+    pub client: String,
+    pub server: String,
+    pub request: Option<HttpMessage>,
+    pub response: Option<HttpMessage>,
+}
+
+/// DNS question entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsQuestion {
+    pub name: String,
+    pub record_type: String,
+    pub class: u16,
+}
+
+/// DNS resource record; `data` is rendered as text (addresses, names, TXT strings, or hex)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DnsRecord {
+    pub name: String,
+    pub record_type: String,
+    pub class: u16,
+    pub ttl: u32,
+    pub data: String,
+}
+
+/// Decoded DNS query or response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsMessage {
+    pub packet_id: u64,
+    pub src: String,
+    pub dst: String,
+    pub transaction_id: u16,
+    pub is_response: bool,
+    pub opcode: u8,
+    pub rcode: u8,
+    pub questions: Vec<DnsQuestion>,
+    pub answers: Vec<DnsRecord>,
+}
+
+/// Read a pcap/pcapng file and decode the HTTP exchanges it contains
+pub fn decode_http_streams(pcap: &Path) -> Result<Vec<HttpExchange>, String> {
+    let packets = PcapFileOps::read_pcap_file(pcap)?;
+    Ok(http_exchanges(&packets))
+}
+
+/// Read a pcap/pcapng file and decode the DNS messages it contains
+pub fn decode_dns(pcap: &Path) -> Result<Vec<DnsMessage>, String> {
+    let packets = PcapFileOps::read_pcap_file(pcap)?;
+    Ok(dns_messages(&packets))
+}
+
+/// Attach decoded HTTP and DNS messages to the packets they start in
+pub fn attach_decoded(packets: &mut [CapturedPacket]) {
+    let mut decoded: HashMap<u64, DecodedProtocol> = HashMap::new();
+    for exchange in http_exchanges(packets) {
+        for message in [exchange.request, exchange.response].into_iter().flatten() {
+            if let Some(&first) = message.packet_ids.first() {
+                decoded
+                    .entry(first)
+                    .or_insert(DecodedProtocol::Http(message));
+            }
+        }
+    }
+    for message in dns_messages(packets) {
+        decoded.insert(message.packet_id, DecodedProtocol::Dns(message));
+    }
+    for pkt in packets.iter_mut() {
+        if let Some(d) = decoded.remove(&pkt.id) {
+            pkt.decoded = Some(d);
+        }
+    }
+}
+
+// ==================== Transport extraction ====================
+
+struct TcpSegment {
+    packet_id: u64,
+    src: String,
+    dst: String,
+    seq: u32,
+    syn: bool,
+    payload: Vec<u8>,
+}
+
+struct UdpDatagram {
+    src_port: u16,
+    dst_port: u16,
+    payload: Vec<u8>,
+}
+
+enum Transport {
+    Tcp(TcpSegment),
+    Udp(UdpDatagram),
+}
+
+/// TCP or UDP payload of a single packet (not reassembled)
+pub fn transport_payload(pkt: &CapturedPacket) -> Option<Vec<u8>> {
+    let payload = match parse_transport(pkt)? {
+        Transport::Tcp(segment) => segment.payload,
+        Transport::Udp(datagram) => datagram.payload,
+    };
+    (!payload.is_empty()).then_some(payload)
+}
+
+fn parse_transport(pkt: &CapturedPacket) -> Option<Transport> {
+    let ethernet = EthernetPacket::new(&pkt.raw)?;
+    let (next_protocol, l4): (IpNextHeaderProtocol, Vec<u8>) = match ethernet.get_ethertype() {
+        EtherTypes::Ipv4 => {
+            let ip = Ipv4Packet::new(ethernet.payload())?;
+            (ip.get_next_level_protocol(), ip.payload().to_vec())
+        }
+        EtherTypes::Ipv6 => {
+            let ip = Ipv6Packet::new(ethernet.payload())?;
+            (ip.get_next_header(), ip.payload().to_vec())
+        }
+        _ => return None,
+    };
+
+    match next_protocol {
+        IpNextHeaderProtocols::Tcp => {
+            let tcp = TcpPacket::new(&l4)?;
+            Some(Transport::Tcp(TcpSegment {
+                packet_id: pkt.id,
+                src: pkt.src.clone(),
+                dst: pkt.dst.clone(),
+                seq: tcp.get_sequence(),
+                syn: tcp.get_flags() & 0x02 != 0,
+                payload: tcp.payload().to_vec(),
+            }))
+        }
+        IpNextHeaderProtocols::Udp => {
+            let udp = UdpPacket::new(&l4)?;
+            Some(Transport::Udp(UdpDatagram {
+                src_port: udp.get_source(),
+                dst_port: udp.get_destination(),
+                payload: udp.payload().to_vec(),
+            }))
+        }
+        _ => None,
+    }
+}
+
+// ==================== TCP reassembly ====================
+
+/// One direction of a TCP connection, reassembled in sequence order
+#[derive(Default)]
+struct TcpFlow {
+    data: Vec<u8>,
+    /// (start, end, packet id) of each segment's contribution to `data`
+    segments: Vec<(usize, usize, u64)>,
+    /// A missing segment stopped reassembly early
+    has_gap: bool,
+}
+
+impl TcpFlow {
+    fn packets_in(&self, start: usize, end: usize) -> Vec<u64> {
+        self.segments
+            .iter()
+            .filter(|(s, e, _)| *s < end.max(start + 1) && *e > start)
+            .map(|(_, _, id)| *id)
+            .collect()
+    }
+}
+
+fn reassemble(segments: &[TcpSegment]) -> TcpFlow {
+    let mut flow = TcpFlow::default();
+    let Some(first) = segments
+        .iter()
+        .find(|s| !s.payload.is_empty())
+        .map(|s| s.seq)
+    else {
+        return flow;
+    };
+
+    // Offsets relative to the first data segment, tolerant of sequence wrap-around
+    let relative = |seq: u32| seq.wrapping_sub(first) as i32 as i64;
+    let base = segments
+        .iter()
+        .find(|s| s.syn)
+        .map(|s| relative(s.seq.wrapping_add(1)))
+        .unwrap_or_else(|| {
+            segments
+                .iter()
+                .filter(|s| !s.payload.is_empty())
+                .map(|s| relative(s.seq))
+                .min()
+                .unwrap_or(0)
+        });
+
+    let mut ordered: Vec<(i64, &TcpSegment)> = segments
+        .iter()
+        .filter(|s| !s.payload.is_empty())
+        .map(|s| (relative(s.seq) - base, s))
+        .filter(|(offset, _)| *offset >= 0)
+        .collect();
+    ordered.sort_by_key(|(offset, s)| (*offset, s.packet_id));
+
+    for (offset, segment) in ordered {
+        let offset = offset as usize;
+        let end = offset + segment.payload.len();
+        if end <= flow.data.len() {
+            // Retransmission of data we already have
+            continue;
+        }
+        if offset > flow.data.len() {
+            flow.has_gap = true;
+            break;
+        }
+        let start = flow.data.len();
+        flow.data
+            .extend_from_slice(&segment.payload[start - offset..]);
+        flow.segments
+            .push((start, flow.data.len(), segment.packet_id));
+    }
+    flow
+}
+
+// ==================== HTTP ====================
+
+/// Decode every HTTP exchange in the captured packets
+pub fn http_exchanges(packets: &[CapturedPacket]) -> Vec<HttpExchange> {
+    // stream key -> sender -> segments
+    let mut connections: HashMap<String, HashMap<String, Vec<TcpSegment>>> = HashMap::new();
+    let mut first_seen: HashMap<String, u64> = HashMap::new();
+    for pkt in packets {
+        if let Some(Transport::Tcp(segment)) = parse_transport(pkt) {
+            let key = FileExtractor::stream_key(&segment.src, &segment.dst);
+            first_seen.entry(key.clone()).or_insert(segment.packet_id);
+            connections
+                .entry(key)
+                .or_default()
+                .entry(segment.src.clone())
+                .or_default()
+                .push(segment);
+        }
+    }
+
+    let mut keys: Vec<String> = connections.keys().cloned().collect();
+    keys.sort_by_key(|k| first_seen[k]);
+
+    let mut exchanges = Vec::new();
+    for key in keys {
+        let directions = &connections[&key];
+        let flows: Vec<(&String, TcpFlow)> = directions
+            .iter()
+            .map(|(src, segments)| (src, reassemble(segments)))
+            .collect();
+
+        let Some((client, client_flow)) = flows
+            .iter()
+            .find(|(_, flow)| looks_like_request(&flow.data))
+        else {
+            continue;
+        };
+        let server_flow = flows
+            .iter()
+            .find(|(src, flow)| src != client && flow.data.starts_with(b"HTTP/"));
+        let server = directions
+            .get(*client)
+            .and_then(|segments| segments.first())
+            .map(|s| s.dst.clone())
+            .unwrap_or_default();
+
+        let requests = parse_http_messages(client_flow, &[]);
+        let methods: Vec<String> = requests
+            .iter()
+            .map(|m| match &m.start_line {
+                HttpStartLine::Request { method, .. } => method.clone(),
+                HttpStartLine::Response { .. } => String::new(),
+            })
+            .collect();
+        let responses: Vec<HttpMessage> = server_flow
+            .map(|(_, flow)| parse_http_messages(flow, &methods))
+            .unwrap_or_default()
+            .into_iter()
+            // Interim 1xx responses do not answer a request
+            .filter(
+                |m| !matches!(m.start_line, HttpStartLine::Response { status, .. } if status < 200),
+            )
+            .collect();
+
+        debug!(
+            "HTTP stream {}: {} requests, {} responses",
+            key,
+            requests.len(),
+            responses.len()
+        );
+
+        let count = requests.len().max(responses.len());
+        let mut requests = requests.into_iter();
+        let mut responses = responses.into_iter();
+        for _ in 0..count {
+            exchanges.push(HttpExchange {
+                stream_key: key.clone(),
+                client: (*client).clone(),
+                server: server.clone(),
+                request: requests.next(),
+                response: responses.next(),
+            });
+        }
+    }
+    exchanges
+}
+
+fn looks_like_request(data: &[u8]) -> bool {
+    const METHODS: [&[u8]; 9] = [
+        b"GET ",
+        b"POST ",
+        b"PUT ",
+        b"DELETE ",
+        b"HEAD ",
+        b"OPTIONS ",
+        b"PATCH ",
+        b"CONNECT ",
+        b"TRACE ",
+    ];
+    METHODS.iter().any(|m| data.starts_with(m))
+}
+
+enum Framing {
+    Empty,
+    Length(usize),
+    Chunked,
+    UntilClose,
+}
+
+/// Parse consecutive messages from one direction of a connection.
+/// `request_methods` is empty for the client side; for the server side it
+/// holds the methods of the requests being answered, in order.
+fn parse_http_messages(flow: &TcpFlow, request_methods: &[String]) -> Vec<HttpMessage> {
+    let is_response = !request_methods.is_empty() || flow.data.starts_with(b"HTTP/");
+    let data = &flow.data;
+    let mut messages = Vec::new();
+    let mut pos = 0;
+    let mut answered = 0;
+
+    while pos < data.len() {
+        let rest = &data[pos..];
+        let Some(head_len) = find(rest, b"\r\n\r\n") else {
+            break;
+        };
+        let head = String::from_utf8_lossy(&rest[..head_len]);
+        let mut lines = head.split("\r\n");
+        let Some(start_line) = lines.next().and_then(|l| parse_start_line(l, is_response)) else {
+            break;
+        };
+        let headers: Vec<(String, String)> = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+            .collect();
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+        };
+
+        let framing = match &start_line {
+            HttpStartLine::Response { status, .. } => {
+                let method = request_methods.get(answered).map(String::as_str);
+                if *status >= 200 {
+                    answered += 1;
+                }
+                if *status < 200 || *status == 204 || *status == 304 || method == Some("HEAD") {
+                    Framing::Empty
+                } else {
+                    body_framing(header("transfer-encoding"), header("content-length"))
+                        .unwrap_or(Framing::UntilClose)
+                }
+            }
+            HttpStartLine::Request { .. } => {
+                body_framing(header("transfer-encoding"), header("content-length"))
+                    .unwrap_or(Framing::Empty)
+            }
+        };
+
+        let body_start = head_len + 4;
+        let available = &rest[body_start..];
+        let (mut body, consumed, mut body_truncated) = match framing {
+            Framing::Empty => (Vec::new(), 0, false),
+            Framing::Length(len) => {
+                let take = len.min(available.len());
+                (available[..take].to_vec(), take, take < len)
+            }
+            Framing::Chunked => decode_chunked(available),
+            Framing::UntilClose => (available.to_vec(), available.len(), flow.has_gap),
+        };
+        if pos + body_start + consumed == data.len() && flow.has_gap {
+            body_truncated = true;
+        }
+
+        let mut decode_error = None;
+        if let Some(encoding) = header("content-encoding") {
+            if !body.is_empty() && !body_truncated {
+                match decode_content(&body, encoding, MAX_DECODED_BODY_SIZE) {
+                    Ok(decoded) => body = decoded,
+                    Err(e) => decode_error = Some(e),
+                }
+            }
+        }
+
+        let end = pos + body_start + consumed;
+        messages.push(HttpMessage {
+            start_line,
+            body,
+            body_truncated,
+            decode_error,
+            packet_ids: flow.packets_in(pos, end),
+            headers,
+        });
+        pos = end;
+    }
+    messages
+}
+
+fn body_framing(transfer_encoding: Option<&str>, content_length: Option<&str>) -> Option<Framing> {
+    if transfer_encoding.is_some_and(|te| te.to_ascii_lowercase().contains("chunked")) {
+        return Some(Framing::Chunked);
+    }
+    content_length
+        .and_then(|len| len.trim().parse::<usize>().ok())
+        .map(|len| {
+            if len == 0 {
+                Framing::Empty
+            } else {
+                Framing::Length(len)
+            }
+        })
+}
+
+fn parse_start_line(line: &str, is_response: bool) -> Option<HttpStartLine> {
+    if is_response {
+        let mut parts = line.splitn(3, ' ');
+        let version = parts.next()?;
+        if !version.starts_with("HTTP/") {
+            return None;
+        }
+        let status = parts.next()?.parse().ok()?;
+        Some(HttpStartLine::Response {
+            version: version.to_string(),
+            status,
+            reason: parts.next().unwrap_or_default().to_string(),
+        })
+    } else {
+        let mut parts = line.split(' ');
+        let (method, target, version) = (parts.next()?, parts.next()?, parts.next()?);
+        if !version.starts_with("HTTP/") || !looks_like_request(format!("{} ", method).as_bytes()) {
+            return None;
+        }
+        Some(HttpStartLine::Request {
+            method: method.to_string(),
+            target: target.to_string(),
+            version: version.to_string(),
+        })
+    }
+}
+
+/// Decode a chunked body; returns (body, bytes consumed, truncated)
+fn decode_chunked(data: &[u8]) -> (Vec<u8>, usize, bool) {
+    let mut body = Vec::new();
+    let mut pos = 0;
+    loop {
+        let Some(line_len) = find(&data[pos..], b"\r\n") else {
+            return (body, data.len(), true);
+        };
+        let size_line = String::from_utf8_lossy(&data[pos..pos + line_len]);
+        let size_hex = size_line.split(';').next().unwrap_or_default().trim();
+        let Ok(size) = usize::from_str_radix(size_hex, 16) else {
+            return (body, data.len(), true);
+        };
+        pos += line_len + 2;
+
+        if size == 0 {
+            // Optional trailers end with an empty line
+            if data[pos..].starts_with(b"\r\n") {
+                return (body, pos + 2, false);
+            }
+            return match find(&data[pos..], b"\r\n\r\n") {
+                Some(idx) => (body, pos + idx + 4, false),
+                None => (body, data.len(), true),
+            };
+        }
+
+        if pos + size > data.len() {
+            body.extend_from_slice(&data[pos..]);
+            return (body, data.len(), true);
+        }
+        body.extend_from_slice(&data[pos..pos + size]);
+        pos = (pos + size + 2).min(data.len());
+    }
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+// ==================== DNS ====================
+
+/// Decode every DNS message carried over UDP port 53
+pub fn dns_messages(packets: &[CapturedPacket]) -> Vec<DnsMessage> {
+    packets
+        .iter()
+        .filter_map(|pkt| match parse_transport(pkt)? {
+            Transport::Udp(udp) if udp.src_port == DNS_PORT || udp.dst_port == DNS_PORT => {
+                parse_dns_message(&udp.payload, pkt)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn parse_dns_message(msg: &[u8], pkt: &CapturedPacket) -> Option<DnsMessage> {
+    if msg.len() < DNS_HEADER_LEN {
+        return None;
+    }
+    let u16_at = |i: usize| u16::from_be_bytes([msg[i], msg[i + 1]]);
+    let flags = u16_at(2);
+    let question_count = u16_at(4);
+    let answer_count = u16_at(6);
+
+    let mut pos = DNS_HEADER_LEN;
+    let mut questions = Vec::new();
+    for _ in 0..question_count {
+        let (name, next) = read_dns_name(msg, pos)?;
+        let fixed = msg.get(next..next + 4)?;
+        questions.push(DnsQuestion {
+            name,
+            record_type: dns_type_name(u16::from_be_bytes([fixed[0], fixed[1]])),
+            class: u16::from_be_bytes([fixed[2], fixed[3]]),
+        });
+        pos = next + 4;
+    }
+
+    let mut answers = Vec::new();
+    for _ in 0..answer_count {
+        let Some((record, next)) = read_dns_record(msg, pos) else {
+            // Keep what was decoded from a truncated message
+            break;
+        };
+        answers.push(record);
+        pos = next;
+    }
+
+    Some(DnsMessage {
+        packet_id: pkt.id,
+        src: pkt.src.clone(),
+        dst: pkt.dst.clone(),
+        transaction_id: u16_at(0),
+        is_response: flags & 0x8000 != 0,
+        opcode: ((flags >> 11) & 0x0f) as u8,
+        rcode: (flags & 0x000f) as u8,
+        questions,
+        answers,
+    })
+}
+
+fn read_dns_record(msg: &[u8], pos: usize) -> Option<(DnsRecord, usize)> {
+    let (name, next) = read_dns_name(msg, pos)?;
+    let fixed = msg.get(next..next + 10)?;
+    let record_type = u16::from_be_bytes([fixed[0], fixed[1]]);
+    let class = u16::from_be_bytes([fixed[2], fixed[3]]);
+    let ttl = u32::from_be_bytes([fixed[4], fixed[5], fixed[6], fixed[7]]);
+    let rdlength = u16::from_be_bytes([fixed[8], fixed[9]]) as usize;
+    let rdata_start = next + 10;
+    let rdata = msg.get(rdata_start..rdata_start + rdlength)?;
+
+    let data = match (record_type, rdata.len()) {
+        (1, 4) => Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3]).to_string(),
+        (28, 16) => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(rdata);
+            Ipv6Addr::from(octets).to_string()
+        }
+        // NS, CNAME, PTR
+        (2 | 5 | 12, _) => read_dns_name(msg, rdata_start)
+            .map(|(n, _)| n)
+            .unwrap_or_default(),
+        // MX
+        (15, len) if len > 2 => {
+            let preference = u16::from_be_bytes([rdata[0], rdata[1]]);
+            let exchange = read_dns_name(msg, rdata_start + 2)
+                .map(|(n, _)| n)
+                .unwrap_or_default();
+            format!("{} {}", preference, exchange)
+        }
+        // TXT: length-prefixed character strings
+        (16, _) => {
+            let mut parts = Vec::new();
+            let mut i = 0;
+            while i < rdata.len() {
+                let len = rdata[i] as usize;
+                let end = (i + 1 + len).min(rdata.len());
+                parts.push(String::from_utf8_lossy(&rdata[i + 1..end]).to_string());
+                i = end;
+            }
+            parts.join("")
+        }
+        _ => rdata.iter().map(|b| format!("{:02x}", b)).collect(),
+    };
+
+    Some((
+        DnsRecord {
+            name,
+            record_type: dns_type_name(record_type),
+            class,
+            ttl,
+            data,
+        },
+        rdata_start + rdlength,
+    ))
+}
+
+/// Read a possibly compressed domain name; returns the name and the offset after it
+fn read_dns_name(msg: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut next = None;
+    let mut hops = 0;
+    loop {
+        let len = *msg.get(pos)? as usize;
+        if len & 0xc0 == 0xc0 {
+            let pointer = ((len & 0x3f) << 8) | *msg.get(pos + 1)? as usize;
+            next.get_or_insert(pos + 2);
+            hops += 1;
+            if hops > MAX_DNS_POINTER_HOPS {
+                return None;
+            }
+            pos = pointer;
+            continue;
+        }
+        if len == 0 {
+            next.get_or_insert(pos + 1);
+            break;
+        }
+        let label = msg.get(pos + 1..pos + 1 + len)?;
+        labels.push(String::from_utf8_lossy(label).to_string());
+        pos += 1 + len;
+    }
+    let name = if labels.is_empty() {
+        ".".to_string()
+    } else {
+        labels.join(".")
+    };
+    Some((name, next?))
+}
+
+fn dns_type_name(record_type: u16) -> String {
+    match record_type {
+        1 => "A",
+        2 => "NS",
+        5 => "CNAME",
+        6 => "SOA",
+        12 => "PTR",
+        15 => "MX",
+        16 => "TXT",
+        28 => "AAAA",
+        33 => "SRV",
+        65 => "HTTPS",
+        255 => "ANY",
+        other => return format!("TYPE{}", other),
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use pcap_file::pcap::{PcapPacket, PcapWriter};
+    use std::io::Write;
+    use std::time::Duration;
+
+    const CLIENT: [u8; 4] = [10, 0, 0, 1];
+    const SERVER: [u8; 4] = [10, 0, 0, 2];
+    const RESOLVER: [u8; 4] = [10, 0, 0, 53];
+
+    fn ipv4_frame(src: [u8; 4], dst: [u8; 4], protocol: u8, l4: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x02, 0x02, 0, 0, 0, 0, 0x01, 0x08, 0x00]);
+        let total_len = (20 + l4.len()) as u16;
+        frame.extend_from_slice(&[0x45, 0x00]);
+        frame.extend_from_slice(&total_len.to_be_bytes());
+        frame.extend_from_slice(&[0x00, 0x01, 0x40, 0x00, 64, protocol, 0x00, 0x00]);
+        frame.extend_from_slice(&src);
+        frame.extend_from_slice(&dst);
+        frame.extend_from_slice(l4);
+        frame
+    }
+
+    fn tcp_frame(to_server: bool, seq: u32, flags: u8, payload: &[u8]) -> Vec<u8> {
+        let (src, dst, sport, dport) = if to_server {
+            (CLIENT, SERVER, 51000u16, 80u16)
+        } else {
+            (SERVER, CLIENT, 80, 51000)
+        };
+        let mut tcp = Vec::new();
+        tcp.extend_from_slice(&sport.to_be_bytes());
+        tcp.extend_from_slice(&dport.to_be_bytes());
+        tcp.extend_from_slice(&seq.to_be_bytes());
+        tcp.extend_from_slice(&0u32.to_be_bytes());
+        tcp.extend_from_slice(&[0x50, flags, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00]);
+        tcp.extend_from_slice(payload);
+        ipv4_frame(src, dst, 6, &tcp)
+    }
+
+    fn udp_frame(src: [u8; 4], dst: [u8; 4], sport: u16, dport: u16, payload: &[u8]) -> Vec<u8> {
+        let mut udp = Vec::new();
+        udp.extend_from_slice(&sport.to_be_bytes());
+        udp.extend_from_slice(&dport.to_be_bytes());
+        udp.extend_from_slice(&((8 + payload.len()) as u16).to_be_bytes());
+        udp.extend_from_slice(&[0x00, 0x00]);
+        udp.extend_from_slice(payload);
+        ipv4_frame(src, dst, 17, &udp)
+    }
+
+    fn write_test_pcap(frames: &[Vec<u8>]) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let mut writer = PcapWriter::new(file.reopen().unwrap()).unwrap();
+        for (i, frame) in frames.iter().enumerate() {
+            writer
+                .write_packet(&PcapPacket::new(
+                    Duration::from_secs(1_700_000_000 + i as u64),
+                    frame.len() as u32,
+                    frame,
+                ))
+                .unwrap();
+        }
+        drop(writer);
+        file
+    }
+
+    /// Client SYN, a POST split over two segments (delivered out of order and
+    /// retransmitted), and a chunked + gzip response split mid-chunk
+    fn http_frames() -> Vec<Vec<u8>> {
+        let request_head =
+            b"POST /login HTTP/1.1\r\nHost: example.com\r\nContent-Length: 17\r\n\r\n";
+        let request_body = b"user=admin&pw=123";
+
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(b"{\"ok\":true,\"user\":\"admin\"}").unwrap();
+        let compressed = gz.finish().unwrap();
+        let (first, second) = compressed.split_at(compressed.len() / 2);
+        let mut response = b"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Encoding: gzip\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec();
+        response.extend_from_slice(format!("{:x}\r\n", first.len()).as_bytes());
+        response.extend_from_slice(first);
+        response.extend_from_slice(format!("\r\n{:x}\r\n", second.len()).as_bytes());
+        response.extend_from_slice(second);
+        response.extend_from_slice(b"\r\n0\r\n\r\n");
+        let (resp_a, resp_b) = response.split_at(response.len() - 20);
+
+        let client_isn = 1000u32;
+        let server_isn = 5000u32;
+        let body_seq = client_isn + 1 + request_head.len() as u32;
+        vec![
+            tcp_frame(true, client_isn, 0x02, b""),
+            tcp_frame(false, server_isn, 0x12, b""),
+            tcp_frame(true, body_seq, 0x18, request_body),
+            tcp_frame(true, client_isn + 1, 0x18, request_head),
+            tcp_frame(true, body_seq, 0x18, request_body),
+            tcp_frame(false, server_isn + 1, 0x18, resp_a),
+            tcp_frame(false, server_isn + 1 + resp_a.len() as u32, 0x18, resp_b),
+        ]
+    }
+
+    fn dns_frames() -> Vec<Vec<u8>> {
+        let mut query = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        query.extend_from_slice(b"\x07example\x03com\x00");
+        query.extend_from_slice(&[0, 1, 0, 1]);
+
+        let mut response = query.clone();
+        response[2] = 0x81;
+        response[3] = 0x80;
+        response[7] = 2;
+        // CNAME www.example.com pointing back at the question name, then an A record
+        response.extend_from_slice(&[0xc0, 0x0c, 0, 5, 0, 1, 0, 0, 0x0e, 0x10, 0, 6]);
+        response.extend_from_slice(b"\x03www\xc0\x0c");
+        response.extend_from_slice(&[0xc0, 0x0c, 0, 1, 0, 1, 0, 0, 0x0e, 0x10, 0, 4]);
+        response.extend_from_slice(&[93, 184, 216, 34]);
+
+        vec![
+            udp_frame(CLIENT, RESOLVER, 40000, 53, &query),
+            udp_frame(RESOLVER, CLIENT, 53, 40000, &response),
+        ]
+    }
+
+    fn sample_pcap() -> tempfile::NamedTempFile {
+        let mut frames = http_frames();
+        frames.extend(dns_frames());
+        write_test_pcap(&frames)
+    }
+
+    #[test]
+    fn test_decode_http_exchange_from_pcap() {
+        let pcap = sample_pcap();
+        let exchanges = decode_http_streams(pcap.path()).unwrap();
+        assert_eq!(exchanges.len(), 1);
+
+        let exchange = &exchanges[0];
+        assert_eq!(exchange.client, "10.0.0.1:51000");
+        assert_eq!(exchange.server, "10.0.0.2:80");
+
+        let request = exchange.request.as_ref().unwrap();
+        assert_eq!(
+            request.start_line,
+            HttpStartLine::Request {
+                method: "POST".to_string(),
+                target: "/login".to_string(),
+                version: "HTTP/1.1".to_string(),
+            }
+        );
+        assert_eq!(request.header("host"), Some("example.com"));
+        assert_eq!(request.body, b"user=admin&pw=123");
+        assert!(!request.body_truncated);
+        // Head arrived in packet 4, body in packet 3 (retransmitted as packet 5)
+        assert_eq!(request.packet_ids, vec![4, 3]);
+
+        let response = exchange.response.as_ref().unwrap();
+        assert!(matches!(
+            response.start_line,
+            HttpStartLine::Response { status: 200, .. }
+        ));
+        assert_eq!(response.body, b"{\"ok\":true,\"user\":\"admin\"}");
+        assert!(!response.body_truncated);
+        assert!(response.decode_error.is_none());
+        assert_eq!(response.packet_ids, vec![6, 7]);
+    }
+
+    #[test]
+    fn test_decode_dns_query_and_answer_from_pcap() {
+        let pcap = sample_pcap();
+        let messages = decode_dns(pcap.path()).unwrap();
+        assert_eq!(messages.len(), 2);
+
+        let query = &messages[0];
+        assert!(!query.is_response);
+        assert_eq!(query.transaction_id, 0x1234);
+        assert_eq!(
+            query.questions,
+            vec![DnsQuestion {
+                name: "example.com".to_string(),
+                record_type: "A".to_string(),
+                class: 1,
+            }]
+        );
+        assert!(query.answers.is_empty());
+
+        let response = &messages[1];
+        assert!(response.is_response);
+        assert_eq!(response.rcode, 0);
+        assert_eq!(response.answers.len(), 2);
+        assert_eq!(response.answers[0].record_type, "CNAME");
+        assert_eq!(response.answers[0].data, "www.example.com");
+        assert_eq!(response.answers[1].name, "example.com");
+        assert_eq!(response.answers[1].data, "93.184.216.34");
+        assert_eq!(response.answers[1].ttl, 3600);
+    }
+
+    #[test]
+    fn test_attach_decoded_to_packets() {
+        let pcap = sample_pcap();
+        let mut packets = PcapFileOps::read_pcap_file(pcap.path()).unwrap();
+        attach_decoded(&mut packets);
+
+        let decoded = |id: u64| {
+            packets
+                .iter()
+                .find(|p| p.id == id)
+                .unwrap()
+                .decoded
+                .as_ref()
+        };
+        assert!(matches!(decoded(4), Some(DecodedProtocol::Http(m)) if m.header("host").is_some()));
+        assert!(matches!(decoded(6), Some(DecodedProtocol::Http(_))));
+        assert!(decoded(5).is_none());
+        assert!(matches!(decoded(8), Some(DecodedProtocol::Dns(m)) if !m.is_response));
+        assert!(matches!(decoded(9), Some(DecodedProtocol::Dns(m)) if m.is_response));
+    }
+
+    #[test]
+    fn test_chunked_and_truncated_bodies() {
+        let (body, consumed, truncated) =
+            decode_chunked(b"4;ext=1\r\nWiki\r\n5\r\npedia\r\n0\r\nX-T: 1\r\n\r\nnext");
+        assert_eq!(body, b"Wikipedia");
+        assert_eq!(consumed, 38);
+        assert!(!truncated);
+
+        let (body, _, truncated) = decode_chunked(b"a\r\nshort");
+        assert_eq!(body, b"short");
+        assert!(truncated);
+    }
+}
@@ -6,6 +6,12 @@
 //! - 请求/响应 tee（异步扫描队列）
 //! - 忽略上游证书验证（用于抓取证书异常的站点）
 
+use crate::body_limit::{read_body_capped, CappedBody};
+use crate::certificate::HostCaConfig;
+use crate::match_replace::{
+    has_rules, rewrite_body, rewrite_headers, MatchReplaceTarget, SharedMatchReplaceRules,
+};
+use crate::upstream_chain::{self, UpstreamProxyHop, UpstreamProxyKind};
 use crate::{ProxyStats, RequestContext, ResponseContext, Result, TrafficError};
 use brotli::Decompressor;
 use flate2::read::GzDecoder;
@@ -24,9 +30,8 @@ use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::{io::Read, net::SocketAddr, sync::Arc};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpListener;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, oneshot, RwLock};
 use tokio::task::JoinHandle;
 use tower::{Service, ServiceBuilder};
 use tracing::{debug, error, info, warn};
@@ -87,6 +92,8 @@ impl ServerCertVerifier for InsecureServerCertVerifier {
 pub enum ProxyStream {
     Http(tokio::net::TcpStream),
     Https(tokio_rustls::client::TlsStream<tokio::net::TcpStream>),
+    /// 直连上游 HTTP 代理的连接，请求以 absolute-form 发给代理
+    Forward(tokio::net::TcpStream),
 }
 
 impl tokio::io::AsyncRead for ProxyStream {
@@ -98,6 +105,7 @@ impl tokio::io::AsyncRead for ProxyStream {
         match self.get_mut() {
             ProxyStream::Http(s) => Pin::new(s).poll_read(cx, buf),
             ProxyStream::Https(s) => Pin::new(s).poll_read(cx, buf),
+            ProxyStream::Forward(s) => Pin::new(s).poll_read(cx, buf),
         }
     }
 }
@@ -111,6 +119,7 @@ impl tokio::io::AsyncWrite for ProxyStream {
         match self.get_mut() {
             ProxyStream::Http(s) => Pin::new(s).poll_write(cx, buf),
             ProxyStream::Https(s) => Pin::new(s).poll_write(cx, buf),
+            ProxyStream::Forward(s) => Pin::new(s).poll_write(cx, buf),
         }
     }
 
@@ -118,6 +127,7 @@ impl tokio::io::AsyncWrite for ProxyStream {
         match self.get_mut() {
             ProxyStream::Http(s) => Pin::new(s).poll_flush(cx),
             ProxyStream::Https(s) => Pin::new(s).poll_flush(cx),
+            ProxyStream::Forward(s) => Pin::new(s).poll_flush(cx),
         }
     }
 
@@ -125,6 +135,7 @@ impl tokio::io::AsyncWrite for ProxyStream {
         match self.get_mut() {
             ProxyStream::Http(s) => Pin::new(s).poll_shutdown(cx),
             ProxyStream::Https(s) => Pin::new(s).poll_shutdown(cx),
+            ProxyStream::Forward(s) => Pin::new(s).poll_shutdown(cx),
         }
     }
 }
@@ -176,24 +187,26 @@ impl hyper::rt::Write for ProxyStream {
 
 impl hyper_util::client::legacy::connect::Connection for ProxyStream {
     fn connected(&self) -> hyper_util::client::legacy::connect::Connected {
-        hyper_util::client::legacy::connect::Connected::new()
+        let connected = hyper_util::client::legacy::connect::Connected::new();
+        match self {
+            ProxyStream::Forward(_) => connected.proxy(true),
+            _ => connected,
+        }
     }
 }
 
 /// 自定义 Proxy Connector，用于处理 upstream proxy 连接
-/// 替代 hyper-proxy2，提供更稳定的 CONNECT 隧道处理
+/// 替代 hyper-proxy2，提供更稳定的 CONNECT 隧道处理；按顺序经代理链中的每一跳建立隧道
 #[derive(Clone)]
 pub struct CustomProxyConnector {
-    proxy_host: String,
-    proxy_port: u16,
+    hops: Arc<Vec<UpstreamProxyHop>>,
     tls_connector: tokio_rustls::TlsConnector,
 }
 
 impl CustomProxyConnector {
-    pub fn new(host: String, port: u16, tls_config: Arc<rustls::ClientConfig>) -> Self {
+    pub fn new(hops: Vec<UpstreamProxyHop>, tls_config: Arc<rustls::ClientConfig>) -> Self {
         Self {
-            proxy_host: host,
-            proxy_port: port,
+            hops: Arc::new(hops),
             tls_connector: tokio_rustls::TlsConnector::from(tls_config),
         }
     }
@@ -210,23 +223,16 @@ impl Service<hyper::Uri> for CustomProxyConnector {
     }
 
     fn call(&mut self, dst: hyper::Uri) -> Self::Future {
-        let proxy_host = self.proxy_host.clone();
-        let proxy_port = self.proxy_port;
+        let hops = self.hops.clone();
         let tls_connector = self.tls_connector.clone();
 
         Box::pin(async move {
-            debug!(
-                "CustomProxyConnector: connecting to proxy {}:{}",
-                proxy_host, proxy_port
-            );
-
-            // 1. 连接到 Upstream Proxy
-            let mut stream = tokio::net::TcpStream::connect((proxy_host.as_str(), proxy_port))
-                .await
-                .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
-
-            // 2. 决定是否使用 CONNECT
-            let host = dst.host().unwrap_or("").to_string();
+            let host = dst
+                .host()
+                .unwrap_or("")
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_string();
             let port = dst
                 .port_u16()
                 .unwrap_or(if dst.scheme_str() == Some("https") {
@@ -237,42 +243,40 @@ impl Service<hyper::Uri> for CustomProxyConnector {
 
             let is_https = dst.scheme_str() == Some("https") || port == 443;
 
-            if is_https {
-                debug!("CustomProxyConnector: creating tunnel to {}:{}", host, port);
-                let connect_req = format!(
-                    "CONNECT {}:{} HTTP/1.1\r\nHost: {}:{}\r\nProxy-Connection: Keep-Alive\r\n\r\n",
-                    host, port, host, port
-                );
-
-                stream
-                    .write_all(connect_req.as_bytes())
-                    .await
-                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
-
-                // 逐字节读取响应头，避免多读
-                let mut header = Vec::new();
-                loop {
-                    let b = stream
-                        .read_u8()
-                        .await
-                        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
-                    header.push(b);
-
-                    if header.len() > 4096 {
-                        return Err("Proxy response too large".into());
-                    }
-
-                    if header.ends_with(b"\r\n\r\n") {
-                        let response = String::from_utf8_lossy(&header);
-                        if !response.contains(" 200 ") {
-                            return Err(format!("Proxy connect failed: {}", response).into());
-                        }
-                        debug!("CustomProxyConnector: tunnel established");
-                        break;
-                    }
+            // 单个 HTTP 代理时明文请求直接转发给代理，不走 CONNECT
+            if !is_https {
+                if let Some(hop) = upstream_chain::plain_http_forward_hop(&hops) {
+                    debug!(
+                        "CustomProxyConnector: forwarding {}:{} via proxy {}",
+                        host,
+                        port,
+                        hop.address()
+                    );
+                    let stream = upstream_chain::connect_first_hop(hop).await.map_err(|e| {
+                        warn!("CustomProxyConnector: {}", e);
+                        Box::new(e) as Box<dyn std::error::Error + Send + Sync>
+                    })?;
+                    return Ok(ProxyStream::Forward(stream));
                 }
+            }
 
-                // 3. 建立 TLS 连接
+            // 1. 经代理链建立到目标的隧道
+            debug!(
+                "CustomProxyConnector: creating tunnel to {}:{} through {} hop(s)",
+                host,
+                port,
+                hops.len()
+            );
+            let stream = upstream_chain::connect_through_chain(&hops, &host, port)
+                .await
+                .map_err(|e| {
+                    warn!("CustomProxyConnector: {}", e);
+                    Box::new(e) as Box<dyn std::error::Error + Send + Sync>
+                })?;
+            debug!("CustomProxyConnector: tunnel established");
+
+            if is_https {
+                // 2. 建立 TLS 连接
                 let domain = ServerName::try_from(host.as_str())
                     .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
                 let tls_stream = tls_connector
@@ -288,6 +292,60 @@ impl Service<hyper::Uri> for CustomProxyConnector {
     }
 }
 
+/// 为直接转发给上游 HTTP 代理的明文请求附加 `Proxy-Authorization`
+///
+/// 在内部处理器之后插入，代理凭据不会进入流量历史和插件
+#[derive(Clone)]
+struct UpstreamProxyAuthHandler {
+    inner: TrafficProxyHandler,
+    proxy_authorization: Option<hyper::header::HeaderValue>,
+}
+
+impl UpstreamProxyAuthHandler {
+    fn new(inner: TrafficProxyHandler, upstream_config: &UpstreamProxyConfig) -> Self {
+        let proxy_authorization = upstream_chain::plain_http_forward_hop(&upstream_config.hops())
+            .and_then(UpstreamProxyHop::proxy_authorization)
+            .and_then(|value| hyper::header::HeaderValue::from_str(&value).ok());
+        Self {
+            inner,
+            proxy_authorization,
+        }
+    }
+}
+
+impl HttpHandler for UpstreamProxyAuthHandler {
+    async fn handle_request(&mut self, ctx: &HttpContext, req: Request<Body>) -> RequestOrResponse {
+        match self.inner.handle_request(ctx, req).await {
+            RequestOrResponse::Request(mut req) => {
+                if let Some(value) = &self.proxy_authorization {
+                    if req.uri().scheme_str() == Some("http") {
+                        req.headers_mut()
+                            .insert(hyper::header::PROXY_AUTHORIZATION, value.clone());
+                    }
+                }
+                RequestOrResponse::Request(req)
+            }
+            response => response,
+        }
+    }
+
+    async fn handle_response(&mut self, ctx: &HttpContext, res: Response<Body>) -> Response<Body> {
+        self.inner.handle_response(ctx, res).await
+    }
+
+    async fn handle_error(
+        &mut self,
+        ctx: &HttpContext,
+        err: hyper_util::client::legacy::Error,
+    ) -> Response<Body> {
+        self.inner.handle_error(ctx, err).await
+    }
+
+    async fn should_intercept(&mut self, ctx: &HttpContext, req: &Request<Body>) -> bool {
+        self.inner.should_intercept(ctx, req).await
+    }
+}
+
 /// 创建忽略证书验证的 rustls ClientConfig
 /// 支持弱加密套件和旧版本 TLS，以便抓取证书异常的站点
 /// 创建忽略证书验证的 rustls 配置（不含 ALPN，用于 HttpsConnectorBuilder）
@@ -318,23 +376,19 @@ fn create_insecure_rustls_config_with_alpn() -> rustls::ClientConfig {
 fn create_upstream_proxy_connector(
     upstream_config: &UpstreamProxyConfig,
 ) -> Result<CustomProxyConnector> {
+    let hops = upstream_config.hops();
     info!(
-        "Creating upstream proxy connector: host={}, port={}, auth_type={}",
-        upstream_config.proxy_host, upstream_config.proxy_port, upstream_config.auth_type
+        "Creating upstream proxy connector: chain=[{}]",
+        hops.iter()
+            .map(|hop| format!("{:?} {}", hop.kind, hop.address()))
+            .collect::<Vec<_>>()
+            .join(" -> ")
     );
+    upstream_chain::validate_chain(&hops)?;
 
     // 使用带 ALPN 的配置，因为 CustomProxyConnector 使用 tokio-rustls
     let rustls_config = create_insecure_rustls_config_with_alpn();
-    let proxy_connector = CustomProxyConnector::new(
-        upstream_config.proxy_host.clone(),
-        upstream_config.proxy_port,
-        Arc::new(rustls_config),
-    );
-
-    // TODO: Basic 认证支持将在后续版本实现
-    if upstream_config.auth_type == "Basic" {
-        warn!("Basic authentication for upstream proxy is not yet implemented in CustomProxyConnector");
-    }
+    let proxy_connector = CustomProxyConnector::new(hops, Arc::new(rustls_config));
 
     info!("Upstream proxy connector created successfully");
     Ok(proxy_connector)
@@ -429,6 +483,84 @@ pub struct InterceptFilterRule {
     pub relationship: String,
     /// 匹配条件（正则表达式）
     pub condition: String,
+    /// 限定请求方法（逗号分隔，如 "POST,PUT"；为空表示任意）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// 限定请求 Content-Type（逗号分隔，支持 `application/*`；为空表示任意）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_content_type: Option<String>,
+    /// 限定响应 Content-Type（仅在响应阶段生效；为空表示任意）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_content_type: Option<String>,
+}
+
+impl InterceptFilterRule {
+    /// 是否设置了方法或 Content-Type 条件
+    pub fn has_predicates(&self) -> bool {
+        [
+            &self.method,
+            &self.request_content_type,
+            &self.response_content_type,
+        ]
+        .iter()
+        .any(|p| Self::predicate(p).is_some())
+    }
+
+    /// 组合主条件与方法/Content-Type 条件，所有已设置的条件都满足才算命中
+    ///
+    /// `response_content_type` 为 None 表示当前阶段无法判断（请求阶段），此时忽略该条件。
+    /// 仅设置了附加条件而主条件为空的规则，主条件视为匹配任意值。
+    pub fn combined_match(
+        &self,
+        condition_matches: bool,
+        method: &str,
+        request_content_type: Option<&str>,
+        response_content_type: Option<&str>,
+    ) -> bool {
+        let condition_matches =
+            condition_matches || (self.condition.is_empty() && self.has_predicates());
+        if !condition_matches {
+            return false;
+        }
+
+        let method_ok = Self::predicate(&self.method).is_none_or(|methods| {
+            methods
+                .split(',')
+                .map(str::trim)
+                .any(|m| m == "*" || m.eq_ignore_ascii_case(method))
+        });
+        let request_ct_ok = Self::predicate(&self.request_content_type)
+            .is_none_or(|p| Self::content_type_matches(p, request_content_type.unwrap_or("")));
+        let response_ct_ok = match (
+            Self::predicate(&self.response_content_type),
+            response_content_type,
+        ) {
+            (Some(p), Some(actual)) => Self::content_type_matches(p, actual),
+            _ => true,
+        };
+        method_ok && request_ct_ok && response_ct_ok
+    }
+
+    fn predicate(value: &Option<String>) -> Option<&str> {
+        value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+    }
+
+    /// Content-Type 匹配：忽略参数（如 charset）和大小写，支持 `type/*` 通配
+    fn content_type_matches(patterns: &str, actual: &str) -> bool {
+        let essence = actual
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+        patterns.split(',').any(|pattern| {
+            let pattern = pattern.trim().to_ascii_lowercase();
+            match pattern.strip_suffix('*') {
+                Some(prefix) => !essence.is_empty() && essence.starts_with(prefix),
+                None => !pattern.is_empty() && essence == pattern,
+            }
+        })
+    }
 }
 
 /// 拦截状态（共享）
@@ -454,7 +586,7 @@ pub struct InterceptState {
 }
 
 /// Upstream proxy 配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 pub struct UpstreamProxyConfig {
     /// 是否启用 upstream proxy
     #[serde(default)]
@@ -477,12 +609,56 @@ pub struct UpstreamProxyConfig {
     /// 密码（可选）
     #[serde(default)]
     pub password: Option<String>,
+    /// 代理链（按顺序经过每一跳）；非空时取代上面的单个代理
+    #[serde(default)]
+    pub chain: Vec<UpstreamProxyHop>,
 }
 
 fn default_destination_host() -> String {
     "*".to_string()
 }
 
+impl std::fmt::Debug for UpstreamProxyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出密码明文
+        f.debug_struct("UpstreamProxyConfig")
+            .field("enabled", &self.enabled)
+            .field("destination_host", &self.destination_host)
+            .field("proxy_host", &self.proxy_host)
+            .field("proxy_port", &self.proxy_port)
+            .field("auth_type", &self.auth_type)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .field("chain", &self.chain)
+            .finish()
+    }
+}
+
+impl UpstreamProxyConfig {
+    /// 实际使用的代理链：配置了 `chain` 时使用它，否则为单个 HTTP 代理
+    pub fn hops(&self) -> Vec<UpstreamProxyHop> {
+        if !self.chain.is_empty() {
+            return self.chain.clone();
+        }
+        vec![UpstreamProxyHop {
+            kind: UpstreamProxyKind::Http,
+            host: self.proxy_host.clone(),
+            port: self.proxy_port,
+            auth_type: self.auth_type.clone(),
+            username: self.username.clone(),
+            password: self.password.clone(),
+        }]
+    }
+
+    /// 校验配置（未启用时不校验）
+    pub fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        upstream_chain::validate_chain(&self.hops())
+    }
+}
+
 /// 代理配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProxyConfig {
@@ -492,9 +668,9 @@ pub struct ProxyConfig {
     pub max_port_attempts: u16,
     /// HTTPS MITM 是否启用（默认 true）
     pub mitm_enabled: bool,
-    /// 请求体大小限制（字节，默认 2MB）
+    /// 请求体大小限制（字节，默认 2MB），超过后不再缓冲、直接流式转发，插件只收到截断的 body
     pub max_request_body_size: usize,
-    /// 响应体大小限制（字节，默认 2MB）
+    /// 响应体大小限制（字节，默认 2MB），超过后不再缓冲、直接流式转发，插件只收到截断的 body
     pub max_response_body_size: usize,
     /// 对同一域名发生握手/证书错误的次数阈值，超过后自动绕过 MITM
     #[serde(default = "default_bypass_threshold")]
@@ -505,6 +681,9 @@ pub struct ProxyConfig {
     /// 是否排除本应用流量的扫描（默认 true）
     #[serde(default = "default_exclude_self_traffic")]
     pub exclude_self_traffic: bool,
+    /// 按主机指定的额外根 CA，未匹配的主机使用默认 Root CA
+    #[serde(default)]
+    pub host_cas: Vec<HostCaConfig>,
 }
 
 fn default_bypass_threshold() -> u32 {
@@ -526,6 +705,7 @@ impl Default for ProxyConfig {
             mitm_bypass_fail_threshold: 3,
             upstream_proxy: None,
             exclude_self_traffic: true,
+            host_cas: Vec::new(),
         }
     }
 }
@@ -585,6 +765,10 @@ pub struct TrafficProxyHandler {
     /// 当前请求 ID（用于匹配 handle_request/handle_response）
     /// 注意：必须是“每个 clone 独立”的状态，不能用 Arc 共享。
     current_request_id: std::sync::Mutex<Option<String>>,
+    /// 匹配替换规则
+    match_replace_rules: SharedMatchReplaceRules,
+    /// 当前请求的目标主机（用于响应阶段的规则作用范围，同样每个 clone 独立）
+    current_host: std::sync::Mutex<Option<String>>,
 }
 
 impl Clone for TrafficProxyHandler {
@@ -602,6 +786,8 @@ impl Clone for TrafficProxyHandler {
             intercept_state: self.intercept_state.clone(),
             // 每个 clone 新建一份独立的 request_id 槽位，避免并发覆盖
             current_request_id: std::sync::Mutex::new(None),
+            match_replace_rules: self.match_replace_rules.clone(),
+            current_host: std::sync::Mutex::new(None),
         }
     }
 }
@@ -620,6 +806,8 @@ impl TrafficProxyHandler {
             ws_message_counters: Arc::new(RwLock::new(HashMap::new())),
             intercept_state: None,
             current_request_id: std::sync::Mutex::new(None),
+            match_replace_rules: Arc::new(RwLock::new(Vec::new())),
+            current_host: std::sync::Mutex::new(None),
         }
     }
 
@@ -641,9 +829,17 @@ impl TrafficProxyHandler {
             ws_message_counters: Arc::new(RwLock::new(HashMap::new())),
             intercept_state: Some(intercept_state),
             current_request_id: std::sync::Mutex::new(None),
+            match_replace_rules: Arc::new(RwLock::new(Vec::new())),
+            current_host: std::sync::Mutex::new(None),
         }
     }
 
+    /// 设置匹配替换规则
+    pub fn with_match_replace_rules(mut self, rules: SharedMatchReplaceRules) -> Self {
+        self.match_replace_rules = rules;
+        self
+    }
+
     pub fn stats(&self) -> Arc<RwLock<ProxyStats>> {
         self.stats.clone()
     }
@@ -745,9 +941,10 @@ impl TrafficProxyHandler {
         intercept_state: &InterceptState,
         url: &str,
         method: &str,
-        _headers: &HashMap<String, String>,
+        headers: &HashMap<String, String>,
     ) -> bool {
         let rules = intercept_state.request_filter_rules.read().await;
+        let request_content_type = Self::header_value(headers, "content-type").unwrap_or("");
         if rules.is_empty() {
             return true; // No rules, intercept all
         }
@@ -778,19 +975,12 @@ impl TrafficProxyHandler {
                 _ => continue,
             };
 
-            let matches = if rule.condition.is_empty() {
-                false
-            } else {
-                match regex::Regex::new(&rule.condition) {
-                    Ok(re) => re.is_match(value_to_match),
-                    Err(_) => {
-                        // Fallback to simple contains check
-                        value_to_match
-                            .to_lowercase()
-                            .contains(&rule.condition.to_lowercase())
-                    }
-                }
-            };
+            let matches = rule.combined_match(
+                Self::condition_matches(&rule.condition, value_to_match),
+                method,
+                Some(request_content_type),
+                None,
+            );
 
             // "does_not_match" means: if condition matches, skip interception
             if rule.relationship == "does_not_match" && matches {
@@ -814,6 +1004,87 @@ impl TrafficProxyHandler {
         true
     }
 
+    /// Check if a response should be intercepted based on response filter rules
+    async fn should_intercept_response(
+        intercept_state: &InterceptState,
+        req_ctx: &RequestContext,
+        status: u16,
+        headers: &HashMap<String, String>,
+    ) -> bool {
+        let rules = intercept_state.response_filter_rules.read().await;
+        if rules.is_empty() {
+            return true;
+        }
+
+        let url = req_ctx.url.as_str();
+        let domain = url
+            .split("://")
+            .nth(1)
+            .and_then(|s| s.split('/').next())
+            .and_then(|s| s.split(':').next())
+            .unwrap_or("");
+        let path = url.split('?').next().unwrap_or(url);
+        let file_ext = path.rsplit('.').next().unwrap_or("");
+        let status = status.to_string();
+        let request_content_type =
+            Self::header_value(&req_ctx.headers, "content-type").unwrap_or("");
+        let response_content_type = Self::header_value(headers, "content-type").unwrap_or("");
+
+        for rule in rules.iter() {
+            if !rule.enabled {
+                continue;
+            }
+
+            let value_to_match = match rule.match_type.as_str() {
+                "domain_name" => domain,
+                "url" => url,
+                "http_method" => req_ctx.method.as_str(),
+                "file_extension" => file_ext,
+                "status_code" | "status" => status.as_str(),
+                "content_type" | "contentType" => response_content_type,
+                _ => continue,
+            };
+
+            let matches = rule.combined_match(
+                Self::condition_matches(&rule.condition, value_to_match),
+                &req_ctx.method,
+                Some(request_content_type),
+                Some(response_content_type),
+            );
+
+            if (rule.relationship == "does_not_match" && matches)
+                || (rule.relationship == "matches" && !matches)
+            {
+                debug!(
+                    "Response for {} skipped by filter rule: {} {} {}",
+                    url, rule.match_type, rule.relationship, rule.condition
+                );
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// 主条件匹配：正则，非法正则时退化为不区分大小写的包含匹配
+    fn condition_matches(condition: &str, value: &str) -> bool {
+        if condition.is_empty() {
+            return false;
+        }
+        match regex::Regex::new(condition) {
+            Ok(re) => re.is_match(value),
+            Err(_) => value.to_lowercase().contains(&condition.to_lowercase()),
+        }
+    }
+
+    /// 不区分大小写读取头部
+    fn header_value<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+        headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
     /// 解析修改后的请求内容并重建 HTTP 请求
     /// 格式: METHOD PATH PROTOCOL\nHeader1: Value1\n...\n\nBODY
     fn parse_and_rebuild_request(
@@ -1106,26 +1377,25 @@ impl TrafficProxyHandler {
         // 读取 body 并创建新的 body 用于转发
         let (parts, body) = req.into_parts();
 
-        // 收集 body 数据
-        let body_bytes = match body.collect().await {
-            Ok(collected) => collected.to_bytes(),
+        // 按大小上限读取 body，超过上限的部分不缓冲，直接流式转发
+        let max_size = self.config.max_request_body_size;
+        let capped = match read_body_capped(body, max_size).await {
+            Ok(capped) => capped,
             Err(e) => {
                 warn!("Failed to read request body for {}: {}", url, e);
-                Bytes::new()
+                CappedBody::Complete(Bytes::new())
             }
         };
-
-        // 检查大小限制
-        let body_vec = if body_bytes.len() > self.config.max_request_body_size {
+        let (body_vec, body_truncated) = capped.captured(max_size);
+        let body_size = if body_truncated {
+            let size = Self::content_length(&parts.headers);
             warn!(
-                "Request body too large ({} bytes), truncating to {} bytes for {}",
-                body_bytes.len(),
-                self.config.max_request_body_size,
-                url
+                "Request body exceeds {} bytes (content-length: {:?}), streaming without buffering for {}",
+                max_size, size, url
             );
-            body_bytes[..self.config.max_request_body_size].to_vec()
+            size
         } else {
-            body_bytes.to_vec()
+            None
         };
 
         debug!(
@@ -1136,9 +1406,7 @@ impl TrafficProxyHandler {
         );
 
         // 创建新的请求用于转发（包含原始 body）
-        // hudsucker::Body 实现了 From<Full<Bytes>>
-        let new_body = Body::from(Full::new(body_bytes.clone()));
-        let new_req = Request::from_parts(parts, new_body);
+        let new_req = Request::from_parts(parts, capped.into_forward_body(None));
 
         let req_ctx = RequestContext {
             id,
@@ -1155,17 +1423,25 @@ impl TrafficProxyHandler {
             edited_url: None,
             edited_headers: None,
             edited_body: None,
+            body_truncated,
+            body_size,
         };
 
         Ok((req_ctx, new_req))
     }
 
     /// 从 Hyper Response 构建 ResponseContext（读取 body）
+    ///
+    /// 响应体超过大小上限时不再缓冲，返回的接收端在转发结束后给出实际响应体大小。
     async fn build_response_context(
         &self,
         request_id: String,
         res: Response<Body>,
-    ) -> Result<(ResponseContext, Response<Body>)> {
+    ) -> Result<(
+        ResponseContext,
+        Response<Body>,
+        Option<oneshot::Receiver<u64>>,
+    )> {
         // 提取状态码
         let status = res.status().as_u16();
 
@@ -1189,40 +1465,38 @@ impl TrafficProxyHandler {
         // 读取 body 并创建新的 body 用于转发
         let (parts, body) = res.into_parts();
 
-        // 收集 body 数据
-        let body_bytes = match body.collect().await {
-            Ok(collected) => collected.to_bytes(),
+        // 按大小上限读取 body，超过上限的部分不缓冲，直接流式转发
+        let max_size = self.config.max_response_body_size;
+        let capped = match read_body_capped(body, max_size).await {
+            Ok(capped) => capped,
             Err(e) => {
                 warn!(
                     "Failed to read response body for request {}: {}",
                     request_id, e
                 );
-                Bytes::new()
+                CappedBody::Complete(Bytes::new())
             }
         };
-
-        // 检查大小限制（对压缩后的数据）
-        let compressed_body_vec = if body_bytes.len() > self.config.max_response_body_size {
+        let (compressed_body_vec, body_truncated) = capped.captured(max_size);
+        if body_truncated {
             warn!(
-                "Response body too large ({} bytes), truncating to {} bytes for request {}",
-                body_bytes.len(),
-                self.config.max_response_body_size,
-                request_id
+                "Response body exceeds {} bytes, streaming without buffering for request {}",
+                max_size, request_id
             );
-            body_bytes[..self.config.max_response_body_size].to_vec()
-        } else {
-            body_bytes.to_vec()
-        };
+        }
 
         // 解压响应体（如果有压缩）
-        let (decompressed_body, decompress_success) = if content_encoding.is_some() {
-            debug!(
-                "Detected content encoding: {:?}, attempting decompression for request {}",
-                content_encoding, request_id
-            );
-            Self::decompress_body(&compressed_body_vec, content_encoding)
-        } else {
-            (compressed_body_vec.clone(), true)
+        let (decompressed_body, decompress_success) = match content_encoding {
+            // 截断的压缩数据无法解压，插件收到空 body
+            Some(_) if body_truncated => (Vec::new(), true),
+            Some(_) => {
+                debug!(
+                    "Detected content encoding: {:?}, attempting decompression for request {}",
+                    content_encoding, request_id
+                );
+                Self::decompress_body(&compressed_body_vec, content_encoding)
+            }
+            None => (compressed_body_vec.clone(), true),
         };
 
         // 如果解压失败，记录警告并跳过此响应的扫描
@@ -1256,9 +1530,18 @@ impl TrafficProxyHandler {
         );
 
         // 创建新的响应用于转发（使用压缩后的原始数据，保持原样转发）
-        // hudsucker::Body 实现了 From<Full<Bytes>>
-        let new_body = Body::from(Full::new(body_bytes.clone()));
-        let new_res = Response::from_parts(parts, new_body);
+        let body_size = Self::content_length(&parts.headers);
+        let (size_tx, size_rx) = if body_truncated {
+            let (tx, rx) = oneshot::channel();
+            (Some(tx), Some(rx))
+        } else {
+            (None, None)
+        };
+        let new_res = Response::from_parts(parts, capped.into_forward_body(size_tx));
+
+        // 解压成功时同时保留原始的压缩数据，供历史记录重放
+        let raw_body = (content_encoding.is_some() && !body_truncated && decompress_success)
+            .then_some(compressed_body_vec);
 
         // 但保存到数据库和扫描器的是解压后的数据
         let resp_ctx = ResponseContext {
@@ -1272,9 +1555,20 @@ impl TrafficProxyHandler {
             edited_status: None,
             edited_headers: None,
             edited_body: None,
+            body_truncated,
+            body_size: if body_truncated { body_size } else { None },
+            raw_body,
         };
 
-        Ok((resp_ctx, new_res))
+        Ok((resp_ctx, new_res, size_rx))
+    }
+
+    /// 读取 Content-Length 头
+    fn content_length(headers: &hyper::HeaderMap) -> Option<u64> {
+        headers
+            .get(hyper::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
     }
 
     /// 检查响应是否为流式类型（SSE 或分块传输）
@@ -1303,6 +1597,138 @@ impl TrafficProxyHandler {
         false
     }
 
+    /// 更新改写后的 Content-Length
+    fn set_content_length(headers: &mut hyper::HeaderMap, len: usize) {
+        if headers.contains_key(hyper::header::CONTENT_LENGTH) {
+            headers.insert(
+                hyper::header::CONTENT_LENGTH,
+                hyper::header::HeaderValue::from(len),
+            );
+        }
+    }
+
+    /// 应用请求方向的匹配替换规则
+    async fn apply_request_match_replace(
+        &self,
+        req: Request<Body>,
+        host: Option<&str>,
+    ) -> Request<Body> {
+        let rules = self.match_replace_rules.read().await;
+        let body_rules = has_rules(&rules, MatchReplaceTarget::RequestBody, host);
+        if !body_rules && !has_rules(&rules, MatchReplaceTarget::RequestHeader, host) {
+            return req;
+        }
+
+        let (mut parts, body) = req.into_parts();
+        if rewrite_headers(
+            &rules,
+            MatchReplaceTarget::RequestHeader,
+            host,
+            &mut parts.headers,
+        ) {
+            debug!(
+                "Match-and-replace rewrote request headers for {}",
+                parts.uri
+            );
+        }
+        if !body_rules {
+            return Request::from_parts(parts, body);
+        }
+
+        let body_bytes = match read_body_capped(body, self.config.max_request_body_size).await {
+            Ok(CappedBody::Complete(bytes)) => bytes,
+            Ok(overflow) => {
+                debug!(
+                    "Skipping request body match-and-replace for {} (body over size limit)",
+                    parts.uri
+                );
+                return Request::from_parts(parts, overflow.into_forward_body(None));
+            }
+            Err(e) => {
+                warn!("Failed to read request body for match-and-replace: {}", e);
+                Bytes::new()
+            }
+        };
+        let body_bytes =
+            match rewrite_body(&rules, MatchReplaceTarget::RequestBody, host, &body_bytes) {
+                Some(rewritten) => {
+                    debug!("Match-and-replace rewrote request body for {}", parts.uri);
+                    Self::set_content_length(&mut parts.headers, rewritten.len());
+                    Bytes::from(rewritten)
+                }
+                None => body_bytes,
+            };
+        Request::from_parts(parts, Body::from(Full::new(body_bytes)))
+    }
+
+    /// 应用响应方向的匹配替换规则
+    ///
+    /// 压缩或流式响应只改写头部，正文保持原样转发。
+    async fn apply_response_match_replace(&self, res: Response<Body>) -> Response<Body> {
+        let host = self.current_host.lock().ok().and_then(|g| g.clone());
+        let host = host.as_deref();
+        let rules = self.match_replace_rules.read().await;
+        let body_rules = has_rules(&rules, MatchReplaceTarget::ResponseBody, host);
+        if !body_rules && !has_rules(&rules, MatchReplaceTarget::ResponseHeader, host) {
+            return res;
+        }
+
+        let (mut parts, body) = res.into_parts();
+        if rewrite_headers(
+            &rules,
+            MatchReplaceTarget::ResponseHeader,
+            host,
+            &mut parts.headers,
+        ) {
+            debug!("Match-and-replace rewrote response headers for {:?}", host);
+        }
+
+        let header_map: HashMap<String, String> = parts
+            .headers
+            .iter()
+            .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), v.to_string())))
+            .collect();
+        let encoded = parts
+            .headers
+            .get(hyper::header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| !v.eq_ignore_ascii_case("identity"));
+        if !body_rules || encoded || Self::is_streaming_response(&header_map) {
+            if body_rules {
+                debug!(
+                    "Skipping response body match-and-replace for {:?} (encoded or streaming)",
+                    host
+                );
+            }
+            return Response::from_parts(parts, body);
+        }
+
+        let body_bytes = match read_body_capped(body, self.config.max_response_body_size).await {
+            Ok(CappedBody::Complete(bytes)) => bytes,
+            Ok(overflow) => {
+                debug!(
+                    "Skipping response body match-and-replace for {:?} (body over size limit)",
+                    host
+                );
+                return Response::from_parts(parts, overflow.into_forward_body(None));
+            }
+            Err(e) => {
+                warn!("Failed to read response body for match-and-replace: {}", e);
+                Bytes::new()
+            }
+        };
+        let body_bytes =
+            match rewrite_body(&rules, MatchReplaceTarget::ResponseBody, host, &body_bytes) {
+                Some(rewritten) => {
+                    debug!("Match-and-replace rewrote response body for {:?}", host);
+                    Self::set_content_length(&mut parts.headers, rewritten.len());
+                    Bytes::from(rewritten)
+                }
+                None => body_bytes,
+            };
+        Response::from_parts(parts, Body::from(Full::new(body_bytes)))
+    }
+
     /// 构建流式响应上下文（用于 SSE 等）
     ///
     /// 与 build_response_context 不同，此方法：
@@ -1388,6 +1814,9 @@ impl TrafficProxyHandler {
                     edited_status: None,
                     edited_headers: None,
                     edited_body: None,
+                    body_truncated: truncated,
+                    body_size: None,
+                    raw_body: None,
                 };
 
                 if let Err(e) = tx.send(ScanTask::Response(resp_ctx)) {
@@ -1531,11 +1960,22 @@ impl HttpHandler for TrafficProxyHandler {
                     .map(|s| s.split(':').next().unwrap_or(s).to_string())
             })
         };
-        if let Some(host) = host_for_mapping {
+        if let Some(host) = host_for_mapping.clone() {
             let mut map = self.conn_to_host.write().await;
             map.insert(conn_key, host);
         }
 
+        // 应用匹配替换规则（CONNECT 隧道本身不改写）
+        let req = if is_https {
+            req
+        } else {
+            if let Ok(mut guard) = self.current_host.lock() {
+                *guard = host_for_mapping.clone();
+            }
+            self.apply_request_match_replace(req, host_for_mapping.as_deref())
+                .await
+        };
+
         // 构建上下文并发送到扫描器
         if let Some(tx) = &self.scan_tx {
             match self.build_request_context(ctx, req).await {
@@ -1889,6 +2329,9 @@ impl HttpHandler for TrafficProxyHandler {
             status, conn_key
         );
 
+        // 应用匹配替换规则
+        let res = self.apply_response_match_replace(res).await;
+
         // 提取响应头用于流式检测
         let mut response_headers = std::collections::HashMap::new();
         for (name, value) in res.headers().iter() {
@@ -1913,7 +2356,7 @@ impl HttpHandler for TrafficProxyHandler {
                     request_map.get(&request_id).cloned()
                 };
 
-                if let Some(_req_ctx) = req_ctx_opt {
+                if let Some(req_ctx) = req_ctx_opt {
                     // 流式响应：使用 Tee 机制同时转发和收集
                     if is_streaming {
                         info!(
@@ -1951,7 +2394,7 @@ impl HttpHandler for TrafficProxyHandler {
 
                     // 非流式响应：使用原有的全量缓冲逻辑
                     match self.build_response_context(request_id.clone(), res).await {
-                        Ok((mut resp_ctx, new_res)) => {
+                        Ok((mut resp_ctx, new_res, size_rx)) => {
                             debug!(
                                 "Response captured: request_id={}, status={}, body_size={}, conn_key={}",
                                 request_id,
@@ -1964,7 +2407,14 @@ impl HttpHandler for TrafficProxyHandler {
                             let mut final_response = new_res;
                             if let Some(intercept_state) = &self.intercept_state {
                                 let response_intercept_enabled =
-                                    *intercept_state.response_enabled.read().await;
+                                    *intercept_state.response_enabled.read().await
+                                        && Self::should_intercept_response(
+                                            intercept_state,
+                                            &req_ctx,
+                                            resp_ctx.status,
+                                            &resp_ctx.headers,
+                                        )
+                                        .await;
 
                                 if response_intercept_enabled {
                                     if let Some(pending_tx) = &intercept_state.pending_response_tx {
@@ -2082,7 +2532,18 @@ impl HttpHandler for TrafficProxyHandler {
 
                             // 始终发送到扫描器（用于保存历史记录）
                             // 扫描器会根据配置决定是否进行插件扫描
-                            if let Err(e) = tx.send(ScanTask::Response(resp_ctx)) {
+                            if let Some(size_rx) = size_rx {
+                                // 超过上限的响应体在转发结束后才知道实际大小
+                                let tx = tx.clone();
+                                tokio::spawn(async move {
+                                    if let Ok(size) = size_rx.await {
+                                        resp_ctx.body_size = Some(size);
+                                    }
+                                    if let Err(e) = tx.send(ScanTask::Response(resp_ctx)) {
+                                        warn!("Failed to send response to scanner: {}", e);
+                                    }
+                                });
+                            } else if let Err(e) = tx.send(ScanTask::Response(resp_ctx)) {
                                 warn!("Failed to send response to scanner: {}", e);
                             }
 
@@ -2492,6 +2953,8 @@ pub struct ProxyService {
     actual_port: Arc<RwLock<Option<u16>>>,
     ca_dir: std::path::PathBuf,
     intercept_state: Option<InterceptState>,
+    /// 匹配替换规则（与命令层共享，修改后即时生效）
+    match_replace_rules: SharedMatchReplaceRules,
 }
 
 impl ProxyService {
@@ -2508,6 +2971,7 @@ impl ProxyService {
             actual_port: Arc::new(RwLock::new(None)),
             ca_dir,
             intercept_state: None,
+            match_replace_rules: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
@@ -2520,6 +2984,7 @@ impl ProxyService {
             actual_port: Arc::new(RwLock::new(None)),
             ca_dir,
             intercept_state: None,
+            match_replace_rules: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
@@ -2536,9 +3001,16 @@ impl ProxyService {
             actual_port: Arc::new(RwLock::new(None)),
             ca_dir,
             intercept_state: Some(intercept_state),
+            match_replace_rules: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
+    /// 设置匹配替换规则（传入共享引用以支持热更新）
+    pub fn with_match_replace_rules(mut self, rules: SharedMatchReplaceRules) -> Self {
+        self.match_replace_rules = rules;
+        self
+    }
+
     /// 启动代理服务（端口自动递增）
     pub async fn start(&self, scan_tx: Option<ScanSender>) -> Result<u16> {
         // 检查是否已启动
@@ -2624,7 +3096,7 @@ impl ProxyService {
         }
 
         // 获取 CA authority（使用完整证书链版本）
-        let ca = ca_service.get_chained_ca()?;
+        let ca = ca_service.get_chained_ca_with_host_cas(&self.config.host_cas)?;
 
         // 创建处理器（如果有拦截状态，则使用支持拦截的构造器）
         let handler = if let Some(intercept_state) = &self.intercept_state {
@@ -2635,7 +3107,8 @@ impl ProxyService {
             )
         } else {
             TrafficProxyHandler::new(self.config.clone(), scan_tx)
-        };
+        }
+        .with_match_replace_rules(self.match_replace_rules.clone());
         let stats = handler.stats();
 
         // 检查是否配置了 upstream proxy
@@ -2676,6 +3149,8 @@ impl ProxyService {
                     }
                 };
 
+                let auth_handler = UpstreamProxyAuthHandler::new(handler.clone(), upstream_config);
+
                 // 包装 connector 以返回 hyper_util::rt::TokioIo
                 let http_connector = ServiceBuilder::new()
                     .map_response(hyper_util::rt::TokioIo::new)
@@ -2691,7 +3166,7 @@ impl ProxyService {
                         .with_listener(listener)
                         .with_ca(ca)
                         .with_http_connector(http_connector)
-                        .with_http_handler(handler.clone())
+                        .with_http_handler(auth_handler)
                         .with_websocket_handler(handler)
                         .build()
                     {
@@ -2779,3 +3254,234 @@ impl ProxyService {
         self.stats.read().await.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(match_type: &str, relationship: &str, condition: &str) -> InterceptFilterRule {
+        InterceptFilterRule {
+            enabled: true,
+            operator: "And".to_string(),
+            match_type: match_type.to_string(),
+            relationship: relationship.to_string(),
+            condition: condition.to_string(),
+            method: None,
+            request_content_type: None,
+            response_content_type: None,
+        }
+    }
+
+    fn intercept_state(
+        request_rules: Vec<InterceptFilterRule>,
+        response_rules: Vec<InterceptFilterRule>,
+    ) -> InterceptState {
+        InterceptState {
+            enabled: Arc::new(RwLock::new(true)),
+            response_enabled: Arc::new(RwLock::new(true)),
+            websocket_enabled: Arc::new(RwLock::new(false)),
+            pending_tx: None,
+            pending_response_tx: None,
+            pending_websocket_tx: None,
+            request_filter_rules: Arc::new(RwLock::new(request_rules)),
+            response_filter_rules: Arc::new(RwLock::new(response_rules)),
+        }
+    }
+
+    fn headers(content_type: &str) -> HashMap<String, String> {
+        HashMap::from([("Content-Type".to_string(), content_type.to_string())])
+    }
+
+    #[tokio::test]
+    async fn test_intercept_only_json_posts() {
+        let mut json_posts = rule("url", "matches", "");
+        json_posts.method = Some("POST".to_string());
+        json_posts.request_content_type = Some("application/json".to_string());
+        let state = intercept_state(vec![json_posts], vec![]);
+        let url = "https://api.example.com/v1/users";
+
+        assert!(
+            TrafficProxyHandler::should_intercept_request(
+                &state,
+                url,
+                "POST",
+                &headers("application/json; charset=utf-8"),
+            )
+            .await
+        );
+        assert!(
+            !TrafficProxyHandler::should_intercept_request(
+                &state,
+                url,
+                "GET",
+                &headers("application/json"),
+            )
+            .await
+        );
+        assert!(
+            !TrafficProxyHandler::should_intercept_request(
+                &state,
+                url,
+                "POST",
+                &headers("application/x-www-form-urlencoded"),
+            )
+            .await
+        );
+        assert!(
+            !TrafficProxyHandler::should_intercept_request(&state, url, "POST", &HashMap::new())
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_predicates_combine_with_url_condition() {
+        let mut api_json = rule("domain_name", "matches", r"^api\.example\.com$");
+        api_json.request_content_type = Some("application/*".to_string());
+        let state = intercept_state(vec![api_json], vec![]);
+
+        assert!(
+            TrafficProxyHandler::should_intercept_request(
+                &state,
+                "https://api.example.com/login",
+                "PUT",
+                &headers("application/json"),
+            )
+            .await
+        );
+        assert!(
+            !TrafficProxyHandler::should_intercept_request(
+                &state,
+                "https://www.example.com/login",
+                "PUT",
+                &headers("application/json"),
+            )
+            .await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_legacy_rules_without_predicates() {
+        let skip_images = rule("file_extension", "does_not_match", "^(png|jpg)$");
+        let state = intercept_state(vec![skip_images], vec![]);
+
+        assert!(
+            TrafficProxyHandler::should_intercept_request(
+                &state,
+                "https://example.com/index.html",
+                "GET",
+                &HashMap::new(),
+            )
+            .await
+        );
+        assert!(
+            !TrafficProxyHandler::should_intercept_request(
+                &state,
+                "https://example.com/logo.png",
+                "GET",
+                &HashMap::new(),
+            )
+            .await
+        );
+
+        // 旧版持久化数据中没有新字段，反序列化后条件为空
+        let legacy: InterceptFilterRule = serde_json::from_str(
+            r#"{"enabled":true,"operator":"And","match_type":"url","relationship":"matches","condition":"example"}"#,
+        )
+        .unwrap();
+        assert!(!legacy.has_predicates());
+    }
+
+    #[tokio::test]
+    async fn test_response_content_type_predicate() {
+        let mut json_responses = rule("url", "matches", "");
+        json_responses.response_content_type = Some("application/json".to_string());
+        let state = intercept_state(vec![], vec![json_responses]);
+
+        let req_ctx = RequestContext {
+            id: "req-1".to_string(),
+            method: "GET".to_string(),
+            url: "https://api.example.com/v1/users".to_string(),
+            headers: HashMap::new(),
+            body: vec![],
+            content_type: None,
+            query_params: HashMap::new(),
+            is_https: true,
+            timestamp: chrono::Utc::now(),
+            was_edited: false,
+            edited_method: None,
+            edited_url: None,
+            edited_headers: None,
+            edited_body: None,
+            body_truncated: false,
+            body_size: None,
+        };
+        assert!(
+            TrafficProxyHandler::should_intercept_response(
+                &state,
+                &req_ctx,
+                200,
+                &headers("application/json"),
+            )
+            .await
+        );
+        assert!(
+            !TrafficProxyHandler::should_intercept_response(
+                &state,
+                &req_ctx,
+                200,
+                &headers("text/html"),
+            )
+            .await
+        );
+
+        // 请求阶段无法判断响应类型，响应条件不影响请求匹配
+        let rule = &state.response_filter_rules.read().await[0];
+        assert!(rule.combined_match(false, "POST", Some(""), None));
+    }
+
+    #[tokio::test]
+    async fn test_single_http_proxy_forwards_plain_http_in_absolute_form() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_port = listener.local_addr().unwrap().port();
+        let seen = tokio::spawn(async move {
+            let (mut client, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let n = client.read(&mut buf).await.unwrap();
+            client
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+
+        let config = UpstreamProxyConfig {
+            enabled: true,
+            proxy_host: "127.0.0.1".to_string(),
+            proxy_port,
+            auth_type: "Basic".to_string(),
+            username: Some("corp".to_string()),
+            password: Some("secret".to_string()),
+            ..Default::default()
+        };
+        assert!(!format!("{:?}", config).contains("secret"));
+
+        let connector = ServiceBuilder::new()
+            .map_response(hyper_util::rt::TokioIo::new)
+            .service(create_upstream_proxy_connector(&config).unwrap());
+        let client =
+            hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+                .build::<_, Full<Bytes>>(connector);
+        let res = client
+            .get("http://example.test/path?q=1".parse().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 200);
+
+        // 不走 CONNECT，请求行为 absolute-form
+        let request = seen.await.unwrap();
+        assert!(request.starts_with("GET http://example.test/path?q=1 HTTP/1.1"));
+    }
+}
@@ -0,0 +1,308 @@
+//! 请求模板批量重放
+//!
+//! 模板的 URL、请求头和请求体中可以使用 `{{name}}` 占位符，每个变量取值来自数字范围或词表；
+//! 多个变量按笛卡尔积展开，每个组合生成一个请求。未定义的占位符原样保留。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 单次展开允许生成的最大请求数
+pub const MAX_TEMPLATE_REQUESTS: usize = 1000;
+
+fn default_step() -> i64 {
+    1
+}
+
+/// 变量取值来源
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TemplateVariableSource {
+    /// 数字范围（包含两端），`pad` 为补零后的最小宽度
+    Range {
+        start: i64,
+        end: i64,
+        #[serde(default = "default_step")]
+        step: i64,
+        #[serde(default)]
+        pad: usize,
+    },
+    /// 词表
+    Wordlist { values: Vec<String> },
+}
+
+impl TemplateVariableSource {
+    /// 取值个数
+    pub fn len(&self) -> usize {
+        match self {
+            TemplateVariableSource::Range {
+                start, end, step, ..
+            } => {
+                if *step == 0 || (*step > 0 && start > end) || (*step < 0 && start < end) {
+                    return 0;
+                }
+                let span = (*end as i128 - *start as i128).unsigned_abs();
+                let count = span / step.unsigned_abs() as u128 + 1;
+                usize::try_from(count).unwrap_or(usize::MAX)
+            }
+            TemplateVariableSource::Wordlist { values } => values.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 全部取值
+    pub fn values(&self) -> Vec<String> {
+        match self {
+            TemplateVariableSource::Range {
+                start, step, pad, ..
+            } => (0..self.len())
+                .map(|i| {
+                    let value = *start as i128 + i as i128 * *step as i128;
+                    if value < 0 {
+                        format!("-{:0width$}", -value, width = *pad)
+                    } else {
+                        format!("{:0width$}", value, width = *pad)
+                    }
+                })
+                .collect(),
+            TemplateVariableSource::Wordlist { values } => values.clone(),
+        }
+    }
+}
+
+/// 模板变量
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateVariable {
+    /// 占位符名称（对应 `{{name}}`）
+    pub name: String,
+    #[serde(flatten)]
+    pub source: TemplateVariableSource,
+}
+
+/// 请求模板
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestTemplate {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub body: Option<String>,
+}
+
+/// 展开后的单个请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpandedRequest {
+    /// 本次使用的变量取值
+    pub variables: HashMap<String, String>,
+    pub method: String,
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub body: Option<String>,
+}
+
+/// 替换文本中的 `{{name}}` 占位符（名称两侧允许空白）
+pub fn substitute(text: &str, values: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open + 2..].find("}}") else {
+            break;
+        };
+        let name = rest[open + 2..open + 2 + close].trim();
+        out.push_str(&rest[..open]);
+        match values.get(name) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[open..open + close + 4]),
+        }
+        rest = &rest[open + close + 4..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 按变量展开模板；请求总数超过 `max_requests` 时报错而不是静默截断
+pub fn expand_template(
+    template: &RequestTemplate,
+    variables: &[TemplateVariable],
+    max_requests: usize,
+) -> Result<Vec<ExpandedRequest>, String> {
+    let mut seen = std::collections::HashSet::new();
+    for variable in variables {
+        if variable.name.trim().is_empty() {
+            return Err("Template variable name must not be empty".to_string());
+        }
+        if !seen.insert(variable.name.trim()) {
+            return Err(format!("Duplicate template variable: {}", variable.name));
+        }
+        if variable.source.is_empty() {
+            return Err(format!(
+                "Template variable '{}' has no values",
+                variable.name
+            ));
+        }
+    }
+
+    let total = variables
+        .iter()
+        .try_fold(1usize, |acc, v| acc.checked_mul(v.source.len()))
+        .filter(|total| *total <= max_requests)
+        .ok_or_else(|| {
+            format!(
+                "Template expands to more than {} requests, narrow the variables",
+                max_requests
+            )
+        })?;
+
+    let value_lists: Vec<Vec<String>> = variables.iter().map(|v| v.source.values()).collect();
+    let mut requests = Vec::with_capacity(total);
+    for index in 0..total {
+        // 最后一个变量变化最快
+        let mut remainder = index;
+        let mut values = HashMap::new();
+        for (variable, list) in variables.iter().zip(&value_lists).rev() {
+            values.insert(
+                variable.name.trim().to_string(),
+                list[remainder % list.len()].clone(),
+            );
+            remainder /= list.len();
+        }
+
+        requests.push(ExpandedRequest {
+            method: substitute(&template.method, &values),
+            url: substitute(&template.url, &values),
+            headers: template
+                .headers
+                .iter()
+                .map(|(k, v)| (substitute(k, &values), substitute(v, &values)))
+                .collect(),
+            body: template.body.as_ref().map(|b| substitute(b, &values)),
+            variables: values,
+        });
+    }
+    Ok(requests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template() -> RequestTemplate {
+        RequestTemplate {
+            method: "POST".to_string(),
+            url: "https://api.example.com/users/{{id}}?lang={{ lang }}".to_string(),
+            headers: HashMap::from([("X-Lang".to_string(), "{{lang}}".to_string())]),
+            body: Some(r#"{"id": {{id}}, "keep": "{{unknown}}"}"#.to_string()),
+        }
+    }
+
+    fn range(name: &str, start: i64, end: i64, step: i64, pad: usize) -> TemplateVariable {
+        TemplateVariable {
+            name: name.to_string(),
+            source: TemplateVariableSource::Range {
+                start,
+                end,
+                step,
+                pad,
+            },
+        }
+    }
+
+    fn wordlist(name: &str, values: &[&str]) -> TemplateVariable {
+        TemplateVariable {
+            name: name.to_string(),
+            source: TemplateVariableSource::Wordlist {
+                values: values.iter().map(|v| v.to_string()).collect(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_range_values() {
+        let values = |v: TemplateVariable| v.source.values();
+        assert_eq!(values(range("id", 1, 5, 2, 0)), ["1", "3", "5"]);
+        assert_eq!(values(range("id", 8, 10, 1, 3)), ["008", "009", "010"]);
+        assert_eq!(values(range("id", 1, -1, -1, 2)), ["01", "00", "-01"]);
+        assert!(range("id", 5, 1, 1, 0).source.is_empty());
+        assert!(range("id", 1, 5, 0, 0).source.is_empty());
+    }
+
+    #[test]
+    fn test_expand_range_and_wordlist() {
+        let requests = expand_template(
+            &template(),
+            &[range("id", 1, 3, 1, 0), wordlist("lang", &["en", "zh"])],
+            MAX_TEMPLATE_REQUESTS,
+        )
+        .unwrap();
+        assert_eq!(requests.len(), 6);
+
+        let urls: Vec<_> = requests.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://api.example.com/users/1?lang=en",
+                "https://api.example.com/users/1?lang=zh",
+                "https://api.example.com/users/2?lang=en",
+                "https://api.example.com/users/2?lang=zh",
+                "https://api.example.com/users/3?lang=en",
+                "https://api.example.com/users/3?lang=zh",
+            ]
+        );
+
+        let last = &requests[5];
+        assert_eq!(last.method, "POST");
+        assert_eq!(last.headers["X-Lang"], "zh");
+        assert_eq!(
+            last.body.as_deref(),
+            Some(r#"{"id": 3, "keep": "{{unknown}}"}"#)
+        );
+        assert_eq!(last.variables["id"], "3");
+        assert_eq!(last.variables["lang"], "zh");
+    }
+
+    #[test]
+    fn test_request_cap_and_invalid_variables() {
+        let err = expand_template(&template(), &[range("id", 1, 11, 1, 0)], 10).unwrap_err();
+        assert!(err.contains("more than 10"));
+        assert_eq!(
+            expand_template(&template(), &[range("id", 1, 10, 1, 0)], 10)
+                .unwrap()
+                .len(),
+            10
+        );
+        // 大范围相乘不会溢出
+        assert!(expand_template(
+            &template(),
+            &[
+                range("id", 0, i64::MAX, 1, 0),
+                range("lang", 0, i64::MAX, 1, 0)
+            ],
+            MAX_TEMPLATE_REQUESTS,
+        )
+        .is_err());
+        assert!(expand_template(&template(), &[wordlist("id", &[])], 10).is_err());
+        assert!(expand_template(
+            &template(),
+            &[wordlist("id", &["1"]), wordlist("id", &["2"])],
+            10
+        )
+        .is_err());
+
+        // 没有变量时只生成模板本身
+        let single = expand_template(&template(), &[], 10).unwrap();
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].url, template().url);
+    }
+
+    #[test]
+    fn test_substitute_edge_cases() {
+        let values = HashMap::from([("a".to_string(), "1".to_string())]);
+        assert_eq!(substitute("{{a}}{{a}}", &values), "11");
+        assert_eq!(substitute("x{{a", &values), "x{{a");
+        assert_eq!(substitute("{{b}}-{{a}}", &values), "{{b}}-1");
+        assert_eq!(substitute("", &values), "");
+    }
+}
@@ -0,0 +1,407 @@
+//! 漏洞报告导出（JSON / SARIF）
+//!
+//! - JSON：规范化的结构，供 CI 和外部工具消费
+//! - SARIF 2.1.0：可直接上传到 GitHub code scanning；每种漏洞类型对应一条规则，
+//!   每个漏洞对应一条结果，证据中的 URL 作为结果位置
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use crate::VulnerabilityWithEvidence;
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+pub const JSON_REPORT_SCHEMA_VERSION: u32 = 1;
+const TOOL_NAME: &str = "Sentinel AI";
+const TOOL_INFORMATION_URI: &str = "https://github.com/o0x1024/sentinel-ai";
+
+/// 严重程度统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeveritySummary {
+    pub total: usize,
+    pub critical: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+    pub info: usize,
+}
+
+/// 导出的证据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedEvidence {
+    pub url: String,
+    pub method: String,
+    pub location: String,
+    pub snippet: String,
+    pub response_status: Option<i32>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// 导出的漏洞
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedFinding {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub severity: String,
+    pub confidence: String,
+    pub vuln_type: String,
+    pub plugin_id: String,
+    pub status: String,
+    pub cwe: Option<String>,
+    pub owasp: Option<String>,
+    pub remediation: Option<String>,
+    pub signature: String,
+    pub hit_count: i64,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    pub evidence: Vec<ExportedEvidence>,
+}
+
+/// 规范化 JSON 报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FindingsJsonReport {
+    pub schema_version: u32,
+    pub tool: String,
+    pub tool_version: String,
+    pub generated_at: DateTime<Utc>,
+    pub summary: SeveritySummary,
+    pub findings: Vec<ExportedFinding>,
+}
+
+/// 严重程度映射到 SARIF level
+pub fn severity_to_sarif_level(severity: &str) -> &'static str {
+    match severity.to_ascii_lowercase().as_str() {
+        "critical" | "high" => "error",
+        "medium" => "warning",
+        _ => "note",
+    }
+}
+
+/// GitHub code scanning 使用的 security-severity 分值
+fn security_severity(severity: &str) -> &'static str {
+    match severity.to_ascii_lowercase().as_str() {
+        "critical" => "9.5",
+        "high" => "8.0",
+        "medium" => "5.5",
+        "low" => "3.0",
+        _ => "0.0",
+    }
+}
+
+/// 构建 JSON 报告
+pub fn build_json_report(
+    findings: &[VulnerabilityWithEvidence],
+    generated_at: DateTime<Utc>,
+) -> FindingsJsonReport {
+    let mut summary = SeveritySummary {
+        total: findings.len(),
+        ..Default::default()
+    };
+    for f in findings {
+        match f.vulnerability.severity.to_ascii_lowercase().as_str() {
+            "critical" => summary.critical += 1,
+            "high" => summary.high += 1,
+            "medium" => summary.medium += 1,
+            "low" => summary.low += 1,
+            _ => summary.info += 1,
+        }
+    }
+
+    let findings = findings
+        .iter()
+        .map(|f| {
+            let v = &f.vulnerability;
+            ExportedFinding {
+                id: v.id.clone(),
+                title: v.title.clone(),
+                description: v.description.clone(),
+                severity: v.severity.clone(),
+                confidence: v.confidence.clone(),
+                vuln_type: v.vuln_type.clone(),
+                plugin_id: v.plugin_id.clone(),
+                status: v.status.clone(),
+                cwe: v.cwe.clone(),
+                owasp: v.owasp.clone(),
+                remediation: v.remediation.clone(),
+                signature: v.signature.clone(),
+                hit_count: v.hit_count,
+                first_seen_at: v.first_seen_at,
+                last_seen_at: v.last_seen_at,
+                evidence: f
+                    .evidence
+                    .iter()
+                    .map(|e| ExportedEvidence {
+                        url: e.url.clone(),
+                        method: e.method.clone(),
+                        location: e.location.clone(),
+                        snippet: e.evidence_snippet.clone(),
+                        response_status: e.response_status,
+                        timestamp: e.timestamp,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    FindingsJsonReport {
+        schema_version: JSON_REPORT_SCHEMA_VERSION,
+        tool: TOOL_NAME.to_string(),
+        tool_version: crate::VERSION.to_string(),
+        generated_at,
+        summary,
+        findings,
+    }
+}
+
+/// 构建 SARIF 2.1.0 报告
+pub fn build_sarif_report(findings: &[VulnerabilityWithEvidence]) -> Value {
+    // 每种漏洞类型一条规则，按首次出现的漏洞填充描述
+    let mut rule_index: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut rules: Vec<Value> = Vec::new();
+    for f in findings {
+        let v = &f.vulnerability;
+        if rule_index.contains_key(v.vuln_type.as_str()) {
+            continue;
+        }
+        rule_index.insert(&v.vuln_type, rules.len());
+
+        let mut tags = vec!["security".to_string()];
+        if let Some(cwe) = v.cwe.as_deref().filter(|c| !c.is_empty()) {
+            tags.push(format!("external/cwe/{}", cwe.to_ascii_lowercase()));
+        }
+        if let Some(owasp) = v.owasp.as_deref().filter(|o| !o.is_empty()) {
+            tags.push(format!("external/owasp/{}", owasp));
+        }
+
+        rules.push(json!({
+            "id": v.vuln_type,
+            "name": v.vuln_type,
+            "shortDescription": { "text": v.title },
+            "fullDescription": { "text": non_empty(&v.description, &v.title) },
+            "help": { "text": v.remediation.as_deref().unwrap_or(&v.title) },
+            "defaultConfiguration": { "level": severity_to_sarif_level(&v.severity) },
+            "properties": {
+                "tags": tags,
+                "security-severity": security_severity(&v.severity),
+            }
+        }));
+    }
+
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|f| {
+            let v = &f.vulnerability;
+            let mut locations: Vec<Value> = f
+                .evidence
+                .iter()
+                .map(|e| e.url.as_str())
+                .chain(f.url.as_deref())
+                .filter(|u| !u.is_empty())
+                .fold(Vec::<&str>::new(), |mut urls, u| {
+                    if !urls.contains(&u) {
+                        urls.push(u);
+                    }
+                    urls
+                })
+                .into_iter()
+                .map(|uri| json!({ "physicalLocation": { "artifactLocation": { "uri": uri } } }))
+                .collect();
+            if locations.is_empty() {
+                locations.push(json!({
+                    "physicalLocation": { "artifactLocation": { "uri": v.plugin_id } }
+                }));
+            }
+            let message = if v.description.trim().is_empty() {
+                v.title.clone()
+            } else {
+                format!("{}: {}", v.title, v.description)
+            };
+
+            json!({
+                "ruleId": v.vuln_type,
+                "ruleIndex": rule_index[v.vuln_type.as_str()],
+                "level": severity_to_sarif_level(&v.severity),
+                "message": { "text": message },
+                "locations": locations,
+                "partialFingerprints": { "sentinelSignature/v1": v.signature },
+                "properties": {
+                    "id": v.id,
+                    "severity": v.severity,
+                    "confidence": v.confidence,
+                    "plugin_id": v.plugin_id,
+                    "status": v.status,
+                    "hit_count": v.hit_count,
+                    "first_seen_at": v.first_seen_at.to_rfc3339(),
+                    "last_seen_at": v.last_seen_at.to_rfc3339(),
+                }
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "version": crate::VERSION,
+                    "informationUri": TOOL_INFORMATION_URI,
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+fn non_empty<'a>(value: &'a str, fallback: &'a str) -> &'a str {
+    if value.trim().is_empty() {
+        fallback
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EvidenceRecord, VulnerabilityRecord};
+
+    fn finding(
+        id: &str,
+        vuln_type: &str,
+        severity: &str,
+        urls: &[&str],
+    ) -> VulnerabilityWithEvidence {
+        let now = Utc::now();
+        VulnerabilityWithEvidence {
+            vulnerability: VulnerabilityRecord {
+                id: id.to_string(),
+                plugin_id: "builtin.sqli".to_string(),
+                vuln_type: vuln_type.to_string(),
+                severity: severity.to_string(),
+                confidence: "high".to_string(),
+                title: format!("{} found", vuln_type),
+                description: String::new(),
+                cwe: Some("CWE-89".to_string()),
+                owasp: Some("A03:2021".to_string()),
+                remediation: Some("Use parameterized queries".to_string()),
+                status: "open".to_string(),
+                signature: format!("sig-{}", id),
+                first_seen_at: now,
+                last_seen_at: now,
+                hit_count: 2,
+                session_id: None,
+                created_at: now,
+                updated_at: now,
+            },
+            evidence: urls
+                .iter()
+                .map(|url| EvidenceRecord {
+                    id: format!("ev-{}", id),
+                    vuln_id: id.to_string(),
+                    url: url.to_string(),
+                    method: "GET".to_string(),
+                    location: "query.id".to_string(),
+                    evidence_snippet: "syntax error".to_string(),
+                    request_headers: None,
+                    request_body: None,
+                    response_status: Some(500),
+                    response_headers: None,
+                    response_body: None,
+                    timestamp: now,
+                })
+                .collect(),
+            url: None,
+            method: None,
+        }
+    }
+
+    fn sample() -> Vec<VulnerabilityWithEvidence> {
+        vec![
+            finding("v1", "sqli", "critical", &["https://a.test/item?id=1"]),
+            finding("v2", "sqli", "medium", &["https://a.test/list?id=2"]),
+            finding("v3", "info_leak", "info", &[]),
+        ]
+    }
+
+    #[test]
+    fn test_severity_to_sarif_level() {
+        assert_eq!(severity_to_sarif_level("critical"), "error");
+        assert_eq!(severity_to_sarif_level("HIGH"), "error");
+        assert_eq!(severity_to_sarif_level("medium"), "warning");
+        assert_eq!(severity_to_sarif_level("low"), "note");
+        assert_eq!(severity_to_sarif_level("info"), "note");
+    }
+
+    #[test]
+    fn test_sarif_has_required_fields() {
+        let sarif = build_sarif_report(&sample());
+
+        assert_eq!(sarif["version"], SARIF_VERSION);
+        assert_eq!(sarif["$schema"], SARIF_SCHEMA);
+        let runs = sarif["runs"].as_array().unwrap();
+        assert_eq!(runs.len(), 1);
+
+        let driver = &runs[0]["tool"]["driver"];
+        assert!(driver["name"].as_str().is_some_and(|n| !n.is_empty()));
+        let rules = driver["rules"].as_array().unwrap();
+        let rule_ids: Vec<&str> = rules.iter().map(|r| r["id"].as_str().unwrap()).collect();
+        assert_eq!(rule_ids, vec!["sqli", "info_leak"]);
+        for rule in rules {
+            assert!(rule["shortDescription"]["text"].is_string());
+            assert!(rule["fullDescription"]["text"].is_string());
+        }
+
+        let results = runs[0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        for result in results {
+            assert!(result["message"]["text"]
+                .as_str()
+                .is_some_and(|t| !t.is_empty()));
+            let index = result["ruleIndex"].as_u64().unwrap() as usize;
+            assert_eq!(rules[index]["id"], result["ruleId"]);
+            assert!(
+                ["none", "note", "warning", "error"].contains(&result["level"].as_str().unwrap())
+            );
+            let locations = result["locations"].as_array().unwrap();
+            assert!(!locations.is_empty());
+            for location in locations {
+                assert!(location["physicalLocation"]["artifactLocation"]["uri"].is_string());
+            }
+        }
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[1]["level"], "warning");
+        assert_eq!(results[2]["level"], "note");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "https://a.test/item?id=1"
+        );
+    }
+
+    #[test]
+    fn test_json_report_summary_and_evidence() {
+        let report = build_json_report(&sample(), Utc::now());
+        assert_eq!(report.schema_version, JSON_REPORT_SCHEMA_VERSION);
+        assert_eq!(
+            report.summary,
+            SeveritySummary {
+                total: 3,
+                critical: 1,
+                medium: 1,
+                info: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(report.findings[0].evidence.len(), 1);
+        assert_eq!(report.findings[0].evidence[0].response_status, Some(500));
+
+        let value = serde_json::to_value(&report).unwrap();
+        let parsed: FindingsJsonReport = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, report);
+    }
+}
@@ -5,10 +5,15 @@
 //! - 扇出分发给已启用插件
 //! - 收集 Finding 并去重
 
+use crate::asset_extractor::PassiveAssetExtractor;
+use crate::finding::{DedupWindow, SharedDedupConfig};
 use crate::history_cache::{HttpRequestRecord, ProxyHistoryCache};
+use crate::security_headers::{self, SharedSecurityHeaderConfig};
+use crate::suppression::{find_suppression_rule, SharedSuppressionRules, SUPPRESSED_STATUS};
 use crate::{Finding, InterceptFilterRule, RequestContext, ResponseContext, Result, TrafficError};
+use sentinel_core::shutdown::{self, OperationGuard};
 use sentinel_db::DatabaseService;
-use sentinel_plugins::{types::HttpTransaction, PluginExecutor};
+use sentinel_plugins::{types::HttpTransaction, PluginExecutor, PluginMetrics};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::Emitter;
@@ -50,10 +55,23 @@ pub struct ScanPipeline {
     exclude_self_traffic: Arc<RwLock<bool>>,
     /// 是否启用流量分析插件扫描
     plugin_scanning_enabled: Arc<RwLock<bool>>,
+    /// 被动扫描是否已暂停（暂停时仍记录历史，但不分发给插件）
+    scan_paused: Arc<RwLock<bool>>,
     /// 并发控制信号量（限制同时执行的插件数量）
     plugin_semaphore: Arc<tokio::sync::Semaphore>,
+    /// 插件执行指标（与 PluginManager 共享）
+    plugin_metrics: PluginMetrics,
+    /// 是否从流量中被动提取资产
+    passive_asset_extraction: Arc<RwLock<bool>>,
+    /// 被动资产提取器（缓冲去重后定期写入资产库）
+    asset_extractor: Arc<PassiveAssetExtractor>,
+    /// 内置安全响应头检查配置
+    security_headers: SharedSecurityHeaderConfig,
 }
 
+/// 被动提取资产的写库间隔
+const ASSET_FLUSH_INTERVAL_SECS: u64 = 15;
+
 impl ScanPipeline {
     /// 创建新的扫描流水线
     pub fn new(task_rx: mpsc::UnboundedReceiver<ScanTask>, finding_tx: FindingSender) -> Self {
@@ -69,10 +87,21 @@ impl ScanPipeline {
             response_filter_rules: Arc::new(RwLock::new(Vec::new())),
             exclude_self_traffic: Arc::new(RwLock::new(true)),
             plugin_scanning_enabled: Arc::new(RwLock::new(true)),
+            scan_paused: Arc::new(RwLock::new(false)),
             plugin_semaphore: Arc::new(tokio::sync::Semaphore::new(20)), // 最多20个并发插件执行
+            plugin_metrics: PluginMetrics::new(),
+            passive_asset_extraction: Arc::new(RwLock::new(false)),
+            asset_extractor: Arc::new(PassiveAssetExtractor::new()),
+            security_headers: Arc::new(RwLock::new(Default::default())),
         }
     }
 
+    /// 设置插件执行指标存储
+    pub fn with_plugin_metrics(mut self, metrics: PluginMetrics) -> Self {
+        self.plugin_metrics = metrics;
+        self
+    }
+
     /// 设置请求过滤规则
     pub fn with_request_filter_rules(
         mut self,
@@ -103,6 +132,24 @@ impl ScanPipeline {
         self
     }
 
+    /// 设置被动扫描暂停标志
+    pub fn with_scan_paused(mut self, paused: Arc<RwLock<bool>>) -> Self {
+        self.scan_paused = paused;
+        self
+    }
+
+    /// 设置被动资产提取开关
+    pub fn with_passive_asset_extraction(mut self, enabled: Arc<RwLock<bool>>) -> Self {
+        self.passive_asset_extraction = enabled;
+        self
+    }
+
+    /// 设置安全响应头检查配置
+    pub fn with_security_header_config(mut self, config: SharedSecurityHeaderConfig) -> Self {
+        self.security_headers = config;
+        self
+    }
+
     /// 设置数据库服务（用于加载插件和存储漏洞，不再用于请求历史）
     pub fn with_db_service(mut self, db_service: Arc<DatabaseService>) -> Self {
         self.db_service = Some(db_service);
@@ -127,10 +174,15 @@ impl ScanPipeline {
 
         // 启动请求缓存清理任务（每60秒清理一次超过5分钟的请求）
         let request_cache_clone = self.request_cache.clone();
+        let shutdown_token = shutdown::shutdown_token();
+        let cleanup_shutdown = shutdown_token.clone();
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
             loop {
-                interval.tick().await;
+                tokio::select! {
+                    _ = cleanup_shutdown.cancelled() => break,
+                    _ = interval.tick() => {}
+                }
                 let mut cache = request_cache_clone.write().await;
                 let now = chrono::Utc::now();
                 let original_size = cache.len();
@@ -150,7 +202,72 @@ impl ScanPipeline {
             }
         });
 
-        while let Some(task) = self.task_rx.recv().await {
+        // 恢复持久化的插件指标，并每30秒写回有变化的记录；流水线停止时写回最后一批后退出
+        let metrics_flush_stop = shutdown_token.child_token();
+        let mut metrics_flush_task = None;
+        if let Some(db) = self.db_service.clone() {
+            let metrics = self.plugin_metrics.clone();
+            if !metrics.is_restored() {
+                match db.list_plugin_metrics().await {
+                    Ok(records) => metrics.restore(records),
+                    Err(e) => warn!("Failed to load plugin metrics: {}", e),
+                }
+            }
+            let stop = metrics_flush_stop.clone();
+            metrics_flush_task = Some(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
+                loop {
+                    let stopping = tokio::select! {
+                        _ = stop.cancelled() => true,
+                        _ = interval.tick() => false,
+                    };
+                    let records = metrics.take_dirty();
+                    if !records.is_empty() {
+                        if let Err(e) = db.save_plugin_metrics(&records).await {
+                            warn!("Failed to persist plugin metrics: {}", e);
+                        }
+                    }
+                    if stopping {
+                        break;
+                    }
+                }
+            }));
+        }
+
+        // 定期把被动提取的资产批量写入资产库；流水线停止（或应用关闭）时再写最后一批
+        let asset_flush_stop = shutdown_token.child_token();
+        let asset_flush_task = self.db_service.clone().map(|db| {
+            let extractor = self.asset_extractor.clone();
+            let stop = asset_flush_stop.clone();
+            // 持有到最后一批写完，关闭流程会等待它
+            let guard = shutdown::begin_operation("passive asset flush");
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(
+                    ASSET_FLUSH_INTERVAL_SECS,
+                ));
+                loop {
+                    tokio::select! {
+                        _ = stop.cancelled() => break,
+                        _ = interval.tick() => {}
+                    }
+                    extractor.flush(&db).await;
+                }
+                extractor.flush(&db).await;
+                drop(guard);
+            })
+        });
+
+        loop {
+            let task = tokio::select! {
+                _ = shutdown_token.cancelled() => {
+                    info!("ScanPipeline stopping for shutdown");
+                    break;
+                }
+                task = self.task_rx.recv() => match task {
+                    Some(task) => task,
+                    None => break,
+                },
+            };
             match task {
                 ScanTask::Request(req_ctx) => {
                     self.process_request(req_ctx).await;
@@ -192,10 +309,28 @@ impl ScanPipeline {
             }
         }
 
-        info!("ScanPipeline stopped (channel closed)");
+        metrics_flush_stop.cancel();
+        if let Some(task) = metrics_flush_task {
+            if let Err(e) = task.await {
+                warn!("Plugin metrics flush task failed: {}", e);
+            }
+        }
+        asset_flush_stop.cancel();
+        if let Some(task) = asset_flush_task {
+            if let Err(e) = task.await {
+                warn!("Passive asset flush task failed: {}", e);
+            }
+        }
+
+        info!("ScanPipeline stopped");
         Ok(())
     }
 
+    /// 是否需要把流量分发给插件：插件扫描已启用且被动扫描未暂停
+    async fn plugin_dispatch_enabled(&self) -> bool {
+        *self.plugin_scanning_enabled.read().await && !*self.scan_paused.read().await
+    }
+
     /// 处理请求上下文
     async fn process_request(&self, req_ctx: RequestContext) {
         // 无论是否有插件，都先缓存请求上下文（用于后续响应匹配和历史记录）
@@ -210,11 +345,10 @@ impl ScanPipeline {
             return;
         }
 
-        // 检查流量分析插件扫描是否启用
-        let plugin_scanning_enabled = *self.plugin_scanning_enabled.read().await;
-        if !plugin_scanning_enabled {
+        // 检查流量分析插件扫描是否启用（暂停期间同样跳过）
+        if !self.plugin_dispatch_enabled().await {
             debug!(
-                "Traffic analysis plugin scanning is disabled, skipping request scan: {}",
+                "Traffic analysis plugin scanning is disabled or paused, skipping request scan: {}",
                 req_ctx.url
             );
             return;
@@ -265,6 +399,7 @@ impl ScanPipeline {
         // 克隆 finding_tx 和 semaphore 用于 task
         let finding_tx = self.finding_tx.clone();
         let semaphore = self.plugin_semaphore.clone();
+        let metrics = self.plugin_metrics.clone();
 
         tokio::spawn(async move {
             for (plugin_id, executor) in executors {
@@ -273,6 +408,7 @@ impl ScanPipeline {
                 let plugin_id_clone = plugin_id.clone();
                 let executor_clone = executor.clone();
                 let semaphore_clone = semaphore.clone();
+                let metrics = metrics.clone();
 
                 tokio::spawn(async move {
                     // 获取信号量许可，限制并发执行
@@ -326,7 +462,10 @@ impl ScanPipeline {
                         }
                     }
 
-                    match executor.scan_transaction(tx_clone).await {
+                    match metrics
+                        .track(&plugin_id, executor.scan_transaction(tx_clone))
+                        .await
+                    {
                         Ok(findings) => {
                             if !findings.is_empty() {
                                 debug!(
@@ -351,6 +490,31 @@ impl ScanPipeline {
         });
     }
 
+    /// 被动提取资产（仅处理符合过滤规则的流量）
+    async fn observe_assets(&self, req_ctx: &RequestContext, resp_ctx: &ResponseContext) {
+        if !*self.passive_asset_extraction.read().await || req_ctx.method == "CONNECT" {
+            return;
+        }
+        if !self.should_scan_request(req_ctx).await {
+            return;
+        }
+        self.asset_extractor
+            .observe(&req_ctx.url, Some(resp_ctx.status), Some(&resp_ctx.headers));
+    }
+
+    /// 内置安全响应头检查，Finding 与插件结果一样进入去重流程
+    async fn check_security_headers(&self, req_ctx: &RequestContext, resp_ctx: &ResponseContext) {
+        let findings = {
+            let config = self.security_headers.read().await;
+            security_headers::analyze(&config, req_ctx, resp_ctx)
+        };
+        for finding in findings {
+            if let Err(e) = self.finding_tx.send(finding) {
+                error!("Failed to send finding: {}", e);
+            }
+        }
+    }
+
     /// 处理响应上下文
     async fn process_response(&self, resp_ctx: ResponseContext) {
         // 从缓存中获取请求上下文
@@ -370,11 +534,12 @@ impl ScanPipeline {
             }
         };
 
-        // 检查流量分析插件扫描是否启用
-        let plugin_scanning_enabled = *self.plugin_scanning_enabled.read().await;
-        if !plugin_scanning_enabled {
+        self.observe_assets(&req_ctx, &resp_ctx).await;
+
+        // 检查流量分析插件扫描是否启用（暂停期间同样跳过）
+        if !self.plugin_dispatch_enabled().await {
             debug!(
-                "Traffic analysis plugin scanning is disabled, skipping response scan: {}",
+                "Traffic analysis plugin scanning is disabled or paused, skipping response scan: {}",
                 req_ctx.url
             );
             // 仍然记录到历史缓存，但不进行插件扫描
@@ -405,6 +570,8 @@ impl ScanPipeline {
         // 记录请求到历史缓存
         self.record_to_history_cache(&req_ctx, &resp_ctx).await;
 
+        self.check_security_headers(&req_ctx, &resp_ctx).await;
+
         if !has_plugins {
             // 清理请求缓存
             let mut cache = self.request_cache.write().await;
@@ -426,6 +593,7 @@ impl ScanPipeline {
         // 克隆 finding_tx 和 semaphore 用于 task
         let finding_tx = self.finding_tx.clone();
         let semaphore = self.plugin_semaphore.clone();
+        let metrics = self.plugin_metrics.clone();
 
         // 异步调用插件
         tokio::spawn(async move {
@@ -435,6 +603,7 @@ impl ScanPipeline {
                 let plugin_id_clone = plugin_id.clone();
                 let executor_clone = executor.clone();
                 let semaphore_clone = semaphore.clone();
+                let metrics = metrics.clone();
 
                 tokio::spawn(async move {
                     // 获取信号量许可，限制并发执行
@@ -488,7 +657,10 @@ impl ScanPipeline {
                         }
                     }
 
-                    match executor.scan_transaction(tx_clone).await {
+                    match metrics
+                        .track(&plugin_id, executor.scan_transaction(tx_clone))
+                        .await
+                    {
                         Ok(findings) => {
                             if !findings.is_empty() {
                                 debug!(
@@ -923,6 +1095,8 @@ impl ScanPipeline {
             return true; // No rules, scan all
         }
 
+        let request_content_type = req_ctx.content_type.as_deref().unwrap_or("");
+
         // Extract domain from URL
         let domain = req_ctx
             .url
@@ -963,6 +1137,8 @@ impl ScanPipeline {
                     }
                 }
             };
+            let matches =
+                rule.combined_match(matches, &req_ctx.method, Some(request_content_type), None);
 
             // "does_not_match" or "notMatches" means: if condition matches, skip scanning
             if (rule.relationship == "does_not_match" || rule.relationship == "notMatches")
@@ -1026,6 +1202,8 @@ impl ScanPipeline {
             return true; // No rules, scan all
         }
 
+        let request_content_type = req_ctx.content_type.as_deref().unwrap_or("");
+
         // Extract domain from URL
         let domain = req_ctx
             .url
@@ -1076,6 +1254,12 @@ impl ScanPipeline {
                     }
                 }
             };
+            let matches = rule.combined_match(
+                matches,
+                &req_ctx.method,
+                Some(request_content_type),
+                Some(content_type),
+            );
 
             // "does_not_match" or "notMatches" means: if condition matches, skip scanning
             if (rule.relationship == "does_not_match" || rule.relationship == "notMatches")
@@ -1135,7 +1319,8 @@ impl ScanPipeline {
             let response_headers = serde_json::to_string(&resp_ctx.headers).ok();
 
             // 转换请求体和响应体为 String
-            let request_body = if req_ctx.body.is_empty() {
+            // 超过大小上限的 body 只记录大小，不保存内容
+            let request_body = if req_ctx.body.is_empty() || req_ctx.body_truncated {
                 None
             } else {
                 match String::from_utf8(req_ctx.body.clone()) {
@@ -1150,7 +1335,7 @@ impl ScanPipeline {
                 }
             };
 
-            let response_body = if resp_ctx.body.is_empty() {
+            let response_body = if resp_ctx.body.is_empty() || resp_ctx.body_truncated {
                 None
             } else {
                 match String::from_utf8(resp_ctx.body.clone()) {
@@ -1165,7 +1350,10 @@ impl ScanPipeline {
                 }
             };
 
-            let response_size = resp_ctx.body.len() as i64;
+            let response_size = resp_ctx
+                .body_size
+                .map(|size| size as i64)
+                .unwrap_or(resp_ctx.body.len() as i64);
 
             // 处理 edited 字段
             let (
@@ -1228,6 +1416,11 @@ impl ScanPipeline {
                 request_body,
                 response_headers,
                 response_body: response_body.clone(),
+                raw_response_body: resp_ctx
+                    .raw_body
+                    .as_deref()
+                    .map(crate::content_decoding::body_to_stored),
+                response_body_decoded: false,
                 response_size,
                 response_time,
                 timestamp: req_ctx.timestamp,
@@ -1259,6 +1452,21 @@ impl ScanPipeline {
     }
 }
 
+/// 批量写入 Finding 的默认最大条数
+const FINDING_BATCH_MAX_SIZE: usize = 50;
+/// 缓冲中的 Finding 默认最长等待写入时间
+const FINDING_BATCH_MAX_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+/// 去重窗口记录超过该数量时清理已过期的记录
+const DEDUP_WINDOW_PRUNE_THRESHOLD: usize = 10000;
+
+/// 等待批量写入的 Finding
+struct PendingFinding {
+    finding: Finding,
+    signature: String,
+    /// 命中的抑制规则 ID
+    suppressed_by: Option<String>,
+}
+
 /// Finding 去重服务
 pub struct FindingDeduplicator {
     /// 接收来自插件的 Finding
@@ -1269,6 +1477,20 @@ pub struct FindingDeduplicator {
     db_service: Option<Arc<DatabaseService>>,
     /// 新 Finding 事件发送器（用于通知前端）
     event_tx: Option<mpsc::UnboundedSender<Finding>>,
+    /// 误报抑制规则
+    suppression_rules: SharedSuppressionRules,
+    /// 去重键组成与再次上报窗口（支持按插件覆盖）
+    dedup_config: SharedDedupConfig,
+    /// 设置了 TTL 的去重键最近一次上报时间
+    dedup_window: DedupWindow,
+    /// 等待写入数据库的新 Finding
+    pending: Vec<PendingFinding>,
+    /// 缓冲期间重复命中的次数（按签名），写入后补记
+    pending_hits: HashMap<String, u32>,
+    /// 缓冲区非空期间持有，退出时等待缓冲区写入数据库
+    pending_guard: Option<OperationGuard>,
+    batch_max_size: usize,
+    batch_max_delay: std::time::Duration,
 }
 
 impl FindingDeduplicator {
@@ -1279,6 +1501,14 @@ impl FindingDeduplicator {
             cache: Arc::new(RwLock::new(std::collections::HashSet::new())),
             db_service: None,
             event_tx: None,
+            suppression_rules: Arc::new(RwLock::new(Vec::new())),
+            dedup_config: Arc::new(RwLock::new(Default::default())),
+            dedup_window: DedupWindow::default(),
+            pending: Vec::new(),
+            pending_hits: HashMap::new(),
+            pending_guard: None,
+            batch_max_size: FINDING_BATCH_MAX_SIZE,
+            batch_max_delay: FINDING_BATCH_MAX_DELAY,
         }
     }
 
@@ -1289,6 +1519,14 @@ impl FindingDeduplicator {
             cache: Arc::new(RwLock::new(std::collections::HashSet::new())),
             db_service: Some(db_service),
             event_tx: None,
+            suppression_rules: Arc::new(RwLock::new(Vec::new())),
+            dedup_config: Arc::new(RwLock::new(Default::default())),
+            dedup_window: DedupWindow::default(),
+            pending: Vec::new(),
+            pending_hits: HashMap::new(),
+            pending_guard: None,
+            batch_max_size: FINDING_BATCH_MAX_SIZE,
+            batch_max_delay: FINDING_BATCH_MAX_DELAY,
         }
     }
 
@@ -1307,15 +1545,38 @@ impl FindingDeduplicator {
         self
     }
 
-    /// 将 Finding 转换为 TrafficFinding 并插入数据库
-    async fn insert_finding_to_db(
+    /// 使用共享的误报抑制规则
+    pub fn with_suppression_rules(mut self, rules: SharedSuppressionRules) -> Self {
+        self.suppression_rules = rules;
+        self
+    }
+
+    /// 使用共享的去重配置
+    pub fn with_dedup_config(mut self, config: SharedDedupConfig) -> Self {
+        self.dedup_config = config;
+        self
+    }
+
+    /// 匹配抑制规则，返回命中的规则 ID
+    async fn matching_suppression_rule(
         &self,
         finding: &Finding,
-        db: &Arc<DatabaseService>,
-    ) -> Result<()> {
-        use sentinel_db::TrafficFinding;
+        signature: &str,
+    ) -> Option<String> {
+        let rules = self.suppression_rules.read().await;
+        find_suppression_rule(&rules, finding, signature).map(|r| r.id.clone())
+    }
 
-        let traffic_finding = TrafficFinding {
+    /// 设置批量写入的最大条数与最长等待时间
+    pub fn with_batching(mut self, max_size: usize, max_delay: std::time::Duration) -> Self {
+        self.batch_max_size = max_size.max(1);
+        self.batch_max_delay = max_delay;
+        self
+    }
+
+    /// 将 Finding 转换为 TrafficFinding，以去重键作为入库签名
+    fn to_traffic_finding(finding: &Finding, signature: &str) -> sentinel_db::TrafficFinding {
+        sentinel_db::TrafficFinding {
             id: finding.id.clone(),
             plugin_id: finding.plugin_id.clone(),
             vuln_type: finding.vuln_type.clone(),
@@ -1336,95 +1597,251 @@ impl FindingDeduplicator {
             response_headers: finding.response_headers.clone(),
             response_body: finding.response_body.clone(),
             created_at: finding.created_at,
-        };
-
-        db.insert_traffic_vulnerability(&traffic_finding).await?;
-        Ok(())
+            signature: Some(signature.to_string()),
+        }
     }
 
     /// 启动去重服务
+    ///
+    /// 新 Finding 先进入缓冲区，达到批量上限或等待超时后在一个事务中写入数据库。
     pub async fn start(mut self) -> Result<()> {
         info!("FindingDeduplicator started");
 
-        while let Some(finding) = self.finding_rx.recv().await {
-            let signature = finding.calculate_signature();
+        let mut flush_timer = tokio::time::interval(self.batch_max_delay);
+        flush_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let shutdown_token = shutdown::shutdown_token();
 
-            // 检查内存缓存
-            {
-                let cache = self.cache.read().await;
-                if cache.contains(&signature) {
-                    // 内存缓存命中，更新数据库命中次数
+        loop {
+            tokio::select! {
+                _ = shutdown_token.cancelled() => {
+                    info!("FindingDeduplicator stopping for shutdown");
+                    break;
+                }
+                received = self.finding_rx.recv() => {
+                    let Some(finding) = received else { break };
+                    self.handle_finding(finding).await;
+                    if self.pending.len() >= self.batch_max_size {
+                        self.flush_pending().await;
+                    }
+                }
+                _ = flush_timer.tick() => {
+                    if !self.pending.is_empty() {
+                        self.flush_pending().await;
+                    }
+                    self.prune_dedup_window().await;
+                }
+            }
+        }
+
+        self.flush_pending().await;
+        info!("FindingDeduplicator stopped");
+        Ok(())
+    }
+
+    /// 去重并把新 Finding 放入写入缓冲区
+    async fn handle_finding(&mut self, finding: Finding) {
+        let (signature, ttl) = {
+            let config = self.dedup_config.read().await;
+            let policy = config.policy_for(&finding.plugin_id);
+            (policy.key_for(&finding), policy.ttl())
+        };
+        let now = std::time::Instant::now();
+
+        // 检查内存缓存
+        {
+            let cache = self.cache.read().await;
+            if cache.contains(&signature) {
+                let pending = self.pending.iter().any(|p| p.signature == signature);
+                if !pending && self.dedup_window.should_refire(&signature, ttl, now) {
+                    drop(cache);
+                    self.refire(finding, &signature).await;
+                    return;
+                }
+                // 内存缓存命中，更新数据库命中次数
+                info!(
+                    "Finding duplicate (memory cache hit): {} - signature: {}",
+                    finding.title,
+                    &signature[..8.min(signature.len())]
+                );
+                if pending {
+                    // 原始 Finding 尚未写入，写入后再补记命中次数
+                    *self.pending_hits.entry(signature).or_insert(0) += 1;
+                } else if let Some(ref db) = self.db_service {
+                    if let Err(e) = db.update_traffic_vulnerability_hit(&signature).await {
+                        error!("Failed to update hit count: {}", e);
+                    }
+                }
+                return;
+            }
+        }
+
+        // 检查数据库（如果配置了）
+        if let Some(ref db) = self.db_service {
+            match db.check_traffic_signature_exists(&signature).await {
+                Ok(true) => {
+                    // 数据库已存在，更新命中次数并加入内存缓存
+                    if let Err(e) = db.update_traffic_vulnerability_hit(&signature).await {
+                        error!("Failed to update hit count: {}", e);
+                    }
+                    self.cache.write().await.insert(signature.clone());
+                    self.dedup_window.record(&signature, ttl, now);
                     info!(
-                        "Finding duplicate (memory cache hit): {} - signature: {}",
+                        "Finding exists in DB, updated hit count: {} (signature: {})",
                         finding.title,
                         &signature[..8.min(signature.len())]
                     );
-                    if let Some(ref db) = self.db_service {
-                        if let Err(e) = db.update_traffic_vulnerability_hit(&signature).await {
-                            error!("Failed to update hit count: {}", e);
-                        }
+                }
+                Ok(false) => {
+                    let suppressed_by = self.matching_suppression_rule(&finding, &signature).await;
+                    self.cache.write().await.insert(signature.clone());
+                    self.dedup_window.record(&signature, ttl, now);
+                    if self.pending_guard.is_none() {
+                        self.pending_guard = shutdown::begin_operation("finding batch insert");
                     }
-                    continue;
+                    self.pending.push(PendingFinding {
+                        finding,
+                        signature,
+                        suppressed_by,
+                    });
+                }
+                Err(e) => {
+                    error!("Failed to check signature in DB: {}", e);
                 }
             }
+        } else {
+            // 无数据库，仅内存去重
+            self.cache.write().await.insert(signature.clone());
+            self.dedup_window.record(&signature, ttl, now);
+            info!(
+                "New finding (memory only): {} - {} (signature: {})",
+                finding.title,
+                finding.severity,
+                &signature[..8]
+            );
+        }
+    }
 
-            // 检查数据库（如果配置了）
-            if let Some(ref db) = self.db_service {
-                match db.check_traffic_signature_exists(&signature).await {
-                    Ok(true) => {
-                        // 数据库已存在，更新命中次数并加入内存缓存
-                        if let Err(e) = db.update_traffic_vulnerability_hit(&signature).await {
-                            error!("Failed to update hit count: {}", e);
-                        }
-                        self.cache.write().await.insert(signature.clone());
-                        info!(
-                            "Finding exists in DB, updated hit count: {} (signature: {})",
-                            finding.title,
-                            &signature[..8.min(signature.len())]
-                        );
-                        continue;
-                    }
-                    Ok(false) => {
-                        // 数据库不存在，插入新记录
-                        match self.insert_finding_to_db(&finding, db).await {
-                            Ok(_) => {
-                                self.cache.write().await.insert(signature.clone());
-                                info!(
-                                    "New finding inserted to DB: {} - {} (signature: {})",
-                                    finding.title,
-                                    finding.severity,
-                                    &signature[..8.min(signature.len())]
-                                );
-                                // 发送事件通知前端
-                                if let Some(ref tx) = self.event_tx {
-                                    if let Err(e) = tx.send(finding.clone()) {
-                                        error!("Failed to send finding event: {}", e);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                error!("Failed to insert vulnerability: {}", e);
-                            }
+    /// 超出去重窗口的重复 Finding：补记命中次数并再次通知前端
+    async fn refire(&mut self, finding: Finding, signature: &str) {
+        info!(
+            "Finding re-surfaced after dedup window: {} (signature: {})",
+            finding.title,
+            &signature[..8.min(signature.len())]
+        );
+        if let Some(ref db) = self.db_service {
+            if let Err(e) = db.update_traffic_vulnerability_hit(signature).await {
+                error!("Failed to update hit count: {}", e);
+            }
+        }
+        if let Some(ref tx) = self.event_tx {
+            if let Err(e) = tx.send(finding) {
+                error!("Failed to send finding event: {}", e);
+            }
+        }
+    }
+
+    /// 去重窗口记录过多时清理已过期的记录
+    async fn prune_dedup_window(&mut self) {
+        if self.dedup_window.len() <= DEDUP_WINDOW_PRUNE_THRESHOLD {
+            return;
+        }
+        match self.dedup_config.read().await.max_ttl() {
+            Some(max_ttl) => self.dedup_window.prune(max_ttl, std::time::Instant::now()),
+            None => self.dedup_window = DedupWindow::default(),
+        }
+    }
+
+    /// 批量写入缓冲区中的 Finding；整批失败时逐条重试，仍失败的从缓存移除以便再次检测
+    async fn flush_pending(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let batch = std::mem::take(&mut self.pending);
+        let _guard = self.pending_guard.take();
+        let Some(db) = self.db_service.clone() else {
+            return;
+        };
+
+        let records: Vec<sentinel_db::TrafficFinding> = batch
+            .iter()
+            .map(|p| Self::to_traffic_finding(&p.finding, &p.signature))
+            .collect();
+        match db.insert_findings_batch(&records).await {
+            Ok(_) => {
+                for pending in &batch {
+                    self.after_insert(&db, pending).await;
+                }
+            }
+            Err(e) => {
+                warn!(
+                    "Batch insert of {} findings rolled back, retrying individually: {}",
+                    batch.len(),
+                    e
+                );
+                for (pending, record) in batch.iter().zip(records.iter()) {
+                    match db.insert_findings_batch(std::slice::from_ref(record)).await {
+                        Ok(_) => self.after_insert(&db, pending).await,
+                        Err(e) => {
+                            error!("Failed to insert vulnerability: {}", e);
+                            self.cache.write().await.remove(&pending.signature);
+                            self.pending_hits.remove(&pending.signature);
                         }
                     }
-                    Err(e) => {
-                        error!("Failed to check signature in DB: {}", e);
-                    }
                 }
-            } else {
-                // 无数据库，仅内存去重
-                self.cache.write().await.insert(signature.clone());
-                info!(
-                    "New finding (memory only): {} - {} (signature: {})",
-                    finding.title,
-                    finding.severity,
-                    &signature[..8]
-                );
             }
         }
 
-        info!("FindingDeduplicator stopped (channel closed)");
-        Ok(())
+        let hits: Vec<(String, u32)> = self.pending_hits.drain().collect();
+        for (signature, count) in hits {
+            for _ in 0..count {
+                if let Err(e) = db.update_traffic_vulnerability_hit(&signature).await {
+                    error!("Failed to update hit count: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Finding 入库后的处理：标记抑制或通知前端
+    async fn after_insert(&self, db: &Arc<DatabaseService>, pending: &PendingFinding) {
+        let finding = &pending.finding;
+        let signature = &pending.signature;
+        if let Some(rule_id) = &pending.suppressed_by {
+            // 命中抑制规则：以 suppressed 状态入库，不通知前端
+            if let Err(e) = db
+                .update_traffic_vulnerabilities_status_batch(
+                    std::slice::from_ref(&finding.id),
+                    SUPPRESSED_STATUS,
+                    None,
+                    &format!("suppression-rule:{}", rule_id),
+                )
+                .await
+            {
+                error!("Failed to mark finding as suppressed: {}", e);
+            }
+            if let Err(e) = db.record_traffic_suppression_hit(rule_id).await {
+                error!("Failed to record suppression hit: {}", e);
+            }
+            info!(
+                "Finding suppressed by rule {}: {} (signature: {})",
+                rule_id,
+                finding.title,
+                &signature[..8.min(signature.len())]
+            );
+            return;
+        }
+
+        info!(
+            "New finding inserted to DB: {} - {} (signature: {})",
+            finding.title,
+            finding.severity,
+            &signature[..8.min(signature.len())]
+        );
+        // 发送事件通知前端
+        if let Some(ref tx) = self.event_tx {
+            if let Err(e) = tx.send(finding.clone()) {
+                error!("Failed to send finding event: {}", e);
+            }
+        }
     }
 
     /// 获取缓存大小（用于统计）
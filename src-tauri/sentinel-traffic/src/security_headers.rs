@@ -0,0 +1,502 @@
+//! 安全响应头被动检查
+//!
+//! 作为扫描流水线内置的被动检查项，检查响应中的 CSP、HSTS、X-Frame-Options、
+//! X-Content-Type-Options、Referrer-Policy 与 Permissions-Policy，缺失或配置过弱时
+//! 生成 Finding，交由 `FindingDeduplicator` 去重。Finding 的 URL 取站点根地址，
+//! 因此同一站点的同一问题只上报一次。规则集（启用项与严重等级）可配置。
+
+use crate::{Confidence, Finding, RequestContext, ResponseContext, Severity};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// 内置检查产生的 Finding 使用的插件 ID
+pub const SECURITY_HEADERS_PLUGIN_ID: &str = "builtin:security_headers";
+
+/// HSTS max-age 默认下限（180 天）
+const DEFAULT_HSTS_MIN_MAX_AGE: u64 = 180 * 24 * 60 * 60;
+
+/// 检查的安全响应头
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityHeader {
+    ContentSecurityPolicy,
+    StrictTransportSecurity,
+    XFrameOptions,
+    XContentTypeOptions,
+    ReferrerPolicy,
+    PermissionsPolicy,
+}
+
+impl SecurityHeader {
+    pub fn header_name(&self) -> &'static str {
+        match self {
+            SecurityHeader::ContentSecurityPolicy => "Content-Security-Policy",
+            SecurityHeader::StrictTransportSecurity => "Strict-Transport-Security",
+            SecurityHeader::XFrameOptions => "X-Frame-Options",
+            SecurityHeader::XContentTypeOptions => "X-Content-Type-Options",
+            SecurityHeader::ReferrerPolicy => "Referrer-Policy",
+            SecurityHeader::PermissionsPolicy => "Permissions-Policy",
+        }
+    }
+}
+
+/// 单个响应头的检查规则
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecurityHeaderRule {
+    pub header: SecurityHeader,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 缺失时的严重等级
+    pub missing_severity: Severity,
+    /// 配置过弱时的严重等级
+    pub weak_severity: Severity,
+}
+
+impl SecurityHeaderRule {
+    fn new(header: SecurityHeader, missing_severity: Severity, weak_severity: Severity) -> Self {
+        Self {
+            header,
+            enabled: true,
+            missing_severity,
+            weak_severity,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 安全响应头检查配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecurityHeaderConfig {
+    pub enabled: bool,
+    /// 仅检查 HTML 文档响应（接口、静态资源通常不需要这些头）
+    pub html_only: bool,
+    /// HSTS max-age 低于此值（秒）视为过弱
+    pub hsts_min_max_age: u64,
+    pub rules: Vec<SecurityHeaderRule>,
+}
+
+impl Default for SecurityHeaderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            html_only: true,
+            hsts_min_max_age: DEFAULT_HSTS_MIN_MAX_AGE,
+            rules: vec![
+                SecurityHeaderRule::new(
+                    SecurityHeader::ContentSecurityPolicy,
+                    Severity::Low,
+                    Severity::Low,
+                ),
+                SecurityHeaderRule::new(
+                    SecurityHeader::StrictTransportSecurity,
+                    Severity::Medium,
+                    Severity::Low,
+                ),
+                SecurityHeaderRule::new(
+                    SecurityHeader::XFrameOptions,
+                    Severity::Low,
+                    Severity::Info,
+                ),
+                SecurityHeaderRule::new(
+                    SecurityHeader::XContentTypeOptions,
+                    Severity::Low,
+                    Severity::Info,
+                ),
+                SecurityHeaderRule::new(
+                    SecurityHeader::ReferrerPolicy,
+                    Severity::Info,
+                    Severity::Info,
+                ),
+                SecurityHeaderRule::new(
+                    SecurityHeader::PermissionsPolicy,
+                    Severity::Info,
+                    Severity::Info,
+                ),
+            ],
+        }
+    }
+}
+
+/// 共享的安全响应头检查配置（命令层修改后即时生效）
+pub type SharedSecurityHeaderConfig = Arc<RwLock<SecurityHeaderConfig>>;
+
+/// 检查结果
+#[derive(Debug, Clone, PartialEq)]
+enum Issue {
+    Missing,
+    Weak(String),
+}
+
+/// 按名称（不区分大小写）取响应头
+fn header<'a>(headers: &'a HashMap<String, String>, name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim())
+}
+
+/// 解析 CSP 指令（指令名小写 -> 源列表）
+fn csp_directives(policy: &str) -> HashMap<String, Vec<String>> {
+    policy
+        .split(';')
+        .filter_map(|directive| {
+            let mut parts = directive.split_whitespace();
+            let name = parts.next()?.to_ascii_lowercase();
+            Some((name, parts.map(|s| s.to_ascii_lowercase()).collect()))
+        })
+        .collect()
+}
+
+fn check_csp(policy: Option<&str>) -> Option<Issue> {
+    let Some(policy) = policy else {
+        return Some(Issue::Missing);
+    };
+    let directives = csp_directives(policy);
+    let Some(sources) = directives
+        .get("script-src")
+        .or_else(|| directives.get("default-src"))
+    else {
+        return Some(Issue::Weak(
+            "no script-src or default-src directive".to_string(),
+        ));
+    };
+    // 含 nonce/hash 时支持 CSP2 的浏览器会忽略 'unsafe-inline'
+    let has_nonce_or_hash = sources
+        .iter()
+        .any(|s| s.starts_with("'nonce-") || s.starts_with("'sha"));
+    let mut weaknesses = Vec::new();
+    if sources.iter().any(|s| s == "'unsafe-inline'") && !has_nonce_or_hash {
+        weaknesses.push("'unsafe-inline'");
+    }
+    if sources.iter().any(|s| s == "'unsafe-eval'") {
+        weaknesses.push("'unsafe-eval'");
+    }
+    if sources
+        .iter()
+        .any(|s| matches!(s.as_str(), "*" | "http:" | "https:" | "data:"))
+    {
+        weaknesses.push("wildcard or scheme source");
+    }
+    if weaknesses.is_empty() {
+        None
+    } else {
+        Some(Issue::Weak(format!(
+            "script sources allow {}",
+            weaknesses.join(", ")
+        )))
+    }
+}
+
+fn check_hsts(value: Option<&str>, min_max_age: u64) -> Option<Issue> {
+    let value = value?;
+    let max_age = value.split(';').find_map(|part| {
+        let (key, value) = part.split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("max-age")
+            .then(|| value.trim().trim_matches('"').parse::<u64>().ok())
+            .flatten()
+    });
+    match max_age {
+        None => Some(Issue::Weak("max-age is missing or invalid".to_string())),
+        Some(age) if age < min_max_age => Some(Issue::Weak(format!(
+            "max-age={} is below {}",
+            age, min_max_age
+        ))),
+        Some(_) => None,
+    }
+}
+
+/// 检查一个响应，返回需要上报的 Finding
+pub fn analyze(
+    config: &SecurityHeaderConfig,
+    req: &RequestContext,
+    resp: &ResponseContext,
+) -> Vec<Finding> {
+    if !config.enabled || !(200..300).contains(&resp.status) || resp.status == 204 {
+        return Vec::new();
+    }
+    let headers = resp.edited_headers.as_ref().unwrap_or(&resp.headers);
+    let content_type = resp
+        .content_type
+        .as_deref()
+        .or_else(|| header(headers, "content-type"))
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let is_html = content_type.contains("text/html") || content_type.contains("xhtml");
+    if config.html_only && !is_html {
+        return Vec::new();
+    }
+    let Ok(url) = url::Url::parse(&req.url) else {
+        return Vec::new();
+    };
+    let is_https = url.scheme() == "https";
+    let csp = header(headers, "content-security-policy");
+
+    let mut findings = Vec::new();
+    for rule in config.rules.iter().filter(|r| r.enabled) {
+        let value = header(headers, rule.header.header_name());
+        let issue = match rule.header {
+            SecurityHeader::ContentSecurityPolicy => check_csp(csp),
+            // 浏览器会忽略明文 HTTP 响应中的 HSTS
+            SecurityHeader::StrictTransportSecurity if !is_https => None,
+            SecurityHeader::StrictTransportSecurity => match value {
+                None => Some(Issue::Missing),
+                Some(_) => check_hsts(value, config.hsts_min_max_age),
+            },
+            SecurityHeader::XFrameOptions => {
+                // CSP frame-ancestors 优先于 X-Frame-Options
+                let frame_ancestors =
+                    csp.is_some_and(|p| csp_directives(p).contains_key("frame-ancestors"));
+                match value {
+                    None if frame_ancestors => None,
+                    None => Some(Issue::Missing),
+                    Some(v)
+                        if v.eq_ignore_ascii_case("deny")
+                            || v.eq_ignore_ascii_case("sameorigin") =>
+                    {
+                        None
+                    }
+                    Some(v) => Some(Issue::Weak(format!("unsupported value '{}'", v))),
+                }
+            }
+            SecurityHeader::XContentTypeOptions => match value {
+                None => Some(Issue::Missing),
+                Some(v) if v.eq_ignore_ascii_case("nosniff") => None,
+                Some(v) => Some(Issue::Weak(format!("value '{}' is not nosniff", v))),
+            },
+            SecurityHeader::ReferrerPolicy => match value {
+                None => Some(Issue::Missing),
+                Some(v)
+                    if v.split(',').any(|p| {
+                        matches!(
+                            p.trim().to_ascii_lowercase().as_str(),
+                            "unsafe-url" | "no-referrer-when-downgrade"
+                        )
+                    }) =>
+                {
+                    Some(Issue::Weak(format!("'{}' leaks full URLs", v)))
+                }
+                Some(_) => None,
+            },
+            SecurityHeader::PermissionsPolicy => value.is_none().then_some(Issue::Missing),
+        };
+        if let Some(issue) = issue {
+            findings.push(to_finding(rule, &issue, value, &url, &req.method, resp));
+        }
+    }
+    findings
+}
+
+fn to_finding(
+    rule: &SecurityHeaderRule,
+    issue: &Issue,
+    value: Option<&str>,
+    url: &url::Url,
+    method: &str,
+    resp: &ResponseContext,
+) -> Finding {
+    let name = rule.header.header_name();
+    let (vuln_type, severity, title, description) = match issue {
+        Issue::Missing => (
+            "missing_security_header",
+            rule.missing_severity,
+            format!("Missing {} header", name),
+            format!("The response does not set the {} header.", name),
+        ),
+        Issue::Weak(reason) => (
+            "weak_security_header",
+            rule.weak_severity,
+            format!("Weak {} header", name),
+            format!("The {} header is too permissive: {}.", name, reason),
+        ),
+    };
+    let evidence = match value {
+        Some(value) => format!("{}: {}", name, value),
+        None => format!("{} header not present ({})", name, url),
+    };
+    // 按站点上报：同一站点同一问题只产生一条 Finding
+    let site = format!("{}/", url.origin().ascii_serialization());
+    let mut response_headers: Vec<String> = resp
+        .headers
+        .iter()
+        .map(|(key, value)| format!("{}: {}", key, value))
+        .collect();
+    response_headers.sort();
+
+    Finding {
+        id: uuid::Uuid::new_v4().to_string(),
+        plugin_id: SECURITY_HEADERS_PLUGIN_ID.to_string(),
+        vuln_type: vuln_type.to_string(),
+        severity,
+        title,
+        description,
+        evidence,
+        location: format!("header:{}", name),
+        confidence: Confidence::High,
+        cwe: Some("CWE-693".to_string()),
+        owasp: Some("A05:2021".to_string()),
+        remediation: Some(remediation(rule.header).to_string()),
+        url: site,
+        method: method.to_string(),
+        created_at: Utc::now(),
+        request_headers: None,
+        request_body: None,
+        response_status: Some(resp.status as i32),
+        response_headers: Some(response_headers.join("\r\n")),
+        response_body: None,
+    }
+}
+
+fn remediation(header: SecurityHeader) -> &'static str {
+    match header {
+        SecurityHeader::ContentSecurityPolicy => {
+            "Set a Content-Security-Policy with a restrictive script-src (nonces or hashes instead of 'unsafe-inline'/'unsafe-eval')."
+        }
+        SecurityHeader::StrictTransportSecurity => {
+            "Set Strict-Transport-Security: max-age=31536000; includeSubDomains on all HTTPS responses."
+        }
+        SecurityHeader::XFrameOptions => {
+            "Set X-Frame-Options: DENY (or SAMEORIGIN), or CSP frame-ancestors."
+        }
+        SecurityHeader::XContentTypeOptions => "Set X-Content-Type-Options: nosniff.",
+        SecurityHeader::ReferrerPolicy => {
+            "Set Referrer-Policy: strict-origin-when-cross-origin or stricter."
+        }
+        SecurityHeader::PermissionsPolicy => {
+            "Set a Permissions-Policy that disables unused browser features (camera, microphone, geolocation, ...)."
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(url: &str, headers: &[(&str, &str)]) -> (RequestContext, ResponseContext) {
+        let req = RequestContext {
+            id: "1".to_string(),
+            method: "GET".to_string(),
+            url: url.to_string(),
+            headers: HashMap::new(),
+            body: vec![],
+            content_type: None,
+            query_params: HashMap::new(),
+            is_https: url.starts_with("https"),
+            timestamp: Utc::now(),
+            was_edited: false,
+            edited_method: None,
+            edited_url: None,
+            edited_headers: None,
+            edited_body: None,
+            body_truncated: false,
+            body_size: None,
+        };
+        let resp = ResponseContext {
+            request_id: "1".to_string(),
+            status: 200,
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            body: b"<html></html>".to_vec(),
+            content_type: Some("text/html; charset=utf-8".to_string()),
+            timestamp: Utc::now(),
+            was_edited: false,
+            edited_status: None,
+            edited_headers: None,
+            edited_body: None,
+            body_truncated: false,
+            body_size: None,
+            raw_body: None,
+        };
+        (req, resp)
+    }
+
+    fn finding_for(findings: &[Finding], header: SecurityHeader) -> Option<&Finding> {
+        findings
+            .iter()
+            .find(|f| f.location == format!("header:{}", header.header_name()))
+    }
+
+    #[test]
+    fn test_missing_hsts_on_https_is_flagged() {
+        let (req, resp) = exchange(
+            "https://shop.example.com/account?id=1",
+            &[
+                ("Content-Security-Policy", "default-src 'self'"),
+                ("X-Frame-Options", "DENY"),
+                ("X-Content-Type-Options", "nosniff"),
+            ],
+        );
+        let findings = analyze(&SecurityHeaderConfig::default(), &req, &resp);
+        let hsts = finding_for(&findings, SecurityHeader::StrictTransportSecurity)
+            .expect("missing HSTS finding");
+        assert_eq!(hsts.vuln_type, "missing_security_header");
+        assert_eq!(hsts.severity, Severity::Medium);
+        assert_eq!(hsts.plugin_id, SECURITY_HEADERS_PLUGIN_ID);
+        // 按站点上报，便于去重
+        assert_eq!(hsts.url, "https://shop.example.com/");
+
+        // 明文 HTTP 不要求 HSTS
+        let (req, resp) = exchange("http://shop.example.com/", &[]);
+        let findings = analyze(&SecurityHeaderConfig::default(), &req, &resp);
+        assert!(finding_for(&findings, SecurityHeader::StrictTransportSecurity).is_none());
+    }
+
+    #[test]
+    fn test_strong_csp_produces_no_finding() {
+        let (req, resp) = exchange(
+            "https://app.example.com/",
+            &[(
+                "content-security-policy",
+                "default-src 'none'; script-src 'self' 'nonce-r4nd0m' 'unsafe-inline'; frame-ancestors 'none'",
+            )],
+        );
+        let findings = analyze(&SecurityHeaderConfig::default(), &req, &resp);
+        assert!(finding_for(&findings, SecurityHeader::ContentSecurityPolicy).is_none());
+        // frame-ancestors 代替 X-Frame-Options
+        assert!(finding_for(&findings, SecurityHeader::XFrameOptions).is_none());
+
+        let (req, resp) = exchange(
+            "https://app.example.com/",
+            &[(
+                "Content-Security-Policy",
+                "script-src 'self' 'unsafe-inline' 'unsafe-eval'",
+            )],
+        );
+        let findings = analyze(&SecurityHeaderConfig::default(), &req, &resp);
+        let csp = finding_for(&findings, SecurityHeader::ContentSecurityPolicy).unwrap();
+        assert_eq!(csp.vuln_type, "weak_security_header");
+        assert!(csp.description.contains("'unsafe-eval'"));
+    }
+
+    #[test]
+    fn test_rule_set_is_configurable() {
+        let (req, resp) = exchange(
+            "https://app.example.com/",
+            &[("Strict-Transport-Security", "max-age=3600")],
+        );
+        let mut config = SecurityHeaderConfig::default();
+        for rule in config.rules.iter_mut() {
+            rule.enabled = rule.header == SecurityHeader::StrictTransportSecurity;
+        }
+        let findings = analyze(&config, &req, &resp);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].vuln_type, "weak_security_header");
+
+        config.hsts_min_max_age = 3600;
+        assert!(analyze(&config, &req, &resp).is_empty());
+
+        // 非 HTML 响应默认跳过
+        let (req, mut resp) = exchange("https://api.example.com/v1/users", &[]);
+        resp.content_type = Some("application/json".to_string());
+        assert!(analyze(&SecurityHeaderConfig::default(), &req, &resp).is_empty());
+    }
+}
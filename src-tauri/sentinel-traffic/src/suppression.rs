@@ -0,0 +1,185 @@
+//! Finding 误报抑制
+//!
+//! 规则按 插件 ID + 主机/路径 glob + 证据特征 匹配新 Finding，命中的 Finding
+//! 以 `suppressed` 状态入库，不再推送到前端。规则为空的字段视为匹配任意值。
+
+use crate::Finding;
+use sentinel_db::TrafficSuppressionRule;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// 被抑制的 Finding 使用的状态
+pub const SUPPRESSED_STATUS: &str = "suppressed";
+
+/// 共享的抑制规则（命令层修改后即时生效）
+pub type SharedSuppressionRules = Arc<RwLock<Vec<TrafficSuppressionRule>>>;
+
+/// 查找第一条匹配的已启用规则
+pub fn find_suppression_rule<'a>(
+    rules: &'a [TrafficSuppressionRule],
+    finding: &Finding,
+    signature: &str,
+) -> Option<&'a TrafficSuppressionRule> {
+    let parsed = url::Url::parse(&finding.url).ok();
+    let host = parsed
+        .as_ref()
+        .and_then(|u| u.host_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let path = parsed.as_ref().map(|u| u.path()).unwrap_or("/");
+
+    rules.iter().find(|rule| {
+        rule.enabled
+            && pattern_set(&rule.plugin_id).is_none_or(|p| p == finding.plugin_id)
+            && pattern_set(&rule.host_pattern)
+                .is_none_or(|p| glob_match(&p.to_ascii_lowercase(), &host))
+            && pattern_set(&rule.path_pattern).is_none_or(|p| glob_match(p, path))
+            && pattern_set(&rule.evidence_pattern)
+                .is_none_or(|p| p == signature || glob_match(p, &finding.evidence))
+    })
+}
+
+fn pattern_set(pattern: &Option<String>) -> Option<&str> {
+    pattern.as_deref().map(str::trim).filter(|p| !p.is_empty())
+}
+
+/// 简单 glob：`*` 匹配任意字符序列，`?` 匹配单个字符
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Confidence, Severity};
+
+    fn finding(plugin_id: &str, url: &str, evidence: &str) -> Finding {
+        Finding {
+            id: "f1".to_string(),
+            plugin_id: plugin_id.to_string(),
+            vuln_type: "info_leak".to_string(),
+            severity: Severity::Low,
+            title: "Server version disclosed".to_string(),
+            description: String::new(),
+            evidence: evidence.to_string(),
+            location: "header.Server".to_string(),
+            confidence: Confidence::High,
+            cwe: None,
+            owasp: None,
+            remediation: None,
+            url: url.to_string(),
+            method: "GET".to_string(),
+            created_at: chrono::Utc::now(),
+            request_headers: None,
+            request_body: None,
+            response_status: None,
+            response_headers: None,
+            response_body: None,
+        }
+    }
+
+    fn rule() -> TrafficSuppressionRule {
+        TrafficSuppressionRule {
+            id: "r1".to_string(),
+            name: "CDN banner".to_string(),
+            plugin_id: Some("builtin.info_leak".to_string()),
+            host_pattern: Some("*.Example.com".to_string()),
+            path_pattern: Some("/static/*".to_string()),
+            evidence_pattern: Some("Server: nginx*".to_string()),
+            enabled: true,
+            hit_count: 0,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.example.com", "cdn.example.com"));
+        assert!(!glob_match("*.example.com", "example.org"));
+        assert!(glob_match("/api/v?/*", "/api/v1/users"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("/static/*", "/admin"));
+    }
+
+    #[test]
+    fn test_matching_finding_is_suppressed() {
+        let rules = vec![rule()];
+        let f = finding(
+            "builtin.info_leak",
+            "https://cdn.example.com/static/app.js",
+            "Server: nginx/1.25",
+        );
+        let matched = find_suppression_rule(&rules, &f, &f.calculate_signature());
+        assert_eq!(matched.map(|r| r.id.as_str()), Some("r1"));
+
+        // 证据特征也可以是精确的 Finding 签名
+        let mut by_signature = rule();
+        by_signature.evidence_pattern = Some(f.calculate_signature());
+        assert!(find_suppression_rule(&[by_signature], &f, &f.calculate_signature()).is_some());
+    }
+
+    #[test]
+    fn test_non_matching_finding_is_not_suppressed() {
+        let rules = vec![rule()];
+        let cases = [
+            finding(
+                "builtin.xss",
+                "https://cdn.example.com/static/app.js",
+                "Server: nginx/1.25",
+            ),
+            finding(
+                "builtin.info_leak",
+                "https://api.other.com/static/app.js",
+                "Server: nginx/1.25",
+            ),
+            finding(
+                "builtin.info_leak",
+                "https://cdn.example.com/admin",
+                "Server: nginx/1.25",
+            ),
+            finding(
+                "builtin.info_leak",
+                "https://cdn.example.com/static/app.js",
+                "Server: Apache",
+            ),
+        ];
+        for f in &cases {
+            assert!(
+                find_suppression_rule(&rules, f, &f.calculate_signature()).is_none(),
+                "{} {} should not be suppressed",
+                f.plugin_id,
+                f.url
+            );
+        }
+
+        let mut disabled = rule();
+        disabled.enabled = false;
+        let f = &finding(
+            "builtin.info_leak",
+            "https://cdn.example.com/static/app.js",
+            "Server: nginx/1.25",
+        );
+        assert!(find_suppression_rule(&[disabled], f, &f.calculate_signature()).is_none());
+    }
+}
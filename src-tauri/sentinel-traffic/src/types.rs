@@ -9,6 +9,9 @@ pub struct ProxyStatus {
     pub running: bool,
     pub port: u16,
     pub mitm_enabled: bool,
+    /// 被动扫描是否已暂停（代理仍在监听，仅跳过插件分析）
+    #[serde(default)]
+    pub paused: bool,
     pub stats: ProxyStats,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -0,0 +1,487 @@
+//! 上游代理链
+//!
+//! 按顺序经多个上游代理建立到目标的隧道（如 企业 HTTP 代理 → SOCKS5 → 目标），
+//! 每一跳可单独配置认证：
+//! - HTTP 代理使用 CONNECT，Basic 认证通过 `Proxy-Authorization` 发送
+//! - SOCKS5 代理支持无认证和用户名/密码认证（RFC 1929）
+//!
+//! 某一跳不可达、认证失败或握手异常时返回 [`UpstreamHopError`]，指明是第几跳。
+
+use crate::{Result, TrafficError};
+use base64::Engine;
+use sentinel_core::global_proxy::{http_connect_handshake, socks5_handshake, ProxyEndpoint};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::net::TcpStream;
+
+/// 每一跳连接与握手的超时
+const HOP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 上游代理类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpstreamProxyKind {
+    #[default]
+    Http,
+    Socks5,
+}
+
+/// 代理链中的一跳
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct UpstreamProxyHop {
+    #[serde(default)]
+    pub kind: UpstreamProxyKind,
+    pub host: String,
+    pub port: u16,
+    /// 认证类型（None, Basic）；SOCKS5 下 Basic 表示用户名/密码认证
+    #[serde(default)]
+    pub auth_type: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl std::fmt::Debug for UpstreamProxyHop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出密码明文
+        f.debug_struct("UpstreamProxyHop")
+            .field("kind", &self.kind)
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("auth_type", &self.auth_type)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .finish()
+    }
+}
+
+impl UpstreamProxyHop {
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    /// Basic 认证对应的 `Proxy-Authorization` 头值；未配置认证时返回 None
+    pub fn proxy_authorization(&self) -> Option<String> {
+        let (username, password) = self.credentials()?;
+        let token =
+            base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+        Some(format!("Basic {}", token))
+    }
+
+    /// 转换为 sentinel-core 握手使用的代理端点；仅 Basic 认证时携带凭据
+    fn endpoint(&self) -> ProxyEndpoint {
+        let credentials = self.credentials();
+        ProxyEndpoint {
+            scheme: None,
+            host: self.host.clone(),
+            port: self.port,
+            username: credentials.map(|(username, _)| username.to_string()),
+            password: credentials.map(|(_, password)| password.to_string()),
+        }
+    }
+
+    fn credentials(&self) -> Option<(&str, &str)> {
+        if !self.auth_type.eq_ignore_ascii_case("basic") {
+            return None;
+        }
+        Some((
+            self.username.as_deref().unwrap_or_default(),
+            self.password.as_deref().unwrap_or_default(),
+        ))
+    }
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.host.trim().is_empty() {
+            return Err("host is empty".to_string());
+        }
+        if self.port == 0 {
+            return Err("port is 0".to_string());
+        }
+        match self.auth_type.as_str() {
+            "" => {}
+            t if t.eq_ignore_ascii_case("none") => {}
+            t if t.eq_ignore_ascii_case("basic") => {
+                let username = self.username.as_deref().unwrap_or_default();
+                if username.is_empty() {
+                    return Err("Basic auth requires a username".to_string());
+                }
+                if self.kind == UpstreamProxyKind::Socks5
+                    && (username.len() > 255
+                        || self.password.as_deref().unwrap_or_default().len() > 255)
+                {
+                    return Err("SOCKS5 username and password must be at most 255 bytes".into());
+                }
+            }
+            other => return Err(format!("unsupported auth type '{}'", other)),
+        }
+        Ok(())
+    }
+}
+
+/// 代理链中某一跳失败
+#[derive(Debug, thiserror::Error)]
+#[error("上游代理第 {hop} 跳 ({address}) 失败: {reason}")]
+pub struct UpstreamHopError {
+    /// 从 1 开始的跳数
+    pub hop: usize,
+    pub address: String,
+    pub reason: String,
+}
+
+/// 校验代理链配置
+pub fn validate_chain(hops: &[UpstreamProxyHop]) -> Result<()> {
+    if hops.is_empty() {
+        return Err(TrafficError::Proxy(
+            "Upstream proxy chain is empty".to_string(),
+        ));
+    }
+    for (i, hop) in hops.iter().enumerate() {
+        hop.validate().map_err(|reason| {
+            TrafficError::Proxy(format!(
+                "Upstream proxy hop {} ({}): {}",
+                i + 1,
+                hop.address(),
+                reason
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+/// 隧道建立失败的原因
+enum TunnelError {
+    /// 当前这一跳的问题（认证失败、协议错误、IO 错误）
+    Hop(String),
+    /// 当前这一跳无法连到下一跳
+    Unreachable(String),
+}
+
+/// 明文 HTTP 请求可直接转发给的代理：链中只有一个 HTTP 代理时返回它。
+///
+/// 此时请求以 absolute-form 发给该代理而不是 CONNECT，很多企业代理拒绝到 80 端口的 CONNECT。
+pub fn plain_http_forward_hop(hops: &[UpstreamProxyHop]) -> Option<&UpstreamProxyHop> {
+    match hops {
+        [hop] if hop.kind == UpstreamProxyKind::Http => Some(hop),
+        _ => None,
+    }
+}
+
+/// 连接到代理链的第一跳（带超时）
+pub async fn connect_first_hop(
+    first: &UpstreamProxyHop,
+) -> std::result::Result<TcpStream, UpstreamHopError> {
+    let hop_error = |reason: String| UpstreamHopError {
+        hop: 1,
+        address: first.address(),
+        reason,
+    };
+    match tokio::time::timeout(HOP_TIMEOUT, TcpStream::connect(first.address())).await {
+        Ok(Ok(stream)) => Ok(stream),
+        Ok(Err(e)) => Err(hop_error(format!("connect failed: {}", e))),
+        Err(_) => Err(hop_error("connect timed out".to_string())),
+    }
+}
+
+/// 经代理链建立到 `target_host:target_port` 的 TCP 隧道
+pub async fn connect_through_chain(
+    hops: &[UpstreamProxyHop],
+    target_host: &str,
+    target_port: u16,
+) -> std::result::Result<TcpStream, UpstreamHopError> {
+    let hop_error = |index: usize, reason: String| UpstreamHopError {
+        hop: index + 1,
+        address: hops[index].address(),
+        reason,
+    };
+
+    let Some(first) = hops.first() else {
+        return Err(UpstreamHopError {
+            hop: 0,
+            address: format!("{}:{}", target_host, target_port),
+            reason: "upstream proxy chain is empty".to_string(),
+        });
+    };
+    let mut stream = connect_first_hop(first).await?;
+
+    for (i, hop) in hops.iter().enumerate() {
+        let (next_host, next_port) = match hops.get(i + 1) {
+            Some(next) => (next.host.as_str(), next.port),
+            None => (target_host, target_port),
+        };
+        let result = match tokio::time::timeout(
+            HOP_TIMEOUT,
+            open_tunnel(&mut stream, hop, next_host, next_port),
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(_) => Err(TunnelError::Hop("handshake timed out".to_string())),
+        };
+        match result {
+            Ok(()) => {}
+            // 下一跳不可达时归咎于下一跳；目标不可达时归咎于最后一跳
+            Err(TunnelError::Unreachable(reason)) if i + 1 < hops.len() => {
+                return Err(hop_error(i + 1, format!("unreachable: {}", reason)));
+            }
+            Err(TunnelError::Unreachable(reason)) => {
+                return Err(hop_error(
+                    i,
+                    format!(
+                        "cannot reach target {}:{}: {}",
+                        target_host, target_port, reason
+                    ),
+                ));
+            }
+            Err(TunnelError::Hop(reason)) => return Err(hop_error(i, reason)),
+        }
+    }
+
+    Ok(stream)
+}
+
+async fn open_tunnel(
+    stream: &mut TcpStream,
+    hop: &UpstreamProxyHop,
+    host: &str,
+    port: u16,
+) -> std::result::Result<(), TunnelError> {
+    let endpoint = hop.endpoint();
+    let result = match hop.kind {
+        UpstreamProxyKind::Http => http_connect_handshake(stream, &endpoint, host, port).await,
+        // 域名交给代理解析，与 socks5h 一致
+        UpstreamProxyKind::Socks5 => socks5_handshake(stream, &endpoint, host, port, true).await,
+    };
+    result.map_err(|e| match e.kind() {
+        std::io::ErrorKind::HostUnreachable => TunnelError::Unreachable(e.to_string()),
+        _ => TunnelError::Hop(e.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    type Seen = Arc<Mutex<Vec<String>>>;
+
+    async fn relay(mut client: TcpStream, target: &str) {
+        if let Ok(mut upstream) = TcpStream::connect(target).await {
+            let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+        }
+    }
+
+    /// 记录收到的 CONNECT 请求头的 HTTP 代理
+    async fn mock_http_proxy(seen: Seen) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut client, _)) = listener.accept().await {
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    let mut header = Vec::new();
+                    while !header.ends_with(b"\r\n\r\n") {
+                        header.push(client.read_u8().await.unwrap());
+                    }
+                    let header = String::from_utf8_lossy(&header).to_string();
+                    let target = header.split_whitespace().nth(1).unwrap().to_string();
+                    seen.lock().unwrap().push(header);
+                    match TcpStream::connect(&target).await {
+                        Ok(mut upstream) => {
+                            client
+                                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                                .await
+                                .unwrap();
+                            let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+                        }
+                        Err(_) => {
+                            let _ = client.write_all(b"HTTP/1.1 502 Bad Gateway\r\n\r\n").await;
+                        }
+                    }
+                });
+            }
+        });
+        port
+    }
+
+    /// 要求用户名/密码认证的 SOCKS5 代理，记录 "user:pass -> host:port"
+    async fn mock_socks5_proxy(seen: Seen) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut client, _)) = listener.accept().await {
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    let mut head = [0u8; 2];
+                    client.read_exact(&mut head).await.unwrap();
+                    let mut methods = vec![0u8; head[1] as usize];
+                    client.read_exact(&mut methods).await.unwrap();
+                    assert!(methods.contains(&0x02));
+                    client.write_all(&[0x05, 0x02]).await.unwrap();
+
+                    assert_eq!(client.read_u8().await.unwrap(), 0x01);
+                    let username = read_string(&mut client).await;
+                    let password = read_string(&mut client).await;
+                    client.write_all(&[0x01, 0x00]).await.unwrap();
+
+                    let mut request = [0u8; 4];
+                    client.read_exact(&mut request).await.unwrap();
+                    let host = match request[3] {
+                        0x01 => {
+                            let mut ip = [0u8; 4];
+                            client.read_exact(&mut ip).await.unwrap();
+                            std::net::Ipv4Addr::from(ip).to_string()
+                        }
+                        0x03 => read_string(&mut client).await,
+                        atyp => panic!("unexpected address type {}", atyp),
+                    };
+                    let port = client.read_u16().await.unwrap();
+                    let target = format!("{}:{}", host, port);
+                    seen.lock()
+                        .unwrap()
+                        .push(format!("{}:{} -> {}", username, password, target));
+                    client
+                        .write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+                        .await
+                        .unwrap();
+                    relay(client, &target).await;
+                });
+            }
+        });
+        port
+    }
+
+    async fn read_string(client: &mut TcpStream) -> String {
+        let len = client.read_u8().await.unwrap() as usize;
+        let mut buf = vec![0u8; len];
+        client.read_exact(&mut buf).await.unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    async fn echo_server() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let (mut reader, mut writer) = socket.split();
+                    let _ = tokio::io::copy(&mut reader, &mut writer).await;
+                });
+            }
+        });
+        port
+    }
+
+    fn hop(
+        kind: UpstreamProxyKind,
+        port: u16,
+        credentials: Option<(&str, &str)>,
+    ) -> UpstreamProxyHop {
+        UpstreamProxyHop {
+            kind,
+            host: "127.0.0.1".to_string(),
+            port,
+            auth_type: if credentials.is_some() {
+                "Basic"
+            } else {
+                "None"
+            }
+            .to_string(),
+            username: credentials.map(|(u, _)| u.to_string()),
+            password: credentials.map(|(_, p)| p.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_two_hop_chain_used_in_order() {
+        let http_seen = Seen::default();
+        let socks_seen = Seen::default();
+        let http_port = mock_http_proxy(http_seen.clone()).await;
+        let socks_port = mock_socks5_proxy(socks_seen.clone()).await;
+        let target_port = echo_server().await;
+
+        let hops = vec![
+            hop(UpstreamProxyKind::Http, http_port, Some(("corp", "secret"))),
+            hop(UpstreamProxyKind::Socks5, socks_port, Some(("sock", "pw"))),
+        ];
+        validate_chain(&hops).unwrap();
+
+        let mut stream = connect_through_chain(&hops, "127.0.0.1", target_port)
+            .await
+            .unwrap();
+        stream.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+
+        // 第一跳（HTTP）收到的是到第二跳的 CONNECT，并带有本跳的认证
+        let http_seen = http_seen.lock().unwrap().clone();
+        assert_eq!(http_seen.len(), 1);
+        assert!(http_seen[0].starts_with(&format!("CONNECT 127.0.0.1:{} HTTP/1.1", socks_port)));
+        let token = base64::engine::general_purpose::STANDARD.encode("corp:secret");
+        assert!(http_seen[0].contains(&format!("Proxy-Authorization: Basic {}", token)));
+
+        // 第二跳（SOCKS5）用自己的凭据连接最终目标
+        assert_eq!(
+            socks_seen.lock().unwrap().clone(),
+            vec![format!("sock:pw -> 127.0.0.1:{}", target_port)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_hop_is_identified() {
+        let http_port = mock_http_proxy(Seen::default()).await;
+        // 占用后释放一个端口，确保无人监听
+        let dead_port = {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        let hops = vec![
+            hop(UpstreamProxyKind::Http, http_port, None),
+            hop(UpstreamProxyKind::Http, dead_port, None),
+        ];
+
+        let err = connect_through_chain(&hops, "127.0.0.1", 80)
+            .await
+            .unwrap_err();
+        assert_eq!(err.hop, 2);
+        assert_eq!(err.address, format!("127.0.0.1:{}", dead_port));
+
+        let err = connect_through_chain(&hops[1..], "127.0.0.1", 80)
+            .await
+            .unwrap_err();
+        assert_eq!(err.hop, 1);
+    }
+
+    #[test]
+    fn test_validate_chain() {
+        assert!(validate_chain(&[]).is_err());
+        assert!(validate_chain(&[hop(UpstreamProxyKind::Http, 0, None)]).is_err());
+
+        let mut missing_user = hop(UpstreamProxyKind::Socks5, 1080, None);
+        missing_user.auth_type = "Basic".to_string();
+        let err = validate_chain(&[hop(UpstreamProxyKind::Http, 8080, None), missing_user])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("hop 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_debug_redacts_password() {
+        let hop = hop(UpstreamProxyKind::Http, 8080, Some(("corp", "secret")));
+        let debug = format!("{:?}", hop);
+        assert!(debug.contains("corp"));
+        assert!(!debug.contains("secret"));
+    }
+
+    #[test]
+    fn test_plain_http_forward_only_for_single_http_hop() {
+        let http = hop(UpstreamProxyKind::Http, 8080, None);
+        let socks = hop(UpstreamProxyKind::Socks5, 1080, None);
+        assert!(plain_http_forward_hop(std::slice::from_ref(&http)).is_some());
+        assert!(plain_http_forward_hop(std::slice::from_ref(&socks)).is_none());
+        assert!(plain_http_forward_hop(&[http, socks]).is_none());
+    }
+}
@@ -0,0 +1,132 @@
+//! 被动扫描暂停测试：暂停期间流量仍记录到历史，但不产生任何 Finding
+
+use chrono::Utc;
+use sentinel_traffic::{
+    Finding, PluginMetadata, ProxyHistoryCache, RequestContext, ResponseContext, ScanPipeline,
+    ScanTask, Severity,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+
+const ALWAYS_FIND_PLUGIN: &str = r#"
+export async function scan_transaction(transaction) {
+    Sentinel.emitFinding({
+        vuln_type: "pause_test",
+        title: "Pause Test",
+        description: "Emitted for every transaction",
+        evidence: transaction.request.url,
+        location: "url",
+        severity: "info",
+        confidence: "high"
+    });
+}
+"#;
+
+fn metadata() -> PluginMetadata {
+    PluginMetadata {
+        id: "pause_test_plugin".to_string(),
+        name: "Pause Test Plugin".to_string(),
+        version: "1.0.0".to_string(),
+        author: None,
+        main_category: "traffic".to_string(),
+        category: "test".to_string(),
+        default_severity: Severity::Info,
+        tags: vec!["test".to_string()],
+        description: None,
+    }
+}
+
+fn request(id: &str) -> RequestContext {
+    RequestContext {
+        id: id.to_string(),
+        method: "GET".to_string(),
+        url: format!("https://example.com/{}", id),
+        headers: HashMap::new(),
+        body: vec![],
+        content_type: None,
+        query_params: HashMap::new(),
+        is_https: true,
+        timestamp: Utc::now(),
+        was_edited: false,
+        edited_method: None,
+        edited_url: None,
+        edited_headers: None,
+        edited_body: None,
+        body_truncated: false,
+        body_size: None,
+    }
+}
+
+fn response(request_id: &str) -> ResponseContext {
+    ResponseContext {
+        request_id: request_id.to_string(),
+        status: 200,
+        headers: HashMap::new(),
+        body: b"ok".to_vec(),
+        content_type: Some("text/plain".to_string()),
+        timestamp: Utc::now(),
+        was_edited: false,
+        edited_status: None,
+        edited_headers: None,
+        edited_body: None,
+        body_truncated: false,
+        body_size: None,
+        raw_body: None,
+    }
+}
+
+fn send_exchange(task_tx: &mpsc::UnboundedSender<ScanTask>, id: &str) {
+    task_tx.send(ScanTask::Request(request(id))).unwrap();
+    task_tx.send(ScanTask::Response(response(id))).unwrap();
+}
+
+#[tokio::test]
+async fn test_paused_scan_records_history_without_findings() {
+    let (task_tx, task_rx) = mpsc::unbounded_channel::<ScanTask>();
+    let (finding_tx, mut finding_rx) = mpsc::unbounded_channel::<Finding>();
+    let history_cache = Arc::new(ProxyHistoryCache::with_defaults());
+    let paused = Arc::new(RwLock::new(true));
+
+    let pipeline = ScanPipeline::new(task_rx, finding_tx)
+        .with_history_cache(history_cache.clone())
+        .with_scan_paused(paused.clone());
+    pipeline
+        .add_plugin(
+            "pause_test_plugin".to_string(),
+            metadata(),
+            ALWAYS_FIND_PLUGIN.to_string(),
+        )
+        .await
+        .expect("plugin should load");
+
+    // 与应用内一致，ScanPipeline 在 LocalSet 中运行
+    let local = tokio::task::LocalSet::new();
+    local.spawn_local(pipeline.start());
+    local
+        .run_until(async move {
+            // 暂停期间：流量照常记录，但插件不被调用
+            send_exchange(&task_tx, "paused-1");
+            send_exchange(&task_tx, "paused-2");
+            tokio::time::sleep(Duration::from_millis(500)).await;
+
+            assert_eq!(history_cache.count_http_requests().await, 2);
+            assert!(
+                finding_rx.try_recv().is_err(),
+                "paused scan must not produce findings"
+            );
+
+            // 恢复后：同一插件重新开始产出 Finding
+            *paused.write().await = false;
+            send_exchange(&task_tx, "resumed-1");
+
+            let finding = tokio::time::timeout(Duration::from_secs(10), finding_rx.recv())
+                .await
+                .expect("resumed scan should produce a finding")
+                .expect("finding channel closed");
+            assert_eq!(finding.vuln_type, "pause_test");
+            assert_eq!(history_cache.count_http_requests().await, 3);
+        })
+        .await;
+}
@@ -5,8 +5,11 @@ use std::sync::Arc;
 use tauri::{AppHandle, Emitter, State};
 
 use crate::engine::{WorkflowDefinition, WorkflowEngine, WorkflowMetadata, WorkflowStep};
+use crate::plan::{branch_allows, evaluate_branch_expr, plan_workflow_run, WorkflowRunPlan};
 use rig::tool::ToolSet;
+use sentinel_db::core::models::database::NotificationRule;
 use sentinel_db::core::models::rag_config::RagConfig as CoreRagConfig;
+use sentinel_db::core::shutdown;
 use sentinel_db::Database;
 use sentinel_db::DatabaseService;
 use sentinel_prompt::{
@@ -85,6 +88,21 @@ pub struct WorkflowGraph {
     pub output_schema: Option<serde_json::Value>,
 }
 
+/// 触发节点参数中保存外部输入（如 Webhook 请求体）的字段名
+pub const TRIGGER_PAYLOAD_PARAM: &str = "payload";
+
+/// 将外部输入写入所有触发节点
+pub fn inject_trigger_payload(graph: &mut WorkflowGraph, input: serde_json::Value) {
+    for node in graph
+        .nodes
+        .iter_mut()
+        .filter(|n| n.node_type.starts_with("trigger_"))
+    {
+        node.params
+            .insert(TRIGGER_PAYLOAD_PARAM.to_string(), input.clone());
+    }
+}
+
 pub fn graph_to_definition(graph: &WorkflowGraph) -> WorkflowDefinition {
     let mut depends_map: HashMap<String, Vec<String>> = HashMap::new();
     for e in &graph.edges {
@@ -130,6 +148,49 @@ pub fn graph_to_definition(graph: &WorkflowGraph) -> WorkflowDefinition {
     }
 }
 
+/// 节点输出转为产物内容：字符串按纯文本保存，其余按 JSON 保存
+fn artifact_content(result: &serde_json::Value) -> (&'static str, String) {
+    match result {
+        serde_json::Value::String(text) => ("text/plain", text.clone()),
+        other => (
+            "application/json",
+            serde_json::to_string_pretty(other).unwrap_or_else(|_| other.to_string()),
+        ),
+    }
+}
+
+/// 通知规则的触发条件是否满足；上游输出中的 `finding` 对象（或输出本身）作为判定依据
+///
+/// 与 `dispatch_finding_notification` 一致：条件无效的规则跳过；上游未给出 `in_scope`
+/// 时按漏洞赏金项目的范围规则判定主机是否在范围内。
+async fn notification_conditions_met(
+    db: &DatabaseService,
+    rule: &NotificationRule,
+    upstream_result: Option<&serde_json::Value>,
+) -> bool {
+    let Some(conditions) =
+        sentinel_notify::NotificationConditions::for_rule(&rule.id, rule.conditions.as_deref())
+    else {
+        return false;
+    };
+    if conditions.is_empty() {
+        return true;
+    }
+    let mut event = upstream_result
+        .map(|result| result.get("finding").unwrap_or(result))
+        .and_then(|finding| {
+            serde_json::from_value::<sentinel_notify::FindingEvent>(finding.clone()).ok()
+        })
+        .unwrap_or_default();
+    if let Some(host) = conditions.scope_to_resolve(&event) {
+        match db.is_target_in_any_scope(host).await {
+            Ok(in_scope) => event.in_scope = Some(in_scope),
+            Err(e) => tracing::warn!("Failed to resolve scope of {}: {}", host, e),
+        }
+    }
+    conditions.matches(&event)
+}
+
 pub fn topo_order(nodes: &[NodeDef], edges: &[(String, String)]) -> Vec<String> {
     let mut indeg: HashMap<String, usize> = nodes.iter().map(|n| (n.id.clone(), 0)).collect();
     let mut adj: HashMap<String, Vec<String>> =
@@ -140,10 +201,11 @@ pub fn topo_order(nodes: &[NodeDef], edges: &[(String, String)]) -> Vec<String>
         }
         adj.entry(u.clone()).or_default().push(v.clone());
     }
-    let mut q: VecDeque<String> = indeg
+    // 按节点声明顺序入队，保证同一张图每次得到相同的顺序（dry-run 计划依赖这一点）
+    let mut q: VecDeque<String> = nodes
         .iter()
-        .filter(|(_, &d)| d == 0)
-        .map(|(k, _)| k.clone())
+        .filter(|n| indeg.get(&n.id) == Some(&0))
+        .map(|n| n.id.clone())
         .collect();
     let mut order = Vec::new();
     while let Some(u) = q.pop_front() {
@@ -198,9 +260,32 @@ fn convert_core_to_rag(core: CoreRagConfig) -> RagConfig {
             sentinel_db::core::models::rag_config::ChunkingStrategy::StructureAware => {
                 sentinel_rag::config::ChunkingStrategy::StructureAware
             }
+            sentinel_db::core::models::rag_config::ChunkingStrategy::SentenceBoundary => {
+                sentinel_rag::config::ChunkingStrategy::SentenceBoundary
+            }
+            sentinel_db::core::models::rag_config::ChunkingStrategy::Markdown => {
+                sentinel_rag::config::ChunkingStrategy::Markdown
+            }
         },
         min_chunk_size_chars: core.min_chunk_size_chars,
         max_chunk_size_chars: core.max_chunk_size_chars,
+        retrieval_mode: core.retrieval_mode,
+        hybrid_alpha: core.hybrid_alpha as f32,
+        embedding_fallbacks: core
+            .embedding_fallbacks
+            .into_iter()
+            .map(|f| sentinel_rag::config::EmbeddingConfig {
+                provider: f.provider,
+                model: f.model,
+                api_key: f.api_key,
+                base_url: f.base_url,
+                dimensions: f.dimensions,
+                timeout_secs: f.timeout_secs,
+            })
+            .collect(),
+        embedding_timeout_secs: core.embedding_timeout_secs,
+        embedding_failure_threshold: core.embedding_failure_threshold,
+        embedding_circuit_cooldown_secs: core.embedding_circuit_cooldown_secs,
     }
 }
 
@@ -222,6 +307,8 @@ pub async fn execute_workflow_steps(
     let def_clone = def;
     let toolset_clone = toolset;
     let plugin_manager_clone = plugin_manager;
+    let shutdown_token = shutdown::shutdown_token();
+    let create_guard = shutdown::begin_operation("workflow run write");
     if let Err(e) = db_clone
         .create_workflow_run(
             &execution_id_for_spawn,
@@ -235,6 +322,7 @@ pub async fn execute_workflow_steps(
     {
         tracing::warn!("Failed to create workflow_run: {}", e);
     }
+    drop(create_guard);
 
     let edges: Vec<(String, String)> = graph
         .edges
@@ -247,6 +335,23 @@ pub async fn execute_workflow_steps(
     let mut branch_results: HashMap<String, bool> = HashMap::new();
 
     for node_id in order {
+        // 退出时不再开始新步骤，并把运行记为已取消
+        if shutdown_token.is_cancelled() {
+            tracing::info!(
+                "Stopping workflow run {} for shutdown",
+                execution_id_for_spawn
+            );
+            let _ = db_clone
+                .update_workflow_run_status(
+                    &execution_id_for_spawn,
+                    "cancelled",
+                    Some(Utc::now()),
+                    Some("Application shutting down"),
+                )
+                .await;
+            return;
+        }
+
         let _ = app_handle_clone.emit(
             "workflow:step-start",
             &serde_json::json!({
@@ -267,26 +372,7 @@ pub async fn execute_workflow_steps(
 
         let mut wrote_result = false;
 
-        let incoming = graph
-            .edges
-            .iter()
-            .filter(|e| e.to_node == node_id)
-            .collect::<Vec<_>>();
-        let mut gated_by_branch = false;
-        let mut branch_allowed = true;
-        for e in &incoming {
-            if let Some(from_step) = def_clone.steps.iter().find(|s| s.id == e.from_node) {
-                if from_step.action == "branch" {
-                    gated_by_branch = true;
-                    let selected = branch_results.get(&e.from_node).cloned().unwrap_or(true);
-                    let expects_true = e.from_port == "true";
-                    if selected != expects_true {
-                        branch_allowed = false;
-                    }
-                }
-            }
-        }
-        if gated_by_branch && !branch_allowed {
+        if !branch_allows(&graph, &def_clone, &node_id, &branch_results) {
             let result = serde_json::json!({"skipped": true});
             engine_clone
                 .mark_step_completed_with_result(&execution_id_for_spawn, &node_id, result.clone())
@@ -305,9 +391,7 @@ pub async fn execute_workflow_steps(
                 tracing::warn!("failed to update step status: {}", e);
             }
             wrote_result = true;
-        }
-
-        if let Some(step_def) = def_clone.steps.iter().find(|s| s.id == node_id) {
+        } else if let Some(step_def) = def_clone.steps.iter().find(|s| s.id == node_id) {
             let action = step_def.action.clone();
             if action.starts_with("tool::") {
                 let mut tool_name = action;
@@ -563,11 +647,7 @@ pub async fn execute_workflow_steps(
                     .get("expr")
                     .and_then(|v| v.as_str())
                     .unwrap_or("true");
-                let selected = match expr {
-                    "true" => true,
-                    "false" => false,
-                    _ => true,
-                };
+                let selected = evaluate_branch_expr(expr);
                 branch_results.insert(node_id.clone(), selected);
                 let result_json = serde_json::json!({"result": selected});
                 engine_clone
@@ -1077,26 +1157,73 @@ pub async fn execute_workflow_steps(
                     .unwrap_or("")
                     .to_string();
 
-                // 如果启用了使用输入作为内容，从上游节点获取数据
-                if use_input_as_content {
-                    let input_edges = graph
-                        .edges
-                        .iter()
-                        .filter(|e| e.to_node == node_id)
-                        .collect::<Vec<_>>();
-                    if let Some(edge) = input_edges.first() {
-                        if let Some(upstream_result) = engine_clone
+                let upstream_result = match graph.edges.iter().find(|e| e.to_node == node_id) {
+                    Some(edge) => {
+                        engine_clone
                             .get_step_result(&execution_id_for_spawn, &edge.from_node)
                             .await
-                        {
-                            content =
-                                serde_json::to_string_pretty(&upstream_result).unwrap_or(content);
-                        }
+                    }
+                    None => None,
+                };
+
+                // 如果启用了使用输入作为内容，从上游节点获取数据
+                if use_input_as_content {
+                    if let Some(upstream_result) = &upstream_result {
+                        content = serde_json::to_string_pretty(upstream_result).unwrap_or(content);
                     }
                 }
 
-                // 发送通知
-                if !notification_rule_id.is_empty() {
+                // 规则设置了触发条件（严重级别、范围等）时，不满足条件则静默跳过
+                let notification_rule = if notification_rule_id.is_empty() {
+                    None
+                } else {
+                    db_clone
+                        .get_notification_rule(notification_rule_id)
+                        .await
+                        .ok()
+                        .flatten()
+                };
+                let conditions_met = match &notification_rule {
+                    Some(rule) => {
+                        notification_conditions_met(&db_clone, rule, upstream_result.as_ref()).await
+                    }
+                    None => true,
+                };
+
+                if !conditions_met {
+                    tracing::debug!(
+                        "Notification rule {} conditions not met, skipping node: {}",
+                        notification_rule_id,
+                        node_id
+                    );
+                    let result_json = serde_json::json!({
+                        "status": "skipped",
+                        "reason": "conditions_not_met",
+                        "notification_rule_id": notification_rule_id
+                    });
+                    engine_clone
+                        .mark_step_completed_with_result(
+                            &execution_id_for_spawn,
+                            &node_id,
+                            result_json.clone(),
+                        )
+                        .await;
+                    if let Err(e) = db_clone
+                        .update_workflow_run_step_status(
+                            &execution_id_for_spawn,
+                            &node_id,
+                            "completed",
+                            Utc::now(),
+                            Some(result_json.to_string()),
+                            None,
+                        )
+                        .await
+                    {
+                        tracing::warn!("failed to update step status: {}", e);
+                    }
+                    wrote_result = true;
+                } else if !notification_rule_id.is_empty() {
+                    // 发送通知
                     // 从inputs中获取通知配置（前端保存workflow时已经附加）
                     let channel = step_def
                         .inputs
@@ -1117,10 +1244,18 @@ pub async fn execute_workflow_steps(
                         title
                     );
 
-                    // 使用 sentinel-notify 发送通知
-                    match sentinel_notify::send(
+                    // 使用 sentinel-notify 发送通知（经过规则的去重/摘要设置）
+                    let throttle = notification_rule
+                        .as_ref()
+                        .and_then(|rule| {
+                            sentinel_notify::ThrottleConfig::parse(rule.throttle.as_deref()).ok()
+                        })
+                        .unwrap_or_default();
+                    match sentinel_notify::send_throttled(
+                        notification_rule_id,
                         &channel,
                         config.clone(),
+                        &throttle,
                         sentinel_notify::NotificationMessage {
                             title: title.clone(),
                             content: content.clone(),
@@ -1128,10 +1263,14 @@ pub async fn execute_workflow_steps(
                     )
                     .await
                     {
-                        Ok(_) => {
-                            tracing::info!("Notification sent successfully for node: {}", node_id);
+                        Ok(decision) => {
+                            tracing::info!(
+                                "Notification {} for node: {}",
+                                decision.as_str(),
+                                node_id
+                            );
                             let result_json = serde_json::json!({
-                                "status": "sent",
+                                "status": decision.as_str(),
                                 "title": title,
                                 "content": content,
                                 "channel": channel,
@@ -1235,6 +1374,29 @@ pub async fn execute_workflow_steps(
                     node_id,
                     action
                 );
+                // Webhook 触发时，请求携带的输入作为触发节点的输出传给下游
+                if let Some(payload) = step_def.inputs.get(TRIGGER_PAYLOAD_PARAM) {
+                    engine_clone
+                        .mark_step_completed_with_result(
+                            &execution_id_for_spawn,
+                            &node_id,
+                            payload.clone(),
+                        )
+                        .await;
+                    if let Err(e) = db_clone
+                        .update_workflow_run_step_status(
+                            &execution_id_for_spawn,
+                            &node_id,
+                            "completed",
+                            Utc::now(),
+                            Some(payload.to_string()),
+                            None,
+                        )
+                        .await
+                    {
+                        tracing::warn!("failed to update step status: {}", e);
+                    }
+                }
                 wrote_result = true;
             } else if action == "ai_chat" || action == "ai_agent" {
                 // AI Chat / AI Agent 节点执行
@@ -1425,6 +1587,20 @@ pub async fn execute_workflow_steps(
         let step_result = engine_clone
             .get_step_result(&execution_id_for_spawn, &node_id)
             .await;
+        if let Some(result) = step_result.as_ref() {
+            let (content_type, content) = artifact_content(result);
+            if let Err(e) = db_clone
+                .save_workflow_run_artifact(
+                    &execution_id_for_spawn,
+                    &node_id,
+                    content_type,
+                    &content,
+                )
+                .await
+            {
+                tracing::warn!("failed to save step artifact: {}", e);
+            }
+        }
         let _ = app_handle_clone.emit(
             "workflow:step-complete",
             &serde_json::json!({
@@ -1448,6 +1624,7 @@ pub async fn execute_workflow_steps(
                 "total_steps": total
             }),
         );
+        let _guard = shutdown::begin_operation("workflow run write");
         let _ = db_clone
             .update_workflow_run_progress(&execution_id_for_spawn, progress, completed, total)
             .await;
@@ -1456,6 +1633,7 @@ pub async fn execute_workflow_steps(
     engine_clone
         .mark_execution_completed(&execution_id_for_spawn)
         .await;
+    let _guard = shutdown::begin_operation("workflow run write");
     let _ = db_clone
         .update_workflow_run_status(&execution_id_for_spawn, "completed", Some(Utc::now()), None)
         .await;
@@ -1467,20 +1645,40 @@ pub async fn execute_workflow_steps(
     );
 }
 
+/// `start_workflow_run` 的返回值：正常运行返回执行 ID，dry-run 返回执行计划
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StartWorkflowRunResult {
+    Started(String),
+    Plan(WorkflowRunPlan),
+}
+
 #[tauri::command]
 pub async fn start_workflow_run(
-    graph: WorkflowGraph,
+    mut graph: WorkflowGraph,
+    inputs: Option<serde_json::Value>,
+    dry_run: Option<bool>,
     app_handle: AppHandle,
     engine: State<'_, Arc<WorkflowEngine>>,
     db: State<'_, Arc<DatabaseService>>,
     plugin_manager: State<'_, Arc<PluginManager>>,
-) -> Result<String, String> {
+) -> Result<StartWorkflowRunResult, String> {
     // Multi-point license verification
     #[cfg(not(debug_assertions))]
     if !sentinel_license::is_licensed() {
         return Err("License required for this feature".to_string());
     }
 
+    // dry-run 只推演执行顺序，不调用工具、不创建运行记录
+    if dry_run.unwrap_or(false) {
+        return Ok(StartWorkflowRunResult::Plan(plan_workflow_run(
+            &graph, inputs,
+        )));
+    }
+    if let Some(inputs) = inputs {
+        inject_trigger_payload(&mut graph, inputs);
+    }
+
     let def = graph_to_definition(&graph);
     let execution_id = engine
         .execute_workflow(&def, None)
@@ -1510,20 +1708,26 @@ pub async fn start_workflow_run(
     let toolset = Arc::new(sentinel_tools::create_buildin_toolset());
 
     tokio::spawn(async move {
-        execute_workflow_steps(
-            execution_id_for_spawn,
-            graph_clone,
-            def_clone,
-            db_clone,
-            app_handle_clone,
-            engine_clone,
-            toolset,
-            plugin_manager_clone,
+        let scope_id = execution_id_for_spawn.clone();
+        // Tool steps (built-in and MCP) are cancelled by stop_workflow_run
+        sentinel_tools::cancellation::with_cancellation_scope(
+            &scope_id,
+            execute_workflow_steps(
+                execution_id_for_spawn,
+                graph_clone,
+                def_clone,
+                db_clone,
+                app_handle_clone,
+                engine_clone,
+                toolset,
+                plugin_manager_clone,
+            ),
         )
         .await;
+        sentinel_tools::cancellation::clear_cancellation_scope(&scope_id).await;
     });
 
-    Ok(execution_id)
+    Ok(StartWorkflowRunResult::Started(execution_id))
 }
 
 #[tauri::command]
@@ -1550,6 +1754,7 @@ pub async fn stop_workflow_run(
         .cancel_execution(&execution_id)
         .await
         .map_err(|e| e.to_string())?;
+    sentinel_tools::cancellation::cancel_scope(&execution_id).await;
 
     // 发送停止事件
     let _ = app_handle.emit(
@@ -1600,11 +1805,26 @@ pub async fn get_workflow_run_detail(
         .map_err(|e| e.to_string())
 }
 
+/// 获取运行中某个节点的输出产物
+#[tauri::command]
+pub async fn get_workflow_run_artifact(
+    run_id: String,
+    node_id: String,
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<Option<sentinel_db::WorkflowRunArtifactContent>, String> {
+    db.get_workflow_run_artifact(&run_id, &node_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn delete_workflow_run(
     run_id: String,
     db: State<'_, Arc<DatabaseService>>,
 ) -> Result<(), String> {
+    if let Err(e) = db.delete_workflow_run_artifacts(&run_id).await {
+        tracing::warn!("failed to delete run artifacts: {}", e);
+    }
     db.delete_workflow_run(&run_id)
         .await
         .map_err(|e| e.to_string())
@@ -1919,6 +2139,7 @@ pub async fn validate_workflow_graph(
 // ==================== 定时调度相关 ====================
 
 use crate::scheduler::{ScheduleConfig, ScheduleExecutor, ScheduleInfo, WorkflowScheduler};
+use crate::webhook::{start_webhook_listener, WebhookConfig, WebhookStatus, WEBHOOK_RUNTIME};
 
 /// 调度执行器实现
 pub struct WorkflowScheduleExecutor {
@@ -1948,9 +2169,14 @@ impl WorkflowScheduleExecutor {
     }
 }
 
-#[async_trait::async_trait]
-impl ScheduleExecutor for WorkflowScheduleExecutor {
-    async fn execute_workflow(&self, workflow_id: &str) -> Result<String, String> {
+impl WorkflowScheduleExecutor {
+    /// 加载工作流并在后台执行，`input` 会写入所有触发节点
+    async fn start_run(
+        &self,
+        workflow_id: &str,
+        input: Option<serde_json::Value>,
+        event: &str,
+    ) -> Result<String, String> {
         // 从数据库加载工作流定义
         let wf_data = self
             .db
@@ -1964,8 +2190,11 @@ impl ScheduleExecutor for WorkflowScheduleExecutor {
             .get("graph")
             .ok_or_else(|| "Workflow graph not found".to_string())?;
 
-        let graph: WorkflowGraph = serde_json::from_value(graph_value.clone())
+        let mut graph: WorkflowGraph = serde_json::from_value(graph_value.clone())
             .map_err(|e| format!("Failed to parse workflow graph: {}", e))?;
+        if let Some(input) = input {
+            inject_trigger_payload(&mut graph, input);
+        }
 
         let def = graph_to_definition(&graph);
         let execution_id = self
@@ -1976,7 +2205,7 @@ impl ScheduleExecutor for WorkflowScheduleExecutor {
 
         // 发送事件通知前端
         let _ = self.app_handle.emit(
-            "workflow:schedule-triggered",
+            event,
             &serde_json::json!({
                 "workflow_id": workflow_id,
                 "execution_id": execution_id,
@@ -2010,6 +2239,23 @@ impl ScheduleExecutor for WorkflowScheduleExecutor {
     }
 }
 
+#[async_trait::async_trait]
+impl ScheduleExecutor for WorkflowScheduleExecutor {
+    async fn execute_workflow(&self, workflow_id: &str) -> Result<String, String> {
+        self.start_run(workflow_id, None, "workflow:schedule-triggered")
+            .await
+    }
+
+    async fn execute_workflow_with_input(
+        &self,
+        workflow_id: &str,
+        input: serde_json::Value,
+    ) -> Result<String, String> {
+        self.start_run(workflow_id, Some(input), "workflow:webhook-triggered")
+            .await
+    }
+}
+
 /// 启动工作流定时调度
 #[tauri::command]
 pub async fn start_workflow_schedule(
@@ -2071,3 +2317,126 @@ pub async fn get_workflow_schedule(
 ) -> Result<Option<ScheduleInfo>, String> {
     Ok(scheduler.get_schedule(&workflow_id).await)
 }
+
+const WEBHOOK_CONFIG_CATEGORY: &str = "workflow";
+const WEBHOOK_CONFIG_KEY: &str = "webhook_config";
+
+async fn load_webhook_config(db: &DatabaseService) -> Result<WebhookConfig, String> {
+    match db
+        .get_config(WEBHOOK_CONFIG_CATEGORY, WEBHOOK_CONFIG_KEY)
+        .await
+    {
+        Ok(Some(raw)) => {
+            serde_json::from_str(&raw).map_err(|e| format!("Failed to parse webhook config: {}", e))
+        }
+        Ok(None) => Ok(WebhookConfig::default()),
+        Err(e) => Err(format!("Failed to load webhook config: {}", e)),
+    }
+}
+
+async fn start_webhook_with(
+    config: &WebhookConfig,
+    executor: Arc<dyn ScheduleExecutor + Send + Sync>,
+) -> Result<String, String> {
+    let mut runtime = WEBHOOK_RUNTIME.lock().await;
+    if runtime.as_ref().map(|r| r.is_finished()).unwrap_or(false) {
+        *runtime = None;
+    }
+    if runtime.is_some() {
+        return Err("Webhook listener is already running".to_string());
+    }
+    let started = start_webhook_listener(config, executor).await?;
+    let bind_addr = started.bind_addr();
+    *runtime = Some(started);
+    Ok(format!("Webhook listener started on {}", bind_addr))
+}
+
+/// 应用启动时按配置自动开启 Webhook 监听
+pub async fn auto_start_workflow_webhook_if_enabled(
+    executor: WorkflowScheduleExecutor,
+) -> Result<(), String> {
+    let config = load_webhook_config(&executor.db).await?;
+    if !config.enabled {
+        return Ok(());
+    }
+    start_webhook_with(&config, Arc::new(executor)).await?;
+    Ok(())
+}
+
+/// 获取 Webhook 监听配置（密钥以占位符返回）
+#[tauri::command]
+pub async fn get_workflow_webhook_config(
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<WebhookConfig, String> {
+    Ok(load_webhook_config(db.inner()).await?.masked())
+}
+
+/// 保存 Webhook 监听配置
+#[tauri::command]
+pub async fn save_workflow_webhook_config(
+    config: WebhookConfig,
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<(), String> {
+    let config = config.with_stored_secret(&load_webhook_config(db.inner()).await?);
+    if config.enabled {
+        config.validate()?;
+    }
+    let serialized = serde_json::to_string(&config)
+        .map_err(|e| format!("Failed to serialize webhook config: {}", e))?;
+    db.set_config(
+        WEBHOOK_CONFIG_CATEGORY,
+        WEBHOOK_CONFIG_KEY,
+        &serialized,
+        Some("Workflow webhook listener config"),
+    )
+    .await
+    .map_err(|e| format!("Failed to save webhook config: {}", e))
+}
+
+/// 启动 Webhook 监听，未传配置时使用已保存的配置
+#[tauri::command]
+pub async fn start_workflow_webhook(
+    config: Option<WebhookConfig>,
+    app_handle: AppHandle,
+    engine: State<'_, Arc<WorkflowEngine>>,
+    db: State<'_, Arc<DatabaseService>>,
+    plugin_manager: State<'_, Arc<PluginManager>>,
+) -> Result<String, String> {
+    let stored = load_webhook_config(db.inner()).await?;
+    let config = match config {
+        Some(config) => config.with_stored_secret(&stored),
+        None => stored,
+    };
+    let executor =
+        WorkflowScheduleExecutor::new(engine.inner().clone(), db.inner().clone(), app_handle)
+            .with_plugin_manager(plugin_manager.inner().clone());
+    start_webhook_with(&config, Arc::new(executor)).await
+}
+
+/// 停止 Webhook 监听
+#[tauri::command]
+pub async fn stop_workflow_webhook() -> Result<(), String> {
+    let runtime = WEBHOOK_RUNTIME.lock().await.take();
+    if let Some(runtime) = runtime {
+        runtime.stop().await;
+    }
+    Ok(())
+}
+
+/// 获取 Webhook 监听状态
+#[tauri::command]
+pub async fn get_workflow_webhook_status() -> Result<WebhookStatus, String> {
+    let runtime = WEBHOOK_RUNTIME.lock().await;
+    Ok(match runtime.as_ref() {
+        Some(r) => WebhookStatus {
+            running: !r.is_finished(),
+            bind_addr: Some(r.bind_addr().to_string()),
+            started_at: Some(r.started_at()),
+        },
+        None => WebhookStatus {
+            running: false,
+            bind_addr: None,
+            started_at: None,
+        },
+    })
+}
@@ -1,9 +1,13 @@
 pub mod commands;
 pub mod engine;
+pub mod plan;
 pub mod scheduler;
+pub mod webhook;
 
 pub use commands::{
     execute_workflow_steps, graph_to_definition, topo_order, EdgeDef, NodeDef, WorkflowGraph,
 };
 pub use engine::{WorkflowDefinition, WorkflowEngine};
+pub use plan::{plan_workflow_run, PlannedNode, WorkflowRunPlan};
 pub use scheduler::{ScheduleConfig, ScheduleExecutor, ScheduleInfo, WorkflowScheduler};
+pub use webhook::{WebhookConfig, WebhookStatus};
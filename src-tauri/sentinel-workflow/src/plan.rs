@@ -0,0 +1,299 @@
+//! 工作流执行计划（dry-run）
+//!
+//! 按与实际执行相同的拓扑顺序和分支规则推演一次运行，只解析节点输入、
+//! 判断分支走向并估算工具调用次数，不调用任何工具、不写数据库。
+
+use crate::commands::{graph_to_definition, inject_trigger_payload, topo_order, WorkflowGraph};
+use crate::engine::WorkflowDefinition;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const PLAN_WOULD_EXECUTE: &str = "would_execute";
+pub const PLAN_WOULD_SKIP: &str = "would_skip";
+
+/// 计划中的单个节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedNode {
+    pub node_id: String,
+    pub node_name: String,
+    pub action: String,
+    /// `would_execute` 或 `would_skip`
+    pub status: String,
+    /// 解析后的节点输入
+    pub inputs: HashMap<String, serde_json::Value>,
+    /// 预计工具调用次数（retry 节点按最大重试次数计）
+    pub tool_calls: u32,
+    /// 分支节点选择的出口
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<bool>,
+}
+
+/// 工作流执行计划
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRunPlan {
+    pub workflow_id: String,
+    pub workflow_name: String,
+    pub dry_run: bool,
+    pub nodes: Vec<PlannedNode>,
+    pub estimated_tool_calls: u32,
+}
+
+impl WorkflowRunPlan {
+    /// 将会执行的节点顺序
+    pub fn execution_order(&self) -> Vec<&str> {
+        self.nodes
+            .iter()
+            .filter(|n| n.status == PLAN_WOULD_EXECUTE)
+            .map(|n| n.node_id.as_str())
+            .collect()
+    }
+}
+
+/// 计算分支节点表达式，目前只识别 `true` / `false`，其余视为 `true`
+pub fn evaluate_branch_expr(expr: &str) -> bool {
+    !matches!(expr, "false")
+}
+
+/// 节点是否被上游分支放行；没有分支上游的节点总是放行
+pub fn branch_allows(
+    graph: &WorkflowGraph,
+    def: &WorkflowDefinition,
+    node_id: &str,
+    branch_results: &HashMap<String, bool>,
+) -> bool {
+    graph
+        .edges
+        .iter()
+        .filter(|e| e.to_node == node_id)
+        .all(|e| {
+            let from_branch = def
+                .steps
+                .iter()
+                .any(|s| s.id == e.from_node && s.action == "branch");
+            if !from_branch {
+                return true;
+            }
+            let selected = branch_results.get(&e.from_node).cloned().unwrap_or(true);
+            selected == (e.from_port == "true")
+        })
+}
+
+fn estimated_tool_calls(action: &str, inputs: &HashMap<String, serde_json::Value>) -> u32 {
+    if action.starts_with("tool::") || action.starts_with("plugin::") {
+        1
+    } else if action == "retry" {
+        let has_tool = inputs
+            .get("tool_name")
+            .and_then(|v| v.as_str())
+            .is_some_and(|name| !name.is_empty());
+        if has_tool {
+            inputs
+                .get("times")
+                .and_then(|v| v.as_u64())
+                .map(|n| n as u32)
+                .unwrap_or(3)
+        } else {
+            0
+        }
+    } else {
+        0
+    }
+}
+
+/// 生成执行计划，`input` 与 Webhook 触发一样写入触发节点
+pub fn plan_workflow_run(
+    graph: &WorkflowGraph,
+    input: Option<serde_json::Value>,
+) -> WorkflowRunPlan {
+    let mut graph = graph.clone();
+    if let Some(input) = input {
+        inject_trigger_payload(&mut graph, input);
+    }
+    let def = graph_to_definition(&graph);
+    let edges: Vec<(String, String)> = graph
+        .edges
+        .iter()
+        .map(|e| (e.from_node.clone(), e.to_node.clone()))
+        .collect();
+
+    let mut branch_results: HashMap<String, bool> = HashMap::new();
+    let mut nodes = Vec::new();
+    for node_id in topo_order(&graph.nodes, &edges) {
+        let Some(step) = def.steps.iter().find(|s| s.id == node_id) else {
+            continue;
+        };
+        let allowed = branch_allows(&graph, &def, &node_id, &branch_results);
+        let mut branch = None;
+        if allowed && step.action == "branch" {
+            let expr = step
+                .inputs
+                .get("expr")
+                .and_then(|v| v.as_str())
+                .unwrap_or("true");
+            let selected = evaluate_branch_expr(expr);
+            branch_results.insert(node_id.clone(), selected);
+            branch = Some(selected);
+        }
+
+        nodes.push(PlannedNode {
+            node_id: node_id.clone(),
+            node_name: step.name.clone(),
+            action: step.action.clone(),
+            status: if allowed {
+                PLAN_WOULD_EXECUTE
+            } else {
+                PLAN_WOULD_SKIP
+            }
+            .to_string(),
+            inputs: step.inputs.clone(),
+            tool_calls: if allowed {
+                estimated_tool_calls(&step.action, &step.inputs)
+            } else {
+                0
+            },
+            branch,
+        });
+    }
+
+    WorkflowRunPlan {
+        workflow_id: def.metadata.id.clone(),
+        workflow_name: def.metadata.name.clone(),
+        dry_run: true,
+        estimated_tool_calls: nodes.iter().map(|n| n.tool_calls).sum(),
+        nodes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{EdgeDef, NodeDef};
+
+    fn node(id: &str, node_type: &str, params: serde_json::Value) -> NodeDef {
+        NodeDef {
+            id: id.to_string(),
+            node_type: node_type.to_string(),
+            node_name: id.to_string(),
+            x: 0.0,
+            y: 0.0,
+            params: serde_json::from_value(params).unwrap(),
+            input_ports: vec![],
+            output_ports: vec![],
+        }
+    }
+
+    fn edge(from: &str, from_port: &str, to: &str) -> EdgeDef {
+        EdgeDef {
+            id: format!("{}-{}", from, to),
+            from_node: from.to_string(),
+            from_port: from_port.to_string(),
+            to_node: to.to_string(),
+            to_port: "in".to_string(),
+        }
+    }
+
+    fn graph(expr: &str) -> WorkflowGraph {
+        WorkflowGraph {
+            id: "wf".to_string(),
+            name: "recon".to_string(),
+            version: "1".to_string(),
+            nodes: vec![
+                node("start", "trigger_manual", serde_json::json!({})),
+                node(
+                    "ports",
+                    "tool::port_scan",
+                    serde_json::json!({"target": "10.0.0.1"}),
+                ),
+                node("check", "branch", serde_json::json!({"expr": expr})),
+                node(
+                    "brute",
+                    "retry",
+                    serde_json::json!({"tool_name": "subdomain_brute", "times": 2}),
+                ),
+                node("report", "notify", serde_json::json!({})),
+            ],
+            edges: vec![
+                edge("start", "out", "ports"),
+                edge("ports", "out", "check"),
+                edge("check", "true", "brute"),
+                edge("check", "false", "report"),
+            ],
+            variables: vec![],
+            credentials: vec![],
+            input_schema: None,
+            output_schema: None,
+        }
+    }
+
+    /// 计划中各节点的 (节点, 状态, 分支出口, 预计工具调用)
+    fn planned(plan: &WorkflowRunPlan) -> Vec<(&str, &str, Option<bool>, u32)> {
+        plan.nodes
+            .iter()
+            .map(|n| {
+                (
+                    n.node_id.as_str(),
+                    n.status.as_str(),
+                    n.branch,
+                    n.tool_calls,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_plan_matches_execution_order() {
+        let taken = plan_workflow_run(&graph("true"), None);
+        assert_eq!(
+            planned(&taken),
+            vec![
+                ("start", PLAN_WOULD_EXECUTE, None, 0),
+                ("ports", PLAN_WOULD_EXECUTE, None, 1),
+                ("check", PLAN_WOULD_EXECUTE, Some(true), 0),
+                ("brute", PLAN_WOULD_EXECUTE, None, 2),
+                ("report", PLAN_WOULD_SKIP, None, 0),
+            ]
+        );
+        assert_eq!(
+            taken.execution_order(),
+            vec!["start", "ports", "check", "brute"]
+        );
+        assert_eq!(taken.estimated_tool_calls, 3);
+
+        let not_taken = plan_workflow_run(&graph("false"), None);
+        assert_eq!(
+            planned(&not_taken),
+            vec![
+                ("start", PLAN_WOULD_EXECUTE, None, 0),
+                ("ports", PLAN_WOULD_EXECUTE, None, 1),
+                ("check", PLAN_WOULD_EXECUTE, Some(false), 0),
+                ("brute", PLAN_WOULD_SKIP, None, 0),
+                ("report", PLAN_WOULD_EXECUTE, None, 0),
+            ]
+        );
+        assert_eq!(
+            not_taken.execution_order(),
+            vec!["start", "ports", "check", "report"]
+        );
+        assert_eq!(not_taken.estimated_tool_calls, 1);
+    }
+
+    #[test]
+    fn test_plan_resolves_trigger_input() {
+        let plan = plan_workflow_run(
+            &graph("true"),
+            Some(serde_json::json!({"target": "example.com"})),
+        );
+        let start = &plan.nodes[0];
+        assert_eq!(start.node_id, "start");
+        assert_eq!(
+            start.inputs.get(crate::commands::TRIGGER_PAYLOAD_PARAM),
+            Some(&serde_json::json!({"target": "example.com"}))
+        );
+        let ports = plan.nodes.iter().find(|n| n.node_id == "ports").unwrap();
+        assert_eq!(ports.status, PLAN_WOULD_EXECUTE);
+        assert_eq!(
+            ports.inputs.get("target"),
+            Some(&serde_json::json!("10.0.0.1"))
+        );
+    }
+}
@@ -1,10 +1,12 @@
 //! 工作流定时调度器
 //!
-//! 支持间隔触发、每日触发、每周触发
+//! 支持间隔触发、每日触发、每周触发、Cron 表达式触发
 
-use chrono::{Datelike, Local, Timelike};
+use chrono::{DateTime, Datelike, Local, Timelike, Utc};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
@@ -12,12 +14,130 @@ use tokio::task::JoinHandle;
 /// 调度配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScheduleConfig {
-    pub trigger_type: String,          // interval, daily, weekly
+    pub trigger_type: String,          // interval, daily, weekly, cron
     pub interval_seconds: Option<u64>, // 间隔秒数
     pub hour: Option<u32>,             // 小时
     pub minute: Option<u32>,           // 分钟
     pub second: Option<u32>,           // 秒
     pub weekdays: Option<String>,      // 星期几，逗号分隔
+    #[serde(default)]
+    pub cron: Option<String>, // Cron 表达式（5 段标准格式或 6/7 段带秒格式）
+    #[serde(default)]
+    pub timezone: Option<String>, // IANA 时区名，如 Asia/Shanghai，缺省为 UTC
+}
+
+impl ScheduleConfig {
+    /// 是否使用 Cron 表达式调度
+    pub fn is_cron(&self) -> bool {
+        self.trigger_type == "cron"
+            || self
+                .cron
+                .as_deref()
+                .map(|c| !c.trim().is_empty())
+                .unwrap_or(false)
+    }
+
+    /// 校验配置，Cron 表达式或时区非法、或 Cron 表达式不会再触发时返回描述性错误
+    pub fn validate(&self) -> Result<(), String> {
+        let tz = parse_timezone(self.timezone.as_deref())?;
+        if self.is_cron() {
+            let expr = self
+                .cron
+                .as_deref()
+                .filter(|c| !c.trim().is_empty())
+                .ok_or_else(|| "Cron trigger requires a non-empty cron expression".to_string())?;
+            let schedule = parse_cron_expression(expr)?;
+            if next_cron_fire(&schedule, tz, Utc::now()).is_none() {
+                return Err(format!(
+                    "Cron expression '{}' has no upcoming fire time",
+                    expr
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 解析 Cron 表达式
+///
+/// 5 段表达式按标准 crontab 语义处理（分 时 日 月 周，周日为 0 或 7），
+/// 6/7 段表达式（带秒/年）直接交给 cron 库解析。
+pub fn parse_cron_expression(expr: &str) -> Result<cron::Schedule, String> {
+    let fields: Vec<&str> = expr.split_whitespace().collect();
+    let normalized = match fields.len() {
+        5 => format!(
+            "0 {} {} {} {} {}",
+            fields[0],
+            fields[1],
+            fields[2],
+            fields[3],
+            normalize_weekday_field(fields[4])
+        ),
+        6 | 7 => fields.join(" "),
+        n => {
+            return Err(format!(
+                "Invalid cron expression '{}': expected 5, 6 or 7 fields, got {}",
+                expr, n
+            ))
+        }
+    };
+
+    cron::Schedule::from_str(&normalized)
+        .map_err(|e| format!("Invalid cron expression '{}': {}", expr, e))
+}
+
+/// 解析时区，缺省为 UTC
+pub fn parse_timezone(tz: Option<&str>) -> Result<Tz, String> {
+    match tz.map(str::trim).filter(|t| !t.is_empty()) {
+        None => Ok(Tz::UTC),
+        Some(name) => name.parse::<Tz>().map_err(|_| {
+            format!(
+                "Invalid timezone '{}': expected an IANA name like 'Asia/Shanghai'",
+                name
+            )
+        }),
+    }
+}
+
+/// 计算 Cron 表达式在 `after` 之后的下一次触发时间
+pub fn next_cron_fire(
+    schedule: &cron::Schedule,
+    tz: Tz,
+    after: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    schedule
+        .after(&after.with_timezone(&tz))
+        .next()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// 将标准 crontab 的数字星期（0/7=周日，1=周一）转换为名称，
+/// 避免与 cron 库的 1=周日 语义冲突；步长部分保持不变
+fn normalize_weekday_field(field: &str) -> String {
+    const NAMES: [&str; 8] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT", "SUN"];
+
+    field
+        .split(',')
+        .map(|item| {
+            let (range, step) = match item.split_once('/') {
+                Some((r, s)) => (r, Some(s)),
+                None => (item, None),
+            };
+            let mapped = range
+                .split('-')
+                .map(|part| match part.parse::<usize>() {
+                    Ok(n) if n < NAMES.len() => NAMES[n].to_string(),
+                    _ => part.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("-");
+            match step {
+                Some(s) => format!("{}/{}", mapped, s),
+                None => mapped,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
 /// 调度任务信息
@@ -49,6 +169,16 @@ pub struct WorkflowScheduler {
 #[async_trait::async_trait]
 pub trait ScheduleExecutor: Send + Sync {
     async fn execute_workflow(&self, workflow_id: &str) -> Result<String, String>;
+
+    /// 携带输入启动工作流（Webhook 触发），默认忽略输入
+    async fn execute_workflow_with_input(
+        &self,
+        workflow_id: &str,
+        input: serde_json::Value,
+    ) -> Result<String, String> {
+        let _ = input;
+        self.execute_workflow(workflow_id).await
+    }
 }
 
 impl WorkflowScheduler {
@@ -66,6 +196,8 @@ impl WorkflowScheduler {
         workflow_name: String,
         config: ScheduleConfig,
     ) -> Result<(), String> {
+        config.validate()?;
+
         // 检查是否已存在
         {
             let tasks = self.tasks.read().await;
@@ -157,8 +289,19 @@ impl WorkflowScheduler {
         );
 
         loop {
-            // 计算等待时间
-            let wait_duration = Self::calculate_wait_duration(&config);
+            // 计算等待时间；Cron 表达式不再触发时结束调度
+            let Some(wait_duration) = Self::calculate_wait_duration(&config) else {
+                tracing::warn!(
+                    "[Scheduler] Workflow {} has no upcoming fire time, stopping schedule",
+                    workflow_id
+                );
+                let mut tasks = tasks_ref.write().await;
+                if let Some(task) = tasks.get_mut(&workflow_id) {
+                    task.info.is_running = false;
+                    task.info.next_run = None;
+                }
+                break;
+            };
 
             tracing::info!(
                 "[Scheduler] Workflow {} next run in {} seconds",
@@ -197,9 +340,14 @@ impl WorkflowScheduler {
         }
     }
 
-    /// 计算等待时长
-    fn calculate_wait_duration(config: &ScheduleConfig) -> std::time::Duration {
-        match config.trigger_type.as_str() {
+    /// 计算等待时长，Cron 表达式没有下一次触发时间时返回 `None`
+    fn calculate_wait_duration(config: &ScheduleConfig) -> Option<std::time::Duration> {
+        if config.is_cron() {
+            let next = Self::next_cron_run(config)?;
+            return Some((next - Utc::now()).to_std().unwrap_or_default());
+        }
+
+        let duration = match config.trigger_type.as_str() {
             "interval" => {
                 let secs = config.interval_seconds.unwrap_or(60);
                 std::time::Duration::from_secs(secs)
@@ -219,16 +367,33 @@ impl WorkflowScheduler {
                 )
             }
             _ => std::time::Duration::from_secs(60),
-        }
+        };
+        Some(duration)
     }
 
     /// 计算下次运行时间字符串
     fn calculate_next_run(config: &ScheduleConfig) -> Option<String> {
-        let duration = Self::calculate_wait_duration(config);
+        if config.is_cron() {
+            let next = Self::next_cron_run(config)?;
+            return Some(
+                next.with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+            );
+        }
+
+        let duration = Self::calculate_wait_duration(config)?;
         let next = Local::now() + chrono::Duration::from_std(duration).ok()?;
         Some(next.format("%Y-%m-%d %H:%M:%S").to_string())
     }
 
+    /// 计算 Cron 调度的下一次触发时间
+    fn next_cron_run(config: &ScheduleConfig) -> Option<DateTime<Utc>> {
+        let schedule = parse_cron_expression(config.cron.as_deref()?).ok()?;
+        let tz = parse_timezone(config.timezone.as_deref()).ok()?;
+        next_cron_fire(&schedule, tz, Utc::now())
+    }
+
     /// 计算到指定时间的等待时长
     fn duration_until_time(hour: u32, minute: u32, second: u32) -> std::time::Duration {
         let now = Local::now();
@@ -320,3 +485,116 @@ impl WorkflowScheduler {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn cron_config(expr: &str, tz: Option<&str>) -> ScheduleConfig {
+        ScheduleConfig {
+            trigger_type: "cron".to_string(),
+            interval_seconds: None,
+            hour: None,
+            minute: None,
+            second: None,
+            weekdays: None,
+            cron: Some(expr.to_string()),
+            timezone: tz.map(|t| t.to_string()),
+        }
+    }
+
+    #[test]
+    fn weekday_at_nine_skips_weekend() {
+        let schedule = parse_cron_expression("0 9 * * 1-5").unwrap();
+        let tz: Tz = "Asia/Shanghai".parse().unwrap();
+
+        // 2024-06-07 是周五，10:00 之后下一次应为下周一 09:00
+        let after = tz
+            .with_ymd_and_hms(2024, 6, 7, 10, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        let next = next_cron_fire(&schedule, tz, after)
+            .unwrap()
+            .with_timezone(&tz);
+
+        assert_eq!(next, tz.with_ymd_and_hms(2024, 6, 10, 9, 0, 0).unwrap());
+        assert_eq!(next.weekday(), chrono::Weekday::Mon);
+
+        let following = next_cron_fire(&schedule, tz, next.with_timezone(&Utc))
+            .unwrap()
+            .with_timezone(&tz);
+        assert_eq!(
+            following,
+            tz.with_ymd_and_hms(2024, 6, 11, 9, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn weekday_names_and_numbers_agree() {
+        let numeric = parse_cron_expression("0 9 * * 1-5").unwrap();
+        let named = parse_cron_expression("0 9 * * MON-FRI").unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        let a: Vec<_> = numeric.after(&after).take(10).collect();
+        let b: Vec<_> = named.after(&after).take(10).collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn daily_fire_keeps_local_hour_across_dst() {
+        let schedule = parse_cron_expression("0 9 * * *").unwrap();
+        let tz: Tz = "America/New_York".parse().unwrap();
+
+        // 2024-03-10 美东进入夏令时，09:00 本地时间由 UTC-5 变为 UTC-4
+        let before = Utc.with_ymd_and_hms(2024, 3, 9, 15, 0, 0).unwrap();
+        let next = next_cron_fire(&schedule, tz, before).unwrap();
+        assert_eq!(next, Utc.with_ymd_and_hms(2024, 3, 10, 13, 0, 0).unwrap());
+        assert_eq!(next.with_timezone(&tz).hour(), 9);
+
+        // 2024-11-03 退出夏令时，09:00 本地时间回到 UTC-5
+        let before = Utc.with_ymd_and_hms(2024, 11, 2, 14, 0, 0).unwrap();
+        let next = next_cron_fire(&schedule, tz, before).unwrap();
+        assert_eq!(next, Utc.with_ymd_and_hms(2024, 11, 3, 14, 0, 0).unwrap());
+        assert_eq!(next.with_timezone(&tz).hour(), 9);
+    }
+
+    #[test]
+    fn validate_rejects_bad_cron_and_timezone() {
+        let err = cron_config("61 9 * * *", None).validate().unwrap_err();
+        assert!(err.contains("Invalid cron expression"), "{}", err);
+
+        let err = cron_config("0 9 * *", None).validate().unwrap_err();
+        assert!(err.contains("expected 5, 6 or 7 fields"), "{}", err);
+
+        let err = cron_config("0 9 * * *", Some("Mars/Olympus"))
+            .validate()
+            .unwrap_err();
+        assert!(err.contains("Invalid timezone"), "{}", err);
+
+        assert!(cron_config("0 9 * * 1-5", Some("Europe/Berlin"))
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn cron_next_run_is_reported() {
+        let config = cron_config("*/5 * * * *", Some("UTC"));
+        assert!(WorkflowScheduler::calculate_next_run(&config).is_some());
+        assert!(
+            WorkflowScheduler::calculate_wait_duration(&config).unwrap()
+                <= std::time::Duration::from_secs(300)
+        );
+    }
+
+    #[test]
+    fn cron_without_upcoming_fire_is_rejected() {
+        let config = cron_config("0 0 9 1 1 * 2020", None);
+        assert!(parse_cron_expression("0 0 9 1 1 * 2020").is_ok());
+        assert!(WorkflowScheduler::calculate_wait_duration(&config).is_none());
+        assert!(WorkflowScheduler::calculate_next_run(&config).is_none());
+
+        let err = config.validate().unwrap_err();
+        assert!(err.contains("no upcoming fire time"), "{}", err);
+    }
+}
@@ -0,0 +1,521 @@
+//! Webhook 触发工作流
+//!
+//! 可选的入站 HTTP 监听：外部系统（CI、侦察服务等）以
+//! `POST /webhook/workflow` 提交 `{"workflow_id": ..., "input": ...}`，
+//! 签名方式与出站通知相同（见 `sentinel_notify::signing`）：请求带
+//! `X-Sentinel-Timestamp`（Unix 秒）与 `X-Sentinel-Signature`
+//! （对 `<timestamp>.<body>` 的 HMAC-SHA256，`sha256=<hex>`）两个头。
+//! 未签名、签名不符或时间戳超出容忍窗口的请求一律返回 401；窗口内同一签名
+//! 只接受一次，重复提交同样返回 401，以防重放。
+
+use crate::scheduler::ScheduleExecutor;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::{Json, Router};
+use chrono::Utc;
+use sentinel_notify::signing::{
+    verify_signature, DEFAULT_SIGNATURE_HEADER, DEFAULT_TOLERANCE_SECS, TIMESTAMP_HEADER,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
+
+/// 返回给前端的配置中代替真实密钥的占位符
+pub const MASKED_SECRET: &str = "********";
+/// 触发路径
+pub const WEBHOOK_PATH: &str = "/webhook/workflow";
+
+/// Webhook 监听配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// 应用启动时自动开启监听
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_host")]
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// HMAC 共享密钥
+    #[serde(default)]
+    pub secret: String,
+    /// 允许绑定非回环地址
+    #[serde(default)]
+    pub allow_remote: bool,
+}
+
+fn default_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_port() -> u16 {
+    17891
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: default_host(),
+            port: default_port(),
+            secret: String::new(),
+            allow_remote: false,
+        }
+    }
+}
+
+impl WebhookConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.secret.trim().is_empty() {
+            return Err("Webhook secret must not be empty".to_string());
+        }
+        if !self.allow_remote && !is_loopback_host(&self.host) {
+            return Err(format!(
+                "Webhook host {} is not a loopback address; enable allow_remote to expose it",
+                self.host
+            ));
+        }
+        Ok(())
+    }
+
+    /// 用占位符替换密钥，供前端展示
+    pub fn masked(mut self) -> Self {
+        if !self.secret.is_empty() {
+            self.secret = MASKED_SECRET.to_string();
+        }
+        self
+    }
+
+    /// 前端回传占位符时沿用已保存的密钥
+    pub fn with_stored_secret(mut self, stored: &WebhookConfig) -> Self {
+        if self.secret == MASKED_SECRET {
+            self.secret = stored.secret.clone();
+        }
+        self
+    }
+
+    fn bind_addr(&self) -> Result<SocketAddr, String> {
+        let host = if self.host.eq_ignore_ascii_case("localhost") {
+            "127.0.0.1"
+        } else {
+            self.host.as_str()
+        };
+        let ip: IpAddr = host
+            .parse()
+            .map_err(|e| format!("Invalid webhook host {}: {}", self.host, e))?;
+        Ok(SocketAddr::new(ip, self.port))
+    }
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<IpAddr>()
+            .map(|ip| ip.is_loopback())
+            .unwrap_or(false)
+}
+
+/// 触发请求体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookTriggerRequest {
+    pub workflow_id: String,
+    #[serde(default)]
+    pub input: serde_json::Value,
+}
+
+#[derive(Clone)]
+struct WebhookAppState {
+    secret: Arc<String>,
+    executor: Arc<dyn ScheduleExecutor + Send + Sync>,
+    replay_guard: Arc<ReplayGuard>,
+}
+
+/// 容忍窗口内已接受的 (时间戳, 签名)
+#[derive(Default)]
+struct ReplayGuard {
+    seen: std::sync::Mutex<HashSet<(u64, String)>>,
+}
+
+impl ReplayGuard {
+    /// 记录一次已验签的请求；窗口内已出现过时返回 false
+    fn record(&self, timestamp: u64, signature: &str, now: u64) -> bool {
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        // 超出窗口的签名会被验签拒绝，无需继续记录
+        seen.retain(|(seen_at, _)| now.abs_diff(*seen_at) <= DEFAULT_TOLERANCE_SECS);
+        seen.insert((timestamp, signature.trim().to_string()))
+    }
+}
+
+async fn trigger_workflow(
+    State(state): State<WebhookAppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+    };
+    let now = Utc::now().timestamp().max(0) as u64;
+    let signature = header(DEFAULT_SIGNATURE_HEADER);
+    let verified = header(TIMESTAMP_HEADER)
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|timestamp| {
+            verify_signature(
+                &state.secret,
+                *timestamp,
+                &body,
+                signature,
+                now,
+                DEFAULT_TOLERANCE_SECS,
+            )
+        });
+    let Some(timestamp) = verified else {
+        tracing::warn!("[Webhook] Rejected request with missing or invalid signature");
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "invalid signature"})),
+        );
+    };
+    if !state.replay_guard.record(timestamp, signature, now) {
+        tracing::warn!(
+            "[Webhook] Rejected replayed request (timestamp {})",
+            timestamp
+        );
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "replayed request"})),
+        );
+    }
+
+    let request: WebhookTriggerRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": format!("invalid body: {}", e)})),
+            )
+        }
+    };
+
+    match state
+        .executor
+        .execute_workflow_with_input(&request.workflow_id, request.input)
+        .await
+    {
+        Ok(execution_id) => {
+            tracing::info!(
+                "[Webhook] Workflow {} started (exec_id: {})",
+                request.workflow_id,
+                execution_id
+            );
+            (
+                StatusCode::ACCEPTED,
+                Json(serde_json::json!({
+                    "workflow_id": request.workflow_id,
+                    "execution_id": execution_id,
+                })),
+            )
+        }
+        Err(e) => {
+            tracing::error!(
+                "[Webhook] Failed to start workflow {}: {}",
+                request.workflow_id,
+                e
+            );
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(serde_json::json!({"error": e})),
+            )
+        }
+    }
+}
+
+/// 运行中的监听
+pub struct WebhookRuntime {
+    bind_addr: SocketAddr,
+    started_at: chrono::DateTime<Utc>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+    task: JoinHandle<()>,
+}
+
+impl WebhookRuntime {
+    pub fn bind_addr(&self) -> SocketAddr {
+        self.bind_addr
+    }
+
+    pub fn started_at(&self) -> String {
+        self.started_at.to_rfc3339()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    pub async fn stop(mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+        let _ = tokio::time::timeout(Duration::from_secs(3), self.task).await;
+    }
+}
+
+/// 监听状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookStatus {
+    pub running: bool,
+    pub bind_addr: Option<String>,
+    pub started_at: Option<String>,
+}
+
+/// 全局监听实例（同一时间只运行一个）
+pub static WEBHOOK_RUNTIME: Mutex<Option<WebhookRuntime>> = Mutex::const_new(None);
+
+/// 启动 Webhook 监听
+pub async fn start_webhook_listener(
+    config: &WebhookConfig,
+    executor: Arc<dyn ScheduleExecutor + Send + Sync>,
+) -> Result<WebhookRuntime, String> {
+    config.validate()?;
+    let listener = TcpListener::bind(config.bind_addr()?)
+        .await
+        .map_err(|e| format!("Failed to bind webhook listener: {}", e))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read local address: {}", e))?;
+
+    let app = Router::new()
+        .route(WEBHOOK_PATH, post(trigger_workflow))
+        .with_state(WebhookAppState {
+            secret: Arc::new(config.secret.clone()),
+            executor,
+            replay_guard: Arc::new(ReplayGuard::default()),
+        });
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let task = tokio::spawn(async move {
+        tracing::info!("[Webhook] Listening on {}", local_addr);
+        let result = axum::serve(listener, app)
+            .with_graceful_shutdown(async move {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+        if let Err(e) = result {
+            tracing::error!("[Webhook] Server error: {}", e);
+        }
+        tracing::info!("[Webhook] Listener stopped");
+    });
+
+    Ok(WebhookRuntime {
+        bind_addr: local_addr,
+        started_at: Utc::now(),
+        shutdown_tx: Some(shutdown_tx),
+        task,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sentinel_notify::signing::sign_payload;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    #[derive(Default)]
+    struct RecordingExecutor {
+        calls: std::sync::Mutex<Vec<(String, serde_json::Value)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ScheduleExecutor for RecordingExecutor {
+        async fn execute_workflow(&self, workflow_id: &str) -> Result<String, String> {
+            self.execute_workflow_with_input(workflow_id, serde_json::Value::Null)
+                .await
+        }
+
+        async fn execute_workflow_with_input(
+            &self,
+            workflow_id: &str,
+            input: serde_json::Value,
+        ) -> Result<String, String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((workflow_id.to_string(), input));
+            Ok("exec-1".to_string())
+        }
+    }
+
+    fn test_config() -> WebhookConfig {
+        WebhookConfig {
+            port: 0,
+            secret: "s3cret".to_string(),
+            ..WebhookConfig::default()
+        }
+    }
+
+    async fn post(addr: SocketAddr, body: &str, signature: Option<(u64, &str)>) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let signature_line = signature
+            .map(|(ts, s)| {
+                format!(
+                    "{}: {}\r\n{}: {}\r\n",
+                    TIMESTAMP_HEADER, ts, DEFAULT_SIGNATURE_HEADER, s
+                )
+            })
+            .unwrap_or_default();
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            WEBHOOK_PATH,
+            addr,
+            signature_line,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[test]
+    fn test_config_defaults_to_localhost_and_requires_secret() {
+        let config = WebhookConfig::default();
+        assert_eq!(config.host, "127.0.0.1");
+        assert!(config.validate().is_err());
+
+        let remote = WebhookConfig {
+            host: "0.0.0.0".to_string(),
+            ..test_config()
+        };
+        assert!(remote.validate().is_err());
+        assert!(WebhookConfig {
+            allow_remote: true,
+            ..remote
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_signed_request_starts_run() {
+        let executor = Arc::new(RecordingExecutor::default());
+        let runtime = start_webhook_listener(&test_config(), executor.clone())
+            .await
+            .unwrap();
+
+        let body = r#"{"workflow_id":"wf-1","input":{"target":"example.com"}}"#;
+        let now = Utc::now().timestamp() as u64;
+        let signature = sign_payload("s3cret", now, body.as_bytes());
+        let response = post(runtime.bind_addr(), body, Some((now, &signature))).await;
+        runtime.stop().await;
+
+        assert!(response.starts_with("HTTP/1.1 202"), "{}", response);
+        assert!(response.contains("exec-1"));
+        let calls = executor.calls.lock().unwrap();
+        assert_eq!(
+            *calls,
+            vec![(
+                "wf-1".to_string(),
+                serde_json::json!({"target": "example.com"})
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_signature_is_rejected() {
+        let executor = Arc::new(RecordingExecutor::default());
+        let runtime = start_webhook_listener(&test_config(), executor.clone())
+            .await
+            .unwrap();
+        let addr = runtime.bind_addr();
+
+        let body = r#"{"workflow_id":"wf-1","input":{}}"#;
+        let now = Utc::now().timestamp() as u64;
+        let forged = sign_payload("wrong-secret", now, body.as_bytes());
+        let forged_response = post(addr, body, Some((now, &forged))).await;
+        let unsigned_response = post(addr, body, None).await;
+        // 合法签名但时间戳过期（重放）
+        let stale = now - DEFAULT_TOLERANCE_SECS - 1;
+        let replayed = sign_payload("s3cret", stale, body.as_bytes());
+        let replayed_response = post(addr, body, Some((stale, &replayed))).await;
+        runtime.stop().await;
+
+        assert!(
+            forged_response.starts_with("HTTP/1.1 401"),
+            "{}",
+            forged_response
+        );
+        assert!(
+            unsigned_response.starts_with("HTTP/1.1 401"),
+            "{}",
+            unsigned_response
+        );
+        assert!(
+            replayed_response.starts_with("HTTP/1.1 401"),
+            "{}",
+            replayed_response
+        );
+        assert!(executor.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replayed_signature_is_rejected() {
+        let executor = Arc::new(RecordingExecutor::default());
+        let runtime = start_webhook_listener(&test_config(), executor.clone())
+            .await
+            .unwrap();
+        let addr = runtime.bind_addr();
+
+        let body = r#"{"workflow_id":"wf-1","input":{}}"#;
+        let now = Utc::now().timestamp() as u64;
+        let signature = sign_payload("s3cret", now, body.as_bytes());
+        let first = post(addr, body, Some((now, &signature))).await;
+        // 窗口内原样重放同一请求
+        let replayed = post(addr, body, Some((now, &signature))).await;
+        // 新时间戳重新签名的请求不受影响
+        let resigned = sign_payload("s3cret", now - 1, body.as_bytes());
+        let fresh = post(addr, body, Some((now - 1, &resigned))).await;
+        runtime.stop().await;
+
+        assert!(first.starts_with("HTTP/1.1 202"), "{}", first);
+        assert!(replayed.starts_with("HTTP/1.1 401"), "{}", replayed);
+        assert!(replayed.contains("replayed request"));
+        assert!(fresh.starts_with("HTTP/1.1 202"), "{}", fresh);
+        assert_eq!(executor.calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_replay_guard_forgets_signatures_outside_window() {
+        let guard = ReplayGuard::default();
+        assert!(guard.record(1_000, "sha256=ab", 1_000));
+        assert!(!guard.record(1_000, " sha256=ab ", 1_100));
+        assert!(guard.record(1_200, "sha256=cd", 1_200));
+        // 第一个签名已超出窗口，被清理
+        guard.record(1_400, "sha256=ef", 1_000 + DEFAULT_TOLERANCE_SECS + 1);
+        assert_eq!(guard.seen.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_masked_secret_round_trips_to_stored_secret() {
+        let stored = test_config();
+        let masked = stored.clone().masked();
+        assert_eq!(masked.secret, MASKED_SECRET);
+        assert_eq!(masked.with_stored_secret(&stored).secret, "s3cret");
+
+        let rotated = WebhookConfig {
+            secret: "new-secret".to_string(),
+            ..test_config()
+        };
+        assert_eq!(rotated.with_stored_secret(&stored).secret, "new-secret");
+    }
+}
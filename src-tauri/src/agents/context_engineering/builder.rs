@@ -7,6 +7,7 @@ use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 
 use sentinel_llm::{ChatMessage, LlmConfig};
+use sentinel_memory::{get_global_memory, MemoryContextRequest, PlanningMemoryContext};
 use sentinel_tools::output_storage::{
     get_history_path, get_host_context_dir, CONTAINER_CONTEXT_DIR,
 };
@@ -25,7 +26,9 @@ use crate::agents::context_engineering::token_utils::{
 };
 use crate::agents::context_engineering::tool_digest::condense_text;
 use crate::agents::context_engineering::types::{trim_history_preserve_tool_pairs, ContextPacket};
-use crate::agents::sliding_window::{SlidingWindowConfig, SlidingWindowManager};
+use crate::agents::sliding_window::{
+    load_compression_aggressiveness, SlidingWindowConfig, SlidingWindowManager,
+};
 use crate::agents::types::DocumentAttachmentInfo;
 
 const USER_FORCED_RULES_CONFIG_CATEGORY: &str = "agent";
@@ -169,6 +172,22 @@ pub async fn build_context(input: ContextBuildInput) -> Result<ContextBuildResul
         system_prompt = inject_task_mainline_summary(system_prompt, &input.task);
     }
 
+    let mut execution_memory_ids: Vec<String> = Vec::new();
+    if policy.include_execution_memory {
+        if let Some(memory) = load_execution_memory(
+            &input.app_handle,
+            &input.task,
+            &input.selected_tool_ids,
+            &policy,
+        )
+        .await
+        {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&memory.text);
+            execution_memory_ids = memory.record_ids;
+        }
+    }
+
     if policy.include_run_state {
         let init_state = ContextRunState {
             task: input.task.clone(),
@@ -184,6 +203,8 @@ pub async fn build_context(input: ContextBuildInput) -> Result<ContextBuildResul
             memory_items: Vec::new(),
             run_state_version: 0,
             last_updated_at_ms: chrono::Utc::now().timestamp_millis(),
+            run_cost: None,
+            plan_memory_ids: Vec::new(),
         };
         let mut state =
             load_or_init_run_state(&input.app_handle, &input.execution_id, init_state).await?;
@@ -215,6 +236,9 @@ pub async fn build_context(input: ContextBuildInput) -> Result<ContextBuildResul
         // fact/decision/todo are kept in run-state memory only.
         ingest_memory_items(&mut state, &memory_facts, &memory_decisions, &memory_todos);
         evict_low_value_items(&mut state);
+        if !execution_memory_ids.is_empty() {
+            state.plan_memory_ids = execution_memory_ids.clone();
+        }
         run_state_digests = state.last_tool_digests.clone();
         if policy.feature_context_packet_v2 {
             let query = MemoryQuery {
@@ -382,6 +406,7 @@ pub async fn build_context(input: ContextBuildInput) -> Result<ContextBuildResul
 
     let sw_config = SlidingWindowConfig {
         max_context_tokens: max_context_length as usize,
+        compression_aggressiveness: load_compression_aggressiveness(&input.app_handle).await,
         ..Default::default()
     };
 
@@ -609,6 +634,7 @@ pub async fn build_context(input: ContextBuildInput) -> Result<ContextBuildResul
             max_tokens,
             trim_trace,
             retrieval_ids: retrieved_memory_ids,
+            execution_memory_ids,
         },
     );
 
@@ -619,6 +645,56 @@ pub async fn build_context(input: ContextBuildInput) -> Result<ContextBuildResul
     })
 }
 
+/// Similar successful executions from sentinel-memory, when enabled in config
+async fn load_execution_memory(
+    app_handle: &AppHandle,
+    task: &str,
+    selected_tool_ids: &[String],
+    policy: &ContextPolicy,
+) -> Option<PlanningMemoryContext> {
+    let db = app_handle.try_state::<Arc<sentinel_db::DatabaseService>>()?;
+    let enabled = db
+        .get_config("agent", "memory_planning_enabled")
+        .await
+        .ok()
+        .flatten()
+        .map(|raw| {
+            matches!(
+                raw.trim().to_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            )
+        })
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+
+    let request = MemoryContextRequest {
+        task: task.to_string(),
+        environment: None,
+        tool_names: selected_tool_ids.to_vec(),
+        max_results: 3,
+    };
+    match get_global_memory()
+        .build_planning_context(request, policy.execution_memory_max_chars)
+        .await
+    {
+        Ok(Some(memory)) => {
+            tracing::info!(
+                "Injected {} prior executions into planning context: {:?}",
+                memory.record_ids.len(),
+                memory.record_ids
+            );
+            Some(memory)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            tracing::warn!("Failed to load execution memory: {}", e);
+            None
+        }
+    }
+}
+
 fn split_context_messages(
     fallback_system_prompt: &str,
     context_messages: Vec<ChatMessage>,
@@ -10,6 +10,7 @@ use tokio::sync::Mutex as TokioMutex;
 
 use crate::agents::context_engineering::policy::ContextPolicy;
 use crate::agents::context_engineering::tool_digest::ToolDigest;
+use crate::agents::executor::cost::RunCost;
 
 /// Per-execution-id lock to prevent concurrent read-modify-write races on RunState.
 fn get_state_lock(execution_id: &str) -> Arc<TokioMutex<()>> {
@@ -55,6 +56,11 @@ pub struct ContextRunState {
     #[serde(default)]
     pub run_state_version: i64,
     pub last_updated_at_ms: i64,
+    #[serde(default)]
+    pub run_cost: Option<RunCost>,
+    /// sentinel-memory executions that informed the plan of this run
+    #[serde(default)]
+    pub plan_memory_ids: Vec<String>,
 }
 
 pub async fn load_run_state(
@@ -123,6 +129,31 @@ pub async fn append_tool_digest(
     Ok(())
 }
 
+pub async fn save_run_cost(
+    app_handle: &AppHandle,
+    execution_id: &str,
+    cost: RunCost,
+) -> Result<()> {
+    let lock = get_state_lock(execution_id);
+    let _guard = lock.lock().await;
+
+    let mut state = load_run_state(app_handle, execution_id)
+        .await?
+        .unwrap_or_default();
+    // Usage persists asynchronously; never let a stale write roll the total back
+    let is_newer = state
+        .run_cost
+        .as_ref()
+        .map(|prev| prev.llm_turns <= cost.llm_turns)
+        .unwrap_or(true);
+    if is_newer {
+        state.run_cost = Some(cost);
+        state.last_updated_at_ms = chrono::Utc::now().timestamp_millis();
+        save_run_state(app_handle, execution_id, &state).await?;
+    }
+    Ok(())
+}
+
 pub async fn append_tool_digests(
     app_handle: &AppHandle,
     execution_id: &str,
@@ -17,7 +17,8 @@ mod tests;
 
 pub use builder::{build_context, ContextBuildInput, ContextBuildResult};
 pub use checkpoint::{
-    append_tool_digest, append_tool_digests, load_run_state, save_run_state, ContextRunState,
+    append_tool_digest, append_tool_digests, load_run_state, save_run_cost, save_run_state,
+    ContextRunState,
 };
 pub use memory_index::{
     evict_low_value_items, ingest_memory_items, ingest_memory_items_persistent,
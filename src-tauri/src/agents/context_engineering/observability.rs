@@ -16,6 +16,8 @@ pub struct ContextSnapshot {
     pub max_tokens: usize,
     pub trim_trace: Vec<String>,
     pub retrieval_ids: Vec<String>,
+    /// sentinel-memory executions injected into the prompt
+    pub execution_memory_ids: Vec<String>,
 }
 
 pub fn record_context_snapshot(app_handle: &AppHandle, snapshot: &ContextSnapshot) {
@@ -45,6 +47,7 @@ pub fn record_context_snapshot(app_handle: &AppHandle, snapshot: &ContextSnapsho
             "max_tokens": snapshot.max_tokens,
             "trim_trace": snapshot.trim_trace,
             "retrieval_ids": snapshot.retrieval_ids,
+            "execution_memory_ids": snapshot.execution_memory_ids,
         }),
     );
 }
@@ -59,6 +59,10 @@ pub struct ContextPolicy {
     pub task_brief_max_chars: usize,
     pub layer_max_chars: usize,
     pub feature_context_packet_v2: bool,
+    /// Inject similar successful prior executions (sentinel-memory) so planning can reuse
+    /// their tool sequences. Also requires the `agent.memory_planning_enabled` config.
+    pub include_execution_memory: bool,
+    pub execution_memory_max_chars: usize,
     pub budget: ContextBudgetPolicy,
 }
 
@@ -78,6 +82,8 @@ impl Default for ContextPolicy {
             task_brief_max_chars: 600,
             layer_max_chars: 12000,
             feature_context_packet_v2: true,
+            include_execution_memory: true,
+            execution_memory_max_chars: 1600,
             budget: ContextBudgetPolicy {
                 system_max_tokens: 4000,
                 run_state_max_tokens: 1800,
@@ -105,6 +111,8 @@ impl ContextPolicy {
             task_brief_max_chars: 400,
             layer_max_chars: 8000,
             feature_context_packet_v2: true,
+            include_execution_memory: false,
+            execution_memory_max_chars: 800,
             budget: ContextBudgetPolicy {
                 system_max_tokens: 2500,
                 run_state_max_tokens: 1200,
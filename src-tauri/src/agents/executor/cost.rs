@@ -0,0 +1,399 @@
+//! Per-execution cost accounting.
+//!
+//! 每轮 LLM 调用的 token 用量在流式回调中经 [`CostTracker`] 同步累加，超过
+//! `cost_limit_usd` 时终止工具调用循环。累计结果同时写入 run state 以便事后查询；
+//! 内存中的条目只在执行期间存在，执行结束时由 [`end_run`] 移除。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+
+/// 单次执行的累计用量与成本
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RunCost {
+    pub execution_id: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+    /// 已上报用量的 LLM 轮次数
+    pub llm_turns: u32,
+    #[serde(default)]
+    pub cost_limit_usd: Option<f64>,
+    #[serde(default)]
+    pub limit_exceeded: bool,
+    pub updated_at_ms: i64,
+}
+
+impl RunCost {
+    fn new(execution_id: &str) -> Self {
+        Self {
+            execution_id: execution_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 累加一轮用量，返回是否因此首次超出限额
+    fn record(&mut self, input_tokens: u32, output_tokens: u32, cost_usd: f64) -> bool {
+        self.input_tokens += input_tokens as u64;
+        self.output_tokens += output_tokens as u64;
+        self.cost_usd += cost_usd;
+        self.llm_turns += 1;
+        self.updated_at_ms = chrono::Utc::now().timestamp_millis();
+
+        let over = self
+            .cost_limit_usd
+            .map(|limit| self.cost_usd > limit)
+            .unwrap_or(false);
+        let newly_exceeded = over && !self.limit_exceeded;
+        self.limit_exceeded |= over;
+        newly_exceeded
+    }
+}
+
+static RUN_COSTS: LazyLock<Mutex<HashMap<String, RunCost>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 开始一次执行并设置限额（`None` 表示不限）
+///
+/// `previous` 为 run state 中持久化的累计成本，同一会话的后续执行从它继续累计。
+pub fn begin_run(execution_id: &str, cost_limit_usd: Option<f64>, previous: Option<RunCost>) {
+    let mut runs = RUN_COSTS.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = runs.entry(execution_id.to_string()).or_insert_with(|| {
+        previous
+            .filter(|cost| cost.execution_id == execution_id)
+            .unwrap_or_else(|| RunCost::new(execution_id))
+    });
+    // 同一会话的后续执行沿用累计成本，只更新限额与超限标记
+    entry.cost_limit_usd = cost_limit_usd.filter(|limit| *limit >= 0.0);
+    entry.limit_exceeded = entry
+        .cost_limit_usd
+        .map(|limit| entry.cost_usd > limit)
+        .unwrap_or(false);
+}
+
+/// 记录一轮用量，返回更新后的累计值
+pub fn record_usage(
+    execution_id: &str,
+    provider: &str,
+    model: &str,
+    input_tokens: u32,
+    output_tokens: u32,
+) -> RunCost {
+    let cost = sentinel_llm::calculate_cost(provider, model, input_tokens, output_tokens);
+    let mut runs = RUN_COSTS.lock().unwrap_or_else(|e| e.into_inner());
+    let entry = runs
+        .entry(execution_id.to_string())
+        .or_insert_with(|| RunCost::new(execution_id));
+    if entry.record(input_tokens, output_tokens, cost) {
+        tracing::warn!(
+            "Cost limit exceeded - execution_id: {}, cost: ${:.4}, limit: ${:.4}",
+            execution_id,
+            entry.cost_usd,
+            entry.cost_limit_usd.unwrap_or_default()
+        );
+    }
+    entry.clone()
+}
+
+/// 结束一次执行：移除内存中的累计并返回最终值，由调用方写回 run state
+pub fn end_run(execution_id: &str) -> Option<RunCost> {
+    RUN_COSTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(execution_id)
+}
+
+/// 执行被取消（future 被丢弃）时兜底移除内存中的累计
+pub struct RunCostGuard {
+    execution_id: String,
+}
+
+impl RunCostGuard {
+    pub fn new(execution_id: &str) -> Self {
+        Self {
+            execution_id: execution_id.to_string(),
+        }
+    }
+}
+
+impl Drop for RunCostGuard {
+    fn drop(&mut self) {
+        end_run(&self.execution_id);
+    }
+}
+
+/// 当前执行是否已超出限额
+pub fn is_limit_exceeded(execution_id: &str) -> bool {
+    RUN_COSTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(execution_id)
+        .map(|c| c.limit_exceeded)
+        .unwrap_or(false)
+}
+
+/// 获取内存中的累计成本
+pub fn get_run_cost(execution_id: &str) -> Option<RunCost> {
+    RUN_COSTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(execution_id)
+        .cloned()
+}
+
+/// 工具调用循环中的成本跟踪
+///
+/// 流式回调每收到一轮用量就调用 [`CostTracker::record`]；一旦超限，
+/// [`CostTracker::should_continue`] 返回 false 以终止当前流，
+/// 循环结束后由 [`CostTracker::finish`] 在回复末尾追加说明。
+#[derive(Debug, Clone)]
+pub struct CostTracker {
+    execution_id: String,
+    provider: String,
+    model: String,
+    limit_reached: Arc<AtomicBool>,
+}
+
+impl CostTracker {
+    pub fn new(execution_id: &str, provider: &str, model: &str) -> Self {
+        Self {
+            execution_id: execution_id.to_string(),
+            provider: provider.to_string(),
+            model: model.to_string(),
+            limit_reached: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// 开始新一轮前检查：会话此前的累计已超限时返回当前累计
+    pub fn exceeded_before_turn(&self) -> Option<RunCost> {
+        if !is_limit_exceeded(&self.execution_id) {
+            return None;
+        }
+        get_run_cost(&self.execution_id)
+    }
+
+    /// 记录一轮用量，返回更新后的累计值
+    pub fn record(&self, input_tokens: u32, output_tokens: u32) -> RunCost {
+        let cost = record_usage(
+            &self.execution_id,
+            &self.provider,
+            &self.model,
+            input_tokens,
+            output_tokens,
+        );
+        if cost.limit_exceeded {
+            self.limit_reached.store(true, Ordering::SeqCst);
+        }
+        cost
+    }
+
+    /// 当前流是否可以继续
+    pub fn should_continue(&self) -> bool {
+        !self.limit_reached()
+    }
+
+    /// 本次循环中是否已触达限额
+    pub fn limit_reached(&self) -> bool {
+        self.limit_reached.load(Ordering::SeqCst)
+    }
+
+    /// 触达限额时在回复末尾追加说明，同时返回当时的累计成本
+    pub fn finish(&self, response: String) -> (String, Option<RunCost>) {
+        if !self.limit_reached() {
+            return (response, None);
+        }
+        let Some(cost) = get_run_cost(&self.execution_id) else {
+            return (response, None);
+        };
+        let notice = limit_reached_message(&cost);
+        let response = if response.trim().is_empty() {
+            notice
+        } else {
+            format!("{}\n\n{}", response, notice)
+        };
+        (response, Some(cost))
+    }
+}
+
+/// 超限时追加到回复末尾的说明
+pub fn limit_reached_message(cost: &RunCost) -> String {
+    format!(
+        "[Cost limit reached] Execution stopped after {} LLM turns: ${:.4} spent, limit ${:.4}.",
+        cost.llm_turns,
+        cost.cost_usd,
+        cost.cost_limit_usd.unwrap_or_default()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sentinel_llm::{LlmConfig, StreamContent, StreamingLlmClient};
+    use sentinel_tools::{DynamicTool, DynamicToolBuilder};
+    use std::sync::atomic::AtomicUsize;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// 每次请求都回一轮工具调用（1000 输入 / 500 输出 token），模型自己永远不会结束
+    const TOOL_CALL_TURN: &str = r#"
+data: {"choices":[{"index":0,"delta":{"role":"assistant","tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"http_request","arguments":"{\"url\":\"https://example.com\"}"}}]},"finish_reason":null}]}
+
+data: {"choices":[{"index":0,"delta":{},"finish_reason":"tool_calls"}]}
+
+data: {"choices":[],"usage":{"prompt_tokens":1000,"completion_tokens":500,"total_tokens":1500}}
+
+data: [DONE]
+
+"#;
+
+    /// OpenAI 兼容的 SSE 服务，返回地址与请求计数
+    async fn serve_tool_loop() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let counter = counter.clone();
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 8192];
+                    // 读完请求头与 Content-Length 指定的请求体
+                    loop {
+                        let n = socket.read(&mut chunk).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        let text = String::from_utf8_lossy(&buf).to_lowercase();
+                        if let Some(end) = text.find("\r\n\r\n") {
+                            let length = text[..end]
+                                .lines()
+                                .find_map(|l| l.strip_prefix("content-length:"))
+                                .and_then(|v| v.trim().parse::<usize>().ok())
+                                .unwrap_or(0);
+                            if buf.len() >= end + 4 + length {
+                                break;
+                            }
+                        }
+                    }
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        TOOL_CALL_TURN.len(),
+                        TOOL_CALL_TURN
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        (format!("http://{}", addr), requests)
+    }
+
+    fn http_request_tool() -> DynamicTool {
+        let def = DynamicToolBuilder::new("http_request")
+            .description("Fetch a URL")
+            .input_schema(serde_json::json!({
+                "type": "object",
+                "properties": { "url": { "type": "string" } },
+                "required": ["url"]
+            }))
+            .executor(|_args| async move { Ok(serde_json::json!({ "status": 200 })) })
+            .build()
+            .unwrap();
+        DynamicTool::new(def)
+    }
+
+    /// 按执行器的方式跑真实的流式工具调用循环，返回最终回复与模型请求数
+    async fn run_tool_loop(
+        execution_id: &str,
+        limit: Option<f64>,
+        max_turns: usize,
+    ) -> (String, usize) {
+        let (base_url, requests) = serve_tool_loop().await;
+        begin_run(execution_id, limit, None);
+        let tracker = CostTracker::new(execution_id, "openai", "gpt-4o");
+        let client = StreamingLlmClient::new(
+            LlmConfig::new("openai", "gpt-4o")
+                .with_api_key("test-key")
+                .with_base_url(base_url)
+                .with_max_turns(max_turns)
+                .with_timeout(30),
+        );
+
+        let stream_tracker = tracker.clone();
+        let response = client
+            .stream_chat_with_dynamic_tools(
+                None,
+                "Fetch https://example.com",
+                &[],
+                None,
+                vec![http_request_tool()],
+                |content| {
+                    if let StreamContent::Usage {
+                        input_tokens,
+                        output_tokens,
+                        ..
+                    } = content
+                    {
+                        stream_tracker.record(input_tokens, output_tokens);
+                    }
+                    stream_tracker.should_continue()
+                },
+            )
+            .await
+            .unwrap_or_default();
+        let (response, _) = tracker.finish(response);
+        (response, requests.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    async fn test_low_limit_stops_tool_loop_early() {
+        let id = format!("cost-test-{}", uuid::Uuid::new_v4());
+        // 纯工具调用轮次只有估算用量（每轮约 $0.0002），几轮之后即超出预算
+        let limit = 0.0004;
+        let (response, requests) = run_tool_loop(&id, Some(limit), 10).await;
+        assert!(response.contains("[Cost limit reached]"));
+
+        let cost = end_run(&id).unwrap();
+        assert!(cost.limit_exceeded);
+        assert!(cost.cost_usd >= limit);
+        // 超出预算的那一轮之后不再发起新的模型请求
+        assert!(requests > 1 && requests < 10);
+        assert_eq!(cost.llm_turns as usize, requests);
+        assert!(response.contains(&format!("{} LLM turns", requests)));
+        assert!(get_run_cost(&id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_no_limit_runs_until_max_turns() {
+        let id = format!("cost-test-{}", uuid::Uuid::new_v4());
+        let (response, requests) = run_tool_loop(&id, None, 4).await;
+        assert!(requests > 3);
+        assert!(!response.contains("[Cost limit reached]"));
+        assert!(!end_run(&id).unwrap().limit_exceeded);
+    }
+
+    #[test]
+    fn test_limit_applies_to_accumulated_session_cost() {
+        let id = format!("cost-test-{}", uuid::Uuid::new_v4());
+        begin_run(&id, None, None);
+        for _ in 0..4 {
+            record_usage(&id, "openai", "gpt-4o", 1_000, 500);
+        }
+        // 执行结束后内存条目被移除，下一次执行从持久化的累计继续
+        let persisted = end_run(&id);
+        assert!(get_run_cost(&id).is_none());
+
+        // 已花费 $0.03，新限额 $0.02 在开始前即已超出
+        begin_run(&id, Some(0.02), persisted);
+        let guard = RunCostGuard::new(&id);
+        let tracker = CostTracker::new(&id, "openai", "gpt-4o");
+        let cost = tracker.exceeded_before_turn().unwrap();
+        assert_eq!(cost.llm_turns, 4);
+        drop(guard);
+        assert!(get_run_cost(&id).is_none());
+    }
+}
@@ -57,6 +57,7 @@ pub async fn save_assistant_message(
             structured_data: None,
         };
 
+        let _guard = crate::managers::shutdown_coordinator::begin_operation("agent message save");
         if let Err(e) = db.create_ai_message(&msg).await {
             tracing::warn!("Failed to save assistant message: {}", e);
         } else {
@@ -8,18 +8,24 @@ use sentinel_db::Database;
 use sentinel_llm::LlmConfig;
 use sentinel_tools::{get_tool_server, mcp_adapter};
 
+use crate::agents::prompt_wrap::SystemPromptWrap;
 use crate::agents::tenth_man::TenthManConfig;
 use crate::agents::tool_router::ToolConfig;
 use crate::agents::ContextPolicy;
 use crate::agents::DocumentAttachmentInfo;
+use crate::managers::resource_registry::ResourceCleanupGuard;
 use crate::utils::ai_generation_settings::apply_generation_settings_from_db;
 
 use self::run_simple::execute_agent_simple;
 use self::run_with_tools::execute_agent_with_tools;
 
+pub mod cost;
 pub mod message_store;
 pub mod run_simple;
 pub mod run_with_tools;
+pub mod step_timeout;
+pub mod tool_cache;
+pub mod tool_call_log;
 pub mod tool_exec;
 pub mod types;
 pub mod utils;
@@ -50,15 +56,24 @@ pub struct AgentExecuteParams {
     pub subagent_run_id: Option<String>,
     pub context_policy: Option<ContextPolicy>,
     pub recursion_depth: usize,
+    /// Stop the tool-calling loop once accumulated spend exceeds this amount (USD)
+    pub cost_limit_usd: Option<f64>,
+    /// Subagent depth and fan-out ceilings, inherited by spawned subagents
+    pub subagent_limits: crate::agents::subagent_executor::SubagentLimits,
 }
 
 /// Execute agent task.
-pub async fn execute_agent(app_handle: &AppHandle, params: AgentExecuteParams) -> Result<String> {
+pub async fn execute_agent(
+    app_handle: &AppHandle,
+    mut params: AgentExecuteParams,
+) -> Result<String> {
     let rig_provider = params.rig_provider.to_lowercase();
     let execution_id = params.execution_id.clone();
+    // Releases browsers/proxies left behind by this run, even if it is cancelled or panics
+    let resource_guard = ResourceCleanupGuard::new(&execution_id);
 
     tracing::info!(
-        "Executing agent - rig_provider: {}, model: {}, execution_id: {}, tools_enabled: {}, recursion_depth: {}",
+        "Executing agent - rig_provider: {}, model: {}, execution_id: {}, tools_enabled: {}, recursion_depth: {}/{}",
         rig_provider,
         params.model,
         params.execution_id,
@@ -67,7 +82,8 @@ pub async fn execute_agent(app_handle: &AppHandle, params: AgentExecuteParams) -
             .as_ref()
             .map(|c| c.enabled)
             .unwrap_or(false),
-        params.recursion_depth
+        params.recursion_depth,
+        params.subagent_limits.max_recursion_depth
     );
 
     let parent_context = crate::agents::subagent_executor::SubagentParentContext {
@@ -81,9 +97,18 @@ pub async fn execute_agent(app_handle: &AppHandle, params: AgentExecuteParams) -
         timeout_secs: params.timeout_secs,
         task_context: params.task.clone(),
         recursion_depth: params.recursion_depth,
+        limits: params.subagent_limits,
+        root_execution_id: crate::agents::subagent_executor::root_execution_id(&execution_id).await,
     };
     crate::agents::subagent_executor::set_parent_context(execution_id.clone(), parent_context)
         .await;
+    let previous_cost = crate::agents::load_run_state(app_handle, &execution_id)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|state| state.run_cost);
+    cost::begin_run(&execution_id, params.cost_limit_usd, previous_cost);
+    let _cost_guard = cost::RunCostGuard::new(&execution_id);
 
     if let Some(db) = app_handle.try_state::<Arc<sentinel_db::DatabaseService>>() {
         if let Ok(client) = db.get_db() {
@@ -95,6 +120,10 @@ pub async fn execute_agent(app_handle: &AppHandle, params: AgentExecuteParams) -
         if let Ok(api_key) = db.get_config("ai", "tavily_api_key").await {
             sentinel_tools::tool_server::set_tavily_api_key(api_key).await;
         }
+
+        // 子 Agent 继承的是未包裹的系统提示词，避免前后缀重复叠加
+        let wrap = SystemPromptWrap::load(db.as_ref()).await;
+        params.system_prompt = wrap.apply(&params.system_prompt);
     }
 
     let tool_server = get_tool_server();
@@ -213,6 +242,18 @@ pub async fn execute_agent(app_handle: &AppHandle, params: AgentExecuteParams) -
     };
 
     crate::agents::subagent_executor::clear_parent_context(&execution_id).await;
+    sentinel_tools::cancellation::clear_cancellation_scope(&execution_id).await;
+    crate::utils::clear_session_counters(&execution_id);
+    // 用量在回调中异步持久化，结束时写回最终累计，避免内存条目随进程常驻
+    if let Some(run_cost) = cost::end_run(&execution_id) {
+        if let Err(e) =
+            crate::agents::context_engineering::save_run_cost(app_handle, &execution_id, run_cost)
+                .await
+        {
+            tracing::warn!("Failed to persist run cost: {}", e);
+        }
+    }
+    resource_guard.cleanup().await;
 
     result
 }
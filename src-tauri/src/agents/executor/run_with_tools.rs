@@ -13,19 +13,27 @@ use sentinel_tools::buildin_tools::todos::{
     auto_complete_all_todos, get_execution_todos, TodoStatus as ExecutionTodoStatus, TodosList,
 };
 use sentinel_tools::buildin_tools::{ShellTool, SkillsTool, TodosTool};
-use sentinel_tools::dynamic_tool::{DynamicTool, DynamicToolDef, ToolExecutor, ToolSource};
-use sentinel_tools::ToolServer;
+use sentinel_tools::dynamic_tool::{
+    DynamicTool, DynamicToolDef, SchemaValidation, ToolExecutor, ToolSource,
+};
+use sentinel_tools::{ToolInfo, ToolServer};
 
+use super::cost;
+use super::step_timeout;
+use super::tool_cache::{self, ToolResultCache};
 use super::AgentExecuteParams;
 use crate::agents::context_engineering::reflection::{
     record_execution_reflection, ExecutionOutcome,
 };
+use crate::agents::context_engineering::save_run_cost;
 use crate::agents::executor::message_store::save_assistant_message;
+use crate::agents::executor::tool_call_log;
 use crate::agents::executor::types::ToolCallRecord;
 use crate::agents::executor::utils::{cleanup_container_context_async, truncate_for_memory};
 use crate::agents::tenth_man::{InterventionContext, InterventionMode, TenthMan, TriggerReason};
 use crate::agents::tool_router::ToolRouter;
 use crate::agents::{append_tool_digests, build_context, build_tool_digest, ContextBuildInput};
+use crate::managers::resource_registry::{register_resource, unregister_resource, ResourceKind};
 use crate::utils::ai_generation_settings::apply_generation_settings_from_db;
 
 async fn is_skills_enabled_in_db(db: &DatabaseService) -> bool {
@@ -57,15 +65,24 @@ async fn register_skills_tool_guard(
         return Ok(());
     };
 
-    let input_schema = info.input_schema.clone();
-    let description = info.description.clone();
-
+    let def = skills_tool_guard_def(&info, db, None);
     tool_server.unregister_tool(SkillsTool::NAME).await;
+    tool_server.register_tool(def).await;
+    Ok(())
+}
 
+/// Build the guarded skills tool; skill hooks run on behalf of `execution_id`
+fn skills_tool_guard_def(
+    info: &ToolInfo,
+    db: Arc<DatabaseService>,
+    execution_id: Option<String>,
+) -> DynamicToolDef {
     let executor: ToolExecutor = Arc::new(move |args: serde_json::Value| {
         let db = db.clone();
+        let execution_id = execution_id.clone();
         Box::pin(async move {
             use rig::tool::Tool;
+            use crate::skills::hooks::HookPhase;
             use sentinel_tools::buildin_tools::skills::{SkillsAction, SkillsTool, SkillsToolArgs};
 
             let tool_args: SkillsToolArgs =
@@ -88,12 +105,34 @@ async fn register_skills_tool_guard(
                 }
             }
 
+            // Loading a skill is its invocation: run its hooks around it
+            let skill_hooks = match (&tool_args.action, skill_id) {
+                (SkillsAction::Load, Some(id)) => crate::skills::hooks::load_skill_hooks(&db, id)
+                    .await
+                    .map_err(|e| format!("{:#}", e))?
+                    .map(|(runner, hooks)| (runner.with_execution_id(execution_id), hooks)),
+                _ => None,
+            };
+            if let Some((runner, hooks)) = &skill_hooks {
+                runner
+                    .run(hooks, HookPhase::Pre)
+                    .await
+                    .map_err(|e| format!("{:#}", e))?;
+            }
+
             let tool = SkillsTool;
             let mut result = tool
                 .call(tool_args)
                 .await
                 .map_err(|e| format!("Skills operation failed: {}", e))?;
 
+            if let Some((runner, hooks)) = &skill_hooks {
+                runner
+                    .run(hooks, HookPhase::Post)
+                    .await
+                    .map_err(|e| format!("{:#}", e))?;
+            }
+
             if matches!(result.action.as_str(), "list") {
                 if let Some(skills) = result.skills.take() {
                     let mut filtered = Vec::new();
@@ -110,18 +149,16 @@ async fn register_skills_tool_guard(
         })
     });
 
-    let def = DynamicToolDef {
+    DynamicToolDef {
         name: SkillsTool::NAME.to_string(),
-        description,
-        input_schema,
+        description: info.description.clone(),
+        input_schema: info.input_schema.clone(),
         output_schema: None,
         source: ToolSource::Builtin,
         category: "system".to_string(),
+        schema_validation: SchemaValidation::default(),
         executor,
-    };
-
-    tool_server.register_tool(def).await;
-    Ok(())
+    }
 }
 
 fn apply_allowed_tools_policy(mut tool_ids: Vec<String>, allowed_tools: &[String]) -> Vec<String> {
@@ -309,6 +346,7 @@ async fn persist_ai_message_with_retry(
     log_label: &str,
 ) {
     const MAX_RETRIES: usize = 3;
+    let _guard = crate::managers::shutdown_coordinator::begin_operation("agent message save");
     for attempt in 0..=MAX_RETRIES {
         match db.upsert_ai_message_append(&msg).await {
             Ok(_) => return,
@@ -533,6 +571,11 @@ pub async fn execute_agent_with_tools(
     let tool_seq: Arc<AtomicU32> = Arc::new(AtomicU32::new(0));
     let tool_call_counter: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
     let loop_break_requested: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+    let cost_tracker = cost::CostTracker::new(
+        &params.execution_id,
+        &params.rig_provider,
+        &params.model,
+    );
     let loop_guard_prompt_needed: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
     let last_tool_fingerprint: Arc<Mutex<Option<u64>>> = Arc::new(Mutex::new(None));
     let repeated_tool_fingerprint_count: Arc<AtomicUsize> = Arc::new(AtomicUsize::new(0));
@@ -551,6 +594,7 @@ pub async fn execute_agent_with_tools(
     let seq_counter = tool_seq.clone();
     let tool_counter = tool_call_counter.clone();
     let loop_break_flag = loop_break_requested.clone();
+    let cost_tracker_for_stream = cost_tracker.clone();
     let loop_prompt_flag = loop_guard_prompt_needed.clone();
     let last_tool_fp = last_tool_fingerprint.clone();
     let repeated_tool_fp_count = repeated_tool_fingerprint_count.clone();
@@ -560,8 +604,11 @@ pub async fn execute_agent_with_tools(
     let persisted_seg_count = persisted_segment_count.clone();
 
     // Ensure skills tool enforces per-skill enable flags at execution time.
-    if let Some(db) = app_handle.try_state::<Arc<sentinel_db::DatabaseService>>() {
-        register_skills_tool_guard(tool_server, db.inner().clone()).await?;
+    let skills_db = app_handle
+        .try_state::<Arc<sentinel_db::DatabaseService>>()
+        .map(|db| db.inner().clone());
+    if let Some(db) = &skills_db {
+        register_skills_tool_guard(tool_server, db.clone()).await?;
     }
 
     // 7. 调用带动态工具的流式方法，增加重试机制以应对模型抖动或解析错误
@@ -628,7 +675,6 @@ pub async fn execute_agent_with_tools(
             );
             let mut tool_calls_msg = ChatMessage::assistant(".");
             tool_calls_msg.tool_calls = Some(tool_calls_json);
-            tool_calls_msg.reasoning_content = Some(String::new());
             history.push(tool_calls_msg);
 
             for call in ordered_calls.iter() {
@@ -650,6 +696,9 @@ pub async fn execute_agent_with_tools(
     let team_stream_started = Arc::new(AtomicBool::new(false));
     let team_stream_had_delta = Arc::new(AtomicBool::new(false));
 
+    // 本次执行内的工具结果缓存（跨重试保留）
+    let tool_result_cache = Arc::new(ToolResultCache::new());
+
     let mut force_history_with_tools = false;
     while retries <= max_retries {
         // Early exit if cancelled before starting a new stream turn
@@ -668,6 +717,12 @@ pub async fn execute_agent_with_tools(
             return Ok(String::new());
         }
 
+        // Spend accumulated earlier in this conversation may already exceed the limit
+        if let Some(run_cost) = cost_tracker.exceeded_before_turn() {
+            let _ = app_handle.emit("agent:cost_limit_reached", &run_cost);
+            return Ok(cost::limit_reached_message(&run_cost));
+        }
+
         let mut dynamic_tools = tool_server.get_dynamic_tools(&current_tool_ids).await;
 
         if current_tool_ids.iter().any(|id| id == ShellTool::NAME) {
@@ -692,11 +747,25 @@ pub async fn execute_agent_with_tools(
                         let tool_args: ShellArgs = serde_json::from_value(patched_args)
                             .map_err(|e| format!("Invalid arguments: {}", e))?;
 
+                        // A cancelled run kills its running command through the cleanup guard
+                        let cancel_id = execution_id_for_shell.clone();
+                        let resource = register_resource(
+                            &execution_id_for_shell,
+                            ResourceKind::Process,
+                            "shell command",
+                            move || async move {
+                                sentinel_tools::buildin_tools::shell::cancel_shell_execution(
+                                    &cancel_id,
+                                )
+                                .await;
+                                Ok(())
+                            },
+                        );
                         let tool = ShellTool::new();
-                        let result = tool
-                            .call(tool_args)
-                            .await
-                            .map_err(|e| format!("Shell execution failed: {}", e))?;
+                        let result = tool.call(tool_args).await;
+                        unregister_resource(&execution_id_for_shell, &resource);
+                        let result =
+                            result.map_err(|e| format!("Shell execution failed: {}", e))?;
 
                         serde_json::to_value(result)
                             .map_err(|e| format!("Failed to serialize shell result: {}", e))
@@ -710,6 +779,7 @@ pub async fn execute_agent_with_tools(
                     output_schema: None,
                     source: ToolSource::Builtin,
                     category: "system".to_string(),
+                    schema_validation: SchemaValidation::default(),
                     executor: shell_executor,
                 };
 
@@ -768,6 +838,7 @@ pub async fn execute_agent_with_tools(
                     output_schema: None,
                     source: ToolSource::Builtin,
                     category: "system".to_string(),
+                    schema_validation: SchemaValidation::default(),
                     executor: todos_executor,
                 };
 
@@ -784,6 +855,53 @@ pub async fn execute_agent_with_tools(
             }
         }
 
+        if let Some(db) = &skills_db {
+            if current_tool_ids.iter().any(|id| id == SkillsTool::NAME) {
+                if let Some(skills_info) = tool_server.get_tool(SkillsTool::NAME).await {
+                    // Skill hooks ask for shell approval on behalf of this execution,
+                    // never one named in the model's arguments
+                    let skills_def = skills_tool_guard_def(
+                        &skills_info,
+                        db.clone(),
+                        Some(params.execution_id.clone()),
+                    );
+                    dynamic_tools = dynamic_tools
+                        .into_iter()
+                        .map(|tool| {
+                            if tool.name() == SkillsTool::NAME {
+                                DynamicTool::new(skills_def.clone())
+                            } else {
+                                tool
+                            }
+                        })
+                        .collect();
+                }
+            }
+        }
+
+        let dynamic_tools =
+            tool_cache::wrap_tools(dynamic_tools, &tool_result_cache, &params.execution_id);
+        // Every tool call follows the execution's cancellation scope
+        let dynamic_tools: Vec<DynamicTool> = dynamic_tools
+            .into_iter()
+            .map(|tool| {
+                let mut def = tool.def().clone();
+                def.executor = sentinel_tools::cancellation::scoped_executor(
+                    params.execution_id.clone(),
+                    def.executor,
+                );
+                DynamicTool::new(def)
+            })
+            .collect();
+        let dynamic_tools = match tool_config.step_timeout_secs.filter(|secs| *secs > 0) {
+            Some(secs) => step_timeout::wrap_tools(
+                dynamic_tools,
+                std::time::Duration::from_secs(secs),
+                &params.execution_id,
+            ),
+            None => dynamic_tools,
+        };
+
         tracing::info!(
             "Got {} dynamic tool instances for rig-core native tool calling",
             dynamic_tools.len()
@@ -1037,21 +1155,12 @@ pub async fn execute_agent_with_tools(
                                         .single()
                                         .unwrap_or_else(chrono::Utc::now);
 
-                                    // Get reasoning content (for deepseek-reasoner with tool calls, always include it)
-                                    // 参考：https://api-docs.deepseek.com/zh-cn/guides/thinking_mode#tool-calls
+                                    // 推理内容仅在非空时保存，历史回放时由 sentinel-llm 统一处理各提供商的字段要求
                                     let reasoning = reasoning_buf
                                         .lock()
-                                        .map(|g| {
-                                            let r = g.clone();
-                                            // 即使为空也返回 Some("")，因为 deepseek-reasoner 要求必须有此字段
-                                            Some(if r.trim().is_empty() {
-                                                String::new()
-                                            } else {
-                                                r
-                                            })
-                                        })
                                         .ok()
-                                        .flatten();
+                                        .map(|g| g.clone())
+                                        .filter(|r| !r.trim().is_empty());
 
                                     let seg_msg = core_db::AiMessage {
                                         id: uuid::Uuid::new_v4().to_string(),
@@ -1187,19 +1296,27 @@ pub async fn execute_agent_with_tools(
                                         tool_success,
                                         &result,
                                     );
-                                    if let Ok(mut records) = collector.lock() {
-                                        records.push(ToolCallRecord {
-                                            id: id.clone(),
-                                            name,
-                                            arguments,
-                                            result: Some(result.clone()),
-                                            success: tool_success,
-                                            sequence: seq,
-                                            started_at_ms,
-                                            completed_at_ms,
-                                            duration_ms,
+                                    let record = ToolCallRecord {
+                                        id: id.clone(),
+                                        name,
+                                        arguments,
+                                        result: Some(result.clone()),
+                                        success: tool_success,
+                                        sequence: seq,
+                                        started_at_ms,
+                                        completed_at_ms,
+                                        duration_ms,
+                                    };
+                                    if let Some(db) = db_for_stream.clone() {
+                                        let persisted =
+                                            tool_call_log::to_persisted(&execution_id, &record);
+                                        tauri::async_runtime::spawn(async move {
+                                            tool_call_log::persist(db, persisted).await;
                                         });
                                     }
+                                    if let Ok(mut records) = collector.lock() {
+                                        records.push(record);
+                                    }
 
                                     // Update persisted tool message with result (keep timestamp as started_at to avoid reordering).
                                     if let Some(db) = db_for_stream.clone() {
@@ -1358,13 +1475,15 @@ pub async fn execute_agent_with_tools(
                 StreamContent::Usage {
                     input_tokens,
                     output_tokens,
+                    estimated,
                 } => {
                     tracing::info!(
-                        "Token usage report - execution_id: {}, input: {}, output: {}, total: {}",
+                        "Token usage report - execution_id: {}, input: {}, output: {}, total: {}, estimated: {}",
                         execution_id,
                         input_tokens,
                         output_tokens,
-                        input_tokens + output_tokens
+                        input_tokens + output_tokens,
+                        estimated
                     );
                     let _ = app.emit(
                                 "agent:chunk",
@@ -1373,6 +1492,7 @@ pub async fn execute_agent_with_tools(
                                     "chunk_type": "usage",
                                     "input_tokens": input_tokens,
                                     "output_tokens": output_tokens,
+                                    "estimated": estimated,
                                 }),
                             );
 
@@ -1412,6 +1532,24 @@ pub async fn execute_agent_with_tools(
                                         }
                                     });
                                 }
+
+                                // 累计本次执行的成本，超出限额时终止循环
+                                let run_cost =
+                                    cost_tracker_for_stream.record(input_tokens, output_tokens);
+                                let _ = app.emit("agent:cost_update", &run_cost);
+                                let app_for_cost = app.clone();
+                                let execution_id_for_cost = execution_id.clone();
+                                tokio::spawn(async move {
+                                    if let Err(e) = save_run_cost(
+                                        &app_for_cost,
+                                        &execution_id_for_cost,
+                                        run_cost,
+                                    )
+                                    .await
+                                    {
+                                        tracing::warn!("Failed to persist run cost: {}", e);
+                                    }
+                                });
                             }
                         }
                         StreamContent::Done => {
@@ -1428,6 +1566,9 @@ pub async fn execute_agent_with_tools(
                     if loop_break_flag.load(Ordering::SeqCst) {
                         return false;
                     }
+                    if !cost_tracker_for_stream.should_continue() {
+                        return false;
+                    }
                     if skill_reload_requested.load(Ordering::SeqCst) {
                         return false;
                     }
@@ -1451,6 +1592,22 @@ pub async fn execute_agent_with_tools(
             tracing::warn!("Failed to flush tool digests: {}", e);
         }
 
+        // Cost limit reached: finish with what we have instead of retrying or reloading
+        let cost_limit_hit = cost_tracker.limit_reached();
+        let result = if cost_limit_hit {
+            skill_reload_requested.store(false, Ordering::SeqCst);
+            loop_break_requested.store(false, Ordering::SeqCst);
+            result.map(|response| {
+                let (response, run_cost) = cost_tracker.finish(response);
+                if let Some(run_cost) = run_cost {
+                    let _ = app_handle.emit("agent:cost_limit_reached", &run_cost);
+                }
+                response
+            })
+        } else {
+            result
+        };
+
         if skill_reload_requested.load(Ordering::SeqCst) {
             if skill_reload_count >= max_skill_reload {
                 skill_reload_requested.store(false, Ordering::SeqCst);
@@ -1575,7 +1732,11 @@ pub async fn execute_agent_with_tools(
                                     structured_data: None,
                                 };
                                 let db_clone = db;
+                                let guard = crate::managers::shutdown_coordinator::begin_operation(
+                                    "agent message save",
+                                );
                                 tauri::async_runtime::spawn(async move {
+                                    let _guard = guard;
                                     if let Err(e) = db_clone.upsert_ai_message_append(&msg).await {
                                         tracing::warn!(
                                             "Failed to persist skill_loaded message: {}",
@@ -1677,7 +1838,7 @@ pub async fn execute_agent_with_tools(
                     .await
                     .map(|list| collect_incomplete_todo_summaries(&list, 5))
                     .unwrap_or_default();
-                if !incomplete_todos.is_empty() {
+                if !incomplete_todos.is_empty() && !cost_limit_hit {
                     // Smart completion detection: if the agent's response clearly
                     // indicates the task is done, auto-complete the remaining todos
                     // instead of retrying and causing an infinite loop.
@@ -0,0 +1,215 @@
+//! Per-step tool timeout.
+//!
+//! 单个工具调用（一个执行步骤）超过 `ToolConfig::step_timeout_secs` 时被取消，
+//! 返回结构化的超时结果而不是一直阻塞；同时把当前进行中的 todo 标记为失败，
+//! 由模型通过 todos `replan` 重试或绕开该步骤。该超时与整体执行超时相互独立。
+
+use serde::Serialize;
+use serde_json::Value;
+use std::time::Duration;
+
+use sentinel_tools::buildin_tools::todos::{mark_current_todo_failed, TIMEOUT_RESULT_PREFIX};
+use sentinel_tools::dynamic_tool::{DynamicTool, ToolExecutor};
+
+/// 不受步骤超时约束的工具：编排类工具本身就会等待其他步骤完成
+const EXEMPT_TOOLS: &[&str] = &[
+    "todos",
+    "subagent_execute",
+    "subagent_await",
+    "subagent_channel",
+];
+
+/// 步骤状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    TimedOut,
+}
+
+/// 步骤超时时返回给模型的工具结果
+#[derive(Debug, Clone, Serialize)]
+pub struct StepTimeoutResult {
+    pub success: bool,
+    pub status: StepStatus,
+    pub tool_name: String,
+    pub timeout_secs: u64,
+    pub error: String,
+    pub hint: String,
+}
+
+impl StepTimeoutResult {
+    pub fn new(tool_name: &str, timeout: Duration) -> Self {
+        Self {
+            success: false,
+            status: StepStatus::TimedOut,
+            tool_name: tool_name.to_string(),
+            timeout_secs: timeout.as_secs(),
+            error: format!(
+                "Tool step '{}' timed out after {}s and was cancelled",
+                tool_name,
+                timeout.as_secs()
+            ),
+            hint: "The current todo was marked failed. Replan with todos(action=\"replan\", replan_trigger=\"timeout\") to retry with a narrower scope or route around this step.".to_string(),
+        }
+    }
+}
+
+/// 为工具包装步骤超时，豁免工具原样返回
+pub fn wrap_tools(
+    tools: Vec<DynamicTool>,
+    timeout: Duration,
+    execution_id: &str,
+) -> Vec<DynamicTool> {
+    tools
+        .into_iter()
+        .map(|tool| {
+            if EXEMPT_TOOLS.contains(&tool.def().name.as_str()) {
+                return tool;
+            }
+            let mut def = tool.def().clone();
+            def.executor = timed_executor(
+                def.name.clone(),
+                def.executor.clone(),
+                timeout,
+                execution_id.to_string(),
+            );
+            DynamicTool::new(def)
+        })
+        .collect()
+}
+
+fn timed_executor(
+    tool_name: String,
+    inner: ToolExecutor,
+    timeout: Duration,
+    execution_id: String,
+) -> ToolExecutor {
+    std::sync::Arc::new(move |args: Value| {
+        let tool_name = tool_name.clone();
+        let inner = inner.clone();
+        let execution_id = execution_id.clone();
+        Box::pin(async move {
+            match tokio::time::timeout(timeout, inner(args)).await {
+                Ok(result) => result,
+                Err(_) => {
+                    tracing::warn!(
+                        "Tool step timed out - execution_id: {}, tool: {}, timeout: {}s",
+                        execution_id,
+                        tool_name,
+                        timeout.as_secs()
+                    );
+                    let result = StepTimeoutResult::new(&tool_name, timeout);
+                    mark_current_todo_failed(
+                        &execution_id,
+                        &format!("{} {}", TIMEOUT_RESULT_PREFIX, result.error),
+                    )
+                    .await;
+                    serde_json::to_value(result).map_err(|e| e.to_string())
+                }
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rig::tool::Tool;
+    use sentinel_tools::buildin_tools::todos::{
+        cleanup_execution_todos, get_replan_events, ReplanTrigger, TodosArgs, TodosTool,
+    };
+    use sentinel_tools::dynamic_tool::{DynamicToolDef, SchemaValidation, ToolSource};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn sleeping_tool(name: &str, delay: Duration) -> DynamicTool {
+        let executor: ToolExecutor = Arc::new(move |_args: Value| {
+            Box::pin(async move {
+                tokio::time::sleep(delay).await;
+                Ok(json!({ "success": true }))
+            })
+        });
+        DynamicTool::new(DynamicToolDef {
+            name: name.to_string(),
+            description: String::new(),
+            input_schema: json!({ "type": "object" }),
+            output_schema: None,
+            source: ToolSource::Builtin,
+            category: "test".to_string(),
+            schema_validation: SchemaValidation::default(),
+            executor,
+        })
+    }
+
+    fn todos_args(execution_id: &str, action: &str, items: &[&str]) -> TodosArgs {
+        TodosArgs {
+            execution_id: execution_id.to_string(),
+            action: action.to_string(),
+            items: Some(items.iter().map(|s| s.to_string()).collect()),
+            item_index: None,
+            status: None,
+            result: None,
+            new_description: None,
+            replan_trigger: None,
+            replan_reason: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_step_times_out_and_triggers_replan() {
+        let execution_id = "test_step_timeout_replan";
+        let todos = TodosTool::new();
+        todos
+            .call(todos_args(
+                execution_id,
+                "add_items",
+                &["full port scan", "write report"],
+            ))
+            .await
+            .unwrap();
+
+        let tools = wrap_tools(
+            vec![
+                sleeping_tool("port_scan", Duration::from_secs(30)),
+                sleeping_tool("fast_probe", Duration::from_millis(1)),
+            ],
+            Duration::from_millis(50),
+            execution_id,
+        );
+
+        let started = std::time::Instant::now();
+        let result = tools[0].call(json!({})).await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(result["success"], false);
+        assert_eq!(result["status"], "timed_out");
+        assert_eq!(result["tool_name"], "port_scan");
+
+        // Steps that finish in time are unaffected
+        assert_eq!(tools[1].call(json!({})).await.unwrap()["success"], true);
+
+        todos
+            .call(todos_args(
+                execution_id,
+                "replan",
+                &["scan top 100 ports", "write report"],
+            ))
+            .await
+            .unwrap();
+        let events = get_replan_events(execution_id).await;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].trigger, ReplanTrigger::Timeout);
+        assert_eq!(events[0].failed_steps, ["full port scan"]);
+
+        cleanup_execution_todos(execution_id).await;
+    }
+
+    #[tokio::test]
+    async fn test_orchestration_tools_are_exempt() {
+        let tools = wrap_tools(
+            vec![sleeping_tool("subagent_await", Duration::from_millis(100))],
+            Duration::from_millis(10),
+            "test_step_timeout_exempt",
+        );
+        assert_eq!(tools[0].call(json!({})).await.unwrap()["success"], true);
+    }
+}
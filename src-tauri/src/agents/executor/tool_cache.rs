@@ -0,0 +1,313 @@
+//! In-run tool result cache.
+//!
+//! 同一次执行中模型有时会以完全相同的参数重复调用工具（例如对同一主机重复端口扫描）。
+//! 内置工具默认按「工具名 + 规范化参数」缓存成功结果，重复调用直接返回；
+//! 有副作用或有状态的工具（shell、浏览器、记忆、子代理等）在排除列表中，不缓存。
+//! MCP/插件/工作流工具的副作用未知，也不缓存。缓存只在单个 execution_id 的生命周期内有效。
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use sentinel_tools::dynamic_tool::{DynamicTool, DynamicToolDef, ToolExecutor, ToolSource};
+
+/// 不缓存的内置工具：有副作用、依赖会话状态或结果随时间变化
+const UNCACHEABLE_TOOLS: &[&str] = &[
+    "shell",
+    "interactive_shell",
+    "http_request",
+    "local_time",
+    "todos",
+    "memory",
+    "skills",
+    "subagent_execute",
+    "subagent_await",
+    "subagent_channel",
+    "tenth_man_review",
+    "web_explorer",
+];
+
+/// 浏览器工具共享同一个浏览器会话，全部不缓存
+const UNCACHEABLE_PREFIXES: &[&str] = &["browser_"];
+
+/// 单次执行的工具结果缓存
+#[derive(Default)]
+pub struct ToolResultCache {
+    entries: Mutex<HashMap<String, Value>>,
+    hits: AtomicUsize,
+}
+
+impl ToolResultCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 工具是否可缓存：不在排除列表中的内置工具
+    pub fn is_cacheable(def: &DynamicToolDef) -> bool {
+        let name = def.name.as_str();
+        def.source == ToolSource::Builtin
+            && !UNCACHEABLE_TOOLS.contains(&name)
+            && !UNCACHEABLE_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+    }
+
+    pub fn get(&self, tool_name: &str, args: &Value) -> Option<Value> {
+        let key = cache_key(tool_name, args);
+        let cached = self
+            .entries
+            .lock()
+            .ok()
+            .and_then(|entries| entries.get(&key).cloned());
+        if cached.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        cached
+    }
+
+    pub fn insert(&self, tool_name: &str, args: &Value, result: Value) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(cache_key(tool_name, args), result);
+        }
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().map(|e| e.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 为可缓存工具包装执行器，其余工具原样返回
+pub fn wrap_tools(
+    tools: Vec<DynamicTool>,
+    cache: &Arc<ToolResultCache>,
+    execution_id: &str,
+) -> Vec<DynamicTool> {
+    tools
+        .into_iter()
+        .map(|tool| {
+            if !ToolResultCache::is_cacheable(tool.def()) {
+                return tool;
+            }
+            let mut def = tool.def().clone();
+            def.executor = cached_executor(
+                def.name.clone(),
+                def.executor.clone(),
+                cache.clone(),
+                execution_id.to_string(),
+            );
+            DynamicTool::new(def)
+        })
+        .collect()
+}
+
+fn cached_executor(
+    tool_name: String,
+    inner: ToolExecutor,
+    cache: Arc<ToolResultCache>,
+    execution_id: String,
+) -> ToolExecutor {
+    Arc::new(move |args: Value| {
+        let tool_name = tool_name.clone();
+        let inner = inner.clone();
+        let cache = cache.clone();
+        let execution_id = execution_id.clone();
+        Box::pin(async move {
+            if let Some(result) = cache.get(&tool_name, &args) {
+                tracing::info!(
+                    "Tool cache hit - execution_id: {}, tool: {}, total_hits: {}",
+                    execution_id,
+                    tool_name,
+                    cache.hits()
+                );
+                return Ok(result);
+            }
+
+            let result = inner(args.clone()).await?;
+            // 只缓存成功结果，失败的调用允许模型重试
+            if !reports_failure(&result) {
+                cache.insert(&tool_name, &args, result.clone());
+            }
+            Ok(result)
+        })
+    })
+}
+
+/// 工具以 `success: false` 报告的失败（执行器本身返回 Ok）
+fn reports_failure(result: &Value) -> bool {
+    result.get("success") == Some(&Value::Bool(false))
+}
+
+/// 缓存 key 直接使用「工具名 + 规范化参数」字符串，避免哈希碰撞返回错误结果
+fn cache_key(tool_name: &str, args: &Value) -> String {
+    format!("{}:{}", tool_name, normalize_args(args))
+}
+
+/// 规范化参数：对象键排序、忽略 null 字段，使等价参数得到相同的 key（字符串值原样保留）
+fn normalize_args(args: &Value) -> String {
+    match args {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map
+                .iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, _)| k)
+                .collect();
+            keys.sort();
+            let fields = keys
+                .into_iter()
+                .map(|k| format!("{:?}:{}", k, normalize_args(&map[k])))
+                .collect::<Vec<_>>();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => {
+            let items = items.iter().map(normalize_args).collect::<Vec<_>>();
+            format!("[{}]", items.join(","))
+        }
+        Value::String(s) => format!("{:?}", s),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rig::tool::Tool;
+    use sentinel_tools::dynamic_tool::SchemaValidation;
+    use serde_json::json;
+
+    fn counting_tool(name: &str, calls: Arc<AtomicUsize>) -> DynamicTool {
+        let executor: ToolExecutor = Arc::new(move |args: Value| {
+            let calls = calls.clone();
+            Box::pin(async move {
+                let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                let success = args.get("fail").is_none();
+                Ok(json!({ "success": success, "call": n, "args": args }))
+            })
+        });
+        DynamicTool::new(DynamicToolDef {
+            name: name.to_string(),
+            description: String::new(),
+            input_schema: json!({ "type": "object" }),
+            output_schema: None,
+            source: ToolSource::Builtin,
+            category: "test".to_string(),
+            schema_validation: SchemaValidation::default(),
+            executor,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_repeated_call_resolves_from_cache() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cache = Arc::new(ToolResultCache::new());
+        let tools = wrap_tools(
+            vec![counting_tool("port_scan", calls.clone())],
+            &cache,
+            "exec-1",
+        );
+
+        let first = tools[0]
+            .call(json!({ "target": "10.0.0.5", "ports": "1-1024" }))
+            .await
+            .unwrap();
+        // Same arguments in a different key order, with an explicit null
+        let second = tools[0]
+            .call(json!({ "ports": "1-1024", "target": "10.0.0.5", "threads": null }))
+            .await
+            .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first, second);
+        assert_eq!(cache.hits(), 1);
+
+        tools[0]
+            .call(json!({ "target": "10.0.0.6", "ports": "1-1024" }))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_results_are_not_cached() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cache = Arc::new(ToolResultCache::new());
+        let tools = wrap_tools(
+            vec![counting_tool("jwt_analyze", calls.clone())],
+            &cache,
+            "exec-3",
+        );
+
+        let args = json!({ "token": "not-a-jwt", "fail": true });
+        tools[0].call(args.clone()).await.unwrap();
+        tools[0].call(args).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(cache.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_opted_out_tools_are_not_cached() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cache = Arc::new(ToolResultCache::new());
+        let tools = wrap_tools(
+            vec![counting_tool("shell", calls.clone())],
+            &cache,
+            "exec-2",
+        );
+
+        let args = json!({ "command": "id" });
+        tools[0].call(args.clone()).await.unwrap();
+        tools[0].call(args).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(cache.is_empty());
+
+        // Stateful tools are opted out; everything else builtin is cached
+        for name in ["http_request", "memory", "todos", "browser_click"] {
+            let tool = counting_tool(name, calls.clone());
+            assert!(!ToolResultCache::is_cacheable(tool.def()), "{}", name);
+        }
+        for name in [
+            "port_scan",
+            "subdomain_brute",
+            "jwt_analyze",
+            "search_exploit",
+        ] {
+            let tool = counting_tool(name, calls.clone());
+            assert!(ToolResultCache::is_cacheable(tool.def()), "{}", name);
+        }
+
+        // Side effects of external tools are unknown
+        let mut def = counting_tool("port_scan", calls.clone()).def().clone();
+        def.source = ToolSource::Mcp {
+            server_name: "scanner".to_string(),
+        };
+        assert!(!ToolResultCache::is_cacheable(&def));
+    }
+
+    #[test]
+    fn test_normalize_args_is_order_independent() {
+        assert_eq!(
+            normalize_args(&json!({ "a": 1, "b": ["x", { "d": true, "c": null }] })),
+            normalize_args(&json!({ "b": ["x", { "d": true }], "a": 1 }))
+        );
+        // Whitespace can be significant (payloads, paths)
+        assert_ne!(
+            normalize_args(&json!({ "a": " x " })),
+            normalize_args(&json!({ "a": "x" }))
+        );
+        assert_ne!(
+            normalize_args(&json!({ "a": 1 })),
+            normalize_args(&json!({ "a": "1" }))
+        );
+    }
+}
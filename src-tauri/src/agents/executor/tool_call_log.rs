@@ -0,0 +1,220 @@
+//! Agent 工具调用记录
+//!
+//! 每次工具调用完成后按 `execution_id` 持久化（参数脱敏、结果截断），供事后查看；
+//! 单条记录可以通过 `unified_execute_tool` 重新执行。
+
+use std::sync::{Arc, LazyLock};
+
+use sentinel_db::AgentToolCallRecord;
+use serde_json::Value;
+
+use crate::agents::executor::types::ToolCallRecord;
+use crate::commands::tool_commands::{unified_execute_tool, ToolExecutionResult};
+use crate::utils::log_redact::SecretRedactor;
+
+/// 结果摘要的最大字符数
+const RESULT_SUMMARY_MAX_CHARS: usize = 2000;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// 参数名（去掉 `-`/`_`、小写后）以这些词结尾时，其值整体脱敏
+const SENSITIVE_KEY_SUFFIXES: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "apikey",
+    "authorization",
+    "cookie",
+    "privatekey",
+];
+
+static REDACTOR: LazyLock<SecretRedactor> = LazyLock::new(SecretRedactor::default);
+
+fn is_sensitive_key(key: &str) -> bool {
+    let normalized: String = key
+        .chars()
+        .filter(|c| *c != '-' && *c != '_')
+        .collect::<String>()
+        .to_ascii_lowercase();
+    SENSITIVE_KEY_SUFFIXES
+        .iter()
+        .any(|suffix| normalized.ends_with(suffix))
+}
+
+/// 递归脱敏 JSON：敏感字段整体替换，其余字符串按密钥模式替换
+pub(crate) fn redact_value(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = REDACTOR.redact(s),
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_sensitive_key(key) && !value.is_null() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(value);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 脱敏工具参数：JSON 参数按字段处理，其它文本按密钥模式替换
+pub fn redact_arguments(arguments: &str) -> String {
+    match serde_json::from_str::<Value>(arguments) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            value.to_string()
+        }
+        Err(_) => REDACTOR.redact(arguments),
+    }
+}
+
+/// 转换为持久化记录
+pub fn to_persisted(execution_id: &str, record: &ToolCallRecord) -> AgentToolCallRecord {
+    AgentToolCallRecord {
+        execution_id: execution_id.to_string(),
+        tool_call_id: record.id.clone(),
+        tool_name: record.name.clone(),
+        arguments: redact_arguments(&record.arguments),
+        result_summary: record.result.as_ref().map(|result| {
+            let redacted = REDACTOR.redact(result);
+            if redacted.chars().count() > RESULT_SUMMARY_MAX_CHARS {
+                let truncated: String = redacted.chars().take(RESULT_SUMMARY_MAX_CHARS).collect();
+                format!("{}...[truncated]", truncated)
+            } else {
+                redacted
+            }
+        }),
+        success: record.success,
+        sequence: record.sequence as i64,
+        started_at_ms: record.started_at_ms,
+        duration_ms: record.duration_ms,
+    }
+}
+
+/// 写入数据库，失败只记录日志
+pub async fn persist(db: Arc<sentinel_db::DatabaseService>, record: AgentToolCallRecord) {
+    if let Err(e) = db.save_agent_tool_call(&record).await {
+        tracing::warn!(
+            "Failed to persist tool call {} of execution {}: {}",
+            record.tool_call_id,
+            record.execution_id,
+            e
+        );
+    }
+}
+
+/// 重新执行一条记录的工具调用
+///
+/// 记录中的参数含脱敏占位符时必须通过 `arguments_override` 提供完整参数。
+pub async fn replay(
+    record: &AgentToolCallRecord,
+    arguments_override: Option<Value>,
+) -> Result<ToolExecutionResult, String> {
+    let arguments = match arguments_override {
+        Some(arguments) => arguments,
+        None => {
+            if record.arguments.contains(REDACTED) {
+                return Err(format!(
+                    "Recorded arguments of tool call {} contain redacted values; provide the arguments to replay it",
+                    record.tool_call_id
+                ));
+            }
+            serde_json::from_str(&record.arguments)
+                .map_err(|e| format!("Recorded arguments are not valid JSON: {}", e))?
+        }
+    };
+
+    tracing::info!(
+        "Replaying tool call {} ({}) of execution {}",
+        record.tool_call_id,
+        record.tool_name,
+        record.execution_id
+    );
+    unified_execute_tool(record.tool_name.clone(), arguments, None, None).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(id: &str, name: &str, arguments: Value, sequence: u32) -> ToolCallRecord {
+        ToolCallRecord {
+            id: id.to_string(),
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            result: Some("ok".to_string()),
+            success: true,
+            sequence,
+            started_at_ms: 1_000,
+            completed_at_ms: 1_050,
+            duration_ms: 50,
+        }
+    }
+
+    #[test]
+    fn test_arguments_are_redacted() {
+        let record = call(
+            "call-1",
+            "http_request",
+            serde_json::json!({
+                "url": "https://example.com/login?api_key=abcdef123456",
+                "headers": { "Authorization": "Bearer abc.def.ghi", "X-Trace": "1" },
+                "body": "{\"user\":\"bob\"}",
+                "password": "hunter2",
+                "max_tokens": 5
+            }),
+            0,
+        );
+
+        let persisted = to_persisted("exec-1", &record);
+        let args: Value = serde_json::from_str(&persisted.arguments).unwrap();
+        assert_eq!(args["password"], REDACTED);
+        assert_eq!(args["headers"]["Authorization"], REDACTED);
+        assert_eq!(args["headers"]["X-Trace"], "1");
+        assert_eq!(args["max_tokens"], 5);
+        assert!(!persisted.arguments.contains("abcdef123456"));
+        assert!(!persisted.arguments.contains("hunter2"));
+        assert_eq!(persisted.execution_id, "exec-1");
+        assert_eq!(persisted.duration_ms, 50);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_completed_run_tool_calls_are_replayable() {
+        // 一次已完成执行产生的工具调用记录
+        let run = [
+            call(
+                "call-1",
+                "local_time",
+                serde_json::json!({ "timezone": "utc" }),
+                0,
+            ),
+            call(
+                "call-2",
+                "http_request",
+                serde_json::json!({ "url": "https://example.com", "headers": { "Cookie": "sid=1" } }),
+                1,
+            ),
+        ];
+        let persisted: Vec<AgentToolCallRecord> =
+            run.iter().map(|r| to_persisted("exec-1", r)).collect();
+        assert_eq!(persisted[0].sequence, 0);
+        assert_eq!(persisted[1].tool_name, "http_request");
+
+        let replayed = replay(&persisted[0], None).await.unwrap();
+        assert!(replayed.success, "replay failed: {:?}", replayed.error);
+        assert!(replayed.output.is_some());
+
+        // 含脱敏参数的调用需要显式提供参数
+        assert!(replay(&persisted[1], None).await.is_err());
+        let replayed = replay(
+            &persisted[0],
+            Some(serde_json::json!({ "timezone": "local" })),
+        )
+        .await
+        .unwrap();
+        assert!(replayed.success);
+    }
+}
@@ -3,6 +3,7 @@
 pub mod agent_builder;
 pub mod context_engineering;
 pub mod executor;
+pub mod prompt_wrap;
 pub mod sliding_window;
 pub mod subagent_executor;
 pub mod tenth_man;
@@ -25,6 +26,7 @@ pub use context_engineering::{
     ToolDigestEntry,
 };
 pub use executor::{execute_agent, AgentExecuteParams};
+pub use prompt_wrap::SystemPromptWrap;
 pub use tool_router::{
     SelectedSkill, ToolConfig, ToolRouter, ToolSelectionPlan, ToolSelectionStrategy,
 };
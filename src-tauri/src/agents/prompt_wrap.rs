@@ -0,0 +1,104 @@
+//! 全局系统提示词前缀/后缀
+//!
+//! 前缀（prelude）与后缀（postlude）保存在 `ai` 配置分类下，对所有 Agent 运行统一生效。
+//! 最终系统提示词的顺序固定为：前缀 → 原始系统提示词 → 后缀，各部分之间以空行分隔，
+//! 空白内容会被忽略。
+
+use sentinel_db::Database;
+use serde::{Deserialize, Serialize};
+
+/// 前缀配置键
+pub const SYSTEM_PROMPT_PRELUDE_KEY: &str = "system_prompt_prelude";
+/// 后缀配置键
+pub const SYSTEM_PROMPT_POSTLUDE_KEY: &str = "system_prompt_postlude";
+
+/// 系统提示词前后缀
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemPromptWrap {
+    /// 追加在系统提示词之前的内容（如全局安全/范围约束）
+    pub prelude: Option<String>,
+    /// 追加在系统提示词之后的内容（如输出格式提醒）
+    pub postlude: Option<String>,
+}
+
+impl SystemPromptWrap {
+    pub fn new(prelude: Option<String>, postlude: Option<String>) -> Self {
+        let normalize =
+            |s: Option<String>| s.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        Self {
+            prelude: normalize(prelude),
+            postlude: normalize(postlude),
+        }
+    }
+
+    /// 从数据库读取前后缀配置
+    pub async fn load(db: &dyn Database) -> Self {
+        let prelude = db
+            .get_config("ai", SYSTEM_PROMPT_PRELUDE_KEY)
+            .await
+            .ok()
+            .flatten();
+        let postlude = db
+            .get_config("ai", SYSTEM_PROMPT_POSTLUDE_KEY)
+            .await
+            .ok()
+            .flatten();
+        Self::new(prelude, postlude)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prelude.is_none() && self.postlude.is_none()
+    }
+
+    /// 用前后缀包裹系统提示词
+    pub fn apply(&self, system_prompt: &str) -> String {
+        if self.is_empty() {
+            return system_prompt.to_string();
+        }
+        [
+            self.prelude.as_deref(),
+            Some(system_prompt.trim()),
+            self.postlude.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prelude_body_postlude_order() {
+        let wrap = SystemPromptWrap::new(
+            Some("Only test in-scope hosts.".to_string()),
+            Some("Answer in Markdown.".to_string()),
+        );
+        let prompt = wrap.apply("You are a security agent.");
+        assert_eq!(
+            prompt,
+            "Only test in-scope hosts.\n\nYou are a security agent.\n\nAnswer in Markdown."
+        );
+
+        let prelude = prompt.find("Only test").unwrap();
+        let body = prompt.find("You are").unwrap();
+        let postlude = prompt.find("Answer in").unwrap();
+        assert!(prelude < body && body < postlude);
+    }
+
+    #[test]
+    fn test_blank_parts_are_skipped() {
+        let wrap = SystemPromptWrap::new(Some("  ".to_string()), Some("Be brief.".to_string()));
+        assert_eq!(wrap.prelude, None);
+        assert_eq!(wrap.apply("Body"), "Body\n\nBe brief.");
+        assert_eq!(wrap.apply(""), "Be brief.");
+
+        let empty = SystemPromptWrap::default();
+        assert!(empty.is_empty());
+        assert_eq!(empty.apply("Body"), "Body");
+    }
+}
@@ -1,9 +1,10 @@
 use anyhow::Result;
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
-use std::collections::VecDeque;
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock, Mutex};
 use tauri::{AppHandle, Emitter, Manager};
 use tracing::info; // Removed warn
 
@@ -27,6 +28,9 @@ pub struct SlidingWindowConfig {
     pub global_summary_ratio: f64,
     /// Token allocation ratio for segment summaries
     pub segment_summary_ratio: f64,
+    /// Share of the recent window summarized per compression step (0.0-1.0).
+    /// Higher values keep fewer messages intact; 0.5 keeps half of `recent_message_count`.
+    pub compression_aggressiveness: f64,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -43,14 +47,150 @@ impl Default for SlidingWindowConfig {
             recent_message_count: 20,
             max_segment_summaries: 10,
             max_context_tokens: 128000,
-            global_summary_ratio: 0.08, // 8% for global summary
+            global_summary_ratio: 0.08,  // 8% for global summary
             segment_summary_ratio: 0.15, // 15% for segment summaries
-                                        // Remaining ~77% for history, but we reserve 30% for system prompt + tools
-                                        // So effective history budget is ~47% of max_context_tokens
+            // Remaining ~77% for history, but we reserve 30% for system prompt + tools
+            // So effective history budget is ~47% of max_context_tokens
+            compression_aggressiveness: DEFAULT_COMPRESSION_AGGRESSIVENESS,
         }
     }
 }
 
+impl SlidingWindowConfig {
+    /// Number of recent messages kept intact when a segment is created
+    fn keep_count(&self) -> usize {
+        let aggressiveness = self.compression_aggressiveness.clamp(0.0, 1.0);
+        let keep = (self.recent_message_count as f64 * (1.0 - aggressiveness)).round() as usize;
+        keep.max(1)
+    }
+}
+
+/// AI config key for compression aggressiveness
+pub const COMPRESSION_AGGRESSIVENESS_KEY: &str = "context_compression_aggressiveness";
+const DEFAULT_COMPRESSION_AGGRESSIVENESS: f64 = 0.5;
+
+/// Load the configured compression aggressiveness, falling back to the default
+pub async fn load_compression_aggressiveness(app_handle: &AppHandle) -> f64 {
+    let Some(db) = app_handle.try_state::<Arc<dyn Database>>() else {
+        return DEFAULT_COMPRESSION_AGGRESSIVENESS;
+    };
+    db.get_config("ai", COMPRESSION_AGGRESSIVENESS_KEY)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|v| v.is_finite())
+        .map(|v| v.clamp(0.0, 1.0))
+        .unwrap_or(DEFAULT_COMPRESSION_AGGRESSIVENESS)
+}
+
+/// What a compression step folded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionKind {
+    /// Recent messages summarized into a segment
+    Segment,
+    /// Segment summaries merged into the global summary
+    GlobalMerge,
+}
+
+/// Telemetry for a single compression step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompressionStep {
+    pub step: u32,
+    pub kind: CompressionKind,
+    pub original_tokens: usize,
+    pub compressed_tokens: usize,
+    pub items_summarized: usize,
+    /// compressed_tokens / original_tokens (0 when nothing was compressed)
+    pub ratio: f64,
+    pub created_at: i64,
+}
+
+/// Accumulated compression telemetry for a run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompressionStats {
+    pub conversation_id: String,
+    pub steps: Vec<CompressionStep>,
+    pub total_original_tokens: usize,
+    pub total_compressed_tokens: usize,
+    pub overall_ratio: f64,
+}
+
+impl CompressionStats {
+    fn record(
+        &mut self,
+        kind: CompressionKind,
+        original_tokens: usize,
+        compressed_tokens: usize,
+        items_summarized: usize,
+    ) -> CompressionStep {
+        let step = CompressionStep {
+            step: self.steps.len() as u32 + 1,
+            kind,
+            original_tokens,
+            compressed_tokens,
+            items_summarized,
+            ratio: compression_ratio(original_tokens, compressed_tokens),
+            created_at: Utc::now().timestamp(),
+        };
+        self.total_original_tokens += original_tokens;
+        self.total_compressed_tokens += compressed_tokens;
+        self.overall_ratio =
+            compression_ratio(self.total_original_tokens, self.total_compressed_tokens);
+        self.steps.push(step.clone());
+        step
+    }
+}
+
+/// Ratio of compressed to original tokens
+pub fn compression_ratio(original_tokens: usize, compressed_tokens: usize) -> f64 {
+    if original_tokens == 0 {
+        return 0.0;
+    }
+    compressed_tokens as f64 / original_tokens as f64
+}
+
+static COMPRESSION_STATS: LazyLock<Mutex<HashMap<String, CompressionStats>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn record_compression_step(
+    conversation_id: &str,
+    kind: CompressionKind,
+    original_tokens: usize,
+    compressed_tokens: usize,
+    items_summarized: usize,
+) -> CompressionStep {
+    let mut stats = COMPRESSION_STATS.lock().unwrap_or_else(|e| e.into_inner());
+    stats
+        .entry(conversation_id.to_string())
+        .or_insert_with(|| CompressionStats {
+            conversation_id: conversation_id.to_string(),
+            ..Default::default()
+        })
+        .record(kind, original_tokens, compressed_tokens, items_summarized)
+}
+
+/// Compression telemetry recorded for a run in this process
+pub fn get_compression_stats(conversation_id: &str) -> Option<CompressionStats> {
+    COMPRESSION_STATS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(conversation_id)
+        .cloned()
+}
+
+fn message_tokens(message: &ChatMessage) -> usize {
+    let mut tokens = estimate_tokens(&message.content);
+    if let Some(ref tc) = message.tool_calls {
+        tokens += estimate_tokens(tc);
+    }
+    if let Some(ref rc) = message.reasoning_content {
+        tokens += estimate_tokens(rc);
+    }
+    tokens
+}
+
 // 从 sentinel-core 导入模型定义
 pub use sentinel_core::models::database::{ConversationSegment, GlobalSummary};
 
@@ -224,20 +364,7 @@ impl SlidingWindowManager {
     /// Returns true if compression occurred
     pub async fn compress_if_needed(&mut self, llm_config: &LlmConfig) -> Result<bool> {
         // Calculate tokens for all message components
-        let recent_tokens: usize = self
-            .recent_messages
-            .iter()
-            .map(|m| {
-                let mut tokens = estimate_tokens(&m.content);
-                if let Some(ref tc) = m.tool_calls {
-                    tokens += estimate_tokens(tc);
-                }
-                if let Some(ref rc) = m.reasoning_content {
-                    tokens += estimate_tokens(rc);
-                }
-                tokens
-            })
-            .sum();
+        let recent_tokens: usize = self.recent_messages.iter().map(message_tokens).sum();
 
         // Align with builder safe limit ratio and reserve summary allocations
         let history_ratio = SAFE_CONTEXT_RATIO
@@ -276,8 +403,8 @@ impl SlidingWindowManager {
         }
 
         // Determine how many messages to summarize
-        // We keep the most recent 'recent_message_count' / 2 messages to maintain context continuity
-        let mut keep_count = (self.config.recent_message_count / 2).min(self.recent_messages.len());
+        // Keep the most recent messages (scaled by compression aggressiveness) to maintain context continuity
+        let mut keep_count = self.config.keep_count().min(self.recent_messages.len());
         keep_count = self.adjust_keep_count_for_tool_boundaries(keep_count);
         if self.recent_messages.len() <= keep_count {
             return Ok(());
@@ -353,6 +480,15 @@ impl SlidingWindowManager {
             }),
         );
 
+        let original_tokens = messages_to_summarize.iter().map(message_tokens).sum();
+        self.emit_compression_step(record_compression_step(
+            &self.conversation_id,
+            CompressionKind::Segment,
+            original_tokens,
+            summary_tokens.max(0) as usize,
+            messages_to_summarize.len(),
+        ));
+
         Ok(())
     }
 
@@ -377,6 +513,15 @@ impl SlidingWindowManager {
         }
 
         let new_covers_up_to = segments_to_merge.last().unwrap().end_message_index;
+        let original_tokens: usize = segments_to_merge
+            .iter()
+            .map(|s| s.summary_tokens.max(0) as usize)
+            .sum::<usize>()
+            + self
+                .global_summary
+                .as_ref()
+                .map(|g| g.summary_tokens.max(0) as usize)
+                .unwrap_or(0);
 
         // Prepare prompt
         let mut prompt = String::new();
@@ -434,9 +579,37 @@ impl SlidingWindowManager {
             }),
         );
 
+        self.emit_compression_step(record_compression_step(
+            &self.conversation_id,
+            CompressionKind::GlobalMerge,
+            original_tokens,
+            new_summary_tokens.max(0) as usize,
+            segments_to_merge.len(),
+        ));
+
         Ok(())
     }
 
+    fn emit_compression_step(&self, step: CompressionStep) {
+        info!(
+            "Context compression step #{} ({:?}): {} -> {} tokens, {} items, ratio {:.2}",
+            step.step,
+            step.kind,
+            step.original_tokens,
+            step.compressed_tokens,
+            step.items_summarized,
+            step.ratio
+        );
+
+        let _ = self.app_handle.emit(
+            "agent:context_compression",
+            &json!({
+                "conversation_id": self.conversation_id,
+                "step": step,
+            }),
+        );
+    }
+
     async fn generate_summary(
         &self,
         messages: &[ChatMessage],
@@ -608,3 +781,44 @@ fn trim_text(text: &str, max_lines: usize, max_chars: usize) -> String {
 
     lines
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_ratio_matches_before_after() {
+        let id = "test-compression-ratio";
+        let step = record_compression_step(id, CompressionKind::Segment, 4_000, 1_000, 12);
+        assert_eq!(step.step, 1);
+        assert_eq!(step.items_summarized, 12);
+        assert!((step.ratio - 0.25).abs() < f64::EPSILON);
+
+        record_compression_step(id, CompressionKind::GlobalMerge, 2_000, 1_000, 3);
+        let stats = get_compression_stats(id).unwrap();
+        assert_eq!(stats.steps.len(), 2);
+        assert_eq!(stats.steps[1].kind, CompressionKind::GlobalMerge);
+        assert!((stats.steps[1].ratio - 0.5).abs() < f64::EPSILON);
+        assert_eq!(stats.total_original_tokens, 6_000);
+        assert_eq!(stats.total_compressed_tokens, 2_000);
+        assert!((stats.overall_ratio - 2_000.0 / 6_000.0).abs() < f64::EPSILON);
+
+        assert_eq!(compression_ratio(0, 10), 0.0);
+        assert!(get_compression_stats("test-compression-missing").is_none());
+    }
+
+    #[test]
+    fn test_aggressiveness_scales_keep_count() {
+        let config = |compression_aggressiveness| SlidingWindowConfig {
+            recent_message_count: 20,
+            compression_aggressiveness,
+            ..Default::default()
+        };
+        assert_eq!(SlidingWindowConfig::default().keep_count(), 10);
+        assert_eq!(config(0.8).keep_count(), 4);
+        assert_eq!(config(0.0).keep_count(), 20);
+        // Always keep at least one message
+        assert_eq!(config(1.0).keep_count(), 1);
+        assert_eq!(config(7.0).keep_count(), 1);
+    }
+}
@@ -63,9 +63,18 @@ static GLOBAL_SEMAPHORE: Lazy<Arc<Semaphore>> = Lazy::new(|| Arc::new(Semaphore:
 static PARENT_SEMAPHORES: Lazy<Arc<RwLock<HashMap<String, Arc<Semaphore>>>>> =
     Lazy::new(|| Arc::new(RwLock::new(HashMap::new())));
 
+/// Number of subagents spawned so far (keyed by root execution_id)
+static SPAWN_COUNTS: Lazy<Arc<RwLock<HashMap<String, usize>>>> =
+    Lazy::new(|| Arc::new(RwLock::new(HashMap::new())));
+
+/// Root execution_id of every running subagent (keyed by subagent task_id)
+static SUBAGENT_ROOTS: Lazy<Arc<RwLock<HashMap<String, String>>>> =
+    Lazy::new(|| Arc::new(RwLock::new(HashMap::new())));
+
 const MAX_SUBAGENTS_PER_PARENT: usize = 3;
 const MAX_EVENTS_PER_CHANNEL: usize = 500;
-const MAX_SUBAGENT_RECURSION_DEPTH: usize = 4;
+const DEFAULT_MAX_SUBAGENT_RECURSION_DEPTH: usize = 4;
+const DEFAULT_MAX_SUBAGENTS_PER_RUN: usize = 20;
 const SUBAGENT_TOOL_IDS: [&str; 3] = ["subagent_execute", "subagent_await", "subagent_channel"];
 
 // ============================================================================
@@ -84,6 +93,37 @@ pub struct SubagentParentContext {
     pub timeout_secs: u64,
     pub task_context: String,
     pub recursion_depth: usize,
+    pub limits: SubagentLimits,
+    /// Top-level run this context belongs to; fan-out is counted against it
+    pub root_execution_id: String,
+}
+
+/// Ceilings on subagent nesting and fan-out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubagentLimits {
+    /// Deepest allowed subagent level (the top-level agent is depth 0)
+    pub max_recursion_depth: usize,
+    /// Total subagents a single run may spawn, concurrent or not
+    pub max_subagents_per_run: usize,
+}
+
+impl SubagentLimits {
+    pub fn new(max_recursion_depth: Option<usize>, max_subagents_per_run: Option<usize>) -> Self {
+        let defaults = Self::default();
+        Self {
+            max_recursion_depth: max_recursion_depth.unwrap_or(defaults.max_recursion_depth),
+            max_subagents_per_run: max_subagents_per_run.unwrap_or(defaults.max_subagents_per_run),
+        }
+    }
+}
+
+impl Default for SubagentLimits {
+    fn default() -> Self {
+        Self {
+            max_recursion_depth: DEFAULT_MAX_SUBAGENT_RECURSION_DEPTH,
+            max_subagents_per_run: DEFAULT_MAX_SUBAGENTS_PER_RUN,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -134,11 +174,23 @@ pub async fn set_parent_context(execution_id: String, context: SubagentParentCon
     contexts.insert(execution_id, context);
 }
 
+/// Top-level run an execution belongs to (itself unless it is a subagent)
+pub async fn root_execution_id(execution_id: &str) -> String {
+    SUBAGENT_ROOTS
+        .read()
+        .await
+        .get(execution_id)
+        .cloned()
+        .unwrap_or_else(|| execution_id.to_string())
+}
+
 pub async fn clear_parent_context(execution_id: &str) {
     {
         let mut contexts = PARENT_CONTEXTS.write().await;
         contexts.remove(execution_id);
     }
+    SPAWN_COUNTS.write().await.remove(execution_id);
+    SUBAGENT_ROOTS.write().await.remove(execution_id);
 
     // Abort all active tasks spawned by this parent
     abort_parent_tasks(execution_id).await;
@@ -193,6 +245,7 @@ fn default_subagent_tool_config() -> ToolConfig {
         fixed_tools: vec![],
         disabled_tools: vec![],
         allowed_tools: vec![],
+        step_timeout_secs: None,
     })
 }
 
@@ -350,6 +403,35 @@ fn get_app_handle() -> Result<&'static tauri::AppHandle, SubagentToolError> {
         .ok_or_else(|| SubagentToolError::InternalError("AppHandle not initialized".to_string()))
 }
 
+/// Depth of a child spawned by `parent`, or an error if it would exceed the limit
+fn child_recursion_depth(parent: &SubagentParentContext) -> Result<usize, SubagentToolError> {
+    let depth = parent.recursion_depth + 1;
+    if depth > parent.limits.max_recursion_depth {
+        return Err(SubagentToolError::InvalidArguments(format!(
+            "subagent recursion depth exceeded: {} (max {})",
+            depth, parent.limits.max_recursion_depth
+        )));
+    }
+    Ok(depth)
+}
+
+/// Count one more spawn against the root run, refusing once the limit is reached
+async fn reserve_spawn_slot(
+    root_id: &str,
+    limits: &SubagentLimits,
+) -> Result<usize, SubagentToolError> {
+    let mut counts = SPAWN_COUNTS.write().await;
+    let spawned = counts.entry(root_id.to_string()).or_insert(0);
+    if *spawned >= limits.max_subagents_per_run {
+        return Err(SubagentToolError::InvalidArguments(format!(
+            "subagent limit reached for this run: {} spawned (max {})",
+            spawned, limits.max_subagents_per_run
+        )));
+    }
+    *spawned += 1;
+    Ok(*spawned)
+}
+
 async fn get_parent_context(parent_id: &str) -> Result<SubagentParentContext, SubagentToolError> {
     let contexts = PARENT_CONTEXTS.read().await;
     contexts
@@ -755,9 +837,21 @@ async fn run_task(task_id: String) {
         subagent_run_id: Some(task_id.clone()),
         context_policy: Some(subagent_context_policy()),
         recursion_depth: pending_data.recursion_depth,
+        cost_limit_usd: None,
+        subagent_limits: pending_data.parent.limits,
     };
 
-    let result = execute_agent(&app_handle, params).await;
+    // 子任务作用域挂在父执行之下：停止父执行时子任务及其工具调用一并中断
+    let scope_token =
+        sentinel_tools::cancellation::link_child_scope(&parent_execution_id, &task_id).await;
+    let result = tokio::select! {
+        result = execute_agent(&app_handle, params) => result,
+        _ = scope_token.cancelled() => Err(anyhow::anyhow!(
+            "Subagent cancelled with parent execution {}",
+            parent_execution_id
+        )),
+    };
+    sentinel_tools::cancellation::clear_cancellation_scope(&task_id).await;
 
     match result {
         Ok(output) => {
@@ -854,15 +948,34 @@ async fn execute_spawn(args: SubagentSpawnArgs) -> Result<SubagentSpawnOutput, S
         }
     }
 
+    let refuse = |e: &SubagentToolError| {
+        tracing::warn!(
+            "Refusing subagent spawn - parent: {}, {}",
+            args.parent_execution_id,
+            e
+        )
+    };
+    let recursion_depth = child_recursion_depth(&parent).inspect_err(refuse)?;
+    // Nested subagents share the root run's budget, so fan-out can't multiply per level
+    let spawned = reserve_spawn_slot(&parent.root_execution_id, &parent.limits)
+        .await
+        .inspect_err(refuse)?;
+
     let task_id = uuid::Uuid::new_v4().to_string();
+    SUBAGENT_ROOTS
+        .write()
+        .await
+        .insert(task_id.clone(), parent.root_execution_id.clone());
     let now = chrono::Utc::now();
-    let recursion_depth = parent.recursion_depth + 1;
-    if recursion_depth > MAX_SUBAGENT_RECURSION_DEPTH {
-        return Err(SubagentToolError::InvalidArguments(format!(
-            "subagent recursion depth exceeded: {} (max {})",
-            recursion_depth, MAX_SUBAGENT_RECURSION_DEPTH
-        )));
-    }
+    tracing::info!(
+        "Spawning subagent - task_id: {}, parent: {}, depth: {}/{}, spawned: {}/{}",
+        task_id,
+        args.parent_execution_id,
+        recursion_depth,
+        parent.limits.max_recursion_depth,
+        spawned,
+        parent.limits.max_subagents_per_run
+    );
 
     let (tx, rx) = watch::channel(None);
 
@@ -1839,3 +1952,94 @@ pub fn init_subagent_executor() {
 
     tracing::info!("Subagent executors initialized (execute/await/channel)");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent_at_depth(recursion_depth: usize, limits: SubagentLimits) -> SubagentParentContext {
+        SubagentParentContext {
+            rig_provider: "openai".to_string(),
+            model: "gpt-4o".to_string(),
+            api_key: None,
+            api_base: None,
+            system_prompt: String::new(),
+            tool_config: default_subagent_tool_config(),
+            max_iterations: 10,
+            timeout_secs: 60,
+            task_context: String::new(),
+            recursion_depth,
+            limits,
+            root_execution_id: "root".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_depth_limit_refuses_spawn() {
+        let limits = SubagentLimits::new(Some(2), None);
+
+        assert_eq!(
+            child_recursion_depth(&parent_at_depth(0, limits)).unwrap(),
+            1
+        );
+        assert_eq!(
+            child_recursion_depth(&parent_at_depth(1, limits)).unwrap(),
+            2
+        );
+
+        let err = child_recursion_depth(&parent_at_depth(2, limits)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("recursion depth exceeded: 3 (max 2)"));
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_limit_refuses_spawn() {
+        let parent_id = format!("fan-out-test-{}", uuid::Uuid::new_v4());
+        let limits = SubagentLimits::new(None, Some(3));
+
+        for expected in 1..=3 {
+            assert_eq!(
+                reserve_spawn_slot(&parent_id, &limits).await.unwrap(),
+                expected
+            );
+        }
+        let err = reserve_spawn_slot(&parent_id, &limits).await.unwrap_err();
+        assert!(err.to_string().contains("3 spawned (max 3)"));
+
+        // Limits are per run: another parent is unaffected, and ending the run resets the count
+        let other_id = format!("fan-out-test-{}", uuid::Uuid::new_v4());
+        assert!(reserve_spawn_slot(&other_id, &limits).await.is_ok());
+        clear_parent_context(&parent_id).await;
+        assert!(reserve_spawn_slot(&parent_id, &limits).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_limit_counts_nested_spawns_against_root() {
+        let root_id = format!("fan-out-root-{}", uuid::Uuid::new_v4());
+        let child_id = format!("fan-out-child-{}", uuid::Uuid::new_v4());
+        let limits = SubagentLimits::new(None, Some(2));
+        SUBAGENT_ROOTS
+            .write()
+            .await
+            .insert(child_id.clone(), root_id.clone());
+
+        assert_eq!(root_execution_id(&root_id).await, root_id);
+        assert_eq!(root_execution_id(&child_id).await, root_id);
+
+        // One spawn from the root, one from its subagent: the shared budget is used up
+        let from_root = root_execution_id(&root_id).await;
+        let from_child = root_execution_id(&child_id).await;
+        assert_eq!(reserve_spawn_slot(&from_root, &limits).await.unwrap(), 1);
+        assert_eq!(reserve_spawn_slot(&from_child, &limits).await.unwrap(), 2);
+        assert!(reserve_spawn_slot(&from_child, &limits).await.is_err());
+
+        // The subagent finishing doesn't reset the root's count; the root finishing does
+        clear_parent_context(&child_id).await;
+        assert_eq!(root_execution_id(&child_id).await, child_id);
+        assert!(reserve_spawn_slot(&root_id, &limits).await.is_err());
+        clear_parent_context(&root_id).await;
+        assert!(reserve_spawn_slot(&root_id, &limits).await.is_ok());
+        clear_parent_context(&root_id).await;
+    }
+}
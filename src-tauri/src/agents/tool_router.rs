@@ -143,6 +143,9 @@ pub struct ToolConfig {
     pub allowed_tools: Vec<String>,
     /// 是否启用工具调用
     pub enabled: bool,
+    /// 单个工具调用的超时（秒），与整体执行超时相互独立；None 表示不限制
+    #[serde(default)]
+    pub step_timeout_secs: Option<u64>,
 }
 
 /// 工具统计信息
@@ -201,6 +204,7 @@ impl Default for ToolConfig {
             disabled_tools: vec![],
             allowed_tools: vec![],
             enabled: false, // 默认关闭，避免意外消耗
+            step_timeout_secs: None,
         }
     }
 }
@@ -2321,6 +2325,7 @@ mod tests {
             fixed_tools: vec![],
             disabled_tools: vec![],
             allowed_tools: vec![],
+            step_timeout_secs: None,
         };
 
         // 测试端口扫描任务
@@ -2352,6 +2357,7 @@ mod tests {
             fixed_tools: vec![],
             disabled_tools: vec![],
             allowed_tools: vec![],
+            step_timeout_secs: None,
         };
 
         let selected = router
@@ -2373,6 +2379,7 @@ mod tests {
             fixed_tools: vec![],
             disabled_tools: vec!["shell".to_string()],
             allowed_tools: vec![],
+            step_timeout_secs: None,
         };
 
         let selected = router
@@ -152,6 +152,17 @@ pub fn cancel_conversation_stream(conversation_id: &str) {
     }
 }
 
+/// 取消所有进行中的对话流（应用退出时调用）
+pub fn cancel_all_conversation_streams() -> usize {
+    let Ok(tokens) = CANCELLATION_TOKENS.lock() else {
+        return 0;
+    };
+    for (token, _) in tokens.values() {
+        token.cancel();
+    }
+    tokens.len()
+}
+
 /// 检查某个会话是否已被取消（用于停止后续事件发送）
 pub fn is_conversation_cancelled(conversation_id: &str) -> bool {
     get_cancellation_token(conversation_id)
@@ -555,11 +566,13 @@ async fn stream_chat_with_llm(
                     StreamContent::Usage {
                         input_tokens,
                         output_tokens,
+                        estimated,
                     } => {
                         tracing::info!(
-                            "Stream usage received: input={}, output={}",
+                            "Stream usage received: input={}, output={}, estimated={}",
                             input_tokens,
-                            output_tokens
+                            output_tokens,
+                            estimated
                         );
                         if let Ok(mut guard) = usage_data_clone.lock() {
                             *guard = Some((input_tokens, output_tokens));
@@ -823,6 +836,8 @@ pub async fn cancel_ai_stream(
     // Also cancel long-running tool executions (e.g. VisionExplorer) that use the global cancellation manager.
     // conversation_id is used as execution_id across the app.
     let _ = crate::managers::cancellation_manager::cancel_execution(&conversation_id).await;
+    sentinel_tools::cancellation::cancel_scope(&conversation_id).await;
+    crate::managers::resource_registry::cleanup_execution_resources(&conversation_id).await;
 
     // 发送取消事件通知前端
     let _ = app_handle.emit(
@@ -1395,6 +1410,84 @@ pub async fn get_ai_usage_stats(
     Ok(map)
 }
 
+/// 获取单次 Agent 执行的累计 token 用量与成本
+#[tauri::command]
+pub async fn get_agent_run_cost(
+    app_handle: AppHandle,
+    execution_id: String,
+) -> Result<Option<crate::agents::executor::cost::RunCost>, String> {
+    if let Some(cost) = crate::agents::executor::cost::get_run_cost(&execution_id) {
+        return Ok(Some(cost));
+    }
+
+    // 进程重启后从持久化的 run state 读取
+    let state = crate::agents::load_run_state(&app_handle, &execution_id)
+        .await
+        .map_err(|e| format!("Failed to load run state: {}", e))?;
+    Ok(state.and_then(|s| s.run_cost))
+}
+
+/// 列出单次执行仍在登记中的资源（浏览器、代理等）
+#[tauri::command]
+pub async fn list_execution_resources(
+    execution_id: String,
+) -> Result<Vec<crate::managers::resource_registry::TrackedResource>, String> {
+    Ok(crate::managers::resource_registry::list_resources(
+        &execution_id,
+    ))
+}
+
+/// 强制释放单次执行登记的全部资源，返回释放的数量
+#[tauri::command]
+pub async fn force_cleanup_execution_resources(execution_id: String) -> Result<usize, String> {
+    Ok(crate::managers::resource_registry::cleanup_execution_resources(&execution_id).await)
+}
+
+/// 获取单次 Agent 执行的上下文压缩统计
+#[tauri::command]
+pub async fn get_context_compression_stats(
+    conversation_id: String,
+) -> Result<Option<crate::agents::sliding_window::CompressionStats>, String> {
+    Ok(crate::agents::sliding_window::get_compression_stats(
+        &conversation_id,
+    ))
+}
+
+/// 获取单次 Agent 执行的重新规划记录（按时间顺序）
+#[tauri::command]
+pub async fn get_agent_replan_events(
+    execution_id: String,
+) -> Result<Vec<sentinel_tools::buildin_tools::todos::ReplanEvent>, String> {
+    Ok(sentinel_tools::buildin_tools::todos::get_replan_events(&execution_id).await)
+}
+
+/// 获取单次 Agent 执行的工具调用记录（按调用顺序）
+#[tauri::command]
+pub async fn get_agent_tool_calls(
+    db: State<'_, Arc<DatabaseService>>,
+    execution_id: String,
+) -> Result<Vec<sentinel_db::AgentToolCallRecord>, String> {
+    db.list_agent_tool_calls(&execution_id)
+        .await
+        .map_err(|e| format!("Failed to load tool calls: {}", e))
+}
+
+/// 重新执行一条记录的工具调用；参数已脱敏时需通过 `arguments` 提供完整参数
+#[tauri::command]
+pub async fn replay_agent_tool_call(
+    db: State<'_, Arc<DatabaseService>>,
+    execution_id: String,
+    tool_call_id: String,
+    arguments: Option<serde_json::Value>,
+) -> Result<tool_commands::ToolExecutionResult, String> {
+    let record = db
+        .get_agent_tool_call(&execution_id, &tool_call_id)
+        .await
+        .map_err(|e| format!("Failed to load tool call: {}", e))?
+        .ok_or_else(|| format!("Tool call {} not found", tool_call_id))?;
+    crate::agents::executor::tool_call_log::replay(&record, arguments).await
+}
+
 #[tauri::command]
 pub async fn get_detailed_ai_usage_stats(
     db: tauri::State<'_, Arc<DatabaseService>>,
@@ -2384,14 +2477,20 @@ pub async fn test_lm_studio_provider_connection(
     Err("LM Studio provider test disabled - ai_adapter removed, use Rig instead".to_string())
 }
 
-/// 上传图片文件并转换为 base64
+/// 上传图片文件并转换为 base64（按工作区设置校验大小与类型，必要时缩放）
 #[tauri::command]
-pub async fn upload_image_attachment(file_path: String) -> Result<serde_json::Value, String> {
+pub async fn upload_image_attachment(
+    app_handle: AppHandle,
+    file_path: String,
+) -> Result<serde_json::Value, String> {
     use crate::models::attachment::{load_image_from_path, MessageAttachment};
 
     tracing::info!("上传图片附件: {}", file_path);
 
-    match load_image_from_path(&file_path).await {
+    let policy = crate::commands::document_commands::load_workspace_settings(&app_handle)
+        .await
+        .image_upload_policy();
+    match load_image_from_path(&file_path, &policy).await {
         Ok(image_attachment) => {
             let attachment = MessageAttachment::Image(image_attachment);
             serde_json::to_value(&attachment).map_err(|e| format!("序列化图片附件失败: {}", e))
@@ -2406,17 +2505,22 @@ pub async fn upload_image_attachment(file_path: String) -> Result<serde_json::Va
 /// 批量上传图片文件
 #[tauri::command]
 pub async fn upload_multiple_images(
+    app_handle: AppHandle,
     file_paths: Vec<String>,
 ) -> Result<Vec<serde_json::Value>, String> {
     use crate::models::attachment::{load_image_from_path, MessageAttachment};
 
     tracing::info!("批量上传 {} 个图片", file_paths.len());
 
+    let policy = crate::commands::document_commands::load_workspace_settings(&app_handle)
+        .await
+        .image_upload_policy();
+
     let mut attachments = Vec::new();
     let mut errors = Vec::new();
 
     for file_path in file_paths {
-        match load_image_from_path(&file_path).await {
+        match load_image_from_path(&file_path, &policy).await {
             Ok(image_attachment) => {
                 let attachment = MessageAttachment::Image(image_attachment);
                 if let Ok(value) = serde_json::to_value(&attachment) {
@@ -2478,6 +2582,24 @@ pub struct AgentExecuteConfig {
     pub enable_tenth_man_rule: Option<bool>,
     #[serde(default)]
     pub tenth_man_config: Option<crate::agents::tenth_man::TenthManConfig>,
+    /// 单次会话的成本上限（美元），超出后停止工具调用循环
+    #[serde(default)]
+    pub cost_limit_usd: Option<f64>,
+    /// 子代理最大嵌套深度（顶层代理为 0），默认 4
+    #[serde(default)]
+    pub max_recursion_depth: Option<usize>,
+    /// 单次执行最多派生的子代理数量，默认 20
+    #[serde(default)]
+    pub max_subagents_per_run: Option<usize>,
+}
+
+impl AgentExecuteConfig {
+    fn subagent_limits(&self) -> crate::agents::subagent_executor::SubagentLimits {
+        crate::agents::subagent_executor::SubagentLimits::new(
+            self.max_recursion_depth,
+            self.max_subagents_per_run,
+        )
+    }
 }
 
 /// Agent执行请求
@@ -2573,6 +2695,9 @@ pub async fn agent_execute(
         force_todos: None,
         enable_tenth_man_rule: None,
         tenth_man_config: None,
+        cost_limit_usd: None,
+        max_recursion_depth: None,
+        max_subagents_per_run: None,
     });
 
     let conversation_id = config
@@ -3080,6 +3205,8 @@ pub async fn agent_execute(
                     subagent_run_id: None,
                     context_policy: None,
                     recursion_depth: 0,
+                    cost_limit_usd: config.cost_limit_usd,
+                    subagent_limits: config.subagent_limits(),
                 };
 
                 // 调用工具支持的代理执行器
@@ -1,4 +1,5 @@
 use crate::services::ai::AiServiceManager;
+use crate::services::ai_manager::{check_model_availability, ModelAvailability};
 use crate::services::database::DatabaseService;
 use sentinel_core::global_proxy::create_client_with_proxy;
 use serde::{Deserialize, Serialize};
@@ -57,6 +58,9 @@ pub struct AiProviderConfig {
     pub organization: Option<String>,
     pub enabled: bool,
     pub default_model: String,
+    /// 默认模型被提供商下线时使用的备用模型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_model: Option<String>,
     pub models: Vec<serde_json::Value>,
     pub rig_provider: Option<String>,
     pub max_context_length: Option<u32>,
@@ -101,9 +105,31 @@ pub async fn get_provider_models(
 }
 
 /// Test AI provider connection
+///
+/// 连接成功且请求中带有模型时，还会校验该模型是否仍在提供商的模型列表中
 #[tauri::command]
 pub async fn test_ai_connection(
     request: TestConnectionRequest,
+) -> Result<TestConnectionResponse, String> {
+    let provider = request.provider.clone();
+    let model = request.model.clone().filter(|m| !m.trim().is_empty());
+    let mut response = test_provider_connection(request).await?;
+
+    if let (true, Some(model), Some(listed)) = (response.success, model, response.models.as_ref()) {
+        if !listed.is_empty() {
+            if let ModelAvailability::Unavailable { message, .. } =
+                check_model_availability(&provider, &model, listed, None)
+            {
+                response.success = false;
+                response.message = message;
+            }
+        }
+    }
+    Ok(response)
+}
+
+pub(crate) async fn test_provider_connection(
+    request: TestConnectionRequest,
 ) -> Result<TestConnectionResponse, String> {
     match request.provider.to_lowercase().as_str() {
         "openai" => test_openai_connection(request).await,
@@ -260,6 +286,7 @@ pub async fn add_custom_provider(
         organization: None,
         enabled: true,
         default_model: request.model_id.clone(),
+        fallback_model: None,
         models: vec![model_config],
         rig_provider: Some(request.compat_mode.clone()),
         max_context_length: Some(128000), // Default context length
@@ -115,13 +115,54 @@ pub async fn search_assets(
     asset_service: State<'_, AssetService>,
     query: String,
     asset_types: Option<Vec<AssetType>>,
+    tags: Option<Vec<String>>,
     limit: Option<u32>,
 ) -> Result<Vec<Asset>, String> {
     asset_service
-        .search_assets(&query, asset_types, limit)
+        .search_assets(&query, asset_types, tags, limit)
         .await
 }
 
+/// 为资产添加标签
+#[tauri::command]
+pub async fn add_asset_tags(
+    asset_service: State<'_, AssetService>,
+    asset_ids: Vec<String>,
+    tags: Vec<String>,
+) -> Result<usize, String> {
+    asset_service.add_asset_tags(&asset_ids, &tags).await
+}
+
+/// 移除资产标签
+#[tauri::command]
+pub async fn remove_asset_tags(
+    asset_service: State<'_, AssetService>,
+    asset_ids: Vec<String>,
+    tags: Vec<String>,
+) -> Result<usize, String> {
+    asset_service.remove_asset_tags(&asset_ids, &tags).await
+}
+
+/// 批量打标签
+#[tauri::command]
+pub async fn bulk_tag_assets(
+    asset_service: State<'_, AssetService>,
+    request: BulkTagAssetsRequest,
+) -> Result<BulkTagAssetsResult, String> {
+    asset_service.bulk_tag_assets(request).await
+}
+
+/// 按标签查询资产
+#[tauri::command]
+pub async fn list_assets_by_tag(
+    asset_service: State<'_, AssetService>,
+    tag: String,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<Asset>, String> {
+    asset_service.list_assets_by_tag(&tag, limit, offset).await
+}
+
 /// 获取相关资产
 #[tauri::command]
 pub async fn get_related_assets(
@@ -131,6 +172,19 @@ pub async fn get_related_assets(
     asset_service.get_related_assets(&asset_id).await
 }
 
+/// 导出资产关系图
+#[tauri::command]
+pub async fn export_asset_graph(
+    asset_service: State<'_, AssetService>,
+    root_id: String,
+    depth: Option<u32>,
+    format: Option<AssetGraphFormat>,
+) -> Result<String, String> {
+    asset_service
+        .export_asset_graph(&root_id, depth, format.unwrap_or_default())
+        .await
+}
+
 /// 验证资产
 #[tauri::command]
 pub async fn verify_asset(
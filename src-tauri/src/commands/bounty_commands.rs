@@ -392,7 +392,7 @@ pub async fn bounty_validate_scope(
 
     // Check out-of-scope first
     for scope in scopes.iter().filter(|s| s.scope_type == "out_of_scope") {
-        if target_matches(&scope.target, &scope.target_type, &target) {
+        if scope.matches(&target) {
             return Ok(ScopeValidation {
                 in_scope: false,
                 matched_scope: None,
@@ -407,7 +407,7 @@ pub async fn bounty_validate_scope(
 
     // Check in-scope
     for scope in scopes.iter().filter(|s| s.scope_type == "in_scope") {
-        if target_matches(&scope.target, &scope.target_type, &target) {
+        if scope.matches(&target) {
             return Ok(ScopeValidation {
                 in_scope: true,
                 matched_scope: Some(scope.clone()),
@@ -425,19 +425,6 @@ pub async fn bounty_validate_scope(
     })
 }
 
-/// Check if a target matches a scope pattern
-fn target_matches(scope_target: &str, target_type: &str, target: &str) -> bool {
-    match target_type {
-        "domain" => target == scope_target || target.ends_with(&format!(".{}", scope_target)),
-        "wildcard_domain" => {
-            let base = scope_target.trim_start_matches("*.");
-            target.ends_with(base)
-        }
-        "url" => target.starts_with(scope_target),
-        _ => target == scope_target,
-    }
-}
-
 // ============================================================================
 // Finding Request/Response Types
 // ============================================================================
@@ -1,6 +1,8 @@
 use crate::services::database::DatabaseService;
+use sentinel_core::auth_session::AuthSession;
 use sentinel_core::global_proxy::GlobalProxyConfig;
 use sentinel_db::Database;
+use sentinel_tools::buildin_tools::ip_enrichment::{set_ip_enrichment_config, IpEnrichmentConfig};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tauri::State;
@@ -95,15 +97,85 @@ pub async fn get_global_proxy_config(
             return Ok(cfg);
         }
     }
-    Ok(GlobalProxyConfig {
-        enabled: false,
-        scheme: None,
-        host: None,
-        port: None,
-        username: None,
-        password: None,
-        no_proxy: None,
-    })
+    Ok(GlobalProxyConfig::default())
+}
+
+// 设置 IP 富化数据源（先校验可用再保存到DB）
+#[tauri::command]
+pub async fn save_ip_enrichment_config(
+    cfg: IpEnrichmentConfig,
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<(), String> {
+    // 前端回传占位符时沿用已保存的 token
+    let cfg = cfg.with_stored_token(&load_ip_enrichment_config(&db).await);
+    set_ip_enrichment_config(&cfg)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let json = serde_json::to_string(&cfg).map_err(|e| e.to_string())?;
+    db.set_config(
+        "tools",
+        "ip_enrichment",
+        &json,
+        Some("IP ASN/geolocation enrichment data source"),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    tracing::info!("IP enrichment provider set to {:?}", cfg.provider);
+    Ok(())
+}
+
+// 读取 IP 富化数据源
+#[tauri::command]
+pub async fn get_ip_enrichment_config(
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<IpEnrichmentConfig, String> {
+    // 不向前端返回 token 明文
+    Ok(load_ip_enrichment_config(&db).await.masked())
+}
+
+async fn load_ip_enrichment_config(db: &DatabaseService) -> IpEnrichmentConfig {
+    if let Ok(Some(json)) = db.get_config("tools", "ip_enrichment").await {
+        if let Ok(cfg) = serde_json::from_str::<IpEnrichmentConfig>(&json) {
+            return cfg;
+        }
+    }
+    IpEnrichmentConfig::default()
+}
+
+// 设置认证会话（仅保存在内存中）
+// 传入 cookie_header 时按 domain 解析手动填写的 Cookie，合并到 session 中
+#[tauri::command]
+pub async fn set_auth_session(
+    session: Option<AuthSession>,
+    domain: Option<String>,
+    cookie_header: Option<String>,
+) -> Result<AuthSession, String> {
+    let mut session = session.unwrap_or_default();
+    if let Some(header) = cookie_header.filter(|h| !h.trim().is_empty()) {
+        let domain = domain
+            .filter(|d| !d.trim().is_empty())
+            .ok_or_else(|| "domain is required for cookie_header".to_string())?;
+        session.add_cookie_header(&domain, &header);
+    }
+    if session.cookies.is_empty() {
+        return Err("Auth session has no cookies".to_string());
+    }
+    sentinel_core::auth_session::set_auth_session(session.clone());
+    Ok(session)
+}
+
+// 读取当前认证会话
+#[tauri::command]
+pub async fn get_auth_session() -> Result<Option<AuthSession>, String> {
+    Ok(sentinel_core::auth_session::get_auth_session())
+}
+
+// 清除认证会话
+#[tauri::command]
+pub async fn clear_auth_session() -> Result<(), String> {
+    sentinel_core::auth_session::clear_auth_session();
+    Ok(())
 }
 
 // 获取配置
@@ -264,3 +336,32 @@ pub async fn set_language(lang: String, db: State<'_, Arc<DatabaseService>>) ->
         .await
         .map_err(|e| e.to_string())
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetLogLevelRequest {
+    /// 模块路径，例如 `sentinel_passive`
+    pub target: String,
+    /// trace/debug/info/warn/error/off
+    pub level: String,
+    /// 是否在重启后保留
+    #[serde(default)]
+    pub persist: bool,
+}
+
+// 运行时调整某个模块的日志级别（无需重启）
+#[tauri::command]
+pub async fn set_log_level(request: SetLogLevelRequest) -> Result<(), String> {
+    crate::utils::log_filter::set_log_level(&request.target, &request.level, request.persist)
+}
+
+// 撤销日志级别覆盖，恢复默认
+#[tauri::command]
+pub async fn reset_log_level(target: String) -> Result<(), String> {
+    crate::utils::log_filter::reset_log_level(&target)
+}
+
+// 获取当前生效的日志级别覆盖
+#[tauri::command]
+pub async fn get_log_levels() -> Result<std::collections::BTreeMap<String, String>, String> {
+    Ok(crate::utils::log_filter::log_level_overrides())
+}
@@ -202,26 +202,29 @@ pub async fn test_database_connection(
     }
 }
 
-/// 创建数据库备份
+/// 创建数据库备份（提供密码时生成加密备份）
 #[tauri::command]
 pub async fn create_database_backup(
     backup_path: Option<String>,
+    passphrase: Option<String>,
     db_service: State<'_, Arc<DatabaseService>>,
 ) -> Result<String, String> {
     let path = backup_path.map(PathBuf::from);
+    let passphrase = passphrase.filter(|p| !p.is_empty());
 
     let result_path = db_service
-        .backup(path)
+        .backup(path, passphrase.as_deref())
         .await
         .map_err(|e| format!("创建备份失败: {}", e))?;
 
     Ok(result_path.to_string_lossy().to_string())
 }
 
-/// 恢复数据库备份
+/// 恢复数据库备份（加密备份需提供密码）
 #[tauri::command]
 pub async fn restore_database_backup(
     backup_path: String,
+    passphrase: Option<String>,
     db_service: State<'_, Arc<DatabaseService>>,
 ) -> Result<(), String> {
     let path = PathBuf::from(backup_path);
@@ -231,7 +234,7 @@ pub async fn restore_database_backup(
     }
 
     db_service
-        .restore(path)
+        .restore(path, passphrase.as_deref())
         .await
         .map_err(|e| format!("恢复备份失败: {}", e))
 }
@@ -585,6 +588,9 @@ pub async fn get_database_statistics(
         .await
         .unwrap_or_default();
 
+    // 探测一次连接池，同时返回连接数与获取等待时间
+    let pool = db_service.check_pool_health().await;
+
     Ok(serde_json::json!({
         "scan_tasks_count": stats.scan_tasks_count,
         "vulnerabilities_count": stats.vulnerabilities_count,
@@ -593,7 +599,8 @@ pub async fn get_database_statistics(
         "db_size_bytes": stats.db_size_bytes,
         "db_size_formatted": format_file_size(stats.db_size_bytes),
         "tables": table_info,
-        "last_backup": stats.last_backup
+        "last_backup": stats.last_backup,
+        "pool": pool
     }))
 }
 
@@ -610,7 +617,7 @@ pub async fn reset_database(
 
     // 首先创建备份
     let backup_path = db_service
-        .backup(None)
+        .backup(None, None)
         .await
         .map_err(|e| format!("创建备份失败: {}", e))?;
 
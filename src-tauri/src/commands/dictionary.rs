@@ -6,7 +6,8 @@ use crate::services::DatabaseService;
 use crate::services::DictionaryService;
 use sentinel_core::models::dictionary::{
     Dictionary, DictionaryExport, DictionaryFilter, DictionaryImportOptions, DictionarySet,
-    DictionaryStats, DictionaryType, DictionaryWord, ServiceType,
+    DictionaryStats, DictionaryType, DictionaryWord, PermutationResult, PermutationRules,
+    ServiceType, WordOrder,
 };
 use sentinel_db::Database;
 use std::collections::HashMap;
@@ -151,6 +152,7 @@ pub async fn get_dictionary_words_paged(
     offset: Option<u32>,
     limit: Option<u32>,
     pattern: Option<String>,
+    order: Option<WordOrder>,
 ) -> Result<Vec<DictionaryWord>, String> {
     let pool = db_service.get_runtime_pool().map_err(|e| e.to_string())?;
     let dictionary_service = DictionaryService::new(pool.clone());
@@ -166,7 +168,7 @@ pub async fn get_dictionary_words_paged(
             .map_err(|e| e.to_string());
     }
     dictionary_service
-        .get_dictionary_words_paged(&dictionary_id, off, lim)
+        .get_dictionary_words_paged(&dictionary_id, off, lim, order.unwrap_or_default())
         .await
         .map_err(|e| e.to_string())
 }
@@ -203,6 +205,87 @@ pub async fn remove_dictionary_words(
         .map_err(|e| e.to_string())
 }
 
+/// 记录词条命中，提升权重以便下次优先尝试
+#[tauri::command(rename_all = "snake_case")]
+pub async fn record_dictionary_word_hits(
+    db_service: State<'_, Arc<DatabaseService>>,
+    dictionary_id: String,
+    words: Vec<String>,
+    increment: Option<f64>,
+) -> Result<u64, String> {
+    let pool = db_service.get_runtime_pool().map_err(|e| e.to_string())?;
+    let dictionary_service = DictionaryService::new(pool.clone());
+
+    dictionary_service
+        .record_word_hits(&dictionary_id, &words, increment.unwrap_or(1.0))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 设置词条权重
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_dictionary_word_weight(
+    db_service: State<'_, Arc<DatabaseService>>,
+    dictionary_id: String,
+    word: String,
+    weight: f64,
+) -> Result<u64, String> {
+    let pool = db_service.get_runtime_pool().map_err(|e| e.to_string())?;
+    let dictionary_service = DictionaryService::new(pool.clone());
+
+    dictionary_service
+        .set_word_weight(&dictionary_id, &word, weight)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 根据已知子域名生成变异候选，可选合并到目标字典
+///
+/// 合并时写入剥离根域名后的子域名部分，与子域名字典的词条格式一致。
+#[tauri::command(rename_all = "snake_case")]
+pub async fn generate_subdomain_permutations(
+    db_service: State<'_, Arc<DatabaseService>>,
+    base_domains: Vec<String>,
+    rules: Option<PermutationRules>,
+    target_dictionary_id: Option<String>,
+) -> Result<PermutationResult, String> {
+    let rules = rules.unwrap_or_default();
+    let mut result = DictionaryService::generate_permutations(&base_domains, &rules);
+    tracing::info!(
+        "Generated {} subdomain permutations from {} domains (truncated: {})",
+        result.count,
+        base_domains.len(),
+        result.truncated
+    );
+
+    if let Some(dictionary_id) = target_dictionary_id {
+        let pool = db_service.get_runtime_pool().map_err(|e| e.to_string())?;
+        let dictionary_service = DictionaryService::new(pool.clone());
+
+        let suffix = rules
+            .root_domain
+            .as_deref()
+            .map(|r| format!(".{}", r.trim().trim_matches('.').to_lowercase()));
+        let words: Vec<String> = result
+            .candidates
+            .iter()
+            .map(|c| match &suffix {
+                Some(suffix) => c.strip_suffix(suffix.as_str()).unwrap_or(c).to_string(),
+                None => c.clone(),
+            })
+            .collect();
+
+        result.merged_count = Some(
+            dictionary_service
+                .merge_words(&dictionary_id, words)
+                .await
+                .map_err(|e| e.to_string())?,
+        );
+    }
+
+    Ok(result)
+}
+
 /// 搜索字典词条
 #[tauri::command(rename_all = "snake_case")]
 pub async fn search_dictionary_words(
@@ -1,6 +1,6 @@
 //! Unified file upload and attachment commands.
 
-use crate::models::attachment::DocumentAttachment;
+use crate::models::attachment::{DocumentAttachment, ImageUploadPolicy};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
@@ -18,6 +18,10 @@ const DEFAULT_MAX_FILE_MB: u64 = 20;
 const DEFAULT_MAX_TOTAL_MB: u64 = 1024;
 const DEFAULT_MAX_FILES_PER_CONVERSATION: usize = 100;
 const DEFAULT_RETENTION_DAYS: i64 = 30;
+const DEFAULT_MAX_IMAGE_MB: u64 = 5;
+const DEFAULT_MAX_IMAGE_DIMENSION: u32 = 2048;
+const DEFAULT_ALLOWED_IMAGE_TYPES: &[&str] =
+    &["image/jpeg", "image/png", "image/gif", "image/webp"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DockerAnalysisStatus {
@@ -71,6 +75,38 @@ pub struct WorkspaceSettings {
     pub max_file_mb: u64,
     pub max_total_mb: u64,
     pub max_files_per_conversation: usize,
+    #[serde(default = "default_max_image_mb")]
+    pub max_image_mb: u64,
+    /// 图片超过该边长时自动缩小，0 表示不缩放
+    #[serde(default = "default_max_image_dimension")]
+    pub max_image_dimension: u32,
+    #[serde(default = "default_allowed_image_types")]
+    pub allowed_image_types: Vec<String>,
+}
+
+fn default_max_image_mb() -> u64 {
+    DEFAULT_MAX_IMAGE_MB
+}
+
+fn default_max_image_dimension() -> u32 {
+    DEFAULT_MAX_IMAGE_DIMENSION
+}
+
+fn default_allowed_image_types() -> Vec<String> {
+    DEFAULT_ALLOWED_IMAGE_TYPES
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+impl WorkspaceSettings {
+    pub fn image_upload_policy(&self) -> ImageUploadPolicy {
+        ImageUploadPolicy {
+            max_bytes: self.max_image_mb * 1024 * 1024,
+            allowed_mime_types: self.allowed_image_types.clone(),
+            max_dimension: (self.max_image_dimension > 0).then_some(self.max_image_dimension),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -184,6 +220,9 @@ pub async fn load_workspace_settings(app_handle: &AppHandle) -> WorkspaceSetting
         max_file_mb: DEFAULT_MAX_FILE_MB,
         max_total_mb: DEFAULT_MAX_TOTAL_MB,
         max_files_per_conversation: DEFAULT_MAX_FILES_PER_CONVERSATION,
+        max_image_mb: DEFAULT_MAX_IMAGE_MB,
+        max_image_dimension: DEFAULT_MAX_IMAGE_DIMENSION,
+        allowed_image_types: default_allowed_image_types(),
     };
 
     let db_state = app_handle.try_state::<Arc<crate::services::database::DatabaseService>>();
@@ -220,6 +259,32 @@ pub async fn load_workspace_settings(app_handle: &AppHandle) -> WorkspaceSetting
             settings.max_files_per_conversation = n.max(1);
         }
     }
+    if let Ok(Some(v)) = db.get_config("agent", "workspace_max_image_mb").await {
+        if let Ok(n) = v.parse::<u64>() {
+            settings.max_image_mb = n.max(1);
+        }
+    }
+    if let Ok(Some(v)) = db
+        .get_config("agent", "workspace_max_image_dimension")
+        .await
+    {
+        if let Ok(n) = v.parse::<u32>() {
+            settings.max_image_dimension = n;
+        }
+    }
+    if let Ok(Some(v)) = db
+        .get_config("agent", "workspace_allowed_image_types")
+        .await
+    {
+        let types: Vec<String> = v
+            .split(',')
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+        if !types.is_empty() {
+            settings.allowed_image_types = types;
+        }
+    }
 
     settings
 }
@@ -282,6 +347,30 @@ pub async fn save_workspace_settings(
     )
     .await
     .map_err(|e| format!("Failed to save config: {}", e))?;
+    db.set_config(
+        "agent",
+        "workspace_max_image_mb",
+        &settings.max_image_mb.to_string(),
+        None,
+    )
+    .await
+    .map_err(|e| format!("Failed to save config: {}", e))?;
+    db.set_config(
+        "agent",
+        "workspace_max_image_dimension",
+        &settings.max_image_dimension.to_string(),
+        None,
+    )
+    .await
+    .map_err(|e| format!("Failed to save config: {}", e))?;
+    db.set_config(
+        "agent",
+        "workspace_allowed_image_types",
+        &settings.allowed_image_types.join(","),
+        None,
+    )
+    .await
+    .map_err(|e| format!("Failed to save config: {}", e))?;
 
     Ok(())
 }
@@ -485,6 +574,11 @@ pub async fn upload_document_attachment(
             file_size, settings.max_file_mb
         ));
     }
+    let source_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(&file_path);
+    DocumentAttachment::validate_upload(source_name, &bytes)?;
 
     let root = ensure_upload_root().await?;
     let mut index = read_upload_index(&root).await?;
@@ -0,0 +1,531 @@
+//! 漏洞知识库自动摄取
+//!
+//! 漏洞进入终态（reviewed / fixed）时，把标题、描述、证据摘录和修复建议写入专用 RAG 集合，
+//! 供助手检索。文档按 finding id 去重：再次摄取会先删除该漏洞的旧文档。
+
+use crate::commands::rag_commands::get_or_init_rag_service;
+use crate::services::database::DatabaseService;
+use sentinel_db::{
+    Database, TrafficEvidenceRecord, TrafficVulnerabilityFilters, TrafficVulnerabilityRecord,
+};
+use sentinel_rag::models::DocumentSource;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::State;
+use tracing::{info, warn};
+
+/// 漏洞知识集合名称
+pub const FINDINGS_COLLECTION_NAME: &str = "security_findings";
+const FINDINGS_COLLECTION_DESCRIPTION: &str =
+    "Reviewed and fixed traffic findings with evidence and remediation.";
+
+const FINDINGS_RAG_CATEGORY: &str = "rag";
+const FINDINGS_RAG_AUTO_INGEST_KEY: &str = "findings_auto_ingest";
+
+/// 触发摄取的终态
+pub const RAG_INGEST_STATUSES: [&str; 2] = ["reviewed", "fixed"];
+
+/// 每条证据摘录的最大字符数
+const EVIDENCE_EXCERPT_CHARS: usize = 800;
+/// 每个漏洞最多摄取的证据条数
+const MAX_EVIDENCE_ITEMS: usize = 3;
+
+/// 回填结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FindingsBackfillReport {
+    pub ingested: usize,
+    pub failed: usize,
+    pub errors: Vec<String>,
+}
+
+pub fn is_rag_ingest_status(status: &str) -> bool {
+    RAG_INGEST_STATUSES.contains(&status)
+}
+
+/// 文档标题后缀，用于按 finding id 定位已摄取的文档
+fn finding_document_suffix(finding_id: &str) -> String {
+    format!(" [finding:{}]", finding_id)
+}
+
+fn excerpt(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let cut: String = text.chars().take(max_chars).collect();
+        format!("{}...", cut)
+    }
+}
+
+/// 构建漏洞文档：返回（标题, 正文, 元数据）
+pub fn build_finding_document(
+    vuln: &TrafficVulnerabilityRecord,
+    evidence: &[TrafficEvidenceRecord],
+) -> (String, String, HashMap<String, String>) {
+    let title = format!("{}{}", vuln.title, finding_document_suffix(&vuln.id));
+
+    let mut content = format!(
+        "# {}\n\nType: {}\nSeverity: {}\nConfidence: {}\nStatus: {}\n",
+        vuln.title, vuln.vuln_type, vuln.severity, vuln.confidence, vuln.status
+    );
+    if let Some(cwe) = &vuln.cwe {
+        content.push_str(&format!("CWE: {}\n", cwe));
+    }
+    if let Some(owasp) = &vuln.owasp {
+        content.push_str(&format!("OWASP: {}\n", owasp));
+    }
+    content.push_str(&format!(
+        "\n## Description\n\n{}\n",
+        vuln.description.trim()
+    ));
+    if !evidence.is_empty() {
+        content.push_str("\n## Evidence\n");
+        for item in evidence.iter().take(MAX_EVIDENCE_ITEMS) {
+            content.push_str(&format!(
+                "\n{} {} ({})\n{}\n",
+                item.method,
+                item.url,
+                item.location,
+                excerpt(&item.evidence_snippet, EVIDENCE_EXCERPT_CHARS)
+            ));
+        }
+    }
+    if let Some(remediation) = vuln.remediation.as_deref().filter(|r| !r.trim().is_empty()) {
+        content.push_str(&format!("\n## Remediation\n\n{}\n", remediation.trim()));
+    }
+
+    let metadata = HashMap::from([
+        ("source".to_string(), "traffic_finding".to_string()),
+        ("finding_id".to_string(), vuln.id.clone()),
+        ("vuln_type".to_string(), vuln.vuln_type.clone()),
+        ("severity".to_string(), vuln.severity.clone()),
+        ("status".to_string(), vuln.status.clone()),
+    ]);
+
+    (title, content, metadata)
+}
+
+/// 集合中属于该漏洞的已有文档 id
+pub fn existing_finding_documents(documents: &[DocumentSource], finding_id: &str) -> Vec<String> {
+    let suffix = finding_document_suffix(finding_id);
+    documents
+        .iter()
+        .filter(|doc| doc.file_name.ends_with(&suffix) || doc.file_path.ends_with(&suffix))
+        .map(|doc| doc.id.clone())
+        .collect()
+}
+
+pub async fn is_findings_auto_ingest_enabled(db: &DatabaseService) -> bool {
+    matches!(
+        db.get_config(FINDINGS_RAG_CATEGORY, FINDINGS_RAG_AUTO_INGEST_KEY)
+            .await,
+        Ok(Some(raw)) if raw == "true"
+    )
+}
+
+async fn ensure_findings_collection(db: Arc<DatabaseService>) -> Result<String, String> {
+    let rag_service = get_or_init_rag_service(db).await?;
+    let status = rag_service.get_status().await.map_err(|e| e.to_string())?;
+    if let Some(collection) = status
+        .collections
+        .iter()
+        .find(|c| c.name == FINDINGS_COLLECTION_NAME)
+    {
+        return Ok(collection.id.clone());
+    }
+
+    info!("Creating findings collection: {}", FINDINGS_COLLECTION_NAME);
+    rag_service
+        .create_collection(
+            FINDINGS_COLLECTION_NAME,
+            Some(FINDINGS_COLLECTION_DESCRIPTION),
+        )
+        .await
+        .map_err(|e| format!("Failed to create findings collection: {}", e))
+}
+
+/// 摄取单个漏洞；漏洞未处于终态时不摄取并返回 false
+pub async fn ingest_finding_to_rag(
+    db: Arc<DatabaseService>,
+    finding_id: &str,
+) -> Result<bool, String> {
+    let vuln = db
+        .get_traffic_vulnerability_by_id(finding_id)
+        .await
+        .map_err(|e| format!("Failed to load finding {}: {}", finding_id, e))?
+        .ok_or_else(|| format!("Finding not found: {}", finding_id))?;
+    if !is_rag_ingest_status(&vuln.status) {
+        return Ok(false);
+    }
+    let evidence = db
+        .get_traffic_evidence_by_vuln_id(finding_id)
+        .await
+        .map_err(|e| format!("Failed to load evidence for {}: {}", finding_id, e))?;
+
+    let collection_id = ensure_findings_collection(db.clone()).await?;
+    let rag_service = get_or_init_rag_service(db).await?;
+
+    // 先删除旧文档，重复摄取只更新不重复
+    let documents = rag_service
+        .get_documents(&collection_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    for document_id in existing_finding_documents(&documents, finding_id) {
+        rag_service
+            .delete_document(&document_id)
+            .await
+            .map_err(|e| format!("Failed to replace document {}: {}", document_id, e))?;
+    }
+
+    let (title, content, metadata) = build_finding_document(&vuln, &evidence);
+    rag_service
+        .ingest_text(&title, &content, Some(&collection_id), Some(metadata))
+        .await
+        .map_err(|e| format!("Failed to ingest finding {}: {}", finding_id, e))?;
+    Ok(true)
+}
+
+/// 状态变更后按开关在后台摄取
+pub fn spawn_findings_auto_ingest(
+    db: Arc<DatabaseService>,
+    finding_ids: Vec<String>,
+    status: &str,
+) {
+    if !is_rag_ingest_status(status) || finding_ids.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        if !is_findings_auto_ingest_enabled(&db).await {
+            return;
+        }
+        #[cfg(not(debug_assertions))]
+        if !sentinel_license::is_licensed() {
+            return;
+        }
+        for finding_id in finding_ids {
+            if let Err(e) = ingest_finding_to_rag(db.clone(), &finding_id).await {
+                warn!("Findings auto-ingest failed: {}", e);
+            }
+        }
+    });
+}
+
+/// 获取漏洞自动摄取开关
+#[tauri::command]
+pub async fn get_findings_rag_auto_ingest(
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<bool, String> {
+    Ok(is_findings_auto_ingest_enabled(db.inner()).await)
+}
+
+/// 设置漏洞自动摄取开关
+#[tauri::command]
+pub async fn set_findings_rag_auto_ingest(
+    enabled: bool,
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<(), String> {
+    db.set_config(
+        FINDINGS_RAG_CATEGORY,
+        FINDINGS_RAG_AUTO_INGEST_KEY,
+        if enabled { "true" } else { "false" },
+        Some("Ingest reviewed/fixed findings into RAG"),
+    )
+    .await
+    .map_err(|e| format!("Failed to save findings auto-ingest setting: {}", e))
+}
+
+/// 回填：摄取所有已处于终态的漏洞
+#[tauri::command]
+pub async fn backfill_findings_to_rag(
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<FindingsBackfillReport, String> {
+    #[cfg(not(debug_assertions))]
+    if !sentinel_license::is_licensed() {
+        return Err("License required for RAG feature".to_string());
+    }
+
+    let db = db.inner().clone();
+    let mut report = FindingsBackfillReport::default();
+    for status in RAG_INGEST_STATUSES {
+        let findings = db
+            .list_traffic_vulnerabilities(TrafficVulnerabilityFilters {
+                status: Some(status.to_string()),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| format!("Failed to list {} findings: {}", status, e))?;
+        for finding in findings {
+            match ingest_finding_to_rag(db.clone(), &finding.id).await {
+                Ok(true) => report.ingested += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    report.failed += 1;
+                    report.errors.push(e);
+                }
+            }
+        }
+    }
+    info!(
+        "Findings backfill: {} ingested, {} failed",
+        report.ingested, report.failed
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use sentinel_rag::models::IngestionStatusEnum;
+
+    fn vuln(status: &str) -> TrafficVulnerabilityRecord {
+        TrafficVulnerabilityRecord {
+            id: "vuln-1".to_string(),
+            plugin_id: "sqli".to_string(),
+            vuln_type: "sqli".to_string(),
+            severity: "high".to_string(),
+            confidence: "high".to_string(),
+            title: "SQL injection in id parameter".to_string(),
+            description: "Error-based SQL injection via the id query parameter.".to_string(),
+            cwe: Some("CWE-89".to_string()),
+            owasp: None,
+            remediation: Some("Use parameterized queries.".to_string()),
+            status: status.to_string(),
+            signature: "sig".to_string(),
+            first_seen_at: Utc::now(),
+            last_seen_at: Utc::now(),
+            hit_count: 1,
+            session_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn evidence(snippet: &str) -> TrafficEvidenceRecord {
+        TrafficEvidenceRecord {
+            id: "ev-1".to_string(),
+            vuln_id: "vuln-1".to_string(),
+            url: "https://shop.example.com/item?id=1'".to_string(),
+            method: "GET".to_string(),
+            location: "query:id".to_string(),
+            evidence_snippet: snippet.to_string(),
+            request_headers: None,
+            request_body: None,
+            response_status: Some(500),
+            response_headers: None,
+            response_body: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn document(id: &str, title: &str) -> DocumentSource {
+        DocumentSource {
+            id: id.to_string(),
+            file_path: format!("manual://{}", title),
+            file_name: title.to_string(),
+            file_type: "text".to_string(),
+            file_size: 0,
+            file_hash: String::new(),
+            file_mtime: None,
+            chunk_count: 1,
+            ingestion_status: IngestionStatusEnum::Completed,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_finding_document_contains_searchable_fields() {
+        let long_snippet = "You have an error in your SQL syntax ".repeat(50);
+        let (title, content, metadata) =
+            build_finding_document(&vuln("reviewed"), &[evidence(&long_snippet)]);
+
+        assert_eq!(title, "SQL injection in id parameter [finding:vuln-1]");
+        assert!(content.contains("Error-based SQL injection"));
+        assert!(content.contains("CWE-89"));
+        assert!(content.contains("GET https://shop.example.com/item?id=1'"));
+        assert!(content.contains("Use parameterized queries."));
+        assert!(content.len() < long_snippet.len());
+        assert_eq!(
+            metadata.get("finding_id").map(String::as_str),
+            Some("vuln-1")
+        );
+    }
+
+    #[test]
+    fn test_reingest_replaces_existing_document() {
+        let (title, _, _) = build_finding_document(&vuln("fixed"), &[]);
+        let documents = vec![
+            document("doc-1", &title),
+            document("doc-2", "Old title [finding:vuln-1]"),
+            document("doc-3", "Other [finding:vuln-10]"),
+            document("doc-4", "notes"),
+        ];
+        assert_eq!(
+            existing_finding_documents(&documents, "vuln-1"),
+            vec!["doc-1", "doc-2"]
+        );
+    }
+
+    #[test]
+    fn test_only_terminal_statuses_are_ingested() {
+        assert!(is_rag_ingest_status("reviewed"));
+        assert!(is_rag_ingest_status("fixed"));
+        assert!(!is_rag_ingest_status("open"));
+        assert!(!is_rag_ingest_status("false_positive"));
+    }
+
+    /// OpenAI 兼容的嵌入服务：每条输入返回一个固定的 4 维向量
+    async fn serve_embeddings() -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 8192];
+                    let body_start = loop {
+                        let n = socket.read(&mut chunk).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        buf.extend_from_slice(&chunk[..n]);
+                        let text = String::from_utf8_lossy(&buf).to_lowercase();
+                        if let Some(end) = text.find("\r\n\r\n") {
+                            let length = text[..end]
+                                .lines()
+                                .find_map(|l| l.strip_prefix("content-length:"))
+                                .and_then(|v| v.trim().parse::<usize>().ok())
+                                .unwrap_or(0);
+                            if buf.len() >= end + 4 + length {
+                                break end + 4;
+                            }
+                        }
+                    };
+                    let request: serde_json::Value =
+                        serde_json::from_slice(&buf[body_start..]).unwrap_or_default();
+                    let inputs = match &request["input"] {
+                        serde_json::Value::Array(items) => items.len(),
+                        _ => 1,
+                    };
+                    let data: Vec<_> = (0..inputs)
+                        .map(|index| {
+                            serde_json::json!({
+                                "object": "embedding",
+                                "index": index,
+                                "embedding": [0.1, 0.2, 0.3, 0.4],
+                            })
+                        })
+                        .collect();
+                    let body = serde_json::json!({
+                        "object": "list",
+                        "data": data,
+                        "model": "text-embedding-3-small",
+                        "usage": { "prompt_tokens": 1, "total_tokens": 1 },
+                    })
+                    .to_string();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                });
+            }
+        });
+        format!("http://{}/v1", addr)
+    }
+
+    #[tokio::test]
+    async fn test_ingested_finding_is_retrievable_via_rag_query() {
+        use crate::commands::rag_commands::{convert_rag_to_core, execute_rag_query};
+        use sentinel_rag::config::{RagConfig, RetrievalMode};
+        use sentinel_rag::models::RagQueryRequest;
+
+        let dir = std::env::temp_dir().join(format!("finding-rag-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut db = DatabaseService::new();
+        db.initialize_with_config(sentinel_db::DatabaseConfig {
+            path: Some(dir.join("app.db").to_string_lossy().to_string()),
+            max_connections: 1,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let db = Arc::new(db);
+
+        db.save_rag_config(&convert_rag_to_core(RagConfig {
+            database_path: Some(dir.join("rag_vectors.db")),
+            embedding_provider: "openai".to_string(),
+            embedding_model: "text-embedding-3-small".to_string(),
+            embedding_dimensions: Some(4),
+            embedding_api_key: Some("test-key".to_string()),
+            embedding_base_url: Some(serve_embeddings().await),
+            retrieval_mode: RetrievalMode::Keyword,
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+
+        let record = vuln("reviewed");
+        db.insert_traffic_vulnerability(&sentinel_db::TrafficFinding {
+            id: record.id.clone(),
+            plugin_id: record.plugin_id.clone(),
+            vuln_type: record.vuln_type.clone(),
+            severity: record.severity.clone(),
+            confidence: record.confidence.clone(),
+            title: record.title.clone(),
+            description: record.description.clone(),
+            cwe: record.cwe.clone(),
+            owasp: None,
+            remediation: record.remediation.clone(),
+            url: "https://shop.example.com/item?id=1'".to_string(),
+            method: "GET".to_string(),
+            location: "query:id".to_string(),
+            evidence: "You have an error in your SQL syntax".to_string(),
+            request_headers: None,
+            request_body: None,
+            response_status: Some(500),
+            response_headers: None,
+            response_body: None,
+            created_at: Utc::now(),
+            signature: None,
+        })
+        .await
+        .unwrap();
+        db.update_traffic_vulnerability_status(&record.id, "reviewed")
+            .await
+            .unwrap();
+
+        assert!(ingest_finding_to_rag(db.clone(), &record.id).await.unwrap());
+        let collection_id = ensure_findings_collection(db.clone()).await.unwrap();
+
+        let response = execute_rag_query(
+            db.clone(),
+            RagQueryRequest {
+                query: "parameterized queries".to_string(),
+                collection_id: Some(collection_id),
+                top_k: Some(5),
+                use_mmr: None,
+                mmr_lambda: None,
+                filters: None,
+                use_embedding: Some(true),
+                reranking_enabled: Some(false),
+                similarity_threshold: Some(0.0),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(!response.results.is_empty());
+        assert!(response.context.contains("SQL injection in id parameter"));
+        assert!(response.context.contains("Use parameterized queries."));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
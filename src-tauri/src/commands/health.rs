@@ -0,0 +1,373 @@
+// 系统健康检查命令模块
+//
+// 汇总数据库、AI 提供商、RAG 向量化端点、MCP 连接和代理的状态，
+// 作为排查"数据库状态异常"等问题的统一诊断入口。
+
+use crate::commands::aisettings::{test_provider_connection, TestConnectionRequest};
+use crate::commands::traffic_analysis_commands::TrafficAnalysisState;
+use crate::services::ai::AiServiceManager;
+use crate::services::database::DatabaseService;
+use once_cell::sync::Lazy;
+use sentinel_db::Database;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+/// 单个子系统检查的超时时间
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 各子系统最近一次失败信息（跨多次检查保留）
+static LAST_ERRORS: Lazy<Mutex<HashMap<String, (String, i64)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 健康状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+    /// 子系统未启用（如代理未启动），不影响整体状态
+    Disabled,
+}
+
+/// 子系统探测结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbeOutcome {
+    pub status: HealthStatus,
+    pub message: String,
+}
+
+impl ProbeOutcome {
+    pub fn healthy(message: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Healthy,
+            message: message.into(),
+        }
+    }
+
+    pub fn degraded(message: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Degraded,
+            message: message.into(),
+        }
+    }
+
+    pub fn disabled(message: impl Into<String>) -> Self {
+        Self {
+            status: HealthStatus::Disabled,
+            message: message.into(),
+        }
+    }
+}
+
+/// 子系统健康信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubsystemHealth {
+    pub name: String,
+    pub status: HealthStatus,
+    /// 是否为关键子系统（关键子系统不可用时整体状态为 unhealthy）
+    pub critical: bool,
+    pub latency_ms: u64,
+    pub message: String,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<i64>,
+}
+
+/// 系统健康汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemHealth {
+    pub status: HealthStatus,
+    pub checked_at: i64,
+    /// 以安全模式启动（未自动连接 MCP、未加载插件工具、未自动启动代理）
+    pub safe_mode: bool,
+    pub subsystems: Vec<SubsystemHealth>,
+}
+
+/// 执行一次子系统探测：计时、超时处理并记录最近一次错误
+pub async fn run_probe<F>(name: &str, critical: bool, probe: F) -> SubsystemHealth
+where
+    F: Future<Output = Result<ProbeOutcome, String>>,
+{
+    let started = Instant::now();
+    let outcome = match tokio::time::timeout(PROBE_TIMEOUT, probe).await {
+        Ok(Ok(outcome)) => outcome,
+        Ok(Err(e)) => ProbeOutcome {
+            status: HealthStatus::Unhealthy,
+            message: e,
+        },
+        Err(_) => ProbeOutcome {
+            status: HealthStatus::Unhealthy,
+            message: format!("Health check timed out after {}s", PROBE_TIMEOUT.as_secs()),
+        },
+    };
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let mut last_errors = LAST_ERRORS.lock().unwrap();
+    if matches!(
+        outcome.status,
+        HealthStatus::Unhealthy | HealthStatus::Degraded
+    ) {
+        last_errors.insert(
+            name.to_string(),
+            (
+                outcome.message.clone(),
+                chrono::Utc::now().timestamp_millis(),
+            ),
+        );
+    }
+    let (last_error, last_error_at) = match last_errors.get(name) {
+        Some((error, at)) => (Some(error.clone()), Some(*at)),
+        None => (None, None),
+    };
+
+    SubsystemHealth {
+        name: name.to_string(),
+        status: outcome.status,
+        critical,
+        latency_ms,
+        message: outcome.message,
+        last_error,
+        last_error_at,
+    }
+}
+
+/// 汇总整体状态：关键子系统不可用为 unhealthy，其它子系统异常为 degraded
+pub fn aggregate_status(subsystems: &[SubsystemHealth]) -> HealthStatus {
+    let mut status = HealthStatus::Healthy;
+    for subsystem in subsystems {
+        match subsystem.status {
+            HealthStatus::Unhealthy if subsystem.critical => return HealthStatus::Unhealthy,
+            HealthStatus::Unhealthy | HealthStatus::Degraded => status = HealthStatus::Degraded,
+            HealthStatus::Healthy | HealthStatus::Disabled => {}
+        }
+    }
+    status
+}
+
+async fn probe_database(db: Arc<DatabaseService>) -> Result<ProbeOutcome, String> {
+    db.ping()
+        .await
+        .map_err(|e| format!("Database query failed: {}", e))?;
+    let db_type = db
+        .get_db_config()
+        .map(|c| format!("{:?}", c.db_type))
+        .unwrap_or_else(|| "SQLite".to_string());
+    Ok(ProbeOutcome::healthy(format!("{} responded", db_type)))
+}
+
+async fn probe_ai_provider(
+    db: Arc<DatabaseService>,
+    ai_manager: Option<Arc<AiServiceManager>>,
+) -> Result<ProbeOutcome, String> {
+    let Some(ai_manager) = ai_manager else {
+        return Err("AI service manager is not initialized".to_string());
+    };
+    let provider = match db.get_config("ai", "default_llm_provider").await {
+        Ok(Some(provider)) if !provider.trim().is_empty() => provider,
+        _ => return Ok(ProbeOutcome::disabled("No default AI provider configured")),
+    };
+    let config = ai_manager
+        .get_provider_config(&provider)
+        .await
+        .map_err(|e| format!("Failed to load config for provider '{}': {}", provider, e))?
+        .ok_or_else(|| format!("Provider '{}' is not configured", provider))?;
+
+    let response = test_provider_connection(TestConnectionRequest {
+        provider: provider.clone(),
+        api_key: config.api_key,
+        api_base: config.api_base,
+        organization: config.organization,
+        model: None,
+    })
+    .await?;
+    if response.success {
+        Ok(ProbeOutcome::healthy(format!(
+            "Provider '{}' reachable",
+            provider
+        )))
+    } else {
+        Err(format!("Provider '{}': {}", provider, response.message))
+    }
+}
+
+async fn probe_rag(db: Arc<DatabaseService>) -> Result<ProbeOutcome, String> {
+    let config = db
+        .get_rag_config()
+        .await
+        .map_err(|e| format!("Failed to load RAG config: {}", e))?;
+    let Some(config) = config else {
+        return Ok(ProbeOutcome::disabled("RAG is not configured"));
+    };
+    let Some(base_url) = config
+        .embedding_base_url
+        .filter(|url| !url.trim().is_empty())
+    else {
+        return Ok(ProbeOutcome::healthy(format!(
+            "Embedding provider '{}' uses its default endpoint",
+            config.embedding_provider
+        )));
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    // 能收到任意 HTTP 响应即视为端点可达
+    let response = client
+        .get(&base_url)
+        .send()
+        .await
+        .map_err(|e| format!("Embedding endpoint {} unreachable: {}", base_url, e))?;
+    if response.status().is_server_error() {
+        return Ok(ProbeOutcome::degraded(format!(
+            "Embedding endpoint {} returned {}",
+            base_url,
+            response.status()
+        )));
+    }
+    Ok(ProbeOutcome::healthy(format!(
+        "Embedding endpoint {} reachable ({})",
+        base_url, config.embedding_provider
+    )))
+}
+
+async fn probe_mcp() -> Result<ProbeOutcome, String> {
+    let connections = crate::commands::mcp_commands::get_active_mcp_connections().await;
+    if connections.is_empty() {
+        return Ok(ProbeOutcome::disabled("No MCP servers connected"));
+    }
+    let failing: Vec<String> = connections
+        .iter()
+        .filter(|c| !c.status.eq_ignore_ascii_case("connected"))
+        .map(|c| format!("{} ({})", c.name, c.status))
+        .collect();
+    if failing.is_empty() {
+        Ok(ProbeOutcome::healthy(format!(
+            "{} MCP server(s) connected",
+            connections.len()
+        )))
+    } else {
+        Ok(ProbeOutcome::degraded(format!(
+            "{}/{} MCP server(s) not connected: {}",
+            failing.len(),
+            connections.len(),
+            failing.join(", ")
+        )))
+    }
+}
+
+async fn probe_proxy(app: AppHandle) -> Result<ProbeOutcome, String> {
+    let Some(state) = app.try_state::<TrafficAnalysisState>() else {
+        return Err("Traffic analysis state is not initialized".to_string());
+    };
+    let is_running = *state.get_is_running().read().await;
+    if !is_running {
+        return Ok(ProbeOutcome::disabled("Proxy is stopped"));
+    }
+    match state.get_running_proxy_address().await {
+        Some(address) => Ok(ProbeOutcome::healthy(format!(
+            "Proxy listening on {}",
+            address
+        ))),
+        None => Err("Proxy is marked running but has no listener".to_string()),
+    }
+}
+
+/// 获取系统健康状态
+#[tauri::command]
+pub async fn get_system_health(app: AppHandle) -> Result<SystemHealth, String> {
+    let db = app
+        .try_state::<Arc<DatabaseService>>()
+        .map(|s| s.inner().clone());
+    let ai_manager = app
+        .try_state::<Arc<AiServiceManager>>()
+        .map(|s| s.inner().clone());
+
+    let mut subsystems = Vec::new();
+    match db {
+        Some(db) => {
+            let (database, ai, rag, mcp, proxy) = tokio::join!(
+                run_probe("database", true, probe_database(db.clone())),
+                run_probe(
+                    "ai_provider",
+                    false,
+                    probe_ai_provider(db.clone(), ai_manager)
+                ),
+                run_probe("rag", false, probe_rag(db)),
+                run_probe("mcp", false, probe_mcp()),
+                run_probe("proxy", false, probe_proxy(app.clone())),
+            );
+            subsystems.extend([database, ai, rag, mcp, proxy]);
+        }
+        None => {
+            let not_ready = || async { Err("Database service is not initialized".to_string()) };
+            let (database, mcp, proxy) = tokio::join!(
+                run_probe("database", true, not_ready()),
+                run_probe("mcp", false, probe_mcp()),
+                run_probe("proxy", false, probe_proxy(app.clone())),
+            );
+            subsystems.extend([database, mcp, proxy]);
+        }
+    }
+
+    Ok(SystemHealth {
+        status: aggregate_status(&subsystems),
+        checked_at: chrono::Utc::now().timestamp_millis(),
+        safe_mode: crate::utils::safe_mode::is_safe_mode(),
+        subsystems,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unhealthy_subsystem_degrades_overall_status() {
+        let subsystems = vec![
+            run_probe("test_db", true, async { Ok(ProbeOutcome::healthy("ok")) }).await,
+            run_probe("test_rag", false, async {
+                Err("connection refused".to_string())
+            })
+            .await,
+            run_probe("test_proxy", false, async {
+                Ok(ProbeOutcome::disabled("stopped"))
+            })
+            .await,
+        ];
+
+        assert_eq!(subsystems[1].status, HealthStatus::Unhealthy);
+        assert_eq!(
+            subsystems[1].last_error.as_deref(),
+            Some("connection refused")
+        );
+        assert!(subsystems[0].last_error.is_none());
+        assert_eq!(aggregate_status(&subsystems), HealthStatus::Degraded);
+
+        // 恢复后仍保留最近一次错误
+        let recovered =
+            run_probe("test_rag", false, async { Ok(ProbeOutcome::healthy("ok")) }).await;
+        assert_eq!(recovered.status, HealthStatus::Healthy);
+        assert_eq!(recovered.last_error.as_deref(), Some("connection refused"));
+    }
+
+    #[tokio::test]
+    async fn test_critical_subsystem_failure_is_unhealthy() {
+        let subsystems = vec![
+            run_probe("test_critical_db", true, async {
+                Err("pool timed out".to_string())
+            })
+            .await,
+            run_probe("test_critical_mcp", false, async {
+                Ok(ProbeOutcome::healthy("ok"))
+            })
+            .await,
+        ];
+        assert_eq!(aggregate_status(&subsystems), HealthStatus::Unhealthy);
+        assert_eq!(aggregate_status(&subsystems[1..]), HealthStatus::Healthy);
+    }
+}
@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LicenseInfo {
     pub machine_id: String,
+    /// Per-component fingerprint, lets the license survive minor hardware changes
+    pub machine_fingerprint: String,
     pub is_licensed: bool,
     pub needs_activation: bool,
 }
@@ -20,6 +22,7 @@ pub struct ActivationResult {
 pub fn get_license_info() -> LicenseInfo {
     LicenseInfo {
         machine_id: sentinel_license::get_machine_id(),
+        machine_fingerprint: sentinel_license::get_machine_fingerprint(),
         is_licensed: sentinel_license::is_licensed(),
         needs_activation: sentinel_license::needs_activation(),
     }
@@ -46,6 +49,47 @@ pub fn activate_license(license_key: String) -> ActivationResult {
     }
 }
 
+/// Write an offline activation request (challenge) file for air-gapped machines
+#[tauri::command]
+pub fn generate_offline_activation_request(path: String) -> ActivationResult {
+    let result = sentinel_license::generate_activation_request().and_then(|content| {
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write activation request: {}", e))
+    });
+
+    match result {
+        Ok(()) => ActivationResult {
+            success: true,
+            message: format!("Activation request saved to {}", path),
+        },
+        Err(message) => ActivationResult {
+            success: false,
+            message,
+        },
+    }
+}
+
+/// Activate license from an offline activation response file
+#[tauri::command]
+pub fn activate_license_from_file(path: String) -> ActivationResult {
+    use sentinel_license::ValidationResult;
+
+    match sentinel_license::activate_from_response_file(&path) {
+        ValidationResult::Valid => ActivationResult {
+            success: true,
+            message: "License activated successfully".to_string(),
+        },
+        ValidationResult::Invalid(reason) => ActivationResult {
+            success: false,
+            message: reason,
+        },
+        ValidationResult::NotActivated => ActivationResult {
+            success: false,
+            message: "Activation failed".to_string(),
+        },
+    }
+}
+
 /// Check if license is valid (quick check for multi-point validation)
 #[tauri::command]
 pub fn check_license() -> bool {
@@ -3,16 +3,18 @@ use chrono::Utc;
 use regex::Regex;
 use sentinel_core::global_proxy;
 use sentinel_db::core::models::scan_session::{
-    CreateScanSessionRequest, ScanSession, ScanSessionStatus, UpdateScanSessionRequest,
+    CreateScanSessionRequest, ScanProgressTracker, ScanSession, ScanSessionStatus,
+    UpdateScanSessionRequest, SCAN_PROGRESS_EVENT,
 };
 use sentinel_db::Database;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
 use uuid::Uuid;
 
+use crate::services::message_emitter::{MessageEmitter, TauriMessageEmitter};
 use crate::services::DatabaseService;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -380,6 +382,7 @@ pub async fn llm_test_get_run(
 pub async fn llm_test_execute_cases(
     run_id: String,
     request: ExecuteLlmTestBatchRequest,
+    app: AppHandle,
     db: State<'_, Arc<DatabaseService>>,
 ) -> Result<LlmTestResponse<ExecuteLlmTestBatchResponse>, String> {
     let run_uuid = Uuid::parse_str(&run_id).map_err(|e| format!("无效 run_id: {}", e))?;
@@ -402,6 +405,9 @@ pub async fn llm_test_execute_cases(
     let mut processed_cases = 0_usize;
     let mut stopped_early = false;
     let mut results = Vec::new();
+    let emitter = TauriMessageEmitter::new(app);
+    let started_stage = "batch_execution_started";
+    let mut tracker = ScanProgressTracker::new(run_uuid, started_stage, total_cases as u64);
 
     let mut summary = session.results_summary.clone().unwrap_or_else(|| {
         json!({
@@ -418,7 +424,7 @@ pub async fn llm_test_execute_cases(
             run_uuid,
             UpdateScanSessionRequest {
                 status: Some(ScanSessionStatus::Running),
-                current_stage: Some("batch_execution_started".to_string()),
+                current_stage: Some(started_stage.to_string()),
                 ..Default::default()
             },
         )
@@ -426,6 +432,7 @@ pub async fn llm_test_execute_cases(
     {
         return Ok(LlmTestResponse::err(format!("更新运行状态失败: {}", e)));
     }
+    emit_scan_progress(&emitter, &mut tracker, started_stage, 0).await;
 
     for batch_case in &request.cases {
         let case_req = ExecuteLlmTestCaseRequest {
@@ -476,11 +483,9 @@ pub async fn llm_test_execute_cases(
         }
         processed_cases += 1;
 
-        let progress = if total_cases == 0 {
-            0.0
-        } else {
-            (processed_cases as f64 / total_cases as f64) * 100.0
-        };
+        // 按最近吞吐量推送阶段进度与预计剩余时间
+        let stage = format!("batch_case:{}", batch_case.case_id);
+        let progress = emit_scan_progress(&emitter, &mut tracker, &stage, processed_cases).await;
         let _ = db
             .inner()
             .update_scan_session(
@@ -488,7 +493,7 @@ pub async fn llm_test_execute_cases(
                 UpdateScanSessionRequest {
                     status: Some(ScanSessionStatus::Running),
                     progress: Some(progress),
-                    current_stage: Some(format!("batch_case:{}", batch_case.case_id)),
+                    current_stage: Some(stage),
                     results_summary: Some(summary.clone()),
                     ..Default::default()
                 },
@@ -506,6 +511,8 @@ pub async fn llm_test_execute_cases(
         ScanSessionStatus::Completed
     };
 
+    let completed_stage = "batch_execution_completed";
+    emit_scan_progress(&emitter, &mut tracker, completed_stage, processed_cases).await;
     let _ = db
         .inner()
         .update_scan_session(
@@ -513,7 +520,7 @@ pub async fn llm_test_execute_cases(
             UpdateScanSessionRequest {
                 status: Some(final_status),
                 progress: Some(100.0),
-                current_stage: Some("batch_execution_completed".to_string()),
+                current_stage: Some(completed_stage.to_string()),
                 results_summary: Some(summary),
                 ..Default::default()
             },
@@ -530,6 +537,21 @@ pub async fn llm_test_execute_cases(
     }))
 }
 
+/// 以会话当前阶段名推送 `scan:progress` 事件，返回总体进度
+async fn emit_scan_progress(
+    emitter: &TauriMessageEmitter,
+    tracker: &mut ScanProgressTracker,
+    stage: &str,
+    done: usize,
+) -> f64 {
+    tracker.set_stage(stage);
+    let event = tracker.record(done as u64);
+    if let Ok(payload) = serde_json::to_value(&event) {
+        let _ = emitter.emit(SCAN_PROGRESS_EVENT, payload).await;
+    }
+    event.overall_progress
+}
+
 #[tauri::command]
 pub async fn llm_test_list_runs(
     request: LlmTestListRunsRequest,
@@ -6,13 +6,18 @@ use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, State};
+use tauri::State;
 use tokio::process::Command as TokioCommand;
 use tokio::sync::RwLock;
 
 use sentinel_db::Database;
 use sentinel_db::DatabaseService;
 
+use crate::services::mcp_server::{
+    generate_access_token, start_mcp_server, McpContentSource, SentinelMcpServerRuntime,
+};
+use crate::services::message_emitter::MessageEmitter;
+
 use rmcp::model::{ClientCapabilities, ClientInfo, Implementation};
 use rmcp::service::RunningService;
 use rmcp::{RoleClient, ServiceExt};
@@ -386,7 +391,10 @@ pub async fn mcp_set_auto_connect(
 }
 
 /// Auto-connect MCP servers that have auto_connect=true
-pub async fn mcp_auto_connect_servers(db: Arc<DatabaseService>, app: AppHandle) {
+pub async fn mcp_auto_connect_servers(db: Arc<DatabaseService>, emitter: &dyn MessageEmitter) {
+    if !crate::utils::safe_mode::should_run(crate::utils::safe_mode::StartupTask::McpAutoConnect) {
+        return;
+    }
     tracing::info!("Auto-connecting MCP servers...");
 
     let configs = match db.get_auto_connect_mcp_servers().await {
@@ -529,13 +537,15 @@ pub async fn mcp_auto_connect_servers(db: Arc<DatabaseService>, app: AppHandle)
                 );
 
                 // Notify frontend to update status
-                let _ = app.emit(
-                    "mcp:tools-changed",
-                    serde_json::json!({
-                        "action": "server_connected",
-                        "serverName": config.name.clone()
-                    }),
-                );
+                let _ = emitter
+                    .emit(
+                        "mcp:tools-changed",
+                        serde_json::json!({
+                            "action": "server_connected",
+                            "serverName": config.name.clone()
+                        }),
+                    )
+                    .await;
             }
             Err(e) => {
                 tracing::error!("Failed to auto-connect MCP server {}: {}", config.name, e);
@@ -644,15 +654,18 @@ pub async fn mcp_call_tool(
         None
     };
 
+    let config = sentinel_tools::mcp_client::get_mcp_client_config().await;
+    let request = rmcp::model::CallToolRequestParam {
+        name: tool_name.clone().into(),
+        arguments: args_map,
+    };
+
     let result = if let Some(client_arc) = client_arc {
-        // Reuse existing client
+        // Reuse existing client; release the lock before calling so a slow tool
+        // does not block other calls on the same connection
         tracing::debug!("Reusing persistent client for {}", server_name);
-        let client = client_arc.lock().await;
-        client
-            .call_tool(rmcp::model::CallToolRequestParam {
-                name: tool_name.clone().into(),
-                arguments: args_map,
-            })
+        let peer = client_arc.lock().await.peer().clone();
+        sentinel_tools::mcp_client::call_tool(&peer, request, &config, None)
             .await
             .map_err(|e| format!("Failed to call tool: {}", e))?
     } else {
@@ -684,54 +697,28 @@ pub async fn mcp_call_tool(
             .await
             .map_err(|e| format!("Connection failed: {}", e))?;
 
-        client
-            .call_tool(rmcp::model::CallToolRequestParam {
-                name: tool_name.clone().into(),
-                arguments: args_map,
-            })
+        sentinel_tools::mcp_client::call_tool(client.peer(), request, &config, None)
             .await
             .map_err(|e| format!("Tool call failed: {}", e))?
     };
 
-    // Convert result to JSON
-    let content_json: Vec<serde_json::Value> = result
-        .content
-        .iter()
-        .map(|c| {
-            // Access the raw content through Annotated wrapper
-            match &c.raw {
-                rmcp::model::RawContent::Text(text) => serde_json::json!({
-                    "type": "text",
-                    "text": text.text
-                }),
-                rmcp::model::RawContent::Image(img) => serde_json::json!({
-                    "type": "image",
-                    "data": img.data,
-                    "mime_type": img.mime_type
-                }),
-                rmcp::model::RawContent::Audio(audio) => serde_json::json!({
-                    "type": "audio",
-                    "data": audio.data,
-                    "mime_type": audio.mime_type
-                }),
-                rmcp::model::RawContent::Resource(res) => serde_json::json!({
-                    "type": "resource",
-                    "resource": res.resource
-                }),
-                rmcp::model::RawContent::ResourceLink(link) => serde_json::json!({
-                    "type": "resource_link",
-                    "uri": link.uri
-                }),
-            }
-        })
-        .collect();
+    Ok(sentinel_tools::mcp_client::call_result_to_json(&result))
+}
 
-    let response = serde_json::json!({
-        "content": content_json,
-        "is_error": result.is_error.unwrap_or(false)
-    });
+/// Get MCP client settings
+#[tauri::command]
+pub async fn mcp_get_client_config() -> Result<sentinel_tools::mcp_client::McpClientConfig, String>
+{
+    Ok(sentinel_tools::mcp_client::get_mcp_client_config().await)
+}
 
-    Ok(response)
+/// Update MCP client settings (e.g. per-call timeout)
+#[tauri::command]
+pub async fn mcp_update_client_config(
+    config: sentinel_tools::mcp_client::McpClientConfig,
+) -> Result<(), String> {
+    sentinel_tools::mcp_client::set_mcp_client_config(config).await;
+    Ok(())
 }
 
 /// Test an MCP server tool (alias for mcp_call_tool)
@@ -889,3 +876,172 @@ pub async fn mcp_get_all_tools() -> Result<Vec<serde_json::Value>, String> {
 
     Ok(all_tools)
 }
+
+/// Default port for the built-in Sentinel MCP server
+const DEFAULT_SENTINEL_MCP_PORT: u16 = 8931;
+
+/// Running built-in MCP server, if any
+static SENTINEL_MCP_SERVER: Lazy<RwLock<Option<SentinelMcpServerRuntime>>> =
+    Lazy::new(|| RwLock::new(None));
+
+/// Built-in MCP server status for frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentinelMcpServerStatus {
+    pub running: bool,
+    pub endpoint: Option<String>,
+    pub started_at: Option<String>,
+    /// Bearer token MCP clients must send
+    pub access_token: Option<String>,
+}
+
+/// Per-install access token for the built-in MCP server, created on first use
+async fn sentinel_mcp_access_token(db: &DatabaseService) -> Result<String, String> {
+    if let Ok(Some(token)) = db.get_config("mcp", "sentinel_server_token").await {
+        if !token.is_empty() {
+            return Ok(token);
+        }
+    }
+    let token = generate_access_token();
+    db.set_config(
+        "mcp",
+        "sentinel_server_token",
+        &token,
+        Some("Bearer token for the built-in Sentinel MCP server"),
+    )
+    .await
+    .map_err(|e| format!("Failed to store MCP server token: {}", e))?;
+    Ok(token)
+}
+
+/// Start the built-in MCP server exposing findings, proxy history and prompts
+#[tauri::command]
+pub async fn start_sentinel_mcp_server(
+    port: Option<u16>,
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<String, String> {
+    let mut server = SENTINEL_MCP_SERVER.write().await;
+    if server
+        .as_ref()
+        .map(|runtime| runtime.is_finished())
+        .unwrap_or(false)
+    {
+        *server = None;
+    }
+    if server.is_some() {
+        return Err("Sentinel MCP server is already running".to_string());
+    }
+
+    let token = sentinel_mcp_access_token(db.inner()).await?;
+    let source: Arc<dyn McpContentSource> = db.inner().clone();
+    let runtime =
+        start_mcp_server(source, port.unwrap_or(DEFAULT_SENTINEL_MCP_PORT), &token).await?;
+    let endpoint = runtime.endpoint_url();
+    *server = Some(runtime);
+    Ok(endpoint)
+}
+
+/// Stop the built-in MCP server
+#[tauri::command]
+pub async fn stop_sentinel_mcp_server() -> Result<(), String> {
+    let runtime = SENTINEL_MCP_SERVER.write().await.take();
+    if let Some(runtime) = runtime {
+        runtime.stop().await;
+    }
+    Ok(())
+}
+
+/// Get the built-in MCP server status
+#[tauri::command]
+pub async fn get_sentinel_mcp_server_status(
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<SentinelMcpServerStatus, String> {
+    let server = SENTINEL_MCP_SERVER.read().await;
+    Ok(match server.as_ref() {
+        Some(runtime) => SentinelMcpServerStatus {
+            running: !runtime.is_finished(),
+            endpoint: Some(runtime.endpoint_url()),
+            started_at: Some(runtime.started_at()),
+            access_token: Some(sentinel_mcp_access_token(db.inner()).await?),
+        },
+        None => SentinelMcpServerStatus {
+            running: false,
+            endpoint: None,
+            started_at: None,
+            access_token: None,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::safe_mode;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingEmitter {
+        events: Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl MessageEmitter for RecordingEmitter {
+        async fn emit(&self, event: &str, _payload: serde_json::Value) -> anyhow::Result<()> {
+            self.events.lock().unwrap().push(event.to_string());
+            Ok(())
+        }
+
+        async fn emit_all(&self, event: &str, payload: serde_json::Value) -> anyhow::Result<()> {
+            self.emit(event, payload).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_connect_skipped_in_safe_mode() {
+        let dir = std::env::temp_dir().join(format!("mcp-auto-connect-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut db = DatabaseService::new();
+        db.initialize_with_config(sentinel_db::DatabaseConfig {
+            path: Some(dir.join("app.db").to_string_lossy().to_string()),
+            max_connections: 1,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        let db = Arc::new(db);
+
+        // 启动即在目录中留下标记文件，用于判断是否尝试过连接
+        let marker = dir.join("spawned");
+        let name = format!("safe-mode-{}", uuid::Uuid::new_v4());
+        let id = db
+            .create_mcp_server_config(
+                &name,
+                None,
+                "sh",
+                &["-c".to_string(), format!("touch '{}'", marker.display())],
+            )
+            .await
+            .unwrap();
+        db.update_mcp_server_auto_connect(&id, true).await.unwrap();
+
+        let _guard = safe_mode::TEST_GUARD.lock().await;
+        let emitter = RecordingEmitter::default();
+
+        safe_mode::set_safe_mode(true);
+        mcp_auto_connect_servers(db.clone(), &emitter).await;
+        assert!(!marker.exists());
+        assert!(!ACTIVE_CONNECTIONS.read().await.contains_key(&name));
+        assert!(emitter.events.lock().unwrap().is_empty());
+
+        // 关闭安全模式后会启动该服务器进程
+        safe_mode::set_safe_mode(false);
+        tokio::time::timeout(
+            std::time::Duration::from_secs(30),
+            mcp_auto_connect_servers(db.clone(), &emitter),
+        )
+        .await
+        .unwrap();
+        assert!(marker.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -12,6 +12,8 @@ pub mod config_commands;
 pub mod database;
 pub mod dictionary;
 pub mod document_commands;
+pub mod finding_rag_commands;
+pub mod health;
 pub mod http_gateway_commands;
 pub mod license_commands;
 pub mod llm_test_commands;
@@ -51,6 +53,7 @@ pub use config_commands::*;
 pub use database::*;
 pub use dictionary::*;
 pub use document_commands::*;
+pub use finding_rag_commands::*;
 pub use http_gateway_commands::*;
 pub use license_commands::*;
 pub use llm_test_commands::*;
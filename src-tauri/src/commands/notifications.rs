@@ -1,6 +1,6 @@
 use crate::services::database::DatabaseService;
 use sentinel_core::models::database::NotificationRule;
-use sentinel_db::Database;
+use sentinel_db::{Database, NotificationDeliveryRecord};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
@@ -81,23 +81,111 @@ pub async fn test_notification_rule_connection(
         }
     }
 
-    match sentinel_notify::send(
-        &channel,
-        config,
-        sentinel_notify::NotificationMessage { title, content },
+    // 连接测试只记录投递结果，不进入重试队列
+    let record = sentinel_notify::send_tracked(
+        sentinel_notify::OutgoingNotification {
+            rule_id: request.id.clone(),
+            channel,
+            config,
+            message: sentinel_notify::NotificationMessage { title, content },
+        },
+        false,
     )
-    .await
-    {
-        Ok(_) => Ok(true),
-        Err(e) => Err(e.to_string()),
+    .await;
+    match record.status {
+        sentinel_notify::DeliveryStatus::Sent => Ok(true),
+        _ => Err(record.last_error.unwrap_or_default()),
     }
 }
 
+fn validate_rule_settings(rule: &NotificationRule) -> Result<(), String> {
+    sentinel_notify::NotificationConditions::parse(rule.conditions.as_deref())
+        .map_err(|e| e.to_string())?;
+    sentinel_notify::ThrottleConfig::parse(rule.throttle.as_deref()).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DispatchFindingNotificationRequest {
+    pub finding: sentinel_notify::FindingEvent,
+    pub message: NotificationMessage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DispatchedNotification {
+    pub rule_id: String,
+    pub decision: sentinel_notify::ThrottleDecision,
+}
+
+/// 按触发条件把发现分发给所有启用的通知规则，返回条件匹配的规则及其去重/摘要处理结果
+///
+/// 条件不满足或无效的规则静默跳过；发现未标明 `in_scope` 时按漏洞赏金项目的范围规则判定。
+/// 单个规则发送失败不影响其他规则。
+#[tauri::command]
+pub async fn dispatch_finding_notification(
+    db_service: State<'_, Arc<DatabaseService>>,
+    request: DispatchFindingNotificationRequest,
+) -> Result<Vec<DispatchedNotification>, String> {
+    let _guard = crate::managers::shutdown_coordinator::begin_operation("notification dispatch")
+        .ok_or_else(|| "Application is shutting down".to_string())?;
+    let rules = db_service
+        .get_notification_rules()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut sent = Vec::new();
+    for rule in rules.into_iter().filter(|r| r.enabled) {
+        let Some(conditions) =
+            sentinel_notify::NotificationConditions::for_rule(&rule.id, rule.conditions.as_deref())
+        else {
+            continue;
+        };
+        let mut finding = request.finding.clone();
+        if let Some(host) = conditions.scope_to_resolve(&finding) {
+            match db_service.is_target_in_any_scope(host).await {
+                Ok(in_scope) => finding.in_scope = Some(in_scope),
+                Err(e) => tracing::warn!("Failed to resolve scope of {}: {}", host, e),
+            }
+        }
+        if !conditions.matches(&finding) {
+            continue;
+        }
+        let throttle =
+            sentinel_notify::ThrottleConfig::parse(rule.throttle.as_deref()).unwrap_or_default();
+        let config = rule
+            .config
+            .as_deref()
+            .and_then(|cfg| serde_json::from_str(cfg).ok())
+            .unwrap_or_else(|| serde_json::json!({}));
+
+        match sentinel_notify::send_throttled(
+            &rule.id,
+            &rule.channel,
+            config,
+            &throttle,
+            sentinel_notify::NotificationMessage {
+                title: request.message.title.clone(),
+                content: request.message.content.clone(),
+            },
+        )
+        .await
+        {
+            Ok(decision) => sent.push(DispatchedNotification {
+                rule_id: rule.id,
+                decision,
+            }),
+            Err(e) => tracing::warn!("Failed to send notification rule {}: {}", rule.id, e),
+        }
+    }
+    Ok(sent)
+}
+
 #[tauri::command]
 pub async fn create_notification_rule(
     db_service: State<'_, Arc<DatabaseService>>,
     rule: NotificationRule,
 ) -> Result<bool, String> {
+    validate_rule_settings(&rule)?;
     db_service
         .create_notification_rule(&rule)
         .await
@@ -110,6 +198,7 @@ pub async fn update_notification_rule(
     db_service: State<'_, Arc<DatabaseService>>,
     rule: NotificationRule,
 ) -> Result<bool, String> {
+    validate_rule_settings(&rule)?;
     db_service
         .update_notification_rule(&rule)
         .await
@@ -149,3 +238,45 @@ pub async fn get_notification_rule(
         .await
         .map_err(|e| e.to_string())
 }
+
+fn to_persisted(record: sentinel_notify::DeliveryRecord) -> NotificationDeliveryRecord {
+    NotificationDeliveryRecord {
+        id: record.id,
+        rule_id: record.rule_id,
+        channel: record.channel,
+        title: record.title,
+        status: record.status.as_str().to_string(),
+        attempts: record.attempts as i64,
+        last_error: record.last_error,
+        created_at_ms: record.created_at_ms,
+        updated_at_ms: record.updated_at_ms,
+    }
+}
+
+/// 将每次通知投递（含后台重试）的结果写入投递日志
+pub fn register_delivery_log(db: Arc<DatabaseService>) {
+    sentinel_notify::set_delivery_listener(Arc::new(move |record| {
+        let db = db.clone();
+        // 退出时等待投递日志落库
+        let guard = crate::managers::shutdown_coordinator::begin_operation("notification log");
+        tokio::spawn(async move {
+            let _guard = guard;
+            if let Err(e) = db.save_notification_delivery(&to_persisted(record)).await {
+                tracing::warn!("Failed to persist notification delivery: {}", e);
+            }
+        });
+    }));
+}
+
+/// 查询通知投递日志（按更新时间倒序），可按规则过滤
+#[tauri::command]
+pub async fn get_notification_delivery_log(
+    db_service: State<'_, Arc<DatabaseService>>,
+    rule_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<NotificationDeliveryRecord>, String> {
+    db_service
+        .list_notification_deliveries(rule_id.as_deref(), limit.unwrap_or(100).clamp(1, 1000))
+        .await
+        .map_err(|e| e.to_string())
+}
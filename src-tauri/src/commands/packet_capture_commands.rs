@@ -11,7 +11,8 @@ use tokio::sync::RwLock;
 use tracing::{error, info};
 
 use sentinel_traffic::{
-    CapturedPacket, FileExtractor, InterfaceInfo, PacketCaptureService, PcapFileOps,
+    CapturedPacket, CredentialExtractor, CredentialExtractorConfig, CredentialFinding, DnsMessage,
+    FileExtractor, HttpExchange, InterfaceInfo, PacketCaptureService, PcapFileOps,
 };
 
 /// Packet capture state
@@ -128,7 +129,31 @@ pub async fn open_pcap_file(file_path: String) -> Result<Vec<CapturedPacket>, St
         return Err(format!("File not found: {}", file_path));
     }
 
-    PcapFileOps::read_pcap_file(&path)
+    let mut packets = PcapFileOps::read_pcap_file(&path)?;
+    sentinel_traffic::attach_decoded(&mut packets);
+    Ok(packets)
+}
+
+/// Decode HTTP request/response exchanges from a pcap file
+#[tauri::command]
+pub async fn decode_pcap_http(file_path: String) -> Result<Vec<HttpExchange>, String> {
+    info!("Decoding HTTP streams from pcap file: {}", file_path);
+    let path = PathBuf::from(&file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+    sentinel_traffic::decode_http_streams(&path)
+}
+
+/// Decode DNS queries and responses from a pcap file
+#[tauri::command]
+pub async fn decode_pcap_dns(file_path: String) -> Result<Vec<DnsMessage>, String> {
+    info!("Decoding DNS messages from pcap file: {}", file_path);
+    let path = PathBuf::from(&file_path);
+    if !path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+    sentinel_traffic::decode_dns(&path)
 }
 
 /// Save packets to pcap file
@@ -151,6 +176,17 @@ pub async fn save_pcap_file(file_path: String, packets: Vec<CapturedPacket>) ->
     }
 }
 
+/// Detect leaked credentials (Basic-Auth, tokens, cookies, API keys) in packets
+#[tauri::command]
+pub async fn extract_credentials(
+    packets: Vec<CapturedPacket>,
+    config: Option<CredentialExtractorConfig>,
+) -> Result<Vec<CredentialFinding>, String> {
+    info!("Extracting credentials from {} packets", packets.len());
+    let extractor = CredentialExtractor::new(&config.unwrap_or_default())?;
+    Ok(extractor.extract(&packets))
+}
+
 /// Extract file info from packets (preview without saving)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractedFileInfo {
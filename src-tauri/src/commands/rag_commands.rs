@@ -4,9 +4,10 @@ use sentinel_core::models::rag_config::RagConfig as RagConfigCore;
 use sentinel_db::Database;
 use sentinel_rag::config::RagConfig as RagConfigRag;
 use sentinel_rag::db::RagDatabase;
+use sentinel_rag::incremental::IncrementalIngestReport;
 use sentinel_rag::models::{
-    DocumentChunk, DocumentSource, IngestRequest, IngestResponse, RagQueryRequest,
-    RagQueryResponse, RagStatus,
+    AssistantRagRequest, DocumentChunk, DocumentSource, IngestRequest, IngestResponse,
+    IngestionStatus, RagQueryRequest, RagQueryResponse, RagStatus,
 };
 use sentinel_rag::service::RagService;
 use serde::{Deserialize, Serialize};
@@ -52,6 +53,12 @@ fn convert_chunking_strategy_core_to_rag(
         sentinel_core::models::rag_config::ChunkingStrategy::StructureAware => {
             sentinel_rag::config::ChunkingStrategy::StructureAware
         }
+        sentinel_core::models::rag_config::ChunkingStrategy::SentenceBoundary => {
+            sentinel_rag::config::ChunkingStrategy::SentenceBoundary
+        }
+        sentinel_core::models::rag_config::ChunkingStrategy::Markdown => {
+            sentinel_rag::config::ChunkingStrategy::Markdown
+        }
     }
 }
 
@@ -71,6 +78,12 @@ fn convert_chunking_strategy_rag_to_core(
         sentinel_rag::config::ChunkingStrategy::StructureAware => {
             sentinel_core::models::rag_config::ChunkingStrategy::StructureAware
         }
+        sentinel_rag::config::ChunkingStrategy::SentenceBoundary => {
+            sentinel_core::models::rag_config::ChunkingStrategy::SentenceBoundary
+        }
+        sentinel_rag::config::ChunkingStrategy::Markdown => {
+            sentinel_core::models::rag_config::ChunkingStrategy::Markdown
+        }
     }
 }
 
@@ -101,10 +114,27 @@ pub fn convert_core_to_rag(core: RagConfigCore) -> RagConfigRag {
         chunk_expansion_enabled: core.chunk_expansion_enabled,
         chunk_expansion_before: core.chunk_expansion_before,
         chunk_expansion_after: core.chunk_expansion_after,
+        retrieval_mode: core.retrieval_mode,
+        hybrid_alpha: core.hybrid_alpha as f32,
+        embedding_fallbacks: core
+            .embedding_fallbacks
+            .into_iter()
+            .map(|f| sentinel_rag::config::EmbeddingConfig {
+                provider: f.provider,
+                model: f.model,
+                api_key: f.api_key,
+                base_url: f.base_url,
+                dimensions: f.dimensions,
+                timeout_secs: f.timeout_secs,
+            })
+            .collect(),
+        embedding_timeout_secs: core.embedding_timeout_secs,
+        embedding_failure_threshold: core.embedding_failure_threshold,
+        embedding_circuit_cooldown_secs: core.embedding_circuit_cooldown_secs,
     }
 }
 
-fn convert_rag_to_core(rag: RagConfigRag) -> RagConfigCore {
+pub(crate) fn convert_rag_to_core(rag: RagConfigRag) -> RagConfigCore {
     RagConfigCore {
         database_path: rag.database_path,
         chunk_size_chars: rag.chunk_size_chars,
@@ -130,6 +160,25 @@ fn convert_rag_to_core(rag: RagConfigRag) -> RagConfigCore {
         chunk_expansion_enabled: rag.chunk_expansion_enabled,
         chunk_expansion_before: rag.chunk_expansion_before,
         chunk_expansion_after: rag.chunk_expansion_after,
+        retrieval_mode: rag.retrieval_mode,
+        hybrid_alpha: rag.hybrid_alpha as f64,
+        embedding_fallbacks: rag
+            .embedding_fallbacks
+            .into_iter()
+            .map(
+                |f| sentinel_core::models::rag_config::EmbeddingFallbackConfig {
+                    provider: f.provider,
+                    model: f.model,
+                    api_key: f.api_key,
+                    base_url: f.base_url,
+                    dimensions: f.dimensions,
+                    timeout_secs: f.timeout_secs,
+                },
+            )
+            .collect(),
+        embedding_timeout_secs: rag.embedding_timeout_secs,
+        embedding_failure_threshold: rag.embedding_failure_threshold,
+        embedding_circuit_cooldown_secs: rag.embedding_circuit_cooldown_secs,
     }
 }
 
@@ -252,6 +301,17 @@ pub async fn initialize_global_rag_service(database: Arc<DatabaseService>) -> Re
         }
     }
 
+    // 降级嵌入提供商同样使用 AI 配置中的凭据
+    if !config.embedding_fallbacks.is_empty() {
+        if let Ok(Some(providers_json)) = database.get_config("ai", "providers_config").await {
+            if let Ok(providers) = serde_json::from_str::<serde_json::Value>(&providers_json) {
+                for fallback in &mut config.embedding_fallbacks {
+                    apply_ai_provider_credentials(&providers, fallback);
+                }
+            }
+        }
+    }
+
     let rag_service = RagService::new(config, database)
         .await
         .map_err(|e| format!("Failed to create RAG service: {}", e))?;
@@ -334,14 +394,46 @@ pub struct RagSystemStatus {
     pub total_chunks: usize,
 }
 
+/// 正在运行的导入任务（job_id -> 取消令牌）
+static RAG_INGEST_JOBS: std::sync::LazyLock<
+    std::sync::Mutex<HashMap<String, sentinel_rag::ingest_job::IngestCancelToken>>,
+> = std::sync::LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+fn register_ingest_job(job_id: &str) -> sentinel_rag::ingest_job::IngestCancelToken {
+    let token = sentinel_rag::ingest_job::IngestCancelToken::new();
+    if let Ok(mut jobs) = RAG_INGEST_JOBS.lock() {
+        jobs.insert(job_id.to_string(), token.clone());
+    }
+    token
+}
+
+fn finish_ingest_job(job_id: &str) {
+    if let Ok(mut jobs) = RAG_INGEST_JOBS.lock() {
+        jobs.remove(job_id);
+    }
+}
+
+/// 导入任务进度事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestJobProgress {
+    pub job_id: String,
+    pub file_path: String,
+    pub status: IngestionStatus,
+}
+
 /// 导入数据源到RAG系统
+///
+/// 在后台执行导入并立即返回 job_id；进度通过 `rag_ingest_progress` 事件推送，
+/// 结束时发送 `rag_ingest_complete`（含 IngestResponse）或 `rag_ingest_error`。
+/// 可用 `cancel_rag_ingest` 取消。
 #[tauri::command]
 pub async fn rag_ingest_source(
     database: State<'_, Arc<DatabaseService>>,
     file_path: String,
     collection_id: Option<String>,
     metadata: Option<HashMap<String, String>>,
-) -> Result<IngestResponse, String> {
+    app: AppHandle,
+) -> Result<String, String> {
     info!("开始导入数据源: {}", file_path);
 
     // License check
@@ -356,9 +448,86 @@ pub async fn rag_ingest_source(
         metadata,
     };
 
+    let rag_service = get_or_init_rag_service(database.inner().clone()).await?;
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let cancel = register_ingest_job(&job_id);
+
+    let job = job_id.clone();
+    tokio::spawn(async move {
+        let progress_app = app.clone();
+        let progress_job = job.clone();
+        let result = rag_service
+            .ingest_source_with_cancel(request, &cancel, |status| {
+                let _ = progress_app.emit(
+                    "rag_ingest_progress",
+                    &IngestJobProgress {
+                        job_id: progress_job.clone(),
+                        file_path: file_path.clone(),
+                        status: status.clone(),
+                    },
+                );
+            })
+            .await;
+        finish_ingest_job(&job);
+
+        match result {
+            Ok(response) => {
+                let _ = app.emit(
+                    "rag_ingest_complete",
+                    serde_json::json!({ "job_id": job, "response": response }),
+                );
+            }
+            Err(e) => {
+                warn!("导入任务 {} 失败: {}", job, e);
+                let _ = app.emit(
+                    "rag_ingest_error",
+                    serde_json::json!({ "job_id": job, "error": e.to_string() }),
+                );
+            }
+        }
+    });
+
+    Ok(job_id)
+}
+
+/// 取消导入任务（单文件导入的 job_id 或批量导入的 batch_id）
+///
+/// 当前批次的 chunk 写完后停止，已导入的 chunk 保留。任务不存在或已结束时返回 false。
+#[tauri::command]
+pub async fn cancel_rag_ingest(job_id: String) -> Result<bool, String> {
+    let token = RAG_INGEST_JOBS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&job_id)
+        .cloned();
+    match token {
+        Some(token) => {
+            info!("取消导入任务: {}", job_id);
+            token.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// 增量重新导入目录：仅处理新增/变化的文件，并删除已消失文件的文档
+#[tauri::command]
+pub async fn rag_reingest_incremental(
+    database: State<'_, Arc<DatabaseService>>,
+    collection: String,
+    path: String,
+) -> Result<IncrementalIngestReport, String> {
+    info!("开始增量导入: {} -> {}", path, collection);
+
+    // License check
+    #[cfg(not(debug_assertions))]
+    if !sentinel_license::is_licensed() {
+        return Err("License required for RAG feature".to_string());
+    }
+
     let rag_service = get_or_init_rag_service(database.inner().clone()).await?;
     rag_service
-        .ingest_source(request)
+        .reingest_incremental(&collection, &path)
         .await
         .map_err(|e| e.to_string())
 }
@@ -412,6 +581,7 @@ pub async fn rag_batch_ingest_sources(
     let batch_id = uuid::Uuid::new_v4().to_string();
     let total = file_paths.len();
     let rag_service = get_or_init_rag_service(database.inner().clone()).await?;
+    let cancel = register_ingest_job(&batch_id);
 
     // 使用信号量控制并发数
     let max_concurrent = 3; // 最多同时处理3个文件
@@ -429,6 +599,11 @@ pub async fn rag_batch_ingest_sources(
             .acquire_owned()
             .await
             .map_err(|e| e.to_string())?;
+        if cancel.is_cancelled() {
+            info!("批量导入 {} 已取消，跳过剩余文件", batch_id);
+            break;
+        }
+        let cancel = cancel.clone();
         let rag_service = rag_service.clone();
         let collection_id = collection_id.clone();
         let app = app.clone();
@@ -470,7 +645,10 @@ pub async fn rag_batch_ingest_sources(
                 ])),
             };
 
-            match rag_service.ingest_source(request).await {
+            match rag_service
+                .ingest_source_with_cancel(request, &cancel, |_| {})
+                .await
+            {
                 Ok(response) => {
                     success_count.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                     total_chunks.fetch_add(
@@ -503,6 +681,7 @@ pub async fn rag_batch_ingest_sources(
     for task in tasks {
         let _ = task.await;
     }
+    finish_ingest_job(&batch_id);
 
     let success = success_count.load(std::sync::atomic::Ordering::Relaxed);
     let failed = failed_count.load(std::sync::atomic::Ordering::Relaxed);
@@ -517,7 +696,9 @@ pub async fn rag_batch_ingest_sources(
         success,
         failed,
         current_file: String::new(),
-        status: if failed == 0 {
+        status: if cancel.is_cancelled() {
+            "cancelled".to_string()
+        } else if failed == 0 {
             "completed".to_string()
         } else {
             "partial".to_string()
@@ -596,10 +777,144 @@ pub async fn rag_query(
         similarity_threshold,
     };
 
-    let rag_service = get_or_init_rag_service(database.inner().clone()).await?;
+    execute_rag_query(database.inner().clone(), request).await
+}
+
+/// 通过全局 RAG 服务执行查询（`rag_query` 的实现）
+pub async fn execute_rag_query(
+    database: Arc<DatabaseService>,
+    request: RagQueryRequest,
+) -> Result<RagQueryResponse, String> {
+    let rag_service = get_or_init_rag_service(database).await?;
     rag_service.query(request).await.map_err(|e| e.to_string())
 }
 
+/// 将 sentinel-llm 流式客户端适配为 RAG 回答的文本流
+struct LlmAnswerStreamer(sentinel_llm::StreamingLlmClient);
+
+#[async_trait::async_trait]
+impl sentinel_rag::answer::AnswerStreamer for LlmAnswerStreamer {
+    async fn stream(
+        &self,
+        system_prompt: &str,
+        user_prompt: &str,
+        on_text: &mut (dyn for<'a> FnMut(&'a str) -> bool + Send),
+    ) -> anyhow::Result<String> {
+        let system_prompt = Some(system_prompt).filter(|s| !s.trim().is_empty());
+        self.0
+            .stream_completion(system_prompt, user_prompt, |chunk| match chunk {
+                sentinel_llm::StreamContent::Text(text) => on_text(&text),
+                _ => true,
+            })
+            .await
+    }
+}
+
+/// 基于知识库流式回答问题
+///
+/// 事件（均携带 `stream_id`）：
+/// - `rag_answer_delta`：文本增量
+/// - `rag_answer_citation`：回答中首次引用的 chunk
+/// - `rag_answer_citations`：回答结束时被引用的 chunk 列表
+/// - `rag_answer_complete` / `rag_answer_error`：完整结果或错误
+#[tauri::command]
+pub async fn assistant_rag_answer_stream(
+    request: AssistantRagRequest,
+    stream_id: Option<String>,
+    app_handle: AppHandle,
+    database: State<'_, Arc<DatabaseService>>,
+    ai_manager: State<'_, Arc<crate::services::ai::AiServiceManager>>,
+) -> Result<String, String> {
+    info!("RAG流式回答: {}", request.query);
+
+    if request.query.trim().is_empty() {
+        return Err("问题不能为空".to_string());
+    }
+
+    let mut service_name = request
+        .model_provider
+        .clone()
+        .map(|p| p.to_lowercase())
+        .unwrap_or_else(|| "default".to_string());
+    if service_name == "default" {
+        if let Ok(Some(provider)) = database.get_config("ai", "default_llm_provider").await {
+            let provider = provider.to_lowercase();
+            if ai_manager.get_service(&provider).is_some() {
+                service_name = provider;
+            }
+        }
+    }
+    let service = ai_manager
+        .get_service(&service_name)
+        .or_else(|| ai_manager.get_service("default"))
+        .ok_or_else(|| format!("AI service '{}' not found", service_name))?;
+
+    let mut llm_config = crate::utils::ai_generation_settings::apply_generation_settings_from_db(
+        database.inner().as_ref(),
+        service.service.to_llm_config(),
+    )
+    .await;
+    if let Some(model) = request.model_name.as_ref().filter(|m| !m.is_empty()) {
+        llm_config = llm_config.with_model(model);
+    }
+    if let Some(temperature) = request.temperature {
+        llm_config = llm_config.with_temperature(temperature as f32);
+    }
+    if let Some(max_tokens) = request.max_tokens {
+        llm_config = llm_config.with_max_tokens(max_tokens);
+    }
+
+    let rag_service = get_or_init_rag_service(database.inner().clone()).await?;
+    let stream_id = stream_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let sid = stream_id.clone();
+
+    tokio::spawn(async move {
+        let streamer = LlmAnswerStreamer(sentinel_llm::StreamingLlmClient::new(llm_config));
+        let app = app_handle.clone();
+        let event_sid = sid.clone();
+        let result = rag_service
+            .stream_assistant_answer(&request, &streamer, move |event| {
+                let emitted = match event {
+                    sentinel_rag::answer::RagAnswerEvent::Delta(delta) => app.emit(
+                        "rag_answer_delta",
+                        serde_json::json!({ "stream_id": event_sid, "delta": delta }),
+                    ),
+                    sentinel_rag::answer::RagAnswerEvent::Cited(citation) => app.emit(
+                        "rag_answer_citation",
+                        serde_json::json!({ "stream_id": event_sid, "citation": citation }),
+                    ),
+                    sentinel_rag::answer::RagAnswerEvent::Completed(citations) => app.emit(
+                        "rag_answer_citations",
+                        serde_json::json!({ "stream_id": event_sid, "citations": citations }),
+                    ),
+                };
+                if let Err(e) = emitted {
+                    warn!("Failed to emit rag answer event: {}", e);
+                }
+                true
+            })
+            .await;
+
+        match result {
+            Ok(response) => {
+                let _ = app_handle.emit(
+                    "rag_answer_complete",
+                    serde_json::json!({ "stream_id": sid, "response": response }),
+                );
+            }
+            Err(e) => {
+                warn!("RAG流式回答失败: {}", e);
+                let _ = app_handle.emit(
+                    "rag_answer_error",
+                    serde_json::json!({ "stream_id": sid, "error": e.to_string() }),
+                );
+            }
+        }
+    });
+
+    Ok(stream_id)
+}
+
 /// 清空RAG集合
 #[tauri::command]
 pub async fn rag_clear_collection(
@@ -1168,51 +1483,74 @@ pub async fn get_active_rag_collections(
         .collect())
 }
 
+/// 用 AI 提供商配置中的 api_base / api_key 覆盖嵌入配置
+fn apply_ai_provider_credentials(
+    providers: &serde_json::Value,
+    embedding_config: &mut sentinel_rag::config::EmbeddingConfig,
+) {
+    let Some(providers_obj) = providers.as_object() else {
+        return;
+    };
+    for provider_data in providers_obj.values() {
+        let Some(provider_obj) = provider_data.as_object() else {
+            continue;
+        };
+        let Some(provider_name) = provider_obj.get("provider").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        if provider_name.eq_ignore_ascii_case(&embedding_config.provider) {
+            if let Some(api_base) = provider_obj.get("api_base").and_then(|v| v.as_str()) {
+                if !api_base.is_empty() {
+                    embedding_config.base_url = Some(api_base.to_string());
+                }
+            }
+            if let Some(api_key) = provider_obj.get("api_key").and_then(|v| v.as_str()) {
+                if !api_key.is_empty() {
+                    embedding_config.api_key = Some(api_key.to_string());
+                }
+            }
+            break;
+        }
+    }
+}
+
 /// 测试嵌入连接
+///
+/// 请求中可带 `fallbacks` 指定降级提供商，否则使用已保存 RAG 配置中的降级链。
+/// 返回值中的 `active_provider` 为实际生成测试向量的提供商。
 #[tauri::command]
 pub async fn test_embedding_connection(
     config: serde_json::Value,
     database: State<'_, Arc<DatabaseService>>,
 ) -> Result<serde_json::Value, String> {
     use sentinel_rag::config::EmbeddingConfig;
-    use sentinel_rag::embeddings::create_embedding_provider;
+    use sentinel_rag::embeddings::{
+        create_fallback_embedding_provider, CircuitBreakerPolicy, EmbeddingProvider,
+    };
 
     info!("测试嵌入连接");
 
     // 解析配置
     let mut embedding_config: EmbeddingConfig =
-        serde_json::from_value(config).map_err(|e| format!("解析嵌入配置失败: {}", e))?;
+        serde_json::from_value(config.clone()).map_err(|e| format!("解析嵌入配置失败: {}", e))?;
+
+    let saved_config = match database.get_rag_config().await {
+        Ok(Some(core)) => convert_core_to_rag(core),
+        _ => RagConfigRag::default(),
+    };
+    let mut fallbacks: Vec<EmbeddingConfig> = match config.get("fallbacks") {
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| format!("解析降级嵌入配置失败: {}", e))?,
+        None => saved_config.embedding_fallbacks.clone(),
+    };
 
     // 从 AI 配置中获取 api_key 和 base_url
     match database.get_config("ai", "providers_config").await {
         Ok(Some(providers_json)) => {
             if let Ok(providers) = serde_json::from_str::<serde_json::Value>(&providers_json) {
-                if let Some(providers_obj) = providers.as_object() {
-                    for (_key, provider_data) in providers_obj {
-                        if let Some(provider_obj) = provider_data.as_object() {
-                            if let Some(provider_name) =
-                                provider_obj.get("provider").and_then(|v| v.as_str())
-                            {
-                                if provider_name.eq_ignore_ascii_case(&embedding_config.provider) {
-                                    if let Some(api_base) =
-                                        provider_obj.get("api_base").and_then(|v| v.as_str())
-                                    {
-                                        if !api_base.is_empty() {
-                                            embedding_config.base_url = Some(api_base.to_string());
-                                        }
-                                    }
-                                    if let Some(api_key) =
-                                        provider_obj.get("api_key").and_then(|v| v.as_str())
-                                    {
-                                        if !api_key.is_empty() {
-                                            embedding_config.api_key = Some(api_key.to_string());
-                                        }
-                                    }
-                                    break;
-                                }
-                            }
-                        }
-                    }
+                apply_ai_provider_credentials(&providers, &mut embedding_config);
+                for fallback in &mut fallbacks {
+                    apply_ai_provider_credentials(&providers, fallback);
                 }
             }
         }
@@ -1221,9 +1559,29 @@ pub async fn test_embedding_connection(
         }
     }
 
-    // 创建嵌入提供商
-    let provider = create_embedding_provider(&embedding_config)
-        .map_err(|e| format!("创建嵌入提供商失败: {}", e))?;
+    // 创建嵌入提供商（主提供商 + 降级链）
+    let mut configs = vec![embedding_config];
+    configs.extend(fallbacks);
+    let provider = create_fallback_embedding_provider(
+        &configs,
+        std::time::Duration::from_secs(saved_config.embedding_timeout_secs),
+        CircuitBreakerPolicy::default(),
+    )
+    .map_err(|e| format!("创建嵌入提供商失败: {}", e))?;
+
+    // 降级提供商维度与主提供商不一致时，降级后的向量无法与已有向量混用
+    let dimension_warning = if configs.len() > 1 {
+        provider
+            .check_dimensions()
+            .await
+            .err()
+            .map(|e| e.to_string())
+    } else {
+        None
+    };
+    if let Some(warning) = &dimension_warning {
+        warn!("{}", warning);
+    }
 
     // 测试嵌入生成
     let test_texts = vec!["Hello world".to_string(), "Test embedding".to_string()];
@@ -1231,26 +1589,41 @@ pub async fn test_embedding_connection(
     match provider.embed_texts(&test_texts).await {
         Ok(embeddings) => {
             let dimension = provider.get_embedding_dimension().await.unwrap_or(0);
+            let active_provider = provider.active_provider().unwrap_or_default();
+            let fallback_used = provider.chain().active_index().unwrap_or(0) > 0;
             info!(
-                "嵌入连接测试成功: 提供商={}, 模型={}, 维度={}, 测试向量数={}",
+                "嵌入连接测试成功: 提供商={}, 模型={}, 维度={}, 测试向量数={}, 降级={}",
+                provider.provider_name(),
+                provider.model_name(),
+                dimension,
+                embeddings.len(),
+                fallback_used
+            );
+
+            let mut message = format!(
+                "Successfully connected to {} ({}), dimension: {}, generated {} test embeddings",
                 provider.provider_name(),
                 provider.model_name(),
                 dimension,
                 embeddings.len()
             );
+            if fallback_used {
+                message.push_str(&format!(
+                    " (primary provider unavailable, using fallback {})",
+                    active_provider
+                ));
+            }
 
             Ok(serde_json::json!({
                 "success": true,
-                "message": format!(
-                    "Successfully connected to {} ({}), dimension: {}, generated {} test embeddings",
-                    provider.provider_name(),
-                    provider.model_name(),
-                    dimension,
-                    embeddings.len()
-                ),
+                "message": message,
                 "provider": provider.provider_name(),
                 "model": provider.model_name(),
+                "active_provider": active_provider,
+                "fallback_used": fallback_used,
+                "providers": provider.chain().status(),
                 "dimension": dimension,
+                "dimension_warning": dimension_warning,
                 "test_embeddings_count": embeddings.len()
             }))
         }
@@ -1262,7 +1635,10 @@ pub async fn test_embedding_connection(
                 "success": false,
                 "message": error_msg,
                 "provider": provider.provider_name(),
-                "model": provider.model_name()
+                "model": provider.model_name(),
+                "active_provider": serde_json::Value::Null,
+                "providers": provider.chain().status(),
+                "dimension_warning": dimension_warning
             }))
         }
     }
@@ -2,6 +2,7 @@ use crate::services::DatabaseService;
 use anyhow::Result;
 use sentinel_db::core::models::scan_session::*;
 use sentinel_db::Database;
+use sentinel_db::{ScanBundleImport, ScanBundleManifest};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tauri::State;
@@ -39,7 +40,28 @@ pub struct ProgressResponse {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct StagesResponse {
     pub success: bool,
-    pub data: Vec<ScanStage>,
+    pub data: Vec<ScanStageWithEstimate>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SessionDiffResponse {
+    pub success: bool,
+    pub data: Option<ScanSessionDiff>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleExportResponse {
+    pub success: bool,
+    pub data: Option<ScanBundleManifest>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleImportResponse {
+    pub success: bool,
+    pub data: Option<ScanBundleImport>,
     pub message: Option<String>,
 }
 
@@ -191,7 +213,7 @@ pub async fn get_scan_progress(
     }
 }
 
-/// 获取会话的扫描阶段
+/// 获取会话的扫描阶段（含耗时估算）
 #[tauri::command]
 pub async fn get_session_stages(
     session_id: String,
@@ -202,7 +224,7 @@ pub async fn get_session_stages(
     match db.inner().get_scan_session_stages(uuid).await {
         Ok(stages) => Ok(StagesResponse {
             success: true,
-            data: stages,
+            data: ScanStageWithEstimate::estimate(stages, chrono::Utc::now()),
             message: None,
         }),
         Err(e) => Ok(StagesResponse {
@@ -212,3 +234,79 @@ pub async fn get_session_stages(
         }),
     }
 }
+
+/// 对比两次扫描会话：返回新增、已修复与持续存在的发现和资产
+#[tauri::command]
+pub async fn diff_scan_sessions(
+    base_session_id: String,
+    compare_session_id: String,
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<SessionDiffResponse, String> {
+    let base = Uuid::parse_str(&base_session_id).map_err(|e| format!("无效的会话ID: {}", e))?;
+    let compare =
+        Uuid::parse_str(&compare_session_id).map_err(|e| format!("无效的会话ID: {}", e))?;
+
+    match db.inner().diff_scan_sessions(base, compare).await {
+        Ok(diff) => Ok(SessionDiffResponse {
+            success: true,
+            data: Some(diff),
+            message: None,
+        }),
+        Err(e) => Ok(SessionDiffResponse {
+            success: false,
+            data: None,
+            message: Some(format!("对比扫描会话失败: {}", e)),
+        }),
+    }
+}
+
+/// 将扫描会话导出为便携的 zip 包（会话、发现、证据、资产与关联请求的 HAR）
+#[tauri::command]
+pub async fn export_scan_session_bundle(
+    session_id: String,
+    path: String,
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<BundleExportResponse, String> {
+    let uuid = Uuid::parse_str(&session_id).map_err(|e| format!("无效的会话ID: {}", e))?;
+
+    match db
+        .inner()
+        .export_scan_session_bundle(uuid, std::path::Path::new(&path))
+        .await
+    {
+        Ok(manifest) => Ok(BundleExportResponse {
+            success: true,
+            data: Some(manifest),
+            message: None,
+        }),
+        Err(e) => Ok(BundleExportResponse {
+            success: false,
+            data: None,
+            message: Some(format!("导出扫描会话失败: {}", e)),
+        }),
+    }
+}
+
+/// 导入扫描会话包，ID 冲突时自动分配新 ID
+#[tauri::command]
+pub async fn import_scan_session_bundle(
+    path: String,
+    db: State<'_, Arc<DatabaseService>>,
+) -> Result<BundleImportResponse, String> {
+    match db
+        .inner()
+        .import_scan_session_bundle(std::path::Path::new(&path))
+        .await
+    {
+        Ok(result) => Ok(BundleImportResponse {
+            success: true,
+            data: Some(result),
+            message: None,
+        }),
+        Err(e) => Ok(BundleImportResponse {
+            success: false,
+            data: None,
+            message: Some(format!("导入扫描会话失败: {}", e)),
+        }),
+    }
+}
@@ -4241,6 +4241,7 @@ fn team_v3_default_tool_config() -> ToolConfig {
         fixed_tools: vec!["interactive_shell".to_string()],
         disabled_tools: Vec::new(),
         allowed_tools: Vec::new(),
+        step_timeout_secs: None,
     }
 }
 
@@ -4536,6 +4537,8 @@ async fn generate_team_v3_execution_plan_with_main_agent(
             ..ContextPolicy::default()
         }),
         recursion_depth: 0,
+        cost_limit_usd: None,
+        subagent_limits: Default::default(),
     };
     let planner_output = tokio::select! {
         _ = cancellation_token.cancelled() => Err(anyhow!("Team execution cancelled")),
@@ -4982,6 +4985,8 @@ async fn run_team_v3_execution_orchestrator(
                         ..ContextPolicy::default()
                     }),
                     recursion_depth: 0,
+                    cost_limit_usd: None,
+                    subagent_limits: Default::default(),
                 };
                 let execution_result = tokio::select! {
                     _ = cancel_token.cancelled() => Err(anyhow!("Team execution cancelled")),
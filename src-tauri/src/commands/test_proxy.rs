@@ -1,6 +1,6 @@
 //! 代理配置测试模块
 
-use sentinel_core::global_proxy::get_global_proxy;
+use sentinel_core::global_proxy::{get_global_proxy, ProxyHealthStatus};
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
 use tracing::{info, warn};
@@ -99,6 +99,18 @@ pub async fn get_current_proxy_config() -> Result<Option<serde_json::Value>, Str
     ))
 }
 
+/// 获取各上游代理的健康状态
+#[tauri::command]
+pub async fn get_proxy_health() -> Result<Vec<ProxyHealthStatus>, String> {
+    Ok(sentinel_core::global_proxy::get_proxy_health().await)
+}
+
+/// 立即检查所有上游代理
+#[tauri::command]
+pub async fn check_proxy_health() -> Result<Vec<ProxyHealthStatus>, String> {
+    Ok(sentinel_core::global_proxy::check_proxy_health().await)
+}
+
 /// 辅助函数：屏蔽密码
 fn mask_password(url: &str) -> String {
     if let Some(at_pos) = url.find('@') {
@@ -41,6 +41,9 @@ pub struct ToolExecutionResult {
     pub success: bool,
     pub output: Option<serde_json::Value>,
     pub error: Option<String>,
+    /// Argument schema violations when the call was rejected before execution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation_errors: Option<Vec<sentinel_tools::SchemaViolation>>,
     pub execution_time_ms: u64,
 }
 
@@ -171,6 +174,10 @@ pub async fn get_builtin_tools_with_status() -> Result<Vec<BuiltinToolInfo>, Str
                         "type": "boolean",
                         "description": "Follow redirects",
                         "default": true
+                    },
+                    "proxy": {
+                        "type": "string",
+                        "description": "Proxy URL for this request only (http/https/socks5/socks5h), or \"direct\" to bypass the global proxy"
                     }
                 },
                 "required": ["url"]
@@ -1038,7 +1045,7 @@ pub async fn toggle_builtin_tool(tool_name: String, enabled: bool) -> Result<(),
 pub async fn unified_execute_tool(
     tool_name: String,
     inputs: serde_json::Value,
-    _context: Option<serde_json::Value>,
+    context: Option<serde_json::Value>,
     _timeout: Option<u64>,
 ) -> Result<ToolExecutionResult, String> {
     let start = std::time::Instant::now();
@@ -1050,6 +1057,7 @@ pub async fn unified_execute_tool(
             success: false,
             output: None,
             error: Some("License required for tool execution".to_string()),
+            validation_errors: None,
             execution_time_ms: start.elapsed().as_millis() as u64,
         });
     }
@@ -1062,6 +1070,7 @@ pub async fn unified_execute_tool(
                 success: false,
                 output: None,
                 error: Some(format!("Tool '{}' is disabled", tool_name)),
+                validation_errors: None,
                 execution_time_ms: start.elapsed().as_millis() as u64,
             });
         }
@@ -1071,12 +1080,38 @@ pub async fn unified_execute_tool(
     let tool_server = get_tool_server();
     tool_server.init_builtin_tools().await;
 
-    let result = tool_server.execute(&tool_name, inputs).await;
+    // Calls made on behalf of an execution stop when that execution is cancelled
+    let scope_id = context
+        .as_ref()
+        .and_then(|c| c.get("execution_id"))
+        .and_then(|v| v.as_str())
+        .filter(|id| !id.is_empty());
+    let result = match scope_id {
+        Some(scope_id) => {
+            sentinel_tools::cancellation::with_call_scope(scope_id, async {
+                sentinel_tools::cancellation::run_cancellable(
+                    tool_server.execute(&tool_name, inputs),
+                )
+                .await
+                .unwrap_or_else(|e| sentinel_tools::ToolResult {
+                    success: false,
+                    tool_name: tool_name.clone(),
+                    output: None,
+                    error: Some(e.to_string()),
+                    validation_errors: None,
+                    execution_time_ms: start.elapsed().as_millis() as u64,
+                })
+            })
+            .await
+        }
+        None => tool_server.execute(&tool_name, inputs).await,
+    };
 
     Ok(ToolExecutionResult {
         success: result.success,
         output: result.output,
         error: result.error,
+        validation_errors: result.validation_errors,
         execution_time_ms: result.execution_time_ms,
     })
 }
@@ -1169,6 +1204,7 @@ async fn execute_workflow_tool(
         success: false,
         output: None,
         error: Some(format!("Workflow tool '{}' execution not yet implemented. Please use WorkflowStudio to run workflows.", workflow_id)),
+        validation_errors: None,
         execution_time_ms: start.elapsed().as_millis() as u64,
     })
 }
@@ -1762,18 +1798,51 @@ pub async fn get_tool_usage_stats() -> Result<ToolUsageStatistics, String> {
     Ok(get_tool_usage_statistics().await)
 }
 
-/// Clear tool usage records
+/// Get per-tool call counts, error rates and latency percentiles
+#[tauri::command]
+pub async fn get_tool_performance_stats() -> Result<Vec<sentinel_tools::ToolLatencyStats>, String> {
+    Ok(sentinel_tools::get_tool_latency_stats())
+}
+
+/// Clear tool usage records and latency histograms
 #[tauri::command]
 pub async fn clear_tool_usage_stats() -> Result<(), String> {
     clear_tool_usage_records().await;
+    sentinel_tools::reset_tool_metrics();
+    Ok(())
+}
+
+/// Get tool concurrency limits
+#[tauri::command]
+pub async fn get_tool_concurrency_config() -> Result<sentinel_tools::ToolConcurrencyConfig, String>
+{
+    Ok(sentinel_tools::get_tool_concurrency_config().await)
+}
+
+/// Update tool concurrency limits
+#[tauri::command]
+pub async fn set_tool_concurrency_config(
+    config: sentinel_tools::ToolConcurrencyConfig,
+) -> Result<(), String> {
+    if config.max_concurrent == 0 {
+        return Err("max_concurrent must be greater than 0".to_string());
+    }
+    sentinel_tools::set_tool_concurrency_config(config).await;
     Ok(())
 }
 
+/// Get running and queued tool call counts
+#[tauri::command]
+pub async fn get_tool_queue_status() -> Result<sentinel_tools::ToolQueueStatus, String> {
+    Ok(sentinel_tools::get_tool_queue_status().await)
+}
+
 pub mod tool_server;
 pub use tool_server::{
     execute_tool_server_tool, get_tool_input_schema, get_tool_output_schema, get_tool_server_stats,
     get_tool_server_tool, init_tool_server, list_tool_server_tools, list_tools_by_source,
     refresh_all_dynamic_tools, register_mcp_tools_from_server, register_workflow_tools,
+    set_tool_schema_validation,
 };
 
 // ============================================================================
@@ -1911,6 +1980,18 @@ pub async fn refresh_skills_index(
     skills::refresh_skills_index(db_service).await
 }
 
+#[tauri::command]
+pub async fn start_skills_watcher(
+    db_service: tauri::State<'_, Arc<sentinel_db::DatabaseService>>,
+) -> Result<bool, String> {
+    skills::start_skills_watcher(db_service).await
+}
+
+#[tauri::command]
+pub async fn stop_skills_watcher() -> Result<bool, String> {
+    skills::stop_skills_watcher().await
+}
+
 #[tauri::command]
 pub async fn list_skill_files(
     id: String,
@@ -6,6 +6,7 @@ use std::sync::Arc;
 use sentinel_db::{Database, Skill, SkillDetail, SkillSummary};
 use serde::{Deserialize, Serialize};
 
+use crate::skills::watcher::{self, SkillIndexSink};
 use crate::skills::{
     parse_skill_markdown, scan_and_upsert_skills, skills_root, validate_skill_with_skills_ref,
 };
@@ -425,6 +426,22 @@ pub async fn refresh_skills_index(
         .map_err(|e| e.to_string())
 }
 
+/// Start watching the skills directory and reindex changed skills automatically
+pub async fn start_skills_watcher(
+    db_service: tauri::State<'_, Arc<sentinel_db::DatabaseService>>,
+) -> Result<bool, String> {
+    let root = skills_root(&db_service);
+    let sink: Arc<dyn SkillIndexSink> = db_service.inner().clone();
+    watcher::start_skills_watcher(&root, sink)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Stop the skills directory watcher
+pub async fn stop_skills_watcher() -> Result<bool, String> {
+    Ok(watcher::stop_skills_watcher().await)
+}
+
 #[derive(Debug, Serialize)]
 pub struct SkillFileEntry {
     pub path: String,
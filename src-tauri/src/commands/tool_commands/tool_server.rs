@@ -118,6 +118,7 @@ pub async fn execute_tool_server_tool(
             tool_name: tool_name.clone(),
             output: None,
             error: Some("License required for tool execution".to_string()),
+            validation_errors: None,
             execution_time_ms: 0,
         });
     }
@@ -127,6 +128,21 @@ pub async fn execute_tool_server_tool(
     Ok(server.execute(&tool_name, args).await)
 }
 
+/// Set argument schema validation mode for a tool (strict, warn, off)
+#[tauri::command]
+pub async fn set_tool_schema_validation(
+    tool_name: String,
+    mode: sentinel_tools::SchemaValidation,
+) -> Result<(), String> {
+    let server = get_tool_server();
+    server.init_builtin_tools().await;
+    if !server.set_schema_validation(&tool_name, mode).await {
+        return Err(format!("Tool not found: {}", tool_name));
+    }
+    tracing::info!("Tool '{}' schema validation set to {:?}", tool_name, mode);
+    Ok(())
+}
+
 /// Get tool server statistics
 #[tauri::command]
 pub async fn get_tool_server_stats() -> Result<serde_json::Value, String> {
@@ -3,6 +3,7 @@
 //! 提供前端调用的流量分析相关命令：
 //! - start_traffic_analysis: 启动流量分析代理
 //! - stop_traffic_analysis: 停止流量分析代理
+//! - pause_passive_scan / resume_passive_scan: 暂停/恢复被动扫描（代理保持监听）
 //! - get_proxy_status: 获取代理状态
 //! - list_findings: 列出漏洞发现
 //! - enable_plugin: 启用插件
@@ -19,10 +20,10 @@ use tokio::sync::{mpsc::UnboundedSender, RwLock};
 use sentinel_traffic::{
     CertificateService, EvidenceRecord, Finding, FindingDeduplicator,
     InterceptAction as TrafficInterceptAction, InterceptFilterRule as TrafficInterceptFilterRule,
-    InterceptState, PendingInterceptRequest, PendingInterceptResponse,
+    InterceptState, MatchReplaceRule, PendingInterceptRequest, PendingInterceptResponse,
     PendingInterceptWebSocketMessage, PluginManager, PluginMetadata, PluginRecord, PluginStatus,
-    ProxyConfig, ProxyService, ProxyStats, ProxyStatus, ScanPipeline, ScanTask,
-    VulnerabilityFilters, VulnerabilityRecord,
+    ProxyConfig, ProxyService, ProxyStats, ProxyStatus, ScanPipeline, ScanTask, SuppressionRule,
+    VulnerabilityFilters, VulnerabilityRecord, VulnerabilityWithEvidence,
 };
 
 use sentinel_db::DatabaseService;
@@ -117,10 +118,22 @@ pub struct TrafficAnalysisState {
     pub response_filter_rules: Arc<RwLock<Vec<TrafficInterceptFilterRule>>>,
     /// Finding去重缓存（用于删除漏洞时清理）
     pub dedupe_cache: Arc<RwLock<std::collections::HashSet<String>>>,
+    /// 误报抑制规则（去重阶段使用）
+    pub suppression_rules: sentinel_traffic::SharedSuppressionRules,
+    /// Finding 去重键与再次上报窗口配置
+    pub dedup_config: sentinel_traffic::SharedDedupConfig,
+    /// 内置安全响应头检查规则
+    pub security_header_config: sentinel_traffic::SharedSecurityHeaderConfig,
     /// 是否排除本应用流量的扫描
     pub exclude_self_traffic: Arc<RwLock<bool>>,
     /// 是否启用流量分析插件扫描
     pub plugin_scanning_enabled: Arc<RwLock<bool>>,
+    /// 是否从代理流量中被动提取资产
+    pub passive_asset_extraction_enabled: Arc<RwLock<bool>>,
+    /// 被动扫描是否已暂停（代理和 CA 保持不变，仅跳过插件分发，历史照常记录）
+    pub scan_paused: Arc<RwLock<bool>>,
+    /// 匹配替换规则（与代理共享，修改后即时生效）
+    pub match_replace_rules: sentinel_traffic::SharedMatchReplaceRules,
 }
 
 /// 内部使用的拦截 WebSocket 消息结构（包含响应通道）
@@ -157,8 +170,14 @@ impl Clone for TrafficAnalysisState {
             request_filter_rules: self.request_filter_rules.clone(),
             response_filter_rules: self.response_filter_rules.clone(),
             dedupe_cache: self.dedupe_cache.clone(),
+            suppression_rules: self.suppression_rules.clone(),
+            dedup_config: self.dedup_config.clone(),
+            security_header_config: self.security_header_config.clone(),
             exclude_self_traffic: self.exclude_self_traffic.clone(),
             plugin_scanning_enabled: self.plugin_scanning_enabled.clone(),
+            passive_asset_extraction_enabled: self.passive_asset_extraction_enabled.clone(),
+            scan_paused: self.scan_paused.clone(),
+            match_replace_rules: self.match_replace_rules.clone(),
         }
     }
 }
@@ -205,8 +224,14 @@ impl TrafficAnalysisState {
             request_filter_rules: Arc::new(RwLock::new(Vec::new())),
             response_filter_rules: Arc::new(RwLock::new(Vec::new())),
             dedupe_cache: Arc::new(RwLock::new(std::collections::HashSet::new())),
+            suppression_rules: Arc::new(RwLock::new(Vec::new())),
+            dedup_config: Arc::new(RwLock::new(Default::default())),
+            security_header_config: Arc::new(RwLock::new(Default::default())),
             exclude_self_traffic: Arc::new(RwLock::new(true)),
             plugin_scanning_enabled: Arc::new(RwLock::new(true)), // 默认启用
+            passive_asset_extraction_enabled: Arc::new(RwLock::new(false)),
+            scan_paused: Arc::new(RwLock::new(false)),
+            match_replace_rules: Arc::new(RwLock::new(Vec::new())),
         }
     }
 
@@ -461,6 +486,9 @@ pub async fn start_traffic_analysis_internal(
             match_type: rule.match_type.clone(),
             relationship: rule.relationship.clone(),
             condition: rule.condition.clone(),
+            method: rule.method.clone(),
+            request_content_type: rule.request_content_type.clone(),
+            response_content_type: rule.response_content_type.clone(),
         };
 
         if rule.rule_type == "request" {
@@ -499,6 +527,44 @@ pub async fn start_traffic_analysis_internal(
         );
     }
 
+    // 从数据库加载被动资产提取开关（默认关闭）
+    let passive_asset_extraction_enabled = matches!(
+        db_service
+            .load_proxy_config("passive_asset_extraction_enabled")
+            .await,
+        Ok(Some(value)) if value == "true"
+    );
+    *state.passive_asset_extraction_enabled.write().await = passive_asset_extraction_enabled;
+
+    // 从数据库加载 Finding 去重配置
+    if let Ok(Some(value)) = db_service.load_proxy_config(DEDUP_CONFIG_KEY).await {
+        match serde_json::from_str::<sentinel_traffic::DedupConfig>(&value) {
+            Ok(config) => *state.dedup_config.write().await = config,
+            Err(e) => tracing::warn!("Invalid finding dedup config, using defaults: {}", e),
+        }
+    }
+
+    // 从数据库加载安全响应头检查配置
+    if let Ok(Some(value)) = db_service
+        .load_proxy_config(SECURITY_HEADER_CONFIG_KEY)
+        .await
+    {
+        match serde_json::from_str::<sentinel_traffic::SecurityHeaderConfig>(&value) {
+            Ok(config) => *state.security_header_config.write().await = config,
+            Err(e) => tracing::warn!("Invalid security header config, using defaults: {}", e),
+        }
+    }
+
+    // 从数据库加载匹配替换规则
+    match load_match_replace_rules(&db_service).await {
+        Ok(rules) => {
+            let compiled = sentinel_traffic::compile_match_replace_rules(&rules);
+            tracing::info!("Loaded {} active match-and-replace rules", compiled.len());
+            *state.match_replace_rules.write().await = compiled;
+        }
+        Err(e) => tracing::warn!("Failed to load match-and-replace rules: {}", e),
+    }
+
     // 创建拦截状态
     let intercept_state = InterceptState {
         enabled: state.intercept_enabled.clone(),
@@ -512,7 +578,8 @@ pub async fn start_traffic_analysis_internal(
     };
 
     // 创建代理服务（支持拦截）
-    let proxy = ProxyService::with_intercept(config, ca_dir, intercept_state);
+    let proxy = ProxyService::with_intercept(config, ca_dir, intercept_state)
+        .with_match_replace_rules(state.match_replace_rules.clone());
 
     // 创建扫描与发现通道（scan_rx 在单独线程内消费）
     let (scan_tx, scan_rx) = tokio::sync::mpsc::unbounded_channel();
@@ -539,6 +606,10 @@ pub async fn start_traffic_analysis_internal(
     let response_filter_rules = state.response_filter_rules.clone();
     let exclude_self_traffic = state.exclude_self_traffic.clone();
     let plugin_scanning_enabled = state.plugin_scanning_enabled.clone();
+    let passive_asset_extraction_enabled = state.passive_asset_extraction_enabled.clone();
+    let scan_paused = state.scan_paused.clone();
+    let security_header_config = state.security_header_config.clone();
+    let plugin_metrics = state.plugin_manager.metrics();
     std::thread::spawn(move || {
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
@@ -556,7 +627,11 @@ pub async fn start_traffic_analysis_internal(
                         .with_request_filter_rules(request_filter_rules)
                         .with_response_filter_rules(response_filter_rules)
                         .with_exclude_self_traffic(exclude_self_traffic)
-                        .with_plugin_scanning_enabled(plugin_scanning_enabled);
+                        .with_plugin_scanning_enabled(plugin_scanning_enabled)
+                        .with_passive_asset_extraction(passive_asset_extraction_enabled)
+                        .with_scan_paused(scan_paused)
+                        .with_security_header_config(security_header_config)
+                        .with_plugin_metrics(plugin_metrics);
                     match pipeline
                         .load_enabled_plugins_from_db(&db_for_pipeline)
                         .await
@@ -576,9 +651,14 @@ pub async fn start_traffic_analysis_internal(
 
     // 启动 Finding 去重服务（带数据库和事件发送，使用共享缓存）
     let dedupe_cache_for_dedup = state.dedupe_cache.clone();
+    if let Err(e) = reload_suppression_rules(state).await {
+        tracing::warn!("Failed to load suppression rules: {}", e);
+    }
     let deduplicator = FindingDeduplicator::with_database(finding_rx, db_service.clone())
         .with_event_sender(event_tx)
-        .with_shared_cache(dedupe_cache_for_dedup);
+        .with_shared_cache(dedupe_cache_for_dedup)
+        .with_suppression_rules(state.suppression_rules.clone())
+        .with_dedup_config(state.dedup_config.clone());
     tokio::spawn(async move {
         if let Err(e) = deduplicator.start().await {
             tracing::error!("FindingDeduplicator error: {}", e);
@@ -833,6 +913,7 @@ pub async fn start_traffic_analysis_internal(
     // 保存服务实例
     *state.proxy_service.write().await = Some(proxy);
     *is_running = true;
+    update_tray_scan_status(app, true, *state.scan_paused.read().await);
 
     // 发射代理状态事件
     emit_proxy_status(
@@ -841,6 +922,7 @@ pub async fn start_traffic_analysis_internal(
             running: true,
             port,
             mitm: true,
+            paused: *state.scan_paused.read().await,
             stats: ProxyStats::default(),
         },
     );
@@ -892,6 +974,9 @@ pub async fn stop_traffic_analysis_internal(
     }
 
     *is_running = false;
+    // 停止后清除暂停状态，下次启动时恢复完整扫描
+    *state.scan_paused.write().await = false;
+    update_tray_scan_status(app, false, false);
 
     // 发射代理停止事件
     emit_proxy_status(
@@ -900,6 +985,7 @@ pub async fn stop_traffic_analysis_internal(
             running: false,
             port: 0,
             mitm: false,
+            paused: false,
             stats: ProxyStats::default(),
         },
     );
@@ -920,6 +1006,74 @@ pub async fn stop_traffic_analysis(
     }
 }
 
+/// 内部暂停/恢复函数：切换被动扫描暂停标志，代理与 CA 保持不变
+pub async fn set_passive_scan_paused_internal(
+    app: &AppHandle,
+    state: &TrafficAnalysisState,
+    paused: bool,
+) -> Result<(), String> {
+    if !*state.is_running.read().await {
+        return Err("Proxy not running".to_string());
+    }
+
+    *state.scan_paused.write().await = paused;
+
+    let (port, stats) = match state.proxy_service.read().await.as_ref() {
+        Some(proxy) => (proxy.get_port().await.unwrap_or(0), proxy.get_stats().await),
+        None => (0, ProxyStats::default()),
+    };
+    emit_proxy_status(
+        app,
+        ProxyStatusEvent {
+            running: true,
+            port,
+            mitm: true,
+            paused,
+            stats,
+        },
+    );
+    update_tray_scan_status(app, true, paused);
+
+    tracing::info!("Passive scan {}", if paused { "paused" } else { "resumed" });
+    Ok(())
+}
+
+/// 暂停被动扫描（代理继续转发并记录历史，但不分发给插件）
+#[tauri::command]
+pub async fn pause_passive_scan(
+    app: AppHandle,
+    state: State<'_, TrafficAnalysisState>,
+) -> Result<CommandResponse<String>, String> {
+    match set_passive_scan_paused_internal(&app, &state, true).await {
+        Ok(_) => Ok(CommandResponse::ok("Passive scan paused".to_string())),
+        Err(e) => Ok(CommandResponse::err(e)),
+    }
+}
+
+/// 恢复被动扫描
+#[tauri::command]
+pub async fn resume_passive_scan(
+    app: AppHandle,
+    state: State<'_, TrafficAnalysisState>,
+) -> Result<CommandResponse<String>, String> {
+    match set_passive_scan_paused_internal(&app, &state, false).await {
+        Ok(_) => Ok(CommandResponse::ok("Passive scan resumed".to_string())),
+        Err(e) => Ok(CommandResponse::err(e)),
+    }
+}
+
+/// 更新托盘提示，区分运行中 / 已暂停 / 已停止
+fn update_tray_scan_status(app: &AppHandle, running: bool, paused: bool) {
+    if let Some(tray) = app.tray_by_id("main") {
+        let tooltip = match (running, paused) {
+            (true, true) => "Sentinel AI - 被动扫描已暂停",
+            (true, false) => "Sentinel AI - 代理运行中",
+            (false, _) => "Sentinel AI",
+        };
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
+}
+
 /// 重载插件（在运行时热更新插件代码）
 #[tauri::command]
 pub async fn reload_plugin_in_pipeline(
@@ -966,6 +1120,7 @@ pub async fn get_proxy_status(
                 running: true,
                 port,
                 mitm_enabled: true,
+                paused: *state.scan_paused.read().await,
                 stats,
             }
         } else {
@@ -973,6 +1128,7 @@ pub async fn get_proxy_status(
                 running: false,
                 port: 0,
                 mitm_enabled: false,
+                paused: false,
                 stats: ProxyStats::default(),
             }
         }
@@ -981,6 +1137,7 @@ pub async fn get_proxy_status(
             running: false,
             port: 0,
             mitm_enabled: false,
+            paused: false,
             stats: ProxyStats::default(),
         }
     };
@@ -1043,6 +1200,64 @@ pub async fn count_findings(
     }
 }
 
+/// 分页查询漏洞发现，支持服务端过滤（严重程度、状态、插件、主机、时间范围、关键词）
+#[tauri::command]
+pub async fn query_findings(
+    state: State<'_, TrafficAnalysisState>,
+    filters: VulnerabilityFilters,
+) -> Result<CommandResponse<sentinel_traffic::VulnerabilityPage>, String> {
+    let db_service = state.get_db_service();
+    match db_service.list_traffic_vulnerabilities_page(filters).await {
+        Ok(page) => Ok(CommandResponse::ok(page)),
+        Err(e) => {
+            tracing::error!("Failed to query findings: {}", e);
+            Ok(CommandResponse::err(format!("Database error: {}", e)))
+        }
+    }
+}
+
+/// 全文搜索漏洞发现（标题、描述、URL、证据）
+#[tauri::command]
+pub async fn search_findings(
+    state: State<'_, TrafficAnalysisState>,
+    query: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<CommandResponse<Vec<sentinel_db::TrafficVulnerabilityRecord>>, String> {
+    let db_service = state.get_db_service();
+    match db_service
+        .search_traffic_vulnerabilities(&query, limit.unwrap_or(50), offset.unwrap_or(0))
+        .await
+    {
+        Ok(records) => Ok(CommandResponse::ok(records)),
+        Err(e) => {
+            tracing::error!("Failed to search findings: {}", e);
+            Ok(CommandResponse::err(format!("Search error: {}", e)))
+        }
+    }
+}
+
+/// 全文搜索代理历史（URL、请求/响应头和正文）
+#[tauri::command]
+pub async fn search_proxy_history(
+    state: State<'_, TrafficAnalysisState>,
+    query: String,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<CommandResponse<Vec<sentinel_db::ProxyRequestRecord>>, String> {
+    let db_service = state.get_db_service();
+    match db_service
+        .search_proxy_requests(&query, limit.unwrap_or(50), offset.unwrap_or(0))
+        .await
+    {
+        Ok(records) => Ok(CommandResponse::ok(records)),
+        Err(e) => {
+            tracing::error!("Failed to search proxy history: {}", e);
+            Ok(CommandResponse::err(format!("Search error: {}", e)))
+        }
+    }
+}
+
 /// Agent 审计发现输入
 
 // （已移除）文件路径加载插件命令。插件仅从数据库读取。
@@ -1188,6 +1403,23 @@ pub async fn list_plugins(
     Ok(CommandResponse::ok(plugins))
 }
 
+/// 获取插件执行指标（调用次数、耗时分位数、Finding 数、错误数）
+#[tauri::command]
+pub async fn get_plugin_metrics(
+    state: State<'_, TrafficAnalysisState>,
+) -> Result<CommandResponse<Vec<sentinel_traffic::PluginExecutionMetrics>>, String> {
+    let metrics = state.plugin_manager.metrics();
+    if !metrics.is_restored() {
+        let records = state
+            .db_service
+            .list_plugin_metrics()
+            .await
+            .map_err(|e| format!("Failed to load plugin metrics: {}", e))?;
+        metrics.restore(records);
+    }
+    Ok(CommandResponse::ok(metrics.snapshot()))
+}
+
 // （已移除）扫描插件目录命令。插件仅从数据库读取。
 
 // ============================================================================
@@ -1399,6 +1631,8 @@ pub async fn regenerate_ca_cert(
 
         // 更新运行状态
         *state.is_running.write().await = false;
+        *state.scan_paused.write().await = false;
+        update_tray_scan_status(&app, false, false);
 
         // 发送停止事件
         emit_proxy_status(
@@ -1407,6 +1641,7 @@ pub async fn regenerate_ca_cert(
                 running: false,
                 port: 0,
                 mitm: false,
+                paused: false,
                 stats: ProxyStats::default(),
             },
         );
@@ -1624,6 +1859,8 @@ pub struct FindingDetail {
     pub vulnerability: VulnerabilityRecord,
     /// 相关证据列表
     pub evidence: Vec<EvidenceRecord>,
+    /// 状态变更记录（按时间先后）
+    pub status_history: Vec<sentinel_db::TrafficStatusChange>,
 }
 
 /// 根据 ID 获取漏洞详情（包含所有证据）
@@ -1653,9 +1890,16 @@ pub async fn get_finding(
         .await
         .map_err(|e| format!("Failed to fetch evidence: {}", e))?;
 
+    // 查询状态变更记录
+    let status_history = db_service
+        .get_traffic_vulnerability_status_history(&finding_id)
+        .await
+        .map_err(|e| format!("Failed to fetch status history: {}", e))?;
+
     let detail = FindingDetail {
         vulnerability,
         evidence,
+        status_history,
     };
 
     tracing::debug!(
@@ -1667,33 +1911,65 @@ pub async fn get_finding(
     Ok(CommandResponse::ok(Some(detail)))
 }
 
+/// 允许的漏洞状态
+const VALID_FINDING_STATUSES: [&str; 6] = [
+    "open",
+    "reviewed",
+    "false_positive",
+    "not_applicable",
+    "fixed",
+    sentinel_traffic::SUPPRESSED_STATUS,
+];
+
+/// 状态变更记录中未指定操作者时使用的默认值
+const DEFAULT_STATUS_ACTOR: &str = "user";
+
+fn validate_finding_status(status: &str) -> Result<(), String> {
+    if VALID_FINDING_STATUSES.contains(&status) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid status: {}. Must be one of: {}",
+            status,
+            VALID_FINDING_STATUSES.join(", ")
+        ))
+    }
+}
+
 /// 更新漏洞状态
 #[tauri::command]
 pub async fn update_finding_status(
     state: State<'_, TrafficAnalysisState>,
     finding_id: String,
     status: String,
+    note: Option<String>,
+    actor: Option<String>,
 ) -> Result<CommandResponse<String>, String> {
     // 验证状态值
-    let valid_statuses = ["open", "reviewed", "false_positive", "fixed"];
-    if !valid_statuses.contains(&status.as_str()) {
-        return Ok(CommandResponse::err(format!(
-            "Invalid status: {}. Must be one of: {}",
-            status,
-            valid_statuses.join(", ")
-        )));
+    if let Err(e) = validate_finding_status(&status) {
+        return Ok(CommandResponse::err(e));
     }
 
     // 获取数据库服务
     let db_service = state.get_db_service();
 
-    // 更新状态
+    // 更新状态并记录变更
     db_service
-        .update_traffic_vulnerability_status(&finding_id, &status)
+        .update_traffic_vulnerabilities_status_batch(
+            std::slice::from_ref(&finding_id),
+            &status,
+            note.as_deref(),
+            actor.as_deref().unwrap_or(DEFAULT_STATUS_ACTOR),
+        )
         .await
         .map_err(|e| format!("Failed to update vulnerability status: {}", e))?;
 
     tracing::info!("Updated finding {} status to {}", finding_id, status);
+    crate::commands::finding_rag_commands::spawn_findings_auto_ingest(
+        db_service,
+        vec![finding_id.clone()],
+        &status,
+    );
 
     Ok(CommandResponse::ok(format!(
         "Finding {} status updated to {}",
@@ -1701,6 +1977,42 @@ pub async fn update_finding_status(
     )))
 }
 
+/// 批量更新漏洞状态（单个事务，任一失败则全部回滚）
+#[tauri::command]
+pub async fn update_findings_status_batch(
+    state: State<'_, TrafficAnalysisState>,
+    finding_ids: Vec<String>,
+    status: String,
+    note: Option<String>,
+    actor: Option<String>,
+) -> Result<CommandResponse<Vec<sentinel_db::TrafficStatusChange>>, String> {
+    if let Err(e) = validate_finding_status(&status) {
+        return Ok(CommandResponse::err(e));
+    }
+    if finding_ids.is_empty() {
+        return Ok(CommandResponse::ok(Vec::new()));
+    }
+
+    let db_service = state.get_db_service();
+    let changes = db_service
+        .update_traffic_vulnerabilities_status_batch(
+            &finding_ids,
+            &status,
+            note.as_deref(),
+            actor.as_deref().unwrap_or(DEFAULT_STATUS_ACTOR),
+        )
+        .await
+        .map_err(|e| format!("Failed to update vulnerability statuses: {}", e))?;
+
+    tracing::info!("Updated {} findings to status {}", changes.len(), status);
+    crate::commands::finding_rag_commands::spawn_findings_auto_ingest(
+        db_service,
+        changes.iter().map(|c| c.vuln_id.clone()).collect(),
+        &status,
+    );
+    Ok(CommandResponse::ok(changes))
+}
+
 /// HTML 报告数据结构
 #[derive(Debug, Serialize)]
 struct ReportSummary {
@@ -1760,15 +2072,7 @@ pub async fn export_findings_html(
     let db_service = state.get_db_service();
 
     // 查询漏洞数据
-    let filters = filters.unwrap_or(VulnerabilityFilters {
-        vuln_type: None,
-        severity: None,
-        status: None,
-        plugin_id: None,
-        exclude_plugin_id: None,
-        limit: Some(1000), // 默认最多导出1000条
-        offset: Some(0),
-    });
+    let filters = filters.unwrap_or_else(default_export_filters);
 
     let vulnerabilities = db_service
         .list_traffic_vulnerabilities(filters.clone())
@@ -1933,6 +2237,108 @@ pub async fn export_findings_html(
     Ok(CommandResponse::ok(path_str))
 }
 
+/// 导出时的默认过滤条件
+fn default_export_filters() -> VulnerabilityFilters {
+    VulnerabilityFilters {
+        vuln_type: None,
+        severity: None,
+        status: None,
+        plugin_id: None,
+        exclude_plugin_id: None,
+        limit: Some(1000), // 默认最多导出1000条
+        offset: Some(0),
+        ..Default::default()
+    }
+}
+
+/// 查询待导出的漏洞及其全部证据
+async fn load_findings_for_export(
+    db_service: &DatabaseService,
+    filters: Option<VulnerabilityFilters>,
+) -> Result<Vec<VulnerabilityWithEvidence>, String> {
+    let vulnerabilities = db_service
+        .list_traffic_vulnerabilities(filters.unwrap_or_else(default_export_filters))
+        .await
+        .map_err(|e| format!("Failed to list vulnerabilities: {}", e))?;
+
+    let mut findings = Vec::with_capacity(vulnerabilities.len());
+    for vulnerability in vulnerabilities {
+        let evidence = db_service
+            .get_traffic_evidence_by_vuln_id(&vulnerability.id)
+            .await
+            .unwrap_or_default();
+        findings.push(VulnerabilityWithEvidence {
+            url: evidence.first().map(|e| e.url.clone()),
+            method: evidence.first().map(|e| e.method.clone()),
+            vulnerability,
+            evidence,
+        });
+    }
+    Ok(findings)
+}
+
+/// 将报告写入 ~/.sentinel-ai/reports，返回文件路径
+fn write_report_file(content: &str, extension: &str) -> Result<String, String> {
+    let output_dir = dirs::home_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join(".sentinel-ai")
+        .join("reports");
+
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output directory: {}", e))?;
+
+    let filename = format!(
+        "traffic_analysis_report_{}.{}",
+        chrono::Utc::now().format("%Y%m%d_%H%M%S"),
+        extension
+    );
+    let output_path = output_dir.join(&filename);
+
+    std::fs::write(&output_path, content).map_err(|e| format!("Failed to write report: {}", e))?;
+
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+/// 导出 JSON 报告
+#[tauri::command]
+pub async fn export_findings_json(
+    state: State<'_, TrafficAnalysisState>,
+    filters: Option<VulnerabilityFilters>,
+) -> Result<CommandResponse<String>, String> {
+    tracing::info!("Exporting JSON report with filters: {:?}", filters);
+
+    let db_service = state.get_db_service();
+    let findings = load_findings_for_export(&db_service, filters).await?;
+    let report = sentinel_traffic::build_json_report(&findings, chrono::Utc::now());
+    let content = serde_json::to_string_pretty(&report)
+        .map_err(|e| format!("Failed to serialize report: {}", e))?;
+
+    let path_str = write_report_file(&content, "json")?;
+    tracing::info!("JSON report exported to: {}", path_str);
+
+    Ok(CommandResponse::ok(path_str))
+}
+
+/// 导出 SARIF 2.1.0 报告（可上传到 GitHub code scanning）
+#[tauri::command]
+pub async fn export_findings_sarif(
+    state: State<'_, TrafficAnalysisState>,
+    filters: Option<VulnerabilityFilters>,
+) -> Result<CommandResponse<String>, String> {
+    tracing::info!("Exporting SARIF report with filters: {:?}", filters);
+
+    let db_service = state.get_db_service();
+    let findings = load_findings_for_export(&db_service, filters).await?;
+    let sarif = sentinel_traffic::build_sarif_report(&findings);
+    let content = serde_json::to_string_pretty(&sarif)
+        .map_err(|e| format!("Failed to serialize report: {}", e))?;
+
+    let path_str = write_report_file(&content, "sarif")?;
+    tracing::info!("SARIF report exported to: {}", path_str);
+
+    Ok(CommandResponse::ok(path_str))
+}
+
 // ============================================================
 // 代理请求历史相关命令（使用内存缓存）
 // ============================================================
@@ -1967,19 +2373,113 @@ pub async fn list_proxy_requests(
     Ok(CommandResponse::ok(requests))
 }
 
+/// 代理请求详情（附带解码后的响应体）
+#[derive(Debug, Clone, Serialize)]
+pub struct ProxyRequestDetail {
+    #[serde(flatten)]
+    pub record: sentinel_traffic::HttpRequestRecord,
+    /// 按 Content-Encoding 解压后的响应体（用于展示）
+    pub body: Option<String>,
+    /// 原始响应体（保持编码，用于重放）
+    pub raw_body: Option<String>,
+}
+
+impl From<sentinel_traffic::HttpRequestRecord> for ProxyRequestDetail {
+    fn from(record: sentinel_traffic::HttpRequestRecord) -> Self {
+        use sentinel_traffic::content_decoding::{decode_body_for_display, MAX_DECODED_BODY_SIZE};
+
+        // 代理已解压并保留了原始数据时直接使用；已解压但无原始数据时不再解压也不提供重放数据；
+        // 否则记录里存的就是原始数据，按需解压
+        let (body, raw_body) = match &record.raw_response_body {
+            Some(raw) => (record.response_body.clone(), Some(raw.clone())),
+            None if record.response_body_decoded => (record.response_body.clone(), None),
+            None => (
+                decode_body_for_display(
+                    record.response_body.as_deref(),
+                    record.response_headers.as_deref(),
+                    MAX_DECODED_BODY_SIZE,
+                ),
+                record.response_body.clone(),
+            ),
+        };
+        Self {
+            record,
+            body,
+            raw_body,
+        }
+    }
+}
+
 /// 获取代理请求详情（从内存缓存）
 #[tauri::command]
 pub async fn get_proxy_request(
     state: State<'_, TrafficAnalysisState>,
     id: i64,
-) -> Result<CommandResponse<Option<sentinel_traffic::HttpRequestRecord>>, String> {
+) -> Result<CommandResponse<Option<ProxyRequestDetail>>, String> {
     let cache = state.get_history_cache();
 
-    let request = cache.get_http_request_by_id(id).await;
+    let request = cache
+        .get_http_request_by_id(id)
+        .await
+        .map(ProxyRequestDetail::from);
 
     Ok(CommandResponse::ok(request))
 }
 
+/// 从抓到的登录请求导入认证会话
+///
+/// 取请求携带的 Cookie 与响应下发的 `Set-Cookie`，替换当前认证会话
+#[tauri::command]
+pub async fn import_auth_session_from_proxy_request(
+    state: State<'_, TrafficAnalysisState>,
+    id: i64,
+    label: Option<String>,
+) -> Result<CommandResponse<sentinel_core::auth_session::AuthSession>, String> {
+    let cache = state.get_history_cache();
+    let Some(record) = cache.get_http_request_by_id(id).await else {
+        return Ok(CommandResponse::err(format!("Request {} not found", id)));
+    };
+    let url = match url::Url::parse(&record.url) {
+        Ok(url) => url,
+        Err(e) => return Ok(CommandResponse::err(format!("Invalid request URL: {}", e))),
+    };
+    let parse_headers = |json: Option<&String>| {
+        json.and_then(|h| serde_json::from_str::<std::collections::HashMap<String, String>>(h).ok())
+            .unwrap_or_default()
+    };
+    let header = |headers: &std::collections::HashMap<String, String>, name: &str| {
+        headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.clone())
+    };
+
+    let mut session = sentinel_core::auth_session::AuthSession {
+        label: label.or_else(|| Some(format!("{} {}", record.method, record.url))),
+        cookies: Vec::new(),
+    };
+    let request_headers = parse_headers(record.request_headers.as_ref());
+    if let (Some(cookie), Some(host)) = (header(&request_headers, "cookie"), url.host_str()) {
+        session.add_cookie_header(host, &cookie);
+    }
+    // 代理合并多个 Set-Cookie 时以换行分隔
+    let response_headers = parse_headers(record.response_headers.as_ref());
+    if let Some(set_cookie) = header(&response_headers, "set-cookie") {
+        for line in set_cookie.lines() {
+            session.add_set_cookie(&url, line);
+        }
+    }
+
+    if session.cookies.is_empty() {
+        return Ok(CommandResponse::err(format!(
+            "Request {} carries no cookies",
+            id
+        )));
+    }
+    sentinel_core::auth_session::set_auth_session(session.clone());
+    Ok(CommandResponse::ok(session))
+}
+
 /// 清空代理请求历史（清空内存缓存）
 #[tauri::command]
 pub async fn clear_proxy_requests(
@@ -2101,6 +2601,9 @@ pub async fn load_history_from_database(
             request_body: db_record.request_body,
             response_headers: db_record.response_headers,
             response_body: db_record.response_body,
+            // 数据库只保存了解压后的响应体
+            raw_response_body: None,
+            response_body_decoded: true,
             response_size: db_record.response_size,
             response_time: db_record.response_time,
             timestamp: db_record.timestamp,
@@ -2647,6 +3150,8 @@ pub async fn test_plugin(
             edited_url: None,
             edited_headers: None,
             edited_body: None,
+            body_truncated: false,
+            body_size: None,
         };
 
         // 调用插件进行一次扫描，捕获真实 findings。
@@ -2874,6 +3379,8 @@ pub async fn test_plugin_advanced(
                     edited_url: None,
                     edited_headers: None,
                     edited_body: None,
+                    body_truncated: false,
+                    body_size: None,
                 };
                 let transaction = HttpTransaction {
                     request: ctx.clone(),
@@ -3379,6 +3886,94 @@ pub async fn clear_vulnerability_dedupe_cache(
     Ok(CommandResponse::ok(()))
 }
 
+/// 从数据库重新加载抑制规则到共享缓存
+async fn reload_suppression_rules(state: &TrafficAnalysisState) -> Result<usize, String> {
+    let rules = state
+        .get_db_service()
+        .list_traffic_suppression_rules()
+        .await
+        .map_err(|e| format!("Failed to list suppression rules: {}", e))?;
+    let count = rules.len();
+    *state.suppression_rules.write().await = rules;
+    Ok(count)
+}
+
+/// 列出误报抑制规则
+#[tauri::command]
+pub async fn list_suppression_rules(
+    state: State<'_, TrafficAnalysisState>,
+) -> Result<CommandResponse<Vec<SuppressionRule>>, String> {
+    let rules = state
+        .get_db_service()
+        .list_traffic_suppression_rules()
+        .await
+        .map_err(|e| format!("Failed to list suppression rules: {}", e))?;
+    Ok(CommandResponse::ok(rules))
+}
+
+/// 创建或更新误报抑制规则（id 为空时创建）
+#[tauri::command]
+pub async fn save_suppression_rule(
+    state: State<'_, TrafficAnalysisState>,
+    rule: SuppressionRule,
+) -> Result<CommandResponse<SuppressionRule>, String> {
+    if rule.name.trim().is_empty() {
+        return Ok(CommandResponse::err("Rule name is required".to_string()));
+    }
+
+    let saved = state
+        .get_db_service()
+        .save_traffic_suppression_rule(&rule)
+        .await
+        .map_err(|e| format!("Failed to save suppression rule: {}", e))?;
+    reload_suppression_rules(&state).await?;
+
+    tracing::info!("Saved suppression rule {} ({})", saved.id, saved.name);
+    Ok(CommandResponse::ok(saved))
+}
+
+/// 删除误报抑制规则
+#[tauri::command]
+pub async fn delete_suppression_rule(
+    state: State<'_, TrafficAnalysisState>,
+    rule_id: String,
+) -> Result<CommandResponse<bool>, String> {
+    let deleted = state
+        .get_db_service()
+        .delete_traffic_suppression_rule(&rule_id)
+        .await
+        .map_err(|e| format!("Failed to delete suppression rule: {}", e))?;
+    reload_suppression_rules(&state).await?;
+
+    tracing::info!("Deleted suppression rule {}: {}", rule_id, deleted);
+    Ok(CommandResponse::ok(deleted))
+}
+
+/// 列出被抑制的漏洞
+#[tauri::command]
+pub async fn list_suppressed_findings(
+    state: State<'_, TrafficAnalysisState>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<CommandResponse<Vec<VulnerabilityRecord>>, String> {
+    let filters = VulnerabilityFilters {
+        vuln_type: None,
+        severity: None,
+        status: Some(sentinel_traffic::SUPPRESSED_STATUS.to_string()),
+        plugin_id: None,
+        exclude_plugin_id: None,
+        limit: Some(limit.unwrap_or(100)),
+        offset: Some(offset.unwrap_or(0)),
+        ..Default::default()
+    };
+    let findings = state
+        .get_db_service()
+        .list_traffic_vulnerabilities(filters)
+        .await
+        .map_err(|e| format!("Failed to list suppressed findings: {}", e))?;
+    Ok(CommandResponse::ok(findings))
+}
+
 /// 获取漏洞去重缓存状态（用于调试）
 #[tauri::command]
 pub async fn get_vulnerability_dedupe_cache_info(
@@ -3461,6 +4056,7 @@ pub async fn start_proxy_listener(
                     mitm_bypass_fail_threshold: 3,
                     upstream_proxy: None,
                     exclude_self_traffic: true,
+                    host_cas: Vec::new(),
                 }
             }
         },
@@ -3475,6 +4071,7 @@ pub async fn start_proxy_listener(
                 mitm_bypass_fail_threshold: 3,
                 upstream_proxy: None,
                 exclude_self_traffic: true,
+                host_cas: Vec::new(),
             }
         }
         Err(e) => {
@@ -3488,6 +4085,7 @@ pub async fn start_proxy_listener(
                 mitm_bypass_fail_threshold: 3,
                 upstream_proxy: None,
                 exclude_self_traffic: true,
+                host_cas: Vec::new(),
             }
         }
     };
@@ -3552,10 +4150,25 @@ pub async fn stop_proxy_listener(
 #[tauri::command]
 pub async fn save_proxy_config(
     state: State<'_, TrafficAnalysisState>,
-    config: ProxyConfig,
+    mut config: ProxyConfig,
 ) -> Result<CommandResponse<()>, String> {
     tracing::info!("Saving proxy configuration: {:?}", config);
 
+    // 校验上游代理（链）配置
+    if let Some(upstream) = &config.upstream_proxy {
+        if let Err(e) = upstream.validate() {
+            return Ok(CommandResponse::err(e.to_string()));
+        }
+    }
+
+    // 校验主机 CA，私钥移到证书目录，不写入配置
+    if let Err(e) = state
+        .certificate_service
+        .store_host_ca_keys(&mut config.host_cas)
+    {
+        return Ok(CommandResponse::err(e.to_string()));
+    }
+
     // 获取数据库服务
     let db = state.get_db_service();
 
@@ -3598,7 +4211,7 @@ pub async fn get_proxy_config(
     let db = state.get_db_service();
 
     // 从数据库加载配置
-    let config = match db.load_proxy_config("proxy_config").await {
+    let mut config = match db.load_proxy_config("proxy_config").await {
         Ok(Some(config_json)) => {
             // 反序列化配置
             match serde_json::from_str::<ProxyConfig>(&config_json) {
@@ -3622,6 +4235,25 @@ pub async fn get_proxy_config(
         }
     };
 
+    // 旧配置中明文保存的主机 CA 私钥迁移到证书目录
+    if config.host_cas.iter().any(|c| !c.key_pem.is_empty()) {
+        match state
+            .certificate_service
+            .store_host_ca_keys(&mut config.host_cas)
+        {
+            Ok(()) => match serde_json::to_string(&config) {
+                Ok(config_json) => {
+                    if let Err(e) = db.save_proxy_config("proxy_config", &config_json).await {
+                        tracing::warn!("Failed to persist migrated host CA keys: {}", e);
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to serialize config: {}", e),
+            },
+            Err(e) => tracing::warn!("Failed to migrate host CA keys: {}", e),
+        }
+    }
+    config.host_cas = config.host_cas.iter().map(|c| c.redacted()).collect();
+
     Ok(CommandResponse::ok(config))
 }
 
@@ -3709,6 +4341,93 @@ pub async fn get_traffic_analysis_plugin_enabled(
     Ok(CommandResponse::ok(enabled))
 }
 
+/// 设置被动资产提取开关
+#[tauri::command]
+pub async fn set_passive_asset_extraction_enabled(
+    state: State<'_, TrafficAnalysisState>,
+    enabled: bool,
+) -> Result<CommandResponse<()>, String> {
+    *state.passive_asset_extraction_enabled.write().await = enabled;
+
+    let db = state.get_db_service();
+    db.save_proxy_config("passive_asset_extraction_enabled", &enabled.to_string())
+        .await
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+
+    tracing::info!("Passive asset extraction set to: {}", enabled);
+    Ok(CommandResponse::ok(()))
+}
+
+/// 获取被动资产提取开关状态
+#[tauri::command]
+pub async fn get_passive_asset_extraction_enabled(
+    state: State<'_, TrafficAnalysisState>,
+) -> Result<CommandResponse<bool>, String> {
+    Ok(CommandResponse::ok(
+        *state.passive_asset_extraction_enabled.read().await,
+    ))
+}
+
+/// Finding 去重配置在代理配置表中的键
+const DEDUP_CONFIG_KEY: &str = "finding_dedup_config";
+
+/// 获取 Finding 去重配置
+#[tauri::command]
+pub async fn get_finding_dedup_config(
+    state: State<'_, TrafficAnalysisState>,
+) -> Result<CommandResponse<sentinel_traffic::DedupConfig>, String> {
+    Ok(CommandResponse::ok(state.dedup_config.read().await.clone()))
+}
+
+/// 设置 Finding 去重配置（去重键组成、再次上报窗口、按插件覆盖），即时生效
+#[tauri::command]
+pub async fn set_finding_dedup_config(
+    state: State<'_, TrafficAnalysisState>,
+    config: sentinel_traffic::DedupConfig,
+) -> Result<CommandResponse<()>, String> {
+    let value = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    state
+        .get_db_service()
+        .save_proxy_config(DEDUP_CONFIG_KEY, &value)
+        .await
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+
+    *state.dedup_config.write().await = config;
+    tracing::info!("Finding dedup config updated");
+    Ok(CommandResponse::ok(()))
+}
+
+/// 安全响应头检查配置在代理配置表中的键
+const SECURITY_HEADER_CONFIG_KEY: &str = "security_header_config";
+
+/// 获取安全响应头检查配置
+#[tauri::command]
+pub async fn get_security_header_config(
+    state: State<'_, TrafficAnalysisState>,
+) -> Result<CommandResponse<sentinel_traffic::SecurityHeaderConfig>, String> {
+    Ok(CommandResponse::ok(
+        state.security_header_config.read().await.clone(),
+    ))
+}
+
+/// 设置安全响应头检查配置（启用项、严重等级、HSTS 下限），即时生效
+#[tauri::command]
+pub async fn set_security_header_config(
+    state: State<'_, TrafficAnalysisState>,
+    config: sentinel_traffic::SecurityHeaderConfig,
+) -> Result<CommandResponse<()>, String> {
+    let value = serde_json::to_string(&config).map_err(|e| e.to_string())?;
+    state
+        .get_db_service()
+        .save_proxy_config(SECURITY_HEADER_CONFIG_KEY, &value)
+        .await
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+
+    *state.security_header_config.write().await = config;
+    tracing::info!("Security header check config updated");
+    Ok(CommandResponse::ok(()))
+}
+
 // ============================================================
 // 历史记录持久化配置命令
 // ============================================================
@@ -3978,38 +4697,58 @@ pub async fn replay_request(
 ) -> Result<CommandResponse<ReplayResult>, String> {
     tracing::info!("Replaying request: {} {}", method, url);
 
-    let start = std::time::Instant::now();
+    let client = replay_client()?;
+    match send_replay_request(&client, &method, &url, headers.unwrap_or_default(), body).await {
+        Ok(result) => Ok(CommandResponse::ok(result)),
+        Err(ReplayError::UnsupportedMethod) => Ok(CommandResponse::err(format!(
+            "Unsupported method: {}",
+            method
+        ))),
+        Err(ReplayError::Send(e)) => Err(e),
+    }
+}
 
-    // 创建 HTTP 客户端（禁用证书验证和代理以避免循环）
-    let client = reqwest::Client::builder()
+/// 创建重放用的 HTTP 客户端（禁用证书验证和代理以避免循环）
+fn replay_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
         .danger_accept_invalid_certs(true)
         .no_proxy() // 禁用代理，避免通过自身代理造成循环
         .timeout(std::time::Duration::from_secs(30))
         .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// 重放请求失败原因
+enum ReplayError {
+    UnsupportedMethod,
+    Send(String),
+}
+
+/// 发送单个重放请求
+async fn send_replay_request(
+    client: &reqwest::Client,
+    method: &str,
+    url: &str,
+    headers: std::collections::HashMap<String, String>,
+    body: Option<String>,
+) -> Result<ReplayResult, ReplayError> {
+    let start = std::time::Instant::now();
 
     // 构建请求
     let mut request = match method.to_uppercase().as_str() {
-        "GET" => client.get(&url),
-        "POST" => client.post(&url),
-        "PUT" => client.put(&url),
-        "DELETE" => client.delete(&url),
-        "PATCH" => client.patch(&url),
-        "HEAD" => client.head(&url),
-        "OPTIONS" => client.request(reqwest::Method::OPTIONS, &url),
-        _ => {
-            return Ok(CommandResponse::err(format!(
-                "Unsupported method: {}",
-                method
-            )))
-        }
+        "GET" => client.get(url),
+        "POST" => client.post(url),
+        "PUT" => client.put(url),
+        "DELETE" => client.delete(url),
+        "PATCH" => client.patch(url),
+        "HEAD" => client.head(url),
+        "OPTIONS" => client.request(reqwest::Method::OPTIONS, url),
+        _ => return Err(ReplayError::UnsupportedMethod),
     };
 
     // 添加请求头
-    if let Some(hdrs) = headers {
-        for (key, value) in hdrs {
-            request = request.header(&key, &value);
-        }
+    for (key, value) in headers {
+        request = request.header(&key, &value);
     }
 
     // 添加请求体
@@ -4021,7 +4760,7 @@ pub async fn replay_request(
     let response = request
         .send()
         .await
-        .map_err(|e| format!("Failed to send request: {}", e))?;
+        .map_err(|e| ReplayError::Send(format!("Failed to send request: {}", e)))?;
 
     let elapsed = start.elapsed().as_millis() as u64;
 
@@ -4048,12 +4787,93 @@ pub async fn replay_request(
         elapsed
     );
 
-    Ok(CommandResponse::ok(ReplayResult {
+    Ok(ReplayResult {
         status_code,
         headers: resp_headers,
         body,
         response_time_ms: elapsed,
-    }))
+    })
+}
+
+/// 模板重放中单个请求的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplatedReplayItem {
+    /// 本次使用的变量取值
+    pub variables: std::collections::HashMap<String, String>,
+    pub method: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<ReplayResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 模板重放默认并发数
+const TEMPLATED_REPLAY_CONCURRENCY: usize = 5;
+
+/// 按变量展开请求模板并逐个重放（`{{var}}` 占位符，支持数字范围与词表）
+#[tauri::command]
+pub async fn replay_request_templated(
+    template: sentinel_traffic::RequestTemplate,
+    variables: Vec<sentinel_traffic::TemplateVariable>,
+    max_requests: Option<usize>,
+    concurrency: Option<usize>,
+) -> Result<CommandResponse<Vec<TemplatedReplayItem>>, String> {
+    use futures::{stream, StreamExt};
+
+    let max_requests = max_requests
+        .unwrap_or(sentinel_traffic::MAX_TEMPLATE_REQUESTS)
+        .clamp(1, sentinel_traffic::MAX_TEMPLATE_REQUESTS);
+    let requests = match sentinel_traffic::expand_template(&template, &variables, max_requests) {
+        Ok(requests) => requests,
+        Err(e) => return Ok(CommandResponse::err(e)),
+    };
+    tracing::info!(
+        "Replaying templated request {} {} as {} requests",
+        template.method,
+        template.url,
+        requests.len()
+    );
+
+    let client = replay_client()?;
+    let items = stream::iter(requests)
+        .map(|request| {
+            let client = client.clone();
+            async move {
+                let outcome = send_replay_request(
+                    &client,
+                    &request.method,
+                    &request.url,
+                    request.headers,
+                    request.body,
+                )
+                .await;
+                let (result, error) = match outcome {
+                    Ok(result) => (Some(result), None),
+                    Err(ReplayError::UnsupportedMethod) => (
+                        None,
+                        Some(format!("Unsupported method: {}", request.method)),
+                    ),
+                    Err(ReplayError::Send(e)) => (None, Some(e)),
+                };
+                TemplatedReplayItem {
+                    variables: request.variables,
+                    method: request.method,
+                    url: request.url,
+                    result,
+                    error,
+                }
+            }
+        })
+        .buffered(
+            concurrency
+                .unwrap_or(TEMPLATED_REPLAY_CONCURRENCY)
+                .clamp(1, 20),
+        )
+        .collect::<Vec<_>>()
+        .await;
+
+    Ok(CommandResponse::ok(items))
 }
 
 /// 解码 chunked 传输编码
@@ -4505,6 +5325,15 @@ pub struct InterceptFilterRule {
     /// 是否启用
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// 限定请求方法（为空表示任意）
+    #[serde(default)]
+    pub method: Option<String>,
+    /// 限定请求 Content-Type（为空表示任意）
+    #[serde(default)]
+    pub request_content_type: Option<String>,
+    /// 限定响应 Content-Type（为空表示任意）
+    #[serde(default)]
+    pub response_content_type: Option<String>,
 }
 
 fn default_enabled() -> bool {
@@ -4542,6 +5371,9 @@ pub async fn add_intercept_filter_rule(
         condition: rule.condition,
         action: rule.action,
         enabled: rule.enabled,
+        method: rule.method,
+        request_content_type: rule.request_content_type,
+        response_content_type: rule.response_content_type,
     };
 
     rules.rules.push(new_rule.clone());
@@ -4640,6 +5472,12 @@ pub struct RuntimeInterceptRule {
     pub match_type: String,
     pub relationship: String,
     pub condition: String,
+    #[serde(default)]
+    pub method: Option<String>,
+    #[serde(default)]
+    pub request_content_type: Option<String>,
+    #[serde(default)]
+    pub response_content_type: Option<String>,
 }
 
 /// 更新运行时拦截过滤规则（直接应用到代理并持久化）
@@ -4664,6 +5502,9 @@ pub async fn update_runtime_filter_rules(
             match_type: r.match_type.clone(),
             relationship: r.relationship.clone(),
             condition: r.condition.clone(),
+            method: r.method.clone(),
+            request_content_type: r.request_content_type.clone(),
+            response_content_type: r.response_content_type.clone(),
         })
         .collect();
 
@@ -4705,6 +5546,9 @@ pub async fn update_runtime_filter_rules(
             condition: rule.condition.clone(),
             action: "exclude".to_string(), // Default action
             enabled: rule.enabled,
+            method: rule.method.clone(),
+            request_content_type: rule.request_content_type.clone(),
+            response_content_type: rule.response_content_type.clone(),
         });
     }
 
@@ -4723,6 +5567,136 @@ pub async fn update_runtime_filter_rules(
     Ok(CommandResponse::ok(()))
 }
 
+// ============================================================
+// 匹配替换规则相关命令
+// ============================================================
+
+/// 匹配替换规则列表
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct MatchReplaceRules {
+    pub rules: Vec<MatchReplaceRule>,
+}
+
+/// 从数据库加载匹配替换规则（按用户设定的顺序）
+async fn load_match_replace_rules(db: &DatabaseService) -> Result<Vec<MatchReplaceRule>, String> {
+    match db.load_proxy_config("match_replace_rules").await {
+        Ok(Some(json)) => serde_json::from_str::<MatchReplaceRules>(&json)
+            .map(|r| r.rules)
+            .map_err(|e| format!("Failed to parse match-and-replace rules: {}", e)),
+        Ok(None) => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to load match-and-replace rules: {}", e)),
+    }
+}
+
+/// 持久化规则并热更新到运行中的代理
+async fn save_match_replace_rules(
+    state: &TrafficAnalysisState,
+    rules: Vec<MatchReplaceRule>,
+) -> Result<(), String> {
+    let compiled = sentinel_traffic::compile_match_replace_rules(&rules);
+    let json = serde_json::to_string(&MatchReplaceRules { rules })
+        .map_err(|e| format!("Serialization error: {}", e))?;
+    state
+        .get_db_service()
+        .save_proxy_config("match_replace_rules", &json)
+        .await
+        .map_err(|e| format!("Failed to save rules: {}", e))?;
+
+    *state.match_replace_rules.write().await = compiled;
+    Ok(())
+}
+
+/// 添加匹配替换规则（追加到末尾）
+#[tauri::command]
+pub async fn add_match_replace_rule(
+    state: State<'_, TrafficAnalysisState>,
+    rule: MatchReplaceRule,
+) -> Result<CommandResponse<MatchReplaceRule>, String> {
+    tracing::info!("Adding match-and-replace rule: {:?}", rule);
+
+    if let Err(e) = sentinel_traffic::validate_match_replace_rule(&rule) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let mut rules = load_match_replace_rules(&state.get_db_service()).await?;
+    let new_rule = MatchReplaceRule {
+        id: uuid::Uuid::new_v4().to_string(),
+        ..rule
+    };
+    rules.push(new_rule.clone());
+    save_match_replace_rules(&state, rules).await?;
+
+    tracing::info!("Match-and-replace rule added: {}", new_rule.id);
+    Ok(CommandResponse::ok(new_rule))
+}
+
+/// 获取所有匹配替换规则
+#[tauri::command]
+pub async fn get_match_replace_rules(
+    state: State<'_, TrafficAnalysisState>,
+) -> Result<CommandResponse<Vec<MatchReplaceRule>>, String> {
+    match load_match_replace_rules(&state.get_db_service()).await {
+        Ok(rules) => Ok(CommandResponse::ok(rules)),
+        Err(e) => Ok(CommandResponse::err(e)),
+    }
+}
+
+/// 删除匹配替换规则
+#[tauri::command]
+pub async fn remove_match_replace_rule(
+    state: State<'_, TrafficAnalysisState>,
+    rule_id: String,
+) -> Result<CommandResponse<()>, String> {
+    tracing::info!("Removing match-and-replace rule: {}", rule_id);
+
+    let mut rules = load_match_replace_rules(&state.get_db_service()).await?;
+    rules.retain(|r| r.id != rule_id);
+    save_match_replace_rules(&state, rules).await?;
+
+    Ok(CommandResponse::ok(()))
+}
+
+/// 更新匹配替换规则（包括启用/禁用）
+#[tauri::command]
+pub async fn update_match_replace_rule(
+    state: State<'_, TrafficAnalysisState>,
+    rule: MatchReplaceRule,
+) -> Result<CommandResponse<()>, String> {
+    tracing::info!("Updating match-and-replace rule: {:?}", rule);
+
+    if let Err(e) = sentinel_traffic::validate_match_replace_rule(&rule) {
+        return Ok(CommandResponse::err(e));
+    }
+
+    let mut rules = load_match_replace_rules(&state.get_db_service()).await?;
+    if let Some(existing) = rules.iter_mut().find(|r| r.id == rule.id) {
+        *existing = rule;
+    } else {
+        return Ok(CommandResponse::err(format!("Rule not found: {}", rule.id)));
+    }
+    save_match_replace_rules(&state, rules).await?;
+
+    Ok(CommandResponse::ok(()))
+}
+
+/// 调整匹配替换规则的执行顺序（rule_ids 为新的完整顺序）
+#[tauri::command]
+pub async fn reorder_match_replace_rules(
+    state: State<'_, TrafficAnalysisState>,
+    rule_ids: Vec<String>,
+) -> Result<CommandResponse<()>, String> {
+    let mut rules = load_match_replace_rules(&state.get_db_service()).await?;
+    if rule_ids.len() != rules.len() || rules.iter().any(|r| !rule_ids.contains(&r.id)) {
+        return Ok(CommandResponse::err(
+            "Rule order must list every existing rule exactly once".to_string(),
+        ));
+    }
+    rules.sort_by_key(|r| rule_ids.iter().position(|id| id == &r.id));
+    save_match_replace_rules(&state, rules).await?;
+
+    Ok(CommandResponse::ok(()))
+}
+
 // ============================================================
 // 插件商店命令
 // ============================================================
@@ -5095,3 +6069,52 @@ pub async fn update_store_plugin(
 
     Ok(CommandResponse::ok(plugin.id))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(
+        response_body: &str,
+        response_body_decoded: bool,
+    ) -> sentinel_traffic::HttpRequestRecord {
+        sentinel_traffic::HttpRequestRecord {
+            id: 1,
+            url: "https://example.com/".to_string(),
+            host: "example.com".to_string(),
+            protocol: "https".to_string(),
+            method: "GET".to_string(),
+            status_code: 200,
+            request_headers: None,
+            request_body: None,
+            response_headers: Some("Content-Encoding: gzip".to_string()),
+            response_body: Some(response_body.to_string()),
+            raw_response_body: None,
+            response_body_decoded,
+            response_size: response_body.len() as i64,
+            response_time: 10,
+            timestamp: chrono::Utc::now(),
+            was_edited: false,
+            edited_request_headers: None,
+            edited_request_body: None,
+            edited_method: None,
+            edited_url: None,
+            edited_response_headers: None,
+            edited_response_body: None,
+            edited_status_code: None,
+        }
+    }
+
+    #[test]
+    fn test_decoded_record_is_not_decoded_again_or_replayed() {
+        let detail = ProxyRequestDetail::from(record("<html>ok</html>", true));
+        assert_eq!(detail.body.as_deref(), Some("<html>ok</html>"));
+        assert!(detail.raw_body.is_none());
+    }
+
+    #[test]
+    fn test_undecoded_record_keeps_body_as_raw() {
+        let detail = ProxyRequestDetail::from(record("not gzip", false));
+        assert_eq!(detail.raw_body.as_deref(), Some("not gzip"));
+    }
+}
@@ -3,14 +3,79 @@
 //! NOTE: After ReAct refactoring, the engine is accessed through Rig Tool interface.
 //! This module is kept for compatibility but most functions are disabled.
 
-use std::collections::HashMap;
+use crate::commands::traffic_analysis_commands::TrafficAnalysisState;
+use crate::engines::web_explorer::export::{
+    build_openapi, build_sitemap, CapturedExchange, ExportFormat,
+};
+use crate::engines::web_explorer::{ExplorationResult, ReActTrace};
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use tauri::State;
 use tokio::sync::RwLock;
 
+/// Number of finished explorations kept in memory for export
+const MAX_STORED_RESULTS: usize = 50;
+/// Number of run traces kept in memory
+const MAX_STORED_TRACES: usize = 50;
+
 /// State container for Web Explorer sessions (kept for compatibility)
 pub struct WebExplorerState {
     /// Active engine sessions (kept for compatibility, currently unused)
     pub sessions: Arc<RwLock<HashMap<String, WebExplorerSession>>>,
+    /// Finished explorations keyed by execution ID (oldest evicted past the cap)
+    pub results: Arc<RwLock<RecentRuns<CompletedExploration>>>,
+    /// Redacted step traces keyed by execution ID (oldest evicted past the cap)
+    pub traces: Arc<RwLock<RecentRuns<ReActTrace>>>,
+}
+
+/// Per-run entries keyed by execution ID, evicting the oldest past `capacity`
+pub struct RecentRuns<T> {
+    entries: HashMap<String, T>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl<T> RecentRuns<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Insert or replace an entry; a replaced run counts as the newest
+    pub fn insert(&mut self, execution_id: String, value: T) {
+        if self.entries.insert(execution_id.clone(), value).is_some() {
+            self.order.retain(|id| id != &execution_id);
+        }
+        self.order.push_back(execution_id);
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn get(&self, execution_id: &str) -> Option<&T> {
+        self.entries.get(execution_id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// A finished exploration kept for export
+#[derive(Debug, Clone)]
+pub struct CompletedExploration {
+    pub target_url: String,
+    pub result: ExplorationResult,
+    pub completed_at: u64,
 }
 
 /// A single Web Explorer session (kept for compatibility)
@@ -28,10 +93,191 @@ impl Default for WebExplorerState {
     fn default() -> Self {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            results: Arc::new(RwLock::new(RecentRuns::new(MAX_STORED_RESULTS))),
+            traces: Arc::new(RwLock::new(RecentRuns::new(MAX_STORED_TRACES))),
+        }
+    }
+}
+
+impl WebExplorerState {
+    /// Keep a finished exploration for later export
+    pub async fn store_result(
+        &self,
+        execution_id: String,
+        target_url: String,
+        result: ExplorationResult,
+    ) {
+        let completed_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.results.write().await.insert(
+            execution_id,
+            CompletedExploration {
+                target_url,
+                result,
+                completed_at,
+            },
+        );
+    }
+
+    /// Keep the (already redacted) trace of a run
+    pub async fn store_trace(&self, execution_id: String, trace: ReActTrace) {
+        self.traces.write().await.insert(execution_id, trace);
+    }
+}
+
+/// Fetch the step-by-step trace of a run
+///
+/// `format` is `json` (default) or `markdown`. Secrets were redacted when the
+/// trace was stored.
+#[tauri::command]
+pub async fn get_vision_explorer_v2_trace(
+    execution_id: String,
+    format: Option<String>,
+    state: State<'_, WebExplorerState>,
+) -> Result<String, String> {
+    let trace = state
+        .traces
+        .read()
+        .await
+        .get(&execution_id)
+        .cloned()
+        .ok_or_else(|| format!("No trace found for execution {}", execution_id))?;
+
+    match format.as_deref().unwrap_or("json").to_lowercase().as_str() {
+        "json" => trace
+            .to_json()
+            .map_err(|e| format!("Failed to serialize trace: {}", e)),
+        "markdown" | "md" => Ok(trace.to_markdown()),
+        other => Err(format!("Unsupported trace format: {}", other)),
+    }
+}
+
+/// Export the discoveries of a finished exploration
+///
+/// `format` is `openapi` (OpenAPI 3.0 JSON) or `sitemap` (XML). Request and
+/// response examples are taken from the proxy history when the endpoint was
+/// captured there.
+#[tauri::command]
+pub async fn export_vision_explorer_v2_sitemap(
+    execution_id: String,
+    format: String,
+    state: State<'_, WebExplorerState>,
+    traffic_state: State<'_, TrafficAnalysisState>,
+) -> Result<String, String> {
+    let format: ExportFormat = format.parse()?;
+    let completed = state
+        .results
+        .read()
+        .await
+        .get(&execution_id)
+        .cloned()
+        .ok_or_else(|| format!("Exploration not found: {}", execution_id))?;
+
+    match format {
+        ExportFormat::Sitemap => Ok(build_sitemap(&completed.result)),
+        ExportFormat::OpenApi => {
+            let captures =
+                collect_captures(&completed.result, &traffic_state.get_history_cache()).await;
+            let doc = build_openapi(&completed.target_url, &completed.result, &captures);
+            serde_json::to_string_pretty(&doc).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Look up proxy-captured exchanges for the discovered API endpoints
+async fn collect_captures(
+    result: &ExplorationResult,
+    cache: &sentinel_traffic::ProxyHistoryCache,
+) -> Vec<CapturedExchange> {
+    let mut captures = Vec::new();
+    for api in &result.api_list {
+        let Some((method, url)) = api.split_once(' ') else {
+            continue;
+        };
+        let records = cache
+            .list_http_requests(sentinel_traffic::HttpRequestFilters {
+                method: Some(method.to_uppercase()),
+                search: Some(url.to_string()),
+                limit: Some(20),
+                ..Default::default()
+            })
+            .await;
+        if let Some(record) = records.into_iter().find(|r| r.url == url) {
+            let response_content_type = record
+                .response_headers
+                .as_deref()
+                .and_then(|h| serde_json::from_str::<HashMap<String, String>>(h).ok())
+                .and_then(|headers| {
+                    headers
+                        .into_iter()
+                        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+                        .map(|(_, v)| v)
+                });
+            captures.push(CapturedExchange {
+                method: record.method,
+                url: record.url,
+                status_code: record.status_code,
+                request_body: record.request_body,
+                response_body: record.response_body,
+                response_content_type,
+            });
         }
     }
+    captures
 }
 
 // All commands are disabled after ReAct refactoring
 // The new ReAct engine is accessed through Rig Tool interface
 // See: src/engines/web_explorer/tool.rs
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_runs_evicts_oldest() {
+        let mut runs = RecentRuns::new(2);
+        runs.insert("a".to_string(), 1);
+        runs.insert("b".to_string(), 2);
+        runs.insert("c".to_string(), 3);
+        assert_eq!(runs.len(), 2);
+        assert!(runs.get("a").is_none());
+        assert_eq!(runs.get("c"), Some(&3));
+    }
+
+    #[test]
+    fn test_recent_runs_replacing_refreshes_entry() {
+        let mut runs = RecentRuns::new(2);
+        runs.insert("a".to_string(), 1);
+        runs.insert("b".to_string(), 2);
+        runs.insert("a".to_string(), 10);
+        runs.insert("c".to_string(), 3);
+        assert_eq!(runs.get("a"), Some(&10));
+        assert!(runs.get("b").is_none());
+        assert_eq!(runs.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_state_caps_stored_traces() {
+        let state = WebExplorerState::default();
+        for i in 0..MAX_STORED_TRACES + 5 {
+            let trace = ReActTrace {
+                session_id: format!("session-{}", i),
+                target_url: "https://example.com".to_string(),
+                started_at: 0,
+                finished_at: 0,
+                completion_reason: None,
+                steps: Vec::new(),
+            };
+            state.store_trace(format!("exec-{}", i), trace).await;
+        }
+        let traces = state.traces.read().await;
+        assert_eq!(traces.len(), MAX_STORED_TRACES);
+        assert!(traces.get("exec-0").is_none());
+        assert!(traces
+            .get(&format!("exec-{}", MAX_STORED_TRACES + 4))
+            .is_some());
+    }
+}
@@ -5,16 +5,41 @@
 
 use super::types::{Action, ActionResult, ScrollDirection};
 use anyhow::Result;
-use sentinel_tools::agent_browser::{get_browser_service, SnapshotOptions};
+use sentinel_tools::agent_browser::{get_browser_service, AgentBrowserService, SnapshotOptions};
+use tokio::sync::OwnedRwLockWriteGuard;
 use tracing::{debug, info, warn};
 
 /// Action executor using AgentBrowserService
-pub struct ActionExecutor;
+pub struct ActionExecutor {
+    /// Owned tab every action runs in; None acts on whichever tab is active
+    tab_id: Option<u64>,
+}
 
 impl ActionExecutor {
     /// Create a new action executor
     pub fn new() -> Self {
-        Self
+        Self { tab_id: None }
+    }
+
+    /// Create an action executor bound to a tab opened with `new_owned_tab`
+    pub fn for_tab(tab_id: u64) -> Self {
+        Self {
+            tab_id: Some(tab_id),
+        }
+    }
+
+    /// Lock the shared browser with this executor's tab active.
+    ///
+    /// Other runs switch tabs between our actions, so the tab is activated under
+    /// the same lock the action runs under.
+    async fn browser(&self) -> Result<OwnedRwLockWriteGuard<AgentBrowserService>> {
+        let mut service = get_browser_service().await.write_owned().await;
+        if let Some(tab_id) = self.tab_id {
+            if !service.activate_owned_tab(tab_id).await? {
+                anyhow::bail!("Browser tab #{} was closed", tab_id);
+            }
+        }
+        Ok(service)
     }
 
     // ==================== Core Actions ====================
@@ -54,8 +79,7 @@ impl ActionExecutor {
     async fn execute_navigate(&self, url: &str) -> Result<ActionResult> {
         debug!("Navigating to: {}", url);
 
-        let service = get_browser_service().await;
-        let mut service = service.write().await;
+        let mut service = self.browser().await?;
 
         match service.open(url, Some("load"), None).await {
             Ok(result) => {
@@ -98,8 +122,7 @@ impl ActionExecutor {
             index, selector, x, y
         );
 
-        let service = get_browser_service().await;
-        let mut service = service.write().await;
+        let mut service = self.browser().await?;
 
         // NOTE: Do NOT take snapshot here! The index is based on the snapshot
         // that was taken during the observe phase. Taking a new snapshot would
@@ -180,8 +203,7 @@ impl ActionExecutor {
             index, selector, value
         );
 
-        let service = get_browser_service().await;
-        let mut service = service.write().await;
+        let mut service = self.browser().await?;
 
         let target = if let Some(idx) = index {
             format!("@e{}", idx)
@@ -216,8 +238,7 @@ impl ActionExecutor {
     async fn execute_submit(&self, selector: &str) -> Result<ActionResult> {
         debug!("Submitting form: {}", selector);
 
-        let service = get_browser_service().await;
-        let mut service = service.write().await;
+        let mut service = self.browser().await?;
 
         match service.press("Enter", Some(selector)).await {
             Ok(_) => {
@@ -249,8 +270,7 @@ impl ActionExecutor {
     ) -> Result<ActionResult> {
         debug!("Scrolling: {:?} by {}", direction, amount);
 
-        let service = get_browser_service().await;
-        let mut service = service.write().await;
+        let mut service = self.browser().await?;
 
         let scroll_dir = match direction {
             ScrollDirection::Down => sentinel_tools::agent_browser::ScrollDirection::Down,
@@ -306,8 +326,7 @@ impl ActionExecutor {
     async fn execute_go_back(&self) -> Result<ActionResult> {
         debug!("Going back");
 
-        let service = get_browser_service().await;
-        let mut service = service.write().await;
+        let mut service = self.browser().await?;
 
         match service.back().await {
             Ok(_) => {
@@ -334,15 +353,13 @@ impl ActionExecutor {
 
     /// Get page snapshot (ARIA tree with refs)
     pub async fn get_snapshot(&self) -> Result<sentinel_tools::agent_browser::Snapshot> {
-        let service = get_browser_service().await;
-        let mut service = service.write().await;
+        let mut service = self.browser().await?;
         service.snapshot(SnapshotOptions::interactive()).await
     }
 
     /// Get full snapshot with all elements
     pub async fn get_full_snapshot(&self) -> Result<sentinel_tools::agent_browser::Snapshot> {
-        let service = get_browser_service().await;
-        let mut service = service.write().await;
+        let mut service = self.browser().await?;
         service.snapshot(SnapshotOptions::full()).await
     }
 
@@ -350,8 +367,7 @@ impl ActionExecutor {
 
     /// Enable network request interception for API discovery
     pub async fn enable_network_interception(&self) -> Result<()> {
-        let service = get_browser_service().await;
-        let mut service = service.write().await;
+        let mut service = self.browser().await?;
         service.start_network_tracking().await?;
         info!("Network interception enabled for API discovery");
         Ok(())
@@ -359,8 +375,7 @@ impl ActionExecutor {
 
     /// Get discovered API endpoints from network interception
     pub async fn get_discovered_apis(&self) -> Result<Vec<String>> {
-        let service = get_browser_service().await;
-        let mut service = service.write().await;
+        let mut service = self.browser().await?;
         let apis = service.get_discovered_apis().await?;
         debug!("Discovered {} API endpoints", apis.len());
         Ok(apis)
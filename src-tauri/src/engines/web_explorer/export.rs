@@ -0,0 +1,408 @@
+//! Export exploration discoveries
+//!
+//! Turns an `ExplorationResult` into a machine-readable inventory: either an
+//! OpenAPI 3.0 skeleton of the discovered routes and API endpoints, or an XML
+//! sitemap of the visited pages.
+
+use super::types::ExplorationResult;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+use url::Url;
+
+/// Export document format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    OpenApi,
+    Sitemap,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "openapi" | "openapi3" | "json" => Ok(Self::OpenApi),
+            "sitemap" | "xml" => Ok(Self::Sitemap),
+            other => Err(format!("Unsupported export format: {}", other)),
+        }
+    }
+}
+
+/// A request/response pair captured for a discovered endpoint
+#[derive(Debug, Clone, Default)]
+pub struct CapturedExchange {
+    pub method: String,
+    pub url: String,
+    pub status_code: i32,
+    pub request_body: Option<String>,
+    pub response_body: Option<String>,
+    pub response_content_type: Option<String>,
+}
+
+/// Collapse identifier-like path segments into `{id}` placeholders
+///
+/// Numeric segments, UUIDs and long hex strings are treated as identifiers.
+/// When a path has several, later ones are numbered (`{id}`, `{id2}`, ...).
+pub fn templatize_path(path: &str) -> String {
+    let mut count = 0;
+    let segments: Vec<String> = path
+        .split('/')
+        .map(|segment| {
+            if is_identifier_segment(segment) {
+                count += 1;
+                if count == 1 {
+                    "{id}".to_string()
+                } else {
+                    format!("{{id{}}}", count)
+                }
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect();
+    segments.join("/")
+}
+
+fn is_identifier_segment(segment: &str) -> bool {
+    if segment.is_empty() {
+        return false;
+    }
+    if segment.chars().all(|c| c.is_ascii_digit()) {
+        return true;
+    }
+    let is_uuid = segment.len() == 36
+        && segment.chars().enumerate().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        });
+    let is_hex_id = segment.len() >= 16 && segment.chars().all(|c| c.is_ascii_hexdigit());
+    is_uuid || is_hex_id
+}
+
+/// Split a "METHOD URL" API string; bare URLs are treated as GET
+fn parse_api_entry(entry: &str) -> (String, String) {
+    match entry.trim().split_once(' ') {
+        Some((method, url)) if !method.contains("://") => {
+            (method.to_uppercase(), url.trim().to_string())
+        }
+        _ => ("GET".to_string(), entry.trim().to_string()),
+    }
+}
+
+fn origin(url: &Url) -> String {
+    url.origin().ascii_serialization()
+}
+
+/// All page URLs seen during exploration (visited URLs plus graph nodes)
+fn page_urls(result: &ExplorationResult) -> BTreeSet<String> {
+    let mut urls: BTreeSet<String> = result.visited_urls.iter().cloned().collect();
+    if let Some(nodes) = result.graph.get("nodes").and_then(|n| n.as_array()) {
+        for node in nodes {
+            if let Some(url) = node.get("url").and_then(|u| u.as_str()) {
+                urls.insert(url.to_string());
+            }
+        }
+    }
+    urls.into_iter()
+        .filter_map(|u| Url::parse(&u).ok())
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+        .map(|mut u| {
+            u.set_fragment(None);
+            u.to_string()
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Operation {
+    /// query parameter name -> first observed value
+    query: BTreeMap<String, String>,
+    example: Option<CapturedExchange>,
+}
+
+/// Build an OpenAPI 3.0 skeleton from the exploration result
+pub fn build_openapi(
+    target_url: &str,
+    result: &ExplorationResult,
+    captures: &[CapturedExchange],
+) -> Value {
+    let mut servers: BTreeSet<String> = BTreeSet::new();
+    if let Ok(target) = Url::parse(target_url) {
+        servers.insert(origin(&target));
+    }
+
+    let mut entries: Vec<(String, String)> = page_urls(result)
+        .into_iter()
+        .map(|url| ("GET".to_string(), url))
+        .collect();
+    entries.extend(result.api_list.iter().map(|a| parse_api_entry(a)));
+
+    // path template -> method -> operation
+    let mut paths: BTreeMap<String, BTreeMap<String, Operation>> = BTreeMap::new();
+    for (method, raw_url) in entries {
+        let Ok(url) = Url::parse(&raw_url) else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            continue;
+        }
+        servers.insert(origin(&url));
+
+        let template = templatize_path(url.path());
+        let operation = paths
+            .entry(template)
+            .or_default()
+            .entry(method.to_lowercase())
+            .or_default();
+        for (key, value) in url.query_pairs() {
+            operation
+                .query
+                .entry(key.into_owned())
+                .or_insert_with(|| value.into_owned());
+        }
+        if operation.example.is_none() {
+            operation.example = captures
+                .iter()
+                .find(|c| c.method.eq_ignore_ascii_case(&method) && c.url == raw_url)
+                .cloned();
+        }
+    }
+
+    let mut path_items = Map::new();
+    for (template, operations) in paths {
+        let mut item = Map::new();
+        for (method, operation) in operations {
+            item.insert(
+                method.clone(),
+                operation_to_json(&template, &method, operation),
+            );
+        }
+        path_items.insert(template, Value::Object(item));
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": format!("Discovered API for {}", target_url),
+            "version": "1.0.0",
+            "description": format!(
+                "Generated from web exploration: {} pages visited, {} APIs discovered",
+                result.pages_visited, result.apis_discovered
+            ),
+        },
+        "servers": servers.into_iter().map(|url| json!({ "url": url })).collect::<Vec<_>>(),
+        "paths": Value::Object(path_items),
+    })
+}
+
+fn operation_to_json(template: &str, method: &str, operation: Operation) -> Value {
+    let mut parameters: Vec<Value> = template
+        .split('/')
+        .filter_map(|s| s.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            })
+        })
+        .collect();
+    parameters.extend(operation.query.iter().map(|(name, example)| {
+        json!({
+            "name": name,
+            "in": "query",
+            "required": false,
+            "schema": { "type": "string" },
+            "example": example,
+        })
+    }));
+
+    let mut op = Map::new();
+    op.insert(
+        "summary".to_string(),
+        json!(format!("{} {}", method.to_uppercase(), template)),
+    );
+    if !parameters.is_empty() {
+        op.insert("parameters".to_string(), Value::Array(parameters));
+    }
+
+    let mut responses = Map::new();
+    match operation.example {
+        Some(example) => {
+            if let Some(body) = example.request_body.filter(|b| !b.is_empty()) {
+                op.insert(
+                    "requestBody".to_string(),
+                    json!({ "content": example_content(content_type_for(&body, None), &body) }),
+                );
+            }
+            let mut response = Map::new();
+            response.insert("description".to_string(), json!("Observed response"));
+            if let Some(body) = example.response_body.filter(|b| !b.is_empty()) {
+                let content_type =
+                    content_type_for(&body, example.response_content_type.as_deref());
+                response.insert("content".to_string(), example_content(content_type, &body));
+            }
+            let status = if example.status_code > 0 {
+                example.status_code.to_string()
+            } else {
+                "default".to_string()
+            };
+            responses.insert(status, Value::Object(response));
+        }
+        None => {
+            responses.insert(
+                "default".to_string(),
+                json!({ "description": "Observed during exploration" }),
+            );
+        }
+    }
+    op.insert("responses".to_string(), Value::Object(responses));
+
+    Value::Object(op)
+}
+
+fn content_type_for(body: &str, declared: Option<&str>) -> String {
+    if let Some(declared) = declared {
+        return declared
+            .split(';')
+            .next()
+            .unwrap_or(declared)
+            .trim()
+            .to_string();
+    }
+    if serde_json::from_str::<Value>(body).is_ok() {
+        "application/json".to_string()
+    } else {
+        "text/plain".to_string()
+    }
+}
+
+/// `{ "<content-type>": { "example": <body> } }`, JSON bodies embedded as objects
+fn example_content(content_type: String, body: &str) -> Value {
+    let example = serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.to_string()));
+    let mut content = Map::new();
+    content.insert(content_type, json!({ "example": example }));
+    Value::Object(content)
+}
+
+/// Build an XML sitemap of the visited pages
+pub fn build_sitemap(result: &ExplorationResult) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for url in page_urls(result) {
+        xml.push_str("  <url><loc>");
+        xml.push_str(&xml_escape(&url));
+        xml.push_str("</loc></url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result() -> ExplorationResult {
+        ExplorationResult {
+            success: true,
+            pages_visited: 3,
+            apis_discovered: 3,
+            actions_performed: 5,
+            duration_seconds: 10,
+            error: None,
+            completion_reason: None,
+            graph: json!({
+                "nodes": [
+                    { "url": "https://shop.example.com/products/42" },
+                    { "url": "https://shop.example.com/about#team" },
+                ],
+                "edges": [],
+            }),
+            api_list: vec![
+                "GET https://shop.example.com/api/orders/1001?expand=items".to_string(),
+                "GET https://shop.example.com/api/orders/1002".to_string(),
+                "POST https://shop.example.com/api/orders".to_string(),
+            ],
+            visited_urls: vec![
+                "https://shop.example.com/".to_string(),
+                "https://shop.example.com/products/7".to_string(),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_templatize_path() {
+        assert_eq!(templatize_path("/api/orders/1001"), "/api/orders/{id}");
+        assert_eq!(
+            templatize_path("/users/7/posts/550e8400-e29b-41d4-a716-446655440000"),
+            "/users/{id}/posts/{id2}"
+        );
+        assert_eq!(templatize_path("/v2/login"), "/v2/login");
+    }
+
+    #[test]
+    fn test_openapi_dedups_paths_and_includes_examples() {
+        let captures = vec![CapturedExchange {
+            method: "POST".to_string(),
+            url: "https://shop.example.com/api/orders".to_string(),
+            status_code: 201,
+            request_body: Some(r#"{"sku":"A1"}"#.to_string()),
+            response_body: Some(r#"{"id":1003}"#.to_string()),
+            response_content_type: Some("application/json; charset=utf-8".to_string()),
+        }];
+        let doc = build_openapi("https://shop.example.com/", &result(), &captures);
+
+        assert_eq!(doc["openapi"], "3.0.3");
+        let paths = doc["paths"].as_object().unwrap();
+        assert!(paths.contains_key("/api/orders/{id}"));
+        assert!(paths.contains_key("/products/{id}"));
+        assert!(!paths.contains_key("/api/orders/1001"));
+
+        let get = &paths["/api/orders/{id}"]["get"];
+        let params = get["parameters"].as_array().unwrap();
+        assert!(params
+            .iter()
+            .any(|p| p["in"] == "path" && p["name"] == "id"));
+        assert!(params
+            .iter()
+            .any(|p| p["in"] == "query" && p["name"] == "expand" && p["example"] == "items"));
+
+        let post = &paths["/api/orders"]["post"];
+        assert_eq!(
+            post["requestBody"]["content"]["application/json"]["example"]["sku"],
+            "A1"
+        );
+        assert_eq!(
+            post["responses"]["201"]["content"]["application/json"]["example"]["id"],
+            1003
+        );
+    }
+
+    #[test]
+    fn test_sitemap_lists_pages_once() {
+        let xml = build_sitemap(&result());
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<loc>https://shop.example.com/about</loc>"));
+        assert!(xml.contains("<loc>https://shop.example.com/products/42</loc>"));
+        assert!(!xml.contains("/api/orders"));
+        assert_eq!(xml.matches("<url>").count(), 4);
+    }
+
+    #[test]
+    fn test_export_format_parse() {
+        assert_eq!("OpenAPI".parse::<ExportFormat>(), Ok(ExportFormat::OpenApi));
+        assert_eq!("sitemap".parse::<ExportFormat>(), Ok(ExportFormat::Sitemap));
+        assert!("yaml".parse::<ExportFormat>().is_err());
+    }
+}
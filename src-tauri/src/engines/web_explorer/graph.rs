@@ -41,6 +41,27 @@ impl ExplorationGraph {
         }
     }
 
+    /// Rebuild a graph from its nodes and edges
+    pub fn from_data(nodes: Vec<GraphNode>, edges: Vec<GraphEdge>) -> Self {
+        Self {
+            nodes: nodes.into_iter().map(|n| (n.id.clone(), n)).collect(),
+            edges,
+        }
+    }
+
+    /// Load a graph previously exported with [`ExplorationGraph::to_json`]
+    pub fn load_from_json(value: &serde_json::Value) -> Result<Self, serde_json::Error> {
+        let field = |name: &str| {
+            value
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| serde_json::Value::Array(Vec::new()))
+        };
+        let nodes: Vec<GraphNode> = serde_json::from_value(field("nodes"))?;
+        let edges: Vec<GraphEdge> = serde_json::from_value(field("edges"))?;
+        Ok(Self::from_data(nodes, edges))
+    }
+
     /// Add a node to the graph
     pub fn add_node(
         &mut self,
@@ -168,6 +189,35 @@ mod tests {
         assert!(graph.has_node("node2"));
     }
 
+    #[test]
+    fn test_json_round_trip() {
+        let mut graph = ExplorationGraph::new();
+        graph.add_node(
+            "a".to_string(),
+            "https://example.com".to_string(),
+            "Home".to_string(),
+            "dashboard".to_string(),
+            0,
+        );
+        graph.add_node(
+            "b".to_string(),
+            "https://example.com/login".to_string(),
+            "Login".to_string(),
+            "login".to_string(),
+            1,
+        );
+        graph.add_edge("a".to_string(), "b".to_string(), "click".to_string());
+
+        let restored = ExplorationGraph::load_from_json(&graph.to_json()).unwrap();
+        assert_eq!(restored.node_count(), 2);
+        assert_eq!(restored.edge_count(), 1);
+        assert_eq!(
+            restored.get_node("b").unwrap().url,
+            "https://example.com/login"
+        );
+        assert_eq!(restored.get_edges_from("a")[0].to, "b");
+    }
+
     #[test]
     fn test_get_edges_from() {
         let mut graph = ExplorationGraph::new();
@@ -17,19 +17,30 @@
 //! - `perception`: Page analysis using Vision LLM and snapshot
 //! - `action_executor`: Browser action execution via AgentBrowserService
 //! - `react_engine`: Main ReAct loop implementation
+//! - `seed`: Seed URLs from passive proxy traffic
+//! - `safety`: Safety policy presets guarding navigations and actions
 //! - `tool`: Rig tool interface for agent integration
+//! - `export`: OpenAPI / sitemap export of discoveries
+//! - `trace`: Step-by-step trace export (JSON / Markdown)
 
 pub mod action_executor;
+pub mod export;
 pub mod graph;
 pub mod perception;
 pub mod react_engine;
+pub mod safety;
+pub mod seed;
 pub mod tool;
+pub mod trace;
 pub mod types;
 
 // Re-export key items
 pub use graph::{ExplorationGraph, GraphEdge, GraphNode};
 pub use react_engine::ReActEngine;
+pub use safety::{SafetyLayer, SafetyPolicy, SafetyPreset};
+pub use seed::SeedUrlSource;
 pub use tool::WebExplorerTool;
+pub use trace::{ReActTrace, TraceStep};
 pub use types::{
     AIConfig, Action, ActionResult, AuthStatus, Element, ExplorationResult, ExplorationState,
     FormField, FormInfo, Observation, PageContext, PageType, ReActDecision, ScrollDirection, Step,
@@ -8,10 +8,15 @@
 
 use super::action_executor::ActionExecutor;
 use super::graph::ExplorationGraph;
+use super::safety::{SafetyDecision, SafetyLayer};
+use super::seed::{select_seed_urls, SeedUrlSource};
+use super::trace::ReActTrace;
 use super::types::*;
+use crate::agents::prompt_wrap::SystemPromptWrap;
 use crate::engines::LlmClient;
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use sentinel_tools::agent_browser::get_browser_service;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
@@ -24,24 +29,37 @@ pub struct ReActEngine {
     reasoning_llm: LlmClient,
     session_id: String,
     message_callback: Option<Arc<dyn Fn(WebExplorerMessage) + Send + Sync>>,
+    seed_source: Option<Arc<dyn SeedUrlSource>>,
+    seed_urls: Vec<String>,
+    safety: SafetyLayer,
+    /// Global prelude/postlude wrapped around the reasoning system prompt
+    system_prompt_wrap: SystemPromptWrap,
+    /// Run start time (ms since epoch), 0 before `run`
+    started_at: u64,
 }
 
 impl ReActEngine {
     /// Create a new ReAct engine (using AgentBrowserService)
-    pub fn new(config: WebExplorerConfig) -> Self {
+    ///
+    /// Fails when the safety policy does not compile, rather than exploring
+    /// under a policy the caller did not ask for.
+    pub fn new(config: WebExplorerConfig) -> Result<Self> {
         let action_executor = Arc::new(ActionExecutor::new());
         let reasoning_llm = LlmClient::new(config.ai_config.fast_llm_config());
 
-        let state = ExplorationState::new(
+        let mut state = ExplorationState::new(
             config.target_url.clone(),
             config.max_depth,
             config.max_steps,
         );
+        state.max_idle_steps = config.max_idle_steps;
 
         let graph = ExplorationGraph::new();
         let session_id = uuid::Uuid::new_v4().to_string();
+        let safety =
+            SafetyLayer::new(config.safety_policy.clone()).context("Invalid safety policy")?;
 
-        Self {
+        Ok(Self {
             config,
             state,
             graph,
@@ -49,6 +67,158 @@ impl ReActEngine {
             reasoning_llm,
             session_id,
             message_callback: None,
+            seed_source: None,
+            seed_urls: Vec::new(),
+            safety,
+            system_prompt_wrap: SystemPromptWrap::default(),
+            started_at: 0,
+        })
+    }
+
+    /// Set the source of previously seen URLs (e.g. proxy history)
+    pub fn with_seed_source(mut self, source: Arc<dyn SeedUrlSource>) -> Self {
+        self.seed_source = Some(source);
+        self
+    }
+
+    /// Run every browser action in a tab opened with `new_owned_tab`
+    pub fn with_tab(mut self, tab_id: u64) -> Self {
+        self.action_executor = Arc::new(ActionExecutor::for_tab(tab_id));
+        self
+    }
+
+    /// Set the global system prompt prelude/postlude
+    pub fn with_system_prompt_wrap(mut self, wrap: SystemPromptWrap) -> Self {
+        self.system_prompt_wrap = wrap;
+        self
+    }
+
+    /// Get the exploration graph
+    pub fn graph(&self) -> &ExplorationGraph {
+        &self.graph
+    }
+
+    /// Get the exploration state
+    pub fn state(&self) -> &ExplorationState {
+        &self.state
+    }
+
+    /// Build the step-by-step trace recorded so far
+    pub fn trace(&self) -> ReActTrace {
+        ReActTrace::from_state(
+            &self.session_id,
+            &self.config.target_url,
+            self.started_at,
+            &self.state,
+        )
+    }
+
+    /// Capture the current engine state
+    pub fn snapshot(&self) -> ExplorationSnapshot {
+        ExplorationSnapshot {
+            session_id: self.session_id.clone(),
+            target_url: self.config.target_url.clone(),
+            state: self.state.clone(),
+            graph: self.graph.to_json(),
+            seed_urls: self.seed_urls.clone(),
+            saved_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+        }
+    }
+
+    /// Snapshot file for a session. Session ids come from tool arguments on
+    /// resume, so only UUID-like ids (`[A-Za-z0-9_-]`) are accepted to keep
+    /// the path inside `dir`.
+    fn snapshot_path(dir: &str, session_id: &str) -> Result<PathBuf> {
+        let valid = !session_id.is_empty()
+            && session_id.len() <= 128
+            && session_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(anyhow!("Invalid exploration session id: {:?}", session_id));
+        }
+        Ok(Path::new(dir).join(format!("{}.json", session_id)))
+    }
+
+    /// Write a snapshot to `snapshot_dir`, if configured
+    pub async fn save_snapshot(&self) -> Result<Option<PathBuf>> {
+        let Some(dir) = self.config.snapshot_dir.as_deref() else {
+            return Ok(None);
+        };
+        tokio::fs::create_dir_all(dir).await?;
+
+        let path = Self::snapshot_path(dir, &self.session_id)?;
+        let tmp_path = path.with_extension("json.tmp");
+        let content = serde_json::to_vec(&self.snapshot())?;
+        // Write then rename, so a crash mid-write never leaves a truncated snapshot
+        tokio::fs::write(&tmp_path, content).await?;
+        tokio::fs::rename(&tmp_path, &path).await?;
+
+        Ok(Some(path))
+    }
+
+    /// Resume an interrupted exploration from its snapshot
+    ///
+    /// Returns `Ok(None)` when snapshots are disabled or no snapshot exists
+    /// for `session_id`. Step budget and depth limits come from `config`, so a
+    /// resumed run may be given a larger budget.
+    pub async fn try_resume(config: WebExplorerConfig, session_id: &str) -> Result<Option<Self>> {
+        let Some(dir) = config.snapshot_dir.clone() else {
+            return Ok(None);
+        };
+        let path = Self::snapshot_path(&dir, session_id)?;
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let content = tokio::fs::read(&path).await?;
+        let snapshot: ExplorationSnapshot =
+            serde_json::from_slice(&content).context("Failed to parse exploration snapshot")?;
+        if snapshot.target_url != config.target_url {
+            return Err(anyhow!(
+                "Snapshot {} was taken for {}, not {}",
+                session_id,
+                snapshot.target_url,
+                config.target_url
+            ));
+        }
+
+        let graph = ExplorationGraph::load_from_json(&snapshot.graph)
+            .context("Failed to restore exploration graph")?;
+        let mut state = snapshot.state;
+        state.max_steps = config.max_steps;
+        state.max_depth = config.max_depth;
+        state.max_idle_steps = config.max_idle_steps;
+
+        let mut engine = Self::new(config)?;
+        engine.session_id = snapshot.session_id;
+        engine.state = state;
+        engine.graph = graph;
+        engine.seed_urls = snapshot.seed_urls;
+
+        info!(
+            "Resumed exploration {} at step {} with {} visited URLs",
+            engine.session_id,
+            engine.state.steps_taken,
+            engine.state.visited_urls.len()
+        );
+        Ok(Some(engine))
+    }
+
+    /// Remove the snapshot once the exploration has finished
+    async fn discard_snapshot(&self) {
+        if let Some(dir) = self.config.snapshot_dir.as_deref() {
+            let Ok(path) = Self::snapshot_path(dir, &self.session_id) else {
+                return;
+            };
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("Failed to remove snapshot {}: {}", path.display(), e);
+                }
+            }
         }
     }
 
@@ -70,14 +240,13 @@ impl ReActEngine {
     pub async fn run(&mut self) -> Result<ExplorationResult> {
         info!("Starting ReAct exploration: {}", self.config.target_url);
 
-        // Ensure browser visibility matches the Vision config.
-        // Vision exploration should typically be headed (headless=false), but this also
-        // fixes cases where the global browser service was previously initialized headless.
+        // Apply the configured visibility when the shared browser is not running yet;
+        // restarting it would close tabs other runs are using
         {
             let service = get_browser_service().await;
             let mut service = service.write().await;
-            if let Err(e) = service.set_headless(self.config.headless).await {
-                error!("Failed to set browser headless mode: {}", e);
+            if !service.prefer_headless(self.config.headless) {
+                info!("Browser already running with another headless mode; reusing it");
             }
         }
 
@@ -87,10 +256,16 @@ impl ReActEngine {
         });
 
         let start_time = std::time::Instant::now();
+        self.started_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        self.seed_from_history().await;
 
-        // Initialize: Navigate to start URL
+        // Initialize: Navigate to start URL (or where a resumed run left off)
         let init_action = Action::Navigate {
-            url: self.config.target_url.clone(),
+            url: self.state.current_url.clone(),
         };
 
         match self.action_executor.execute(init_action).await {
@@ -110,6 +285,7 @@ impl ReActEngine {
                     actions_performed: 0,
                     duration_seconds: start_time.elapsed().as_secs(),
                     error: Some(error_msg),
+                    completion_reason: None,
                     graph: self.graph.to_json(),
                     api_list: Vec::new(),
                     visited_urls: Vec::new(),
@@ -124,6 +300,7 @@ impl ReActEngine {
                     actions_performed: 0,
                     duration_seconds: start_time.elapsed().as_secs(),
                     error: Some(e.to_string()),
+                    completion_reason: None,
                     graph: self.graph.to_json(),
                     api_list: Vec::new(),
                     visited_urls: Vec::new(),
@@ -135,6 +312,9 @@ impl ReActEngine {
         while self.state.should_continue() {
             match self.react_step().await {
                 Ok(should_continue) => {
+                    if let Err(e) = self.save_snapshot().await {
+                        warn!("Failed to save exploration snapshot: {}", e);
+                    }
                     if !should_continue {
                         info!("ReAct loop terminated by action");
                         break;
@@ -162,6 +342,8 @@ impl ReActEngine {
                 .complete("Max steps or depth reached".to_string());
         }
 
+        self.discard_snapshot().await;
+
         let duration = start_time.elapsed().as_secs();
         let result = ExplorationResult {
             success: true,
@@ -170,6 +352,7 @@ impl ReActEngine {
             actions_performed: self.state.steps_taken,
             duration_seconds: duration,
             error: None,
+            completion_reason: self.state.completion_reason.clone(),
             graph: self.graph.to_json(),
             api_list: self.state.discovered_apis.iter().cloned().collect(),
             visited_urls: self.state.visited_urls.iter().cloned().collect(),
@@ -181,13 +364,52 @@ impl ReActEngine {
         });
 
         info!(
-            "Exploration completed: {} pages, {} APIs, {} actions",
-            result.pages_visited, result.apis_discovered, result.actions_performed
+            "Exploration completed: {} pages, {} APIs, {} actions ({})",
+            result.pages_visited,
+            result.apis_discovered,
+            result.actions_performed,
+            result.completion_reason.as_deref().unwrap_or("unknown")
         );
 
         Ok(result)
     }
 
+    /// Seed in-scope URLs from the configured source as candidate graph nodes
+    pub async fn seed_from_history(&mut self) -> usize {
+        if !self.config.seed_from_proxy_history {
+            return 0;
+        }
+        let Some(source) = self.seed_source.clone() else {
+            return 0;
+        };
+
+        // Over-fetch, since many history entries are out of scope or duplicates
+        let candidates = source
+            .recent_urls(self.config.max_proxy_seeds.saturating_mul(4))
+            .await;
+        let seeds: Vec<String> = select_seed_urls(
+            &self.config.target_url,
+            candidates,
+            self.config.max_proxy_seeds,
+            self.config.seed_include_subdomains,
+        )
+        .into_iter()
+        .filter(|url| self.safety.check_url(url).is_allowed())
+        .collect();
+
+        for url in &seeds {
+            let id = format!("seed:{}", url);
+            if !self.graph.has_node(&id) {
+                self.graph
+                    .add_node(id, url.clone(), String::new(), "seed".to_string(), 1);
+            }
+        }
+
+        info!("Seeded {} URLs from proxy history", seeds.len());
+        self.seed_urls = seeds;
+        self.seed_urls.len()
+    }
+
     /// Execute one ReAct step
     async fn react_step(&mut self) -> Result<bool> {
         let step_number = self.state.steps_taken + 1;
@@ -228,14 +450,27 @@ impl ReActEngine {
         // Send action executing message
         self.send_message(WebExplorerMessage::ActionExecuting {
             step_number,
-            action_type: self.action_type_name(&decision.action),
-            action_details: self.action_to_json(&decision.action),
+            action_type: Self::action_type_name(&decision.action),
+            action_details: Self::action_to_json(&decision.action),
         });
 
-        let action_result = self
-            .action_executor
-            .execute(decision.action.clone())
-            .await?;
+        let action_result = match self.safety.check_action(&decision.action, &observation) {
+            SafetyDecision::Allowed => {
+                self.action_executor
+                    .execute(decision.action.clone())
+                    .await?
+            }
+            SafetyDecision::Blocked { reason } => {
+                warn!("Action blocked by safety policy: {}", reason);
+                // Feed the refusal back so the LLM picks something else next step
+                ActionResult {
+                    success: false,
+                    new_url: None,
+                    error: Some(format!("Blocked by safety policy: {}", reason)),
+                    observation: None,
+                }
+            }
+        };
 
         // Send action result
         self.send_message(WebExplorerMessage::ActionResult {
@@ -387,7 +622,9 @@ impl ReActEngine {
     async fn think(&self, observation: &Observation) -> Result<ReActDecision> {
         debug!("Thinking about next action");
 
-        let system_prompt = self.build_thinking_system_prompt();
+        let system_prompt = self
+            .system_prompt_wrap
+            .apply(&self.build_thinking_system_prompt());
         let user_prompt = self.build_thinking_user_prompt(observation);
 
         let response = self
@@ -408,10 +645,13 @@ impl ReActEngine {
     ) -> Result<()> {
         let prev_url = self.state.current_url.clone();
         let prev_depth = self.state.current_depth;
+        let known_pages = self.state.visited_urls.len();
+        let known_apis = self.state.discovered_apis.len();
 
         // Record step in history
         let step = Step {
             step_number: self.state.steps_taken + 1,
+            url: prev_url.clone(),
             observation: observation.clone(),
             thought: decision.thought.clone(),
             action: decision.action.clone(),
@@ -511,6 +751,11 @@ impl ReActEngine {
             format!("{:?}", decision.action).to_lowercase(),
         );
 
+        self.state.note_progress(
+            self.state.visited_urls.len() > known_pages
+                || self.state.discovered_apis.len() > known_apis,
+        );
+
         Ok(())
     }
 
@@ -611,7 +856,7 @@ Links:
 
 Recent History (last 3 steps):
 {}
-
+{}
 Decide what to do next. Use @eN refs from the snapshot for click/fill actions.
 **IMPORTANT**: You must answer in Chinese (Simplified Chinese)"#,
             self.state.current_url,
@@ -629,7 +874,31 @@ Decide what to do next. Use @eN refs from the snapshot for click/fill actions.
             elements_section,
             self.format_forms(&observation.forms),
             self.format_links(&observation.links),
-            recent_history
+            recent_history,
+            self.format_unvisited_seeds()
+        )
+    }
+
+    /// Format proxy-seeded URLs that have not been visited yet
+    fn format_unvisited_seeds(&self) -> String {
+        let pending: Vec<&String> = self
+            .seed_urls
+            .iter()
+            .filter(|url| !self.state.visited_urls.contains(*url))
+            .take(20)
+            .collect();
+        if pending.is_empty() {
+            return String::new();
+        }
+
+        let list = pending
+            .iter()
+            .map(|url| format!("- {}", url))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            "\nKnown URLs from proxy history (not yet visited, navigate to them if relevant):\n{}\n",
+            list
         )
     }
 
@@ -818,7 +1087,7 @@ Decide what to do next. Use @eN refs from the snapshot for click/fill actions.
     }
 
     /// Get action type name
-    fn action_type_name(&self, action: &Action) -> String {
+    pub(crate) fn action_type_name(action: &Action) -> String {
         match action {
             Action::Navigate { .. } => "navigate".to_string(),
             Action::Click { .. } => "click".to_string(),
@@ -833,7 +1102,7 @@ Decide what to do next. Use @eN refs from the snapshot for click/fill actions.
     }
 
     /// Convert action to JSON for UI
-    fn action_to_json(&self, action: &Action) -> serde_json::Value {
+    pub(crate) fn action_to_json(action: &Action) -> serde_json::Value {
         match action {
             Action::Navigate { url } => serde_json::json!({
                 "url": url
@@ -952,3 +1221,255 @@ Decide what to do next. Use @eN refs from the snapshot for click/fill actions.
         domain
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::web_explorer::safety::SafetyPolicy;
+    use async_trait::async_trait;
+
+    struct MockHistory(Vec<String>);
+
+    #[async_trait]
+    impl SeedUrlSource for MockHistory {
+        async fn recent_urls(&self, limit: usize) -> Vec<String> {
+            self.0.iter().take(limit).cloned().collect()
+        }
+    }
+
+    fn engine(seed: bool) -> ReActEngine {
+        let config = WebExplorerConfig {
+            target_url: "https://shop.example.com/".to_string(),
+            seed_from_proxy_history: seed,
+            ..Default::default()
+        };
+        let history = MockHistory(vec![
+            "https://shop.example.com/cart".to_string(),
+            "https://shop.example.com/account/orders".to_string(),
+            "https://cdn.other.net/lib.js".to_string(),
+            "https://shop.example.com/cart".to_string(),
+        ]);
+        ReActEngine::new(config)
+            .unwrap()
+            .with_seed_source(Arc::new(history))
+    }
+
+    #[tokio::test]
+    async fn test_seed_nodes_added_from_history() {
+        let mut engine = engine(true);
+        assert_eq!(engine.seed_from_history().await, 2);
+
+        let graph = engine.graph();
+        assert_eq!(graph.node_count(), 2);
+        let node = graph
+            .get_node("seed:https://shop.example.com/account/orders")
+            .unwrap();
+        assert_eq!(node.page_type, "seed");
+        assert!(graph.has_node("seed:https://shop.example.com/cart"));
+        assert!(engine
+            .format_unvisited_seeds()
+            .contains("https://shop.example.com/account/orders"));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip_resumes_progress() {
+        let dir = std::env::temp_dir().join(format!("web-explorer-{}", uuid::Uuid::new_v4()));
+        let config = WebExplorerConfig {
+            target_url: "https://shop.example.com/".to_string(),
+            seed_from_proxy_history: true,
+            snapshot_dir: Some(dir.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+
+        let history = MockHistory(vec!["https://shop.example.com/cart".to_string()]);
+        let mut engine = ReActEngine::new(config.clone())
+            .unwrap()
+            .with_seed_source(Arc::new(history));
+        engine.seed_from_history().await;
+
+        // Simulate progress mid-exploration
+        engine.state.steps_taken = 7;
+        engine.state.current_depth = 2;
+        engine.state.current_url = "https://shop.example.com/account".to_string();
+        engine
+            .state
+            .mark_visited("https://shop.example.com/account".to_string());
+        engine
+            .state
+            .add_api("GET https://shop.example.com/api/me".to_string());
+        engine.graph.add_node(
+            "home".to_string(),
+            "https://shop.example.com/".to_string(),
+            "Home".to_string(),
+            "unknown".to_string(),
+            0,
+        );
+        engine.graph.add_node(
+            "account".to_string(),
+            "https://shop.example.com/account".to_string(),
+            "Account".to_string(),
+            "dashboard".to_string(),
+            1,
+        );
+        engine.graph.add_edge(
+            "home".to_string(),
+            "account".to_string(),
+            "click".to_string(),
+        );
+
+        let path = engine.save_snapshot().await.unwrap().unwrap();
+        assert!(path.exists());
+
+        let resumed = ReActEngine::try_resume(config.clone(), engine.session_id())
+            .await
+            .unwrap()
+            .expect("snapshot should exist");
+
+        assert_eq!(resumed.session_id(), engine.session_id());
+        assert_eq!(resumed.state().steps_taken, 7);
+        assert_eq!(resumed.state().current_depth, 2);
+        assert_eq!(
+            resumed.state().current_url,
+            "https://shop.example.com/account"
+        );
+        assert_eq!(resumed.state().visited_urls, engine.state().visited_urls);
+        assert!(resumed
+            .state()
+            .is_visited("https://shop.example.com/account"));
+        assert_eq!(
+            resumed.state().discovered_apis,
+            engine.state().discovered_apis
+        );
+
+        let node_ids = |e: &ReActEngine| {
+            let mut ids: Vec<String> = e
+                .graph()
+                .get_all_nodes()
+                .iter()
+                .map(|n| n.id.clone())
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(node_ids(&resumed), node_ids(&engine));
+        assert_eq!(resumed.graph().edge_count(), 1);
+        assert_eq!(resumed.seed_urls, engine.seed_urls);
+
+        // A snapshot for another target is rejected
+        let other = WebExplorerConfig {
+            target_url: "https://other.example.com/".to_string(),
+            ..config.clone()
+        };
+        assert!(ReActEngine::try_resume(other, engine.session_id())
+            .await
+            .is_err());
+
+        engine.discard_snapshot().await;
+        assert!(ReActEngine::try_resume(config, engine.session_id())
+            .await
+            .unwrap()
+            .is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_resume_rejects_path_traversal_ids() {
+        let dir = std::env::temp_dir().join(format!("web-explorer-{}", uuid::Uuid::new_v4()));
+        let config = WebExplorerConfig {
+            target_url: "https://shop.example.com/".to_string(),
+            snapshot_dir: Some(dir.join("snapshots").to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        // A JSON file outside the snapshot directory
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("outside.json"), b"{}").unwrap();
+
+        for session_id in ["../outside", "/etc/passwd", "a/b", "", "..\\outside"] {
+            assert!(
+                ReActEngine::try_resume(config.clone(), session_id)
+                    .await
+                    .is_err(),
+                "{:?}",
+                session_id
+            );
+        }
+        let id = uuid::Uuid::new_v4().to_string();
+        assert!(ReActEngine::try_resume(config, &id)
+            .await
+            .unwrap()
+            .is_none());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_no_progress_stops_before_step_budget() {
+        let engine = ReActEngine::new(WebExplorerConfig {
+            target_url: "https://shop.example.com/".to_string(),
+            max_steps: 100,
+            max_idle_steps: 3,
+            ..Default::default()
+        })
+        .unwrap();
+        let mut state = engine.state().clone();
+
+        // A new page resets the idle run
+        state.steps_taken += 1;
+        state.mark_visited("https://shop.example.com/cart".to_string());
+        state.note_progress(true);
+        while state.should_continue() {
+            state.steps_taken += 1;
+            state.note_progress(false);
+        }
+
+        assert_eq!(state.steps_taken, 4);
+        assert_eq!(
+            state.completion_reason.as_deref(),
+            Some("No progress in 3 consecutive steps")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idle_check_is_off_by_default() {
+        let config = WebExplorerConfig {
+            target_url: "https://shop.example.com/".to_string(),
+            max_steps: 20,
+            ..Default::default()
+        };
+        assert_eq!(config.max_idle_steps, 0);
+        let engine = ReActEngine::new(config).unwrap();
+        let mut state = engine.state().clone();
+
+        // Without an idle budget only the step budget ends the run
+        while state.should_continue() {
+            state.steps_taken += 1;
+            state.note_progress(false);
+        }
+
+        assert_eq!(state.steps_taken, 20);
+        assert!(!state
+            .completion_reason
+            .as_deref()
+            .is_some_and(|r| r.starts_with("No progress")));
+    }
+
+    #[tokio::test]
+    async fn test_seeding_disabled_by_config() {
+        let mut engine = engine(false);
+        assert_eq!(engine.seed_from_history().await, 0);
+        assert_eq!(engine.graph().node_count(), 0);
+        assert!(engine.format_unvisited_seeds().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_safety_policy_is_rejected() {
+        let config = WebExplorerConfig {
+            target_url: "https://shop.example.com/".to_string(),
+            safety_policy: SafetyPolicy::custom(vec!["(".to_string()], Vec::new(), true),
+            ..Default::default()
+        };
+        let err = ReActEngine::new(config).err().unwrap();
+        assert!(err.to_string().contains("Invalid safety policy"));
+    }
+}
@@ -0,0 +1,506 @@
+//! Safety policy for exploration actions
+//!
+//! Every navigation and interaction chosen by the LLM passes through a
+//! [`SafetyLayer`] before it reaches the browser. The policy is picked from a
+//! named preset or built from custom regex lists.
+
+use super::types::{Action, Element, Observation};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Session-ending URLs and controls, blocked by every preset
+const LOGOUT_PATTERNS: &[&str] = &[r"log[-_ ]?out", r"sign[-_ ]?out", r"log[-_ ]?off"];
+
+/// Destructive operations that delete or irreversibly change data
+const DESTRUCTIVE_PATTERNS: &[&str] = &[
+    r"\bdelete\b",
+    r"\bremove\b",
+    r"\bdestroy\b",
+    r"\bdrop\b",
+    r"\bpurge\b",
+    r"\bdeactivate\b",
+    r"\breset\b",
+    r"删除",
+    r"移除",
+];
+
+/// Operations that change server state without being destructive
+const STATE_CHANGING_PATTERNS: &[&str] = &[
+    r"\bsubmit\b",
+    r"\bsave\b",
+    r"\bupdate\b",
+    r"\bcreate\b",
+    r"\bsend\b",
+    r"\bconfirm\b",
+    r"\bpay\b",
+    r"\bcheckout\b",
+    r"\bpurchase\b",
+    r"\bupload\b",
+    r"提交",
+    r"保存",
+];
+
+/// Named safety presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SafetyPreset {
+    /// Read-only: blocks form submission and any state-changing control
+    Conservative,
+    /// Blocks logout and destructive controls, allows ordinary forms
+    #[default]
+    Balanced,
+    /// Only blocks logout; forms are submitted with generated data
+    Aggressive,
+    /// User-supplied pattern lists
+    Custom,
+}
+
+impl FromStr for SafetyPreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "conservative" => Ok(Self::Conservative),
+            "balanced" => Ok(Self::Balanced),
+            "aggressive" => Ok(Self::Aggressive),
+            "custom" => Ok(Self::Custom),
+            other => Err(format!("Unknown safety preset: {}", other)),
+        }
+    }
+}
+
+/// Safety policy applied to navigations and actions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafetyPolicy {
+    pub preset: SafetyPreset,
+    /// Regexes matched (case-insensitively) against navigation URLs
+    pub blocked_url_patterns: Vec<String>,
+    /// Regexes matched (case-insensitively) against the target element's label
+    pub blocked_action_patterns: Vec<String>,
+    /// Whether `Submit` actions are allowed
+    pub allow_form_submission: bool,
+}
+
+impl SafetyPolicy {
+    /// Build the policy for a named preset
+    ///
+    /// `SafetyPreset::Custom` yields an empty policy; use [`SafetyPolicy::custom`].
+    pub fn from_preset(preset: SafetyPreset) -> Self {
+        let patterns = |groups: &[&[&str]]| -> Vec<String> {
+            groups
+                .iter()
+                .flat_map(|g| g.iter().map(|p| p.to_string()))
+                .collect()
+        };
+
+        match preset {
+            SafetyPreset::Conservative => Self {
+                preset,
+                blocked_url_patterns: patterns(&[LOGOUT_PATTERNS, DESTRUCTIVE_PATTERNS]),
+                blocked_action_patterns: patterns(&[
+                    LOGOUT_PATTERNS,
+                    DESTRUCTIVE_PATTERNS,
+                    STATE_CHANGING_PATTERNS,
+                ]),
+                allow_form_submission: false,
+            },
+            SafetyPreset::Balanced => Self {
+                preset,
+                blocked_url_patterns: patterns(&[LOGOUT_PATTERNS, DESTRUCTIVE_PATTERNS]),
+                blocked_action_patterns: patterns(&[LOGOUT_PATTERNS, DESTRUCTIVE_PATTERNS]),
+                allow_form_submission: true,
+            },
+            SafetyPreset::Aggressive => Self {
+                preset,
+                blocked_url_patterns: patterns(&[LOGOUT_PATTERNS]),
+                blocked_action_patterns: patterns(&[LOGOUT_PATTERNS]),
+                allow_form_submission: true,
+            },
+            SafetyPreset::Custom => Self::custom(Vec::new(), Vec::new(), true),
+        }
+    }
+
+    /// Build a policy from custom regex lists
+    pub fn custom(
+        blocked_url_patterns: Vec<String>,
+        blocked_action_patterns: Vec<String>,
+        allow_form_submission: bool,
+    ) -> Self {
+        Self {
+            preset: SafetyPreset::Custom,
+            blocked_url_patterns,
+            blocked_action_patterns,
+            allow_form_submission,
+        }
+    }
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self::from_preset(SafetyPreset::default())
+    }
+}
+
+/// Outcome of a safety check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SafetyDecision {
+    Allowed,
+    Blocked { reason: String },
+}
+
+impl SafetyDecision {
+    pub fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allowed)
+    }
+}
+
+/// Compiled safety policy
+#[derive(Debug, Clone)]
+pub struct SafetyLayer {
+    policy: SafetyPolicy,
+    url_patterns: Vec<Regex>,
+    action_patterns: Vec<Regex>,
+}
+
+impl SafetyLayer {
+    /// Compile a policy, failing on the first invalid regex
+    pub fn new(policy: SafetyPolicy) -> Result<Self, regex::Error> {
+        let compile = |patterns: &[String]| -> Result<Vec<Regex>, regex::Error> {
+            patterns
+                .iter()
+                .map(|p| RegexBuilder::new(p).case_insensitive(true).build())
+                .collect()
+        };
+        Ok(Self {
+            url_patterns: compile(&policy.blocked_url_patterns)?,
+            action_patterns: compile(&policy.blocked_action_patterns)?,
+            policy,
+        })
+    }
+
+    pub fn policy(&self) -> &SafetyPolicy {
+        &self.policy
+    }
+
+    /// Check whether navigating to `url` is allowed
+    pub fn check_url(&self, url: &str) -> SafetyDecision {
+        match self.url_patterns.iter().find(|re| re.is_match(url)) {
+            Some(re) => SafetyDecision::Blocked {
+                reason: format!("URL {} matches blocked pattern '{}'", url, re.as_str()),
+            },
+            None => SafetyDecision::Allowed,
+        }
+    }
+
+    /// Check whether an action is allowed on the observed page
+    ///
+    /// Clicks and fills are judged by the label of the element they target,
+    /// resolved from the observation the action was chosen on: by index, by
+    /// selector, or by the element box containing a click point. A selector
+    /// that matches no element is judged by its own text, and a point outside
+    /// every element box is allowed. An index missing from the page is blocked.
+    pub fn check_action(&self, action: &Action, observation: &Observation) -> SafetyDecision {
+        match action {
+            Action::Navigate { url } => self.check_url(url),
+            Action::Submit { selector } => {
+                if !self.policy.allow_form_submission {
+                    return SafetyDecision::Blocked {
+                        reason: format!(
+                            "Form submission is disabled by the {:?} policy",
+                            self.policy.preset
+                        ),
+                    };
+                }
+                self.check_label(selector)
+            }
+            Action::Click {
+                index,
+                selector,
+                x,
+                y,
+            } => self.check_target(observation, *index, selector.as_deref(), x.zip(*y)),
+            Action::Fill {
+                index, selector, ..
+            } => self.check_target(observation, *index, selector.as_deref(), None),
+            Action::Scroll { .. }
+            | Action::Wait { .. }
+            | Action::TakeSnapshot
+            | Action::GoBack
+            | Action::Stop { .. } => SafetyDecision::Allowed,
+        }
+    }
+
+    fn check_label(&self, label: &str) -> SafetyDecision {
+        match self.action_patterns.iter().find(|re| re.is_match(label)) {
+            Some(re) => SafetyDecision::Blocked {
+                reason: format!(
+                    "Target '{}' matches blocked pattern '{}'",
+                    label,
+                    re.as_str()
+                ),
+            },
+            None => SafetyDecision::Allowed,
+        }
+    }
+
+    fn check_target(
+        &self,
+        observation: &Observation,
+        index: Option<u32>,
+        selector: Option<&str>,
+        point: Option<(i32, i32)>,
+    ) -> SafetyDecision {
+        match (index, selector, point) {
+            (Some(idx), _, _) => {
+                let ref_id = format!("@e{}", idx);
+                match observation.elements.iter().find(|e| e.element_id == ref_id) {
+                    Some(element) => self.check_label(&Self::element_label(element)),
+                    None => SafetyDecision::Blocked {
+                        reason: format!("Target {} is not on the current page", ref_id),
+                    },
+                }
+            }
+            (None, Some(sel), _) => {
+                match observation
+                    .elements
+                    .iter()
+                    .find(|e| e.selector == sel || e.element_id == sel)
+                {
+                    Some(element) => self.check_label(&Self::element_label(element)),
+                    None => self.check_label(sel),
+                }
+            }
+            (None, None, Some((x, y))) => match Self::element_at(observation, x, y) {
+                Some(element) => self.check_label(&Self::element_label(element)),
+                None => SafetyDecision::Allowed,
+            },
+            (None, None, None) => SafetyDecision::Blocked {
+                reason: "Target without index, selector or coordinates".to_string(),
+            },
+        }
+    }
+
+    /// Find the innermost element whose box contains the point
+    fn element_at(observation: &Observation, x: i32, y: i32) -> Option<&Element> {
+        observation
+            .elements
+            .iter()
+            .filter_map(|e| {
+                let (left, top) = (e.x?, e.y?);
+                let (width, height) = (e.width? as i64, e.height? as i64);
+                let (px, py) = (x as i64 - left as i64, y as i64 - top as i64);
+                ((0..=width).contains(&px) && (0..=height).contains(&py))
+                    .then_some((width * height, e))
+            })
+            .min_by_key(|(area, _)| *area)
+            .map(|(_, e)| e)
+    }
+
+    /// Human-readable label for an element
+    fn element_label(element: &Element) -> String {
+        let mut label = element.text.clone().unwrap_or_default();
+        if let Some(href) = &element.href {
+            label = format!("{} {}", label, href);
+        }
+        label
+    }
+}
+
+impl Default for SafetyLayer {
+    fn default() -> Self {
+        Self::new(SafetyPolicy::default()).expect("built-in safety patterns are valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::web_explorer::types::{AuthStatus, Element, PageType};
+
+    fn observation_with(label: &str) -> Observation {
+        Observation {
+            page_type: PageType::Unknown,
+            description: String::new(),
+            auth_status: AuthStatus::Unknown,
+            elements: vec![Element {
+                element_id: "@e3".to_string(),
+                element_type: "button".to_string(),
+                selector: "@e3".to_string(),
+                text: Some(label.to_string()),
+                href: None,
+                x: None,
+                y: None,
+                width: None,
+                height: None,
+                is_visible: true,
+            }],
+            forms: Vec::new(),
+            links: Vec::new(),
+            api_endpoints: Vec::new(),
+            confidence: 1.0,
+            metadata: Default::default(),
+            snapshot_tree: None,
+        }
+    }
+
+    fn layer(preset: SafetyPreset) -> SafetyLayer {
+        SafetyLayer::new(SafetyPolicy::from_preset(preset)).unwrap()
+    }
+
+    fn click(index: u32) -> Action {
+        Action::Click {
+            index: Some(index),
+            selector: None,
+            x: None,
+            y: None,
+        }
+    }
+
+    #[test]
+    fn test_delete_blocked_conservative_allowed_aggressive() {
+        let observation = observation_with("Delete account");
+
+        assert!(!layer(SafetyPreset::Conservative)
+            .check_action(&click(3), &observation)
+            .is_allowed());
+        assert!(layer(SafetyPreset::Aggressive)
+            .check_action(&click(3), &observation)
+            .is_allowed());
+
+        let delete_url = "https://app.example.com/users/7/delete";
+        assert!(!layer(SafetyPreset::Conservative)
+            .check_url(delete_url)
+            .is_allowed());
+        assert!(layer(SafetyPreset::Aggressive)
+            .check_url(delete_url)
+            .is_allowed());
+    }
+
+    #[test]
+    fn test_form_submission_by_preset() {
+        let observation = observation_with("Search");
+        let submit = Action::Submit {
+            selector: "#search-form".to_string(),
+        };
+
+        assert!(!layer(SafetyPreset::Conservative)
+            .check_action(&submit, &observation)
+            .is_allowed());
+        assert!(layer(SafetyPreset::Balanced)
+            .check_action(&submit, &observation)
+            .is_allowed());
+        assert!(layer(SafetyPreset::Aggressive)
+            .check_action(&submit, &observation)
+            .is_allowed());
+    }
+
+    #[test]
+    fn test_logout_blocked_by_every_preset() {
+        for preset in [
+            SafetyPreset::Conservative,
+            SafetyPreset::Balanced,
+            SafetyPreset::Aggressive,
+        ] {
+            assert!(!layer(preset)
+                .check_url("https://app.example.com/logout")
+                .is_allowed());
+            assert!(!layer(preset)
+                .check_action(&click(3), &observation_with("Sign out"))
+                .is_allowed());
+        }
+    }
+
+    #[test]
+    fn test_custom_patterns() {
+        let policy = SafetyPolicy::custom(
+            vec![r"/admin/".to_string()],
+            vec![r"^archive$".to_string()],
+            true,
+        );
+        let layer = SafetyLayer::new(policy).unwrap();
+
+        assert!(!layer
+            .check_url("https://app.example.com/admin/users")
+            .is_allowed());
+        assert!(layer
+            .check_url("https://app.example.com/logout")
+            .is_allowed());
+        assert!(!layer
+            .check_action(&click(3), &observation_with("Archive"))
+            .is_allowed());
+
+        assert!(
+            SafetyLayer::new(SafetyPolicy::custom(vec!["(".to_string()], vec![], true)).is_err()
+        );
+        assert_eq!(
+            "Aggressive".parse::<SafetyPreset>().unwrap(),
+            SafetyPreset::Aggressive
+        );
+    }
+
+    #[test]
+    fn test_missing_index_is_blocked() {
+        let observation = observation_with("Search");
+        let layer = SafetyLayer::new(SafetyPolicy::custom(vec![], vec![], true)).unwrap();
+
+        assert!(layer.check_action(&click(3), &observation).is_allowed());
+        assert!(!layer.check_action(&click(9), &observation).is_allowed());
+    }
+
+    #[test]
+    fn test_unmatched_selector_is_judged_by_its_text() {
+        let observation = observation_with("Search");
+        let fill = |selector: &str| Action::Fill {
+            index: None,
+            selector: Some(selector.to_string()),
+            value: "x".to_string(),
+        };
+
+        let permissive = SafetyLayer::new(SafetyPolicy::custom(vec![], vec![], true)).unwrap();
+        assert!(permissive
+            .check_action(&fill("form input[name=q]"), &observation)
+            .is_allowed());
+        assert!(permissive
+            .check_action(&fill("#delete-btn"), &observation)
+            .is_allowed());
+
+        let balanced = layer(SafetyPreset::Balanced);
+        assert!(balanced
+            .check_action(&fill("form input[name=q]"), &observation)
+            .is_allowed());
+        assert!(!balanced
+            .check_action(&fill("#delete-btn"), &observation)
+            .is_allowed());
+    }
+
+    #[test]
+    fn test_coordinate_click_checks_element_under_point() {
+        let mut observation = observation_with("Delete account");
+        let button = &mut observation.elements[0];
+        button.x = Some(100);
+        button.y = Some(200);
+        button.width = Some(80);
+        button.height = Some(30);
+        let click_at = |x: i32, y: i32| Action::Click {
+            index: None,
+            selector: None,
+            x: Some(x),
+            y: Some(y),
+        };
+
+        let balanced = layer(SafetyPreset::Balanced);
+        assert!(!balanced
+            .check_action(&click_at(140, 215), &observation)
+            .is_allowed());
+        // Outside every element box: nothing to check against
+        assert!(balanced
+            .check_action(&click_at(10, 20), &observation)
+            .is_allowed());
+
+        let permissive = SafetyLayer::new(SafetyPolicy::custom(vec![], vec![], true)).unwrap();
+        assert!(permissive
+            .check_action(&click_at(140, 215), &observation)
+            .is_allowed());
+    }
+}
@@ -0,0 +1,162 @@
+//! Seed URLs from passive proxy traffic
+//!
+//! Pages the user already browsed through the proxy are fed into the
+//! exploration graph as candidate nodes, so the explorer does not have to
+//! rediscover them from scratch.
+
+use async_trait::async_trait;
+use sentinel_traffic::{HttpRequestFilters, ProxyHistoryCache};
+use std::collections::HashSet;
+use url::Url;
+
+/// Source of previously seen URLs
+#[async_trait]
+pub trait SeedUrlSource: Send + Sync {
+    /// Return recently seen URLs, newest first
+    async fn recent_urls(&self, limit: usize) -> Vec<String>;
+}
+
+#[async_trait]
+impl SeedUrlSource for ProxyHistoryCache {
+    async fn recent_urls(&self, limit: usize) -> Vec<String> {
+        self.list_http_requests(HttpRequestFilters {
+            method: Some("GET".to_string()),
+            status_code_min: Some(200),
+            status_code_max: Some(399),
+            limit: Some(limit),
+            ..Default::default()
+        })
+        .await
+        .into_iter()
+        .map(|r| r.url)
+        .collect()
+    }
+}
+
+/// Static resources that are not worth visiting as pages
+const STATIC_EXTENSIONS: &[&str] = &[
+    "js", "css", "map", "png", "jpg", "jpeg", "gif", "svg", "ico", "webp", "woff", "woff2", "ttf",
+    "eot", "mp4", "mp3", "pdf", "zip",
+];
+
+/// Filter candidate URLs down to in-scope, de-duplicated page URLs
+///
+/// A URL is in scope when it uses http(s), its host equals the target host
+/// (or is a subdomain of it, when `include_subdomains` is set), and its path
+/// lies under the target's base path (the target path up to and including
+/// the last `/`).
+pub fn select_seed_urls(
+    target_url: &str,
+    candidates: Vec<String>,
+    limit: usize,
+    include_subdomains: bool,
+) -> Vec<String> {
+    let Ok(target) = Url::parse(target_url) else {
+        return Vec::new();
+    };
+    let Some(target_host) = target.host_str().map(|h| h.to_lowercase()) else {
+        return Vec::new();
+    };
+    let base_path = match target.path().rfind('/') {
+        Some(idx) => target.path()[..=idx].to_string(),
+        None => "/".to_string(),
+    };
+
+    let mut seen = HashSet::new();
+    seen.insert(normalize(&target));
+
+    let mut seeds = Vec::new();
+    for candidate in candidates {
+        if seeds.len() >= limit {
+            break;
+        }
+        let Ok(url) = Url::parse(&candidate) else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            continue;
+        }
+        let Some(host) = url.host_str().map(|h| h.to_lowercase()) else {
+            continue;
+        };
+        let in_scope = host == target_host
+            || (include_subdomains && host.ends_with(&format!(".{}", target_host)));
+        if !in_scope {
+            continue;
+        }
+        if !url.path().starts_with(&base_path) || is_static_resource(&url) {
+            continue;
+        }
+
+        let normalized = normalize(&url);
+        if seen.insert(normalized.clone()) {
+            seeds.push(normalized);
+        }
+    }
+
+    seeds
+}
+
+fn normalize(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.to_string()
+}
+
+fn is_static_resource(url: &Url) -> bool {
+    url.path()
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| STATIC_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_seed_urls_respects_scope() {
+        let candidates = vec![
+            "https://app.example.com/portal/orders?page=2".to_string(),
+            "https://app.example.com/portal/orders?page=2#top".to_string(),
+            "https://api.app.example.com/portal/v1/users".to_string(),
+            "https://app.example.com/admin/".to_string(),
+            "https://app.example.com/portal/static/app.js".to_string(),
+            "https://evil.com/portal/".to_string(),
+            "https://app.example.com/portal/login".to_string(),
+        ];
+
+        let seeds = select_seed_urls(
+            "https://app.example.com/portal/login",
+            candidates.clone(),
+            10,
+            false,
+        );
+        assert_eq!(
+            seeds,
+            vec!["https://app.example.com/portal/orders?page=2".to_string()]
+        );
+
+        let seeds = select_seed_urls("https://app.example.com/portal/login", candidates, 10, true);
+        assert_eq!(
+            seeds,
+            vec![
+                "https://app.example.com/portal/orders?page=2".to_string(),
+                "https://api.app.example.com/portal/v1/users".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_select_seed_urls_limit() {
+        let candidates = (0..10)
+            .map(|i| format!("https://example.com/page/{}", i))
+            .collect();
+        assert_eq!(
+            select_seed_urls("https://example.com", candidates, 3, false).len(),
+            3
+        );
+    }
+}
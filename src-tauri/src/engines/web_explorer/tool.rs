@@ -2,16 +2,51 @@
 //! Uses AgentBrowserService for browser automation
 
 use super::react_engine::ReActEngine;
+use super::safety::{SafetyLayer, SafetyPolicy, SafetyPreset};
 use super::types::{WebExplorerConfig, WebExplorerMessage};
 use crate::engines::LlmConfig;
+use crate::managers::resource_registry::{register_resource, release_resource, ResourceKind};
+use crate::utils::log_redact::SecretRedactor;
 use rig::completion::ToolDefinition;
 use rig::tool::{Tool, ToolError};
+use sentinel_tools::agent_browser::get_browser_service;
 use serde::Deserialize;
 use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 
+/// Web explorer runs are headed so the user can follow along
+const RUN_HEADLESS: bool = false;
+
+/// Open a dedicated browser tab for one run and register it with the run's
+/// cleanup guard; returns the tab id and the resource id
+async fn open_run_tab(execution_id: &str, headless: bool) -> anyhow::Result<(u64, String)> {
+    let service = get_browser_service().await;
+    let tab_id = {
+        let mut service = service.write().await;
+        // The browser is shared with other runs: only pick the mode when it is not
+        // running yet, never restart it
+        if !service.prefer_headless(headless) {
+            tracing::info!("Browser already running with another headless mode; reusing it");
+        }
+        service.new_owned_tab().await?
+    };
+
+    let resource = register_resource(
+        execution_id,
+        ResourceKind::Browser,
+        &format!("web_explorer tab #{}", tab_id),
+        move || async move {
+            let service = get_browser_service().await;
+            let mut service = service.write().await;
+            // Close this run's tab only, wherever other runs' closes moved it
+            service.close_owned_tab(tab_id).await.map(|_| ())
+        },
+    );
+    Ok((tab_id, resource))
+}
+
 /// Get message type name for frontend
 fn msg_type_name(msg: &WebExplorerMessage) -> String {
     match msg {
@@ -141,9 +176,50 @@ pub struct WebExplorerArgs {
     max_depth: Option<u32>,
     /// Maximum steps
     max_steps: Option<u32>,
+    /// Consecutive steps without new pages or APIs before stopping early
+    max_idle_steps: Option<u32>,
     /// Custom HTTP headers
     #[allow(dead_code)]
     headers: Option<HashMap<String, String>>,
+    /// Seed exploration with in-scope URLs from proxy history
+    seed_from_proxy_history: Option<bool>,
+    /// Also seed URLs on subdomains of the target host
+    seed_include_subdomains: Option<bool>,
+    /// Resume an interrupted exploration from its snapshot
+    resume_session_id: Option<String>,
+    /// Safety preset: conservative, balanced, aggressive or custom
+    safety_preset: Option<String>,
+    /// Extra URL regexes to block (the whole list when preset is custom)
+    blocked_url_patterns: Option<Vec<String>>,
+    /// Extra element-label regexes to block (the whole list when preset is custom)
+    blocked_action_patterns: Option<Vec<String>>,
+    /// Extra secret regexes redacted from the exported trace
+    trace_redact_patterns: Option<Vec<String>>,
+}
+
+impl WebExplorerArgs {
+    /// Resolve the safety policy from the preset name and custom pattern lists
+    fn safety_policy(&self) -> Result<SafetyPolicy, String> {
+        let preset = match self.safety_preset.as_deref() {
+            Some(name) => name.parse::<SafetyPreset>()?,
+            None => SafetyPreset::default(),
+        };
+        let url_patterns = self.blocked_url_patterns.clone().unwrap_or_default();
+        let action_patterns = self.blocked_action_patterns.clone().unwrap_or_default();
+
+        let policy = if preset == SafetyPreset::Custom {
+            SafetyPolicy::custom(url_patterns, action_patterns, true)
+        } else {
+            let mut policy = SafetyPolicy::from_preset(preset);
+            policy.blocked_url_patterns.extend(url_patterns);
+            policy.blocked_action_patterns.extend(action_patterns);
+            policy
+        };
+
+        // Reject invalid regexes up front instead of silently falling back
+        SafetyLayer::new(policy.clone()).map_err(|e| format!("Invalid safety pattern: {}", e))?;
+        Ok(policy)
+    }
 }
 
 /// Web Explorer Tool for Agent integration
@@ -204,10 +280,46 @@ impl Tool for WebExplorerTool {
                         "type": "integer", 
                         "description": "Maximum exploration steps (default: 100)"
                     },
+                    "max_idle_steps": {
+                        "type": "integer",
+                        "description": "Stop after this many consecutive steps without new pages or APIs (default: 0, disabled)"
+                    },
                     "headers": {
                         "type": "object",
                         "description": "Custom HTTP headers (e.g. Authorization)",
                         "additionalProperties": { "type": "string" }
+                    },
+                    "seed_from_proxy_history": {
+                        "type": "boolean",
+                        "description": "Start from in-scope URLs already captured by the passive proxy (default: false)"
+                    },
+                    "seed_include_subdomains": {
+                        "type": "boolean",
+                        "description": "When seeding, also include URLs on subdomains of the target host (default: false)"
+                    },
+                    "resume_session_id": {
+                        "type": "string",
+                        "description": "Session id of an interrupted exploration to resume from its snapshot"
+                    },
+                    "safety_preset": {
+                        "type": "string",
+                        "enum": ["conservative", "balanced", "aggressive", "custom"],
+                        "description": "Safety policy: conservative blocks all state-changing actions, balanced (default) blocks logout and destructive actions, aggressive submits forms with generated data"
+                    },
+                    "blocked_url_patterns": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Additional regexes for URLs that must not be visited"
+                    },
+                    "blocked_action_patterns": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Additional regexes for element labels that must not be clicked or filled"
+                    },
+                    "trace_redact_patterns": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Additional secret regexes to redact from the run trace (API keys and tokens are redacted by default)"
                     }
                 },
                 "required": ["url"]
@@ -255,13 +367,39 @@ impl Tool for WebExplorerTool {
             }
         }
 
+        let safety_policy = args
+            .safety_policy()
+            .map_err(|e| ToolError::ToolCallError(e.into()))?;
+        // Patterns configured for log redaction also apply to the trace
+        let trace_redact_patterns = [
+            crate::utils::log_redact::extra_patterns(),
+            args.trace_redact_patterns.clone().unwrap_or_default(),
+        ]
+        .concat();
+        let redactor = SecretRedactor::new(&trace_redact_patterns).map_err(|e| {
+            ToolError::ToolCallError(format!("Invalid trace redact pattern: {}", e).into())
+        })?;
+
         let config = WebExplorerConfig {
             target_url: args.url.clone(),
             max_depth: args.max_depth.unwrap_or(5),
             max_steps: args.max_steps.unwrap_or(100),
+            max_idle_steps: args.max_idle_steps.unwrap_or(0),
             user_agent: None,
-            headless: false,
+            headless: RUN_HEADLESS,
             ai_config,
+            seed_from_proxy_history: args.seed_from_proxy_history.unwrap_or(false),
+            seed_include_subdomains: args.seed_include_subdomains.unwrap_or(false),
+            safety_policy,
+            trace_redact_patterns,
+            snapshot_dir: dirs::data_dir().map(|dir| {
+                dir.join("sentinel-ai")
+                    .join("web_explorer")
+                    .join("snapshots")
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            ..Default::default()
         };
 
         // Create engine with message callback
@@ -272,7 +410,31 @@ impl Tool for WebExplorerTool {
 
         let app_handle_clone = self.app_handle.clone();
         let execution_id_clone = execution_id.clone();
-        let mut engine = ReActEngine::new(config).with_message_callback(move |msg| {
+        let resumed = match args.resume_session_id.as_deref() {
+            Some(session_id) => ReActEngine::try_resume(config.clone(), session_id)
+                .await
+                .map_err(|e| ToolError::ToolCallError(e.to_string().into()))?,
+            None => None,
+        };
+        let mut engine = match resumed {
+            Some(engine) => engine,
+            None => ReActEngine::new(config)
+                .map_err(|e| ToolError::ToolCallError(format!("{:#}", e).into()))?,
+        };
+        if let Some(traffic_state) = self.app_handle.as_ref().and_then(|handle| {
+            handle.try_state::<crate::commands::traffic_analysis_commands::TrafficAnalysisState>()
+        }) {
+            engine = engine.with_seed_source(traffic_state.get_history_cache());
+        }
+        if let Some(db) = self
+            .app_handle
+            .as_ref()
+            .and_then(|handle| handle.try_state::<Arc<sentinel_db::DatabaseService>>())
+        {
+            let wrap = crate::agents::prompt_wrap::SystemPromptWrap::load(db.as_ref()).await;
+            engine = engine.with_system_prompt_wrap(wrap);
+        }
+        let engine = engine.with_message_callback(move |msg| {
             if let Some(ref handle) = app_handle_clone {
                 // Wrap message in envelope format expected by frontend
                 let envelope = serde_json::json!({
@@ -285,15 +447,45 @@ impl Tool for WebExplorerTool {
             }
         });
 
+        // The run explores in its own tab, closed when the run ends or is cancelled
+        let (tab_id, tab_resource) =
+            open_run_tab(&execution_id, RUN_HEADLESS)
+                .await
+                .map_err(|e| {
+                    ToolError::ToolCallError(format!("Failed to open browser tab: {:#}", e).into())
+                })?;
+        let mut engine = engine.with_tab(tab_id);
+
         let session_id = engine.session_id().to_string();
 
         // Start exploration
         let start_time = std::time::Instant::now();
 
-        match engine.run().await {
+        let run_result = engine.run().await;
+        release_resource(&execution_id, &tab_resource).await;
+
+        // Keep the trace of every run, failed ones included, for debugging
+        if let Some(explorer_state) = self.app_handle.as_ref().and_then(|handle| {
+            handle.try_state::<crate::commands::web_explorer::WebExplorerState>()
+        }) {
+            explorer_state
+                .store_trace(execution_id.clone(), engine.trace().redacted(&redactor))
+                .await;
+        }
+
+        match run_result {
             Ok(result) => {
                 let duration = start_time.elapsed().as_secs();
 
+                // Keep the result so discoveries can be exported later
+                if let Some(explorer_state) = self.app_handle.as_ref().and_then(|handle| {
+                    handle.try_state::<crate::commands::web_explorer::WebExplorerState>()
+                }) {
+                    explorer_state
+                        .store_result(execution_id.clone(), args.url.clone(), result.clone())
+                        .await;
+                }
+
                 // Format API list
                 let api_list_str = if result.api_list.is_empty() {
                     "  (none)".to_string()
@@ -324,6 +516,7 @@ impl Tool for WebExplorerTool {
                      Pages visited: {}\n\
                      APIs discovered: {}\n\
                      Actions performed: {}\n\
+                     Stopped: {}\n\
                      Duration: {}s\n\n\
                      Discovered API Endpoints:\n{}\n\n\
                      Visited URLs:\n{}",
@@ -332,6 +525,7 @@ impl Tool for WebExplorerTool {
                     result.pages_visited,
                     result.apis_discovered,
                     result.actions_performed,
+                    result.completion_reason.as_deref().unwrap_or("unknown"),
                     duration,
                     api_list_str,
                     urls_str
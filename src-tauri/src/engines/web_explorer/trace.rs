@@ -0,0 +1,266 @@
+//! Step-by-step trace of a ReAct run
+//!
+//! Captures what the engine saw, thought and did at every step so a finished
+//! run can be replayed for debugging or audit. Traces are exported as JSON or
+//! as a Markdown transcript, with secrets redacted.
+
+use super::react_engine::ReActEngine;
+use super::types::ExplorationState;
+use crate::utils::log_redact::SecretRedactor;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+/// One observe/think/act cycle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub step_number: u32,
+    /// Page the step started on
+    pub url: String,
+    pub page_type: String,
+    pub observation: String,
+    pub elements_count: usize,
+    pub links_count: usize,
+    pub thought: String,
+    pub action_type: String,
+    pub action: serde_json::Value,
+    pub success: bool,
+    pub new_url: Option<String>,
+    pub error: Option<String>,
+    /// Completion time (ms since epoch)
+    pub timestamp: u64,
+    /// Time spent since the previous step finished
+    pub duration_ms: u64,
+}
+
+/// Full trace of a ReAct run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReActTrace {
+    pub session_id: String,
+    pub target_url: String,
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub completion_reason: Option<String>,
+    pub steps: Vec<TraceStep>,
+}
+
+impl ReActTrace {
+    /// Build a trace from the recorded step history
+    pub fn from_state(
+        session_id: &str,
+        target_url: &str,
+        started_at: u64,
+        state: &ExplorationState,
+    ) -> Self {
+        let mut previous = started_at;
+        let steps = state
+            .history
+            .iter()
+            .map(|step| {
+                let duration_ms = step.timestamp.saturating_sub(previous);
+                previous = step.timestamp;
+                TraceStep {
+                    step_number: step.step_number,
+                    url: step.url.clone(),
+                    page_type: format!("{:?}", step.observation.page_type).to_lowercase(),
+                    observation: step.observation.description.clone(),
+                    elements_count: step.observation.elements.len(),
+                    links_count: step.observation.links.len(),
+                    thought: step.thought.clone(),
+                    action_type: ReActEngine::action_type_name(&step.action),
+                    action: ReActEngine::action_to_json(&step.action),
+                    success: step.result.success,
+                    new_url: step.result.new_url.clone(),
+                    error: step.result.error.clone(),
+                    timestamp: step.timestamp,
+                    duration_ms,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            session_id: session_id.to_string(),
+            target_url: target_url.to_string(),
+            started_at,
+            finished_at: steps.last().map(|s| s.timestamp).unwrap_or(started_at),
+            completion_reason: state.completion_reason.clone(),
+            steps,
+        }
+    }
+
+    /// Return a copy with secrets removed from all text fields
+    pub fn redacted(&self, redactor: &SecretRedactor) -> Self {
+        let mut trace = self.clone();
+        trace.target_url = redactor.redact(&trace.target_url);
+        trace.completion_reason = trace.completion_reason.map(|r| redactor.redact(&r));
+        for step in &mut trace.steps {
+            step.url = redactor.redact(&step.url);
+            step.observation = redactor.redact(&step.observation);
+            step.thought = redactor.redact(&step.thought);
+            redactor.redact_value(&mut step.action);
+            step.new_url = step.new_url.as_deref().map(|u| redactor.redact(u));
+            step.error = step.error.as_deref().map(|e| redactor.redact(e));
+        }
+        trace
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Render a human-readable transcript
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "# ReAct trace: {}\n", self.target_url);
+        let _ = writeln!(md, "- Session: `{}`", self.session_id);
+        let _ = writeln!(md, "- Started: {}", format_timestamp(self.started_at));
+        let _ = writeln!(
+            md,
+            "- Duration: {:.1}s",
+            self.finished_at.saturating_sub(self.started_at) as f64 / 1000.0
+        );
+        let _ = writeln!(md, "- Steps: {}", self.steps.len());
+        if let Some(reason) = &self.completion_reason {
+            let _ = writeln!(md, "- Completion: {}", reason);
+        }
+
+        for step in &self.steps {
+            let _ = writeln!(
+                md,
+                "\n## Step {}: {} ({:.1}s)\n",
+                step.step_number,
+                step.action_type,
+                step.duration_ms as f64 / 1000.0
+            );
+            let _ = writeln!(md, "**URL:** {}\n", step.url);
+            let _ = writeln!(
+                md,
+                "**Observation:** [{}] {} ({} elements, {} links)\n",
+                step.page_type, step.observation, step.elements_count, step.links_count
+            );
+            let _ = writeln!(md, "**Thought:** {}\n", step.thought.trim());
+            let _ = writeln!(md, "**Action:** `{}` `{}`\n", step.action_type, step.action);
+
+            let mut outcome = if step.success { "ok" } else { "failed" }.to_string();
+            if let Some(url) = &step.new_url {
+                let _ = write!(outcome, ", now at {}", url);
+            }
+            if let Some(error) = &step.error {
+                let _ = write!(outcome, ", error: {}", error);
+            }
+            let _ = writeln!(md, "**Result:** {}", outcome);
+        }
+
+        md
+    }
+}
+
+fn format_timestamp(ms: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms as i64)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| ms.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::web_explorer::types::{
+        Action, ActionResult, AuthStatus, Observation, PageType, Step,
+    };
+
+    fn observation(description: &str) -> Observation {
+        Observation {
+            page_type: PageType::Form,
+            description: description.to_string(),
+            auth_status: AuthStatus::Unknown,
+            elements: Vec::new(),
+            forms: Vec::new(),
+            links: vec!["/next".to_string()],
+            api_endpoints: Vec::new(),
+            confidence: 1.0,
+            metadata: Default::default(),
+            snapshot_tree: None,
+        }
+    }
+
+    fn multi_step_state() -> ExplorationState {
+        let mut state = ExplorationState::new("https://app.example.com/".to_string(), 5, 10);
+        let actions = [
+            Action::Navigate {
+                url: "https://app.example.com/login".to_string(),
+            },
+            Action::Fill {
+                index: Some(2),
+                selector: None,
+                value: "api_key=abcd1234efgh5678".to_string(),
+            },
+            Action::Submit {
+                selector: "#login".to_string(),
+            },
+        ];
+        for (i, action) in actions.into_iter().enumerate() {
+            state.record_step(Step {
+                step_number: i as u32 + 1,
+                url: "https://app.example.com/login".to_string(),
+                observation: observation("Login form"),
+                thought: format!("Step {} reasoning", i + 1),
+                action,
+                result: ActionResult {
+                    success: i != 2,
+                    new_url: None,
+                    error: (i == 2).then(|| "Bearer abcdefghijklmnop rejected".to_string()),
+                    observation: None,
+                },
+                timestamp: 1_000 + (i as u64 + 1) * 500,
+            });
+        }
+        state.complete("done".to_string());
+        state
+    }
+
+    #[test]
+    fn test_multi_step_trace_is_ordered() {
+        let trace = ReActTrace::from_state(
+            "session-1",
+            "https://app.example.com/",
+            1_000,
+            &multi_step_state(),
+        );
+
+        let numbers: Vec<u32> = trace.steps.iter().map(|s| s.step_number).collect();
+        assert_eq!(numbers, vec![1, 2, 3]);
+        let types: Vec<&str> = trace.steps.iter().map(|s| s.action_type.as_str()).collect();
+        assert_eq!(types, vec!["navigate", "fill", "submit"]);
+        assert!(trace.steps.iter().all(|s| s.duration_ms == 500));
+        assert_eq!(trace.finished_at, 2_500);
+        assert_eq!(trace.completion_reason.as_deref(), Some("done"));
+
+        let json: ReActTrace = serde_json::from_str(&trace.to_json().unwrap()).unwrap();
+        assert_eq!(json.steps.len(), 3);
+
+        let md = trace.to_markdown();
+        let first = md.find("## Step 1: navigate").unwrap();
+        let second = md.find("## Step 2: fill").unwrap();
+        let third = md.find("## Step 3: submit").unwrap();
+        assert!(first < second && second < third);
+    }
+
+    #[test]
+    fn test_trace_redacts_secrets() {
+        let redactor = SecretRedactor::new(&[r"corp-[0-9]{6}".to_string()]).unwrap();
+        let mut state = multi_step_state();
+        state.history[0].thought =
+            "Use token sk-abcdefghijklmnopqrstuv and corp-123456".to_string();
+
+        let trace = ReActTrace::from_state("s", "https://app.example.com/", 1_000, &state)
+            .redacted(&redactor);
+        let md = trace.to_markdown();
+
+        assert!(!md.contains("sk-abcdefghijklmnopqrstuv"));
+        assert!(!md.contains("corp-123456"));
+        assert!(!md.contains("abcd1234efgh5678"));
+        assert!(!md.contains("abcdefghijklmnop rejected"));
+        assert!(md.contains("api_key=[REDACTED]"));
+        assert!(md.contains("Bearer [REDACTED]"));
+        assert_eq!(trace.steps[1].action["value"], "api_key=[REDACTED]");
+    }
+}
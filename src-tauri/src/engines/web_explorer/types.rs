@@ -1,5 +1,6 @@
 //! Data types for Web Explorer ReAct Architecture
 
+use super::safety::SafetyPolicy;
 use crate::engines::LlmConfig;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -18,6 +19,11 @@ pub struct WebExplorerConfig {
     /// Maximum total steps (budget)
     pub max_steps: u32,
 
+    /// Stop after this many consecutive steps that neither visit a new page
+    /// nor discover a new API (0, the default, disables the check)
+    #[serde(default)]
+    pub max_idle_steps: u32,
+
     /// UserAgent string
     pub user_agent: Option<String>,
 
@@ -26,6 +32,34 @@ pub struct WebExplorerConfig {
 
     /// AI Configuration
     pub ai_config: AIConfig,
+
+    /// Seed the graph with in-scope URLs already seen by the passive proxy
+    #[serde(default)]
+    pub seed_from_proxy_history: bool,
+
+    /// Also seed URLs on subdomains of the target host
+    #[serde(default)]
+    pub seed_include_subdomains: bool,
+
+    /// Maximum number of proxy history URLs to seed
+    #[serde(default = "default_max_proxy_seeds")]
+    pub max_proxy_seeds: usize,
+
+    /// Directory for crash-recovery snapshots; snapshots are disabled when unset
+    #[serde(default)]
+    pub snapshot_dir: Option<String>,
+
+    /// Safety policy applied to navigations and actions
+    #[serde(default)]
+    pub safety_policy: SafetyPolicy,
+
+    /// Extra regexes redacted from exported traces, on top of the built-in secret patterns
+    #[serde(default)]
+    pub trace_redact_patterns: Vec<String>,
+}
+
+fn default_max_proxy_seeds() -> usize {
+    50
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +125,7 @@ impl Default for WebExplorerConfig {
             target_url: "about:blank".to_string(),
             max_depth: 5,
             max_steps: 100,
+            max_idle_steps: 0,
             user_agent: None,
             headless: false,
             ai_config: AIConfig {
@@ -103,6 +138,12 @@ impl Default for WebExplorerConfig {
                 fast_base_url: None,
                 vision_base_url: None,
             },
+            seed_from_proxy_history: false,
+            seed_include_subdomains: false,
+            max_proxy_seeds: default_max_proxy_seeds(),
+            snapshot_dir: None,
+            safety_policy: SafetyPolicy::default(),
+            trace_redact_patterns: Vec::new(),
         }
     }
 }
@@ -329,6 +370,14 @@ pub struct ExplorationState {
     /// Maximum allowed steps
     pub max_steps: u32,
 
+    /// Consecutive steps without progress that end the exploration (0 = never)
+    #[serde(default)]
+    pub max_idle_steps: u32,
+
+    /// Current run of steps without progress
+    #[serde(default)]
+    pub idle_steps: u32,
+
     /// URLs that have been visited
     pub visited_urls: HashSet<String>,
 
@@ -353,6 +402,8 @@ impl ExplorationState {
             max_depth,
             steps_taken: 0,
             max_steps,
+            max_idle_steps: 0,
+            idle_steps: 0,
             visited_urls: HashSet::from([start_url]),
             discovered_apis: Vec::new(),
             history: Vec::new(),
@@ -374,6 +425,22 @@ impl ExplorationState {
         self.history.push(step);
     }
 
+    /// Record whether the last step made progress, completing the exploration
+    /// once `max_idle_steps` consecutive steps made none
+    pub fn note_progress(&mut self, progressed: bool) {
+        if progressed {
+            self.idle_steps = 0;
+            return;
+        }
+        self.idle_steps += 1;
+        if self.max_idle_steps > 0 && self.idle_steps >= self.max_idle_steps && !self.is_complete {
+            self.complete(format!(
+                "No progress in {} consecutive steps",
+                self.idle_steps
+            ));
+        }
+    }
+
     /// Mark URL as visited
     pub fn mark_visited(&mut self, url: String) {
         self.visited_urls.insert(url);
@@ -398,12 +465,28 @@ impl ExplorationState {
     }
 }
 
+/// Persisted engine state used to resume an interrupted exploration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExplorationSnapshot {
+    pub session_id: String,
+    pub target_url: String,
+    pub state: ExplorationState,
+    /// Graph in the `ExplorationGraph::to_json` format
+    pub graph: serde_json::Value,
+    #[serde(default)]
+    pub seed_urls: Vec<String>,
+    pub saved_at: u64,
+}
+
 // ==================== Step (History Entry) ====================
 
 /// A single step in the exploration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Step {
     pub step_number: u32,
+    /// Page the step started on
+    #[serde(default)]
+    pub url: String,
     pub observation: Observation,
     pub thought: String,
     pub action: Action,
@@ -434,6 +517,9 @@ pub struct ExplorationResult {
     pub actions_performed: u32,
     pub duration_seconds: u64,
     pub error: Option<String>,
+    /// Why the exploration stopped (agent stop, step budget, no progress, ...)
+    #[serde(default)]
+    pub completion_reason: Option<String>,
     pub graph: serde_json::Value, // Simplified graph for export
     /// List of discovered API endpoints
     #[serde(default)]
@@ -16,6 +16,9 @@ pub struct ProxyStatusEvent {
     pub running: bool,
     pub port: u16,
     pub mitm: bool,
+    /// 被动扫描是否已暂停
+    #[serde(default)]
+    pub paused: bool,
     pub stats: ProxyStats,
 }
 
@@ -814,6 +814,7 @@ interface RequestContext {
     headers: Record<string, string>;
     query_params: Record<string, string>;
     body: number[]; // UTF-8 bytes
+    body_truncated: boolean; // body exceeded the proxy size limit, only a prefix is provided
     timestamp: string;
 }
 
@@ -822,6 +823,7 @@ interface ResponseContext {
     status: number;
     headers: Record<string, string>;
     body: number[]; // UTF-8 bytes
+    body_truncated: boolean; // body exceeded the proxy size limit, only a prefix is provided
     timestamp: string;
 }
 
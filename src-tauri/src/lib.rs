@@ -34,7 +34,7 @@ use services::{ai::AiServiceManager, database::DatabaseService};
 use crate::skills::scan_and_upsert_skills;
 use commands::{
     ai, aisettings, asset, cleanup_expired_cache, config, database as db_commands, delete_cache,
-    dictionary, get_all_cache_keys, get_cache, llm_test_commands,
+    dictionary, finding_rag_commands, get_all_cache_keys, get_cache, llm_test_commands,
     monitor_commands::MonitorSchedulerState,
     packet_capture_commands::{self, PacketCaptureState},
     performance,
@@ -191,22 +191,23 @@ pub fn run() {
     };
     let logs_dir = logs_dir.to_string_lossy().to_string();
 
-    let file_appender = tracing_appender::rolling::daily(&logs_dir, "sentinel-ai.log");
+    let log_config_dir = dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("sentinel-ai");
+    let log_format = utils::json_log::LogFormat::resolve(&log_config_dir);
+    utils::log_redact::load_configured_patterns(&log_config_dir);
+    // JSON 日志单独成文件，便于采集端按文件名区分
+    let log_file_name = match log_format {
+        utils::json_log::LogFormat::Json => "sentinel-ai.jsonl",
+        utils::json_log::LogFormat::Text => "sentinel-ai.log",
+    };
+    let file_appender = tracing_appender::rolling::daily(&logs_dir, log_file_name);
     let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
+    // 落盘前脱敏，日志常被直接附在 issue 中
+    let non_blocking = utils::log_redact::RedactingMakeWriter::new(non_blocking);
 
-    let env_filter = tracing_subscriber::EnvFilter::from_default_env()
-        .add_directive("sentinel_ai=info".parse().unwrap())
-        .add_directive("sentinel_plugins=info".parse().unwrap())
-        .add_directive("sentinel_workflow=info".parse().unwrap())
-        .add_directive("sentinel_traffic=info".parse().unwrap())
-        .add_directive("sentinel_rag=info".parse().unwrap())
-        .add_directive("sentinel_llm=info".parse().unwrap())
-        .add_directive("hudsucker=off".parse().unwrap())
-        .add_directive(
-            "rig::agent::prompt_request::streaming=warn"
-                .parse()
-                .unwrap(),
-        );
+    // 过滤器可在运行时通过 set_log_level 调整
+    let env_filter = utils::log_filter::init_reloadable_filter(log_config_dir);
 
     // let rig_debug = std::env::var("SENTINEL_RIG_DEBUG")
     //     .map(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "YES"))
@@ -222,16 +223,38 @@ pub fn run() {
     //         );
     // }
 
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
-        .with_writer(non_blocking)
-        .without_time()
-        .with_line_number(true)
-        .with_ansi(false)
-        .init();
+    {
+        use tracing_subscriber::layer::SubscriberExt;
+        use tracing_subscriber::util::SubscriberInitExt;
+
+        let registry = tracing_subscriber::registry().with(env_filter);
+        match log_format {
+            utils::json_log::LogFormat::Json => registry
+                .with(
+                    tracing_subscriber::fmt::layer()
+                        .with_writer(non_blocking)
+                        .fmt_fields(utils::json_log::JsonFields)
+                        .event_format(utils::json_log::JsonEventFormat),
+                )
+                .init(),
+            utils::json_log::LogFormat::Text => registry
+                .with(
+                    tracing_subscriber::fmt::layer()
+                        .with_writer(non_blocking)
+                        .without_time()
+                        .with_line_number(true)
+                        .with_ansi(false),
+                )
+                .init(),
+        }
+    }
 
     std::mem::forget(_guard);
 
+    if utils::safe_mode::init_from_env() {
+        tracing::warn!("Starting in safe mode: MCP auto-connect, plugin tools and proxy auto-start are disabled");
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_single_instance::init(|_app, _argv, _cwd| {}))
         .plugin(tauri_plugin_window_state::Builder::default().build())
@@ -416,6 +439,7 @@ pub fn run() {
                     std::process::exit(1);
                 }
                 let db_service = Arc::new(db_service);
+                commands::notifications::register_delivery_log(db_service.clone());
 
                 // Initialize dictionary pool for plugins
                 if let Ok(pool) = db_service.get_pool() {
@@ -574,6 +598,22 @@ pub fn run() {
 
                 let ai_manager = Arc::new(ai_manager);
 
+                // 校验已配置的默认模型是否仍被提供商提供（网络请求，不阻塞启动）
+                let ai_manager_for_validation = ai_manager.clone();
+                tauri::async_runtime::spawn(async move {
+                    ai_manager_for_validation
+                        .validate_model_availability(|config| async move {
+                            commands::aisettings::get_provider_models(
+                                config.provider,
+                                config.api_key,
+                                config.api_base,
+                                config.organization,
+                            )
+                            .await
+                        })
+                        .await;
+                });
+
                 let asset_service = crate::services::AssetService::new(db_service.clone());
                 let vulnerability_service = Arc::new(crate::services::VulnerabilityService::new(
                     db_service.clone(),
@@ -595,6 +635,12 @@ pub fn run() {
                     .with_plugin_manager(plugin_manager_for_workflow.clone()),
                 );
                 let workflow_scheduler = Arc::new(WorkflowScheduler::new(scheduler_executor));
+                let webhook_executor = sentinel_workflow::commands::WorkflowScheduleExecutor::new(
+                    workflow_engine.clone(),
+                    db_service.clone(),
+                    handle.clone(),
+                )
+                .with_plugin_manager(plugin_manager_for_workflow.clone());
 
                 // Save a clone before manage() moves db_service
                 let db_service_for_mcp = db_service.clone();
@@ -608,6 +654,27 @@ pub fn run() {
                 // db_service is already Arc<DatabaseService>, so we just clone it and cast/coerce
                 let db_trait_obj: Arc<dyn Database> = db_service.clone();
                 handle.manage(db_trait_obj);
+                sentinel_tools::buildin_tools::subdomain_brute::set_subdomain_checkpoint_store(
+                    db_service.clone(),
+                )
+                .await;
+                sentinel_tools::buildin_tools::content_discovery::set_content_dictionary_source(
+                    Arc::new(tools::DictionaryWordSource::new(db_service.clone())),
+                )
+                .await;
+                if let Ok(Some(json)) = db_service.get_config("tools", "ip_enrichment").await {
+                    use sentinel_tools::buildin_tools::ip_enrichment::{
+                        set_ip_enrichment_config, IpEnrichmentConfig,
+                    };
+                    match serde_json::from_str::<IpEnrichmentConfig>(&json) {
+                        Ok(cfg) => {
+                            if let Err(e) = set_ip_enrichment_config(&cfg).await {
+                                tracing::warn!("Failed to apply IP enrichment config: {}", e);
+                            }
+                        }
+                        Err(e) => tracing::warn!("Failed to parse IP enrichment config: {}", e),
+                    }
+                }
                 handle.manage(ai_manager);
                 handle.manage(asset_service);
                 handle.manage(vulnerability_service);
@@ -626,6 +693,19 @@ pub fn run() {
                 ));
                 handle.manage(enrichment_service);
 
+                // 定期清理空闲 session 的序列号计数器
+                crate::utils::spawn_session_counter_sweeper();
+
+                // 定期探测数据库连接池，连续获取失败时自动尝试恢复
+                let db_for_pool_monitor = db_service.clone();
+                tauri::async_runtime::spawn(async move {
+                    let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
+                    loop {
+                        interval.tick().await;
+                        db_for_pool_monitor.check_pool_health().await;
+                    }
+                });
+
                 // Initialize Tenth Man executor
                 crate::agents::tenth_man_executor::set_app_handle(handle.clone());
                 crate::agents::tenth_man_executor::init_tenth_man_executor();
@@ -653,7 +733,10 @@ pub fn run() {
                 tokio::spawn(async move {
                     // Wait a bit for app to be fully ready
                     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-                    
+                    if !utils::safe_mode::should_run(utils::safe_mode::StartupTask::ProxyAutoStart) {
+                        return;
+                    }
+
                     if let Err(e) = auto_start_proxy_if_enabled(&handle_for_proxy, &traffic_state_for_proxy).await {
                         tracing::warn!("Failed to auto-start proxy listener: {}", e);
                     }
@@ -674,6 +757,18 @@ pub fn run() {
                     }
                 });
 
+                // Auto-start workflow webhook listener if enabled in config
+                tokio::spawn(async move {
+                    if let Err(e) =
+                        sentinel_workflow::commands::auto_start_workflow_webhook_if_enabled(
+                            webhook_executor,
+                        )
+                        .await
+                    {
+                        tracing::warn!("Failed to auto-start workflow webhook listener: {}", e);
+                    }
+                });
+
                 // Auto-start agent-browser daemon for browser automation
                 tokio::spawn(async move {
                     tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
@@ -710,8 +805,8 @@ pub fn run() {
                         .await;
                     }
 
-                    // Register plugin tools
-                    {
+                    // Register plugin tools (skipped in safe mode)
+                    if utils::safe_mode::should_run(utils::safe_mode::StartupTask::PluginTools) {
                         let tool_server = sentinel_tools::get_tool_server();
                         let db_plugin = db_service_for_mcp.clone();
                         
@@ -771,7 +866,7 @@ pub fn run() {
 
                     commands::mcp_commands::mcp_auto_connect_servers(
                         db_service_for_mcp,
-                        handle_for_mcp,
+                        &services::TauriMessageEmitter::new(handle_for_mcp),
                     )
                     .await;
                 });
@@ -823,6 +918,13 @@ pub fn run() {
             ai::test_lm_studio_provider_connection,
             ai::save_scheduler_config,
             ai::get_ai_usage_stats,
+            ai::get_agent_run_cost,
+            ai::get_context_compression_stats,
+            ai::list_execution_resources,
+            ai::force_cleanup_execution_resources,
+            ai::get_agent_tool_calls,
+            ai::get_agent_replan_events,
+            ai::replay_agent_tool_call,
             ai::get_detailed_ai_usage_stats,
             ai::clear_ai_usage_stats,
             ai::generate_workflow_from_nl,
@@ -878,7 +980,12 @@ pub fn run() {
             asset::import_assets,
             asset::extract_assets_from_scan,
             asset::search_assets,
+            asset::add_asset_tags,
+            asset::remove_asset_tags,
+            asset::bulk_tag_assets,
+            asset::list_assets_by_tag,
             asset::get_related_assets,
+            asset::export_asset_graph,
             asset::verify_asset,
             asset::update_asset_last_seen,
             asset::get_asset_types,
@@ -999,6 +1106,9 @@ pub fn run() {
             commands::monitor_get_available_plugins,
             commands::monitor_test_plugin,
             commands::monitor_update_task_plugins,
+            // Web explorer commands
+            commands::web_explorer::export_vision_explorer_v2_sitemap,
+            commands::web_explorer::get_vision_explorer_v2_trace,
             // Asset enrichment commands
             commands::asset_enrichment_commands::enrich_asset,
             commands::asset_enrichment_commands::start_asset_enrichment,
@@ -1016,6 +1126,14 @@ pub fn run() {
             config::set_language,
             config::get_global_proxy_config,
             config::set_global_proxy_config,
+            config::get_ip_enrichment_config,
+            config::save_ip_enrichment_config,
+            config::set_auth_session,
+            config::get_auth_session,
+            config::clear_auth_session,
+            config::set_log_level,
+            config::reset_log_level,
+            config::get_log_levels,
             commands::check_command_exists,
             commands::role::get_ai_roles,
             commands::role::create_ai_role,
@@ -1030,6 +1148,9 @@ pub fn run() {
             scan_session_commands::list_scan_sessions,
             scan_session_commands::delete_scan_session,
             scan_session_commands::get_scan_progress,
+            scan_session_commands::diff_scan_sessions,
+            scan_session_commands::export_scan_session_bundle,
+            scan_session_commands::import_scan_session_bundle,
             scan_session_commands::get_session_stages,
             // LLM test commands
             llm_test_commands::llm_test_create_run,
@@ -1070,6 +1191,9 @@ pub fn run() {
             dictionary::get_dictionary_words_paged,
             dictionary::add_dictionary_words,
             dictionary::remove_dictionary_words,
+            dictionary::generate_subdomain_permutations,
+            dictionary::record_dictionary_word_hits,
+            dictionary::set_dictionary_word_weight,
             dictionary::search_dictionary_words,
             dictionary::clear_dictionary,
             dictionary::export_dictionary,
@@ -1101,8 +1225,14 @@ pub fn run() {
             window::set_window_size,
             // RAG commands
             rag_commands::rag_ingest_source,
+            rag_commands::cancel_rag_ingest,
+            rag_commands::rag_reingest_incremental,
             rag_commands::rag_ingest_text,
             rag_commands::rag_query,
+            rag_commands::assistant_rag_answer_stream,
+            finding_rag_commands::get_findings_rag_auto_ingest,
+            finding_rag_commands::set_findings_rag_auto_ingest,
+            finding_rag_commands::backfill_findings_to_rag,
             rag_commands::rag_clear_collection,
             rag_commands::rag_initialize_service,
             rag_commands::rag_shutdown_service,
@@ -1128,16 +1258,21 @@ pub fn run() {
             // Traffic scan commands
             traffic_analysis_commands::start_traffic_analysis,
             traffic_analysis_commands::stop_traffic_analysis,
+            traffic_analysis_commands::pause_passive_scan,
+            traffic_analysis_commands::resume_passive_scan,
             traffic_analysis_commands::get_proxy_status,
             traffic_analysis_commands::reload_plugin_in_pipeline,
             traffic_analysis_commands::list_findings,
             traffic_analysis_commands::count_findings,
+            traffic_analysis_commands::query_findings,
+            traffic_analysis_commands::search_findings,
 
             traffic_analysis_commands::enable_plugin,
             traffic_analysis_commands::disable_plugin,
             traffic_analysis_commands::batch_enable_plugins,
             traffic_analysis_commands::batch_disable_plugins,
             traffic_analysis_commands::list_plugins,
+            traffic_analysis_commands::get_plugin_metrics,
             traffic_analysis_commands::download_ca_cert,
             traffic_analysis_commands::get_ca_cert_path,
             traffic_analysis_commands::trust_ca_cert,
@@ -1149,11 +1284,16 @@ pub fn run() {
             traffic_analysis_commands::export_ca_pkcs12,
             traffic_analysis_commands::get_finding,
             traffic_analysis_commands::update_finding_status,
+            traffic_analysis_commands::update_findings_status_batch,
             traffic_analysis_commands::export_findings_html,
+            traffic_analysis_commands::export_findings_json,
+            traffic_analysis_commands::export_findings_sarif,
             traffic_analysis_commands::list_proxy_requests,
             traffic_analysis_commands::get_proxy_request,
+            traffic_analysis_commands::import_auth_session_from_proxy_request,
             traffic_analysis_commands::clear_proxy_requests,
             traffic_analysis_commands::count_proxy_requests,
+            traffic_analysis_commands::search_proxy_history,
             traffic_analysis_commands::create_plugin_in_db,
             traffic_analysis_commands::update_plugin,
             traffic_analysis_commands::get_plugin_code,
@@ -1165,6 +1305,10 @@ pub fn run() {
             traffic_analysis_commands::delete_all_traffic_vulnerabilities,
             traffic_analysis_commands::clear_vulnerability_dedupe_cache,
             traffic_analysis_commands::get_vulnerability_dedupe_cache_info,
+            traffic_analysis_commands::list_suppression_rules,
+            traffic_analysis_commands::save_suppression_rule,
+            traffic_analysis_commands::delete_suppression_rule,
+            traffic_analysis_commands::list_suppressed_findings,
             traffic_analysis_commands::test_plugin_advanced,
             traffic_analysis_commands::test_agent_plugin,
             traffic_analysis_commands::get_plugin_input_schema,
@@ -1177,6 +1321,12 @@ pub fn run() {
             traffic_analysis_commands::get_proxy_auto_start,
             traffic_analysis_commands::set_traffic_analysis_plugin_enabled,
             traffic_analysis_commands::get_traffic_analysis_plugin_enabled,
+            traffic_analysis_commands::set_passive_asset_extraction_enabled,
+            traffic_analysis_commands::get_passive_asset_extraction_enabled,
+            traffic_analysis_commands::get_finding_dedup_config,
+            traffic_analysis_commands::set_finding_dedup_config,
+            traffic_analysis_commands::get_security_header_config,
+            traffic_analysis_commands::set_security_header_config,
             traffic_analysis_commands::set_intercept_enabled,
             traffic_analysis_commands::get_intercept_enabled,
             traffic_analysis_commands::get_intercepted_requests,
@@ -1188,6 +1338,7 @@ pub fn run() {
             traffic_analysis_commands::forward_intercepted_response,
             traffic_analysis_commands::drop_intercepted_response,
             traffic_analysis_commands::replay_request,
+            traffic_analysis_commands::replay_request_templated,
             traffic_analysis_commands::replay_raw_request,
             traffic_analysis_commands::list_websocket_connections,
             traffic_analysis_commands::list_websocket_messages,
@@ -1199,6 +1350,11 @@ pub fn run() {
             traffic_analysis_commands::forward_intercepted_websocket,
             traffic_analysis_commands::drop_intercepted_websocket,
             traffic_analysis_commands::add_intercept_filter_rule,
+            traffic_analysis_commands::add_match_replace_rule,
+            traffic_analysis_commands::get_match_replace_rules,
+            traffic_analysis_commands::remove_match_replace_rule,
+            traffic_analysis_commands::update_match_replace_rule,
+            traffic_analysis_commands::reorder_match_replace_rules,
             traffic_analysis_commands::get_intercept_filter_rules,
             traffic_analysis_commands::remove_intercept_filter_rule,
             traffic_analysis_commands::update_intercept_filter_rule,
@@ -1255,13 +1411,18 @@ pub fn run() {
             commands::notifications::list_notification_rules,
             commands::notifications::get_notification_rule,
             commands::notifications::test_notification_rule_connection,
+            commands::notifications::dispatch_finding_notification,
+            commands::notifications::get_notification_delivery_log,
             // Packet capture (requires Npcap on Windows)
             packet_capture_commands::get_network_interfaces,
             packet_capture_commands::start_packet_capture,
             packet_capture_commands::stop_packet_capture,
             packet_capture_commands::is_capture_running,
             packet_capture_commands::open_pcap_file,
+            packet_capture_commands::decode_pcap_http,
+            packet_capture_commands::decode_pcap_dns,
             packet_capture_commands::save_pcap_file,
+            packet_capture_commands::extract_credentials,
             packet_capture_commands::extract_files_preview,
             packet_capture_commands::extract_files_to_dir,
             packet_capture_commands::save_extracted_file,
@@ -1271,6 +1432,10 @@ pub fn run() {
             // Test commands
             commands::test_proxy::test_proxy_connection,
             commands::test_proxy::get_current_proxy_config,
+            commands::test_proxy::get_proxy_health,
+            commands::test_proxy::check_proxy_health,
+            // Health commands
+            commands::health::get_system_health,
             // Tool commands
             tool_commands::get_builtin_tools_with_status,
             tool_commands::toggle_builtin_tool,
@@ -1284,6 +1449,10 @@ pub fn run() {
             tool_commands::get_tool_metadata,
             tool_commands::get_tool_usage_stats,
             tool_commands::clear_tool_usage_stats,
+            tool_commands::get_tool_performance_stats,
+            tool_commands::get_tool_concurrency_config,
+            tool_commands::set_tool_concurrency_config,
+            tool_commands::get_tool_queue_status,
             tool_commands::get_exploitdb_settings,
             tool_commands::save_exploitdb_settings,
             tool_commands::get_exploitdb_sync_status,
@@ -1367,6 +1536,8 @@ pub fn run() {
             tool_commands::update_skill,
             tool_commands::delete_skill,
             tool_commands::refresh_skills_index,
+            tool_commands::start_skills_watcher,
+            tool_commands::stop_skills_watcher,
             tool_commands::list_skill_files,
             tool_commands::read_skill_file,
             tool_commands::save_skill_file,
@@ -1385,6 +1556,7 @@ pub fn run() {
             tool_commands::tool_server::get_tool_input_schema,
             tool_commands::tool_server::get_tool_output_schema,
             tool_commands::tool_server::execute_tool_server_tool,
+            tool_commands::tool_server::set_tool_schema_validation,
             tool_commands::tool_server::get_tool_server_stats,
             tool_commands::tool_server::register_mcp_tools_from_server,
             tool_commands::tool_server::register_workflow_tools,
@@ -1398,15 +1570,22 @@ pub fn run() {
             commands::mcp_commands::mcp_update_server_config,
             commands::mcp_commands::mcp_get_connection_tools,
             commands::mcp_commands::mcp_call_tool,
+            commands::mcp_commands::mcp_get_client_config,
+            commands::mcp_commands::mcp_update_client_config,
             commands::mcp_commands::mcp_test_server_tool,
             commands::mcp_commands::mcp_get_all_tools,
             commands::mcp_commands::quick_create_mcp_server,
             commands::mcp_commands::import_mcp_servers_from_json,
             commands::mcp_commands::cleanup_duplicate_mcp_servers,
             commands::mcp_commands::mcp_set_auto_connect,
+            commands::mcp_commands::start_sentinel_mcp_server,
+            commands::mcp_commands::stop_sentinel_mcp_server,
+            commands::mcp_commands::get_sentinel_mcp_server_status,
             // License commands
             commands::license_commands::get_license_info,
             commands::license_commands::activate_license,
+            commands::license_commands::generate_offline_activation_request,
+            commands::license_commands::activate_license_from_file,
             commands::license_commands::check_license,
             commands::license_commands::get_machine_id,
             commands::license_commands::get_machine_id_full,
@@ -1418,6 +1597,7 @@ pub fn run() {
             sentinel_workflow::commands::list_workflow_runs,
             sentinel_workflow::commands::list_workflow_runs_paginated,
             sentinel_workflow::commands::get_workflow_run_detail,
+            sentinel_workflow::commands::get_workflow_run_artifact,
             sentinel_workflow::commands::delete_workflow_run,
             sentinel_workflow::commands::save_workflow_definition,
             sentinel_workflow::commands::get_workflow_definition,
@@ -1429,6 +1609,11 @@ pub fn run() {
             sentinel_workflow::commands::stop_workflow_schedule,
             sentinel_workflow::commands::list_workflow_schedules,
             sentinel_workflow::commands::get_workflow_schedule,
+            sentinel_workflow::commands::get_workflow_webhook_config,
+            sentinel_workflow::commands::save_workflow_webhook_config,
+            sentinel_workflow::commands::start_workflow_webhook,
+            sentinel_workflow::commands::stop_workflow_webhook,
+            sentinel_workflow::commands::get_workflow_webhook_status,
             // Team V3 commands (non-backward-compatible cutover)
             commands::team_v3_commands::team_v3_ensure_schema,
             commands::team_v3_commands::team_v3_reset_schema,
@@ -1493,9 +1678,21 @@ fn update_proxy_menu_text(app: &tauri::AppHandle, is_running: bool) {
     }
 }
 
+/// 优雅退出：通知子系统停止，等待进行中的操作结束并关闭数据库后退出进程
+///
+/// 托盘“退出”与关闭窗口（选择退出）都经过这里。
 async fn cleanup_and_exit(app: &tauri::AppHandle) {
     let _ = app.save_window_state(StateFlags::all());
 
+    // Signal agent executions, workflow runs and conversation streams to stop
+    let scopes = sentinel_tools::cancellation::cancel_all_scopes().await;
+    let streams = commands::ai::cancel_all_conversation_streams();
+    tracing::info!(
+        "Shutdown requested, cancelled {} execution scope(s) and {} stream(s)",
+        scopes,
+        streams
+    );
+
     if let Some(state) = app.try_state::<TrafficAnalysisState>() {
         let is_running_arc = state.get_is_running();
         let is_running = *is_running_arc.read().await;
@@ -1508,6 +1705,20 @@ async fn cleanup_and_exit(app: &tauri::AppHandle) {
     tracing::info!("Stopping agent-browser daemon...");
     sentinel_tools::agent_browser::stop_all_daemons();
 
+    // Wait (bounded) for in-flight DB writes and notifications, then flush the DB
+    let outcome = managers::shutdown_coordinator::coordinator()
+        .drain(managers::shutdown_coordinator::DEFAULT_DRAIN_TIMEOUT)
+        .await;
+    if !outcome.drained {
+        tracing::warn!(
+            "Shutdown timed out with {} operation(s) still in flight",
+            outcome.remaining
+        );
+    }
+    if let Some(db) = app.try_state::<Arc<DatabaseService>>() {
+        db.close().await;
+    }
+
     tracing::info!("Application cleanup completed, exiting");
     std::process::exit(0);
 }
@@ -18,9 +18,9 @@ async fn get_tokens() -> Arc<RwLock<HashMap<String, CancellationToken>>> {
         .clone()
 }
 
-/// 注册新的取消令牌
+/// 注册新的取消令牌（应用退出时随全局退出信号一并取消）
 pub async fn register_cancellation_token(execution_id: String) -> CancellationToken {
-    let token = CancellationToken::new();
+    let token = crate::managers::shutdown_coordinator::shutdown_token().child_token();
     let tokens_store = get_tokens().await;
     let mut tokens = tokens_store.write().await;
     tokens.insert(execution_id.clone(), token.clone());
@@ -1,6 +1,8 @@
 //! Managers module
 
 pub mod cancellation_manager;
+pub mod resource_registry;
 pub mod security_test_manager;
+pub mod shutdown_coordinator;
 
 pub use security_test_manager::{SecurityTestManager, SessionStats};
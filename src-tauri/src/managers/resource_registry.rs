@@ -0,0 +1,297 @@
+//! 执行资源登记表
+//!
+//! 执行过程中创建的浏览器、代理、子进程等资源按执行ID登记，并附带释放函数。
+//! 执行正常结束时由 [`ResourceCleanupGuard::cleanup`] 释放仍未注销的资源；
+//! 执行被取消、提前返回或 panic 导致守卫被丢弃时，由 `Drop` 在后台完成释放。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{LazyLock, Mutex};
+
+type CleanupFn =
+    Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>> + Send>;
+
+/// 资源类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceKind {
+    Browser,
+    Proxy,
+    Process,
+    Other,
+}
+
+/// 已登记资源的描述信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedResource {
+    pub id: String,
+    pub kind: ResourceKind,
+    pub description: String,
+    pub registered_at: i64,
+}
+
+struct ResourceEntry {
+    info: TrackedResource,
+    cleanup: CleanupFn,
+}
+
+static LIVE_RESOURCES: LazyLock<Mutex<HashMap<String, Vec<ResourceEntry>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 登记资源，返回资源ID；`cleanup` 在资源被强制释放时调用
+pub fn register_resource<F, Fut>(
+    execution_id: &str,
+    kind: ResourceKind,
+    description: &str,
+    cleanup: F,
+) -> String
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = anyhow::Result<()>> + Send + 'static,
+{
+    let id = uuid::Uuid::new_v4().to_string();
+    let entry = ResourceEntry {
+        info: TrackedResource {
+            id: id.clone(),
+            kind,
+            description: description.to_string(),
+            registered_at: chrono::Utc::now().timestamp_millis(),
+        },
+        cleanup: Box::new(move || Box::pin(cleanup())),
+    };
+    LIVE_RESOURCES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(execution_id.to_string())
+        .or_default()
+        .push(entry);
+    tracing::debug!(
+        "Registered {:?} resource {} for execution {}",
+        kind,
+        id,
+        execution_id
+    );
+    id
+}
+
+/// 资源已由持有方正常关闭时注销（不调用释放函数）
+pub fn unregister_resource(execution_id: &str, resource_id: &str) -> bool {
+    let mut resources = LIVE_RESOURCES.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(entries) = resources.get_mut(execution_id) else {
+        return false;
+    };
+    let before = entries.len();
+    entries.retain(|entry| entry.info.id != resource_id);
+    let removed = entries.len() < before;
+    if entries.is_empty() {
+        resources.remove(execution_id);
+    }
+    removed
+}
+
+/// 持有方用完资源时注销并立即调用其释放函数，返回是否找到该资源
+pub async fn release_resource(execution_id: &str, resource_id: &str) -> bool {
+    let entry = {
+        let mut resources = LIVE_RESOURCES.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(entries) = resources.get_mut(execution_id) else {
+            return false;
+        };
+        let entry = entries
+            .iter()
+            .position(|entry| entry.info.id == resource_id)
+            .map(|index| entries.remove(index));
+        if entries.is_empty() {
+            resources.remove(execution_id);
+        }
+        entry
+    };
+    match entry {
+        Some(entry) => {
+            release_entries(execution_id, vec![entry]).await;
+            true
+        }
+        None => false,
+    }
+}
+
+/// 列出某次执行仍在登记中的资源
+pub fn list_resources(execution_id: &str) -> Vec<TrackedResource> {
+    LIVE_RESOURCES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(execution_id)
+        .map(|entries| entries.iter().map(|entry| entry.info.clone()).collect())
+        .unwrap_or_default()
+}
+
+fn take_resources(execution_id: &str) -> Vec<ResourceEntry> {
+    LIVE_RESOURCES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(execution_id)
+        .unwrap_or_default()
+}
+
+async fn release_entries(execution_id: &str, entries: Vec<ResourceEntry>) -> usize {
+    let count = entries.len();
+    // 后登记的资源可能依赖先登记的资源，按相反顺序释放
+    for entry in entries.into_iter().rev() {
+        if let Err(e) = (entry.cleanup)().await {
+            tracing::warn!(
+                "Failed to release {:?} resource {} ({}) for execution {}: {}",
+                entry.info.kind,
+                entry.info.id,
+                entry.info.description,
+                execution_id,
+                e
+            );
+        }
+    }
+    if count > 0 {
+        tracing::info!(
+            "Released {} resources for execution {}",
+            count,
+            execution_id
+        );
+    }
+    count
+}
+
+/// 立即释放某次执行登记的全部资源，返回释放的数量
+pub async fn cleanup_execution_resources(execution_id: &str) -> usize {
+    release_entries(execution_id, take_resources(execution_id)).await
+}
+
+/// 执行期间持有的释放守卫
+///
+/// 守卫被丢弃（取消、提前返回、panic）而未调用 [`cleanup`](Self::cleanup) 时，
+/// 登记的资源会在后台任务中释放。
+pub struct ResourceCleanupGuard {
+    execution_id: String,
+    armed: bool,
+}
+
+impl ResourceCleanupGuard {
+    pub fn new(execution_id: &str) -> Self {
+        Self {
+            execution_id: execution_id.to_string(),
+            armed: true,
+        }
+    }
+
+    /// 正常结束时释放仍在登记中的资源
+    pub async fn cleanup(mut self) -> usize {
+        self.armed = false;
+        cleanup_execution_resources(&self.execution_id).await
+    }
+}
+
+impl Drop for ResourceCleanupGuard {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        let entries = take_resources(&self.execution_id);
+        if entries.is_empty() {
+            return;
+        }
+        let execution_id = self.execution_id.clone();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    release_entries(&execution_id, entries).await;
+                });
+            }
+            Err(_) => tracing::warn!(
+                "No runtime available to release {} resources for execution {}",
+                entries.len(),
+                execution_id
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn track(execution_id: &str, kind: ResourceKind, released: &Arc<AtomicUsize>) -> String {
+        let released = released.clone();
+        register_resource(execution_id, kind, "test resource", move || async move {
+            released.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_cancel_mid_run_releases_resources() {
+        let execution_id = "test_resource_cancel";
+        let released = Arc::new(AtomicUsize::new(0));
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+
+        let run = {
+            let released = released.clone();
+            tokio::spawn(async move {
+                let guard = ResourceCleanupGuard::new(execution_id);
+                track(execution_id, ResourceKind::Browser, &released);
+                track(execution_id, ResourceKind::Proxy, &released);
+                let _ = started_tx.send(());
+                // 模拟长时间运行，等待被取消
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                guard.cleanup().await
+            })
+        };
+
+        started_rx.await.unwrap();
+        assert_eq!(list_resources(execution_id).len(), 2);
+        run.abort();
+        assert!(run.await.unwrap_err().is_cancelled());
+
+        for _ in 0..50 {
+            if released.load(Ordering::SeqCst) == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(released.load(Ordering::SeqCst), 2);
+        assert!(list_resources(execution_id).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_resources_are_not_released() {
+        let execution_id = "test_resource_unregister";
+        let released = Arc::new(AtomicUsize::new(0));
+
+        let guard = ResourceCleanupGuard::new(execution_id);
+        let browser = track(execution_id, ResourceKind::Browser, &released);
+        track(execution_id, ResourceKind::Process, &released);
+        assert!(unregister_resource(execution_id, &browser));
+        assert!(!unregister_resource(execution_id, &browser));
+
+        assert_eq!(guard.cleanup().await, 1);
+        assert_eq!(released.load(Ordering::SeqCst), 1);
+        assert_eq!(cleanup_execution_resources(execution_id).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_released_resource_runs_cleanup_once() {
+        let execution_id = "test_resource_release";
+        let released = Arc::new(AtomicUsize::new(0));
+
+        let guard = ResourceCleanupGuard::new(execution_id);
+        let tab = track(execution_id, ResourceKind::Browser, &released);
+        track(execution_id, ResourceKind::Process, &released);
+        assert!(release_resource(execution_id, &tab).await);
+        assert_eq!(released.load(Ordering::SeqCst), 1);
+        assert!(!release_resource(execution_id, &tab).await);
+        assert_eq!(list_resources(execution_id).len(), 1);
+
+        assert_eq!(guard.cleanup().await, 1);
+        assert_eq!(released.load(Ordering::SeqCst), 2);
+    }
+}
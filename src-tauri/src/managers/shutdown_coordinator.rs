@@ -0,0 +1,5 @@
+//! 优雅退出协调器
+//!
+//! 实现位于 `sentinel_core::shutdown`，以便扫描器、工作流等子 crate 也能登记操作。
+
+pub use sentinel_core::shutdown::*;
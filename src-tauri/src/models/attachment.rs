@@ -32,6 +32,139 @@ impl ImageMediaType {
             ImageMediaType::WEBP => "image/webp",
         }
     }
+
+    /// 从 MIME 类型字符串解析
+    pub fn from_mime_type(mime: &str) -> Option<Self> {
+        match mime.trim().to_lowercase().as_str() {
+            "image/jpeg" | "image/jpg" => Some(ImageMediaType::JPEG),
+            "image/png" => Some(ImageMediaType::PNG),
+            "image/gif" => Some(ImageMediaType::GIF),
+            "image/webp" => Some(ImageMediaType::WEBP),
+            _ => None,
+        }
+    }
+
+    fn image_format(&self) -> image::ImageFormat {
+        match self {
+            ImageMediaType::JPEG => image::ImageFormat::Jpeg,
+            ImageMediaType::PNG => image::ImageFormat::Png,
+            ImageMediaType::GIF => image::ImageFormat::Gif,
+            ImageMediaType::WEBP => image::ImageFormat::WebP,
+        }
+    }
+}
+
+/// 图片上传校验策略
+#[derive(Debug, Clone)]
+pub struct ImageUploadPolicy {
+    /// 单张图片最大字节数
+    pub max_bytes: u64,
+    /// 允许的 MIME 类型
+    pub allowed_mime_types: Vec<String>,
+    /// 超过该边长时自动等比缩小（None 表示不缩放）
+    pub max_dimension: Option<u32>,
+}
+
+impl Default for ImageUploadPolicy {
+    fn default() -> Self {
+        Self {
+            // 主流提供商单图上限为 5MB（Anthropic）~ 20MB（OpenAI），取较小值
+            max_bytes: 5 * 1024 * 1024,
+            allowed_mime_types: vec![
+                "image/jpeg".to_string(),
+                "image/png".to_string(),
+                "image/gif".to_string(),
+                "image/webp".to_string(),
+            ],
+            max_dimension: Some(2048),
+        }
+    }
+}
+
+impl ImageUploadPolicy {
+    fn allows(&self, media_type: &ImageMediaType) -> bool {
+        self.allowed_mime_types
+            .iter()
+            .any(|m| ImageMediaType::from_mime_type(m).as_ref() == Some(media_type))
+    }
+
+    /// 校验图片并按需缩放，返回最终的字节与媒体类型
+    ///
+    /// 媒体类型以文件内容识别为准，扩展名与内容不符时拒绝
+    pub fn prepare(
+        &self,
+        bytes: Vec<u8>,
+        declared: ImageMediaType,
+    ) -> anyhow::Result<(Vec<u8>, ImageMediaType)> {
+        let detected = image::guess_format(&bytes)
+            .ok()
+            .and_then(|f| ImageMediaType::from_mime_type(f.to_mime_type()))
+            .ok_or_else(|| anyhow::anyhow!("无法识别的图片内容"))?;
+        if detected != declared {
+            return Err(anyhow::anyhow!(
+                "图片内容({})与扩展名({})不符",
+                detected.to_mime_type(),
+                declared.to_mime_type()
+            ));
+        }
+        if !self.allows(&detected) {
+            return Err(anyhow::anyhow!(
+                "不允许的图片类型: {}（允许: {}）",
+                detected.to_mime_type(),
+                self.allowed_mime_types.join(", ")
+            ));
+        }
+
+        // GIF 可能是动图，缩放会丢帧，只做大小校验
+        let (bytes, media_type) = match self.max_dimension {
+            Some(max_dim) if detected != ImageMediaType::GIF => {
+                downscale_image(bytes, detected, max_dim)?
+            }
+            _ => (bytes, detected),
+        };
+
+        if bytes.len() as u64 > self.max_bytes {
+            return Err(anyhow::anyhow!(
+                "图片过大: {} 字节（上限 {} 字节）",
+                bytes.len(),
+                self.max_bytes
+            ));
+        }
+        Ok((bytes, media_type))
+    }
+}
+
+/// 将边长超过 `max_dimension` 的图片等比缩小；JPEG 保持 JPEG，其余格式重新编码为 PNG
+fn downscale_image(
+    bytes: Vec<u8>,
+    media_type: ImageMediaType,
+    max_dimension: u32,
+) -> anyhow::Result<(Vec<u8>, ImageMediaType)> {
+    let img = image::load_from_memory_with_format(&bytes, media_type.image_format())
+        .map_err(|e| anyhow::anyhow!("解码图片失败: {}", e))?;
+    if img.width() <= max_dimension && img.height() <= max_dimension {
+        return Ok((bytes, media_type));
+    }
+
+    let resized = img.resize(
+        max_dimension,
+        max_dimension,
+        image::imageops::FilterType::Lanczos3,
+    );
+    let output_type = match media_type {
+        ImageMediaType::JPEG => ImageMediaType::JPEG,
+        _ => ImageMediaType::PNG,
+    };
+    let mut out = std::io::Cursor::new(Vec::new());
+    let resized = if output_type == ImageMediaType::JPEG {
+        image::DynamicImage::ImageRgb8(resized.to_rgb8())
+    } else {
+        resized
+    };
+    resized
+        .write_to(&mut out, output_type.image_format())
+        .map_err(|e| anyhow::anyhow!("编码缩放后的图片失败: {}", e))?;
+    Ok((out.into_inner(), output_type))
 }
 
 /// 文档源类型（与 Rig 兼容）
@@ -191,6 +324,34 @@ impl DocumentAttachment {
         true
     }
 
+    /// 校验上传的文档能否转换为模型可接收的内容
+    ///
+    /// 已知格式由沙箱内的提取流程处理，文档原始数据不会直接发给提供商；
+    /// 未知扩展名会按文本发送给模型，因此内容必须是文本，二进制文件直接拒绝，
+    /// 避免请求被提供商拒收
+    pub fn validate_upload(filename: &str, bytes: &[u8]) -> Result<(), String> {
+        let ext = std::path::Path::new(filename)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        if Self::SUPPORTED_EXTENSIONS.contains(&ext.as_str())
+            || ImageMediaType::from_extension(&ext).is_some()
+        {
+            return Ok(());
+        }
+        let sample = &bytes[..bytes.len().min(8192)];
+        // 末尾被截断的多字节字符不算非法
+        let invalid_utf8 = std::str::from_utf8(sample).is_err_and(|e| e.error_len().is_some());
+        if sample.contains(&0) || invalid_utf8 {
+            return Err(format!(
+                "Unsupported document type: {} (binary content with unknown extension)",
+                filename
+            ));
+        }
+        Ok(())
+    }
+
     /// 从扩展名推断 MIME 类型
     pub fn mime_type_from_extension(ext: &str) -> &'static str {
         match ext.to_lowercase().as_str() {
@@ -287,8 +448,11 @@ impl MessageAttachment {
     }
 }
 
-/// 从文件路径读取图片并创建附件
-pub async fn load_image_from_path(file_path: &str) -> anyhow::Result<ImageAttachment> {
+/// 从文件路径读取图片并创建附件（按策略校验类型与大小，必要时缩放）
+pub async fn load_image_from_path(
+    file_path: &str,
+    policy: &ImageUploadPolicy,
+) -> anyhow::Result<ImageAttachment> {
     use std::path::Path;
 
     let path = Path::new(file_path);
@@ -303,8 +467,27 @@ pub async fn load_image_from_path(file_path: &str) -> anyhow::Result<ImageAttach
     let media_type = ImageMediaType::from_extension(extension)
         .ok_or_else(|| anyhow::anyhow!("不支持的图片格式: {}", extension))?;
 
+    if !policy.allows(&media_type) {
+        return Err(anyhow::anyhow!(
+            "不允许的图片类型: {}（允许: {}）",
+            media_type.to_mime_type(),
+            policy.allowed_mime_types.join(", ")
+        ));
+    }
+
+    // 不缩放时，超限文件在读取前即拒绝
+    let file_size = tokio::fs::metadata(file_path).await?.len();
+    if policy.max_dimension.is_none() && file_size > policy.max_bytes {
+        return Err(anyhow::anyhow!(
+            "图片过大: {} 字节（上限 {} 字节）",
+            file_size,
+            policy.max_bytes
+        ));
+    }
+
     // 读取文件内容
     let bytes = tokio::fs::read(file_path).await?;
+    let (bytes, media_type) = policy.prepare(bytes, media_type)?;
 
     // 获取文件名
     let filename = path
@@ -362,4 +545,76 @@ mod tests {
         assert!(attachment.is_image());
         assert!(attachment.as_image().is_some());
     }
+
+    fn encode_png(width: u32, height: u32) -> Vec<u8> {
+        // 噪声像素避免 PNG 压缩得过小
+        let img = image::RgbImage::from_fn(width, height, |x, y| {
+            let v = (x.wrapping_mul(31) ^ y.wrapping_mul(17)) as u8;
+            image::Rgb([v, v.wrapping_add(85), v.wrapping_add(170)])
+        });
+        let mut out = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(img)
+            .write_to(&mut out, image::ImageFormat::Png)
+            .unwrap();
+        out.into_inner()
+    }
+
+    #[test]
+    fn test_oversize_image_rejected() {
+        let bytes = encode_png(512, 512);
+        let policy = ImageUploadPolicy {
+            max_bytes: 1024,
+            max_dimension: None,
+            ..Default::default()
+        };
+
+        let err = policy.prepare(bytes, ImageMediaType::PNG).unwrap_err();
+        assert!(err.to_string().contains("图片过大"));
+    }
+
+    #[test]
+    fn test_disallowed_or_mismatched_type_rejected() {
+        let bytes = encode_png(8, 8);
+        let policy = ImageUploadPolicy {
+            allowed_mime_types: vec!["image/jpeg".to_string()],
+            ..Default::default()
+        };
+        assert!(policy.prepare(bytes.clone(), ImageMediaType::PNG).is_err());
+
+        // 扩展名声明为 JPEG 但内容是 PNG
+        assert!(ImageUploadPolicy::default()
+            .prepare(bytes, ImageMediaType::JPEG)
+            .is_err());
+    }
+
+    #[test]
+    fn test_large_image_downscaled() {
+        let bytes = encode_png(3000, 1500);
+        let policy = ImageUploadPolicy {
+            max_bytes: 20 * 1024 * 1024,
+            max_dimension: Some(1000),
+            ..Default::default()
+        };
+
+        let (out, media_type) = policy.prepare(bytes.clone(), ImageMediaType::PNG).unwrap();
+        assert_eq!(media_type, ImageMediaType::PNG);
+        assert!(out.len() < bytes.len());
+        let img = image::load_from_memory(&out).unwrap();
+        assert_eq!((img.width(), img.height()), (1000, 500));
+    }
+
+    #[test]
+    fn test_document_validation() {
+        assert!(DocumentAttachment::validate_upload("report.pdf", &[0, 1, 2]).is_ok());
+        assert!(DocumentAttachment::validate_upload("notes.custom", b"plain text").is_ok());
+        assert!(DocumentAttachment::validate_upload("payload.bin", &[0x7f, b'E', 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_binary_documents_accepted_for_sandbox_extraction() {
+        // 文档在沙箱内提取，即便当前提供商（如 DeepSeek）不接收文件也不拒绝上传
+        for name in ["report.pdf", "report.docx", "sheet.xlsx", "bundle.zip"] {
+            assert!(DocumentAttachment::validate_upload(name, &[0x50, 0x4b, 0, 0]).is_ok());
+        }
+    }
 }
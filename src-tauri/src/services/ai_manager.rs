@@ -5,6 +5,7 @@
 use anyhow::Result;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 use tauri::AppHandle;
 use tracing::{debug, error, info, warn};
@@ -21,6 +22,68 @@ pub struct AiServiceManager {
     services: Arc<std::sync::RwLock<HashMap<String, AiServiceWrapper>>>,
     db: Arc<dyn Database + Send + Sync>,
     app_handle: Arc<std::sync::RwLock<Option<AppHandle>>>,
+    /// 服务名 -> 提供商下线模型时的备用模型
+    fallback_models: Arc<std::sync::RwLock<HashMap<String, String>>>,
+    /// 服务名 -> 模型不可用原因（已被提供商下线且无备用模型）
+    unavailable_models: Arc<std::sync::RwLock<HashMap<String, String>>>,
+}
+
+/// 模型可用性校验结果
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ModelAvailability {
+    /// 模型仍被提供商列出
+    Available,
+    /// 模型已下线，改用备用模型
+    FellBack { model: String, fallback: String },
+    /// 模型已下线且没有可用的备用模型
+    Unavailable { model: String, message: String },
+}
+
+/// 对照提供商返回的模型列表检查配置的模型
+///
+/// Gemini 等提供商返回 `models/<id>` 形式，比较时忽略该前缀与大小写；
+/// Ollama 等提供商的 `:latest` 标签与省略标签视为同一模型
+pub fn check_model_availability(
+    provider: &str,
+    model: &str,
+    listed: &[String],
+    fallback: Option<&str>,
+) -> ModelAvailability {
+    fn normalize(id: &str) -> String {
+        let id = id.trim().trim_start_matches("models/").to_lowercase();
+        match id.strip_suffix(":latest") {
+            Some(base) => base.to_string(),
+            None => id,
+        }
+    }
+    let is_listed = |id: &str| {
+        let id = normalize(id);
+        listed.iter().any(|m| normalize(m) == id)
+    };
+
+    if is_listed(model) {
+        return ModelAvailability::Available;
+    }
+    if let Some(fallback) = fallback.filter(|f| !f.trim().is_empty() && is_listed(f)) {
+        return ModelAvailability::FellBack {
+            model: model.to_string(),
+            fallback: fallback.to_string(),
+        };
+    }
+
+    let mut suggestions: Vec<&str> = listed.iter().map(|m| m.as_str()).take(5).collect();
+    suggestions.sort_unstable();
+    ModelAvailability::Unavailable {
+        model: model.to_string(),
+        message: format!(
+            "Model '{}' is no longer offered by provider '{}'. Choose another default model in AI Settings{}{}",
+            model,
+            provider,
+            if suggestions.is_empty() { "" } else { ", e.g. " },
+            suggestions.join(", ")
+        ),
+    }
 }
 
 /// 包装 AiService 并添加应用特定功能
@@ -157,6 +220,9 @@ pub struct ProviderConfig {
     organization: Option<String>,
     enabled: bool,
     default_model: String,
+    /// 默认模型被提供商下线时使用的备用模型
+    #[serde(default)]
+    fallback_model: Option<String>,
     #[allow(unused)]
     models: Vec<ModelDefinition>,
 }
@@ -178,6 +244,8 @@ impl AiServiceManager {
             services: Arc::new(std::sync::RwLock::new(HashMap::new())),
             db,
             app_handle: Arc::new(std::sync::RwLock::new(None)),
+            fallback_models: Arc::new(std::sync::RwLock::new(HashMap::new())),
+            unavailable_models: Arc::new(std::sync::RwLock::new(HashMap::new())),
         }
     }
 
@@ -336,11 +404,100 @@ impl AiServiceManager {
         Ok(())
     }
 
+    /// 获取服务；模型已被提供商下线的服务不会返回
     pub fn get_service(&self, name: &str) -> Option<AiServiceWrapper> {
+        if let Some(reason) = self.model_unavailable_reason(name) {
+            warn!("AI service '{}' skipped: {}", name, reason);
+            return None;
+        }
         let services = self.services.read().unwrap();
         services.get(name).cloned()
     }
 
+    /// 获取服务，找不到时返回可操作的错误信息
+    pub fn get_service_checked(&self, name: &str) -> Result<AiServiceWrapper> {
+        if let Some(reason) = self.model_unavailable_reason(name) {
+            anyhow::bail!(reason);
+        }
+        self.get_service(name)
+            .ok_or_else(|| anyhow::anyhow!("AI service '{}' not found", name))
+    }
+
+    /// 服务配置的模型不可用的原因
+    pub fn model_unavailable_reason(&self, name: &str) -> Option<String> {
+        self.unavailable_models.read().unwrap().get(name).cloned()
+    }
+
+    /// 对照提供商模型列表校验每个服务配置的模型
+    ///
+    /// `list_models` 返回提供商当前提供的模型；列表获取失败或为空时视为无法校验，保持原状。
+    /// 模型已下线时切换到备用模型，没有备用模型则标记为不可用，`get_service` 不再返回该服务。
+    pub async fn validate_model_availability<F, Fut>(
+        &self,
+        list_models: F,
+    ) -> HashMap<String, ModelAvailability>
+    where
+        F: Fn(AiConfig) -> Fut,
+        Fut: Future<Output = std::result::Result<Vec<String>, String>>,
+    {
+        let services: Vec<(String, AiConfig)> = {
+            let services = self.services.read().unwrap();
+            services
+                .iter()
+                .map(|(name, svc)| (name.clone(), svc.get_config().clone()))
+                .collect()
+        };
+
+        let mut results = HashMap::new();
+        for (name, config) in services {
+            if config.provider == "unconfigured" || config.model.trim().is_empty() {
+                continue;
+            }
+            let listed = match list_models(config.clone()).await {
+                Ok(listed) if !listed.is_empty() => listed,
+                Ok(_) => continue,
+                Err(e) => {
+                    debug!("Skip model validation for '{}': {}", name, e);
+                    continue;
+                }
+            };
+
+            let fallback = self.fallback_models.read().unwrap().get(&name).cloned();
+            let availability = check_model_availability(
+                &config.provider,
+                &config.model,
+                &listed,
+                fallback.as_deref(),
+            );
+            match &availability {
+                ModelAvailability::Available => {
+                    self.unavailable_models.write().unwrap().remove(&name);
+                }
+                ModelAvailability::FellBack { model, fallback } => {
+                    warn!(
+                        "Model '{}' for AI service '{}' is no longer available, falling back to '{}'",
+                        model, name, fallback
+                    );
+                    let mut config = config.clone();
+                    config.model = fallback.clone();
+                    self.unavailable_models.write().unwrap().remove(&name);
+                    if let Err(e) = self.add_service(name.clone(), config).await {
+                        error!("Failed to switch '{}' to fallback model: {}", name, e);
+                    }
+                }
+                ModelAvailability::Unavailable { message, .. } => {
+                    error!("AI service '{}': {}", name, message);
+                    self.unavailable_models
+                        .write()
+                        .unwrap()
+                        .insert(name.clone(), message.clone());
+                }
+            }
+            results.insert(name, availability);
+        }
+        results
+    }
+
     pub fn list_services(&self) -> Vec<String> {
         let services = self.services.read().unwrap();
         services.keys().cloned().collect()
@@ -357,6 +514,8 @@ impl AiServiceManager {
             let mut services = self.services.write().unwrap();
             services.clear();
         }
+        self.fallback_models.write().unwrap().clear();
+        self.unavailable_models.write().unwrap().clear();
         self.init_default_services().await
     }
 
@@ -462,6 +621,14 @@ impl AiServiceManager {
 
                         // Use lowercase name as service key for consistency
                         let service_key = provider_config.name.to_lowercase();
+                        if let Some(fallback) =
+                            provider_config.fallback_model.filter(|s| !s.is_empty())
+                        {
+                            self.fallback_models
+                                .write()
+                                .unwrap()
+                                .insert(service_key.clone(), fallback);
+                        }
                         if let Err(e) = self.add_service(service_key.clone(), config).await {
                             error!(
                                 "Failed to add service for provider {}: {}",
@@ -558,6 +725,7 @@ impl AiServiceManager {
                 if let Some(wrapper) = self.get_service(provider) {
                     let config = wrapper.get_config().clone();
                     self.add_service("default".to_string(), config).await?;
+                    self.alias_fallback_model("default", provider);
                     debug!("Created default alias pointing to {}", provider);
                     return Ok(());
                 }
@@ -568,6 +736,7 @@ impl AiServiceManager {
             if let Some(wrapper) = self.get_service(first) {
                 let config = wrapper.get_config().clone();
                 self.add_service("default".to_string(), config).await?;
+                self.alias_fallback_model("default", first);
                 info!("Created default alias pointing to {}", first);
             }
         }
@@ -579,7 +748,9 @@ impl AiServiceManager {
         let provider_lc = provider.to_lowercase();
 
         // First try to get service by lowercase name (our standard key format)
-        let service = self.get_service(&provider_lc);
+        let service = self
+            .get_service(&provider_lc)
+            .map(|svc| (provider_lc.clone(), svc));
 
         // If not found, search by provider field in config
         let service = if service.is_some() {
@@ -589,10 +760,13 @@ impl AiServiceManager {
             services
                 .iter()
                 .find(|(_n, svc)| svc.get_config().provider.to_lowercase() == provider_lc)
-                .map(|(_, svc)| svc.clone())
+                .map(|(n, svc)| (n.clone(), svc.clone()))
         };
 
-        let Some(service) = service else {
+        let Some((service_key, service)) = service else {
+            if let Some(reason) = self.model_unavailable_reason(&provider_lc) {
+                anyhow::bail!(reason);
+            }
             anyhow::bail!("Target provider service '{}' not found", provider);
         };
 
@@ -602,10 +776,27 @@ impl AiServiceManager {
             services.remove("default");
         }
         self.add_service("default".to_string(), config).await?;
+        self.alias_fallback_model("default", &service_key);
         info!("Default service alias now points to '{}'", provider_lc);
         Ok(())
     }
 
+    /// 别名服务沿用源服务的备用模型与不可用状态
+    fn alias_fallback_model(&self, alias: &str, source: &str) {
+        let fallback = self.fallback_models.read().unwrap().get(source).cloned();
+        let mut fallbacks = self.fallback_models.write().unwrap();
+        match fallback {
+            Some(fallback) => fallbacks.insert(alias.to_string(), fallback),
+            None => fallbacks.remove(alias),
+        };
+        let reason = self.model_unavailable_reason(source);
+        let mut unavailable = self.unavailable_models.write().unwrap();
+        match reason {
+            Some(reason) => unavailable.insert(alias.to_string(), reason),
+            None => unavailable.remove(alias),
+        };
+    }
+
     async fn create_minimal_default_service(&self) -> anyhow::Result<()> {
         warn!("Creating minimal default service - no AI providers configured!");
         let (temperature, max_tokens) = self.load_generation_settings().await;
@@ -809,3 +1000,84 @@ pub struct ModelInfo {
 
 // 为兼容性提供类型别名
 pub type LegacyAiService = AiServiceWrapper;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listed(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_listed_model_is_available() {
+        let models = listed(&["models/gemini-2.5-flash", "models/gemini-2.5-pro"]);
+        assert_eq!(
+            check_model_availability("gemini", "gemini-2.5-flash", &models, None),
+            ModelAvailability::Available
+        );
+    }
+
+    #[test]
+    fn test_latest_tag_and_case_are_ignored() {
+        let models = listed(&["llama3.1:latest", "Qwen2.5:7b"]);
+        assert_eq!(
+            check_model_availability("ollama", "llama3.1", &models, None),
+            ModelAvailability::Available
+        );
+        assert_eq!(
+            check_model_availability("ollama", "LLAMA3.1:Latest", &models, None),
+            ModelAvailability::Available
+        );
+        assert_eq!(
+            check_model_availability("ollama", "qwen2.5:7b", &models, None),
+            ModelAvailability::Available
+        );
+        // 其它标签仍需精确匹配
+        assert!(matches!(
+            check_model_availability("ollama", "qwen2.5", &models, None),
+            ModelAvailability::Unavailable { .. }
+        ));
+    }
+
+    #[test]
+    fn test_decommissioned_model_is_unavailable() {
+        let models = listed(&["claude-3-5-haiku-20241022", "claude-sonnet-4-20250514"]);
+        match check_model_availability("anthropic", "claude-3-haiku-20240307", &models, None) {
+            ModelAvailability::Unavailable { model, message } => {
+                assert_eq!(model, "claude-3-haiku-20240307");
+                assert!(message.contains("no longer offered"));
+                assert!(message.contains("claude-3-5-haiku-20241022"));
+            }
+            other => panic!("expected unavailable, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decommissioned_model_falls_back() {
+        let models = listed(&["claude-3-5-haiku-20241022"]);
+        assert_eq!(
+            check_model_availability(
+                "anthropic",
+                "claude-3-haiku-20240307",
+                &models,
+                Some("claude-3-5-haiku-20241022"),
+            ),
+            ModelAvailability::FellBack {
+                model: "claude-3-haiku-20240307".to_string(),
+                fallback: "claude-3-5-haiku-20241022".to_string(),
+            }
+        );
+
+        // 备用模型本身也已下线时不能切换
+        assert!(matches!(
+            check_model_availability(
+                "anthropic",
+                "claude-3-haiku-20240307",
+                &models,
+                Some("claude-2.1")
+            ),
+            ModelAvailability::Unavailable { .. }
+        ));
+    }
+}
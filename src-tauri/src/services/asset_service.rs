@@ -3,6 +3,13 @@ use sentinel_db::{Database, DatabaseService};
 use std::collections::HashMap;
 use std::sync::Arc;
 
+/// 关系图导出的默认深度
+const DEFAULT_GRAPH_DEPTH: u32 = 2;
+/// 关系图导出的最大深度
+const MAX_GRAPH_DEPTH: u32 = 6;
+/// 关系图导出的最大节点数
+const MAX_GRAPH_NODES: usize = 500;
+
 pub struct AssetService {
     db: Arc<DatabaseService>,
 }
@@ -396,24 +403,69 @@ impl AssetService {
         &self,
         query: &str,
         asset_types: Option<Vec<AssetType>>,
+        tags: Option<Vec<String>>,
         limit: Option<u32>,
     ) -> Result<Vec<Asset>, String> {
         let filter = AssetFilter {
             asset_types,
-            statuses: None,
-            risk_levels: None,
-            sources: None,
-            tags: None,
+            tags,
             search: Some(query.to_string()),
-            created_after: None,
-            created_before: None,
-            last_seen_after: None,
-            last_seen_before: None,
+            ..Default::default()
         };
 
         self.list_assets(Some(filter), limit, None).await
     }
 
+    /// 为资产添加标签，返回标签发生变化的资产数
+    pub async fn add_asset_tags(
+        &self,
+        asset_ids: &[String],
+        tags: &[String],
+    ) -> Result<usize, String> {
+        self.db
+            .add_asset_tags(asset_ids, tags)
+            .await
+            .map_err(|e: anyhow::Error| format!("Database error: {}", e))
+    }
+
+    /// 移除资产标签，返回标签发生变化的资产数
+    pub async fn remove_asset_tags(
+        &self,
+        asset_ids: &[String],
+        tags: &[String],
+    ) -> Result<usize, String> {
+        self.db
+            .remove_asset_tags(asset_ids, tags)
+            .await
+            .map_err(|e: anyhow::Error| format!("Database error: {}", e))
+    }
+
+    /// 批量打标签（按 ID 列表或过滤条件）
+    pub async fn bulk_tag_assets(
+        &self,
+        request: BulkTagAssetsRequest,
+    ) -> Result<BulkTagAssetsResult, String> {
+        self.db
+            .bulk_tag_assets(request)
+            .await
+            .map_err(|e: anyhow::Error| format!("Database error: {}", e))
+    }
+
+    /// 按标签查询资产
+    pub async fn list_assets_by_tag(
+        &self,
+        tag: &str,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<Vec<Asset>, String> {
+        let filter = AssetFilter {
+            tags: Some(vec![tag.to_string()]),
+            ..Default::default()
+        };
+
+        self.list_assets(Some(filter), limit, offset).await
+    }
+
     /// 获取资产的相关资产（通过关系）
     pub async fn get_related_assets(&self, asset_id: &str) -> Result<Vec<Asset>, String> {
         let (incoming, outgoing) = self
@@ -445,6 +497,29 @@ impl AssetService {
         Ok(related_assets)
     }
 
+    /// 导出以某个资产为根的关系图（DOT 或节点/边 JSON）
+    pub async fn export_asset_graph(
+        &self,
+        root_id: &str,
+        depth: Option<u32>,
+        format: AssetGraphFormat,
+    ) -> Result<String, String> {
+        let depth = depth
+            .unwrap_or(DEFAULT_GRAPH_DEPTH)
+            .clamp(1, MAX_GRAPH_DEPTH);
+        let graph = self
+            .db
+            .build_asset_graph(root_id, depth, MAX_GRAPH_NODES)
+            .await
+            .map_err(|e: anyhow::Error| format!("Database error: {}", e))?;
+
+        match format {
+            AssetGraphFormat::Dot => Ok(graph.to_dot()),
+            AssetGraphFormat::Json => serde_json::to_string(&graph)
+                .map_err(|e| format!("Failed to serialize asset graph: {}", e)),
+        }
+    }
+
     /// 标记资产为已验证
     pub async fn verify_asset(&self, asset_id: &str) -> Result<bool, String> {
         let update_request = UpdateAssetRequest {
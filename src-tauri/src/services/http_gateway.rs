@@ -1689,6 +1689,8 @@ async fn run_agent_execution(
         subagent_run_id: None,
         context_policy: None,
         recursion_depth: 0,
+        cost_limit_usd: None,
+        subagent_limits: Default::default(),
     };
 
     crate::agents::execute_agent(&state.app_handle, params)
@@ -2323,6 +2325,7 @@ async fn bridge_invoke(
             } else {
                 let _ =
                     crate::managers::cancellation_manager::cancel_execution(&conversation_id).await;
+                sentinel_tools::cancellation::cancel_scope(&conversation_id).await;
                 Ok(json!(null))
             }
         }
@@ -3812,7 +3815,7 @@ async fn chat_stream(
                             sentinel_llm::StreamContent::ToolCallDelta { id, delta } => json!({"type":"tool_call_delta","id":id,"delta":delta, "request_id": request_id_for_task}),
                             sentinel_llm::StreamContent::ToolCallComplete { id, name, arguments } => json!({"type":"tool_call_complete","id":id,"name":name,"arguments":arguments, "request_id": request_id_for_task}),
                             sentinel_llm::StreamContent::ToolResult { id, result } => json!({"type":"tool_result","id":id,"result":result, "request_id": request_id_for_task}),
-                            sentinel_llm::StreamContent::Usage { input_tokens, output_tokens } => json!({"type":"usage","input_tokens":input_tokens,"output_tokens":output_tokens, "request_id": request_id_for_task}),
+                            sentinel_llm::StreamContent::Usage { input_tokens, output_tokens, estimated } => json!({"type":"usage","input_tokens":input_tokens,"output_tokens":output_tokens,"estimated":estimated, "request_id": request_id_for_task}),
                             sentinel_llm::StreamContent::Done => json!({"type":"task_status","status":"stream_done","request_id": request_id_for_task}),
                         };
                         tx.send(evt.to_string()).is_ok()
@@ -0,0 +1,785 @@
+//! MCP server exposing Sentinel data to external MCP clients
+//!
+//! Resources (read-only):
+//! - `sentinel://findings/{id}`: passive scan findings
+//! - `sentinel://proxy-history/{id}`: captured HTTP requests and responses
+//!
+//! Prompts are the active stored prompt templates; `{{variable}}` placeholders
+//! are filled from the `get_prompt` arguments.
+//!
+//! The server is exposed over streamable HTTP on `127.0.0.1` through
+//! [`start_mcp_server`], driven by the `*_sentinel_mcp_server` commands.
+//! Clients must send the per-install token as `Authorization: Bearer <token>`;
+//! requests whose `Host` or `Origin` is not a loopback name are rejected, which
+//! keeps web pages (including DNS rebinding) out.
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{header, Request, StatusCode};
+use axum::middleware::{from_fn_with_state, Next};
+use axum::response::{IntoResponse, Response};
+use rmcp::model::{
+    AnnotateAble, GetPromptRequestParam, GetPromptResult, Implementation, ListPromptsResult,
+    ListResourcesResult, PaginatedRequestParam, Prompt, PromptArgument, PromptMessage,
+    PromptMessageRole, RawResource, ReadResourceRequestParam, ReadResourceResult, Resource,
+    ResourceContents, ServerCapabilities, ServerInfo,
+};
+use rmcp::service::RequestContext;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::streamable_http_server::{StreamableHttpServerConfig, StreamableHttpService};
+use rmcp::{ErrorData as McpError, RoleServer, ServerHandler};
+use sentinel_db::{
+    DatabaseService, PromptTemplateRecord, ProxyRequestFilters, ProxyRequestRecord,
+    TrafficVulnerabilityFilters, TrafficVulnerabilityRecord,
+};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::{error, info};
+
+const FINDINGS_URI_PREFIX: &str = "sentinel://findings/";
+const PROXY_HISTORY_URI_PREFIX: &str = "sentinel://proxy-history/";
+const JSON_MIME_TYPE: &str = "application/json";
+
+/// HTTP path the streamable MCP endpoint is mounted on
+pub const MCP_ENDPOINT_PATH: &str = "/mcp";
+
+/// Most recent items listed per resource kind
+const MAX_LISTED_RESOURCES: i64 = 100;
+
+/// Read-only data served over MCP
+#[async_trait]
+pub trait McpContentSource: Send + Sync {
+    async fn list_findings(&self, limit: i64) -> Result<Vec<TrafficVulnerabilityRecord>>;
+    async fn get_finding(&self, id: &str) -> Result<Option<TrafficVulnerabilityRecord>>;
+    async fn list_proxy_requests(&self, limit: i64) -> Result<Vec<ProxyRequestRecord>>;
+    async fn get_proxy_request(&self, id: i64) -> Result<Option<ProxyRequestRecord>>;
+    async fn list_prompt_templates(&self) -> Result<Vec<PromptTemplateRecord>>;
+}
+
+#[async_trait]
+impl McpContentSource for DatabaseService {
+    async fn list_findings(&self, limit: i64) -> Result<Vec<TrafficVulnerabilityRecord>> {
+        self.list_traffic_vulnerabilities(TrafficVulnerabilityFilters {
+            limit: Some(limit),
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn get_finding(&self, id: &str) -> Result<Option<TrafficVulnerabilityRecord>> {
+        self.get_traffic_vulnerability_by_id(id).await
+    }
+
+    async fn list_proxy_requests(&self, limit: i64) -> Result<Vec<ProxyRequestRecord>> {
+        DatabaseService::list_proxy_requests(
+            self,
+            ProxyRequestFilters {
+                limit: Some(limit),
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    async fn get_proxy_request(&self, id: i64) -> Result<Option<ProxyRequestRecord>> {
+        self.get_proxy_request_by_id(id).await
+    }
+
+    async fn list_prompt_templates(&self) -> Result<Vec<PromptTemplateRecord>> {
+        self.list_active_prompt_templates().await
+    }
+}
+
+/// Sentinel MCP server
+#[derive(Clone)]
+pub struct SentinelMcpServer {
+    source: Arc<dyn McpContentSource>,
+}
+
+impl SentinelMcpServer {
+    pub fn new(source: Arc<dyn McpContentSource>) -> Self {
+        Self { source }
+    }
+
+    async fn find_prompt(&self, name: &str) -> Result<PromptTemplateRecord, McpError> {
+        self.source
+            .list_prompt_templates()
+            .await
+            .map_err(internal_error)?
+            .into_iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| McpError::invalid_params(format!("Prompt not found: {}", name), None))
+    }
+}
+
+impl ServerHandler for SentinelMcpServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: Default::default(),
+            capabilities: ServerCapabilities::builder()
+                .enable_resources()
+                .enable_prompts()
+                .build(),
+            server_info: Implementation {
+                name: "sentinel-ai".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                ..Default::default()
+            },
+            instructions: Some(
+                "Read-only access to Sentinel scan findings, proxy history and prompt templates."
+                    .to_string(),
+            ),
+        }
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        let findings = self
+            .source
+            .list_findings(MAX_LISTED_RESOURCES)
+            .await
+            .map_err(internal_error)?;
+        let requests = self
+            .source
+            .list_proxy_requests(MAX_LISTED_RESOURCES)
+            .await
+            .map_err(internal_error)?;
+
+        let resources = findings
+            .iter()
+            .map(finding_resource)
+            .chain(requests.iter().filter_map(proxy_request_resource))
+            .collect();
+        Ok(ListResourcesResult::with_all_items(resources))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let uri = request.uri;
+        let body = if let Some(id) = uri.strip_prefix(FINDINGS_URI_PREFIX) {
+            self.source
+                .get_finding(id)
+                .await
+                .map_err(internal_error)?
+                .map(|finding| serde_json::to_string_pretty(&finding))
+        } else if let Some(id) = uri.strip_prefix(PROXY_HISTORY_URI_PREFIX) {
+            let id = id.parse::<i64>().map_err(|_| resource_not_found(&uri))?;
+            self.source
+                .get_proxy_request(id)
+                .await
+                .map_err(internal_error)?
+                .map(|request| serde_json::to_string_pretty(&request))
+        } else {
+            None
+        };
+
+        let text = body
+            .ok_or_else(|| resource_not_found(&uri))?
+            .map_err(internal_error)?;
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::text(text, uri)],
+        })
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        let prompts = self
+            .source
+            .list_prompt_templates()
+            .await
+            .map_err(internal_error)?
+            .iter()
+            .map(template_prompt)
+            .collect();
+        Ok(ListPromptsResult::with_all_items(prompts))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        let template = self.find_prompt(&request.name).await?;
+        let arguments: BTreeMap<String, String> = request
+            .arguments
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(k, v)| {
+                let value = match v {
+                    serde_json::Value::String(s) => s,
+                    other => other.to_string(),
+                };
+                (k, value)
+            })
+            .collect();
+
+        let missing: Vec<String> = template
+            .variable_names()
+            .into_iter()
+            .filter(|name| !arguments.contains_key(name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(McpError::invalid_params(
+                format!("Missing prompt arguments: {}", missing.join(", ")),
+                None,
+            ));
+        }
+
+        Ok(GetPromptResult {
+            description: template.description.clone(),
+            messages: vec![PromptMessage::new_text(
+                PromptMessageRole::User,
+                render_template(&template.content, &arguments),
+            )],
+        })
+    }
+}
+
+/// Running MCP HTTP endpoint
+pub struct SentinelMcpServerRuntime {
+    bind_addr: SocketAddr,
+    started_at: chrono::DateTime<chrono::Utc>,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+    task: JoinHandle<()>,
+}
+
+impl SentinelMcpServerRuntime {
+    pub fn bind_addr(&self) -> String {
+        self.bind_addr.to_string()
+    }
+
+    pub fn endpoint_url(&self) -> String {
+        format!("http://{}{}", self.bind_addr, MCP_ENDPOINT_PATH)
+    }
+
+    pub fn started_at(&self) -> String {
+        self.started_at.to_rfc3339()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    pub async fn stop(mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+
+        let _ = tokio::time::timeout(Duration::from_secs(3), self.task).await;
+    }
+}
+
+/// Generate a random access token for the MCP endpoint
+pub fn generate_access_token() -> String {
+    use rand::RngCore;
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    matches!(host, "127.0.0.1" | "::1") || host.eq_ignore_ascii_case("localhost")
+}
+
+/// `Host` header value (`host[:port]`) naming this machine
+fn is_loopback_authority(value: &str) -> bool {
+    value
+        .parse::<axum::http::uri::Authority>()
+        .is_ok_and(|authority| is_loopback_host(authority.host()))
+}
+
+/// `Origin` header value (`scheme://host[:port]`) naming this machine
+fn is_loopback_origin(value: &str) -> bool {
+    value
+        .parse::<axum::http::Uri>()
+        .ok()
+        .and_then(|uri| uri.host().map(is_loopback_host))
+        .unwrap_or(false)
+}
+
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    let (provided, expected) = (provided.as_bytes(), expected.as_bytes());
+    provided.len() == expected.len()
+        && provided
+            .iter()
+            .zip(expected)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Reject non-loopback `Host`/`Origin` and requests without the access token
+async fn access_middleware(
+    State(token): State<Arc<str>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let headers = req.headers();
+    let host_ok = headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(is_loopback_authority);
+    let origin_ok = headers
+        .get(header::ORIGIN)
+        .map(|v| v.to_str().is_ok_and(is_loopback_origin))
+        .unwrap_or(true);
+    if !host_ok || !origin_ok {
+        return (StatusCode::FORBIDDEN, "Host or Origin not allowed").into_response();
+    }
+
+    let authorized = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|provided| tokens_match(provided.trim(), &token));
+    if !authorized {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            "Missing or invalid access token",
+        )
+            .into_response();
+    }
+
+    next.run(req).await
+}
+
+/// Serve the MCP server over streamable HTTP on `127.0.0.1:{port}`, accepting
+/// only requests that carry `access_token`
+pub async fn start_mcp_server(
+    source: Arc<dyn McpContentSource>,
+    port: u16,
+    access_token: &str,
+) -> Result<SentinelMcpServerRuntime, String> {
+    if access_token.is_empty() {
+        return Err("MCP server access token is empty".to_string());
+    }
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("Failed to bind MCP server: {}", e))?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read local address: {}", e))?;
+
+    let service = StreamableHttpService::new(
+        move || Ok(SentinelMcpServer::new(source.clone())),
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig::default(),
+    );
+    let app = axum::Router::new()
+        .nest_service(MCP_ENDPOINT_PATH, service)
+        .layer(from_fn_with_state(
+            Arc::<str>::from(access_token),
+            access_middleware,
+        ));
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+    let task = tokio::spawn(async move {
+        info!("MCP server listening on {}", local_addr);
+        let result = axum::serve(listener, app)
+            .with_graceful_shutdown(async move {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+
+        if let Err(e) = result {
+            error!("MCP server error: {}", e);
+        }
+
+        info!("MCP server stopped");
+    });
+
+    Ok(SentinelMcpServerRuntime {
+        bind_addr: local_addr,
+        started_at: chrono::Utc::now(),
+        shutdown_tx: Some(shutdown_tx),
+        task,
+    })
+}
+
+fn finding_resource(finding: &TrafficVulnerabilityRecord) -> Resource {
+    let mut resource = RawResource::new(
+        format!("{}{}", FINDINGS_URI_PREFIX, finding.id),
+        format!("[{}] {}", finding.severity, finding.title),
+    );
+    resource.description = Some(format!(
+        "{} finding ({}), status {}",
+        finding.vuln_type, finding.confidence, finding.status
+    ));
+    resource.mime_type = Some(JSON_MIME_TYPE.to_string());
+    resource.no_annotation()
+}
+
+fn proxy_request_resource(request: &ProxyRequestRecord) -> Option<Resource> {
+    let id = request.id?;
+    let mut resource = RawResource::new(
+        format!("{}{}", PROXY_HISTORY_URI_PREFIX, id),
+        format!("{} {}", request.method, request.url),
+    );
+    resource.description = Some(format!(
+        "HTTP {} at {}",
+        request.status_code,
+        request.timestamp.to_rfc3339()
+    ));
+    resource.mime_type = Some(JSON_MIME_TYPE.to_string());
+    Some(resource.no_annotation())
+}
+
+fn template_prompt(template: &PromptTemplateRecord) -> Prompt {
+    let arguments = template
+        .variable_names()
+        .into_iter()
+        .map(|name| PromptArgument {
+            name,
+            title: None,
+            description: None,
+            required: Some(true),
+        })
+        .collect::<Vec<_>>();
+    Prompt::new(
+        template.name.clone(),
+        template.description.clone(),
+        (!arguments.is_empty()).then_some(arguments),
+    )
+}
+
+/// Replace `{{name}}` placeholders in a single left-to-right pass.
+/// Substituted values are never re-scanned, so a value containing `{{other}}`
+/// is emitted literally; unknown placeholders are left as-is
+fn render_template(content: &str, arguments: &BTreeMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            rendered.push_str(&rest[start..]);
+            return rendered;
+        };
+        let name = &after_open[..end];
+        match arguments.get(name) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after_open[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+fn internal_error(e: impl std::fmt::Display) -> McpError {
+    McpError::internal_error(e.to_string(), None)
+}
+
+fn resource_not_found(uri: &str) -> McpError {
+    McpError::resource_not_found(format!("Resource not found: {}", uri), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::ServiceExt;
+
+    struct StaticSource {
+        findings: Vec<TrafficVulnerabilityRecord>,
+        requests: Vec<ProxyRequestRecord>,
+        templates: Vec<PromptTemplateRecord>,
+    }
+
+    #[async_trait]
+    impl McpContentSource for StaticSource {
+        async fn list_findings(&self, _limit: i64) -> Result<Vec<TrafficVulnerabilityRecord>> {
+            Ok(self.findings.clone())
+        }
+
+        async fn get_finding(&self, id: &str) -> Result<Option<TrafficVulnerabilityRecord>> {
+            Ok(self.findings.iter().find(|f| f.id == id).cloned())
+        }
+
+        async fn list_proxy_requests(&self, _limit: i64) -> Result<Vec<ProxyRequestRecord>> {
+            Ok(self.requests.clone())
+        }
+
+        async fn get_proxy_request(&self, id: i64) -> Result<Option<ProxyRequestRecord>> {
+            Ok(self.requests.iter().find(|r| r.id == Some(id)).cloned())
+        }
+
+        async fn list_prompt_templates(&self) -> Result<Vec<PromptTemplateRecord>> {
+            Ok(self.templates.clone())
+        }
+    }
+
+    fn source() -> StaticSource {
+        let now = chrono::Utc::now();
+        StaticSource {
+            findings: vec![TrafficVulnerabilityRecord {
+                id: "vuln-1".to_string(),
+                plugin_id: "sqli".to_string(),
+                vuln_type: "sqli".to_string(),
+                severity: "high".to_string(),
+                confidence: "firm".to_string(),
+                title: "SQL injection in id".to_string(),
+                description: "Error-based SQL injection".to_string(),
+                cwe: Some("CWE-89".to_string()),
+                owasp: None,
+                remediation: None,
+                status: "open".to_string(),
+                signature: "sig-1".to_string(),
+                first_seen_at: now,
+                last_seen_at: now,
+                hit_count: 1,
+                session_id: None,
+                created_at: now,
+                updated_at: now,
+            }],
+            requests: vec![ProxyRequestRecord {
+                id: Some(42),
+                url: "https://example.com/api/items?id=1".to_string(),
+                host: "example.com".to_string(),
+                protocol: "https".to_string(),
+                method: "GET".to_string(),
+                status_code: 200,
+                request_headers: None,
+                request_body: None,
+                response_headers: None,
+                response_body: Some("{\"items\":[]}".to_string()),
+                response_size: 12,
+                response_time: 35,
+                timestamp: now,
+                request_body_compressed: false,
+                response_body_compressed: false,
+            }],
+            templates: vec![PromptTemplateRecord {
+                id: 1,
+                name: "triage".to_string(),
+                description: Some("Triage a finding".to_string()),
+                content: "Triage {{finding}} on {{target}}.".to_string(),
+                category: None,
+                variables: Some("[\"finding\",\"target\"]".to_string()),
+            }],
+        }
+    }
+
+    async fn connect() -> rmcp::service::RunningService<rmcp::RoleClient, ()> {
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        let server = SentinelMcpServer::new(Arc::new(source()));
+        tokio::spawn(async move {
+            if let Ok(running) = server.serve(server_io).await {
+                let _ = running.waiting().await;
+            }
+        });
+        ().serve(client_io).await.expect("client handshake")
+    }
+
+    fn text_of(result: &ReadResourceResult) -> &str {
+        match &result.contents[0] {
+            ResourceContents::TextResourceContents { text, .. } => text,
+            other => panic!("unexpected resource contents: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resources_list_and_read() {
+        let client = connect().await;
+
+        let capabilities = client.peer_info().unwrap().capabilities.clone();
+        assert!(capabilities.resources.is_some());
+        assert!(capabilities.prompts.is_some());
+
+        let uris: Vec<String> = client
+            .list_all_resources()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.raw.uri)
+            .collect();
+        assert_eq!(
+            uris,
+            vec!["sentinel://findings/vuln-1", "sentinel://proxy-history/42"]
+        );
+
+        let finding = client
+            .read_resource(ReadResourceRequestParam {
+                uri: "sentinel://findings/vuln-1".to_string(),
+            })
+            .await
+            .unwrap();
+        let finding: serde_json::Value = serde_json::from_str(text_of(&finding)).unwrap();
+        assert_eq!(finding["title"], "SQL injection in id");
+        assert_eq!(finding["cwe"], "CWE-89");
+
+        let request = client
+            .read_resource(ReadResourceRequestParam {
+                uri: "sentinel://proxy-history/42".to_string(),
+            })
+            .await
+            .unwrap();
+        let request: serde_json::Value = serde_json::from_str(text_of(&request)).unwrap();
+        assert_eq!(request["url"], "https://example.com/api/items?id=1");
+
+        assert!(client
+            .read_resource(ReadResourceRequestParam {
+                uri: "sentinel://findings/missing".to_string(),
+            })
+            .await
+            .is_err());
+
+        let _ = client.cancel().await;
+    }
+
+    #[tokio::test]
+    async fn test_prompts_list_and_render() {
+        let client = connect().await;
+
+        let prompts = client.list_all_prompts().await.unwrap();
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0].name, "triage");
+        assert_eq!(prompts[0].arguments.as_ref().map(|a| a.len()), Some(2));
+
+        let mut arguments = serde_json::Map::new();
+        arguments.insert("finding".to_string(), "vuln-1".into());
+        arguments.insert("target".to_string(), "example.com".into());
+        let prompt = client
+            .get_prompt(GetPromptRequestParam {
+                name: "triage".to_string(),
+                arguments: Some(arguments),
+            })
+            .await
+            .unwrap();
+        let rendered = serde_json::to_value(&prompt.messages[0]).unwrap();
+        assert_eq!(rendered["content"]["text"], "Triage vuln-1 on example.com.");
+
+        assert!(client
+            .get_prompt(GetPromptRequestParam {
+                name: "triage".to_string(),
+                arguments: None,
+            })
+            .await
+            .is_err());
+
+        let _ = client.cancel().await;
+    }
+
+    #[test]
+    fn test_render_template_does_not_rescan_values() {
+        let mut arguments = BTreeMap::new();
+        arguments.insert("a".to_string(), "{{b}}".to_string());
+        arguments.insert("b".to_string(), "B".to_string());
+
+        assert_eq!(
+            render_template("{{a}} {{b}} {{c}} {{", &arguments),
+            "{{b}} B {{c}} {{"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_http_endpoint_starts_and_stops() {
+        let runtime = start_mcp_server(Arc::new(source()), 0, "test-token")
+            .await
+            .unwrap();
+        assert!(runtime.endpoint_url().ends_with(MCP_ENDPOINT_PATH));
+        assert!(!runtime.is_finished());
+
+        let addr = runtime.bind_addr();
+        assert!(tokio::net::TcpStream::connect(&addr).await.is_ok());
+
+        runtime.stop().await;
+        assert!(tokio::net::TcpStream::connect(&addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_http_endpoint_requires_token_and_loopback_host() {
+        let token = generate_access_token();
+        let runtime = start_mcp_server(Arc::new(source()), 0, &token)
+            .await
+            .unwrap();
+        let url = runtime.endpoint_url();
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let initialize = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": {"name": "test", "version": "1.0"}
+            }
+        });
+        let post = || {
+            client
+                .post(&url)
+                .header("Accept", "application/json, text/event-stream")
+                .json(&initialize)
+        };
+
+        let status = |res: reqwest::Response| res.status().as_u16();
+        assert_eq!(status(post().send().await.unwrap()), 401);
+        assert_eq!(
+            status(post().bearer_auth("wrong").send().await.unwrap()),
+            401
+        );
+        // DNS rebinding: the request reaches 127.0.0.1 under a foreign name
+        assert_eq!(
+            status(
+                post()
+                    .bearer_auth(&token)
+                    .header("Host", "attacker.example:8931")
+                    .send()
+                    .await
+                    .unwrap()
+            ),
+            403
+        );
+        assert_eq!(
+            status(
+                post()
+                    .bearer_auth(&token)
+                    .header("Origin", "https://attacker.example")
+                    .send()
+                    .await
+                    .unwrap()
+            ),
+            403
+        );
+
+        let res = post()
+            .bearer_auth(&token)
+            .header("Origin", "http://localhost:5173")
+            .send()
+            .await
+            .unwrap();
+        assert!(res.status().is_success(), "status {}", res.status());
+
+        runtime.stop().await;
+    }
+
+    #[test]
+    fn test_loopback_host_and_origin() {
+        assert!(is_loopback_authority("127.0.0.1:8931"));
+        assert!(is_loopback_authority("localhost"));
+        assert!(is_loopback_authority("[::1]:8931"));
+        assert!(!is_loopback_authority("127.0.0.1.attacker.example"));
+        assert!(is_loopback_origin("http://127.0.0.1:3000"));
+        assert!(!is_loopback_origin("null"));
+        assert!(!is_loopback_origin("http://localhost.attacker.example"));
+    }
+}
@@ -8,6 +8,7 @@ pub mod database {
 }
 pub mod http_gateway;
 pub mod mcp;
+pub mod mcp_server;
 pub mod vulnerability;
 
 // Re-export from sentinel-services
@@ -0,0 +1,430 @@
+//! Skill hook runner
+//!
+//! Hooks come from the skill's `hooks` field (SKILL.md frontmatter or DB record):
+//!
+//! ```yaml
+//! hooks:
+//!   pre:
+//!     - type: script
+//!       path: scripts/setup.sh
+//!   post:
+//!     - type: shell
+//!       command: rm -f work.tmp
+//!     - type: tool
+//!       name: http_request
+//!       args: { url: "http://127.0.0.1:8080/reset" }
+//! ```
+//!
+//! Shell and script hooks run in the skill directory. They need `shell`
+//! listed explicitly in the skill's `allowed_tools` (an empty list denies
+//! them) and go through the same permission/approval gate as the shell tool.
+//! Tool hooks need their tool listed when `allowed_tools` is non-empty.
+//! Script paths go through `resolve_skill_file_path`, so they cannot escape
+//! the skill directory.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::process::Command;
+
+use sentinel_db::{Database, DatabaseService};
+
+use super::{resolve_skill_file_path, skills_root};
+
+const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 60;
+const SHELL_TOOL: &str = "shell";
+/// Tools that run arbitrary commands and must always be listed explicitly
+const SHELL_TOOLS: [&str; 2] = [SHELL_TOOL, "interactive_shell"];
+
+/// One hook step
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SkillHook {
+    /// Shell command run in the skill directory
+    Shell {
+        command: String,
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+    /// Script file inside the skill directory
+    Script {
+        path: String,
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+    /// Registered tool call
+    Tool {
+        name: String,
+        #[serde(default)]
+        args: Value,
+    },
+}
+
+impl SkillHook {
+    fn required_tool(&self) -> &str {
+        match self {
+            Self::Shell { .. } | Self::Script { .. } => SHELL_TOOL,
+            Self::Tool { name, .. } => name,
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Self::Shell { command, .. } => format!("shell `{}`", command),
+            Self::Script { path, .. } => format!("script `{}`", path),
+            Self::Tool { name, .. } => format!("tool `{}`", name),
+        }
+    }
+}
+
+/// Pre/post hooks of a skill
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub struct SkillHooks {
+    #[serde(default)]
+    pub pre: Vec<SkillHook>,
+    #[serde(default)]
+    pub post: Vec<SkillHook>,
+}
+
+impl SkillHooks {
+    /// Parse the stored hooks value; `null` and `{}` mean no hooks
+    pub fn from_value(value: Option<&Value>) -> Result<Self> {
+        match value {
+            None | Some(Value::Null) => Ok(Self::default()),
+            Some(value) => serde_json::from_value(value.clone()).context("Invalid skill hooks"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pre.is_empty() && self.post.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookPhase {
+    Pre,
+    Post,
+}
+
+impl std::fmt::Display for HookPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pre => write!(f, "pre"),
+            Self::Post => write!(f, "post"),
+        }
+    }
+}
+
+/// Runs a skill's hooks under its tool restrictions
+pub struct SkillHookRunner {
+    root: PathBuf,
+    skill_id: String,
+    allowed_tools: Vec<String>,
+    /// Execution the hooks run for, used to route shell approval requests
+    execution_id: Option<String>,
+}
+
+impl SkillHookRunner {
+    pub fn new(root: &Path, skill_id: &str, allowed_tools: &[String]) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            skill_id: skill_id.to_string(),
+            allowed_tools: allowed_tools
+                .iter()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect(),
+            execution_id: None,
+        }
+    }
+
+    pub fn with_execution_id(mut self, execution_id: Option<String>) -> Self {
+        self.execution_id = execution_id;
+        self
+    }
+
+    /// Run every hook of `phase` in order, stopping at the first failure
+    pub async fn run(&self, hooks: &SkillHooks, phase: HookPhase) -> Result<()> {
+        let steps = match phase {
+            HookPhase::Pre => &hooks.pre,
+            HookPhase::Post => &hooks.post,
+        };
+        for (index, hook) in steps.iter().enumerate() {
+            self.run_hook(hook).await.with_context(|| {
+                format!(
+                    "Skill '{}' {}-hook #{} ({}) failed",
+                    self.skill_id,
+                    phase,
+                    index + 1,
+                    hook.label()
+                )
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn run_hook(&self, hook: &SkillHook) -> Result<()> {
+        let required = hook.required_tool();
+        let listed = self.allowed_tools.iter().any(|t| t == required);
+        if SHELL_TOOLS.contains(&required) {
+            // Shell access is never implied by an unrestricted skill
+            if !listed {
+                anyhow::bail!(
+                    "'{}' must be listed in the skill's allowed_tools to run shell hooks",
+                    required
+                );
+            }
+        } else if !self.allowed_tools.is_empty() && !listed {
+            anyhow::bail!("'{}' is not in the skill's allowed_tools", required);
+        }
+
+        match hook {
+            SkillHook::Shell {
+                command,
+                timeout_secs,
+            } => {
+                self.check_shell_permission(command).await?;
+                self.run_command(shell_command(command), *timeout_secs)
+                    .await
+            }
+            SkillHook::Script { path, timeout_secs } => {
+                let script = resolve_skill_file_path(&self.root, &self.skill_id, path)?;
+                self.check_shell_permission(&format!("sh {}", path)).await?;
+                self.run_command(script_command(&script), *timeout_secs)
+                    .await
+            }
+            SkillHook::Tool { name, args } => {
+                let result = sentinel_tools::get_tool_server()
+                    .execute(name, args.clone())
+                    .await;
+                if result.success {
+                    Ok(())
+                } else {
+                    anyhow::bail!(result
+                        .error
+                        .unwrap_or_else(|| "tool call failed".to_string()))
+                }
+            }
+        }
+    }
+
+    /// Same deny list, allow list and user approval as the shell tool
+    async fn check_shell_permission(&self, command: &str) -> Result<()> {
+        sentinel_tools::buildin_tools::shell::check_shell_permission(
+            command,
+            self.execution_id.as_deref(),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!(e))
+    }
+
+    async fn run_command(&self, mut command: Command, timeout_secs: Option<u64>) -> Result<()> {
+        let skill_dir = self.root.join(&self.skill_id);
+        command.current_dir(&skill_dir).kill_on_drop(true);
+
+        let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS));
+        let output = tokio::time::timeout(timeout, command.output())
+            .await
+            .map_err(|_| anyhow::anyhow!("timed out after {}s", timeout.as_secs()))?
+            .context("failed to start")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("exited with {}: {}", output.status, stderr.trim());
+        }
+        Ok(())
+    }
+}
+
+/// Hooks of an installed skill with a runner bound to its restrictions, if it has any
+pub async fn load_skill_hooks(
+    db: &DatabaseService,
+    skill_id: &str,
+) -> Result<Option<(SkillHookRunner, SkillHooks)>> {
+    let Some(skill) = db.get_skill(skill_id).await? else {
+        return Ok(None);
+    };
+    let hooks = SkillHooks::from_value(skill.hooks.as_ref())
+        .with_context(|| format!("Skill '{}' has invalid hooks", skill_id))?;
+    if hooks.is_empty() {
+        return Ok(None);
+    }
+    let runner = SkillHookRunner::new(&skills_root(db), skill_id, &skill.allowed_tools);
+    Ok(Some((runner, hooks)))
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
+fn script_command(script: &Path) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(script);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg(script);
+        cmd
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use sentinel_tools::buildin_tools::shell::{
+        set_permission_handler, set_shell_config, ShellConfig, ShellPermissionHandler,
+    };
+    use serde_json::json;
+    use std::fs;
+    use std::sync::Arc;
+
+    /// Approves every command except those mentioning `rejected`
+    struct TestApprover;
+
+    #[async_trait::async_trait]
+    impl ShellPermissionHandler for TestApprover {
+        async fn check_permission(&self, command: &str, _execution_id: Option<&str>) -> bool {
+            !command.contains("rejected")
+        }
+    }
+
+    async fn install_approver() {
+        set_shell_config(ShellConfig::default()).await;
+        set_permission_handler(Arc::new(TestApprover)).await;
+    }
+
+    fn temp_skill(skill_id: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("skill-hooks-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join(skill_id).join("scripts")).unwrap();
+        root
+    }
+
+    #[tokio::test]
+    async fn test_pre_hook_sets_up_and_post_hook_cleans_up() {
+        install_approver().await;
+        let root = temp_skill("recon");
+        let skill_dir = root.join("recon");
+        fs::write(
+            skill_dir.join("scripts/setup.sh"),
+            "mkdir -p work && echo ready > work/state\n",
+        )
+        .unwrap();
+
+        let hooks = SkillHooks::from_value(Some(&json!({
+            "pre": [{ "type": "script", "path": "scripts/setup.sh" }],
+            "post": [{ "type": "shell", "command": "find work -delete" }]
+        })))
+        .unwrap();
+        let runner = SkillHookRunner::new(&root, "recon", &["shell".to_string()]);
+
+        runner.run(&hooks, HookPhase::Pre).await.unwrap();
+        assert_eq!(
+            fs::read_to_string(skill_dir.join("work/state"))
+                .unwrap()
+                .trim(),
+            "ready"
+        );
+
+        runner.run(&hooks, HookPhase::Post).await.unwrap();
+        assert!(!skill_dir.join("work").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_failing_hook_aborts_with_clear_error() {
+        install_approver().await;
+        let root = temp_skill("recon");
+        let hooks = SkillHooks::from_value(Some(&json!({
+            "pre": [
+                { "type": "shell", "command": "echo missing dependency >&2; exit 3" },
+                { "type": "shell", "command": "touch should-not-run" }
+            ]
+        })))
+        .unwrap();
+        let runner = SkillHookRunner::new(&root, "recon", &["shell".to_string()]);
+
+        let err = runner.run(&hooks, HookPhase::Pre).await.unwrap_err();
+        let message = format!("{:#}", err);
+        assert!(message.contains("Skill 'recon' pre-hook #1"));
+        assert!(message.contains("missing dependency"));
+        assert!(!root.join("recon/should-not-run").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_hooks_respect_allowed_tools_and_skill_dir() {
+        install_approver().await;
+        let root = temp_skill("recon");
+        fs::write(root.join("outside.sh"), "touch escaped\n").unwrap();
+
+        let restricted = SkillHookRunner::new(&root, "recon", &["http_request".to_string()]);
+        let shell_hooks = SkillHooks::from_value(Some(&json!({
+            "pre": [{ "type": "shell", "command": "true" }]
+        })))
+        .unwrap();
+        let err = restricted
+            .run(&shell_hooks, HookPhase::Pre)
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("'shell' must be listed"));
+
+        // An unrestricted skill still may not run shell hooks
+        let unrestricted = SkillHookRunner::new(&root, "recon", &[]);
+        let err = unrestricted
+            .run(&shell_hooks, HookPhase::Pre)
+            .await
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("'shell' must be listed"));
+
+        let runner = SkillHookRunner::new(&root, "recon", &["shell".to_string()]);
+        let escape = SkillHooks::from_value(Some(&json!({
+            "pre": [{ "type": "script", "path": "../outside.sh" }]
+        })))
+        .unwrap();
+        let err = runner.run(&escape, HookPhase::Pre).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("Path escapes skill directory"));
+        assert!(!root.join("recon/escaped").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_shell_hooks_go_through_permission_gate() {
+        install_approver().await;
+        let root = temp_skill("recon");
+        let runner = SkillHookRunner::new(&root, "recon", &["shell".to_string()]);
+
+        // Denied by the shell deny list
+        let denied = SkillHooks::from_value(Some(&json!({
+            "pre": [{ "type": "shell", "command": "rm -rf ." }]
+        })))
+        .unwrap();
+        let err = runner.run(&denied, HookPhase::Pre).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("Command denied by policy"));
+        assert!(root.join("recon").exists());
+
+        // Rejected by the user
+        let rejected = SkillHooks::from_value(Some(&json!({
+            "pre": [{ "type": "shell", "command": "touch rejected" }]
+        })))
+        .unwrap();
+        let err = runner.run(&rejected, HookPhase::Pre).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("User rejected execution"));
+        assert!(!root.join("recon/rejected").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -6,6 +6,9 @@ use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 use sentinel_db::{Database, DatabaseService};
 
+pub mod hooks;
+pub mod watcher;
+
 // 0 = unknown, 1 = available, 2 = unavailable
 static SKILLS_REF_STATUS: AtomicU8 = AtomicU8::new(0);
 static SKILLS_REF_UNAVAILABLE_LOGGED: AtomicBool = AtomicBool::new(false);
@@ -16,6 +19,9 @@ pub struct SkillFrontmatter {
     pub description: String,
     #[serde(default)]
     pub when_to_use: Option<String>,
+    /// Pre/post invocation hooks, see [`hooks`]
+    #[serde(default)]
+    pub hooks: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone)]
@@ -138,85 +144,107 @@ pub async fn scan_and_upsert_skills(db_service: &DatabaseService) -> Result<usiz
         if !entry.file_type()?.is_dir() {
             continue;
         }
-        let dir_path = entry.path();
-        let dir_name = entry.file_name().to_string_lossy().to_string();
-        let skill_md = dir_path.join("SKILL.md");
-        if !skill_md.exists() {
-            continue;
+        if upsert_skill_dir(db_service, &root, &entry.path()).await? {
+            count += 1;
         }
+    }
 
-        let content = match fs::read_to_string(&skill_md) {
-            Ok(c) => c,
-            Err(e) => {
-                tracing::warn!("Failed to read SKILL.md {}: {}", skill_md.display(), e);
-                continue;
-            }
-        };
+    Ok(count)
+}
 
-        let doc = match parse_skill_markdown(&content) {
-            Ok(doc) => doc,
-            Err(e) => {
-                tracing::warn!("Invalid SKILL.md {}: {}", skill_md.display(), e);
-                continue;
-            }
-        };
+/// Parse one skill directory and upsert it into the DB index
+///
+/// Returns `false` when the directory has no readable, valid SKILL.md; such
+/// problems are logged rather than returned so one bad skill does not stop a scan.
+pub async fn upsert_skill_dir(
+    db_service: &DatabaseService,
+    root: &Path,
+    dir_path: &Path,
+) -> Result<bool> {
+    let dir_name = dir_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let skill_md = dir_path.join("SKILL.md");
+    if !skill_md.exists() {
+        return Ok(false);
+    }
 
-        if let Err(e) = validate_skill_with_skills_ref(&dir_path) {
-            tracing::warn!("skills-ref validation warning (scan): {}", e);
+    let content = match fs::read_to_string(&skill_md) {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::warn!("Failed to read SKILL.md {}: {}", skill_md.display(), e);
+            return Ok(false);
         }
+    };
 
-        if doc.frontmatter.name != dir_name {
-            tracing::warn!(
-                "Skill name mismatch: dir='{}' frontmatter='{}' (continuing)",
-                dir_name,
-                doc.frontmatter.name
-            );
+    let doc = match parse_skill_markdown(&content) {
+        Ok(doc) => doc,
+        Err(e) => {
+            tracing::warn!("Invalid SKILL.md {}: {}", skill_md.display(), e);
+            return Ok(false);
         }
+    };
 
-        let source_path = skill_md
-            .strip_prefix(&root)
-            .unwrap_or(&skill_md)
-            .to_string_lossy()
-            .to_string();
-
-        let existing = db_service.get_skill(&dir_name).await?;
-        if let Some(existing) = existing {
-            let update = sentinel_db::UpdateSkill {
-                name: Some(dir_name.clone()),
-                description: Some(doc.frontmatter.description.clone()),
-                source_path: Some(source_path),
-                argument_hint: Some(existing.argument_hint),
-                disable_model_invocation: Some(existing.disable_model_invocation),
-                user_invocable: Some(existing.user_invocable),
-                allowed_tools: Some(existing.allowed_tools),
-                model: Some(existing.model),
-                context: Some(existing.context),
-                agent: Some(existing.agent),
-                hooks: existing.hooks,
-            };
-            db_service.update_skill(&dir_name, &update).await?;
-        } else {
-            let create = sentinel_db::CreateSkill {
-                id: dir_name.clone(),
-                name: dir_name.clone(),
-                description: doc.frontmatter.description.clone(),
-                source_path,
-                argument_hint: String::new(),
-                disable_model_invocation: false,
-                user_invocable: true,
-                allowed_tools: vec![],
-                model: String::new(),
-                context: String::new(),
-                agent: String::new(),
-                hooks: Some(serde_json::Value::Object(Default::default())),
-            };
-            db_service.create_skill(&create).await?;
-        }
+    if let Err(e) = validate_skill_with_skills_ref(dir_path) {
+        tracing::warn!("skills-ref validation warning (scan): {}", e);
+    }
 
-        count += 1;
+    if doc.frontmatter.name != dir_name {
+        tracing::warn!(
+            "Skill name mismatch: dir='{}' frontmatter='{}' (continuing)",
+            dir_name,
+            doc.frontmatter.name
+        );
     }
 
-    Ok(count)
+    let source_path = skill_md
+        .strip_prefix(root)
+        .unwrap_or(&skill_md)
+        .to_string_lossy()
+        .to_string();
+
+    let existing = db_service.get_skill(&dir_name).await?;
+    if let Some(existing) = existing {
+        let update = sentinel_db::UpdateSkill {
+            name: Some(dir_name.clone()),
+            description: Some(doc.frontmatter.description.clone()),
+            source_path: Some(source_path),
+            argument_hint: Some(existing.argument_hint),
+            disable_model_invocation: Some(existing.disable_model_invocation),
+            user_invocable: Some(existing.user_invocable),
+            allowed_tools: Some(existing.allowed_tools),
+            model: Some(existing.model),
+            context: Some(existing.context),
+            agent: Some(existing.agent),
+            hooks: doc.frontmatter.hooks.clone().or(existing.hooks),
+        };
+        db_service.update_skill(&dir_name, &update).await?;
+    } else {
+        let create = sentinel_db::CreateSkill {
+            id: dir_name.clone(),
+            name: dir_name.clone(),
+            description: doc.frontmatter.description.clone(),
+            source_path,
+            argument_hint: String::new(),
+            disable_model_invocation: false,
+            user_invocable: true,
+            allowed_tools: vec![],
+            model: String::new(),
+            context: String::new(),
+            agent: String::new(),
+            hooks: Some(
+                doc.frontmatter
+                    .hooks
+                    .clone()
+                    .unwrap_or_else(|| serde_json::Value::Object(Default::default())),
+            ),
+        };
+        db_service.create_skill(&create).await?;
+    }
+
+    Ok(true)
 }
 
 // ── Built-in Skills ─────────────────────────────────────────────────────────
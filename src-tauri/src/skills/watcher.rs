@@ -0,0 +1,320 @@
+//! Skills directory watcher
+//!
+//! Watches `skills_root` and, after changes settle for a debounce window,
+//! re-parses just the affected skill directories: a directory with a SKILL.md is
+//! upserted. A directory whose SKILL.md is missing is only removed from the
+//! index if it is still missing on the rescan one debounce window later, so
+//! editors that save by delete-and-rename don't drop the skill.
+//! Malformed SKILL.md files are logged by the upsert path and never abort the
+//! watcher.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+
+use sentinel_db::{Database, DatabaseService};
+
+use super::upsert_skill_dir;
+
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Where reindex results go
+#[async_trait]
+pub trait SkillIndexSink: Send + Sync + 'static {
+    /// Re-parse and upsert one skill directory; `false` if it was skipped
+    async fn upsert_skill(&self, root: &Path, skill_dir: &Path) -> Result<bool>;
+    /// Drop a skill whose directory or SKILL.md is gone
+    async fn remove_skill(&self, skill_id: &str) -> Result<bool>;
+}
+
+#[async_trait]
+impl SkillIndexSink for DatabaseService {
+    async fn upsert_skill(&self, root: &Path, skill_dir: &Path) -> Result<bool> {
+        upsert_skill_dir(self, root, skill_dir).await
+    }
+
+    async fn remove_skill(&self, skill_id: &str) -> Result<bool> {
+        self.delete_skill(skill_id).await
+    }
+}
+
+/// Running watcher; dropping it stops watching
+pub struct SkillsWatcher {
+    root: PathBuf,
+    _watcher: RecommendedWatcher,
+    task: JoinHandle<()>,
+}
+
+impl SkillsWatcher {
+    pub fn start(root: &Path, sink: Arc<dyn SkillIndexSink>, debounce: Duration) -> Result<Self> {
+        std::fs::create_dir_all(root)
+            .with_context(|| format!("Failed to create skills dir: {}", root.display()))?;
+        // Event paths are absolute and resolved, so match against the canonical root
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+                Ok(event) => {
+                    let _ = tx.send(event);
+                }
+                Err(e) => tracing::warn!("Skills watcher error: {}", e),
+            })
+            .context("Failed to create skills watcher")?;
+        watcher
+            .watch(&root, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch skills dir: {}", root.display()))?;
+
+        let task = tokio::spawn(run_reindex_loop(root.clone(), sink, rx, debounce));
+        tracing::info!("Skills watcher started: {}", root.display());
+
+        Ok(Self {
+            root,
+            _watcher: watcher,
+            task,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl Drop for SkillsWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run_reindex_loop(
+    root: PathBuf,
+    sink: Arc<dyn SkillIndexSink>,
+    mut rx: mpsc::UnboundedReceiver<Event>,
+    debounce: Duration,
+) {
+    // Skills whose SKILL.md was missing on the last pass, awaiting the confirming rescan
+    let mut missing = BTreeSet::new();
+    loop {
+        let mut pending = BTreeSet::new();
+        // With removals awaiting confirmation, a quiet window alone triggers the rescan
+        if missing.is_empty() {
+            match rx.recv().await {
+                Some(event) => collect_skill_ids(&root, &event, &mut pending),
+                None => return,
+            }
+        }
+
+        // Keep collecting until no event arrives for a full debounce window
+        let mut closed = false;
+        loop {
+            match tokio::time::timeout(debounce, rx.recv()).await {
+                Ok(Some(event)) => collect_skill_ids(&root, &event, &mut pending),
+                Ok(None) => {
+                    closed = true;
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+
+        reindex(&root, sink.as_ref(), pending, &mut missing).await;
+        if closed {
+            return;
+        }
+    }
+}
+
+/// Map event paths to the top-level skill directory they belong to
+fn collect_skill_ids(root: &Path, event: &Event, pending: &mut BTreeSet<String>) {
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+    for path in &event.paths {
+        let Ok(relative) = path.strip_prefix(root) else {
+            continue;
+        };
+        if let Some(Component::Normal(name)) = relative.components().next() {
+            let name = name.to_string_lossy();
+            if !name.starts_with('.') {
+                pending.insert(name.to_string());
+            }
+        }
+    }
+}
+
+/// Upsert skills that have a SKILL.md; a missing one is deferred into `missing` and
+/// only removed if it is still missing when the next pass rechecks it
+async fn reindex(
+    root: &Path,
+    sink: &dyn SkillIndexSink,
+    mut skill_ids: BTreeSet<String>,
+    missing: &mut BTreeSet<String>,
+) {
+    let confirming = std::mem::take(missing);
+    skill_ids.extend(confirming.iter().cloned());
+
+    for skill_id in skill_ids {
+        let skill_dir = root.join(&skill_id);
+        let result = if skill_dir.join("SKILL.md").is_file() {
+            sink.upsert_skill(root, &skill_dir).await
+        } else if confirming.contains(&skill_id) {
+            sink.remove_skill(&skill_id).await
+        } else {
+            tracing::debug!(
+                "Skills watcher deferring removal of '{}' until rescan",
+                skill_id
+            );
+            missing.insert(skill_id);
+            continue;
+        };
+        match result {
+            Ok(changed) => {
+                tracing::debug!(
+                    "Skills watcher reindexed '{}' (changed: {})",
+                    skill_id,
+                    changed
+                )
+            }
+            Err(e) => tracing::warn!("Skills watcher failed to reindex '{}': {}", skill_id, e),
+        }
+    }
+}
+
+static SKILLS_WATCHER: Lazy<Mutex<Option<SkillsWatcher>>> = Lazy::new(|| Mutex::new(None));
+
+/// Start the global skills watcher; returns `false` if it is already running
+pub async fn start_skills_watcher(root: &Path, sink: Arc<dyn SkillIndexSink>) -> Result<bool> {
+    let mut guard = SKILLS_WATCHER.lock().await;
+    if guard.is_some() {
+        return Ok(false);
+    }
+    *guard = Some(SkillsWatcher::start(root, sink, DEFAULT_DEBOUNCE)?);
+    Ok(true)
+}
+
+/// Stop the global skills watcher; returns `false` if it was not running
+pub async fn stop_skills_watcher() -> bool {
+    match SKILLS_WATCHER.lock().await.take() {
+        Some(watcher) => {
+            tracing::info!("Skills watcher stopped: {}", watcher.root().display());
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[derive(Default)]
+    struct RecordingSink {
+        upserts: std::sync::Mutex<Vec<String>>,
+        removals: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl SkillIndexSink for RecordingSink {
+        async fn upsert_skill(&self, _root: &Path, skill_dir: &Path) -> Result<bool> {
+            let id = skill_dir.file_name().unwrap().to_string_lossy().to_string();
+            self.upserts.lock().unwrap().push(id);
+            Ok(true)
+        }
+
+        async fn remove_skill(&self, skill_id: &str) -> Result<bool> {
+            self.removals.lock().unwrap().push(skill_id.to_string());
+            Ok(true)
+        }
+    }
+
+    async fn wait_for(check: impl Fn() -> bool) -> bool {
+        for _ in 0..50 {
+            if check() {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn test_new_skill_dir_triggers_upsert_and_removal() {
+        let root = std::env::temp_dir().join(format!("skills-watch-{}", uuid::Uuid::new_v4()));
+        let sink = Arc::new(RecordingSink::default());
+        let watcher =
+            SkillsWatcher::start(&root, sink.clone(), Duration::from_millis(100)).unwrap();
+
+        let skill_dir = root.join("port-scan");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: port-scan\ndescription: Scan ports\n---\nBody\n",
+        )
+        .unwrap();
+
+        assert!(
+            wait_for(|| sink
+                .upserts
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|id| id == "port-scan"))
+            .await,
+            "creating a skill dir should upsert it"
+        );
+        assert!(sink.removals.lock().unwrap().is_empty());
+
+        fs::remove_dir_all(&skill_dir).unwrap();
+        assert!(
+            wait_for(|| sink
+                .removals
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|id| id == "port-scan"))
+            .await,
+            "deleting a skill dir should remove it"
+        );
+
+        drop(watcher);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_removal_requires_skill_md_missing_across_rescan() {
+        let root = std::env::temp_dir().join(format!("skills-rescan-{}", uuid::Uuid::new_v4()));
+        let skill_dir = root.join("recon");
+        fs::create_dir_all(&skill_dir).unwrap();
+        let sink = RecordingSink::default();
+        let mut missing = BTreeSet::new();
+        let ids = || BTreeSet::from(["recon".to_string()]);
+
+        // SKILL.md briefly gone (atomic save): deferred, then re-upserted on rescan
+        reindex(&root, &sink, ids(), &mut missing).await;
+        assert!(sink.removals.lock().unwrap().is_empty());
+        assert!(missing.contains("recon"));
+        fs::write(skill_dir.join("SKILL.md"), "---\nname: recon\n---\n").unwrap();
+        reindex(&root, &sink, BTreeSet::new(), &mut missing).await;
+        assert!(sink.removals.lock().unwrap().is_empty());
+        assert_eq!(*sink.upserts.lock().unwrap(), vec!["recon".to_string()]);
+        assert!(missing.is_empty());
+
+        // Still missing on the rescan: removed
+        fs::remove_file(skill_dir.join("SKILL.md")).unwrap();
+        reindex(&root, &sink, ids(), &mut missing).await;
+        assert!(sink.removals.lock().unwrap().is_empty());
+        reindex(&root, &sink, BTreeSet::new(), &mut missing).await;
+        assert_eq!(*sink.removals.lock().unwrap(), vec!["recon".to_string()]);
+        assert!(missing.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
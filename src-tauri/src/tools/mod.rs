@@ -9,3 +9,35 @@ pub use sentinel_tools::buildin_tools::{
 
 // Re-export ToolSet
 pub use rig::tool::ToolSet;
+
+use crate::services::{DatabaseService, DictionaryService};
+use sentinel_tools::buildin_tools::content_discovery::ContentDictionarySource;
+use std::sync::Arc;
+
+/// Resolves stored dictionaries for the content discovery tool
+pub struct DictionaryWordSource {
+    db_service: Arc<DatabaseService>,
+}
+
+impl DictionaryWordSource {
+    pub fn new(db_service: Arc<DatabaseService>) -> Self {
+        Self { db_service }
+    }
+}
+
+#[async_trait::async_trait]
+impl ContentDictionarySource for DictionaryWordSource {
+    async fn words(&self, dictionary_id: &str) -> anyhow::Result<Vec<String>> {
+        let pool = self.db_service.get_runtime_pool()?;
+        let service = DictionaryService::new(pool);
+        if service.get_dictionary(dictionary_id).await?.is_none() {
+            anyhow::bail!("dictionary not found");
+        }
+        Ok(service
+            .get_dictionary_words(dictionary_id)
+            .await?
+            .into_iter()
+            .map(|w| w.word)
+            .collect())
+    }
+}
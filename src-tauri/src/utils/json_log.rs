@@ -0,0 +1,256 @@
+//! 结构化 JSON 日志
+//!
+//! 每个事件输出一行 JSON（level、target、fields、所在 span 链），便于 Loki/ELK 等
+//! 采集。字段按与工具调用记录相同的规则脱敏。通过环境变量 `SENTINEL_LOG_FORMAT=json`
+//! 或数据目录下 `logging.json`（`{"format": "json"}`）开启，默认仍为文本格式。
+
+use std::fmt;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields, FormattedFields};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::agents::executor::tool_call_log::redact_value;
+
+/// 选择日志格式的环境变量（优先于配置文件）
+pub const LOG_FORMAT_ENV: &str = "SENTINEL_LOG_FORMAT";
+/// 数据目录下的日志配置文件
+pub const LOG_CONFIG_FILE: &str = "logging.json";
+
+/// 日志输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// 人类可读文本（默认）
+    #[default]
+    Text,
+    /// 每行一个 JSON 对象
+    Json,
+}
+
+impl LogFormat {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "json" => Some(LogFormat::Json),
+            "text" | "plain" => Some(LogFormat::Text),
+            _ => None,
+        }
+    }
+
+    /// 依次读取环境变量与 `config_dir/logging.json`，都未设置时为文本格式
+    pub fn resolve(config_dir: &Path) -> Self {
+        if let Some(format) = std::env::var(LOG_FORMAT_ENV)
+            .ok()
+            .and_then(|v| Self::parse(&v))
+        {
+            return format;
+        }
+        std::fs::read_to_string(config_dir.join(LOG_CONFIG_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+            .and_then(|config| config.get("format")?.as_str().and_then(Self::parse))
+            .unwrap_or_default()
+    }
+}
+
+/// 将字段收集为 JSON 对象
+#[derive(Default)]
+struct JsonVisitor(Map<String, Value>);
+
+impl Visit for JsonVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(
+            field.name().to_string(),
+            Value::from(format!("{:?}", value)),
+        );
+    }
+}
+
+fn redacted(map: Map<String, Value>) -> Map<String, Value> {
+    let mut value = Value::Object(map);
+    redact_value(&mut value);
+    match value {
+        Value::Object(map) => map,
+        _ => Map::new(),
+    }
+}
+
+/// span 字段格式化器：以 JSON 对象保存，供 [`JsonEventFormat`] 读取
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonFields;
+
+impl<'writer> FormatFields<'writer> for JsonFields {
+    fn format_fields<R: tracing_subscriber::field::RecordFields>(
+        &self,
+        mut writer: Writer<'writer>,
+        fields: R,
+    ) -> fmt::Result {
+        let mut visitor = JsonVisitor::default();
+        fields.record(&mut visitor);
+        write!(writer, "{}", Value::Object(redacted(visitor.0)))
+    }
+
+    fn add_fields(
+        &self,
+        current: &'writer mut FormattedFields<Self>,
+        fields: &tracing::span::Record<'_>,
+    ) -> fmt::Result {
+        let mut map: Map<String, Value> = serde_json::from_str(&current.fields).unwrap_or_default();
+        let mut visitor = JsonVisitor::default();
+        fields.record(&mut visitor);
+        map.extend(redacted(visitor.0));
+        current.fields = Value::Object(map).to_string();
+        Ok(())
+    }
+}
+
+/// 事件格式化器：每个事件一行 JSON
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonEventFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonEventFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+
+        let spans: Vec<Value> = ctx
+            .event_scope()
+            .into_iter()
+            .flat_map(|scope| scope.from_root())
+            .map(|span| {
+                let mut fields: Map<String, Value> = span
+                    .extensions()
+                    .get::<FormattedFields<N>>()
+                    .and_then(|f| serde_json::from_str(&f.fields).ok())
+                    .unwrap_or_default();
+                fields.insert("name".to_string(), Value::from(span.name()));
+                Value::Object(fields)
+            })
+            .collect();
+
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".to_string(),
+            Value::from(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+        );
+        line.insert(
+            "level".to_string(),
+            Value::from(metadata.level().to_string()),
+        );
+        line.insert("target".to_string(), Value::from(metadata.target()));
+        if let Some(number) = metadata.line() {
+            line.insert("line".to_string(), Value::from(number));
+        }
+        line.insert("fields".to_string(), Value::Object(redacted(visitor.0)));
+        if let Some(current) = spans.last() {
+            line.insert("span".to_string(), current.clone());
+            line.insert("spans".to_string(), Value::Array(spans));
+        }
+        writeln!(writer, "{}", Value::Object(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_lines_are_parseable_and_redacted() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .fmt_fields(JsonFields)
+            .event_format(JsonEventFormat)
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("scan", task_id = "task-1", api_key = "sk-live-123");
+            let _guard = span.enter();
+            tracing::warn!(host = "example.com", password = "hunter2", "Probe failed");
+            tracing::info!(retries = 3u64, "Retrying");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line is JSON"))
+            .collect();
+        assert_eq!(lines.len(), 2);
+
+        let first = &lines[0];
+        assert_eq!(first["level"], "WARN");
+        assert!(first["target"].as_str().unwrap().contains("json_log"));
+        assert!(first["timestamp"].is_string());
+        assert_eq!(first["fields"]["message"], "Probe failed");
+        assert_eq!(first["fields"]["host"], "example.com");
+        assert_eq!(first["fields"]["password"], "[REDACTED]");
+        assert_eq!(first["span"]["name"], "scan");
+        assert_eq!(first["span"]["task_id"], "task-1");
+        assert_eq!(first["span"]["api_key"], "[REDACTED]");
+        assert_eq!(lines[1]["fields"]["retries"], 3);
+        assert!(!output.contains("hunter2") && !output.contains("sk-live-123"));
+    }
+
+    #[test]
+    fn test_log_format_config_file() {
+        let dir = std::env::temp_dir().join(format!("sentinel-log-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        if std::env::var(LOG_FORMAT_ENV).is_err() {
+            assert_eq!(LogFormat::resolve(&dir), LogFormat::Text);
+            std::fs::write(dir.join(LOG_CONFIG_FILE), r#"{"format": "json"}"#).unwrap();
+            assert_eq!(LogFormat::resolve(&dir), LogFormat::Json);
+        }
+        assert_eq!(LogFormat::parse("JSON"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse("yaml"), None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
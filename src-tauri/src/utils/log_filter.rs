@@ -0,0 +1,217 @@
+//! 运行时可调整的日志级别
+//!
+//! 启动时的 `EnvFilter` 包在 `reload::Layer` 中，按模块覆盖的级别（例如把
+//! `sentinel_passive` 调到 debug）可在不重启的情况下生效或撤销。需要时覆盖项会写入
+//! 数据目录下的 `log_levels.json`，下次启动自动加载。
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{LazyLock, Mutex, OnceLock};
+
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::filter::Directive;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// 持久化覆盖项的文件名
+pub const LOG_LEVELS_FILE: &str = "log_levels.json";
+
+/// 未设置 `RUST_LOG` 时各模块的默认级别
+const BASE_DIRECTIVES: &[&str] = &[
+    "sentinel_ai=info",
+    "sentinel_plugins=info",
+    "sentinel_workflow=info",
+    "sentinel_traffic=info",
+    "sentinel_rag=info",
+    "sentinel_llm=info",
+    "hudsucker=off",
+    "rig::agent::prompt_request::streaming=warn",
+];
+
+pub type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+static FILTER_HANDLE: OnceLock<FilterHandle> = OnceLock::new();
+/// 当前生效的覆盖项：target -> level
+static OVERRIDES: LazyLock<Mutex<BTreeMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+static CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// 校验 target 与 level，返回规范化后的指令
+pub fn parse_directive(target: &str, level: &str) -> Result<Directive, String> {
+    let target = target.trim();
+    if target.is_empty()
+        || !target
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | ':' | '-'))
+    {
+        return Err(format!("Invalid log target: {}", target));
+    }
+    let level = LevelFilter::from_str(level.trim())
+        .map_err(|_| format!("Invalid log level: {}", level.trim()))?;
+    format!("{}={}", target, level)
+        .parse::<Directive>()
+        .map_err(|e| format!("Invalid log directive: {}", e))
+}
+
+/// 由 `RUST_LOG`、默认级别与覆盖项构建过滤器（覆盖项优先）
+pub fn build_filter(overrides: &BTreeMap<String, String>) -> EnvFilter {
+    let mut filter = EnvFilter::from_default_env();
+    for directive in BASE_DIRECTIVES {
+        if let Ok(directive) = directive.parse() {
+            filter = filter.add_directive(directive);
+        }
+    }
+    for (target, level) in overrides {
+        match parse_directive(target, level) {
+            Ok(directive) => filter = filter.add_directive(directive),
+            Err(e) => tracing::warn!("Ignoring log level override: {}", e),
+        }
+    }
+    filter
+}
+
+fn load_persisted(config_dir: &Path) -> BTreeMap<String, String> {
+    std::fs::read_to_string(config_dir.join(LOG_LEVELS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn persist(config_dir: &Path, overrides: &BTreeMap<String, String>) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(overrides).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(config_dir).map_err(|e| e.to_string())?;
+    std::fs::write(config_dir.join(LOG_LEVELS_FILE), content)
+        .map_err(|e| format!("Failed to persist log levels: {}", e))
+}
+
+/// 创建可重载的过滤层，加载 `config_dir` 中持久化的覆盖项（启动时调用一次）
+pub fn init_reloadable_filter(config_dir: PathBuf) -> reload::Layer<EnvFilter, Registry> {
+    let persisted = load_persisted(&config_dir);
+    let filter = build_filter(&persisted);
+    *OVERRIDES.lock().unwrap_or_else(|e| e.into_inner()) = persisted;
+    let _ = CONFIG_DIR.set(config_dir);
+
+    let (layer, handle) = reload::Layer::new(filter);
+    let _ = FILTER_HANDLE.set(handle);
+    layer
+}
+
+fn apply(handle: &FilterHandle, overrides: &BTreeMap<String, String>) -> Result<(), String> {
+    handle
+        .reload(build_filter(overrides))
+        .map_err(|e| format!("Failed to reload log filter: {}", e))
+}
+
+fn update_overrides(
+    update: impl FnOnce(&mut BTreeMap<String, String>),
+    persist_change: bool,
+) -> Result<(), String> {
+    let handle = FILTER_HANDLE
+        .get()
+        .ok_or_else(|| "Log filter is not reloadable".to_string())?;
+    let mut overrides = OVERRIDES.lock().unwrap_or_else(|e| e.into_inner());
+    let mut updated = overrides.clone();
+    update(&mut updated);
+    apply(handle, &updated)?;
+    if persist_change {
+        if let Some(dir) = CONFIG_DIR.get() {
+            persist(dir, &updated)?;
+        }
+    }
+    *overrides = updated;
+    Ok(())
+}
+
+/// 设置某个模块的日志级别，立即生效；`persist` 为真时下次启动仍然生效
+pub fn set_log_level(target: &str, level: &str, persist: bool) -> Result<(), String> {
+    let directive = parse_directive(target, level)?;
+    tracing::info!("Log level override: {}", directive);
+    let level = level.trim().to_ascii_lowercase();
+    update_overrides(
+        |overrides| {
+            overrides.insert(target.trim().to_string(), level);
+        },
+        persist,
+    )
+}
+
+/// 撤销某个模块的级别覆盖，恢复默认级别（同时从持久化配置中移除）
+pub fn reset_log_level(target: &str) -> Result<(), String> {
+    update_overrides(
+        |overrides| {
+            overrides.remove(target.trim());
+        },
+        true,
+    )
+}
+
+/// 当前生效的覆盖项
+pub fn log_level_overrides() -> BTreeMap<String, String> {
+    OVERRIDES.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::Arc;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_reloaded_filter_affects_later_events() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let (layer, handle) = reload::Layer::new(build_filter(&BTreeMap::new()));
+        let subscriber = tracing_subscriber::registry().with(layer).with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(move || writer.clone())
+                .with_ansi(false),
+        );
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(target: "sentinel_passive::scanner", "before override");
+
+            let overrides = BTreeMap::from([("sentinel_passive".to_string(), "debug".to_string())]);
+            apply(&handle, &overrides).unwrap();
+            tracing::debug!(target: "sentinel_passive::scanner", "during override");
+            tracing::trace!(target: "sentinel_passive::scanner", "too verbose");
+
+            apply(&handle, &BTreeMap::new()).unwrap();
+            tracing::debug!(target: "sentinel_passive::scanner", "after revert");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(!output.contains("before override"));
+        assert!(output.contains("during override"));
+        assert!(!output.contains("too verbose"));
+        assert!(!output.contains("after revert"));
+    }
+
+    #[test]
+    fn test_directive_validation_and_persistence() {
+        assert!(parse_directive("sentinel_passive", "debug").is_ok());
+        assert!(parse_directive("rig::agent", "WARN").is_ok());
+        assert!(parse_directive("sentinel_passive", "loud").is_err());
+        assert!(parse_directive("", "debug").is_err());
+        assert!(parse_directive("a=b,c", "debug").is_err());
+
+        let dir = std::env::temp_dir().join(format!("sentinel-loglevel-{}", uuid::Uuid::new_v4()));
+        let overrides = BTreeMap::from([("sentinel_passive".to_string(), "debug".to_string())]);
+        persist(&dir, &overrides).unwrap();
+        assert_eq!(load_persisted(&dir), overrides);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -2,10 +2,14 @@ pub mod ai_generation_settings;
 pub mod aliyun_oss;
 pub mod builtin_tool_tracking;
 pub mod image_ocr;
+pub mod json_log;
+pub mod log_filter;
+pub mod log_redact;
 pub mod mcp_tracking;
 pub mod message_emitter;
 pub mod ordered_message;
 pub mod plugin_tracking;
+pub mod safe_mode;
 pub mod streaming_optimizer;
 
 // macOS 系统代理模块已移至 sentinel_traffic::system_proxy
@@ -14,14 +18,23 @@ pub mod streaming_optimizer;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tauri::AppHandle;
 
+/// 会话计数器空闲超过该时长后被定期清理
+pub const SESSION_COUNTER_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+/// 会话计数器清理间隔
+const SESSION_COUNTER_SWEEP_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 static APP_HANDLE_STORE: once_cell::sync::Lazy<Mutex<Option<AppHandle>>> =
     once_cell::sync::Lazy::new(|| Mutex::new(None));
 static SEQUENCE_MAP: once_cell::sync::Lazy<Mutex<HashMap<String, Arc<AtomicU64>>>> =
     once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
 static PLAN_VERSION_MAP: once_cell::sync::Lazy<Mutex<HashMap<String, Arc<AtomicU32>>>> =
     once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+/// 每个 session 计数器最近一次使用的时间
+static SESSION_LAST_USED: once_cell::sync::Lazy<Mutex<HashMap<String, Instant>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
 
 /// 在应用启动时注册全局 AppHandle（需在 setup 中调用）
 pub fn register_app_handle(handle: &AppHandle) {
@@ -34,23 +47,53 @@ pub fn app_handle_safe() -> Option<AppHandle> {
     APP_HANDLE_STORE.lock().unwrap().clone()
 }
 
-/// 获取某个 session 的下一个序列号（从1开始）
+fn touch_session(session_id: &str) {
+    SESSION_LAST_USED
+        .lock()
+        .unwrap()
+        .insert(session_id.to_string(), Instant::now());
+}
+
+/// 获取某个 session 的下一个序列号（从1开始，到达 u64::MAX 后回绕到1）
 pub fn next_sequence(session_id: &str) -> u64 {
-    let mut map = SEQUENCE_MAP.lock().unwrap();
-    let counter = map
-        .entry(session_id.to_string())
-        .or_insert_with(|| Arc::new(AtomicU64::new(0)))
-        .clone();
-    counter.fetch_add(1, Ordering::SeqCst) + 1
+    let counter = {
+        let mut map = SEQUENCE_MAP.lock().unwrap();
+        map.entry(session_id.to_string())
+            .or_insert_with(|| Arc::new(AtomicU64::new(0)))
+            .clone()
+    };
+    touch_session(session_id);
+    let previous = counter
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |v| {
+            Some(if v == u64::MAX { 1 } else { v + 1 })
+        })
+        .unwrap_or_default();
+    if previous == u64::MAX {
+        1
+    } else {
+        previous + 1
+    }
+}
+
+/// 查看某个 session 当前的序列号（不递增，不存在则返回0）
+pub fn peek_sequence(session_id: &str) -> u64 {
+    SEQUENCE_MAP
+        .lock()
+        .unwrap()
+        .get(session_id)
+        .map(|c| c.load(Ordering::SeqCst))
+        .unwrap_or(0)
 }
 
 /// 初始化计划版本（若不存在则设为1并返回1；若已存在则返回当前版本不改变）
 pub fn init_plan_version(session_id: &str) -> u32 {
-    let mut map = PLAN_VERSION_MAP.lock().unwrap();
-    let counter = map
-        .entry(session_id.to_string())
-        .or_insert_with(|| Arc::new(AtomicU32::new(1)))
-        .clone();
+    let counter = {
+        let mut map = PLAN_VERSION_MAP.lock().unwrap();
+        map.entry(session_id.to_string())
+            .or_insert_with(|| Arc::new(AtomicU32::new(1)))
+            .clone()
+    };
+    touch_session(session_id);
     counter.load(Ordering::SeqCst)
 }
 
@@ -64,10 +107,109 @@ pub fn current_plan_version(session_id: &str) -> u32 {
 
 /// 递增并返回新的计划版本（若不存在则从1开始返回1）
 pub fn next_plan_version(session_id: &str) -> u32 {
-    let mut map = PLAN_VERSION_MAP.lock().unwrap();
-    let counter = map
-        .entry(session_id.to_string())
-        .or_insert_with(|| Arc::new(AtomicU32::new(0)))
-        .clone();
-    counter.fetch_add(1, Ordering::SeqCst) + 1
+    let counter = {
+        let mut map = PLAN_VERSION_MAP.lock().unwrap();
+        map.entry(session_id.to_string())
+            .or_insert_with(|| Arc::new(AtomicU32::new(0)))
+            .clone()
+    };
+    touch_session(session_id);
+    counter.fetch_add(1, Ordering::SeqCst).wrapping_add(1)
+}
+
+/// 清理某个 session 的序列号、计划版本以及有序消息计数器（session 结束时调用）
+pub fn clear_session_counters(session_id: &str) {
+    SEQUENCE_MAP.lock().unwrap().remove(session_id);
+    PLAN_VERSION_MAP.lock().unwrap().remove(session_id);
+    SESSION_LAST_USED.lock().unwrap().remove(session_id);
+    ordered_message::cleanup_sequence_counter(session_id);
+}
+
+/// 清理空闲超过 `ttl` 的 session 计数器和有序消息计数器，返回清理数量
+pub fn sweep_idle_session_counters(ttl: Duration) -> usize {
+    let now = Instant::now();
+    let idle: Vec<String> = SESSION_LAST_USED
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, last_used)| now.duration_since(**last_used) >= ttl)
+        .map(|(session_id, _)| session_id.clone())
+        .collect();
+    for session_id in &idle {
+        clear_session_counters(session_id);
+    }
+    idle.len() + ordered_message::sweep_idle_sequence_counters(ttl)
+}
+
+/// 启动后台任务，定期清理空闲 session 计数器
+pub fn spawn_session_counter_sweeper() {
+    tauri::async_runtime::spawn(async {
+        let mut interval = tokio::time::interval(SESSION_COUNTER_SWEEP_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let removed = sweep_idle_session_counters(SESSION_COUNTER_TTL);
+            if removed > 0 {
+                tracing::debug!("Swept {} idle session counters", removed);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clear_session_counters_removes_entries() {
+        let session = "test-session-clear";
+        assert_eq!(next_sequence(session), 1);
+        assert_eq!(next_plan_version(session), 1);
+
+        clear_session_counters(session);
+        assert!(!SEQUENCE_MAP.lock().unwrap().contains_key(session));
+        assert!(!PLAN_VERSION_MAP.lock().unwrap().contains_key(session));
+        assert!(!SESSION_LAST_USED.lock().unwrap().contains_key(session));
+        assert_eq!(peek_sequence(session), 0);
+        assert_eq!(current_plan_version(session), 0);
+
+        // 清理后重新从1开始
+        assert_eq!(next_sequence(session), 1);
+        clear_session_counters(session);
+    }
+
+    #[test]
+    fn test_peek_does_not_advance() {
+        let session = "test-session-peek";
+        assert_eq!(peek_sequence(session), 0);
+        next_sequence(session);
+        next_sequence(session);
+        assert_eq!(peek_sequence(session), 2);
+        assert_eq!(peek_sequence(session), 2);
+        assert_eq!(next_sequence(session), 3);
+        clear_session_counters(session);
+    }
+
+    #[test]
+    fn test_sweep_drops_idle_sessions_and_wraps() {
+        let session = "test-session-sweep";
+        SEQUENCE_MAP
+            .lock()
+            .unwrap()
+            .insert(session.to_string(), Arc::new(AtomicU64::new(u64::MAX)));
+        assert_eq!(next_sequence(session), 1);
+
+        let ttl = Duration::from_secs(60);
+        sweep_idle_session_counters(ttl);
+        assert_eq!(peek_sequence(session), 1);
+
+        // 模拟 session 已空闲超过 TTL
+        let idle_since = Instant::now().checked_sub(ttl * 2).unwrap();
+        SESSION_LAST_USED
+            .lock()
+            .unwrap()
+            .insert(session.to_string(), idle_since);
+        assert!(sweep_idle_session_counters(ttl) >= 1);
+        assert_eq!(peek_sequence(session), 0);
+    }
 }
@@ -5,7 +5,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 use tauri::{AppHandle, Emitter};
 
 /// 架构类型标识
@@ -69,16 +69,30 @@ pub struct OrderedMessageChunk {
     pub structured_data: Option<serde_json::Value>,
 }
 
-/// 每个执行的序号分配器
-static SEQUENCE_COUNTERS: std::sync::LazyLock<Mutex<HashMap<String, u64>>> =
+/// 序号计数器，记录所属执行和最近使用时间，便于执行结束或空闲时清理
+struct SequenceCounter {
+    execution_id: String,
+    value: u64,
+    last_used: Instant,
+}
+
+/// 每条消息的序号分配器
+static SEQUENCE_COUNTERS: std::sync::LazyLock<Mutex<HashMap<String, SequenceCounter>>> =
     std::sync::LazyLock::new(|| Mutex::new(HashMap::new()));
 
 /// 获取下一个序号
-fn next_sequence_for(execution_id: &str) -> u64 {
+fn next_sequence_for(execution_id: &str, key: &str) -> u64 {
     if let Ok(mut counters) = SEQUENCE_COUNTERS.lock() {
-        let entry = counters.entry(execution_id.to_string()).or_insert(0);
-        *entry += 1;
-        *entry
+        let counter = counters
+            .entry(key.to_string())
+            .or_insert_with(|| SequenceCounter {
+                execution_id: execution_id.to_string(),
+                value: 0,
+                last_used: Instant::now(),
+            });
+        counter.value += 1;
+        counter.last_used = Instant::now();
+        counter.value
     } else {
         log::error!(
             "Failed to acquire sequence counter lock for execution_id: {}",
@@ -88,10 +102,10 @@ fn next_sequence_for(execution_id: &str) -> u64 {
     }
 }
 
-/// 清理执行ID的序号计数器（执行完成后调用）
+/// 清理执行ID下所有消息的序号计数器（执行完成后调用）
 pub fn cleanup_sequence_counter(execution_id: &str) {
     if let Ok(mut counters) = SEQUENCE_COUNTERS.lock() {
-        counters.remove(execution_id);
+        counters.retain(|_, counter| counter.execution_id != execution_id);
         log::debug!(
             "Cleaned up sequence counter for execution_id: {}",
             execution_id
@@ -99,6 +113,17 @@ pub fn cleanup_sequence_counter(execution_id: &str) {
     }
 }
 
+/// 清理空闲超过 `ttl` 的序号计数器，返回清理数量
+pub fn sweep_idle_sequence_counters(ttl: Duration) -> usize {
+    let Ok(mut counters) = SEQUENCE_COUNTERS.lock() else {
+        return 0;
+    };
+    let now = Instant::now();
+    let before = counters.len();
+    counters.retain(|_, counter| now.duration_since(counter.last_used) < ttl);
+    before - counters.len()
+}
+
 /// 统一的消息块发送函数
 pub fn emit_message_chunk(
     app_handle: &AppHandle,
@@ -143,7 +168,7 @@ pub fn emit_message_chunk_with_arch(
     // 使用 message_id 作为序号计数的键，确保同一条前端消息的所有来源（LLM流、工具结果、Meta）
     // 共享一个严格递增的序列，从根本上消除跨 execution_id 的交错问题
     let sequence_key = format!("msg:{}", message_id);
-    let sequence = next_sequence_for(&sequence_key);
+    let sequence = next_sequence_for(execution_id, &sequence_key);
 
     let chunk = OrderedMessageChunk {
         execution_id: execution_id.to_string(),
@@ -405,10 +430,11 @@ mod tests {
     #[test]
     fn test_sequence_generation() {
         let exec_id = "test_exec_1";
+        let key = "msg:test_msg_1";
 
-        let seq1 = next_sequence_for(exec_id);
-        let seq2 = next_sequence_for(exec_id);
-        let seq3 = next_sequence_for(exec_id);
+        let seq1 = next_sequence_for(exec_id, key);
+        let seq2 = next_sequence_for(exec_id, key);
+        let seq3 = next_sequence_for(exec_id, key);
 
         assert_eq!(seq1, 1);
         assert_eq!(seq2, 2);
@@ -416,8 +442,51 @@ mod tests {
 
         cleanup_sequence_counter(exec_id);
 
-        let seq4 = next_sequence_for(exec_id);
+        let seq4 = next_sequence_for(exec_id, key);
         assert_eq!(seq4, 1);
+        cleanup_sequence_counter(exec_id);
+    }
+
+    #[test]
+    fn test_message_counters_cleared_when_execution_finishes() {
+        let exec_id = "test_exec_finish";
+        let other_exec_id = "test_exec_finish_other";
+        next_sequence_for(exec_id, "msg:finish_1");
+        next_sequence_for(exec_id, "msg:finish_2");
+        next_sequence_for(other_exec_id, "msg:finish_3");
+
+        // 执行结束时 executor 通过 clear_session_counters 清理
+        crate::utils::clear_session_counters(exec_id);
+
+        let counters = SEQUENCE_COUNTERS.lock().unwrap();
+        assert!(!counters.contains_key("msg:finish_1"));
+        assert!(!counters.contains_key("msg:finish_2"));
+        assert!(counters.contains_key("msg:finish_3"));
+        drop(counters);
+        cleanup_sequence_counter(other_exec_id);
+    }
+
+    #[test]
+    fn test_sweep_drops_idle_message_counters() {
+        let exec_id = "test_exec_sweep";
+        let ttl = Duration::from_secs(60);
+        next_sequence_for(exec_id, "msg:sweep_idle");
+        next_sequence_for(exec_id, "msg:sweep_active");
+
+        // 模拟执行未正常结束，消息已空闲超过 TTL
+        SEQUENCE_COUNTERS
+            .lock()
+            .unwrap()
+            .get_mut("msg:sweep_idle")
+            .unwrap()
+            .last_used = Instant::now().checked_sub(ttl * 2).unwrap();
+
+        assert!(crate::utils::sweep_idle_session_counters(ttl) >= 1);
+        let counters = SEQUENCE_COUNTERS.lock().unwrap();
+        assert!(!counters.contains_key("msg:sweep_idle"));
+        assert!(counters.contains_key("msg:sweep_active"));
+        drop(counters);
+        cleanup_sequence_counter(exec_id);
     }
 
     #[test]
@@ -0,0 +1,115 @@
+//! 安全模式启动
+//!
+//! 某个 MCP 服务器或插件在启动时卡死/崩溃会导致应用无法打开。以 `--safe-mode`
+//! 参数或环境变量 `SENTINEL_SAFE_MODE=1` 启动时，跳过 MCP 自动连接、插件工具加载与
+//! 被动代理自动启动，用户可以进入界面后再排查、禁用有问题的配置。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 开启安全模式的环境变量
+pub const SAFE_MODE_ENV: &str = "SENTINEL_SAFE_MODE";
+/// 开启安全模式的命令行参数
+pub const SAFE_MODE_ARG: &str = "--safe-mode";
+
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// 测试间串行修改全局安全模式开关
+#[cfg(test)]
+pub(crate) static TEST_GUARD: once_cell::sync::Lazy<tokio::sync::Mutex<()>> =
+    once_cell::sync::Lazy::new(|| tokio::sync::Mutex::new(()));
+
+/// 启动时的自动任务
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupTask {
+    /// 自动连接 `auto_connect=true` 的 MCP 服务器
+    McpAutoConnect,
+    /// 将已启用的 agent 插件注册为工具
+    PluginTools,
+    /// 按配置自动启动被动代理
+    ProxyAutoStart,
+}
+
+impl StartupTask {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StartupTask::McpAutoConnect => "MCP auto-connect",
+            StartupTask::PluginTools => "plugin tool loading",
+            StartupTask::ProxyAutoStart => "proxy auto-start",
+        }
+    }
+}
+
+fn is_enabled_value(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+/// 由命令行参数与环境变量值判断是否开启安全模式
+pub fn resolve<I, S>(args: I, env_value: Option<&str>) -> bool
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    env_value.is_some_and(is_enabled_value) || args.into_iter().any(|a| a.as_ref() == SAFE_MODE_ARG)
+}
+
+/// 读取进程参数与环境变量设置安全模式（启动时调用一次），返回是否开启
+pub fn init_from_env() -> bool {
+    let enabled = resolve(
+        std::env::args().skip(1),
+        std::env::var(SAFE_MODE_ENV).ok().as_deref(),
+    );
+    set_safe_mode(enabled);
+    enabled
+}
+
+pub fn set_safe_mode(enabled: bool) {
+    SAFE_MODE.store(enabled, Ordering::SeqCst);
+}
+
+/// 当前是否处于安全模式
+pub fn is_safe_mode() -> bool {
+    SAFE_MODE.load(Ordering::SeqCst)
+}
+
+/// 启动任务是否应当执行；安全模式下跳过并记录日志
+pub fn should_run(task: StartupTask) -> bool {
+    if is_safe_mode() {
+        tracing::info!("Safe mode: skipping {}", task.name());
+        return false;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_mode_from_args_and_env() {
+        assert!(resolve(["--safe-mode"], None));
+        assert!(resolve(["--verbose", "--safe-mode"], None));
+        assert!(resolve(Vec::<String>::new(), Some("1")));
+        assert!(resolve(Vec::<String>::new(), Some("TRUE")));
+        assert!(!resolve(Vec::<String>::new(), Some("0")));
+        assert!(!resolve(["--safe-mode=false"], None));
+        assert!(!resolve(Vec::<String>::new(), None));
+    }
+
+    #[test]
+    fn test_safe_mode_skips_startup_tasks() {
+        let _guard = TEST_GUARD.blocking_lock();
+        set_safe_mode(true);
+        for task in [
+            StartupTask::McpAutoConnect,
+            StartupTask::PluginTools,
+            StartupTask::ProxyAutoStart,
+        ] {
+            assert!(!should_run(task), "{} should be skipped", task.name());
+        }
+        set_safe_mode(false);
+        assert!(should_run(StartupTask::McpAutoConnect));
+    }
+}
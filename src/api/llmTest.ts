@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api/core'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 
 export interface LlmTestTarget {
   app_id: string
@@ -170,6 +171,25 @@ export interface LlmSuiteDefinition {
   }>
 }
 
+/** `scan:progress` 事件 payload，stage_name 与会话的 current_stage 一致 */
+export interface ScanProgressEvent {
+  session_id: string
+  stage_name: string
+  done: number
+  total: number
+  throughput: number
+  eta_seconds?: number | null
+  estimated_completion?: string | null
+  overall_progress: number
+  timestamp: string
+}
+
+export async function onScanProgress(
+  handler: (event: ScanProgressEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<ScanProgressEvent>('scan:progress', (event) => handler(event.payload))
+}
+
 export async function llmTestCreateRun(
   request: CreateLlmTestRunRequest
 ): Promise<LlmTestResponse<LlmTestRunCreated>> {
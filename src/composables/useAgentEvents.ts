@@ -22,6 +22,7 @@ interface AgentChunkEvent {
   content?: string
   input_tokens?: number
   output_tokens?: number
+  estimated?: boolean  // 提供商未返回 usage 时由后端分词器估算
 }
 
 // 后端发送的 agent:tool_call 事件（旧格式兼容）
@@ -48,6 +48,14 @@ export default {
   defaultMaxResults: 'Default Max Results',
   save: 'Save Settings',
 
+  // System prompt prelude/postlude
+  systemPromptWrap: 'System Prompt Prelude / Postlude',
+  systemPromptWrapDescription: 'Wrapped around the system prompt of every agent run, in the order prelude, prompt, postlude',
+  systemPromptPrelude: 'Prelude',
+  systemPromptPreludePlaceholder: 'e.g. Only test hosts that are explicitly in scope.',
+  systemPromptPostlude: 'Postlude',
+  systemPromptPostludePlaceholder: 'e.g. Always end with a summary table of findings.',
+
   // Aliyun DashScope
   aliyunDashScope: 'Aliyun DashScope',
   dashscopeApiKey: 'DashScope API Key',
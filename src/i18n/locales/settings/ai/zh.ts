@@ -48,6 +48,14 @@ export default {
   defaultMaxResults: '默认最大结果数',
   save: '保存设置',
 
+  // 系统提示词前缀/后缀
+  systemPromptWrap: '系统提示词前缀 / 后缀',
+  systemPromptWrapDescription: '包裹在每次 Agent 运行的系统提示词外，顺序为：前缀、提示词、后缀',
+  systemPromptPrelude: '前缀',
+  systemPromptPreludePlaceholder: '例如：只测试明确在授权范围内的主机。',
+  systemPromptPostlude: '后缀',
+  systemPromptPostludePlaceholder: '例如：最后始终输出一个漏洞汇总表格。',
+
   // 阿里云 DashScope
   aliyunDashScope: '阿里云 DashScope',
   dashscopeApiKey: 'DashScope API Key',
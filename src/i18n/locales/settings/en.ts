@@ -454,6 +454,8 @@ export default {
       maxFileMb: 'Max file size (MB)',
       maxTotalMb: 'Max total storage (MB)',
       maxPerConversation: 'Max files per conversation',
+      maxImageMb: 'Max image size (MB)',
+      maxImageDimension: 'Max image dimension (px, 0 disables downscaling)',
       autoCleanup: 'Enable auto cleanup',
       retentionDays: 'Retention days',
       clearAll: 'Clear All Files',
@@ -447,6 +447,8 @@ export default {
       maxFileMb: '单文件大小上限 (MB)',
       maxTotalMb: '总存储上限 (MB)',
       maxPerConversation: '单会话文件上限',
+      maxImageMb: '单张图片大小上限 (MB)',
+      maxImageDimension: '图片最大边长 (px，0 表示不缩放)',
       autoCleanup: '启用自动清理',
       retentionDays: '保留天数',
       clearAll: '清空全部文件',
@@ -143,11 +143,15 @@ export default {
     refreshStatus: 'Refresh Status',
     proxyConfig: 'Configure browser proxy to',
     proxySettings: 'Proxy configuration and interception rules can be set in the Proxy Settings page',
+    pauseScan: 'Pause Scanning',
+    resumeScan: 'Resume Scanning',
     stats: {
       proxyStatus: 'Proxy Status',
       running: 'Running',
       stopped: 'Stopped',
       notStarted: 'Not started',
+      paused: 'Paused',
+      pausedDesc: 'Proxy listening, plugin analysis paused',
       mitmStatus: 'MITM Status',
       enabled: 'Enabled',
       disabled: 'Disabled',
@@ -760,6 +764,11 @@ export default {
     matchRelationship: 'Match relationship:',
     matchCondition: 'Match condition:',
     conditionPlaceholder: 'Enter regex pattern or value',
+    methodFilter: 'HTTP method (optional):',
+    methodFilterPlaceholder: 'e.g. POST,PUT',
+    requestContentTypeFilter: 'Request Content-Type (optional):',
+    responseContentTypeFilter: 'Response Content-Type (optional):',
+    contentTypeFilterPlaceholder: 'e.g. application/json, text/*',
     ok: 'OK',
     // Match types
     matchTypes: {
@@ -137,11 +137,15 @@ export default {
   // Proxy Control Component
   control: {
     title: '代理控制',
+    pauseScan: '暂停扫描',
+    resumeScan: '恢复扫描',
     stats: {
       proxyStatus: '代理状态',
       running: '运行中',
       stopped: '已停止',
       notStarted: '未启动',
+      paused: '已暂停',
+      pausedDesc: '代理监听中，插件分析已暂停',
       mitmStatus: 'MITM 状态',
       enabled: '已启用',
       disabled: '未启用',
@@ -704,6 +708,11 @@ export default {
     matchRelationship: '匹配关系：',
     matchCondition: '匹配条件：',
     conditionPlaceholder: '输入正则表达式或值',
+    methodFilter: 'HTTP 方法（可选）：',
+    methodFilterPlaceholder: '如 POST,PUT',
+    requestContentTypeFilter: '请求 Content-Type（可选）：',
+    responseContentTypeFilter: '响应 Content-Type（可选）：',
+    contentTypeFilterPlaceholder: '如 application/json, text/*',
     ok: '确定',
     // Match types
     matchTypes: {
@@ -26,6 +26,14 @@ export interface HttpRequestRecord {
     mime_type?: string;
 }
 
+// HTTP 请求详情（附带解码后的响应体）
+export interface HttpRequestDetail extends HttpRequestRecord {
+    /** 按 Content-Encoding 解压后的响应体（用于展示） */
+    body?: string;
+    /** 原始响应体（保持编码，用于重放） */
+    raw_body?: string;
+}
+
 // WebSocket 连接状态
 export type WebSocketConnectionStatus = 'open' | 'closed' | 'error';
 
@@ -110,8 +118,8 @@ export async function listHttpRequests(options?: {
 /**
  * 获取单个 HTTP 请求详情
  */
-export async function getHttpRequest(id: number): Promise<HttpRequestRecord | null> {
-    const response = await invoke<ApiResponse<HttpRequestRecord | null>>('get_proxy_request', { id });
+export async function getHttpRequest(id: number): Promise<HttpRequestDetail | null> {
+    const response = await invoke<ApiResponse<HttpRequestDetail | null>>('get_proxy_request', { id });
 
     if (response.success) {
         return response.data ?? null;
@@ -21,6 +21,21 @@ export interface RagConfig {
   chunk_expansion_enabled: boolean
   chunk_expansion_before: number
   chunk_expansion_after: number
+  retrieval_mode?: 'vector' | 'keyword' | 'hybrid'
+  hybrid_alpha?: number
+  embedding_fallbacks?: EmbeddingFallbackConfig[]
+  embedding_timeout_secs?: number
+  embedding_failure_threshold?: number
+  embedding_circuit_cooldown_secs?: number
+}
+
+export interface EmbeddingFallbackConfig {
+  provider: string
+  model: string
+  api_key?: string
+  base_url?: string
+  dimensions?: number
+  timeout_secs?: number
 }
 
 export async function getRagConfig(): Promise<RagConfig> {
@@ -106,13 +106,13 @@ export class RagService {
   }
 
   /**
-   * 导入数据源到RAG系统
+   * 导入数据源到RAG系统（后台执行，返回 job id；进度见 rag_ingest_progress 事件）
    */
   static async ingestSource(
     filePath: string,
     collectionName?: string,
     metadata?: Record<string, string>
-  ): Promise<RagIngestResponse> {
+  ): Promise<string> {
     try {
       return await invoke('rag_ingest_source', {
         filePath,
@@ -125,6 +125,18 @@ export class RagService {
     }
   }
 
+  /**
+   * 取消导入任务（job id 或批量导入的 batch id）
+   */
+  static async cancelIngest(jobId: string): Promise<boolean> {
+    try {
+      return await invoke('cancel_rag_ingest', { jobId })
+    } catch (error) {
+      console.error('Failed to cancel ingest:', error)
+      throw error
+    }
+  }
+
   /**
    * 查询RAG系统
    */